@@ -0,0 +1,46 @@
+use anyhow::{Context, Result, bail};
+use cruxe_core::config::Config;
+use cruxe_core::constants;
+use cruxe_core::types::generate_project_id;
+use cruxe_state::{compaction, db, project, schema};
+use std::path::Path;
+
+pub fn run(workspace: &Path, config_file: Option<&Path>) -> Result<()> {
+    let workspace = std::fs::canonicalize(workspace).context("Failed to resolve workspace path")?;
+    let workspace_str = workspace.to_string_lossy().to_string();
+    let config = Config::load_with_file(Some(&workspace), config_file)?;
+    let project_id = generate_project_id(&workspace_str);
+    let data_dir = config.project_data_dir(&project_id);
+    let db_path = data_dir.join(constants::STATE_DB_FILE);
+    let conn = db::open_connection(&db_path)?;
+    schema::create_tables(&conn)?;
+
+    if project::get_by_root(&conn, &workspace_str)?.is_none() {
+        bail!(
+            "Project not initialized. Run `cruxe init --path {}` first.",
+            workspace_str
+        );
+    }
+
+    let report = compaction::compact_project(&conn, &data_dir)?;
+
+    println!("Index compaction complete");
+    println!("  Workspace: {}", workspace.display());
+    println!("  Pruned symbol rows: {}", report.pruned_symbol_rows);
+    println!("  Pruned edge rows: {}", report.pruned_edge_rows);
+    println!("  Pruned index documents: {}", report.pruned_documents);
+    println!(
+        "  Index segments: {} -> {}",
+        report.segments_before, report.segments_after
+    );
+    println!(
+        "  SQLite bytes: {} -> {}",
+        report.sqlite_bytes_before, report.sqlite_bytes_after
+    );
+    println!(
+        "  Index bytes: {} -> {}",
+        report.index_bytes_before, report.index_bytes_after
+    );
+    println!("  Bytes reclaimed: {}", report.bytes_reclaimed);
+    Ok(())
+}
@@ -92,6 +92,14 @@ pub fn run_retrieval(
                 plan_override: None,
                 policy_mode_override: None,
                 policy_runtime: None,
+                preferred_language: None,
+                languages: Vec::new(),
+                semantic: None,
+                kind: None,
+                offset: 0,
+                highlight: false,
+                visibility: cruxe_core::types::VisibilityFilter::Any,
+                intent: None,
             },
         );
 
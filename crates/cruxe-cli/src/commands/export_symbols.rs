@@ -0,0 +1,66 @@
+use anyhow::{Context, Result};
+use cruxe_core::config::Config;
+use cruxe_core::constants;
+use cruxe_core::types::generate_project_id;
+use cruxe_core::vcs;
+use cruxe_state::{db, project, schema, symbols};
+use std::io::Write;
+use std::path::Path;
+
+const EXPORT_PAGE_SIZE: usize = 5_000;
+
+/// Stream every symbol name for a ref to stdout as NDJSON
+/// `[qualified_name, kind, path, line_start]` tuples, sorted by qualified name.
+pub fn run(repo_root: &Path, r#ref: Option<&str>, config_file: Option<&Path>) -> Result<()> {
+    let repo_root = std::fs::canonicalize(repo_root).context("Failed to resolve project path")?;
+    let repo_root_str = repo_root.to_string_lossy().to_string();
+
+    let config = Config::load_with_file(Some(&repo_root), config_file)?;
+    let project_id = generate_project_id(&repo_root_str);
+    let db_path = config
+        .project_data_dir(&project_id)
+        .join(constants::STATE_DB_FILE);
+
+    let conn = db::open_connection_with_config(
+        &db_path,
+        config.storage.busy_timeout_ms,
+        config.storage.cache_size,
+    )
+    .map_err(|e| anyhow::anyhow!("Failed to open state DB: {}", e))?;
+    schema::create_tables(&conn)
+        .map_err(|e| anyhow::anyhow!("Failed to initialize schema: {}", e))?;
+    let proj = project::get_by_root(&conn, &repo_root_str)?
+        .ok_or_else(|| anyhow::anyhow!("Project not initialized. Run `cruxe init` first."))?;
+    let resolved_ref = vcs::resolve_effective_ref(&repo_root, r#ref, &proj.default_ref);
+
+    let stdout = std::io::stdout();
+    let mut out = std::io::BufWriter::new(stdout.lock());
+    let mut offset = 0u64;
+    loop {
+        let page = symbols::list_symbol_names_page(
+            &conn,
+            &project_id,
+            &resolved_ref,
+            offset,
+            EXPORT_PAGE_SIZE,
+        )?;
+        for entry in &page {
+            serde_json::to_writer(
+                &mut out,
+                &(
+                    &entry.qualified_name,
+                    &entry.kind,
+                    &entry.path,
+                    entry.line_start,
+                ),
+            )?;
+            out.write_all(b"\n")?;
+        }
+        if page.len() < EXPORT_PAGE_SIZE {
+            break;
+        }
+        offset += page.len() as u64;
+    }
+    out.flush()?;
+    Ok(())
+}
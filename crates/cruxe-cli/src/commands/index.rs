@@ -3,7 +3,7 @@ use cruxe_core::config::Config;
 use cruxe_core::constants;
 use cruxe_core::ids::new_job_id;
 use cruxe_core::time::now_iso8601;
-use cruxe_core::types::{FileRecord, JobStatus, generate_project_id};
+use cruxe_core::types::{FileRecord, JobStatus, SkipReason, generate_project_id};
 use cruxe_core::vcs;
 use cruxe_indexer::{
     call_extract, embed_writer, import_extract, prepare, scanner,
@@ -11,9 +11,10 @@ use cruxe_indexer::{
     writer,
 };
 use cruxe_state::{
-    branch_state, db, edges, jobs, manifest, project, schema, symbols, tantivy_index,
+    branch_state, db, disk_space, edges, jobs, manifest, project, schema, skipped_files, symbols,
+    tantivy_index,
 };
-use cruxe_vcs::Git2VcsAdapter;
+use cruxe_vcs::{CommitTree, Git2VcsAdapter};
 use rayon::prelude::*;
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
@@ -21,12 +22,12 @@ use std::time::Instant;
 use tracing::{info, warn};
 
 const PROGRESS_UPDATE_EVERY: u64 = 100;
-const INDEX_PARALLELISM_ENV: &str = "CRUXE_INDEX_PARALLELISM";
 
 pub fn run(
     repo_root: &Path,
     force: bool,
     r#ref: Option<&str>,
+    commit: Option<&str>,
     config_file: Option<&Path>,
 ) -> Result<()> {
     let repo_root = std::fs::canonicalize(repo_root).context("Failed to resolve project path")?;
@@ -49,21 +50,59 @@ pub fn run(
     let proj = project::get_by_root(&conn, &repo_root_str)?
         .ok_or_else(|| anyhow::anyhow!("Project not initialized. Run `cruxe init` first."))?;
 
+    // The MCP wrapper reserves a queued job and passes its id; that one is ours.
+    let reserved_job_id = std::env::var("CRUXE_JOB_ID")
+        .ok()
+        .filter(|id| !id.trim().is_empty());
+
     // Check for active jobs
-    if let Some(active) = jobs::get_active_job(&conn, &project_id)? {
+    if let Some(active) = jobs::get_active_job(&conn, &project_id)?
+        && reserved_job_id.as_deref() != Some(active.job_id.as_str())
+    {
         bail!("Index already in progress: job_id={}", active.job_id);
     }
 
-    // Determine ref: explicit > current HEAD branch > project default
-    let effective_ref = vcs::resolve_effective_ref(&repo_root, r#ref, &proj.default_ref);
+    if let Err(err) = disk_space::ensure_free_disk(&data_dir, config.index.min_free_disk_mb) {
+        if let Some(job_id) = reserved_job_id.as_deref() {
+            jobs::release_reserved_job(&conn, job_id)?;
+        }
+        return Err(err.into());
+    }
+
+    // `--commit` reads files from the git object database instead of the
+    // working tree, so bare clones and commits other than HEAD can be indexed.
+    let commit_tree = match commit {
+        Some(treeish) => match CommitTree::open(&repo_root, treeish) {
+            Ok(tree) => Some(tree),
+            Err(err) => {
+                if let Some(job_id) = reserved_job_id.as_deref() {
+                    jobs::release_reserved_job(&conn, job_id)?;
+                }
+                return Err(
+                    anyhow::Error::new(err).context(format!("Failed to read commit `{treeish}`"))
+                );
+            }
+        },
+        None => None,
+    };
+
+    // Determine ref: commit-scoped > explicit > current HEAD branch > project default
+    let effective_ref = match &commit_tree {
+        Some(tree) => commit_ref_name(tree.commit()),
+        None => vcs::resolve_effective_ref(&repo_root, r#ref, &proj.default_ref),
+    };
 
     // VCS mode non-default refs use spec-005 overlay incremental sync path.
-    if proj.vcs_mode && effective_ref != proj.default_ref {
+    if proj.vcs_mode && commit_tree.is_none() && effective_ref != proj.default_ref {
         let last_indexed_commit =
             branch_state::get_branch_state(&conn, &project_id, &effective_ref)?
                 .map(|state| state.last_indexed_commit);
         let sync_id = format!("sync-{}", new_job_id());
         let adapter = Git2VcsAdapter;
+        // Overlay sync records its own job; hand the reserved slot over to it.
+        if let Some(job_id) = reserved_job_id.as_deref() {
+            jobs::release_reserved_job(&conn, job_id)?;
+        }
         println!(
             "Syncing overlay {} (base: {}) ...",
             effective_ref, proj.default_ref
@@ -94,10 +133,7 @@ pub fn run(
     }
 
     // Create job (allow MCP wrapper to inject a stable job id)
-    let job_id = std::env::var("CRUXE_JOB_ID")
-        .ok()
-        .filter(|id| !id.trim().is_empty())
-        .unwrap_or_else(new_job_id);
+    let job_id = reserved_job_id.clone().unwrap_or_else(new_job_id);
     let now = now_iso8601();
     let job = jobs::IndexJob {
         job_id: job_id.clone(),
@@ -108,7 +144,7 @@ pub fn run(
         } else {
             "incremental".into()
         },
-        head_commit: None,
+        head_commit: commit_tree.as_ref().map(|tree| tree.commit().to_string()),
         sync_id: None,
         status: "running".into(),
         changed_files: 0,
@@ -119,30 +155,42 @@ pub fn run(
         files_scanned: 0,
         files_indexed: 0,
         symbols_extracted: 0,
+        files_skipped: 0,
+        files_unchanged: 0,
+        files_excluded: 0,
+        files_per_second: None,
+        index_commits: 0,
         created_at: now.clone(),
         updated_at: now.clone(),
+        error_data: None,
     };
-    jobs::create_job(&conn, &job)?;
+    let adopted = reserved_job_id.is_some() && jobs::start_reserved_job(&conn, &job_id, &job.mode)?;
+    if !adopted {
+        jobs::create_job(&conn, &job)?;
+    }
 
     println!(
         "Indexing {} (ref: {}, mode: {}) ...",
         repo_root_str, effective_ref, job.mode
     );
     let start = Instant::now();
-    let index_result: Result<(u64, u64, u64, u64)> = (|| {
+    let index_result: Result<(u64, u64, Vec<scanner::SkippedFile>, u64, u64, u64)> = (|| {
         // Open Tantivy indices. In --force mode, recover by rebuilding incompatible indices.
-        let index_set = match tantivy_index::IndexSet::open(&data_dir) {
+        let schema_options = tantivy_index::IndexSchemaOptions::from_config(&config.index);
+        let index_set = match tantivy_index::IndexSet::open_with_options(&data_dir, schema_options)
+        {
             Ok(set) => set,
             Err(
                 cruxe_core::error::StateError::SchemaMigrationRequired { .. }
-                | cruxe_core::error::StateError::CorruptManifest(_),
+                | cruxe_core::error::StateError::CorruptManifest(_)
+                | cruxe_core::error::StateError::TokenizerChanged { .. },
             ) if force => {
                 let base_dir = data_dir.join("base");
                 if base_dir.exists() {
                     std::fs::remove_dir_all(&base_dir)
                         .context("Failed to remove incompatible Tantivy indices")?;
                 }
-                tantivy_index::IndexSet::open(&data_dir)?
+                tantivy_index::IndexSet::open_with_options(&data_dir, schema_options)?
             }
             Err(e) => return Err(e.into()),
         };
@@ -150,10 +198,13 @@ pub fn run(
         // Create batch writer — one IndexWriter per index for the entire operation.
         // NOTE: SQLite writes are auto-committed (no explicit transaction) so that
         // progress updates in `index_jobs` are immediately visible to `index_status`
-        // polling from the MCP server.  Tantivy is committed at the end via
-        // `batch.commit()`.  A crash mid-indexing may leave partial SQLite data, but
-        // the next incremental or force index run will reconcile both stores.
-        let batch = writer::BatchWriter::new(&index_set)?;
+        // polling from the MCP server.  Tantivy is committed every
+        // `index.commit_every_files` files and once more at the end; a file's
+        // manifest row is only written after the commit holding its documents, so
+        // a crash mid-batch leaves those files unrecorded and the next run
+        // re-indexes them.
+        let mut batch = writer::BatchWriter::new(&index_set)?
+            .with_commit_every(config.index.commit_every_files);
         let mut embedding_writer = embed_writer::EmbeddingWriter::new(
             &config.search.semantic,
             &project_id,
@@ -182,16 +233,37 @@ pub fn run(
         }
 
         // Scan files (filtered by configured languages)
-        let files = scanner::scan_directory_filtered(
-            &repo_root,
-            config.index.max_file_size,
-            &config.index.languages,
-        );
+        let scanner::ScanReport {
+            files,
+            skipped: mut skipped_entries,
+            excluded,
+        } = match &commit_tree {
+            Some(tree) => scanner::scan_commit_tree(
+                tree,
+                config.index.max_file_size,
+                &config.index.languages,
+                &config.index.text_only_extensions,
+                &config.index.exclude,
+                scanner::ScanLimits::from_config(&config.index),
+            )?,
+            None => scanner::scan_directory_with_report(
+                &repo_root,
+                config.index.max_file_size,
+                &config.index.languages,
+                &config.index.text_only_extensions,
+                &config.index.exclude,
+                scanner::ScanLimits::from_config(&config.index),
+            )?,
+        };
         let total_scanned = files.len() as i64;
         if let Err(err) = jobs::update_progress(&conn, &job_id, total_scanned, 0, 0) {
             warn!(job_id = %job_id, "Failed to update index progress: {}", err);
         }
+        jobs::set_files_excluded(&conn, &job_id, excluded as i64)?;
         println!("Found {} source files", files.len());
+        if excluded > 0 {
+            println!("Excluded {excluded} files matching index.exclude");
+        }
 
         let scanned_paths: HashSet<&str> = files.iter().map(|f| f.relative_path.as_str()).collect();
         let existing_manifest_entries = if force {
@@ -255,12 +327,13 @@ pub fn run(
         }
 
         let mut indexed_count = 0u64;
+        let mut unchanged_count = 0u64;
         let mut symbol_count = 0u64;
-        let mut skipped = 0u64;
+        let mut reprocessed_paths: HashSet<String> = HashSet::new();
         let mut pending_imports: Vec<(String, Vec<import_extract::RawImport>)> = Vec::new();
         let mut pending_call_edges: Vec<(String, Vec<cruxe_core::types::CallEdge>)> = Vec::new();
 
-        let parallelism = resolve_index_parallelism();
+        let parallelism = config.index.worker_threads();
         let chunk_size = std::cmp::max(parallelism * 8, PROGRESS_UPDATE_EVERY as usize);
         let worker_pool = rayon::ThreadPoolBuilder::new()
             .num_threads(parallelism)
@@ -273,17 +346,41 @@ pub fn run(
             parallelism
         );
 
+        let tree_files: HashMap<&str, &cruxe_vcs::TreeFile> = commit_tree
+            .iter()
+            .flat_map(|tree| tree.files())
+            .map(|file| (file.path.as_str(), file))
+            .collect();
+
         for file_chunk in files.chunks(chunk_size) {
+            // Blobs are read on this thread: the git repository handle is not
+            // shareable across the parse workers.
+            let blobs: Vec<Option<std::io::Result<Vec<u8>>>> = file_chunk
+                .iter()
+                .map(|file| {
+                    let tree = commit_tree.as_ref()?;
+                    let blob = tree_files
+                        .get(file.relative_path.as_str())
+                        .ok_or_else(|| std::io::Error::other("file missing from commit tree"))
+                        .and_then(|entry| tree.read(entry).map_err(std::io::Error::other));
+                    Some(blob)
+                })
+                .collect();
             let prepared_chunk: Vec<PreparedIndexOutcome> = worker_pool.install(|| {
                 file_chunk
                     .par_iter()
-                    .map(|file| {
+                    .zip(blobs)
+                    .map(|(file, blob)| {
                         prepare_file_for_indexing(
                             file,
+                            blob,
                             &project_id,
                             &effective_ref,
                             force,
                             existing_hashes.get(&file.relative_path).map(String::as_str),
+                            config.index.parse_timeout_ms_for(&file.language),
+                            config.index.max_syntax_depth,
+                            config.index.max_snippet_lines,
                         )
                     })
                     .collect()
@@ -292,10 +389,19 @@ pub fn run(
             let mut pending_embedding_batches = Vec::new();
             for prepared in prepared_chunk {
                 match prepared {
-                    PreparedIndexOutcome::Unchanged => {}
-                    PreparedIndexOutcome::SkippedRead { path, error } => {
+                    PreparedIndexOutcome::Unchanged => unchanged_count += 1,
+                    PreparedIndexOutcome::SkippedRead {
+                        path,
+                        reason,
+                        error,
+                    } => {
                         warn!(path = %path, error = %error, "Failed to read file");
-                        skipped += 1;
+                        reprocessed_paths.insert(path.clone());
+                        skipped_entries.push(scanner::SkippedFile {
+                            relative_path: path,
+                            reason,
+                            detail: Some(error),
+                        });
                     }
                     PreparedIndexOutcome::Ready(prepared) => {
                         let PreparedIndexFile {
@@ -306,15 +412,32 @@ pub fn run(
                             file_record,
                             mtime_ns,
                             parse_error,
+                            parse_timed_out,
                             had_previous_index,
                         } = *prepared;
 
-                        if let Some(parse_error) = parse_error.as_deref() {
-                            warn!(
-                                path = %file_record.path,
-                                error = %parse_error,
-                                "Parse failed"
-                            );
+                        reprocessed_paths.insert(file_record.path.clone());
+                        if let Some(parse_error) = parse_error {
+                            let reason = if parse_timed_out {
+                                warn!(
+                                    path = %file_record.path,
+                                    error = %parse_error,
+                                    "Parse timed out; skipping symbol extraction"
+                                );
+                                SkipReason::ParseTimeout
+                            } else {
+                                warn!(
+                                    path = %file_record.path,
+                                    error = %parse_error,
+                                    "Parse failed"
+                                );
+                                SkipReason::ParseFailure
+                            };
+                            skipped_entries.push(scanner::SkippedFile {
+                                relative_path: file_record.path.clone(),
+                                reason,
+                                detail: Some(parse_error),
+                            });
                         }
 
                         if !force {
@@ -330,16 +453,11 @@ pub fn run(
                             embedding_writer.delete_for_file_vectors(&conn, &file_record.path)?;
                         }
 
-                        symbols::delete_symbols_for_file(
-                            &conn,
-                            &project_id,
-                            &effective_ref,
-                            &file_record.path,
-                        )?;
                         batch.add_symbols(&index_set.symbols, &symbols_for_file)?;
                         batch.add_snippets(&index_set.snippets, &snippets)?;
                         batch.add_file(&index_set.files, &file_record)?;
                         batch.write_sqlite(&conn, &symbols_for_file, &file_record, mtime_ns)?;
+                        batch.commit_if_due(&conn)?;
 
                         let symbol_delta = symbols_for_file.len() as u64;
                         pending_imports.push((file_record.path.clone(), raw_imports));
@@ -369,6 +487,21 @@ pub fn run(
                     .iter()
                     .map(|(symbols, snippets)| (symbols.as_slice(), snippets.as_slice())),
             )?;
+
+            // Checked per chunk so a runaway ref aborts early instead of producing a
+            // giant index; the count may overshoot the cap by at most one chunk.
+            let max_symbols = config.index.max_symbols_per_ref;
+            if max_symbols > 0 {
+                let ref_symbol_count = symbols::symbol_count(&conn, &project_id, &effective_ref)?;
+                if ref_symbol_count > max_symbols {
+                    return Err(cruxe_core::error::StateError::symbol_cap_exceeded(
+                        effective_ref.clone(),
+                        ref_symbol_count,
+                        max_symbols,
+                    )
+                    .into());
+                }
+            }
         }
 
         // Resolve imports after all symbols are written so cross-file lookups can
@@ -395,19 +528,16 @@ pub fn run(
             )?;
         }
 
-        // Commit Tantivy segment updates.
-        match batch.commit() {
-            Ok(()) => {}
-            Err(e) => {
-                return Err(e.into());
-            }
-        }
+        // Commit the remaining Tantivy segment updates and manifest rows.
+        let index_commits = batch.commit(&conn)?;
 
         let changed_files = indexed_count + removed_count;
         let file_count = manifest::file_count(&conn, &project_id, &effective_ref)?;
         let total_symbol_count = symbols::symbol_count(&conn, &project_id, &effective_ref)?;
         let now = now_iso8601();
-        let indexed_commit = if vcs::is_git_repo(&repo_root) {
+        let indexed_commit = if let Some(tree) = &commit_tree {
+            tree.commit().to_string()
+        } else if vcs::is_git_repo(&repo_root) {
             vcs::detect_head_commit(&repo_root).unwrap_or_else(|_| effective_ref.clone())
         } else {
             effective_ref.clone()
@@ -437,13 +567,71 @@ pub fn run(
             warn!(job_id = %job_id, "Failed to update index progress: {}", err);
         }
 
-        Ok((indexed_count, skipped, symbol_count, changed_files))
+        // Unchanged files are not re-parsed, so carry their earlier parse
+        // failures and timeouts forward instead of silently dropping them.
+        for reason in [SkipReason::ParseFailure, SkipReason::ParseTimeout] {
+            let previous_skips = skipped_files::list_for_ref(
+                &conn,
+                &project_id,
+                &effective_ref,
+                Some(reason.as_str()),
+                0,
+                usize::MAX,
+            )?;
+            for previous in previous_skips.files {
+                if scanned_paths.contains(previous.path.as_str())
+                    && !reprocessed_paths.contains(&previous.path)
+                {
+                    skipped_entries.push(scanner::SkippedFile {
+                        relative_path: previous.path,
+                        reason,
+                        detail: previous.detail,
+                    });
+                }
+            }
+        }
+
+        Ok((
+            indexed_count,
+            unchanged_count,
+            skipped_entries,
+            symbol_count,
+            changed_files,
+            index_commits,
+        ))
     })();
 
     match index_result {
-        Ok((indexed_count, skipped, symbol_count, changed_files)) => {
+        Ok((
+            indexed_count,
+            unchanged_count,
+            skipped_entries,
+            symbol_count,
+            changed_files,
+            index_commits,
+        )) => {
             let duration = start.elapsed();
             let duration_ms = duration.as_millis() as i64;
+            let files_per_second = indexed_count as f64 / duration.as_secs_f64().max(0.001);
+            let skipped = skipped_entries.len();
+            let records: Vec<skipped_files::SkippedFileRecord> = skipped_entries
+                .into_iter()
+                .map(|file| skipped_files::SkippedFileRecord {
+                    path: file.relative_path,
+                    reason: file.reason.as_str().to_string(),
+                    detail: file.detail,
+                })
+                .collect();
+            skipped_files::replace_for_job(
+                &mut conn,
+                &project_id,
+                &effective_ref,
+                &job_id,
+                &records,
+            )?;
+            jobs::set_files_skipped(&conn, &job_id, skipped as i64)?;
+            jobs::set_files_unchanged(&conn, &job_id, unchanged_count as i64)?;
+            jobs::set_throughput(&conn, &job_id, files_per_second, index_commits as i64)?;
 
             // Update job status
             jobs::update_job_status(
@@ -459,15 +647,26 @@ pub fn run(
             println!();
             println!("Indexing complete!");
             println!("  Files indexed: {}", indexed_count);
+            println!("  Files unchanged: {}", unchanged_count);
             println!("  Files skipped: {}", skipped);
             println!("  Symbols found: {}", symbol_count);
             println!("  Changed files: {}", changed_files);
             println!("  Duration:      {:.1}s", duration.as_secs_f64());
+            println!(
+                "  Throughput:    {:.1} files/s ({} index commits)",
+                files_per_second, index_commits
+            );
             println!("  Job ID:        {}", job_id);
 
             info!(
                 indexed_count,
-                symbol_count, changed_files, duration_ms, "Indexing complete"
+                unchanged_count,
+                symbol_count,
+                changed_files,
+                duration_ms,
+                files_per_second,
+                index_commits,
+                "Indexing complete"
             );
             Ok(())
         }
@@ -483,6 +682,12 @@ pub fn run(
                 Some(&error_message),
                 &now_iso8601(),
             );
+            if let Some(error_data) = err
+                .downcast_ref::<cruxe_core::error::StateError>()
+                .and_then(jobs::error_data_for)
+            {
+                let _ = jobs::set_job_error_data(&conn, &job_id, &error_data);
+            }
             Err(err)
         }
     }
@@ -504,32 +709,62 @@ struct PreparedIndexFile {
     file_record: FileRecord,
     mtime_ns: Option<i64>,
     parse_error: Option<String>,
+    parse_timed_out: bool,
     had_previous_index: bool,
 }
 
 enum PreparedIndexOutcome {
     Unchanged,
-    SkippedRead { path: String, error: String },
+    SkippedRead {
+        path: String,
+        reason: SkipReason,
+        error: String,
+    },
     Ready(Box<PreparedIndexFile>),
 }
 
+/// Ref a `--commit` run indexes into.
+fn commit_ref_name(commit: &str) -> String {
+    format!("commit/{commit}")
+}
+
+/// Read, hash and parse one file. `blob` holds the file's bytes when indexing
+/// from a commit tree; otherwise the file is read from disk.
+#[allow(clippy::too_many_arguments)]
 fn prepare_file_for_indexing(
     file: &scanner::ScannedFile,
+    blob: Option<std::io::Result<Vec<u8>>>,
     project_id: &str,
     effective_ref: &str,
     force: bool,
     existing_hash: Option<&str>,
+    parse_timeout_ms: u64,
+    max_syntax_depth: usize,
+    max_snippet_lines: usize,
 ) -> PreparedIndexOutcome {
-    let content = match std::fs::read_to_string(&file.path) {
-        Ok(c) => c,
+    let from_tree = blob.is_some();
+    let source = match blob {
+        Some(bytes) => bytes.and_then(|bytes| prepare::decode_source(bytes, &file.path)),
+        None => prepare::read_source_file(&file.path),
+    };
+    let source = match source {
+        Ok(source) => source,
         Err(err) => {
+            // NUL bytes mark a binary file with a source extension.
+            let reason = if err.kind() == std::io::ErrorKind::InvalidData {
+                SkipReason::Binary
+            } else {
+                SkipReason::Unreadable
+            };
             return PreparedIndexOutcome::SkippedRead {
                 path: file.relative_path.clone(),
+                reason,
                 error: err.to_string(),
             };
         }
     };
 
+    let content = source.content;
     let content_hash = blake3::hash(content.as_bytes()).to_hex().to_string();
     let had_previous_index = existing_hash.is_some();
     if !force && existing_hash == Some(content_hash.as_str()) {
@@ -537,20 +772,30 @@ fn prepare_file_for_indexing(
     }
 
     let artifacts = prepare::build_source_artifacts(
-        &content,
-        &file.language,
-        &file.relative_path,
-        project_id,
-        effective_ref,
-        None,
-        true,
+        prepare::ArtifactBuildInput {
+            content: &content,
+            language: &file.language,
+            source_path: &file.relative_path,
+            project_id,
+            ref_name: effective_ref,
+            source_layer: None,
+            include_imports: true,
+            max_syntax_depth,
+            max_snippet_lines,
+        },
+        parse_timeout_ms,
     );
     let filename = file
         .path
         .file_name()
         .map(|n| n.to_string_lossy().to_string())
         .unwrap_or_default();
-    let mut file_record = prepare::build_file_record(
+    let build_record = if file.text_only {
+        prepare::build_text_only_file_record
+    } else {
+        prepare::build_file_record
+    };
+    let mut file_record = build_record(
         project_id,
         effective_ref,
         &file.relative_path,
@@ -560,6 +805,7 @@ fn prepare_file_for_indexing(
     );
     // Reuse the precomputed hash used for unchanged short-circuit checks.
     file_record.content_hash = content_hash;
+    file_record.encoding_warning = source.encoding_warning;
 
     PreparedIndexOutcome::Ready(Box::new(PreparedIndexFile {
         symbols_for_file: artifacts.symbols,
@@ -567,20 +813,13 @@ fn prepare_file_for_indexing(
         raw_imports: artifacts.raw_imports,
         call_edges: artifacts.call_edges,
         file_record,
-        mtime_ns: file_mtime_ns(&file.path),
+        mtime_ns: if from_tree {
+            None
+        } else {
+            file_mtime_ns(&file.path)
+        },
         parse_error: artifacts.parse_error,
+        parse_timed_out: artifacts.parse_timed_out,
         had_previous_index,
     }))
 }
-
-fn resolve_index_parallelism() -> usize {
-    std::env::var(INDEX_PARALLELISM_ENV)
-        .ok()
-        .and_then(|raw| raw.parse::<usize>().ok())
-        .filter(|value| *value > 0)
-        .unwrap_or_else(|| {
-            std::thread::available_parallelism()
-                .map(std::num::NonZeroUsize::get)
-                .unwrap_or(1)
-        })
-}
@@ -42,7 +42,7 @@ pub fn run(repo_root: &Path, config_file: Option<&Path>) -> Result<()> {
     let default_ref = if vcs_mode {
         vcs::detect_default_ref(&repo_root, "main")
     } else {
-        constants::REF_LIVE.to_string()
+        config.index.non_vcs_ref().to_string()
     };
 
     let now = now_iso8601();
@@ -63,7 +63,10 @@ pub fn run(repo_root: &Path, config_file: Option<&Path>) -> Result<()> {
     project::create_project(&conn, &project)?;
 
     // Create Tantivy index directories
-    let _index_set = tantivy_index::IndexSet::open(&data_dir)?;
+    let _index_set = tantivy_index::IndexSet::open_with_options(
+        &data_dir,
+        tantivy_index::IndexSchemaOptions::from_config(&config.index),
+    )?;
 
     println!("Project initialized successfully!");
     println!("  ID:       {}", project_id);
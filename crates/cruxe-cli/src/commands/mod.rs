@@ -1,5 +1,7 @@
+pub mod compact;
 pub mod doctor;
 pub mod eval;
+pub mod export_symbols;
 pub mod index;
 pub mod init;
 pub mod prune_overlays;
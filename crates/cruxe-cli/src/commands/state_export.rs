@@ -22,17 +22,25 @@ pub fn run(workspace: &Path, output_path: &Path, config_file: Option<&Path>) ->
         );
     };
 
-    let metadata = export::PortableStateMetadata::new(
+    // Fold the WAL into state.db so the bundle carries one self-contained file.
+    conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);")
+        .map_err(cruxe_core::error::StateError::sqlite)?;
+    let mut metadata = export::PortableStateMetadata::new(
         project_row.schema_version,
         project_row.parser_version,
         project_row.project_id,
         workspace_str.clone(),
     );
+    metadata.db_schema_version = schema::applied_schema_version(&conn)?;
     export::export_bundle(&data_dir, output_path, &metadata)?;
 
     println!("State export complete");
     println!("  Workspace: {}", workspace_str);
     println!("  Project ID: {}", metadata.project_id);
+    println!(
+        "  Versions: schema={}, parser={}, state_db={}",
+        metadata.schema_version, metadata.parser_version, metadata.db_schema_version
+    );
     println!("  Output: {}", output_path.display());
     Ok(())
 }
@@ -10,6 +10,7 @@ use std::path::Path;
 struct ImportRemapSpec<'a> {
     imported_project_id: &'a str,
     local_project_id: &'a str,
+    imported_repo_root: &'a str,
     workspace_str: &'a str,
     display_name: Option<String>,
     default_ref: &'a str,
@@ -27,14 +28,6 @@ pub fn run(workspace: &Path, bundle_path: &Path, config_file: Option<&Path>) ->
     let _maintenance_lock = maintenance_lock::acquire_project_lock(&data_dir, "state_import")?;
 
     let metadata = import::import_bundle(bundle_path, &data_dir)?;
-    if metadata.parser_version != constants::PARSER_VERSION {
-        eprintln!(
-            "Warning: imported parser_version={} differs from local parser_version={}. Run `cruxe index --force --path {}` to refresh parser-dependent symbols.",
-            metadata.parser_version,
-            constants::PARSER_VERSION,
-            workspace.display()
-        );
-    }
     let db_path = data_dir.join(constants::STATE_DB_FILE);
     let mut conn = db::open_connection(&db_path)?;
     schema::create_tables(&conn)?;
@@ -43,13 +36,14 @@ pub fn run(workspace: &Path, bundle_path: &Path, config_file: Option<&Path>) ->
     let default_ref = if is_vcs_repo {
         vcs::detect_default_ref(&workspace, "main")
     } else {
-        constants::REF_LIVE.to_string()
+        config.index.non_vcs_ref().to_string()
     };
     remap_imported_project_data(
         &mut conn,
         ImportRemapSpec {
             imported_project_id: &metadata.project_id,
             local_project_id: &project_id,
+            imported_repo_root: &metadata.repo_root,
             workspace_str: &workspace_str,
             display_name: workspace
                 .file_name()
@@ -75,6 +69,7 @@ pub fn run(workspace: &Path, bundle_path: &Path, config_file: Option<&Path>) ->
         "  Local project ID: {}",
         generate_project_id(&workspace_str)
     );
+    println!("  Source workspace: {}", metadata.repo_root);
     println!("  Schema version: {}", metadata.schema_version);
     Ok(())
 }
@@ -86,6 +81,9 @@ fn remap_imported_project_data(
     let tx = conn
         .transaction()
         .map_err(cruxe_core::error::StateError::sqlite)?;
+    // Rows are repointed before the local project row exists; check references at commit.
+    tx.execute_batch("PRAGMA defer_foreign_keys = ON;")
+        .map_err(cruxe_core::error::StateError::sqlite)?;
     let created_at = existing_created_at(&tx, spec.local_project_id)?.unwrap_or_else(now_iso8601);
     let updated_at = now_iso8601();
 
@@ -107,6 +105,7 @@ fn remap_imported_project_data(
         )
         .map_err(cruxe_core::error::StateError::sqlite)?;
     }
+    rewrite_workspace_paths(&tx, spec.imported_repo_root, spec.workspace_str)?;
 
     tx.execute(
         "INSERT INTO projects
@@ -138,6 +137,41 @@ fn remap_imported_project_data(
     Ok(())
 }
 
+/// Point host-specific paths at the new workspace root.
+///
+/// Indexed paths are repo-relative and need no rewrite. Known workspaces under
+/// the exporting root are moved to the local root; worktree leases refer to
+/// checkouts on the exporting machine, so they are dropped and recreated on demand.
+fn rewrite_workspace_paths(
+    tx: &rusqlite::Transaction<'_>,
+    imported_repo_root: &str,
+    workspace_str: &str,
+) -> Result<()> {
+    tx.execute("DELETE FROM worktree_leases", [])
+        .map_err(cruxe_core::error::StateError::sqlite)?;
+    if imported_repo_root.is_empty() || imported_repo_root == workspace_str {
+        return Ok(());
+    }
+    let imported_root = imported_repo_root.trim_end_matches('/');
+    // Drop local rows that would collide with the rewritten ones.
+    tx.execute(
+        "DELETE FROM known_workspaces
+         WHERE workspace_path = ?1
+            OR substr(workspace_path, 1, length(?1) + 1) = ?1 || '/'",
+        rusqlite::params![workspace_str],
+    )
+    .map_err(cruxe_core::error::StateError::sqlite)?;
+    tx.execute(
+        "UPDATE known_workspaces
+         SET workspace_path = ?2 || substr(workspace_path, length(?1) + 1)
+         WHERE workspace_path = ?1
+            OR substr(workspace_path, 1, length(?1) + 1) = ?1 || '/'",
+        rusqlite::params![imported_root, workspace_str],
+    )
+    .map_err(cruxe_core::error::StateError::sqlite)?;
+    Ok(())
+}
+
 fn existing_created_at(tx: &rusqlite::Transaction<'_>, project_id: &str) -> Result<Option<String>> {
     use rusqlite::OptionalExtension;
 
@@ -69,6 +69,7 @@ enum Commands {
     ///   cruxe index
     ///   cruxe index --force
     ///   cruxe index --ref feat/auth
+    ///   cruxe index --commit 3f2a9c1
     Index {
         /// Path to the project root (default: current directory)
         #[arg(short, long)]
@@ -78,9 +79,14 @@ enum Commands {
         #[arg(long)]
         force: bool,
 
-        /// Ref/branch to index under (default: auto-detect or "live")
+        /// Ref/branch to index under (default: auto-detect or `index.default_ref`)
         #[arg(long)]
         r#ref: Option<String>,
+
+        /// Index the files of this commit (sha, branch or tag) straight from the
+        /// git object database into ref `commit/<sha>`; works on bare clones
+        #[arg(long, conflicts_with = "ref")]
+        commit: Option<String>,
     },
     /// Search code in the index
     ///
@@ -96,11 +102,11 @@ enum Commands {
         /// Search query (symbol name, file path, error string, or natural language)
         query: String,
 
-        /// Branch/ref scope (default: auto-detect or "live")
+        /// Branch/ref scope (default: auto-detect or `index.default_ref`)
         #[arg(long)]
         r#ref: Option<String>,
 
-        /// Filter by programming language (rust, typescript, python, go)
+        /// Filter by programming language (rust, typescript, python, go, c, cpp, csharp, ruby, kotlin)
         #[arg(long)]
         lang: Option<String>,
 
@@ -108,6 +114,24 @@ enum Commands {
         #[arg(long, default_value = "10")]
         limit: usize,
     },
+    /// Export all symbol names for a ref as NDJSON
+    ///
+    /// Prints one `[qualified_name, kind, path, line_start]` tuple per line,
+    /// sorted by qualified name, for loading into editor fuzzy finders.
+    ///
+    /// Examples:
+    ///   cruxe export-symbols > symbols.ndjson
+    ///   cruxe export-symbols --ref main --workspace /path/to/project
+    #[command(name = "export-symbols")]
+    ExportSymbols {
+        /// Path to the project root (default: current directory)
+        #[arg(long)]
+        workspace: Option<String>,
+
+        /// Branch/ref scope (default: auto-detect or `index.default_ref`)
+        #[arg(long)]
+        r#ref: Option<String>,
+    },
     /// Incremental sync based on file changes
     ///
     /// Detects changed files since last index and updates only those entries.
@@ -147,6 +171,17 @@ enum Commands {
         #[arg(long = "older-than", default_value = "30")]
         older_than_days: u64,
     },
+    /// Reclaim space left behind by incremental re-indexing
+    ///
+    /// Prunes symbols, call edges and index documents for files that are no
+    /// longer in the manifest, merges Tantivy segments and vacuums SQLite.
+    ///
+    /// Example: cruxe compact --workspace /path/to/project
+    Compact {
+        /// Path to the project root (default: current directory)
+        #[arg(long)]
+        workspace: Option<String>,
+    },
     /// Start MCP server (stdio or HTTP JSON-RPC transport)
     ///
     /// Exposes tools (locate_symbol, search_code, index_status, index_repo,
@@ -178,7 +213,7 @@ enum Commands {
         bind: String,
 
         /// Enable auto-discovery of workspaces passed via the `workspace` tool parameter.
-        /// Requires at least one --allowed-root.
+        /// Requires at least one --allowed-root or `server.allowed_workspace_roots` entry.
         #[arg(long)]
         auto_workspace: bool,
 
@@ -204,7 +239,7 @@ enum StateCommands {
         #[arg(long)]
         workspace: Option<String>,
     },
-    /// Import state bundle from `.tar.zst`
+    /// Import state bundle from `.tar.zst` (rejects schema/parser version mismatches)
     Import {
         /// Input archive path
         path: String,
@@ -282,11 +317,20 @@ fn main() -> anyhow::Result<()> {
 
     // Set up tracing
     let filter = if cli.verbose { "debug" } else { "info" };
+    // `export-symbols` writes NDJSON to stdout, so its logs must go to stderr.
+    let logs_to_stderr = matches!(cli.command, Commands::ExportSymbols { .. });
     tracing_subscriber::fmt()
         .with_env_filter(
             EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(filter)),
         )
         .with_target(false)
+        .with_writer(move || -> Box<dyn std::io::Write> {
+            if logs_to_stderr {
+                Box::new(std::io::stderr())
+            } else {
+                Box::new(std::io::stdout())
+            }
+        })
         .init();
 
     let config_file = cli.config.as_deref().map(std::path::Path::new);
@@ -300,9 +344,20 @@ fn main() -> anyhow::Result<()> {
             let path = resolve_path(path)?;
             commands::doctor::run(&path, config_file)?;
         }
-        Commands::Index { path, force, r#ref } => {
+        Commands::Index {
+            path,
+            force,
+            r#ref,
+            commit,
+        } => {
             let path = resolve_path(path)?;
-            commands::index::run(&path, force, r#ref.as_deref(), config_file)?;
+            commands::index::run(
+                &path,
+                force,
+                r#ref.as_deref(),
+                commit.as_deref(),
+                config_file,
+            )?;
         }
         Commands::Search {
             query,
@@ -320,9 +375,13 @@ fn main() -> anyhow::Result<()> {
                 config_file,
             )?;
         }
+        Commands::ExportSymbols { workspace, r#ref } => {
+            let path = resolve_path(workspace)?;
+            commands::export_symbols::run(&path, r#ref.as_deref(), config_file)?;
+        }
         Commands::Sync { workspace, force } => {
             let path = resolve_path(workspace)?;
-            commands::index::run(&path, force, None, config_file)?;
+            commands::index::run(&path, force, None, None, config_file)?;
         }
         Commands::Eval { command } => match command {
             EvalCommands::Retrieval {
@@ -385,6 +444,10 @@ fn main() -> anyhow::Result<()> {
             let workspace = resolve_path(workspace)?;
             commands::prune_overlays::run(&workspace, older_than_days, config_file)?;
         }
+        Commands::Compact { workspace } => {
+            let workspace = resolve_path(workspace)?;
+            commands::compact::run(&workspace, config_file)?;
+        }
         Commands::ServeMcp {
             workspace,
             no_prewarm,
@@ -396,7 +459,6 @@ fn main() -> anyhow::Result<()> {
             max_auto_workspaces,
         } => {
             let path = resolve_path(workspace)?;
-            validate_serve_mcp_args(auto_workspace, &allowed_roots)?;
             // HIGH-4: Canonicalize --allowed-root paths at startup; reject nonexistent
             let mut canonical_roots = Vec::new();
             for root in &allowed_roots {
@@ -434,13 +496,6 @@ fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
-fn validate_serve_mcp_args(auto_workspace: bool, allowed_roots: &[String]) -> anyhow::Result<()> {
-    if auto_workspace && allowed_roots.is_empty() {
-        anyhow::bail!("--auto-workspace requires at least one --allowed-root");
-    }
-    Ok(())
-}
-
 fn resolve_path(path: Option<String>) -> anyhow::Result<std::path::PathBuf> {
     match path {
         Some(p) => Ok(std::path::PathBuf::from(p)),
@@ -480,19 +535,4 @@ mod tests {
         let parsed = Cli::try_parse_from(["cruxe", "serve-mcp", "--transport", "HTTP"]);
         assert!(parsed.is_err(), "uppercase transport should be rejected");
     }
-
-    #[test]
-    fn serve_mcp_validate_requires_allowed_root_when_auto_workspace_enabled() {
-        let err = validate_serve_mcp_args(true, &[]).expect_err("validation should fail");
-        assert!(
-            err.to_string()
-                .contains("--auto-workspace requires at least one --allowed-root"),
-            "unexpected validation message: {err}"
-        );
-    }
-
-    #[test]
-    fn serve_mcp_validate_allows_disabled_auto_workspace_without_roots() {
-        validate_serve_mcp_args(false, &[]).expect("validation should pass");
-    }
 }
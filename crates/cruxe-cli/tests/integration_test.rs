@@ -7,6 +7,7 @@
 //! T056: Search Snippet Join Enrichment
 //! T071: Ref-Scoped Search Isolation
 
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use tempfile::tempdir;
 
@@ -209,6 +210,7 @@ fn index_repo_with_import_edges(
             size_bytes: content.len() as u64,
             updated_at: "2026-01-01T00:00:00Z".to_string(),
             content_head: Some(content.lines().take(20).collect::<Vec<_>>().join("\n")),
+            encoding_warning: false,
         };
 
         cruxe_state::symbols::delete_symbols_for_file(
@@ -304,6 +306,7 @@ fn index_repo_with_import_and_call_edges(
                 &symbols,
                 project_id,
                 effective_ref,
+                cruxe_core::constants::DEFAULT_MAX_SYNTAX_DEPTH,
             )
         } else {
             Vec::new()
@@ -324,6 +327,7 @@ fn index_repo_with_import_and_call_edges(
             size_bytes: content.len() as u64,
             updated_at: "2026-01-01T00:00:00Z".to_string(),
             content_head: Some(content.lines().take(20).collect::<Vec<_>>().join("\n")),
+            encoding_warning: false,
         };
 
         cruxe_state::symbols::delete_symbols_for_file(
@@ -385,7 +389,7 @@ fn index_repo_with_import_edges_batched(
 ) {
     let files =
         cruxe_indexer::scanner::scan_directory(repo_root, cruxe_core::constants::MAX_FILE_SIZE);
-    let batch = cruxe_indexer::writer::BatchWriter::new(index_set).expect("create batch");
+    let mut batch = cruxe_indexer::writer::BatchWriter::new(index_set).expect("create batch");
     let mut pending_imports = Vec::new();
 
     for file in &files {
@@ -439,6 +443,7 @@ fn index_repo_with_import_edges_batched(
             size_bytes: content.len() as u64,
             updated_at: "2026-01-01T00:00:00Z".to_string(),
             content_head: Some(content.lines().take(20).collect::<Vec<_>>().join("\n")),
+            encoding_warning: false,
         };
 
         cruxe_state::symbols::delete_symbols_for_file(
@@ -476,7 +481,7 @@ fn index_repo_with_import_edges_batched(
         .expect("replace import edges for file");
     }
 
-    batch.commit().expect("commit batch");
+    batch.commit(conn).expect("commit batch");
 }
 
 /// Batched variant used by T359 benchmark to isolate call-edge overhead from
@@ -490,7 +495,7 @@ fn index_repo_with_import_and_call_edges_batched(
 ) {
     let files =
         cruxe_indexer::scanner::scan_directory(repo_root, cruxe_core::constants::MAX_FILE_SIZE);
-    let batch = cruxe_indexer::writer::BatchWriter::new(index_set).expect("create batch");
+    let mut batch = cruxe_indexer::writer::BatchWriter::new(index_set).expect("create batch");
     let mut pending_imports = Vec::new();
     let mut pending_call_edges = Vec::new();
 
@@ -545,6 +550,7 @@ fn index_repo_with_import_and_call_edges_batched(
                 &symbols,
                 project_id,
                 effective_ref,
+                cruxe_core::constants::DEFAULT_MAX_SYNTAX_DEPTH,
             )
         } else {
             Vec::new()
@@ -565,6 +571,7 @@ fn index_repo_with_import_and_call_edges_batched(
             size_bytes: content.len() as u64,
             updated_at: "2026-01-01T00:00:00Z".to_string(),
             content_head: Some(content.lines().take(20).collect::<Vec<_>>().join("\n")),
+            encoding_warning: false,
         };
 
         cruxe_state::symbols::delete_symbols_for_file(
@@ -619,7 +626,7 @@ fn index_repo_with_import_and_call_edges_batched(
         .expect("replace call edges for files");
     }
 
-    batch.commit().expect("commit batch");
+    batch.commit(conn).expect("commit batch");
 }
 
 /// Batched variant used by T379 benchmark to measure embedding overhead.
@@ -633,7 +640,7 @@ fn index_repo_with_import_and_embeddings_batched(
 ) {
     let files =
         cruxe_indexer::scanner::scan_directory(repo_root, cruxe_core::constants::MAX_FILE_SIZE);
-    let batch = cruxe_indexer::writer::BatchWriter::new(index_set).expect("create batch");
+    let mut batch = cruxe_indexer::writer::BatchWriter::new(index_set).expect("create batch");
     let mut embedding_writer =
         cruxe_indexer::embed_writer::EmbeddingWriter::new(semantic, project_id, effective_ref)
             .expect("create embedding writer");
@@ -690,6 +697,7 @@ fn index_repo_with_import_and_embeddings_batched(
             size_bytes: content.len() as u64,
             updated_at: "2026-01-01T00:00:00Z".to_string(),
             content_head: Some(content.lines().take(20).collect::<Vec<_>>().join("\n")),
+            encoding_warning: false,
         };
 
         cruxe_state::symbols::delete_symbols_for_file(
@@ -734,7 +742,7 @@ fn index_repo_with_import_and_embeddings_batched(
         .expect("replace import edges for file");
     }
 
-    batch.commit().expect("commit batch");
+    batch.commit(conn).expect("commit batch");
 }
 
 // ===========================================================================
@@ -969,6 +977,7 @@ fn t043_index_and_locate_validate_token() {
             size_bytes: content.len() as u64,
             updated_at: "2026-01-01T00:00:00Z".to_string(),
             content_head: Some(content.lines().take(20).collect::<Vec<_>>().join("\n")),
+            encoding_warning: false,
         };
 
         // Delete old records (idempotent on first run)
@@ -1085,6 +1094,7 @@ fn t043_index_and_locate_with_kind_filter() {
             size_bytes: content.len() as u64,
             updated_at: "2026-01-01T00:00:00Z".to_string(),
             content_head: None,
+            encoding_warning: false,
         };
 
         cruxe_state::symbols::delete_symbols_for_file(
@@ -1199,6 +1209,7 @@ fn t043_index_discovers_multiple_symbols() {
             size_bytes: content.len() as u64,
             updated_at: "2026-01-01T00:00:00Z".to_string(),
             content_head: None,
+            encoding_warning: false,
         };
 
         for sym in &symbols {
@@ -1329,6 +1340,7 @@ fn t043_locate_nonexistent_symbol_returns_empty() {
             size_bytes: content.len() as u64,
             updated_at: "2026-01-01T00:00:00Z".to_string(),
             content_head: None,
+            encoding_warning: false,
         };
 
         cruxe_state::symbols::delete_symbols_for_file(
@@ -1438,6 +1450,7 @@ fn t054_search_error_intent() {
             size_bytes: content.len() as u64,
             updated_at: "2026-01-01T00:00:00Z".to_string(),
             content_head: Some(content.lines().take(20).collect::<Vec<_>>().join("\n")),
+            encoding_warning: false,
         };
 
         cruxe_state::symbols::delete_symbols_for_file(
@@ -1552,6 +1565,7 @@ fn t055_search_path_intent() {
             size_bytes: content.len() as u64,
             updated_at: "2026-01-01T00:00:00Z".to_string(),
             content_head: Some(content.lines().take(20).collect::<Vec<_>>().join("\n")),
+            encoding_warning: false,
         };
 
         cruxe_state::symbols::delete_symbols_for_file(
@@ -1677,6 +1691,7 @@ fn t056_search_snippet_results_include_symbol_metadata_when_available() {
             size_bytes: content.len() as u64,
             updated_at: "2026-01-01T00:00:00Z".to_string(),
             content_head: Some(content.lines().take(20).collect::<Vec<_>>().join("\n")),
+            encoding_warning: false,
         };
 
         cruxe_state::symbols::delete_symbols_for_file(
@@ -1793,6 +1808,7 @@ fn t071_ref_scoped_search_isolates_branches() {
             size_bytes: content.len() as u64,
             updated_at: "2026-01-01T00:00:00Z".to_string(),
             content_head: None,
+            encoding_warning: false,
         };
 
         cruxe_state::symbols::delete_symbols_for_file(
@@ -1860,6 +1876,7 @@ fn t071_ref_scoped_search_isolates_branches() {
             size_bytes: content.len() as u64,
             updated_at: "2026-01-01T00:00:00Z".to_string(),
             content_head: None,
+            encoding_warning: false,
         };
 
         cruxe_state::symbols::delete_symbols_for_file(
@@ -1909,6 +1926,11 @@ fn t071_ref_scoped_search_isolates_branches() {
         parent_symbol_id: None,
         visibility: Some("pub".to_string()),
         content: Some("fn branch_only_function() {}".to_string()),
+        attributes: Vec::new(),
+        byte_start: None,
+        byte_end: None,
+        doc_comment: None,
+        parsed_signature: None,
     };
 
     let extra_file_record = cruxe_core::types::FileRecord {
@@ -1924,6 +1946,7 @@ fn t071_ref_scoped_search_isolates_branches() {
         size_bytes: 28,
         updated_at: "2026-01-01T00:00:00Z".to_string(),
         content_head: Some("fn branch_only_function() {}".to_string()),
+        encoding_warning: false,
     };
 
     // Write the extra symbol via the standard writer pipeline
@@ -2339,6 +2362,7 @@ fn t342_get_call_graph_depth1_matches_golden() {
             direction: cruxe_query::call_graph::CallGraphDirection::Both,
             depth: 1,
             limit: 20,
+            max_breadth: cruxe_core::constants::DEFAULT_RELATION_MAX_BREADTH,
         },
     )
     .unwrap();
@@ -2379,6 +2403,7 @@ fn t343_get_call_graph_depth2_matches_golden() {
             direction: cruxe_query::call_graph::CallGraphDirection::Both,
             depth: 2,
             limit: 20,
+            max_breadth: cruxe_core::constants::DEFAULT_RELATION_MAX_BREADTH,
         },
     )
     .unwrap();
@@ -2560,6 +2585,7 @@ fn t081_relevance_benchmark_top1_precision() {
             size_bytes: content.len() as u64,
             updated_at: "2026-01-01T00:00:00Z".to_string(),
             content_head: Some(content.lines().take(20).collect::<Vec<_>>().join("\n")),
+            encoding_warning: false,
         };
 
         cruxe_state::symbols::delete_symbols_for_file(
@@ -3026,6 +3052,115 @@ fn t317_import_stale_state_sync_reindexes_only_delta() {
     assert_eq!(changed_files, 1);
 }
 
+#[test]
+fn t496_state_import_into_another_workspace_rewrites_root() {
+    let fixture = fixture_repo_path();
+    let tmp = tempdir().expect("tempdir");
+    let source = tmp.path().join("ci-checkout");
+    copy_dir_recursive(&fixture, &source);
+    let target = tmp.path().join("replica-checkout");
+    copy_dir_recursive(&fixture, &target);
+
+    let data_root = tmp.path().join("cc-data");
+    std::fs::create_dir_all(&data_root).unwrap();
+    let config_path = tmp.path().join("config.toml");
+    write_test_config(&config_path, &data_root);
+
+    run_cruxe_checked(&[
+        "--config".to_string(),
+        config_path.to_string_lossy().to_string(),
+        "init".to_string(),
+        "--path".to_string(),
+        source.to_string_lossy().to_string(),
+    ]);
+    run_cruxe_checked(&[
+        "--config".to_string(),
+        config_path.to_string_lossy().to_string(),
+        "index".to_string(),
+        "--path".to_string(),
+        source.to_string_lossy().to_string(),
+    ]);
+
+    let source_root = std::fs::canonicalize(&source)
+        .unwrap()
+        .to_string_lossy()
+        .to_string();
+    let source_project_id = cruxe_core::types::generate_project_id(&source_root);
+    let source_db = data_root
+        .join("data")
+        .join(&source_project_id)
+        .join(cruxe_core::constants::STATE_DB_FILE);
+    {
+        let conn = cruxe_state::db::open_connection(&source_db).unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO known_workspaces (workspace_path, project_id, last_used_at)
+             VALUES (?1, ?2, '2026-01-01T00:00:00Z')",
+            rusqlite::params![source_root, source_project_id],
+        )
+        .unwrap();
+    }
+
+    let bundle_path = tmp.path().join("index.tar.zst");
+    run_cruxe_checked(&[
+        "--config".to_string(),
+        config_path.to_string_lossy().to_string(),
+        "state".to_string(),
+        "export".to_string(),
+        bundle_path.to_string_lossy().to_string(),
+        "--workspace".to_string(),
+        source.to_string_lossy().to_string(),
+    ]);
+    run_cruxe_checked(&[
+        "--config".to_string(),
+        config_path.to_string_lossy().to_string(),
+        "state".to_string(),
+        "import".to_string(),
+        bundle_path.to_string_lossy().to_string(),
+        "--workspace".to_string(),
+        target.to_string_lossy().to_string(),
+    ]);
+
+    let target_root = std::fs::canonicalize(&target)
+        .unwrap()
+        .to_string_lossy()
+        .to_string();
+    let target_project_id = cruxe_core::types::generate_project_id(&target_root);
+    let target_dir = data_root.join("data").join(&target_project_id);
+    let conn =
+        cruxe_state::db::open_connection(&target_dir.join(cruxe_core::constants::STATE_DB_FILE))
+            .unwrap();
+    let project = cruxe_state::project::get_by_root(&conn, &target_root)
+        .unwrap()
+        .expect("project remapped to the replica root");
+    assert_eq!(project.project_id, target_project_id);
+    assert!(
+        cruxe_state::project::get_by_root(&conn, &source_root)
+            .unwrap()
+            .is_none()
+    );
+    let known: Vec<String> = conn
+        .prepare("SELECT workspace_path FROM known_workspaces")
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(known, vec![target_root.clone()]);
+
+    let index_set = cruxe_state::tantivy_index::IndexSet::open(&target_dir).unwrap();
+    let results = cruxe_query::search::search_code(
+        &index_set,
+        Some(&conn),
+        "validate_token",
+        Some(cruxe_core::constants::REF_LIVE),
+        Some("rust"),
+        10,
+        false,
+    )
+    .unwrap();
+    assert!(!results.results.is_empty());
+}
+
 #[test]
 fn t330_state_import_fails_fast_when_maintenance_lock_is_held() {
     let fixture = fixture_repo_path();
@@ -3148,7 +3283,7 @@ fn t321_prune_overlays_removes_stale_overlay_dirs() {
         status: "active".to_string(),
         eviction_eligible_at: None,
         created_at: "2026-01-01T00:00:00Z".to_string(),
-        last_accessed_at: "2026-02-24T00:00:00Z".to_string(),
+        last_accessed_at: cruxe_core::time::now_iso8601(),
     };
     cruxe_state::branch_state::upsert_branch_state(&conn, &stale).unwrap();
     cruxe_state::branch_state::upsert_branch_state(&conn, &recent).unwrap();
@@ -3270,3 +3405,473 @@ fn t235_serve_mcp_help_defaults_bind_to_localhost() {
         "default bind should remain localhost for security"
     );
 }
+
+#[test]
+fn t480_index_aborts_when_symbol_cap_exceeded() {
+    let tmp = tempdir().expect("tempdir");
+    let workspace = tmp.path().join("workspace");
+    create_synthetic_rust_repo(&workspace, 5);
+
+    let data_root = tmp.path().join("cc-data");
+    std::fs::create_dir_all(&data_root).unwrap();
+    let config_path = tmp.path().join("config.toml");
+    std::fs::write(
+        &config_path,
+        format!(
+            "[index]\nmax_symbols_per_ref = 2\n\n[storage]\ndata_dir = \"{}\"\n",
+            data_root.to_string_lossy()
+        ),
+    )
+    .expect("write test config");
+
+    run_cruxe_checked(&[
+        "--config".to_string(),
+        config_path.to_string_lossy().to_string(),
+        "init".to_string(),
+        "--path".to_string(),
+        workspace.to_string_lossy().to_string(),
+    ]);
+    let output = run_cruxe(&[
+        "--config".to_string(),
+        config_path.to_string_lossy().to_string(),
+        "index".to_string(),
+        "--path".to_string(),
+        workspace.to_string_lossy().to_string(),
+    ]);
+    assert!(
+        !output.status.success(),
+        "index should fail once the symbol cap is exceeded:\nstdout:{}\nstderr:{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("symbol cap exceeded"),
+        "expected symbol cap error, stderr:\n{}",
+        stderr
+    );
+
+    let workspace_canonical = std::fs::canonicalize(&workspace).unwrap();
+    let project_id = cruxe_core::types::generate_project_id(&workspace_canonical.to_string_lossy());
+    let db_path = data_root
+        .join("data")
+        .join(&project_id)
+        .join(cruxe_core::constants::STATE_DB_FILE);
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    let jobs = cruxe_state::jobs::get_recent_jobs(&conn, &project_id, 1).unwrap();
+    assert_eq!(jobs[0].status, "failed");
+    let error_data = jobs[0].error_data.as_ref().expect("job error data");
+    assert_eq!(error_data["code"], "symbol_cap_exceeded");
+    assert_eq!(error_data["max_symbols_per_ref"], 2);
+    assert_eq!(error_data["symbol_count"], 5);
+}
+
+#[test]
+fn t484_export_symbols_streams_sorted_ndjson_tuples() {
+    let tmp = tempdir().expect("tempdir");
+    let workspace = tmp.path().join("workspace");
+    create_synthetic_rust_repo(&workspace, 3);
+
+    let data_root = tmp.path().join("cc-data");
+    std::fs::create_dir_all(&data_root).unwrap();
+    let config_path = tmp.path().join("config.toml");
+    write_test_config(&config_path, &data_root);
+    let config_arg = config_path.to_string_lossy().to_string();
+    let workspace_arg = workspace.to_string_lossy().to_string();
+
+    run_cruxe_checked(&[
+        "--config".to_string(),
+        config_arg.clone(),
+        "init".to_string(),
+        "--path".to_string(),
+        workspace_arg.clone(),
+    ]);
+    run_cruxe_checked(&[
+        "--config".to_string(),
+        config_arg.clone(),
+        "index".to_string(),
+        "--path".to_string(),
+        workspace_arg.clone(),
+    ]);
+    let output = run_cruxe(&[
+        "--config".to_string(),
+        config_arg,
+        "export-symbols".to_string(),
+        "--workspace".to_string(),
+        workspace_arg,
+    ]);
+    assert!(
+        output.status.success(),
+        "export-symbols failed:\nstderr:{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let rows: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("each line should be a json tuple"))
+        .collect();
+    let names: Vec<&str> = rows.iter().map(|row| row[0].as_str().unwrap()).collect();
+    assert_eq!(names, vec!["func_0", "func_1", "func_2"]);
+    assert_eq!(rows[0][1], "function");
+    assert_eq!(rows[0][2], "src/file_00000.rs");
+    assert_eq!(rows[0][3], 1);
+}
+
+#[test]
+fn t485_non_git_workspace_uses_configured_default_ref() {
+    let tmp = tempdir().expect("tempdir");
+    let workspace = tmp.path().join("workspace");
+    create_synthetic_rust_repo(&workspace, 2);
+
+    let data_root = tmp.path().join("cc-data");
+    std::fs::create_dir_all(&data_root).unwrap();
+    let config_path = tmp.path().join("config.toml");
+    std::fs::write(
+        &config_path,
+        format!(
+            "[storage]\ndata_dir = \"{}\"\n\n[index]\ndefault_ref = \"snapshot\"\n",
+            data_root.to_string_lossy()
+        ),
+    )
+    .expect("write test config");
+    let config_arg = config_path.to_string_lossy().to_string();
+    let workspace_arg = workspace.to_string_lossy().to_string();
+
+    for command in ["init", "index"] {
+        run_cruxe_checked(&[
+            "--config".to_string(),
+            config_arg.clone(),
+            command.to_string(),
+            "--path".to_string(),
+            workspace_arg.clone(),
+        ]);
+    }
+
+    let export = |extra: &[&str]| {
+        let mut args = vec![
+            "--config".to_string(),
+            config_arg.clone(),
+            "export-symbols".to_string(),
+            "--workspace".to_string(),
+            workspace_arg.clone(),
+        ];
+        args.extend(extra.iter().map(|arg| arg.to_string()));
+        let output = run_cruxe(&args);
+        assert!(
+            output.status.success(),
+            "export-symbols failed:\nstderr:{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).lines().count()
+    };
+
+    // Default resolution and the explicit configured ref see the same symbols;
+    // nothing was written under the built-in `live` ref.
+    assert_eq!(export(&[]), 2);
+    assert_eq!(export(&["--ref", "snapshot"]), 2);
+    assert_eq!(export(&["--ref", "live"]), 0);
+}
+
+#[test]
+fn t498_text_only_extensions_make_sql_content_searchable() {
+    let tmp = tempdir().expect("tempdir");
+    let workspace = tmp.path().join("workspace");
+    create_synthetic_rust_repo(&workspace, 1);
+    let migrations = workspace.join("db/migrations");
+    std::fs::create_dir_all(&migrations).unwrap();
+    // Put the table well past the first lines so only full-content indexing finds it.
+    let mut sql = "-- schema bootstrap\n".repeat(40);
+    sql.push_str("CREATE TABLE ledger_adjustments (id INTEGER PRIMARY KEY);\n");
+    std::fs::write(migrations.join("001_init.sql"), sql).unwrap();
+
+    let data_root = tmp.path().join("cc-data");
+    std::fs::create_dir_all(&data_root).unwrap();
+    let config_path = tmp.path().join("config.toml");
+    std::fs::write(
+        &config_path,
+        format!(
+            "[storage]\ndata_dir = \"{}\"\n\n[index]\ntext_only_extensions = [\"sql\"]\n",
+            data_root.to_string_lossy()
+        ),
+    )
+    .expect("write test config");
+    let config_arg = config_path.to_string_lossy().to_string();
+    let workspace_arg = workspace.to_string_lossy().to_string();
+
+    for command in ["init", "index"] {
+        run_cruxe_checked(&[
+            "--config".to_string(),
+            config_arg.clone(),
+            command.to_string(),
+            "--path".to_string(),
+            workspace_arg.clone(),
+        ]);
+    }
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_cruxe"))
+        .args([
+            "--config",
+            &config_arg,
+            "search",
+            "where is the ledger adjustments table",
+        ])
+        .current_dir(&workspace)
+        .output()
+        .expect("run cruxe search");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "search failed:\nstderr:{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains("db/migrations/001_init.sql"),
+        "expected a file hit for the migration:\n{stdout}"
+    );
+}
+
+#[test]
+fn t507_reindex_skips_files_with_unchanged_content_hash() {
+    let tmp = tempdir().expect("tempdir");
+    let workspace = tmp.path().join("workspace");
+    create_synthetic_rust_repo(&workspace, 3);
+
+    let data_root = tmp.path().join("cc-data");
+    std::fs::create_dir_all(&data_root).unwrap();
+    let config_path = tmp.path().join("config.toml");
+    write_test_config(&config_path, &data_root);
+    let index_args = |extra: &[&str]| {
+        let mut args = vec![
+            "--config".to_string(),
+            config_path.to_string_lossy().to_string(),
+            "index".to_string(),
+            "--path".to_string(),
+            workspace.to_string_lossy().to_string(),
+        ];
+        args.extend(extra.iter().map(|arg| arg.to_string()));
+        args
+    };
+
+    run_cruxe_checked(&[
+        "--config".to_string(),
+        config_path.to_string_lossy().to_string(),
+        "init".to_string(),
+        "--path".to_string(),
+        workspace.to_string_lossy().to_string(),
+    ]);
+    run_cruxe_checked(&index_args(&[]));
+
+    let workspace_canonical = std::fs::canonicalize(&workspace).unwrap();
+    let project_id = cruxe_core::types::generate_project_id(&workspace_canonical.to_string_lossy());
+    let db_path = data_root
+        .join("data")
+        .join(&project_id)
+        .join(cruxe_core::constants::STATE_DB_FILE);
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    // Job timestamps have second resolution, so pick the new job by id rather
+    // than relying on `created_at` ordering.
+    let mut seen_jobs: HashSet<String> = HashSet::new();
+    let mut latest_job = || {
+        let jobs = cruxe_state::jobs::get_recent_jobs(&conn, &project_id, 10).unwrap();
+        let job = jobs
+            .into_iter()
+            .find(|job| !seen_jobs.contains(&job.job_id))
+            .expect("a new index job");
+        seen_jobs.insert(job.job_id.clone());
+        job
+    };
+    let initial = latest_job();
+    assert_eq!(initial.files_indexed, 3);
+    assert_eq!(initial.files_unchanged, 0);
+
+    std::fs::write(
+        workspace.join("src/file_00001.rs"),
+        "pub fn func_1_renamed() {}\n",
+    )
+    .expect("edit synthetic file");
+    run_cruxe_checked(&index_args(&[]));
+    let incremental = latest_job();
+    assert_eq!(incremental.status, "published");
+    assert_eq!(incremental.files_indexed, 1);
+    assert_eq!(incremental.files_unchanged, 2);
+
+    run_cruxe_checked(&index_args(&["--force"]));
+    let forced = latest_job();
+    assert_eq!(forced.files_indexed, 3);
+    assert_eq!(forced.files_unchanged, 0);
+}
+
+#[test]
+fn t515_index_exclude_globs_skip_files_and_record_count() {
+    let tmp = tempdir().expect("tempdir");
+    let workspace = tmp.path().join("workspace");
+    create_synthetic_rust_repo(&workspace, 2);
+    let generated = workspace.join("proto/gen");
+    std::fs::create_dir_all(&generated).unwrap();
+    std::fs::write(generated.join("api.rs"), "pub fn generated_api() {}\n").unwrap();
+    std::fs::write(generated.join("types.rs"), "pub struct GeneratedType;\n").unwrap();
+
+    let data_root = tmp.path().join("cc-data");
+    std::fs::create_dir_all(&data_root).unwrap();
+    let config_path = tmp.path().join("config.toml");
+    std::fs::write(
+        &config_path,
+        format!(
+            "[storage]\ndata_dir = \"{}\"\n\n[index]\nexclude = [\"proto\"]\n",
+            data_root.to_string_lossy()
+        ),
+    )
+    .expect("write test config");
+    let config_arg = config_path.to_string_lossy().to_string();
+    let workspace_arg = workspace.to_string_lossy().to_string();
+
+    for command in ["init", "index"] {
+        run_cruxe_checked(&[
+            "--config".to_string(),
+            config_arg.clone(),
+            command.to_string(),
+            "--path".to_string(),
+            workspace_arg.clone(),
+        ]);
+    }
+
+    let workspace_canonical = std::fs::canonicalize(&workspace).unwrap();
+    let project_id = cruxe_core::types::generate_project_id(&workspace_canonical.to_string_lossy());
+    let db_path = data_root
+        .join("data")
+        .join(&project_id)
+        .join(cruxe_core::constants::STATE_DB_FILE);
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    let job = cruxe_state::jobs::get_recent_jobs(&conn, &project_id, 1)
+        .unwrap()
+        .into_iter()
+        .next()
+        .expect("index job");
+    assert_eq!(job.status, "published");
+    assert_eq!(job.files_indexed, 2);
+    assert_eq!(job.files_excluded, 2);
+    assert_eq!(job.index_commits, 1, "two files fit in one commit batch");
+    assert!(job.files_per_second.is_some_and(|rate| rate > 0.0));
+
+    let ref_name = job.r#ref.clone();
+    let excluded_symbols = cruxe_state::symbols::list_symbols_in_file(
+        &conn,
+        &project_id,
+        &ref_name,
+        "proto/gen/api.rs",
+    )
+    .unwrap();
+    assert!(excluded_symbols.is_empty());
+}
+
+/// Commit `files` on top of HEAD in a (possibly bare) repository without
+/// touching any working tree.
+fn commit_blobs(repo: &git2::Repository, files: &[(&str, &str)]) -> String {
+    let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    let base = match &parent {
+        Some(commit) => commit.tree().unwrap(),
+        None => repo
+            .find_tree(repo.treebuilder(None).unwrap().write().unwrap())
+            .unwrap(),
+    };
+    let mut update = git2::build::TreeUpdateBuilder::new();
+    for (path, content) in files {
+        let blob = repo.blob(content.as_bytes()).unwrap();
+        update.upsert(*path, blob, git2::FileMode::Blob);
+    }
+    let tree = repo
+        .find_tree(update.create_updated(repo, &base).unwrap())
+        .unwrap();
+    let signature = git2::Signature::now("ci", "ci@example.com").unwrap();
+    let parents: Vec<&git2::Commit<'_>> = parent.iter().collect();
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "commit",
+        &tree,
+        &parents,
+    )
+    .unwrap()
+    .to_string()
+}
+
+#[test]
+fn t516_index_commit_reads_bare_repository_into_commit_scoped_ref() {
+    let tmp = tempdir().expect("tempdir");
+    let bare = tmp.path().join("repo.git");
+    let repo = git2::Repository::init_bare(&bare).unwrap();
+    let first = commit_blobs(&repo, &[("src/auth.rs", "pub fn first_commit_fn() {}\n")]);
+    let second = commit_blobs(
+        &repo,
+        &[
+            ("src/auth.rs", "pub fn second_commit_fn() {}\n"),
+            ("src/db.rs", "pub fn connect() {}\n"),
+            (
+                "node_modules/dep/index.ts",
+                "export function vendored() {}\n",
+            ),
+        ],
+    );
+
+    let data_root = tmp.path().join("cc-data");
+    std::fs::create_dir_all(&data_root).unwrap();
+    let config_path = tmp.path().join("config.toml");
+    write_test_config(&config_path, &data_root);
+    let cruxe = |args: &[&str]| {
+        let mut full = vec![
+            "--config".to_string(),
+            config_path.to_string_lossy().to_string(),
+        ];
+        full.extend(args.iter().map(|arg| arg.to_string()));
+        full.extend(["--path".to_string(), bare.to_string_lossy().to_string()]);
+        run_cruxe_checked(&full);
+    };
+    cruxe(&["init"]);
+    cruxe(&["index", "--commit", &first[..10]]);
+    cruxe(&["index", "--commit", "HEAD"]);
+
+    let bare_canonical = std::fs::canonicalize(&bare).unwrap();
+    let project_id = cruxe_core::types::generate_project_id(&bare_canonical.to_string_lossy());
+    let db_path = data_root
+        .join("data")
+        .join(&project_id)
+        .join(cruxe_core::constants::STATE_DB_FILE);
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    let jobs = cruxe_state::jobs::get_recent_jobs(&conn, &project_id, 2).unwrap();
+    let job_for = |commit: &str| {
+        jobs.iter()
+            .find(|job| job.head_commit.as_deref() == Some(commit))
+            .unwrap_or_else(|| panic!("no job for {commit}: {jobs:?}"))
+    };
+
+    let first_ref = format!("commit/{first}");
+    let first_job = job_for(&first);
+    assert_eq!(first_job.r#ref, first_ref);
+    assert_eq!(first_job.status, "published");
+    assert_eq!(first_job.files_indexed, 1);
+    let names_in = |ref_name: &str, path: &str| -> Vec<String> {
+        cruxe_state::symbols::list_symbols_in_file(&conn, &project_id, ref_name, path)
+            .unwrap()
+            .into_iter()
+            .map(|symbol| symbol.name)
+            .collect()
+    };
+    assert_eq!(names_in(&first_ref, "src/auth.rs"), vec!["first_commit_fn"]);
+
+    let second_ref = format!("commit/{second}");
+    let second_job = job_for(&second);
+    assert_eq!(second_job.r#ref, second_ref);
+    assert_eq!(second_job.files_indexed, 2, "node_modules stays ignored");
+    assert_eq!(
+        names_in(&second_ref, "src/auth.rs"),
+        vec!["second_commit_fn"]
+    );
+    assert_eq!(names_in(&first_ref, "src/auth.rs"), vec!["first_commit_fn"]);
+
+    let state = cruxe_state::branch_state::get_branch_state(&conn, &project_id, &second_ref)
+        .unwrap()
+        .expect("branch state for commit ref");
+    assert_eq!(state.last_indexed_commit, second);
+}
@@ -1,9 +1,12 @@
 use crate::constants;
 use crate::error::ConfigError;
 use crate::languages;
-use crate::types::{FreshnessPolicy, PolicyMode, QueryIntent, RankingExplainLevel, SemanticMode};
+use crate::types::{
+    DetailLevel, FreshnessPolicy, PolicyMode, QueryIntent, RankingExplainLevel, SemanticMode,
+};
 use serde::de::Deserializer;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -18,6 +21,8 @@ pub struct Config {
     pub logging: LoggingConfig,
     #[serde(default)]
     pub debug: DebugConfig,
+    #[serde(default)]
+    pub server: ServerConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +33,66 @@ pub struct IndexConfig {
     pub default_limit: usize,
     #[serde(default = "default_languages")]
     pub languages: Vec<String>,
+    /// Abort indexing once a ref holds more symbols than this. `0` disables the guard.
+    #[serde(default = "default_max_symbols_per_ref")]
+    pub max_symbols_per_ref: u64,
+    /// Ref used for workspaces that are not git repositories.
+    #[serde(default = "default_index_ref")]
+    pub default_ref: String,
+    /// Kill an indexer subprocess and fail its job after this many seconds. `0` disables the limit.
+    #[serde(default = "default_max_job_duration_secs")]
+    pub max_job_duration_secs: u64,
+    /// Give up on parsing a single file after this many milliseconds and record it
+    /// as skipped with reason `parse_timeout`. `0` disables the limit.
+    #[serde(default = "default_parse_timeout_ms")]
+    pub parse_timeout_ms: u64,
+    /// Per-language overrides of `parse_timeout_ms`, keyed by language name.
+    #[serde(default)]
+    pub parse_timeout_ms_by_language: BTreeMap<String, u64>,
+    /// Stop descending into syntax-tree nodes nested deeper than this while
+    /// extracting symbols and call sites, so pathologically nested (usually
+    /// generated) files cannot overflow the stack. `0` disables the cap.
+    #[serde(default = "default_max_syntax_depth")]
+    pub max_syntax_depth: usize,
+    /// Store at most this many lines of each snippet body; the rest is replaced
+    /// by a truncation marker. `0` stores bodies in full.
+    #[serde(default = "default_max_snippet_lines")]
+    pub max_snippet_lines: usize,
+    /// File extensions (e.g. `sql`, `yaml`) indexed as plain text: their content
+    /// is full-text searchable as file hits, but no symbols are extracted.
+    #[serde(default)]
+    pub text_only_extensions: Vec<String>,
+    /// Globs (e.g. `vendor/**`, `proto`) of workspace-relative paths the
+    /// scanner leaves out; a pattern matching a directory excludes everything
+    /// below it.
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Refuse to start indexing unless the data volume has at least this much
+    /// free space (MiB), or twice the current index size if larger. `0` disables the check.
+    #[serde(default = "default_min_free_disk_mb")]
+    pub min_free_disk_mb: u64,
+    /// Worker threads used to read, parse and extract files while indexing.
+    /// `0` uses one worker per available core.
+    #[serde(default)]
+    pub parallelism: usize,
+    /// Commit the Tantivy indexes after every this many written files during a
+    /// full index run, instead of once at the end. `0` commits only at the end.
+    #[serde(default = "default_commit_every_files")]
+    pub commit_every_files: usize,
+    /// Abort a full index scan once it has collected more source files than
+    /// this. `0` disables the limit.
+    #[serde(default = "default_max_scan_files")]
+    pub max_scan_files: u64,
+    /// Abort a full index scan once the collected source files add up to more
+    /// bytes than this. `0` disables the limit.
+    #[serde(default = "default_max_scan_bytes")]
+    pub max_scan_bytes: u64,
+    /// Index full-text content with the identifier-aware tokenizer, which also
+    /// emits the camelCase/snake_case parts of each word so `getUserById`
+    /// matches `get_user_by_id`. Fixed when the index is built; changing it
+    /// requires `cruxe index --force`.
+    #[serde(default)]
+    pub identifier_tokenizer: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -50,6 +115,27 @@ pub struct SearchConfig {
     pub ranking_explain_level: String,
     #[serde(default = "default_max_response_bytes")]
     pub max_response_bytes: usize,
+    /// `detail_level` used by `search_code` and `locate_symbol` when the
+    /// argument is absent: `location`, `signature` or `context`.
+    #[serde(default = "default_detail_level")]
+    pub default_detail_level: String,
+    /// `limit` used by `search_code` and `locate_symbol` when the argument is
+    /// absent.
+    #[serde(default = "default_search_limit")]
+    pub default_limit: usize,
+    /// Expand identifier queries into snake/camel/Pascal spellings so one
+    /// query matches equivalent symbols across language naming conventions.
+    #[serde(default)]
+    pub fold_naming_conventions: bool,
+    /// Expand natural-language queries with synonyms (`auth` → `authentication`)
+    /// from `synonyms` plus a built-in set of common programming abbreviations.
+    #[serde(default)]
+    pub expand_synonyms: bool,
+    /// Extra synonym groups keyed by term; each key and its values expand to one another.
+    #[serde(default)]
+    pub synonyms: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    pub ranking_log: RankingLogConfig,
     #[serde(default)]
     pub ranking_signal_budgets: RankingSignalBudgetConfig,
     #[serde(default)]
@@ -62,12 +148,81 @@ pub struct SearchConfig {
     pub policy: RetrievalPolicyConfig,
 }
 
+/// Opt-in local log of per-query ranking outcomes for offline relevance tuning.
+/// Records are appended to a JSONL file and never leave the machine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankingLogConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Output file. Empty resolves to `<storage.data_dir>/ranking-log.jsonl`.
+    #[serde(default)]
+    pub path: String,
+    /// Also record the raw query text. Off by default: only the intent and
+    /// anonymized query features are written.
+    #[serde(default)]
+    pub include_query_text: bool,
+    /// Number of top results whose scores are recorded per query.
+    #[serde(default = "default_ranking_log_top_n")]
+    pub top_n: usize,
+}
+
+impl Default for RankingLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: String::new(),
+            include_query_text: false,
+            top_n: default_ranking_log_top_n(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DebugConfig {
     #[serde(default)]
     pub ranking_reasons: bool,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ServerConfig {
+    /// Path prefixes under which MCP clients may point the `workspace` parameter.
+    /// Merged with `--allowed-root`; empty means only the server's own workspace.
+    #[serde(default)]
+    pub allowed_workspace_roots: Vec<String>,
+    /// Base directory under which HTTP clients may relocate `storage.data_dir`
+    /// per request via the `X-Cruxe-Data-Dir` header. Empty rejects the header.
+    #[serde(default)]
+    pub tenant_data_dir_root: String,
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+}
+
+/// Per-client token bucket for the HTTP JSON-RPC endpoint (`POST /`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    /// Sustained requests per second per client address; `0` disables limiting.
+    #[serde(default = "default_rate_limit_requests_per_second")]
+    pub requests_per_second: f64,
+    /// Bucket size: requests a client may issue back-to-back before throttling.
+    #[serde(default = "default_rate_limit_burst")]
+    pub burst: u32,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            requests_per_second: default_rate_limit_requests_per_second(),
+            burst: default_rate_limit_burst(),
+        }
+    }
+}
+
+impl RateLimitConfig {
+    pub fn enabled(&self) -> bool {
+        self.requests_per_second.is_finite() && self.requests_per_second > 0.0 && self.burst > 0
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LoggingConfig {
     #[serde(default = "default_log_level")]
@@ -153,6 +308,12 @@ pub struct RankingSignalBudgetConfig {
     pub kind_match: RankingSignalBudgetRange,
     #[serde(default = "default_budget_test_file_penalty")]
     pub test_file_penalty: RankingSignalBudgetRange,
+    /// Nudge for results in the request's preferred language (`prefer_language`).
+    #[serde(default = "default_budget_language_preference")]
+    pub language_preference: RankingSignalBudgetRange,
+    /// Penalty for one- and two-line symbols on natural-language queries.
+    #[serde(default = "default_budget_short_match_penalty")]
+    pub short_match_penalty: RankingSignalBudgetRange,
     #[serde(default = "default_budget_secondary_cap_when_exact")]
     pub secondary_cap_when_exact: RankingSignalBudgetRange,
 }
@@ -334,6 +495,36 @@ fn default_max_file_size() -> u64 {
 fn default_limit() -> usize {
     constants::DEFAULT_LIMIT
 }
+fn default_max_symbols_per_ref() -> u64 {
+    constants::MAX_SYMBOLS_PER_REF
+}
+fn default_index_ref() -> String {
+    constants::REF_LIVE.into()
+}
+fn default_max_job_duration_secs() -> u64 {
+    constants::DEFAULT_MAX_JOB_DURATION_SECS
+}
+fn default_parse_timeout_ms() -> u64 {
+    constants::DEFAULT_PARSE_TIMEOUT_MS
+}
+fn default_max_syntax_depth() -> usize {
+    constants::DEFAULT_MAX_SYNTAX_DEPTH
+}
+fn default_max_snippet_lines() -> usize {
+    constants::DEFAULT_MAX_SNIPPET_LINES
+}
+fn default_commit_every_files() -> usize {
+    constants::DEFAULT_COMMIT_EVERY_FILES
+}
+fn default_max_scan_files() -> u64 {
+    constants::DEFAULT_MAX_SCAN_FILES
+}
+fn default_max_scan_bytes() -> u64 {
+    constants::DEFAULT_MAX_SCAN_BYTES
+}
+fn default_min_free_disk_mb() -> u64 {
+    constants::DEFAULT_MIN_FREE_DISK_MB
+}
 fn default_languages() -> Vec<String> {
     languages::supported_indexable_languages()
         .iter()
@@ -361,6 +552,15 @@ fn default_ranking_explain_level() -> String {
 fn default_max_response_bytes() -> usize {
     64 * 1024
 }
+fn default_detail_level() -> String {
+    "signature".into()
+}
+fn default_search_limit() -> usize {
+    10
+}
+fn default_ranking_log_top_n() -> usize {
+    10
+}
 fn default_budget_numeric_sentinel() -> f64 {
     f64::NAN
 }
@@ -418,6 +618,20 @@ fn default_budget_test_file_penalty() -> RankingSignalBudgetRange {
         default: -0.5,
     }
 }
+fn default_budget_language_preference() -> RankingSignalBudgetRange {
+    RankingSignalBudgetRange {
+        min: 0.0,
+        max: 1.0,
+        default: 0.5,
+    }
+}
+fn default_budget_short_match_penalty() -> RankingSignalBudgetRange {
+    RankingSignalBudgetRange {
+        min: -1.0,
+        max: 0.0,
+        default: -0.3,
+    }
+}
 fn default_budget_secondary_cap_when_exact() -> RankingSignalBudgetRange {
     RankingSignalBudgetRange {
         min: 0.5,
@@ -624,6 +838,12 @@ fn default_policy_opa_query() -> String {
 fn default_log_level() -> String {
     "info".into()
 }
+fn default_rate_limit_requests_per_second() -> f64 {
+    20.0
+}
+fn default_rate_limit_burst() -> u32 {
+    40
+}
 
 impl Default for IndexConfig {
     fn default() -> Self {
@@ -631,10 +851,64 @@ impl Default for IndexConfig {
             max_file_size: default_max_file_size(),
             default_limit: default_limit(),
             languages: default_languages(),
+            max_symbols_per_ref: default_max_symbols_per_ref(),
+            default_ref: default_index_ref(),
+            max_job_duration_secs: default_max_job_duration_secs(),
+            parse_timeout_ms: default_parse_timeout_ms(),
+            parse_timeout_ms_by_language: BTreeMap::new(),
+            max_syntax_depth: default_max_syntax_depth(),
+            max_snippet_lines: default_max_snippet_lines(),
+            text_only_extensions: Vec::new(),
+            exclude: Vec::new(),
+            min_free_disk_mb: default_min_free_disk_mb(),
+            parallelism: 0,
+            commit_every_files: default_commit_every_files(),
+            max_scan_files: default_max_scan_files(),
+            max_scan_bytes: default_max_scan_bytes(),
+            identifier_tokenizer: false,
         }
     }
 }
 
+impl IndexConfig {
+    /// Ref that non-git workspaces are indexed and queried under.
+    ///
+    /// Blank values fall back to `live` so a stray empty setting cannot
+    /// produce an unnamed ref.
+    pub fn non_vcs_ref(&self) -> &str {
+        let trimmed = self.default_ref.trim();
+        if trimmed.is_empty() {
+            constants::REF_LIVE
+        } else {
+            trimmed
+        }
+    }
+
+    /// Wall-clock limit for an indexer subprocess, or `None` when disabled.
+    pub fn max_job_duration(&self) -> Option<std::time::Duration> {
+        (self.max_job_duration_secs > 0)
+            .then(|| std::time::Duration::from_secs(self.max_job_duration_secs))
+    }
+
+    /// Parse time budget for one file of `language` in milliseconds (`0` = unlimited).
+    pub fn parse_timeout_ms_for(&self, language: &str) -> u64 {
+        self.parse_timeout_ms_by_language
+            .get(language)
+            .copied()
+            .unwrap_or(self.parse_timeout_ms)
+    }
+
+    /// Number of indexing workers, resolving `0` to the available core count.
+    pub fn worker_threads(&self) -> usize {
+        if self.parallelism > 0 {
+            return self.parallelism;
+        }
+        std::thread::available_parallelism()
+            .map(std::num::NonZeroUsize::get)
+            .unwrap_or(1)
+    }
+}
+
 impl Default for StorageConfig {
     fn default() -> Self {
         Self {
@@ -652,6 +926,12 @@ impl Default for SearchConfig {
             freshness_policy: default_freshness_policy(),
             ranking_explain_level: default_ranking_explain_level(),
             max_response_bytes: default_max_response_bytes(),
+            default_detail_level: default_detail_level(),
+            default_limit: default_search_limit(),
+            fold_naming_conventions: false,
+            expand_synonyms: false,
+            synonyms: BTreeMap::new(),
+            ranking_log: RankingLogConfig::default(),
             ranking_signal_budgets: RankingSignalBudgetConfig::default(),
             intent: SearchIntentConfig::default(),
             adaptive_plan: AdaptivePlanConfig::default(),
@@ -670,6 +950,8 @@ impl Default for RankingSignalBudgetConfig {
             definition_boost: default_budget_definition_boost(),
             kind_match: default_budget_kind_match(),
             test_file_penalty: default_budget_test_file_penalty(),
+            language_preference: default_budget_language_preference(),
+            short_match_penalty: default_budget_short_match_penalty(),
             secondary_cap_when_exact: default_budget_secondary_cap_when_exact(),
         }
     }
@@ -834,6 +1116,10 @@ impl SearchConfig {
         parse_ranking_explain_level(&self.ranking_explain_level).unwrap_or(RankingExplainLevel::Off)
     }
 
+    pub fn default_detail_level_typed(&self) -> DetailLevel {
+        parse_detail_level(&self.default_detail_level).unwrap_or(DetailLevel::Signature)
+    }
+
     pub fn semantic_mode_typed(&self) -> SemanticMode {
         parse_semantic_mode(&self.semantic.mode).unwrap_or(SemanticMode::Off)
     }
@@ -876,6 +1162,7 @@ impl SearchConfig {
                 self.semantic.overrides.error.ratio,
                 "search.semantic.overrides.error.ratio",
             ),
+            QueryIntent::Phrase => (None, "search.semantic.ratio"),
         };
         clamp_unit_f64_with_warning(
             config_override.unwrap_or(self.semantic.ratio),
@@ -1005,6 +1292,11 @@ impl Config {
             normalize_freshness_policy(&config.search.freshness_policy);
         config.search.ranking_explain_level =
             normalize_ranking_explain_level(&config.search.ranking_explain_level);
+        config.search.default_detail_level =
+            normalize_detail_level(&config.search.default_detail_level);
+        if config.search.default_limit == 0 {
+            config.search.default_limit = default_search_limit();
+        }
         config.search.ranking_signal_budgets = config.search.ranking_signal_budgets.normalized();
         config.search.intent = config.search.intent.normalized();
         config.search.adaptive_plan = normalize_adaptive_plan_config(config.search.adaptive_plan);
@@ -1229,6 +1521,16 @@ impl Config {
 
         // Expand ~ in data_dir
         config.storage.data_dir = expand_tilde(&config.storage.data_dir);
+        config.server.tenant_data_dir_root =
+            expand_tilde(config.server.tenant_data_dir_root.trim());
+        config.search.ranking_log.path = if config.search.ranking_log.path.trim().is_empty() {
+            PathBuf::from(&config.storage.data_dir)
+                .join(constants::RANKING_LOG_FILE)
+                .to_string_lossy()
+                .to_string()
+        } else {
+            expand_tilde(config.search.ranking_log.path.trim())
+        };
 
         Ok(config)
     }
@@ -1336,6 +1638,70 @@ fn apply_env_overrides(config: &mut Config) {
     {
         config.index.default_limit = n;
     }
+    if let Ok(v) = std::env::var("CRUXE_INDEX_MAX_SYMBOLS_PER_REF")
+        && let Ok(n) = v.parse()
+    {
+        config.index.max_symbols_per_ref = n;
+    }
+    if let Ok(v) = std::env::var("CRUXE_INDEX_DEFAULT_REF") {
+        config.index.default_ref = v;
+    }
+    if let Ok(v) = std::env::var("CRUXE_INDEX_MAX_JOB_DURATION_SECS")
+        && let Ok(n) = v.parse()
+    {
+        config.index.max_job_duration_secs = n;
+    }
+    if let Ok(v) = std::env::var("CRUXE_INDEX_PARSE_TIMEOUT_MS")
+        && let Ok(n) = v.parse()
+    {
+        config.index.parse_timeout_ms = n;
+    }
+    if let Ok(v) = std::env::var("CRUXE_INDEX_MAX_SYNTAX_DEPTH")
+        && let Ok(n) = v.parse()
+    {
+        config.index.max_syntax_depth = n;
+    }
+    if let Ok(v) = std::env::var("CRUXE_INDEX_MAX_SNIPPET_LINES")
+        && let Ok(n) = v.parse()
+    {
+        config.index.max_snippet_lines = n;
+    }
+    if let Ok(v) = std::env::var("CRUXE_INDEX_MIN_FREE_DISK_MB")
+        && let Ok(n) = v.parse()
+    {
+        config.index.min_free_disk_mb = n;
+    }
+    if let Ok(v) = std::env::var("CRUXE_INDEX_COMMIT_EVERY_FILES")
+        && let Ok(n) = v.parse()
+    {
+        config.index.commit_every_files = n;
+    }
+    if let Ok(v) = std::env::var("CRUXE_INDEX_MAX_SCAN_FILES")
+        && let Ok(n) = v.parse()
+    {
+        config.index.max_scan_files = n;
+    }
+    if let Ok(v) = std::env::var("CRUXE_INDEX_MAX_SCAN_BYTES")
+        && let Ok(n) = v.parse()
+    {
+        config.index.max_scan_bytes = n;
+    }
+    if let Ok(v) = std::env::var("CRUXE_INDEX_PARALLELISM")
+        && let Ok(n) = v.parse()
+    {
+        config.index.parallelism = n;
+    }
+    if let Ok(v) = std::env::var("CRUXE_INDEX_IDENTIFIER_TOKENIZER")
+        && let Some(parsed) = parse_env_bool(&v)
+    {
+        config.index.identifier_tokenizer = parsed;
+    }
+    if let Ok(v) = std::env::var("CRUXE_INDEX_TEXT_ONLY_EXTENSIONS") {
+        config.index.text_only_extensions = parse_csv_env_list(&v);
+    }
+    if let Ok(v) = std::env::var("CRUXE_INDEX_EXCLUDE") {
+        config.index.exclude = parse_csv_env_list(&v);
+    }
     if let Ok(v) = std::env::var("CRUXE_SEARCH_DEFAULT_REF") {
         config.search.default_ref = v;
     }
@@ -1355,6 +1721,24 @@ fn apply_env_overrides(config: &mut Config) {
     {
         config.search.max_response_bytes = n;
     }
+    if let Ok(v) = std::env::var("CRUXE_SEARCH_DEFAULT_DETAIL_LEVEL") {
+        config.search.default_detail_level = v;
+    }
+    if let Ok(v) = std::env::var("CRUXE_SEARCH_DEFAULT_LIMIT")
+        && let Ok(n) = v.parse()
+    {
+        config.search.default_limit = n;
+    }
+    if let Ok(v) = std::env::var("CRUXE_SEARCH_FOLD_NAMING_CONVENTIONS")
+        && let Some(parsed) = parse_env_bool(&v)
+    {
+        config.search.fold_naming_conventions = parsed;
+    }
+    if let Ok(v) = std::env::var("CRUXE_SEARCH_EXPAND_SYNONYMS")
+        && let Some(parsed) = parse_env_bool(&v)
+    {
+        config.search.expand_synonyms = parsed;
+    }
     apply_env_ranking_budget_default(
         config,
         "CRUXE_SEARCH_RANKING_BUDGET_EXACT_MATCH_DEFAULT",
@@ -1397,6 +1781,18 @@ fn apply_env_overrides(config: &mut Config) {
         "search.ranking_signal_budgets.secondary_cap_when_exact.default",
         |budgets, parsed| budgets.secondary_cap_when_exact.default = parsed,
     );
+    apply_env_ranking_budget_default(
+        config,
+        "CRUXE_SEARCH_RANKING_BUDGET_LANGUAGE_PREFERENCE_DEFAULT",
+        "search.ranking_signal_budgets.language_preference.default",
+        |budgets, parsed| budgets.language_preference.default = parsed,
+    );
+    apply_env_ranking_budget_default(
+        config,
+        "CRUXE_SEARCH_RANKING_BUDGET_SHORT_MATCH_PENALTY_DEFAULT",
+        "search.ranking_signal_budgets.short_match_penalty.default",
+        |budgets, parsed| budgets.short_match_penalty.default = parsed,
+    );
     if let Ok(v) = std::env::var("CRUXE_SEARCH_POLICY_MODE") {
         config.search.policy.mode = v;
     }
@@ -1677,6 +2073,35 @@ fn apply_env_overrides(config: &mut Config) {
     {
         config.search.semantic.allow_code_payload_to_external = parsed;
     }
+    if let Ok(v) = std::env::var("CRUXE_SEARCH_RANKING_LOG_ENABLED")
+        && let Some(parsed) = parse_env_bool(&v)
+    {
+        config.search.ranking_log.enabled = parsed;
+    }
+    if let Ok(v) = std::env::var("CRUXE_SEARCH_RANKING_LOG_PATH") {
+        config.search.ranking_log.path = v;
+    }
+    if let Ok(v) = std::env::var("CRUXE_SEARCH_RANKING_LOG_INCLUDE_QUERY_TEXT")
+        && let Some(parsed) = parse_env_bool(&v)
+    {
+        config.search.ranking_log.include_query_text = parsed;
+    }
+    if let Ok(v) = std::env::var("CRUXE_SERVER_ALLOWED_WORKSPACE_ROOTS") {
+        config.server.allowed_workspace_roots = parse_csv_env_list(&v);
+    }
+    if let Ok(v) = std::env::var("CRUXE_SERVER_TENANT_DATA_DIR_ROOT") {
+        config.server.tenant_data_dir_root = v;
+    }
+    if let Ok(v) = std::env::var("CRUXE_SERVER_RATE_LIMIT_REQUESTS_PER_SECOND")
+        && let Ok(n) = v.parse()
+    {
+        config.server.rate_limit.requests_per_second = n;
+    }
+    if let Ok(v) = std::env::var("CRUXE_SERVER_RATE_LIMIT_BURST")
+        && let Ok(n) = v.parse()
+    {
+        config.server.rate_limit.burst = n;
+    }
     if let Ok(v) = std::env::var("CRUXE_DEBUG_RANKING_REASONS")
         && let Some(parsed) = parse_env_bool(&v)
     {
@@ -1810,6 +2235,28 @@ fn normalize_ranking_explain_level(raw: &str) -> String {
     ranking_explain_level_to_str(level).to_string()
 }
 
+fn parse_detail_level(raw: &str) -> Option<DetailLevel> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "location" => Some(DetailLevel::Location),
+        "signature" => Some(DetailLevel::Signature),
+        "context" => Some(DetailLevel::Context),
+        _ => None,
+    }
+}
+
+fn detail_level_to_str(level: DetailLevel) -> &'static str {
+    match level {
+        DetailLevel::Location => "location",
+        DetailLevel::Signature => "signature",
+        DetailLevel::Context => "context",
+    }
+}
+
+fn normalize_detail_level(raw: &str) -> String {
+    let level = parse_detail_level(raw).unwrap_or(DetailLevel::Signature);
+    detail_level_to_str(level).to_string()
+}
+
 fn parse_semantic_mode(raw: &str) -> Option<SemanticMode> {
     match raw.trim().to_ascii_lowercase().as_str() {
         "off" => Some(SemanticMode::Off),
@@ -2170,6 +2617,16 @@ impl RankingSignalBudgetConfig {
                 default_budget_test_file_penalty(),
                 "search.ranking_signal_budgets.test_file_penalty",
             ),
+            language_preference: normalize_budget_range(
+                self.language_preference.clone(),
+                default_budget_language_preference(),
+                "search.ranking_signal_budgets.language_preference",
+            ),
+            short_match_penalty: normalize_budget_range(
+                self.short_match_penalty.clone(),
+                default_budget_short_match_penalty(),
+                "search.ranking_signal_budgets.short_match_penalty",
+            ),
             secondary_cap_when_exact: normalize_budget_range(
                 self.secondary_cap_when_exact.clone(),
                 default_budget_secondary_cap_when_exact(),
@@ -2467,6 +2924,8 @@ mod tests {
             definition_boost: default_budget_definition_boost(),
             kind_match: default_budget_kind_match(),
             test_file_penalty: default_budget_test_file_penalty(),
+            language_preference: default_budget_language_preference(),
+            short_match_penalty: default_budget_short_match_penalty(),
             secondary_cap_when_exact: default_budget_secondary_cap_when_exact(),
         };
         let normalized = raw.normalized();
@@ -2793,6 +3252,166 @@ mod tests {
         );
     }
 
+    #[test]
+    fn load_with_file_resolves_default_ranking_log_path_under_data_dir() {
+        let temp = tempdir().unwrap();
+        let config_path = temp.path().join("config.toml");
+        let data_dir = temp.path().join("cruxe-data");
+        std::fs::write(
+            &config_path,
+            format!(
+                "[storage]\ndata_dir = {:?}\n\n[search.ranking_log]\nenabled = true\n",
+                data_dir.to_string_lossy()
+            ),
+        )
+        .unwrap();
+
+        let loaded = Config::load_with_file(None, Some(&config_path)).unwrap();
+        assert!(loaded.search.ranking_log.enabled);
+        assert!(!loaded.search.ranking_log.include_query_text);
+        assert_eq!(
+            Path::new(&loaded.search.ranking_log.path),
+            data_dir.join(constants::RANKING_LOG_FILE)
+        );
+    }
+
+    #[test]
+    fn load_with_file_applies_per_language_parse_timeouts() {
+        let temp = tempdir().unwrap();
+        let config_path = temp.path().join("config.toml");
+        std::fs::write(
+            &config_path,
+            "[index]\nparse_timeout_ms = 200\nparse_timeout_ms_by_language = { typescript = 900 }\n",
+        )
+        .unwrap();
+
+        let loaded = Config::load_with_file(None, Some(&config_path)).unwrap();
+        assert_eq!(loaded.index.parse_timeout_ms_for("typescript"), 900);
+        assert_eq!(loaded.index.parse_timeout_ms_for("rust"), 200);
+        assert_eq!(
+            Config::default().index.parse_timeout_ms_for("go"),
+            constants::DEFAULT_PARSE_TIMEOUT_MS
+        );
+    }
+
+    #[test]
+    fn load_with_file_reads_max_syntax_depth() {
+        let temp = tempdir().unwrap();
+        let config_path = temp.path().join("config.toml");
+        std::fs::write(&config_path, "[index]\nmax_syntax_depth = 64\n").unwrap();
+
+        let loaded = Config::load_with_file(None, Some(&config_path)).unwrap();
+        assert_eq!(loaded.index.max_syntax_depth, 64);
+        assert_eq!(
+            Config::default().index.max_syntax_depth,
+            constants::DEFAULT_MAX_SYNTAX_DEPTH
+        );
+    }
+
+    #[test]
+    fn load_with_file_reads_max_snippet_lines() {
+        let temp = tempdir().unwrap();
+        let config_path = temp.path().join("config.toml");
+        std::fs::write(&config_path, "[index]\nmax_snippet_lines = 40\n").unwrap();
+
+        let loaded = Config::load_with_file(None, Some(&config_path)).unwrap();
+        assert_eq!(loaded.index.max_snippet_lines, 40);
+        assert_eq!(
+            Config::default().index.max_snippet_lines,
+            constants::DEFAULT_MAX_SNIPPET_LINES
+        );
+    }
+
+    #[test]
+    fn load_with_file_reads_project_search_defaults() {
+        let temp = tempdir().unwrap();
+        let repo = temp.path().join("repo");
+        std::fs::create_dir_all(repo.join(".cruxe")).unwrap();
+        std::fs::write(
+            repo.join(constants::PROJECT_CONFIG_FILE),
+            "[search]\ndefault_detail_level = \"Context\"\ndefault_limit = 25\n",
+        )
+        .unwrap();
+
+        let loaded = Config::load_with_file(Some(&repo), None).unwrap();
+        assert_eq!(loaded.search.default_detail_level, "context");
+        assert_eq!(
+            loaded.search.default_detail_level_typed(),
+            DetailLevel::Context
+        );
+        assert_eq!(loaded.search.default_limit, 25);
+
+        std::fs::write(
+            repo.join(constants::PROJECT_CONFIG_FILE),
+            "[search]\ndefault_detail_level = \"verbose\"\ndefault_limit = 0\n",
+        )
+        .unwrap();
+        let loaded = Config::load_with_file(Some(&repo), None).unwrap();
+        assert_eq!(loaded.search.default_detail_level, "signature");
+        assert_eq!(loaded.search.default_limit, 10);
+    }
+
+    #[test]
+    fn load_with_file_reads_server_rate_limit() {
+        let temp = tempdir().unwrap();
+        let config_path = temp.path().join("config.toml");
+        std::fs::write(
+            &config_path,
+            "[server.rate_limit]\nrequests_per_second = 0\nburst = 5\n",
+        )
+        .unwrap();
+
+        let loaded = Config::load_with_file(None, Some(&config_path)).unwrap();
+        assert_eq!(loaded.server.rate_limit.burst, 5);
+        assert!(!loaded.server.rate_limit.enabled());
+        assert!(Config::default().server.rate_limit.enabled());
+    }
+
+    #[test]
+    fn index_worker_threads_resolve_zero_to_available_cores() {
+        let temp = tempdir().unwrap();
+        let config_path = temp.path().join("config.toml");
+        std::fs::write(&config_path, "[index]\nparallelism = 3\n").unwrap();
+
+        let loaded = Config::load_with_file(None, Some(&config_path)).unwrap();
+        assert_eq!(loaded.index.parallelism, 3);
+        assert_eq!(loaded.index.worker_threads(), 3);
+
+        let auto = Config::default().index;
+        assert_eq!(auto.parallelism, 0);
+        assert!(auto.worker_threads() >= 1);
+    }
+
+    #[test]
+    fn load_with_file_reads_text_only_extensions() {
+        let temp = tempdir().unwrap();
+        let config_path = temp.path().join("config.toml");
+        std::fs::write(
+            &config_path,
+            "[index]\ntext_only_extensions = [\"sql\", \".YAML\"]\n",
+        )
+        .unwrap();
+
+        let loaded = Config::load_with_file(None, Some(&config_path)).unwrap();
+        assert_eq!(loaded.index.text_only_extensions, vec!["sql", ".YAML"]);
+        assert!(Config::default().index.text_only_extensions.is_empty());
+    }
+
+    #[test]
+    fn load_with_file_reads_index_exclude_globs() {
+        let temp = tempdir().unwrap();
+        let config_path = temp.path().join("config.toml");
+        std::fs::write(
+            &config_path,
+            "[index]\nexclude = [\"vendor/**\", \"proto\"]\n",
+        )
+        .unwrap();
+
+        let loaded = Config::load_with_file(None, Some(&config_path)).unwrap();
+        assert_eq!(loaded.index.exclude, vec!["vendor/**", "proto"]);
+        assert!(Config::default().index.exclude.is_empty());
+    }
+
     #[test]
     fn load_with_file_partial_budget_range_falls_back_to_canonical_defaults() {
         let temp = tempdir().unwrap();
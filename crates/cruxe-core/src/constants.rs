@@ -13,6 +13,37 @@ pub const PARSER_VERSION: u32 = 1;
 /// Maximum file size to index (1MB).
 pub const MAX_FILE_SIZE: u64 = 1_048_576;
 
+/// Maximum symbols indexed into a single ref before indexing aborts.
+pub const MAX_SYMBOLS_PER_REF: u64 = 2_000_000;
+
+/// Default ceiling on a single indexer subprocess run (seconds) before it is killed.
+pub const DEFAULT_MAX_JOB_DURATION_SECS: u64 = 3_600;
+
+/// Default per-file tree-sitter parse budget (milliseconds) before the file is skipped.
+pub const DEFAULT_PARSE_TIMEOUT_MS: u64 = 5_000;
+
+/// Default syntax-tree depth below which symbol and call-site extraction stops descending.
+pub const DEFAULT_MAX_SYNTAX_DEPTH: usize = 500;
+
+/// Default cap on lines stored per snippet; longer bodies are truncated.
+pub const DEFAULT_MAX_SNIPPET_LINES: usize = 300;
+
+/// Default number of files written between Tantivy commits in a full index run.
+pub const DEFAULT_COMMIT_EVERY_FILES: usize = 500;
+
+/// Default ceiling on source files a single scan may collect before indexing aborts.
+pub const DEFAULT_MAX_SCAN_FILES: u64 = 200_000;
+
+/// Default ceiling on the total bytes of source files a single scan may collect (4 GiB).
+pub const DEFAULT_MAX_SCAN_BYTES: u64 = 4 * 1024 * 1024 * 1024;
+
+/// Default free space (MiB) required on the data volume before indexing starts.
+pub const DEFAULT_MIN_FREE_DISK_MB: u64 = 256;
+
+/// Multiple of the current index size an index run may need while it writes
+/// new segments next to the old ones.
+pub const INDEX_DISK_GROWTH_FACTOR: u64 = 2;
+
 /// Default data directory name under home.
 pub const DEFAULT_DATA_DIR: &str = ".cruxe";
 
@@ -30,3 +61,12 @@ pub const IGNORE_FILE: &str = ".cruxeignore";
 
 /// SQLite database file name.
 pub const STATE_DB_FILE: &str = "state.db";
+
+/// Default file name for the opt-in ranking log, under the data directory.
+pub const RANKING_LOG_FILE: &str = "ranking-log.jsonl";
+
+/// Default per-node edge cap for relation traversal tools.
+pub const DEFAULT_RELATION_MAX_BREADTH: usize = 50;
+
+/// Upper bound accepted for the per-node edge cap of relation traversal tools.
+pub const MAX_RELATION_BREADTH: usize = 1_000;
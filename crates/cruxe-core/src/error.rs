@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -14,14 +15,19 @@ pub enum WorkspaceError {
     #[error("workspace limit exceeded: max {max} auto-discovered workspaces")]
     LimitExceeded { max: usize },
 
-    #[error("--allowed-root is required when --auto-workspace is enabled")]
+    #[error(
+        "--allowed-root or server.allowed_workspace_roots is required when --auto-workspace is enabled"
+    )]
     AllowedRootRequired,
 }
 
 /// Canonical protocol-level error codes shared by MCP/HTTP transports.
 ///
-/// Source of truth: `openspec/meta/protocol-error-codes.md`.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Source of truth: `openspec/meta/protocol-error-codes.md`. Serializes to the
+/// same snake_case strings as [`ProtocolErrorCode::as_str`], so clients can
+/// deserialize `error.code` and match on it exhaustively.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ProtocolErrorCode {
     InvalidInput,
     InvalidStrategy,
@@ -33,6 +39,8 @@ pub enum ProtocolErrorCode {
     IndexInProgress,
     IndexNotReady,
     SyncInProgress,
+    SymbolCapExceeded,
+    InsufficientDisk,
     IndexStale,
     IndexIncompatible,
     RefNotIndexed,
@@ -43,10 +51,41 @@ pub enum ProtocolErrorCode {
     FileNotFound,
     ResultNotFound,
     NoEdgesAvailable,
+    RateLimited,
+    DataDirNotAllowed,
     InternalError,
 }
 
 impl ProtocolErrorCode {
+    /// Every registered code, in registry order.
+    pub const ALL: [Self; 25] = [
+        Self::InvalidInput,
+        Self::InvalidStrategy,
+        Self::InvalidMaxTokens,
+        Self::ProjectNotFound,
+        Self::WorkspaceNotRegistered,
+        Self::WorkspaceNotAllowed,
+        Self::WorkspaceLimitExceeded,
+        Self::IndexInProgress,
+        Self::IndexNotReady,
+        Self::SyncInProgress,
+        Self::SymbolCapExceeded,
+        Self::InsufficientDisk,
+        Self::IndexStale,
+        Self::IndexIncompatible,
+        Self::RefNotIndexed,
+        Self::OverlayNotReady,
+        Self::MergeBaseFailed,
+        Self::SymbolNotFound,
+        Self::AmbiguousSymbol,
+        Self::FileNotFound,
+        Self::ResultNotFound,
+        Self::NoEdgesAvailable,
+        Self::RateLimited,
+        Self::DataDirNotAllowed,
+        Self::InternalError,
+    ];
+
     pub const fn as_str(self) -> &'static str {
         match self {
             Self::InvalidInput => "invalid_input",
@@ -59,6 +98,8 @@ impl ProtocolErrorCode {
             Self::IndexInProgress => "index_in_progress",
             Self::IndexNotReady => "index_not_ready",
             Self::SyncInProgress => "sync_in_progress",
+            Self::SymbolCapExceeded => "symbol_cap_exceeded",
+            Self::InsufficientDisk => "insufficient_disk",
             Self::IndexStale => "index_stale",
             Self::IndexIncompatible => "index_incompatible",
             Self::RefNotIndexed => "ref_not_indexed",
@@ -69,6 +110,8 @@ impl ProtocolErrorCode {
             Self::FileNotFound => "file_not_found",
             Self::ResultNotFound => "result_not_found",
             Self::NoEdgesAvailable => "no_edges_available",
+            Self::RateLimited => "rate_limited",
+            Self::DataDirNotAllowed => "data_dir_not_allowed",
             Self::InternalError => "internal_error",
         }
     }
@@ -145,6 +188,33 @@ pub enum StateError {
         reason: String,
     },
 
+    #[error(
+        "symbol cap exceeded: ref={ref_name}, symbol_count={symbol_count}, max_symbols_per_ref={cap}"
+    )]
+    SymbolCapExceeded {
+        ref_name: String,
+        symbol_count: u64,
+        cap: u64,
+    },
+
+    #[error(
+        "scan limit exceeded: {limit}={cap} reached while scanning {repo_root}; add index.exclude patterns for large vendored or generated directories, or raise {limit}"
+    )]
+    ScanLimitExceeded {
+        repo_root: String,
+        limit: String,
+        cap: u64,
+    },
+
+    #[error(
+        "insufficient disk: path={path}, available_bytes={available_bytes}, required_bytes={required_bytes}"
+    )]
+    InsufficientDisk {
+        path: String,
+        available_bytes: u64,
+        required_bytes: u64,
+    },
+
     #[error("result not found: path={path}, line_start={line_start}")]
     ResultNotFound { path: String, line_start: u32 },
 
@@ -160,6 +230,24 @@ pub enum StateError {
     #[error("corrupt manifest: {0}")]
     CorruptManifest(String),
 
+    #[error(
+        "index tokenizer changed: {index} index content was built with `{indexed}` but config selects `{configured}`; run `cruxe index --force` to rebuild"
+    )]
+    TokenizerChanged {
+        index: String,
+        indexed: String,
+        configured: String,
+    },
+
+    #[error(
+        "incompatible state bundle: {component} version bundle={bundle}, local={local}; re-export it with a matching cruxe build"
+    )]
+    IncompatibleBundle {
+        component: String,
+        bundle: u32,
+        local: u32,
+    },
+
     #[error("io error: {0}")]
     Io(#[from] std::io::Error),
 }
@@ -233,6 +321,26 @@ impl StateError {
         }
     }
 
+    pub fn symbol_cap_exceeded(ref_name: impl Into<String>, symbol_count: u64, cap: u64) -> Self {
+        Self::SymbolCapExceeded {
+            ref_name: ref_name.into(),
+            symbol_count,
+            cap,
+        }
+    }
+
+    pub fn insufficient_disk(
+        path: impl Into<String>,
+        available_bytes: u64,
+        required_bytes: u64,
+    ) -> Self {
+        Self::InsufficientDisk {
+            path: path.into(),
+            available_bytes,
+            required_bytes,
+        }
+    }
+
     pub fn result_not_found(path: impl Into<String>, line_start: u32) -> Self {
         Self::ResultNotFound {
             path: path.into(),
@@ -259,6 +367,9 @@ pub enum ParseError {
     #[error("grammar not available: {language}")]
     GrammarNotAvailable { language: String },
 
+    #[error("parse exceeded {timeout_ms}ms budget ({language})")]
+    Timeout { language: String, timeout_ms: u64 },
+
     #[error("io error: {0}")]
     Io(#[from] std::io::Error),
 }
@@ -293,4 +404,15 @@ mod tests {
         );
         assert_eq!(ProtocolErrorCode::InternalError.as_str(), "internal_error");
     }
+
+    #[test]
+    fn protocol_error_code_serde_matches_wire_strings() {
+        for code in ProtocolErrorCode::ALL {
+            let encoded = serde_json::to_value(code).unwrap();
+            assert_eq!(encoded, serde_json::Value::String(code.as_str().into()));
+            let decoded: ProtocolErrorCode = serde_json::from_value(encoded).unwrap();
+            assert_eq!(decoded, code);
+        }
+        assert!(serde_json::from_str::<ProtocolErrorCode>("\"index_stail\"").is_err());
+    }
 }
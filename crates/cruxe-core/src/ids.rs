@@ -1,13 +1,22 @@
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{SystemTime, UNIX_EPOCH};
 
-static JOB_COUNTER: AtomicU64 = AtomicU64::new(0);
+static ID_COUNTER: AtomicU64 = AtomicU64::new(0);
 
 /// Generate a process-local monotonic job id.
 ///
 /// The identifier combines current wall-clock time, process id, thread id,
 /// and an atomic counter to reduce collision risk across rapid calls.
 pub fn new_job_id() -> String {
+    unique_hex_id()
+}
+
+/// Generate an id correlating one MCP request with the log lines it produced.
+pub fn new_request_id() -> String {
+    format!("req-{}", unique_hex_id())
+}
+
+fn unique_hex_id() -> String {
     use std::collections::hash_map::DefaultHasher;
     use std::hash::{Hash, Hasher};
 
@@ -19,7 +28,7 @@ pub fn new_job_id() -> String {
     std::thread::current().id().hash(&mut thread_hasher);
     let thread_hash = thread_hasher.finish() as u128;
     let pid = std::process::id() as u128;
-    let counter = JOB_COUNTER.fetch_add(1, Ordering::Relaxed) as u128;
+    let counter = ID_COUNTER.fetch_add(1, Ordering::Relaxed) as u128;
 
     let mixed = now_nanos ^ (pid << 32) ^ thread_hash ^ counter;
     format!("{:032x}", mixed)
@@ -36,4 +45,12 @@ mod tests {
         let unique: HashSet<_> = ids.iter().collect();
         assert_eq!(ids.len(), unique.len());
     }
+
+    #[test]
+    fn request_ids_are_prefixed_and_unique() {
+        let ids: Vec<String> = (0..128).map(|_| new_request_id()).collect();
+        assert!(ids.iter().all(|id| id.starts_with("req-")));
+        let unique: HashSet<_> = ids.iter().collect();
+        assert_eq!(ids.len(), unique.len());
+    }
 }
@@ -1,7 +1,17 @@
 /// Canonical list of first-class indexable source languages.
 ///
 /// These languages have full parser/extractor support in the index pipeline.
-pub const INDEXABLE_SOURCE_LANGUAGES: [&str; 4] = ["rust", "typescript", "python", "go"];
+pub const INDEXABLE_SOURCE_LANGUAGES: [&str; 9] = [
+    "rust",
+    "typescript",
+    "python",
+    "go",
+    "c",
+    "cpp",
+    "csharp",
+    "ruby",
+    "kotlin",
+];
 
 /// Returns true if the language has full parser/extractor support.
 pub fn is_indexable_source_language(language: &str) -> bool {
@@ -21,7 +31,16 @@ pub fn supported_indexable_languages() -> &'static [&'static str] {
 pub fn is_semantic_code_language(language: &str) -> bool {
     matches!(
         language,
-        "rust" | "typescript" | "python" | "go" | "javascript"
+        "rust"
+            | "typescript"
+            | "python"
+            | "go"
+            | "c"
+            | "cpp"
+            | "csharp"
+            | "ruby"
+            | "kotlin"
+            | "javascript"
     )
 }
 
@@ -38,7 +57,8 @@ pub fn detect_language_from_extension(ext: &str) -> Option<&'static str> {
         "go" => Some("go"),
         "java" => Some("java"),
         "c" | "h" => Some("c"),
-        "cpp" | "cc" | "cxx" | "hpp" => Some("cpp"),
+        "cpp" | "cc" | "cxx" | "hpp" | "hh" | "hxx" => Some("cpp"),
+        "cs" => Some("csharp"),
         "rb" => Some("ruby"),
         "swift" => Some("swift"),
         "kt" | "kts" => Some("kotlin"),
@@ -56,9 +76,24 @@ mod tests {
     fn indexable_language_set_matches_v1_scope() {
         assert_eq!(
             supported_indexable_languages(),
-            &["rust", "typescript", "python", "go"]
+            &[
+                "rust",
+                "typescript",
+                "python",
+                "go",
+                "c",
+                "cpp",
+                "csharp",
+                "ruby",
+                "kotlin"
+            ]
         );
         assert!(is_indexable_source_language("rust"));
+        assert!(is_indexable_source_language("c"));
+        assert!(is_indexable_source_language("cpp"));
+        assert!(is_indexable_source_language("csharp"));
+        assert!(is_indexable_source_language("ruby"));
+        assert!(is_indexable_source_language("kotlin"));
         assert!(!is_indexable_source_language("javascript"));
     }
 
@@ -67,6 +102,7 @@ mod tests {
         assert_eq!(detect_language_from_extension("rs"), Some("rust"));
         assert_eq!(detect_language_from_extension("ts"), Some("typescript"));
         assert_eq!(detect_language_from_extension("js"), Some("javascript"));
+        assert_eq!(detect_language_from_extension("cs"), Some("csharp"));
         assert_eq!(detect_language_from_extension("md"), None);
     }
 }
@@ -32,6 +32,7 @@ pub enum SymbolKind {
     TypeAlias,
     #[serde(alias = "import")]
     Module,
+    Field,
 }
 
 /// Cross-language semantic symbol role used for coarse filtering and ranking.
@@ -59,6 +60,7 @@ impl SymbolKind {
             Self::Variable => "variable",
             Self::TypeAlias => "type_alias",
             Self::Module => "module",
+            Self::Field => "field",
         }
     }
 
@@ -75,6 +77,7 @@ impl SymbolKind {
             "variable" | "var" => Some(Self::Variable),
             "type_alias" | "type" => Some(Self::TypeAlias),
             "module" | "mod" | "import" | "use" => Some(Self::Module),
+            "field" => Some(Self::Field),
             _ => None,
         }
     }
@@ -86,7 +89,7 @@ impl SymbolKind {
             }
             Self::TypeAlias => SymbolRole::Alias,
             Self::Function | Self::Method => SymbolRole::Callable,
-            Self::Constant | Self::Variable => SymbolRole::Value,
+            Self::Constant | Self::Variable | Self::Field => SymbolRole::Value,
             Self::Module => SymbolRole::Namespace,
         }
     }
@@ -131,6 +134,79 @@ impl FromStr for SymbolRole {
     }
 }
 
+/// Canonical spelling of a language's visibility keyword: `public`,
+/// `internal`, `protected` or `private`.
+///
+/// `pub`/`export`/`open` are public, `pub(crate)`-style restrictions and
+/// `package` are internal, `fileprivate` and C `static` are private.
+/// Unrecognised keywords come back trimmed and lowercased.
+pub fn canonical_visibility(raw: &str) -> String {
+    let raw = raw.trim().to_ascii_lowercase();
+    let canonical = match raw.as_str() {
+        "pub" | "public" | "export" | "open" => "public",
+        "internal" | "package" | "crate" => "internal",
+        "protected" | "protected internal" | "private protected" => "protected",
+        "private" | "fileprivate" | "static" => "private",
+        restricted if restricted.starts_with("pub(") => "internal",
+        _ => return raw,
+    };
+    canonical.to_string()
+}
+
+/// Visibility filter accepted by `locate_symbol` and `search_code`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VisibilityFilter {
+    #[default]
+    Any,
+    /// Only symbols whose visibility is `public`.
+    Public,
+    /// Symbols with a recorded visibility other than `public`.
+    Private,
+}
+
+impl VisibilityFilter {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Any => "any",
+            Self::Public => "public",
+            Self::Private => "private",
+        }
+    }
+
+    pub fn is_any(&self) -> bool {
+        *self == Self::Any
+    }
+
+    /// Whether a symbol with stored `visibility` passes the filter. Stored
+    /// values are canonicalised first, so indexes that still hold raw
+    /// keywords such as `pub` filter the same way; an empty value counts
+    /// as unrecorded.
+    pub fn matches(&self, visibility: Option<&str>) -> bool {
+        let canonical = visibility
+            .filter(|value| !value.trim().is_empty())
+            .map(canonical_visibility);
+        match self {
+            Self::Any => true,
+            Self::Public => canonical.as_deref() == Some("public"),
+            Self::Private => canonical.is_some_and(|value| value != "public"),
+        }
+    }
+}
+
+impl FromStr for VisibilityFilter {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "any" => Ok(Self::Any),
+            "public" => Ok(Self::Public),
+            "private" => Ok(Self::Private),
+            _ => Err(()),
+        }
+    }
+}
+
 /// A symbol definition extracted from source code.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SymbolRecord {
@@ -147,9 +223,52 @@ pub struct SymbolRecord {
     pub signature: Option<String>,
     pub line_start: u32,
     pub line_end: u32,
+    /// Byte range of the definition (end exclusive); `None` for symbols indexed
+    /// before byte ranges were recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub byte_start: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub byte_end: Option<u32>,
     pub parent_symbol_id: Option<String>,
     pub visibility: Option<String>,
     pub content: Option<String>,
+    /// Outer attributes or decorators attached to the symbol (e.g. `test`,
+    /// `derive(Debug, Clone)`, `app.get("/users")`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<String>,
+    /// Documentation comment preceding the symbol, with comment markers stripped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc_comment: Option<String>,
+    /// Structured form of `signature` for callables in languages that support it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parsed_signature: Option<ParsedSignature>,
+}
+
+/// A callable's signature split into generics, receiver, parameters and return type.
+/// Types are kept as source text.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParsedSignature {
+    /// Generic/type parameters as written, e.g. `T: Clone` or `K comparable`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub generics: Vec<String>,
+    /// Rust `self` or Go method receiver.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub receiver: Option<SignatureParam>,
+    #[serde(default)]
+    pub params: Vec<SignatureParam>,
+    /// `None` when the callable returns nothing (`()` in Rust, no result in Go).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub return_type: Option<String>,
+}
+
+/// One parameter of a [`ParsedSignature`]. Go allows unnamed parameters, so
+/// `name` is optional.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignatureParam {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<String>,
 }
 
 /// A code snippet (function body, class body) for full-text search.
@@ -180,6 +299,10 @@ pub struct FileRecord {
     pub size_bytes: u64,
     pub updated_at: String,
     pub content_head: Option<String>,
+    /// The file was not valid UTF-8 and was indexed from a lossy decode, with
+    /// invalid sequences replaced by U+FFFD.
+    #[serde(default)]
+    pub encoding_warning: bool,
 }
 
 /// A directed relationship edge between two symbols.
@@ -360,6 +483,8 @@ pub struct RankingReasons {
     pub kind_match: f64,
     pub test_file_penalty: f64,
     #[serde(default)]
+    pub language_preference_boost: f64,
+    #[serde(default)]
     pub confidence_structural_boost: f64,
     #[serde(default)]
     pub structural_weighted_centrality: f64,
@@ -403,9 +528,40 @@ pub enum QueryIntent {
     Symbol,
     Path,
     Error,
+    /// Balanced double-quoted multi-word text matched as an exact phrase.
+    Phrase,
     NaturalLanguage,
 }
 
+impl QueryIntent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Symbol => "symbol",
+            Self::Path => "path",
+            Self::Error => "error",
+            Self::Phrase => "phrase",
+            Self::NaturalLanguage => "natural_language",
+        }
+    }
+}
+
+/// Parses a caller-supplied intent override; `natural` is accepted as the
+/// short form of `natural_language`.
+impl FromStr for QueryIntent {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "symbol" => Ok(Self::Symbol),
+            "path" => Ok(Self::Path),
+            "error" => Ok(Self::Error),
+            "phrase" => Ok(Self::Phrase),
+            "natural" | "natural_language" => Ok(Self::NaturalLanguage),
+            _ => Err(()),
+        }
+    }
+}
+
 /// Ref scope for queries.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RefScope {
@@ -577,6 +733,49 @@ impl JobStatus {
     }
 }
 
+/// Why a file under the workspace root was not fully indexed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    UnsupportedLanguage,
+    TooLarge,
+    Binary,
+    Ignored,
+    ParseFailure,
+    ParseTimeout,
+    Unreadable,
+}
+
+impl SkipReason {
+    pub const ALL: [SkipReason; 7] = [
+        Self::UnsupportedLanguage,
+        Self::TooLarge,
+        Self::Binary,
+        Self::Ignored,
+        Self::ParseFailure,
+        Self::ParseTimeout,
+        Self::Unreadable,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::UnsupportedLanguage => "unsupported_language",
+            Self::TooLarge => "too_large",
+            Self::Binary => "binary",
+            Self::Ignored => "ignored",
+            Self::ParseFailure => "parse_failure",
+            Self::ParseTimeout => "parse_timeout",
+            Self::Unreadable => "unreadable",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|reason| reason.as_str() == value)
+    }
+}
+
 /// Generate project_id from repo root path.
 /// Uses blake3 hash of the canonical path, truncated to 16 hex characters.
 pub fn generate_project_id(repo_root: &str) -> String {
@@ -604,6 +803,10 @@ impl Default for WorkspaceConfig {
     }
 }
 
+/// `WorkspaceError::NotAllowed` reason for paths outside every allowed root.
+pub const OUTSIDE_ALLOWED_ROOTS_REASON: &str =
+    "path is outside all allowed workspace roots (--allowed-root / server.allowed_workspace_roots)";
+
 /// Newtype around a set of allowed root path prefixes for workspace validation.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AllowedRoots(Vec<PathBuf>);
@@ -622,6 +825,15 @@ impl AllowedRoots {
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
+
+    /// Add roots, skipping any already present. Roots must be canonicalized.
+    pub fn extend(&mut self, roots: impl IntoIterator<Item = PathBuf>) {
+        for root in roots {
+            if !self.0.contains(&root) {
+                self.0.push(root);
+            }
+        }
+    }
 }
 
 /// Validate and canonicalize a workspace path against allowed roots.
@@ -642,7 +854,7 @@ pub fn validate_workspace_path(
     if !allowed_roots.contains(&canonical) {
         return Err(crate::error::WorkspaceError::NotAllowed {
             path: canonical.display().to_string(),
-            reason: "path is outside all --allowed-root prefixes".to_string(),
+            reason: OUTSIDE_ALLOWED_ROOTS_REASON.to_string(),
         });
     }
 
@@ -888,6 +1100,7 @@ mod tests {
             SymbolKind::Variable,
             SymbolKind::TypeAlias,
             SymbolKind::Module,
+            SymbolKind::Field,
         ] {
             assert_eq!(SymbolKind::parse_kind(kind.as_str()), Some(kind));
         }
@@ -931,6 +1144,25 @@ mod tests {
         assert_eq!(SymbolKind::TypeAlias.role(), SymbolRole::Alias);
     }
 
+    #[test]
+    fn test_canonical_visibility_and_filter() {
+        assert_eq!(canonical_visibility("pub"), "public");
+        assert_eq!(canonical_visibility("pub(crate)"), "internal");
+        assert_eq!(canonical_visibility("fileprivate"), "private");
+        assert_eq!(canonical_visibility("protected internal"), "protected");
+        assert_eq!(canonical_visibility("Sealed"), "sealed");
+
+        assert!(VisibilityFilter::Public.matches(Some("pub")));
+        assert!(!VisibilityFilter::Public.matches(Some("pub(super)")));
+        assert!(VisibilityFilter::Private.matches(Some("pub(super)")));
+        assert!(!VisibilityFilter::Private.matches(None));
+        assert!(!VisibilityFilter::Private.matches(Some("")));
+        assert!(!VisibilityFilter::Public.matches(None));
+        assert!(VisibilityFilter::Any.matches(None));
+        assert_eq!("PRIVATE".parse(), Ok(VisibilityFilter::Private));
+        assert!("exported".parse::<VisibilityFilter>().is_err());
+    }
+
     // ------------------------------------------------------------------
     // T201: AllowedRoots::contains() unit tests
     // ------------------------------------------------------------------
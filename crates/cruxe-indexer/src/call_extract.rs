@@ -2,13 +2,15 @@ use cruxe_core::error::StateError;
 use cruxe_core::types::{CallEdge, SymbolKind, SymbolRecord};
 use rusqlite::{Connection, params};
 use std::collections::{HashMap, HashSet, hash_map::Entry};
-use tracing::debug;
+use tracing::{debug, warn};
 
 use crate::import_extract::source_symbol_id_for_path;
 
 /// Extract per-file call edges from parsed AST and resolve caller symbols by line coverage.
 ///
-/// Callee resolution is deferred to `resolve_call_targets`.
+/// Callee resolution is deferred to `resolve_call_targets`. Calls nested deeper
+/// than `max_depth` syntax-tree levels are skipped with a warning.
+#[allow(clippy::too_many_arguments)]
 pub fn extract_call_edges_for_file(
     tree: &tree_sitter::Tree,
     source: &str,
@@ -17,8 +19,16 @@ pub fn extract_call_edges_for_file(
     symbols: &[SymbolRecord],
     repo: &str,
     ref_name: &str,
+    max_depth: usize,
 ) -> Vec<CallEdge> {
-    let call_sites = crate::languages::extract_call_sites(tree, source, language);
+    let (call_sites, depth_limit_reached) =
+        crate::languages::extract_call_sites(tree, source, language, max_depth);
+    if depth_limit_reached {
+        warn!(
+            source_file,
+            max_depth, "syntax tree exceeds max depth; deeper call sites were skipped"
+        );
+    }
     let mut edges = Vec::new();
     for site in call_sites {
         let caller_id = resolve_caller_symbol(symbols, site.line)
@@ -305,6 +315,11 @@ mod tests {
             parent_symbol_id: None,
             visibility: Some("pub".to_string()),
             content: None,
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
         }
     }
 
@@ -340,6 +355,7 @@ fn validate_token() {}
             &symbols,
             "repo",
             "main",
+            cruxe_core::constants::DEFAULT_MAX_SYNTAX_DEPTH,
         );
         assert_eq!(edges.len(), 2);
         assert!(
@@ -382,6 +398,7 @@ function handler() {
             &symbols,
             "repo",
             "main",
+            cruxe_core::constants::DEFAULT_MAX_SYNTAX_DEPTH,
         );
         assert!(
             edges
@@ -738,6 +755,7 @@ function handler() {
             &symbols,
             "repo",
             "main",
+            cruxe_core::constants::DEFAULT_MAX_SYNTAX_DEPTH,
         );
         assert!(
             edges
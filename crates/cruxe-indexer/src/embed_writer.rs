@@ -331,6 +331,11 @@ mod tests {
             parent_symbol_id: None,
             visibility: Some("pub".to_string()),
             content: Some("fn auth() {}".to_string()),
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
         }];
         let snippets = vec![SnippetRecord {
             repo: "proj".to_string(),
@@ -396,6 +401,11 @@ mod tests {
             parent_symbol_id: None,
             visibility: Some("pub".to_string()),
             content: Some("fn auth() {}".to_string()),
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
         };
         let snippet = SnippetRecord {
             repo: "proj".to_string(),
@@ -459,6 +469,11 @@ mod tests {
             parent_symbol_id: None,
             visibility: Some("pub".to_string()),
             content: Some("fn a() {}".to_string()),
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
         }];
         let snippets_file_a = vec![SnippetRecord {
             repo: "proj".to_string(),
@@ -490,6 +505,11 @@ mod tests {
             parent_symbol_id: None,
             visibility: Some("pub".to_string()),
             content: Some("fn b() {}".to_string()),
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
         }];
         let snippets_file_b = vec![SnippetRecord {
             repo: "proj".to_string(),
@@ -65,7 +65,7 @@ pub fn resolve_imports(
     let mut seen = HashSet::new();
 
     for raw in raw_imports {
-        let resolution = resolve_target_symbol_stable_id(conn, repo, ref_name, &raw)?;
+        let resolution = resolve_import_target(conn, repo, ref_name, &raw)?;
         let unresolved_name = if resolution.to_symbol_id.is_none() {
             Some(if raw.target_name.trim().is_empty() {
                 raw.target_qualified_name.clone()
@@ -116,17 +116,27 @@ pub fn resolve_imports(
     Ok(edges)
 }
 
-struct ImportResolution {
-    to_symbol_id: Option<String>,
-    outcome: &'static str,
+/// Where a single import lands in the indexed corpus.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportTarget {
+    /// Stable ID of the imported symbol, when one resolved.
+    pub to_symbol_id: Option<String>,
+    /// Indexed file the import's module maps to, when path resolution ran.
+    pub module_path: Option<String>,
+    pub outcome: &'static str,
 }
 
-fn resolve_target_symbol_stable_id(
+/// Resolve one raw import to its target symbol and/or module file.
+///
+/// Lookup order: exact qualified name, bare name, then the module path
+/// relative to the importing file (only for `file::` sources). A module path
+/// that is not in the file manifest is an external reference.
+pub fn resolve_import_target(
     conn: &Connection,
     repo: &str,
     ref_name: &str,
     raw: &RawImport,
-) -> Result<ImportResolution, StateError> {
+) -> Result<ImportTarget, StateError> {
     if !raw.target_qualified_name.is_empty() {
         let mut stmt = conn
             .prepare(
@@ -141,8 +151,9 @@ fn resolve_target_symbol_stable_id(
             })
             .ok();
         if exact.is_some() {
-            return Ok(ImportResolution {
+            return Ok(ImportTarget {
                 to_symbol_id: exact,
+                module_path: None,
                 outcome: RESOLUTION_RESOLVED_INTERNAL,
             });
         }
@@ -163,8 +174,9 @@ fn resolve_target_symbol_stable_id(
             })
             .ok();
         if by_name.is_some() {
-            return Ok(ImportResolution {
+            return Ok(ImportTarget {
                 to_symbol_id: by_name,
+                module_path: None,
                 outcome: RESOLUTION_RESOLVED_INTERNAL,
             });
         }
@@ -172,17 +184,20 @@ fn resolve_target_symbol_stable_id(
 
     if let Some(importing_file) = raw.source_qualified_name.strip_prefix("file::") {
         let module_spec = module_spec_for_lookup(raw);
-        if let Some(resolved_path) = resolve_import_path(
+        let candidates = import_path_candidates(
             importing_file,
             module_spec.as_str(),
-            infer_language_from_path(importing_file),
-        ) {
-            if !file_exists_in_manifest(conn, repo, ref_name, &resolved_path)? {
-                return Ok(ImportResolution {
+            import_language_for_path(importing_file),
+        );
+        if !candidates.is_empty() {
+            let Some(resolved_path) = first_manifest_path(conn, repo, ref_name, &candidates)?
+            else {
+                return Ok(ImportTarget {
                     to_symbol_id: None,
+                    module_path: None,
                     outcome: RESOLUTION_EXTERNAL_REFERENCE,
                 });
-            }
+            };
             let mut stmt = conn
                 .prepare(
                     "SELECT symbol_stable_id
@@ -205,15 +220,15 @@ fn resolve_target_symbol_stable_id(
                     |row| row.get::<_, String>(0),
                 )
                 .ok();
-            if from_resolved_path.is_some() {
-                return Ok(ImportResolution {
-                    to_symbol_id: from_resolved_path,
-                    outcome: RESOLUTION_RESOLVED_INTERNAL,
-                });
-            }
-            return Ok(ImportResolution {
-                to_symbol_id: None,
-                outcome: RESOLUTION_UNRESOLVED,
+            let outcome = if from_resolved_path.is_some() {
+                RESOLUTION_RESOLVED_INTERNAL
+            } else {
+                RESOLUTION_UNRESOLVED
+            };
+            return Ok(ImportTarget {
+                to_symbol_id: from_resolved_path,
+                module_path: Some(resolved_path),
+                outcome,
             });
         }
     }
@@ -223,30 +238,38 @@ fn resolve_target_symbol_stable_id(
     } else {
         RESOLUTION_UNRESOLVED
     };
-    Ok(ImportResolution {
+    Ok(ImportTarget {
         to_symbol_id: None,
+        module_path: None,
         outcome,
     })
 }
 
-fn file_exists_in_manifest(
+/// First candidate path present in the file manifest, in preference order.
+fn first_manifest_path(
     conn: &Connection,
     repo: &str,
     ref_name: &str,
-    path: &str,
-) -> Result<bool, StateError> {
-    let exists: i64 = conn
-        .query_row(
+    candidates: &[String],
+) -> Result<Option<String>, StateError> {
+    let mut stmt = conn
+        .prepare(
             "SELECT EXISTS(
                 SELECT 1 FROM file_manifest
                 WHERE repo = ?1 AND \"ref\" = ?2 AND path = ?3
                 LIMIT 1
             )",
-            params![repo, ref_name, path],
-            |row| row.get(0),
         )
         .map_err(StateError::sqlite)?;
-    Ok(exists == 1)
+    for candidate in candidates {
+        let exists: i64 = stmt
+            .query_row(params![repo, ref_name, candidate], |row| row.get(0))
+            .map_err(StateError::sqlite)?;
+        if exists == 1 {
+            return Ok(Some(candidate.clone()));
+        }
+    }
+    Ok(None)
 }
 
 fn module_spec_for_lookup(raw: &RawImport) -> String {
@@ -271,7 +294,9 @@ fn module_spec_for_lookup(raw: &RawImport) -> String {
     raw.target_qualified_name.clone()
 }
 
-fn infer_language_from_path(path: &str) -> &str {
+/// Language whose import syntax applies to `path` (JavaScript uses the TypeScript
+/// extractor); empty when imports are not extracted for the file.
+pub fn import_language_for_path(path: &str) -> &str {
     if path.ends_with(".rs") {
         "rust"
     } else if path.ends_with(".go") {
@@ -289,32 +314,32 @@ fn infer_language_from_path(path: &str) -> &str {
     }
 }
 
-pub fn resolve_import_path(
+/// Repository-relative files an import's module could live in, most likely
+/// first. Candidates are checked against the file manifest, not the disk.
+pub fn import_path_candidates(
     importing_file: &str,
     module_spec: &str,
     language: &str,
-) -> Option<String> {
+) -> Vec<String> {
     let importing_dir = Path::new(importing_file)
         .parent()
         .unwrap_or_else(|| Path::new(""));
-    match language {
+    let candidates: Vec<PathBuf> = match language {
         "typescript" => {
+            // The extractor already rewrites `./` and `../` specifiers to
+            // repository-relative paths; only raw relative specs need joining.
             let module_path = Path::new(module_spec);
-            let base = if module_path.is_absolute() {
-                module_path.to_path_buf()
-            } else {
+            let base = if module_spec.starts_with('.') {
                 normalize_path(importing_dir.join(module_path))
+            } else {
+                normalize_path(module_path.to_path_buf())
             };
-            for candidate in [
+            vec![
                 base.with_extension("ts"),
                 base.with_extension("tsx"),
                 base.join("index.ts"),
-            ] {
-                if candidate.exists() {
-                    return Some(candidate.to_string_lossy().replace('\\', "/"));
-                }
-            }
-            Some(base.to_string_lossy().replace('\\', "/"))
+                base,
+            ]
         }
         "rust" => {
             let normalized = module_spec.trim();
@@ -332,34 +357,33 @@ pub fn resolve_import_path(
             }
             let module = parts.first().copied().unwrap_or("");
             if module.is_empty() {
-                return None;
-            }
-            let file_candidate = normalize_path(parent.join(format!("{module}.rs")));
-            if file_candidate.exists() {
-                return Some(file_candidate.to_string_lossy().replace('\\', "/"));
-            }
-            let mod_candidate = normalize_path(parent.join(module).join("mod.rs"));
-            if mod_candidate.exists() {
-                return Some(mod_candidate.to_string_lossy().replace('\\', "/"));
+                return Vec::new();
             }
-            Some(file_candidate.to_string_lossy().replace('\\', "/"))
+            vec![
+                normalize_path(parent.join(format!("{module}.rs"))),
+                normalize_path(parent.join(module).join("mod.rs")),
+            ]
         }
         "python" => {
             let module = module_spec.trim_start_matches('.');
             let dotted = module.replace('.', "/");
-            let py_candidate = normalize_path(importing_dir.join(format!("{dotted}.py")));
-            if py_candidate.exists() {
-                return Some(py_candidate.to_string_lossy().replace('\\', "/"));
-            }
-            let init_candidate = normalize_path(importing_dir.join(dotted).join("__init__.py"));
-            if init_candidate.exists() {
-                return Some(init_candidate.to_string_lossy().replace('\\', "/"));
-            }
-            Some(py_candidate.to_string_lossy().replace('\\', "/"))
+            // Qualified names from the extractor are package-absolute, so the
+            // repository root is tried after the importing directory.
+            vec![
+                normalize_path(importing_dir.join(format!("{dotted}.py"))),
+                normalize_path(importing_dir.join(&dotted).join("__init__.py")),
+                normalize_path(PathBuf::from(format!("{dotted}.py"))),
+                normalize_path(PathBuf::from(&dotted).join("__init__.py")),
+            ]
         }
-        "go" => None,
-        _ => None,
-    }
+        _ => Vec::new(),
+    };
+    let mut seen = HashSet::new();
+    candidates
+        .into_iter()
+        .map(|path| path.to_string_lossy().replace('\\', "/"))
+        .filter(|path| !path.is_empty() && seen.insert(path.clone()))
+        .collect()
 }
 
 fn normalize_path(path: PathBuf) -> PathBuf {
@@ -412,6 +436,11 @@ mod tests {
             parent_symbol_id: None,
             visibility: Some("pub".to_string()),
             content: None,
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
         };
         symbols::insert_symbol(conn, &record).unwrap();
     }
@@ -500,6 +529,51 @@ mod tests {
         assert!((edges[0].confidence_weight - 0.2).abs() < f64::EPSILON);
     }
 
+    #[test]
+    fn resolve_import_target_finds_module_file_in_manifest() {
+        let conn = setup_test_db();
+        cruxe_state::manifest::upsert_manifest(
+            &conn,
+            &cruxe_state::manifest::ManifestEntry {
+                repo: "my-repo".to_string(),
+                r#ref: "main".to_string(),
+                path: "src/utils/index.ts".to_string(),
+                content_hash: "hash".to_string(),
+                size_bytes: 10,
+                mtime_ns: None,
+                language: Some("typescript".to_string()),
+                indexed_at: "2026-01-01T00:00:00Z".to_string(),
+            },
+        )
+        .unwrap();
+
+        let namespace = RawImport {
+            source_qualified_name: source_symbol_id_for_path("src/index.ts"),
+            target_qualified_name: "src/utils::*".to_string(),
+            target_name: "Utils".to_string(),
+            import_line: 1,
+        };
+        let target = resolve_import_target(&conn, "my-repo", "main", &namespace).unwrap();
+        assert!(target.to_symbol_id.is_none());
+        assert_eq!(target.module_path.as_deref(), Some("src/utils/index.ts"));
+        assert_eq!(target.outcome, RESOLUTION_UNRESOLVED);
+
+        let missing = RawImport {
+            target_qualified_name: "src/missing::*".to_string(),
+            ..namespace
+        };
+        let target = resolve_import_target(&conn, "my-repo", "main", &missing).unwrap();
+        assert!(target.module_path.is_none());
+        assert_eq!(target.outcome, RESOLUTION_EXTERNAL_REFERENCE);
+    }
+
+    #[test]
+    fn import_path_candidates_cover_package_absolute_python_modules() {
+        let candidates = import_path_candidates("pkg/handlers.py", "pkg.models", "python");
+        assert!(candidates.contains(&"pkg/models.py".to_string()));
+        assert!(import_path_candidates("main.go", "fmt", "go").is_empty());
+    }
+
     #[test]
     fn extract_imports_dispatch_rust_nested_use() {
         let source = r#"
@@ -28,7 +28,16 @@ pub fn tag_language_spec(language: &str) -> Option<TagLanguageSpec> {
 }
 
 pub fn parser_language(language: &str) -> Option<tree_sitter::Language> {
-    tag_language_spec(language).map(|spec| spec.language)
+    match language {
+        // C, C++, C#, Ruby and Kotlin symbols are extracted by walking the
+        // tree, not via a tags query.
+        "c" => Some(tree_sitter_c::LANGUAGE.into()),
+        "cpp" => Some(tree_sitter_cpp::LANGUAGE.into()),
+        "csharp" => Some(tree_sitter_c_sharp::LANGUAGE.into()),
+        "ruby" => Some(tree_sitter_ruby::LANGUAGE.into()),
+        "kotlin" => Some(tree_sitter_kotlin_ng::LANGUAGE.into()),
+        _ => tag_language_spec(language).map(|spec| spec.language),
+    }
 }
 
 pub fn combined_tags_query(language: &str) -> Option<String> {
@@ -46,6 +55,7 @@ fn custom_query_extra(language: &str) -> &'static str {
             r#"
 (const_item name: (identifier) @name) @definition.constant
 (static_item name: (identifier) @name) @definition.variable
+(trait_item body: (declaration_list (function_signature_item name: (identifier) @name) @definition.method))
 "#
         }
         "typescript" => {
@@ -57,6 +67,9 @@ fn custom_query_extra(language: &str) -> &'static str {
 (type_alias_declaration name: (type_identifier) @name) @definition.class
 (lexical_declaration (variable_declarator name: (identifier) @name)) @definition.variable
 (variable_declaration (variable_declarator name: (identifier) @name)) @definition.variable
+(lexical_declaration (variable_declarator name: (identifier) @name value: [(arrow_function) (function_expression)])) @definition.function
+(variable_declaration (variable_declarator name: (identifier) @name value: [(arrow_function) (function_expression)])) @definition.function
+(public_field_definition name: (property_identifier) @name value: [(arrow_function) (function_expression)]) @definition.method
 "#
         }
         "go" => {
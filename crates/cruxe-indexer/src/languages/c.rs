@@ -0,0 +1,314 @@
+use super::{DepthGuard, ExtractedSymbol};
+use cruxe_core::types::SymbolKind;
+
+/// Extract C symbols by walking the top level of the tree-sitter-c grammar.
+///
+/// C has no scoping constructs that matter for navigation, so only file-level
+/// items are emitted: function definitions, tagged struct/union/enum types,
+/// typedefs and `#define` macros. Items wrapped in preprocessor conditionals
+/// (header guards, `#ifdef` feature blocks) are still treated as top level.
+pub fn extract_symbols(
+    tree: &tree_sitter::Tree,
+    source: &str,
+    guard: &mut DepthGuard,
+) -> Vec<ExtractedSymbol> {
+    let mut symbols = Vec::new();
+    collect_top_level(tree.root_node(), source, 0, guard, &mut symbols);
+    super::sort_symbols(&mut symbols);
+    symbols
+}
+
+fn collect_top_level(
+    node: tree_sitter::Node,
+    source: &str,
+    depth: usize,
+    guard: &mut DepthGuard,
+    out: &mut Vec<ExtractedSymbol>,
+) {
+    if !guard.allows(depth) {
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        match child.kind() {
+            "function_definition" => {
+                if let Some(symbol) = function_symbol(child, source) {
+                    out.push(symbol);
+                }
+            }
+            "declaration" => {
+                // `struct point { ... } origin;` still defines the tagged type.
+                if let Some(type_node) = child.child_by_field_name("type")
+                    && let Some(symbol) = tagged_type_symbol(type_node, None, child, source)
+                {
+                    out.push(symbol);
+                }
+            }
+            "struct_specifier" | "union_specifier" | "enum_specifier" => {
+                if let Some(symbol) = tagged_type_symbol(child, None, child, source) {
+                    out.push(symbol);
+                }
+            }
+            "type_definition" => typedef_symbols(child, source, out),
+            "preproc_def" | "preproc_function_def" => {
+                if let Some(symbol) = macro_symbol(child, source) {
+                    out.push(symbol);
+                }
+            }
+            "preproc_if" | "preproc_ifdef" | "preproc_else" | "preproc_elif"
+            | "preproc_elifdef" => collect_top_level(child, source, depth + 1, guard, out),
+            _ => {}
+        }
+    }
+}
+
+fn function_symbol(node: tree_sitter::Node, source: &str) -> Option<ExtractedSymbol> {
+    let declarator = node.child_by_field_name("declarator")?;
+    let name = declarator_name(declarator, source)?;
+    let signature_end = node
+        .child_by_field_name("body")
+        .map(|body| body.start_byte())
+        .unwrap_or_else(|| node.end_byte());
+    let signature = source
+        .get(node.start_byte()..signature_end)
+        .map(collapse_whitespace)
+        .filter(|s| !s.is_empty());
+    let visibility = is_static(node, source).then(|| "private".to_string());
+
+    let mut symbol = build_symbol(name, SymbolKind::Function, node, source);
+    symbol.signature = signature;
+    symbol.visibility = visibility;
+    Some(symbol)
+}
+
+/// Emit a symbol for a struct/union/enum specifier that carries a body.
+///
+/// `typedef_name` names anonymous specifiers (`typedef struct { ... } Point;`);
+/// forward declarations such as `struct node;` have no body and are skipped.
+fn tagged_type_symbol(
+    specifier: tree_sitter::Node,
+    typedef_name: Option<&str>,
+    definition: tree_sitter::Node,
+    source: &str,
+) -> Option<ExtractedSymbol> {
+    let kind = match specifier.kind() {
+        "struct_specifier" | "union_specifier" => SymbolKind::Struct,
+        "enum_specifier" => SymbolKind::Enum,
+        _ => return None,
+    };
+    specifier.child_by_field_name("body")?;
+    let name = match specifier.child_by_field_name("name") {
+        Some(name_node) => node_text(name_node, source).to_string(),
+        None => typedef_name?.to_string(),
+    };
+    Some(build_symbol(name, kind, definition, source))
+}
+
+fn typedef_symbols(node: tree_sitter::Node, source: &str, out: &mut Vec<ExtractedSymbol>) {
+    let mut cursor = node.walk();
+    let names: Vec<String> = node
+        .children_by_field_name("declarator", &mut cursor)
+        .filter_map(|declarator| declarator_name(declarator, source))
+        .collect();
+    let Some(type_node) = node.child_by_field_name("type") else {
+        return;
+    };
+
+    let anonymous = type_node.child_by_field_name("name").is_none();
+    let mut aliases = names.iter();
+    if anonymous {
+        // The first typedef name stands in for the anonymous type itself.
+        if let Some(symbol) =
+            tagged_type_symbol(type_node, names.first().map(String::as_str), node, source)
+        {
+            out.push(symbol);
+            aliases.next();
+        }
+    } else if let Some(symbol) = tagged_type_symbol(type_node, None, node, source) {
+        out.push(symbol);
+    }
+
+    for name in aliases {
+        out.push(build_symbol(
+            name.clone(),
+            SymbolKind::TypeAlias,
+            node,
+            source,
+        ));
+    }
+}
+
+fn macro_symbol(node: tree_sitter::Node, source: &str) -> Option<ExtractedSymbol> {
+    let name = node_text(node.child_by_field_name("name")?, source).to_string();
+    let mut symbol = build_symbol(name, SymbolKind::Constant, node, source);
+    symbol.signature = source
+        .get(node.byte_range())
+        .and_then(|text| text.lines().next())
+        .map(|line| line.trim().to_string())
+        .filter(|s| !s.is_empty());
+    Some(symbol)
+}
+
+/// Resolve the identifier a declarator introduces, unwrapping pointer,
+/// array, function and parenthesized declarators.
+fn declarator_name(node: tree_sitter::Node, source: &str) -> Option<String> {
+    match node.kind() {
+        "identifier" | "type_identifier" | "field_identifier" | "primitive_type" => {
+            Some(node_text(node, source).to_string())
+        }
+        "parenthesized_declarator" | "attributed_declarator" => {
+            let mut cursor = node.walk();
+            let inner = node.named_children(&mut cursor).next()?;
+            declarator_name(inner, source)
+        }
+        _ => declarator_name(node.child_by_field_name("declarator")?, source),
+    }
+}
+
+fn is_static(node: tree_sitter::Node, source: &str) -> bool {
+    let mut cursor = node.walk();
+    node.children(&mut cursor).any(|child| {
+        child.kind() == "storage_class_specifier" && node_text(child, source) == "static"
+    })
+}
+
+fn build_symbol(
+    name: String,
+    kind: SymbolKind,
+    node: tree_sitter::Node,
+    source: &str,
+) -> ExtractedSymbol {
+    let range = node.byte_range();
+    ExtractedSymbol {
+        qualified_name: name.clone(),
+        name,
+        kind,
+        language: "c".to_string(),
+        signature: None,
+        line_start: node.start_position().row as u32 + 1,
+        line_end: node.end_position().row as u32 + 1,
+        byte_start: range.start as u32,
+        byte_end: range.end as u32,
+        visibility: None,
+        parent_name: None,
+        body: source.get(range).map(String::from),
+        attributes: Vec::new(),
+        doc_comment: None,
+        parsed_signature: None,
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn node_text<'a>(node: tree_sitter::Node, source: &'a str) -> &'a str {
+    source.get(node.byte_range()).unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_file;
+
+    const SOURCE: &str = r#"
+#ifndef LEDGER_H
+#define LEDGER_H
+
+#define MAX_ENTRIES 128
+#define CLAMP(x, lo, hi) ((x) < (lo) ? (lo) : (x))
+
+struct entry {
+    int id;
+    long amount;
+};
+
+union value {
+    int i;
+    double d;
+};
+
+enum status { OPEN, CLOSED };
+
+typedef struct {
+    int x;
+    int y;
+} Point, *PointPtr;
+
+typedef struct entry Entry;
+
+static int
+clamp_amount(long amount,
+             long limit)
+{
+    return amount > limit ? (int)limit : (int)amount;
+}
+
+const char *ledger_name(void) {
+    return "ledger";
+}
+
+#endif
+"#;
+
+    fn symbols() -> Vec<ExtractedSymbol> {
+        let tree = parse_file(SOURCE, "c").expect("parse c");
+        extract_symbols(&tree, SOURCE, &mut DepthGuard::default())
+    }
+
+    fn find<'a>(symbols: &'a [ExtractedSymbol], name: &str) -> &'a ExtractedSymbol {
+        symbols
+            .iter()
+            .find(|s| s.name == name)
+            .unwrap_or_else(|| panic!("expected symbol {name}"))
+    }
+
+    #[test]
+    fn functions_carry_declarator_signature_and_static_visibility() {
+        let symbols = symbols();
+
+        let clamp = find(&symbols, "clamp_amount");
+        assert_eq!(clamp.kind, SymbolKind::Function);
+        assert_eq!(
+            clamp.signature.as_deref(),
+            Some("static int clamp_amount(long amount, long limit)")
+        );
+        assert_eq!(clamp.visibility.as_deref(), Some("private"));
+
+        let name_fn = find(&symbols, "ledger_name");
+        assert_eq!(
+            name_fn.signature.as_deref(),
+            Some("const char *ledger_name(void)")
+        );
+        assert_eq!(name_fn.visibility, None);
+    }
+
+    #[test]
+    fn tagged_types_and_typedefs_are_named() {
+        let symbols = symbols();
+
+        assert_eq!(find(&symbols, "entry").kind, SymbolKind::Struct);
+        assert_eq!(find(&symbols, "value").kind, SymbolKind::Struct);
+        assert_eq!(find(&symbols, "status").kind, SymbolKind::Enum);
+        assert_eq!(find(&symbols, "Point").kind, SymbolKind::Struct);
+        assert_eq!(find(&symbols, "PointPtr").kind, SymbolKind::TypeAlias);
+        assert_eq!(find(&symbols, "Entry").kind, SymbolKind::TypeAlias);
+        assert_eq!(
+            symbols.iter().filter(|s| s.name == "entry").count(),
+            1,
+            "typedef of a named struct should not duplicate the struct"
+        );
+    }
+
+    #[test]
+    fn macros_inside_header_guards_are_constants() {
+        let symbols = symbols();
+
+        let max = find(&symbols, "MAX_ENTRIES");
+        assert_eq!(max.kind, SymbolKind::Constant);
+        assert_eq!(max.signature.as_deref(), Some("#define MAX_ENTRIES 128"));
+        assert_eq!(find(&symbols, "CLAMP").kind, SymbolKind::Constant);
+        assert_eq!(find(&symbols, "LEDGER_H").kind, SymbolKind::Constant);
+        assert!(symbols.iter().all(|s| s.language == "c"));
+    }
+}
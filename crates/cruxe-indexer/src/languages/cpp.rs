@@ -0,0 +1,636 @@
+use super::generic_mapper::strip_generic_args;
+use super::{DepthGuard, ExtractedSymbol};
+use cruxe_core::types::SymbolKind;
+
+/// Extract C++ symbols by walking declarations of the tree-sitter-cpp grammar.
+///
+/// Emits namespaces, classes, structs/unions, enums (including `enum class`),
+/// function and member-function definitions, type aliases and `#define`
+/// macros. `qualified_name` joins namespace and class nesting with `::`, so
+/// `namespace billing { class Ledger { void post(); }; }` yields
+/// `billing::Ledger::post`. Declarations wrapped in `template <...>` span the
+/// whole template and keep the parameter list in their signature. Out-of-line
+/// definitions (`void Ledger::post() { ... }`) are methods whose
+/// `parent_name` is the last qualifier, here `Ledger`. Prototypes without a
+/// body are skipped, as in the C extractor.
+pub fn extract_symbols(
+    tree: &tree_sitter::Tree,
+    source: &str,
+    guard: &mut DepthGuard,
+) -> Vec<ExtractedSymbol> {
+    let mut symbols = Vec::new();
+    collect_declarations(
+        tree.root_node(),
+        source,
+        &Scope::default(),
+        0,
+        guard,
+        &mut symbols,
+    );
+    super::sort_symbols(&mut symbols);
+    symbols
+}
+
+/// Lexical position of a declaration list: the enclosing namespace/class path
+/// and, inside a class body, the access level members start with.
+#[derive(Default)]
+struct Scope {
+    path: Vec<String>,
+    default_access: Option<&'static str>,
+}
+
+impl Scope {
+    fn in_type(&self) -> bool {
+        self.default_access.is_some()
+    }
+
+    fn child(
+        &self,
+        segments: impl IntoIterator<Item = String>,
+        access: Option<&'static str>,
+    ) -> Scope {
+        let mut path = self.path.clone();
+        path.extend(segments);
+        Scope {
+            path,
+            default_access: access,
+        }
+    }
+}
+
+/// A declaration as it appears in a body: `outer` is the node whose range and
+/// signature the symbol takes (the `template_declaration` when templated),
+/// `item` the declaration itself.
+#[derive(Clone, Copy)]
+struct Item<'t> {
+    outer: tree_sitter::Node<'t>,
+    item: tree_sitter::Node<'t>,
+}
+
+fn collect_declarations(
+    node: tree_sitter::Node,
+    source: &str,
+    scope: &Scope,
+    depth: usize,
+    guard: &mut DepthGuard,
+    out: &mut Vec<ExtractedSymbol>,
+) {
+    if !guard.allows(depth) {
+        return;
+    }
+    let mut access = scope.default_access.map(str::to_string);
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if child.kind() == "access_specifier" {
+            access = Some(
+                node_text(child, source)
+                    .trim_end_matches(':')
+                    .trim()
+                    .to_string(),
+            );
+            continue;
+        }
+        let visibility = access.as_deref();
+        match child.kind() {
+            "namespace_definition" => namespace_symbols(child, source, scope, depth, guard, out),
+            "template_declaration" => {
+                if let Some(item) = templated_item(child) {
+                    collect_item(item, source, scope, visibility, depth, guard, out);
+                }
+            }
+            "linkage_specification"
+            | "declaration_list"
+            | "preproc_if"
+            | "preproc_ifdef"
+            | "preproc_else"
+            | "preproc_elif"
+            | "preproc_elifdef" => {
+                collect_declarations(child, source, scope, depth + 1, guard, out)
+            }
+            "preproc_def" | "preproc_function_def" => {
+                out.extend(macro_symbol(child, source, scope))
+            }
+            _ => collect_item(
+                Item {
+                    outer: child,
+                    item: child,
+                },
+                source,
+                scope,
+                visibility,
+                depth,
+                guard,
+                out,
+            ),
+        }
+    }
+}
+
+fn collect_item(
+    item: Item,
+    source: &str,
+    scope: &Scope,
+    visibility: Option<&str>,
+    depth: usize,
+    guard: &mut DepthGuard,
+    out: &mut Vec<ExtractedSymbol>,
+) {
+    match item.item.kind() {
+        "function_definition" => out.extend(function_symbol(item, source, scope, visibility)),
+        "class_specifier" | "struct_specifier" | "union_specifier" | "enum_specifier" => {
+            type_symbols(item, None, source, scope, visibility, depth, guard, out);
+        }
+        // `class Ledger { ... } ledger;` and class bodies nested in another
+        // class still define the type.
+        "declaration" | "field_declaration" => {
+            if let Some(specifier) = item.item.child_by_field_name("type") {
+                let item = Item {
+                    outer: item.outer,
+                    item: specifier,
+                };
+                type_symbols(item, None, source, scope, visibility, depth, guard, out);
+            }
+        }
+        "type_definition" => typedef_symbols(item, source, scope, visibility, depth, guard, out),
+        "alias_declaration" => {
+            if let Some(name) = item.item.child_by_field_name("name") {
+                let name = node_text(name, source).to_string();
+                let mut symbol =
+                    build_symbol(name, SymbolKind::TypeAlias, item.outer, source, &scope.path);
+                symbol.signature = Some(collapse_whitespace(
+                    node_text(item.outer, source).trim_end_matches(';'),
+                ));
+                symbol.visibility = visibility.map(str::to_string);
+                out.push(symbol);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn namespace_symbols(
+    node: tree_sitter::Node,
+    source: &str,
+    scope: &Scope,
+    depth: usize,
+    guard: &mut DepthGuard,
+    out: &mut Vec<ExtractedSymbol>,
+) {
+    // `namespace a::b { ... }` opens both `a` and `b`; anonymous namespaces
+    // add no path segment.
+    let segments: Vec<String> = node
+        .child_by_field_name("name")
+        .map(|name| qualifier_segments(name, source))
+        .unwrap_or_default();
+    let body = node.child_by_field_name("body");
+
+    if let Some((name, outer)) = segments.split_last() {
+        let mut path = scope.path.clone();
+        path.extend(outer.iter().cloned());
+        let mut symbol = build_symbol(name.clone(), SymbolKind::Module, node, source, &path);
+        symbol.signature = signature_before(node, body, source);
+        out.push(symbol);
+    }
+    if let Some(body) = body {
+        let inner = scope.child(segments, None);
+        collect_declarations(body, source, &inner, depth + 1, guard, out);
+    }
+}
+
+/// Emit a class/struct/union/enum specifier that carries a body, then its
+/// members.
+///
+/// `typedef_name` names anonymous specifiers (`typedef struct { ... } Point;`);
+/// forward declarations such as `class Ledger;` have no body and are skipped.
+#[allow(clippy::too_many_arguments)]
+fn type_symbols(
+    item: Item,
+    typedef_name: Option<&str>,
+    source: &str,
+    scope: &Scope,
+    visibility: Option<&str>,
+    depth: usize,
+    guard: &mut DepthGuard,
+    out: &mut Vec<ExtractedSymbol>,
+) -> bool {
+    let specifier = item.item;
+    let (kind, member_access) = match specifier.kind() {
+        "class_specifier" => (SymbolKind::Class, "private"),
+        "struct_specifier" | "union_specifier" => (SymbolKind::Struct, "public"),
+        "enum_specifier" => (SymbolKind::Enum, "public"),
+        _ => return false,
+    };
+    let Some(body) = specifier.child_by_field_name("body") else {
+        return false;
+    };
+    let name = match specifier.child_by_field_name("name") {
+        Some(name_node) => match qualifier_segments(name_node, source).pop() {
+            Some(name) => name,
+            None => return false,
+        },
+        None => match typedef_name {
+            Some(name) => name.to_string(),
+            None => return false,
+        },
+    };
+
+    let mut symbol = build_symbol(name.clone(), kind, item.outer, source, &scope.path);
+    symbol.signature = signature_before(item.outer, Some(body), source);
+    symbol.visibility = visibility.map(str::to_string);
+    out.push(symbol);
+
+    if kind != SymbolKind::Enum {
+        let inner = scope.child([name], Some(member_access));
+        collect_declarations(body, source, &inner, depth + 1, guard, out);
+    }
+    true
+}
+
+fn typedef_symbols(
+    item: Item,
+    source: &str,
+    scope: &Scope,
+    visibility: Option<&str>,
+    depth: usize,
+    guard: &mut DepthGuard,
+    out: &mut Vec<ExtractedSymbol>,
+) {
+    let mut cursor = item.item.walk();
+    let names: Vec<String> = item
+        .item
+        .children_by_field_name("declarator", &mut cursor)
+        .filter_map(|declarator| declarator_path(declarator, source)?.pop())
+        .collect();
+    let Some(type_node) = item.item.child_by_field_name("type") else {
+        return;
+    };
+
+    let anonymous = type_node.child_by_field_name("name").is_none();
+    let specifier = Item {
+        outer: item.outer,
+        item: type_node,
+    };
+    let mut aliases = names.iter();
+    // The first typedef name stands in for an anonymous type itself.
+    let typedef_name = names.first().map(String::as_str).filter(|_| anonymous);
+    if type_symbols(
+        specifier,
+        typedef_name,
+        source,
+        scope,
+        visibility,
+        depth,
+        guard,
+        out,
+    ) && anonymous
+    {
+        aliases.next();
+    }
+
+    for name in aliases {
+        let mut symbol = build_symbol(
+            name.clone(),
+            SymbolKind::TypeAlias,
+            item.outer,
+            source,
+            &scope.path,
+        );
+        symbol.visibility = visibility.map(str::to_string);
+        out.push(symbol);
+    }
+}
+
+fn function_symbol(
+    item: Item,
+    source: &str,
+    scope: &Scope,
+    visibility: Option<&str>,
+) -> Option<ExtractedSymbol> {
+    let declarator = item.item.child_by_field_name("declarator")?;
+    let mut segments = declarator_path(declarator, source)?;
+    let name = segments.pop()?;
+    let kind = if scope.in_type() || !segments.is_empty() {
+        SymbolKind::Method
+    } else {
+        SymbolKind::Function
+    };
+    let mut path = scope.path.clone();
+    path.extend(segments);
+
+    let mut symbol = build_symbol(name, kind, item.outer, source, &path);
+    symbol.signature = signature_before(item.outer, item.item.child_by_field_name("body"), source);
+    symbol.visibility = match visibility {
+        Some(access) => Some(access.to_string()),
+        None => is_static(item.item, source).then(|| "private".to_string()),
+    };
+    Some(symbol)
+}
+
+fn macro_symbol(node: tree_sitter::Node, source: &str, scope: &Scope) -> Option<ExtractedSymbol> {
+    let name = node_text(node.child_by_field_name("name")?, source).to_string();
+    let mut symbol = build_symbol(name, SymbolKind::Constant, node, source, &scope.path);
+    symbol.signature = node_text(node, source)
+        .lines()
+        .next()
+        .map(|line| line.trim().to_string())
+        .filter(|s| !s.is_empty());
+    Some(symbol)
+}
+
+/// The declaration inside `template <...>`; nested templates
+/// (`template <class T> template <class U>`) unwrap to the innermost one.
+fn templated_item(node: tree_sitter::Node) -> Option<Item> {
+    let mut inner = node;
+    while inner.kind() == "template_declaration" {
+        let mut cursor = inner.walk();
+        inner = inner
+            .named_children(&mut cursor)
+            .filter(|child| child.kind() != "template_parameter_list")
+            .last()?;
+    }
+    Some(Item {
+        outer: node,
+        item: inner,
+    })
+}
+
+/// Resolve the (possibly qualified) name a declarator introduces, unwrapping
+/// pointer, reference, function and parenthesized declarators.
+///
+/// `int *a::Ledger::post(int)` resolves to `["a", "Ledger", "post"]`.
+fn declarator_path(node: tree_sitter::Node, source: &str) -> Option<Vec<String>> {
+    match node.kind() {
+        "identifier"
+        | "field_identifier"
+        | "type_identifier"
+        | "primitive_type"
+        | "destructor_name"
+        | "operator_name"
+        | "qualified_identifier"
+        | "template_function" => {
+            let segments = qualifier_segments(node, source);
+            (!segments.is_empty()).then_some(segments)
+        }
+        "parenthesized_declarator" | "attributed_declarator" | "reference_declarator" => {
+            let mut cursor = node.walk();
+            let inner = node.named_children(&mut cursor).next()?;
+            declarator_path(inner, source)
+        }
+        _ => declarator_path(node.child_by_field_name("declarator")?, source),
+    }
+}
+
+/// Split a name node on `::`, dropping template arguments from each segment.
+fn qualifier_segments(node: tree_sitter::Node, source: &str) -> Vec<String> {
+    strip_generic_args(&collapse_whitespace(node_text(node, source)))
+        .split("::")
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn is_static(node: tree_sitter::Node, source: &str) -> bool {
+    let mut cursor = node.walk();
+    node.children(&mut cursor).any(|child| {
+        child.kind() == "storage_class_specifier" && node_text(child, source) == "static"
+    })
+}
+
+fn build_symbol(
+    name: String,
+    kind: SymbolKind,
+    node: tree_sitter::Node,
+    source: &str,
+    path: &[String],
+) -> ExtractedSymbol {
+    let range = node.byte_range();
+    let qualified_name = path
+        .iter()
+        .cloned()
+        .chain(std::iter::once(name.clone()))
+        .collect::<Vec<_>>()
+        .join("::");
+    ExtractedSymbol {
+        qualified_name,
+        name,
+        kind,
+        language: "cpp".to_string(),
+        signature: None,
+        line_start: node.start_position().row as u32 + 1,
+        line_end: node.end_position().row as u32 + 1,
+        byte_start: range.start as u32,
+        byte_end: range.end as u32,
+        visibility: None,
+        parent_name: path.last().cloned(),
+        body: source.get(range).map(String::from),
+        attributes: Vec::new(),
+        doc_comment: None,
+        parsed_signature: None,
+    }
+}
+
+/// Declaration text up to (not including) `end`, usually the body.
+fn signature_before(
+    node: tree_sitter::Node,
+    end: Option<tree_sitter::Node>,
+    source: &str,
+) -> Option<String> {
+    let end = end.map_or(node.end_byte(), |n| n.start_byte());
+    let text = source.get(node.start_byte()..end)?;
+    let signature = collapse_whitespace(text.trim_end().trim_end_matches(';'));
+    (!signature.is_empty()).then_some(signature)
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn node_text<'a>(node: tree_sitter::Node, source: &'a str) -> &'a str {
+    source.get(node.byte_range()).unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_file;
+
+    const SOURCE: &str = r#"
+#pragma once
+#define LEDGER_VERSION 2
+
+namespace billing {
+namespace detail {
+
+template <typename T, int N = 3>
+T clamp(T value,
+        T limit)
+{
+    return value > limit ? limit : value;
+}
+
+}  // namespace detail
+
+class Ledger : public Base {
+public:
+    Ledger() {}
+    ~Ledger();
+    int total() const { return total_; }
+
+    template <class U>
+    void add(U amount) {}
+
+private:
+    struct Entry {
+        int id;
+    };
+    enum class Status : int { Open, Closed };
+    int total_ = 0;
+};
+
+void Ledger::post(int amount) {}
+Ledger::~Ledger() {}
+
+namespace {
+int hidden() { return 1; }
+}
+
+}  // namespace billing
+
+namespace billing::reports {
+template <typename K>
+struct Page {
+    K key;
+};
+
+using PageId = long;
+}
+
+enum Color { Red, Green };
+
+static int helper(void) { return 0; }
+
+int *billing::Ledger::raw() { return nullptr; }
+"#;
+
+    fn symbols() -> Vec<ExtractedSymbol> {
+        let tree = parse_file(SOURCE, "cpp").expect("parse cpp");
+        extract_symbols(&tree, SOURCE, &mut DepthGuard::default())
+    }
+
+    fn find<'a>(symbols: &'a [ExtractedSymbol], qualified_name: &str) -> &'a ExtractedSymbol {
+        symbols
+            .iter()
+            .find(|s| s.qualified_name == qualified_name)
+            .unwrap_or_else(|| panic!("expected symbol {qualified_name}"))
+    }
+
+    #[test]
+    fn namespaces_and_classes_qualify_names_with_double_colons() {
+        let symbols = symbols();
+
+        assert_eq!(find(&symbols, "billing").kind, SymbolKind::Module);
+        assert_eq!(find(&symbols, "billing::detail").kind, SymbolKind::Module);
+        let reports = find(&symbols, "billing::reports");
+        assert_eq!(reports.name, "reports");
+        assert_eq!(reports.parent_name.as_deref(), Some("billing"));
+
+        let ledger = find(&symbols, "billing::Ledger");
+        assert_eq!(ledger.kind, SymbolKind::Class);
+        assert_eq!(
+            ledger.signature.as_deref(),
+            Some("class Ledger : public Base")
+        );
+
+        let total = find(&symbols, "billing::Ledger::total");
+        assert_eq!(total.kind, SymbolKind::Method);
+        assert_eq!(total.parent_name.as_deref(), Some("Ledger"));
+        assert_eq!(total.visibility.as_deref(), Some("public"));
+        assert_eq!(
+            find(&symbols, "billing::Ledger::Ledger").kind,
+            SymbolKind::Method
+        );
+
+        let entry = find(&symbols, "billing::Ledger::Entry");
+        assert_eq!(entry.kind, SymbolKind::Struct);
+        assert_eq!(entry.visibility.as_deref(), Some("private"));
+        let status = find(&symbols, "billing::Ledger::Status");
+        assert_eq!(status.kind, SymbolKind::Enum);
+        assert_eq!(status.signature.as_deref(), Some("enum class Status : int"));
+
+        assert_eq!(find(&symbols, "billing::hidden").kind, SymbolKind::Function);
+        assert_eq!(find(&symbols, "Color").kind, SymbolKind::Enum);
+        assert_eq!(
+            find(&symbols, "billing::reports::PageId").kind,
+            SymbolKind::TypeAlias
+        );
+        assert_eq!(find(&symbols, "LEDGER_VERSION").kind, SymbolKind::Constant);
+        assert_eq!(
+            find(&symbols, "helper").visibility.as_deref(),
+            Some("private")
+        );
+        assert_eq!(
+            symbols
+                .iter()
+                .filter(|s| s.qualified_name == "billing::Ledger::~Ledger")
+                .count(),
+            1,
+            "in-class prototypes without a body are skipped"
+        );
+        assert!(symbols.iter().all(|s| s.language == "cpp"));
+    }
+
+    #[test]
+    fn templates_keep_the_parameter_list_in_the_signature() {
+        let symbols = symbols();
+
+        let clamp = find(&symbols, "billing::detail::clamp");
+        assert_eq!(clamp.kind, SymbolKind::Function);
+        assert_eq!(
+            clamp.signature.as_deref(),
+            Some("template <typename T, int N = 3> T clamp(T value, T limit)")
+        );
+        assert!(
+            clamp
+                .body
+                .as_deref()
+                .is_some_and(|body| body.starts_with("template <typename T")),
+            "the symbol range should cover the template header"
+        );
+
+        let add = find(&symbols, "billing::Ledger::add");
+        assert_eq!(add.kind, SymbolKind::Method);
+        assert_eq!(
+            add.signature.as_deref(),
+            Some("template <class U> void add(U amount)")
+        );
+
+        let page = find(&symbols, "billing::reports::Page");
+        assert_eq!(page.kind, SymbolKind::Struct);
+        assert_eq!(
+            page.signature.as_deref(),
+            Some("template <typename K> struct Page")
+        );
+    }
+
+    #[test]
+    fn out_of_line_definitions_are_methods_of_their_qualifier() {
+        let symbols = symbols();
+
+        let post = find(&symbols, "billing::Ledger::post");
+        assert_eq!(post.kind, SymbolKind::Method);
+        assert_eq!(post.parent_name.as_deref(), Some("Ledger"));
+        assert_eq!(
+            post.signature.as_deref(),
+            Some("void Ledger::post(int amount)")
+        );
+
+        let destructor = find(&symbols, "billing::Ledger::~Ledger");
+        assert_eq!(destructor.kind, SymbolKind::Method);
+        assert_eq!(destructor.parent_name.as_deref(), Some("Ledger"));
+
+        let raw = find(&symbols, "billing::Ledger::raw");
+        assert_eq!(raw.parent_name.as_deref(), Some("Ledger"));
+        assert_eq!(raw.visibility, None);
+    }
+}
@@ -0,0 +1,548 @@
+use super::{DepthGuard, ExtractedSymbol};
+use cruxe_core::types::SymbolKind;
+
+/// Extract C# symbols by walking declarations of the tree-sitter-c-sharp
+/// grammar.
+///
+/// Emits namespaces (block and file-scoped), classes, structs, interfaces,
+/// records, enums (and their members), methods, constructors, properties,
+/// fields and events. `qualified_name` joins namespace and type nesting with
+/// `.`, so `namespace Billing.Core { class Invoice { void Pay() {} } }` yields
+/// `Billing.Core.Invoice.Pay`. Access modifiers (`public`, `protected
+/// internal`, ...) become `visibility`; positional record parameters are
+/// fields. The parts of a `partial` type declared in one file share a single
+/// type symbol (the first part), so every member resolves to the same parent;
+/// parts in other files carry the same `qualified_name`.
+pub fn extract_symbols(
+    tree: &tree_sitter::Tree,
+    source: &str,
+    guard: &mut DepthGuard,
+) -> Vec<ExtractedSymbol> {
+    let mut symbols = Vec::new();
+    collect_body(tree.root_node(), source, &[], 0, guard, &mut symbols);
+    super::sort_symbols(&mut symbols);
+    symbols
+}
+
+fn collect_body(
+    node: tree_sitter::Node,
+    source: &str,
+    scope: &[String],
+    depth: usize,
+    guard: &mut DepthGuard,
+    out: &mut Vec<ExtractedSymbol>,
+) {
+    if !guard.allows(depth) {
+        return;
+    }
+    // `namespace Billing;` applies to every declaration that follows it.
+    let mut scope = scope.to_vec();
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        match child.kind() {
+            "namespace_declaration" => {
+                namespace_symbols(child, source, &scope, depth, guard, out);
+            }
+            "file_scoped_namespace_declaration" => {
+                scope = namespace_symbols(child, source, &scope, depth, guard, out);
+            }
+            "class_declaration"
+            | "struct_declaration"
+            | "interface_declaration"
+            | "record_declaration"
+            | "enum_declaration" => type_symbols(child, source, &scope, depth, guard, out),
+            "method_declaration" | "constructor_declaration" => {
+                out.extend(method_symbol(child, source, &scope))
+            }
+            "property_declaration" => out.extend(property_symbol(child, source, &scope)),
+            "field_declaration" | "event_field_declaration" => {
+                field_symbols(child, source, &scope, out)
+            }
+            "enum_member_declaration" => {
+                if let Some(name) = child.child_by_field_name("name") {
+                    let name = node_text(name, source).to_string();
+                    out.push(build_symbol(
+                        name,
+                        SymbolKind::Constant,
+                        child,
+                        source,
+                        &scope,
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Emit a namespace symbol and walk its body; returns the path it opens.
+fn namespace_symbols(
+    node: tree_sitter::Node,
+    source: &str,
+    scope: &[String],
+    depth: usize,
+    guard: &mut DepthGuard,
+    out: &mut Vec<ExtractedSymbol>,
+) -> Vec<String> {
+    let segments = node
+        .child_by_field_name("name")
+        .map(|name| dotted_segments(name, source))
+        .unwrap_or_default();
+    let body = node.child_by_field_name("body");
+
+    let mut path = scope.to_vec();
+    if let Some((name, outer)) = segments.split_last() {
+        path.extend(outer.iter().cloned());
+        let mut symbol = build_symbol(name.clone(), SymbolKind::Module, node, source, &path);
+        symbol.signature = signature_before(node, body, source);
+        out.push(symbol);
+        path.push(name.clone());
+    }
+    if let Some(body) = body {
+        collect_body(body, source, &path, depth + 1, guard, out);
+    }
+    path
+}
+
+fn type_symbols(
+    node: tree_sitter::Node,
+    source: &str,
+    scope: &[String],
+    depth: usize,
+    guard: &mut DepthGuard,
+    out: &mut Vec<ExtractedSymbol>,
+) {
+    let Some(name) = node.child_by_field_name("name") else {
+        return;
+    };
+    let name = node_text(name, source).to_string();
+    let kind = match node.kind() {
+        "struct_declaration" => SymbolKind::Struct,
+        "interface_declaration" => SymbolKind::Interface,
+        "enum_declaration" => SymbolKind::Enum,
+        "record_declaration" if has_token(node, "struct") => SymbolKind::Struct,
+        _ => SymbolKind::Class,
+    };
+    let body = node.child_by_field_name("body");
+
+    let symbol = build_symbol(name.clone(), kind, node, source, scope);
+    let merged = has_modifier(node, source, "partial")
+        && out
+            .iter()
+            .any(|s| s.kind == kind && s.qualified_name == symbol.qualified_name);
+    if !merged {
+        let mut symbol = symbol;
+        symbol.signature = signature_before(node, body, source);
+        out.push(symbol);
+    }
+
+    let mut path = scope.to_vec();
+    path.push(name);
+    if node.kind() == "record_declaration"
+        && let Some(parameters) = named_child_of_kind(node, &["parameter_list"])
+    {
+        record_properties(parameters, source, &path, out);
+    }
+    if let Some(body) = body {
+        collect_body(body, source, &path, depth + 1, guard, out);
+    }
+}
+
+fn method_symbol(
+    node: tree_sitter::Node,
+    source: &str,
+    scope: &[String],
+) -> Option<ExtractedSymbol> {
+    let name = node_text(node.child_by_field_name("name")?, source).to_string();
+    let mut symbol = build_symbol(name, SymbolKind::Method, node, source, scope);
+    symbol.signature = signature_before(node, node.child_by_field_name("body"), source);
+    Some(symbol)
+}
+
+fn property_symbol(
+    node: tree_sitter::Node,
+    source: &str,
+    scope: &[String],
+) -> Option<ExtractedSymbol> {
+    let name = node_text(node.child_by_field_name("name")?, source).to_string();
+    let end = node
+        .child_by_field_name("accessors")
+        .or_else(|| node.child_by_field_name("value"));
+    let mut symbol = build_symbol(name, SymbolKind::Field, node, source, scope);
+    symbol.signature = signature_before(node, end, source);
+    Some(symbol)
+}
+
+/// `private int _total, _count;` declares one field per declarator.
+fn field_symbols(
+    node: tree_sitter::Node,
+    source: &str,
+    scope: &[String],
+    out: &mut Vec<ExtractedSymbol>,
+) {
+    let kind = if has_modifier(node, source, "const") {
+        SymbolKind::Constant
+    } else {
+        SymbolKind::Field
+    };
+    let Some(declaration) = named_child_of_kind(node, &["variable_declaration"]) else {
+        return;
+    };
+    let signature = signature_before(node, None, source);
+    let mut cursor = declaration.walk();
+    for declarator in declaration.named_children(&mut cursor) {
+        if declarator.kind() != "variable_declarator" {
+            continue;
+        }
+        let Some(name) = declarator.child_by_field_name("name") else {
+            continue;
+        };
+        let name = node_text(name, source).to_string();
+        let mut symbol = build_symbol(name, kind, node, source, scope);
+        symbol.signature = signature.clone();
+        out.push(symbol);
+    }
+}
+
+/// `record Money(decimal Amount, string Currency)` declares public properties.
+fn record_properties(
+    parameters: tree_sitter::Node,
+    source: &str,
+    scope: &[String],
+    out: &mut Vec<ExtractedSymbol>,
+) {
+    let mut cursor = parameters.walk();
+    for parameter in parameters.named_children(&mut cursor) {
+        if parameter.kind() != "parameter" {
+            continue;
+        }
+        let Some(name) = parameter.child_by_field_name("name") else {
+            continue;
+        };
+        let name = node_text(name, source).to_string();
+        let mut symbol = build_symbol(name, SymbolKind::Field, parameter, source, scope);
+        symbol.signature = Some(collapse_whitespace(node_text(parameter, source)));
+        symbol.visibility = Some("public".to_string());
+        out.push(symbol);
+    }
+}
+
+fn build_symbol(
+    name: String,
+    kind: SymbolKind,
+    node: tree_sitter::Node,
+    source: &str,
+    scope: &[String],
+) -> ExtractedSymbol {
+    let range = node.byte_range();
+    let qualified_name = scope
+        .iter()
+        .cloned()
+        .chain(std::iter::once(name.clone()))
+        .collect::<Vec<_>>()
+        .join(".");
+    ExtractedSymbol {
+        qualified_name,
+        name,
+        kind,
+        language: "csharp".to_string(),
+        signature: None,
+        line_start: node.start_position().row as u32 + 1,
+        line_end: node.end_position().row as u32 + 1,
+        byte_start: range.start as u32,
+        byte_end: range.end as u32,
+        visibility: visibility(node, source),
+        parent_name: scope.last().cloned(),
+        body: source.get(range).map(String::from),
+        attributes: attributes(node, source),
+        doc_comment: None,
+        parsed_signature: None,
+    }
+}
+
+/// Declaration text up to (not including) `end`, e.g. the type or method
+/// body; a trailing `;` is dropped.
+fn signature_before(
+    node: tree_sitter::Node,
+    end: Option<tree_sitter::Node>,
+    source: &str,
+) -> Option<String> {
+    let end = end.map_or(node.end_byte(), |n| n.start_byte());
+    let text = source.get(node.start_byte()..end)?;
+    let signature = collapse_whitespace(text.trim_end().trim_end_matches(';'));
+    (!signature.is_empty()).then_some(signature)
+}
+
+/// `Billing.Core` -> `["Billing", "Core"]`.
+fn dotted_segments(name: tree_sitter::Node, source: &str) -> Vec<String> {
+    node_text(name, source)
+        .split('.')
+        .map(|segment| segment.trim().to_string())
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
+/// Access modifiers in source order, e.g. `protected internal`.
+fn visibility(node: tree_sitter::Node, source: &str) -> Option<String> {
+    let access = modifiers(node, source)
+        .into_iter()
+        .filter(|modifier| {
+            matches!(
+                *modifier,
+                "public" | "private" | "protected" | "internal" | "file"
+            )
+        })
+        .collect::<Vec<_>>();
+    (!access.is_empty()).then(|| access.join(" "))
+}
+
+/// Attribute text without the brackets, one entry per attribute, e.g.
+/// `HttpGet("/users")`.
+fn attributes(node: tree_sitter::Node, source: &str) -> Vec<String> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .filter(|child| child.kind() == "attribute_list")
+        .flat_map(|list| {
+            let mut cursor = list.walk();
+            list.named_children(&mut cursor)
+                .filter(|attribute| attribute.kind() == "attribute")
+                .map(|attribute| collapse_whitespace(node_text(attribute, source)))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn has_modifier(node: tree_sitter::Node, source: &str, keyword: &str) -> bool {
+    modifiers(node, source).contains(&keyword)
+}
+
+fn modifiers<'a>(node: tree_sitter::Node, source: &'a str) -> Vec<&'a str> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .filter(|child| child.kind() == "modifier")
+        .map(|modifier| node_text(modifier, source))
+        .collect()
+}
+
+/// Whether `node` has an anonymous keyword child such as `struct`.
+fn has_token(node: tree_sitter::Node, token: &str) -> bool {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .any(|child| !child.is_named() && child.kind() == token)
+}
+
+fn named_child_of_kind<'t>(
+    node: tree_sitter::Node<'t>,
+    kinds: &[&str],
+) -> Option<tree_sitter::Node<'t>> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .find(|child| kinds.contains(&child.kind()))
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn node_text<'a>(node: tree_sitter::Node, source: &'a str) -> &'a str {
+    source.get(node.byte_range()).unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_file;
+
+    const SOURCE: &str = r#"
+using System;
+
+namespace Billing.Core
+{
+    [Serializable]
+    public partial class Invoice<T> : Base, IPayable where T : class
+    {
+        private readonly int _total, _count;
+        public const int MaxLines = 10;
+        public string Id { get; set; }
+        public int Count => 3;
+        public event EventHandler Paid;
+
+        public Invoice(int total) { _total = total; }
+
+        public static bool Pay(int amount, ref string note)
+        {
+            return true;
+        }
+
+        protected internal int Twice(int x) => x * 2;
+
+        internal class Line { void Sum() {} }
+    }
+
+    public partial class Invoice<T>
+    {
+        private void Extra() {}
+    }
+
+    public interface IPayable { bool Pay(int amount); }
+    public struct Point { public int X; }
+    public record Money(decimal Amount, string Currency);
+    public record struct Pair(int A, int B);
+    public enum Color { Red, Green = 2 }
+}
+"#;
+
+    const FILE_SCOPED: &str = r#"
+namespace Billing.Reports;
+
+class Summary
+{
+    void Print() {}
+}
+"#;
+
+    fn symbols(source: &str) -> Vec<ExtractedSymbol> {
+        let tree = parse_file(source, "csharp").expect("parse csharp");
+        extract_symbols(&tree, source, &mut DepthGuard::default())
+    }
+
+    fn find<'a>(symbols: &'a [ExtractedSymbol], qualified_name: &str) -> &'a ExtractedSymbol {
+        symbols
+            .iter()
+            .find(|s| s.qualified_name == qualified_name)
+            .unwrap_or_else(|| panic!("expected symbol {qualified_name}"))
+    }
+
+    #[test]
+    fn declarations_use_namespace_and_nesting_in_qualified_names() {
+        let symbols = symbols(SOURCE);
+
+        let namespace = find(&symbols, "Billing.Core");
+        assert_eq!(namespace.kind, SymbolKind::Module);
+        assert_eq!(namespace.name, "Core");
+
+        let invoice = find(&symbols, "Billing.Core.Invoice");
+        assert_eq!(invoice.kind, SymbolKind::Class);
+        assert_eq!(invoice.parent_name.as_deref(), Some("Core"));
+        assert_eq!(invoice.visibility.as_deref(), Some("public"));
+        assert_eq!(invoice.attributes, vec!["Serializable".to_string()]);
+        assert_eq!(
+            invoice.signature.as_deref(),
+            Some("[Serializable] public partial class Invoice<T> : Base, IPayable where T : class")
+        );
+
+        let pay = find(&symbols, "Billing.Core.Invoice.Pay");
+        assert_eq!(pay.kind, SymbolKind::Method);
+        assert_eq!(pay.parent_name.as_deref(), Some("Invoice"));
+        assert_eq!(
+            pay.signature.as_deref(),
+            Some("public static bool Pay(int amount, ref string note)")
+        );
+        assert_eq!(
+            find(&symbols, "Billing.Core.Invoice.Twice")
+                .signature
+                .as_deref(),
+            Some("protected internal int Twice(int x)")
+        );
+        assert!(
+            symbols
+                .iter()
+                .any(|s| s.qualified_name == "Billing.Core.Invoice.Invoice"
+                    && s.kind == SymbolKind::Method),
+            "constructors are methods"
+        );
+        assert_eq!(
+            find(&symbols, "Billing.Core.Invoice.Line.Sum").kind,
+            SymbolKind::Method
+        );
+
+        assert_eq!(
+            find(&symbols, "Billing.Core.IPayable").kind,
+            SymbolKind::Interface
+        );
+        assert_eq!(
+            find(&symbols, "Billing.Core.IPayable.Pay").kind,
+            SymbolKind::Method
+        );
+        assert_eq!(
+            find(&symbols, "Billing.Core.Point").kind,
+            SymbolKind::Struct
+        );
+        assert_eq!(find(&symbols, "Billing.Core.Money").kind, SymbolKind::Class);
+        assert_eq!(find(&symbols, "Billing.Core.Pair").kind, SymbolKind::Struct);
+        assert_eq!(find(&symbols, "Billing.Core.Color").kind, SymbolKind::Enum);
+        assert_eq!(
+            find(&symbols, "Billing.Core.Color.Green").kind,
+            SymbolKind::Constant
+        );
+        assert!(symbols.iter().all(|s| s.language == "csharp"));
+    }
+
+    #[test]
+    fn members_map_to_fields_constants_and_record_properties() {
+        let symbols = symbols(SOURCE);
+
+        let total = find(&symbols, "Billing.Core.Invoice._total");
+        assert_eq!(total.kind, SymbolKind::Field);
+        assert_eq!(total.visibility.as_deref(), Some("private"));
+        assert_eq!(
+            total.signature.as_deref(),
+            Some("private readonly int _total, _count")
+        );
+        assert_eq!(
+            find(&symbols, "Billing.Core.Invoice._count").kind,
+            SymbolKind::Field
+        );
+        assert_eq!(
+            find(&symbols, "Billing.Core.Invoice.MaxLines").kind,
+            SymbolKind::Constant
+        );
+
+        let id = find(&symbols, "Billing.Core.Invoice.Id");
+        assert_eq!(id.kind, SymbolKind::Field);
+        assert_eq!(id.signature.as_deref(), Some("public string Id"));
+        assert_eq!(
+            find(&symbols, "Billing.Core.Invoice.Count")
+                .signature
+                .as_deref(),
+            Some("public int Count")
+        );
+        assert_eq!(
+            find(&symbols, "Billing.Core.Invoice.Paid").kind,
+            SymbolKind::Field
+        );
+
+        let amount = find(&symbols, "Billing.Core.Money.Amount");
+        assert_eq!(amount.kind, SymbolKind::Field);
+        assert_eq!(amount.signature.as_deref(), Some("decimal Amount"));
+        assert_eq!(amount.visibility.as_deref(), Some("public"));
+    }
+
+    #[test]
+    fn partial_type_parts_in_one_file_share_a_single_type_symbol() {
+        let symbols = symbols(SOURCE);
+
+        let parts = symbols
+            .iter()
+            .filter(|s| s.qualified_name == "Billing.Core.Invoice")
+            .count();
+        assert_eq!(parts, 1);
+        let extra = find(&symbols, "Billing.Core.Invoice.Extra");
+        assert_eq!(extra.parent_name.as_deref(), Some("Invoice"));
+        assert_eq!(extra.visibility.as_deref(), Some("private"));
+    }
+
+    #[test]
+    fn file_scoped_namespace_qualifies_following_declarations() {
+        let symbols = symbols(FILE_SCOPED);
+
+        assert_eq!(find(&symbols, "Billing.Reports").kind, SymbolKind::Module);
+        let summary = find(&symbols, "Billing.Reports.Summary");
+        assert_eq!(summary.kind, SymbolKind::Class);
+        assert_eq!(summary.visibility, None);
+        assert_eq!(
+            find(&symbols, "Billing.Reports.Summary.Print").kind,
+            SymbolKind::Method
+        );
+    }
+}
@@ -1,6 +1,7 @@
-use super::ExtractedCallSite;
-use super::text::node_text_owned;
+use super::text::{collapsed_node_text, node_text_owned};
+use super::{DepthGuard, ExtractedCallSite, ExtractedSymbol};
 use crate::import_extract::RawImport;
+use cruxe_core::types::{ParsedSignature, SignatureParam, SymbolKind};
 
 /// Extract Go imports from single and grouped import declarations.
 pub fn extract_imports(
@@ -81,14 +82,230 @@ fn extract_go_path(fragment: &str) -> Option<String> {
     Some(rest[..end].to_string())
 }
 
+/// Extract the fields of named struct types as `Field` symbols parented to the struct.
+///
+/// `A, B int` yields one symbol per name, each with `A, B int` as its signature.
+/// Embedded fields (`*sync.Mutex`, `io.Reader`) take the embedded type name as
+/// the field name. Fields of anonymous nested struct types are not descended into.
+pub fn extract_struct_fields(
+    tree: &tree_sitter::Tree,
+    source: &str,
+    guard: &mut DepthGuard,
+) -> Vec<ExtractedSymbol> {
+    let mut fields = Vec::new();
+    collect_struct_fields(tree.root_node(), source, 0, guard, &mut fields);
+    fields
+}
+
+fn collect_struct_fields(
+    node: tree_sitter::Node,
+    source: &str,
+    depth: usize,
+    guard: &mut DepthGuard,
+    out: &mut Vec<ExtractedSymbol>,
+) {
+    if !guard.allows(depth) {
+        return;
+    }
+    if node.kind() == "type_spec"
+        && let Some(name_node) = node.child_by_field_name("name")
+        && let Some(struct_type) = node
+            .child_by_field_name("type")
+            .filter(|ty| ty.kind() == "struct_type")
+    {
+        let struct_name = node_text_owned(name_node, source);
+        let mut cursor = struct_type.walk();
+        for list in struct_type.named_children(&mut cursor) {
+            if list.kind() != "field_declaration_list" {
+                continue;
+            }
+            let mut list_cursor = list.walk();
+            for declaration in list.named_children(&mut list_cursor) {
+                if declaration.kind() == "field_declaration" {
+                    push_field_symbols(declaration, &struct_name, source, out);
+                }
+            }
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_struct_fields(child, source, depth + 1, guard, out);
+    }
+}
+
+fn push_field_symbols(
+    declaration: tree_sitter::Node,
+    struct_name: &str,
+    source: &str,
+    out: &mut Vec<ExtractedSymbol>,
+) {
+    let Some(type_node) = declaration.child_by_field_name("type") else {
+        return;
+    };
+    let type_text = node_text_owned(type_node, source);
+    let mut cursor = declaration.walk();
+    let mut names: Vec<String> = declaration
+        .children_by_field_name("name", &mut cursor)
+        .map(|name| node_text_owned(name, source))
+        .collect();
+    let signature = if names.is_empty() {
+        let Some(embedded) = embedded_type_name(type_node, source) else {
+            return;
+        };
+        names.push(embedded);
+        // Include the `*` of pointer embeddings, which precedes the type node.
+        source
+            .get(declaration.start_byte()..type_node.end_byte())
+            .unwrap_or(&type_text)
+            .to_string()
+    } else {
+        format!("{} {}", names.join(", "), type_text)
+    };
+    let signature = signature.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    let range = declaration.byte_range();
+    for name in names {
+        let visibility = Some(visibility_for_name(&name).to_string());
+        out.push(ExtractedSymbol {
+            qualified_name: format!("{struct_name}.{name}"),
+            name,
+            kind: SymbolKind::Field,
+            language: "go".to_string(),
+            signature: Some(signature.clone()),
+            line_start: declaration.start_position().row as u32 + 1,
+            line_end: declaration.end_position().row as u32 + 1,
+            byte_start: range.start as u32,
+            byte_end: range.end as u32,
+            visibility,
+            parent_name: Some(struct_name.to_string()),
+            body: source.get(range.clone()).map(String::from),
+            attributes: Vec::new(),
+            doc_comment: None,
+            parsed_signature: None,
+        });
+    }
+}
+
+/// Go exports identifiers that start with an upper-case letter.
+pub fn visibility_for_name(name: &str) -> &'static str {
+    if name.chars().next().is_some_and(char::is_uppercase) {
+        "public"
+    } else {
+        "private"
+    }
+}
+
+/// Split a function, method or interface method into type parameters,
+/// receiver, parameters and result. Returns `None` for other nodes.
+///
+/// `a, b int` yields one parameter per name; a multi-value result keeps its
+/// parenthesized text, e.g. `(int, error)`.
+pub fn parse_signature(node: tree_sitter::Node, source: &str) -> Option<ParsedSignature> {
+    if !matches!(
+        node.kind(),
+        "function_declaration" | "method_declaration" | "method_elem" | "method_spec"
+    ) {
+        return None;
+    }
+    let generics = node
+        .child_by_field_name("type_parameters")
+        .map(|list| {
+            let mut cursor = list.walk();
+            list.named_children(&mut cursor)
+                .filter(|child| !child.is_extra())
+                .map(|child| collapsed_node_text(child, source))
+                .collect()
+        })
+        .unwrap_or_default();
+    let receiver = node
+        .child_by_field_name("receiver")
+        .and_then(|list| parameter_list(list, source).into_iter().next());
+    let params = node
+        .child_by_field_name("parameters")
+        .map(|list| parameter_list(list, source))
+        .unwrap_or_default();
+    let return_type = node
+        .child_by_field_name("result")
+        .map(|result| collapsed_node_text(result, source));
+    Some(ParsedSignature {
+        generics,
+        receiver,
+        params,
+        return_type,
+    })
+}
+
+fn parameter_list(list: tree_sitter::Node, source: &str) -> Vec<SignatureParam> {
+    let mut params = Vec::new();
+    let mut cursor = list.walk();
+    for declaration in list.named_children(&mut cursor) {
+        let variadic = match declaration.kind() {
+            "parameter_declaration" => false,
+            "variadic_parameter_declaration" => true,
+            _ => continue,
+        };
+        let r#type = declaration
+            .child_by_field_name("type")
+            .map(|ty| collapsed_node_text(ty, source))
+            .map(|ty| if variadic { format!("...{ty}") } else { ty });
+        let mut name_cursor = declaration.walk();
+        let names: Vec<String> = declaration
+            .children_by_field_name("name", &mut name_cursor)
+            .map(|name| node_text_owned(name, source))
+            .collect();
+        if names.is_empty() {
+            params.push(SignatureParam { name: None, r#type });
+        } else {
+            params.extend(names.into_iter().map(|name| SignatureParam {
+                name: Some(name),
+                r#type: r#type.clone(),
+            }));
+        }
+    }
+    params
+}
+
+/// Name of an embedded field: the last identifier of its (possibly pointer,
+/// qualified or generic) type, e.g. `Mutex` for `*sync.Mutex`.
+fn embedded_type_name(type_node: tree_sitter::Node, source: &str) -> Option<String> {
+    match type_node.kind() {
+        "type_identifier" => Some(node_text_owned(type_node, source)),
+        "qualified_type" => type_node
+            .child_by_field_name("name")
+            .map(|name| node_text_owned(name, source)),
+        "pointer_type" | "generic_type" => {
+            let mut cursor = type_node.walk();
+            let inner = type_node
+                .named_children(&mut cursor)
+                .find(|child| child.kind() != "type_arguments")?;
+            embedded_type_name(inner, source)
+        }
+        _ => None,
+    }
+}
+
 /// Extract Go call-sites using `call_expression` nodes.
-pub fn extract_call_sites(tree: &tree_sitter::Tree, source: &str) -> Vec<ExtractedCallSite> {
+pub fn extract_call_sites(
+    tree: &tree_sitter::Tree,
+    source: &str,
+    guard: &mut DepthGuard,
+) -> Vec<ExtractedCallSite> {
     let mut calls = Vec::new();
-    collect_call_sites(tree.root_node(), source, &mut calls);
+    collect_call_sites(tree.root_node(), source, 0, guard, &mut calls);
     calls
 }
 
-fn collect_call_sites(node: tree_sitter::Node, source: &str, calls: &mut Vec<ExtractedCallSite>) {
+fn collect_call_sites(
+    node: tree_sitter::Node,
+    source: &str,
+    depth: usize,
+    guard: &mut DepthGuard,
+    calls: &mut Vec<ExtractedCallSite>,
+) {
+    if !guard.allows(depth) {
+        return;
+    }
     if node.kind() == "call_expression"
         && let Some(call) = parse_call_node(node, source)
     {
@@ -96,7 +313,7 @@ fn collect_call_sites(node: tree_sitter::Node, source: &str, calls: &mut Vec<Ext
     }
     for idx in 0..node.child_count() {
         if let Some(child) = node.child(idx) {
-            collect_call_sites(child, source, calls);
+            collect_call_sites(child, source, depth + 1, guard, calls);
         }
     }
 }
@@ -128,7 +345,9 @@ fn normalize_call_target(prefix: &str) -> Option<String> {
 #[cfg(test)]
 mod tests {
     use super::extract_imports;
+    use crate::languages::extract_symbols;
     use crate::parser;
+    use cruxe_core::types::{ParsedSignature, SignatureParam, SymbolKind};
     use std::collections::HashSet;
 
     #[test]
@@ -156,4 +375,115 @@ import (
         assert!(target_names.contains("auth"));
         assert!(target_names.contains("cfg"));
     }
+
+    #[test]
+    fn extract_symbols_emits_struct_fields_as_children() {
+        let source = r#"
+package demo
+
+type Server struct {
+    Addr, Host string
+    *sync.Mutex
+    io.Reader
+    Handler `json:"handler"`
+    opts struct {
+        Verbose bool
+    }
+}
+
+type ID string
+"#;
+        let tree = parser::parse_file(source, "go").unwrap();
+        let symbols = extract_symbols(&tree, source, "go");
+        let fields: Vec<_> = symbols
+            .iter()
+            .filter(|symbol| symbol.kind == SymbolKind::Field)
+            .collect();
+        let summary: Vec<(&str, &str, Option<&str>)> = fields
+            .iter()
+            .map(|field| {
+                (
+                    field.name.as_str(),
+                    field.qualified_name.as_str(),
+                    field.signature.as_deref(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("Addr", "Server.Addr", Some("Addr, Host string")),
+                ("Host", "Server.Host", Some("Addr, Host string")),
+                ("Mutex", "Server.Mutex", Some("*sync.Mutex")),
+                ("Reader", "Server.Reader", Some("io.Reader")),
+                ("Handler", "Server.Handler", Some("Handler")),
+                ("opts", "Server.opts", Some("opts struct { Verbose bool }")),
+            ]
+        );
+        assert!(
+            fields
+                .iter()
+                .all(|field| field.parent_name.as_deref() == Some("Server"))
+        );
+        let server = symbols
+            .iter()
+            .find(|symbol| symbol.name == "Server")
+            .expect("Server symbol");
+        assert_eq!(server.kind, SymbolKind::Struct);
+    }
+
+    #[test]
+    fn extract_symbols_parses_function_and_method_signatures() {
+        let source = r#"
+package demo
+
+func Map[K comparable, V any](m map[K]V, fn func(V) V) map[K]V { return m }
+
+func (s *Server) Serve(addr, host string, opts ...Option) (int, error) { return 0, nil }
+
+func Reset(int) {}
+"#;
+        let tree = parser::parse_file(source, "go").unwrap();
+        let symbols = extract_symbols(&tree, source, "go");
+        let parsed_of = |name: &str| {
+            symbols
+                .iter()
+                .find(|symbol| symbol.name == name)
+                .unwrap_or_else(|| panic!("missing symbol {name}"))
+                .parsed_signature
+                .clone()
+                .unwrap_or_else(|| panic!("missing parsed signature for {name}"))
+        };
+        let param = |name: Option<&str>, ty: &str| SignatureParam {
+            name: name.map(str::to_string),
+            r#type: Some(ty.to_string()),
+        };
+
+        assert_eq!(
+            parsed_of("Map"),
+            ParsedSignature {
+                generics: vec!["K comparable".to_string(), "V any".to_string()],
+                receiver: None,
+                params: vec![param(Some("m"), "map[K]V"), param(Some("fn"), "func(V) V"),],
+                return_type: Some("map[K]V".to_string()),
+            }
+        );
+        assert_eq!(
+            parsed_of("Serve"),
+            ParsedSignature {
+                generics: Vec::new(),
+                receiver: Some(param(Some("s"), "*Server")),
+                params: vec![
+                    param(Some("addr"), "string"),
+                    param(Some("host"), "string"),
+                    param(Some("opts"), "...Option"),
+                ],
+                return_type: Some("(int, error)".to_string()),
+            }
+        );
+        let reset = parsed_of("Reset");
+        assert_eq!(reset.params, vec![param(None, "int")]);
+        assert_eq!(reset.return_type, None);
+    }
 }
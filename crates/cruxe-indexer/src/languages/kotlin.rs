@@ -0,0 +1,534 @@
+use super::{DepthGuard, ExtractedSymbol};
+use cruxe_core::types::SymbolKind;
+
+/// Extract Kotlin symbols by walking declarations of the tree-sitter-kotlin-ng
+/// grammar.
+///
+/// Emits classes, interfaces, enums (and their entries), `object` and
+/// `companion object` declarations, functions, `val`/`var` properties
+/// (including `val`/`var` primary-constructor parameters) and type aliases.
+/// `qualified_name` joins the `package` header and the lexical nesting with
+/// `.`, so `package billing; class Invoice { companion object { fun create() } }`
+/// yields `billing.Invoice.Companion.create`. Top-level functions are
+/// [`SymbolKind::Function`], members are [`SymbolKind::Method`]; extension
+/// functions (`fun String.shout()`) record the receiver type in `parent_name`.
+pub fn extract_symbols(
+    tree: &tree_sitter::Tree,
+    source: &str,
+    guard: &mut DepthGuard,
+) -> Vec<ExtractedSymbol> {
+    let root = tree.root_node();
+    let package = package_name(root, source);
+    let context = Context {
+        source,
+        package: package.as_deref(),
+    };
+    let mut symbols = Vec::new();
+    collect_body(root, &context, &[], 0, guard, &mut symbols);
+    super::sort_symbols(&mut symbols);
+    symbols
+}
+
+/// File-level inputs shared by every symbol in one source file.
+struct Context<'a> {
+    source: &'a str,
+    package: Option<&'a str>,
+}
+
+fn collect_body(
+    node: tree_sitter::Node,
+    cx: &Context,
+    scope: &[String],
+    depth: usize,
+    guard: &mut DepthGuard,
+    out: &mut Vec<ExtractedSymbol>,
+) {
+    if !guard.allows(depth) {
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        match child.kind() {
+            "class_declaration" | "object_declaration" | "companion_object" => {
+                type_symbols(child, cx, scope, depth, guard, out)
+            }
+            "function_declaration" => out.extend(function_symbol(child, cx, scope)),
+            "property_declaration" => property_symbols(child, cx, scope, out),
+            "type_alias" => out.extend(type_alias_symbol(child, cx, scope)),
+            "enum_entry" => out.extend(enum_entry_symbol(child, cx, scope)),
+            _ => {}
+        }
+    }
+}
+
+fn type_symbols(
+    node: tree_sitter::Node,
+    cx: &Context,
+    scope: &[String],
+    depth: usize,
+    guard: &mut DepthGuard,
+    out: &mut Vec<ExtractedSymbol>,
+) {
+    let name = match node.child_by_field_name("name") {
+        Some(name) => node_text(name, cx.source).to_string(),
+        // `companion object { ... }` without a name is `Companion`.
+        None if node.kind() == "companion_object" => "Companion".to_string(),
+        None => return,
+    };
+    let kind = match node.kind() {
+        "class_declaration" if has_token(node, "interface") => SymbolKind::Interface,
+        "class_declaration" if has_modifier(node, cx.source, "enum") => SymbolKind::Enum,
+        _ => SymbolKind::Class,
+    };
+    let body = named_child_of_kind(node, &["class_body", "enum_class_body"]);
+
+    let mut symbol = build_symbol(name.clone(), kind, node, cx, scope);
+    symbol.signature = signature_before(node, body, cx.source);
+    out.push(symbol);
+
+    let mut path = scope.to_vec();
+    path.push(name);
+    if let Some(parameters) = named_child_of_kind(node, &["primary_constructor"])
+        .and_then(|ctor| named_child_of_kind(ctor, &["class_parameters"]))
+    {
+        constructor_properties(parameters, cx, &path, out);
+    }
+    if let Some(body) = body {
+        collect_body(body, cx, &path, depth + 1, guard, out);
+    }
+}
+
+fn function_symbol(
+    node: tree_sitter::Node,
+    cx: &Context,
+    scope: &[String],
+) -> Option<ExtractedSymbol> {
+    let name_node = node.child_by_field_name("name")?;
+    let name = node_text(name_node, cx.source).to_string();
+    let kind = if scope.is_empty() {
+        SymbolKind::Function
+    } else {
+        SymbolKind::Method
+    };
+
+    let mut symbol = build_symbol(name, kind, node, cx, scope);
+    let body = named_child_of_kind(node, &["function_body"]);
+    symbol.signature = signature_before(node, body, cx.source);
+    if let Some(receiver) = receiver_type(node, name_node, cx.source) {
+        symbol.parent_name = Some(receiver);
+    }
+    Some(symbol)
+}
+
+fn property_symbols(
+    node: tree_sitter::Node,
+    cx: &Context,
+    scope: &[String],
+    out: &mut Vec<ExtractedSymbol>,
+) {
+    let kind = if has_modifier(node, cx.source, "const") {
+        SymbolKind::Constant
+    } else if scope.is_empty() {
+        SymbolKind::Variable
+    } else {
+        SymbolKind::Field
+    };
+    let signature = first_line(node, cx.source);
+
+    let mut declarations = Vec::new();
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        match child.kind() {
+            "variable_declaration" => declarations.push(child),
+            "multi_variable_declaration" => {
+                let mut inner = child.walk();
+                declarations.extend(
+                    child
+                        .named_children(&mut inner)
+                        .filter(|n| n.kind() == "variable_declaration"),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    for declaration in declarations {
+        let Some(name) = named_child_of_kind(declaration, &["identifier"]) else {
+            continue;
+        };
+        let name = node_text(name, cx.source).to_string();
+        let mut symbol = build_symbol(name, kind, node, cx, scope);
+        symbol.signature = signature.clone();
+        out.push(symbol);
+    }
+}
+
+/// `class Invoice(val id: Long, private var total: Int)` declares properties.
+fn constructor_properties(
+    parameters: tree_sitter::Node,
+    cx: &Context,
+    scope: &[String],
+    out: &mut Vec<ExtractedSymbol>,
+) {
+    let mut cursor = parameters.walk();
+    for parameter in parameters.named_children(&mut cursor) {
+        if parameter.kind() != "class_parameter"
+            || !(has_token(parameter, "val") || has_token(parameter, "var"))
+        {
+            continue;
+        }
+        let Some(name) = named_child_of_kind(parameter, &["identifier"]) else {
+            continue;
+        };
+        let name = node_text(name, cx.source).to_string();
+        let mut symbol = build_symbol(name, SymbolKind::Field, parameter, cx, scope);
+        symbol.signature = Some(collapse_whitespace(node_text(parameter, cx.source)));
+        out.push(symbol);
+    }
+}
+
+fn type_alias_symbol(
+    node: tree_sitter::Node,
+    cx: &Context,
+    scope: &[String],
+) -> Option<ExtractedSymbol> {
+    let name = node_text(node.child_by_field_name("type")?, cx.source).to_string();
+    let mut symbol = build_symbol(name, SymbolKind::TypeAlias, node, cx, scope);
+    symbol.signature = first_line(node, cx.source);
+    Some(symbol)
+}
+
+fn enum_entry_symbol(
+    node: tree_sitter::Node,
+    cx: &Context,
+    scope: &[String],
+) -> Option<ExtractedSymbol> {
+    let name = node_text(named_child_of_kind(node, &["identifier"])?, cx.source).to_string();
+    Some(build_symbol(name, SymbolKind::Constant, node, cx, scope))
+}
+
+fn build_symbol(
+    name: String,
+    kind: SymbolKind,
+    node: tree_sitter::Node,
+    cx: &Context,
+    scope: &[String],
+) -> ExtractedSymbol {
+    let range = node.byte_range();
+    let qualified_name = cx
+        .package
+        .into_iter()
+        .map(str::to_string)
+        .chain(scope.iter().cloned())
+        .chain(std::iter::once(name.clone()))
+        .collect::<Vec<_>>()
+        .join(".");
+    ExtractedSymbol {
+        qualified_name,
+        name,
+        kind,
+        language: "kotlin".to_string(),
+        signature: None,
+        line_start: node.start_position().row as u32 + 1,
+        line_end: node.end_position().row as u32 + 1,
+        byte_start: range.start as u32,
+        byte_end: range.end as u32,
+        visibility: visibility(node, cx.source),
+        parent_name: scope.last().cloned(),
+        body: cx.source.get(range).map(String::from),
+        attributes: annotations(node, cx.source),
+        doc_comment: None,
+        parsed_signature: None,
+    }
+}
+
+/// Declaration text up to (not including) `end`, e.g. the class or function
+/// body; a trailing expression-body `=` is dropped.
+fn signature_before(
+    node: tree_sitter::Node,
+    end: Option<tree_sitter::Node>,
+    source: &str,
+) -> Option<String> {
+    let end = end.map_or(node.end_byte(), |n| n.start_byte());
+    let text = source.get(node.start_byte()..end)?;
+    let signature = collapse_whitespace(text.trim_end().trim_end_matches('='));
+    (!signature.is_empty()).then_some(signature)
+}
+
+fn first_line(node: tree_sitter::Node, source: &str) -> Option<String> {
+    node_text(node, source)
+        .lines()
+        .next()
+        .map(|line| line.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+fn package_name(root: tree_sitter::Node, source: &str) -> Option<String> {
+    let header = named_child_of_kind(root, &["package_header"])?;
+    let name = named_child_of_kind(header, &["qualified_identifier"])?;
+    let name = collapse_whitespace(node_text(name, source)).replace(' ', "");
+    (!name.is_empty()).then_some(name)
+}
+
+/// Receiver of an extension function: the type written before the name,
+/// without type arguments or nullability (`List<T>.second` -> `List`).
+fn receiver_type(
+    node: tree_sitter::Node,
+    name_node: tree_sitter::Node,
+    source: &str,
+) -> Option<String> {
+    let mut cursor = node.walk();
+    let receiver = node
+        .named_children(&mut cursor)
+        .take_while(|child| child.id() != name_node.id())
+        .filter(|child| !matches!(child.kind(), "modifiers" | "type_parameters"))
+        .last()?;
+    let user_type = if receiver.kind() == "user_type" {
+        receiver
+    } else {
+        named_child_of_kind(receiver, &["user_type"])?
+    };
+    let mut cursor = user_type.walk();
+    let segments = user_type
+        .named_children(&mut cursor)
+        .filter(|child| child.kind() == "identifier")
+        .map(|child| node_text(child, source))
+        .collect::<Vec<_>>();
+    (!segments.is_empty()).then(|| segments.join("."))
+}
+
+fn visibility(node: tree_sitter::Node, source: &str) -> Option<String> {
+    modifier_nodes(node)
+        .into_iter()
+        .find(|modifier| modifier.kind() == "visibility_modifier")
+        .map(|modifier| node_text(modifier, source).to_string())
+}
+
+/// Annotation text without the leading `@`, e.g. `Composable`.
+fn annotations(node: tree_sitter::Node, source: &str) -> Vec<String> {
+    modifier_nodes(node)
+        .into_iter()
+        .filter(|modifier| modifier.kind() == "annotation")
+        .map(|annotation| {
+            collapse_whitespace(node_text(annotation, source).trim_start_matches('@'))
+        })
+        .collect()
+}
+
+fn has_modifier(node: tree_sitter::Node, source: &str, keyword: &str) -> bool {
+    modifier_nodes(node)
+        .into_iter()
+        .any(|modifier| node_text(modifier, source) == keyword)
+}
+
+fn modifier_nodes(node: tree_sitter::Node) -> Vec<tree_sitter::Node> {
+    let Some(modifiers) = named_child_of_kind(node, &["modifiers"]) else {
+        return Vec::new();
+    };
+    let mut cursor = modifiers.walk();
+    modifiers.named_children(&mut cursor).collect()
+}
+
+/// Whether `node` has an anonymous keyword child such as `interface` or `val`.
+fn has_token(node: tree_sitter::Node, token: &str) -> bool {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .any(|child| !child.is_named() && child.kind() == token)
+}
+
+fn named_child_of_kind<'t>(
+    node: tree_sitter::Node<'t>,
+    kinds: &[&str],
+) -> Option<tree_sitter::Node<'t>> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .find(|child| kinds.contains(&child.kind()))
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn node_text<'a>(node: tree_sitter::Node, source: &'a str) -> &'a str {
+    source.get(node.byte_range()).unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_file;
+
+    const SOURCE: &str = r#"
+package com.example.billing
+
+import kotlin.math.max
+
+const val MAX_RETRIES = 3
+var counter = 0
+
+fun helper(x: Int): Int = x + 1
+
+fun String.shout(): String {
+    return uppercase()
+}
+
+fun <T> List<T>?.second(): T = this!![1]
+
+@Serializable
+private class Invoice(val id: Long, private var total: Int, note: String) : Base(), Payable {
+    private var paid = false
+
+    fun pay(amount: Int): Boolean {
+        return true
+    }
+
+    inner class Line {
+        fun sum() = 1
+    }
+
+    companion object {
+        fun create(): Invoice = Invoice(1, 2, "")
+    }
+}
+
+object Registry {
+    fun register() {}
+}
+
+interface Payable {
+    fun pay(amount: Int): Boolean
+}
+
+enum class Color {
+    RED,
+    GREEN;
+
+    fun hex() = ""
+}
+
+typealias Amount = Long
+"#;
+
+    fn symbols() -> Vec<ExtractedSymbol> {
+        let tree = parse_file(SOURCE, "kotlin").expect("parse kotlin");
+        extract_symbols(&tree, SOURCE, &mut DepthGuard::default())
+    }
+
+    fn find<'a>(symbols: &'a [ExtractedSymbol], qualified_name: &str) -> &'a ExtractedSymbol {
+        symbols
+            .iter()
+            .find(|s| s.qualified_name == qualified_name)
+            .unwrap_or_else(|| panic!("expected symbol {qualified_name}"))
+    }
+
+    #[test]
+    fn declarations_use_package_and_nesting_in_qualified_names() {
+        let symbols = symbols();
+
+        let invoice = find(&symbols, "com.example.billing.Invoice");
+        assert_eq!(invoice.kind, SymbolKind::Class);
+        assert_eq!(invoice.parent_name, None);
+        assert_eq!(invoice.visibility.as_deref(), Some("private"));
+        assert_eq!(invoice.attributes, vec!["Serializable".to_string()]);
+        assert_eq!(
+            invoice.signature.as_deref(),
+            Some(
+                "@Serializable private class Invoice(val id: Long, private var total: Int, note: String) : Base(), Payable"
+            )
+        );
+
+        let pay = find(&symbols, "com.example.billing.Invoice.pay");
+        assert_eq!(pay.kind, SymbolKind::Method);
+        assert_eq!(pay.parent_name.as_deref(), Some("Invoice"));
+        assert_eq!(
+            pay.signature.as_deref(),
+            Some("fun pay(amount: Int): Boolean")
+        );
+        assert_eq!(
+            find(&symbols, "com.example.billing.Invoice.Line.sum").kind,
+            SymbolKind::Method
+        );
+
+        let companion = find(&symbols, "com.example.billing.Invoice.Companion");
+        assert_eq!(companion.kind, SymbolKind::Class);
+        let create = find(&symbols, "com.example.billing.Invoice.Companion.create");
+        assert_eq!(create.kind, SymbolKind::Method);
+        assert_eq!(create.parent_name.as_deref(), Some("Companion"));
+
+        assert_eq!(
+            find(&symbols, "com.example.billing.Registry").kind,
+            SymbolKind::Class
+        );
+        assert_eq!(
+            find(&symbols, "com.example.billing.Payable").kind,
+            SymbolKind::Interface
+        );
+        assert_eq!(
+            find(&symbols, "com.example.billing.Payable.pay").kind,
+            SymbolKind::Method
+        );
+        assert_eq!(
+            find(&symbols, "com.example.billing.Color").kind,
+            SymbolKind::Enum
+        );
+        assert_eq!(
+            find(&symbols, "com.example.billing.Color.GREEN").kind,
+            SymbolKind::Constant
+        );
+        assert_eq!(
+            find(&symbols, "com.example.billing.Amount").kind,
+            SymbolKind::TypeAlias
+        );
+        assert!(symbols.iter().all(|s| s.language == "kotlin"));
+    }
+
+    #[test]
+    fn top_level_and_extension_functions_are_functions() {
+        let symbols = symbols();
+
+        let helper = find(&symbols, "com.example.billing.helper");
+        assert_eq!(helper.kind, SymbolKind::Function);
+        assert_eq!(helper.parent_name, None);
+        assert_eq!(helper.signature.as_deref(), Some("fun helper(x: Int): Int"));
+
+        let shout = find(&symbols, "com.example.billing.shout");
+        assert_eq!(shout.kind, SymbolKind::Function);
+        assert_eq!(shout.parent_name.as_deref(), Some("String"));
+
+        let second = find(&symbols, "com.example.billing.second");
+        assert_eq!(second.parent_name.as_deref(), Some("List"));
+    }
+
+    #[test]
+    fn properties_map_to_constants_variables_and_fields() {
+        let symbols = symbols();
+
+        assert_eq!(
+            find(&symbols, "com.example.billing.MAX_RETRIES").kind,
+            SymbolKind::Constant
+        );
+        assert_eq!(
+            find(&symbols, "com.example.billing.counter").kind,
+            SymbolKind::Variable
+        );
+        let paid = find(&symbols, "com.example.billing.Invoice.paid");
+        assert_eq!(paid.kind, SymbolKind::Field);
+        assert_eq!(paid.visibility.as_deref(), Some("private"));
+
+        let id = find(&symbols, "com.example.billing.Invoice.id");
+        assert_eq!(id.kind, SymbolKind::Field);
+        assert_eq!(id.signature.as_deref(), Some("val id: Long"));
+        assert_eq!(
+            find(&symbols, "com.example.billing.Invoice.total")
+                .visibility
+                .as_deref(),
+            Some("private")
+        );
+        assert!(
+            !symbols
+                .iter()
+                .any(|s| s.qualified_name == "com.example.billing.Invoice.note"),
+            "plain constructor parameters are not properties"
+        );
+    }
+}
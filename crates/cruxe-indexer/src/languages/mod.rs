@@ -1,6 +1,11 @@
 // Per-language modules (call sites + imports remain here).
+pub mod c;
+pub mod cpp;
+pub mod csharp;
 pub mod go;
+pub mod kotlin;
 pub mod python;
+pub mod ruby;
 pub mod rust;
 pub mod typescript;
 
@@ -9,7 +14,7 @@ pub mod generic_mapper;
 pub mod tag_extract;
 pub(crate) mod text;
 
-use cruxe_core::types::SymbolKind;
+use cruxe_core::types::{ParsedSignature, SymbolKind};
 
 /// Extracted symbol from tree-sitter.
 #[derive(Debug, Clone)]
@@ -21,9 +26,19 @@ pub struct ExtractedSymbol {
     pub signature: Option<String>,
     pub line_start: u32,
     pub line_end: u32,
+    /// Byte range of the definition node within the source (end exclusive).
+    pub byte_start: u32,
+    pub byte_end: u32,
     pub visibility: Option<String>,
     pub parent_name: Option<String>,
     pub body: Option<String>,
+    /// Outer attributes (Rust, e.g. `test`, `derive(Debug)`) or decorators (Python,
+    /// e.g. `app.get("/users")`) attached to the item, in source order.
+    pub attributes: Vec<String>,
+    /// Documentation comment preceding the item (Rust only), markers stripped.
+    pub doc_comment: Option<String>,
+    /// Structured signature for Rust and Go functions and methods.
+    pub parsed_signature: Option<ParsedSignature>,
 }
 
 /// Extracted call-site from tree-sitter source traversal.
@@ -37,6 +52,48 @@ pub struct ExtractedCallSite {
 #[derive(Debug, Clone, Copy, Default)]
 pub struct SymbolExtractionDiagnostics {
     pub had_parse_error: bool,
+    /// Some nodes sat below the depth cap and were not visited.
+    pub depth_limit_reached: bool,
+}
+
+/// Depth budget for the recursive tree walks in this module.
+///
+/// Generated sources can nest thousands of levels deep, which overflows the
+/// stack of a naive per-child recursion. Walkers consult [`DepthGuard::allows`]
+/// before visiting a node and skip everything below `max_depth` (`0` disables
+/// the cap); whatever was found above the cutoff is still returned.
+#[derive(Debug, Clone, Copy)]
+pub struct DepthGuard {
+    max_depth: usize,
+    limit_reached: bool,
+}
+
+impl DepthGuard {
+    pub fn new(max_depth: usize) -> Self {
+        Self {
+            max_depth,
+            limit_reached: false,
+        }
+    }
+
+    /// Whether a node at `depth` (the root is `0`) may be visited.
+    pub fn allows(&mut self, depth: usize) -> bool {
+        if self.max_depth > 0 && depth > self.max_depth {
+            self.limit_reached = true;
+            return false;
+        }
+        true
+    }
+
+    pub fn limit_reached(&self) -> bool {
+        self.limit_reached
+    }
+}
+
+impl Default for DepthGuard {
+    fn default() -> Self {
+        Self::new(cruxe_core::constants::DEFAULT_MAX_SYNTAX_DEPTH)
+    }
 }
 
 /// Extract symbols using the pre-parsed tree + tree-sitter query pipeline.
@@ -44,42 +101,105 @@ pub struct SymbolExtractionDiagnostics {
 /// The pre-parsed `tree` is reused for both:
 /// - query capture matching (`@definition.*` + `@name`)
 /// - enrichment (parent walking, visibility extraction, kind disambiguation)
+///
+/// C, C++, C#, Ruby and Kotlin are the exceptions: their extractors walk the
+/// tree directly (see [`c`], [`cpp`], [`csharp`], [`ruby`] and [`kotlin`]).
 pub fn extract_symbols(
     tree: &tree_sitter::Tree,
     source: &str,
     language: &str,
 ) -> Vec<ExtractedSymbol> {
-    extract_symbols_with_diagnostics(tree, source, language).0
+    extract_symbols_with_diagnostics(
+        tree,
+        source,
+        language,
+        cruxe_core::constants::DEFAULT_MAX_SYNTAX_DEPTH,
+    )
+    .0
 }
 
+/// [`extract_symbols`] with parse diagnostics; tree walks stop below `max_depth`.
 pub fn extract_symbols_with_diagnostics(
     tree: &tree_sitter::Tree,
     source: &str,
     language: &str,
+    max_depth: usize,
 ) -> (Vec<ExtractedSymbol>, SymbolExtractionDiagnostics) {
-    let (symbols, diagnostics) =
+    let mut guard = DepthGuard::new(max_depth);
+    let walked = match language {
+        "c" => Some(c::extract_symbols(tree, source, &mut guard)),
+        "cpp" => Some(cpp::extract_symbols(tree, source, &mut guard)),
+        "csharp" => Some(csharp::extract_symbols(tree, source, &mut guard)),
+        "ruby" => Some(ruby::extract_symbols(tree, source, &mut guard)),
+        "kotlin" => Some(kotlin::extract_symbols(tree, source, &mut guard)),
+        _ => None,
+    };
+    if let Some(mut symbols) = walked {
+        canonicalize_visibility(&mut symbols);
+        return (
+            symbols,
+            SymbolExtractionDiagnostics {
+                had_parse_error: tree.root_node().has_error(),
+                depth_limit_reached: guard.limit_reached(),
+            },
+        );
+    }
+
+    let (mut symbols, diagnostics) =
         tag_extract::extract_symbols_via_tags_with_diagnostics(tree, source, language);
+    if language == "go" {
+        symbols.extend(go::extract_struct_fields(tree, source, &mut guard));
+        sort_symbols(&mut symbols);
+    }
+    canonicalize_visibility(&mut symbols);
     (
         symbols,
         SymbolExtractionDiagnostics {
             had_parse_error: diagnostics.had_parse_error,
+            depth_limit_reached: guard.limit_reached(),
         },
     )
 }
 
+/// Rewrite per-language visibility keywords (`pub`, `export`, `static`, ...)
+/// into the canonical set, see [`cruxe_core::types::canonical_visibility`].
+fn canonicalize_visibility(symbols: &mut [ExtractedSymbol]) {
+    for symbol in symbols {
+        if let Some(raw) = symbol.visibility.as_deref() {
+            symbol.visibility = Some(cruxe_core::types::canonical_visibility(raw));
+        }
+    }
+}
+
+/// Order symbols by position (start line, end line, then name).
+pub(crate) fn sort_symbols(symbols: &mut [ExtractedSymbol]) {
+    symbols.sort_by(|a, b| {
+        a.line_start
+            .cmp(&b.line_start)
+            .then_with(|| a.line_end.cmp(&b.line_end))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+}
+
 /// Extract call-sites from a parsed tree for a given language.
+///
+/// Calls nested deeper than `max_depth` are skipped; the flag reports whether
+/// that happened.
 pub fn extract_call_sites(
     tree: &tree_sitter::Tree,
     source: &str,
     language: &str,
-) -> Vec<ExtractedCallSite> {
-    match language {
-        "rust" => rust::extract_call_sites(tree, source),
-        "typescript" => typescript::extract_call_sites(tree, source),
-        "python" => python::extract_call_sites(tree, source),
-        "go" => go::extract_call_sites(tree, source),
+    max_depth: usize,
+) -> (Vec<ExtractedCallSite>, bool) {
+    let mut guard = DepthGuard::new(max_depth);
+    let calls = match language {
+        "rust" => rust::extract_call_sites(tree, source, &mut guard),
+        "typescript" => typescript::extract_call_sites(tree, source, &mut guard),
+        "python" => python::extract_call_sites(tree, source, &mut guard),
+        "go" => go::extract_call_sites(tree, source, &mut guard),
         _ => Vec::new(),
-    }
+    };
+    (calls, guard.limit_reached())
 }
 
 #[cfg(test)]
@@ -127,4 +247,115 @@ var legacyCount = 1;
             "expected legacyCount variable symbol"
         );
     }
+
+    #[test]
+    fn deeply_nested_source_keeps_calls_above_the_depth_cap() {
+        let nesting = 2_000;
+        let source = format!(
+            "function outer() {{\n  top();\n  return {}deep(){};\n}}\n",
+            "[".repeat(nesting),
+            "]".repeat(nesting)
+        );
+        let tree = parse_file(&source, "typescript").expect("parse typescript");
+
+        let (calls, truncated) = extract_call_sites(&tree, &source, "typescript", 100);
+        assert!(truncated, "nesting beyond the cap should be reported");
+        let callees: Vec<&str> = calls.iter().map(|c| c.callee_name.as_str()).collect();
+        assert_eq!(callees, vec!["top"]);
+
+        let (symbols, diagnostics) =
+            extract_symbols_with_diagnostics(&tree, &source, "typescript", 100);
+        assert!(symbols.iter().any(|s| s.name == "outer"));
+        assert!(!diagnostics.had_parse_error);
+
+        let (calls, truncated) = extract_call_sites(&tree, &source, "typescript", 0);
+        assert!(!truncated, "0 disables the cap");
+        assert_eq!(calls.len(), 2);
+    }
+
+    fn visibility_of<'a>(symbols: &'a [ExtractedSymbol], qualified_name: &str) -> Option<&'a str> {
+        symbols
+            .iter()
+            .find(|s| s.qualified_name == qualified_name)
+            .unwrap_or_else(|| panic!("missing symbol {qualified_name}"))
+            .visibility
+            .as_deref()
+    }
+
+    #[test]
+    fn go_visibility_follows_identifier_case() {
+        let source = r#"
+package demo
+
+type Account struct {
+    Owner   string
+    balance int
+}
+
+func (a *Account) Deposit(amount int) {}
+func (a *Account) audit() {}
+func NewAccount() *Account { return nil }
+func helper() {}
+const MaxRetries = 3
+var _skipped = 0
+"#;
+        let tree = parse_file(source, "go").expect("parse go");
+        let symbols = extract_symbols(&tree, source, "go");
+
+        assert_eq!(visibility_of(&symbols, "Account"), Some("public"));
+        assert_eq!(visibility_of(&symbols, "Account.Owner"), Some("public"));
+        assert_eq!(visibility_of(&symbols, "Account.balance"), Some("private"));
+        assert_eq!(visibility_of(&symbols, "NewAccount"), Some("public"));
+        assert_eq!(visibility_of(&symbols, "helper"), Some("private"));
+        assert_eq!(visibility_of(&symbols, "MaxRetries"), Some("public"));
+        assert_eq!(visibility_of(&symbols, "_skipped"), Some("private"));
+        let deposit = symbols.iter().find(|s| s.name == "Deposit").unwrap();
+        assert_eq!(deposit.visibility.as_deref(), Some("public"));
+        let audit = symbols.iter().find(|s| s.name == "audit").unwrap();
+        assert_eq!(audit.visibility.as_deref(), Some("private"));
+    }
+
+    #[test]
+    fn visibility_keywords_are_canonicalized_across_languages() {
+        let rust = r#"
+pub struct Token;
+pub(crate) fn issue() {}
+fn check() {}
+pub trait Verify {
+    fn verify(&self);
+}
+"#;
+        let tree = parse_file(rust, "rust").expect("parse rust");
+        let symbols = extract_symbols(&tree, rust, "rust");
+        assert_eq!(visibility_of(&symbols, "Token"), Some("public"));
+        assert_eq!(visibility_of(&symbols, "issue"), Some("internal"));
+        assert_eq!(visibility_of(&symbols, "check"), Some("private"));
+        assert_eq!(visibility_of(&symbols, "Verify::verify"), None);
+
+        let typescript = r#"
+export function login() {}
+function local() {}
+class Session {
+    private token = "";
+    refresh() {}
+}
+"#;
+        let tree = parse_file(typescript, "typescript").expect("parse typescript");
+        let symbols = extract_symbols(&tree, typescript, "typescript");
+        assert_eq!(visibility_of(&symbols, "login"), Some("public"));
+        assert_eq!(visibility_of(&symbols, "local"), Some("private"));
+        assert_eq!(visibility_of(&symbols, "Session.refresh"), Some("public"));
+
+        let python =
+            "def _hidden():\n    pass\n\nclass Repo:\n    def __init__(self):\n        pass\n";
+        let tree = parse_file(python, "python").expect("parse python");
+        let symbols = extract_symbols(&tree, python, "python");
+        assert_eq!(visibility_of(&symbols, "_hidden"), Some("private"));
+        assert_eq!(visibility_of(&symbols, "Repo.__init__"), Some("public"));
+
+        let c = "static int clamp(int v) { return v; }\n";
+        let tree = parse_file(c, "c").expect("parse c");
+        let symbols = extract_symbols(&tree, c, "c");
+        assert_eq!(visibility_of(&symbols, "clamp"), Some("private"));
+    }
 }
@@ -1,16 +1,29 @@
-use super::ExtractedCallSite;
 use super::text::node_text_owned;
+use super::{DepthGuard, ExtractedCallSite};
 use crate::import_extract::RawImport;
 use std::path::Path;
 
 /// Extract Python call-sites using `call` nodes.
-pub fn extract_call_sites(tree: &tree_sitter::Tree, source: &str) -> Vec<ExtractedCallSite> {
+pub fn extract_call_sites(
+    tree: &tree_sitter::Tree,
+    source: &str,
+    guard: &mut DepthGuard,
+) -> Vec<ExtractedCallSite> {
     let mut calls = Vec::new();
-    collect_call_sites(tree.root_node(), source, &mut calls);
+    collect_call_sites(tree.root_node(), source, 0, guard, &mut calls);
     calls
 }
 
-fn collect_call_sites(node: tree_sitter::Node, source: &str, calls: &mut Vec<ExtractedCallSite>) {
+fn collect_call_sites(
+    node: tree_sitter::Node,
+    source: &str,
+    depth: usize,
+    guard: &mut DepthGuard,
+    calls: &mut Vec<ExtractedCallSite>,
+) {
+    if !guard.allows(depth) {
+        return;
+    }
     if node.kind() == "call"
         && let Some(call) = parse_call_node(node, source)
     {
@@ -18,7 +31,7 @@ fn collect_call_sites(node: tree_sitter::Node, source: &str, calls: &mut Vec<Ext
     }
     for idx in 0..node.child_count() {
         if let Some(child) = node.child(idx) {
-            collect_call_sites(child, source, calls);
+            collect_call_sites(child, source, depth + 1, guard, calls);
         }
     }
 }
@@ -47,6 +60,39 @@ fn normalize_call_target(prefix: &str) -> Option<String> {
     Some(value.to_string())
 }
 
+/// Visibility by naming convention: a leading underscore marks a name
+/// private, except for dunder names such as `__init__`.
+pub fn visibility_for_name(name: &str) -> &'static str {
+    let dunder = name.len() > 4 && name.starts_with("__") && name.ends_with("__");
+    if name.starts_with('_') && !dunder {
+        "private"
+    } else {
+        "public"
+    }
+}
+
+/// Collect decorators (`@app.get("/users")`, `@staticmethod`) attached to a definition.
+///
+/// Decorators are returned without the leading `@`, in source order, with
+/// whitespace runs collapsed. The definition node itself stays the inner
+/// `function_definition`/`class_definition`, so its line range excludes them.
+pub fn extract_decorators(node: tree_sitter::Node, source: &str) -> Vec<String> {
+    let Some(parent) = node.parent().filter(|p| p.kind() == "decorated_definition") else {
+        return Vec::new();
+    };
+    let mut cursor = parent.walk();
+    parent
+        .named_children(&mut cursor)
+        .filter(|child| child.kind() == "decorator")
+        .map(|decorator| {
+            let text = node_text_owned(decorator, source);
+            let text = text.trim().trim_start_matches('@');
+            text.split_whitespace().collect::<Vec<_>>().join(" ")
+        })
+        .filter(|text| !text.is_empty())
+        .collect()
+}
+
 /// Extract Python import statements, including multi-line parenthesized forms.
 pub fn extract_imports(
     _tree: &tree_sitter::Tree,
@@ -201,6 +247,7 @@ fn resolve_python_module(source_path: &str, module: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::extract_imports;
+    use crate::languages::extract_symbols;
     use crate::parser;
     use std::collections::HashSet;
 
@@ -252,4 +299,52 @@ from auth.jwt import (
         );
         assert!(target_names.contains("Claims"), "missing Claims");
     }
+
+    #[test]
+    fn extract_symbols_captures_stacked_decorators_in_source_order() {
+        let source = r#"
+@app.get("/users")
+@requires_auth(
+    scope="read",
+)
+def list_users():
+    return []
+
+
+@dataclass
+class User:
+    @staticmethod
+    def build():
+        pass
+
+
+def plain():
+    pass
+"#;
+        let tree = parser::parse_file(source, "python").unwrap();
+        let symbols = extract_symbols(&tree, source, "python");
+        let find = |name: &str| {
+            symbols
+                .iter()
+                .find(|symbol| symbol.name == name)
+                .unwrap_or_else(|| panic!("missing symbol {name}"))
+                .clone()
+        };
+
+        let list_users = find("list_users");
+        assert_eq!(
+            list_users.attributes,
+            vec![
+                "app.get(\"/users\")".to_string(),
+                "requires_auth( scope=\"read\", )".to_string()
+            ]
+        );
+        assert_eq!(
+            list_users.line_start, 6,
+            "line range should exclude decorators"
+        );
+        assert_eq!(find("User").attributes, vec!["dataclass".to_string()]);
+        assert_eq!(find("build").attributes, vec!["staticmethod".to_string()]);
+        assert!(find("plain").attributes.is_empty());
+    }
 }
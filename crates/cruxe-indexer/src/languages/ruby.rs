@@ -0,0 +1,414 @@
+use super::{DepthGuard, ExtractedSymbol};
+use cruxe_core::types::SymbolKind;
+
+/// Extract Ruby symbols by walking module/class bodies of the tree-sitter-ruby
+/// grammar.
+///
+/// Emits modules, classes, instance and singleton methods (`def self.x` and
+/// `class << self` bodies) and constant assignments. `qualified_name` joins the
+/// lexical module/class nesting with `::`, so `module Billing; class Invoice;
+/// def pay` yields `Billing::Invoice::pay`. `attr_reader`/`attr_writer`/
+/// `attr_accessor` produce synthetic accessor methods (`name`, `name=`) so
+/// attribute-based APIs are searchable. A bare `private`/`protected` marks the
+/// methods that follow it in the same body.
+pub fn extract_symbols(
+    tree: &tree_sitter::Tree,
+    source: &str,
+    guard: &mut DepthGuard,
+) -> Vec<ExtractedSymbol> {
+    let mut symbols = Vec::new();
+    collect_body(tree.root_node(), source, &[], 0, guard, &mut symbols);
+    super::sort_symbols(&mut symbols);
+    symbols
+}
+
+fn collect_body(
+    node: tree_sitter::Node,
+    source: &str,
+    scope: &[String],
+    depth: usize,
+    guard: &mut DepthGuard,
+    out: &mut Vec<ExtractedSymbol>,
+) {
+    if !guard.allows(depth) {
+        return;
+    }
+    let mut visibility: Option<&'static str> = None;
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        match child.kind() {
+            "module" | "class" => namespace_symbols(child, source, scope, depth, guard, out),
+            "singleton_class" => {
+                if let Some(body) = child.child_by_field_name("body") {
+                    collect_body(body, source, scope, depth + 1, guard, out);
+                }
+            }
+            "method" | "singleton_method" => {
+                if let Some(symbol) = method_symbol(child, source, scope, visibility) {
+                    out.push(symbol);
+                }
+            }
+            "assignment" => {
+                if let Some(symbol) = constant_symbol(child, source, scope) {
+                    out.push(symbol);
+                }
+            }
+            "identifier" if !scope.is_empty() => match node_text(child, source) {
+                "private" => visibility = Some("private"),
+                "protected" => visibility = Some("protected"),
+                "public" => visibility = None,
+                _ => {}
+            },
+            "call" => call_symbols(child, source, scope, out),
+            _ => {}
+        }
+    }
+}
+
+fn namespace_symbols(
+    node: tree_sitter::Node,
+    source: &str,
+    scope: &[String],
+    depth: usize,
+    guard: &mut DepthGuard,
+    out: &mut Vec<ExtractedSymbol>,
+) {
+    let Some(name_node) = node.child_by_field_name("name") else {
+        return;
+    };
+    let mut path = scope.to_vec();
+    path.extend(constant_path(name_node, source));
+    let Some(name) = path.last().cloned() else {
+        return;
+    };
+    let kind = if node.kind() == "module" {
+        SymbolKind::Module
+    } else {
+        SymbolKind::Class
+    };
+
+    let signature_end = node
+        .child_by_field_name("superclass")
+        .unwrap_or(name_node)
+        .end_byte();
+    let mut symbol = build_symbol(name, kind, node, source, scope);
+    symbol.qualified_name = path.join("::");
+    symbol.signature = source
+        .get(node.start_byte()..signature_end)
+        .map(collapse_whitespace);
+    out.push(symbol);
+
+    if let Some(body) = node.child_by_field_name("body") {
+        collect_body(body, source, &path, depth + 1, guard, out);
+    }
+}
+
+fn method_symbol(
+    node: tree_sitter::Node,
+    source: &str,
+    scope: &[String],
+    visibility: Option<&'static str>,
+) -> Option<ExtractedSymbol> {
+    let name = node_text(node.child_by_field_name("name")?, source).to_string();
+    let kind = if scope.is_empty() && node.kind() == "method" {
+        SymbolKind::Function
+    } else {
+        SymbolKind::Method
+    };
+    let signature_end = node
+        .child_by_field_name("parameters")
+        .or_else(|| node.child_by_field_name("name"))
+        .map(|n| n.end_byte())?;
+
+    let mut symbol = build_symbol(name, kind, node, source, scope);
+    symbol.signature = source
+        .get(node.start_byte()..signature_end)
+        .map(collapse_whitespace);
+    symbol.visibility = visibility.map(str::to_string);
+    Some(symbol)
+}
+
+fn constant_symbol(
+    node: tree_sitter::Node,
+    source: &str,
+    scope: &[String],
+) -> Option<ExtractedSymbol> {
+    let left = node.child_by_field_name("left")?;
+    if left.kind() != "constant" {
+        return None;
+    }
+    let name = node_text(left, source).to_string();
+    let mut symbol = build_symbol(name, SymbolKind::Constant, node, source, scope);
+    symbol.signature = source
+        .get(node.byte_range())
+        .and_then(|text| text.lines().next())
+        .map(|line| line.trim().to_string())
+        .filter(|s| !s.is_empty());
+    Some(symbol)
+}
+
+/// Handle receiver-less calls in a class body: `attr_*` accessors and
+/// `private def ...`-style visibility wrappers.
+fn call_symbols(
+    node: tree_sitter::Node,
+    source: &str,
+    scope: &[String],
+    out: &mut Vec<ExtractedSymbol>,
+) {
+    if scope.is_empty() || node.child_by_field_name("receiver").is_some() {
+        return;
+    }
+    let Some(method) = node.child_by_field_name("method") else {
+        return;
+    };
+    let Some(arguments) = node.child_by_field_name("arguments") else {
+        return;
+    };
+    let (readers, writers) = match node_text(method, source) {
+        "attr_reader" => (true, false),
+        "attr_writer" => (false, true),
+        "attr_accessor" => (true, true),
+        wrapper @ ("private" | "protected" | "public") => {
+            let visibility = match wrapper {
+                "private" => Some("private"),
+                "protected" => Some("protected"),
+                _ => None,
+            };
+            let mut cursor = arguments.walk();
+            for argument in arguments.named_children(&mut cursor) {
+                if matches!(argument.kind(), "method" | "singleton_method")
+                    && let Some(symbol) = method_symbol(argument, source, scope, visibility)
+                {
+                    out.push(symbol);
+                }
+            }
+            return;
+        }
+        _ => return,
+    };
+
+    let signature = source.get(node.byte_range()).map(collapse_whitespace);
+    let mut cursor = arguments.walk();
+    for argument in arguments.named_children(&mut cursor) {
+        let Some(attribute) = attribute_name(argument, source) else {
+            continue;
+        };
+        let mut names = Vec::new();
+        if readers {
+            names.push(attribute.to_string());
+        }
+        if writers {
+            names.push(format!("{attribute}="));
+        }
+        for name in names {
+            let mut symbol = build_symbol(name, SymbolKind::Method, node, source, scope);
+            symbol.signature = signature.clone();
+            out.push(symbol);
+        }
+    }
+}
+
+/// `:name` or `"name"` accessor arguments.
+fn attribute_name<'a>(node: tree_sitter::Node, source: &'a str) -> Option<&'a str> {
+    let text = node_text(node, source);
+    let name = match node.kind() {
+        "simple_symbol" => text.strip_prefix(':')?,
+        "string" => text.trim_matches(|c| c == '"' || c == '\''),
+        _ => return None,
+    };
+    (!name.is_empty()).then_some(name)
+}
+
+/// Segments of a `Foo` or `Foo::Bar` constant path.
+fn constant_path(node: tree_sitter::Node, source: &str) -> Vec<String> {
+    match node.kind() {
+        "scope_resolution" => {
+            let mut path = node
+                .child_by_field_name("scope")
+                .map(|scope| constant_path(scope, source))
+                .unwrap_or_default();
+            if let Some(name) = node.child_by_field_name("name") {
+                path.push(node_text(name, source).to_string());
+            }
+            path
+        }
+        _ => vec![node_text(node, source).to_string()],
+    }
+}
+
+fn build_symbol(
+    name: String,
+    kind: SymbolKind,
+    node: tree_sitter::Node,
+    source: &str,
+    scope: &[String],
+) -> ExtractedSymbol {
+    let range = node.byte_range();
+    let qualified_name = if scope.is_empty() {
+        name.clone()
+    } else {
+        format!("{}::{}", scope.join("::"), name)
+    };
+    ExtractedSymbol {
+        qualified_name,
+        name,
+        kind,
+        language: "ruby".to_string(),
+        signature: None,
+        line_start: node.start_position().row as u32 + 1,
+        line_end: node.end_position().row as u32 + 1,
+        byte_start: range.start as u32,
+        byte_end: range.end as u32,
+        visibility: None,
+        parent_name: scope.last().cloned(),
+        body: source.get(range).map(String::from),
+        attributes: Vec::new(),
+        doc_comment: None,
+        parsed_signature: None,
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn node_text<'a>(node: tree_sitter::Node, source: &'a str) -> &'a str {
+    source.get(node.byte_range()).unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_file;
+
+    const SOURCE: &str = r#"
+module Billing
+  TAX_RATE = 0.2
+
+  class Invoice < ApplicationRecord
+    attr_accessor :total
+    attr_reader :number, "issued_on"
+
+    def self.build(attrs)
+      new(attrs)
+    end
+
+    def pay(amount, via: :card)
+      charge(amount)
+    end
+
+    class << self
+      def lookup(number)
+        find_by(number: number)
+      end
+    end
+
+    private
+
+    def charge(amount)
+      amount
+    end
+  end
+end
+
+class Admin::Report
+  private def secret; end
+end
+
+def helper; end
+"#;
+
+    fn symbols() -> Vec<ExtractedSymbol> {
+        let tree = parse_file(SOURCE, "ruby").expect("parse ruby");
+        extract_symbols(&tree, SOURCE, &mut DepthGuard::default())
+    }
+
+    fn find<'a>(symbols: &'a [ExtractedSymbol], qualified_name: &str) -> &'a ExtractedSymbol {
+        symbols
+            .iter()
+            .find(|s| s.qualified_name == qualified_name)
+            .unwrap_or_else(|| panic!("expected symbol {qualified_name}"))
+    }
+
+    #[test]
+    fn namespaces_methods_and_constants_use_nested_qualified_names() {
+        let symbols = symbols();
+
+        assert_eq!(find(&symbols, "Billing").kind, SymbolKind::Module);
+        assert_eq!(
+            find(&symbols, "Billing::TAX_RATE").kind,
+            SymbolKind::Constant
+        );
+        let invoice = find(&symbols, "Billing::Invoice");
+        assert_eq!(invoice.kind, SymbolKind::Class);
+        assert_eq!(
+            invoice.signature.as_deref(),
+            Some("class Invoice < ApplicationRecord")
+        );
+        assert_eq!(invoice.parent_name.as_deref(), Some("Billing"));
+
+        let pay = find(&symbols, "Billing::Invoice::pay");
+        assert_eq!(pay.kind, SymbolKind::Method);
+        assert_eq!(
+            pay.signature.as_deref(),
+            Some("def pay(amount, via: :card)")
+        );
+        assert_eq!(pay.parent_name.as_deref(), Some("Invoice"));
+        assert_eq!(
+            find(&symbols, "Billing::Invoice::build")
+                .signature
+                .as_deref(),
+            Some("def self.build(attrs)")
+        );
+        assert_eq!(
+            find(&symbols, "Billing::Invoice::lookup").kind,
+            SymbolKind::Method
+        );
+
+        let report = find(&symbols, "Admin::Report");
+        assert_eq!(report.name, "Report");
+        assert_eq!(find(&symbols, "helper").kind, SymbolKind::Function);
+        assert!(symbols.iter().all(|s| s.language == "ruby"));
+    }
+
+    #[test]
+    fn attr_macros_emit_synthetic_accessor_methods() {
+        let symbols = symbols();
+
+        for name in [
+            "Billing::Invoice::total",
+            "Billing::Invoice::total=",
+            "Billing::Invoice::number",
+            "Billing::Invoice::issued_on",
+        ] {
+            let accessor = find(&symbols, name);
+            assert_eq!(accessor.kind, SymbolKind::Method);
+            assert!(accessor.signature.as_deref().unwrap().starts_with("attr_"));
+        }
+        assert!(
+            !symbols
+                .iter()
+                .any(|s| s.qualified_name == "Billing::Invoice::number="),
+            "attr_reader must not emit a writer"
+        );
+    }
+
+    #[test]
+    fn private_sections_and_wrappers_mark_visibility() {
+        let symbols = symbols();
+
+        assert_eq!(
+            find(&symbols, "Billing::Invoice::charge")
+                .visibility
+                .as_deref(),
+            Some("private")
+        );
+        assert_eq!(find(&symbols, "Billing::Invoice::pay").visibility, None);
+        assert_eq!(
+            find(&symbols, "Admin::Report::secret")
+                .visibility
+                .as_deref(),
+            Some("private")
+        );
+    }
+}
@@ -1,15 +1,29 @@
-use super::ExtractedCallSite;
-use super::text::node_text_owned;
+use super::text::{collapsed_node_text, node_text_owned};
+use super::{DepthGuard, ExtractedCallSite};
 use crate::import_extract::RawImport;
+use cruxe_core::types::{ParsedSignature, SignatureParam};
 
 /// Extract Rust call-sites using `call_expression` and `method_call_expression` nodes.
-pub fn extract_call_sites(tree: &tree_sitter::Tree, source: &str) -> Vec<ExtractedCallSite> {
+pub fn extract_call_sites(
+    tree: &tree_sitter::Tree,
+    source: &str,
+    guard: &mut DepthGuard,
+) -> Vec<ExtractedCallSite> {
     let mut calls = Vec::new();
-    collect_call_sites(tree.root_node(), source, &mut calls);
+    collect_call_sites(tree.root_node(), source, 0, guard, &mut calls);
     calls
 }
 
-fn collect_call_sites(node: tree_sitter::Node, source: &str, calls: &mut Vec<ExtractedCallSite>) {
+fn collect_call_sites(
+    node: tree_sitter::Node,
+    source: &str,
+    depth: usize,
+    guard: &mut DepthGuard,
+    calls: &mut Vec<ExtractedCallSite>,
+) {
+    if !guard.allows(depth) {
+        return;
+    }
     match node.kind() {
         "call_expression" | "method_call_expression" => {
             if let Some(call) = parse_call_node(node, source) {
@@ -20,7 +34,7 @@ fn collect_call_sites(node: tree_sitter::Node, source: &str, calls: &mut Vec<Ext
     }
     for idx in 0..node.child_count() {
         if let Some(child) = node.child(idx) {
-            collect_call_sites(child, source, calls);
+            collect_call_sites(child, source, depth + 1, guard, calls);
         }
     }
 }
@@ -61,6 +75,181 @@ fn normalize_call_target(prefix: &str) -> Option<String> {
     Some(value.to_string())
 }
 
+/// Collect outer attributes (`#[test]`, `#[derive(Debug, Clone)]`) attached to an item.
+///
+/// Attributes are returned without the `#[...]` wrapper, in source order, with
+/// whitespace runs collapsed. Doc comments between attributes are skipped; inner
+/// attributes (`#![...]`) are not attached to items and are ignored.
+pub fn extract_outer_attributes(node: tree_sitter::Node, source: &str) -> Vec<String> {
+    let mut attributes = Vec::new();
+    let mut sibling = node.prev_sibling();
+    while let Some(current) = sibling {
+        match current.kind() {
+            "attribute_item" => {
+                let attribute = (0..current.named_child_count())
+                    .filter_map(|idx| current.named_child(idx))
+                    .find(|child| child.kind() == "attribute");
+                if let Some(attribute) = attribute {
+                    let text = node_text_owned(attribute, source);
+                    attributes.push(text.split_whitespace().collect::<Vec<_>>().join(" "));
+                }
+            }
+            "line_comment" | "block_comment" => {}
+            _ => break,
+        }
+        sibling = current.prev_sibling();
+    }
+    attributes.reverse();
+    attributes
+}
+
+/// Visibility of an item: its `pub`/`pub(crate)`/... modifier, or `private`
+/// when it has none.
+///
+/// Trait items, trait impl items and enum variants take the visibility of the
+/// trait or enum, so `None` is returned for them rather than guessing.
+pub fn extract_visibility(node: tree_sitter::Node, source: &str) -> Option<String> {
+    let mut cursor = node.walk();
+    let modifier = node
+        .children(&mut cursor)
+        .find(|child| child.kind() == "visibility_modifier");
+    if let Some(modifier) = modifier {
+        return Some(node_text_owned(modifier, source));
+    }
+    if matches!(node.kind(), "enum_variant" | "macro_definition") {
+        return None;
+    }
+    let inherits = node
+        .parent()
+        .filter(|parent| parent.kind() == "declaration_list")
+        .and_then(|list| list.parent())
+        .is_some_and(|owner| {
+            owner.kind() == "trait_item"
+                || (owner.kind() == "impl_item" && owner.child_by_field_name("trait").is_some())
+        });
+    (!inherits).then(|| "private".to_string())
+}
+
+/// Collect the outer doc comment (`///` lines or a `/** */` block) attached to an item.
+///
+/// Comment markers and the conventional leading space / `*` gutter are stripped
+/// and lines are joined with `\n`. Attributes and plain comments between the doc
+/// comment and the item are skipped; `////` and `/***` are not doc comments.
+pub fn extract_doc_comment(node: tree_sitter::Node, source: &str) -> Option<String> {
+    let mut blocks = Vec::new();
+    let mut sibling = node.prev_sibling();
+    while let Some(current) = sibling {
+        match current.kind() {
+            "line_comment" | "block_comment" => {
+                let text = node_text_owned(current, source);
+                if let Some(doc) = strip_doc_comment_markers(text.trim_end()) {
+                    blocks.push(doc);
+                }
+            }
+            "attribute_item" => {}
+            _ => break,
+        }
+        sibling = current.prev_sibling();
+    }
+    blocks.reverse();
+    let doc = blocks.join("\n");
+    let doc = doc.trim_matches('\n');
+    (!doc.is_empty()).then(|| doc.to_string())
+}
+
+fn strip_doc_comment_markers(comment: &str) -> Option<String> {
+    if let Some(line) = comment.strip_prefix("///") {
+        if line.starts_with('/') {
+            return None;
+        }
+        return Some(line.strip_prefix(' ').unwrap_or(line).to_string());
+    }
+    let block = comment.strip_prefix("/**")?.strip_suffix("*/")?;
+    if block.starts_with('*') || block.is_empty() {
+        return None;
+    }
+    let lines: Vec<&str> = block
+        .lines()
+        .map(|line| {
+            let line = line.trim();
+            let line = line.strip_prefix('*').unwrap_or(line);
+            line.strip_prefix(' ').unwrap_or(line)
+        })
+        .collect();
+    Some(lines.join("\n").trim_matches('\n').to_string())
+}
+
+/// Split a `function_item` / `function_signature_item` into generics, `self`
+/// receiver, parameters and return type. Returns `None` for other nodes.
+///
+/// The receiver's type is spelled with `Self` (`&mut self` becomes `&mut Self`);
+/// an explicitly typed `self: Box<Self>` keeps its written type.
+pub fn parse_signature(node: tree_sitter::Node, source: &str) -> Option<ParsedSignature> {
+    if !matches!(node.kind(), "function_item" | "function_signature_item") {
+        return None;
+    }
+    let generics = node
+        .child_by_field_name("type_parameters")
+        .map(|list| {
+            let mut cursor = list.walk();
+            list.named_children(&mut cursor)
+                .filter(|child| !child.is_extra())
+                .map(|child| collapsed_node_text(child, source))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut receiver = None;
+    let mut params = Vec::new();
+    if let Some(list) = node.child_by_field_name("parameters") {
+        let mut cursor = list.walk();
+        for child in list.named_children(&mut cursor) {
+            match child.kind() {
+                "self_parameter" => {
+                    let text = collapsed_node_text(child, source);
+                    let text = text.strip_prefix("mut ").unwrap_or(&text);
+                    receiver = Some(SignatureParam {
+                        name: Some("self".to_string()),
+                        r#type: Some(format!("{}Self", text.trim_end_matches("self"))),
+                    });
+                }
+                "parameter" => {
+                    let name = child
+                        .child_by_field_name("pattern")
+                        .map(|pattern| collapsed_node_text(pattern, source));
+                    let param = SignatureParam {
+                        r#type: child
+                            .child_by_field_name("type")
+                            .map(|ty| collapsed_node_text(ty, source)),
+                        name: name.map(|name| name.trim_start_matches("mut ").to_string()),
+                    };
+                    if param.name.as_deref() == Some("self") && params.is_empty() {
+                        receiver = Some(param);
+                    } else {
+                        params.push(param);
+                    }
+                }
+                "variadic_parameter" => params.push(SignatureParam {
+                    name: None,
+                    r#type: Some("...".to_string()),
+                }),
+                _ => {}
+            }
+        }
+    }
+
+    let return_type = node
+        .child_by_field_name("return_type")
+        .map(|ty| collapsed_node_text(ty, source))
+        .filter(|ty| ty != "()");
+    Some(ParsedSignature {
+        generics,
+        receiver,
+        params,
+        return_type,
+    })
+}
+
 /// Extract Rust `use` imports from source text.
 pub fn extract_imports(
     _tree: &tree_sitter::Tree,
@@ -215,7 +404,9 @@ fn normalize_rust_target(target: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::extract_imports;
+    use crate::languages::extract_symbols;
     use crate::parser;
+    use cruxe_core::types::{ParsedSignature, SignatureParam};
     use std::collections::HashSet;
 
     #[test]
@@ -251,4 +442,148 @@ mod tests {
         assert_eq!(imports.len(), 1);
         assert_eq!(imports[0].target_qualified_name, "a::*");
     }
+
+    #[test]
+    fn extract_symbols_captures_outer_attributes_in_source_order() {
+        let source = r#"
+#[derive(Debug,   Clone)]
+/// Claims carried by a token.
+#[serde(rename_all = "camelCase")]
+pub struct Claims {}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn validates_token() {}
+}
+
+fn plain() {}
+"#;
+        let tree = parser::parse_file(source, "rust").unwrap();
+        let symbols = extract_symbols(&tree, source, "rust");
+        let attributes_of = |name: &str| {
+            symbols
+                .iter()
+                .find(|symbol| symbol.name == name)
+                .unwrap_or_else(|| panic!("missing symbol {name}"))
+                .attributes
+                .clone()
+        };
+
+        assert_eq!(
+            attributes_of("Claims"),
+            vec![
+                "derive(Debug, Clone)".to_string(),
+                "serde(rename_all = \"camelCase\")".to_string()
+            ]
+        );
+        assert_eq!(attributes_of("tests"), vec!["cfg(test)".to_string()]);
+        assert_eq!(attributes_of("validates_token"), vec!["test".to_string()]);
+        assert!(attributes_of("plain").is_empty());
+    }
+
+    #[test]
+    fn extract_symbols_captures_doc_comments() {
+        let source = r#"
+/// Claims carried by a token.
+///
+/// Expiry is checked separately.
+#[derive(Debug)]
+pub struct Claims {}
+
+impl Claims {
+    /**
+     * Returns true once the token has expired.
+     */
+    pub fn is_expired(&self) -> bool { false }
+
+    //// Not a doc comment.
+    fn internal(&self) {}
+}
+
+// Plain comment.
+fn plain() {}
+"#;
+        let tree = parser::parse_file(source, "rust").unwrap();
+        let symbols = extract_symbols(&tree, source, "rust");
+        let doc_of = |name: &str| {
+            symbols
+                .iter()
+                .find(|symbol| symbol.name == name)
+                .unwrap_or_else(|| panic!("missing symbol {name}"))
+                .doc_comment
+                .clone()
+        };
+
+        assert_eq!(
+            doc_of("Claims").as_deref(),
+            Some("Claims carried by a token.\n\nExpiry is checked separately.")
+        );
+        assert_eq!(
+            doc_of("is_expired").as_deref(),
+            Some("Returns true once the token has expired.")
+        );
+        assert_eq!(doc_of("internal"), None);
+        assert_eq!(doc_of("plain"), None);
+    }
+
+    #[test]
+    fn extract_symbols_parses_function_signatures() {
+        let source = r#"
+pub fn parse<'a, T: Clone>(
+    input: &'a str,
+    mut limit: usize,
+) -> Result<Vec<T>, Error> where T: Default { todo!() }
+
+impl Claims {
+    pub fn refresh(&mut self, ttl: Duration) {}
+    fn into_boxed(self: Box<Self>) -> Box<dyn Any> { self }
+}
+
+trait Store {
+    fn get(&self, key: &str) -> Option<String>;
+}
+
+struct Claims {}
+"#;
+        let tree = parser::parse_file(source, "rust").unwrap();
+        let symbols = extract_symbols(&tree, source, "rust");
+        let parsed_of = |name: &str| {
+            symbols
+                .iter()
+                .find(|symbol| symbol.name == name)
+                .unwrap_or_else(|| panic!("missing symbol {name}"))
+                .parsed_signature
+                .clone()
+        };
+        let param = |name: &str, ty: &str| SignatureParam {
+            name: Some(name.to_string()),
+            r#type: Some(ty.to_string()),
+        };
+
+        assert_eq!(
+            parsed_of("parse"),
+            Some(ParsedSignature {
+                generics: vec!["'a".to_string(), "T: Clone".to_string()],
+                receiver: None,
+                params: vec![param("input", "&'a str"), param("limit", "usize")],
+                return_type: Some("Result<Vec<T>, Error>".to_string()),
+            })
+        );
+        assert_eq!(
+            parsed_of("refresh"),
+            Some(ParsedSignature {
+                receiver: Some(param("self", "&mut Self")),
+                params: vec![param("ttl", "Duration")],
+                ..ParsedSignature::default()
+            })
+        );
+        let boxed = parsed_of("into_boxed").unwrap();
+        assert_eq!(boxed.receiver, Some(param("self", "Box<Self>")));
+        assert!(boxed.params.is_empty());
+        let get = parsed_of("get").unwrap();
+        assert_eq!(get.receiver, Some(param("self", "&Self")));
+        assert_eq!(get.return_type.as_deref(), Some("Option<String>"));
+        assert_eq!(parsed_of("Claims"), None);
+    }
 }
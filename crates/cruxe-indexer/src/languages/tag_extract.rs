@@ -1,6 +1,7 @@
 use super::ExtractedSymbol;
 use super::generic_mapper;
 use crate::language_grammars;
+use cruxe_core::types::SymbolKind;
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 use std::ops::Range;
@@ -110,8 +111,9 @@ fn collect_definition_symbols(
 ) -> Vec<ExtractedSymbol> {
     let capture_names = query.capture_names();
     let mut cursor = QueryCursor::new();
-    let mut seen = HashSet::<(usize, usize, usize, usize, cruxe_core::types::SymbolKind)>::new();
+    let mut seen = HashSet::<(usize, usize, usize, usize, SymbolKind)>::new();
     let mut symbols = Vec::new();
+    let mut callable_names = HashSet::<(usize, usize)>::new();
     let mut matches = cursor.matches(query, tree.root_node(), source.as_bytes());
 
     while let Some(query_match) = matches.next() {
@@ -148,15 +150,25 @@ fn collect_definition_symbols(
             continue;
         }
 
-        symbols.push(symbol);
+        let name_range = (name_capture.node.start_byte(), name_capture.node.end_byte());
+        if matches!(symbol.kind, SymbolKind::Function | SymbolKind::Method) {
+            callable_names.insert(name_range);
+        }
+        symbols.push((name_range, symbol));
     }
 
-    symbols.sort_by(|a, b| {
-        a.line_start
-            .cmp(&b.line_start)
-            .then_with(|| a.line_end.cmp(&b.line_end))
-            .then_with(|| a.name.cmp(&b.name))
-    });
+    // `const f = () => {}` matches both the plain variable pattern and the
+    // function-valued one; keep only the callable.
+    let mut symbols: Vec<ExtractedSymbol> = symbols
+        .into_iter()
+        .filter(|(name_range, symbol)| {
+            !(matches!(symbol.kind, SymbolKind::Variable | SymbolKind::Constant)
+                && callable_names.contains(name_range))
+        })
+        .map(|(_, symbol)| symbol)
+        .collect();
+
+    super::sort_symbols(&mut symbols);
     symbols
 }
 
@@ -200,13 +212,50 @@ fn map_capture_to_symbol(
 
     let parent_name = generic_mapper::find_parent_scope(definition_node, source);
     let has_parent = parent_name.is_some();
-    let kind = generic_mapper::map_tag_kind(tag_kind, has_parent, Some(definition_node.kind()))?;
-    let signature = generic_mapper::extract_signature(
-        kind,
-        source,
-        range_from_node_or_default(source, definition_range.clone()),
-    );
-    let visibility = None;
+    // Go `type Foo struct {...}` is tagged on the `type_spec`; its shape lives on
+    // the spec's `type` child.
+    let node_kind = match definition_node.kind() {
+        "type_spec" => definition_node
+            .child_by_field_name("type")
+            .map_or("type_spec", |ty| ty.kind()),
+        other => other,
+    };
+    let kind = generic_mapper::map_tag_kind(tag_kind, has_parent, Some(node_kind))?;
+    let signature = match language {
+        "typescript" if matches!(kind, SymbolKind::Function | SymbolKind::Method) => {
+            super::typescript::function_value_signature(name_capture.node, source)
+        }
+        _ => None,
+    }
+    .or_else(|| {
+        generic_mapper::extract_signature(
+            kind,
+            source,
+            range_from_node_or_default(source, definition_range.clone()),
+        )
+    });
+    let visibility = match language {
+        "rust" => super::rust::extract_visibility(definition_node, source),
+        "typescript" => super::typescript::extract_visibility(definition_node, source),
+        "python" => Some(super::python::visibility_for_name(&name).to_string()),
+        "go" => Some(super::go::visibility_for_name(&name).to_string()),
+        _ => None,
+    };
+    let attributes = match language {
+        "rust" => super::rust::extract_outer_attributes(definition_node, source),
+        "python" => super::python::extract_decorators(definition_node, source),
+        _ => Vec::new(),
+    };
+    let doc_comment = match language {
+        "rust" => super::rust::extract_doc_comment(definition_node, source),
+        _ => None,
+    };
+    let parsed_signature = match language {
+        "rust" => super::rust::parse_signature(definition_node, source),
+        "go" => super::go::parse_signature(definition_node, source),
+        _ => None,
+    }
+    .filter(|_| matches!(kind, SymbolKind::Function | SymbolKind::Method));
 
     let qualified_name = match &parent_name {
         Some(parent) => format!(
@@ -226,9 +275,14 @@ fn map_capture_to_symbol(
         signature,
         line_start: definition_node.start_position().row as u32 + 1,
         line_end: definition_node.end_position().row as u32 + 1,
+        byte_start: definition_range.start as u32,
+        byte_end: definition_range.end as u32,
         visibility,
         parent_name,
         body,
+        attributes,
+        doc_comment,
+        parsed_signature,
     })
 }
 
@@ -262,6 +316,24 @@ fn run() {}
         assert!(fn_symbol.signature.is_some(), "expected callable signature");
     }
 
+    #[test]
+    fn trait_method_signatures_are_extracted_as_methods() {
+        let source = r#"
+trait Greeter {
+    fn greet(&self) -> String;
+    fn wave(&self) {}
+}
+"#;
+        let tree = parse_file(source, "rust").expect("parse rust");
+        let symbols = extract_symbols_via_tags(&tree, source, "rust");
+
+        for name in ["greet", "wave"] {
+            let method = symbols.iter().find(|s| s.name == name).expect(name);
+            assert_eq!(method.kind, cruxe_core::types::SymbolKind::Method);
+            assert_eq!(method.qualified_name, format!("Greeter::{name}"));
+        }
+    }
+
     #[test]
     fn diagnostics_flag_partial_parse_errors() {
         let source = "fn broken( {";
@@ -4,3 +4,13 @@
 pub(crate) fn node_text_owned(node: tree_sitter::Node, source: &str) -> String {
     source.get(node.byte_range()).unwrap_or("").to_string()
 }
+
+/// Node text with whitespace runs collapsed, so multi-line types read as one line.
+pub(crate) fn collapsed_node_text(node: tree_sitter::Node, source: &str) -> String {
+    source
+        .get(node.byte_range())
+        .unwrap_or("")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
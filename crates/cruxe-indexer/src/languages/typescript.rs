@@ -1,16 +1,29 @@
-use super::ExtractedCallSite;
 use super::text::node_text_owned;
+use super::{DepthGuard, ExtractedCallSite};
 use crate::import_extract::RawImport;
 use std::path::{Component, Path, PathBuf};
 
 /// Extract TypeScript call-sites using `call_expression` and `new_expression`.
-pub fn extract_call_sites(tree: &tree_sitter::Tree, source: &str) -> Vec<ExtractedCallSite> {
+pub fn extract_call_sites(
+    tree: &tree_sitter::Tree,
+    source: &str,
+    guard: &mut DepthGuard,
+) -> Vec<ExtractedCallSite> {
     let mut calls = Vec::new();
-    collect_call_sites(tree.root_node(), source, &mut calls);
+    collect_call_sites(tree.root_node(), source, 0, guard, &mut calls);
     calls
 }
 
-fn collect_call_sites(node: tree_sitter::Node, source: &str, calls: &mut Vec<ExtractedCallSite>) {
+fn collect_call_sites(
+    node: tree_sitter::Node,
+    source: &str,
+    depth: usize,
+    guard: &mut DepthGuard,
+    calls: &mut Vec<ExtractedCallSite>,
+) {
+    if !guard.allows(depth) {
+        return;
+    }
     match node.kind() {
         "call_expression" | "new_expression" => {
             if let Some(call) = parse_call_node(node, source) {
@@ -21,7 +34,7 @@ fn collect_call_sites(node: tree_sitter::Node, source: &str, calls: &mut Vec<Ext
     }
     for idx in 0..node.child_count() {
         if let Some(child) = node.child(idx) {
-            collect_call_sites(child, source, calls);
+            collect_call_sites(child, source, depth + 1, guard, calls);
         }
     }
 }
@@ -57,6 +70,64 @@ fn normalize_call_target(prefix: &str) -> Option<String> {
     Some(value.to_string())
 }
 
+/// Visibility of a declaration: the member's `public`/`private`/`protected`
+/// modifier, `private` for `#name` members, `public` for other class and
+/// interface members, `export` for exported declarations and `private` for
+/// module-local ones.
+pub fn extract_visibility(node: tree_sitter::Node, source: &str) -> Option<String> {
+    let mut cursor = node.walk();
+    let modifier = node
+        .children(&mut cursor)
+        .find(|child| child.kind() == "accessibility_modifier");
+    if let Some(modifier) = modifier {
+        return Some(node_text_owned(modifier, source));
+    }
+    if node
+        .child_by_field_name("name")
+        .is_some_and(|name| name.kind() == "private_property_identifier")
+    {
+        return Some("private".to_string());
+    }
+    let mut current = node.parent();
+    while let Some(parent) = current {
+        match parent.kind() {
+            "export_statement" => return Some("export".to_string()),
+            "class_body" | "interface_body" | "object_type" => return Some("public".to_string()),
+            "program" | "statement_block" => return Some("private".to_string()),
+            _ => current = parent.parent(),
+        }
+    }
+    None
+}
+
+/// Build the signature for a function-valued binding (`const f = async (a: A) => {}`,
+/// `handle = function (e) {}` class fields) from the binding's name node.
+///
+/// The signature runs from the declaration keyword (or field modifiers) up to the
+/// function body, with whitespace collapsed and the trailing `=>` dropped. Returns
+/// `None` when the bound value is not an arrow function or function expression.
+pub fn function_value_signature(name_node: tree_sitter::Node, source: &str) -> Option<String> {
+    let binding = name_node.parent()?;
+    let value = binding.child_by_field_name("value")?;
+    if !matches!(value.kind(), "arrow_function" | "function_expression") {
+        return None;
+    }
+    let body_start = value.child_by_field_name("body")?.start_byte();
+
+    let head = match binding.kind() {
+        "variable_declarator" => {
+            let declaration = binding.parent()?;
+            let keyword = declaration.child(0)?;
+            let declarator = source.get(binding.start_byte()..body_start)?;
+            format!("{} {}", node_text_owned(keyword, source), declarator)
+        }
+        _ => source.get(binding.start_byte()..body_start)?.to_string(),
+    };
+    let signature = head.split_whitespace().collect::<Vec<_>>().join(" ");
+    let signature = signature.trim_end_matches("=>").trim_end();
+    (!signature.is_empty()).then(|| signature.to_string())
+}
+
 /// Extract TypeScript/JavaScript imports and require() calls.
 pub fn extract_imports(
     _tree: &tree_sitter::Tree,
@@ -275,7 +346,9 @@ fn extract_quoted(input: &str) -> Option<String> {
 #[cfg(test)]
 mod tests {
     use super::extract_imports;
+    use crate::languages::extract_symbols;
     use crate::parser;
+    use cruxe_core::types::SymbolKind;
     use std::collections::HashSet;
 
     #[test]
@@ -342,4 +415,63 @@ const { Router, Request } = require("express");
         assert!(target_names.contains("Router"), "missing Router");
         assert!(target_names.contains("Request"), "missing Request");
     }
+
+    #[test]
+    fn extract_symbols_treats_function_valued_bindings_as_functions() {
+        let source = r#"
+export const fetchUser = async (id: string): Promise<User> => {
+    return load(id);
+};
+const handler = function (req: Request) {
+    return req;
+};
+let onClose = () => {};
+const retries = 3;
+
+class Widget {
+    handleClick = (event: MouseEvent): void => {};
+}
+"#;
+        let tree = parser::parse_file(source, "typescript").unwrap();
+        let symbols = extract_symbols(&tree, source, "typescript");
+        let find = |name: &str| {
+            let matching: Vec<_> = symbols.iter().filter(|s| s.name == name).collect();
+            assert_eq!(
+                matching.len(),
+                1,
+                "expected one {name} symbol: {matching:?}"
+            );
+            matching[0].clone()
+        };
+
+        let fetch_user = find("fetchUser");
+        assert_eq!(fetch_user.kind, SymbolKind::Function);
+        assert_eq!(
+            fetch_user.signature.as_deref(),
+            Some("const fetchUser = async (id: string): Promise<User>")
+        );
+
+        let handler = find("handler");
+        assert_eq!(handler.kind, SymbolKind::Function);
+        assert_eq!(
+            handler.signature.as_deref(),
+            Some("const handler = function (req: Request)")
+        );
+
+        let on_close = find("onClose");
+        assert_eq!(on_close.kind, SymbolKind::Function);
+        assert_eq!(on_close.signature.as_deref(), Some("let onClose = ()"));
+
+        let retries = find("retries");
+        assert_eq!(retries.kind, SymbolKind::Variable);
+        assert_eq!(retries.signature, None);
+
+        let handle_click = find("handleClick");
+        assert_eq!(handle_click.kind, SymbolKind::Method);
+        assert_eq!(handle_click.qualified_name, "Widget.handleClick");
+        assert_eq!(
+            handle_click.signature.as_deref(),
+            Some("handleClick = (event: MouseEvent): void")
+        );
+    }
 }
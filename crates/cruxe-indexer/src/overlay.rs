@@ -44,7 +44,7 @@ pub fn create_overlay_dir(data_dir: &Path, ref_name: &str) -> Result<PathBuf, St
 /// Create (or open) overlay Tantivy indices for a ref.
 pub fn create_overlay_index_set(data_dir: &Path, ref_name: &str) -> Result<IndexSet, StateError> {
     let dir = create_overlay_dir(data_dir, ref_name)?;
-    IndexSet::open_at(&dir)
+    IndexSet::open_at_matching(&dir, &data_dir.join("base"))
 }
 
 /// List active overlay directories (normalized names).
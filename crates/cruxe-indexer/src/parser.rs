@@ -1,9 +1,32 @@
+use std::sync::atomic::AtomicUsize;
+
 use crate::language_grammars;
 use cruxe_core::error::ParseError;
 use cruxe_core::languages;
 
 /// Parse a source file with tree-sitter and return the syntax tree.
 pub fn parse_file(source: &str, language: &str) -> Result<tree_sitter::Tree, ParseError> {
+    parse_file_with_timeout(source, language, 0)
+}
+
+/// Parse like [`parse_file`], abandoning the parse after `timeout_ms`
+/// milliseconds (`0` = no limit) so one pathological file cannot stall indexing.
+pub fn parse_file_with_timeout(
+    source: &str,
+    language: &str,
+    timeout_ms: u64,
+) -> Result<tree_sitter::Tree, ParseError> {
+    parse_with_limits(source, language, timeout_ms, None)
+}
+
+/// Shared parse path; a set `cancellation` flag halts the parse exactly like
+/// an elapsed timeout, which lets tests trip the limit without a wall clock.
+fn parse_with_limits(
+    source: &str,
+    language: &str,
+    timeout_ms: u64,
+    cancellation: Option<&AtomicUsize>,
+) -> Result<tree_sitter::Tree, ParseError> {
     let mut parser = tree_sitter::Parser::new();
 
     let ts_language = get_language(language)?;
@@ -12,12 +35,22 @@ pub fn parse_file(source: &str, language: &str) -> Result<tree_sitter::Tree, Par
         .map_err(|e| ParseError::GrammarNotAvailable {
             language: format!("{}: {}", language, e),
         })?;
+    parser.set_timeout_micros(timeout_ms.saturating_mul(1_000));
+    // SAFETY: the flag outlives `parser`, which is dropped before returning.
+    unsafe { parser.set_cancellation_flag(cancellation) };
 
-    parser
-        .parse(source, None)
-        .ok_or_else(|| ParseError::TreeSitterFailed {
-            path: format!("<{} source>", language),
-        })
+    parser.parse(source, None).ok_or_else(|| {
+        if timeout_ms > 0 {
+            ParseError::Timeout {
+                language: language.to_string(),
+                timeout_ms,
+            }
+        } else {
+            ParseError::TreeSitterFailed {
+                path: format!("<{} source>", language),
+            }
+        }
+    })
 }
 
 /// Get the tree-sitter language grammar for a given language.
@@ -36,3 +69,23 @@ pub fn is_language_supported(language: &str) -> bool {
 pub fn supported_languages() -> Vec<&'static str> {
     languages::supported_indexable_languages().to_vec()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_file_with_timeout_abandons_slow_parses() {
+        let source = "fn f() { let x = [1, 2, 3]; }\n".repeat(1_000);
+        let expired = AtomicUsize::new(1);
+        let err = parse_with_limits(&source, "rust", 1, Some(&expired)).unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::Timeout { ref language, timeout_ms: 1 } if language == "rust"
+        ));
+
+        let running = AtomicUsize::new(0);
+        assert!(parse_with_limits(&source, "rust", 0, Some(&running)).is_ok());
+        assert!(parse_file_with_timeout(&source, "rust", 0).is_ok());
+    }
+}
@@ -1,6 +1,9 @@
 use crate::{call_extract, import_extract, languages, parser, snippet_extract, symbol_extract};
+use cruxe_core::error::ParseError;
 use cruxe_core::time::now_iso8601;
 use cruxe_core::types::{CallEdge, FileRecord, SnippetRecord, SymbolRecord};
+use std::path::Path;
+use tracing::warn;
 
 #[derive(Debug, Clone)]
 pub struct SourceArtifacts {
@@ -9,6 +12,8 @@ pub struct SourceArtifacts {
     pub call_edges: Vec<CallEdge>,
     pub raw_imports: Vec<import_extract::RawImport>,
     pub parse_error: Option<String>,
+    /// The parse was abandoned at the time budget; `parse_error` holds the detail.
+    pub parse_timed_out: bool,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -20,32 +25,23 @@ pub struct ArtifactBuildInput<'a> {
     pub ref_name: &'a str,
     pub source_layer: Option<&'a str>,
     pub include_imports: bool,
+    /// Syntax-tree depth below which extraction stops descending (`0` = no cap).
+    pub max_syntax_depth: usize,
+    /// Lines of each snippet body kept in the index (`0` = full bodies).
+    pub max_snippet_lines: usize,
 }
 
 /// Build parser-derived artifacts for one file.
 ///
 /// `include_imports` can be disabled in flows that do not need import edges
-/// (e.g. overlay incremental sync currently manages call edges only).
+/// (e.g. overlay incremental sync currently manages call edges only). Parsing
+/// is abandoned after `parse_timeout_ms` (`0` = no limit).
 pub fn build_source_artifacts(
-    content: &str,
-    language: &str,
-    source_path: &str,
-    project_id: &str,
-    ref_name: &str,
-    source_layer: Option<&str>,
-    include_imports: bool,
+    input: ArtifactBuildInput<'_>,
+    parse_timeout_ms: u64,
 ) -> SourceArtifacts {
-    let input = ArtifactBuildInput {
-        content,
-        language,
-        source_path,
-        project_id,
-        ref_name,
-        source_layer,
-        include_imports,
-    };
     build_source_artifacts_with_parser(input, |source, lang| {
-        parser::parse_file(source, lang).map_err(|err| err.to_string())
+        parser::parse_file_with_timeout(source, lang, parse_timeout_ms)
     })
 }
 
@@ -55,7 +51,7 @@ pub fn build_source_artifacts_with_parser<F>(
     mut parse_source: F,
 ) -> SourceArtifacts
 where
-    F: FnMut(&str, &str) -> Result<tree_sitter::Tree, String>,
+    F: FnMut(&str, &str) -> Result<tree_sitter::Tree, ParseError>,
 {
     let ArtifactBuildInput {
         content,
@@ -65,14 +61,28 @@ where
         ref_name,
         source_layer,
         include_imports,
+        max_syntax_depth,
+        max_snippet_lines,
     } = input;
 
+    let mut parse_timed_out = false;
     let (parsed_tree, extracted, raw_imports, parse_error) =
         if parser::is_language_supported(language) {
             match parse_source(content, language) {
                 Ok(tree) => {
-                    let (extracted, diagnostics) =
-                        languages::extract_symbols_with_diagnostics(&tree, content, language);
+                    let (extracted, diagnostics) = languages::extract_symbols_with_diagnostics(
+                        &tree,
+                        content,
+                        language,
+                        max_syntax_depth,
+                    );
+                    if diagnostics.depth_limit_reached {
+                        warn!(
+                            path = source_path,
+                            max_depth = max_syntax_depth,
+                            "syntax tree exceeds max depth; deeper symbols were skipped"
+                        );
+                    }
                     let raw_imports = if include_imports {
                         import_extract::extract_imports(&tree, content, language, source_path)
                     } else {
@@ -84,7 +94,10 @@ where
                 });
                     (Some(tree), extracted, raw_imports, parse_error)
                 }
-                Err(err) => (None, Vec::new(), Vec::new(), Some(err)),
+                Err(err) => {
+                    parse_timed_out = matches!(err, ParseError::Timeout { .. });
+                    (None, Vec::new(), Vec::new(), Some(err.to_string()))
+                }
             }
         } else {
             (None, Vec::new(), Vec::new(), None)
@@ -97,12 +110,13 @@ where
         source_path,
         source_layer,
     );
-    let snippets = snippet_extract::build_snippet_records(
+    let snippets = snippet_extract::build_snippet_records_with_limit(
         &extracted,
         project_id,
         ref_name,
         source_path,
         source_layer,
+        max_snippet_lines,
     );
     let call_edges = parsed_tree.as_ref().map_or_else(Vec::new, |tree| {
         call_extract::extract_call_edges_for_file(
@@ -113,6 +127,7 @@ where
             &symbols,
             project_id,
             ref_name,
+            max_syntax_depth,
         )
     });
 
@@ -122,6 +137,53 @@ where
         call_edges,
         raw_imports,
         parse_error,
+        parse_timed_out,
+    }
+}
+
+/// Source text read from disk for indexing.
+#[derive(Debug, Clone)]
+pub struct SourceText {
+    pub content: String,
+    /// The bytes were not valid UTF-8 and `content` is a lossy decode.
+    pub encoding_warning: bool,
+}
+
+/// Read a source file as UTF-8, falling back to a lossy decode for legacy
+/// encodings (e.g. latin-1) so the file is still extracted and searchable.
+///
+/// Content containing NUL bytes fails with `InvalidData`: that is a binary
+/// file with a source extension, not text in another encoding.
+pub fn read_source_file(path: &Path) -> std::io::Result<SourceText> {
+    decode_source(std::fs::read(path)?, path)
+}
+
+/// Decode raw file bytes the way [`read_source_file`] does, for content that
+/// does not come from the filesystem (e.g. a git blob). `path` is only used
+/// for logging.
+pub fn decode_source(bytes: Vec<u8>, path: &Path) -> std::io::Result<SourceText> {
+    match String::from_utf8(bytes) {
+        Ok(content) => Ok(SourceText {
+            content,
+            encoding_warning: false,
+        }),
+        Err(err) => {
+            let bytes = err.into_bytes();
+            if bytes.contains(&0) {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "file contains NUL bytes",
+                ));
+            }
+            warn!(
+                path = %path.display(),
+                "File is not valid UTF-8; indexing a lossy decode"
+            );
+            Ok(SourceText {
+                content: String::from_utf8_lossy(&bytes).into_owned(),
+                encoding_warning: true,
+            })
+        }
     }
 }
 
@@ -144,5 +206,54 @@ pub fn build_file_record(
         size_bytes: content.len() as u64,
         updated_at: now_iso8601(),
         content_head: Some(content.lines().take(20).collect::<Vec<_>>().join("\n")),
+        encoding_warning: false,
+    }
+}
+
+/// File record for a text-only file (`index.text_only_extensions`).
+///
+/// These files have no symbols to search, so the whole content goes into
+/// `content_head` to make any term in them reachable as a file hit.
+pub fn build_text_only_file_record(
+    project_id: &str,
+    ref_name: &str,
+    path: &str,
+    filename: &str,
+    language: &str,
+    content: &str,
+) -> FileRecord {
+    FileRecord {
+        content_head: Some(content.to_string()),
+        ..build_file_record(project_id, ref_name, path, filename, language, content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_source_file_decodes_invalid_utf8_lossily() {
+        let dir = tempfile::tempdir().unwrap();
+        let utf8 = dir.path().join("utf8.py");
+        std::fs::write(&utf8, "name = \"caf\u{e9}\"\n").unwrap();
+        let source = read_source_file(&utf8).unwrap();
+        assert_eq!(source.content, "name = \"caf\u{e9}\"\n");
+        assert!(!source.encoding_warning);
+
+        // "café" in latin-1: the trailing 0xE9 is not a valid UTF-8 sequence.
+        let latin1 = dir.path().join("latin1.py");
+        std::fs::write(&latin1, b"name = \"caf\xe9\"\ndef greet():\n    pass\n").unwrap();
+        let source = read_source_file(&latin1).unwrap();
+        assert_eq!(
+            source.content,
+            "name = \"caf\u{fffd}\"\ndef greet():\n    pass\n"
+        );
+        assert!(source.encoding_warning);
+
+        let binary = dir.path().join("blob.py");
+        std::fs::write(&binary, b"\xff\x00\x01").unwrap();
+        let err = read_source_file(&binary).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
     }
 }
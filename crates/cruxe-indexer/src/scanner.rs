@@ -1,8 +1,11 @@
 use cruxe_core::constants;
+use cruxe_core::error::StateError;
+use cruxe_core::types::SkipReason;
 use globset::{Glob, GlobSet, GlobSetBuilder};
 use ignore::WalkBuilder;
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
-use std::sync::OnceLock;
+use std::sync::{Arc, Mutex, OnceLock};
 use tracing::{debug, warn};
 
 /// A discovered source file with its detected language.
@@ -11,6 +14,42 @@ pub struct ScannedFile {
     pub path: PathBuf,
     pub relative_path: String,
     pub language: String,
+    /// Matched `index.text_only_extensions`: index content for full-text
+    /// search only, without symbol extraction. `language` is the extension.
+    pub text_only: bool,
+}
+
+/// A file the scanner saw but did not hand to the parser.
+#[derive(Debug, Clone)]
+pub struct SkippedFile {
+    pub relative_path: String,
+    pub reason: SkipReason,
+    pub detail: Option<String>,
+}
+
+/// Result of a scan that also keeps track of skipped files.
+#[derive(Debug, Clone, Default)]
+pub struct ScanReport {
+    pub files: Vec<ScannedFile>,
+    pub skipped: Vec<SkippedFile>,
+    /// Files matched by `index.exclude`; counted only, not listed in `skipped`.
+    pub excluded: usize,
+}
+
+/// Upper bounds on what one scan may collect; `0` disables a bound.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScanLimits {
+    pub max_files: u64,
+    pub max_total_bytes: u64,
+}
+
+impl ScanLimits {
+    pub fn from_config(config: &cruxe_core::config::IndexConfig) -> Self {
+        Self {
+            max_files: config.max_scan_files,
+            max_total_bytes: config.max_scan_bytes,
+        }
+    }
 }
 
 /// Built-in default ignore patterns for binary/generated files.
@@ -19,6 +58,11 @@ const BUILTIN_IGNORE_EXTENSIONS: &[&str] = &[
     ".min.css",
 ];
 
+/// Subset of built-in ignored extensions that are compiled/binary artifacts.
+const BINARY_EXTENSIONS: &[&str] = &[
+    ".exe", ".dll", ".so", ".dylib", ".o", ".a", ".wasm", ".pyc", ".class", ".jar",
+];
+
 const BUILTIN_IGNORE_DIRS: &[&str] = &[
     ".git",
     "node_modules",
@@ -49,12 +93,85 @@ pub fn scan_directory_filtered(
     max_file_size: u64,
     languages: &[String],
 ) -> Vec<ScannedFile> {
+    scan_directory_with_report(
+        repo_root,
+        max_file_size,
+        languages,
+        &[],
+        &[],
+        ScanLimits::default(),
+    )
+    .map(|report| report.files)
+    .unwrap_or_default()
+}
+
+/// Scan like [`scan_directory_filtered`], also reporting every visited file
+/// that was left out and why. Files excluded by `.gitignore`/`.cruxeignore`
+/// are never visited and therefore not reported.
+///
+/// Files without a supported language whose extension is listed in
+/// `text_only_extensions` are included as text-only files regardless of
+/// `languages`. Files matching an `exclude` glob (see [`exclude_globset`]) are
+/// dropped and only counted in [`ScanReport::excluded`].
+///
+/// Symlinks are followed only when their target resolves inside `repo_root`;
+/// links pointing elsewhere are skipped. Each directory is entered at most
+/// once by canonical path, so symlink loops cannot hang the walk. Built-in
+/// ignored directories such as `node_modules` and `target` are pruned without
+/// being entered. Collecting more files or bytes than `limits` allows fails
+/// with [`StateError::ScanLimitExceeded`].
+pub fn scan_directory_with_report(
+    repo_root: &Path,
+    max_file_size: u64,
+    languages: &[String],
+    text_only_extensions: &[String],
+    exclude: &[String],
+    limits: ScanLimits,
+) -> Result<ScanReport, StateError> {
     let mut walker = WalkBuilder::new(repo_root);
+    let visited_dirs = Arc::new(Mutex::new(HashSet::new()));
+    let canonical_root = repo_root
+        .canonicalize()
+        .unwrap_or_else(|_| repo_root.to_path_buf());
     walker
         .hidden(true)
         .git_ignore(true)
         .git_global(false)
-        .git_exclude(false);
+        .git_exclude(false)
+        .follow_links(true)
+        .filter_entry(move |entry| {
+            let is_dir = entry.file_type().is_some_and(|kind| kind.is_dir());
+            if !is_dir && !entry.path_is_symlink() {
+                return true;
+            }
+            if is_dir
+                && entry.depth() > 0
+                && BUILTIN_IGNORE_DIRS
+                    .iter()
+                    .any(|dir| entry.file_name() == *dir)
+            {
+                debug!(path = ?entry.path(), "Pruned built-in ignored directory");
+                return false;
+            }
+            let Ok(canonical) = entry.path().canonicalize() else {
+                return !entry.path_is_symlink();
+            };
+            if !canonical.starts_with(&canonical_root) {
+                warn!(path = ?entry.path(), target = ?canonical, "Skipped symlink pointing outside the repository");
+                return false;
+            }
+            if !is_dir {
+                return true;
+            }
+            let first_visit = visited_dirs
+                .lock()
+                .map(|mut visited| visited.insert(canonical))
+                .unwrap_or(true);
+            if !first_visit {
+                warn!(path = ?entry.path(), "Skipped directory already scanned through another path (symlink loop?)");
+            }
+            first_visit
+        });
 
     // Add .cruxeignore
     let ignore_file = repo_root.join(constants::IGNORE_FILE);
@@ -62,7 +179,16 @@ pub fn scan_directory_filtered(
         walker.add_custom_ignore_filename(constants::IGNORE_FILE);
     }
 
-    let mut files = Vec::new();
+    let mut filter = ScanFilter {
+        repo_root: &repo_root.display().to_string(),
+        max_file_size,
+        languages,
+        text_only_extensions,
+        excluded: exclude_globset(exclude),
+        limits,
+        total_bytes: 0,
+        report: ScanReport::default(),
+    };
 
     for entry in walker.build() {
         let entry = match entry {
@@ -80,43 +206,168 @@ pub fn scan_directory_filtered(
             continue;
         }
 
+        let relative = path
+            .strip_prefix(repo_root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string();
+        filter.consider(path.to_path_buf(), relative, || {
+            std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+        })?;
+    }
+
+    Ok(filter.finish())
+}
+
+/// Scan the files of a git commit instead of the working tree, applying the
+/// same built-in ignores, `exclude` globs, size, language and `limits` checks
+/// as [`scan_directory_with_report`]. `.gitignore` and `.cruxeignore` are not
+/// consulted: a commit only holds tracked files. Each [`ScannedFile::path`] is
+/// the repository-relative path, since there is no file on disk to read.
+pub fn scan_commit_tree(
+    tree: &cruxe_vcs::CommitTree,
+    max_file_size: u64,
+    languages: &[String],
+    text_only_extensions: &[String],
+    exclude: &[String],
+    limits: ScanLimits,
+) -> Result<ScanReport, StateError> {
+    let mut filter = ScanFilter {
+        repo_root: tree.commit(),
+        max_file_size,
+        languages,
+        text_only_extensions,
+        excluded: exclude_globset(exclude),
+        limits,
+        total_bytes: 0,
+        report: ScanReport::default(),
+    };
+    for file in tree.files() {
+        filter.consider(PathBuf::from(&file.path), file.path.clone(), || file.size)?;
+    }
+    Ok(filter.finish())
+}
+
+/// Per-file checks shared by the working-tree and commit-tree scans.
+struct ScanFilter<'a> {
+    /// What is being scanned, for the limit error message.
+    repo_root: &'a str,
+    max_file_size: u64,
+    languages: &'a [String],
+    text_only_extensions: &'a [String],
+    excluded: GlobSet,
+    limits: ScanLimits,
+    total_bytes: u64,
+    report: ScanReport,
+}
+
+impl ScanFilter<'_> {
+    fn consider(
+        &mut self,
+        path: PathBuf,
+        relative: String,
+        size: impl FnOnce() -> u64,
+    ) -> Result<(), StateError> {
+        if is_excluded(&self.excluded, &relative) {
+            debug!(?path, "Skipped by index.exclude");
+            self.report.excluded += 1;
+            return Ok(());
+        }
+
+        let report = &mut self.report;
+        let mut skip = |reason: SkipReason, detail: Option<String>| {
+            report.skipped.push(SkippedFile {
+                relative_path: relative.clone(),
+                reason,
+                detail,
+            });
+        };
+
         // Skip files matching built-in ignore patterns
-        let path_str = path.to_string_lossy();
-        if should_ignore_builtin(&path_str) {
+        let rooted = format!("/{relative}");
+        if should_ignore_builtin(&rooted) {
             debug!(?path, "Skipped by built-in ignore");
-            continue;
+            let reason = if is_binary_extension(&rooted) {
+                SkipReason::Binary
+            } else {
+                SkipReason::Ignored
+            };
+            skip(reason, Some("built-in ignore rule".to_string()));
+            return Ok(());
         }
 
         // Check file size
-        if let Ok(metadata) = std::fs::metadata(path)
-            && metadata.len() > max_file_size
-        {
-            warn!(?path, size = metadata.len(), "Skipped: file too large");
-            continue;
+        let size = size();
+        let max_file_size = self.max_file_size;
+        if size > max_file_size {
+            warn!(?path, size, "Skipped: file too large");
+            skip(
+                SkipReason::TooLarge,
+                Some(format!(
+                    "{size} bytes exceeds index.max_file_size={max_file_size}"
+                )),
+            );
+            return Ok(());
         }
 
         // Detect language
-        if let Some(language) = detect_language(path) {
-            // Filter by configured languages (if non-empty)
-            if !languages.is_empty() && !languages.iter().any(|l| l == &language) {
-                continue;
-            }
-
-            let relative = path
-                .strip_prefix(repo_root)
-                .unwrap_or(path)
-                .to_string_lossy()
-                .to_string();
+        let (language, text_only) = match detect_language(&path) {
+            Some(language) => (language, false),
+            None => match detect_text_only_language(&path, self.text_only_extensions) {
+                Some(language) => (language, true),
+                None => {
+                    skip(SkipReason::UnsupportedLanguage, None);
+                    return Ok(());
+                }
+            },
+        };
+        // Filter by configured languages (if non-empty)
+        let languages = self.languages;
+        if !text_only && !languages.is_empty() && !languages.iter().any(|l| l == &language) {
+            skip(
+                SkipReason::UnsupportedLanguage,
+                Some(format!("{language} is not enabled in index.languages")),
+            );
+            return Ok(());
+        }
 
-            files.push(ScannedFile {
-                path: path.to_path_buf(),
-                relative_path: relative,
-                language,
-            });
+        self.report.files.push(ScannedFile {
+            path,
+            relative_path: relative,
+            language,
+            text_only,
+        });
+        self.total_bytes += size;
+        let limits = self.limits;
+        let exceeded = if limits.max_files > 0 && self.report.files.len() as u64 > limits.max_files
+        {
+            Some(("index.max_scan_files", limits.max_files))
+        } else if limits.max_total_bytes > 0 && self.total_bytes > limits.max_total_bytes {
+            Some(("index.max_scan_bytes", limits.max_total_bytes))
+        } else {
+            None
+        };
+        match exceeded {
+            Some((limit, cap)) => Err(StateError::ScanLimitExceeded {
+                repo_root: self.repo_root.to_string(),
+                limit: limit.to_string(),
+                cap,
+            }),
+            None => Ok(()),
         }
     }
 
-    files
+    fn finish(mut self) -> ScanReport {
+        // Walk order depends on the filesystem; sort so downstream writes are deterministic.
+        self.report
+            .files
+            .sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+        self.report
+    }
+}
+
+fn is_binary_extension(path: &str) -> bool {
+    BINARY_EXTENSIONS.iter().any(|ext| path.ends_with(ext))
 }
 
 fn should_ignore_builtin(path: &str) -> bool {
@@ -153,11 +404,61 @@ fn builtin_ignore_globset() -> &'static GlobSet {
 }
 
 /// Detect programming language from file extension.
+/// Compile `index.exclude` globs. Invalid patterns are logged and ignored so a
+/// typo cannot abort indexing.
+pub fn exclude_globset(patterns: &[String]) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let pattern = pattern.trim().trim_end_matches('/');
+        if pattern.is_empty() {
+            continue;
+        }
+        match Glob::new(pattern) {
+            Ok(glob) => {
+                builder.add(glob);
+            }
+            Err(err) => warn!(pattern, %err, "Ignoring invalid index.exclude pattern"),
+        }
+    }
+    builder.build().unwrap_or_else(|err| {
+        warn!(%err, "Failed to build index.exclude patterns; excluding nothing");
+        GlobSet::empty()
+    })
+}
+
+/// Whether a workspace-relative path, or any directory above it, matches `set`.
+fn is_excluded(set: &GlobSet, relative_path: &str) -> bool {
+    if set.is_empty() {
+        return false;
+    }
+    let normalized = relative_path.replace('\\', "/");
+    Path::new(&normalized)
+        .ancestors()
+        .filter(|ancestor| !ancestor.as_os_str().is_empty())
+        .any(|ancestor| set.is_match(ancestor))
+}
+
 pub fn detect_language(path: &Path) -> Option<String> {
     let ext = path.extension()?.to_str()?;
     cruxe_core::languages::detect_language_from_extension(ext).map(str::to_string)
 }
 
+/// Detect a text-only file from `index.text_only_extensions` (matched
+/// case-insensitively, leading dot optional). Returns the lowercased extension,
+/// which doubles as the file's language label.
+pub fn detect_text_only_language(path: &Path, text_only_extensions: &[String]) -> Option<String> {
+    let ext = path.extension()?.to_str()?;
+    text_only_extensions
+        .iter()
+        .any(|configured| {
+            configured
+                .trim()
+                .trim_start_matches('.')
+                .eq_ignore_ascii_case(ext)
+        })
+        .then(|| ext.to_ascii_lowercase())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -288,6 +589,185 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_scan_report_records_skip_reasons() {
+        let dir = create_temp_project(&[
+            ("src/main.rs", "fn main() {}"),
+            ("README.md", "# readme"),
+            ("src/app.ts", "function app() {}"),
+            ("huge.rs", &"x".repeat(2_048)),
+            ("lib/native.so", "not really elf"),
+            ("web/app.min.js", "var a=1;"),
+        ]);
+
+        let report = scan_directory_with_report(
+            dir.path(),
+            1_024,
+            &["rust".to_string()],
+            &[],
+            &[],
+            ScanLimits::default(),
+        )
+        .unwrap();
+        let reason_for = |path: &str| {
+            report
+                .skipped
+                .iter()
+                .find(|s| s.relative_path == path)
+                .map(|s| s.reason)
+        };
+
+        assert_eq!(report.files.len(), 1);
+        assert_eq!(
+            reason_for("README.md"),
+            Some(SkipReason::UnsupportedLanguage)
+        );
+        assert_eq!(
+            reason_for("src/app.ts"),
+            Some(SkipReason::UnsupportedLanguage)
+        );
+        assert_eq!(reason_for("huge.rs"), Some(SkipReason::TooLarge));
+        assert_eq!(reason_for("lib/native.so"), Some(SkipReason::Binary));
+        assert_eq!(reason_for("web/app.min.js"), Some(SkipReason::Ignored));
+        assert_eq!(reason_for("src/main.rs"), None);
+    }
+
+    #[test]
+    fn test_scan_exclude_globs_drop_and_count_matching_files() {
+        let dir = create_temp_project(&[
+            ("src/main.rs", "fn main() {}"),
+            ("proto/gen/api.rs", "fn api() {}"),
+            ("third_party/lib.rs", "fn lib() {}"),
+            ("src/gen_api.rs", "fn gen() {}"),
+            ("src/keep.rs", "fn keep() {}"),
+        ]);
+        let exclude = vec![
+            "proto".to_string(),
+            "third_party/**".to_string(),
+            "**/gen_*.rs".to_string(),
+            "[invalid".to_string(),
+        ];
+
+        let report = scan_directory_with_report(
+            dir.path(),
+            1_048_576,
+            &[],
+            &[],
+            &exclude,
+            ScanLimits::default(),
+        )
+        .unwrap();
+        let paths: Vec<&str> = report
+            .files
+            .iter()
+            .map(|f| f.relative_path.as_str())
+            .collect();
+
+        assert_eq!(paths, vec!["src/keep.rs", "src/main.rs"]);
+        assert_eq!(report.excluded, 3);
+        assert!(
+            report.skipped.is_empty(),
+            "excluded files are counted, not listed: {:?}",
+            report.skipped
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_follows_in_root_symlinks_without_looping() {
+        let dir = create_temp_project(&[
+            ("src/main.rs", "fn main() {}"),
+            ("shared/util.rs", "fn util() {}"),
+        ]);
+        std::os::unix::fs::symlink(dir.path().join("src"), dir.path().join("src/again")).unwrap();
+        std::os::unix::fs::symlink(dir.path().join("shared"), dir.path().join("src/shared"))
+            .unwrap();
+
+        let files = scan_directory(dir.path(), 1_048_576);
+        let mut paths: Vec<&str> = files.iter().map(|f| f.relative_path.as_str()).collect();
+        paths.sort();
+
+        // Both links stay inside the root, so they are followed: `shared` is
+        // reachable twice but scanned once, and the loop back into `src` is
+        // not entered at all.
+        assert_eq!(paths.len(), 2, "{paths:?}");
+        assert!(paths.contains(&"src/main.rs"));
+        assert!(paths.iter().any(|p| p.ends_with("util.rs")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_skips_symlinks_pointing_outside_root() {
+        let outside = create_temp_project(&[("secret.rs", "fn secret() {}")]);
+        let dir = create_temp_project(&[("src/main.rs", "fn main() {}")]);
+        std::os::unix::fs::symlink(outside.path(), dir.path().join("src/foreign")).unwrap();
+        std::os::unix::fs::symlink(
+            outside.path().join("secret.rs"),
+            dir.path().join("src/linked.rs"),
+        )
+        .unwrap();
+
+        let files = scan_directory(dir.path(), 1_048_576);
+        let paths: Vec<&str> = files.iter().map(|f| f.relative_path.as_str()).collect();
+
+        assert_eq!(paths, vec!["src/main.rs"]);
+    }
+
+    #[test]
+    fn test_scan_prunes_builtin_dirs_without_reporting_their_files() {
+        let dir = create_temp_project(&[
+            ("src/main.rs", "fn main() {}"),
+            ("node_modules/pkg/index.js", "module.exports = {}"),
+            ("web/node_modules/dep/lib.ts", "export {}"),
+        ]);
+
+        let report =
+            scan_directory_with_report(dir.path(), 1_048_576, &[], &[], &[], ScanLimits::default())
+                .unwrap();
+        assert_eq!(report.files.len(), 1);
+        assert!(report.skipped.is_empty(), "{:?}", report.skipped);
+    }
+
+    #[test]
+    fn test_scan_limits_abort_with_exclude_hint() {
+        let dir = create_temp_project(&[
+            ("a.rs", "fn a() {}"),
+            ("b.rs", "fn b() {}"),
+            ("c.rs", "fn c() {}"),
+        ]);
+        let scan = |limits: ScanLimits| {
+            scan_directory_with_report(dir.path(), 1_048_576, &[], &[], &[], limits)
+        };
+
+        let err = scan(ScanLimits {
+            max_files: 2,
+            max_total_bytes: 0,
+        })
+        .unwrap_err();
+        assert!(matches!(
+            &err,
+            StateError::ScanLimitExceeded { limit, cap: 2, .. } if limit == "index.max_scan_files"
+        ));
+        assert!(err.to_string().contains("index.exclude"), "{err}");
+
+        let err = scan(ScanLimits {
+            max_files: 0,
+            max_total_bytes: 10,
+        })
+        .unwrap_err();
+        assert!(matches!(
+            &err,
+            StateError::ScanLimitExceeded { limit, .. } if limit == "index.max_scan_bytes"
+        ));
+
+        let report = scan(ScanLimits {
+            max_files: 3,
+            max_total_bytes: 27,
+        })
+        .unwrap();
+        assert_eq!(report.files.len(), 3);
+    }
+
     #[test]
     fn test_scan_filtered_by_languages() {
         let dir = create_temp_project(&[
@@ -323,6 +803,53 @@ mod tests {
         assert!(files.len() >= 2, "all supported files should be included");
     }
 
+    #[test]
+    fn test_scan_includes_text_only_extensions() {
+        let dir = create_temp_project(&[
+            ("src/main.rs", "fn main() {}"),
+            ("db/001_init.SQL", "CREATE TABLE users (id INTEGER);"),
+            ("deploy/app.yaml", "replicas: 2"),
+            ("notes.md", "# notes"),
+        ]);
+
+        let text_only = vec!["sql".to_string(), ".yaml".to_string()];
+        let report = scan_directory_with_report(
+            dir.path(),
+            1_048_576,
+            &["rust".to_string()],
+            &text_only,
+            &[],
+            ScanLimits::default(),
+        )
+        .unwrap();
+        let mut files: Vec<(&str, &str, bool)> = report
+            .files
+            .iter()
+            .map(|f| (f.relative_path.as_str(), f.language.as_str(), f.text_only))
+            .collect();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![
+                ("db/001_init.SQL", "sql", true),
+                ("deploy/app.yaml", "yaml", true),
+                ("src/main.rs", "rust", false),
+            ]
+        );
+        assert!(
+            report
+                .skipped
+                .iter()
+                .any(|s| s.relative_path == "notes.md"
+                    && s.reason == SkipReason::UnsupportedLanguage)
+        );
+
+        // Without the setting these files stay unsupported.
+        let files = scan_directory(dir.path(), 1_048_576);
+        assert!(files.iter().all(|f| !f.text_only));
+        assert_eq!(files.len(), 1);
+    }
+
     #[test]
     fn test_detect_language() {
         assert_eq!(detect_language(Path::new("foo.rs")), Some("rust".into()));
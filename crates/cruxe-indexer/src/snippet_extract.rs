@@ -2,13 +2,34 @@ use crate::languages::ExtractedSymbol;
 use cruxe_core::types::SnippetRecord;
 
 /// Build SnippetRecords from extracted symbols.
-/// Each function/method/class body becomes a snippet.
+/// Each function/method/class body becomes a snippet, capped at
+/// [`DEFAULT_MAX_SNIPPET_LINES`](cruxe_core::constants::DEFAULT_MAX_SNIPPET_LINES) lines.
 pub fn build_snippet_records(
     extracted: &[ExtractedSymbol],
     repo: &str,
     r#ref: &str,
     path: &str,
     commit: Option<&str>,
+) -> Vec<SnippetRecord> {
+    build_snippet_records_with_limit(
+        extracted,
+        repo,
+        r#ref,
+        path,
+        commit,
+        cruxe_core::constants::DEFAULT_MAX_SNIPPET_LINES,
+    )
+}
+
+/// [`build_snippet_records`] storing at most `max_lines` lines of each body
+/// (`0` = no cap). `line_start`/`line_end` always describe the full body.
+pub fn build_snippet_records_with_limit(
+    extracted: &[ExtractedSymbol],
+    repo: &str,
+    r#ref: &str,
+    path: &str,
+    commit: Option<&str>,
+    max_lines: usize,
 ) -> Vec<SnippetRecord> {
     extracted
         .iter()
@@ -39,8 +60,95 @@ pub fn build_snippet_records(
                 imports: None,
                 line_start: sym.line_start,
                 line_end: sym.line_end,
-                content: body.clone(),
+                content: truncate_lines(body, max_lines, &sym.language),
             })
         })
         .collect()
 }
+
+/// Keep the first `max_lines` lines of `body` and replace the rest with a
+/// comment-style marker counting the dropped lines.
+fn truncate_lines(body: &str, max_lines: usize, language: &str) -> String {
+    if max_lines == 0 {
+        return body.to_string();
+    }
+    let total = body.lines().count();
+    if total <= max_lines {
+        return body.to_string();
+    }
+    let kept_end = body
+        .match_indices('\n')
+        .nth(max_lines - 1)
+        .map_or(body.len(), |(idx, _)| idx + 1);
+    let comment = match language {
+        "python" | "ruby" => "#",
+        _ => "//",
+    };
+    format!(
+        "{}{comment} ... truncated ({} lines) ...",
+        &body[..kept_end],
+        total - max_lines
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cruxe_core::types::SymbolKind;
+
+    fn function_symbol(body: String, language: &str) -> ExtractedSymbol {
+        let line_end = body.lines().count() as u32;
+        ExtractedSymbol {
+            name: "generated".to_string(),
+            qualified_name: "generated".to_string(),
+            kind: SymbolKind::Function,
+            language: language.to_string(),
+            signature: None,
+            line_start: 1,
+            line_end,
+            byte_start: 0,
+            byte_end: body.len() as u32,
+            visibility: None,
+            parent_name: None,
+            body: Some(body),
+            attributes: Vec::new(),
+            doc_comment: None,
+            parsed_signature: None,
+        }
+    }
+
+    #[test]
+    fn long_bodies_are_truncated_at_a_line_boundary() {
+        let body = (1..=10)
+            .map(|n| format!("    let v{n} = {n};"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let symbols = [function_symbol(body, "rust")];
+
+        let snippets = build_snippet_records_with_limit(&symbols, "r", "main", "a.rs", None, 4);
+        assert_eq!(
+            snippets[0].content,
+            "    let v1 = 1;\n    let v2 = 2;\n    let v3 = 3;\n    let v4 = 4;\n// ... truncated (6 lines) ..."
+        );
+        assert_eq!(snippets[0].line_start, 1);
+        assert_eq!(snippets[0].line_end, 10);
+
+        let full = build_snippet_records_with_limit(&symbols, "r", "main", "a.rs", None, 0);
+        assert_eq!(full[0].content.lines().count(), 10);
+    }
+
+    #[test]
+    fn bodies_within_the_cap_are_stored_verbatim() {
+        let body = "def f():\n    return 1\n".to_string();
+        let symbols = [function_symbol(body.clone(), "python")];
+
+        let snippets = build_snippet_records_with_limit(&symbols, "r", "main", "a.py", None, 2);
+        assert_eq!(snippets[0].content, body);
+
+        let truncated = build_snippet_records_with_limit(&symbols, "r", "main", "a.py", None, 1);
+        assert_eq!(
+            truncated[0].content,
+            "def f():\n# ... truncated (1 lines) ..."
+        );
+    }
+}
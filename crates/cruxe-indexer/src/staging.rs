@@ -23,7 +23,7 @@ pub fn staging_dir(data_dir: &Path, sync_id: &str) -> PathBuf {
 pub fn create_staging_index_set(data_dir: &Path, sync_id: &str) -> Result<IndexSet, StateError> {
     let dir = staging_dir(data_dir, sync_id);
     std::fs::create_dir_all(&dir)?;
-    IndexSet::open_at(&dir)
+    IndexSet::open_at_matching(&dir, &data_dir.join("base"))
 }
 
 /// Atomically publish staging as overlay for `ref_name` via directory rename.
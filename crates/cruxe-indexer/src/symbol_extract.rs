@@ -1,7 +1,16 @@
+use std::collections::HashMap;
+
 use crate::languages::ExtractedSymbol;
-use cruxe_core::types::{SymbolRecord, compute_symbol_id, compute_symbol_stable_id};
+use cruxe_core::types::{SymbolKind, SymbolRecord, compute_symbol_id, compute_symbol_stable_id};
 
 /// Build SymbolRecords from extracted symbols.
+///
+/// Overlapping tree-sitter matches can report the same definition twice (e.g. a
+/// method inside `mod bar { impl Foo { .. } }` matched both through the impl and
+/// as a bare `function_item`). Symbols sharing `(byte_start, byte_end, name,
+/// kind)` within the file are collapsed to the one with the richest
+/// `qualified_name`; distinct definitions that merely share a line span, such
+/// as one-line macros, are kept.
 pub fn build_symbol_records(
     extracted: &[ExtractedSymbol],
     repo: &str,
@@ -9,9 +18,10 @@ pub fn build_symbol_records(
     path: &str,
     commit: Option<&str>,
 ) -> Vec<SymbolRecord> {
+    let extracted = dedup_overlapping_symbols(extracted);
     extracted
         .iter()
-        .map(|sym| {
+        .map(|&sym| {
             let symbol_id =
                 compute_symbol_id(repo, r#ref, path, &sym.kind, sym.line_start, &sym.name);
             let symbol_stable_id = compute_symbol_stable_id(
@@ -52,10 +62,130 @@ pub fn build_symbol_records(
                 signature: sym.signature.clone(),
                 line_start: sym.line_start,
                 line_end: sym.line_end,
+                byte_start: Some(sym.byte_start),
+                byte_end: Some(sym.byte_end),
                 parent_symbol_id,
                 visibility: sym.visibility.clone(),
                 content: sym.body.clone(),
+                attributes: sym.attributes.clone(),
+                doc_comment: sym.doc_comment.clone(),
+                parsed_signature: sym.parsed_signature.clone(),
             }
         })
         .collect()
 }
+
+/// Drop duplicate matches of one definition, keeping first-seen order.
+fn dedup_overlapping_symbols(extracted: &[ExtractedSymbol]) -> Vec<&ExtractedSymbol> {
+    let mut kept: Vec<&ExtractedSymbol> = Vec::with_capacity(extracted.len());
+    let mut slots: HashMap<(u32, u32, &str, SymbolKind), usize> = HashMap::new();
+    for sym in extracted {
+        let key = (sym.byte_start, sym.byte_end, sym.name.as_str(), sym.kind);
+        match slots.get(&key) {
+            Some(&slot) => {
+                if qualified_name_richness(sym) > qualified_name_richness(kept[slot]) {
+                    kept[slot] = sym;
+                }
+            }
+            None => {
+                slots.insert(key, kept.len());
+                kept.push(sym);
+            }
+        }
+    }
+    kept
+}
+
+/// Rank qualified names by path depth, then length: `bar::Foo::run` beats `run`.
+fn qualified_name_richness(sym: &ExtractedSymbol) -> (usize, usize) {
+    let segments = sym
+        .qualified_name
+        .split([':', '.'])
+        .filter(|segment| !segment.is_empty())
+        .count();
+    (segments, sym.qualified_name.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::languages::extract_symbols;
+    use crate::parser::parse_file;
+
+    #[test]
+    fn nested_module_impl_methods_are_recorded_once() {
+        let source = r#"
+mod outer {
+    mod bar {
+        pub struct Foo;
+
+        impl Foo {
+            pub fn run(&self) -> u32 {
+                1
+            }
+
+            fn stop(&self) {}
+        }
+
+        impl Drop for Foo {
+            fn drop(&mut self) {}
+        }
+    }
+}
+"#;
+        let tree = parse_file(source, "rust").expect("parse rust");
+        let mut extracted = extract_symbols(&tree, source, "rust");
+        let unique = extracted.len();
+        let run_qualified = extracted
+            .iter()
+            .find(|sym| sym.name == "run")
+            .map(|sym| sym.qualified_name.clone())
+            .expect("run extracted");
+        assert_ne!(run_qualified, "run");
+
+        // Re-report every method the way a bare `function_item` match sees it:
+        // same byte range, name and kind, but without the module/impl path.
+        let bare_matches: Vec<ExtractedSymbol> = extracted
+            .iter()
+            .filter(|sym| sym.kind == SymbolKind::Method)
+            .map(|sym| ExtractedSymbol {
+                qualified_name: sym.name.clone(),
+                ..sym.clone()
+            })
+            .collect();
+        assert_eq!(bare_matches.len(), 3, "{extracted:?}");
+        extracted.splice(0..0, bare_matches);
+        assert_eq!(extracted.len(), unique + 3);
+
+        let records = build_symbol_records(&extracted, "repo", "main", "src/lib.rs", None);
+        assert_eq!(records.len(), unique, "dedup drops the overlapping matches");
+
+        for method in ["run", "stop", "drop"] {
+            let matches: Vec<_> = records.iter().filter(|r| r.name == method).collect();
+            assert_eq!(matches.len(), 1, "{method} recorded once: {matches:?}");
+            assert_eq!(matches[0].kind, SymbolKind::Method);
+            assert!(matches[0].parent_symbol_id.is_some());
+        }
+        let run = records.iter().find(|r| r.name == "run").unwrap();
+        assert_eq!(run.qualified_name, run_qualified, "richest match wins");
+    }
+
+    #[test]
+    fn distinct_symbols_on_the_same_lines_are_kept() {
+        let source = "const A: u32 = 1; const B: u32 = 2;\n";
+        let tree = parse_file(source, "rust").expect("parse rust");
+        let extracted = extract_symbols(&tree, source, "rust");
+        let records = build_symbol_records(&extracted, "repo", "main", "src/lib.rs", None);
+        assert_eq!(records.len(), 2);
+    }
+
+    #[test]
+    fn same_line_definitions_with_distinct_byte_ranges_are_kept() {
+        let source = "macro_rules! m { () => {} } macro_rules! m { ($x:expr) => { $x } }\n";
+        let tree = parse_file(source, "rust").expect("parse rust");
+        let extracted = extract_symbols(&tree, source, "rust");
+        assert_eq!(extracted.len(), 2, "{extracted:?}");
+        let records = build_symbol_records(&extracted, "repo", "main", "src/lib.rs", None);
+        assert_eq!(records.len(), 2);
+    }
+}
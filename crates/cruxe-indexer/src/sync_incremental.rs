@@ -245,8 +245,14 @@ pub fn create_sync_job(
         files_scanned: 0,
         files_indexed: 0,
         symbols_extracted: 0,
+        files_skipped: 0,
+        files_unchanged: 0,
+        files_excluded: 0,
+        files_per_second: None,
+        index_commits: 0,
         created_at: now.clone(),
         updated_at: now,
+        error_data: None,
     };
     jobs::create_job(conn, &job)?;
     Ok(job_id)
@@ -343,7 +349,7 @@ fn write_actions_to_staging(
     project_id: &str,
     ref_name: &str,
     actions: &[SyncAction],
-    semantic: &SemanticConfig,
+    config: &Config,
 ) -> Result<(usize, usize, Vec<SyncAction>), StateError> {
     write_actions_to_staging_with_parser(
         StagingWriteContext {
@@ -353,9 +359,18 @@ fn write_actions_to_staging(
             project_id,
             ref_name,
             actions,
-            semantic,
+            semantic: &config.search.semantic,
+            text_only_extensions: &config.index.text_only_extensions,
+            max_syntax_depth: config.index.max_syntax_depth,
+            max_snippet_lines: config.index.max_snippet_lines,
+        },
+        |content, language| {
+            parser::parse_file_with_timeout(
+                content,
+                language,
+                config.index.parse_timeout_ms_for(language),
+            )
         },
-        |content, language| parser::parse_file(content, language).map_err(|err| err.to_string()),
     )
 }
 
@@ -367,6 +382,9 @@ struct StagingWriteContext<'a> {
     ref_name: &'a str,
     actions: &'a [SyncAction],
     semantic: &'a SemanticConfig,
+    text_only_extensions: &'a [String],
+    max_syntax_depth: usize,
+    max_snippet_lines: usize,
 }
 
 fn write_actions_to_staging_with_parser<F>(
@@ -374,7 +392,7 @@ fn write_actions_to_staging_with_parser<F>(
     mut parse_changed_file: F,
 ) -> Result<(usize, usize, Vec<SyncAction>), StateError>
 where
-    F: FnMut(&str, &str) -> Result<tree_sitter::Tree, String>,
+    F: FnMut(&str, &str) -> Result<tree_sitter::Tree, cruxe_core::error::ParseError>,
 {
     let StagingWriteContext {
         conn,
@@ -384,9 +402,12 @@ where
         ref_name,
         actions,
         semantic,
+        text_only_extensions,
+        max_syntax_depth,
+        max_snippet_lines,
     } = ctx;
 
-    let batch = writer::BatchWriter::new(index_set)?;
+    let mut batch = writer::BatchWriter::new(index_set)?;
     let mut embedding_writer = embed_writer::EmbeddingWriter::new(semantic, project_id, ref_name)?;
     let mut processed_files = 0usize;
     let mut symbols_written = 0usize;
@@ -436,8 +457,8 @@ where
             SyncAction::Added { .. } | SyncAction::Modified { .. } => {
                 let is_modified = matches!(action, SyncAction::Modified { .. });
                 let full_path = repo_root.join(path);
-                let content = match std::fs::read_to_string(&full_path) {
-                    Ok(c) => c,
+                let source = match prepare::read_source_file(&full_path) {
+                    Ok(source) => source,
                     Err(err) => {
                         return Err(StateError::Io(std::io::Error::new(
                             err.kind(),
@@ -445,12 +466,19 @@ where
                         )));
                     }
                 };
-                let language = match crate::scanner::detect_language(&full_path) {
-                    Some(lang) => lang,
-                    None => {
-                        warn!(path, "Skipping changed file with unsupported language");
-                        continue;
-                    }
+                let content = source.content;
+                let (language, text_only) = match crate::scanner::detect_language(&full_path) {
+                    Some(lang) => (lang, false),
+                    None => match crate::scanner::detect_text_only_language(
+                        &full_path,
+                        text_only_extensions,
+                    ) {
+                        Some(lang) => (lang, true),
+                        None => {
+                            warn!(path, "Skipping changed file with unsupported language");
+                            continue;
+                        }
+                    },
                 };
                 let artifacts = prepare::build_source_artifacts_with_parser(
                     prepare::ArtifactBuildInput {
@@ -461,6 +489,8 @@ where
                         ref_name,
                         source_layer: Some("overlay"),
                         include_imports: false,
+                        max_syntax_depth,
+                        max_snippet_lines,
                     },
                     &mut parse_changed_file,
                 );
@@ -476,15 +506,19 @@ where
                     .file_name()
                     .map(|s| s.to_string_lossy().to_string())
                     .unwrap_or_default();
-                let file = prepare::build_file_record(
-                    project_id, ref_name, path, &filename, &language, &content,
-                );
+                let build_record = if text_only {
+                    prepare::build_text_only_file_record
+                } else {
+                    prepare::build_file_record
+                };
+                let mut file =
+                    build_record(project_id, ref_name, path, &filename, &language, &content);
+                file.encoding_warning = source.encoding_warning;
 
                 if is_modified && embedding_enabled {
                     embedding_writer.delete_for_file_vectors(conn, path)?;
                 }
 
-                cruxe_state::symbols::delete_symbols_for_file(conn, project_id, ref_name, path)?;
                 batch.add_symbols(&index_set.symbols, &artifacts.symbols)?;
                 batch.add_snippets(&index_set.snippets, &artifacts.snippets)?;
                 batch.add_file(&index_set.files, &file)?;
@@ -528,7 +562,7 @@ where
         )?;
     }
 
-    batch.commit()?;
+    batch.commit(conn)?;
     Ok((processed_files, symbols_written, applied_actions))
 }
 
@@ -577,17 +611,16 @@ where
     }
 
     let mut job_id: Option<String> = None;
-    let semantic_config = Config::load(Some(&execution_root))
-        .map(|config| config.search.semantic)
-        .unwrap_or_else(|err| {
-            warn!(
-                project_id = request.project_id,
-                ref_name = request.ref_name,
-                error = %err,
-                "Failed to load semantic config for incremental sync, defaulting to semantic=off"
-            );
-            SemanticConfig::default()
-        });
+    let config = Config::load(Some(&execution_root)).unwrap_or_else(|err| {
+        warn!(
+            project_id = request.project_id,
+            ref_name = request.ref_name,
+            error = %err,
+            "Failed to load config for incremental sync, using defaults (semantic=off)"
+        );
+        Config::default()
+    });
+    let semantic_config = &config.search.semantic;
     let sync_result = (|| -> Result<IncrementalSyncStats, StateError> {
         let head_commit = adapter
             .resolve_head(&execution_root)
@@ -641,13 +674,23 @@ where
             request.project_id,
             request.ref_name,
             &plan.actions,
-            &semantic_config,
+            &config,
         )?;
         apply_tombstones_for_actions(&tx, request.project_id, request.ref_name, &applied_actions)?;
         let total_file_count =
             cruxe_state::manifest::file_count(&tx, request.project_id, request.ref_name)?;
         let total_symbol_count =
             cruxe_state::symbols::symbol_count(&tx, request.project_id, request.ref_name)?;
+        // Same cap as a full index, checked before anything is published so a
+        // runaway branch leaves the previous overlay untouched.
+        let max_symbols = config.index.max_symbols_per_ref;
+        if max_symbols > 0 && total_symbol_count > max_symbols {
+            return Err(StateError::symbol_cap_exceeded(
+                request.ref_name,
+                total_symbol_count,
+                max_symbols,
+            ));
+        }
         let publish = staging::commit_staging_to_overlay(
             request.data_dir,
             request.sync_id,
@@ -719,6 +762,9 @@ where
                     started.elapsed().as_millis() as i64,
                     &err.to_string(),
                 );
+                if let Some(error_data) = jobs::error_data_for(&err) {
+                    let _ = jobs::set_job_error_data(conn, job_id, &error_data);
+                }
             }
             Err(err)
         }
@@ -1011,6 +1057,11 @@ mod tests {
                 parent_symbol_id: None,
                 visibility: Some("pub".to_string()),
                 content: Some("pub fn stale_symbol() {}".to_string()),
+                attributes: Vec::new(),
+                byte_start: None,
+                byte_end: None,
+                doc_comment: None,
+                parsed_signature: None,
             },
         )
         .unwrap();
@@ -1028,8 +1079,15 @@ mod tests {
                     ref_name: "feat/auth",
                     actions: &actions,
                     semantic: &SemanticConfig::default(),
+                    text_only_extensions: &[],
+                    max_syntax_depth: cruxe_core::constants::DEFAULT_MAX_SYNTAX_DEPTH,
+                    max_snippet_lines: cruxe_core::constants::DEFAULT_MAX_SNIPPET_LINES,
+                },
+                |_content, _language| {
+                    Err(cruxe_core::error::ParseError::TreeSitterFailed {
+                        path: "src/lib.rs".to_string(),
+                    })
                 },
-                |_content, _language| Err("synthetic parse failure".to_string()),
             )
             .unwrap();
 
@@ -1055,6 +1113,49 @@ mod tests {
         assert_eq!(manifest_paths, vec!["src/lib.rs".to_string()]);
     }
 
+    #[test]
+    fn write_actions_to_staging_indexes_non_utf8_file_from_lossy_decode() {
+        let tmp = tempdir().unwrap();
+        let repo_root = tmp.path().join("repo");
+        std::fs::create_dir_all(repo_root.join("src")).unwrap();
+        // Latin-1 comment: 0xE9 is not a valid UTF-8 sequence.
+        std::fs::write(
+            repo_root.join("src/legacy.rs"),
+            b"// caf\xe9\npub fn legacy_greeting() {}\n",
+        )
+        .unwrap();
+
+        let conn = db::open_connection(&tmp.path().join("state.db")).unwrap();
+        schema::create_tables(&conn).unwrap();
+        let index_set =
+            cruxe_state::tantivy_index::IndexSet::open(&tmp.path().join("index")).unwrap();
+
+        let actions = vec![SyncAction::Added {
+            path: "src/legacy.rs".to_string(),
+        }];
+        let (processed_files, symbols_written, _) = write_actions_to_staging(
+            &conn,
+            &index_set,
+            &repo_root,
+            "proj-1",
+            "feat/auth",
+            &actions,
+            &Config::default(),
+        )
+        .unwrap();
+
+        assert_eq!(processed_files, 1);
+        assert_eq!(symbols_written, 1);
+        let symbols = cruxe_state::symbols::list_symbols_in_file(
+            &conn,
+            "proj-1",
+            "feat/auth",
+            "src/legacy.rs",
+        )
+        .unwrap();
+        assert_eq!(symbols[0].name, "legacy_greeting");
+    }
+
     #[test]
     fn ensure_no_active_sync_for_ref_rejects_parallel_runs() {
         let tmp = tempdir().unwrap();
@@ -1092,8 +1193,14 @@ mod tests {
             files_scanned: 0,
             files_indexed: 0,
             symbols_extracted: 0,
+            files_skipped: 0,
+            files_unchanged: 0,
+            files_excluded: 0,
+            files_per_second: None,
+            index_commits: 0,
             created_at: "2026-02-25T00:00:00Z".to_string(),
             updated_at: "2026-02-25T00:00:00Z".to_string(),
+            error_data: None,
         };
         jobs::create_job(&conn, &running).unwrap();
 
@@ -1352,6 +1459,11 @@ mod tests {
             parent_symbol_id: None,
             visibility: Some("pub".to_string()),
             content: None,
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
         };
         let b = SymbolRecord {
             repo: "proj-1".to_string(),
@@ -1370,6 +1482,11 @@ mod tests {
             parent_symbol_id: None,
             visibility: Some("pub".to_string()),
             content: None,
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
         };
         cruxe_state::symbols::insert_symbol(&conn, &a).unwrap();
         cruxe_state::symbols::insert_symbol(&conn, &b).unwrap();
@@ -1472,6 +1589,72 @@ mod tests {
         );
     }
 
+    #[test]
+    fn run_incremental_sync_over_symbol_cap_fails_job_with_error_data() {
+        let tmp = tempdir().unwrap();
+        let repo_root = tmp.path().join("repo");
+        let data_dir = tmp.path().join("data");
+        std::fs::create_dir_all(repo_root.join("src")).unwrap();
+        std::fs::create_dir_all(repo_root.join(".cruxe")).unwrap();
+        std::fs::write(
+            repo_root.join(".cruxe/config.toml"),
+            "[index]\nmax_symbols_per_ref = 2\n",
+        )
+        .unwrap();
+        std::fs::write(
+            repo_root.join("src/many.rs"),
+            "pub fn one() {}\npub fn two() {}\npub fn three() {}\n",
+        )
+        .unwrap();
+
+        let mut conn = db::open_connection(&tmp.path().join("state.db")).unwrap();
+        schema::create_tables(&conn).unwrap();
+        insert_project(&conn, "proj-1", &repo_root);
+
+        let adapter = FakeAdapter {
+            merge_base: "base123".to_string(),
+            head: "head999".to_string(),
+            diff: vec![DiffEntry::added("src/many.rs")],
+            ancestor: true,
+        };
+        let err = run_incremental_sync(
+            &adapter,
+            &mut conn,
+            IncrementalSyncRequest {
+                repo_root: &repo_root,
+                data_dir: &data_dir,
+                project_id: "proj-1",
+                ref_name: "feat/auth",
+                base_ref: "main",
+                sync_id: "sync-cap",
+                last_indexed_commit: Some("head998"),
+                is_default_branch: false,
+            },
+        )
+        .unwrap_err();
+
+        assert!(
+            matches!(err, StateError::SymbolCapExceeded { cap: 2, .. }),
+            "{err}"
+        );
+        let job = &jobs::get_recent_jobs(&conn, "proj-1", 1).unwrap()[0];
+        assert_eq!(job.status, "rolled_back");
+        let error_data = job
+            .error_data
+            .as_ref()
+            .expect("cap failure records error_data");
+        assert_eq!(error_data["code"], "symbol_cap_exceeded");
+        assert_eq!(error_data["ref"], "feat/auth");
+        assert_eq!(error_data["symbol_count"], 3);
+        assert_eq!(error_data["max_symbols_per_ref"], 2);
+        assert!(
+            cruxe_state::branch_state::get_branch_state(&conn, "proj-1", "feat/auth")
+                .unwrap()
+                .is_none(),
+            "capped sync must not persist branch state"
+        );
+    }
+
     #[test]
     fn run_incremental_sync_noop_keeps_total_branch_state_counts() {
         let tmp = tempdir().unwrap();
@@ -1693,6 +1876,7 @@ mod tests {
                 size_bytes: content.len() as u64,
                 updated_at: now_iso8601(),
                 content_head: Some(content.lines().take(20).collect::<Vec<_>>().join("\n")),
+                encoding_warning: false,
             };
             writer::write_file_records(&base_index_set, conn, &symbols, &snippets, &record)
                 .unwrap();
@@ -37,7 +37,7 @@ pub fn open_index_set_for_target(
     target: WriteTarget<'_>,
 ) -> Result<IndexSet, StateError> {
     let root = target.index_root(data_dir);
-    IndexSet::open_at(&root)
+    IndexSet::open_at_matching(&root, &WriteTarget::Base.index_root(data_dir))
 }
 
 /// Open an existing index set for the specified write target.
@@ -51,16 +51,28 @@ pub fn open_existing_index_set_for_target(
 
 /// Batch writer that holds a single IndexWriter per index.
 /// Documents are accumulated and committed together for performance.
+///
+/// Manifest rows are held back until the Tantivy commit that covers their
+/// documents, so an interrupted run never records a file as indexed while its
+/// documents are missing; the next run simply re-indexes it.
 pub struct BatchWriter {
     symbol_writer: IndexWriter,
     snippet_writer: IndexWriter,
     file_writer: IndexWriter,
+    /// Manifest rows of files written since the last commit.
+    pending_manifest: Vec<manifest::ManifestEntry>,
+    /// Files per intermediate commit; `0` commits only in [`BatchWriter::commit`].
+    commit_every_files: usize,
+    commits: u64,
 }
 
 impl BatchWriter {
     /// Create a new batch writer. Allocates one IndexWriter per index (50MB buffer each).
     pub fn new(index_set: &IndexSet) -> Result<Self, StateError> {
         Ok(Self {
+            pending_manifest: Vec::new(),
+            commit_every_files: 0,
+            commits: 0,
             symbol_writer: index_set
                 .symbols
                 .writer(50_000_000)
@@ -76,6 +88,13 @@ impl BatchWriter {
         })
     }
 
+    /// Commit every `files` written files (see [`BatchWriter::commit_if_due`]);
+    /// `0` keeps a single commit at the end.
+    pub fn with_commit_every(mut self, files: usize) -> Self {
+        self.commit_every_files = files;
+        self
+    }
+
     /// Delete all stale Tantivy documents for a file before re-indexing.
     /// Uses the `file_key` STRING field (`repo|ref|path`) for efficient `delete_term`.
     pub fn delete_file_docs(&self, index_set: &IndexSet, repo: &str, r#ref: &str, path: &str) {
@@ -151,10 +170,13 @@ impl BatchWriter {
         let f_content = f("content")?;
         let f_line_start = f("line_start")?;
         let f_line_end = f("line_end")?;
+        let f_attributes = schema.get_field("attributes").ok();
+        let f_byte_start = schema.get_field("byte_start").ok();
+        let f_byte_end = schema.get_field("byte_end").ok();
 
         for sym in symbols {
             let key = tantivy_index::file_key(&sym.repo, &sym.r#ref, &sym.path);
-            let doc = doc!(
+            let mut doc = doc!(
                 fk => key.as_str(),
                 f_repo => sym.repo.as_str(),
                 f_ref => sym.r#ref.as_str(),
@@ -173,6 +195,17 @@ impl BatchWriter {
                 f_line_start => sym.line_start as u64,
                 f_line_end => sym.line_end as u64
             );
+            if let Some(f_attributes) = f_attributes {
+                for attribute in &sym.attributes {
+                    doc.add_text(f_attributes, attribute);
+                }
+            }
+            if let (Some(field), Some(byte_start)) = (f_byte_start, sym.byte_start) {
+                doc.add_u64(field, byte_start as u64);
+            }
+            if let (Some(field), Some(byte_end)) = (f_byte_end, sym.byte_end) {
+                doc.add_u64(field, byte_end as u64);
+            }
             self.symbol_writer
                 .add_document(doc)
                 .map_err(StateError::tantivy)?;
@@ -254,32 +287,35 @@ impl BatchWriter {
         Ok(())
     }
 
-    /// Write symbols to SQLite and manifest entry for a file.
+    /// Write a file's symbols (replacing its previous set, see
+    /// [`symbols::replace_symbols_for_file`]) to SQLite. Its manifest entry is
+    /// written by the next commit.
     pub fn write_sqlite(
-        &self,
+        &mut self,
         conn: &Connection,
         symbols: &[SymbolRecord],
         file_record: &FileRecord,
         mtime_ns: Option<i64>,
     ) -> Result<(), StateError> {
-        for sym in symbols {
-            symbols::insert_symbol(conn, sym)?;
-        }
-
-        manifest::upsert_manifest(
+        symbols::replace_symbols_for_file(
             conn,
-            &manifest::ManifestEntry {
-                repo: file_record.repo.clone(),
-                r#ref: file_record.r#ref.clone(),
-                path: file_record.path.clone(),
-                content_hash: file_record.content_hash.clone(),
-                size_bytes: file_record.size_bytes,
-                mtime_ns,
-                language: Some(file_record.language.clone()),
-                indexed_at: now_iso8601(),
-            },
+            &file_record.repo,
+            &file_record.r#ref,
+            &file_record.path,
+            symbols,
         )?;
 
+        self.pending_manifest.push(manifest::ManifestEntry {
+            repo: file_record.repo.clone(),
+            r#ref: file_record.r#ref.clone(),
+            path: file_record.path.clone(),
+            content_hash: file_record.content_hash.clone(),
+            size_bytes: file_record.size_bytes,
+            mtime_ns,
+            language: Some(file_record.language.clone()),
+            indexed_at: now_iso8601(),
+        });
+
         Ok(())
     }
 
@@ -324,14 +360,47 @@ impl BatchWriter {
         self::replace_call_edges_for_files(conn, repo, ref_name, edges_by_file)
     }
 
-    /// Commit all three index writers at once.
-    pub fn commit(mut self) -> Result<(), StateError> {
+    /// Commit once `commit_every_files` files have been written since the
+    /// last commit. Returns whether a commit happened.
+    pub fn commit_if_due(&mut self, conn: &Connection) -> Result<bool, StateError> {
+        if self.commit_every_files == 0 || self.pending_manifest.len() < self.commit_every_files {
+            return Ok(false);
+        }
+        self.commit_pending(conn)?;
+        Ok(true)
+    }
+
+    /// Commit all three index writers, then record the manifest rows of the
+    /// files they now contain.
+    fn commit_pending(&mut self, conn: &Connection) -> Result<(), StateError> {
         self.symbol_writer.commit().map_err(StateError::tantivy)?;
         self.snippet_writer.commit().map_err(StateError::tantivy)?;
         self.file_writer.commit().map_err(StateError::tantivy)?;
-        info!("All indices committed");
+        self.commits += 1;
+
+        if conn.is_autocommit() {
+            let tx = conn.unchecked_transaction().map_err(StateError::sqlite)?;
+            for entry in self.pending_manifest.drain(..) {
+                manifest::upsert_manifest(&tx, &entry)?;
+            }
+            tx.commit().map_err(StateError::sqlite)?;
+        } else {
+            // Incremental sync already holds a transaction around the batch.
+            for entry in self.pending_manifest.drain(..) {
+                manifest::upsert_manifest(conn, &entry)?;
+            }
+        }
+        debug!(commits = self.commits, "Committed index batch");
         Ok(())
     }
+
+    /// Commit whatever is still pending and return how many commits the
+    /// writer made in total.
+    pub fn commit(mut self, conn: &Connection) -> Result<u64, StateError> {
+        self.commit_pending(conn)?;
+        info!(commits = self.commits, "All indices committed");
+        Ok(self.commits)
+    }
 }
 
 /// Write all records for a single file to both Tantivy and SQLite.
@@ -481,6 +550,9 @@ fn write_symbols_to_tantivy(
     let f_line_start = f("line_start")?;
     let f_line_end = f("line_end")?;
     let f_file_key = schema.get_field("file_key").ok();
+    let f_attributes = schema.get_field("attributes").ok();
+    let f_byte_start = schema.get_field("byte_start").ok();
+    let f_byte_end = schema.get_field("byte_end").ok();
 
     let mut writer = index.writer(50_000_000).map_err(StateError::tantivy)?;
 
@@ -507,6 +579,17 @@ fn write_symbols_to_tantivy(
             let key = tantivy_index::file_key(&sym.repo, &sym.r#ref, &sym.path);
             doc.add_text(fk, &key);
         }
+        if let Some(f_attributes) = f_attributes {
+            for attribute in &sym.attributes {
+                doc.add_text(f_attributes, attribute);
+            }
+        }
+        if let (Some(field), Some(byte_start)) = (f_byte_start, sym.byte_start) {
+            doc.add_u64(field, byte_start as u64);
+        }
+        if let (Some(field), Some(byte_end)) = (f_byte_end, sym.byte_end) {
+            doc.add_u64(field, byte_end as u64);
+        }
         writer.add_document(doc).map_err(StateError::tantivy)?;
     }
 
@@ -596,6 +679,7 @@ fn write_file_to_tantivy(index: &tantivy::Index, file: &FileRecord) -> Result<()
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::prepare;
     use tempfile::tempdir;
 
     #[test]
@@ -650,4 +734,105 @@ mod tests {
         )
         .unwrap();
     }
+
+    #[test]
+    fn write_sqlite_keeps_the_row_of_a_symbol_that_only_moved() {
+        let dir = tempdir().unwrap();
+        let index_set = IndexSet::open(dir.path()).unwrap();
+        let conn = cruxe_state::db::open_connection(&dir.path().join("state.db")).unwrap();
+        cruxe_state::schema::create_tables(&conn).unwrap();
+        let mut batch = BatchWriter::new(&index_set).unwrap();
+
+        let mut write = |content: &str| {
+            let artifacts = prepare::build_source_artifacts(
+                prepare::ArtifactBuildInput {
+                    content,
+                    language: "rust",
+                    source_path: "src/lib.rs",
+                    project_id: "proj",
+                    ref_name: "main",
+                    source_layer: None,
+                    include_imports: false,
+                    max_syntax_depth: 0,
+                    max_snippet_lines: 0,
+                },
+                0,
+            );
+            let file =
+                prepare::build_file_record("proj", "main", "src/lib.rs", "lib.rs", "rust", content);
+            batch
+                .write_sqlite(&conn, &artifacts.symbols, &file, None)
+                .unwrap();
+        };
+        let row = |name: &str| -> Vec<(i64, String, u32)> {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT id, symbol_stable_id, line_start FROM symbol_relations
+                     WHERE repo = 'proj' AND \"ref\" = 'main' AND name = ?1",
+                )
+                .unwrap();
+            stmt.query_map([name], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+                .unwrap()
+                .map(Result::unwrap)
+                .collect()
+        };
+
+        write("fn run() -> u32 {\n    1\n}\n\nfn gone() {}\n");
+        let before = row("run");
+        assert_eq!(before.len(), 1);
+
+        write(&format!(
+            "{}fn run() -> u32 {{\n    1\n}}\n",
+            "\n".repeat(10)
+        ));
+        let after = row("run");
+        assert_eq!(after.len(), 1, "moving a symbol must not duplicate its row");
+        assert_eq!(after[0].0, before[0].0, "the row is updated in place");
+        assert_eq!(after[0].1, before[0].1, "stable id ignores line numbers");
+        assert_eq!(after[0].2, before[0].2 + 10);
+        assert!(
+            row("gone").is_empty(),
+            "symbols no longer in the file are removed"
+        );
+    }
+
+    #[test]
+    fn batch_writer_commits_every_n_files_and_defers_manifest_rows() {
+        let dir = tempdir().unwrap();
+        let index_set = IndexSet::open(dir.path()).unwrap();
+        let conn = cruxe_state::db::open_connection(&dir.path().join("state.db")).unwrap();
+        cruxe_state::schema::create_tables(&conn).unwrap();
+        let mut batch = BatchWriter::new(&index_set).unwrap().with_commit_every(2);
+
+        let write = |batch: &mut BatchWriter, name: &str| -> bool {
+            let path = format!("src/{name}.rs");
+            let file = prepare::build_file_record(
+                "proj",
+                "main",
+                &path,
+                &format!("{name}.rs"),
+                "rust",
+                "fn run() {}\n",
+            );
+            batch.add_file(&index_set.files, &file).unwrap();
+            batch.write_sqlite(&conn, &[], &file, None).unwrap();
+            batch.commit_if_due(&conn).unwrap()
+        };
+        let committed_files = || index_set.files.reader().unwrap().searcher().num_docs();
+
+        assert!(!write(&mut batch, "a"));
+        assert_eq!(manifest::file_count(&conn, "proj", "main").unwrap(), 0);
+        assert!(write(&mut batch, "b"), "the second file completes a batch");
+        assert!(!write(&mut batch, "c"));
+        assert_eq!(committed_files(), 2);
+        assert_eq!(
+            manifest::file_count(&conn, "proj", "main").unwrap(),
+            2,
+            "the uncommitted file has no manifest row yet"
+        );
+
+        assert_eq!(batch.commit(&conn).unwrap(), 2);
+        assert_eq!(committed_files(), 3);
+        assert_eq!(manifest::file_count(&conn, "proj", "main").unwrap(), 3);
+    }
 }
@@ -0,0 +1,86 @@
+//! `initialize` handshake payload shared by the stdio and HTTP transports.
+
+use crate::tools;
+use serde_json::{Value, json};
+
+/// Protocol revisions this server speaks, newest first.
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2024-11-05"];
+
+/// Features a transport can actually deliver for the current session.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ServerFeatures {
+    /// Whether `notifications/progress` will be pushed to the client.
+    pub progress_notifications: bool,
+}
+
+/// Pick the protocol version for the session: the client's requested version
+/// when supported, otherwise the newest version this server implements.
+pub fn negotiate_protocol_version(requested: Option<&str>) -> &'static str {
+    requested
+        .and_then(|requested| {
+            SUPPORTED_PROTOCOL_VERSIONS
+                .iter()
+                .copied()
+                .find(|supported| *supported == requested)
+        })
+        .unwrap_or(SUPPORTED_PROTOCOL_VERSIONS[0])
+}
+
+/// Build the `capabilities` object advertised in the `initialize` result.
+///
+/// Only implemented surfaces are listed: `resources` and `prompts` stay absent
+/// until the server handles their methods.
+pub fn build_capabilities(features: ServerFeatures) -> Value {
+    let mut capabilities = serde_json::Map::new();
+    if !tools::list_tools().is_empty() {
+        // The tool set is fixed at compile time.
+        capabilities.insert("tools".into(), json!({ "listChanged": false }));
+    }
+    if features.progress_notifications {
+        capabilities.insert("experimental".into(), json!({ "progress": {} }));
+    }
+    Value::Object(capabilities)
+}
+
+/// Full `initialize` result for a request's `params`.
+pub fn initialize_result(params: &Value, features: ServerFeatures) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    json!({
+        "protocolVersion": negotiate_protocol_version(requested),
+        "capabilities": build_capabilities(features),
+        "serverInfo": {
+            "name": "cruxe",
+            "version": env!("CARGO_PKG_VERSION")
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negotiation_echoes_supported_version_and_falls_back_to_latest() {
+        assert_eq!(negotiate_protocol_version(Some("2024-11-05")), "2024-11-05");
+        assert_eq!(
+            negotiate_protocol_version(Some("1999-01-01")),
+            SUPPORTED_PROTOCOL_VERSIONS[0]
+        );
+        assert_eq!(
+            negotiate_protocol_version(None),
+            SUPPORTED_PROTOCOL_VERSIONS[0]
+        );
+    }
+
+    #[test]
+    fn progress_capability_tracks_transport_features() {
+        let without = build_capabilities(ServerFeatures::default());
+        assert!(without.get("experimental").is_none());
+        assert_eq!(without["tools"]["listChanged"], false);
+
+        let with = build_capabilities(ServerFeatures {
+            progress_notifications: true,
+        });
+        assert!(with.pointer("/experimental/progress").is_some());
+    }
+}
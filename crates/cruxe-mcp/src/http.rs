@@ -2,27 +2,57 @@
 //!
 //! Provides a JSON-RPC over HTTP endpoint that reuses the same tool dispatch
 //! as the stdio transport. Routes:
-//! - `GET /health` — aggregated health/status
-//! - `POST /`      — JSON-RPC MCP handler
+//! - `GET /health`            — aggregated health/status
+//! - `GET /progress/{job_id}`  — Server-Sent Events stream of indexing progress
+//! - `POST /`                  — JSON-RPC MCP handler
+//!
+//! `POST /` negotiates its response format from `Accept`:
+//! - `application/x-ndjson` on a `search_code` call answers with
+//!   newline-delimited JSON (one result object per line, followed by a summary
+//!   line). A ranked page is computed in full before its first line; only
+//!   `export: true` writes lines while the index is being read.
+//! - `text/event-stream` on an `index_repo`/`sync_repo` call streams the job's
+//!   `notifications/progress` as Server-Sent Events, then the JSON-RPC response.
+//! - Anything else acceptable gets one buffered JSON response; an `Accept`
+//!   header that admits none of these formats gets HTTP 406.
+//!
+//! `POST /` also takes `tools/call_batch` requests, which run several tool
+//! calls against one resolved workspace and answer them in order.
+//!
+//! `POST /` accepts an `X-Cruxe-Data-Dir` header (alias `X-CodeCompass-Data-Dir`)
+//! that swaps `storage.data_dir` for that request, so one server can serve
+//! several tenants' indexes. The path must resolve inside
+//! `server.tenant_data_dir_root`; anything else gets HTTP 403.
+//!
+//! `POST /` is rate limited per client IP (`[server.rate_limit]`); clients over
+//! the limit get HTTP 429 with a JSON-RPC error body. `/health` is exempt.
+//!
+//! SIGINT/SIGTERM stops accepting connections, drains in-flight requests and
+//! marks still-active index jobs `interrupted` before the process exits.
 
-use crate::notifications::{NullProgressNotifier, ProgressNotifier};
+use crate::notifications::{BroadcastProgressNotifier, ProgressEvent, ProgressNotifier};
 use crate::protocol::{JsonRpcRequest, JsonRpcResponse};
 use crate::workspace_router::WorkspaceRouter;
-use axum::body::Bytes;
-use axum::extract::State;
-use axum::http::{HeaderMap, StatusCode};
-use axum::response::IntoResponse;
+use axum::body::{Body, Bytes};
+use axum::extract::{ConnectInfo, Path, State};
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
 use axum::routing::{get, post};
 use axum::{Json, Router};
-use cruxe_core::config::Config;
+use cruxe_core::config::{Config, RateLimitConfig};
 use cruxe_core::constants;
 use cruxe_core::error::ProtocolErrorCode;
 use cruxe_core::types::{SchemaStatus, WorkspaceConfig, generate_project_id};
 use serde_json::{Value, json};
-use std::path::PathBuf;
-use std::sync::atomic::{AtomicU8, Ordering};
+use std::collections::HashMap;
+use std::future::IntoFuture;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Component, Path as FsPath, PathBuf};
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
 use tracing::{info, warn};
 
 /// Shared state for the HTTP transport.
@@ -38,10 +68,34 @@ pub struct HttpState {
     pub health_cache: Arc<Mutex<Option<(Instant, Value)>>>,
     pub server_start: Instant,
     pub router: WorkspaceRouter,
+    /// Index progress published by tool calls, relayed by `GET /progress/{job_id}`.
+    pub progress: broadcast::Sender<ProgressEvent>,
 }
 
 const HEALTH_CACHE_TTL: Duration = Duration::from_secs(1);
 
+const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+const EVENT_STREAM_CONTENT_TYPE: &str = "text/event-stream";
+/// Media ranges that can always be answered with a buffered JSON response.
+const JSON_MEDIA_RANGES: [&str; 3] = ["application/json", "application/*", "*/*"];
+/// Lines buffered between the blocking search worker and the response body.
+const NDJSON_CHANNEL_CAPACITY: usize = 64;
+/// Progress events buffered per subscriber before slow ones start lagging.
+const PROGRESS_CHANNEL_CAPACITY: usize = 256;
+/// How long shutdown waits for in-flight requests before closing them.
+const SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(20);
+/// Headers that relocate `storage.data_dir` for one request, in precedence order.
+const DATA_DIR_HEADERS: [&str; 2] = ["x-cruxe-data-dir", "x-codecompass-data-dir"];
+
+/// Per-request scope taken from `POST /` headers.
+#[derive(Debug, Clone, Default)]
+struct HttpRequestScope {
+    session: Option<String>,
+    /// Replacement `storage.data_dir`, already confined to
+    /// `server.tenant_data_dir_root`.
+    data_root: Option<PathBuf>,
+}
+
 /// Start the HTTP transport server on the given bind address and port.
 pub async fn run_http_server(
     workspace: &std::path::Path,
@@ -67,6 +121,11 @@ pub async fn run_http_server(
     }
 
     // Create workspace router
+    let mut workspace_config = workspace_config;
+    crate::workspace_router::merge_configured_roots(
+        &mut workspace_config,
+        &config.server.allowed_workspace_roots,
+    );
     let router = WorkspaceRouter::new(workspace_config, workspace.to_path_buf(), db_path.clone())
         .map_err(|e| format!("workspace config error: {}", e))?;
 
@@ -90,29 +149,269 @@ pub async fn run_http_server(
         workspace: workspace.to_path_buf(),
         project_id,
         data_dir,
-        db_path,
+        db_path: db_path.clone(),
         connection_manager: Arc::new(crate::server::ConnectionManager::new()),
         prewarm_status,
         warmset_enabled: !no_prewarm,
         health_cache: Arc::new(Mutex::new(None)),
         server_start: Instant::now(),
         router,
+        progress: broadcast::channel(PROGRESS_CHANNEL_CAPACITY).0,
     });
 
-    let app = Router::new()
-        .route("/health", get(health_handler))
-        .route("/", post(jsonrpc_handler))
-        .with_state(state);
+    let app = build_router(state);
 
     let addr = format!("{}:{}", bind_addr, port);
     info!("MCP HTTP server listening on {}", addr);
 
     let listener = tokio::net::TcpListener::bind(&addr).await?;
-    axum::serve(listener, app).await?;
+    let report = serve_until_shutdown(listener, app, &db_path, shutdown_signal()).await?;
+    info!(
+        requests_drained = report.requests_drained,
+        interrupted_jobs = report.interrupted_jobs,
+        "MCP HTTP server shut down"
+    );
 
     Ok(())
 }
 
+fn build_router(state: Arc<HttpState>) -> Router {
+    let mut jsonrpc = post(jsonrpc_handler);
+    if let Some(limiter) = RateLimiter::new(&state.config.server.rate_limit) {
+        jsonrpc = jsonrpc.layer(axum::middleware::from_fn_with_state(
+            Arc::new(limiter),
+            rate_limit_jsonrpc,
+        ));
+    }
+    Router::new()
+        .route("/health", get(health_handler))
+        .route("/progress/{job_id}", get(progress_handler))
+        .route("/", jsonrpc)
+        .with_state(state)
+}
+
+/// Per-client token buckets for `POST /`, keyed by remote IP address.
+struct RateLimiter {
+    requests_per_second: f64,
+    burst: f64,
+    buckets: Mutex<HashMap<IpAddr, TokenBucket>>,
+}
+
+struct TokenBucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// Idle buckets are pruned once this many clients are tracked.
+const RATE_LIMIT_PRUNE_THRESHOLD: usize = 1024;
+
+impl RateLimiter {
+    fn new(config: &RateLimitConfig) -> Option<Self> {
+        config.enabled().then(|| Self {
+            requests_per_second: config.requests_per_second,
+            burst: f64::from(config.burst),
+            buckets: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Take one token for `client`, or return how long until one is available.
+    fn try_acquire(&self, client: IpAddr, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        if buckets.len() >= RATE_LIMIT_PRUNE_THRESHOLD {
+            buckets.retain(|_, bucket| self.refill(bucket, now) < self.burst);
+        }
+        let bucket = buckets.entry(client).or_insert(TokenBucket {
+            tokens: self.burst,
+            refilled_at: now,
+        });
+        let tokens = self.refill(bucket, now);
+        if tokens >= 1.0 {
+            bucket.tokens = tokens - 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - tokens) / self.requests_per_second,
+            ))
+        }
+    }
+
+    fn refill(&self, bucket: &mut TokenBucket, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(bucket.refilled_at);
+        bucket.tokens =
+            (bucket.tokens + elapsed.as_secs_f64() * self.requests_per_second).min(self.burst);
+        bucket.refilled_at = now;
+        bucket.tokens
+    }
+}
+
+/// Reject `POST /` with HTTP 429 and a JSON-RPC error once the client's
+/// bucket is empty. Requests without a known peer address pass through.
+async fn rate_limit_jsonrpc(
+    State(limiter): State<Arc<RateLimiter>>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> Response {
+    let client = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    let Some(client) = client else {
+        return next.run(request).await;
+    };
+    match limiter.try_acquire(client, Instant::now()) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => {
+            let retry_after_ms = retry_after.as_millis().max(1) as u64;
+            warn!(%client, retry_after_ms, "Rate limit exceeded for JSON-RPC client");
+            let mut body = JsonRpcResponse::error(
+                None,
+                -32000,
+                "Rate limit exceeded; retry after the indicated delay.".to_string(),
+            );
+            if let Some(error) = body.error.as_mut() {
+                error.data = Some(json!({
+                    "code": ProtocolErrorCode::RateLimited.as_str(),
+                    "retry_after_ms": retry_after_ms,
+                }));
+            }
+            let retry_after_secs = retry_after_ms.div_ceil(1000).to_string();
+            (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, retry_after_secs)],
+                Json(body),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Outcome of a graceful shutdown.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ShutdownReport {
+    /// Requests that were in flight when the signal arrived and ran to completion.
+    requests_drained: usize,
+    /// Active job rows marked `interrupted` on the way out.
+    interrupted_jobs: usize,
+}
+
+/// Serve `app` until `signal` resolves, then stop accepting connections and
+/// drain in-flight requests for up to `SHUTDOWN_DRAIN_TIMEOUT`. Long-lived
+/// responses such as progress streams are cut off once the timeout elapses.
+/// Active job rows are marked `interrupted` before returning.
+async fn serve_until_shutdown(
+    listener: tokio::net::TcpListener,
+    app: Router,
+    db_path: &std::path::Path,
+    signal: impl std::future::Future<Output = ()> + Send + 'static,
+) -> std::io::Result<ShutdownReport> {
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let in_flight_at_signal = Arc::new(AtomicUsize::new(0));
+    let draining = Arc::new(tokio::sync::Notify::new());
+    let app = app.layer(axum::middleware::from_fn_with_state(
+        Arc::clone(&in_flight),
+        track_in_flight,
+    ));
+
+    let serve = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown({
+        let in_flight = Arc::clone(&in_flight);
+        let in_flight_at_signal = Arc::clone(&in_flight_at_signal);
+        let draining = Arc::clone(&draining);
+        async move {
+            signal.await;
+            let pending = in_flight.load(Ordering::Acquire);
+            in_flight_at_signal.store(pending, Ordering::Release);
+            info!(
+                pending,
+                "Shutdown signal received; draining in-flight requests"
+            );
+            draining.notify_one();
+        }
+    })
+    .into_future();
+    let drain_deadline = async {
+        draining.notified().await;
+        tokio::time::sleep(SHUTDOWN_DRAIN_TIMEOUT).await;
+    };
+    tokio::select! {
+        result = serve => result?,
+        () = drain_deadline => {
+            warn!(
+                remaining = in_flight.load(Ordering::Acquire),
+                timeout_secs = SHUTDOWN_DRAIN_TIMEOUT.as_secs(),
+                "Shutdown drain timed out; closing remaining connections"
+            );
+        }
+    }
+
+    let at_signal = in_flight_at_signal.load(Ordering::Acquire);
+    let requests_drained = at_signal.saturating_sub(in_flight.load(Ordering::Acquire));
+    let db_path = db_path.to_path_buf();
+    let interrupted_jobs = tokio::task::spawn_blocking(move || {
+        let conn = cruxe_state::db::open_connection(&db_path).ok()?;
+        cruxe_state::jobs::mark_interrupted_jobs(&conn).ok()
+    })
+    .await
+    .ok()
+    .flatten()
+    .unwrap_or(0);
+
+    Ok(ShutdownReport {
+        requests_drained,
+        interrupted_jobs,
+    })
+}
+
+/// Resolve on SIGINT (Ctrl-C) or, on Unix, SIGTERM.
+async fn shutdown_signal() {
+    let interrupt = async {
+        if let Err(err) = tokio::signal::ctrl_c().await {
+            warn!(error = %err, "Failed to listen for SIGINT");
+            std::future::pending::<()>().await;
+        }
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut stream) => {
+                stream.recv().await;
+            }
+            Err(err) => {
+                warn!(error = %err, "Failed to listen for SIGTERM");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        () = interrupt => {}
+        () = terminate => {}
+    }
+}
+
+/// Count requests between routing and the handler returning its response.
+async fn track_in_flight(
+    State(in_flight): State<Arc<AtomicUsize>>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> Response {
+    struct Guard(Arc<AtomicUsize>);
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            self.0.fetch_sub(1, Ordering::AcqRel);
+        }
+    }
+
+    in_flight.fetch_add(1, Ordering::AcqRel);
+    let _guard = Guard(in_flight);
+    next.run(request).await
+}
+
 /// GET /health — aggregated server health (T224).
 async fn health_handler(State(state): State<Arc<HttpState>>) -> impl IntoResponse {
     let result = tokio::task::spawn_blocking({
@@ -130,6 +429,127 @@ async fn health_handler(State(state): State<Arc<HttpState>>) -> impl IntoRespons
     }
 }
 
+/// GET /progress/{job_id} — live indexing progress as Server-Sent Events.
+///
+/// The stream opens with a `snapshot` event read from the job row, then
+/// relays the job's `begin`/`report`/`end` notifications, each carrying the
+/// row's latest `files_scanned`/`files_indexed`/`symbols_extracted`. It closes
+/// after `end`, or straight after the snapshot if the job already finished.
+async fn progress_handler(
+    State(state): State<Arc<HttpState>>,
+    Path(job_id): Path<String>,
+) -> Response {
+    // Subscribe before reading the row so nothing published in between is lost.
+    let receiver = state.progress.subscribe();
+    let job = match load_job(&state, &job_id).await {
+        Ok(Some(job)) => job,
+        Ok(None) => {
+            let body = json!({
+                "error": {
+                    "code": ProtocolErrorCode::InvalidInput.as_str(),
+                    "message": format!("No index job with id {job_id}."),
+                }
+            });
+            return (StatusCode::NOT_FOUND, Json(body)).into_response();
+        }
+        Err(message) => {
+            let body = json!({
+                "error": {
+                    "code": ProtocolErrorCode::InternalError.as_str(),
+                    "message": message,
+                }
+            });
+            return (StatusCode::INTERNAL_SERVER_ERROR, Json(body)).into_response();
+        }
+    };
+
+    let snapshot = progress_sse_event("snapshot", json!({ "message": job.status }), &job);
+    let stream = ProgressStream {
+        token: progress_token_for_job(&job_id),
+        finished: !is_active_job_status(&job.status),
+        pending: Some(snapshot),
+        state,
+        job_id,
+        receiver,
+    };
+    let events = futures::stream::unfold(stream, |mut stream| async move {
+        if let Some(event) = stream.pending.take() {
+            return Some((Ok::<_, std::convert::Infallible>(event), stream));
+        }
+        if stream.finished {
+            return None;
+        }
+        loop {
+            match stream.receiver.recv().await {
+                Ok(progress) if progress.token == stream.token => {
+                    stream.finished = progress.kind == "end";
+                    let job = load_job(&stream.state, &stream.job_id)
+                        .await
+                        .ok()
+                        .flatten()?;
+                    let event = progress_sse_event(progress.kind, json!(progress), &job);
+                    return Some((Ok(event), stream));
+                }
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+    Sse::new(events)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+struct ProgressStream {
+    state: Arc<HttpState>,
+    job_id: String,
+    token: String,
+    receiver: broadcast::Receiver<ProgressEvent>,
+    pending: Option<Event>,
+    finished: bool,
+}
+
+/// Index tool calls over HTTP publish under the server-generated token.
+fn progress_token_for_job(job_id: &str) -> String {
+    format!("index-job-{job_id}")
+}
+
+fn is_active_job_status(status: &str) -> bool {
+    matches!(status, "queued" | "running" | "validating")
+}
+
+async fn load_job(
+    state: &Arc<HttpState>,
+    job_id: &str,
+) -> Result<Option<cruxe_state::jobs::IndexJob>, String> {
+    let db_path = state.db_path.clone();
+    let job_id = job_id.to_string();
+    tokio::task::spawn_blocking(move || {
+        let conn = cruxe_state::db::open_connection(&db_path).map_err(|e| e.to_string())?;
+        cruxe_state::jobs::get_job(&conn, &job_id).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Merge the job row's counters into an event payload.
+fn progress_sse_event(kind: &str, payload: Value, job: &cruxe_state::jobs::IndexJob) -> Event {
+    let mut data = payload;
+    if let Value::Object(object) = &mut data {
+        object.remove("token");
+        object.insert("kind".to_string(), json!(kind));
+        object.insert("job_id".to_string(), json!(job.job_id));
+        object.insert("status".to_string(), json!(job.status));
+        object.insert("files_scanned".to_string(), json!(job.files_scanned));
+        object.insert("files_indexed".to_string(), json!(job.files_indexed));
+        object.insert(
+            "symbols_extracted".to_string(),
+            json!(job.symbols_extracted),
+        );
+    }
+    Event::default().event(kind).data(data.to_string())
+}
+
 /// POST / — JSON-RPC MCP handler (T225).
 async fn jsonrpc_handler(
     State(state): State<Arc<HttpState>>,
@@ -148,11 +568,29 @@ async fn jsonrpc_handler(
             return (StatusCode::BAD_REQUEST, Json(body)).into_response();
         }
     };
-    let session_scope = session_scope_from_headers(&headers);
+    let data_root = match data_root_from_headers(&state.config, &headers) {
+        Ok(data_root) => data_root,
+        Err(reason) => return data_dir_forbidden_response(request.id, reason),
+    };
+    let scope = HttpRequestScope {
+        session: session_scope_from_headers(&headers),
+        data_root,
+    };
+
+    match negotiate_response_format(&headers, &request) {
+        Some(ResponseFormat::Json) => {}
+        Some(ResponseFormat::Ndjson) => {
+            return ndjson_search_response(state, request, scope);
+        }
+        Some(ResponseFormat::EventStream) => {
+            return progress_event_stream_response(state, request, scope).await;
+        }
+        None => return not_acceptable_response(&headers, request.id),
+    }
 
     let result = tokio::task::spawn_blocking({
         let state = Arc::clone(&state);
-        move || handle_http_request(&state, &request, session_scope.as_deref())
+        move || handle_http_request(&state, &request, &scope)
     })
     .await;
 
@@ -165,6 +603,311 @@ async fn jsonrpc_handler(
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResponseFormat {
+    Json,
+    Ndjson,
+    EventStream,
+}
+
+/// Pick the response format for `request` from the `Accept` header, or `None`
+/// when the client accepts nothing we can produce. A missing header accepts
+/// anything. Streaming formats fall back to JSON for calls that cannot stream.
+fn negotiate_response_format(
+    headers: &HeaderMap,
+    request: &JsonRpcRequest,
+) -> Option<ResponseFormat> {
+    let accepted = accepted_media_types(headers);
+    if accepted.is_empty() {
+        return Some(ResponseFormat::Json);
+    }
+    let accepts = |ty: &str| accepted.iter().any(|media| media == ty);
+    if accepts(NDJSON_CONTENT_TYPE) && is_search_code_call(request) {
+        return Some(ResponseFormat::Ndjson);
+    }
+    if accepts(EVENT_STREAM_CONTENT_TYPE) && is_progress_tool_call(request) {
+        return Some(ResponseFormat::EventStream);
+    }
+    let json_ok = JSON_MEDIA_RANGES
+        .iter()
+        .chain([&NDJSON_CONTENT_TYPE, &EVENT_STREAM_CONTENT_TYPE])
+        .any(|ty| accepts(ty));
+    json_ok.then_some(ResponseFormat::Json)
+}
+
+/// Lowercased media ranges from every `Accept` header, minus those with `q=0`.
+fn accepted_media_types(headers: &HeaderMap) -> Vec<String> {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|media| {
+            let mut parts = media.split(';');
+            let ty = parts.next()?.trim().to_ascii_lowercase();
+            let refused = parts.any(|param| {
+                param
+                    .trim()
+                    .strip_prefix("q=")
+                    .and_then(|q| q.trim().parse::<f32>().ok())
+                    .is_some_and(|q| q <= 0.0)
+            });
+            (!ty.is_empty() && !refused).then_some(ty)
+        })
+        .collect()
+}
+
+fn is_search_code_call(request: &JsonRpcRequest) -> bool {
+    request.method == "tools/call"
+        && request.params.get("name").and_then(Value::as_str) == Some("search_code")
+}
+
+/// Tool calls that start an index job and report its progress.
+fn is_progress_tool_call(request: &JsonRpcRequest) -> bool {
+    request.method == "tools/call"
+        && matches!(
+            request.params.get("name").and_then(Value::as_str),
+            Some("index_repo" | "sync_repo")
+        )
+}
+
+/// HTTP 406 with a JSON-RPC error naming the formats `POST /` can produce.
+fn not_acceptable_response(headers: &HeaderMap, id: Option<Value>) -> Response {
+    let accept = headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut body = JsonRpcResponse::error(
+        id,
+        -32000,
+        "Not acceptable: no supported media type in the Accept header.".to_string(),
+    );
+    if let Some(error) = body.error.as_mut() {
+        error.data = Some(json!({
+            "code": ProtocolErrorCode::InvalidInput.as_str(),
+            "accept": accept,
+            "supported": ["application/json", EVENT_STREAM_CONTENT_TYPE, NDJSON_CONTENT_TYPE],
+        }));
+    }
+    (StatusCode::NOT_ACCEPTABLE, Json(body)).into_response()
+}
+
+/// Resolve the data dir override header, if any. `Err` carries the reason the
+/// request must be refused.
+fn data_root_from_headers(config: &Config, headers: &HeaderMap) -> Result<Option<PathBuf>, String> {
+    let Some(value) = DATA_DIR_HEADERS.iter().find_map(|name| headers.get(*name)) else {
+        return Ok(None);
+    };
+    let requested = value
+        .to_str()
+        .map_err(|_| "Data dir header is not valid UTF-8.".to_string())?
+        .trim();
+    if requested.is_empty() {
+        return Ok(None);
+    }
+    confine_data_root(&config.server.tenant_data_dir_root, requested).map(Some)
+}
+
+/// Resolve `requested` (absolute, or relative to `root`) and require it to stay
+/// inside `root`. `..` is refused outright; the longest existing prefix is
+/// canonicalized so symlinks cannot lead out of the root.
+fn confine_data_root(root: &str, requested: &str) -> Result<PathBuf, String> {
+    if root.is_empty() {
+        return Err(
+            "Data dir overrides are disabled; set server.tenant_data_dir_root to allow them."
+                .to_string(),
+        );
+    }
+    let root = std::fs::canonicalize(root)
+        .map_err(|e| format!("Tenant data dir root {} is unavailable: {}", root, e))?;
+    let requested_path = FsPath::new(requested);
+    if requested_path
+        .components()
+        .any(|component| matches!(component, Component::ParentDir))
+    {
+        return Err(format!("Data dir {} must not contain `..`.", requested));
+    }
+    let resolved = canonicalize_existing_prefix(&root.join(requested_path));
+    if resolved.starts_with(&root) {
+        Ok(resolved)
+    } else {
+        Err(format!(
+            "Data dir {} is outside the allowed root {}.",
+            requested,
+            root.display()
+        ))
+    }
+}
+
+fn canonicalize_existing_prefix(path: &FsPath) -> PathBuf {
+    let mut missing = Vec::new();
+    let mut existing = path;
+    loop {
+        if let Ok(canonical) = std::fs::canonicalize(existing) {
+            return missing
+                .iter()
+                .rev()
+                .fold(canonical, |acc, part| acc.join(part));
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name);
+                existing = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+fn data_dir_forbidden_response(id: Option<Value>, reason: String) -> Response {
+    warn!(reason = %reason, "Rejected JSON-RPC data dir override");
+    let mut body = JsonRpcResponse::error(id, -32000, reason);
+    if let Some(error) = body.error.as_mut() {
+        error.data = Some(json!({
+            "code": ProtocolErrorCode::DataDirNotAllowed.as_str(),
+        }));
+    }
+    (StatusCode::FORBIDDEN, Json(body)).into_response()
+}
+
+/// Run an index tool call and stream its job's progress as SSE `message`
+/// events carrying `notifications/progress`, followed by the JSON-RPC
+/// response once the job ends. Calls that fail before starting a job send
+/// only the response.
+async fn progress_event_stream_response(
+    state: Arc<HttpState>,
+    request: JsonRpcRequest,
+    scope: HttpRequestScope,
+) -> Response {
+    // Subscribe before dispatch so the job's `begin` event is not missed.
+    let receiver = state.progress.subscribe();
+    let client_token = request
+        .params
+        .get("_meta")
+        .and_then(|meta| meta.get("progressToken"))
+        .cloned();
+    let response = tokio::task::spawn_blocking({
+        let state = Arc::clone(&state);
+        move || handle_http_request(&state, &request, &scope)
+    })
+    .await
+    .unwrap_or_else(|e| JsonRpcResponse::error(None, -32603, format!("Internal error: {}", e)));
+
+    Sse::new(progress_notification_stream(
+        receiver,
+        client_token,
+        response,
+    ))
+    .keep_alive(KeepAlive::default())
+    .into_response()
+}
+
+struct ProgressNotificationStream {
+    receiver: broadcast::Receiver<ProgressEvent>,
+    /// Server-generated token the job publishes under; `None` when no job started.
+    job_token: Option<String>,
+    /// Token echoed to the client: its `_meta.progressToken`, else `job_token`.
+    client_token: Value,
+    response: Option<JsonRpcResponse>,
+}
+
+fn progress_notification_stream(
+    receiver: broadcast::Receiver<ProgressEvent>,
+    client_token: Option<Value>,
+    response: JsonRpcResponse,
+) -> impl futures::Stream<Item = Result<Event, std::convert::Infallible>> {
+    let job_token = tool_progress_token(&response);
+    let stream = ProgressNotificationStream {
+        receiver,
+        client_token: client_token.unwrap_or_else(|| json!(job_token.clone().unwrap_or_default())),
+        job_token,
+        response: Some(response),
+    };
+    futures::stream::unfold(stream, |mut stream| async move {
+        if let Some(token) = stream.job_token.clone() {
+            loop {
+                match stream.receiver.recv().await {
+                    Ok(progress) if progress.token == token => {
+                        if progress.kind == "end" {
+                            stream.job_token = None;
+                        }
+                        let mut value = json!(progress);
+                        if let Value::Object(object) = &mut value {
+                            object.remove("token");
+                        }
+                        let notification = json!({
+                            "jsonrpc": "2.0",
+                            "method": "notifications/progress",
+                            "params": {
+                                "progressToken": stream.client_token,
+                                "value": value,
+                            },
+                        });
+                        let event = Event::default().data(notification.to_string());
+                        return Some((Ok(event), stream));
+                    }
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => {
+                        stream.job_token = None;
+                        break;
+                    }
+                }
+            }
+        }
+        let response = stream.response.take()?;
+        let event = Event::default().data(serde_json::to_string(&response).unwrap_or_default());
+        Some((Ok(event), stream))
+    })
+}
+
+/// The `progress_token` an index tool call reported for its job, if it started one.
+fn tool_progress_token(response: &JsonRpcResponse) -> Option<String> {
+    let text = response
+        .result
+        .as_ref()?
+        .get("content")?
+        .get(0)?
+        .get("text")?
+        .as_str()?;
+    let payload: Value = serde_json::from_str(text).ok()?;
+    payload
+        .get("progress_token")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+/// Stream `search_code` results as NDJSON. The search runs on a blocking
+/// worker that hands serialized lines to the response body through a bounded
+/// channel, so a slow client applies backpressure and a disconnected client
+/// stops the worker at the next line.
+fn ndjson_search_response(
+    state: Arc<HttpState>,
+    request: JsonRpcRequest,
+    scope: HttpRequestScope,
+) -> Response {
+    let (tx, rx) = tokio::sync::mpsc::channel::<Bytes>(NDJSON_CHANNEL_CAPACITY);
+    tokio::task::spawn_blocking(move || {
+        stream_http_search(&state, &request, &scope, &mut |line| {
+            let mut bytes = serde_json::to_vec(&line).unwrap_or_default();
+            bytes.push(b'\n');
+            tx.blocking_send(Bytes::from(bytes)).is_ok()
+        });
+    });
+
+    let body = futures::stream::unfold(rx, |mut rx| async move {
+        rx.recv()
+            .await
+            .map(|line| (Ok::<_, std::convert::Infallible>(line), rx))
+    });
+    (
+        [(header::CONTENT_TYPE, NDJSON_CONTENT_TYPE)],
+        Body::from_stream(body),
+    )
+        .into_response()
+}
+
 fn session_scope_from_headers(headers: &HeaderMap) -> Option<String> {
     headers
         .get("mcp-session-id")
@@ -217,8 +960,13 @@ fn build_health_response_uncached(state: &HttpState) -> Value {
         }
     });
     let conn = conn_guard.as_deref();
-    let effective_ref =
-        crate::server::resolve_tool_ref_public(None, &state.workspace, conn, &state.project_id);
+    let effective_ref = crate::server::resolve_tool_ref_public(
+        None,
+        &state.workspace,
+        conn,
+        &state.project_id,
+        &state.config,
+    );
 
     let pw_status = state.prewarm_status.load(Ordering::Acquire);
     let pw_label = crate::server::prewarm_status_label(pw_status);
@@ -236,8 +984,7 @@ fn build_health_response_uncached(state: &HttpState) -> Value {
         );
     }
     let warmset_capacity = crate::server::warmset_capacity();
-    let warmset_members =
-        crate::server::collect_warmset_members(conn, &state.workspace, warmset_capacity);
+    let warmset = crate::server::collect_warmset_members(conn, &state.workspace, warmset_capacity);
 
     // SQLite health
     let (sqlite_ok, sqlite_error) = conn
@@ -291,7 +1038,8 @@ fn build_health_response_uncached(state: &HttpState) -> Value {
         "workspace_warmset": {
             "enabled": state.warmset_enabled,
             "capacity": warmset_capacity,
-            "members": if state.warmset_enabled { warmset_members } else { Vec::<String>::new() },
+            "members": if state.warmset_enabled { warmset.members } else { Vec::<String>::new() },
+            "evicted": if state.warmset_enabled { warmset.evicted } else { Vec::<String>::new() },
         },
     })
 }
@@ -301,32 +1049,73 @@ fn build_health_response_uncached(state: &HttpState) -> Value {
 fn handle_http_request(
     state: &HttpState,
     request: &JsonRpcRequest,
-    session_scope: Option<&str>,
+    scope: &HttpRequestScope,
 ) -> JsonRpcResponse {
+    with_http_dispatch(state, scope, |runtime, transport| {
+        crate::server::execute_transport_request(request, runtime, transport)
+    })
+}
+
+/// Run a `search_code` call over HTTP, handing each NDJSON line to `emit`.
+fn stream_http_search(
+    state: &HttpState,
+    request: &JsonRpcRequest,
+    scope: &HttpRequestScope,
+    emit: &mut dyn FnMut(Value) -> bool,
+) {
+    with_http_dispatch(state, scope, |runtime, transport| {
+        crate::server::execute_transport_search_stream(request, runtime, transport, emit)
+    })
+}
+
+fn with_http_dispatch<R>(
+    state: &HttpState,
+    scope: &HttpRequestScope,
+    run: impl FnOnce(
+        &crate::server::DispatchRuntime<'_>,
+        &crate::server::TransportExecutionContext<'_>,
+    ) -> R,
+) -> R {
+    let tenant_config = scope.data_root.as_ref().map(|root| {
+        let mut config = state.config.clone();
+        config.storage.data_dir = root.to_string_lossy().into_owned();
+        config
+    });
+    let config = tenant_config.as_ref().unwrap_or(&state.config);
+    let data_dir = match &tenant_config {
+        Some(config) => config.project_data_dir(&state.project_id),
+        None => state.data_dir.clone(),
+    };
     let runtime = crate::server::DispatchRuntime {
-        config: &state.config,
+        config,
         router: &state.router,
         workspace: &state.workspace,
         project_id: &state.project_id,
-        data_dir: &state.data_dir,
+        data_dir: &data_dir,
         connection_manager: &state.connection_manager,
         prewarm_status: &state.prewarm_status,
         server_start: &state.server_start,
     };
+    // HTTP has no channel for `notifications/progress`; index jobs publish to
+    // the progress broadcast instead, and the empty token makes them use the
+    // server-generated `index-job-<id>` token that `/progress/{job_id}` follows.
     let transport = crate::server::TransportExecutionContext {
-        notifier: Arc::new(NullProgressNotifier) as Arc<dyn ProgressNotifier>,
-        progress_token: None,
-        session_scope,
+        notifier: Arc::new(BroadcastProgressNotifier::new(state.progress.clone()))
+            as Arc<dyn ProgressNotifier>,
+        progress_notifications: false,
+        progress_token: Some(""),
+        session_scope: scope.session.as_deref(),
         transport_label: "http",
         log_workspace_resolution_failures: true,
         log_degraded_sqlite_open: true,
     };
-    crate::server::execute_transport_request(request, &runtime, &transport)
+    run(&runtime, &transport)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::notifications::NullProgressNotifier;
     use cruxe_core::types::{AllowedRoots, Project, WorkspaceConfig};
     use std::time::Duration;
 
@@ -401,6 +1190,7 @@ mod tests {
                     .collect::<Vec<_>>()
                     .join("\n")
                     .into(),
+                encoding_warning: false,
             };
             writer::write_file_records(&index_set, &conn, &symbols, &snippets, &file_record)
                 .unwrap();
@@ -412,6 +1202,14 @@ mod tests {
         }
     }
 
+    /// Drop the per-call `metadata.request_id` so payloads can be compared.
+    fn without_request_id(mut payload: Value) -> Value {
+        if let Some(metadata) = payload.get_mut("metadata").and_then(Value::as_object_mut) {
+            metadata.remove("request_id");
+        }
+        payload
+    }
+
     fn extract_payload(response: &JsonRpcResponse) -> Value {
         let result = response.result.as_ref().expect("result should be present");
         let content = result
@@ -445,6 +1243,7 @@ mod tests {
             health_cache: Arc::new(Mutex::new(None)),
             server_start: Instant::now(),
             router,
+            progress: broadcast::channel(PROGRESS_CHANNEL_CAPACITY).0,
         }
     }
 
@@ -461,6 +1260,7 @@ mod tests {
         };
         let transport = crate::server::TransportExecutionContext {
             notifier: Arc::new(NullProgressNotifier),
+            progress_notifications: false,
             progress_token: None,
             session_scope: Some("stdio-test"),
             transport_label: "stdio-test",
@@ -498,6 +1298,7 @@ mod tests {
             health_cache: Arc::new(Mutex::new(None)),
             server_start: Instant::now(),
             router,
+            progress: broadcast::channel(PROGRESS_CHANNEL_CAPACITY).0,
         };
 
         let health = build_health_response(&state);
@@ -551,6 +1352,7 @@ mod tests {
             health_cache: Arc::new(Mutex::new(None)),
             server_start: Instant::now(),
             router,
+            progress: broadcast::channel(PROGRESS_CHANNEL_CAPACITY).0,
         };
 
         let request = JsonRpcRequest {
@@ -560,7 +1362,7 @@ mod tests {
             params: json!({}),
         };
 
-        let response = handle_http_request(&state, &request, None);
+        let response = handle_http_request(&state, &request, &HttpRequestScope::default());
         let result = response.result.unwrap();
         let tool_array = result["tools"].as_array().unwrap();
         assert!(!tool_array.is_empty());
@@ -609,6 +1411,7 @@ mod tests {
             health_cache: Arc::new(Mutex::new(None)),
             server_start: Instant::now(),
             router,
+            progress: broadcast::channel(PROGRESS_CHANNEL_CAPACITY).0,
         });
 
         let response = jsonrpc_handler(
@@ -658,7 +1461,7 @@ mod tests {
             method: "unknown/method".into(),
             params: json!({}),
         };
-        let response = handle_http_request(&state, &request, None);
+        let response = handle_http_request(&state, &request, &HttpRequestScope::default());
         let error = response.error.expect("unknown methods should return error");
         assert_eq!(error.code, -32601);
         assert!(error.message.contains("Method not found"));
@@ -696,6 +1499,7 @@ mod tests {
             health_cache: Arc::new(Mutex::new(None)),
             server_start: Instant::now(),
             router,
+            progress: broadcast::channel(PROGRESS_CHANNEL_CAPACITY).0,
         };
 
         let request = JsonRpcRequest {
@@ -710,7 +1514,7 @@ mod tests {
                 }
             }),
         };
-        let response = handle_http_request(&state, &request, None);
+        let response = handle_http_request(&state, &request, &HttpRequestScope::default());
         assert!(
             response.error.is_none(),
             "workspace routing failures are reported as tool-level payload errors"
@@ -818,6 +1622,7 @@ mod tests {
             health_cache: Arc::new(Mutex::new(None)),
             server_start: Instant::now(),
             router,
+            progress: broadcast::channel(PROGRESS_CHANNEL_CAPACITY).0,
         };
 
         let http_health = build_health_response(&state);
@@ -903,6 +1708,7 @@ mod tests {
             health_cache: Arc::new(Mutex::new(None)),
             server_start: Instant::now(),
             router,
+            progress: broadcast::channel(PROGRESS_CHANNEL_CAPACITY).0,
         };
 
         let http_request = JsonRpcRequest {
@@ -914,7 +1720,8 @@ mod tests {
                 "arguments": { "name": "validate_token" }
             }),
         };
-        let http_response = handle_http_request(&state, &http_request, None);
+        let http_response =
+            handle_http_request(&state, &http_request, &HttpRequestScope::default());
         assert!(
             http_response.error.is_none(),
             "http locate_symbol should succeed"
@@ -963,12 +1770,12 @@ mod tests {
             }),
         };
 
-        let http_response = handle_http_request(&state, &request, None);
+        let http_response = handle_http_request(&state, &request, &HttpRequestScope::default());
         let stdio_response = dispatch_stdio_equivalent(&state, &request);
 
         assert_eq!(
-            extract_payload(&http_response),
-            extract_payload(&stdio_response),
+            without_request_id(extract_payload(&http_response)),
+            without_request_id(extract_payload(&stdio_response)),
             "validation failure semantics must remain equivalent across transports"
         );
     }
@@ -993,12 +1800,12 @@ mod tests {
             }),
         };
 
-        let http_response = handle_http_request(&state, &request, None);
+        let http_response = handle_http_request(&state, &request, &HttpRequestScope::default());
         let stdio_response = dispatch_stdio_equivalent(&state, &request);
 
         assert_eq!(
-            extract_payload(&http_response),
-            extract_payload(&stdio_response),
+            without_request_id(extract_payload(&http_response)),
+            without_request_id(extract_payload(&stdio_response)),
             "compatibility failure semantics must remain equivalent across transports"
         );
     }
@@ -1048,8 +1855,14 @@ mod tests {
             files_scanned: 50,
             files_indexed: 20,
             symbols_extracted: 100,
+            files_skipped: 0,
+            files_unchanged: 0,
+            files_excluded: 0,
+            files_per_second: None,
+            index_commits: 0,
             created_at: now.clone(),
             updated_at: now,
+            error_data: None,
         };
         cruxe_state::jobs::create_job(&conn, &active_job).unwrap();
 
@@ -1071,6 +1884,7 @@ mod tests {
             health_cache: Arc::new(Mutex::new(None)),
             server_start: Instant::now(),
             router,
+            progress: broadcast::channel(PROGRESS_CHANNEL_CAPACITY).0,
         };
 
         let health = build_health_response(&state);
@@ -1081,6 +1895,96 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn progress_endpoint_streams_job_events_until_end() {
+        use axum::body::to_bytes;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        config.storage.data_dir = tmp.path().join("cc-data").to_string_lossy().to_string();
+        let state = Arc::new(build_test_state(tmp.path(), config));
+        std::fs::create_dir_all(&state.data_dir).unwrap();
+        let conn = cruxe_state::db::open_connection(&state.db_path).unwrap();
+        cruxe_state::schema::create_tables(&conn).unwrap();
+        cruxe_state::project::create_project(
+            &conn,
+            &Project {
+                project_id: state.project_id.clone(),
+                repo_root: state.workspace.to_string_lossy().to_string(),
+                display_name: None,
+                default_ref: constants::REF_LIVE.to_string(),
+                vcs_mode: false,
+                schema_version: constants::SCHEMA_VERSION,
+                parser_version: constants::PARSER_VERSION,
+                created_at: "2026-02-24T00:00:00Z".to_string(),
+                updated_at: "2026-02-24T00:00:00Z".to_string(),
+            },
+        )
+        .unwrap();
+        cruxe_state::jobs::create_job(
+            &conn,
+            &cruxe_state::jobs::IndexJob {
+                job_id: "job-sse".to_string(),
+                project_id: state.project_id.clone(),
+                r#ref: constants::REF_LIVE.to_string(),
+                mode: "full".to_string(),
+                head_commit: None,
+                sync_id: None,
+                status: "running".to_string(),
+                changed_files: 0,
+                duration_ms: None,
+                error_message: None,
+                retry_count: 0,
+                progress_token: None,
+                files_scanned: 40,
+                files_indexed: 20,
+                symbols_extracted: 90,
+                files_skipped: 0,
+                files_unchanged: 0,
+                files_excluded: 0,
+                files_per_second: None,
+                index_commits: 0,
+                created_at: "2026-02-24T00:00:00Z".to_string(),
+                updated_at: "2026-02-24T00:00:00Z".to_string(),
+                error_data: None,
+            },
+        )
+        .unwrap();
+
+        let missing = progress_handler(State(Arc::clone(&state)), Path("nope".to_string())).await;
+        assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+
+        let response =
+            progress_handler(State(Arc::clone(&state)), Path("job-sse".to_string())).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "text/event-stream"
+        );
+
+        let notifier = BroadcastProgressNotifier::new(state.progress.clone());
+        notifier.emit_progress("index-job-other", "Indexing", "someone else", Some(5));
+        notifier.emit_progress("index-job-job-sse", "Indexing", "Parsing files", Some(50));
+        notifier.emit_end("index-job-job-sse", "Indexing complete", "Done");
+
+        let body = tokio::time::timeout(
+            Duration::from_secs(5),
+            to_bytes(response.into_body(), usize::MAX),
+        )
+        .await
+        .expect("stream should close after the end event")
+        .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        let events: Vec<&str> = body
+            .lines()
+            .filter_map(|line| line.strip_prefix("event: "))
+            .collect();
+        assert_eq!(events, vec!["snapshot", "report", "end"], "{body}");
+        assert!(body.contains("\"files_indexed\":20"), "{body}");
+        assert!(body.contains("Parsing files"), "{body}");
+        assert!(!body.contains("someone else"), "{body}");
+    }
+
     #[tokio::test]
     async fn t232_http_server_reports_port_conflict() {
         use tokio::time::timeout;
@@ -1119,6 +2023,403 @@ mod tests {
         drop(listener);
     }
 
+    fn build_indexed_test_state(root: &std::path::Path) -> HttpState {
+        let workspace = root.join("workspace");
+        std::fs::create_dir_all(&workspace).unwrap();
+
+        let mut config = Config::default();
+        config.storage.data_dir = root.join("cc-data").to_string_lossy().to_string();
+        let state = build_test_state(&workspace, config);
+
+        build_fixture_index_at(&state.data_dir);
+        let conn = cruxe_state::db::open_connection(&state.db_path).unwrap();
+        let now = "2026-02-24T00:00:00Z".to_string();
+        cruxe_state::project::create_project(
+            &conn,
+            &Project {
+                project_id: state.project_id.clone(),
+                repo_root: workspace.to_string_lossy().to_string(),
+                display_name: Some("http-ndjson".to_string()),
+                default_ref: constants::REF_LIVE.to_string(),
+                vcs_mode: false,
+                schema_version: constants::SCHEMA_VERSION,
+                parser_version: constants::PARSER_VERSION,
+                created_at: now.clone(),
+                updated_at: now,
+            },
+        )
+        .unwrap();
+        state
+    }
+
+    async fn post_ndjson(state: HttpState, body: &'static str) -> (Response, Vec<Value>) {
+        use axum::body::to_bytes;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ACCEPT, NDJSON_CONTENT_TYPE.parse().unwrap());
+        let response = jsonrpc_handler(State(Arc::new(state)), headers, Bytes::from(body))
+            .await
+            .into_response();
+        let (parts, body) = response.into_parts();
+        let bytes = to_bytes(body, usize::MAX).await.unwrap();
+        let lines = std::str::from_utf8(&bytes)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("each line should be valid json"))
+            .collect();
+        (Response::from_parts(parts, Body::empty()), lines)
+    }
+
+    #[test]
+    fn accepts_ndjson_matches_media_type_in_accept_list() {
+        let accepts_ndjson = |headers: &HeaderMap| {
+            accepted_media_types(headers)
+                .iter()
+                .any(|media| media == NDJSON_CONTENT_TYPE)
+        };
+        let mut headers = HeaderMap::new();
+        assert!(!accepts_ndjson(&headers));
+        headers.insert(
+            header::ACCEPT,
+            "application/json, application/x-ndjson;q=0.9"
+                .parse()
+                .unwrap(),
+        );
+        assert!(accepts_ndjson(&headers));
+        headers.insert(header::ACCEPT, "application/json".parse().unwrap());
+        assert!(!accepts_ndjson(&headers));
+    }
+
+    #[tokio::test]
+    async fn t481_search_code_streams_ndjson_results_then_summary() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = build_indexed_test_state(tmp.path());
+        let buffered = extract_payload(&handle_http_request(
+            &state,
+            &JsonRpcRequest {
+                jsonrpc: "2.0".into(),
+                id: Some(json!(1)),
+                method: "tools/call".into(),
+                params: json!({
+                    "name": "search_code",
+                    "arguments": { "query": "validate_token", "limit": 5 }
+                }),
+            },
+            &HttpRequestScope::default(),
+        ));
+
+        let (response, lines) = post_ndjson(
+            state,
+            r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"search_code","arguments":{"query":"validate_token","limit":5}}}"#,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response
+                .headers()
+                .get(header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok()),
+            Some(NDJSON_CONTENT_TYPE)
+        );
+
+        let (summary, results) = lines.split_last().expect("stream should not be empty");
+        assert_eq!(summary["type"], "summary");
+        assert!(summary.get("metadata").is_some());
+        assert!(summary.get("results").is_none());
+        assert_eq!(summary["total_candidates"], buffered["total_candidates"]);
+
+        let buffered_results = buffered["results"].as_array().unwrap();
+        assert!(!buffered_results.is_empty());
+        assert_eq!(results.len(), buffered_results.len());
+        for (line, expected) in results.iter().zip(buffered_results) {
+            assert_eq!(line["type"], "result");
+            assert_eq!(&line["result"], expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn t482_search_code_ndjson_reports_validation_error_line() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = build_indexed_test_state(tmp.path());
+
+        let (response, lines) = post_ndjson(
+            state,
+            r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"search_code","arguments":{"query":"  "}}}"#,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0]["type"], "error");
+        assert_eq!(lines[0]["error"]["code"], "invalid_input");
+        assert!(lines[0].get("metadata").is_some());
+    }
+
+    #[tokio::test]
+    async fn t544_search_code_export_streams_every_match_unranked() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = build_indexed_test_state(tmp.path());
+        let buffered = extract_payload(&handle_http_request(
+            &state,
+            &JsonRpcRequest {
+                jsonrpc: "2.0".into(),
+                id: Some(json!(1)),
+                method: "tools/call".into(),
+                params: json!({
+                    "name": "search_code",
+                    "arguments": { "query": "token", "export": true }
+                }),
+            },
+            &HttpRequestScope::default(),
+        ));
+        assert_eq!(buffered["error"]["code"], "invalid_input");
+
+        let (response, lines) = post_ndjson(
+            state,
+            r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"search_code","arguments":{"query":"token","limit":1,"export":true}}}"#,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let (summary, results) = lines.split_last().expect("stream should not be empty");
+        assert_eq!(summary["type"], "summary", "{summary}");
+        assert!(summary.get("metadata").is_some());
+        assert_eq!(summary["export"]["complete"], true);
+        assert_eq!(summary["total_results"], results.len());
+        // `limit` does not cap an export.
+        assert!(results.len() > 1, "{lines:?}");
+        let symbols = results
+            .iter()
+            .filter(|line| line["result"]["result_type"] == "symbol")
+            .count();
+        assert_eq!(summary["export"]["symbols"], symbols);
+        assert!(results.iter().all(|line| line["type"] == "result"));
+    }
+
+    fn tool_call(name: &str) -> JsonRpcRequest {
+        JsonRpcRequest {
+            jsonrpc: "2.0".into(),
+            id: Some(json!(7)),
+            method: "tools/call".into(),
+            params: json!({ "name": name, "arguments": {} }),
+        }
+    }
+
+    #[test]
+    fn negotiate_response_format_honors_accept_and_tool_capabilities() {
+        let negotiate = |accept: Option<&str>, tool: &str| {
+            let mut headers = HeaderMap::new();
+            if let Some(accept) = accept {
+                headers.insert(header::ACCEPT, accept.parse().unwrap());
+            }
+            negotiate_response_format(&headers, &tool_call(tool))
+        };
+        use ResponseFormat::*;
+        assert_eq!(negotiate(None, "index_repo"), Some(Json));
+        assert_eq!(negotiate(Some("*/*"), "search_code"), Some(Json));
+        assert_eq!(
+            negotiate(Some("application/json, text/event-stream"), "sync_repo"),
+            Some(EventStream)
+        );
+        assert_eq!(
+            negotiate(Some("Text/Event-Stream"), "index_repo"),
+            Some(EventStream)
+        );
+        assert_eq!(
+            negotiate(Some("text/event-stream"), "search_code"),
+            Some(Json)
+        );
+        assert_eq!(
+            negotiate(Some("application/x-ndjson"), "search_code"),
+            Some(Ndjson)
+        );
+        assert_eq!(negotiate(Some("text/html"), "search_code"), None);
+        assert_eq!(
+            negotiate(Some("application/json;q=0, text/html"), "index_repo"),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn t525_jsonrpc_rejects_unsupported_accept_with_406() {
+        use axum::body::to_bytes;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let state = build_indexed_test_state(tmp.path());
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ACCEPT, "text/html, image/png".parse().unwrap());
+        let response = jsonrpc_handler(
+            State(Arc::new(state)),
+            headers,
+            Bytes::from(r#"{"jsonrpc":"2.0","id":3,"method":"tools/list","params":{}}"#),
+        )
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::NOT_ACCEPTABLE);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["jsonrpc"], "2.0");
+        assert_eq!(body["id"], 3);
+        assert_eq!(body["error"]["data"]["code"], "invalid_input");
+        assert_eq!(body["error"]["data"]["accept"], "text/html, image/png");
+    }
+
+    #[tokio::test]
+    async fn t531_data_dir_header_is_confined_to_tenant_root() {
+        use axum::body::to_bytes;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let tenant_root = tmp.path().join("tenants");
+        std::fs::create_dir_all(&tenant_root).unwrap();
+        let mut state = build_indexed_test_state(tmp.path());
+        state.config.server.tenant_data_dir_root = tenant_root.to_string_lossy().to_string();
+        // Tenant "acme" holds the indexed fixture; the default data dir is empty.
+        let tenant_data_dir = tenant_root
+            .join("acme")
+            .join("data")
+            .join(&state.project_id);
+        std::fs::create_dir_all(tenant_data_dir.parent().unwrap()).unwrap();
+        std::fs::rename(&state.data_dir, &tenant_data_dir).unwrap();
+        let state = Arc::new(state);
+
+        let post = |data_dir: Option<&str>| {
+            let state = Arc::clone(&state);
+            let mut headers = HeaderMap::new();
+            if let Some(data_dir) = data_dir {
+                headers.insert("x-codecompass-data-dir", data_dir.parse().unwrap());
+            }
+            async move {
+                let response = jsonrpc_handler(
+                    State(state),
+                    headers,
+                    Bytes::from(
+                        r#"{"jsonrpc":"2.0","id":7,"method":"tools/call","params":{"name":"locate_symbol","arguments":{"name":"validate_token"}}}"#,
+                    ),
+                )
+                .await
+                .into_response();
+                let status = response.status();
+                let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                (status, serde_json::from_slice::<Value>(&body).unwrap())
+            }
+        };
+
+        let (status, body) = post(Some("acme")).await;
+        assert_eq!(status, StatusCode::OK);
+        let text = body["result"]["content"][0]["text"].as_str().unwrap();
+        let payload: Value = serde_json::from_str(text).unwrap();
+        assert!(
+            !payload["results"].as_array().unwrap().is_empty(),
+            "tenant data dir should serve the fixture index: {payload}"
+        );
+
+        let outside = tmp.path().join("cc-data");
+        for rejected in ["../cc-data", outside.to_str().unwrap()] {
+            let (status, body) = post(Some(rejected)).await;
+            assert_eq!(status, StatusCode::FORBIDDEN, "{rejected}");
+            assert_eq!(body["id"], 7);
+            assert_eq!(body["error"]["data"]["code"], "data_dir_not_allowed");
+        }
+
+        let unconfigured = build_test_state(tmp.path(), Config::default());
+        let response = jsonrpc_handler(
+            State(Arc::new(unconfigured)),
+            [("x-cruxe-data-dir", "acme")]
+                .into_iter()
+                .map(|(k, v)| (header::HeaderName::from_static(k), v.parse().unwrap()))
+                .collect(),
+            Bytes::from(r#"{"jsonrpc":"2.0","id":8,"method":"tools/list","params":{}}"#),
+        )
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn t536_tools_call_batch_over_http_answers_each_call() {
+        use axum::body::to_bytes;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let state = build_indexed_test_state(tmp.path());
+
+        let response = jsonrpc_handler(
+            State(Arc::new(state)),
+            HeaderMap::new(),
+            Bytes::from(
+                r#"{"jsonrpc":"2.0","id":4,"method":"tools/call_batch","params":{"calls":[{"name":"locate_symbol","arguments":{"name":"validate_token"}},{"name":"missing_tool"},{"name":"locate_symbol","arguments":{"name":"AuthError"}}]}}"#,
+            ),
+        )
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: Value =
+            serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await.unwrap())
+                .unwrap();
+        assert_eq!(body["id"], 4);
+        let results = body["result"]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 3);
+        for (element, name) in [(&results[0], "validate_token"), (&results[2], "AuthError")] {
+            let text = element["result"]["content"][0]["text"].as_str().unwrap();
+            let payload: Value = serde_json::from_str(text).unwrap();
+            assert_eq!(payload["results"][0]["name"], name, "{payload}");
+        }
+        assert_eq!(results[1]["error"]["code"], -32601);
+    }
+
+    #[tokio::test]
+    async fn t526_progress_stream_sends_notifications_then_response() {
+        use futures::StreamExt;
+
+        let (sender, receiver) = broadcast::channel(PROGRESS_CHANNEL_CAPACITY);
+        let notifier = BroadcastProgressNotifier::new(sender.clone());
+        notifier.emit_begin("index-job-j1", "Indexing", "Starting indexer...");
+        notifier.emit_progress("index-job-other", "Indexing", "someone else", Some(5));
+        notifier.emit_progress("index-job-j1", "Indexing", "Parsing files", Some(50));
+        notifier.emit_end("index-job-j1", "Indexing complete", "Done");
+
+        let payload = json!({ "job_id": "j1", "progress_token": "index-job-j1" });
+        let response = JsonRpcResponse::success(
+            Some(json!(9)),
+            json!({ "content": [{ "type": "text", "text": payload.to_string() }] }),
+        );
+        let events: Vec<Event> =
+            progress_notification_stream(receiver, Some(json!("client-token")), response)
+                .map(Result::unwrap)
+                .collect()
+                .await;
+        let body = Sse::new(futures::stream::iter(
+            events.into_iter().map(Ok::<_, std::convert::Infallible>),
+        ))
+        .into_response();
+        let body = axum::body::to_bytes(body.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let messages: Vec<Value> = std::str::from_utf8(&body)
+            .unwrap()
+            .lines()
+            .filter_map(|line| line.strip_prefix("data: "))
+            .map(|data| serde_json::from_str(data).unwrap())
+            .collect();
+        assert_eq!(messages.len(), 4, "{messages:?}");
+        let kinds: Vec<&str> = messages[..3]
+            .iter()
+            .map(|message| {
+                assert_eq!(message["method"], "notifications/progress");
+                assert_eq!(message["params"]["progressToken"], "client-token");
+                message["params"]["value"]["kind"].as_str().unwrap()
+            })
+            .collect();
+        assert_eq!(kinds, vec!["begin", "report", "end"]);
+        assert_eq!(messages[3]["id"], 9);
+        assert!(messages[3]["result"].is_object());
+
+        // A call that never started a job streams only its response.
+        let error = JsonRpcResponse::error(Some(json!(10)), -32603, "boom".to_string());
+        let only: Vec<Event> = progress_notification_stream(sender.subscribe(), None, error)
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(only.len(), 1);
+    }
+
     #[test]
     fn t457_health_endpoint_smoke_guard() {
         let tmp = tempfile::tempdir().unwrap();
@@ -1167,6 +2468,7 @@ mod tests {
             health_cache: Arc::new(Mutex::new(None)),
             server_start: Instant::now(),
             router,
+            progress: broadcast::channel(PROGRESS_CHANNEL_CAPACITY).0,
         };
 
         let mut samples = Vec::new();
@@ -1233,6 +2535,7 @@ mod tests {
             health_cache: Arc::new(Mutex::new(None)),
             server_start: Instant::now(),
             router,
+            progress: broadcast::channel(PROGRESS_CHANNEL_CAPACITY).0,
         };
 
         let mut samples = Vec::new();
@@ -1249,4 +2552,210 @@ mod tests {
             p95.as_millis()
         );
     }
+
+    #[tokio::test]
+    async fn graceful_shutdown_drains_in_flight_requests_and_interrupts_jobs() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let tmp = tempfile::tempdir().unwrap();
+        let db_path = tmp.path().join(constants::STATE_DB_FILE);
+        let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+        cruxe_state::schema::create_tables(&conn).unwrap();
+        cruxe_state::project::create_project(
+            &conn,
+            &Project {
+                project_id: "proj".to_string(),
+                repo_root: tmp.path().to_string_lossy().to_string(),
+                display_name: None,
+                default_ref: constants::REF_LIVE.to_string(),
+                vcs_mode: false,
+                schema_version: 1,
+                parser_version: 1,
+                created_at: "2026-01-01T00:00:00Z".to_string(),
+                updated_at: "2026-01-01T00:00:00Z".to_string(),
+            },
+        )
+        .unwrap();
+        cruxe_state::jobs::create_job(
+            &conn,
+            &cruxe_state::jobs::IndexJob {
+                job_id: "job-shutdown-1".to_string(),
+                project_id: "proj".to_string(),
+                r#ref: constants::REF_LIVE.to_string(),
+                mode: "full".to_string(),
+                head_commit: None,
+                sync_id: None,
+                status: "running".to_string(),
+                changed_files: 0,
+                duration_ms: None,
+                error_message: None,
+                retry_count: 0,
+                progress_token: None,
+                files_scanned: 0,
+                files_indexed: 0,
+                symbols_extracted: 0,
+                files_skipped: 0,
+                files_unchanged: 0,
+                files_excluded: 0,
+                files_per_second: None,
+                index_commits: 0,
+                created_at: "2026-01-01T00:00:00Z".to_string(),
+                updated_at: "2026-01-01T00:00:00Z".to_string(),
+                error_data: None,
+            },
+        )
+        .unwrap();
+
+        let entered = Arc::new(tokio::sync::Notify::new());
+        let release = Arc::new(tokio::sync::Notify::new());
+        let app = Router::new().route(
+            "/slow",
+            get({
+                let entered = Arc::clone(&entered);
+                let release = Arc::clone(&release);
+                move || async move {
+                    entered.notify_one();
+                    release.notified().await;
+                    "done"
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (signal_tx, signal_rx) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn({
+            let db_path = db_path.clone();
+            async move {
+                serve_until_shutdown(listener, app, &db_path, async {
+                    let _ = signal_rx.await;
+                })
+                .await
+            }
+        });
+        let client = tokio::spawn(async move {
+            let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+            stream
+                .write_all(b"GET /slow HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+                .await
+                .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await.unwrap();
+            response
+        });
+
+        entered.notified().await;
+        signal_tx.send(()).unwrap();
+        // Let the shutdown future record the pending request before releasing it.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        release.notify_one();
+
+        let response = client.await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        assert!(response.ends_with("done"), "{response}");
+        let report = server.await.unwrap().unwrap();
+        assert_eq!(
+            report,
+            ShutdownReport {
+                requests_drained: 1,
+                interrupted_jobs: 1,
+            }
+        );
+        let job = cruxe_state::jobs::get_job(&conn, "job-shutdown-1")
+            .unwrap()
+            .unwrap();
+        assert_eq!(job.status, "interrupted");
+    }
+
+    #[test]
+    fn token_bucket_refills_at_the_configured_rate() {
+        let limiter = RateLimiter::new(&RateLimitConfig {
+            requests_per_second: 2.0,
+            burst: 2,
+        })
+        .unwrap();
+        let client: IpAddr = "10.0.0.1".parse().unwrap();
+        let other: IpAddr = "10.0.0.2".parse().unwrap();
+        let start = Instant::now();
+
+        assert!(limiter.try_acquire(client, start).is_ok());
+        assert!(limiter.try_acquire(client, start).is_ok());
+        let retry_after = limiter.try_acquire(client, start).unwrap_err();
+        assert_eq!(retry_after, Duration::from_millis(500));
+        assert!(
+            limiter.try_acquire(other, start).is_ok(),
+            "buckets are per client"
+        );
+        assert!(
+            limiter
+                .try_acquire(client, start + Duration::from_millis(500))
+                .is_ok()
+        );
+        assert!(
+            RateLimiter::new(&RateLimitConfig {
+                requests_per_second: 0.0,
+                burst: 2,
+            })
+            .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn jsonrpc_requests_past_the_rate_limit_get_429_while_health_stays_open() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let tmp = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        config.storage.data_dir = tmp.path().join("cc-data").to_string_lossy().to_string();
+        config.server.rate_limit = RateLimitConfig {
+            requests_per_second: 0.01,
+            burst: 2,
+        };
+        let state = Arc::new(build_test_state(tmp.path(), config));
+        let db_path = state.db_path.clone();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (signal_tx, signal_rx) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(async move {
+            serve_until_shutdown(listener, build_router(state), &db_path, async {
+                let _ = signal_rx.await;
+            })
+            .await
+        });
+
+        let send = |raw: String| async move {
+            let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+            stream.write_all(raw.as_bytes()).await.unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await.unwrap();
+            response
+        };
+        let body = r#"{"jsonrpc":"2.0","id":1,"method":"tools/list","params":{}}"#;
+        let post = format!(
+            "POST / HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        let health =
+            "GET /health HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n".to_string();
+
+        for _ in 0..2 {
+            let response = send(post.clone()).await;
+            assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        }
+        let limited = send(post.clone()).await;
+        assert!(limited.starts_with("HTTP/1.1 429"), "{limited}");
+        assert!(
+            limited.to_ascii_lowercase().contains("retry-after:"),
+            "{limited}"
+        );
+        let (_, limited_body) = limited.split_once("\r\n\r\n").unwrap();
+        let limited_body: Value = serde_json::from_str(limited_body).unwrap();
+        assert_eq!(limited_body["jsonrpc"], "2.0");
+        assert_eq!(limited_body["error"]["data"]["code"], "rate_limited");
+
+        let response = send(health).await;
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+
+        signal_tx.send(()).unwrap();
+        server.await.unwrap().unwrap();
+    }
 }
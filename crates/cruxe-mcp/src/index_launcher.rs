@@ -1,5 +1,13 @@
 use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use cruxe_core::types::JobStatus;
+use tracing::warn;
+
+const REAP_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const SPAWN_MAX_ATTEMPTS: u32 = 3;
+const SPAWN_RETRY_BASE_DELAY: Duration = Duration::from_millis(50);
 
 pub(crate) struct IndexLaunchRequest<'a> {
     pub workspace: &'a Path,
@@ -11,9 +19,59 @@ pub(crate) struct IndexLaunchRequest<'a> {
     pub job_id: Option<&'a str>,
 }
 
-pub(crate) fn spawn_index_process(request: &IndexLaunchRequest<'_>) -> std::io::Result<Child> {
-    let mut cmd = build_index_command(request);
-    cmd.stdout(Stdio::null()).stderr(Stdio::null()).spawn()
+/// The indexer could not be started; `attempts` counts spawn calls made.
+#[derive(Debug)]
+pub(crate) struct SpawnFailure {
+    pub error: std::io::Error,
+    pub attempts: u32,
+}
+
+/// Start the indexer subprocess, retrying transient spawn failures (e.g.
+/// `EAGAIN` under process-table pressure) with exponential backoff.
+pub(crate) fn spawn_index_process(request: &IndexLaunchRequest<'_>) -> Result<Child, SpawnFailure> {
+    spawn_with_retry(
+        || {
+            let mut cmd = build_index_command(request);
+            cmd.stdout(Stdio::null()).stderr(Stdio::null()).spawn()
+        },
+        SPAWN_MAX_ATTEMPTS,
+        SPAWN_RETRY_BASE_DELAY,
+    )
+}
+
+/// Call `spawn` up to `max_attempts` times, sleeping `base_delay`, then twice
+/// that, and so on between attempts. Errors that retrying cannot fix (e.g. a
+/// missing binary) are returned after the first attempt.
+pub(crate) fn spawn_with_retry<T>(
+    mut spawn: impl FnMut() -> std::io::Result<T>,
+    max_attempts: u32,
+    base_delay: Duration,
+) -> Result<T, SpawnFailure> {
+    let mut attempts = 0;
+    let mut delay = base_delay;
+    loop {
+        attempts += 1;
+        match spawn() {
+            Ok(spawned) => return Ok(spawned),
+            Err(error) if attempts < max_attempts && is_transient_spawn_error(&error) => {
+                warn!(attempt = attempts, error = %error, "Indexer spawn failed; retrying");
+                std::thread::sleep(delay);
+                delay = delay.saturating_mul(2);
+            }
+            Err(error) => return Err(SpawnFailure { error, attempts }),
+        }
+    }
+}
+
+fn is_transient_spawn_error(error: &std::io::Error) -> bool {
+    use std::io::ErrorKind;
+    matches!(
+        error.kind(),
+        ErrorKind::WouldBlock
+            | ErrorKind::Interrupted
+            | ErrorKind::OutOfMemory
+            | ErrorKind::ResourceBusy
+    )
 }
 
 fn build_index_command(request: &IndexLaunchRequest<'_>) -> Command {
@@ -44,6 +102,84 @@ fn build_index_command(request: &IndexLaunchRequest<'_>) -> Command {
     cmd
 }
 
+/// Kill `child` once it has run longer than `max_duration`.
+///
+/// Returns `true` when the process was killed; `None` disables the limit.
+pub(crate) fn kill_if_overdue(
+    child: &mut Child,
+    started: Instant,
+    max_duration: Option<Duration>,
+) -> bool {
+    let Some(max_duration) = max_duration else {
+        return false;
+    };
+    if started.elapsed() < max_duration {
+        return false;
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+    true
+}
+
+/// Mark a job whose indexer was killed for exceeding `max_duration` as failed,
+/// so it no longer counts as active and blocks later index runs.
+pub(crate) fn mark_job_timed_out(db_path: &Path, job_id: &str, max_duration: Duration) {
+    let result = cruxe_state::db::open_connection(db_path).and_then(|conn| {
+        let message = format!(
+            "Indexer exceeded index.max_job_duration_secs ({}s) and was killed.",
+            max_duration.as_secs()
+        );
+        cruxe_state::jobs::update_job_status(
+            &conn,
+            job_id,
+            JobStatus::Failed,
+            None,
+            Some(max_duration.as_millis() as i64),
+            Some(&message),
+            &cruxe_core::time::now_iso8601(),
+        )?;
+        cruxe_state::jobs::set_job_error_data(
+            &conn,
+            job_id,
+            &serde_json::json!({
+                "reason": "timeout",
+                "max_job_duration_secs": max_duration.as_secs(),
+            }),
+        )
+    });
+    if let Err(err) = result {
+        warn!(job_id, error = %err, "Failed to mark timed-out index job as failed");
+    }
+}
+
+/// Wait for an indexer subprocess on the current thread, killing it and failing
+/// its job if it runs past `max_duration`.
+pub(crate) fn reap_with_deadline(
+    mut child: Child,
+    db_path: &Path,
+    job_id: &str,
+    max_duration: Option<Duration>,
+) {
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) | Err(_) => return,
+            Ok(None) => {}
+        }
+        if kill_if_overdue(&mut child, started, max_duration) {
+            warn!(
+                job_id,
+                "Indexer subprocess exceeded max job duration; killed"
+            );
+            if let Some(max_duration) = max_duration {
+                mark_job_timed_out(db_path, job_id, max_duration);
+            }
+            return;
+        }
+        std::thread::sleep(REAP_POLL_INTERVAL);
+    }
+}
+
 pub(crate) fn resolve_index_binary() -> PathBuf {
     if let Some(path) = std::env::var_os("CRUXE_INDEX_BIN") {
         return PathBuf::from(path);
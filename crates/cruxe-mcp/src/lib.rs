@@ -1,3 +1,4 @@
+pub mod capabilities;
 pub mod http;
 mod index_launcher;
 pub mod notifications;
@@ -1,11 +1,14 @@
+use serde::Serialize;
 use serde_json::{Value, json};
 use std::io::Write;
 use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
 
 /// Progress notification emitter for MCP index operations.
 ///
-/// Two implementations:
+/// Three implementations:
 /// - `McpProgressNotifier`: emits JSON-RPC `notifications/progress` to the client
+/// - `BroadcastProgressNotifier`: fans events out to HTTP `/progress` subscribers
 /// - `NullProgressNotifier`: no-op for clients without notification support
 pub trait ProgressNotifier: Send + Sync {
     /// Emit the start of a progress operation.
@@ -85,6 +88,56 @@ impl ProgressNotifier for McpProgressNotifier {
     }
 }
 
+/// A single progress notification, in the shape of the MCP progress `value`
+/// plus the token it belongs to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProgressEvent {
+    pub token: String,
+    /// `begin`, `report` or `end`.
+    pub kind: &'static str,
+    pub title: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub percentage: Option<u32>,
+}
+
+/// Publishes progress into a broadcast channel consumed by the HTTP
+/// transport's Server-Sent Events endpoint. Events sent while nobody is
+/// subscribed are dropped.
+pub struct BroadcastProgressNotifier {
+    sender: broadcast::Sender<ProgressEvent>,
+}
+
+impl BroadcastProgressNotifier {
+    pub fn new(sender: broadcast::Sender<ProgressEvent>) -> Self {
+        Self { sender }
+    }
+
+    fn send(&self, token: &str, kind: &'static str, title: &str, message: &str, pct: Option<u32>) {
+        let _ = self.sender.send(ProgressEvent {
+            token: token.to_string(),
+            kind,
+            title: title.to_string(),
+            message: message.to_string(),
+            percentage: pct.map(|pct| pct.min(100)),
+        });
+    }
+}
+
+impl ProgressNotifier for BroadcastProgressNotifier {
+    fn emit_begin(&self, token: &str, title: &str, message: &str) {
+        self.send(token, "begin", title, message, None);
+    }
+
+    fn emit_progress(&self, token: &str, title: &str, message: &str, percentage: Option<u32>) {
+        self.send(token, "report", title, message, percentage);
+    }
+
+    fn emit_end(&self, token: &str, title: &str, message: &str) {
+        self.send(token, "end", title, message, None);
+    }
+}
+
 /// No-op progress notifier for clients that don't support notifications.
 pub struct NullProgressNotifier;
 
@@ -183,6 +236,33 @@ mod tests {
         assert_eq!(parsed["params"]["value"]["message"], "Starting");
     }
 
+    #[test]
+    fn broadcast_notifier_delivers_events_to_subscribers() {
+        let (sender, mut receiver) = broadcast::channel(8);
+        let notifier = BroadcastProgressNotifier::new(sender);
+        notifier.emit_begin("index-job-1", "Indexing", "Starting");
+        notifier.emit_progress("index-job-1", "Indexing", "Parsing", Some(140));
+        notifier.emit_end("index-job-1", "Indexing complete", "Done");
+
+        let begin = receiver.try_recv().unwrap();
+        assert_eq!(begin.kind, "begin");
+        assert_eq!(begin.token, "index-job-1");
+        let report = receiver.try_recv().unwrap();
+        assert_eq!(report.kind, "report");
+        assert_eq!(report.percentage, Some(100));
+        let end = receiver.try_recv().unwrap();
+        assert_eq!(end.kind, "end");
+        assert_eq!(end.message, "Done");
+    }
+
+    #[test]
+    fn broadcast_notifier_without_subscribers_does_not_panic() {
+        let (sender, receiver) = broadcast::channel(1);
+        drop(receiver);
+        let notifier = BroadcastProgressNotifier::new(sender);
+        notifier.emit_progress("tok", "title", "msg", Some(50));
+    }
+
     #[test]
     fn null_notifier_does_not_panic() {
         let notifier = NullProgressNotifier;
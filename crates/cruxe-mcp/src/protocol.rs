@@ -16,8 +16,14 @@ pub struct ProtocolMetadata {
     pub ranking_reasons: Option<Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub suppressed_duplicate_count: Option<usize>,
+    /// Results `search_code` dropped for scoring below `min_score`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_score_dropped_count: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub safety_limit_applied: Option<bool>,
+    /// Opaque cursor for the next page of a paged query; absent on the last page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub warnings: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -92,6 +98,9 @@ pub struct ProtocolMetadata {
     pub query_plan_downgrade_reason: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub query_plan_budget_used: Option<Value>,
+    /// Indexed commit a commit-pinned query resolved to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
 }
 
 impl ProtocolMetadata {
@@ -112,7 +121,9 @@ impl ProtocolMetadata {
             schema_status,
             ranking_reasons: None,
             suppressed_duplicate_count: None,
+            min_score_dropped_count: None,
             safety_limit_applied: None,
+            next_cursor: None,
             warnings: None,
             policy_mode: None,
             policy_blocked_count: None,
@@ -150,6 +161,7 @@ impl ProtocolMetadata {
             query_plan_downgraded: None,
             query_plan_downgrade_reason: None,
             query_plan_budget_used: None,
+            commit: None,
         }
     }
 
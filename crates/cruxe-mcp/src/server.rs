@@ -1,3 +1,4 @@
+use crate::capabilities::{self, ServerFeatures};
 use crate::notifications::{McpProgressNotifier, NullProgressNotifier, ProgressNotifier};
 use crate::protocol::{JsonRpcRequest, JsonRpcResponse, ProtocolMetadata};
 use crate::tools;
@@ -17,11 +18,16 @@ use cruxe_query::freshness::{
     parse_freshness_policy, trigger_async_sync,
 };
 use cruxe_query::hierarchy;
+use cruxe_query::implementations;
+use cruxe_query::import_graph;
 use cruxe_query::locate;
 use cruxe_query::ranking;
+use cruxe_query::ref_diff;
 use cruxe_query::related;
+use cruxe_query::resolve_import;
 use cruxe_query::search;
 use cruxe_query::symbol_compare;
+use cruxe_query::tests_for_symbol;
 use cruxe_query::tombstone::TombstoneCache;
 use cruxe_state::tantivy_index::IndexSet;
 use serde_json::{Value, json};
@@ -43,6 +49,8 @@ pub const PREWARM_COMPLETE: u8 = 2;
 pub const PREWARM_FAILED: u8 = 3;
 pub const PREWARM_SKIPPED: u8 = 4;
 const DEFAULT_WARMSET_CAPACITY: usize = 3;
+/// Workspaces past capacity that are looked up to report as evicted.
+const WARMSET_EVICTION_REPORT_LIMIT: usize = 16;
 const DEFAULT_MAX_OPEN_CONNECTIONS: usize = 32;
 const DEFAULT_SESSION_SCOPE: &str = "default";
 const SESSION_OVERRIDE_MAX_ENTRIES: usize = 4096;
@@ -72,52 +80,92 @@ pub(crate) fn warmset_capacity() -> usize {
         .unwrap_or(DEFAULT_WARMSET_CAPACITY)
 }
 
+/// Warmset membership chosen by least-recently-used order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct WarmsetSelection {
+    /// Kept warm: the pinned default first, then most recently used first.
+    pub members: Vec<String>,
+    /// Known entries that did not fit, most recently used first (capped at
+    /// [`WARMSET_EVICTION_REPORT_LIMIT`]).
+    pub evicted: Vec<String>,
+}
+
+/// LRU selection: `pinned` always stays warm, then `recent` (ordered most to
+/// least recently used) fills the remaining capacity and the rest is evicted.
+fn select_warmset(
+    pinned: String,
+    recent: impl IntoIterator<Item = String>,
+    capacity: usize,
+) -> WarmsetSelection {
+    let mut seen: HashSet<String> = HashSet::from([pinned.clone()]);
+    let mut selection = WarmsetSelection {
+        members: vec![pinned],
+        evicted: Vec::new(),
+    };
+    for entry in recent {
+        if !seen.insert(entry.clone()) {
+            continue;
+        }
+        if selection.members.len() < capacity {
+            selection.members.push(entry);
+        } else {
+            selection.evicted.push(entry);
+        }
+    }
+    selection.evicted.truncate(WARMSET_EVICTION_REPORT_LIMIT);
+    selection
+}
+
+fn recent_workspaces_for_warmset(
+    conn: &rusqlite::Connection,
+    capacity: usize,
+) -> Vec<cruxe_state::workspace::KnownWorkspace> {
+    cruxe_state::workspace::list_recent_workspaces(
+        conn,
+        capacity.saturating_add(WARMSET_EVICTION_REPORT_LIMIT),
+    )
+    .unwrap_or_default()
+}
+
 pub(crate) fn collect_warmset_project_ids(
     db_path: &Path,
     default_project_id: &str,
     capacity: usize,
 ) -> Vec<String> {
-    let mut project_ids = vec![default_project_id.to_string()];
-    let mut seen: HashSet<String> = HashSet::from([default_project_id.to_string()]);
-    if let Ok(conn) = cruxe_state::db::open_connection(db_path)
-        && let Ok(recent) = cruxe_state::workspace::list_recent_workspaces(&conn, capacity)
-    {
-        for ws in recent {
-            let pid = ws
-                .project_id
-                .unwrap_or_else(|| generate_project_id(&ws.workspace_path));
-            if seen.insert(pid.clone()) {
-                project_ids.push(pid);
-            }
-        }
-    }
-    if project_ids.len() > capacity {
-        project_ids.truncate(capacity);
+    let recent = cruxe_state::db::open_connection(db_path)
+        .map(|conn| recent_workspaces_for_warmset(&conn, capacity))
+        .unwrap_or_default();
+    let selection = select_warmset(
+        default_project_id.to_string(),
+        recent.into_iter().map(|ws| {
+            ws.project_id
+                .unwrap_or_else(|| generate_project_id(&ws.workspace_path))
+        }),
+        capacity,
+    );
+    if !selection.evicted.is_empty() {
+        info!(
+            evicted = ?selection.evicted,
+            capacity,
+            "Warmset capacity reached; skipping least recently used projects"
+        );
     }
-    project_ids
+    selection.members
 }
 
 pub(crate) fn collect_warmset_members(
     conn: Option<&rusqlite::Connection>,
     default_workspace: &Path,
     capacity: usize,
-) -> Vec<String> {
-    let default_member = default_workspace.to_string_lossy().to_string();
-    let mut members = vec![default_member.clone()];
-    let mut seen: HashSet<String> = HashSet::from([default_member]);
-    if let Some(c) = conn
-        && let Ok(recent) = cruxe_state::workspace::list_recent_workspaces(c, capacity)
-    {
-        for ws in recent {
-            if seen.insert(ws.workspace_path.clone()) {
-                members.push(ws.workspace_path);
-            }
-        }
-    }
-    if members.len() > capacity {
-        members.truncate(capacity);
-    }
-    members
+) -> WarmsetSelection {
+    let recent = conn
+        .map(|c| recent_workspaces_for_warmset(c, capacity))
+        .unwrap_or_default();
+    select_warmset(
+        default_workspace.to_string_lossy().to_string(),
+        recent.into_iter().map(|ws| ws.workspace_path),
+        capacity,
+    )
 }
 
 pub(crate) fn prewarm_projects(status: Arc<AtomicU8>, config: Config, project_ids: Vec<String>) {
@@ -306,6 +354,11 @@ pub fn run_server(
     }
 
     // Create workspace router (validates config at startup — T206/T208)
+    let mut workspace_config = workspace_config;
+    crate::workspace_router::merge_configured_roots(
+        &mut workspace_config,
+        &config.server.allowed_workspace_roots,
+    );
     let router = WorkspaceRouter::new(workspace_config, workspace.to_path_buf(), db_path.clone())
         .map_err(|e| format!("workspace config error: {}", e))?;
 
@@ -394,6 +447,7 @@ pub fn run_server(
         };
         let transport = TransportExecutionContext {
             notifier,
+            progress_notifications: notifications_enabled_now,
             progress_token: progress_token.as_deref(),
             // stdio serves a single MCP client per process; keep one shared scope
             // unless transports provide explicit per-session identifiers.
@@ -434,7 +488,8 @@ fn workspace_error_to_response(id: Option<Value>, err: &WorkspaceError) -> JsonR
         ),
         WorkspaceError::AllowedRootRequired => (
             ProtocolErrorCode::InvalidInput,
-            "--allowed-root is required when --auto-workspace is enabled.".to_string(),
+            "--allowed-root or server.allowed_workspace_roots is required when --auto-workspace is enabled."
+                .to_string(),
         ),
     };
 
@@ -455,6 +510,8 @@ pub fn bootstrap_and_index(
     project_id: &str,
     data_dir: &Path,
     storage_data_dir: &str,
+    non_vcs_ref: &str,
+    max_job_duration: Option<std::time::Duration>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Create data directory
     std::fs::create_dir_all(data_dir)?;
@@ -471,7 +528,7 @@ pub fn bootstrap_and_index(
         let default_ref = if vcs_mode {
             cruxe_core::vcs::detect_default_ref(workspace, "main")
         } else {
-            constants::REF_LIVE.to_string()
+            non_vcs_ref.to_string()
         };
 
         let now = cruxe_core::time::now_iso8601();
@@ -514,8 +571,12 @@ pub fn bootstrap_and_index(
     match crate::index_launcher::spawn_index_process(&launch_request) {
         Ok(child) => {
             std::thread::spawn(move || {
-                let mut child = child;
-                let _ = child.wait();
+                crate::index_launcher::reap_with_deadline(
+                    child,
+                    &db_path,
+                    &bootstrap_job_id,
+                    max_job_duration,
+                );
             });
             info!(
                 project_id,
@@ -523,8 +584,13 @@ pub fn bootstrap_and_index(
                 "On-demand indexing started for auto-discovered workspace"
             );
         }
-        Err(e) => {
-            error!(project_id, "Failed to spawn on-demand indexer: {}", e);
+        Err(failure) => {
+            error!(
+                project_id,
+                attempts = failure.attempts,
+                "Failed to spawn on-demand indexer: {}",
+                failure.error
+            );
         }
     }
 
@@ -572,6 +638,8 @@ pub struct DispatchRuntime<'a> {
 
 pub struct TransportExecutionContext<'a> {
     pub notifier: Arc<dyn ProgressNotifier>,
+    /// Whether this transport pushes `notifications/progress` for the session.
+    pub progress_notifications: bool,
     pub progress_token: Option<&'a str>,
     pub session_scope: Option<&'a str>,
     pub transport_label: &'static str,
@@ -585,8 +653,8 @@ struct EffectiveWorkspaceContext {
     data_dir: std::path::PathBuf,
 }
 
-enum DispatchOutcome {
-    Continue(EffectiveWorkspaceContext),
+enum DispatchOutcome<T = EffectiveWorkspaceContext> {
+    Continue(T),
     Response(JsonRpcResponse),
 }
 
@@ -600,10 +668,12 @@ fn resolve_tool_call_workspace(
         .get("name")
         .and_then(|v| v.as_str())
         .unwrap_or("");
+    // `tools/call_batch` carries one `workspace` for all of its calls.
     let ws_param = request
         .params
         .get("arguments")
         .and_then(|a| a.get("workspace"))
+        .or_else(|| request.params.get("workspace"))
         .and_then(|v| v.as_str());
 
     match runtime.router.resolve_workspace(ws_param) {
@@ -616,6 +686,8 @@ fn resolve_tool_call_workspace(
                         &resolved.project_id,
                         &eff_data_dir,
                         &runtime.config.storage.data_dir,
+                        runtime.config.index.non_vcs_ref(),
+                        runtime.config.index.max_job_duration(),
                     )
                 {
                     error!(
@@ -627,7 +699,7 @@ fn resolve_tool_call_workspace(
                 if !is_status_tool(tool_name) {
                     let effective_ref = cruxe_core::vcs::detect_default_ref(
                         &resolved.workspace_path,
-                        constants::REF_LIVE,
+                        runtime.config.index.non_vcs_ref(),
                     );
                     let metadata = ProtocolMetadata::syncing(&effective_ref);
                     return DispatchOutcome::Response(tool_calls::tool_text_response(
@@ -670,19 +742,110 @@ pub fn execute_transport_request(
     runtime: &DispatchRuntime<'_>,
     transport: &TransportExecutionContext<'_>,
 ) -> JsonRpcResponse {
+    // The handshake does not touch workspace state, and its capabilities depend
+    // on the transport rather than the resolved project.
+    if request.method == "initialize" {
+        return JsonRpcResponse::success(
+            request.id.clone(),
+            capabilities::initialize_result(
+                &request.params,
+                ServerFeatures {
+                    progress_notifications: transport.progress_notifications,
+                },
+            ),
+        );
+    }
+    match with_transport_request_context(request, runtime, transport, |request_ctx| {
+        handle_request_with_ctx(request, request_ctx)
+    }) {
+        DispatchOutcome::Continue(response) | DispatchOutcome::Response(response) => response,
+    }
+}
+
+/// Executes a `search_code` tool call, emitting NDJSON line values through
+/// `emit` instead of building a single buffered response.
+///
+/// Every outcome is delivered as lines: ranked results followed by a summary
+/// line on success, or a single `{"type":"error",..}` line when workspace
+/// resolution, validation, or the search itself fails.
+pub fn execute_transport_search_stream(
+    request: &JsonRpcRequest,
+    runtime: &DispatchRuntime<'_>,
+    transport: &TransportExecutionContext<'_>,
+    emit: &mut dyn FnMut(Value) -> bool,
+) {
+    let outcome = with_transport_request_context(request, runtime, transport, |request_ctx| {
+        let arguments = request
+            .params
+            .get("arguments")
+            .cloned()
+            .unwrap_or(json!({}));
+        tool_calls::stream_search_code_call(
+            tool_call_params(request, "search_code", &arguments, request_ctx),
+            emit,
+        )
+    });
+    match outcome {
+        DispatchOutcome::Continue(None) => {}
+        DispatchOutcome::Continue(Some(response)) | DispatchOutcome::Response(response) => {
+            emit(stream_error_line(response));
+        }
+    }
+}
+
+/// Renders a non-streamed response as the terminal NDJSON line.
+fn stream_error_line(response: JsonRpcResponse) -> Value {
+    if let Some(error) = response.error {
+        return json!({
+            "type": "error",
+            "error": {
+                "code": error.code,
+                "message": error.message,
+                "data": error.data,
+            },
+        });
+    }
+    let mut payload = response
+        .result
+        .as_ref()
+        .and_then(|result| result.get("content"))
+        .and_then(|content| content.get(0))
+        .and_then(|item| item.get("text"))
+        .and_then(|text| text.as_str())
+        .and_then(|text| serde_json::from_str::<Value>(text).ok())
+        .unwrap_or_else(|| json!({}));
+    // Non-error tool payloads (e.g. on-demand indexing status) still end the
+    // stream, so they are surfaced as the summary line.
+    let line_type = if payload.get("error").is_some() {
+        "error"
+    } else {
+        "summary"
+    };
+    if let Some(object) = payload.as_object_mut() {
+        object.insert("type".to_string(), json!(line_type));
+    }
+    payload
+}
+
+fn with_transport_request_context<R>(
+    request: &JsonRpcRequest,
+    runtime: &DispatchRuntime<'_>,
+    transport: &TransportExecutionContext<'_>,
+    run: impl FnOnce(&RequestContext<'_>) -> R,
+) -> DispatchOutcome<R> {
     let _session_scope_guard = set_active_session_scope(transport.session_scope);
     let mut effective_workspace = runtime.workspace.to_path_buf();
     let mut effective_project_id = runtime.project_id.to_string();
     let mut effective_data_dir = runtime.data_dir.to_path_buf();
 
-    if request.method == "tools/call" {
+    if matches!(request.method.as_str(), "tools/call" | "tools/call_batch") {
         match resolve_tool_call_workspace(request, runtime, transport) {
             DispatchOutcome::Continue(ctx) => {
                 effective_workspace = ctx.workspace;
                 effective_project_id = ctx.project_id;
                 effective_data_dir = ctx.data_dir;
             }
-            DispatchOutcome::Response(response) => return response,
+            DispatchOutcome::Response(response) => return DispatchOutcome::Response(response),
         }
     }
 
@@ -736,24 +899,11 @@ pub fn execute_transport_request(
         notifier: transport.notifier.clone(),
         progress_token: transport.progress_token,
     };
-    handle_request_with_ctx(request, &request_ctx)
+    DispatchOutcome::Continue(run(&request_ctx))
 }
 
 fn handle_request_with_ctx(request: &JsonRpcRequest, ctx: &RequestContext<'_>) -> JsonRpcResponse {
     match request.method.as_str() {
-        "initialize" => JsonRpcResponse::success(
-            request.id.clone(),
-            json!({
-                "protocolVersion": "2024-11-05",
-                "capabilities": {
-                    "tools": {}
-                },
-                "serverInfo": {
-                    "name": "cruxe",
-                    "version": env!("CARGO_PKG_VERSION")
-                }
-            }),
-        ),
         "notifications/initialized" => JsonRpcResponse::success(request.id.clone(), json!({})),
         "tools/list" => {
             let tools = tools::list_tools();
@@ -771,23 +921,9 @@ fn handle_request_with_ctx(request: &JsonRpcRequest, ctx: &RequestContext<'_>) -
                 .cloned()
                 .unwrap_or(json!({}));
 
-            handle_tool_call(ToolCallParams {
-                id: request.id.clone(),
-                tool_name,
-                arguments: &arguments,
-                config: ctx.config,
-                index_set: ctx.index_set,
-                schema_status: ctx.schema_status,
-                compatibility_reason: ctx.compatibility_reason,
-                conn: ctx.conn,
-                workspace: ctx.workspace,
-                project_id: ctx.project_id,
-                prewarm_status: ctx.prewarm_status,
-                server_start: ctx.server_start,
-                notifier: ctx.notifier.clone(),
-                progress_token: ctx.progress_token.map(|s| s.to_string()),
-            })
+            handle_tool_call(tool_call_params(request, tool_name, &arguments, ctx))
         }
+        "tools/call_batch" => handle_tool_call_batch(request, ctx),
         _ => JsonRpcResponse::error(
             request.id.clone(),
             -32601,
@@ -796,6 +932,89 @@ fn handle_request_with_ctx(request: &JsonRpcRequest, ctx: &RequestContext<'_>) -
     }
 }
 
+/// Upper bound on the number of calls in one `tools/call_batch` request.
+const MAX_BATCH_CALLS: usize = 64;
+
+/// Run the `{name, arguments}` entries of a `tools/call_batch` request in
+/// order against one request context, so the workspace, index and SQLite
+/// connection are resolved once for the whole batch.
+///
+/// Calls fail independently: `results[i]` is `{"result": ..}` holding what
+/// `tools/call` would have returned for `calls[i]`, or `{"error": ..}` when
+/// that entry could not be dispatched. A per-call `workspace` that differs from
+/// the batch's is rejected, since the batch is bound to one project.
+fn handle_tool_call_batch(request: &JsonRpcRequest, ctx: &RequestContext<'_>) -> JsonRpcResponse {
+    let Some(calls) = request.params.get("calls").and_then(Value::as_array) else {
+        return JsonRpcResponse::error(
+            request.id.clone(),
+            -32602,
+            "Invalid params: `calls` must be an array of {name, arguments} objects.".to_string(),
+        );
+    };
+    if calls.len() > MAX_BATCH_CALLS {
+        return JsonRpcResponse::error(
+            request.id.clone(),
+            -32602,
+            format!(
+                "Invalid params: a batch holds at most {MAX_BATCH_CALLS} calls, got {}.",
+                calls.len()
+            ),
+        );
+    }
+    let batch_workspace = request.params.get("workspace").and_then(Value::as_str);
+
+    let results: Vec<Value> = calls
+        .iter()
+        .map(|call| {
+            let Some(tool_name) = call.get("name").and_then(Value::as_str) else {
+                return batch_error(-32602, "Invalid params: each call needs a string `name`.");
+            };
+            let arguments = call.get("arguments").cloned().unwrap_or(json!({}));
+            let call_workspace = arguments.get("workspace").and_then(Value::as_str);
+            if call_workspace.is_some() && call_workspace != batch_workspace {
+                return batch_error(
+                    -32602,
+                    "Invalid params: set `workspace` on the batch, not on individual calls.",
+                );
+            }
+            let response = handle_tool_call(tool_call_params(request, tool_name, &arguments, ctx));
+            match response.error {
+                Some(error) => json!({ "error": error }),
+                None => json!({ "result": response.result }),
+            }
+        })
+        .collect();
+    JsonRpcResponse::success(request.id.clone(), json!({ "results": results }))
+}
+
+fn batch_error(code: i32, message: &str) -> Value {
+    json!({ "error": { "code": code, "message": message } })
+}
+
+fn tool_call_params<'a>(
+    request: &JsonRpcRequest,
+    tool_name: &'a str,
+    arguments: &'a Value,
+    ctx: &RequestContext<'a>,
+) -> ToolCallParams<'a> {
+    ToolCallParams {
+        id: request.id.clone(),
+        tool_name,
+        arguments,
+        config: ctx.config,
+        index_set: ctx.index_set,
+        schema_status: ctx.schema_status,
+        compatibility_reason: ctx.compatibility_reason,
+        conn: ctx.conn,
+        workspace: ctx.workspace,
+        project_id: ctx.project_id,
+        prewarm_status: ctx.prewarm_status,
+        server_start: ctx.server_start,
+        notifier: ctx.notifier.clone(),
+        progress_token: ctx.progress_token.map(|s| s.to_string()),
+    }
+}
+
 struct IndexRuntime {
     index_set: Option<IndexSet>,
     schema_status: SchemaStatus,
@@ -1098,7 +1317,7 @@ pub(crate) fn build_health_core_payload(request: HealthCoreRequest<'_>) -> Healt
         for p in projects {
             let project_workspace = Path::new(&p.repo_root);
             let project_ref = if p.default_ref.trim().is_empty() {
-                constants::REF_LIVE.to_string()
+                config.index.non_vcs_ref().to_string()
             } else {
                 p.default_ref.clone()
             };
@@ -1142,12 +1361,23 @@ pub(crate) fn build_health_core_payload(request: HealthCoreRequest<'_>) -> Healt
                 cruxe_state::manifest::file_count(c, &p.project_id, &project_ref).unwrap_or(0);
             let symbol_count =
                 cruxe_state::symbols::symbol_count(c, &p.project_id, &project_ref).unwrap_or(0);
-            let last_indexed_at = cruxe_state::jobs::get_recent_jobs(c, &p.project_id, 10)
-                .ok()
-                .and_then(|jobs| {
-                    jobs.into_iter()
-                        .find(|j| j.status == "published" && j.r#ref == project_ref)
-                        .map(|j| j.updated_at)
+            let recent_ref_jobs = cruxe_state::jobs::get_recent_jobs(c, &p.project_id, 10)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|j| j.r#ref == project_ref)
+                .collect::<Vec<_>>();
+            let last_indexed_at = recent_ref_jobs
+                .iter()
+                .find(|j| j.status == "published")
+                .map(|j| j.updated_at.clone());
+            // Only the latest job matters: a later successful run clears the cap signal.
+            let symbol_cap_exceeded = recent_ref_jobs
+                .first()
+                .filter(|j| j.status == "failed")
+                .and_then(|j| j.error_data.clone())
+                .filter(|data| {
+                    data.get("code").and_then(Value::as_str)
+                        == Some(ProtocolErrorCode::SymbolCapExceeded.as_str())
                 });
 
             let mut project_payload = json!({
@@ -1158,10 +1388,14 @@ pub(crate) fn build_health_core_payload(request: HealthCoreRequest<'_>) -> Healt
                 "ref": project_ref,
                 "file_count": file_count,
                 "symbol_count": symbol_count,
+                "max_symbols_per_ref": config.index.max_symbols_per_ref,
                 "schema_status": project_schema_status_str,
                 "current_schema_version": project_current_schema_version,
                 "required_schema_version": constants::SCHEMA_VERSION,
             });
+            if let Some(cap_data) = symbol_cap_exceeded {
+                project_payload["symbol_cap_exceeded"] = cap_data;
+            }
             if options.include_freshness_status {
                 let freshness_result = check_freshness_with_scan_params(
                     Some(c),
@@ -1169,7 +1403,7 @@ pub(crate) fn build_health_core_payload(request: HealthCoreRequest<'_>) -> Healt
                     &p.project_id,
                     project_payload["ref"]
                         .as_str()
-                        .unwrap_or(constants::REF_LIVE),
+                        .unwrap_or(config.index.non_vcs_ref()),
                     config.index.max_file_size,
                     Some(&config.index.languages),
                 );
@@ -1208,6 +1442,7 @@ pub(crate) fn build_health_core_payload(request: HealthCoreRequest<'_>) -> Healt
             "symbol_count": conn
                 .and_then(|c| cruxe_state::symbols::symbol_count(c, project_id, effective_ref).ok())
                 .unwrap_or(0),
+            "max_symbols_per_ref": config.index.max_symbols_per_ref,
             "schema_status": fallback_schema_status,
             "current_schema_version": fallback_current_schema_version,
             "required_schema_version": constants::SCHEMA_VERSION,
@@ -1409,15 +1644,38 @@ fn enforce_session_override_capacity(entries: &mut HashMap<String, SessionRefOve
 /// 2. Session `switch_ref` override (process-local, non-persistent)
 /// 3. Current HEAD branch (if available)
 /// 4. Project default_ref from SQLite metadata
-/// 5. `live` fallback
+/// 5. `index.default_ref` fallback (`live` unless configured)
+///
+/// An explicit `HEAD`, `head` or `current` resolves to the checked-out branch,
+/// and `default` to the project default_ref; both fall back to 4-5.
 fn resolve_tool_ref(
     requested_ref: Option<&str>,
     workspace: &Path,
     conn: Option<&rusqlite::Connection>,
     project_id: &str,
+    config: &Config,
+) -> String {
+    let effective_ref =
+        resolve_requested_or_default_ref(requested_ref, workspace, conn, project_id, config);
+    tracing::Span::current().record("ref", effective_ref.as_str());
+    effective_ref
+}
+
+fn resolve_requested_or_default_ref(
+    requested_ref: Option<&str>,
+    workspace: &Path,
+    conn: Option<&rusqlite::Connection>,
+    project_id: &str,
+    config: &Config,
 ) -> String {
-    if let Some(r) = requested_ref {
-        return r.to_string();
+    match requested_ref {
+        Some("HEAD" | "head" | "current") => {
+            return cruxe_core::vcs::detect_head_branch(workspace)
+                .unwrap_or_else(|_| project_or_non_vcs_ref(conn, project_id, config));
+        }
+        Some("default") => return project_or_non_vcs_ref(conn, project_id, config),
+        Some(r) => return r.to_string(),
+        None => {}
     }
     if let Some(session_ref) = get_session_ref_override(workspace, project_id) {
         return session_ref;
@@ -1425,13 +1683,56 @@ fn resolve_tool_ref(
     if let Ok(branch) = cruxe_core::vcs::detect_head_branch(workspace) {
         return branch;
     }
+    project_or_non_vcs_ref(conn, project_id, config)
+}
+
+/// The project's `default_ref`, or the non-VCS ref when the project has none.
+fn project_or_non_vcs_ref(
+    conn: Option<&rusqlite::Connection>,
+    project_id: &str,
+    config: &Config,
+) -> String {
     if let Some(c) = conn
         && let Ok(Some(project)) = cruxe_state::project::get_by_id(c, project_id)
         && !project.default_ref.trim().is_empty()
     {
         return project.default_ref;
     }
-    constants::REF_LIVE.to_string()
+    config.index.non_vcs_ref().to_string()
+}
+
+/// Resolve a commit-pinned query to the ref whose indexed snapshot is `commit`.
+///
+/// Each ref keeps a single indexed snapshot, so a commit is queryable only while
+/// it is some ref's `last_indexed_commit`. An explicit `requested_ref` must be
+/// one of the refs indexed at that commit; otherwise the default branch wins.
+fn resolve_tool_commit(
+    commit: &str,
+    requested_ref: Option<&str>,
+    conn: Option<&rusqlite::Connection>,
+    project_id: &str,
+) -> Result<cruxe_state::branch_state::BranchState, String> {
+    let commit = commit.trim();
+    if commit.len() < 7 || !commit.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(
+            "Parameter `commit` must be a hex commit hash of at least 7 characters.".to_string(),
+        );
+    }
+    let Some(conn) = conn else {
+        return Err("Parameter `commit` requires an initialized project state.".to_string());
+    };
+    let candidates = cruxe_state::branch_state::find_by_indexed_commit(conn, project_id, commit)
+        .map_err(|err| format!("Failed to look up commit `{commit}`: {err}"))?;
+    let resolved = match requested_ref {
+        Some(r) => candidates.into_iter().find(|state| state.r#ref == r),
+        None => candidates.into_iter().next(),
+    };
+    resolved.ok_or_else(|| match requested_ref {
+        Some(r) => format!("Commit `{commit}` is not the indexed snapshot of ref `{r}`."),
+        None => format!(
+            "Commit `{commit}` is not the indexed snapshot of any ref. Sync a ref at that commit first."
+        ),
+    })
 }
 
 // ---- Public API for HTTP transport (T223) ----
@@ -1449,8 +1750,9 @@ pub fn resolve_tool_ref_public(
     workspace: &Path,
     conn: Option<&rusqlite::Connection>,
     project_id: &str,
+    config: &Config,
 ) -> String {
-    resolve_tool_ref(requested_ref, workspace, conn, project_id)
+    resolve_tool_ref(requested_ref, workspace, conn, project_id, config)
 }
 
 /// Public wrapper for `workspace_error_to_response` used by the HTTP transport.
@@ -1519,6 +1821,7 @@ pub fn handle_tool_call_public(params: PublicToolCallParams<'_>) -> JsonRpcRespo
     })
 }
 
+mod recent_symbols;
 mod tool_calls;
 
 #[cfg(test)]
@@ -0,0 +1,175 @@
+//! Per-session working set of symbols returned by lookup tools.
+//!
+//! Process-local and non-persistent, keyed like `switch_ref` overrides by
+//! session scope, project, and workspace. Each session keeps at most
+//! [`RECENT_SYMBOLS_PER_SESSION`] entries; entries and idle sessions expire
+//! after [`RECENT_SYMBOLS_TTL`].
+
+use super::session_ref_key;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+const RECENT_SYMBOLS_PER_SESSION: usize = 200;
+const RECENT_SYMBOLS_MAX_SESSIONS: usize = 1024;
+const RECENT_SYMBOLS_TTL: Duration = Duration::from_secs(2 * 60 * 60);
+
+/// A symbol as returned to the client, before it enters the working set.
+pub(super) struct ReturnedSymbol<'a> {
+    pub symbol_id: &'a str,
+    pub symbol_stable_id: Option<&'a str>,
+    pub name: &'a str,
+    pub qualified_name: &'a str,
+    pub kind: &'a str,
+    pub path: &'a str,
+    pub line_start: u32,
+    pub line_end: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(super) struct RecentSymbol {
+    pub symbol_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol_stable_id: Option<String>,
+    pub name: String,
+    pub qualified_name: String,
+    pub kind: String,
+    pub path: String,
+    pub line_start: u32,
+    pub line_end: u32,
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    /// Tool that most recently returned the symbol.
+    pub via: String,
+    /// Number of times the symbol has been returned this session.
+    pub hits: u32,
+    pub last_seen_secs_ago: u64,
+    #[serde(skip)]
+    last_seen_at: Instant,
+}
+
+struct WorkingSet {
+    /// Most recently returned first.
+    entries: Vec<RecentSymbol>,
+    last_touched_at: Instant,
+}
+
+fn working_sets() -> &'static Mutex<HashMap<String, WorkingSet>> {
+    static WORKING_SETS: OnceLock<Mutex<HashMap<String, WorkingSet>>> = OnceLock::new();
+    WORKING_SETS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Add `symbols` to the current session's working set, most relevant first.
+pub(super) fn record<'a>(
+    workspace: &Path,
+    project_id: &str,
+    ref_name: &str,
+    via: &str,
+    symbols: impl IntoIterator<Item = ReturnedSymbol<'a>>,
+) {
+    let key = session_ref_key(workspace, project_id);
+    let Ok(mut guard) = working_sets().lock() else {
+        return;
+    };
+    let now = Instant::now();
+    prune_expired(&mut guard, now);
+    let set = guard.entry(key).or_insert_with(|| WorkingSet {
+        entries: Vec::new(),
+        last_touched_at: now,
+    });
+    set.last_touched_at = now;
+
+    let mut batch: Vec<RecentSymbol> = Vec::new();
+    for symbol in symbols {
+        if symbol.symbol_id.is_empty()
+            || batch
+                .iter()
+                .any(|entry| entry.symbol_id == symbol.symbol_id && entry.ref_name == ref_name)
+        {
+            continue;
+        }
+        let hits = set
+            .entries
+            .iter()
+            .position(|entry| entry.symbol_id == symbol.symbol_id && entry.ref_name == ref_name)
+            .map(|idx| set.entries.remove(idx).hits)
+            .unwrap_or(0);
+        batch.push(RecentSymbol {
+            symbol_id: symbol.symbol_id.to_string(),
+            symbol_stable_id: symbol.symbol_stable_id.map(str::to_string),
+            name: symbol.name.to_string(),
+            qualified_name: symbol.qualified_name.to_string(),
+            kind: symbol.kind.to_string(),
+            path: symbol.path.to_string(),
+            line_start: symbol.line_start,
+            line_end: symbol.line_end,
+            ref_name: ref_name.to_string(),
+            via: via.to_string(),
+            hits: hits.saturating_add(1),
+            last_seen_secs_ago: 0,
+            last_seen_at: now,
+        });
+    }
+    batch.append(&mut set.entries);
+    batch.truncate(RECENT_SYMBOLS_PER_SESSION);
+    set.entries = batch;
+    enforce_session_capacity(&mut guard);
+}
+
+/// The current session's working set, most recent first, optionally limited to one ref.
+pub(super) fn list(
+    workspace: &Path,
+    project_id: &str,
+    ref_name: Option<&str>,
+    limit: usize,
+) -> Vec<RecentSymbol> {
+    let key = session_ref_key(workspace, project_id);
+    let Ok(mut guard) = working_sets().lock() else {
+        return Vec::new();
+    };
+    let now = Instant::now();
+    prune_expired(&mut guard, now);
+    let Some(set) = guard.get(&key) else {
+        return Vec::new();
+    };
+    set.entries
+        .iter()
+        .filter(|entry| ref_name.is_none_or(|ref_name| entry.ref_name == ref_name))
+        .take(limit)
+        .map(|entry| RecentSymbol {
+            last_seen_secs_ago: now.duration_since(entry.last_seen_at).as_secs(),
+            ..entry.clone()
+        })
+        .collect()
+}
+
+/// Forget the current session's working set.
+pub(super) fn clear(workspace: &Path, project_id: &str) {
+    let key = session_ref_key(workspace, project_id);
+    if let Ok(mut guard) = working_sets().lock() {
+        guard.remove(&key);
+    }
+}
+
+fn prune_expired(sets: &mut HashMap<String, WorkingSet>, now: Instant) {
+    sets.retain(|_, set| {
+        set.entries
+            .retain(|entry| now.duration_since(entry.last_seen_at) <= RECENT_SYMBOLS_TTL);
+        now.duration_since(set.last_touched_at) <= RECENT_SYMBOLS_TTL
+    });
+}
+
+fn enforce_session_capacity(sets: &mut HashMap<String, WorkingSet>) {
+    while sets.len() > RECENT_SYMBOLS_MAX_SESSIONS {
+        let oldest_key = sets
+            .iter()
+            .min_by_key(|(_, set)| set.last_touched_at)
+            .map(|(key, _)| key.clone());
+        let Some(oldest_key) = oldest_key else {
+            break;
+        };
+        sets.remove(&oldest_key);
+    }
+}
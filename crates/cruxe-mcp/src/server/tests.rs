@@ -92,6 +92,54 @@ fn make_request(method: &str, params: serde_json::Value) -> JsonRpcRequest {
     }
 }
 
+/// The parts of a [`RequestContext`] that differ between tool tests; the rest
+/// (compatible schema unless overridden, completed prewarm, no progress
+/// reporting) is fixed.
+struct ToolCallFixture<'a> {
+    config: &'a Config,
+    index_set: Option<&'a IndexSet>,
+    conn: Option<&'a rusqlite::Connection>,
+    workspace: &'a Path,
+    project_id: &'a str,
+}
+
+impl ToolCallFixture<'_> {
+    fn handle(&self, request: &JsonRpcRequest) -> JsonRpcResponse {
+        self.handle_with_schema(request, SchemaStatus::Compatible)
+    }
+
+    fn handle_with_schema(
+        &self,
+        request: &JsonRpcRequest,
+        schema_status: SchemaStatus,
+    ) -> JsonRpcResponse {
+        handle_request_with_ctx(
+            request,
+            &RequestContext {
+                config: self.config,
+                index_set: self.index_set,
+                schema_status,
+                compatibility_reason: None,
+                conn: self.conn,
+                workspace: self.workspace,
+                project_id: self.project_id,
+                prewarm_status: &test_prewarm_status(),
+                server_start: &test_server_start(),
+                notifier: Arc::new(NullProgressNotifier),
+                progress_token: None,
+            },
+        )
+    }
+}
+
+/// Run one `tools/call` request for `name` against `fixture`.
+fn call_tool(fixture: &ToolCallFixture<'_>, name: &str, arguments: Value) -> JsonRpcResponse {
+    fixture.handle(&make_request(
+        "tools/call",
+        json!({ "name": name, "arguments": arguments }),
+    ))
+}
+
 #[derive(Default)]
 struct RecordingNotifier {
     events: Arc<Mutex<Vec<&'static str>>>,
@@ -208,11 +256,17 @@ fn resolve_tool_ref_falls_back_to_project_default_when_head_unavailable() {
     cruxe_state::project::create_project(&conn, &project).unwrap();
 
     // Temp dir is non-git and has no HEAD branch; should fall back to project default_ref.
-    let resolved = resolve_tool_ref(None, workspace, Some(&conn), project_id);
+    let resolved = resolve_tool_ref(None, workspace, Some(&conn), project_id, &Config::default());
     assert_eq!(resolved, "main");
 
     // Explicit argument still has top priority.
-    let explicit = resolve_tool_ref(Some("feat/auth"), workspace, Some(&conn), project_id);
+    let explicit = resolve_tool_ref(
+        Some("feat/auth"),
+        workspace,
+        Some(&conn),
+        project_id,
+        &Config::default(),
+    );
     assert_eq!(explicit, "feat/auth");
 }
 
@@ -246,11 +300,11 @@ fn resolve_tool_ref_scopes_overrides_by_session() {
 
     let session_a_ref = {
         let _scope = set_active_session_scope(Some("session-a"));
-        resolve_tool_ref(None, workspace, Some(&conn), project_id)
+        resolve_tool_ref(None, workspace, Some(&conn), project_id, &Config::default())
     };
     let session_b_ref = {
         let _scope = set_active_session_scope(Some("session-b"));
-        resolve_tool_ref(None, workspace, Some(&conn), project_id)
+        resolve_tool_ref(None, workspace, Some(&conn), project_id, &Config::default())
     };
     assert_eq!(session_a_ref, "feat/auth");
     assert_eq!(session_b_ref, "main");
@@ -261,11 +315,95 @@ fn resolve_tool_ref_scopes_overrides_by_session() {
     }
     let after_clear = {
         let _scope = set_active_session_scope(Some("session-a"));
-        resolve_tool_ref(None, workspace, Some(&conn), project_id)
+        resolve_tool_ref(None, workspace, Some(&conn), project_id, &Config::default())
     };
     assert_eq!(after_clear, "main");
 }
 
+fn create_alias_test_project(conn: &rusqlite::Connection, workspace: &Path, project_id: &str) {
+    cruxe_state::project::create_project(
+        conn,
+        &Project {
+            project_id: project_id.to_string(),
+            repo_root: workspace.to_string_lossy().to_string(),
+            display_name: Some("ref-aliases".to_string()),
+            default_ref: "main".to_string(),
+            vcs_mode: true,
+            schema_version: 1,
+            parser_version: 1,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+        },
+    )
+    .unwrap();
+}
+
+#[test]
+fn resolve_tool_ref_head_aliases_resolve_to_checked_out_branch() {
+    let tmp = tempfile::tempdir().unwrap();
+    let workspace = tmp.path().join("workspace");
+    ensure_git_workspace_with_refs(&workspace);
+    run_git(&workspace, &["checkout", "feat/auth"]);
+    let conn = cruxe_state::db::open_connection(&tmp.path().join("state.db")).unwrap();
+    cruxe_state::schema::create_tables(&conn).unwrap();
+    let project_id = "proj_head_aliases";
+    create_alias_test_project(&conn, &workspace, project_id);
+
+    for alias in ["HEAD", "head", "current"] {
+        let resolved = resolve_tool_ref(
+            Some(alias),
+            &workspace,
+            Some(&conn),
+            project_id,
+            &Config::default(),
+        );
+        assert_eq!(resolved, "feat/auth", "alias {alias}");
+    }
+
+    // Without a checked-out branch the aliases fall back to the project default.
+    let non_git = tmp.path().join("plain");
+    std::fs::create_dir_all(&non_git).unwrap();
+    let resolved = resolve_tool_ref(
+        Some("current"),
+        &non_git,
+        Some(&conn),
+        project_id,
+        &Config::default(),
+    );
+    assert_eq!(resolved, "main");
+}
+
+#[test]
+fn resolve_tool_ref_default_alias_resolves_to_project_default_ref() {
+    let tmp = tempfile::tempdir().unwrap();
+    let workspace = tmp.path().join("workspace");
+    ensure_git_workspace_with_refs(&workspace);
+    run_git(&workspace, &["checkout", "feat/auth"]);
+    let conn = cruxe_state::db::open_connection(&tmp.path().join("state.db")).unwrap();
+    cruxe_state::schema::create_tables(&conn).unwrap();
+    let project_id = "proj_default_alias";
+    create_alias_test_project(&conn, &workspace, project_id);
+
+    let resolved = resolve_tool_ref(
+        Some("default"),
+        &workspace,
+        Some(&conn),
+        project_id,
+        &Config::default(),
+    );
+    assert_eq!(resolved, "main");
+
+    // Unregistered projects fall back to the non-VCS ref.
+    let resolved = resolve_tool_ref(
+        Some("default"),
+        &workspace,
+        Some(&conn),
+        "proj_unregistered",
+        &Config::default(),
+    );
+    assert_eq!(resolved, cruxe_core::constants::REF_LIVE);
+}
+
 // ------------------------------------------------------------------
 // T065: tools/list returns all registered tools
 // ------------------------------------------------------------------
@@ -303,7 +441,7 @@ fn t065_tools_list_returns_all_registered_tools() {
         .as_array()
         .expect("'tools' should be an array");
 
-    assert_eq!(tools.len(), 19, "expected 19 tools, got {}", tools.len());
+    assert_eq!(tools.len(), 37, "expected 37 tools, got {}", tools.len());
 
     let tool_names: Vec<&str> = tools
         .iter()
@@ -316,20 +454,38 @@ fn t065_tools_list_returns_all_registered_tools() {
         "search_code",
         "locate_symbol",
         "get_call_graph",
+        "get_import_graph",
         "compare_symbol_between_commits",
+        "compare_symbols",
+        "get_symbol",
         "diff_context",
+        "diff_refs",
         "find_references",
+        "tests_for_symbol",
+        "resolve_import",
         "explain_ranking",
         "suggest_followup_queries",
         "list_refs",
         "switch_ref",
+        "drop_ref",
         "get_file_outline",
+        "directory_outline",
+        "export_symbol_names",
+        "large_symbols",
+        "find_unreferenced_symbols",
         "get_symbol_hierarchy",
+        "implementations_of",
         "find_related_symbols",
         "get_code_context",
         "build_context_pack",
         "health_check",
         "index_status",
+        "compact_index",
+        "list_skipped_files",
+        "list_files",
+        "symbol_at_offset",
+        "symbol_at_location",
+        "recent_symbols",
     ];
     for name in &expected_names {
         assert!(
@@ -422,6 +578,11 @@ fn t340_compare_symbol_between_commits_returns_modified_summary() {
         parent_symbol_id: None,
         visibility: Some("pub".to_string()),
         content: Some("validate();".to_string()),
+        attributes: Vec::new(),
+        byte_start: None,
+        byte_end: None,
+        doc_comment: None,
+        parsed_signature: None,
     };
     let head_symbol = cruxe_core::types::SymbolRecord {
         repo: project_id.to_string(),
@@ -442,6 +603,11 @@ fn t340_compare_symbol_between_commits_returns_modified_summary() {
         parent_symbol_id: None,
         visibility: Some("pub".to_string()),
         content: Some("validate();\nauthorize(ctx);".to_string()),
+        attributes: Vec::new(),
+        byte_start: None,
+        byte_end: None,
+        doc_comment: None,
+        parsed_signature: None,
     };
     cruxe_state::symbols::insert_symbol(&conn, &base_symbol).unwrap();
     cruxe_state::symbols::insert_symbol(&conn, &head_symbol).unwrap();
@@ -501,6 +667,351 @@ fn t340_compare_symbol_between_commits_returns_modified_summary() {
     );
 }
 
+#[test]
+fn t473_compare_symbols_returns_similarity_and_bounded_diff() {
+    let tmp = tempfile::tempdir().unwrap();
+    let workspace_dir = tmp.path().join("workspace");
+    std::fs::create_dir_all(&workspace_dir).unwrap();
+
+    let db_path = tmp.path().join("state.db");
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    cruxe_state::schema::create_tables(&conn).unwrap();
+
+    let project_id = "compare-bodies-project";
+    let now = "2026-02-26T00:00:00Z".to_string();
+    cruxe_state::branch_state::upsert_branch_state(
+        &conn,
+        &cruxe_state::branch_state::BranchState {
+            repo: project_id.to_string(),
+            r#ref: "main".to_string(),
+            merge_base_commit: None,
+            last_indexed_commit: "abc123".to_string(),
+            overlay_dir: None,
+            file_count: 2,
+            symbol_count: 2,
+            is_default_branch: true,
+            status: "active".to_string(),
+            eviction_eligible_at: None,
+            created_at: now.clone(),
+            last_accessed_at: now,
+        },
+    )
+    .unwrap();
+
+    for (name, path, body) in [
+        (
+            "load_user",
+            "src/users.rs",
+            "let row = db.get(id);\nreturn row;",
+        ),
+        (
+            "load_team",
+            "src/teams.rs",
+            "let team = db.get(team_id);\nreturn team;",
+        ),
+    ] {
+        cruxe_state::symbols::insert_symbol(
+            &conn,
+            &cruxe_core::types::SymbolRecord {
+                repo: project_id.to_string(),
+                r#ref: "main".to_string(),
+                commit: None,
+                path: path.to_string(),
+                symbol_id: format!("main::{name}"),
+                symbol_stable_id: format!("stable::{name}"),
+                name: name.to_string(),
+                qualified_name: format!("crate::{name}"),
+                kind: cruxe_core::types::SymbolKind::Function,
+                language: "rust".to_string(),
+                line_start: 1,
+                line_end: 4,
+                signature: Some(format!("fn {name}(id: u64)")),
+                parent_symbol_id: None,
+                visibility: Some("pub".to_string()),
+                content: Some(body.to_string()),
+                attributes: Vec::new(),
+                byte_start: None,
+                byte_end: None,
+                doc_comment: None,
+                parsed_signature: None,
+            },
+        )
+        .unwrap();
+    }
+
+    let config = Config::default();
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: None,
+        conn: Some(&conn),
+        workspace: workspace_dir.as_path(),
+        project_id,
+    };
+    let call = |arguments: Value| call_tool(&tool_ctx, "compare_symbols", arguments);
+
+    let response = call(json!({
+        "ref": "main",
+        "left_symbol_stable_id": "stable::load_user",
+        "right_symbol_stable_id": "stable::load_team"
+    }));
+    assert!(
+        response.error.is_none(),
+        "expected success: {:?}",
+        response.error
+    );
+    let payload = extract_payload_from_response(&response);
+    assert_eq!(payload["normalization"], "whitespace");
+    assert_eq!(payload["similarity"], 0.0);
+    assert_eq!(payload["diff_truncated"], false);
+    assert!(payload["diff"].as_str().unwrap().contains("+return team;"));
+
+    let response = call(json!({
+        "ref": "main",
+        "left_symbol_stable_id": "stable::load_user",
+        "right_symbol_stable_id": "stable::load_team",
+        "normalize": "identifiers",
+        "similarity_only": true
+    }));
+    let payload = extract_payload_from_response(&response);
+    assert_eq!(payload["identical"], true);
+    assert_eq!(payload["similarity"], 1.0);
+    assert!(payload.get("diff").is_none());
+
+    let response = call(json!({
+        "ref": "main",
+        "left_symbol_stable_id": "stable::load_user",
+        "right_symbol_stable_id": "stable::missing"
+    }));
+    let payload = extract_payload_from_response(&response);
+    assert_eq!(payload["error"]["code"], "symbol_not_found");
+}
+
+#[test]
+fn t483_export_symbol_names_pages_compact_tuples() {
+    let tmp = tempfile::tempdir().unwrap();
+    let workspace_dir = tmp.path().join("workspace");
+    std::fs::create_dir_all(&workspace_dir).unwrap();
+
+    let db_path = tmp.path().join("state.db");
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    cruxe_state::schema::create_tables(&conn).unwrap();
+
+    let project_id = "export-names-project";
+    let now = "2026-02-26T00:00:00Z".to_string();
+    cruxe_state::branch_state::upsert_branch_state(
+        &conn,
+        &cruxe_state::branch_state::BranchState {
+            repo: project_id.to_string(),
+            r#ref: "main".to_string(),
+            merge_base_commit: None,
+            last_indexed_commit: "abc123".to_string(),
+            overlay_dir: None,
+            file_count: 2,
+            symbol_count: 3,
+            is_default_branch: true,
+            status: "active".to_string(),
+            eviction_eligible_at: None,
+            created_at: now.clone(),
+            last_accessed_at: now,
+        },
+    )
+    .unwrap();
+
+    for (idx, (qualified_name, kind, path)) in [
+        (
+            "auth::validate_token",
+            cruxe_core::types::SymbolKind::Function,
+            "src/auth.rs",
+        ),
+        (
+            "auth::Claims",
+            cruxe_core::types::SymbolKind::Struct,
+            "src/auth.rs",
+        ),
+        (
+            "db::connect",
+            cruxe_core::types::SymbolKind::Function,
+            "src/db.rs",
+        ),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let name = qualified_name.rsplit("::").next().unwrap();
+        cruxe_state::symbols::insert_symbol(
+            &conn,
+            &cruxe_core::types::SymbolRecord {
+                repo: project_id.to_string(),
+                r#ref: "main".to_string(),
+                commit: None,
+                path: path.to_string(),
+                symbol_id: format!("sym-{idx}"),
+                symbol_stable_id: format!("stable-{idx}"),
+                name: name.to_string(),
+                qualified_name: qualified_name.to_string(),
+                kind,
+                language: "rust".to_string(),
+                line_start: idx as u32 * 10 + 1,
+                line_end: idx as u32 * 10 + 5,
+                signature: None,
+                parent_symbol_id: None,
+                visibility: None,
+                content: None,
+                attributes: Vec::new(),
+                byte_start: None,
+                byte_end: None,
+                doc_comment: None,
+                parsed_signature: None,
+            },
+        )
+        .unwrap();
+    }
+
+    let config = Config::default();
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: None,
+        conn: Some(&conn),
+        workspace: workspace_dir.as_path(),
+        project_id,
+    };
+    let call = |arguments: Value| call_tool(&tool_ctx, "export_symbol_names", arguments);
+
+    let payload = extract_payload_from_response(&call(json!({ "ref": "main", "limit": 2 })));
+    assert_eq!(
+        payload["columns"],
+        json!(["qualified_name", "kind", "path", "line_start"])
+    );
+    assert_eq!(payload["total_symbols"], 3);
+    assert_eq!(
+        payload["symbols"],
+        json!([
+            ["auth::Claims", "struct", "src/auth.rs", 11],
+            ["auth::validate_token", "function", "src/auth.rs", 1]
+        ])
+    );
+    let cursor = payload["next_cursor"].as_str().unwrap().to_string();
+
+    let payload = extract_payload_from_response(&call(json!({
+        "ref": "main",
+        "limit": 2,
+        "cursor": cursor
+    })));
+    assert_eq!(
+        payload["symbols"],
+        json!([["db::connect", "function", "src/db.rs", 21]])
+    );
+    assert!(payload["next_cursor"].is_null());
+
+    let payload = extract_payload_from_response(&call(json!({ "ref": "main", "cursor": "abc" })));
+    assert_eq!(payload["error"]["code"], "invalid_input");
+    let payload = extract_payload_from_response(&call(json!({ "ref": "main", "limit": 0 })));
+    assert_eq!(payload["error"]["code"], "invalid_input");
+    let payload = extract_payload_from_response(&call(json!({ "ref": "feat/missing" })));
+    assert_eq!(payload["error"]["code"], "ref_not_indexed");
+}
+
+#[test]
+fn t486_list_skipped_files_reports_reasons_with_paging() {
+    let tmp = tempfile::tempdir().unwrap();
+    let workspace_dir = tmp.path().join("workspace");
+    std::fs::create_dir_all(&workspace_dir).unwrap();
+
+    let db_path = tmp.path().join("state.db");
+    let mut conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    cruxe_state::schema::create_tables(&conn).unwrap();
+
+    let project_id = "skipped-files-project";
+    let now = "2026-02-26T00:00:00Z".to_string();
+    cruxe_state::branch_state::upsert_branch_state(
+        &conn,
+        &cruxe_state::branch_state::BranchState {
+            repo: project_id.to_string(),
+            r#ref: "main".to_string(),
+            merge_base_commit: None,
+            last_indexed_commit: "abc123".to_string(),
+            overlay_dir: None,
+            file_count: 1,
+            symbol_count: 0,
+            is_default_branch: true,
+            status: "active".to_string(),
+            eviction_eligible_at: None,
+            created_at: now.clone(),
+            last_accessed_at: now,
+        },
+    )
+    .unwrap();
+    let record = |path: &str, reason: &str, detail: Option<&str>| {
+        cruxe_state::skipped_files::SkippedFileRecord {
+            path: path.to_string(),
+            reason: reason.to_string(),
+            detail: detail.map(str::to_string),
+        }
+    };
+    cruxe_state::skipped_files::replace_for_job(
+        &mut conn,
+        project_id,
+        "main",
+        "job-1",
+        &[
+            record("README.md", "unsupported_language", None),
+            record("assets/logo.png", "binary", None),
+            record("src/broken.rs", "parse_failure", Some("unexpected token")),
+        ],
+    )
+    .unwrap();
+
+    let config = Config::default();
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: None,
+        conn: Some(&conn),
+        workspace: workspace_dir.as_path(),
+        project_id,
+    };
+    let call = |arguments: Value| call_tool(&tool_ctx, "list_skipped_files", arguments);
+
+    let payload = extract_payload_from_response(&call(json!({ "ref": "main", "limit": 2 })));
+    assert_eq!(payload["job_id"], "job-1");
+    assert_eq!(payload["total_skipped"], 3);
+    assert_eq!(
+        payload["skipped_files"],
+        json!([
+            { "path": "README.md", "reason": "unsupported_language" },
+            { "path": "assets/logo.png", "reason": "binary" }
+        ])
+    );
+    assert_eq!(payload["metadata"]["result_completeness"], "truncated");
+    let cursor = payload["next_cursor"].as_str().unwrap().to_string();
+
+    let payload = extract_payload_from_response(&call(json!({
+        "ref": "main",
+        "limit": 2,
+        "cursor": cursor
+    })));
+    assert_eq!(
+        payload["skipped_files"],
+        json!([{
+            "path": "src/broken.rs",
+            "reason": "parse_failure",
+            "detail": "unexpected token"
+        }])
+    );
+    assert!(payload["next_cursor"].is_null());
+
+    let payload = extract_payload_from_response(&call(json!({
+        "ref": "main",
+        "reason": "binary"
+    })));
+    assert_eq!(payload["total_skipped"], 1);
+    assert_eq!(payload["skipped_files"][0]["path"], "assets/logo.png");
+
+    let payload = extract_payload_from_response(&call(json!({ "ref": "main", "reason": "huge" })));
+    assert_eq!(payload["error"]["code"], "invalid_input");
+    let payload = extract_payload_from_response(&call(json!({ "ref": "feat/missing" })));
+    assert_eq!(payload["error"]["code"], "ref_not_indexed");
+}
+
 #[test]
 fn t353_suggest_followup_queries_low_confidence_returns_suggestions() {
     let config = Config::default();
@@ -613,6 +1124,7 @@ fn t462_transport_contexts_produce_same_tools_list_semantics() {
     };
     let stdio_transport = TransportExecutionContext {
         notifier: Arc::new(NullProgressNotifier),
+        progress_notifications: false,
         progress_token: None,
         session_scope: Some("stdio-test"),
         transport_label: "stdio-test",
@@ -621,6 +1133,7 @@ fn t462_transport_contexts_produce_same_tools_list_semantics() {
     };
     let http_transport = TransportExecutionContext {
         notifier: Arc::new(NullProgressNotifier),
+        progress_notifications: false,
         progress_token: None,
         session_scope: Some("http-test"),
         transport_label: "http-test",
@@ -637,6 +1150,79 @@ fn t462_transport_contexts_produce_same_tools_list_semantics() {
     );
 }
 
+#[test]
+fn t491_initialize_advertises_only_served_capabilities() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (config, workspace, project_id, data_dir, router, prewarm_status, server_start) =
+        build_dispatch_runtime_fixture(&tmp);
+    let connection_manager = ConnectionManager::new();
+    let runtime = DispatchRuntime {
+        config: &config,
+        router: &router,
+        workspace: &workspace,
+        project_id: &project_id,
+        data_dir: &data_dir,
+        connection_manager: &connection_manager,
+        prewarm_status: &prewarm_status,
+        server_start: &server_start,
+    };
+    let transport = |progress_notifications: bool| TransportExecutionContext {
+        notifier: Arc::new(NullProgressNotifier),
+        progress_notifications,
+        progress_token: None,
+        session_scope: Some("capabilities-test"),
+        transport_label: "capabilities-test",
+        log_workspace_resolution_failures: false,
+        log_degraded_sqlite_open: false,
+    };
+    let initialize = make_request(
+        "initialize",
+        json!({ "protocolVersion": "2024-11-05", "capabilities": {} }),
+    );
+
+    let result = execute_transport_request(&initialize, &runtime, &transport(false))
+        .result
+        .expect("initialize should succeed");
+    assert_eq!(result["protocolVersion"], "2024-11-05");
+    assert_eq!(result["serverInfo"]["name"], "cruxe");
+    let capabilities = result["capabilities"].as_object().unwrap();
+    assert!(!capabilities.contains_key("experimental"));
+
+    // Every advertised surface must answer; unadvertised ones must not.
+    let tools_list = execute_transport_request(
+        &make_request("tools/list", json!({})),
+        &runtime,
+        &transport(false),
+    );
+    let tool_count = tools_list.result.as_ref().unwrap()["tools"]
+        .as_array()
+        .unwrap()
+        .len();
+    assert_eq!(capabilities.contains_key("tools"), tool_count > 0);
+    for (capability, method) in [("resources", "resources/list"), ("prompts", "prompts/list")] {
+        let response = execute_transport_request(
+            &make_request(method, json!({})),
+            &runtime,
+            &transport(false),
+        );
+        let served = response.error.as_ref().is_none_or(|err| err.code != -32601);
+        assert_eq!(
+            capabilities.contains_key(capability),
+            served,
+            "{capability} advertisement must match {method} support"
+        );
+    }
+
+    let result = execute_transport_request(&initialize, &runtime, &transport(true))
+        .result
+        .expect("initialize should succeed");
+    assert!(
+        result["capabilities"]
+            .pointer("/experimental/progress")
+            .is_some()
+    );
+}
+
 #[test]
 fn t463_transport_contexts_match_for_validation_and_compatibility_errors() {
     let tmp = tempfile::tempdir().unwrap();
@@ -655,6 +1241,7 @@ fn t463_transport_contexts_match_for_validation_and_compatibility_errors() {
     };
     let stdio_transport = TransportExecutionContext {
         notifier: Arc::new(NullProgressNotifier),
+        progress_notifications: false,
         progress_token: None,
         session_scope: Some("stdio-test"),
         transport_label: "stdio-test",
@@ -663,6 +1250,7 @@ fn t463_transport_contexts_match_for_validation_and_compatibility_errors() {
     };
     let http_transport = TransportExecutionContext {
         notifier: Arc::new(NullProgressNotifier),
+        progress_notifications: false,
         progress_token: None,
         session_scope: Some("http-test"),
         transport_label: "http-test",
@@ -680,9 +1268,10 @@ fn t463_transport_contexts_match_for_validation_and_compatibility_errors() {
     let validation_stdio =
         execute_transport_request(&validation_request, &runtime, &stdio_transport);
     let validation_http = execute_transport_request(&validation_request, &runtime, &http_transport);
+    assert_eq!(validation_stdio.id, validation_http.id);
     assert_eq!(
-        serde_json::to_value(&validation_stdio).expect("serialize validation stdio response"),
-        serde_json::to_value(&validation_http).expect("serialize validation http response"),
+        payload_without_request_id(&validation_stdio),
+        payload_without_request_id(&validation_http),
         "validation error envelope should be transport-context invariant"
     );
 
@@ -697,9 +1286,10 @@ fn t463_transport_contexts_match_for_validation_and_compatibility_errors() {
         execute_transport_request(&compatibility_request, &runtime, &stdio_transport);
     let compatibility_http =
         execute_transport_request(&compatibility_request, &runtime, &http_transport);
+    assert_eq!(compatibility_stdio.id, compatibility_http.id);
     assert_eq!(
-        serde_json::to_value(&compatibility_stdio).expect("serialize compatibility stdio response"),
-        serde_json::to_value(&compatibility_http).expect("serialize compatibility http response"),
+        payload_without_request_id(&compatibility_stdio),
+        payload_without_request_id(&compatibility_http),
         "compatibility error envelope should be transport-context invariant"
     );
 }
@@ -785,6 +1375,7 @@ fn build_fixture_index(tmp_dir: &std::path::Path) -> FixtureIndex {
                 .collect::<Vec<_>>()
                 .join("\n")
                 .into(),
+            encoding_warning: false,
         };
 
         writer::write_file_records(&index_set, &conn, &symbols, &snippets, &file_record).unwrap();
@@ -802,23 +1393,47 @@ fn build_fixture_index(tmp_dir: &std::path::Path) -> FixtureIndex {
 }
 
 #[test]
-fn t066_locate_symbol_via_jsonrpc() {
+fn t487_locate_symbol_returns_rust_outer_attributes() {
     let tmp = tempfile::tempdir().unwrap();
     let index_set = build_fixture_index(tmp.path());
-
     let config = Config::default();
-    let workspace = Path::new("/tmp/fake-workspace");
-    let project_id = "test_project";
 
-    let request = make_request(
-        "tools/call",
-        json!({
-            "name": "locate_symbol",
-            "arguments": {
-                "name": "validate_token"
-            }
-        }),
-    );
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: None,
+        workspace: Path::new("/tmp/fake-workspace"),
+        project_id: "test_project",
+    };
+    let response = call_tool(&tool_ctx, "locate_symbol", json!({ "name": "AuthError" }));
+
+    let payload = extract_payload_from_response(&response);
+    let first = &payload["results"][0];
+    assert_eq!(first["name"], "AuthError");
+    assert_eq!(
+        first["attributes"],
+        json!(["derive(Debug, Clone, PartialEq)"])
+    );
+}
+
+#[test]
+fn t066_locate_symbol_via_jsonrpc() {
+    let tmp = tempfile::tempdir().unwrap();
+    let index_set = build_fixture_index(tmp.path());
+
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+    let project_id = "test_project";
+
+    let request = make_request(
+        "tools/call",
+        json!({
+            "name": "locate_symbol",
+            "arguments": {
+                "name": "validate_token"
+            }
+        }),
+    );
 
     let response = handle_request_with_ctx(
         &request,
@@ -1028,6 +1643,246 @@ fn t095_locate_symbol_detail_level_location() {
     );
 }
 
+#[test]
+fn t534_search_defaults_come_from_config_when_arguments_are_absent() {
+    let tmp = tempfile::tempdir().unwrap();
+    let index_set = build_fixture_index(tmp.path());
+
+    let mut config = Config::default();
+    config.search.default_detail_level = "location".to_string();
+    config.search.default_limit = 1;
+    let workspace = Path::new("/tmp/fake-workspace");
+
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: None,
+        workspace,
+        project_id: "test_project",
+    };
+    let call = |name: &str, arguments: Value| {
+        let response = call_tool(&tool_ctx, name, arguments);
+        assert!(response.error.is_none(), "expected success");
+        extract_results_from_response(&response)
+    };
+
+    for tool in ["search_code", "locate_symbol"] {
+        let key = if tool == "search_code" {
+            "query"
+        } else {
+            "name"
+        };
+        let defaulted = call(tool, json!({ key: "validate_token" }));
+        assert_eq!(defaulted.len(), 1, "{tool}: {defaulted:?}");
+        assert!(
+            defaulted[0].get("qualified_name").is_none(),
+            "{tool} should default to location detail"
+        );
+
+        let explicit = call(
+            tool,
+            json!({ key: "validate_token", "limit": 5, "detail_level": "signature" }),
+        );
+        assert!(!explicit.is_empty(), "{tool}");
+        assert!(
+            explicit[0].get("qualified_name").is_some(),
+            "{tool} explicit detail_level overrides the config default"
+        );
+    }
+
+    let wide = call("search_code", json!({ "query": "token", "limit": 5 }));
+    assert!(
+        wide.len() > 1,
+        "explicit limit overrides the config default"
+    );
+}
+
+#[test]
+fn t537_visibility_filter_narrows_locate_symbol_and_search_code() {
+    let tmp = tempfile::tempdir().unwrap();
+    let index_set = build_fixture_index(tmp.path());
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: None,
+        workspace,
+        project_id: "test_project",
+    };
+    let call = |name: &str, arguments: Value| {
+        let response = call_tool(&tool_ctx, name, arguments);
+        extract_payload_from_response(&response)
+    };
+    let locate = |name: &str, visibility: &str| -> Vec<Value> {
+        let payload = call(
+            "locate_symbol",
+            json!({ "name": name, "visibility": visibility }),
+        );
+        payload["results"].as_array().cloned().unwrap_or_default()
+    };
+
+    // `pub fn validate_token` is public; the inherent `fn ok` is private.
+    let public = locate("validate_token", "public");
+    assert_eq!(public.len(), 1, "{public:?}");
+    assert_eq!(public[0]["visibility"], "public");
+    assert!(locate("validate_token", "private").is_empty());
+    let private = locate("ok", "private");
+    assert!(!private.is_empty());
+    assert!(private.iter().all(|r| r["visibility"] == "private"));
+    assert!(locate("ok", "public").is_empty());
+
+    // Trait impl methods have no visibility of their own and only match `any`.
+    assert!(!locate("default", "any").is_empty());
+    assert!(locate("default", "public").is_empty());
+    assert!(locate("default", "private").is_empty());
+
+    let payload = call(
+        "search_code",
+        json!({ "query": "validate_token", "visibility": "public" }),
+    );
+    let results = payload["results"].as_array().unwrap();
+    assert!(!results.is_empty());
+    assert!(results.iter().all(|r| r["result_type"] == "symbol"));
+    assert!(results.iter().all(|r| r["visibility"] == "public"));
+
+    let invalid = call(
+        "search_code",
+        json!({ "query": "validate_token", "visibility": "exported" }),
+    );
+    assert_eq!(invalid["error"]["code"], "invalid_input");
+}
+
+#[test]
+fn t538_search_code_intent_override_bypasses_classifier() {
+    let tmp = tempfile::tempdir().unwrap();
+    let index_set = build_fixture_index(tmp.path());
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: None,
+        workspace,
+        project_id: "test_project",
+    };
+    let search = |arguments: Value| {
+        let response = call_tool(&tool_ctx, "search_code", arguments);
+        extract_payload_from_response(&response)
+    };
+
+    let classified = search(json!({ "query": "AuthHandler" }));
+    assert_eq!(classified["query_intent"], "symbol");
+
+    let forced = search(json!({ "query": "AuthHandler", "intent": "natural" }));
+    assert_eq!(forced["query_intent"], "natural_language");
+    assert!(forced.get("error").is_none(), "{forced}");
+
+    let phrase = search(json!({ "query": "validate token", "intent": "phrase" }));
+    assert_eq!(phrase["query_intent"], "phrase");
+
+    let invalid = search(json!({ "query": "AuthHandler", "intent": "fuzzy" }));
+    assert_eq!(invalid["error"]["code"], "invalid_input");
+}
+
+#[test]
+fn t539_locate_symbol_reports_parent_of_colliding_methods() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: Some(&conn),
+        workspace,
+        project_id: "test-repo",
+    };
+    let locate = |arguments: Value| {
+        let response = call_tool(&tool_ctx, "locate_symbol", arguments);
+        extract_payload_from_response(&response)["results"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default()
+    };
+
+    let results = locate(json!({ "name": "new", "kind": "method" }));
+    let parent_of = |path: &str| {
+        results
+            .iter()
+            .find(|r| r["path"] == path)
+            .map(|r| r["parent"].clone())
+            .unwrap_or_else(|| panic!("no `new` in {path}: {results:?}"))
+    };
+    assert_eq!(
+        parent_of("src/lib.rs"),
+        json!({ "name": "AppState", "kind": "struct", "line_start": 16 })
+    );
+    assert_eq!(parent_of("src/types.rs")["name"], "User");
+
+    // Top-level symbols have no parent; `location` detail leaves it out.
+    let top_level = locate(json!({ "name": "validate_token" }));
+    assert!(top_level[0].get("parent").is_none(), "{top_level:?}");
+    let location = locate(json!({ "name": "new", "detail_level": "location" }));
+    assert!(location.iter().all(|r| r.get("parent").is_none()));
+}
+
+#[test]
+fn t535_tools_call_batch_returns_one_result_per_call_in_order() {
+    let tmp = tempfile::tempdir().unwrap();
+    let index_set = build_fixture_index(tmp.path());
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: None,
+        workspace,
+        project_id: "test_project",
+    };
+    let call = |params: Value| tool_ctx.handle(&make_request("tools/call_batch", params));
+
+    let response = call(json!({
+        "calls": [
+            { "name": "locate_symbol", "arguments": { "name": "validate_token" } },
+            { "name": "no_such_tool", "arguments": {} },
+            { "arguments": {} },
+            { "name": "locate_symbol", "arguments": { "name": "AuthError", "workspace": "/elsewhere" } },
+            { "name": "locate_symbol", "arguments": {} },
+            { "name": "search_code", "arguments": { "query": "validate_token", "limit": 2 } },
+        ]
+    }));
+    assert!(response.error.is_none(), "{:?}", response.error);
+    let results = response.result.as_ref().unwrap()["results"]
+        .as_array()
+        .unwrap()
+        .clone();
+    assert_eq!(results.len(), 6);
+
+    let payload = |element: &Value| -> Value {
+        serde_json::from_str(element["result"]["content"][0]["text"].as_str().unwrap()).unwrap()
+    };
+    let located = payload(&results[0]);
+    assert_eq!(located["results"][0]["name"], "validate_token", "{located}");
+    assert!(located["metadata"]["request_id"].is_string());
+    assert_eq!(results[1]["error"]["code"], -32601);
+    assert_eq!(results[2]["error"]["code"], -32602);
+    assert_eq!(results[3]["error"]["code"], -32602);
+    // Tool-level failures come back as results, as they do for tools/call.
+    assert_eq!(payload(&results[4])["error"]["code"], "invalid_input");
+    assert!(payload(&results[5])["results"].as_array().unwrap().len() <= 2);
+
+    let invalid = call(json!({ "calls": "locate_symbol" }));
+    assert_eq!(invalid.error.as_ref().unwrap().code, -32602);
+    let oversized = call(json!({ "calls": vec![json!({ "name": "health_check" }); 65] }));
+    assert_eq!(oversized.error.as_ref().unwrap().code, -32602);
+}
+
 // ------------------------------------------------------------------
 // T096: locate_symbol with detail_level: "signature" (default)
 // ------------------------------------------------------------------
@@ -1110,7 +1965,7 @@ fn t096_locate_symbol_detail_level_signature_default() {
     );
     assert!(
         vt.get("parent").is_none(),
-        "signature level should NOT include parent"
+        "top-level validate_token should have no parent"
     );
     assert!(
         vt.get("related_symbols").is_none(),
@@ -1193,599 +2048,1047 @@ fn build_fixture_index_with_db(tmp_dir: &std::path::Path) -> (FixtureIndex, std:
     (index_set, db_path)
 }
 
-// ------------------------------------------------------------------
-// T102: get_file_outline nested tree
-// ------------------------------------------------------------------
-
 #[test]
-fn t102_get_file_outline_nested_tree() {
+fn t508_find_references_by_stable_id_includes_name_matches_with_kind() {
     let tmp = tempfile::tempdir().unwrap();
     let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
     let conn = cruxe_state::db::open_connection(&db_path).unwrap();
-
     let config = Config::default();
-    let workspace = Path::new("/tmp/fake-workspace");
-    let project_id = "test-repo";
-
-    // types.rs has struct User + impl User with methods — good for nesting test
-    let request = make_request(
-        "tools/call",
-        json!({
-            "name": "get_file_outline",
-            "arguments": {
-                "path": "src/types.rs"
-            }
-        }),
-    );
-
-    let response = handle_request_with_ctx(
-        &request,
-        &RequestContext {
-            config: &config,
-            index_set: Some(&index_set),
-            schema_status: SchemaStatus::Compatible,
-            compatibility_reason: None,
-            conn: Some(&conn),
-            workspace,
-            project_id,
-            prewarm_status: &test_prewarm_status(),
-            server_start: &test_server_start(),
-            notifier: Arc::new(NullProgressNotifier),
-            progress_token: None,
+    let workspace = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../testdata/fixtures/rust-sample");
+    let now = "2026-02-26T00:00:00Z".to_string();
+    cruxe_state::project::create_project(
+        &conn,
+        &Project {
+            project_id: "test-repo".to_string(),
+            repo_root: workspace.to_string_lossy().to_string(),
+            display_name: Some("rust-sample".to_string()),
+            default_ref: "live".to_string(),
+            vcs_mode: false,
+            schema_version: 1,
+            parser_version: 1,
+            created_at: now.clone(),
+            updated_at: now,
         },
-    );
+    )
+    .unwrap();
+    let target = cruxe_state::symbols::find_symbols_by_name(
+        &conn,
+        "test-repo",
+        "live",
+        "validate_token",
+        None,
+    )
+    .unwrap()
+    .remove(0);
 
-    assert!(
-        response.error.is_none(),
-        "expected success, got error: {:?}",
-        response.error
-    );
-    let result = response.result.as_ref().expect("result should be present");
-    let content = result.get("content").unwrap().as_array().unwrap();
-    let text = content[0].get("text").unwrap().as_str().unwrap();
-    let payload: serde_json::Value = serde_json::from_str(text).unwrap();
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: Some(&conn),
+        workspace: &workspace,
+        project_id: "test-repo",
+    };
+    let call = |arguments: Value| {
+        extract_payload_from_response(&call_tool(&tool_ctx, "find_references", arguments))
+    };
 
-    assert_eq!(
-        payload.get("file_path").unwrap().as_str().unwrap(),
-        "src/types.rs"
+    let payload = call(json!({
+        "symbol_stable_id": target.symbol_stable_id,
+        "include_name_matches": true,
+    }));
+    assert_eq!(payload["symbol"]["name"], "validate_token", "{payload}");
+    let references = payload["references"].as_array().expect("references array");
+    assert_eq!(references[0]["kind"], "definition", "{payload}");
+    assert_eq!(references[0]["path"], "src/auth.rs");
+    assert_eq!(references[0]["line_start"], 72);
+    assert!(
+        references.iter().any(|reference| {
+            reference["path"] == "src/handler.rs"
+                && reference["line_start"] == 90
+                && reference["kind"] == "usage"
+                && reference["edge_type"] == "name_match"
+        }),
+        "{payload}"
     );
-    assert_eq!(payload.get("language").unwrap().as_str().unwrap(), "rust");
 
-    let symbols = payload
-        .get("symbols")
-        .expect("should have symbols")
-        .as_array()
-        .expect("symbols should be an array");
+    let missing = call(json!({}));
+    assert_eq!(missing["error"]["code"], "invalid_input", "{missing}");
+}
 
-    assert!(!symbols.is_empty(), "should have symbols for types.rs");
+#[test]
+fn t500_implementations_of_lists_trait_impls_with_defining_types() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    let config = Config::default();
+    // Rust impl headers are read from the workspace sources.
+    let workspace = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../testdata/fixtures/rust-sample");
 
-    // Verify at least one symbol has children (impl block with methods)
-    let has_children = symbols.iter().any(|s| {
-        let children = s.get("children").and_then(|c| c.as_array());
-        children.map(|c| !c.is_empty()).unwrap_or(false)
-    });
-    assert!(
-        has_children,
-        "types.rs should have impl blocks with children (methods)"
-    );
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: Some(&conn),
+        workspace: &workspace,
+        project_id: "test-repo",
+    };
+    let call = |arguments: Value| {
+        extract_payload_from_response(&call_tool(&tool_ctx, "implementations_of", arguments))
+    };
 
-    // Verify metadata
-    let metadata = payload.get("metadata").expect("should have metadata");
-    assert!(metadata.get("symbol_count").unwrap().as_u64().unwrap() > 0);
+    let payload = call(json!({ "symbol_name": "fmt::Display::fmt", "ref": "live" }));
+    assert_eq!(payload["definitions"], json!([]), "{payload}");
+    let implementations = payload["implementations"].as_array().unwrap();
+    let types: Vec<&str> = implementations
+        .iter()
+        .map(|found| found["defining_type"].as_str().unwrap())
+        .collect();
+    for expected in ["AuthError", "Role", "User", "Config"] {
+        assert!(types.contains(&expected), "{expected} missing: {payload}");
+    }
+    assert!(implementations.iter().all(|found| {
+        found["relation"] == "implements"
+            && found["base_type"] == "Display"
+            && found["path"].is_string()
+    }));
+    assert_eq!(payload["total_implementations"], implementations.len());
+
+    let limited = call(json!({ "symbol_name": "Display::fmt", "ref": "live", "limit": 1 }));
+    assert_eq!(limited["implementations"].as_array().unwrap().len(), 1);
+    assert_eq!(limited["truncated"], true);
+
+    let missing = call(json!({ "symbol_name": "no_such_method", "ref": "live" }));
+    assert_eq!(missing["error"]["code"], "symbol_not_found", "{missing}");
 }
 
-// ------------------------------------------------------------------
-// T103: get_file_outline with depth: "top"
-// ------------------------------------------------------------------
-
 #[test]
-fn t103_get_file_outline_top_level_only() {
+fn t501_locate_symbol_context_level_includes_doc_comment() {
     let tmp = tempfile::tempdir().unwrap();
     let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
     let conn = cruxe_state::db::open_connection(&db_path).unwrap();
-
     let config = Config::default();
-    let workspace = Path::new("/tmp/fake-workspace");
-    let project_id = "test-repo";
 
-    let request = make_request(
-        "tools/call",
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: Some(&conn),
+        workspace: Path::new("/tmp/fake-workspace"),
+        project_id: "test-repo",
+    };
+    let response = call_tool(
+        &tool_ctx,
+        "locate_symbol",
         json!({
-            "name": "get_file_outline",
-            "arguments": {
-                "path": "src/types.rs",
-                "depth": "top"
-            }
+            "name": "validate_token",
+            "detail_level": "context"
         }),
     );
-
-    let response = handle_request_with_ctx(
-        &request,
-        &RequestContext {
-            config: &config,
-            index_set: Some(&index_set),
-            schema_status: SchemaStatus::Compatible,
-            compatibility_reason: None,
-            conn: Some(&conn),
-            workspace,
-            project_id,
-            prewarm_status: &test_prewarm_status(),
-            server_start: &test_server_start(),
-            notifier: Arc::new(NullProgressNotifier),
-            progress_token: None,
-        },
-    );
-
-    assert!(response.error.is_none(), "expected success");
-    let result = response.result.as_ref().unwrap();
-    let content = result.get("content").unwrap().as_array().unwrap();
-    let text = content[0].get("text").unwrap().as_str().unwrap();
-    let payload: serde_json::Value = serde_json::from_str(text).unwrap();
-
-    let symbols = payload.get("symbols").unwrap().as_array().unwrap();
-
-    assert!(!symbols.is_empty(), "should have top-level symbols");
-
-    // With depth="top", no symbol should have non-empty children
-    for sym in symbols {
-        let children = sym.get("children").and_then(|c| c.as_array());
-        let has_children = children.map(|c| !c.is_empty()).unwrap_or(false);
-        assert!(
-            !has_children,
-            "top-level only mode should not include children, but '{}' has children",
-            sym.get("name").unwrap().as_str().unwrap_or("?")
-        );
-    }
-}
-
-// ------------------------------------------------------------------
-// T104: get_file_outline on non-existent file
-// ------------------------------------------------------------------
+    let payload = extract_payload_from_response(&response);
+    let results = payload["results"].as_array().expect("results array");
+    let doc = results
+        .iter()
+        .find(|result| result["name"] == "validate_token")
+        .and_then(|result| result["doc_comment"].as_str())
+        .unwrap_or_else(|| panic!("expected doc_comment on validate_token: {payload}"));
+    assert!(doc.contains("encoded JWT"), "{doc}");
+    assert!(!doc.contains("///"), "{doc}");
+}
 
 #[test]
-fn t104_get_file_outline_nonexistent_file() {
+fn t532_locate_symbol_context_level_includes_parsed_signature() {
     let tmp = tempfile::tempdir().unwrap();
     let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
     let conn = cruxe_state::db::open_connection(&db_path).unwrap();
-
     let config = Config::default();
-    let workspace = Path::new("/tmp/fake-workspace");
-    let project_id = "test-repo";
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: Some(&conn),
+        workspace: Path::new("/tmp/fake-workspace"),
+        project_id: "test-repo",
+    };
+    let call = |detail_level: &str| {
+        let payload = extract_payload_from_response(&call_tool(
+            &tool_ctx,
+            "locate_symbol",
+            json!({ "name": "validate_token", "detail_level": detail_level }),
+        ));
+        payload["results"]
+            .as_array()
+            .and_then(|results| {
+                results
+                    .iter()
+                    .find(|result| result["name"] == "validate_token")
+                    .cloned()
+            })
+            .unwrap_or_else(|| panic!("expected validate_token result: {payload}"))
+    };
 
-    let request = make_request(
-        "tools/call",
+    let context = call("context");
+    assert_eq!(
+        context["parsed_signature"],
         json!({
-            "name": "get_file_outline",
-            "arguments": {
-                "path": "src/nonexistent_file.rs"
-            }
+            "params": [
+                { "name": "auth_header", "type": "&str" },
+                { "name": "secret", "type": "&[u8]" }
+            ],
+            "return_type": "Result<Claims, AuthError>"
         }),
+        "{context}"
     );
+    assert!(call("signature").get("parsed_signature").is_none());
+}
 
-    let response = handle_request_with_ctx(
-        &request,
-        &RequestContext {
-            config: &config,
-            index_set: Some(&index_set),
-            schema_status: SchemaStatus::Compatible,
-            compatibility_reason: None,
-            conn: Some(&conn),
-            workspace,
-            project_id,
-            prewarm_status: &test_prewarm_status(),
-            server_start: &test_server_start(),
-            notifier: Arc::new(NullProgressNotifier),
-            progress_token: None,
-        },
-    );
-
-    assert!(response.error.is_none(), "MCP tool errors are in content");
-    let result = response.result.as_ref().unwrap();
-    let content = result.get("content").unwrap().as_array().unwrap();
-    let text = content[0].get("text").unwrap().as_str().unwrap();
-    let payload: serde_json::Value = serde_json::from_str(text).unwrap();
+#[test]
+fn t502_get_file_outline_includes_symbol_attributes() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    let config = Config::default();
 
-    // Should have an error object with file_not_found code
-    let error = payload.get("error").expect("should have error object");
-    assert_eq!(
-        error.get("code").unwrap().as_str().unwrap(),
-        "file_not_found"
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: Some(&conn),
+        workspace: Path::new("/tmp/fake-workspace"),
+        project_id: "test-repo",
+    };
+    let response = call_tool(
+        &tool_ctx,
+        "get_file_outline",
+        json!({ "path": "src/types.rs", "depth": "top" }),
     );
+    let payload = extract_payload_from_response(&response);
+    let symbols = payload["symbols"].as_array().expect("symbols array");
+    let user = symbols
+        .iter()
+        .find(|symbol| symbol["name"] == "User" && symbol["kind"] == "struct")
+        .unwrap_or_else(|| panic!("expected User struct: {payload}"));
+    assert_eq!(user["attributes"], json!(["derive(Debug, Clone)"]));
 }
 
 #[test]
-fn t104_get_file_outline_existing_file_without_symbols_returns_empty() {
+fn t503_get_import_graph_lists_importers_and_imports() {
     let tmp = tempfile::tempdir().unwrap();
     let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
     let conn = cruxe_state::db::open_connection(&db_path).unwrap();
-
-    cruxe_state::manifest::upsert_manifest(
+    let config = Config::default();
+    let now = "2026-02-26T00:00:00Z".to_string();
+    cruxe_state::branch_state::upsert_branch_state(
         &conn,
-        &cruxe_state::manifest::ManifestEntry {
+        &cruxe_state::branch_state::BranchState {
             repo: "test-repo".to_string(),
             r#ref: "live".to_string(),
-            path: "docs/README.md".to_string(),
-            content_hash: blake3::hash(b"hello").to_hex().to_string(),
-            size_bytes: 5,
-            mtime_ns: None,
-            language: Some("markdown".to_string()),
-            indexed_at: "2026-01-01T00:00:00Z".to_string(),
+            merge_base_commit: None,
+            last_indexed_commit: "abc123".to_string(),
+            overlay_dir: None,
+            file_count: 5,
+            symbol_count: 0,
+            is_default_branch: true,
+            status: "active".to_string(),
+            eviction_eligible_at: None,
+            created_at: now.clone(),
+            last_accessed_at: now,
         },
     )
     .unwrap();
 
-    let config = Config::default();
-    let workspace = Path::new("/tmp/fake-workspace");
-    let project_id = "test-repo";
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: Some(&conn),
+        workspace: Path::new("/tmp/fake-workspace"),
+        project_id: "test-repo",
+    };
+    let call = |arguments: Value| {
+        extract_payload_from_response(&call_tool(&tool_ctx, "get_import_graph", arguments))
+    };
 
-    let request = make_request(
-        "tools/call",
-        json!({
-            "name": "get_file_outline",
-            "arguments": {
-                "path": "docs/README.md",
-                "language": "markdown"
-            }
+    let payload = call(json!({ "symbol_name": "Claims", "path": "src/auth.rs" }));
+    assert_eq!(payload["root"]["path"], "src/auth.rs", "{payload}");
+    let importers = payload["importers"].as_array().expect("importers array");
+    assert!(
+        importers.iter().any(|edge| {
+            edge["source_path"] == "src/handler.rs"
+                && edge["target_path"] == "src/auth.rs"
+                && edge["import_kind"] == "internal"
+                && edge["depth"] == 1
         }),
+        "{payload}"
     );
+    assert_eq!(payload["imports"], json!([]));
 
-    let response = handle_request_with_ctx(
-        &request,
-        &RequestContext {
-            config: &config,
-            index_set: Some(&index_set),
-            schema_status: SchemaStatus::Compatible,
-            compatibility_reason: None,
-            conn: Some(&conn),
-            workspace,
-            project_id,
-            prewarm_status: &test_prewarm_status(),
-            server_start: &test_server_start(),
-            notifier: Arc::new(NullProgressNotifier),
-            progress_token: None,
-        },
-    );
-
-    assert!(response.error.is_none(), "expected success");
-    let result = response.result.as_ref().unwrap();
-    let content = result.get("content").unwrap().as_array().unwrap();
-    let text = content[0].get("text").unwrap().as_str().unwrap();
-    let payload: serde_json::Value = serde_json::from_str(text).unwrap();
-
+    let payload = call(json!({ "path": "src/handler.rs", "direction": "imports" }));
+    let imports = payload["imports"].as_array().expect("imports array");
     assert!(
-        payload.get("error").is_none(),
-        "existing file without symbols should not return file_not_found"
+        imports
+            .iter()
+            .any(|edge| edge["target_path"] == "src/auth.rs"),
+        "{payload}"
     );
-    assert_eq!(
-        payload.get("file_path").unwrap().as_str().unwrap(),
-        "docs/README.md"
+    assert!(
+        imports
+            .iter()
+            .all(|edge| edge["source_path"] == "src/handler.rs")
     );
-    let symbols = payload.get("symbols").unwrap().as_array().unwrap();
-    assert!(symbols.is_empty(), "symbols should be empty");
+
+    let missing = call(json!({}));
+    assert_eq!(missing["error"]["code"], "invalid_input", "{missing}");
+    let bad_direction = call(json!({ "path": "src/auth.rs", "direction": "sideways" }));
+    assert_eq!(bad_direction["error"]["code"], "invalid_input");
 }
 
 #[test]
-fn t165_get_symbol_hierarchy_ancestors_via_jsonrpc() {
+fn t504_locate_symbol_fuzzy_matches_typos_after_exact_hits() {
     let tmp = tempfile::tempdir().unwrap();
     let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
     let conn = cruxe_state::db::open_connection(&db_path).unwrap();
     let config = Config::default();
-    let workspace = Path::new("/tmp/fake-workspace");
-    let project_id = "test-repo";
-
-    let request = make_request(
-        "tools/call",
-        json!({
-            "name": "get_symbol_hierarchy",
-            "arguments": {
-                "symbol_name": "authenticate",
-                "path": "src/handler.rs",
-                "direction": "ancestors"
-            }
-        }),
-    );
 
-    let response = handle_request_with_ctx(
-        &request,
-        &RequestContext {
-            config: &config,
-            index_set: Some(&index_set),
-            schema_status: SchemaStatus::Compatible,
-            compatibility_reason: None,
-            conn: Some(&conn),
-            workspace,
-            project_id,
-            prewarm_status: &test_prewarm_status(),
-            server_start: &test_server_start(),
-            notifier: Arc::new(NullProgressNotifier),
-            progress_token: None,
-        },
-    );
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: Some(&conn),
+        workspace: Path::new("/tmp/fake-workspace"),
+        project_id: "test-repo",
+    };
+    let call = |arguments: Value| {
+        extract_payload_from_response(&call_tool(&tool_ctx, "locate_symbol", arguments))
+    };
 
-    assert!(response.error.is_none(), "expected success");
-    let payload = extract_payload_from_response(&response);
-    assert_eq!(
-        payload.get("direction").unwrap().as_str().unwrap(),
-        "ancestors"
-    );
+    let strict = call(json!({ "name": "valiate_token" }));
+    assert_eq!(strict["results"], json!([]), "{strict}");
 
-    let hierarchy = payload.get("hierarchy").unwrap().as_array().unwrap();
-    assert!(!hierarchy.is_empty(), "hierarchy should not be empty");
-    assert_eq!(
-        hierarchy[0].get("name").unwrap().as_str().unwrap(),
-        "authenticate"
-    );
-    let names = hierarchy
+    let fuzzy = call(json!({ "name": "valiate_token", "fuzzy": true }));
+    let results = fuzzy["results"].as_array().expect("results array");
+    let hit = results
         .iter()
-        .filter_map(|item| item.get("name").and_then(|v| v.as_str()))
-        .collect::<Vec<_>>();
+        .find(|result| result["name"] == "validate_token")
+        .unwrap_or_else(|| panic!("expected fuzzy hit: {fuzzy}"));
+    assert_eq!(hit["match_type"], "fuzzy");
+
+    let mixed = call(json!({ "name": "validate_token", "fuzzy": true }));
+    let results = mixed["results"].as_array().expect("results array");
+    assert_eq!(results[0]["name"], "validate_token", "{mixed}");
+    assert_eq!(results[0]["match_type"], "exact");
+    let first_fuzzy = results
+        .iter()
+        .position(|result| result["match_type"] == "fuzzy")
+        .unwrap_or(results.len());
     assert!(
-        names.contains(&"AuthHandler"),
-        "ancestor chain should include AuthHandler; got {:?}",
-        names
-    );
-
-    let metadata = payload.get("metadata").unwrap();
-    assert_eq!(
-        metadata
-            .get("cruxe_protocol_version")
-            .unwrap()
-            .as_str()
-            .unwrap(),
-        "1.0"
+        results[first_fuzzy..]
+            .iter()
+            .all(|result| result["match_type"] == "fuzzy"),
+        "exact matches must precede fuzzy ones: {mixed}"
     );
 }
 
 #[test]
-fn t166_get_symbol_hierarchy_descendants_via_jsonrpc() {
+fn t505_search_and_locate_cursors_page_deterministically() {
     let tmp = tempfile::tempdir().unwrap();
     let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
     let conn = cruxe_state::db::open_connection(&db_path).unwrap();
     let config = Config::default();
-    let workspace = Path::new("/tmp/fake-workspace");
-    let project_id = "test-repo";
 
-    let request = make_request(
-        "tools/call",
-        json!({
-            "name": "get_symbol_hierarchy",
-            "arguments": {
-                "symbol_name": "AuthHandler",
-                "path": "src/handler.rs",
-                "direction": "descendants"
-            }
-        }),
-    );
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: Some(&conn),
+        workspace: Path::new("/tmp/fake-workspace"),
+        project_id: "test-repo",
+    };
+    let call = |tool: &str, arguments: Value| {
+        extract_payload_from_response(&call_tool(&tool_ctx, tool, arguments))
+    };
+    let keys = |payload: &Value| -> Vec<String> {
+        payload["results"]
+            .as_array()
+            .unwrap_or_else(|| panic!("results array: {payload}"))
+            .iter()
+            .map(|result| {
+                format!(
+                    "{}:{}:{}",
+                    result["path"], result["line_start"], result["name"]
+                )
+            })
+            .collect()
+    };
 
-    let response = handle_request_with_ctx(
-        &request,
-        &RequestContext {
-            config: &config,
-            index_set: Some(&index_set),
-            schema_status: SchemaStatus::Compatible,
-            compatibility_reason: None,
-            conn: Some(&conn),
-            workspace,
-            project_id,
-            prewarm_status: &test_prewarm_status(),
-            server_start: &test_server_start(),
-            notifier: Arc::new(NullProgressNotifier),
-            progress_token: None,
-        },
-    );
+    for (tool, query_arg, query) in [
+        ("search_code", "query", "user"),
+        ("locate_symbol", "name", "fmt"),
+    ] {
+        let full = call(tool, json!({ query_arg: query, "limit": 4 }));
+        let first = call(tool, json!({ query_arg: query, "limit": 2 }));
+        let cursor = first["metadata"]["next_cursor"]
+            .as_str()
+            .unwrap_or_else(|| panic!("{tool} first page should have a cursor: {first}"))
+            .to_string();
+        let second = call(
+            tool,
+            json!({ query_arg: query, "limit": 2, "cursor": cursor }),
+        );
 
-    assert!(response.error.is_none(), "expected success");
-    let payload = extract_payload_from_response(&response);
-    assert_eq!(
-        payload.get("direction").unwrap().as_str().unwrap(),
-        "descendants"
+        let mut paged = keys(&first);
+        paged.extend(keys(&second));
+        assert_eq!(paged, keys(&full), "{tool} pages should tile the ranking");
+
+        let other_query = call(
+            tool,
+            json!({ query_arg: "validate_token", "limit": 2, "cursor": cursor }),
+        );
+        assert_eq!(
+            other_query["error"]["code"], "invalid_input",
+            "{tool}: {other_query}"
+        );
+        let other_ref = call(
+            tool,
+            json!({ query_arg: query, "limit": 2, "cursor": cursor, "ref": "feat/other" }),
+        );
+        assert_eq!(
+            other_ref["error"]["code"], "invalid_input",
+            "{tool}: {other_ref}"
+        );
+    }
+
+    let malformed = call(
+        "locate_symbol",
+        json!({ "name": "fmt", "cursor": "page-two" }),
     );
-    let hierarchy = payload.get("hierarchy").unwrap().as_array().unwrap();
-    assert_eq!(
-        hierarchy.len(),
-        1,
-        "descendants should return a single root"
+    assert_eq!(malformed["error"]["code"], "invalid_input");
+    let last = call(
+        "locate_symbol",
+        json!({ "name": "validate_token", "limit": 10 }),
     );
-    let children = hierarchy[0]
-        .get("children")
-        .unwrap()
+    assert!(last["metadata"].get("next_cursor").is_none(), "{last}");
+}
+
+#[test]
+fn t506_list_files_reports_manifest_entries_with_symbol_counts() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    let config = Config::default();
+    let now = "2026-02-26T00:00:00Z".to_string();
+    cruxe_state::branch_state::upsert_branch_state(
+        &conn,
+        &cruxe_state::branch_state::BranchState {
+            repo: "test-repo".to_string(),
+            r#ref: "live".to_string(),
+            merge_base_commit: None,
+            last_indexed_commit: "abc123".to_string(),
+            overlay_dir: None,
+            file_count: 5,
+            symbol_count: 0,
+            is_default_branch: true,
+            status: "active".to_string(),
+            eviction_eligible_at: None,
+            created_at: now.clone(),
+            last_accessed_at: now,
+        },
+    )
+    .unwrap();
+
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: Some(&conn),
+        workspace: Path::new("/tmp/fake-workspace"),
+        project_id: "test-repo",
+    };
+    let call = |arguments: Value| {
+        extract_payload_from_response(&call_tool(&tool_ctx, "list_files", arguments))
+    };
+
+    let payload = call(json!({ "language": "rust", "path_prefix": "src/" }));
+    let files = payload["files"]
         .as_array()
-        .expect("children should be an array");
-    let child_names = children
+        .unwrap_or_else(|| panic!("{payload}"));
+    let auth = files
         .iter()
-        .filter_map(|item| item.get("name").and_then(|v| v.as_str()))
-        .collect::<Vec<_>>();
-    assert!(
-        child_names.contains(&"handle_request"),
-        "descendants should include handle_request"
-    );
-    assert!(
-        child_names.contains(&"authenticate"),
-        "descendants should include authenticate"
-    );
+        .find(|file| file["path"] == "src/auth.rs")
+        .unwrap_or_else(|| panic!("expected src/auth.rs: {payload}"));
+    assert_eq!(auth["language"], "rust");
+    assert!(auth["size_bytes"].as_u64().unwrap() > 0);
+    assert!(auth["symbol_count"].as_u64().unwrap() > 0);
+    assert!(files.iter().all(|file| {
+        file["path"].as_str().unwrap().starts_with("src/") && file["language"] == "rust"
+    }));
+    assert_eq!(payload["total_files"], files.len());
+
+    let limited = call(json!({ "limit": 1 }));
+    assert_eq!(limited["files"].as_array().unwrap().len(), 1);
+    assert_eq!(limited["metadata"]["result_completeness"], "truncated");
+
+    let none = call(json!({ "language": "cobol" }));
+    assert_eq!(none["files"], json!([]));
+    assert_eq!(none["total_files"], 0);
+
+    let invalid = call(json!({ "limit": 0 }));
+    assert_eq!(invalid["error"]["code"], "invalid_input");
 }
 
 #[test]
-fn t175_find_related_symbols_scope_file_via_jsonrpc() {
+fn t488_symbol_at_offset_resolves_innermost_symbol() {
     let tmp = tempfile::tempdir().unwrap();
     let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
     let conn = cruxe_state::db::open_connection(&db_path).unwrap();
     let config = Config::default();
-    let workspace = Path::new("/tmp/fake-workspace");
-    let project_id = "test-repo";
 
-    let request = make_request(
-        "tools/call",
-        json!({
-            "name": "find_related_symbols",
-            "arguments": {
-                "symbol_name": "authenticate",
-                "path": "src/handler.rs",
-                "scope": "file",
-                "limit": 20
-            }
-        }),
-    );
-    let response = handle_request_with_ctx(
-        &request,
-        &RequestContext {
-            config: &config,
-            index_set: Some(&index_set),
-            schema_status: SchemaStatus::Compatible,
-            compatibility_reason: None,
-            conn: Some(&conn),
-            workspace,
-            project_id,
-            prewarm_status: &test_prewarm_status(),
-            server_start: &test_server_start(),
-            notifier: Arc::new(NullProgressNotifier),
-            progress_token: None,
-        },
-    );
+    let source = std::fs::read_to_string(
+        std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../testdata/fixtures/rust-sample/src/types.rs"),
+    )
+    .unwrap();
+    let method_offset = source.find("pub fn has_role").unwrap() + 20;
+    let struct_offset = source.find("pub struct User {").unwrap() + 4;
 
-    assert!(response.error.is_none(), "expected success");
-    let payload = extract_payload_from_response(&response);
-    let related = payload.get("related").unwrap().as_array().unwrap();
-    assert!(
-        !related.is_empty(),
-        "file scope should return sibling symbols"
-    );
-    assert!(
-        related
-            .iter()
-            .all(|item| item.get("relation").unwrap().as_str().unwrap() == "same_file"),
-        "file scope should only return same_file relation"
-    );
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: Some(&conn),
+        workspace: Path::new("/tmp/fake-workspace"),
+        project_id: "test-repo",
+    };
+    let call = |arguments: Value| call_tool(&tool_ctx, "symbol_at_offset", arguments);
+
+    let payload = extract_payload_from_response(&call(json!({
+        "path": "src/types.rs",
+        "byte_offset": method_offset
+    })));
+    assert_eq!(payload["symbol"]["name"], "has_role", "{payload}");
+    let byte_start = payload["symbol"]["byte_start"].as_u64().unwrap() as usize;
+    let byte_end = payload["symbol"]["byte_end"].as_u64().unwrap() as usize;
+    assert!(byte_start <= method_offset && method_offset < byte_end);
+    assert!(source[byte_start..byte_end].contains("fn has_role"));
+
+    let payload = extract_payload_from_response(&call(json!({
+        "path": "src/types.rs",
+        "byte_offset": struct_offset
+    })));
+    assert_eq!(payload["symbol"]["name"], "User", "{payload}");
+
+    let payload = extract_payload_from_response(&call(json!({
+        "path": "src/types.rs",
+        "byte_offset": 0
+    })));
+    assert_eq!(payload["error"]["code"], "symbol_not_found");
+    let payload = extract_payload_from_response(&call(json!({
+        "path": "src/missing.rs",
+        "byte_offset": 10
+    })));
+    assert_eq!(payload["error"]["code"], "file_not_found");
+    let payload = extract_payload_from_response(&call(json!({
+        "path": "src/types.rs",
+        "byte_offset": -1
+    })));
+    assert_eq!(payload["error"]["code"], "invalid_input");
 }
 
 #[test]
-fn t176_find_related_symbols_scope_module_includes_imported() {
+fn t509_symbol_at_location_returns_innermost_symbol_and_parents() {
     let tmp = tempfile::tempdir().unwrap();
     let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
     let conn = cruxe_state::db::open_connection(&db_path).unwrap();
     let config = Config::default();
-    let workspace = Path::new("/tmp/fake-workspace");
-    let project_id = "test-repo";
 
-    // Add one synthetic symbol outside src/ so module-scope lookup cannot
-    // discover it via path prefix, only via import edges.
-    let synthetic_symbol = cruxe_core::types::SymbolRecord {
-        repo: project_id.to_string(),
-        r#ref: "live".to_string(),
-        commit: None,
-        path: "vendor/external.rs".to_string(),
-        language: "rust".to_string(),
-        symbol_id: cruxe_core::types::compute_symbol_id(
-            project_id,
-            "live",
-            "vendor/external.rs",
-            &cruxe_core::types::SymbolKind::Function,
-            1,
-            "external_helper",
-        ),
-        symbol_stable_id: cruxe_core::types::compute_symbol_stable_id(
-            "rust",
-            &cruxe_core::types::SymbolKind::Function,
-            "vendor::external_helper",
-            None,
-        ),
-        name: "external_helper".to_string(),
-        qualified_name: "vendor::external_helper".to_string(),
-        kind: cruxe_core::types::SymbolKind::Function,
-        signature: Some("fn external_helper()".to_string()),
-        line_start: 1,
-        line_end: 3,
-        parent_symbol_id: None,
-        visibility: Some("pub".to_string()),
-        content: Some("fn external_helper() {}".to_string()),
-    };
-    cruxe_state::symbols::insert_symbol(&conn, &synthetic_symbol).unwrap();
+    let source = std::fs::read_to_string(
+        std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../testdata/fixtures/rust-sample/src/types.rs"),
+    )
+    .unwrap();
+    let method_line = source
+        .lines()
+        .position(|line| line.contains("pub fn has_role"))
+        .unwrap() as u64
+        + 2;
 
-    let edge = cruxe_core::types::SymbolEdge {
-        repo: project_id.to_string(),
-        ref_name: "live".to_string(),
-        from_symbol_id: cruxe_indexer::import_extract::source_symbol_id_for_path("src/handler.rs"),
-        to_symbol_id: synthetic_symbol.symbol_stable_id.clone(),
-        edge_type: "imports".to_string(),
-        confidence: "static".to_string(),
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: Some(&conn),
+        workspace: Path::new("/tmp/fake-workspace"),
+        project_id: "test-repo",
+    };
+    let call = |arguments: Value| {
+        extract_payload_from_response(&call_tool(&tool_ctx, "symbol_at_location", arguments))
     };
-    cruxe_state::edges::insert_edges(&conn, project_id, "live", vec![edge]).unwrap();
-
-    let request = make_request(
-        "tools/call",
-        json!({
-            "name": "find_related_symbols",
-            "arguments": {
-                "symbol_name": "handle_request",
-                "path": "src/handler.rs",
-                "scope": "module",
-                "limit": 200
-            }
-        }),
-    );
-    let response = handle_request_with_ctx(
-        &request,
-        &RequestContext {
-            config: &config,
-            index_set: Some(&index_set),
-            schema_status: SchemaStatus::Compatible,
-            compatibility_reason: None,
-            conn: Some(&conn),
-            workspace,
-            project_id,
-            prewarm_status: &test_prewarm_status(),
-            server_start: &test_server_start(),
-            notifier: Arc::new(NullProgressNotifier),
-            progress_token: None,
-        },
-    );
 
-    assert!(response.error.is_none(), "expected success");
-    let payload = extract_payload_from_response(&response);
-    let related = payload.get("related").unwrap().as_array().unwrap();
-    let relations = related
-        .iter()
-        .filter_map(|item| item.get("relation").and_then(|v| v.as_str()))
-        .collect::<Vec<_>>();
+    let payload = call(json!({ "path": "src/types.rs", "line": method_line }));
+    assert_eq!(payload["symbol"]["name"], "has_role", "{payload}");
+    let line_start = payload["symbol"]["line_start"].as_u64().unwrap();
+    let line_end = payload["symbol"]["line_end"].as_u64().unwrap();
+    assert!(line_start <= method_line && method_line <= line_end);
+    let parents = payload["parents"].as_array().expect("parents array");
     assert!(
-        relations.contains(&"same_module"),
-        "module scope should include same_module symbols"
+        !parents.is_empty(),
+        "method should have a parent: {payload}"
     );
-    assert!(
-        relations.contains(&"imported"),
-        "module scope should include imported symbols when edges exist; got {:?}",
-        relations
+    assert_eq!(parents[0]["depth"], 1);
+    assert_eq!(
+        payload["symbol"]["parent_symbol_id"],
+        parents[0]["symbol_id"]
     );
+
+    let payload = call(json!({ "path": "src/missing.rs", "line": 3 }));
+    assert_eq!(payload["symbol"], Value::Null, "{payload}");
+    assert_eq!(payload["file_indexed"], false);
     assert!(
-        related.iter().any(|item| {
-            item.get("name").and_then(|v| v.as_str()) == Some("external_helper")
-                && item.get("relation").and_then(|v| v.as_str()) == Some("imported")
-        }),
-        "expected external_helper to appear as imported symbol"
+        payload["message"]
+            .as_str()
+            .unwrap()
+            .contains("is not indexed")
     );
+
+    let payload = call(json!({ "path": "src/types.rs", "line": 0 }));
+    assert_eq!(payload["error"]["code"], "invalid_input");
 }
 
 #[test]
-fn t185_get_code_context_breadth_respects_budget() {
+fn t510_diff_refs_groups_symbol_changes_and_lists_unindexed_refs() {
     let tmp = tempfile::tempdir().unwrap();
     let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
     let conn = cruxe_state::db::open_connection(&db_path).unwrap();
     let config = Config::default();
-    let workspace = Path::new("/tmp/fake-workspace");
-    let project_id = "test-repo";
+    let now = "2026-02-26T00:00:00Z".to_string();
+    cruxe_state::project::create_project(
+        &conn,
+        &Project {
+            project_id: "test-repo".to_string(),
+            repo_root: "/tmp/fake-workspace".to_string(),
+            display_name: None,
+            default_ref: "live".to_string(),
+            vcs_mode: true,
+            schema_version: 1,
+            parser_version: 1,
+            created_at: now.clone(),
+            updated_at: now.clone(),
+        },
+    )
+    .unwrap();
+    let mark_indexed = |ref_name: &str| {
+        cruxe_state::branch_state::upsert_branch_state(
+            &conn,
+            &cruxe_state::branch_state::BranchState {
+                repo: "test-repo".to_string(),
+                r#ref: ref_name.to_string(),
+                merge_base_commit: None,
+                last_indexed_commit: "abc123".to_string(),
+                overlay_dir: None,
+                file_count: 1,
+                symbol_count: 0,
+                is_default_branch: ref_name == "live",
+                status: "active".to_string(),
+                eviction_eligible_at: None,
+                created_at: now.clone(),
+                last_accessed_at: now.clone(),
+            },
+        )
+        .unwrap();
+    };
+    mark_indexed("live");
 
-    let request = make_request(
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: Some(&conn),
+        workspace: Path::new("/tmp/fake-workspace"),
+        project_id: "test-repo",
+    };
+    let call = |arguments: Value| call_tool(&tool_ctx, "diff_refs", arguments);
+
+    let payload =
+        extract_payload_from_response(&call(json!({ "base_ref": "gone", "head_ref": "feat" })));
+    assert_eq!(payload["error"]["code"], "ref_not_indexed", "{payload}");
+    assert_eq!(
+        payload["error"]["data"]["missing_refs"],
+        json!(["gone", "feat"])
+    );
+
+    mark_indexed("feat");
+    cruxe_state::symbols::insert_symbol(
+        &conn,
+        &cruxe_core::types::SymbolRecord {
+            repo: "test-repo".to_string(),
+            r#ref: "feat".to_string(),
+            commit: None,
+            path: "src/feature.rs".to_string(),
+            language: "rust".to_string(),
+            symbol_id: "feat-symbol".to_string(),
+            symbol_stable_id: "feat-stable".to_string(),
+            name: "feature_flag".to_string(),
+            qualified_name: "feature::feature_flag".to_string(),
+            kind: cruxe_core::types::SymbolKind::Function,
+            signature: Some("pub fn feature_flag() -> bool".to_string()),
+            line_start: 1,
+            line_end: 3,
+            parent_symbol_id: None,
+            visibility: Some("pub".to_string()),
+            content: None,
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
+        },
+    )
+    .unwrap();
+
+    let payload =
+        extract_payload_from_response(&call(json!({ "base_ref": "live", "head_ref": "feat" })));
+    assert_eq!(payload["added_count"], 1, "{payload}");
+    assert_eq!(payload["removed_count"], 0, "{payload}");
+    assert_eq!(payload["signature_changed_count"], 0, "{payload}");
+    assert_eq!(payload["files"][0]["path"], "src/feature.rs");
+    assert_eq!(payload["files"][0]["added"][0]["name"], "feature_flag");
+}
+
+#[test]
+fn t511_locate_symbol_returns_byte_range_of_definition() {
+    let tmp = tempfile::tempdir().unwrap();
+    let index_set = build_fixture_index(tmp.path());
+    let config = Config::default();
+
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: None,
+        workspace: Path::new("/tmp/fake-workspace"),
+        project_id: "test_project",
+    };
+    let response = call_tool(
+        &tool_ctx,
+        "locate_symbol",
+        json!({ "name": "AuthError", "detail_level": "location" }),
+    );
+
+    let payload = extract_payload_from_response(&response);
+    let first = &payload["results"][0];
+    let byte_start = first["byte_start"].as_u64().expect("byte_start") as usize;
+    let byte_end = first["byte_end"].as_u64().expect("byte_end") as usize;
+    let source = std::fs::read_to_string(
+        std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../testdata/fixtures/rust-sample")
+            .join(first["path"].as_str().unwrap()),
+    )
+    .unwrap();
+    let definition = &source[byte_start..byte_end];
+    assert!(
+        definition.starts_with("pub enum AuthError"),
+        "unexpected range: {definition}"
+    );
+    assert!(definition.ends_with('}'));
+}
+
+#[test]
+fn t490_large_symbols_sorts_by_span_and_scopes_by_path() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    let config = Config::default();
+
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: Some(&conn),
+        workspace: Path::new("/tmp/fake-workspace"),
+        project_id: "test-repo",
+    };
+    let call = |arguments: Value| call_tool(&tool_ctx, "large_symbols", arguments);
+
+    let payload = extract_payload_from_response(&call(json!({ "min_lines": 3, "limit": 5 })));
+    assert_eq!(payload["sort"], "lines", "{payload}");
+    let symbols = payload["symbols"].as_array().unwrap();
+    assert!(!symbols.is_empty() && symbols.len() <= 5, "{payload}");
+    let spans: Vec<u64> = symbols
+        .iter()
+        .map(|symbol| symbol["line_count"].as_u64().unwrap())
+        .collect();
+    assert!(spans.iter().all(|span| *span >= 3));
+    assert!(spans.windows(2).all(|pair| pair[0] >= pair[1]), "{spans:?}");
+
+    let payload = extract_payload_from_response(&call(json!({
+        "min_lines": 1,
+        "path": "src/types.rs",
+        "sort": "nesting"
+    })));
+    let symbols = payload["symbols"].as_array().unwrap();
+    assert!(!symbols.is_empty(), "{payload}");
+    assert!(
+        symbols
+            .iter()
+            .all(|symbol| symbol["path"] == "src/types.rs")
+    );
+
+    let payload = extract_payload_from_response(&call(json!({ "min_lines": 100000 })));
+    assert_eq!(payload["symbols"], json!([]));
+
+    let payload = extract_payload_from_response(&call(json!({ "sort": "complexity" })));
+    assert_eq!(payload["error"]["code"], "invalid_input");
+}
+
+#[test]
+fn t492_tests_for_symbol_returns_referencing_tests() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    let config = Config::default();
+    let now = "2026-02-26T00:00:00Z".to_string();
+    cruxe_state::project::create_project(
+        &conn,
+        &Project {
+            project_id: "test-repo".to_string(),
+            repo_root: "/tmp/fake-workspace".to_string(),
+            display_name: None,
+            default_ref: "live".to_string(),
+            vcs_mode: false,
+            schema_version: 1,
+            parser_version: 1,
+            created_at: now.clone(),
+            updated_at: now,
+        },
+    )
+    .unwrap();
+
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: Some(&conn),
+        workspace: Path::new("/tmp/fake-workspace"),
+        project_id: "test-repo",
+    };
+    let call = |arguments: Value| call_tool(&tool_ctx, "tests_for_symbol", arguments);
+
+    let target = cruxe_state::symbols::find_symbols_by_name(
+        &conn,
+        "test-repo",
+        "live",
+        "has_role",
+        Some("src/types.rs"),
+    )
+    .unwrap()
+    .remove(0);
+    let mut test_symbol = target.clone();
+    test_symbol.symbol_id = "sym-test-has-role".to_string();
+    test_symbol.symbol_stable_id = "stable-test-has-role".to_string();
+    test_symbol.name = "admin_has_role".to_string();
+    test_symbol.qualified_name = "tests::admin_has_role".to_string();
+    test_symbol.parent_symbol_id = None;
+    test_symbol.attributes = vec!["#[test]".to_string()];
+    cruxe_state::symbols::insert_symbol(&conn, &test_symbol).unwrap();
+    cruxe_state::edges::insert_edges(
+        &conn,
+        "test-repo",
+        "live",
+        vec![cruxe_core::types::SymbolEdge {
+            repo: "test-repo".to_string(),
+            ref_name: "live".to_string(),
+            from_symbol_id: test_symbol.symbol_id.clone(),
+            to_symbol_id: target.symbol_stable_id.clone(),
+            edge_type: "calls".to_string(),
+            confidence: "static".to_string(),
+        }],
+    )
+    .unwrap();
+
+    let payload = extract_payload_from_response(&call(json!({ "symbol_name": "has_role" })));
+    assert_eq!(payload["association"], "reference_based", "{payload}");
+    assert_eq!(payload["total_tests"], 1);
+    assert_eq!(payload["tests"][0]["from_symbol"]["name"], "admin_has_role");
+    assert_eq!(payload["tests"][0]["matched_by"], "attribute");
+    assert_eq!(payload["tests"][0]["edge_type"], "calls");
+
+    let payload = extract_payload_from_response(&call(json!({ "symbol_name": "no_such_symbol" })));
+    assert_eq!(payload["error"]["code"], "symbol_not_found");
+    let payload = extract_payload_from_response(&call(json!({})));
+    assert_eq!(payload["error"]["code"], "invalid_input");
+}
+
+#[test]
+fn t493_resolve_import_returns_target_location_or_external() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    let config = Config::default();
+
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: Some(&conn),
+        workspace: Path::new("/tmp/fake-workspace"),
+        project_id: "test-repo",
+    };
+    let call = |arguments: Value| call_tool(&tool_ctx, "resolve_import", arguments);
+
+    let payload = extract_payload_from_response(&call(json!({
+        "path": "src/lib.rs",
+        "import": "use crate::types::User;"
+    })));
+    assert_eq!(payload["external"], false, "{payload}");
+    assert_eq!(payload["targets"][0]["imported_name"], "User");
+    assert_eq!(payload["targets"][0]["target_path"], "src/types.rs");
+    assert_eq!(payload["targets"][0]["symbol"]["name"], "User");
+    assert!(
+        payload["targets"][0]["symbol"]["line_start"]
+            .as_u64()
+            .unwrap()
+            > 0
+    );
+
+    let payload = extract_payload_from_response(&call(json!({
+        "path": "src/lib.rs",
+        "import": "use std::collections::HashMap;"
+    })));
+    assert_eq!(payload["external"], true, "{payload}");
+    assert!(payload["targets"][0].get("target_path").is_none());
+
+    let payload = extract_payload_from_response(&call(json!({
+        "path": "src/lib.rs",
+        "import": "fn main() {}"
+    })));
+    assert_eq!(payload["error"]["code"], "invalid_input");
+    let payload = extract_payload_from_response(&call(json!({ "path": "src/lib.rs" })));
+    assert_eq!(payload["error"]["code"], "invalid_input");
+}
+
+#[test]
+fn t494_recent_symbols_tracks_session_working_set() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    let config = Config::default();
+
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: Some(&conn),
+        workspace: Path::new("/tmp/fake-workspace"),
+        project_id: "test-repo",
+    };
+    let call = |name: &str, arguments: Value| call_tool(&tool_ctx, name, arguments);
+
+    let _scope = set_active_session_scope(Some("t494-session"));
+    let payload = extract_payload_from_response(&call("recent_symbols", json!({})));
+    assert_eq!(payload["total"], 0, "{payload}");
+
+    let located = extract_payload_from_response(&call(
+        "locate_symbol",
+        json!({ "name": "AuthError", "limit": 1 }),
+    ));
+    let symbol_id = located["results"][0]["symbol_id"].clone();
+    call("locate_symbol", json!({ "name": "AuthError", "limit": 1 }));
+
+    let payload = extract_payload_from_response(&call("recent_symbols", json!({})));
+    assert_eq!(payload["total"], 1, "{payload}");
+    assert_eq!(payload["symbols"][0]["symbol_id"], symbol_id);
+    assert_eq!(payload["symbols"][0]["via"], "locate_symbol");
+    assert_eq!(payload["symbols"][0]["hits"], 2);
+    let payload =
+        extract_payload_from_response(&call("recent_symbols", json!({ "ref": "other-ref" })));
+    assert_eq!(payload["total"], 0);
+
+    {
+        let _other = set_active_session_scope(Some("t494-other-session"));
+        let payload = extract_payload_from_response(&call("recent_symbols", json!({})));
+        assert_eq!(payload["total"], 0, "working sets are per session");
+    }
+
+    let payload = extract_payload_from_response(&call("recent_symbols", json!({ "clear": true })));
+    assert_eq!(payload["total"], 1);
+    assert_eq!(payload["cleared"], true);
+    let payload = extract_payload_from_response(&call("recent_symbols", json!({})));
+    assert_eq!(payload["total"], 0);
+}
+
+#[test]
+fn t497_directory_outline_groups_top_level_symbols_by_file() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    let config = Config::default();
+
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: Some(&conn),
+        workspace: Path::new("/tmp/fake-workspace"),
+        project_id: "test-repo",
+    };
+    let call = |arguments: Value| {
+        extract_payload_from_response(&call_tool(&tool_ctx, "directory_outline", arguments))
+    };
+
+    let payload = call(json!({ "path": "src" }));
+    assert_eq!(payload["truncated"], false, "{payload}");
+    let files = payload["files"].as_array().unwrap();
+    assert!(!files.is_empty());
+    assert_eq!(payload["file_count"], files.len());
+    let paths: Vec<&str> = files
+        .iter()
+        .map(|file| file["path"].as_str().unwrap())
+        .collect();
+    let mut sorted = paths.clone();
+    sorted.sort_unstable();
+    assert_eq!(paths, sorted);
+    assert!(paths.iter().all(|path| path.starts_with("src/")));
+    let types = files
+        .iter()
+        .find(|file| file["path"] == "src/types.rs")
+        .expect("types.rs outlined");
+    let names: Vec<&str> = types["symbols"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|symbol| symbol["name"].as_str().unwrap())
+        .collect();
+    assert!(names.contains(&"User"), "{names:?}");
+    assert!(
+        types["symbols"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|symbol| symbol.get("parent_symbol_id").is_none()),
+        "only top-level symbols are listed"
+    );
+
+    let capped = call(json!({ "path": ".", "max_files": 1, "max_symbols_per_file": 1 }));
+    assert_eq!(capped["truncated"], true, "{capped}");
+    assert_eq!(capped["file_count"], 1);
+    assert_eq!(capped["metadata"]["result_completeness"], "truncated");
+    assert!(capped["files"][0]["symbols"].as_array().unwrap().len() <= 1);
+
+    let payload = call(json!({ "path": "src", "max_files": 0 }));
+    assert_eq!(payload["error"]["code"], "invalid_input");
+    let payload = call(json!({}));
+    assert_eq!(payload["error"]["code"], "invalid_input");
+}
+
+// ------------------------------------------------------------------
+// T102: get_file_outline nested tree
+// ------------------------------------------------------------------
+
+#[test]
+fn t102_get_file_outline_nested_tree() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+    let project_id = "test-repo";
+
+    // types.rs has struct User + impl User with methods — good for nesting test
+    let request = make_request(
         "tools/call",
         json!({
-            "name": "get_code_context",
+            "name": "get_file_outline",
             "arguments": {
-                "query": "validate_token",
-                "max_tokens": 500,
-                "strategy": "breadth",
-                "language": "rust"
+                "path": "src/types.rs"
             }
         }),
     );
+
     let response = handle_request_with_ctx(
         &request,
         &RequestContext {
@@ -1803,46 +3106,72 @@ fn t185_get_code_context_breadth_respects_budget() {
         },
     );
 
-    assert!(response.error.is_none(), "expected success");
-    let payload = extract_payload_from_response(&response);
     assert!(
-        payload.get("estimated_tokens").unwrap().as_u64().unwrap() <= 500,
-        "estimated tokens should respect max_tokens budget"
+        response.error.is_none(),
+        "expected success, got error: {:?}",
+        response.error
     );
-    let items = payload.get("context_items").unwrap().as_array().unwrap();
-    assert!(
-        !items.is_empty(),
-        "breadth strategy should return context items"
+    let result = response.result.as_ref().expect("result should be present");
+    let content = result.get("content").unwrap().as_array().unwrap();
+    let text = content[0].get("text").unwrap().as_str().unwrap();
+    let payload: serde_json::Value = serde_json::from_str(text).unwrap();
+
+    assert_eq!(
+        payload.get("file_path").unwrap().as_str().unwrap(),
+        "src/types.rs"
     );
+    assert_eq!(payload.get("language").unwrap().as_str().unwrap(), "rust");
+
+    let symbols = payload
+        .get("symbols")
+        .expect("should have symbols")
+        .as_array()
+        .expect("symbols should be an array");
+
+    assert!(!symbols.is_empty(), "should have symbols for types.rs");
+
+    // Verify at least one symbol has children (impl block with methods)
+    let has_children = symbols.iter().any(|s| {
+        let children = s.get("children").and_then(|c| c.as_array());
+        children.map(|c| !c.is_empty()).unwrap_or(false)
+    });
     assert!(
-        items[0].get("body").is_none(),
-        "breadth strategy should not include full body"
+        has_children,
+        "types.rs should have impl blocks with children (methods)"
     );
+
+    // Verify metadata
+    let metadata = payload.get("metadata").expect("should have metadata");
+    assert!(metadata.get("symbol_count").unwrap().as_u64().unwrap() > 0);
 }
 
+// ------------------------------------------------------------------
+// T103: get_file_outline with depth: "top"
+// ------------------------------------------------------------------
+
 #[test]
-fn t186_and_t187_get_code_context_depth_and_truncation() {
+fn t103_get_file_outline_top_level_only() {
     let tmp = tempfile::tempdir().unwrap();
     let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
     let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+
     let config = Config::default();
     let workspace = Path::new("/tmp/fake-workspace");
     let project_id = "test-repo";
 
-    let depth_request = make_request(
+    let request = make_request(
         "tools/call",
         json!({
-            "name": "get_code_context",
+            "name": "get_file_outline",
             "arguments": {
-                "query": "validate_token",
-                "max_tokens": 1200,
-                "strategy": "depth",
-                "language": "rust"
+                "path": "src/types.rs",
+                "depth": "top"
             }
         }),
     );
-    let depth_response = handle_request_with_ctx(
-        &depth_request,
+
+    let response = handle_request_with_ctx(
+        &request,
         &RequestContext {
             config: &config,
             index_set: Some(&index_set),
@@ -1857,48 +3186,55 @@ fn t186_and_t187_get_code_context_depth_and_truncation() {
             progress_token: None,
         },
     );
-    assert!(depth_response.error.is_none(), "expected success");
-    let depth_payload = extract_payload_from_response(&depth_response);
-    assert!(
-        depth_payload
-            .get("estimated_tokens")
-            .unwrap()
-            .as_u64()
-            .unwrap()
-            <= 1200,
-        "depth response should stay under max_tokens"
-    );
-    let depth_items = depth_payload
-        .get("context_items")
-        .unwrap()
-        .as_array()
-        .unwrap();
-    assert!(
-        !depth_items.is_empty(),
-        "depth strategy should return items"
-    );
-    assert!(
-        depth_items[0]
-            .get("body")
-            .and_then(|v| v.as_str())
-            .is_some_and(|body| !body.is_empty()),
-        "depth strategy should include body text"
-    );
 
-    let tiny_budget_request = make_request(
+    assert!(response.error.is_none(), "expected success");
+    let result = response.result.as_ref().unwrap();
+    let content = result.get("content").unwrap().as_array().unwrap();
+    let text = content[0].get("text").unwrap().as_str().unwrap();
+    let payload: serde_json::Value = serde_json::from_str(text).unwrap();
+
+    let symbols = payload.get("symbols").unwrap().as_array().unwrap();
+
+    assert!(!symbols.is_empty(), "should have top-level symbols");
+
+    // With depth="top", no symbol should have non-empty children
+    for sym in symbols {
+        let children = sym.get("children").and_then(|c| c.as_array());
+        let has_children = children.map(|c| !c.is_empty()).unwrap_or(false);
+        assert!(
+            !has_children,
+            "top-level only mode should not include children, but '{}' has children",
+            sym.get("name").unwrap().as_str().unwrap_or("?")
+        );
+    }
+}
+
+// ------------------------------------------------------------------
+// T104: get_file_outline on non-existent file
+// ------------------------------------------------------------------
+
+#[test]
+fn t104_get_file_outline_nonexistent_file() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+    let project_id = "test-repo";
+
+    let request = make_request(
         "tools/call",
         json!({
-            "name": "get_code_context",
+            "name": "get_file_outline",
             "arguments": {
-                "query": "validate_token",
-                "max_tokens": 50,
-                "strategy": "depth",
-                "language": "rust"
+                "path": "src/nonexistent_file.rs"
             }
         }),
     );
-    let tiny_budget_response = handle_request_with_ctx(
-        &tiny_budget_request,
+
+    let response = handle_request_with_ctx(
+        &request,
         &RequestContext {
             config: &config,
             index_set: Some(&index_set),
@@ -1913,28 +3249,42 @@ fn t186_and_t187_get_code_context_depth_and_truncation() {
             progress_token: None,
         },
     );
-    assert!(tiny_budget_response.error.is_none(), "expected success");
-    let tiny_payload = extract_payload_from_response(&tiny_budget_response);
-    assert!(
-        tiny_payload.get("truncated").unwrap().as_bool().unwrap(),
-        "very small max_tokens should trigger truncation"
-    );
-    assert!(
-        tiny_payload
-            .get("estimated_tokens")
-            .unwrap()
-            .as_u64()
-            .unwrap()
-            <= 50,
-        "estimated tokens should never exceed tiny budget"
+
+    assert!(response.error.is_none(), "MCP tool errors are in content");
+    let result = response.result.as_ref().unwrap();
+    let content = result.get("content").unwrap().as_array().unwrap();
+    let text = content[0].get("text").unwrap().as_str().unwrap();
+    let payload: serde_json::Value = serde_json::from_str(text).unwrap();
+
+    // Should have an error object with file_not_found code
+    let error = payload.get("error").expect("should have error object");
+    assert_eq!(
+        error.get("code").unwrap().as_str().unwrap(),
+        "file_not_found"
     );
 }
 
 #[test]
-fn t360_build_context_pack_returns_sectioned_provenance_payload() {
+fn t104_get_file_outline_existing_file_without_symbols_returns_empty() {
     let tmp = tempfile::tempdir().unwrap();
     let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
     let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+
+    cruxe_state::manifest::upsert_manifest(
+        &conn,
+        &cruxe_state::manifest::ManifestEntry {
+            repo: "test-repo".to_string(),
+            r#ref: "live".to_string(),
+            path: "docs/README.md".to_string(),
+            content_hash: blake3::hash(b"hello").to_hex().to_string(),
+            size_bytes: 5,
+            mtime_ns: None,
+            language: Some("markdown".to_string()),
+            indexed_at: "2026-01-01T00:00:00Z".to_string(),
+        },
+    )
+    .unwrap();
+
     let config = Config::default();
     let workspace = Path::new("/tmp/fake-workspace");
     let project_id = "test-repo";
@@ -1942,15 +3292,14 @@ fn t360_build_context_pack_returns_sectioned_provenance_payload() {
     let request = make_request(
         "tools/call",
         json!({
-            "name": "build_context_pack",
+            "name": "get_file_outline",
             "arguments": {
-                "query": "validate_token",
-                "budget_tokens": 280,
-                "max_candidates": 80,
-                "language": "rust"
+                "path": "docs/README.md",
+                "language": "markdown"
             }
         }),
     );
+
     let response = handle_request_with_ctx(
         &request,
         &RequestContext {
@@ -1969,105 +3318,44 @@ fn t360_build_context_pack_returns_sectioned_provenance_payload() {
     );
 
     assert!(response.error.is_none(), "expected success");
-    let payload = extract_payload_from_response(&response);
-    assert_eq!(
-        payload.get("mode").and_then(|value| value.as_str()),
-        Some("full")
-    );
-    assert!(
-        payload
-            .get("token_budget_used")
-            .and_then(|value| value.as_u64())
-            .unwrap_or_default()
-            <= 280,
-        "token_budget_used must respect budget_tokens"
-    );
-
-    let sections = payload
-        .get("sections")
-        .and_then(|value| value.as_object())
-        .expect("sections should be an object");
-    for section in ["definitions", "usages", "deps", "tests", "config", "docs"] {
-        assert!(
-            sections.contains_key(section),
-            "missing section key: {section}"
-        );
-    }
-
-    let all_items = sections
-        .values()
-        .filter_map(|value| value.as_array())
-        .flat_map(|items| items.iter())
-        .collect::<Vec<_>>();
-    assert!(!all_items.is_empty(), "expected at least one pack item");
-
-    for item in &all_items {
-        assert!(item.get("snippet_id").is_some(), "missing snippet_id");
-        assert!(item.get("ref").is_some(), "missing ref");
-        assert!(item.get("path").is_some(), "missing path");
-        assert!(item.get("line_start").is_some(), "missing line_start");
-        assert!(item.get("line_end").is_some(), "missing line_end");
-        assert!(item.get("content_hash").is_some(), "missing content_hash");
-        assert!(
-            item.get("selection_reason").is_some(),
-            "missing selection_reason"
-        );
-    }
+    let result = response.result.as_ref().unwrap();
+    let content = result.get("content").unwrap().as_array().unwrap();
+    let text = content[0].get("text").unwrap().as_str().unwrap();
+    let payload: serde_json::Value = serde_json::from_str(text).unwrap();
 
     assert!(
-        payload
-            .get("coverage_summary")
-            .and_then(|value| value.get("section_counts"))
-            .is_some(),
-        "coverage_summary should include section_counts"
-    );
-    assert!(
-        payload.get("dropped_candidates").is_some(),
-        "payload should include dropped_candidates diagnostics"
-    );
-    assert!(
-        payload.get("suggested_next_queries").is_some(),
-        "payload should include suggested_next_queries diagnostics"
-    );
-    let metadata = payload.get("metadata").expect("metadata should be present");
-    assert_eq!(
-        metadata
-            .get("section_aliases")
-            .and_then(|value| value.get("key_usages"))
-            .and_then(|value| value.as_str()),
-        Some("usages"),
-        "Continue-compatible alias key_usages should map to usages"
+        payload.get("error").is_none(),
+        "existing file without symbols should not return file_not_found"
     );
     assert_eq!(
-        metadata
-            .get("section_aliases")
-            .and_then(|value| value.get("dependencies"))
-            .and_then(|value| value.as_str()),
-        Some("deps"),
-        "Continue-compatible alias dependencies should map to deps"
+        payload.get("file_path").unwrap().as_str().unwrap(),
+        "docs/README.md"
     );
+    let symbols = payload.get("symbols").unwrap().as_array().unwrap();
+    assert!(symbols.is_empty(), "symbols should be empty");
 }
 
 #[test]
-fn t361_build_context_pack_ref_scope_roundtrip() {
+fn t165_get_symbol_hierarchy_ancestors_via_jsonrpc() {
     let tmp = tempfile::tempdir().unwrap();
-    let (index_set, conn, branch_name) = build_fixture_index_in_git_repo(tmp.path());
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
     let config = Config::default();
-    let workspace_buf = tmp.path().join("workspace");
-    let workspace = workspace_buf.as_path();
+    let workspace = Path::new("/tmp/fake-workspace");
     let project_id = "test-repo";
 
     let request = make_request(
         "tools/call",
         json!({
-            "name": "build_context_pack",
+            "name": "get_symbol_hierarchy",
             "arguments": {
-                "query": "validate_token",
-                "ref": branch_name,
-                "budget_tokens": 320
+                "symbol_name": "authenticate",
+                "path": "src/handler.rs",
+                "direction": "ancestors"
             }
         }),
     );
+
     let response = handle_request_with_ctx(
         &request,
         &RequestContext {
@@ -2088,20 +3376,39 @@ fn t361_build_context_pack_ref_scope_roundtrip() {
     assert!(response.error.is_none(), "expected success");
     let payload = extract_payload_from_response(&response);
     assert_eq!(
-        payload.get("ref").and_then(|value| value.as_str()),
-        Some(branch_name.as_str())
+        payload.get("direction").unwrap().as_str().unwrap(),
+        "ancestors"
     );
+
+    let hierarchy = payload.get("hierarchy").unwrap().as_array().unwrap();
+    assert!(!hierarchy.is_empty(), "hierarchy should not be empty");
     assert_eq!(
-        payload
-            .get("metadata")
-            .and_then(|value| value.get("ref"))
-            .and_then(|value| value.as_str()),
-        Some(branch_name.as_str())
+        hierarchy[0].get("name").unwrap().as_str().unwrap(),
+        "authenticate"
+    );
+    let names = hierarchy
+        .iter()
+        .filter_map(|item| item.get("name").and_then(|v| v.as_str()))
+        .collect::<Vec<_>>();
+    assert!(
+        names.contains(&"AuthHandler"),
+        "ancestor chain should include AuthHandler; got {:?}",
+        names
+    );
+
+    let metadata = payload.get("metadata").unwrap();
+    assert_eq!(
+        metadata
+            .get("cruxe_protocol_version")
+            .unwrap()
+            .as_str()
+            .unwrap(),
+        "1.0"
     );
 }
 
 #[test]
-fn t362_build_context_pack_is_deterministic_across_repeated_calls() {
+fn t166_get_symbol_hierarchy_descendants_via_jsonrpc() {
     let tmp = tempfile::tempdir().unwrap();
     let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
     let conn = cruxe_state::db::open_connection(&db_path).unwrap();
@@ -2112,17 +3419,16 @@ fn t362_build_context_pack_is_deterministic_across_repeated_calls() {
     let request = make_request(
         "tools/call",
         json!({
-            "name": "build_context_pack",
+            "name": "get_symbol_hierarchy",
             "arguments": {
-                "query": "validate_token",
-                "budget_tokens": 260,
-                "max_candidates": 75,
-                "language": "rust"
+                "symbol_name": "AuthHandler",
+                "path": "src/handler.rs",
+                "direction": "descendants"
             }
         }),
     );
 
-    let response_a = handle_request_with_ctx(
+    let response = handle_request_with_ctx(
         &request,
         &RequestContext {
             config: &config,
@@ -2138,77 +3444,61 @@ fn t362_build_context_pack_is_deterministic_across_repeated_calls() {
             progress_token: None,
         },
     );
-    assert!(response_a.error.is_none(), "first call should succeed");
 
-    let response_b = handle_request_with_ctx(
-        &request,
-        &RequestContext {
-            config: &config,
-            index_set: Some(&index_set),
-            schema_status: SchemaStatus::Compatible,
-            compatibility_reason: None,
-            conn: Some(&conn),
-            workspace,
-            project_id,
-            prewarm_status: &test_prewarm_status(),
-            server_start: &test_server_start(),
-            notifier: Arc::new(NullProgressNotifier),
-            progress_token: None,
-        },
+    assert!(response.error.is_none(), "expected success");
+    let payload = extract_payload_from_response(&response);
+    assert_eq!(
+        payload.get("direction").unwrap().as_str().unwrap(),
+        "descendants"
     );
-    assert!(response_b.error.is_none(), "second call should succeed");
-
-    let payload_a = extract_payload_from_response(&response_a);
-    let payload_b = extract_payload_from_response(&response_b);
+    let hierarchy = payload.get("hierarchy").unwrap().as_array().unwrap();
     assert_eq!(
-        payload_a, payload_b,
-        "repeated runs should be deterministic"
+        hierarchy.len(),
+        1,
+        "descendants should return a single root"
+    );
+    let children = hierarchy[0]
+        .get("children")
+        .unwrap()
+        .as_array()
+        .expect("children should be an array");
+    let child_names = children
+        .iter()
+        .filter_map(|item| item.get("name").and_then(|v| v.as_str()))
+        .collect::<Vec<_>>();
+    assert!(
+        child_names.contains(&"handle_request"),
+        "descendants should include handle_request"
+    );
+    assert!(
+        child_names.contains(&"authenticate"),
+        "descendants should include authenticate"
     );
 }
 
 #[test]
-fn t363_context_pack_iterative_fixture_drives_followup_query_loop() {
+fn t175_find_related_symbols_scope_file_via_jsonrpc() {
     let tmp = tempfile::tempdir().unwrap();
     let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
     let conn = cruxe_state::db::open_connection(&db_path).unwrap();
     let config = Config::default();
     let workspace = Path::new("/tmp/fake-workspace");
     let project_id = "test-repo";
-    let fixture_path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-        .join("../../testdata/fixtures/context-pack/iterative-workflow.json");
-    let fixture_raw = std::fs::read_to_string(&fixture_path)
-        .unwrap_or_else(|err| panic!("failed to read fixture {}: {err}", fixture_path.display()));
-    let fixture: serde_json::Value = serde_json::from_str(&fixture_raw).unwrap();
-
-    let initial_query = fixture
-        .get("initial")
-        .and_then(|value| value.get("query"))
-        .and_then(|value| value.as_str())
-        .expect("fixture initial.query should exist");
-    let initial_budget = fixture
-        .get("initial")
-        .and_then(|value| value.get("budget_tokens"))
-        .and_then(|value| value.as_u64())
-        .expect("fixture initial.budget_tokens should exist");
-    let followup_budget = fixture
-        .get("follow_up")
-        .and_then(|value| value.get("budget_tokens"))
-        .and_then(|value| value.as_u64())
-        .expect("fixture follow_up.budget_tokens should exist");
 
-    let initial_request = make_request(
+    let request = make_request(
         "tools/call",
         json!({
-            "name": "build_context_pack",
+            "name": "find_related_symbols",
             "arguments": {
-                "query": initial_query,
-                "budget_tokens": initial_budget,
-                "mode": "edit_minimal"
+                "symbol_name": "authenticate",
+                "path": "src/handler.rs",
+                "scope": "file",
+                "limit": 20
             }
         }),
     );
-    let initial_response = handle_request_with_ctx(
-        &initial_request,
+    let response = handle_request_with_ctx(
+        &request,
         &RequestContext {
             config: &config,
             index_set: Some(&index_set),
@@ -2223,65 +3513,24 @@ fn t363_context_pack_iterative_fixture_drives_followup_query_loop() {
             progress_token: None,
         },
     );
-    assert!(
-        initial_response.error.is_none(),
-        "initial call should succeed"
-    );
-    let initial_payload = extract_payload_from_response(&initial_response);
-    let followup_query = initial_payload
-        .get("suggested_next_queries")
-        .and_then(|value| value.as_array())
-        .and_then(|items| items.first())
-        .and_then(|value| value.as_str())
-        .expect("initial response should suggest a follow-up query");
 
-    let followup_request = make_request(
-        "tools/call",
-        json!({
-            "name": "build_context_pack",
-            "arguments": {
-                "query": followup_query,
-                "budget_tokens": followup_budget
-            }
-        }),
-    );
-    let followup_response = handle_request_with_ctx(
-        &followup_request,
-        &RequestContext {
-            config: &config,
-            index_set: Some(&index_set),
-            schema_status: SchemaStatus::Compatible,
-            compatibility_reason: None,
-            conn: Some(&conn),
-            workspace,
-            project_id,
-            prewarm_status: &test_prewarm_status(),
-            server_start: &test_server_start(),
-            notifier: Arc::new(NullProgressNotifier),
-            progress_token: None,
-        },
-    );
+    assert!(response.error.is_none(), "expected success");
+    let payload = extract_payload_from_response(&response);
+    let related = payload.get("related").unwrap().as_array().unwrap();
     assert!(
-        followup_response.error.is_none(),
-        "follow-up call should succeed"
+        !related.is_empty(),
+        "file scope should return sibling symbols"
     );
-    let followup_payload = extract_payload_from_response(&followup_response);
-
     assert!(
-        followup_payload
-            .get("token_budget_used")
-            .and_then(|value| value.as_u64())
-            .unwrap_or_default()
-            >= initial_payload
-                .get("token_budget_used")
-                .and_then(|value| value.as_u64())
-                .unwrap_or_default(),
-        "follow-up run should expand or maintain context footprint with higher budget"
+        related
+            .iter()
+            .all(|item| item.get("relation").unwrap().as_str().unwrap() == "same_file"),
+        "file scope should only return same_file relation"
     );
 }
 
 #[test]
-fn t364_build_context_pack_accepts_partial_section_caps_patch() {
+fn t176_find_related_symbols_scope_module_includes_imported() {
     let tmp = tempfile::tempdir().unwrap();
     let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
     let conn = cruxe_state::db::open_connection(&db_path).unwrap();
@@ -2289,19 +3538,64 @@ fn t364_build_context_pack_accepts_partial_section_caps_patch() {
     let workspace = Path::new("/tmp/fake-workspace");
     let project_id = "test-repo";
 
+    // Add one synthetic symbol outside src/ so module-scope lookup cannot
+    // discover it via path prefix, only via import edges.
+    let synthetic_symbol = cruxe_core::types::SymbolRecord {
+        repo: project_id.to_string(),
+        r#ref: "live".to_string(),
+        commit: None,
+        path: "vendor/external.rs".to_string(),
+        language: "rust".to_string(),
+        symbol_id: cruxe_core::types::compute_symbol_id(
+            project_id,
+            "live",
+            "vendor/external.rs",
+            &cruxe_core::types::SymbolKind::Function,
+            1,
+            "external_helper",
+        ),
+        symbol_stable_id: cruxe_core::types::compute_symbol_stable_id(
+            "rust",
+            &cruxe_core::types::SymbolKind::Function,
+            "vendor::external_helper",
+            None,
+        ),
+        name: "external_helper".to_string(),
+        qualified_name: "vendor::external_helper".to_string(),
+        kind: cruxe_core::types::SymbolKind::Function,
+        signature: Some("fn external_helper()".to_string()),
+        line_start: 1,
+        line_end: 3,
+        parent_symbol_id: None,
+        visibility: Some("pub".to_string()),
+        content: Some("fn external_helper() {}".to_string()),
+        attributes: Vec::new(),
+        byte_start: None,
+        byte_end: None,
+        doc_comment: None,
+        parsed_signature: None,
+    };
+    cruxe_state::symbols::insert_symbol(&conn, &synthetic_symbol).unwrap();
+
+    let edge = cruxe_core::types::SymbolEdge {
+        repo: project_id.to_string(),
+        ref_name: "live".to_string(),
+        from_symbol_id: cruxe_indexer::import_extract::source_symbol_id_for_path("src/handler.rs"),
+        to_symbol_id: synthetic_symbol.symbol_stable_id.clone(),
+        edge_type: "imports".to_string(),
+        confidence: "static".to_string(),
+    };
+    cruxe_state::edges::insert_edges(&conn, project_id, "live", vec![edge]).unwrap();
+
     let request = make_request(
         "tools/call",
         json!({
-            "name": "build_context_pack",
+            "name": "find_related_symbols",
             "arguments": {
-                "query": "validate_token",
-                "budget_tokens": 500,
-                "mode": "full",
-                "section_caps": {
-                    "definitions": 1,
-                    "key_usages": 4,
-                    "dependencies": 3
-                }
+                "symbol_name": "handle_request",
+                "path": "src/handler.rs",
+                "scope": "module",
+                "limit": 200
             }
         }),
     );
@@ -2324,37 +3618,31 @@ fn t364_build_context_pack_accepts_partial_section_caps_patch() {
 
     assert!(response.error.is_none(), "expected success");
     let payload = extract_payload_from_response(&response);
-    let sections = payload
-        .get("sections")
-        .and_then(|value| value.as_object())
-        .expect("sections should be an object");
+    let related = payload.get("related").unwrap().as_array().unwrap();
+    let relations = related
+        .iter()
+        .filter_map(|item| item.get("relation").and_then(|v| v.as_str()))
+        .collect::<Vec<_>>();
     assert!(
-        sections.contains_key("definitions"),
-        "sections should include definitions bucket"
-    );
-
-    let section_caps = payload
-        .get("metadata")
-        .and_then(|value| value.get("section_caps"))
-        .expect("metadata.section_caps should be present");
-    assert_eq!(
-        section_caps
-            .get("definitions")
-            .and_then(|value| value.as_u64()),
-        Some(1)
+        relations.contains(&"same_module"),
+        "module scope should include same_module symbols"
     );
-    assert_eq!(
-        section_caps.get("usages").and_then(|value| value.as_u64()),
-        Some(4)
+    assert!(
+        relations.contains(&"imported"),
+        "module scope should include imported symbols when edges exist; got {:?}",
+        relations
     );
-    assert_eq!(
-        section_caps.get("deps").and_then(|value| value.as_u64()),
-        Some(3)
+    assert!(
+        related.iter().any(|item| {
+            item.get("name").and_then(|v| v.as_str()) == Some("external_helper")
+                && item.get("relation").and_then(|v| v.as_str()) == Some("imported")
+        }),
+        "expected external_helper to appear as imported symbol"
     );
 }
 
 #[test]
-fn t365_build_context_pack_enforces_max_candidates_upper_bound() {
+fn t185_get_code_context_breadth_respects_budget() {
     let tmp = tempfile::tempdir().unwrap();
     let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
     let conn = cruxe_state::db::open_connection(&db_path).unwrap();
@@ -2365,12 +3653,12 @@ fn t365_build_context_pack_enforces_max_candidates_upper_bound() {
     let request = make_request(
         "tools/call",
         json!({
-            "name": "build_context_pack",
+            "name": "get_code_context",
             "arguments": {
                 "query": "validate_token",
-                "budget_tokens": 5_000,
-                "max_candidates": 1,
-                "mode": "full"
+                "max_tokens": 500,
+                "strategy": "breadth",
+                "language": "rust"
             }
         }),
     );
@@ -2393,40 +3681,23 @@ fn t365_build_context_pack_enforces_max_candidates_upper_bound() {
 
     assert!(response.error.is_none(), "expected success");
     let payload = extract_payload_from_response(&response);
-    let selected_items = payload
-        .get("sections")
-        .and_then(|value| value.as_object())
-        .expect("sections should be object")
-        .values()
-        .filter_map(|value| value.as_array())
-        .map(std::vec::Vec::len)
-        .sum::<usize>();
     assert!(
-        selected_items <= 1,
-        "selected items should honor max_candidates upper bound"
+        payload.get("estimated_tokens").unwrap().as_u64().unwrap() <= 500,
+        "estimated tokens should respect max_tokens budget"
     );
-
-    let metadata = payload.get("metadata").expect("metadata should exist");
+    let items = payload.get("context_items").unwrap().as_array().unwrap();
     assert!(
-        metadata
-            .get("total_raw_candidates")
-            .and_then(|value| value.as_u64())
-            .unwrap_or_default()
-            <= 1,
-        "raw candidates should be capped before dedup"
+        !items.is_empty(),
+        "breadth strategy should return context items"
     );
     assert!(
-        metadata
-            .get("deduped_candidates")
-            .and_then(|value| value.as_u64())
-            .unwrap_or_default()
-            <= 1,
-        "deduped candidates should not exceed max_candidates"
+        items[0].get("body").is_none(),
+        "breadth strategy should not include full body"
     );
 }
 
 #[test]
-fn t366_build_context_pack_zero_results_emits_underfilled_guidance() {
+fn t186_and_t187_get_code_context_depth_and_truncation() {
     let tmp = tempfile::tempdir().unwrap();
     let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
     let conn = cruxe_state::db::open_connection(&db_path).unwrap();
@@ -2434,21 +3705,20 @@ fn t366_build_context_pack_zero_results_emits_underfilled_guidance() {
     let workspace = Path::new("/tmp/fake-workspace");
     let project_id = "test-repo";
 
-    let zero_query = "zzzz_context_pack_zero_result_probe_987654321";
-    let request = make_request(
+    let depth_request = make_request(
         "tools/call",
         json!({
-            "name": "build_context_pack",
+            "name": "get_code_context",
             "arguments": {
-                "query": zero_query,
-                "budget_tokens": 400,
-                "language": "fortran",
-                "mode": "full"
+                "query": "validate_token",
+                "max_tokens": 1200,
+                "strategy": "depth",
+                "language": "rust"
             }
         }),
     );
-    let response = handle_request_with_ctx(
-        &request,
+    let depth_response = handle_request_with_ctx(
+        &depth_request,
         &RequestContext {
             config: &config,
             index_set: Some(&index_set),
@@ -2463,94 +3733,48 @@ fn t366_build_context_pack_zero_results_emits_underfilled_guidance() {
             progress_token: None,
         },
     );
-
-    assert!(response.error.is_none(), "expected success");
-    let payload = extract_payload_from_response(&response);
-    let sections = payload
-        .get("sections")
-        .and_then(|value| value.as_object())
-        .expect("sections should be object");
-    let selected_items = sections
-        .values()
-        .filter_map(|value| value.as_array())
-        .map(std::vec::Vec::len)
-        .sum::<usize>();
-    assert_eq!(
-        selected_items, 0,
-        "zero-result query should return no sections"
-    );
-    assert_eq!(
-        payload
-            .get("token_budget_used")
-            .and_then(|value| value.as_u64())
-            .unwrap_or_default(),
-        0
-    );
-
-    let suggestions = payload
-        .get("suggested_next_queries")
-        .and_then(|value| value.as_array())
-        .expect("suggested_next_queries should exist")
-        .iter()
-        .filter_map(|value| value.as_str())
-        .collect::<Vec<_>>();
+    assert!(depth_response.error.is_none(), "expected success");
+    let depth_payload = extract_payload_from_response(&depth_response);
     assert!(
-        suggestions.contains(&format!("{zero_query} symbol").as_str()),
-        "zero-result guidance should include a symbol-focused follow-up"
+        depth_payload
+            .get("estimated_tokens")
+            .unwrap()
+            .as_u64()
+            .unwrap()
+            <= 1200,
+        "depth response should stay under max_tokens"
     );
-
-    let hints = payload
-        .get("missing_context_hints")
-        .and_then(|value| value.as_array())
-        .expect("missing_context_hints should exist")
-        .iter()
-        .filter_map(|value| value.as_str())
-        .collect::<Vec<_>>();
+    let depth_items = depth_payload
+        .get("context_items")
+        .unwrap()
+        .as_array()
+        .unwrap();
     assert!(
-        hints
-            .iter()
-            .any(|hint| hint.contains("No candidates were selected")),
-        "zero-result guidance should surface explicit underfilled hint"
-    );
-
-    let metadata = payload.get("metadata").expect("metadata should exist");
-    assert_eq!(
-        metadata
-            .get("budget_utilization_ratio")
-            .and_then(|value| value.as_f64()),
-        Some(0.0),
-        "zero results should produce zero budget utilization"
+        !depth_items.is_empty(),
+        "depth strategy should return items"
     );
-    assert_eq!(
-        metadata
-            .get("token_estimation")
-            .and_then(|value| value.get("method"))
-            .and_then(|value| value.as_str()),
-        Some("cruxe_core::tokens::estimate_tokens")
+    assert!(
+        depth_items[0]
+            .get("body")
+            .and_then(|v| v.as_str())
+            .is_some_and(|body| !body.is_empty()),
+        "depth strategy should include body text"
     );
-}
-
-#[test]
-fn t367_build_context_pack_rejects_excessive_budget_tokens() {
-    let tmp = tempfile::tempdir().unwrap();
-    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
-    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
-    let config = Config::default();
-    let workspace = Path::new("/tmp/fake-workspace");
-    let project_id = "test-repo";
 
-    let request = make_request(
+    let tiny_budget_request = make_request(
         "tools/call",
         json!({
-            "name": "build_context_pack",
+            "name": "get_code_context",
             "arguments": {
                 "query": "validate_token",
-                "budget_tokens": 300001
+                "max_tokens": 50,
+                "strategy": "depth",
+                "language": "rust"
             }
         }),
     );
-    let response = handle_request_with_ctx(
-        &request,
+    let tiny_budget_response = handle_request_with_ctx(
+        &tiny_budget_request,
         &RequestContext {
             config: &config,
             index_set: Some(&index_set),
@@ -2565,237 +3789,260 @@ fn t367_build_context_pack_rejects_excessive_budget_tokens() {
             progress_token: None,
         },
     );
-
-    assert!(response.error.is_none(), "MCP tool errors are in content");
-    let payload = extract_payload_from_response(&response);
-    let error = payload
-        .get("error")
-        .expect("excessive budget_tokens response should contain error");
-    assert_eq!(
-        error.get("code").and_then(|v| v.as_str()),
-        Some("invalid_max_tokens")
+    assert!(tiny_budget_response.error.is_none(), "expected success");
+    let tiny_payload = extract_payload_from_response(&tiny_budget_response);
+    assert!(
+        tiny_payload.get("truncated").unwrap().as_bool().unwrap(),
+        "very small max_tokens should trigger truncation"
     );
     assert!(
-        error
-            .get("message")
-            .and_then(|v| v.as_str())
-            .is_some_and(|msg| msg.contains("200000")),
-        "error message should report the max budget bound"
+        tiny_payload
+            .get("estimated_tokens")
+            .unwrap()
+            .as_u64()
+            .unwrap()
+            <= 50,
+        "estimated tokens should never exceed tiny budget"
     );
 }
 
 #[test]
-fn t191_new_tools_error_codes_follow_protocol_registry() {
+fn t495_get_code_context_numbers_body_lines_on_request() {
     let tmp = tempfile::tempdir().unwrap();
     let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
     let conn = cruxe_state::db::open_connection(&db_path).unwrap();
     let config = Config::default();
-    let workspace = Path::new("/tmp/fake-workspace");
-    let project_id = "test-repo";
 
-    let missing_symbol_request = make_request(
-        "tools/call",
-        json!({
-            "name": "get_symbol_hierarchy",
-            "arguments": {
-                "symbol_name": "this_symbol_should_not_exist",
-                "path": "src/handler.rs"
-            }
-        }),
-    );
-    let missing_symbol_response = handle_request_with_ctx(
-        &missing_symbol_request,
-        &RequestContext {
-            config: &config,
-            index_set: Some(&index_set),
-            schema_status: SchemaStatus::Compatible,
-            compatibility_reason: None,
-            conn: Some(&conn),
-            workspace,
-            project_id,
-            prewarm_status: &test_prewarm_status(),
-            server_start: &test_server_start(),
-            notifier: Arc::new(NullProgressNotifier),
-            progress_token: None,
-        },
-    );
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: Some(&conn),
+        workspace: Path::new("/tmp/fake-workspace"),
+        project_id: "test-repo",
+    };
+    let call = |arguments: Value| call_tool(&tool_ctx, "get_code_context", arguments);
+
+    let arguments = json!({
+        "query": "validate_token",
+        "max_tokens": 1200,
+        "strategy": "depth",
+        "language": "rust",
+        "with_line_numbers": true
+    });
+    let payload = extract_payload_from_response(&call(arguments));
+    let item = &payload["context_items"][0];
+    let line_start = item["line_start"].as_u64().unwrap();
+    let body = item["body"].as_str().unwrap();
     assert!(
-        missing_symbol_response.error.is_none(),
-        "MCP tool errors are in content"
-    );
-    let missing_symbol_payload = extract_payload_from_response(&missing_symbol_response);
-    let missing_symbol_error = missing_symbol_payload
-        .get("error")
-        .expect("missing symbol response should contain error");
-    assert_eq!(
-        missing_symbol_error
-            .get("code")
-            .and_then(|v| v.as_str())
-            .unwrap(),
-        "symbol_not_found"
+        body.lines()
+            .next()
+            .unwrap()
+            .starts_with(&format!("{line_start:>4}| ")),
+        "{body}"
     );
+    assert!(body.lines().all(|line| line.contains("| ")));
 
-    let invalid_strategy_request = make_request(
-        "tools/call",
-        json!({
-            "name": "get_code_context",
-            "arguments": {
-                "query": "validate_token",
-                "strategy": "wide"
-            }
-        }),
-    );
-    let invalid_strategy_response = handle_request_with_ctx(
-        &invalid_strategy_request,
-        &RequestContext {
-            config: &config,
-            index_set: Some(&index_set),
-            schema_status: SchemaStatus::Compatible,
-            compatibility_reason: None,
-            conn: Some(&conn),
-            workspace,
-            project_id,
-            prewarm_status: &test_prewarm_status(),
-            server_start: &test_server_start(),
-            notifier: Arc::new(NullProgressNotifier),
-            progress_token: None,
-        },
-    );
+    let raw = extract_payload_from_response(&call(json!({
+        "query": "validate_token",
+        "max_tokens": 1200,
+        "strategy": "depth",
+        "language": "rust"
+    })));
     assert!(
-        invalid_strategy_response.error.is_none(),
-        "MCP tool errors are in content"
-    );
-    let invalid_strategy_payload = extract_payload_from_response(&invalid_strategy_response);
-    let invalid_strategy_error = invalid_strategy_payload
-        .get("error")
-        .expect("invalid strategy response should contain error");
-    assert_eq!(
-        invalid_strategy_error
-            .get("code")
-            .and_then(|v| v.as_str())
-            .unwrap(),
-        "invalid_strategy"
+        !raw["context_items"][0]["body"]
+            .as_str()
+            .unwrap()
+            .starts_with(&format!("{line_start:>4}| "))
     );
 
-    for payload in [&missing_symbol_payload, &invalid_strategy_payload] {
-        let metadata = payload
-            .get("metadata")
-            .expect("error responses should include protocol metadata");
-        assert!(metadata.get("cruxe_protocol_version").is_some());
-        assert!(metadata.get("freshness_status").is_some());
-        assert!(metadata.get("indexing_status").is_some());
-        assert!(metadata.get("result_completeness").is_some());
-        assert!(metadata.get("ref").is_some());
-    }
+    let invalid = extract_payload_from_response(&call(json!({
+        "query": "validate_token",
+        "with_line_numbers": "yes"
+    })));
+    assert_eq!(invalid["error"]["code"], "invalid_input");
 }
 
 #[test]
-fn t357_call_graph_feature_tools_error_codes_are_machine_stable() {
+fn t512_get_code_context_returns_line_window_from_disk_or_content_head() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
     let config = Config::default();
-    let workspace = Path::new("/tmp/fake-workspace");
-    let project_id = "fake_project_id";
-
-    let cases = vec![
-        (
-            "get_call_graph",
-            json!({
-                "direction": "both"
-            }),
-            "invalid_input",
-        ),
-        (
-            "compare_symbol_between_commits",
-            json!({
-                "symbol_name": "process_request",
-                "head_ref": "feat/auth"
-            }),
-            "invalid_input",
-        ),
-        (
-            "suggest_followup_queries",
-            json!({
-                "previous_results": {
-                    "top_score": 0.1
-                }
-            }),
-            "invalid_input",
-        ),
-    ];
+    let fixture_dir = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../testdata/fixtures/rust-sample");
 
-    for (tool, arguments, expected_code) in cases {
-        let request = make_request(
-            "tools/call",
-            json!({
-                "name": tool,
-                "arguments": arguments
-            }),
-        );
-        let response = handle_request_with_ctx(
-            &request,
-            &RequestContext {
+    let call = |workspace: &Path, arguments: Value| {
+        extract_payload_from_response(&call_tool(
+            &ToolCallFixture {
                 config: &config,
-                index_set: None,
-                schema_status: SchemaStatus::Compatible,
-                compatibility_reason: None,
-                conn: None,
+                index_set: Some(&index_set),
+                conn: Some(&conn),
                 workspace,
-                project_id,
-                prewarm_status: &test_prewarm_status(),
-                server_start: &test_server_start(),
-                notifier: Arc::new(NullProgressNotifier),
-                progress_token: None,
+                project_id: "test-repo",
             },
-        );
+            "get_code_context",
+            arguments,
+        ))
+    };
+
+    let window = call(
+        &fixture_dir,
+        json!({ "path": "src/auth.rs", "line": 16, "context_lines": 2 }),
+    );
+    assert_eq!(window["source"], "workspace", "{window}");
+    assert_eq!(window["line_start"], 14);
+    assert_eq!(window["line_end"], 18);
+    let content = window["content"].as_str().unwrap();
+    assert!(content.contains("  16| pub enum AuthError {"), "{content}");
+    assert!(window.get("note").is_none());
+
+    let head = call(
+        Path::new("/tmp/fake-workspace"),
+        json!({ "path": "src/auth.rs", "line": 3, "context_lines": 0 }),
+    );
+    assert_eq!(head["source"], "content_head", "{head}");
+    assert_eq!(head["content"], "   3| use std::fmt;");
+    assert!(head["note"].as_str().is_some());
+
+    let missing_path = call(&fixture_dir, json!({ "line": 3 }));
+    assert_eq!(missing_path["error"]["code"], "invalid_input");
+    let escaping = call(&fixture_dir, json!({ "path": "../secret.rs", "line": 1 }));
+    assert_eq!(escaping["error"]["code"], "invalid_input");
+    let unknown = call(&fixture_dir, json!({ "path": "src/nope.rs", "line": 1 }));
+    assert_eq!(unknown["error"]["code"], "file_not_found");
+}
+
+#[test]
+fn t513_search_code_fields_projects_result_keys() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: Some(&conn),
+        workspace,
+        project_id: "test-repo",
+    };
+    let payload = extract_payload_from_response(&call_tool(
+        &tool_ctx,
+        "search_code",
+        json!({
+            "query": "validate_token",
+            "detail_level": "context",
+            "fields": ["path", "name", "line_start", "no_such_field"]
+        }),
+    ));
+
+    let results = payload["results"].as_array().expect("results array");
+    assert!(!results.is_empty(), "{payload}");
+    for result in results {
+        let keys: Vec<&str> = result
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
         assert!(
-            response.error.is_none(),
-            "tool {tool} should return payload envelope"
-        );
-        let payload = extract_payload_from_response(&response);
-        let code = payload
-            .get("error")
-            .and_then(|value| value.get("code"))
-            .and_then(|value| value.as_str());
-        assert_eq!(
-            code,
-            Some(expected_code),
-            "tool {tool} should emit stable error code"
+            keys.iter()
+                .all(|key| ["path", "name", "line_start"].contains(key)),
+            "{result}"
         );
+        assert!(result.get("path").is_some(), "{result}");
     }
 }
 
 #[test]
-fn t357_get_call_graph_returns_ref_not_indexed_for_unindexed_ref() {
+fn t514_commit_argument_pins_queries_to_an_indexed_snapshot() {
     let tmp = tempfile::tempdir().unwrap();
-    let workspace_dir = tmp.path().join("workspace");
-    std::fs::create_dir_all(&workspace_dir).unwrap();
-
-    let db_path = tmp.path().join("state.db");
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
     let conn = cruxe_state::db::open_connection(&db_path).unwrap();
-    cruxe_state::schema::create_tables(&conn).unwrap();
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+    let indexed_commit = "0123456789abcdef0123456789abcdef01234567";
+    cruxe_state::branch_state::upsert_branch_state(
+        &conn,
+        &cruxe_state::branch_state::BranchState {
+            repo: "test-repo".to_string(),
+            r#ref: "live".to_string(),
+            merge_base_commit: None,
+            last_indexed_commit: indexed_commit.to_string(),
+            overlay_dir: None,
+            file_count: 0,
+            symbol_count: 0,
+            is_default_branch: true,
+            status: "active".to_string(),
+            eviction_eligible_at: None,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            last_accessed_at: "2026-01-01T00:00:00Z".to_string(),
+        },
+    )
+    .unwrap();
 
-    let project_id = "call-graph-ref-not-indexed";
-    let now = "2026-02-26T00:00:00Z".to_string();
-    let project = Project {
-        project_id: project_id.to_string(),
-        repo_root: workspace_dir.to_string_lossy().to_string(),
-        display_name: Some("call-graph-ref-not-indexed".to_string()),
-        default_ref: "main".to_string(),
-        vcs_mode: true,
-        schema_version: 1,
-        parser_version: 1,
-        created_at: now.clone(),
-        updated_at: now,
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: Some(&conn),
+        workspace,
+        project_id: "test-repo",
     };
-    cruxe_state::project::create_project(&conn, &project).unwrap();
+    let call = |tool: &str, arguments: Value| {
+        extract_payload_from_response(&call_tool(&tool_ctx, tool, arguments))
+    };
+
+    let located = call(
+        "locate_symbol",
+        json!({ "name": "validate_token", "commit": "0123456" }),
+    );
+    assert_eq!(located["metadata"]["ref"], "live", "{located}");
+    assert_eq!(located["metadata"]["commit"], indexed_commit);
+    assert!(!located["results"].as_array().unwrap().is_empty());
+
+    let searched = call(
+        "search_code",
+        json!({ "query": "validate_token", "commit": indexed_commit }),
+    );
+    assert_eq!(searched["metadata"]["ref"], "live", "{searched}");
+    assert_eq!(searched["metadata"]["commit"], indexed_commit);
+
+    let unknown = call(
+        "search_code",
+        json!({ "query": "validate_token", "commit": "fedcba9" }),
+    );
+    assert_eq!(unknown["error"]["code"], "invalid_input", "{unknown}");
+    let wrong_ref = call(
+        "locate_symbol",
+        json!({ "name": "validate_token", "ref": "main", "commit": "0123456" }),
+    );
+    assert_eq!(wrong_ref["error"]["code"], "invalid_input", "{wrong_ref}");
+    let not_hex = call(
+        "locate_symbol",
+        json!({ "name": "validate_token", "commit": "HEAD~1" }),
+    );
+    assert_eq!(not_hex["error"]["code"], "invalid_input", "{not_hex}");
+}
 
+#[test]
+fn t360_build_context_pack_returns_sectioned_provenance_payload() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
     let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+    let project_id = "test-repo";
+
     let request = make_request(
         "tools/call",
         json!({
-            "name": "get_call_graph",
+            "name": "build_context_pack",
             "arguments": {
-                "symbol_name": "validate_token",
-                "ref": "feat/missing"
+                "query": "validate_token",
+                "budget_tokens": 280,
+                "max_candidates": 80,
+                "language": "rust"
             }
         }),
     );
@@ -2803,11 +4050,11 @@ fn t357_get_call_graph_returns_ref_not_indexed_for_unindexed_ref() {
         &request,
         &RequestContext {
             config: &config,
-            index_set: None,
+            index_set: Some(&index_set),
             schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
             conn: Some(&conn),
-            workspace: workspace_dir.as_path(),
+            workspace,
             project_id,
             prewarm_status: &test_prewarm_status(),
             server_start: &test_server_start(),
@@ -2816,164 +4063,103 @@ fn t357_get_call_graph_returns_ref_not_indexed_for_unindexed_ref() {
         },
     );
 
-    assert!(response.error.is_none(), "MCP tool errors are in content");
+    assert!(response.error.is_none(), "expected success");
     let payload = extract_payload_from_response(&response);
     assert_eq!(
-        payload
-            .get("error")
-            .and_then(|value| value.get("code"))
-            .and_then(|value| value.as_str()),
-        Some("ref_not_indexed")
+        payload.get("mode").and_then(|value| value.as_str()),
+        Some("full")
     );
-    assert_eq!(
+    assert!(
         payload
-            .get("metadata")
-            .and_then(|value| value.get("ref"))
-            .and_then(|value| value.as_str()),
-        Some("feat/missing")
+            .get("token_budget_used")
+            .and_then(|value| value.as_u64())
+            .unwrap_or_default()
+            <= 280,
+        "token_budget_used must respect budget_tokens"
     );
-}
 
-#[test]
-fn t357_get_call_graph_truncated_sets_metadata_result_completeness() {
-    let tmp = tempfile::tempdir().unwrap();
-    let workspace_dir = tmp.path().join("workspace");
-    std::fs::create_dir_all(&workspace_dir).unwrap();
+    let sections = payload
+        .get("sections")
+        .and_then(|value| value.as_object())
+        .expect("sections should be an object");
+    for section in ["definitions", "usages", "deps", "tests", "config", "docs"] {
+        assert!(
+            sections.contains_key(section),
+            "missing section key: {section}"
+        );
+    }
 
-    let db_path = tmp.path().join("state.db");
-    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
-    cruxe_state::schema::create_tables(&conn).unwrap();
+    let all_items = sections
+        .values()
+        .filter_map(|value| value.as_array())
+        .flat_map(|items| items.iter())
+        .collect::<Vec<_>>();
+    assert!(!all_items.is_empty(), "expected at least one pack item");
 
-    let project_id = "call-graph-truncated";
-    let now = "2026-02-26T00:00:00Z".to_string();
-    let project = Project {
-        project_id: project_id.to_string(),
-        repo_root: workspace_dir.to_string_lossy().to_string(),
-        display_name: Some("call-graph-truncated".to_string()),
-        default_ref: "main".to_string(),
-        vcs_mode: true,
-        schema_version: 1,
-        parser_version: 1,
-        created_at: now.clone(),
-        updated_at: now.clone(),
-    };
-    cruxe_state::project::create_project(&conn, &project).unwrap();
-    cruxe_state::branch_state::upsert_branch_state(
-        &conn,
-        &cruxe_state::branch_state::BranchState {
-            repo: project_id.to_string(),
-            r#ref: "main".to_string(),
-            merge_base_commit: None,
-            last_indexed_commit: "abc123".to_string(),
-            overlay_dir: None,
-            file_count: 1,
-            symbol_count: 3,
-            is_default_branch: true,
-            status: "active".to_string(),
-            eviction_eligible_at: None,
-            created_at: now.clone(),
-            last_accessed_at: now,
-        },
-    )
-    .unwrap();
-
-    let symbols = vec![
-        cruxe_core::types::SymbolRecord {
-            repo: project_id.to_string(),
-            r#ref: "main".to_string(),
-            commit: None,
-            path: "src/lib.rs".to_string(),
-            language: "rust".to_string(),
-            symbol_id: "sym::a".to_string(),
-            symbol_stable_id: "stable::a".to_string(),
-            name: "a".to_string(),
-            qualified_name: "a".to_string(),
-            kind: cruxe_core::types::SymbolKind::Function,
-            signature: Some("fn a()".to_string()),
-            line_start: 1,
-            line_end: 3,
-            parent_symbol_id: None,
-            visibility: Some("pub".to_string()),
-            content: Some("b();\nc();".to_string()),
-        },
-        cruxe_core::types::SymbolRecord {
-            repo: project_id.to_string(),
-            r#ref: "main".to_string(),
-            commit: None,
-            path: "src/lib.rs".to_string(),
-            language: "rust".to_string(),
-            symbol_id: "sym::b".to_string(),
-            symbol_stable_id: "stable::b".to_string(),
-            name: "b".to_string(),
-            qualified_name: "b".to_string(),
-            kind: cruxe_core::types::SymbolKind::Function,
-            signature: Some("fn b()".to_string()),
-            line_start: 5,
-            line_end: 7,
-            parent_symbol_id: None,
-            visibility: Some("pub".to_string()),
-            content: Some("{}".to_string()),
-        },
-        cruxe_core::types::SymbolRecord {
-            repo: project_id.to_string(),
-            r#ref: "main".to_string(),
-            commit: None,
-            path: "src/lib.rs".to_string(),
-            language: "rust".to_string(),
-            symbol_id: "sym::c".to_string(),
-            symbol_stable_id: "stable::c".to_string(),
-            name: "c".to_string(),
-            qualified_name: "c".to_string(),
-            kind: cruxe_core::types::SymbolKind::Function,
-            signature: Some("fn c()".to_string()),
-            line_start: 9,
-            line_end: 11,
-            parent_symbol_id: None,
-            visibility: Some("pub".to_string()),
-            content: Some("{}".to_string()),
-        },
-    ];
-    for symbol in &symbols {
-        cruxe_state::symbols::insert_symbol(&conn, symbol).unwrap();
+    for item in &all_items {
+        assert!(item.get("snippet_id").is_some(), "missing snippet_id");
+        assert!(item.get("ref").is_some(), "missing ref");
+        assert!(item.get("path").is_some(), "missing path");
+        assert!(item.get("line_start").is_some(), "missing line_start");
+        assert!(item.get("line_end").is_some(), "missing line_end");
+        assert!(item.get("content_hash").is_some(), "missing content_hash");
+        assert!(
+            item.get("selection_reason").is_some(),
+            "missing selection_reason"
+        );
     }
 
-    let edges = [
-        cruxe_core::types::CallEdge {
-            repo: project_id.to_string(),
-            ref_name: "main".to_string(),
-            from_symbol_id: "stable::a".to_string(),
-            to_symbol_id: Some("stable::b".to_string()),
-            to_name: None,
-            edge_type: "calls".to_string(),
-            confidence: "static".to_string(),
-            source_file: "src/lib.rs".to_string(),
-            source_line: 2,
-        },
-        cruxe_core::types::CallEdge {
-            repo: project_id.to_string(),
-            ref_name: "main".to_string(),
-            from_symbol_id: "stable::a".to_string(),
-            to_symbol_id: Some("stable::c".to_string()),
-            to_name: None,
-            edge_type: "calls".to_string(),
-            confidence: "static".to_string(),
-            source_file: "src/lib.rs".to_string(),
-            source_line: 3,
-        },
-    ];
-    cruxe_state::edges::insert_call_edges(&conn, project_id, "main", &edges).unwrap();
+    assert!(
+        payload
+            .get("coverage_summary")
+            .and_then(|value| value.get("section_counts"))
+            .is_some(),
+        "coverage_summary should include section_counts"
+    );
+    assert!(
+        payload.get("dropped_candidates").is_some(),
+        "payload should include dropped_candidates diagnostics"
+    );
+    assert!(
+        payload.get("suggested_next_queries").is_some(),
+        "payload should include suggested_next_queries diagnostics"
+    );
+    let metadata = payload.get("metadata").expect("metadata should be present");
+    assert_eq!(
+        metadata
+            .get("section_aliases")
+            .and_then(|value| value.get("key_usages"))
+            .and_then(|value| value.as_str()),
+        Some("usages"),
+        "Continue-compatible alias key_usages should map to usages"
+    );
+    assert_eq!(
+        metadata
+            .get("section_aliases")
+            .and_then(|value| value.get("dependencies"))
+            .and_then(|value| value.as_str()),
+        Some("deps"),
+        "Continue-compatible alias dependencies should map to deps"
+    );
+}
 
+#[test]
+fn t361_build_context_pack_ref_scope_roundtrip() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (index_set, conn, branch_name) = build_fixture_index_in_git_repo(tmp.path());
     let config = Config::default();
+    let workspace_buf = tmp.path().join("workspace");
+    let workspace = workspace_buf.as_path();
+    let project_id = "test-repo";
+
     let request = make_request(
         "tools/call",
         json!({
-            "name": "get_call_graph",
+            "name": "build_context_pack",
             "arguments": {
-                "symbol_name": "a",
-                "path": "src/lib.rs",
-                "direction": "callees",
-                "depth": 1,
-                "limit": 1
+                "query": "validate_token",
+                "ref": branch_name,
+                "budget_tokens": 320
             }
         }),
     );
@@ -2981,11 +4167,11 @@ fn t357_get_call_graph_truncated_sets_metadata_result_completeness() {
         &request,
         &RequestContext {
             config: &config,
-            index_set: None,
+            index_set: Some(&index_set),
             schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
             conn: Some(&conn),
-            workspace: workspace_dir.as_path(),
+            workspace,
             project_id,
             prewarm_status: &test_prewarm_status(),
             server_start: &test_server_start(),
@@ -2997,20 +4183,20 @@ fn t357_get_call_graph_truncated_sets_metadata_result_completeness() {
     assert!(response.error.is_none(), "expected success");
     let payload = extract_payload_from_response(&response);
     assert_eq!(
-        payload.get("truncated").and_then(|value| value.as_bool()),
-        Some(true)
+        payload.get("ref").and_then(|value| value.as_str()),
+        Some(branch_name.as_str())
     );
     assert_eq!(
         payload
             .get("metadata")
-            .and_then(|value| value.get("result_completeness"))
+            .and_then(|value| value.get("ref"))
             .and_then(|value| value.as_str()),
-        Some("truncated")
+        Some(branch_name.as_str())
     );
 }
 
 #[test]
-fn t191_get_code_context_negative_max_tokens_returns_invalid_max_tokens() {
+fn t362_build_context_pack_is_deterministic_across_repeated_calls() {
     let tmp = tempfile::tempdir().unwrap();
     let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
     let conn = cruxe_state::db::open_connection(&db_path).unwrap();
@@ -3021,14 +4207,17 @@ fn t191_get_code_context_negative_max_tokens_returns_invalid_max_tokens() {
     let request = make_request(
         "tools/call",
         json!({
-            "name": "get_code_context",
+            "name": "build_context_pack",
             "arguments": {
                 "query": "validate_token",
-                "max_tokens": -1
+                "budget_tokens": 260,
+                "max_candidates": 75,
+                "language": "rust"
             }
         }),
     );
-    let response = handle_request_with_ctx(
+
+    let response_a = handle_request_with_ctx(
         &request,
         &RequestContext {
             config: &config,
@@ -3044,96 +4233,150 @@ fn t191_get_code_context_negative_max_tokens_returns_invalid_max_tokens() {
             progress_token: None,
         },
     );
+    assert!(response_a.error.is_none(), "first call should succeed");
 
-    assert!(response.error.is_none(), "MCP tool errors are in content");
-    let payload = extract_payload_from_response(&response);
-    let error = payload
-        .get("error")
-        .expect("invalid max_tokens response should contain error");
+    let response_b = handle_request_with_ctx(
+        &request,
+        &RequestContext {
+            config: &config,
+            index_set: Some(&index_set),
+            schema_status: SchemaStatus::Compatible,
+            compatibility_reason: None,
+            conn: Some(&conn),
+            workspace,
+            project_id,
+            prewarm_status: &test_prewarm_status(),
+            server_start: &test_server_start(),
+            notifier: Arc::new(NullProgressNotifier),
+            progress_token: None,
+        },
+    );
+    assert!(response_b.error.is_none(), "second call should succeed");
+
+    let payload_a = payload_without_request_id(&response_a);
+    let payload_b = payload_without_request_id(&response_b);
     assert_eq!(
-        error.get("code").and_then(|v| v.as_str()).unwrap(),
-        "invalid_max_tokens"
+        payload_a, payload_b,
+        "repeated runs should be deterministic"
     );
 }
 
 #[test]
-fn t194_symbol_hierarchy_validation_precision() {
+fn t363_context_pack_iterative_fixture_drives_followup_query_loop() {
     let tmp = tempfile::tempdir().unwrap();
     let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
     let conn = cruxe_state::db::open_connection(&db_path).unwrap();
     let config = Config::default();
     let workspace = Path::new("/tmp/fake-workspace");
     let project_id = "test-repo";
+    let fixture_path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../testdata/fixtures/context-pack/iterative-workflow.json");
+    let fixture_raw = std::fs::read_to_string(&fixture_path)
+        .unwrap_or_else(|err| panic!("failed to read fixture {}: {err}", fixture_path.display()));
+    let fixture: serde_json::Value = serde_json::from_str(&fixture_raw).unwrap();
 
-    let cases = vec![
-        ("validate_token", "src/auth.rs"),
-        ("require_role", "src/auth.rs"),
-        ("is_expired", "src/auth.rs"),
-        ("issuer", "src/auth.rs"),
-        ("handle_request", "src/handler.rs"),
-        ("authenticate", "src/handler.rs"),
-        ("get_user", "src/handler.rs"),
-        ("create_user", "src/handler.rs"),
-        ("new", "src/lib.rs"),
-        ("health_check", "src/lib.rs"),
-    ];
+    let initial_query = fixture
+        .get("initial")
+        .and_then(|value| value.get("query"))
+        .and_then(|value| value.as_str())
+        .expect("fixture initial.query should exist");
+    let initial_budget = fixture
+        .get("initial")
+        .and_then(|value| value.get("budget_tokens"))
+        .and_then(|value| value.as_u64())
+        .expect("fixture initial.budget_tokens should exist");
+    let followup_budget = fixture
+        .get("follow_up")
+        .and_then(|value| value.get("budget_tokens"))
+        .and_then(|value| value.as_u64())
+        .expect("fixture follow_up.budget_tokens should exist");
 
-    let mut correct = 0usize;
-    for (symbol_name, path) in &cases {
-        let request = make_request(
-            "tools/call",
-            json!({
-                "name": "get_symbol_hierarchy",
-                "arguments": {
-                    "symbol_name": symbol_name,
-                    "path": path,
-                    "direction": "ancestors"
-                }
-            }),
-        );
-        let response = handle_request_with_ctx(
-            &request,
-            &RequestContext {
-                config: &config,
-                index_set: Some(&index_set),
-                schema_status: SchemaStatus::Compatible,
-                compatibility_reason: None,
-                conn: Some(&conn),
-                workspace,
-                project_id,
-                prewarm_status: &test_prewarm_status(),
-                server_start: &test_server_start(),
-                notifier: Arc::new(NullProgressNotifier),
-                progress_token: None,
-            },
-        );
-        if response.error.is_some() {
-            continue;
-        }
-        let payload = extract_payload_from_response(&response);
-        let first_name = payload
-            .get("hierarchy")
-            .and_then(|v| v.as_array())
-            .and_then(|arr| arr.first())
-            .and_then(|v| v.get("name"))
-            .and_then(|v| v.as_str());
-        if first_name == Some(*symbol_name) {
-            correct += 1;
-        }
-    }
+    let initial_request = make_request(
+        "tools/call",
+        json!({
+            "name": "build_context_pack",
+            "arguments": {
+                "query": initial_query,
+                "budget_tokens": initial_budget,
+                "mode": "edit_minimal"
+            }
+        }),
+    );
+    let initial_response = handle_request_with_ctx(
+        &initial_request,
+        &RequestContext {
+            config: &config,
+            index_set: Some(&index_set),
+            schema_status: SchemaStatus::Compatible,
+            compatibility_reason: None,
+            conn: Some(&conn),
+            workspace,
+            project_id,
+            prewarm_status: &test_prewarm_status(),
+            server_start: &test_server_start(),
+            notifier: Arc::new(NullProgressNotifier),
+            progress_token: None,
+        },
+    );
+    assert!(
+        initial_response.error.is_none(),
+        "initial call should succeed"
+    );
+    let initial_payload = extract_payload_from_response(&initial_response);
+    let followup_query = initial_payload
+        .get("suggested_next_queries")
+        .and_then(|value| value.as_array())
+        .and_then(|items| items.first())
+        .and_then(|value| value.as_str())
+        .expect("initial response should suggest a follow-up query");
 
-    let precision = correct as f64 / cases.len() as f64;
+    let followup_request = make_request(
+        "tools/call",
+        json!({
+            "name": "build_context_pack",
+            "arguments": {
+                "query": followup_query,
+                "budget_tokens": followup_budget
+            }
+        }),
+    );
+    let followup_response = handle_request_with_ctx(
+        &followup_request,
+        &RequestContext {
+            config: &config,
+            index_set: Some(&index_set),
+            schema_status: SchemaStatus::Compatible,
+            compatibility_reason: None,
+            conn: Some(&conn),
+            workspace,
+            project_id,
+            prewarm_status: &test_prewarm_status(),
+            server_start: &test_server_start(),
+            notifier: Arc::new(NullProgressNotifier),
+            progress_token: None,
+        },
+    );
     assert!(
-        precision >= 0.95,
-        "hierarchy precision should be >= 95%, got {:.1}% ({}/{})",
-        precision * 100.0,
-        correct,
-        cases.len()
+        followup_response.error.is_none(),
+        "follow-up call should succeed"
+    );
+    let followup_payload = extract_payload_from_response(&followup_response);
+
+    assert!(
+        followup_payload
+            .get("token_budget_used")
+            .and_then(|value| value.as_u64())
+            .unwrap_or_default()
+            >= initial_payload
+                .get("token_budget_used")
+                .and_then(|value| value.as_u64())
+                .unwrap_or_default(),
+        "follow-up run should expand or maintain context footprint with higher budget"
     );
 }
 
 #[test]
-fn t195_code_context_budget_validation() {
+fn t364_build_context_pack_accepts_partial_section_caps_patch() {
     let tmp = tempfile::tempdir().unwrap();
     let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
     let conn = cruxe_state::db::open_connection(&db_path).unwrap();
@@ -3141,58 +4384,75 @@ fn t195_code_context_budget_validation() {
     let workspace = Path::new("/tmp/fake-workspace");
     let project_id = "test-repo";
 
-    let budgets = [50_u64, 75, 100, 150, 200, 300, 500, 800, 1200, 2000];
-    for budget in budgets {
-        let request = make_request(
-            "tools/call",
-            json!({
-                "name": "get_code_context",
-                "arguments": {
-                    "query": "validate_token",
-                    "max_tokens": budget,
-                    "strategy": "breadth",
-                    "language": "rust"
+    let request = make_request(
+        "tools/call",
+        json!({
+            "name": "build_context_pack",
+            "arguments": {
+                "query": "validate_token",
+                "budget_tokens": 500,
+                "mode": "full",
+                "section_caps": {
+                    "definitions": 1,
+                    "key_usages": 4,
+                    "dependencies": 3
                 }
-            }),
-        );
-        let response = handle_request_with_ctx(
-            &request,
-            &RequestContext {
-                config: &config,
-                index_set: Some(&index_set),
-                schema_status: SchemaStatus::Compatible,
-                compatibility_reason: None,
-                conn: Some(&conn),
-                workspace,
-                project_id,
-                prewarm_status: &test_prewarm_status(),
-                server_start: &test_server_start(),
-                notifier: Arc::new(NullProgressNotifier),
-                progress_token: None,
-            },
-        );
-        assert!(response.error.is_none(), "context request should succeed");
-        let payload = extract_payload_from_response(&response);
-        let estimated = payload
-            .get("estimated_tokens")
-            .and_then(|v| v.as_u64())
-            .expect("estimated_tokens should be present");
-        assert!(
-            estimated <= budget,
-            "estimated_tokens={} should not exceed budget={}",
-            estimated,
-            budget
-        );
-    }
+            }
+        }),
+    );
+    let response = handle_request_with_ctx(
+        &request,
+        &RequestContext {
+            config: &config,
+            index_set: Some(&index_set),
+            schema_status: SchemaStatus::Compatible,
+            compatibility_reason: None,
+            conn: Some(&conn),
+            workspace,
+            project_id,
+            prewarm_status: &test_prewarm_status(),
+            server_start: &test_server_start(),
+            notifier: Arc::new(NullProgressNotifier),
+            progress_token: None,
+        },
+    );
+
+    assert!(response.error.is_none(), "expected success");
+    let payload = extract_payload_from_response(&response);
+    let sections = payload
+        .get("sections")
+        .and_then(|value| value.as_object())
+        .expect("sections should be an object");
+    assert!(
+        sections.contains_key("definitions"),
+        "sections should include definitions bucket"
+    );
+
+    let section_caps = payload
+        .get("metadata")
+        .and_then(|value| value.get("section_caps"))
+        .expect("metadata.section_caps should be present");
+    assert_eq!(
+        section_caps
+            .get("definitions")
+            .and_then(|value| value.as_u64()),
+        Some(1)
+    );
+    assert_eq!(
+        section_caps.get("usages").and_then(|value| value.as_u64()),
+        Some(4)
+    );
+    assert_eq!(
+        section_caps.get("deps").and_then(|value| value.as_u64()),
+        Some(3)
+    );
 }
 
-/// T111: health_check on a healthy system returns ready status
 #[test]
-fn t111_health_check_on_healthy_system() {
+fn t365_build_context_pack_enforces_max_candidates_upper_bound() {
     let tmp = tempfile::tempdir().unwrap();
     let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
     let conn = cruxe_state::db::open_connection(&db_path).unwrap();
-
     let config = Config::default();
     let workspace = Path::new("/tmp/fake-workspace");
     let project_id = "test-repo";
@@ -3200,11 +4460,15 @@ fn t111_health_check_on_healthy_system() {
     let request = make_request(
         "tools/call",
         json!({
-            "name": "health_check",
-            "arguments": {}
+            "name": "build_context_pack",
+            "arguments": {
+                "query": "validate_token",
+                "budget_tokens": 5_000,
+                "max_candidates": 1,
+                "mode": "full"
+            }
         }),
     );
-
     let response = handle_request_with_ctx(
         &request,
         &RequestContext {
@@ -3222,131 +4486,66 @@ fn t111_health_check_on_healthy_system() {
         },
     );
 
-    assert!(response.error.is_none(), "expected success, got error");
-    let result = response.result.as_ref().unwrap();
-    let content = result.get("content").unwrap().as_array().unwrap();
-    let text = content[0].get("text").unwrap().as_str().unwrap();
-    let payload: serde_json::Value = serde_json::from_str(text).unwrap();
-
-    // Status should be "ready"
-    assert_eq!(
-        payload.get("status").unwrap().as_str().unwrap(),
-        "ready",
-        "expected status 'ready'"
-    );
-
-    // Tantivy should be ok
+    assert!(response.error.is_none(), "expected success");
+    let payload = extract_payload_from_response(&response);
+    let selected_items = payload
+        .get("sections")
+        .and_then(|value| value.as_object())
+        .expect("sections should be object")
+        .values()
+        .filter_map(|value| value.as_array())
+        .map(std::vec::Vec::len)
+        .sum::<usize>();
     assert!(
-        payload.get("tantivy_ok").unwrap().as_bool().unwrap(),
-        "expected tantivy_ok: true"
+        selected_items <= 1,
+        "selected items should honor max_candidates upper bound"
     );
 
-    // SQLite should be ok
+    let metadata = payload.get("metadata").expect("metadata should exist");
     assert!(
-        payload.get("sqlite_ok").unwrap().as_bool().unwrap(),
-        "expected sqlite_ok: true"
-    );
-
-    // Grammars: all 4 should be available
-    let grammars = payload.get("grammars").unwrap();
-    let available = grammars.get("available").unwrap().as_array().unwrap();
-    assert!(
-        available.len() >= 4,
-        "expected at least 4 grammars available, got {}",
-        available.len()
+        metadata
+            .get("total_raw_candidates")
+            .and_then(|value| value.as_u64())
+            .unwrap_or_default()
+            <= 1,
+        "raw candidates should be capped before dedup"
     );
-    let missing = grammars.get("missing").unwrap().as_array().unwrap();
     assert!(
-        missing.is_empty(),
-        "expected no missing grammars, got {:?}",
-        missing
-    );
-
-    // Startup checks
-    let startup = payload.get("startup_checks").unwrap();
-    let index_check = startup.get("index").unwrap();
-    assert_eq!(
-        index_check.get("status").unwrap().as_str().unwrap(),
-        "compatible"
+        metadata
+            .get("deduped_candidates")
+            .and_then(|value| value.as_u64())
+            .unwrap_or_default()
+            <= 1,
+        "deduped candidates should not exceed max_candidates"
     );
-
-    // Protocol version in metadata
-    let meta = payload.get("metadata").unwrap();
-    assert!(meta.get("cruxe_protocol_version").is_some());
-
-    // Prewarm status should be present
-    assert!(payload.get("prewarm_status").is_some());
-    // Warmset metadata should be present
-    let warmset = payload.get("workspace_warmset").unwrap();
-    assert!(warmset.get("enabled").is_some());
-    assert!(warmset.get("capacity").is_some());
-    assert!(warmset.get("members").is_some());
-
-    // Per-project schema compatibility fields should be present
-    let projects = payload.get("projects").unwrap().as_array().unwrap();
-    assert!(!projects.is_empty());
-    let proj = &projects[0];
-    assert!(proj.get("schema_status").is_some());
-    assert!(proj.get("current_schema_version").is_some());
-    assert!(proj.get("required_schema_version").is_some());
 }
 
 #[test]
-fn t111_health_check_active_job_sets_indexing_status() {
+fn t366_build_context_pack_zero_results_emits_underfilled_guidance() {
     let tmp = tempfile::tempdir().unwrap();
     let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
     let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    let config = Config::default();
     let workspace = Path::new("/tmp/fake-workspace");
     let project_id = "test-repo";
 
-    let project = Project {
-        project_id: project_id.to_string(),
-        repo_root: workspace.to_string_lossy().to_string(),
-        display_name: Some("test".to_string()),
-        default_ref: "live".to_string(),
-        vcs_mode: false,
-        schema_version: cruxe_core::constants::SCHEMA_VERSION,
-        parser_version: cruxe_core::constants::PARSER_VERSION,
-        created_at: "2026-01-01T00:00:00Z".to_string(),
-        updated_at: "2026-01-01T00:00:00Z".to_string(),
-    };
-    cruxe_state::project::create_project(&conn, &project).unwrap();
-
-    cruxe_state::jobs::create_job(
-        &conn,
-        &cruxe_state::jobs::IndexJob {
-            job_id: "job_active".to_string(),
-            project_id: project_id.to_string(),
-            r#ref: "live".to_string(),
-            mode: "incremental".to_string(),
-            head_commit: Some("abc123".to_string()),
-            sync_id: None,
-            status: "running".to_string(),
-            changed_files: 1,
-            duration_ms: None,
-            error_message: None,
-            retry_count: 0,
-            progress_token: None,
-            files_scanned: 0,
-            files_indexed: 0,
-            symbols_extracted: 0,
-            created_at: "2026-01-01T00:00:00Z".to_string(),
-            updated_at: "2026-01-01T00:00:00Z".to_string(),
-        },
-    )
-    .unwrap();
-
+    let zero_query = "zzzz_context_pack_zero_result_probe_987654321";
     let request = make_request(
         "tools/call",
         json!({
-            "name": "health_check",
-            "arguments": {}
+            "name": "build_context_pack",
+            "arguments": {
+                "query": zero_query,
+                "budget_tokens": 400,
+                "language": "fortran",
+                "mode": "full"
+            }
         }),
     );
     let response = handle_request_with_ctx(
         &request,
         &RequestContext {
-            config: &Config::default(),
+            config: &config,
             index_set: Some(&index_set),
             schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
@@ -3361,126 +4560,146 @@ fn t111_health_check_active_job_sets_indexing_status() {
     );
 
     assert!(response.error.is_none(), "expected success");
-    let result = response.result.as_ref().unwrap();
-    let content = result.get("content").unwrap().as_array().unwrap();
-    let text = content[0].get("text").unwrap().as_str().unwrap();
-    let payload: serde_json::Value = serde_json::from_str(text).unwrap();
+    let payload = extract_payload_from_response(&response);
+    let sections = payload
+        .get("sections")
+        .and_then(|value| value.as_object())
+        .expect("sections should be object");
+    let selected_items = sections
+        .values()
+        .filter_map(|value| value.as_array())
+        .map(std::vec::Vec::len)
+        .sum::<usize>();
+    assert_eq!(
+        selected_items, 0,
+        "zero-result query should return no sections"
+    );
+    assert_eq!(
+        payload
+            .get("token_budget_used")
+            .and_then(|value| value.as_u64())
+            .unwrap_or_default(),
+        0
+    );
 
-    assert_eq!(payload.get("status").unwrap().as_str().unwrap(), "indexing");
+    let suggestions = payload
+        .get("suggested_next_queries")
+        .and_then(|value| value.as_array())
+        .expect("suggested_next_queries should exist")
+        .iter()
+        .filter_map(|value| value.as_str())
+        .collect::<Vec<_>>();
     assert!(
-        payload.get("active_job").is_some(),
-        "active_job should be present when an indexing job is running"
+        suggestions.contains(&format!("{zero_query} symbol").as_str()),
+        "zero-result guidance should include a symbol-focused follow-up"
+    );
+
+    let hints = payload
+        .get("missing_context_hints")
+        .and_then(|value| value.as_array())
+        .expect("missing_context_hints should exist")
+        .iter()
+        .filter_map(|value| value.as_str())
+        .collect::<Vec<_>>();
+    assert!(
+        hints
+            .iter()
+            .any(|hint| hint.contains("No candidates were selected")),
+        "zero-result guidance should surface explicit underfilled hint"
+    );
+
+    let metadata = payload.get("metadata").expect("metadata should exist");
+    assert_eq!(
+        metadata
+            .get("budget_utilization_ratio")
+            .and_then(|value| value.as_f64()),
+        Some(0.0),
+        "zero results should produce zero budget utilization"
+    );
+    assert_eq!(
+        metadata
+            .get("token_estimation")
+            .and_then(|value| value.get("method"))
+            .and_then(|value| value.as_str()),
+        Some("cruxe_core::tokens::estimate_tokens")
     );
 }
 
 #[test]
-fn t111_health_check_warming_has_priority_over_indexing() {
+fn t367_build_context_pack_rejects_excessive_budget_tokens() {
     let tmp = tempfile::tempdir().unwrap();
     let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
     let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    let config = Config::default();
     let workspace = Path::new("/tmp/fake-workspace");
     let project_id = "test-repo";
 
-    let project = Project {
-        project_id: project_id.to_string(),
-        repo_root: workspace.to_string_lossy().to_string(),
-        display_name: Some("test".to_string()),
-        default_ref: "live".to_string(),
-        vcs_mode: false,
-        schema_version: cruxe_core::constants::SCHEMA_VERSION,
-        parser_version: cruxe_core::constants::PARSER_VERSION,
-        created_at: "2026-01-01T00:00:00Z".to_string(),
-        updated_at: "2026-01-01T00:00:00Z".to_string(),
-    };
-    cruxe_state::project::create_project(&conn, &project).unwrap();
-
-    cruxe_state::jobs::create_job(
-        &conn,
-        &cruxe_state::jobs::IndexJob {
-            job_id: "job_active".to_string(),
-            project_id: project_id.to_string(),
-            r#ref: "live".to_string(),
-            mode: "incremental".to_string(),
-            head_commit: Some("abc123".to_string()),
-            sync_id: None,
-            status: "running".to_string(),
-            changed_files: 1,
-            duration_ms: None,
-            error_message: None,
-            retry_count: 0,
-            progress_token: None,
-            files_scanned: 0,
-            files_indexed: 0,
-            symbols_extracted: 0,
-            created_at: "2026-01-01T00:00:00Z".to_string(),
-            updated_at: "2026-01-01T00:00:00Z".to_string(),
-        },
-    )
-    .unwrap();
-
-    let pw = AtomicU8::new(PREWARM_IN_PROGRESS);
     let request = make_request(
         "tools/call",
         json!({
-            "name": "health_check",
-            "arguments": {}
+            "name": "build_context_pack",
+            "arguments": {
+                "query": "validate_token",
+                "budget_tokens": 300001
+            }
         }),
     );
-
     let response = handle_request_with_ctx(
         &request,
         &RequestContext {
-            config: &Config::default(),
+            config: &config,
             index_set: Some(&index_set),
             schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
             conn: Some(&conn),
             workspace,
             project_id,
-            prewarm_status: &pw,
+            prewarm_status: &test_prewarm_status(),
             server_start: &test_server_start(),
             notifier: Arc::new(NullProgressNotifier),
             progress_token: None,
         },
     );
 
-    assert!(response.error.is_none(), "expected success");
-    let result = response.result.as_ref().unwrap();
-    let content = result.get("content").unwrap().as_array().unwrap();
-    let text = content[0].get("text").unwrap().as_str().unwrap();
-    let payload: serde_json::Value = serde_json::from_str(text).unwrap();
-
+    assert!(response.error.is_none(), "MCP tool errors are in content");
+    let payload = extract_payload_from_response(&response);
+    let error = payload
+        .get("error")
+        .expect("excessive budget_tokens response should contain error");
     assert_eq!(
-        payload.get("status").unwrap().as_str().unwrap(),
-        "warming",
-        "warming should take priority over indexing when both are present"
+        error.get("code").and_then(|v| v.as_str()),
+        Some("invalid_max_tokens")
+    );
+    assert!(
+        error
+            .get("message")
+            .and_then(|v| v.as_str())
+            .is_some_and(|msg| msg.contains("200000")),
+        "error message should report the max budget bound"
     );
 }
 
-/// T116: health_check returns "warming" when prewarm is in progress
 #[test]
-fn t116_health_check_warming_status_during_prewarm() {
+fn t191_new_tools_error_codes_follow_protocol_registry() {
     let tmp = tempfile::tempdir().unwrap();
     let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
     let conn = cruxe_state::db::open_connection(&db_path).unwrap();
-
     let config = Config::default();
     let workspace = Path::new("/tmp/fake-workspace");
     let project_id = "test-repo";
 
-    // Simulate prewarm in progress
-    let pw = AtomicU8::new(PREWARM_IN_PROGRESS);
-
-    let request = make_request(
+    let missing_symbol_request = make_request(
         "tools/call",
         json!({
-            "name": "health_check",
-            "arguments": {}
+            "name": "get_symbol_hierarchy",
+            "arguments": {
+                "symbol_name": "this_symbol_should_not_exist",
+                "path": "src/handler.rs"
+            }
         }),
     );
-
-    let response = handle_request_with_ctx(
-        &request,
+    let missing_symbol_response = handle_request_with_ctx(
+        &missing_symbol_request,
         &RequestContext {
             config: &config,
             index_set: Some(&index_set),
@@ -3489,35 +4708,40 @@ fn t116_health_check_warming_status_during_prewarm() {
             conn: Some(&conn),
             workspace,
             project_id,
-            prewarm_status: &pw,
+            prewarm_status: &test_prewarm_status(),
             server_start: &test_server_start(),
             notifier: Arc::new(NullProgressNotifier),
             progress_token: None,
         },
     );
-
-    assert!(response.error.is_none());
-    let result = response.result.as_ref().unwrap();
-    let content = result.get("content").unwrap().as_array().unwrap();
-    let text = content[0].get("text").unwrap().as_str().unwrap();
-    let payload: serde_json::Value = serde_json::from_str(text).unwrap();
-
-    // Status should be "warming" while prewarm is in progress
-    assert_eq!(
-        payload.get("status").unwrap().as_str().unwrap(),
-        "warming",
-        "expected status 'warming' during prewarm"
+    assert!(
+        missing_symbol_response.error.is_none(),
+        "MCP tool errors are in content"
     );
+    let missing_symbol_payload = extract_payload_from_response(&missing_symbol_response);
+    let missing_symbol_error = missing_symbol_payload
+        .get("error")
+        .expect("missing symbol response should contain error");
     assert_eq!(
-        payload.get("prewarm_status").unwrap().as_str().unwrap(),
-        "warming"
+        missing_symbol_error
+            .get("code")
+            .and_then(|v| v.as_str())
+            .unwrap(),
+        "symbol_not_found"
     );
 
-    // Now simulate prewarm complete
-    pw.store(PREWARM_COMPLETE, Ordering::Release);
-
-    let response2 = handle_request_with_ctx(
-        &request,
+    let invalid_strategy_request = make_request(
+        "tools/call",
+        json!({
+            "name": "get_code_context",
+            "arguments": {
+                "query": "validate_token",
+                "strategy": "wide"
+            }
+        }),
+    );
+    let invalid_strategy_response = handle_request_with_ctx(
+        &invalid_strategy_request,
         &RequestContext {
             config: &config,
             index_set: Some(&index_set),
@@ -3526,183 +4750,399 @@ fn t116_health_check_warming_status_during_prewarm() {
             conn: Some(&conn),
             workspace,
             project_id,
-            prewarm_status: &pw,
+            prewarm_status: &test_prewarm_status(),
             server_start: &test_server_start(),
             notifier: Arc::new(NullProgressNotifier),
             progress_token: None,
         },
     );
-
-    let result2 = response2.result.as_ref().unwrap();
-    let content2 = result2.get("content").unwrap().as_array().unwrap();
-    let text2 = content2[0].get("text").unwrap().as_str().unwrap();
-    let payload2: serde_json::Value = serde_json::from_str(text2).unwrap();
-
-    // Status should now be "ready"
-    assert_eq!(
-        payload2.get("status").unwrap().as_str().unwrap(),
-        "ready",
-        "expected status 'ready' after prewarm completes"
+    assert!(
+        invalid_strategy_response.error.is_none(),
+        "MCP tool errors are in content"
     );
+    let invalid_strategy_payload = extract_payload_from_response(&invalid_strategy_response);
+    let invalid_strategy_error = invalid_strategy_payload
+        .get("error")
+        .expect("invalid strategy response should contain error");
     assert_eq!(
-        payload2.get("prewarm_status").unwrap().as_str().unwrap(),
-        "complete"
+        invalid_strategy_error
+            .get("code")
+            .and_then(|v| v.as_str())
+            .unwrap(),
+        "invalid_strategy"
     );
+
+    for payload in [&missing_symbol_payload, &invalid_strategy_payload] {
+        let metadata = payload
+            .get("metadata")
+            .expect("error responses should include protocol metadata");
+        assert!(metadata.get("cruxe_protocol_version").is_some());
+        assert!(metadata.get("freshness_status").is_some());
+        assert!(metadata.get("indexing_status").is_some());
+        assert!(metadata.get("result_completeness").is_some());
+        assert!(metadata.get("ref").is_some());
+    }
 }
 
-/// T117: health_check returns "ready" immediately with --no-prewarm (skipped)
 #[test]
-fn t117_health_check_no_prewarm_skipped() {
-    let tmp = tempfile::tempdir().unwrap();
-    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
-    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
-
+fn t357_call_graph_feature_tools_error_codes_are_machine_stable() {
     let config = Config::default();
     let workspace = Path::new("/tmp/fake-workspace");
-    let project_id = "test-repo";
+    let project_id = "fake_project_id";
 
-    // Simulate --no-prewarm: status is SKIPPED
-    let pw = AtomicU8::new(PREWARM_SKIPPED);
+    let cases = vec![
+        (
+            "get_call_graph",
+            json!({
+                "direction": "both"
+            }),
+            "invalid_input",
+        ),
+        (
+            "compare_symbol_between_commits",
+            json!({
+                "symbol_name": "process_request",
+                "head_ref": "feat/auth"
+            }),
+            "invalid_input",
+        ),
+        (
+            "compare_symbols",
+            json!({
+                "left_symbol_stable_id": "stable::a"
+            }),
+            "invalid_input",
+        ),
+        (
+            "suggest_followup_queries",
+            json!({
+                "previous_results": {
+                    "top_score": 0.1
+                }
+            }),
+            "invalid_input",
+        ),
+    ];
+
+    for (tool, arguments, expected_code) in cases {
+        let request = make_request(
+            "tools/call",
+            json!({
+                "name": tool,
+                "arguments": arguments
+            }),
+        );
+        let response = handle_request_with_ctx(
+            &request,
+            &RequestContext {
+                config: &config,
+                index_set: None,
+                schema_status: SchemaStatus::Compatible,
+                compatibility_reason: None,
+                conn: None,
+                workspace,
+                project_id,
+                prewarm_status: &test_prewarm_status(),
+                server_start: &test_server_start(),
+                notifier: Arc::new(NullProgressNotifier),
+                progress_token: None,
+            },
+        );
+        assert!(
+            response.error.is_none(),
+            "tool {tool} should return payload envelope"
+        );
+        let payload = extract_payload_from_response(&response);
+        let code = payload
+            .get("error")
+            .and_then(|value| value.get("code"))
+            .and_then(|value| value.as_str());
+        assert_eq!(
+            code,
+            Some(expected_code),
+            "tool {tool} should emit stable error code"
+        );
+    }
+}
+
+#[test]
+fn t357_get_call_graph_returns_ref_not_indexed_for_unindexed_ref() {
+    let tmp = tempfile::tempdir().unwrap();
+    let workspace_dir = tmp.path().join("workspace");
+    std::fs::create_dir_all(&workspace_dir).unwrap();
+
+    let db_path = tmp.path().join("state.db");
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    cruxe_state::schema::create_tables(&conn).unwrap();
+
+    let project_id = "call-graph-ref-not-indexed";
+    let now = "2026-02-26T00:00:00Z".to_string();
+    let project = Project {
+        project_id: project_id.to_string(),
+        repo_root: workspace_dir.to_string_lossy().to_string(),
+        display_name: Some("call-graph-ref-not-indexed".to_string()),
+        default_ref: "main".to_string(),
+        vcs_mode: true,
+        schema_version: 1,
+        parser_version: 1,
+        created_at: now.clone(),
+        updated_at: now,
+    };
+    cruxe_state::project::create_project(&conn, &project).unwrap();
 
+    let config = Config::default();
     let request = make_request(
         "tools/call",
         json!({
-            "name": "health_check",
-            "arguments": {}
+            "name": "get_call_graph",
+            "arguments": {
+                "symbol_name": "validate_token",
+                "ref": "feat/missing"
+            }
         }),
     );
-
     let response = handle_request_with_ctx(
         &request,
         &RequestContext {
             config: &config,
-            index_set: Some(&index_set),
+            index_set: None,
             schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
             conn: Some(&conn),
-            workspace,
+            workspace: workspace_dir.as_path(),
             project_id,
-            prewarm_status: &pw,
+            prewarm_status: &test_prewarm_status(),
             server_start: &test_server_start(),
             notifier: Arc::new(NullProgressNotifier),
             progress_token: None,
         },
     );
 
-    assert!(response.error.is_none());
-    let result = response.result.as_ref().unwrap();
-    let content = result.get("content").unwrap().as_array().unwrap();
-    let text = content[0].get("text").unwrap().as_str().unwrap();
-    let payload: serde_json::Value = serde_json::from_str(text).unwrap();
-
-    // Status should be "ready" immediately (not "warming")
+    assert!(response.error.is_none(), "MCP tool errors are in content");
+    let payload = extract_payload_from_response(&response);
     assert_eq!(
-        payload.get("status").unwrap().as_str().unwrap(),
-        "ready",
-        "expected status 'ready' with --no-prewarm"
+        payload
+            .get("error")
+            .and_then(|value| value.get("code"))
+            .and_then(|value| value.as_str()),
+        Some("ref_not_indexed")
     );
     assert_eq!(
-        payload.get("prewarm_status").unwrap().as_str().unwrap(),
-        "skipped"
+        payload
+            .get("metadata")
+            .and_then(|value| value.get("ref"))
+            .and_then(|value| value.as_str()),
+        Some("feat/missing")
     );
 }
 
 #[test]
-fn t118_health_check_prewarm_failed_reports_error() {
+fn t357_get_call_graph_truncated_sets_metadata_result_completeness() {
     let tmp = tempfile::tempdir().unwrap();
-    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let workspace_dir = tmp.path().join("workspace");
+    std::fs::create_dir_all(&workspace_dir).unwrap();
+
+    let db_path = tmp.path().join("state.db");
     let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    cruxe_state::schema::create_tables(&conn).unwrap();
 
-    let config = Config::default();
-    let workspace = Path::new("/tmp/fake-workspace");
-    let project_id = "test-repo";
-    let pw = AtomicU8::new(PREWARM_FAILED);
+    let project_id = "call-graph-truncated";
+    let now = "2026-02-26T00:00:00Z".to_string();
+    let project = Project {
+        project_id: project_id.to_string(),
+        repo_root: workspace_dir.to_string_lossy().to_string(),
+        display_name: Some("call-graph-truncated".to_string()),
+        default_ref: "main".to_string(),
+        vcs_mode: true,
+        schema_version: 1,
+        parser_version: 1,
+        created_at: now.clone(),
+        updated_at: now.clone(),
+    };
+    cruxe_state::project::create_project(&conn, &project).unwrap();
+    cruxe_state::branch_state::upsert_branch_state(
+        &conn,
+        &cruxe_state::branch_state::BranchState {
+            repo: project_id.to_string(),
+            r#ref: "main".to_string(),
+            merge_base_commit: None,
+            last_indexed_commit: "abc123".to_string(),
+            overlay_dir: None,
+            file_count: 1,
+            symbol_count: 3,
+            is_default_branch: true,
+            status: "active".to_string(),
+            eviction_eligible_at: None,
+            created_at: now.clone(),
+            last_accessed_at: now,
+        },
+    )
+    .unwrap();
+
+    let symbols = vec![
+        cruxe_core::types::SymbolRecord {
+            repo: project_id.to_string(),
+            r#ref: "main".to_string(),
+            commit: None,
+            path: "src/lib.rs".to_string(),
+            language: "rust".to_string(),
+            symbol_id: "sym::a".to_string(),
+            symbol_stable_id: "stable::a".to_string(),
+            name: "a".to_string(),
+            qualified_name: "a".to_string(),
+            kind: cruxe_core::types::SymbolKind::Function,
+            signature: Some("fn a()".to_string()),
+            line_start: 1,
+            line_end: 3,
+            parent_symbol_id: None,
+            visibility: Some("pub".to_string()),
+            content: Some("b();\nc();".to_string()),
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
+        },
+        cruxe_core::types::SymbolRecord {
+            repo: project_id.to_string(),
+            r#ref: "main".to_string(),
+            commit: None,
+            path: "src/lib.rs".to_string(),
+            language: "rust".to_string(),
+            symbol_id: "sym::b".to_string(),
+            symbol_stable_id: "stable::b".to_string(),
+            name: "b".to_string(),
+            qualified_name: "b".to_string(),
+            kind: cruxe_core::types::SymbolKind::Function,
+            signature: Some("fn b()".to_string()),
+            line_start: 5,
+            line_end: 7,
+            parent_symbol_id: None,
+            visibility: Some("pub".to_string()),
+            content: Some("{}".to_string()),
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
+        },
+        cruxe_core::types::SymbolRecord {
+            repo: project_id.to_string(),
+            r#ref: "main".to_string(),
+            commit: None,
+            path: "src/lib.rs".to_string(),
+            language: "rust".to_string(),
+            symbol_id: "sym::c".to_string(),
+            symbol_stable_id: "stable::c".to_string(),
+            name: "c".to_string(),
+            qualified_name: "c".to_string(),
+            kind: cruxe_core::types::SymbolKind::Function,
+            signature: Some("fn c()".to_string()),
+            line_start: 9,
+            line_end: 11,
+            parent_symbol_id: None,
+            visibility: Some("pub".to_string()),
+            content: Some("{}".to_string()),
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
+        },
+    ];
+    for symbol in &symbols {
+        cruxe_state::symbols::insert_symbol(&conn, symbol).unwrap();
+    }
+
+    let edges = [
+        cruxe_core::types::CallEdge {
+            repo: project_id.to_string(),
+            ref_name: "main".to_string(),
+            from_symbol_id: "stable::a".to_string(),
+            to_symbol_id: Some("stable::b".to_string()),
+            to_name: None,
+            edge_type: "calls".to_string(),
+            confidence: "static".to_string(),
+            source_file: "src/lib.rs".to_string(),
+            source_line: 2,
+        },
+        cruxe_core::types::CallEdge {
+            repo: project_id.to_string(),
+            ref_name: "main".to_string(),
+            from_symbol_id: "stable::a".to_string(),
+            to_symbol_id: Some("stable::c".to_string()),
+            to_name: None,
+            edge_type: "calls".to_string(),
+            confidence: "static".to_string(),
+            source_file: "src/lib.rs".to_string(),
+            source_line: 3,
+        },
+    ];
+    cruxe_state::edges::insert_call_edges(&conn, project_id, "main", &edges).unwrap();
 
+    let config = Config::default();
     let request = make_request(
         "tools/call",
         json!({
-            "name": "health_check",
-            "arguments": {}
+            "name": "get_call_graph",
+            "arguments": {
+                "symbol_name": "a",
+                "path": "src/lib.rs",
+                "direction": "callees",
+                "depth": 1,
+                "limit": 1
+            }
         }),
     );
-
     let response = handle_request_with_ctx(
         &request,
         &RequestContext {
             config: &config,
-            index_set: Some(&index_set),
+            index_set: None,
             schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
             conn: Some(&conn),
-            workspace,
+            workspace: workspace_dir.as_path(),
             project_id,
-            prewarm_status: &pw,
+            prewarm_status: &test_prewarm_status(),
             server_start: &test_server_start(),
             notifier: Arc::new(NullProgressNotifier),
             progress_token: None,
         },
     );
 
-    assert!(response.error.is_none());
-    let result = response.result.as_ref().unwrap();
-    let content = result.get("content").unwrap().as_array().unwrap();
-    let text = content[0].get("text").unwrap().as_str().unwrap();
-    let payload: serde_json::Value = serde_json::from_str(text).unwrap();
-
+    assert!(response.error.is_none(), "expected success");
+    let payload = extract_payload_from_response(&response);
     assert_eq!(
-        payload.get("status").unwrap().as_str().unwrap(),
-        "error",
-        "expected status 'error' when prewarm fails"
+        payload.get("truncated").and_then(|value| value.as_bool()),
+        Some(true)
     );
     assert_eq!(
-        payload.get("prewarm_status").unwrap().as_str().unwrap(),
-        "failed"
+        payload
+            .get("metadata")
+            .and_then(|value| value.get("result_completeness"))
+            .and_then(|value| value.as_str()),
+        Some("truncated")
     );
 }
 
 #[test]
-fn t119_health_check_not_indexed_registered_project_sets_error() {
+fn t191_get_code_context_negative_max_tokens_returns_invalid_max_tokens() {
     let tmp = tempfile::tempdir().unwrap();
     let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
     let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    let config = Config::default();
     let workspace = Path::new("/tmp/fake-workspace");
-    let current_project_id = "test-repo";
-    let missing_project_id = "missing-proj-never-indexed";
-
-    let current = Project {
-        project_id: current_project_id.to_string(),
-        repo_root: workspace.to_string_lossy().to_string(),
-        display_name: Some("current".to_string()),
-        default_ref: "live".to_string(),
-        vcs_mode: false,
-        schema_version: cruxe_core::constants::SCHEMA_VERSION,
-        parser_version: cruxe_core::constants::PARSER_VERSION,
-        created_at: "2026-01-01T00:00:00Z".to_string(),
-        updated_at: "2026-01-01T00:00:00Z".to_string(),
-    };
-    cruxe_state::project::create_project(&conn, &current).unwrap();
-
-    let missing = Project {
-        project_id: missing_project_id.to_string(),
-        repo_root: "/tmp/missing-workspace".to_string(),
-        display_name: Some("missing".to_string()),
-        default_ref: "live".to_string(),
-        vcs_mode: false,
-        schema_version: cruxe_core::constants::SCHEMA_VERSION,
-        parser_version: cruxe_core::constants::PARSER_VERSION,
-        created_at: "2026-01-01T00:00:00Z".to_string(),
-        updated_at: "2026-01-01T00:00:00Z".to_string(),
-    };
-    cruxe_state::project::create_project(&conn, &missing).unwrap();
-
-    let mut config = Config::default();
-    config.storage.data_dir = tmp.path().join("health-data").to_string_lossy().to_string();
+    let project_id = "test-repo";
 
     let request = make_request(
         "tools/call",
         json!({
-            "name": "health_check",
-            "arguments": {}
+            "name": "get_code_context",
+            "arguments": {
+                "query": "validate_token",
+                "max_tokens": -1
+            }
         }),
     );
     let response = handle_request_with_ctx(
@@ -3714,7 +5154,7 @@ fn t119_health_check_not_indexed_registered_project_sets_error() {
             compatibility_reason: None,
             conn: Some(&conn),
             workspace,
-            project_id: current_project_id,
+            project_id,
             prewarm_status: &test_prewarm_status(),
             server_start: &test_server_start(),
             notifier: Arc::new(NullProgressNotifier),
@@ -3722,127 +5162,163 @@ fn t119_health_check_not_indexed_registered_project_sets_error() {
         },
     );
 
-    assert!(response.error.is_none(), "expected success");
-    let result = response.result.as_ref().unwrap();
-    let content = result.get("content").unwrap().as_array().unwrap();
-    let text = content[0].get("text").unwrap().as_str().unwrap();
-    let payload: serde_json::Value = serde_json::from_str(text).unwrap();
-
+    assert!(response.error.is_none(), "MCP tool errors are in content");
+    let payload = extract_payload_from_response(&response);
+    let error = payload
+        .get("error")
+        .expect("invalid max_tokens response should contain error");
     assert_eq!(
-        payload.get("status").unwrap().as_str().unwrap(),
-        "error",
-        "overall status should be error when any registered project is not indexed"
+        error.get("code").and_then(|v| v.as_str()).unwrap(),
+        "invalid_max_tokens"
     );
-    let projects = payload.get("projects").unwrap().as_array().unwrap();
-    let missing_status = projects
-        .iter()
-        .find(|p| p.get("project_id").and_then(|v| v.as_str()) == Some(missing_project_id))
-        .and_then(|p| p.get("index_status"))
-        .and_then(|v| v.as_str());
-    assert_eq!(missing_status, Some("error"));
 }
 
-/// T122: search_code with debug ranking_reasons enabled returns per-result explanations
 #[test]
-fn t122_search_code_ranking_reasons_enabled() {
+fn t194_symbol_hierarchy_validation_precision() {
     let tmp = tempfile::tempdir().unwrap();
-    let index_set = build_fixture_index(tmp.path());
-
-    let mut config = Config::default();
-    config.debug.ranking_reasons = true;
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    let config = Config::default();
     let workspace = Path::new("/tmp/fake-workspace");
     let project_id = "test-repo";
 
-    let request = make_request(
-        "tools/call",
-        json!({
-            "name": "search_code",
-            "arguments": {
-                "query": "validate"
-            }
-        }),
-    );
+    let cases = vec![
+        ("validate_token", "src/auth.rs"),
+        ("require_role", "src/auth.rs"),
+        ("is_expired", "src/auth.rs"),
+        ("issuer", "src/auth.rs"),
+        ("handle_request", "src/handler.rs"),
+        ("authenticate", "src/handler.rs"),
+        ("get_user", "src/handler.rs"),
+        ("create_user", "src/handler.rs"),
+        ("new", "src/lib.rs"),
+        ("health_check", "src/lib.rs"),
+    ];
 
-    let response = handle_request_with_ctx(
-        &request,
-        &RequestContext {
-            config: &config,
-            index_set: Some(&index_set),
-            schema_status: SchemaStatus::Compatible,
-            compatibility_reason: None,
-            conn: None,
-            workspace,
-            project_id,
-            prewarm_status: &test_prewarm_status(),
-            server_start: &test_server_start(),
-            notifier: Arc::new(NullProgressNotifier),
-            progress_token: None,
-        },
-    );
-
-    assert!(response.error.is_none(), "expected success");
-    let result = response.result.as_ref().unwrap();
-    let content = result.get("content").unwrap().as_array().unwrap();
-    let text = content[0].get("text").unwrap().as_str().unwrap();
-    let payload: serde_json::Value = serde_json::from_str(text).unwrap();
-
-    // ranking_reasons should be present in metadata
-    let meta = payload.get("metadata").unwrap();
-    let reasons = meta
-        .get("ranking_reasons")
-        .expect("ranking_reasons should be present in metadata when debug is enabled");
-    let reasons_array = reasons.as_array().unwrap();
+    let mut correct = 0usize;
+    for (symbol_name, path) in &cases {
+        let request = make_request(
+            "tools/call",
+            json!({
+                "name": "get_symbol_hierarchy",
+                "arguments": {
+                    "symbol_name": symbol_name,
+                    "path": path,
+                    "direction": "ancestors"
+                }
+            }),
+        );
+        let response = handle_request_with_ctx(
+            &request,
+            &RequestContext {
+                config: &config,
+                index_set: Some(&index_set),
+                schema_status: SchemaStatus::Compatible,
+                compatibility_reason: None,
+                conn: Some(&conn),
+                workspace,
+                project_id,
+                prewarm_status: &test_prewarm_status(),
+                server_start: &test_server_start(),
+                notifier: Arc::new(NullProgressNotifier),
+                progress_token: None,
+            },
+        );
+        if response.error.is_some() {
+            continue;
+        }
+        let payload = extract_payload_from_response(&response);
+        let first_name = payload
+            .get("hierarchy")
+            .and_then(|v| v.as_array())
+            .and_then(|arr| arr.first())
+            .and_then(|v| v.get("name"))
+            .and_then(|v| v.as_str());
+        if first_name == Some(*symbol_name) {
+            correct += 1;
+        }
+    }
 
-    // Should have one entry per result
-    let results = payload.get("results").unwrap().as_array().unwrap();
-    assert_eq!(
-        reasons_array.len(),
-        results.len(),
-        "ranking_reasons should have one entry per result"
+    let precision = correct as f64 / cases.len() as f64;
+    assert!(
+        precision >= 0.95,
+        "hierarchy precision should be >= 95%, got {:.1}% ({}/{})",
+        precision * 100.0,
+        correct,
+        cases.len()
     );
+}
 
-    // Each reason should have all 7 fields
-    if let Some(first) = reasons_array.first() {
-        assert!(first.get("result_index").is_some(), "missing result_index");
-        assert!(
-            first.get("exact_match_boost").is_some(),
-            "missing exact_match_boost"
-        );
-        assert!(
-            first.get("qualified_name_boost").is_some(),
-            "missing qualified_name_boost"
+#[test]
+fn t195_code_context_budget_validation() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+    let project_id = "test-repo";
+
+    let budgets = [50_u64, 75, 100, 150, 200, 300, 500, 800, 1200, 2000];
+    for budget in budgets {
+        let request = make_request(
+            "tools/call",
+            json!({
+                "name": "get_code_context",
+                "arguments": {
+                    "query": "validate_token",
+                    "max_tokens": budget,
+                    "strategy": "breadth",
+                    "language": "rust"
+                }
+            }),
         );
-        assert!(
-            first.get("path_affinity").is_some(),
-            "missing path_affinity"
+        let response = handle_request_with_ctx(
+            &request,
+            &RequestContext {
+                config: &config,
+                index_set: Some(&index_set),
+                schema_status: SchemaStatus::Compatible,
+                compatibility_reason: None,
+                conn: Some(&conn),
+                workspace,
+                project_id,
+                prewarm_status: &test_prewarm_status(),
+                server_start: &test_server_start(),
+                notifier: Arc::new(NullProgressNotifier),
+                progress_token: None,
+            },
         );
+        assert!(response.error.is_none(), "context request should succeed");
+        let payload = extract_payload_from_response(&response);
+        let estimated = payload
+            .get("estimated_tokens")
+            .and_then(|v| v.as_u64())
+            .expect("estimated_tokens should be present");
         assert!(
-            first.get("definition_boost").is_some(),
-            "missing definition_boost"
+            estimated <= budget,
+            "estimated_tokens={} should not exceed budget={}",
+            estimated,
+            budget
         );
-        assert!(first.get("kind_match").is_some(), "missing kind_match");
-        assert!(first.get("bm25_score").is_some(), "missing bm25_score");
-        assert!(first.get("final_score").is_some(), "missing final_score");
     }
 }
 
-/// T123: search_code with debug ranking_reasons disabled (default) omits ranking_reasons
+/// T111: health_check on a healthy system returns ready status
 #[test]
-fn t123_search_code_ranking_reasons_disabled() {
+fn t111_health_check_on_healthy_system() {
     let tmp = tempfile::tempdir().unwrap();
-    let index_set = build_fixture_index(tmp.path());
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
 
-    let config = Config::default(); // ranking_reasons defaults to false
+    let config = Config::default();
     let workspace = Path::new("/tmp/fake-workspace");
     let project_id = "test-repo";
 
     let request = make_request(
         "tools/call",
         json!({
-            "name": "search_code",
-            "arguments": {
-                "query": "validate"
-            }
+            "name": "health_check",
+            "arguments": {}
         }),
     );
 
@@ -3853,7 +5329,7 @@ fn t123_search_code_ranking_reasons_disabled() {
             index_set: Some(&index_set),
             schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
-            conn: None,
+            conn: Some(&conn),
             workspace,
             project_id,
             prewarm_status: &test_prewarm_status(),
@@ -3863,108 +5339,146 @@ fn t123_search_code_ranking_reasons_disabled() {
         },
     );
 
-    assert!(response.error.is_none(), "expected success");
+    assert!(response.error.is_none(), "expected success, got error");
     let result = response.result.as_ref().unwrap();
     let content = result.get("content").unwrap().as_array().unwrap();
     let text = content[0].get("text").unwrap().as_str().unwrap();
     let payload: serde_json::Value = serde_json::from_str(text).unwrap();
 
-    // ranking_reasons should be absent from metadata
-    let meta = payload.get("metadata").unwrap();
-    assert!(
-        meta.get("ranking_reasons").is_none(),
-        "ranking_reasons should be absent when debug is disabled"
+    // Status should be "ready"
+    assert_eq!(
+        payload.get("status").unwrap().as_str().unwrap(),
+        "ready",
+        "expected status 'ready'"
     );
-}
-
-/// T124: search_code with ranking_explain_level=basic returns compact factors
-#[test]
-fn t124_search_code_ranking_reasons_basic_mode() {
-    let tmp = tempfile::tempdir().unwrap();
-    let index_set = build_fixture_index(tmp.path());
-
-    let config = Config::default();
-    let workspace = Path::new("/tmp/fake-workspace");
-    let project_id = "test-repo";
 
-    let request = make_request(
-        "tools/call",
-        json!({
-            "name": "search_code",
-            "arguments": {
-                "query": "validate",
-                "ranking_explain_level": "basic"
-            }
-        }),
+    // Tantivy should be ok
+    assert!(
+        payload.get("tantivy_ok").unwrap().as_bool().unwrap(),
+        "expected tantivy_ok: true"
     );
 
-    let response = handle_request_with_ctx(
-        &request,
-        &RequestContext {
-            config: &config,
-            index_set: Some(&index_set),
-            schema_status: SchemaStatus::Compatible,
-            compatibility_reason: None,
-            conn: None,
-            workspace,
-            project_id,
-            prewarm_status: &test_prewarm_status(),
-            server_start: &test_server_start(),
-            notifier: Arc::new(NullProgressNotifier),
-            progress_token: None,
-        },
+    // SQLite should be ok
+    assert!(
+        payload.get("sqlite_ok").unwrap().as_bool().unwrap(),
+        "expected sqlite_ok: true"
     );
 
-    assert!(response.error.is_none(), "expected success");
-    let payload = extract_payload_from_response(&response);
-    let meta = payload.get("metadata").unwrap();
-    let reasons = meta
-        .get("ranking_reasons")
-        .expect("ranking_reasons should be present for basic mode")
-        .as_array()
-        .unwrap();
-    assert!(!reasons.is_empty(), "expected non-empty ranking_reasons");
-    let first = reasons.first().unwrap();
-    assert!(first.get("exact_match").is_some());
-    assert!(first.get("path_boost").is_some());
-    assert!(first.get("definition_boost").is_some());
-    assert!(first.get("semantic_similarity").is_some());
-    assert!(first.get("final_score").is_some());
+    // Grammars: all 4 should be available
+    let grammars = payload.get("grammars").unwrap();
+    let available = grammars.get("available").unwrap().as_array().unwrap();
     assert!(
-        first.get("exact_match_boost").is_none(),
-        "basic mode must omit full explainability fields"
+        available.len() >= 4,
+        "expected at least 4 grammars available, got {}",
+        available.len()
+    );
+    let missing = grammars.get("missing").unwrap().as_array().unwrap();
+    assert!(
+        missing.is_empty(),
+        "expected no missing grammars, got {:?}",
+        missing
     );
-}
 
-/// T124b: full explain mode includes raw/clamped/effective signal accounting and precedence audit.
-#[test]
-fn t124b_search_code_ranking_reasons_full_mode_includes_budget_fields() {
-    let tmp = tempfile::tempdir().unwrap();
-    let index_set = build_fixture_index(tmp.path());
+    // Startup checks
+    let startup = payload.get("startup_checks").unwrap();
+    let index_check = startup.get("index").unwrap();
+    assert_eq!(
+        index_check.get("status").unwrap().as_str().unwrap(),
+        "compatible"
+    );
 
-    let config = Config::default();
+    // Protocol version in metadata
+    let meta = payload.get("metadata").unwrap();
+    assert!(meta.get("cruxe_protocol_version").is_some());
+
+    // Prewarm status should be present
+    assert!(payload.get("prewarm_status").is_some());
+    // Warmset metadata should be present
+    let warmset = payload.get("workspace_warmset").unwrap();
+    assert!(warmset.get("enabled").is_some());
+    assert!(warmset.get("capacity").is_some());
+    assert!(warmset.get("members").is_some());
+
+    // Per-project schema compatibility fields should be present
+    let projects = payload.get("projects").unwrap().as_array().unwrap();
+    assert!(!projects.is_empty());
+    let proj = &projects[0];
+    assert!(proj.get("schema_status").is_some());
+    assert!(proj.get("current_schema_version").is_some());
+    assert!(proj.get("required_schema_version").is_some());
+    assert_eq!(
+        proj.get("max_symbols_per_ref").and_then(|v| v.as_u64()),
+        Some(cruxe_core::constants::MAX_SYMBOLS_PER_REF)
+    );
+    assert!(proj.get("symbol_cap_exceeded").is_none());
+}
+
+#[test]
+fn t111_health_check_active_job_sets_indexing_status() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
     let workspace = Path::new("/tmp/fake-workspace");
     let project_id = "test-repo";
 
+    let project = Project {
+        project_id: project_id.to_string(),
+        repo_root: workspace.to_string_lossy().to_string(),
+        display_name: Some("test".to_string()),
+        default_ref: "live".to_string(),
+        vcs_mode: false,
+        schema_version: cruxe_core::constants::SCHEMA_VERSION,
+        parser_version: cruxe_core::constants::PARSER_VERSION,
+        created_at: "2026-01-01T00:00:00Z".to_string(),
+        updated_at: "2026-01-01T00:00:00Z".to_string(),
+    };
+    cruxe_state::project::create_project(&conn, &project).unwrap();
+
+    cruxe_state::jobs::create_job(
+        &conn,
+        &cruxe_state::jobs::IndexJob {
+            job_id: "job_active".to_string(),
+            project_id: project_id.to_string(),
+            r#ref: "live".to_string(),
+            mode: "incremental".to_string(),
+            head_commit: Some("abc123".to_string()),
+            sync_id: None,
+            status: "running".to_string(),
+            changed_files: 1,
+            duration_ms: None,
+            error_message: None,
+            retry_count: 0,
+            progress_token: None,
+            files_scanned: 0,
+            files_indexed: 0,
+            symbols_extracted: 0,
+            files_skipped: 0,
+            files_unchanged: 0,
+            files_excluded: 0,
+            files_per_second: None,
+            index_commits: 0,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+            error_data: None,
+        },
+    )
+    .unwrap();
+
     let request = make_request(
         "tools/call",
         json!({
-            "name": "search_code",
-            "arguments": {
-                "query": "validate",
-                "ranking_explain_level": "full"
-            }
+            "name": "health_check",
+            "arguments": {}
         }),
     );
-
     let response = handle_request_with_ctx(
         &request,
         &RequestContext {
-            config: &config,
+            config: &Config::default(),
             index_set: Some(&index_set),
             schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
-            conn: None,
+            conn: Some(&conn),
             workspace,
             project_id,
             prewarm_status: &test_prewarm_status(),
@@ -3975,76 +5489,89 @@ fn t124b_search_code_ranking_reasons_full_mode_includes_budget_fields() {
     );
 
     assert!(response.error.is_none(), "expected success");
-    let payload = extract_payload_from_response(&response);
-    let meta = payload.get("metadata").unwrap();
-    let reasons = meta
-        .get("ranking_reasons")
-        .expect("ranking_reasons should be present for full mode")
-        .as_array()
-        .unwrap();
-    assert!(!reasons.is_empty(), "expected non-empty ranking_reasons");
-    let first = reasons.first().unwrap();
-    // Backward-compatible legacy keys remain available.
-    assert!(first.get("exact_match_boost").is_some());
-    assert!(first.get("qualified_name_boost").is_some());
-    assert!(first.get("bm25_score").is_some());
-    assert!(first.get("final_score").is_some());
-    // Budgeted explainability additions.
-    let contributions = first
-        .get("signal_contributions")
-        .and_then(|v| v.as_array())
-        .expect("full mode should include signal_contributions");
+    let result = response.result.as_ref().unwrap();
+    let content = result.get("content").unwrap().as_array().unwrap();
+    let text = content[0].get("text").unwrap().as_str().unwrap();
+    let payload: serde_json::Value = serde_json::from_str(text).unwrap();
+
+    assert_eq!(payload.get("status").unwrap().as_str().unwrap(), "indexing");
     assert!(
-        contributions.len() >= 3,
-        "expected multiple signal contribution entries"
+        payload.get("active_job").is_some(),
+        "active_job should be present when an indexing job is running"
     );
-    let first_signal = contributions.first().unwrap();
-    assert!(first_signal.get("signal").is_some());
-    assert!(first_signal.get("raw_value").is_some());
-    assert!(first_signal.get("clamped_value").is_some());
-    assert!(first_signal.get("effective_value").is_some());
-    let precedence = first
-        .get("precedence_audit")
-        .expect("full mode should include precedence audit");
-    assert!(precedence.get("lexical_dominance_applied").is_some());
-    assert!(precedence.get("exact_match_present").is_some());
-    assert!(precedence.get("secondary_effective_total").is_some());
-    assert!(precedence.get("secondary_effective_cap").is_some());
 }
 
 #[test]
-fn t403_search_code_exposes_semantic_and_confidence_metadata() {
+fn t111_health_check_warming_has_priority_over_indexing() {
     let tmp = tempfile::tempdir().unwrap();
-    let index_set = build_fixture_index(tmp.path());
-
-    let mut config = Config::default();
-    config.search.semantic.mode = "rerank_only".to_string();
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
     let workspace = Path::new("/tmp/fake-workspace");
     let project_id = "test-repo";
 
+    let project = Project {
+        project_id: project_id.to_string(),
+        repo_root: workspace.to_string_lossy().to_string(),
+        display_name: Some("test".to_string()),
+        default_ref: "live".to_string(),
+        vcs_mode: false,
+        schema_version: cruxe_core::constants::SCHEMA_VERSION,
+        parser_version: cruxe_core::constants::PARSER_VERSION,
+        created_at: "2026-01-01T00:00:00Z".to_string(),
+        updated_at: "2026-01-01T00:00:00Z".to_string(),
+    };
+    cruxe_state::project::create_project(&conn, &project).unwrap();
+
+    cruxe_state::jobs::create_job(
+        &conn,
+        &cruxe_state::jobs::IndexJob {
+            job_id: "job_active".to_string(),
+            project_id: project_id.to_string(),
+            r#ref: "live".to_string(),
+            mode: "incremental".to_string(),
+            head_commit: Some("abc123".to_string()),
+            sync_id: None,
+            status: "running".to_string(),
+            changed_files: 1,
+            duration_ms: None,
+            error_message: None,
+            retry_count: 0,
+            progress_token: None,
+            files_scanned: 0,
+            files_indexed: 0,
+            symbols_extracted: 0,
+            files_skipped: 0,
+            files_unchanged: 0,
+            files_excluded: 0,
+            files_per_second: None,
+            index_commits: 0,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+            error_data: None,
+        },
+    )
+    .unwrap();
+
+    let pw = AtomicU8::new(PREWARM_IN_PROGRESS);
     let request = make_request(
         "tools/call",
         json!({
-            "name": "search_code",
-            "arguments": {
-                "query": "where is validate token",
-                "semantic_ratio": 0.8,
-                "confidence_threshold": 0.2
-            }
+            "name": "health_check",
+            "arguments": {}
         }),
     );
 
     let response = handle_request_with_ctx(
         &request,
         &RequestContext {
-            config: &config,
+            config: &Config::default(),
             index_set: Some(&index_set),
             schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
-            conn: None,
+            conn: Some(&conn),
             workspace,
             project_id,
-            prewarm_status: &test_prewarm_status(),
+            prewarm_status: &pw,
             server_start: &test_server_start(),
             notifier: Arc::new(NullProgressNotifier),
             progress_token: None,
@@ -4052,53 +5579,37 @@ fn t403_search_code_exposes_semantic_and_confidence_metadata() {
     );
 
     assert!(response.error.is_none(), "expected success");
-    let payload = extract_payload_from_response(&response);
-    let meta = payload.get("metadata").expect("metadata should be present");
-    assert_eq!(
-        meta.get("semantic_mode").and_then(|v| v.as_str()),
-        Some("rerank_only")
-    );
-    assert_eq!(
-        meta.get("semantic_enabled").and_then(|v| v.as_bool()),
-        Some(true)
-    );
-    assert!(meta.get("semantic_triggered").is_some());
-    assert_eq!(
-        meta.get("confidence_threshold").and_then(|v| v.as_f64()),
-        Some(0.2)
-    );
-    assert!(meta.get("query_intent_confidence").is_some());
-    assert!(meta.get("low_confidence").is_some());
-    assert!(meta.get("policy_mode").is_some());
-    assert!(meta.get("policy_blocked_count").is_some());
-    assert!(meta.get("policy_redacted_count").is_some());
+    let result = response.result.as_ref().unwrap();
+    let content = result.get("content").unwrap().as_array().unwrap();
+    let text = content[0].get("text").unwrap().as_str().unwrap();
+    let payload: serde_json::Value = serde_json::from_str(text).unwrap();
 
-    let first = payload["results"]
-        .as_array()
-        .and_then(|arr| arr.first())
-        .expect("expected at least one search result");
     assert_eq!(
-        first.get("provenance").and_then(|v| v.as_str()),
-        Some("lexical")
+        payload.get("status").unwrap().as_str().unwrap(),
+        "warming",
+        "warming should take priority over indexing when both are present"
     );
 }
 
+/// T116: health_check returns "warming" when prewarm is in progress
 #[test]
-fn t470_search_code_exposes_adaptive_plan_metadata_fields() {
+fn t116_health_check_warming_status_during_prewarm() {
     let tmp = tempfile::tempdir().unwrap();
-    let index_set = build_fixture_index(tmp.path());
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+
     let config = Config::default();
     let workspace = Path::new("/tmp/fake-workspace");
     let project_id = "test-repo";
 
+    // Simulate prewarm in progress
+    let pw = AtomicU8::new(PREWARM_IN_PROGRESS);
+
     let request = make_request(
         "tools/call",
         json!({
-            "name": "search_code",
-            "arguments": {
-                "query": "validate_token",
-                "plan": "lexical_fast"
-            }
+            "name": "health_check",
+            "arguments": {}
         }),
     );
 
@@ -4109,65 +5620,89 @@ fn t470_search_code_exposes_adaptive_plan_metadata_fields() {
             index_set: Some(&index_set),
             schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
-            conn: None,
+            conn: Some(&conn),
             workspace,
             project_id,
-            prewarm_status: &test_prewarm_status(),
+            prewarm_status: &pw,
             server_start: &test_server_start(),
             notifier: Arc::new(NullProgressNotifier),
             progress_token: None,
         },
     );
 
-    assert!(response.error.is_none(), "expected success");
-    let payload = extract_payload_from_response(&response);
-    let meta = payload.get("metadata").expect("metadata should be present");
+    assert!(response.error.is_none());
+    let result = response.result.as_ref().unwrap();
+    let content = result.get("content").unwrap().as_array().unwrap();
+    let text = content[0].get("text").unwrap().as_str().unwrap();
+    let payload: serde_json::Value = serde_json::from_str(text).unwrap();
 
+    // Status should be "warming" while prewarm is in progress
     assert_eq!(
-        meta.get("query_plan_selected").and_then(|v| v.as_str()),
-        Some("lexical_fast")
+        payload.get("status").unwrap().as_str().unwrap(),
+        "warming",
+        "expected status 'warming' during prewarm"
     );
     assert_eq!(
-        meta.get("query_plan_executed").and_then(|v| v.as_str()),
-        Some("lexical_fast")
+        payload.get("prewarm_status").unwrap().as_str().unwrap(),
+        "warming"
     );
-    assert_eq!(
-        meta.get("query_plan_selection_reason")
-            .and_then(|v| v.as_str()),
-        Some("override")
+
+    // Now simulate prewarm complete
+    pw.store(PREWARM_COMPLETE, Ordering::Release);
+
+    let response2 = handle_request_with_ctx(
+        &request,
+        &RequestContext {
+            config: &config,
+            index_set: Some(&index_set),
+            schema_status: SchemaStatus::Compatible,
+            compatibility_reason: None,
+            conn: Some(&conn),
+            workspace,
+            project_id,
+            prewarm_status: &pw,
+            server_start: &test_server_start(),
+            notifier: Arc::new(NullProgressNotifier),
+            progress_token: None,
+        },
     );
+
+    let result2 = response2.result.as_ref().unwrap();
+    let content2 = result2.get("content").unwrap().as_array().unwrap();
+    let text2 = content2[0].get("text").unwrap().as_str().unwrap();
+    let payload2: serde_json::Value = serde_json::from_str(text2).unwrap();
+
+    // Status should now be "ready"
     assert_eq!(
-        meta.get("query_plan_downgraded").and_then(|v| v.as_bool()),
-        Some(false)
+        payload2.get("status").unwrap().as_str().unwrap(),
+        "ready",
+        "expected status 'ready' after prewarm completes"
     );
-    let budget = meta
-        .get("query_plan_budget_used")
-        .and_then(|v| v.as_object())
-        .expect("query_plan_budget_used should be an object");
-    assert!(
-        budget
-            .get("latency_budget_ms")
-            .and_then(|v| v.as_u64())
-            .is_some(),
-        "latency_budget_ms should be included in query_plan_budget_used"
+    assert_eq!(
+        payload2.get("prewarm_status").unwrap().as_str().unwrap(),
+        "complete"
     );
 }
 
+/// T117: health_check returns "ready" immediately with --no-prewarm (skipped)
 #[test]
-fn t471_locate_symbol_omits_adaptive_plan_metadata_fields() {
+fn t117_health_check_no_prewarm_skipped() {
     let tmp = tempfile::tempdir().unwrap();
-    let index_set = build_fixture_index(tmp.path());
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+
     let config = Config::default();
     let workspace = Path::new("/tmp/fake-workspace");
     let project_id = "test-repo";
 
+    // Simulate --no-prewarm: status is SKIPPED
+    let pw = AtomicU8::new(PREWARM_SKIPPED);
+
     let request = make_request(
         "tools/call",
         json!({
-            "name": "locate_symbol",
-            "arguments": {
-                "name": "validate_token"
-            }
+            "name": "health_check",
+            "arguments": {}
         }),
     );
 
@@ -4178,54 +5713,50 @@ fn t471_locate_symbol_omits_adaptive_plan_metadata_fields() {
             index_set: Some(&index_set),
             schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
-            conn: None,
+            conn: Some(&conn),
             workspace,
             project_id,
-            prewarm_status: &test_prewarm_status(),
+            prewarm_status: &pw,
             server_start: &test_server_start(),
             notifier: Arc::new(NullProgressNotifier),
             progress_token: None,
         },
     );
 
-    assert!(response.error.is_none(), "expected success");
-    let payload = extract_payload_from_response(&response);
-    let meta = payload.get("metadata").expect("metadata should be present");
+    assert!(response.error.is_none());
+    let result = response.result.as_ref().unwrap();
+    let content = result.get("content").unwrap().as_array().unwrap();
+    let text = content[0].get("text").unwrap().as_str().unwrap();
+    let payload: serde_json::Value = serde_json::from_str(text).unwrap();
 
-    assert!(
-        meta.get("query_plan_selected").is_none(),
-        "locate_symbol metadata should omit adaptive plan fields"
-    );
-    assert!(
-        meta.get("query_plan_selection_reason").is_none(),
-        "locate_symbol metadata should omit adaptive plan fields"
-    );
-    assert!(
-        meta.get("query_plan_executed").is_none(),
-        "locate_symbol metadata should omit adaptive plan fields"
+    // Status should be "ready" immediately (not "warming")
+    assert_eq!(
+        payload.get("status").unwrap().as_str().unwrap(),
+        "ready",
+        "expected status 'ready' with --no-prewarm"
     );
-    assert!(
-        meta.get("query_plan_budget_used").is_none(),
-        "locate_symbol metadata should omit adaptive plan fields"
+    assert_eq!(
+        payload.get("prewarm_status").unwrap().as_str().unwrap(),
+        "skipped"
     );
 }
 
 #[test]
-fn t403_search_code_invalid_semantic_ratio_returns_invalid_input() {
+fn t118_health_check_prewarm_failed_reports_error() {
     let tmp = tempfile::tempdir().unwrap();
-    let index_set = build_fixture_index(tmp.path());
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+
     let config = Config::default();
     let workspace = Path::new("/tmp/fake-workspace");
     let project_id = "test-repo";
+    let pw = AtomicU8::new(PREWARM_FAILED);
 
     let request = make_request(
         "tools/call",
         json!({
-            "name": "search_code",
-            "arguments": {
-                "query": "validate",
-                "semantic_ratio": 1.5
-            }
+            "name": "health_check",
+            "arguments": {}
         }),
     );
 
@@ -4236,57 +5767,78 @@ fn t403_search_code_invalid_semantic_ratio_returns_invalid_input() {
             index_set: Some(&index_set),
             schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
-            conn: None,
+            conn: Some(&conn),
             workspace,
             project_id,
-            prewarm_status: &test_prewarm_status(),
+            prewarm_status: &pw,
             server_start: &test_server_start(),
             notifier: Arc::new(NullProgressNotifier),
             progress_token: None,
         },
     );
 
-    assert!(
-        response.error.is_none(),
-        "tool call should return content envelope"
-    );
-    let payload = extract_payload_from_response(&response);
+    assert!(response.error.is_none());
+    let result = response.result.as_ref().unwrap();
+    let content = result.get("content").unwrap().as_array().unwrap();
+    let text = content[0].get("text").unwrap().as_str().unwrap();
+    let payload: serde_json::Value = serde_json::from_str(text).unwrap();
+
     assert_eq!(
-        payload
-            .get("error")
-            .and_then(|v| v.get("code"))
-            .and_then(|v| v.as_str()),
-        Some("invalid_input")
+        payload.get("status").unwrap().as_str().unwrap(),
+        "error",
+        "expected status 'error' when prewarm fails"
     );
-    assert!(
-        payload
-            .get("error")
-            .and_then(|v| v.get("message"))
-            .and_then(|v| v.as_str())
-            .unwrap_or_default()
-            .contains("semantic_ratio")
+    assert_eq!(
+        payload.get("prewarm_status").unwrap().as_str().unwrap(),
+        "failed"
     );
 }
 
 #[test]
-fn t404_search_code_invalid_confidence_threshold_returns_invalid_input_envelope() {
+fn t119_health_check_not_indexed_registered_project_sets_error() {
     let tmp = tempfile::tempdir().unwrap();
-    let index_set = build_fixture_index(tmp.path());
-    let config = Config::default();
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
     let workspace = Path::new("/tmp/fake-workspace");
-    let project_id = "test-repo";
+    let current_project_id = "test-repo";
+    let missing_project_id = "missing-proj-never-indexed";
+
+    let current = Project {
+        project_id: current_project_id.to_string(),
+        repo_root: workspace.to_string_lossy().to_string(),
+        display_name: Some("current".to_string()),
+        default_ref: "live".to_string(),
+        vcs_mode: false,
+        schema_version: cruxe_core::constants::SCHEMA_VERSION,
+        parser_version: cruxe_core::constants::PARSER_VERSION,
+        created_at: "2026-01-01T00:00:00Z".to_string(),
+        updated_at: "2026-01-01T00:00:00Z".to_string(),
+    };
+    cruxe_state::project::create_project(&conn, &current).unwrap();
+
+    let missing = Project {
+        project_id: missing_project_id.to_string(),
+        repo_root: "/tmp/missing-workspace".to_string(),
+        display_name: Some("missing".to_string()),
+        default_ref: "live".to_string(),
+        vcs_mode: false,
+        schema_version: cruxe_core::constants::SCHEMA_VERSION,
+        parser_version: cruxe_core::constants::PARSER_VERSION,
+        created_at: "2026-01-01T00:00:00Z".to_string(),
+        updated_at: "2026-01-01T00:00:00Z".to_string(),
+    };
+    cruxe_state::project::create_project(&conn, &missing).unwrap();
+
+    let mut config = Config::default();
+    config.storage.data_dir = tmp.path().join("health-data").to_string_lossy().to_string();
 
     let request = make_request(
         "tools/call",
         json!({
-            "name": "search_code",
-            "arguments": {
-                "query": "validate",
-                "confidence_threshold": -0.1
-            }
+            "name": "health_check",
+            "arguments": {}
         }),
     );
-
     let response = handle_request_with_ctx(
         &request,
         &RequestContext {
@@ -4294,9 +5846,9 @@ fn t404_search_code_invalid_confidence_threshold_returns_invalid_input_envelope(
             index_set: Some(&index_set),
             schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
-            conn: None,
+            conn: Some(&conn),
             workspace,
-            project_id,
+            project_id: current_project_id,
             prewarm_status: &test_prewarm_status(),
             server_start: &test_server_start(),
             notifier: Arc::new(NullProgressNotifier),
@@ -4304,51 +5856,34 @@ fn t404_search_code_invalid_confidence_threshold_returns_invalid_input_envelope(
         },
     );
 
-    assert!(
-        response.error.is_none(),
-        "tool call should return canonical content envelope"
-    );
-    let payload = extract_payload_from_response(&response);
-    assert_eq!(
-        payload
-            .get("error")
-            .and_then(|v| v.get("code"))
-            .and_then(|v| v.as_str()),
-        Some("invalid_input")
-    );
-    assert!(
-        payload
-            .get("error")
-            .and_then(|v| v.get("message"))
-            .and_then(|v| v.as_str())
-            .unwrap_or_default()
-            .contains("confidence_threshold")
-    );
+    assert!(response.error.is_none(), "expected success");
+    let result = response.result.as_ref().unwrap();
+    let content = result.get("content").unwrap().as_array().unwrap();
+    let text = content[0].get("text").unwrap().as_str().unwrap();
+    let payload: serde_json::Value = serde_json::from_str(text).unwrap();
 
-    let metadata = payload.get("metadata").expect("metadata should be present");
-    assert_eq!(
-        metadata.get("schema_status").and_then(|v| v.as_str()),
-        Some("compatible")
-    );
-    assert_eq!(
-        metadata.get("indexing_status").and_then(|v| v.as_str()),
-        Some("ready")
-    );
     assert_eq!(
-        metadata
-            .get("cruxe_protocol_version")
-            .and_then(|v| v.as_str()),
-        Some(cruxe_core::constants::PROTOCOL_VERSION)
+        payload.get("status").unwrap().as_str().unwrap(),
+        "error",
+        "overall status should be error when any registered project is not indexed"
     );
+    let projects = payload.get("projects").unwrap().as_array().unwrap();
+    let missing_status = projects
+        .iter()
+        .find(|p| p.get("project_id").and_then(|v| v.as_str()) == Some(missing_project_id))
+        .and_then(|p| p.get("index_status"))
+        .and_then(|v| v.as_str());
+    assert_eq!(missing_status, Some("error"));
 }
 
+/// T122: search_code with debug ranking_reasons enabled returns per-result explanations
 #[test]
-fn t470_search_code_policy_mode_override_rejected_in_strict_mode() {
+fn t122_search_code_ranking_reasons_enabled() {
     let tmp = tempfile::tempdir().unwrap();
     let index_set = build_fixture_index(tmp.path());
+
     let mut config = Config::default();
-    config.search.policy.mode = "strict".to_string();
-    config.search.policy.allow_request_override = false;
+    config.debug.ranking_reasons = true;
     let workspace = Path::new("/tmp/fake-workspace");
     let project_id = "test-repo";
 
@@ -4357,8 +5892,7 @@ fn t470_search_code_policy_mode_override_rejected_in_strict_mode() {
         json!({
             "name": "search_code",
             "arguments": {
-                "query": "validate",
-                "policy_mode": "off"
+                "query": "validate"
             }
         }),
     );
@@ -4380,35 +5914,59 @@ fn t470_search_code_policy_mode_override_rejected_in_strict_mode() {
         },
     );
 
-    assert!(
-        response.error.is_none(),
-        "tool call should return canonical content envelope"
-    );
-    let payload = extract_payload_from_response(&response);
+    assert!(response.error.is_none(), "expected success");
+    let result = response.result.as_ref().unwrap();
+    let content = result.get("content").unwrap().as_array().unwrap();
+    let text = content[0].get("text").unwrap().as_str().unwrap();
+    let payload: serde_json::Value = serde_json::from_str(text).unwrap();
+
+    // ranking_reasons should be present in metadata
+    let meta = payload.get("metadata").unwrap();
+    let reasons = meta
+        .get("ranking_reasons")
+        .expect("ranking_reasons should be present in metadata when debug is enabled");
+    let reasons_array = reasons.as_array().unwrap();
+
+    // Should have one entry per result
+    let results = payload.get("results").unwrap().as_array().unwrap();
     assert_eq!(
-        payload
-            .get("error")
-            .and_then(|v| v.get("code"))
-            .and_then(|v| v.as_str()),
-        Some("invalid_input")
-    );
-    assert!(
-        payload
-            .get("error")
-            .and_then(|v| v.get("message"))
-            .and_then(|v| v.as_str())
-            .unwrap_or_default()
-            .contains("Policy")
+        reasons_array.len(),
+        results.len(),
+        "ranking_reasons should have one entry per result"
     );
+
+    // Each reason should have all 7 fields
+    if let Some(first) = reasons_array.first() {
+        assert!(first.get("result_index").is_some(), "missing result_index");
+        assert!(
+            first.get("exact_match_boost").is_some(),
+            "missing exact_match_boost"
+        );
+        assert!(
+            first.get("qualified_name_boost").is_some(),
+            "missing qualified_name_boost"
+        );
+        assert!(
+            first.get("path_affinity").is_some(),
+            "missing path_affinity"
+        );
+        assert!(
+            first.get("definition_boost").is_some(),
+            "missing definition_boost"
+        );
+        assert!(first.get("kind_match").is_some(), "missing kind_match");
+        assert!(first.get("bm25_score").is_some(), "missing bm25_score");
+        assert!(first.get("final_score").is_some(), "missing final_score");
+    }
 }
 
+/// T123: search_code with debug ranking_reasons disabled (default) omits ranking_reasons
 #[test]
-fn t471_search_code_exposes_policy_metadata_fields() {
+fn t123_search_code_ranking_reasons_disabled() {
     let tmp = tempfile::tempdir().unwrap();
     let index_set = build_fixture_index(tmp.path());
-    let mut config = Config::default();
-    config.search.policy.mode = "balanced".to_string();
-    config.search.policy.allow_request_override = true;
+
+    let config = Config::default(); // ranking_reasons defaults to false
     let workspace = Path::new("/tmp/fake-workspace");
     let project_id = "test-repo";
 
@@ -4417,8 +5975,7 @@ fn t471_search_code_exposes_policy_metadata_fields() {
         json!({
             "name": "search_code",
             "arguments": {
-                "query": "validate",
-                "policy_mode": "audit_only"
+                "query": "validate"
             }
         }),
     );
@@ -4441,31 +5998,36 @@ fn t471_search_code_exposes_policy_metadata_fields() {
     );
 
     assert!(response.error.is_none(), "expected success");
-    let payload = extract_payload_from_response(&response);
-    let metadata = payload.get("metadata").expect("metadata should be present");
-    assert_eq!(
-        metadata.get("policy_mode").and_then(|v| v.as_str()),
-        Some("audit_only")
+    let result = response.result.as_ref().unwrap();
+    let content = result.get("content").unwrap().as_array().unwrap();
+    let text = content[0].get("text").unwrap().as_str().unwrap();
+    let payload: serde_json::Value = serde_json::from_str(text).unwrap();
+
+    // ranking_reasons should be absent from metadata
+    let meta = payload.get("metadata").unwrap();
+    assert!(
+        meta.get("ranking_reasons").is_none(),
+        "ranking_reasons should be absent when debug is disabled"
     );
-    assert!(metadata.get("policy_blocked_count").is_some());
-    assert!(metadata.get("policy_redacted_count").is_some());
 }
 
+/// T124: search_code with ranking_explain_level=basic returns compact factors
 #[test]
-fn t458_search_code_low_intent_confidence_emits_escalation_hint() {
+fn t124_search_code_ranking_reasons_basic_mode() {
     let tmp = tempfile::tempdir().unwrap();
     let index_set = build_fixture_index(tmp.path());
+
     let config = Config::default();
     let workspace = Path::new("/tmp/fake-workspace");
     let project_id = "test-repo";
 
-    // "abc" is intentionally ambiguous and classified as symbol intent with low confidence.
     let request = make_request(
         "tools/call",
         json!({
             "name": "search_code",
             "arguments": {
-                "query": "abc"
+                "query": "validate",
+                "ranking_explain_level": "basic"
             }
         }),
     );
@@ -4489,36 +6051,42 @@ fn t458_search_code_low_intent_confidence_emits_escalation_hint() {
 
     assert!(response.error.is_none(), "expected success");
     let payload = extract_payload_from_response(&response);
-    let meta = payload.get("metadata").expect("metadata should be present");
-
-    let confidence = meta
-        .get("query_intent_confidence")
-        .and_then(|v| v.as_f64())
-        .expect("query_intent_confidence should be present");
-    assert!(confidence < 0.65, "expected low-confidence classification");
+    let meta = payload.get("metadata").unwrap();
+    let reasons = meta
+        .get("ranking_reasons")
+        .expect("ranking_reasons should be present for basic mode")
+        .as_array()
+        .unwrap();
+    assert!(!reasons.is_empty(), "expected non-empty ranking_reasons");
+    let first = reasons.first().unwrap();
+    assert!(first.get("exact_match").is_some());
+    assert!(first.get("path_boost").is_some());
+    assert!(first.get("definition_boost").is_some());
+    assert!(first.get("semantic_similarity").is_some());
+    assert!(first.get("final_score").is_some());
     assert!(
-        meta.get("intent_escalation_hint")
-            .and_then(|v| v.as_str())
-            .is_some(),
-        "low-confidence classifications should include escalation hint"
+        first.get("exact_match_boost").is_none(),
+        "basic mode must omit full explainability fields"
     );
 }
 
+/// T124b: full explain mode includes raw/clamped/effective signal accounting and precedence audit.
 #[test]
-fn t458_search_code_high_intent_confidence_suppresses_escalation_hint() {
+fn t124b_search_code_ranking_reasons_full_mode_includes_budget_fields() {
     let tmp = tempfile::tempdir().unwrap();
     let index_set = build_fixture_index(tmp.path());
+
     let config = Config::default();
     let workspace = Path::new("/tmp/fake-workspace");
     let project_id = "test-repo";
 
-    // Path-like query should be classified with high confidence and no hint.
     let request = make_request(
         "tools/call",
         json!({
             "name": "search_code",
             "arguments": {
-                "query": "src/main.rs"
+                "query": "validate",
+                "ranking_explain_level": "full"
             }
         }),
     );
@@ -4542,127 +6110,134 @@ fn t458_search_code_high_intent_confidence_suppresses_escalation_hint() {
 
     assert!(response.error.is_none(), "expected success");
     let payload = extract_payload_from_response(&response);
-    let meta = payload.get("metadata").expect("metadata should be present");
-
-    let confidence = meta
-        .get("query_intent_confidence")
-        .and_then(|v| v.as_f64())
-        .expect("query_intent_confidence should be present");
-    assert!(
-        confidence >= 0.65,
-        "expected high-confidence classification"
-    );
+    let meta = payload.get("metadata").unwrap();
+    let reasons = meta
+        .get("ranking_reasons")
+        .expect("ranking_reasons should be present for full mode")
+        .as_array()
+        .unwrap();
+    assert!(!reasons.is_empty(), "expected non-empty ranking_reasons");
+    let first = reasons.first().unwrap();
+    // Backward-compatible legacy keys remain available.
+    assert!(first.get("exact_match_boost").is_some());
+    assert!(first.get("qualified_name_boost").is_some());
+    assert!(first.get("bm25_score").is_some());
+    assert!(first.get("final_score").is_some());
+    // Budgeted explainability additions.
+    let contributions = first
+        .get("signal_contributions")
+        .and_then(|v| v.as_array())
+        .expect("full mode should include signal_contributions");
     assert!(
-        meta.get("intent_escalation_hint").is_none()
-            || meta
-                .get("intent_escalation_hint")
-                .and_then(|v| v.as_str())
-                .is_none(),
-        "high-confidence classifications should suppress escalation hint"
+        contributions.len() >= 3,
+        "expected multiple signal contribution entries"
     );
+    let first_signal = contributions.first().unwrap();
+    assert!(first_signal.get("signal").is_some());
+    assert!(first_signal.get("raw_value").is_some());
+    assert!(first_signal.get("clamped_value").is_some());
+    assert!(first_signal.get("effective_value").is_some());
+    let precedence = first
+        .get("precedence_audit")
+        .expect("full mode should include precedence audit");
+    assert!(precedence.get("lexical_dominance_applied").is_some());
+    assert!(precedence.get("exact_match_present").is_some());
+    assert!(precedence.get("secondary_effective_total").is_some());
+    assert!(precedence.get("secondary_effective_cap").is_some());
 }
 
-/// T453: basic explainability payload should be smaller than full mode.
 #[test]
-fn t453_ranking_explainability_basic_payload_smaller_than_full() {
+fn t403_search_code_exposes_semantic_and_confidence_metadata() {
     let tmp = tempfile::tempdir().unwrap();
     let index_set = build_fixture_index(tmp.path());
 
-    let config = Config::default();
+    let mut config = Config::default();
+    config.search.semantic.mode = "rerank_only".to_string();
     let workspace = Path::new("/tmp/fake-workspace");
     let project_id = "test-repo";
 
-    let base_ctx = RequestContext {
-        config: &config,
-        index_set: Some(&index_set),
-        schema_status: SchemaStatus::Compatible,
-        compatibility_reason: None,
-        conn: None,
-        workspace,
-        project_id,
-        prewarm_status: &test_prewarm_status(),
-        server_start: &test_server_start(),
-        notifier: Arc::new(NullProgressNotifier),
-        progress_token: None,
-    };
-
-    let basic_request = make_request(
-        "tools/call",
-        json!({
-            "name": "search_code",
-            "arguments": {
-                "query": "validate",
-                "ranking_explain_level": "basic"
-            }
-        }),
-    );
-    let full_request = make_request(
+    let request = make_request(
         "tools/call",
         json!({
             "name": "search_code",
             "arguments": {
-                "query": "validate",
-                "ranking_explain_level": "full"
+                "query": "where is validate token",
+                "semantic_ratio": 0.8,
+                "confidence_threshold": 0.2
             }
         }),
     );
 
-    let basic_response = handle_request_with_ctx(&basic_request, &base_ctx);
-    assert!(
-        basic_response.error.is_none(),
-        "expected basic-mode success"
+    let response = handle_request_with_ctx(
+        &request,
+        &RequestContext {
+            config: &config,
+            index_set: Some(&index_set),
+            schema_status: SchemaStatus::Compatible,
+            compatibility_reason: None,
+            conn: None,
+            workspace,
+            project_id,
+            prewarm_status: &test_prewarm_status(),
+            server_start: &test_server_start(),
+            notifier: Arc::new(NullProgressNotifier),
+            progress_token: None,
+        },
     );
-    let full_response = handle_request_with_ctx(&full_request, &base_ctx);
-    assert!(full_response.error.is_none(), "expected full-mode success");
 
-    let basic_payload = extract_payload_from_response(&basic_response);
-    let full_payload = extract_payload_from_response(&full_response);
-    let basic_reasons = basic_payload["metadata"]["ranking_reasons"]
-        .as_array()
-        .expect("basic mode should include ranking_reasons");
-    let full_reasons = full_payload["metadata"]["ranking_reasons"]
-        .as_array()
-        .expect("full mode should include ranking_reasons");
+    assert!(response.error.is_none(), "expected success");
+    let payload = extract_payload_from_response(&response);
+    let meta = payload.get("metadata").expect("metadata should be present");
     assert_eq!(
-        basic_reasons.len(),
-        full_reasons.len(),
-        "result count should be stable across explainability levels"
+        meta.get("semantic_mode").and_then(|v| v.as_str()),
+        Some("rerank_only")
     );
-
-    let basic_bytes =
-        serde_json::to_vec(&basic_payload["metadata"]["ranking_reasons"]).expect("serialize");
-    let full_bytes =
-        serde_json::to_vec(&full_payload["metadata"]["ranking_reasons"]).expect("serialize");
-    assert!(
-        basic_bytes.len() < full_bytes.len(),
-        "basic payload should be smaller than full payload (basic={}, full={})",
-        basic_bytes.len(),
-        full_bytes.len()
+    assert_eq!(
+        meta.get("semantic_enabled").and_then(|v| v.as_bool()),
+        Some(true)
+    );
+    assert!(meta.get("semantic_triggered").is_some());
+    assert_eq!(
+        meta.get("confidence_threshold").and_then(|v| v.as_f64()),
+        Some(0.2)
+    );
+    assert!(meta.get("query_intent_confidence").is_some());
+    assert!(meta.get("low_confidence").is_some());
+    assert!(meta.get("policy_mode").is_some());
+    assert!(meta.get("policy_blocked_count").is_some());
+    assert!(meta.get("policy_redacted_count").is_some());
+
+    let first = payload["results"]
+        .as_array()
+        .and_then(|arr| arr.first())
+        .expect("expected at least one search result");
+    assert_eq!(
+        first.get("provenance").and_then(|v| v.as_str()),
+        Some("lexical")
     );
 }
 
-/// T125: compact=true keeps identity fields and omits heavy context fields
 #[test]
-fn t125_search_code_compact_context_omits_heavy_fields() {
+fn t470_search_code_exposes_adaptive_plan_metadata_fields() {
     let tmp = tempfile::tempdir().unwrap();
     let index_set = build_fixture_index(tmp.path());
-
     let config = Config::default();
     let workspace = Path::new("/tmp/fake-workspace");
     let project_id = "test-repo";
 
-    let base_request = make_request(
+    let request = make_request(
         "tools/call",
         json!({
             "name": "search_code",
             "arguments": {
-                "query": "validate",
-                "detail_level": "context"
+                "query": "validate_token",
+                "plan": "lexical_fast"
             }
         }),
     );
-    let base_response = handle_request_with_ctx(
-        &base_request,
+
+    let response = handle_request_with_ctx(
+        &request,
         &RequestContext {
             config: &config,
             index_set: Some(&index_set),
@@ -4677,98 +6252,59 @@ fn t125_search_code_compact_context_omits_heavy_fields() {
             progress_token: None,
         },
     );
-    assert!(base_response.error.is_none(), "expected baseline success");
-    let base_payload = extract_payload_from_response(&base_response);
-    let base_results = base_payload.get("results").unwrap().as_array().unwrap();
-    assert!(!base_results.is_empty(), "expected baseline results");
 
-    let has_heavy_field = base_results.iter().any(|item| {
-        item.get("snippet").is_some()
-            || item.get("body_preview").is_some()
-            || item.get("parent").is_some()
-            || item.get("related_symbols").is_some()
-    });
-    assert!(
-        has_heavy_field,
-        "baseline context response should contain at least one heavy field"
-    );
+    assert!(response.error.is_none(), "expected success");
+    let payload = extract_payload_from_response(&response);
+    let meta = payload.get("metadata").expect("metadata should be present");
 
-    let compact_request = make_request(
-        "tools/call",
-        json!({
-            "name": "search_code",
-            "arguments": {
-                "query": "validate",
-                "detail_level": "context",
-                "compact": true
-            }
-        }),
+    assert_eq!(
+        meta.get("query_plan_selected").and_then(|v| v.as_str()),
+        Some("lexical_fast")
     );
-    let compact_response = handle_request_with_ctx(
-        &compact_request,
-        &RequestContext {
-            config: &config,
-            index_set: Some(&index_set),
-            schema_status: SchemaStatus::Compatible,
-            compatibility_reason: None,
-            conn: None,
-            workspace,
-            project_id,
-            prewarm_status: &test_prewarm_status(),
-            server_start: &test_server_start(),
-            notifier: Arc::new(NullProgressNotifier),
-            progress_token: None,
-        },
+    assert_eq!(
+        meta.get("query_plan_executed").and_then(|v| v.as_str()),
+        Some("lexical_fast")
+    );
+    assert_eq!(
+        meta.get("query_plan_selection_reason")
+            .and_then(|v| v.as_str()),
+        Some("override")
+    );
+    assert_eq!(
+        meta.get("query_plan_downgraded").and_then(|v| v.as_bool()),
+        Some(false)
+    );
+    let budget = meta
+        .get("query_plan_budget_used")
+        .and_then(|v| v.as_object())
+        .expect("query_plan_budget_used should be an object");
+    assert!(
+        budget
+            .get("latency_budget_ms")
+            .and_then(|v| v.as_u64())
+            .is_some(),
+        "latency_budget_ms should be included in query_plan_budget_used"
     );
-    assert!(compact_response.error.is_none(), "expected compact success");
-    let compact_payload = extract_payload_from_response(&compact_response);
-    let compact_results = compact_payload.get("results").unwrap().as_array().unwrap();
-    assert!(!compact_results.is_empty(), "expected compact results");
-
-    for item in compact_results {
-        assert!(
-            item.get("result_id").is_some(),
-            "compact mode must preserve stable handles"
-        );
-        assert!(
-            item.get("path").is_some(),
-            "compact mode must preserve location"
-        );
-        assert!(
-            item.get("score").is_some(),
-            "compact mode must preserve score"
-        );
-        assert!(
-            item.get("snippet").is_none()
-                && item.get("body_preview").is_none()
-                && item.get("parent").is_none()
-                && item.get("related_symbols").is_none(),
-            "compact mode must omit heavy optional fields"
-        );
-    }
 }
 
-/// T126: payload safety limit truncates gracefully with metadata marker
 #[test]
-fn t126_search_code_payload_safety_limit_truncates() {
+fn t471_locate_symbol_omits_adaptive_plan_metadata_fields() {
     let tmp = tempfile::tempdir().unwrap();
     let index_set = build_fixture_index(tmp.path());
-
-    let mut config = Config::default();
-    config.search.max_response_bytes = 64;
+    let config = Config::default();
     let workspace = Path::new("/tmp/fake-workspace");
     let project_id = "test-repo";
 
     let request = make_request(
         "tools/call",
         json!({
-            "name": "search_code",
+            "name": "locate_symbol",
             "arguments": {
-                "query": "validate",
-                "detail_level": "context"
+                "name": "validate_token"
             }
         }),
     );
+
     let response = handle_request_with_ctx(
         &request,
         &RequestContext {
@@ -4788,35 +6324,30 @@ fn t126_search_code_payload_safety_limit_truncates() {
 
     assert!(response.error.is_none(), "expected success");
     let payload = extract_payload_from_response(&response);
-    let metadata = payload.get("metadata").unwrap();
-    assert_eq!(
-        metadata
-            .get("result_completeness")
-            .unwrap()
-            .as_str()
-            .unwrap(),
-        "truncated"
-    );
-    assert_eq!(metadata.get("safety_limit_applied"), Some(&json!(true)));
+    let meta = payload.get("metadata").expect("metadata should be present");
 
-    let actions = payload
-        .get("suggested_next_actions")
-        .and_then(|v| v.as_array())
-        .expect("suggested_next_actions should exist");
     assert!(
-        !actions.is_empty(),
-        "truncation should provide follow-up suggestions"
+        meta.get("query_plan_selected").is_none(),
+        "locate_symbol metadata should omit adaptive plan fields"
+    );
+    assert!(
+        meta.get("query_plan_selection_reason").is_none(),
+        "locate_symbol metadata should omit adaptive plan fields"
+    );
+    assert!(
+        meta.get("query_plan_executed").is_none(),
+        "locate_symbol metadata should omit adaptive plan fields"
+    );
+    assert!(
+        meta.get("query_plan_budget_used").is_none(),
+        "locate_symbol metadata should omit adaptive plan fields"
     );
 }
 
-/// T127: search_code reports dedup metadata and preserves pre-dedup candidate count
 #[test]
-fn t127_search_code_reports_suppressed_duplicate_count() {
+fn t403_search_code_invalid_semantic_ratio_returns_invalid_input() {
     let tmp = tempfile::tempdir().unwrap();
     let index_set = build_fixture_index(tmp.path());
-    let db_path = tmp.path().join("data/state.db");
-    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
-
     let config = Config::default();
     let workspace = Path::new("/tmp/fake-workspace");
     let project_id = "test-repo";
@@ -4826,8 +6357,8 @@ fn t127_search_code_reports_suppressed_duplicate_count() {
         json!({
             "name": "search_code",
             "arguments": {
-                "query": "validate_token",
-                "limit": 20
+                "query": "validate",
+                "semantic_ratio": 1.5
             }
         }),
     );
@@ -4839,7 +6370,7 @@ fn t127_search_code_reports_suppressed_duplicate_count() {
             index_set: Some(&index_set),
             schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
-            conn: Some(&conn),
+            conn: None,
             workspace,
             project_id,
             prewarm_status: &test_prewarm_status(),
@@ -4849,50 +6380,43 @@ fn t127_search_code_reports_suppressed_duplicate_count() {
         },
     );
 
-    assert!(response.error.is_none(), "expected success");
+    assert!(
+        response.error.is_none(),
+        "tool call should return content envelope"
+    );
     let payload = extract_payload_from_response(&response);
-
-    let results_len = payload
-        .get("results")
-        .and_then(|v| v.as_array())
-        .map(std::vec::Vec::len)
-        .unwrap_or(0);
-    let total_candidates = payload
-        .get("total_candidates")
-        .and_then(|v| v.as_u64())
-        .unwrap_or(0) as usize;
+    assert_eq!(
+        payload
+            .get("error")
+            .and_then(|v| v.get("code"))
+            .and_then(|v| v.as_str()),
+        Some("invalid_input")
+    );
     assert!(
-        total_candidates >= results_len,
-        "total_candidates should be pre-dedup/pre-truncation count"
+        payload
+            .get("error")
+            .and_then(|v| v.get("message"))
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .contains("semantic_ratio")
     );
-
-    let metadata = payload.get("metadata").unwrap();
-    let suppressed = metadata
-        .get("suppressed_duplicate_count")
-        .and_then(|v| v.as_u64())
-        .expect("expected dedup metadata for duplicate-heavy query");
-    assert!(suppressed > 0, "suppressed_duplicate_count should be > 0");
 }
 
-/// T128: locate_symbol safety truncation returns deterministic follow-up actions
 #[test]
-fn t128_locate_symbol_payload_safety_limit_has_followups() {
+fn t404_search_code_invalid_confidence_threshold_returns_invalid_input_envelope() {
     let tmp = tempfile::tempdir().unwrap();
     let index_set = build_fixture_index(tmp.path());
-
-    let mut config = Config::default();
-    config.search.max_response_bytes = 64;
+    let config = Config::default();
     let workspace = Path::new("/tmp/fake-workspace");
     let project_id = "test-repo";
 
     let request = make_request(
         "tools/call",
         json!({
-            "name": "locate_symbol",
+            "name": "search_code",
             "arguments": {
-                "name": "validate_token",
-                "detail_level": "context",
-                "limit": 10
+                "query": "validate",
+                "confidence_threshold": -0.1
             }
         }),
     );
@@ -4914,50 +6438,71 @@ fn t128_locate_symbol_payload_safety_limit_has_followups() {
         },
     );
 
-    assert!(response.error.is_none(), "expected success");
+    assert!(
+        response.error.is_none(),
+        "tool call should return canonical content envelope"
+    );
     let payload = extract_payload_from_response(&response);
-    let metadata = payload.get("metadata").unwrap();
     assert_eq!(
-        metadata
-            .get("result_completeness")
-            .unwrap()
-            .as_str()
-            .unwrap(),
-        "truncated"
-    );
-    assert_eq!(metadata.get("safety_limit_applied"), Some(&json!(true)));
-
-    let actions = payload
-        .get("suggested_next_actions")
-        .and_then(|v| v.as_array())
-        .expect("locate_symbol truncation should provide suggested_next_actions");
+        payload
+            .get("error")
+            .and_then(|v| v.get("code"))
+            .and_then(|v| v.as_str()),
+        Some("invalid_input")
+    );
     assert!(
-        actions.len() >= 2,
-        "locate_symbol truncation should provide deterministic follow-ups"
+        payload
+            .get("error")
+            .and_then(|v| v.get("message"))
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .contains("confidence_threshold")
     );
+
+    let metadata = payload.get("metadata").expect("metadata should be present");
     assert_eq!(
-        actions[0].get("tool").and_then(|v| v.as_str()),
-        Some("locate_symbol")
+        metadata.get("schema_status").and_then(|v| v.as_str()),
+        Some("compatible")
+    );
+    assert_eq!(
+        metadata.get("indexing_status").and_then(|v| v.as_str()),
+        Some("ready")
+    );
+    assert_eq!(
+        metadata
+            .get("cruxe_protocol_version")
+            .and_then(|v| v.as_str()),
+        Some(cruxe_core::constants::PROTOCOL_VERSION)
     );
 }
 
-// ------------------------------------------------------------------
-// T134: tools/list schema verification for get_file_outline + health_check
-// ------------------------------------------------------------------
-
 #[test]
-fn t134_tools_list_schema_verification() {
-    let config = Config::default();
+fn t470_search_code_policy_mode_override_rejected_in_strict_mode() {
+    let tmp = tempfile::tempdir().unwrap();
+    let index_set = build_fixture_index(tmp.path());
+    let mut config = Config::default();
+    config.search.policy.mode = "strict".to_string();
+    config.search.policy.allow_request_override = false;
     let workspace = Path::new("/tmp/fake-workspace");
-    let project_id = "fake_project_id";
+    let project_id = "test-repo";
+
+    let request = make_request(
+        "tools/call",
+        json!({
+            "name": "search_code",
+            "arguments": {
+                "query": "validate",
+                "policy_mode": "off"
+            }
+        }),
+    );
 
-    let request = make_request("tools/list", json!({}));
     let response = handle_request_with_ctx(
         &request,
         &RequestContext {
             config: &config,
-            index_set: None,
-            schema_status: SchemaStatus::NotIndexed,
+            index_set: Some(&index_set),
+            schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
             conn: None,
             workspace,
@@ -4969,147 +6514,55 @@ fn t134_tools_list_schema_verification() {
         },
     );
 
-    let result = response.result.unwrap();
-    let tools = result.get("tools").unwrap().as_array().unwrap();
-
-    // Verify get_file_outline schema
-    let outline = tools
-        .iter()
-        .find(|t| t.get("name").unwrap().as_str().unwrap() == "get_file_outline")
-        .expect("get_file_outline should be listed");
-    let outline_schema = outline.get("inputSchema").unwrap();
-    let outline_props = outline_schema.get("properties").unwrap();
-    assert!(
-        outline_props.get("path").is_some(),
-        "get_file_outline should have 'path' property"
-    );
-    let outline_required = outline_schema.get("required").unwrap().as_array().unwrap();
-    assert!(
-        outline_required.contains(&json!("path")),
-        "get_file_outline should require 'path'"
-    );
-
-    // Verify health_check schema
-    let health = tools
-        .iter()
-        .find(|t| t.get("name").unwrap().as_str().unwrap() == "health_check")
-        .expect("health_check should be listed");
-    let health_schema = health.get("inputSchema").unwrap();
-    let health_props = health_schema.get("properties").unwrap();
-    assert!(
-        health_props.get("workspace").is_some(),
-        "health_check should have 'workspace' property"
-    );
-
-    let hierarchy = tools
-        .iter()
-        .find(|t| t.get("name").unwrap().as_str().unwrap() == "get_symbol_hierarchy")
-        .expect("get_symbol_hierarchy should be listed");
-    let hierarchy_schema = hierarchy.get("inputSchema").unwrap();
-    let hierarchy_props = hierarchy_schema.get("properties").unwrap();
-    assert!(hierarchy_props.get("symbol_name").is_some());
-    assert!(
-        hierarchy_props
-            .get("direction")
-            .and_then(|v| v.get("enum"))
-            .is_some(),
-        "get_symbol_hierarchy direction should define enum values"
-    );
-    assert!(
-        hierarchy_props.get("compact").is_none(),
-        "003 tools must not expose compact parameter in this phase"
-    );
-    let hierarchy_required = hierarchy_schema
-        .get("required")
-        .unwrap()
-        .as_array()
-        .unwrap();
-    assert!(hierarchy_required.contains(&json!("symbol_name")));
-
-    let related = tools
-        .iter()
-        .find(|t| t.get("name").unwrap().as_str().unwrap() == "find_related_symbols")
-        .expect("find_related_symbols should be listed");
-    let related_schema = related.get("inputSchema").unwrap();
-    let related_props = related_schema.get("properties").unwrap();
-    assert!(related_props.get("scope").is_some());
-    assert!(related_props.get("limit").is_some());
-    assert!(
-        related_props.get("compact").is_none(),
-        "003 tools must not expose compact parameter in this phase"
-    );
-    let related_required = related_schema.get("required").unwrap().as_array().unwrap();
-    assert!(related_required.contains(&json!("symbol_name")));
-
-    let context = tools
-        .iter()
-        .find(|t| t.get("name").unwrap().as_str().unwrap() == "get_code_context")
-        .expect("get_code_context should be listed");
-    let context_schema = context.get("inputSchema").unwrap();
-    let context_props = context_schema.get("properties").unwrap();
-    assert!(context_props.get("query").is_some());
-    assert!(context_props.get("max_tokens").is_some());
-    assert!(context_props.get("strategy").is_some());
-    assert!(
-        context_props.get("policy_mode").is_some(),
-        "get_code_context should expose policy_mode override"
-    );
-    assert!(
-        context_props.get("compact").is_none(),
-        "003 tools must not expose compact parameter in this phase"
-    );
-    let context_required = context_schema.get("required").unwrap().as_array().unwrap();
-    assert!(context_required.contains(&json!("query")));
-
-    let context_pack = tools
-        .iter()
-        .find(|t| t.get("name").unwrap().as_str().unwrap() == "build_context_pack")
-        .expect("build_context_pack should be listed");
-    let context_pack_schema = context_pack.get("inputSchema").unwrap();
-    let context_pack_props = context_pack_schema.get("properties").unwrap();
-    assert!(context_pack_props.get("query").is_some());
-    assert!(context_pack_props.get("budget_tokens").is_some());
-    assert!(context_pack_props.get("mode").is_some());
-    assert!(context_pack_props.get("section_caps").is_some());
-    let context_pack_required = context_pack_schema
-        .get("required")
-        .unwrap()
-        .as_array()
-        .unwrap();
-    assert!(context_pack_required.contains(&json!("query")));
-
-    let search = tools
-        .iter()
-        .find(|t| t.get("name").unwrap().as_str().unwrap() == "search_code")
-        .expect("search_code should be listed");
-    let search_schema = search.get("inputSchema").unwrap();
-    let search_props = search_schema.get("properties").unwrap();
     assert!(
-        search_props.get("semantic_ratio").is_some(),
-        "search_code should expose semantic_ratio override"
+        response.error.is_none(),
+        "tool call should return canonical content envelope"
     );
-    assert!(
-        search_props.get("confidence_threshold").is_some(),
-        "search_code should expose confidence_threshold override"
+    let payload = extract_payload_from_response(&response);
+    assert_eq!(
+        payload
+            .get("error")
+            .and_then(|v| v.get("code"))
+            .and_then(|v| v.as_str()),
+        Some("invalid_input")
     );
     assert!(
-        search_props.get("policy_mode").is_some(),
-        "search_code should expose policy_mode override"
+        payload
+            .get("error")
+            .and_then(|v| v.get("message"))
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .contains("Policy")
     );
 }
 
 #[test]
-fn t319_tools_list_includes_vcs_ga_tool_schemas() {
-    let config = Config::default();
+fn t471_search_code_exposes_policy_metadata_fields() {
+    let tmp = tempfile::tempdir().unwrap();
+    let index_set = build_fixture_index(tmp.path());
+    let mut config = Config::default();
+    config.search.policy.mode = "balanced".to_string();
+    config.search.policy.allow_request_override = true;
     let workspace = Path::new("/tmp/fake-workspace");
-    let project_id = "fake_project_id";
-    let request = make_request("tools/list", json!({}));
+    let project_id = "test-repo";
+
+    let request = make_request(
+        "tools/call",
+        json!({
+            "name": "search_code",
+            "arguments": {
+                "query": "validate",
+                "policy_mode": "audit_only"
+            }
+        }),
+    );
+
     let response = handle_request_with_ctx(
         &request,
         &RequestContext {
             config: &config,
-            index_set: None,
-            schema_status: SchemaStatus::NotIndexed,
+            index_set: Some(&index_set),
+            schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
             conn: None,
             workspace,
@@ -5120,174 +6573,47 @@ fn t319_tools_list_includes_vcs_ga_tool_schemas() {
             progress_token: None,
         },
     );
-    assert!(response.error.is_none(), "tools/list should succeed");
-    let result = response.result.unwrap();
-    let tools = result.get("tools").unwrap().as_array().unwrap();
-
-    for tool_name in [
-        "diff_context",
-        "find_references",
-        "explain_ranking",
-        "list_refs",
-        "switch_ref",
-    ] {
-        let tool = tools
-            .iter()
-            .find(|item| item.get("name").and_then(|v| v.as_str()) == Some(tool_name))
-            .unwrap_or_else(|| panic!("{tool_name} should be present in tools/list"));
-        let schema = tool.get("inputSchema").unwrap();
-        assert!(
-            schema
-                .get("properties")
-                .and_then(|v| v.get("workspace"))
-                .is_some(),
-            "{tool_name} should expose workspace in input schema"
-        );
-    }
-}
 
-fn setup_vcs_project_for_ref_tools(
-    workspace: &Path,
-    conn: &rusqlite::Connection,
-    project_id: &str,
-) {
-    ensure_git_workspace_with_refs(workspace);
-
-    let now = "2026-02-25T00:00:00Z".to_string();
-    let project = Project {
-        project_id: project_id.to_string(),
-        repo_root: workspace.to_string_lossy().to_string(),
-        display_name: Some("ref-tools".to_string()),
-        default_ref: "main".to_string(),
-        vcs_mode: true,
-        schema_version: 1,
-        parser_version: 1,
-        created_at: now.clone(),
-        updated_at: now.clone(),
-    };
-    cruxe_state::project::create_project(conn, &project).unwrap();
-    cruxe_state::branch_state::upsert_branch_state(
-        conn,
-        &cruxe_state::branch_state::BranchState {
-            repo: project_id.to_string(),
-            r#ref: "main".to_string(),
-            merge_base_commit: None,
-            last_indexed_commit: "abc123".to_string(),
-            overlay_dir: None,
-            file_count: 10,
-            symbol_count: 30,
-            is_default_branch: true,
-            status: "active".to_string(),
-            eviction_eligible_at: None,
-            created_at: now.clone(),
-            last_accessed_at: now.clone(),
-        },
-    )
-    .unwrap();
-    cruxe_state::branch_state::upsert_branch_state(
-        conn,
-        &cruxe_state::branch_state::BranchState {
-            repo: project_id.to_string(),
-            r#ref: "feat/auth".to_string(),
-            merge_base_commit: Some("abc123".to_string()),
-            last_indexed_commit: "def456".to_string(),
-            overlay_dir: Some("overlay/feat-auth".to_string()),
-            file_count: 4,
-            symbol_count: 9,
-            is_default_branch: false,
-            status: "active".to_string(),
-            eviction_eligible_at: None,
-            created_at: now.clone(),
-            last_accessed_at: now,
-        },
-    )
-    .unwrap();
-}
-
-fn ensure_git_workspace_with_refs(workspace: &Path) {
-    if workspace.join(".git").exists() {
-        return;
-    }
-    std::fs::create_dir_all(workspace).unwrap();
-
-    run_git(workspace, &["init"]);
-    run_git(workspace, &["config", "user.email", "tests@example.com"]);
-    run_git(workspace, &["config", "user.name", "Cruxe Tests"]);
-
-    std::fs::write(workspace.join("README.md"), "vcs tool test fixture\n").unwrap();
-    run_git(workspace, &["add", "."]);
-    run_git(workspace, &["commit", "-m", "fixture init"]);
-
-    let head = run_git_capture(workspace, &["rev-parse", "--abbrev-ref", "HEAD"]);
-    if head != "main" {
-        run_git(workspace, &["branch", "-m", "main"]);
-    }
-    run_git(workspace, &["branch", "feat/auth"]);
-}
-
-fn run_git(workspace: &Path, args: &[&str]) {
-    let output = std::process::Command::new("git")
-        .args(args)
-        .current_dir(workspace)
-        .output()
-        .unwrap();
-    assert!(
-        output.status.success(),
-        "git {:?} failed: stdout={} stderr={}",
-        args,
-        String::from_utf8_lossy(&output.stdout),
-        String::from_utf8_lossy(&output.stderr)
-    );
-}
-
-fn run_git_capture(workspace: &Path, args: &[&str]) -> String {
-    let output = std::process::Command::new("git")
-        .args(args)
-        .current_dir(workspace)
-        .output()
-        .unwrap();
-    assert!(
-        output.status.success(),
-        "git {:?} failed: stdout={} stderr={}",
-        args,
-        String::from_utf8_lossy(&output.stdout),
-        String::from_utf8_lossy(&output.stderr)
+    assert!(response.error.is_none(), "expected success");
+    let payload = extract_payload_from_response(&response);
+    let metadata = payload.get("metadata").expect("metadata should be present");
+    assert_eq!(
+        metadata.get("policy_mode").and_then(|v| v.as_str()),
+        Some("audit_only")
     );
-    String::from_utf8_lossy(&output.stdout).trim().to_string()
+    assert!(metadata.get("policy_blocked_count").is_some());
+    assert!(metadata.get("policy_redacted_count").is_some());
 }
 
 #[test]
-fn t309_list_refs_returns_branch_state_rows() {
+fn t458_search_code_low_intent_confidence_emits_escalation_hint() {
     let tmp = tempfile::tempdir().unwrap();
-    let workspace = tmp.path().join("workspace");
-    std::fs::create_dir_all(&workspace).unwrap();
-    let mut config = Config::default();
-    config.storage.data_dir = tmp.path().join("cc-data").to_string_lossy().to_string();
-    let project_id = generate_project_id(&workspace.to_string_lossy());
-    let data_dir = config.project_data_dir(&project_id);
-    let conn =
-        cruxe_state::db::open_connection(&data_dir.join(cruxe_core::constants::STATE_DB_FILE))
-            .unwrap();
-    cruxe_state::schema::create_tables(&conn).unwrap();
-    setup_vcs_project_for_ref_tools(&workspace, &conn, &project_id);
+    let index_set = build_fixture_index(tmp.path());
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+    let project_id = "test-repo";
 
+    // "abc" is intentionally ambiguous and classified as symbol intent with low confidence.
     let request = make_request(
         "tools/call",
         json!({
-            "name": "list_refs",
-            "arguments": {}
+            "name": "search_code",
+            "arguments": {
+                "query": "abc"
+            }
         }),
     );
+
     let response = handle_request_with_ctx(
         &request,
         &RequestContext {
             config: &config,
-            index_set: None,
+            index_set: Some(&index_set),
             schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
-            conn: Some(&conn),
-            workspace: &workspace,
-            project_id: &project_id,
+            conn: None,
+            workspace,
+            project_id,
             prewarm_status: &test_prewarm_status(),
             server_start: &test_server_start(),
             notifier: Arc::new(NullProgressNotifier),
@@ -5295,150 +6621,229 @@ fn t309_list_refs_returns_branch_state_rows() {
         },
     );
 
-    assert!(response.error.is_none(), "list_refs should succeed");
+    assert!(response.error.is_none(), "expected success");
     let payload = extract_payload_from_response(&response);
-    let refs = payload.get("refs").unwrap().as_array().unwrap();
-    assert_eq!(refs.len(), 2);
-    assert!(
-        refs.iter()
-            .any(|value| value.get("ref") == Some(&json!("main")))
-    );
+    let meta = payload.get("metadata").expect("metadata should be present");
+
+    let confidence = meta
+        .get("query_intent_confidence")
+        .and_then(|v| v.as_f64())
+        .expect("query_intent_confidence should be present");
+    assert!(confidence < 0.65, "expected low-confidence classification");
     assert!(
-        refs.iter()
-            .any(|value| value.get("ref") == Some(&json!("feat/auth")))
+        meta.get("intent_escalation_hint")
+            .and_then(|v| v.as_str())
+            .is_some(),
+        "low-confidence classifications should include escalation hint"
     );
 }
 
 #[test]
-fn t310_switch_ref_updates_session_ref_without_mutating_project_default() {
+fn t458_search_code_high_intent_confidence_suppresses_escalation_hint() {
     let tmp = tempfile::tempdir().unwrap();
-    let workspace = tmp.path().join("workspace");
-    std::fs::create_dir_all(&workspace).unwrap();
-    let mut config = Config::default();
-    config.storage.data_dir = tmp.path().join("cc-data").to_string_lossy().to_string();
-    let project_id = generate_project_id(&workspace.to_string_lossy());
-    let data_dir = config.project_data_dir(&project_id);
-    let conn =
-        cruxe_state::db::open_connection(&data_dir.join(cruxe_core::constants::STATE_DB_FILE))
-            .unwrap();
-    cruxe_state::schema::create_tables(&conn).unwrap();
-    setup_vcs_project_for_ref_tools(&workspace, &conn, &project_id);
+    let index_set = build_fixture_index(tmp.path());
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+    let project_id = "test-repo";
 
+    // Path-like query should be classified with high confidence and no hint.
     let request = make_request(
         "tools/call",
         json!({
-            "name": "switch_ref",
+            "name": "search_code",
             "arguments": {
-                "ref": "feat/auth"
+                "query": "src/main.rs"
             }
         }),
     );
+
     let response = handle_request_with_ctx(
         &request,
         &RequestContext {
             config: &config,
-            index_set: None,
+            index_set: Some(&index_set),
             schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
-            conn: Some(&conn),
-            workspace: &workspace,
-            project_id: &project_id,
+            conn: None,
+            workspace,
+            project_id,
             prewarm_status: &test_prewarm_status(),
             server_start: &test_server_start(),
             notifier: Arc::new(NullProgressNotifier),
             progress_token: None,
         },
     );
-    assert!(response.error.is_none(), "switch_ref should succeed");
 
+    assert!(response.error.is_none(), "expected success");
     let payload = extract_payload_from_response(&response);
-    assert_eq!(payload.get("ref"), Some(&json!("feat/auth")));
-    assert_eq!(payload.get("previous_ref"), Some(&json!("main")));
+    let meta = payload.get("metadata").expect("metadata should be present");
 
-    let project_row = cruxe_state::project::get_by_id(&conn, &project_id)
-        .unwrap()
-        .expect("project should exist");
-    assert_eq!(project_row.default_ref, "main");
-    assert_eq!(
-        resolve_tool_ref(None, &workspace, Some(&conn), &project_id),
-        "feat/auth"
+    let confidence = meta
+        .get("query_intent_confidence")
+        .and_then(|v| v.as_f64())
+        .expect("query_intent_confidence should be present");
+    assert!(
+        confidence >= 0.65,
+        "expected high-confidence classification"
+    );
+    assert!(
+        meta.get("intent_escalation_hint").is_none()
+            || meta
+                .get("intent_escalation_hint")
+                .and_then(|v| v.as_str())
+                .is_none(),
+        "high-confidence classifications should suppress escalation hint"
     );
 }
 
+/// T453: basic explainability payload should be smaller than full mode.
 #[test]
-fn t311_switch_ref_rejects_unindexed_ref_with_guidance() {
+fn t453_ranking_explainability_basic_payload_smaller_than_full() {
     let tmp = tempfile::tempdir().unwrap();
-    let workspace = tmp.path().join("workspace");
-    std::fs::create_dir_all(&workspace).unwrap();
-    let mut config = Config::default();
-    config.storage.data_dir = tmp.path().join("cc-data").to_string_lossy().to_string();
-    let project_id = generate_project_id(&workspace.to_string_lossy());
-    let data_dir = config.project_data_dir(&project_id);
-    let conn =
-        cruxe_state::db::open_connection(&data_dir.join(cruxe_core::constants::STATE_DB_FILE))
-            .unwrap();
-    cruxe_state::schema::create_tables(&conn).unwrap();
-    setup_vcs_project_for_ref_tools(&workspace, &conn, &project_id);
+    let index_set = build_fixture_index(tmp.path());
 
-    let request = make_request(
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+    let project_id = "test-repo";
+
+    let base_ctx = RequestContext {
+        config: &config,
+        index_set: Some(&index_set),
+        schema_status: SchemaStatus::Compatible,
+        compatibility_reason: None,
+        conn: None,
+        workspace,
+        project_id,
+        prewarm_status: &test_prewarm_status(),
+        server_start: &test_server_start(),
+        notifier: Arc::new(NullProgressNotifier),
+        progress_token: None,
+    };
+
+    let basic_request = make_request(
         "tools/call",
         json!({
-            "name": "switch_ref",
+            "name": "search_code",
             "arguments": {
-                "ref": "feat/missing"
+                "query": "validate",
+                "ranking_explain_level": "basic"
             }
         }),
     );
-    let response = handle_request_with_ctx(
-        &request,
+    let full_request = make_request(
+        "tools/call",
+        json!({
+            "name": "search_code",
+            "arguments": {
+                "query": "validate",
+                "ranking_explain_level": "full"
+            }
+        }),
+    );
+
+    let basic_response = handle_request_with_ctx(&basic_request, &base_ctx);
+    assert!(
+        basic_response.error.is_none(),
+        "expected basic-mode success"
+    );
+    let full_response = handle_request_with_ctx(&full_request, &base_ctx);
+    assert!(full_response.error.is_none(), "expected full-mode success");
+
+    let basic_payload = extract_payload_from_response(&basic_response);
+    let full_payload = extract_payload_from_response(&full_response);
+    let basic_reasons = basic_payload["metadata"]["ranking_reasons"]
+        .as_array()
+        .expect("basic mode should include ranking_reasons");
+    let full_reasons = full_payload["metadata"]["ranking_reasons"]
+        .as_array()
+        .expect("full mode should include ranking_reasons");
+    assert_eq!(
+        basic_reasons.len(),
+        full_reasons.len(),
+        "result count should be stable across explainability levels"
+    );
+
+    let basic_bytes =
+        serde_json::to_vec(&basic_payload["metadata"]["ranking_reasons"]).expect("serialize");
+    let full_bytes =
+        serde_json::to_vec(&full_payload["metadata"]["ranking_reasons"]).expect("serialize");
+    assert!(
+        basic_bytes.len() < full_bytes.len(),
+        "basic payload should be smaller than full payload (basic={}, full={})",
+        basic_bytes.len(),
+        full_bytes.len()
+    );
+}
+
+/// T125: compact=true keeps identity fields and omits heavy context fields
+#[test]
+fn t125_search_code_compact_context_omits_heavy_fields() {
+    let tmp = tempfile::tempdir().unwrap();
+    let index_set = build_fixture_index(tmp.path());
+
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+    let project_id = "test-repo";
+
+    let base_request = make_request(
+        "tools/call",
+        json!({
+            "name": "search_code",
+            "arguments": {
+                "query": "validate",
+                "detail_level": "context"
+            }
+        }),
+    );
+    let base_response = handle_request_with_ctx(
+        &base_request,
         &RequestContext {
             config: &config,
-            index_set: None,
+            index_set: Some(&index_set),
             schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
-            conn: Some(&conn),
-            workspace: &workspace,
-            project_id: &project_id,
+            conn: None,
+            workspace,
+            project_id,
             prewarm_status: &test_prewarm_status(),
             server_start: &test_server_start(),
             notifier: Arc::new(NullProgressNotifier),
             progress_token: None,
         },
     );
+    assert!(base_response.error.is_none(), "expected baseline success");
+    let base_payload = extract_payload_from_response(&base_response);
+    let base_results = base_payload.get("results").unwrap().as_array().unwrap();
+    assert!(!base_results.is_empty(), "expected baseline results");
+
+    let has_heavy_field = base_results.iter().any(|item| {
+        item.get("snippet").is_some()
+            || item.get("body_preview").is_some()
+            || item.get("parent").is_some()
+            || item.get("related_symbols").is_some()
+    });
     assert!(
-        response.error.is_none(),
-        "switch_ref returns tool-level error envelope"
-    );
-    let payload = extract_payload_from_response(&response);
-    let error = payload.get("error").expect("error payload should exist");
-    assert_eq!(error.get("code"), Some(&json!("ref_not_indexed")));
-    assert!(
-        error
-            .get("data")
-            .and_then(|value| value.get("remediation"))
-            .and_then(|value| value.as_str())
-            .is_some_and(|value| value.contains("sync_repo")),
-        "expected remediation guidance"
+        has_heavy_field,
+        "baseline context response should contain at least one heavy field"
     );
-}
-
-// ------------------------------------------------------------------
-// T207: Contract guard – all query/path tools expose `workspace` param
-// ------------------------------------------------------------------
-
-#[test]
-fn t207_all_tools_expose_workspace_parameter() {
-    let config = Config::default();
-    let workspace = Path::new("/tmp/fake-workspace");
-    let project_id = "fake_project_id";
 
-    let request = make_request("tools/list", json!({}));
-    let response = handle_request_with_ctx(
-        &request,
+    let compact_request = make_request(
+        "tools/call",
+        json!({
+            "name": "search_code",
+            "arguments": {
+                "query": "validate",
+                "detail_level": "context",
+                "compact": true
+            }
+        }),
+    );
+    let compact_response = handle_request_with_ctx(
+        &compact_request,
         &RequestContext {
             config: &config,
-            index_set: None,
-            schema_status: SchemaStatus::NotIndexed,
+            index_set: Some(&index_set),
+            schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
             conn: None,
             workspace,
@@ -5449,62 +6854,53 @@ fn t207_all_tools_expose_workspace_parameter() {
             progress_token: None,
         },
     );
+    assert!(compact_response.error.is_none(), "expected compact success");
+    let compact_payload = extract_payload_from_response(&compact_response);
+    let compact_results = compact_payload.get("results").unwrap().as_array().unwrap();
+    assert!(!compact_results.is_empty(), "expected compact results");
 
-    assert!(response.error.is_none(), "tools/list should succeed");
-    let result = response.result.expect("result should be present");
-    let tools = result
-        .get("tools")
-        .expect("result should contain 'tools'")
-        .as_array()
-        .expect("'tools' should be an array");
-
-    // All tools must expose a `workspace` property in their inputSchema
-    for tool in tools {
-        let name = tool.get("name").unwrap().as_str().unwrap();
-        let schema = tool
-            .get("inputSchema")
-            .expect("tool should have inputSchema");
-        let props = schema
-            .get("properties")
-            .unwrap_or_else(|| panic!("tool '{name}' inputSchema missing 'properties'"));
+    for item in compact_results {
         assert!(
-            props.get("workspace").is_some(),
-            "tool '{name}' is missing 'workspace' parameter in inputSchema"
+            item.get("result_id").is_some(),
+            "compact mode must preserve stable handles"
         );
-        // Verify workspace is typed as string
-        let ws_prop = props.get("workspace").unwrap();
-        assert_eq!(
-            ws_prop.get("type").unwrap().as_str().unwrap(),
-            "string",
-            "tool '{name}' workspace parameter should be type 'string'"
+        assert!(
+            item.get("path").is_some(),
+            "compact mode must preserve location"
+        );
+        assert!(
+            item.get("score").is_some(),
+            "compact mode must preserve score"
+        );
+        assert!(
+            item.get("snippet").is_none()
+                && item.get("body_preview").is_none()
+                && item.get("parent").is_none()
+                && item.get("related_symbols").is_none(),
+            "compact mode must omit heavy optional fields"
         );
     }
 }
 
-// ------------------------------------------------------------------
-// T135: Full E2E workflow test
-// ------------------------------------------------------------------
-
+/// T126: payload safety limit truncates gracefully with metadata marker
 #[test]
-fn t135_full_e2e_workflow() {
+fn t126_search_code_payload_safety_limit_truncates() {
     let tmp = tempfile::tempdir().unwrap();
     let index_set = build_fixture_index(tmp.path());
 
-    // Open a DB connection for outline queries
-    let db_path = tmp.path().join("data/state.db");
-    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
-
-    let config = Config::default();
+    let mut config = Config::default();
+    config.search.max_response_bytes = 64;
     let workspace = Path::new("/tmp/fake-workspace");
-    // Must match the repo name used in build_fixture_index
     let project_id = "test-repo";
 
-    // Step 1: health_check
     let request = make_request(
         "tools/call",
         json!({
-            "name": "health_check",
-            "arguments": {}
+            "name": "search_code",
+            "arguments": {
+                "query": "validate",
+                "detail_level": "context"
+            }
         }),
     );
     let response = handle_request_with_ctx(
@@ -5514,7 +6910,7 @@ fn t135_full_e2e_workflow() {
             index_set: Some(&index_set),
             schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
-            conn: Some(&conn),
+            conn: None,
             workspace,
             project_id,
             prewarm_status: &test_prewarm_status(),
@@ -5523,25 +6919,53 @@ fn t135_full_e2e_workflow() {
             progress_token: None,
         },
     );
-    assert!(response.error.is_none(), "health_check should succeed");
+
+    assert!(response.error.is_none(), "expected success");
     let payload = extract_payload_from_response(&response);
+    let metadata = payload.get("metadata").unwrap();
     assert_eq!(
-        payload.get("status").unwrap().as_str().unwrap(),
-        "ready",
-        "health_check should report 'ready'"
+        metadata
+            .get("result_completeness")
+            .unwrap()
+            .as_str()
+            .unwrap(),
+        "truncated"
+    );
+    assert_eq!(metadata.get("safety_limit_applied"), Some(&json!(true)));
+
+    let actions = payload
+        .get("suggested_next_actions")
+        .and_then(|v| v.as_array())
+        .expect("suggested_next_actions should exist");
+    assert!(
+        !actions.is_empty(),
+        "truncation should provide follow-up suggestions"
     );
+}
+
+/// T127: search_code reports dedup metadata and preserves pre-dedup candidate count
+#[test]
+fn t127_search_code_reports_suppressed_duplicate_count() {
+    let tmp = tempfile::tempdir().unwrap();
+    let index_set = build_fixture_index(tmp.path());
+    let db_path = tmp.path().join("data/state.db");
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+    let project_id = "test-repo";
 
-    // Step 2: locate_symbol with detail_level: "location"
     let request = make_request(
         "tools/call",
         json!({
-            "name": "locate_symbol",
+            "name": "search_code",
             "arguments": {
-                "name": "validate_token",
-                "detail_level": "location"
+                "query": "validate_token",
+                "limit": 20
             }
         }),
     );
+
     let response = handle_request_with_ctx(
         &request,
         &RequestContext {
@@ -5558,33 +6982,55 @@ fn t135_full_e2e_workflow() {
             progress_token: None,
         },
     );
+
+    assert!(response.error.is_none(), "expected success");
+    let payload = extract_payload_from_response(&response);
+
+    let results_len = payload
+        .get("results")
+        .and_then(|v| v.as_array())
+        .map(std::vec::Vec::len)
+        .unwrap_or(0);
+    let total_candidates = payload
+        .get("total_candidates")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as usize;
     assert!(
-        response.error.is_none(),
-        "locate_symbol(location) should succeed"
-    );
-    let results = extract_results_from_response(&response);
-    assert!(!results.is_empty(), "locate should find validate_token");
-    let vt = &results[0];
-    assert!(
-        vt.get("path").is_some(),
-        "location level should include path"
-    );
-    assert!(
-        vt.get("qualified_name").is_none(),
-        "location level should NOT include qualified_name"
+        total_candidates >= results_len,
+        "total_candidates should be pre-dedup/pre-truncation count"
     );
 
-    // Step 3: get_file_outline for the found file
-    let found_path = vt.get("path").unwrap().as_str().unwrap();
+    let metadata = payload.get("metadata").unwrap();
+    let suppressed = metadata
+        .get("suppressed_duplicate_count")
+        .and_then(|v| v.as_u64())
+        .expect("expected dedup metadata for duplicate-heavy query");
+    assert!(suppressed > 0, "suppressed_duplicate_count should be > 0");
+}
+
+/// T128: locate_symbol safety truncation returns deterministic follow-up actions
+#[test]
+fn t128_locate_symbol_payload_safety_limit_has_followups() {
+    let tmp = tempfile::tempdir().unwrap();
+    let index_set = build_fixture_index(tmp.path());
+
+    let mut config = Config::default();
+    config.search.max_response_bytes = 64;
+    let workspace = Path::new("/tmp/fake-workspace");
+    let project_id = "test-repo";
+
     let request = make_request(
         "tools/call",
         json!({
-            "name": "get_file_outline",
+            "name": "locate_symbol",
             "arguments": {
-                "path": found_path
+                "name": "validate_token",
+                "detail_level": "context",
+                "limit": 10
             }
         }),
     );
+
     let response = handle_request_with_ctx(
         &request,
         &RequestContext {
@@ -5592,7 +7038,7 @@ fn t135_full_e2e_workflow() {
             index_set: Some(&index_set),
             schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
-            conn: Some(&conn),
+            conn: None,
             workspace,
             project_id,
             prewarm_status: &test_prewarm_status(),
@@ -5601,37 +7047,53 @@ fn t135_full_e2e_workflow() {
             progress_token: None,
         },
     );
-    assert!(response.error.is_none(), "get_file_outline should succeed");
+
+    assert!(response.error.is_none(), "expected success");
     let payload = extract_payload_from_response(&response);
-    assert!(
-        payload.get("symbols").is_some(),
-        "get_file_outline should return symbols"
+    let metadata = payload.get("metadata").unwrap();
+    assert_eq!(
+        metadata
+            .get("result_completeness")
+            .unwrap()
+            .as_str()
+            .unwrap(),
+        "truncated"
     );
-    let symbols = payload.get("symbols").unwrap().as_array().unwrap();
+    assert_eq!(metadata.get("safety_limit_applied"), Some(&json!(true)));
+
+    let actions = payload
+        .get("suggested_next_actions")
+        .and_then(|v| v.as_array())
+        .expect("locate_symbol truncation should provide suggested_next_actions");
     assert!(
-        !symbols.is_empty(),
-        "get_file_outline should return at least one symbol"
+        actions.len() >= 2,
+        "locate_symbol truncation should provide deterministic follow-ups"
     );
-
-    // Step 4: search_code with detail_level: "context"
-    let request = make_request(
-        "tools/call",
-        json!({
-            "name": "search_code",
-            "arguments": {
-                "query": "validate_token",
-                "detail_level": "context"
-            }
-        }),
+    assert_eq!(
+        actions[0].get("tool").and_then(|v| v.as_str()),
+        Some("locate_symbol")
     );
+}
+
+// ------------------------------------------------------------------
+// T134: tools/list schema verification for get_file_outline + health_check
+// ------------------------------------------------------------------
+
+#[test]
+fn t134_tools_list_schema_verification() {
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+    let project_id = "fake_project_id";
+
+    let request = make_request("tools/list", json!({}));
     let response = handle_request_with_ctx(
         &request,
         &RequestContext {
             config: &config,
-            index_set: Some(&index_set),
-            schema_status: SchemaStatus::Compatible,
+            index_set: None,
+            schema_status: SchemaStatus::NotIndexed,
             compatibility_reason: None,
-            conn: Some(&conn),
+            conn: None,
             workspace,
             project_id,
             prewarm_status: &test_prewarm_status(),
@@ -5640,244 +7102,147 @@ fn t135_full_e2e_workflow() {
             progress_token: None,
         },
     );
-    assert!(
-        response.error.is_none(),
-        "search_code(context) should succeed"
-    );
-    let results = extract_results_from_response(&response);
-    assert!(!results.is_empty(), "search should find validate_token");
 
-    // Verify metadata conforms to Protocol v1
     let result = response.result.unwrap();
-    let content = result.get("content").unwrap().as_array().unwrap();
-    let text = content[0].get("text").unwrap().as_str().unwrap();
-    let payload: Value = serde_json::from_str(text).unwrap();
-    let meta = payload.get("metadata").unwrap();
+    let tools = result.get("tools").unwrap().as_array().unwrap();
+
+    // Verify get_file_outline schema
+    let outline = tools
+        .iter()
+        .find(|t| t.get("name").unwrap().as_str().unwrap() == "get_file_outline")
+        .expect("get_file_outline should be listed");
+    let outline_schema = outline.get("inputSchema").unwrap();
+    let outline_props = outline_schema.get("properties").unwrap();
     assert!(
-        meta.get("cruxe_protocol_version").is_some(),
-        "metadata should include protocol version"
+        outline_props.get("path").is_some(),
+        "get_file_outline should have 'path' property"
     );
+    let outline_required = outline_schema.get("required").unwrap().as_array().unwrap();
     assert!(
-        meta.get("freshness_status").is_some(),
-        "metadata should include freshness_status"
+        outline_required.contains(&json!("path")),
+        "get_file_outline should require 'path'"
     );
+
+    // Verify health_check schema
+    let health = tools
+        .iter()
+        .find(|t| t.get("name").unwrap().as_str().unwrap() == "health_check")
+        .expect("health_check should be listed");
+    let health_schema = health.get("inputSchema").unwrap();
+    let health_props = health_schema.get("properties").unwrap();
     assert!(
-        meta.get("schema_status").is_some(),
-        "metadata should include schema_status"
+        health_props.get("workspace").is_some(),
+        "health_check should have 'workspace' property"
     );
-}
-
-#[test]
-fn t190_new_navigation_tools_jsonrpc_e2e() {
-    let tmp = tempfile::tempdir().unwrap();
-    let index_set = build_fixture_index(tmp.path());
-    let db_path = tmp.path().join("data/state.db");
-    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
-    let config = Config::default();
-    let workspace = Path::new("/tmp/fake-workspace");
-    let project_id = "test-repo";
 
-    let hierarchy_request = make_request(
-        "tools/call",
-        json!({
-            "name": "get_symbol_hierarchy",
-            "arguments": {
-                "symbol_name": "authenticate",
-                "path": "src/handler.rs",
-                "direction": "ancestors"
-            }
-        }),
-    );
-    let hierarchy_response = handle_request_with_ctx(
-        &hierarchy_request,
-        &RequestContext {
-            config: &config,
-            index_set: Some(&index_set),
-            schema_status: SchemaStatus::Compatible,
-            compatibility_reason: None,
-            conn: Some(&conn),
-            workspace,
-            project_id,
-            prewarm_status: &test_prewarm_status(),
-            server_start: &test_server_start(),
-            notifier: Arc::new(NullProgressNotifier),
-            progress_token: None,
-        },
+    let hierarchy = tools
+        .iter()
+        .find(|t| t.get("name").unwrap().as_str().unwrap() == "get_symbol_hierarchy")
+        .expect("get_symbol_hierarchy should be listed");
+    let hierarchy_schema = hierarchy.get("inputSchema").unwrap();
+    let hierarchy_props = hierarchy_schema.get("properties").unwrap();
+    assert!(hierarchy_props.get("symbol_name").is_some());
+    assert!(
+        hierarchy_props
+            .get("direction")
+            .and_then(|v| v.get("enum"))
+            .is_some(),
+        "get_symbol_hierarchy direction should define enum values"
     );
     assert!(
-        hierarchy_response.error.is_none(),
-        "hierarchy tool should succeed"
+        hierarchy_props.get("compact").is_none(),
+        "003 tools must not expose compact parameter in this phase"
     );
-    let hierarchy_payload = extract_payload_from_response(&hierarchy_response);
-    assert!(hierarchy_payload.get("hierarchy").unwrap().is_array());
-    assert!(hierarchy_payload.get("metadata").is_some());
+    // `symbol_name` and `module` are alternatives, validated by the handler.
+    assert!(hierarchy_props.get("module").is_some());
+    assert!(hierarchy_schema.get("required").is_none());
 
-    let related_request = make_request(
-        "tools/call",
-        json!({
-            "name": "find_related_symbols",
-            "arguments": {
-                "symbol_name": "authenticate",
-                "path": "src/handler.rs",
-                "scope": "file",
-                "limit": 10
-            }
-        }),
-    );
-    let related_response = handle_request_with_ctx(
-        &related_request,
-        &RequestContext {
-            config: &config,
-            index_set: Some(&index_set),
-            schema_status: SchemaStatus::Compatible,
-            compatibility_reason: None,
-            conn: Some(&conn),
-            workspace,
-            project_id,
-            prewarm_status: &test_prewarm_status(),
-            server_start: &test_server_start(),
-            notifier: Arc::new(NullProgressNotifier),
-            progress_token: None,
-        },
-    );
+    let related = tools
+        .iter()
+        .find(|t| t.get("name").unwrap().as_str().unwrap() == "find_related_symbols")
+        .expect("find_related_symbols should be listed");
+    let related_schema = related.get("inputSchema").unwrap();
+    let related_props = related_schema.get("properties").unwrap();
+    assert!(related_props.get("scope").is_some());
+    assert!(related_props.get("limit").is_some());
     assert!(
-        related_response.error.is_none(),
-        "related tool should succeed"
+        related_props.get("compact").is_none(),
+        "003 tools must not expose compact parameter in this phase"
     );
-    let related_payload = extract_payload_from_response(&related_response);
-    assert!(related_payload.get("anchor").is_some());
-    assert!(related_payload.get("related").unwrap().is_array());
-    assert!(related_payload.get("metadata").is_some());
+    let related_required = related_schema.get("required").unwrap().as_array().unwrap();
+    assert!(related_required.contains(&json!("symbol_name")));
 
-    let context_request = make_request(
-        "tools/call",
-        json!({
-            "name": "get_code_context",
-            "arguments": {
-                "query": "validate_token",
-                "strategy": "breadth",
-                "max_tokens": 300,
-                "language": "rust"
-            }
-        }),
-    );
-    let context_response = handle_request_with_ctx(
-        &context_request,
-        &RequestContext {
-            config: &config,
-            index_set: Some(&index_set),
-            schema_status: SchemaStatus::Compatible,
-            compatibility_reason: None,
-            conn: Some(&conn),
-            workspace,
-            project_id,
-            prewarm_status: &test_prewarm_status(),
-            server_start: &test_server_start(),
-            notifier: Arc::new(NullProgressNotifier),
-            progress_token: None,
-        },
-    );
-    assert!(
-        context_response.error.is_none(),
-        "context tool should succeed"
-    );
-    let context_payload = extract_payload_from_response(&context_response);
-    assert!(context_payload.get("context_items").unwrap().is_array());
+    let context = tools
+        .iter()
+        .find(|t| t.get("name").unwrap().as_str().unwrap() == "get_code_context")
+        .expect("get_code_context should be listed");
+    let context_schema = context.get("inputSchema").unwrap();
+    let context_props = context_schema.get("properties").unwrap();
+    assert!(context_props.get("query").is_some());
+    assert!(context_props.get("max_tokens").is_some());
+    assert!(context_props.get("strategy").is_some());
     assert!(
-        context_payload
-            .get("estimated_tokens")
-            .unwrap()
-            .as_u64()
-            .unwrap()
-            <= 300,
-        "context estimated_tokens should honor max_tokens"
-    );
-    assert!(context_payload.get("metadata").is_some());
-}
-
-// ------------------------------------------------------------------
-// T139: Backward compatibility - default detail_level is "signature"
-// ------------------------------------------------------------------
-
-#[test]
-fn t139_backward_compatibility_default_detail_level() {
-    let tmp = tempfile::tempdir().unwrap();
-    let index_set = build_fixture_index(tmp.path());
-
-    let config = Config::default();
-    let workspace = Path::new("/tmp/fake-workspace");
-    let project_id = "test_project";
-
-    // Call locate_symbol without detail_level parameter
-    let request = make_request(
-        "tools/call",
-        json!({
-            "name": "locate_symbol",
-            "arguments": {
-                "name": "validate_token"
-            }
-        }),
-    );
-    let response = handle_request_with_ctx(
-        &request,
-        &RequestContext {
-            config: &config,
-            index_set: Some(&index_set),
-            schema_status: SchemaStatus::Compatible,
-            compatibility_reason: None,
-            conn: None,
-            workspace,
-            project_id,
-            prewarm_status: &test_prewarm_status(),
-            server_start: &test_server_start(),
-            notifier: Arc::new(NullProgressNotifier),
-            progress_token: None,
-        },
+        context_props.get("policy_mode").is_some(),
+        "get_code_context should expose policy_mode override"
     );
     assert!(
-        response.error.is_none(),
-        "locate_symbol without detail_level should succeed"
+        context_props.get("compact").is_none(),
+        "003 tools must not expose compact parameter in this phase"
     );
-    let results = extract_results_from_response(&response);
-    assert!(!results.is_empty(), "should find validate_token");
+    // `query` is optional because `path` + `line` selects line-window mode.
+    assert!(context_props.get("line").is_some());
+    assert!(context_props.get("context_lines").is_some());
+    assert!(context_schema.get("required").is_none());
 
-    let vt = results
+    let context_pack = tools
         .iter()
-        .find(|r| r.get("name").unwrap().as_str().unwrap() == "validate_token")
+        .find(|t| t.get("name").unwrap().as_str().unwrap() == "build_context_pack")
+        .expect("build_context_pack should be listed");
+    let context_pack_schema = context_pack.get("inputSchema").unwrap();
+    let context_pack_props = context_pack_schema.get("properties").unwrap();
+    assert!(context_pack_props.get("query").is_some());
+    assert!(context_pack_props.get("budget_tokens").is_some());
+    assert!(context_pack_props.get("mode").is_some());
+    assert!(context_pack_props.get("section_caps").is_some());
+    let context_pack_required = context_pack_schema
+        .get("required")
+        .unwrap()
+        .as_array()
         .unwrap();
+    assert!(context_pack_required.contains(&json!("query")));
 
-    // Signature-level fields should be present (default)
+    let search = tools
+        .iter()
+        .find(|t| t.get("name").unwrap().as_str().unwrap() == "search_code")
+        .expect("search_code should be listed");
+    let search_schema = search.get("inputSchema").unwrap();
+    let search_props = search_schema.get("properties").unwrap();
     assert!(
-        vt.get("qualified_name").is_some(),
-        "default should include qualified_name (signature level)"
+        search_props.get("semantic_ratio").is_some(),
+        "search_code should expose semantic_ratio override"
     );
     assert!(
-        vt.get("language").is_some(),
-        "default should include language (signature level)"
+        search_props.get("confidence_threshold").is_some(),
+        "search_code should expose confidence_threshold override"
     );
-
-    // Context-only fields should NOT be present
     assert!(
-        vt.get("body_preview").is_none(),
-        "default should NOT include body_preview (context only)"
+        search_props.get("policy_mode").is_some(),
+        "search_code should expose policy_mode override"
     );
+}
 
-    // Same test for search_code
-    let request = make_request(
-        "tools/call",
-        json!({
-            "name": "search_code",
-            "arguments": {
-                "query": "validate_token"
-            }
-        }),
-    );
+#[test]
+fn t319_tools_list_includes_vcs_ga_tool_schemas() {
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+    let project_id = "fake_project_id";
+    let request = make_request("tools/list", json!({}));
     let response = handle_request_with_ctx(
         &request,
         &RequestContext {
             config: &config,
-            index_set: Some(&index_set),
-            schema_status: SchemaStatus::Compatible,
+            index_set: None,
+            schema_status: SchemaStatus::NotIndexed,
             compatibility_reason: None,
             conn: None,
             workspace,
@@ -5888,460 +7253,334 @@ fn t139_backward_compatibility_default_detail_level() {
             progress_token: None,
         },
     );
-    assert!(
-        response.error.is_none(),
-        "search_code without detail_level should succeed"
-    );
-    let results = extract_results_from_response(&response);
-    assert!(!results.is_empty(), "search should find results");
-
-    // Verify metadata is present
+    assert!(response.error.is_none(), "tools/list should succeed");
     let result = response.result.unwrap();
-    let content = result.get("content").unwrap().as_array().unwrap();
-    let text = content[0].get("text").unwrap().as_str().unwrap();
-    let payload: Value = serde_json::from_str(text).unwrap();
-    assert!(
-        payload.get("metadata").is_some(),
-        "response should include metadata for backward compatibility"
-    );
-}
-
-// ------------------------------------------------------------------
-// T138: Performance smoke guard (strict p95 thresholds moved to benchmark harness)
-// ------------------------------------------------------------------
-
-#[test]
-fn t138_performance_smoke_guard() {
-    let tmp = tempfile::tempdir().unwrap();
-    let index_set = build_fixture_index(tmp.path());
-
-    let db_path = tmp.path().join("data/state.db");
-    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
-
-    let config = Config::default();
-    let workspace = Path::new("/tmp/fake-workspace");
-    let project_id = "test-repo";
+    let tools = result.get("tools").unwrap().as_array().unwrap();
 
-    // Smoke: repeated get_file_outline calls should remain responsive.
-    let mut outline_times = Vec::new();
-    for _ in 0..10 {
-        let request = make_request(
-            "tools/call",
-            json!({
-                "name": "get_file_outline",
-                "arguments": {
-                    "path": "src/auth.rs"
-                }
-            }),
-        );
-        let start = std::time::Instant::now();
-        let response = handle_request_with_ctx(
-            &request,
-            &RequestContext {
-                config: &config,
-                index_set: Some(&index_set),
-                schema_status: SchemaStatus::Compatible,
-                compatibility_reason: None,
-                conn: Some(&conn),
-                workspace,
-                project_id,
-                prewarm_status: &test_prewarm_status(),
-                server_start: &test_server_start(),
-                notifier: Arc::new(NullProgressNotifier),
-                progress_token: None,
-            },
+    for tool_name in [
+        "diff_context",
+        "find_references",
+        "explain_ranking",
+        "list_refs",
+        "switch_ref",
+    ] {
+        let tool = tools
+            .iter()
+            .find(|item| item.get("name").and_then(|v| v.as_str()) == Some(tool_name))
+            .unwrap_or_else(|| panic!("{tool_name} should be present in tools/list"));
+        let schema = tool.get("inputSchema").unwrap();
+        assert!(
+            schema
+                .get("properties")
+                .and_then(|v| v.get("workspace"))
+                .is_some(),
+            "{tool_name} should expose workspace in input schema"
         );
-        let elapsed = start.elapsed();
-        outline_times.push(elapsed);
-        assert!(response.error.is_none(), "get_file_outline should succeed");
     }
-    outline_times.sort();
-    let p95_outline = outline_times[8];
-    assert!(
-        p95_outline.as_millis() < 5_000,
-        "get_file_outline smoke budget should remain < 5000ms, got {}ms",
-        p95_outline.as_millis()
-    );
+}
 
-    // Benchmark first-query latency: search_code after prewarm
-    let request = make_request(
-        "tools/call",
-        json!({
-            "name": "search_code",
-            "arguments": {
-                "query": "validate_token"
-            }
+fn setup_vcs_project_for_ref_tools(
+    workspace: &Path,
+    conn: &rusqlite::Connection,
+    project_id: &str,
+) {
+    ensure_git_workspace_with_refs(workspace);
+
+    let now = "2026-02-25T00:00:00Z".to_string();
+    let project = Project {
+        project_id: project_id.to_string(),
+        repo_root: workspace.to_string_lossy().to_string(),
+        display_name: Some("ref-tools".to_string()),
+        default_ref: "main".to_string(),
+        vcs_mode: true,
+        schema_version: 1,
+        parser_version: 1,
+        created_at: now.clone(),
+        updated_at: now.clone(),
+    };
+    cruxe_state::project::create_project(conn, &project).unwrap();
+    cruxe_state::branch_state::upsert_branch_state(
+        conn,
+        &cruxe_state::branch_state::BranchState {
+            repo: project_id.to_string(),
+            r#ref: "main".to_string(),
+            merge_base_commit: None,
+            last_indexed_commit: "abc123".to_string(),
+            overlay_dir: None,
+            file_count: 10,
+            symbol_count: 30,
+            is_default_branch: true,
+            status: "active".to_string(),
+            eviction_eligible_at: None,
+            created_at: now.clone(),
+            last_accessed_at: now.clone(),
+        },
+    )
+    .unwrap();
+    cruxe_state::branch_state::upsert_branch_state(
+        conn,
+        &cruxe_state::branch_state::BranchState {
+            repo: project_id.to_string(),
+            r#ref: "feat/auth".to_string(),
+            merge_base_commit: Some("abc123".to_string()),
+            last_indexed_commit: "def456".to_string(),
+            overlay_dir: Some("overlay/feat-auth".to_string()),
+            file_count: 4,
+            symbol_count: 9,
+            is_default_branch: false,
+            status: "active".to_string(),
+            eviction_eligible_at: None,
+            created_at: now.clone(),
+            last_accessed_at: now,
+        },
+    )
+    .unwrap();
+}
+
+fn ensure_git_workspace_with_refs(workspace: &Path) {
+    if workspace.join(".git").exists() {
+        return;
+    }
+    std::fs::create_dir_all(workspace).unwrap();
+
+    run_git(workspace, &["init"]);
+    run_git(workspace, &["config", "user.email", "tests@example.com"]);
+    run_git(workspace, &["config", "user.name", "Cruxe Tests"]);
+
+    std::fs::write(workspace.join("README.md"), "vcs tool test fixture\n").unwrap();
+    run_git(workspace, &["add", "."]);
+    run_git(workspace, &["commit", "-m", "fixture init"]);
+
+    let head = run_git_capture(workspace, &["rev-parse", "--abbrev-ref", "HEAD"]);
+    if head != "main" {
+        run_git(workspace, &["branch", "-m", "main"]);
+    }
+    run_git(workspace, &["branch", "feat/auth"]);
+}
+
+fn run_git(workspace: &Path, args: &[&str]) {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(workspace)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "git {:?} failed: stdout={} stderr={}",
+        args,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+fn run_git_capture(workspace: &Path, args: &[&str]) -> String {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(workspace)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "git {:?} failed: stdout={} stderr={}",
+        args,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+#[test]
+fn t309_list_refs_returns_branch_state_rows() {
+    let tmp = tempfile::tempdir().unwrap();
+    let workspace = tmp.path().join("workspace");
+    std::fs::create_dir_all(&workspace).unwrap();
+    let mut config = Config::default();
+    config.storage.data_dir = tmp.path().join("cc-data").to_string_lossy().to_string();
+    let project_id = generate_project_id(&workspace.to_string_lossy());
+    let data_dir = config.project_data_dir(&project_id);
+    let conn =
+        cruxe_state::db::open_connection(&data_dir.join(cruxe_core::constants::STATE_DB_FILE))
+            .unwrap();
+    cruxe_state::schema::create_tables(&conn).unwrap();
+    setup_vcs_project_for_ref_tools(&workspace, &conn, &project_id);
+
+    let request = make_request(
+        "tools/call",
+        json!({
+            "name": "list_refs",
+            "arguments": {}
         }),
     );
-    let start = std::time::Instant::now();
     let response = handle_request_with_ctx(
         &request,
         &RequestContext {
             config: &config,
-            index_set: Some(&index_set),
+            index_set: None,
             schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
             conn: Some(&conn),
-            workspace,
-            project_id,
+            workspace: &workspace,
+            project_id: &project_id,
             prewarm_status: &test_prewarm_status(),
             server_start: &test_server_start(),
             notifier: Arc::new(NullProgressNotifier),
             progress_token: None,
         },
     );
-    let elapsed = start.elapsed();
-    assert!(response.error.is_none(), "search_code should succeed");
+
+    assert!(response.error.is_none(), "list_refs should succeed");
+    let payload = extract_payload_from_response(&response);
+    let refs = payload.get("refs").unwrap().as_array().unwrap();
+    assert_eq!(refs.len(), 2);
     assert!(
-        elapsed.as_millis() < 5_000,
-        "first-query smoke budget should remain < 5000ms, got {}ms",
-        elapsed.as_millis()
+        refs.iter()
+            .any(|value| value.get("ref") == Some(&json!("main")))
+    );
+    assert!(
+        refs.iter()
+            .any(|value| value.get("ref") == Some(&json!("feat/auth")))
     );
 }
 
 #[test]
-#[ignore = "benchmark harness"]
-fn benchmark_t138_get_file_outline_p95_under_50ms() {
+fn t310_switch_ref_updates_session_ref_without_mutating_project_default() {
     let tmp = tempfile::tempdir().unwrap();
-    let index_set = build_fixture_index(tmp.path());
+    let workspace = tmp.path().join("workspace");
+    std::fs::create_dir_all(&workspace).unwrap();
+    let mut config = Config::default();
+    config.storage.data_dir = tmp.path().join("cc-data").to_string_lossy().to_string();
+    let project_id = generate_project_id(&workspace.to_string_lossy());
+    let data_dir = config.project_data_dir(&project_id);
+    let conn =
+        cruxe_state::db::open_connection(&data_dir.join(cruxe_core::constants::STATE_DB_FILE))
+            .unwrap();
+    cruxe_state::schema::create_tables(&conn).unwrap();
+    setup_vcs_project_for_ref_tools(&workspace, &conn, &project_id);
 
-    let db_path = tmp.path().join("data/state.db");
-    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
-    let config = Config::default();
-    let workspace = Path::new("/tmp/fake-workspace");
-    let project_id = "test-repo";
+    let request = make_request(
+        "tools/call",
+        json!({
+            "name": "switch_ref",
+            "arguments": {
+                "ref": "feat/auth"
+            }
+        }),
+    );
+    let response = handle_request_with_ctx(
+        &request,
+        &RequestContext {
+            config: &config,
+            index_set: None,
+            schema_status: SchemaStatus::Compatible,
+            compatibility_reason: None,
+            conn: Some(&conn),
+            workspace: &workspace,
+            project_id: &project_id,
+            prewarm_status: &test_prewarm_status(),
+            server_start: &test_server_start(),
+            notifier: Arc::new(NullProgressNotifier),
+            progress_token: None,
+        },
+    );
+    assert!(response.error.is_none(), "switch_ref should succeed");
 
-    let mut outline_times = Vec::new();
-    for _ in 0..10 {
-        let request = make_request(
-            "tools/call",
-            json!({
-                "name": "get_file_outline",
-                "arguments": {
-                    "path": "src/auth.rs"
-                }
-            }),
-        );
-        let start = std::time::Instant::now();
-        let response = handle_request_with_ctx(
-            &request,
-            &RequestContext {
-                config: &config,
-                index_set: Some(&index_set),
-                schema_status: SchemaStatus::Compatible,
-                compatibility_reason: None,
-                conn: Some(&conn),
-                workspace,
-                project_id,
-                prewarm_status: &test_prewarm_status(),
-                server_start: &test_server_start(),
-                notifier: Arc::new(NullProgressNotifier),
-                progress_token: None,
-            },
-        );
-        assert!(response.error.is_none(), "get_file_outline should succeed");
-        outline_times.push(start.elapsed());
-    }
-    outline_times.sort();
-    let p95_outline = outline_times[8];
-    assert!(
-        p95_outline.as_millis() < 50,
-        "get_file_outline benchmark p95 should be < 50ms, got {}ms",
-        p95_outline.as_millis()
+    let payload = extract_payload_from_response(&response);
+    assert_eq!(payload.get("ref"), Some(&json!("feat/auth")));
+    assert_eq!(payload.get("previous_ref"), Some(&json!("main")));
+
+    let project_row = cruxe_state::project::get_by_id(&conn, &project_id)
+        .unwrap()
+        .expect("project should exist");
+    assert_eq!(project_row.default_ref, "main");
+    assert_eq!(
+        resolve_tool_ref(
+            None,
+            &workspace,
+            Some(&conn),
+            &project_id,
+            &Config::default()
+        ),
+        "feat/auth"
     );
 }
 
-// ------------------------------------------------------------------
-// Helper: extract the full JSON payload from an MCP tool response
-// ------------------------------------------------------------------
-
-fn extract_payload_from_response(response: &JsonRpcResponse) -> serde_json::Value {
-    let result = response.result.as_ref().expect("result should be present");
-    let content = result
-        .get("content")
-        .expect("result should have 'content'")
-        .as_array()
-        .expect("'content' should be an array");
-    let text = content[0].get("text").unwrap().as_str().unwrap();
-    serde_json::from_str(text).expect("text payload should be valid JSON")
-}
-
-// ------------------------------------------------------------------
-// Helper: build a fixture index inside a real git repo for freshness tests
-// ------------------------------------------------------------------
-
-fn build_fixture_index_in_git_repo(
-    tmp_dir: &std::path::Path,
-) -> (IndexSet, rusqlite::Connection, String) {
-    use cruxe_indexer::{languages, parser, scanner, snippet_extract, symbol_extract, writer};
-    use cruxe_state::{db, schema, tantivy_index::IndexSet};
-
-    let fixture_dir = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-        .join("../../testdata/fixtures/rust-sample");
-
-    // Initialize a git repo in tmp_dir and commit
-    let workspace = tmp_dir.join("workspace");
-    std::fs::create_dir_all(&workspace).unwrap();
-    std::process::Command::new("git")
-        .args(["init"])
-        .current_dir(&workspace)
-        .output()
-        .unwrap();
-    std::process::Command::new("git")
-        .args(["config", "user.email", "test@test.com"])
-        .current_dir(&workspace)
-        .output()
-        .unwrap();
-    std::process::Command::new("git")
-        .args(["config", "user.name", "Test"])
-        .current_dir(&workspace)
-        .output()
-        .unwrap();
-
-    // Copy fixture files into workspace (recursive)
-    fn copy_dir_recursive(src: &std::path::Path, dst: &std::path::Path) {
-        std::fs::create_dir_all(dst).unwrap();
-        for entry in std::fs::read_dir(src).unwrap() {
-            let entry = entry.unwrap();
-            let dest = dst.join(entry.file_name());
-            if entry.file_type().unwrap().is_dir() {
-                copy_dir_recursive(&entry.path(), &dest);
-            } else {
-                std::fs::copy(entry.path(), &dest).unwrap();
-            }
-        }
-    }
-    copy_dir_recursive(&fixture_dir, &workspace);
-
-    // Initial commit
-    std::process::Command::new("git")
-        .args(["add", "."])
-        .current_dir(&workspace)
-        .output()
-        .unwrap();
-    std::process::Command::new("git")
-        .args(["commit", "-m", "initial"])
-        .current_dir(&workspace)
-        .output()
-        .unwrap();
-
-    // Get the initial commit hash
-    let output = std::process::Command::new("git")
-        .args(["rev-parse", "--short=12", "HEAD"])
-        .current_dir(&workspace)
-        .output()
-        .unwrap();
-    let initial_commit = String::from_utf8(output.stdout).unwrap().trim().to_string();
-
-    // Build index
-    let data_dir = workspace.join(".cruxe/data");
-    std::fs::create_dir_all(&data_dir).unwrap();
-    let index_set = IndexSet::open(&data_dir).unwrap();
-
-    let db_path = data_dir.join("state.db");
-    let conn = db::open_connection(&db_path).unwrap();
-    schema::create_tables(&conn).unwrap();
-
-    let repo = "test-repo";
-    // Detect the current branch name
-    let branch_output = std::process::Command::new("git")
-        .args(["rev-parse", "--abbrev-ref", "HEAD"])
-        .current_dir(&workspace)
-        .output()
-        .unwrap();
-    let branch_name = String::from_utf8(branch_output.stdout)
-        .unwrap()
-        .trim()
-        .to_string();
-
-    let scanned = scanner::scan_directory(&workspace, 1_048_576);
-    for file in &scanned {
-        let source = std::fs::read_to_string(&file.path).unwrap();
-        let tree = match parser::parse_file(&source, &file.language) {
-            Ok(t) => t,
-            Err(_) => continue,
-        };
-
-        let extracted = languages::extract_symbols(&tree, &source, &file.language);
-        let symbols = symbol_extract::build_symbol_records(
-            &extracted,
-            repo,
-            &branch_name,
-            &file.relative_path,
-            None,
-        );
-        let snippets = snippet_extract::build_snippet_records(
-            &extracted,
-            repo,
-            &branch_name,
-            &file.relative_path,
-            None,
-        );
-
-        let content_hash = blake3::hash(source.as_bytes()).to_hex().to_string();
-        let filename = file.path.file_name().unwrap().to_string_lossy().to_string();
-        let file_record = cruxe_core::types::FileRecord {
-            repo: repo.to_string(),
-            r#ref: branch_name.clone(),
-            commit: None,
-            path: file.relative_path.clone(),
-            filename,
-            language: file.language.clone(),
-            content_hash,
-            size_bytes: source.len() as u64,
-            updated_at: "2026-01-01T00:00:00Z".to_string(),
-            content_head: source
-                .lines()
-                .take(10)
-                .collect::<Vec<_>>()
-                .join("\n")
-                .into(),
-        };
-
-        writer::write_file_records(&index_set, &conn, &symbols, &snippets, &file_record).unwrap();
-    }
-
-    // Store branch_state with initial commit
-    let branch_entry = cruxe_state::branch_state::BranchState {
-        repo: repo.to_string(),
-        r#ref: branch_name.clone(),
-        merge_base_commit: None,
-        last_indexed_commit: initial_commit,
-        overlay_dir: None,
-        file_count: scanned.len() as i64,
-        symbol_count: 0,
-        is_default_branch: false,
-        status: "active".to_string(),
-        eviction_eligible_at: None,
-        created_at: "2026-01-01T00:00:00Z".to_string(),
-        last_accessed_at: "2026-01-01T00:00:00Z".to_string(),
-    };
-    cruxe_state::branch_state::upsert_branch_state(&conn, &branch_entry).unwrap();
-
-    (index_set, conn, branch_name)
-}
-
-/// Make a new commit in the workspace to make the index stale.
-fn make_workspace_stale(workspace: &std::path::Path) {
-    let dummy = workspace.join("dummy.txt");
-    std::fs::write(&dummy, "stale marker").unwrap();
-    std::process::Command::new("git")
-        .args(["add", "dummy.txt"])
-        .current_dir(workspace)
-        .output()
-        .unwrap();
-    std::process::Command::new("git")
-        .args(["commit", "-m", "make stale"])
-        .current_dir(workspace)
-        .output()
-        .unwrap();
-}
-
-// ------------------------------------------------------------------
-// T131: balanced policy with stale index returns results + stale status
-// ------------------------------------------------------------------
-
 #[test]
-fn t131_search_code_balanced_policy_stale_index() {
+fn t311_switch_ref_rejects_unindexed_ref_with_guidance() {
     let tmp = tempfile::tempdir().unwrap();
-    let (index_set, conn, branch_name) = build_fixture_index_in_git_repo(tmp.path());
     let workspace = tmp.path().join("workspace");
-
-    // Make the index stale by creating a new commit
-    make_workspace_stale(&workspace);
-
-    let config = Config::default();
-    let project_id = "test-repo";
+    std::fs::create_dir_all(&workspace).unwrap();
+    let mut config = Config::default();
+    config.storage.data_dir = tmp.path().join("cc-data").to_string_lossy().to_string();
+    let project_id = generate_project_id(&workspace.to_string_lossy());
+    let data_dir = config.project_data_dir(&project_id);
+    let conn =
+        cruxe_state::db::open_connection(&data_dir.join(cruxe_core::constants::STATE_DB_FILE))
+            .unwrap();
+    cruxe_state::schema::create_tables(&conn).unwrap();
+    setup_vcs_project_for_ref_tools(&workspace, &conn, &project_id);
 
     let request = make_request(
         "tools/call",
         json!({
-            "name": "search_code",
+            "name": "switch_ref",
             "arguments": {
-                "query": "validate_token",
-                "ref": branch_name,
-                "freshness_policy": "balanced"
+                "ref": "feat/missing"
             }
         }),
     );
-
     let response = handle_request_with_ctx(
         &request,
         &RequestContext {
             config: &config,
-            index_set: Some(&index_set),
+            index_set: None,
             schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
             conn: Some(&conn),
             workspace: &workspace,
-            project_id,
+            project_id: &project_id,
             prewarm_status: &test_prewarm_status(),
             server_start: &test_server_start(),
             notifier: Arc::new(NullProgressNotifier),
             progress_token: None,
         },
     );
-
     assert!(
         response.error.is_none(),
-        "expected success, got error: {:?}",
-        response.error
+        "switch_ref returns tool-level error envelope"
     );
     let payload = extract_payload_from_response(&response);
-
-    // Should have results (query still runs)
-    let results = payload.get("results").unwrap().as_array().unwrap();
+    let error = payload.get("error").expect("error payload should exist");
+    assert_eq!(error.get("code"), Some(&json!("ref_not_indexed")));
     assert!(
-        !results.is_empty(),
-        "balanced policy should return results even when stale"
-    );
-
-    // Metadata should show stale
-    let meta = payload.get("metadata").unwrap();
-    assert_eq!(
-        meta.get("freshness_status").unwrap().as_str().unwrap(),
-        "stale",
-        "freshness_status should be 'stale' for balanced policy with stale index"
+        error
+            .get("data")
+            .and_then(|value| value.get("remediation"))
+            .and_then(|value| value.as_str())
+            .is_some_and(|value| value.contains("sync_repo")),
+        "expected remediation guidance"
     );
 }
 
 // ------------------------------------------------------------------
-// T132: strict policy with stale index returns index_stale error
+// T207: Contract guard – all query/path tools expose `workspace` param
 // ------------------------------------------------------------------
 
 #[test]
-fn t132_search_code_strict_policy_stale_index_blocks() {
-    let tmp = tempfile::tempdir().unwrap();
-    let (index_set, conn, branch_name) = build_fixture_index_in_git_repo(tmp.path());
-    let workspace = tmp.path().join("workspace");
-
-    // Make the index stale
-    make_workspace_stale(&workspace);
-
+fn t207_all_tools_expose_workspace_parameter() {
     let config = Config::default();
-    let project_id = "test-repo";
-
-    let request = make_request(
-        "tools/call",
-        json!({
-            "name": "search_code",
-            "arguments": {
-                "query": "validate_token",
-                "ref": branch_name,
-                "freshness_policy": "strict"
-            }
-        }),
-    );
+    let workspace = Path::new("/tmp/fake-workspace");
+    let project_id = "fake_project_id";
 
+    let request = make_request("tools/list", json!({}));
     let response = handle_request_with_ctx(
         &request,
         &RequestContext {
             config: &config,
-            index_set: Some(&index_set),
-            schema_status: SchemaStatus::Compatible,
+            index_set: None,
+            schema_status: SchemaStatus::NotIndexed,
             compatibility_reason: None,
-            conn: Some(&conn),
-            workspace: &workspace,
+            conn: None,
+            workspace,
             project_id,
             prewarm_status: &test_prewarm_status(),
             server_start: &test_server_start(),
@@ -6350,86 +7589,72 @@ fn t132_search_code_strict_policy_stale_index_blocks() {
         },
     );
 
-    // The response should be a "success" at JSON-RPC level but contain an error payload
-    assert!(
-        response.error.is_none(),
-        "should be JSON-RPC success (error is in payload)"
-    );
-    let payload = extract_payload_from_response(&response);
-
-    // Should have error object instead of results
-    let error = payload
-        .get("error")
-        .expect("payload should have 'error' for strict+stale");
-    assert_eq!(
-        error.get("code").unwrap().as_str().unwrap(),
-        "index_stale",
-        "error code should be 'index_stale'"
-    );
-    assert!(
-        error
-            .get("message")
-            .unwrap()
-            .as_str()
-            .unwrap()
-            .contains("stale"),
-        "error message should mention stale"
-    );
-    let data = error.get("data").unwrap();
-    assert!(
-        data.get("last_indexed_commit").is_some(),
-        "error data should include last_indexed_commit"
-    );
-    assert!(
-        data.get("current_head").is_some(),
-        "error data should include current_head"
-    );
-    assert!(
-        data.get("suggestion").is_some(),
-        "error data should include suggestion"
-    );
+    assert!(response.error.is_none(), "tools/list should succeed");
+    let result = response.result.expect("result should be present");
+    let tools = result
+        .get("tools")
+        .expect("result should contain 'tools'")
+        .as_array()
+        .expect("'tools' should be an array");
 
-    // Metadata should show stale
-    let meta = payload.get("metadata").unwrap();
-    assert_eq!(
-        meta.get("freshness_status").unwrap().as_str().unwrap(),
-        "stale"
-    );
+    // All tools must expose a `workspace` property in their inputSchema
+    for tool in tools {
+        let name = tool.get("name").unwrap().as_str().unwrap();
+        let schema = tool
+            .get("inputSchema")
+            .expect("tool should have inputSchema");
+        let props = schema
+            .get("properties")
+            .unwrap_or_else(|| panic!("tool '{name}' inputSchema missing 'properties'"));
+        assert!(
+            props.get("workspace").is_some(),
+            "tool '{name}' is missing 'workspace' parameter in inputSchema"
+        );
+        // Verify workspace is typed as string
+        let ws_prop = props.get("workspace").unwrap();
+        assert_eq!(
+            ws_prop.get("type").unwrap().as_str().unwrap(),
+            "string",
+            "tool '{name}' workspace parameter should be type 'string'"
+        );
+    }
 }
 
+// ------------------------------------------------------------------
+// T135: Full E2E workflow test
+// ------------------------------------------------------------------
+
 #[test]
-fn t470_compare_symbol_between_commits_strict_policy_stale_index_blocks() {
+fn t135_full_e2e_workflow() {
     let tmp = tempfile::tempdir().unwrap();
-    let (_index_set, conn, branch_name) = build_fixture_index_in_git_repo(tmp.path());
-    let workspace = tmp.path().join("workspace");
+    let index_set = build_fixture_index(tmp.path());
 
-    // Make the index stale
-    make_workspace_stale(&workspace);
+    // Open a DB connection for outline queries
+    let db_path = tmp.path().join("data/state.db");
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
 
     let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+    // Must match the repo name used in build_fixture_index
     let project_id = "test-repo";
+
+    // Step 1: health_check
     let request = make_request(
         "tools/call",
         json!({
-            "name": "compare_symbol_between_commits",
-            "arguments": {
-                "symbol_name": "validate_token",
-                "base_ref": branch_name,
-                "head_ref": branch_name,
-                "freshness_policy": "strict"
-            }
+            "name": "health_check",
+            "arguments": {}
         }),
     );
-
     let response = handle_request_with_ctx(
         &request,
         &RequestContext {
             config: &config,
-            index_set: None,
+            index_set: Some(&index_set),
             schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
             conn: Some(&conn),
-            workspace: &workspace,
+            workspace,
             project_id,
             prewarm_status: &test_prewarm_status(),
             server_start: &test_server_start(),
@@ -6437,77 +7662,34 @@ fn t470_compare_symbol_between_commits_strict_policy_stale_index_blocks() {
             progress_token: None,
         },
     );
-
-    assert!(
-        response.error.is_none(),
-        "should be JSON-RPC success (error is in payload)"
-    );
+    assert!(response.error.is_none(), "health_check should succeed");
     let payload = extract_payload_from_response(&response);
-    let error = payload
-        .get("error")
-        .expect("payload should have 'error' for strict+stale compare");
     assert_eq!(
-        error.get("code").and_then(|value| value.as_str()),
-        Some("index_stale")
+        payload.get("status").unwrap().as_str().unwrap(),
+        "ready",
+        "health_check should report 'ready'"
     );
-}
-
-#[test]
-fn t471_compare_symbol_between_commits_strict_policy_stale_base_ref_blocks() {
-    let tmp = tempfile::tempdir().unwrap();
-    let (_index_set, conn, base_ref) = build_fixture_index_in_git_repo(tmp.path());
-    let workspace = tmp.path().join("workspace");
-
-    // Make current branch stale. This affects `base_ref`.
-    make_workspace_stale(&workspace);
-
-    // Register a second indexed ref so the compare precheck passes for both refs.
-    // This ref is not the checked-out branch, so freshness check treats it as fresh.
-    let head_ref = "feat/fresh";
-    let head_commit = cruxe_core::vcs::detect_head_commit(&workspace).unwrap();
-    cruxe_state::branch_state::upsert_branch_state(
-        &conn,
-        &cruxe_state::branch_state::BranchState {
-            repo: "test-repo".to_string(),
-            r#ref: head_ref.to_string(),
-            merge_base_commit: None,
-            last_indexed_commit: head_commit,
-            overlay_dir: None,
-            file_count: 0,
-            symbol_count: 0,
-            is_default_branch: false,
-            status: "active".to_string(),
-            eviction_eligible_at: None,
-            created_at: "2026-01-01T00:00:00Z".to_string(),
-            last_accessed_at: "2026-01-01T00:00:00Z".to_string(),
-        },
-    )
-    .unwrap();
 
-    let config = Config::default();
-    let project_id = "test-repo";
+    // Step 2: locate_symbol with detail_level: "location"
     let request = make_request(
         "tools/call",
         json!({
-            "name": "compare_symbol_between_commits",
+            "name": "locate_symbol",
             "arguments": {
-                "symbol_name": "validate_token",
-                "base_ref": base_ref,
-                "head_ref": head_ref,
-                "freshness_policy": "strict"
+                "name": "validate_token",
+                "detail_level": "location"
             }
         }),
     );
-
     let response = handle_request_with_ctx(
         &request,
         &RequestContext {
             config: &config,
-            index_set: None,
+            index_set: Some(&index_set),
             schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
             conn: Some(&conn),
-            workspace: &workspace,
+            workspace,
             project_id,
             prewarm_status: &test_prewarm_status(),
             server_start: &test_server_start(),
@@ -6515,49 +7697,33 @@ fn t471_compare_symbol_between_commits_strict_policy_stale_base_ref_blocks() {
             progress_token: None,
         },
     );
-
     assert!(
         response.error.is_none(),
-        "should be JSON-RPC success (error is in payload)"
+        "locate_symbol(location) should succeed"
     );
-    let payload = extract_payload_from_response(&response);
-    let error = payload
-        .get("error")
-        .expect("payload should have 'error' for strict+stale base ref");
-    assert_eq!(
-        error.get("code").and_then(|value| value.as_str()),
-        Some("index_stale")
+    let results = extract_results_from_response(&response);
+    assert!(!results.is_empty(), "locate should find validate_token");
+    let vt = &results[0];
+    assert!(
+        vt.get("path").is_some(),
+        "location level should include path"
+    );
+    assert!(
+        vt.get("qualified_name").is_none(),
+        "location level should NOT include qualified_name"
     );
-}
-
-// ------------------------------------------------------------------
-// T133: best_effort policy with stale index returns results + stale
-// ------------------------------------------------------------------
-
-#[test]
-fn t133_search_code_best_effort_policy_stale_index() {
-    let tmp = tempfile::tempdir().unwrap();
-    let (index_set, conn, branch_name) = build_fixture_index_in_git_repo(tmp.path());
-    let workspace = tmp.path().join("workspace");
-
-    // Make the index stale
-    make_workspace_stale(&workspace);
-
-    let config = Config::default();
-    let project_id = "test-repo";
 
+    // Step 3: get_file_outline for the found file
+    let found_path = vt.get("path").unwrap().as_str().unwrap();
     let request = make_request(
         "tools/call",
         json!({
-            "name": "search_code",
+            "name": "get_file_outline",
             "arguments": {
-                "query": "validate_token",
-                "ref": branch_name,
-                "freshness_policy": "best_effort"
+                "path": found_path
             }
         }),
     );
-
     let response = handle_request_with_ctx(
         &request,
         &RequestContext {
@@ -6566,7 +7732,7 @@ fn t133_search_code_best_effort_policy_stale_index() {
             schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
             conn: Some(&conn),
-            workspace: &workspace,
+            workspace,
             project_id,
             prewarm_status: &test_prewarm_status(),
             server_start: &test_server_start(),
@@ -6574,47 +7740,26 @@ fn t133_search_code_best_effort_policy_stale_index() {
             progress_token: None,
         },
     );
-
-    assert!(response.error.is_none(), "expected success");
+    assert!(response.error.is_none(), "get_file_outline should succeed");
     let payload = extract_payload_from_response(&response);
-
-    // Should have results (best_effort always returns)
-    let results = payload.get("results").unwrap().as_array().unwrap();
     assert!(
-        !results.is_empty(),
-        "best_effort policy should return results even when stale"
+        payload.get("symbols").is_some(),
+        "get_file_outline should return symbols"
     );
-
-    // Metadata should show stale
-    let meta = payload.get("metadata").unwrap();
-    assert_eq!(
-        meta.get("freshness_status").unwrap().as_str().unwrap(),
-        "stale",
-        "freshness_status should be 'stale' for best_effort policy with stale index"
+    let symbols = payload.get("symbols").unwrap().as_array().unwrap();
+    assert!(
+        !symbols.is_empty(),
+        "get_file_outline should return at least one symbol"
     );
-}
-
-// ------------------------------------------------------------------
-// T219: index_repo emits progress notifications when client supports them
-// ------------------------------------------------------------------
-
-#[test]
-fn t219_index_repo_emits_progress_and_end_notifications() {
-    let tmp = tempfile::tempdir().unwrap();
-    let workspace = tmp.path().join("workspace");
-    std::fs::create_dir_all(&workspace).unwrap();
-    create_rust_workspace(&workspace, 140);
-    let (config, conn, project_id) = setup_indexing_runtime(&workspace);
-
-    let notifier_impl = Arc::new(RecordingNotifier::default());
-    let notifier: Arc<dyn ProgressNotifier> = notifier_impl.clone();
 
+    // Step 4: search_code with detail_level: "context"
     let request = make_request(
         "tools/call",
         json!({
-            "name": "index_repo",
+            "name": "search_code",
             "arguments": {
-                "force": true
+                "query": "validate_token",
+                "detail_level": "context"
             }
         }),
     );
@@ -6622,141 +7767,112 @@ fn t219_index_repo_emits_progress_and_end_notifications() {
         &request,
         &RequestContext {
             config: &config,
-            index_set: None,
-            schema_status: SchemaStatus::NotIndexed,
+            index_set: Some(&index_set),
+            schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
             conn: Some(&conn),
-            workspace: &workspace,
-            project_id: &project_id,
+            workspace,
+            project_id,
             prewarm_status: &test_prewarm_status(),
             server_start: &test_server_start(),
-            notifier,
-            progress_token: Some("client-progress-token"),
+            notifier: Arc::new(NullProgressNotifier),
+            progress_token: None,
         },
     );
     assert!(
         response.error.is_none(),
-        "index_repo should succeed: {:?}",
-        response.error
+        "search_code(context) should succeed"
     );
-    let payload = extract_payload_from_response(&response);
+    let results = extract_results_from_response(&response);
+    assert!(!results.is_empty(), "search should find validate_token");
+
+    // Verify metadata conforms to Protocol v1
+    let result = response.result.unwrap();
+    let content = result.get("content").unwrap().as_array().unwrap();
+    let text = content[0].get("text").unwrap().as_str().unwrap();
+    let payload: Value = serde_json::from_str(text).unwrap();
+    let meta = payload.get("metadata").unwrap();
     assert!(
-        payload
-            .get("progress_token")
-            .and_then(|v| v.as_str())
-            .is_some_and(|token| token.starts_with("index-job-")),
-        "index_repo should always return server-generated progress_token"
+        meta.get("cruxe_protocol_version").is_some(),
+        "metadata should include protocol version"
     );
-
-    for _ in 0..120 {
-        if notifier_impl.has_event("end") {
-            break;
-        }
-        std::thread::sleep(Duration::from_millis(100));
-    }
-
     assert!(
-        notifier_impl.has_event("begin"),
-        "should emit begin progress notification"
+        meta.get("freshness_status").is_some(),
+        "metadata should include freshness_status"
     );
     assert!(
-        notifier_impl.has_event("end"),
-        "should emit end progress notification"
+        meta.get("schema_status").is_some(),
+        "metadata should include schema_status"
     );
 }
 
-// ------------------------------------------------------------------
-// T220: no notifications when client doesn't support them; fallback polling fields available
-// ------------------------------------------------------------------
-
 #[test]
-fn t220_index_repo_without_notifications_uses_index_status_progress_fields() {
+fn t190_new_navigation_tools_jsonrpc_e2e() {
     let tmp = tempfile::tempdir().unwrap();
-    let workspace = tmp.path().join("workspace");
-    std::fs::create_dir_all(&workspace).unwrap();
-    create_rust_workspace(&workspace, 160);
-    let (config, conn, project_id) = setup_indexing_runtime(&workspace);
-
-    let notifier_impl = Arc::new(RecordingNotifier::default());
-    let notifier: Arc<dyn ProgressNotifier> = notifier_impl.clone();
+    let index_set = build_fixture_index(tmp.path());
+    let db_path = tmp.path().join("data/state.db");
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+    let project_id = "test-repo";
 
-    let index_request = make_request(
+    let hierarchy_request = make_request(
         "tools/call",
         json!({
-            "name": "index_repo",
+            "name": "get_symbol_hierarchy",
             "arguments": {
-                "force": true
+                "symbol_name": "authenticate",
+                "path": "src/handler.rs",
+                "direction": "ancestors"
             }
         }),
     );
-    let index_response = handle_request_with_ctx(
-        &index_request,
+    let hierarchy_response = handle_request_with_ctx(
+        &hierarchy_request,
         &RequestContext {
             config: &config,
-            index_set: None,
-            schema_status: SchemaStatus::NotIndexed,
+            index_set: Some(&index_set),
+            schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
             conn: Some(&conn),
-            workspace: &workspace,
-            project_id: &project_id,
+            workspace,
+            project_id,
             prewarm_status: &test_prewarm_status(),
             server_start: &test_server_start(),
-            notifier,
+            notifier: Arc::new(NullProgressNotifier),
             progress_token: None,
         },
     );
     assert!(
-        index_response.error.is_none(),
-        "index_repo should succeed: {:?}",
-        index_response.error
-    );
-    assert_eq!(
-        notifier_impl.event_count("begin")
-            + notifier_impl.event_count("report")
-            + notifier_impl.event_count("end"),
-        0,
-        "no notifications should be emitted when client does not declare progress support"
+        hierarchy_response.error.is_none(),
+        "hierarchy tool should succeed"
     );
+    let hierarchy_payload = extract_payload_from_response(&hierarchy_response);
+    assert!(hierarchy_payload.get("hierarchy").unwrap().is_array());
+    assert!(hierarchy_payload.get("metadata").is_some());
 
-    let now = "2026-02-24T00:00:00Z".to_string();
-    let active_job = cruxe_state::jobs::IndexJob {
-        job_id: "job-progress-fields".to_string(),
-        project_id: project_id.clone(),
-        r#ref: cruxe_core::constants::REF_LIVE.to_string(),
-        mode: "incremental".to_string(),
-        head_commit: None,
-        sync_id: None,
-        status: "running".to_string(),
-        changed_files: 0,
-        duration_ms: None,
-        error_message: None,
-        retry_count: 0,
-        progress_token: Some("index-job-job-progress-fields".to_string()),
-        files_scanned: 120,
-        files_indexed: 50,
-        symbols_extracted: 300,
-        created_at: now.clone(),
-        updated_at: now,
-    };
-    cruxe_state::jobs::create_job(&conn, &active_job).unwrap();
-
-    let status_request = make_request(
+    let related_request = make_request(
         "tools/call",
         json!({
-            "name": "index_status",
-            "arguments": {}
+            "name": "find_related_symbols",
+            "arguments": {
+                "symbol_name": "authenticate",
+                "path": "src/handler.rs",
+                "scope": "file",
+                "limit": 10
+            }
         }),
     );
-    let status_response = handle_request_with_ctx(
-        &status_request,
+    let related_response = handle_request_with_ctx(
+        &related_request,
         &RequestContext {
             config: &config,
-            index_set: None,
-            schema_status: SchemaStatus::NotIndexed,
+            index_set: Some(&index_set),
+            schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
             conn: Some(&conn),
-            workspace: &workspace,
-            project_id: &project_id,
+            workspace,
+            project_id,
             prewarm_status: &test_prewarm_status(),
             server_start: &test_server_start(),
             notifier: Arc::new(NullProgressNotifier),
@@ -6764,72 +7880,36 @@ fn t220_index_repo_without_notifications_uses_index_status_progress_fields() {
         },
     );
     assert!(
-        status_response.error.is_none(),
-        "index_status should succeed"
-    );
-    let payload = extract_payload_from_response(&status_response);
-    let active_job = payload
-        .get("active_job")
-        .and_then(|v| v.as_object())
-        .expect("active_job should be present");
-    assert!(active_job.get("files_scanned").unwrap().is_number());
-    assert!(active_job.get("files_indexed").unwrap().is_number());
-    assert!(active_job.get("symbols_extracted").unwrap().is_number());
-    assert!(
-        active_job
-            .get("estimated_completion_pct")
-            .unwrap()
-            .is_number()
+        related_response.error.is_none(),
+        "related tool should succeed"
     );
-}
-
-#[test]
-fn t472_index_status_surfaces_semantic_profile_recommendation_when_enabled() {
-    let tmp = tempfile::tempdir().unwrap();
-    let workspace = tmp.path().join("workspace");
-    std::fs::create_dir_all(&workspace).unwrap();
-    let (mut config, conn, project_id) = setup_indexing_runtime(&workspace);
-    config.search.semantic.profile_advisor_mode = "suggest".to_string();
-
-    let ref_name = cruxe_core::constants::REF_LIVE;
-    for (path, language) in [
-        ("src/lib.rs", "rust"),
-        ("src/auth.rs", "rust"),
-        ("src/app.ts", "typescript"),
-    ] {
-        cruxe_state::manifest::upsert_manifest(
-            &conn,
-            &cruxe_state::manifest::ManifestEntry {
-                repo: project_id.clone(),
-                r#ref: ref_name.to_string(),
-                path: path.to_string(),
-                content_hash: format!("hash-{path}"),
-                size_bytes: 128,
-                mtime_ns: Some(1),
-                language: Some(language.to_string()),
-                indexed_at: "2026-02-26T00:00:00Z".to_string(),
-            },
-        )
-        .unwrap();
-    }
+    let related_payload = extract_payload_from_response(&related_response);
+    assert!(related_payload.get("anchor").is_some());
+    assert!(related_payload.get("related").unwrap().is_array());
+    assert!(related_payload.get("metadata").is_some());
 
-    let status_request = make_request(
+    let context_request = make_request(
         "tools/call",
         json!({
-            "name": "index_status",
-            "arguments": {}
+            "name": "get_code_context",
+            "arguments": {
+                "query": "validate_token",
+                "strategy": "breadth",
+                "max_tokens": 300,
+                "language": "rust"
+            }
         }),
     );
-    let status_response = handle_request_with_ctx(
-        &status_request,
+    let context_response = handle_request_with_ctx(
+        &context_request,
         &RequestContext {
             config: &config,
-            index_set: None,
+            index_set: Some(&index_set),
             schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
             conn: Some(&conn),
-            workspace: &workspace,
-            project_id: &project_id,
+            workspace,
+            project_id,
             prewarm_status: &test_prewarm_status(),
             server_start: &test_server_start(),
             notifier: Arc::new(NullProgressNotifier),
@@ -6837,403 +7917,1612 @@ fn t472_index_status_surfaces_semantic_profile_recommendation_when_enabled() {
         },
     );
     assert!(
-        status_response.error.is_none(),
-        "index_status should succeed"
+        context_response.error.is_none(),
+        "context tool should succeed"
     );
-
-    let payload = extract_payload_from_response(&status_response);
-    let recommendation = payload
-        .get("semantic_profile_recommendation")
-        .and_then(|value| value.as_object())
-        .expect("semantic_profile_recommendation should be present");
+    let context_payload = extract_payload_from_response(&context_response);
+    assert!(context_payload.get("context_items").unwrap().is_array());
     assert!(
-        recommendation
-            .get("profile")
-            .and_then(|value| value.as_str())
-            .is_some()
-    );
-    assert!(
-        recommendation
-            .get("repo_size_bucket")
-            .and_then(|value| value.as_str())
-            .is_some()
-    );
-    assert!(
-        recommendation
-            .get("reason_codes")
-            .and_then(|value| value.as_array())
-            .is_some()
+        context_payload
+            .get("estimated_tokens")
+            .unwrap()
+            .as_u64()
+            .unwrap()
+            <= 300,
+        "context estimated_tokens should honor max_tokens"
     );
+    assert!(context_payload.get("metadata").is_some());
 }
 
 // ------------------------------------------------------------------
-// T464: index launcher honors override binary and required env propagation
+// T139: Backward compatibility - default detail_level is "signature"
 // ------------------------------------------------------------------
 
-#[cfg(unix)]
 #[test]
-fn t464_index_launcher_override_binary_and_env_propagation() {
-    use std::os::unix::fs::PermissionsExt;
-
-    let _env_guard = ENV_LOCK.lock().expect("env lock");
+fn t139_backward_compatibility_default_detail_level() {
     let tmp = tempfile::tempdir().unwrap();
-    let workspace = tmp.path().join("workspace");
-    std::fs::create_dir_all(&workspace).unwrap();
-    let (config, conn, project_id) = setup_indexing_runtime(&workspace);
-
-    let capture_dir = tmp.path().join("capture");
-    std::fs::create_dir_all(&capture_dir).unwrap();
-    let script_path = tmp.path().join("mock-indexer.sh");
-    let script = format!(
-        "#!/bin/sh\nprintf '%s' \"$CRUXE_PROJECT_ID\" > \"{}\"\nprintf '%s' \"$CRUXE_STORAGE_DATA_DIR\" > \"{}\"\nprintf '%s' \"$CRUXE_JOB_ID\" > \"{}\"\n",
-        capture_dir.join("project_id.txt").display(),
-        capture_dir.join("storage_dir.txt").display(),
-        capture_dir.join("job_id.txt").display(),
-    );
-    std::fs::write(&script_path, script).unwrap();
-    let mut perms = std::fs::metadata(&script_path).unwrap().permissions();
-    perms.set_mode(0o755);
-    std::fs::set_permissions(&script_path, perms).unwrap();
+    let index_set = build_fixture_index(tmp.path());
 
-    unsafe {
-        std::env::set_var("CRUXE_INDEX_BIN", &script_path);
-    }
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+    let project_id = "test_project";
 
+    // Call locate_symbol without detail_level parameter
     let request = make_request(
         "tools/call",
         json!({
-            "name": "index_repo",
-            "arguments": { "force": false }
+            "name": "locate_symbol",
+            "arguments": {
+                "name": "validate_token"
+            }
         }),
     );
     let response = handle_request_with_ctx(
         &request,
         &RequestContext {
             config: &config,
-            index_set: None,
-            schema_status: SchemaStatus::NotIndexed,
+            index_set: Some(&index_set),
+            schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
-            conn: Some(&conn),
-            workspace: &workspace,
-            project_id: &project_id,
+            conn: None,
+            workspace,
+            project_id,
             prewarm_status: &test_prewarm_status(),
             server_start: &test_server_start(),
             notifier: Arc::new(NullProgressNotifier),
             progress_token: None,
         },
     );
+    assert!(
+        response.error.is_none(),
+        "locate_symbol without detail_level should succeed"
+    );
+    let results = extract_results_from_response(&response);
+    assert!(!results.is_empty(), "should find validate_token");
 
-    unsafe {
-        std::env::remove_var("CRUXE_INDEX_BIN");
-    }
+    let vt = results
+        .iter()
+        .find(|r| r.get("name").unwrap().as_str().unwrap() == "validate_token")
+        .unwrap();
 
+    // Signature-level fields should be present (default)
     assert!(
-        response.error.is_none(),
-        "index_repo should spawn override binary"
+        vt.get("qualified_name").is_some(),
+        "default should include qualified_name (signature level)"
     );
-    let payload = extract_payload_from_response(&response);
     assert!(
-        payload
-            .get("progress_token")
-            .and_then(|v| v.as_str())
-            .is_some_and(|token| token.starts_with("index-job-")),
-        "index_repo should return server-generated progress_token"
+        vt.get("language").is_some(),
+        "default should include language (signature level)"
     );
 
-    for _ in 0..80 {
-        let job_path = capture_dir.join("job_id.txt");
-        if job_path.exists()
-            && std::fs::metadata(&job_path)
-                .map(|m| m.len() > 0)
-                .unwrap_or(false)
-        {
-            break;
-        }
-        std::thread::sleep(Duration::from_millis(25));
-    }
-
-    let captured_project_id = std::fs::read_to_string(capture_dir.join("project_id.txt"))
-        .expect("override launcher should capture CRUXE_PROJECT_ID");
-    let captured_storage_dir = std::fs::read_to_string(capture_dir.join("storage_dir.txt"))
-        .expect("override launcher should capture CRUXE_STORAGE_DATA_DIR");
-    let captured_job_id = std::fs::read_to_string(capture_dir.join("job_id.txt"))
-        .expect("override launcher should capture CRUXE_JOB_ID");
-
-    assert_eq!(captured_project_id, project_id);
-    assert_eq!(captured_storage_dir, config.storage.data_dir);
+    // Context-only fields should NOT be present
     assert!(
-        !captured_job_id.trim().is_empty(),
-        "captured job id should be non-empty"
+        vt.get("body_preview").is_none(),
+        "default should NOT include body_preview (context only)"
     );
-}
-
-#[cfg(unix)]
-#[test]
-fn t466_bootstrap_launcher_override_binary_and_env_propagation() {
-    use std::os::unix::fs::PermissionsExt;
-
-    let _env_guard = ENV_LOCK.lock().expect("env lock");
-    let tmp = tempfile::tempdir().unwrap();
-    let workspace = tmp.path().join("workspace-bootstrap");
-    std::fs::create_dir_all(&workspace).unwrap();
-    let project_id = generate_project_id(&workspace.to_string_lossy());
-    let data_dir = tmp.path().join("cc-data").join("data").join(&project_id);
-    let storage_data_dir = tmp.path().join("cc-data");
-    let storage_data_dir_str = storage_data_dir.to_string_lossy().to_string();
 
-    let capture_dir = tmp.path().join("capture-bootstrap");
-    std::fs::create_dir_all(&capture_dir).unwrap();
-    let script_path = tmp.path().join("mock-bootstrap-indexer.sh");
-    let script = format!(
-        "#!/bin/sh\nprintf '%s' \"$CRUXE_PROJECT_ID\" > \"{}\"\nprintf '%s' \"$CRUXE_STORAGE_DATA_DIR\" > \"{}\"\nprintf '%s' \"$CRUXE_JOB_ID\" > \"{}\"\n",
-        capture_dir.join("project_id.txt").display(),
-        capture_dir.join("storage_dir.txt").display(),
-        capture_dir.join("job_id.txt").display(),
+    // Same test for search_code
+    let request = make_request(
+        "tools/call",
+        json!({
+            "name": "search_code",
+            "arguments": {
+                "query": "validate_token"
+            }
+        }),
+    );
+    let response = handle_request_with_ctx(
+        &request,
+        &RequestContext {
+            config: &config,
+            index_set: Some(&index_set),
+            schema_status: SchemaStatus::Compatible,
+            compatibility_reason: None,
+            conn: None,
+            workspace,
+            project_id,
+            prewarm_status: &test_prewarm_status(),
+            server_start: &test_server_start(),
+            notifier: Arc::new(NullProgressNotifier),
+            progress_token: None,
+        },
     );
-    std::fs::write(&script_path, script).unwrap();
-    let mut perms = std::fs::metadata(&script_path).unwrap().permissions();
-    perms.set_mode(0o755);
-    std::fs::set_permissions(&script_path, perms).unwrap();
-
-    unsafe {
-        std::env::set_var("CRUXE_INDEX_BIN", &script_path);
-    }
-
-    let result = bootstrap_and_index(&workspace, &project_id, &data_dir, &storage_data_dir_str);
-
-    unsafe {
-        std::env::remove_var("CRUXE_INDEX_BIN");
-    }
-
     assert!(
-        result.is_ok(),
-        "bootstrap launcher should use override binary"
+        response.error.is_none(),
+        "search_code without detail_level should succeed"
     );
+    let results = extract_results_from_response(&response);
+    assert!(!results.is_empty(), "search should find results");
 
-    for _ in 0..80 {
-        let job_path = capture_dir.join("job_id.txt");
-        if job_path.exists()
-            && std::fs::metadata(&job_path)
-                .map(|m| m.len() > 0)
-                .unwrap_or(false)
-        {
-            break;
-        }
-        std::thread::sleep(Duration::from_millis(25));
-    }
-
-    let captured_project_id = std::fs::read_to_string(capture_dir.join("project_id.txt"))
-        .expect("bootstrap launcher should capture CRUXE_PROJECT_ID");
-    let captured_storage_dir = std::fs::read_to_string(capture_dir.join("storage_dir.txt"))
-        .expect("bootstrap launcher should capture CRUXE_STORAGE_DATA_DIR");
-    let captured_job_id = std::fs::read_to_string(capture_dir.join("job_id.txt"))
-        .expect("bootstrap launcher should capture CRUXE_JOB_ID");
-
-    assert_eq!(captured_project_id, project_id);
-    assert_eq!(captured_storage_dir, storage_data_dir_str);
+    // Verify metadata is present
+    let result = response.result.unwrap();
+    let content = result.get("content").unwrap().as_array().unwrap();
+    let text = content[0].get("text").unwrap().as_str().unwrap();
+    let payload: Value = serde_json::from_str(text).unwrap();
     assert!(
-        !captured_job_id.trim().is_empty(),
-        "captured bootstrap job id should be non-empty"
+        payload.get("metadata").is_some(),
+        "response should include metadata for backward compatibility"
     );
 }
 
+// ------------------------------------------------------------------
+// T138: Performance smoke guard (strict p95 thresholds moved to benchmark harness)
+// ------------------------------------------------------------------
+
 #[test]
-fn t467_connection_manager_reopens_after_failure_and_invalidate() {
+fn t138_performance_smoke_guard() {
     let tmp = tempfile::tempdir().unwrap();
-    let blocking_parent = tmp.path().join("nested");
-    std::fs::write(&blocking_parent, "blocking").unwrap();
-    let db_path = blocking_parent.join("state.db");
-    let manager = ConnectionManager::new();
+    let index_set = build_fixture_index(tmp.path());
 
-    assert!(
-        manager.get_or_open(&db_path).is_err(),
-        "opening when parent path is a file should fail"
-    );
+    let db_path = tmp.path().join("data/state.db");
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
 
-    std::fs::remove_file(&blocking_parent).unwrap();
-    std::fs::create_dir_all(db_path.parent().unwrap()).unwrap();
-    let first = manager
-        .get_or_open(&db_path)
-        .expect("open sqlite after setup");
-    {
-        let conn = first.lock().expect("lock sqlite");
-        cruxe_state::schema::create_tables(&conn).unwrap();
-    }
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+    let project_id = "test-repo";
 
-    manager.invalidate(&db_path);
-    let second = manager
-        .get_or_open(&db_path)
-        .expect("re-open sqlite after invalidate");
-    let conn = second.lock().expect("lock re-opened sqlite");
-    let one: i64 = conn.query_row("SELECT 1", [], |row| row.get(0)).unwrap();
-    assert_eq!(one, 1);
-}
-
-#[test]
-fn t468_connection_manager_evicts_oldest_idle_entry_when_at_capacity() {
-    let tmp = tempfile::tempdir().unwrap();
-    let db1 = tmp.path().join("db1.sqlite");
-    let db2 = tmp.path().join("db2.sqlite");
-    let db3 = tmp.path().join("db3.sqlite");
-    let manager = ConnectionManager::with_capacity(2);
-
-    drop(manager.get_or_open(&db1).expect("open db1"));
-    std::thread::sleep(Duration::from_millis(5));
-    drop(manager.get_or_open(&db2).expect("open db2"));
-    assert!(manager.contains_connection(&db1));
-    assert!(manager.contains_connection(&db2));
-
-    drop(manager.get_or_open(&db3).expect("open db3"));
-
-    assert_eq!(
-        manager.cached_connection_count(),
-        2,
-        "manager should enforce bounded cache size for idle connections"
-    );
-    assert!(
-        !manager.contains_connection(&db1),
-        "oldest idle connection should be evicted first"
-    );
-    assert!(manager.contains_connection(&db2));
-    assert!(manager.contains_connection(&db3));
-}
-
-#[test]
-fn t469_connection_manager_keeps_in_use_entries_even_when_over_capacity() {
-    let tmp = tempfile::tempdir().unwrap();
-    let db1 = tmp.path().join("db1.sqlite");
-    let db2 = tmp.path().join("db2.sqlite");
-    let manager = ConnectionManager::with_capacity(1);
-
-    let held = manager.get_or_open(&db1).expect("open db1");
-    let second = manager.get_or_open(&db2).expect("open db2");
-
-    assert!(
-        manager.contains_connection(&db1),
-        "in-use entry should remain cached"
-    );
-    assert!(manager.contains_connection(&db2));
+    // Smoke: repeated get_file_outline calls should remain responsive.
+    let mut outline_times = Vec::new();
+    for _ in 0..10 {
+        let request = make_request(
+            "tools/call",
+            json!({
+                "name": "get_file_outline",
+                "arguments": {
+                    "path": "src/auth.rs"
+                }
+            }),
+        );
+        let start = std::time::Instant::now();
+        let response = handle_request_with_ctx(
+            &request,
+            &RequestContext {
+                config: &config,
+                index_set: Some(&index_set),
+                schema_status: SchemaStatus::Compatible,
+                compatibility_reason: None,
+                conn: Some(&conn),
+                workspace,
+                project_id,
+                prewarm_status: &test_prewarm_status(),
+                server_start: &test_server_start(),
+                notifier: Arc::new(NullProgressNotifier),
+                progress_token: None,
+            },
+        );
+        let elapsed = start.elapsed();
+        outline_times.push(elapsed);
+        assert!(response.error.is_none(), "get_file_outline should succeed");
+    }
+    outline_times.sort();
+    let p95_outline = outline_times[8];
     assert!(
-        manager.cached_connection_count() >= 2,
-        "manager may temporarily exceed capacity when all older entries are in use"
+        p95_outline.as_millis() < 5_000,
+        "get_file_outline smoke budget should remain < 5000ms, got {}ms",
+        p95_outline.as_millis()
     );
 
-    drop(second);
-    drop(held);
-}
-
-// ------------------------------------------------------------------
-// T465: canonical error envelope + metadata enum snapshots
-// ------------------------------------------------------------------
-
-#[test]
-fn t465_protocol_error_envelope_and_metadata_enum_snapshots() {
-    let tmp = tempfile::tempdir().unwrap();
-    let workspace = tmp.path().join("workspace");
-    std::fs::create_dir_all(&workspace).unwrap();
-    let config = Config::default();
-    let project_id = generate_project_id(&workspace.to_string_lossy());
-
-    let validation_request = make_request(
+    // Benchmark first-query latency: search_code after prewarm
+    let request = make_request(
         "tools/call",
         json!({
-            "name": "locate_symbol",
-            "arguments": {}
+            "name": "search_code",
+            "arguments": {
+                "query": "validate_token"
+            }
         }),
     );
-    let validation_response = handle_request_with_ctx(
-        &validation_request,
+    let start = std::time::Instant::now();
+    let response = handle_request_with_ctx(
+        &request,
         &RequestContext {
             config: &config,
-            index_set: None,
+            index_set: Some(&index_set),
             schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
-            conn: None,
-            workspace: &workspace,
-            project_id: &project_id,
+            conn: Some(&conn),
+            workspace,
+            project_id,
             prewarm_status: &test_prewarm_status(),
             server_start: &test_server_start(),
             notifier: Arc::new(NullProgressNotifier),
             progress_token: None,
         },
     );
-    let validation_payload = extract_payload_from_response(&validation_response);
-    assert_eq!(
-        validation_payload.get("error").and_then(|v| v.get("code")),
-        Some(&json!("invalid_input"))
-    );
-    assert_eq!(
-        validation_payload
-            .get("metadata")
-            .and_then(|v| v.get("indexing_status")),
-        Some(&json!("ready"))
-    );
-    assert_eq!(
-        validation_payload
-            .get("metadata")
-            .and_then(|v| v.get("result_completeness")),
-        Some(&json!("complete"))
+    let elapsed = start.elapsed();
+    assert!(response.error.is_none(), "search_code should succeed");
+    assert!(
+        elapsed.as_millis() < 5_000,
+        "first-query smoke budget should remain < 5000ms, got {}ms",
+        elapsed.as_millis()
     );
+}
 
-    let compatibility_request = make_request(
-        "tools/call",
-        json!({
-            "name": "search_code",
-            "arguments": { "query": "foo" }
-        }),
-    );
-    let compatibility_response = handle_request_with_ctx(
-        &compatibility_request,
-        &RequestContext {
-            config: &config,
-            index_set: None,
-            schema_status: SchemaStatus::NotIndexed,
-            compatibility_reason: Some("No index found"),
-            conn: None,
-            workspace: &workspace,
-            project_id: &project_id,
-            prewarm_status: &test_prewarm_status(),
-            server_start: &test_server_start(),
-            notifier: Arc::new(NullProgressNotifier),
-            progress_token: None,
-        },
-    );
-    let compatibility_payload = extract_payload_from_response(&compatibility_response);
-    assert_eq!(
-        compatibility_payload
-            .get("error")
-            .and_then(|v| v.get("code")),
-        Some(&json!("project_not_found"))
-    );
-    assert_eq!(
-        compatibility_payload
-            .get("metadata")
-            .and_then(|v| v.get("indexing_status")),
-        Some(&json!("not_indexed"))
-    );
-    assert_eq!(
-        compatibility_payload
-            .get("metadata")
-            .and_then(|v| v.get("result_completeness")),
-        Some(&json!("partial"))
+#[test]
+#[ignore = "benchmark harness"]
+fn benchmark_t138_get_file_outline_p95_under_50ms() {
+    let tmp = tempfile::tempdir().unwrap();
+    let index_set = build_fixture_index(tmp.path());
+
+    let db_path = tmp.path().join("data/state.db");
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+    let project_id = "test-repo";
+
+    let mut outline_times = Vec::new();
+    for _ in 0..10 {
+        let request = make_request(
+            "tools/call",
+            json!({
+                "name": "get_file_outline",
+                "arguments": {
+                    "path": "src/auth.rs"
+                }
+            }),
+        );
+        let start = std::time::Instant::now();
+        let response = handle_request_with_ctx(
+            &request,
+            &RequestContext {
+                config: &config,
+                index_set: Some(&index_set),
+                schema_status: SchemaStatus::Compatible,
+                compatibility_reason: None,
+                conn: Some(&conn),
+                workspace,
+                project_id,
+                prewarm_status: &test_prewarm_status(),
+                server_start: &test_server_start(),
+                notifier: Arc::new(NullProgressNotifier),
+                progress_token: None,
+            },
+        );
+        assert!(response.error.is_none(), "get_file_outline should succeed");
+        outline_times.push(start.elapsed());
+    }
+    outline_times.sort();
+    let p95_outline = outline_times[8];
+    assert!(
+        p95_outline.as_millis() < 50,
+        "get_file_outline benchmark p95 should be < 50ms, got {}ms",
+        p95_outline.as_millis()
     );
 }
 
 // ------------------------------------------------------------------
-// T456: restart recovery report + warmset visibility in health_check
+// Helper: extract the full JSON payload from an MCP tool response
 // ------------------------------------------------------------------
 
-#[test]
-fn t456_health_check_surfaces_interrupted_recovery_and_warmset_members() {
-    let tmp = tempfile::tempdir().unwrap();
-    let workspace = tmp.path().join("workspace-a");
-    let workspace_b = tmp.path().join("workspace-b");
-    std::fs::create_dir_all(&workspace).unwrap();
-    std::fs::create_dir_all(&workspace_b).unwrap();
+/// Payload of a tool response without the per-call `metadata.request_id`.
+fn payload_without_request_id(response: &JsonRpcResponse) -> serde_json::Value {
+    let mut payload = extract_payload_from_response(response);
+    if let Some(metadata) = payload
+        .get_mut("metadata")
+        .and_then(|metadata| metadata.as_object_mut())
+    {
+        metadata.remove("request_id");
+    }
+    payload
+}
 
-    let (config, conn, project_id) = setup_indexing_runtime(&workspace);
-    let project_b_id = generate_project_id(&workspace_b.to_string_lossy());
-    let now = "2026-02-24T00:00:00Z".to_string();
+fn extract_payload_from_response(response: &JsonRpcResponse) -> serde_json::Value {
+    let result = response.result.as_ref().expect("result should be present");
+    let content = result
+        .get("content")
+        .expect("result should have 'content'")
+        .as_array()
+        .expect("'content' should be an array");
+    let text = content[0].get("text").unwrap().as_str().unwrap();
+    serde_json::from_str(text).expect("text payload should be valid JSON")
+}
+
+// ------------------------------------------------------------------
+// Helper: build a fixture index inside a real git repo for freshness tests
+// ------------------------------------------------------------------
+
+fn build_fixture_index_in_git_repo(
+    tmp_dir: &std::path::Path,
+) -> (IndexSet, rusqlite::Connection, String) {
+    use cruxe_indexer::{languages, parser, scanner, snippet_extract, symbol_extract, writer};
+    use cruxe_state::{db, schema, tantivy_index::IndexSet};
+
+    let fixture_dir = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../testdata/fixtures/rust-sample");
+
+    // Initialize a git repo in tmp_dir and commit
+    let workspace = tmp_dir.join("workspace");
+    std::fs::create_dir_all(&workspace).unwrap();
+    std::process::Command::new("git")
+        .args(["init"])
+        .current_dir(&workspace)
+        .output()
+        .unwrap();
+    std::process::Command::new("git")
+        .args(["config", "user.email", "test@test.com"])
+        .current_dir(&workspace)
+        .output()
+        .unwrap();
+    std::process::Command::new("git")
+        .args(["config", "user.name", "Test"])
+        .current_dir(&workspace)
+        .output()
+        .unwrap();
+
+    // Copy fixture files into workspace (recursive)
+    fn copy_dir_recursive(src: &std::path::Path, dst: &std::path::Path) {
+        std::fs::create_dir_all(dst).unwrap();
+        for entry in std::fs::read_dir(src).unwrap() {
+            let entry = entry.unwrap();
+            let dest = dst.join(entry.file_name());
+            if entry.file_type().unwrap().is_dir() {
+                copy_dir_recursive(&entry.path(), &dest);
+            } else {
+                std::fs::copy(entry.path(), &dest).unwrap();
+            }
+        }
+    }
+    copy_dir_recursive(&fixture_dir, &workspace);
+
+    // Initial commit
+    std::process::Command::new("git")
+        .args(["add", "."])
+        .current_dir(&workspace)
+        .output()
+        .unwrap();
+    std::process::Command::new("git")
+        .args(["commit", "-m", "initial"])
+        .current_dir(&workspace)
+        .output()
+        .unwrap();
+
+    // Get the initial commit hash
+    let output = std::process::Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .current_dir(&workspace)
+        .output()
+        .unwrap();
+    let initial_commit = String::from_utf8(output.stdout).unwrap().trim().to_string();
+
+    // Build index
+    let data_dir = workspace.join(".cruxe/data");
+    std::fs::create_dir_all(&data_dir).unwrap();
+    let index_set = IndexSet::open(&data_dir).unwrap();
+
+    let db_path = data_dir.join("state.db");
+    let conn = db::open_connection(&db_path).unwrap();
+    schema::create_tables(&conn).unwrap();
+
+    let repo = "test-repo";
+    // Detect the current branch name
+    let branch_output = std::process::Command::new("git")
+        .args(["rev-parse", "--abbrev-ref", "HEAD"])
+        .current_dir(&workspace)
+        .output()
+        .unwrap();
+    let branch_name = String::from_utf8(branch_output.stdout)
+        .unwrap()
+        .trim()
+        .to_string();
+
+    let scanned = scanner::scan_directory(&workspace, 1_048_576);
+    for file in &scanned {
+        let source = std::fs::read_to_string(&file.path).unwrap();
+        let tree = match parser::parse_file(&source, &file.language) {
+            Ok(t) => t,
+            Err(_) => continue,
+        };
+
+        let extracted = languages::extract_symbols(&tree, &source, &file.language);
+        let symbols = symbol_extract::build_symbol_records(
+            &extracted,
+            repo,
+            &branch_name,
+            &file.relative_path,
+            None,
+        );
+        let snippets = snippet_extract::build_snippet_records(
+            &extracted,
+            repo,
+            &branch_name,
+            &file.relative_path,
+            None,
+        );
+
+        let content_hash = blake3::hash(source.as_bytes()).to_hex().to_string();
+        let filename = file.path.file_name().unwrap().to_string_lossy().to_string();
+        let file_record = cruxe_core::types::FileRecord {
+            repo: repo.to_string(),
+            r#ref: branch_name.clone(),
+            commit: None,
+            path: file.relative_path.clone(),
+            filename,
+            language: file.language.clone(),
+            content_hash,
+            size_bytes: source.len() as u64,
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+            content_head: source
+                .lines()
+                .take(10)
+                .collect::<Vec<_>>()
+                .join("\n")
+                .into(),
+            encoding_warning: false,
+        };
+
+        writer::write_file_records(&index_set, &conn, &symbols, &snippets, &file_record).unwrap();
+    }
+
+    // Store branch_state with initial commit
+    let branch_entry = cruxe_state::branch_state::BranchState {
+        repo: repo.to_string(),
+        r#ref: branch_name.clone(),
+        merge_base_commit: None,
+        last_indexed_commit: initial_commit,
+        overlay_dir: None,
+        file_count: scanned.len() as i64,
+        symbol_count: 0,
+        is_default_branch: false,
+        status: "active".to_string(),
+        eviction_eligible_at: None,
+        created_at: "2026-01-01T00:00:00Z".to_string(),
+        last_accessed_at: "2026-01-01T00:00:00Z".to_string(),
+    };
+    cruxe_state::branch_state::upsert_branch_state(&conn, &branch_entry).unwrap();
+
+    (index_set, conn, branch_name)
+}
+
+/// Make a new commit in the workspace to make the index stale.
+fn make_workspace_stale(workspace: &std::path::Path) {
+    let dummy = workspace.join("dummy.txt");
+    std::fs::write(&dummy, "stale marker").unwrap();
+    std::process::Command::new("git")
+        .args(["add", "dummy.txt"])
+        .current_dir(workspace)
+        .output()
+        .unwrap();
+    std::process::Command::new("git")
+        .args(["commit", "-m", "make stale"])
+        .current_dir(workspace)
+        .output()
+        .unwrap();
+}
+
+// ------------------------------------------------------------------
+// T131: balanced policy with stale index returns results + stale status
+// ------------------------------------------------------------------
+
+#[test]
+fn t131_search_code_balanced_policy_stale_index() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (index_set, conn, branch_name) = build_fixture_index_in_git_repo(tmp.path());
+    let workspace = tmp.path().join("workspace");
+
+    // Make the index stale by creating a new commit
+    make_workspace_stale(&workspace);
+
+    let config = Config::default();
+    let project_id = "test-repo";
+
+    let request = make_request(
+        "tools/call",
+        json!({
+            "name": "search_code",
+            "arguments": {
+                "query": "validate_token",
+                "ref": branch_name,
+                "freshness_policy": "balanced"
+            }
+        }),
+    );
+
+    let response = handle_request_with_ctx(
+        &request,
+        &RequestContext {
+            config: &config,
+            index_set: Some(&index_set),
+            schema_status: SchemaStatus::Compatible,
+            compatibility_reason: None,
+            conn: Some(&conn),
+            workspace: &workspace,
+            project_id,
+            prewarm_status: &test_prewarm_status(),
+            server_start: &test_server_start(),
+            notifier: Arc::new(NullProgressNotifier),
+            progress_token: None,
+        },
+    );
+
+    assert!(
+        response.error.is_none(),
+        "expected success, got error: {:?}",
+        response.error
+    );
+    let payload = extract_payload_from_response(&response);
+
+    // Should have results (query still runs)
+    let results = payload.get("results").unwrap().as_array().unwrap();
+    assert!(
+        !results.is_empty(),
+        "balanced policy should return results even when stale"
+    );
+
+    // Metadata should show stale
+    let meta = payload.get("metadata").unwrap();
+    assert_eq!(
+        meta.get("freshness_status").unwrap().as_str().unwrap(),
+        "stale",
+        "freshness_status should be 'stale' for balanced policy with stale index"
+    );
+}
+
+// ------------------------------------------------------------------
+// T132: strict policy with stale index returns index_stale error
+// ------------------------------------------------------------------
+
+#[test]
+fn t132_search_code_strict_policy_stale_index_blocks() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (index_set, conn, branch_name) = build_fixture_index_in_git_repo(tmp.path());
+    let workspace = tmp.path().join("workspace");
+
+    // Make the index stale
+    make_workspace_stale(&workspace);
+
+    let config = Config::default();
+    let project_id = "test-repo";
+
+    let request = make_request(
+        "tools/call",
+        json!({
+            "name": "search_code",
+            "arguments": {
+                "query": "validate_token",
+                "ref": branch_name,
+                "freshness_policy": "strict"
+            }
+        }),
+    );
+
+    let response = handle_request_with_ctx(
+        &request,
+        &RequestContext {
+            config: &config,
+            index_set: Some(&index_set),
+            schema_status: SchemaStatus::Compatible,
+            compatibility_reason: None,
+            conn: Some(&conn),
+            workspace: &workspace,
+            project_id,
+            prewarm_status: &test_prewarm_status(),
+            server_start: &test_server_start(),
+            notifier: Arc::new(NullProgressNotifier),
+            progress_token: None,
+        },
+    );
+
+    // The response should be a "success" at JSON-RPC level but contain an error payload
+    assert!(
+        response.error.is_none(),
+        "should be JSON-RPC success (error is in payload)"
+    );
+    let payload = extract_payload_from_response(&response);
+
+    // Should have error object instead of results
+    let error = payload
+        .get("error")
+        .expect("payload should have 'error' for strict+stale");
+    assert_eq!(
+        error.get("code").unwrap().as_str().unwrap(),
+        "index_stale",
+        "error code should be 'index_stale'"
+    );
+    assert!(
+        error
+            .get("message")
+            .unwrap()
+            .as_str()
+            .unwrap()
+            .contains("stale"),
+        "error message should mention stale"
+    );
+    let data = error.get("data").unwrap();
+    assert!(
+        data.get("last_indexed_commit").is_some(),
+        "error data should include last_indexed_commit"
+    );
+    assert!(
+        data.get("current_head").is_some(),
+        "error data should include current_head"
+    );
+    assert!(
+        data.get("suggestion").is_some(),
+        "error data should include suggestion"
+    );
+
+    // Metadata should show stale
+    let meta = payload.get("metadata").unwrap();
+    assert_eq!(
+        meta.get("freshness_status").unwrap().as_str().unwrap(),
+        "stale"
+    );
+}
+
+#[test]
+fn t470_compare_symbol_between_commits_strict_policy_stale_index_blocks() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (_index_set, conn, branch_name) = build_fixture_index_in_git_repo(tmp.path());
+    let workspace = tmp.path().join("workspace");
+
+    // Make the index stale
+    make_workspace_stale(&workspace);
+
+    let config = Config::default();
+    let project_id = "test-repo";
+    let request = make_request(
+        "tools/call",
+        json!({
+            "name": "compare_symbol_between_commits",
+            "arguments": {
+                "symbol_name": "validate_token",
+                "base_ref": branch_name,
+                "head_ref": branch_name,
+                "freshness_policy": "strict"
+            }
+        }),
+    );
+
+    let response = handle_request_with_ctx(
+        &request,
+        &RequestContext {
+            config: &config,
+            index_set: None,
+            schema_status: SchemaStatus::Compatible,
+            compatibility_reason: None,
+            conn: Some(&conn),
+            workspace: &workspace,
+            project_id,
+            prewarm_status: &test_prewarm_status(),
+            server_start: &test_server_start(),
+            notifier: Arc::new(NullProgressNotifier),
+            progress_token: None,
+        },
+    );
+
+    assert!(
+        response.error.is_none(),
+        "should be JSON-RPC success (error is in payload)"
+    );
+    let payload = extract_payload_from_response(&response);
+    let error = payload
+        .get("error")
+        .expect("payload should have 'error' for strict+stale compare");
+    assert_eq!(
+        error.get("code").and_then(|value| value.as_str()),
+        Some("index_stale")
+    );
+}
+
+#[test]
+fn t471_compare_symbol_between_commits_strict_policy_stale_base_ref_blocks() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (_index_set, conn, base_ref) = build_fixture_index_in_git_repo(tmp.path());
+    let workspace = tmp.path().join("workspace");
+
+    // Make current branch stale. This affects `base_ref`.
+    make_workspace_stale(&workspace);
+
+    // Register a second indexed ref so the compare precheck passes for both refs.
+    // This ref is not the checked-out branch, so freshness check treats it as fresh.
+    let head_ref = "feat/fresh";
+    let head_commit = cruxe_core::vcs::detect_head_commit(&workspace).unwrap();
+    cruxe_state::branch_state::upsert_branch_state(
+        &conn,
+        &cruxe_state::branch_state::BranchState {
+            repo: "test-repo".to_string(),
+            r#ref: head_ref.to_string(),
+            merge_base_commit: None,
+            last_indexed_commit: head_commit,
+            overlay_dir: None,
+            file_count: 0,
+            symbol_count: 0,
+            is_default_branch: false,
+            status: "active".to_string(),
+            eviction_eligible_at: None,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            last_accessed_at: "2026-01-01T00:00:00Z".to_string(),
+        },
+    )
+    .unwrap();
+
+    let config = Config::default();
+    let project_id = "test-repo";
+    let request = make_request(
+        "tools/call",
+        json!({
+            "name": "compare_symbol_between_commits",
+            "arguments": {
+                "symbol_name": "validate_token",
+                "base_ref": base_ref,
+                "head_ref": head_ref,
+                "freshness_policy": "strict"
+            }
+        }),
+    );
+
+    let response = handle_request_with_ctx(
+        &request,
+        &RequestContext {
+            config: &config,
+            index_set: None,
+            schema_status: SchemaStatus::Compatible,
+            compatibility_reason: None,
+            conn: Some(&conn),
+            workspace: &workspace,
+            project_id,
+            prewarm_status: &test_prewarm_status(),
+            server_start: &test_server_start(),
+            notifier: Arc::new(NullProgressNotifier),
+            progress_token: None,
+        },
+    );
+
+    assert!(
+        response.error.is_none(),
+        "should be JSON-RPC success (error is in payload)"
+    );
+    let payload = extract_payload_from_response(&response);
+    let error = payload
+        .get("error")
+        .expect("payload should have 'error' for strict+stale base ref");
+    assert_eq!(
+        error.get("code").and_then(|value| value.as_str()),
+        Some("index_stale")
+    );
+}
+
+// ------------------------------------------------------------------
+// T133: best_effort policy with stale index returns results + stale
+// ------------------------------------------------------------------
+
+#[test]
+fn t133_search_code_best_effort_policy_stale_index() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (index_set, conn, branch_name) = build_fixture_index_in_git_repo(tmp.path());
+    let workspace = tmp.path().join("workspace");
+
+    // Make the index stale
+    make_workspace_stale(&workspace);
+
+    let config = Config::default();
+    let project_id = "test-repo";
+
+    let request = make_request(
+        "tools/call",
+        json!({
+            "name": "search_code",
+            "arguments": {
+                "query": "validate_token",
+                "ref": branch_name,
+                "freshness_policy": "best_effort"
+            }
+        }),
+    );
+
+    let response = handle_request_with_ctx(
+        &request,
+        &RequestContext {
+            config: &config,
+            index_set: Some(&index_set),
+            schema_status: SchemaStatus::Compatible,
+            compatibility_reason: None,
+            conn: Some(&conn),
+            workspace: &workspace,
+            project_id,
+            prewarm_status: &test_prewarm_status(),
+            server_start: &test_server_start(),
+            notifier: Arc::new(NullProgressNotifier),
+            progress_token: None,
+        },
+    );
+
+    assert!(response.error.is_none(), "expected success");
+    let payload = extract_payload_from_response(&response);
+
+    // Should have results (best_effort always returns)
+    let results = payload.get("results").unwrap().as_array().unwrap();
+    assert!(
+        !results.is_empty(),
+        "best_effort policy should return results even when stale"
+    );
+
+    // Metadata should show stale
+    let meta = payload.get("metadata").unwrap();
+    assert_eq!(
+        meta.get("freshness_status").unwrap().as_str().unwrap(),
+        "stale",
+        "freshness_status should be 'stale' for best_effort policy with stale index"
+    );
+}
+
+// ------------------------------------------------------------------
+// T219: index_repo emits progress notifications when client supports them
+// ------------------------------------------------------------------
+
+#[test]
+fn t219_index_repo_emits_progress_and_end_notifications() {
+    let tmp = tempfile::tempdir().unwrap();
+    let workspace = tmp.path().join("workspace");
+    std::fs::create_dir_all(&workspace).unwrap();
+    create_rust_workspace(&workspace, 140);
+    let (config, conn, project_id) = setup_indexing_runtime(&workspace);
+
+    let notifier_impl = Arc::new(RecordingNotifier::default());
+    let notifier: Arc<dyn ProgressNotifier> = notifier_impl.clone();
+
+    let request = make_request(
+        "tools/call",
+        json!({
+            "name": "index_repo",
+            "arguments": {
+                "force": true
+            }
+        }),
+    );
+    let response = handle_request_with_ctx(
+        &request,
+        &RequestContext {
+            config: &config,
+            index_set: None,
+            schema_status: SchemaStatus::NotIndexed,
+            compatibility_reason: None,
+            conn: Some(&conn),
+            workspace: &workspace,
+            project_id: &project_id,
+            prewarm_status: &test_prewarm_status(),
+            server_start: &test_server_start(),
+            notifier,
+            progress_token: Some("client-progress-token"),
+        },
+    );
+    assert!(
+        response.error.is_none(),
+        "index_repo should succeed: {:?}",
+        response.error
+    );
+    let payload = extract_payload_from_response(&response);
+    assert!(
+        payload
+            .get("progress_token")
+            .and_then(|v| v.as_str())
+            .is_some_and(|token| token.starts_with("index-job-")),
+        "index_repo should always return server-generated progress_token"
+    );
+
+    for _ in 0..120 {
+        if notifier_impl.has_event("end") {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+
+    assert!(
+        notifier_impl.has_event("begin"),
+        "should emit begin progress notification"
+    );
+    assert!(
+        notifier_impl.has_event("end"),
+        "should emit end progress notification"
+    );
+}
+
+#[test]
+fn t499_index_repo_refuses_when_free_disk_is_below_minimum() {
+    let tmp = tempfile::tempdir().unwrap();
+    let workspace = tmp.path().join("workspace");
+    std::fs::create_dir_all(&workspace).unwrap();
+    create_rust_workspace(&workspace, 2);
+    let (mut config, conn, project_id) = setup_indexing_runtime(&workspace);
+    // No volume has this much free space.
+    config.index.min_free_disk_mb = u64::MAX / (1024 * 1024);
+
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: None,
+        conn: Some(&conn),
+        workspace: &workspace,
+        project_id: &project_id,
+    };
+    let request = make_request(
+        "tools/call",
+        json!({ "name": "sync_repo", "arguments": {} }),
+    );
+    let response = tool_ctx.handle_with_schema(&request, SchemaStatus::NotIndexed);
+    let payload = extract_payload_from_response(&response);
+    assert_eq!(payload["error"]["code"], "insufficient_disk", "{payload}");
+    let data = &payload["error"]["data"];
+    assert!(
+        data["available_bytes"].as_u64().unwrap() < data["required_bytes"].as_u64().unwrap(),
+        "{payload}"
+    );
+    assert!(
+        cruxe_state::jobs::get_active_job(&conn, &project_id)
+            .unwrap()
+            .is_none(),
+        "a refused run must not leave a reserved job behind"
+    );
+}
+
+// ------------------------------------------------------------------
+// T220: no notifications when client doesn't support them; fallback polling fields available
+// ------------------------------------------------------------------
+
+#[test]
+fn t220_index_repo_without_notifications_uses_index_status_progress_fields() {
+    let tmp = tempfile::tempdir().unwrap();
+    let workspace = tmp.path().join("workspace");
+    std::fs::create_dir_all(&workspace).unwrap();
+    create_rust_workspace(&workspace, 160);
+    let (config, conn, project_id) = setup_indexing_runtime(&workspace);
+
+    let notifier_impl = Arc::new(RecordingNotifier::default());
+    let notifier: Arc<dyn ProgressNotifier> = notifier_impl.clone();
+
+    let index_request = make_request(
+        "tools/call",
+        json!({
+            "name": "index_repo",
+            "arguments": {
+                "force": true
+            }
+        }),
+    );
+    let index_response = handle_request_with_ctx(
+        &index_request,
+        &RequestContext {
+            config: &config,
+            index_set: None,
+            schema_status: SchemaStatus::NotIndexed,
+            compatibility_reason: None,
+            conn: Some(&conn),
+            workspace: &workspace,
+            project_id: &project_id,
+            prewarm_status: &test_prewarm_status(),
+            server_start: &test_server_start(),
+            notifier,
+            progress_token: None,
+        },
+    );
+    assert!(
+        index_response.error.is_none(),
+        "index_repo should succeed: {:?}",
+        index_response.error
+    );
+    assert_eq!(
+        notifier_impl.event_count("begin")
+            + notifier_impl.event_count("report")
+            + notifier_impl.event_count("end"),
+        0,
+        "no notifications should be emitted when client does not declare progress support"
+    );
+
+    // The stub indexer never claims the job index_repo reserved; free the live
+    // ref so the fixture job below is the active one.
+    let reserved = cruxe_state::jobs::get_active_job(&conn, &project_id)
+        .unwrap()
+        .expect("index_repo reserves a job");
+    cruxe_state::jobs::release_reserved_job(&conn, &reserved.job_id).unwrap();
+
+    let now = "2026-02-24T00:00:00Z".to_string();
+    let active_job = cruxe_state::jobs::IndexJob {
+        job_id: "job-progress-fields".to_string(),
+        project_id: project_id.clone(),
+        r#ref: cruxe_core::constants::REF_LIVE.to_string(),
+        mode: "incremental".to_string(),
+        head_commit: None,
+        sync_id: None,
+        status: "running".to_string(),
+        changed_files: 0,
+        duration_ms: None,
+        error_message: None,
+        retry_count: 0,
+        progress_token: Some("index-job-job-progress-fields".to_string()),
+        files_scanned: 120,
+        files_indexed: 50,
+        symbols_extracted: 300,
+        files_skipped: 0,
+        files_unchanged: 0,
+        files_excluded: 0,
+        files_per_second: None,
+        index_commits: 0,
+        created_at: now.clone(),
+        updated_at: now,
+        error_data: None,
+    };
+    cruxe_state::jobs::create_job(&conn, &active_job).unwrap();
+
+    let status_request = make_request(
+        "tools/call",
+        json!({
+            "name": "index_status",
+            "arguments": {}
+        }),
+    );
+    let status_response = handle_request_with_ctx(
+        &status_request,
+        &RequestContext {
+            config: &config,
+            index_set: None,
+            schema_status: SchemaStatus::NotIndexed,
+            compatibility_reason: None,
+            conn: Some(&conn),
+            workspace: &workspace,
+            project_id: &project_id,
+            prewarm_status: &test_prewarm_status(),
+            server_start: &test_server_start(),
+            notifier: Arc::new(NullProgressNotifier),
+            progress_token: None,
+        },
+    );
+    assert!(
+        status_response.error.is_none(),
+        "index_status should succeed"
+    );
+    let payload = extract_payload_from_response(&status_response);
+    let active_job = payload
+        .get("active_job")
+        .and_then(|v| v.as_object())
+        .expect("active_job should be present");
+    assert!(active_job.get("files_scanned").unwrap().is_number());
+    assert!(active_job.get("files_indexed").unwrap().is_number());
+    assert!(active_job.get("symbols_extracted").unwrap().is_number());
+    assert!(
+        active_job
+            .get("estimated_completion_pct")
+            .unwrap()
+            .is_number()
+    );
+}
+
+#[test]
+fn t472_index_status_surfaces_semantic_profile_recommendation_when_enabled() {
+    let tmp = tempfile::tempdir().unwrap();
+    let workspace = tmp.path().join("workspace");
+    std::fs::create_dir_all(&workspace).unwrap();
+    let (mut config, conn, project_id) = setup_indexing_runtime(&workspace);
+    config.search.semantic.profile_advisor_mode = "suggest".to_string();
+
+    let ref_name = cruxe_core::constants::REF_LIVE;
+    for (path, language) in [
+        ("src/lib.rs", "rust"),
+        ("src/auth.rs", "rust"),
+        ("src/app.ts", "typescript"),
+    ] {
+        cruxe_state::manifest::upsert_manifest(
+            &conn,
+            &cruxe_state::manifest::ManifestEntry {
+                repo: project_id.clone(),
+                r#ref: ref_name.to_string(),
+                path: path.to_string(),
+                content_hash: format!("hash-{path}"),
+                size_bytes: 128,
+                mtime_ns: Some(1),
+                language: Some(language.to_string()),
+                indexed_at: "2026-02-26T00:00:00Z".to_string(),
+            },
+        )
+        .unwrap();
+    }
+
+    let status_request = make_request(
+        "tools/call",
+        json!({
+            "name": "index_status",
+            "arguments": {}
+        }),
+    );
+    let status_response = handle_request_with_ctx(
+        &status_request,
+        &RequestContext {
+            config: &config,
+            index_set: None,
+            schema_status: SchemaStatus::Compatible,
+            compatibility_reason: None,
+            conn: Some(&conn),
+            workspace: &workspace,
+            project_id: &project_id,
+            prewarm_status: &test_prewarm_status(),
+            server_start: &test_server_start(),
+            notifier: Arc::new(NullProgressNotifier),
+            progress_token: None,
+        },
+    );
+    assert!(
+        status_response.error.is_none(),
+        "index_status should succeed"
+    );
+
+    let payload = extract_payload_from_response(&status_response);
+    let recommendation = payload
+        .get("semantic_profile_recommendation")
+        .and_then(|value| value.as_object())
+        .expect("semantic_profile_recommendation should be present");
+    assert!(
+        recommendation
+            .get("profile")
+            .and_then(|value| value.as_str())
+            .is_some()
+    );
+    assert!(
+        recommendation
+            .get("repo_size_bucket")
+            .and_then(|value| value.as_str())
+            .is_some()
+    );
+    assert!(
+        recommendation
+            .get("reason_codes")
+            .and_then(|value| value.as_array())
+            .is_some()
+    );
+}
+
+// ------------------------------------------------------------------
+// T464: index launcher honors override binary and required env propagation
+// ------------------------------------------------------------------
+
+#[cfg(unix)]
+#[test]
+fn t464_index_launcher_override_binary_and_env_propagation() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let _env_guard = ENV_LOCK.lock().expect("env lock");
+    let tmp = tempfile::tempdir().unwrap();
+    let workspace = tmp.path().join("workspace");
+    std::fs::create_dir_all(&workspace).unwrap();
+    let (config, conn, project_id) = setup_indexing_runtime(&workspace);
+
+    let capture_dir = tmp.path().join("capture");
+    std::fs::create_dir_all(&capture_dir).unwrap();
+    let script_path = tmp.path().join("mock-indexer.sh");
+    let script = format!(
+        "#!/bin/sh\nprintf '%s' \"$CRUXE_PROJECT_ID\" > \"{}\"\nprintf '%s' \"$CRUXE_STORAGE_DATA_DIR\" > \"{}\"\nprintf '%s' \"$CRUXE_JOB_ID\" > \"{}\"\n",
+        capture_dir.join("project_id.txt").display(),
+        capture_dir.join("storage_dir.txt").display(),
+        capture_dir.join("job_id.txt").display(),
+    );
+    std::fs::write(&script_path, script).unwrap();
+    let mut perms = std::fs::metadata(&script_path).unwrap().permissions();
+    perms.set_mode(0o755);
+    std::fs::set_permissions(&script_path, perms).unwrap();
+
+    unsafe {
+        std::env::set_var("CRUXE_INDEX_BIN", &script_path);
+    }
+
+    let request = make_request(
+        "tools/call",
+        json!({
+            "name": "index_repo",
+            "arguments": { "force": false }
+        }),
+    );
+    let response = handle_request_with_ctx(
+        &request,
+        &RequestContext {
+            config: &config,
+            index_set: None,
+            schema_status: SchemaStatus::NotIndexed,
+            compatibility_reason: None,
+            conn: Some(&conn),
+            workspace: &workspace,
+            project_id: &project_id,
+            prewarm_status: &test_prewarm_status(),
+            server_start: &test_server_start(),
+            notifier: Arc::new(NullProgressNotifier),
+            progress_token: None,
+        },
+    );
+
+    unsafe {
+        std::env::remove_var("CRUXE_INDEX_BIN");
+    }
+
+    assert!(
+        response.error.is_none(),
+        "index_repo should spawn override binary"
+    );
+    let payload = extract_payload_from_response(&response);
+    assert!(
+        payload
+            .get("progress_token")
+            .and_then(|v| v.as_str())
+            .is_some_and(|token| token.starts_with("index-job-")),
+        "index_repo should return server-generated progress_token"
+    );
+
+    for _ in 0..80 {
+        let job_path = capture_dir.join("job_id.txt");
+        if job_path.exists()
+            && std::fs::metadata(&job_path)
+                .map(|m| m.len() > 0)
+                .unwrap_or(false)
+        {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(25));
+    }
+
+    let captured_project_id = std::fs::read_to_string(capture_dir.join("project_id.txt"))
+        .expect("override launcher should capture CRUXE_PROJECT_ID");
+    let captured_storage_dir = std::fs::read_to_string(capture_dir.join("storage_dir.txt"))
+        .expect("override launcher should capture CRUXE_STORAGE_DATA_DIR");
+    let captured_job_id = std::fs::read_to_string(capture_dir.join("job_id.txt"))
+        .expect("override launcher should capture CRUXE_JOB_ID");
+
+    assert_eq!(captured_project_id, project_id);
+    assert_eq!(captured_storage_dir, config.storage.data_dir);
+    assert!(
+        !captured_job_id.trim().is_empty(),
+        "captured job id should be non-empty"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn t489_reaper_kills_overdue_indexer_and_fails_job() {
+    let tmp = tempfile::tempdir().unwrap();
+    let db_path = tmp.path().join("state.db");
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    cruxe_state::schema::create_tables(&conn).unwrap();
+    let project_id = "timeout-project";
+    cruxe_state::project::create_project(
+        &conn,
+        &Project {
+            project_id: project_id.to_string(),
+            repo_root: tmp.path().to_string_lossy().to_string(),
+            display_name: None,
+            default_ref: "live".to_string(),
+            vcs_mode: false,
+            schema_version: cruxe_core::constants::SCHEMA_VERSION,
+            parser_version: cruxe_core::constants::PARSER_VERSION,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+        },
+    )
+    .unwrap();
+    cruxe_state::jobs::create_job(
+        &conn,
+        &cruxe_state::jobs::IndexJob {
+            job_id: "job_wedged".to_string(),
+            project_id: project_id.to_string(),
+            r#ref: "live".to_string(),
+            mode: "full".to_string(),
+            head_commit: None,
+            sync_id: None,
+            status: "running".to_string(),
+            changed_files: 0,
+            duration_ms: None,
+            error_message: None,
+            retry_count: 0,
+            progress_token: None,
+            files_scanned: 0,
+            files_indexed: 0,
+            symbols_extracted: 0,
+            files_skipped: 0,
+            files_unchanged: 0,
+            files_excluded: 0,
+            files_per_second: None,
+            index_commits: 0,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+            error_data: None,
+        },
+    )
+    .unwrap();
+    assert!(has_active_job(Some(&conn), project_id));
+
+    let child = std::process::Command::new("sleep")
+        .arg("30")
+        .spawn()
+        .unwrap();
+    let started = std::time::Instant::now();
+    crate::index_launcher::reap_with_deadline(
+        child,
+        &db_path,
+        "job_wedged",
+        Some(std::time::Duration::from_millis(50)),
+    );
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+
+    assert!(!has_active_job(Some(&conn), project_id));
+    let job = cruxe_state::jobs::get_recent_jobs(&conn, project_id, 1)
+        .unwrap()
+        .remove(0);
+    assert_eq!(job.status, "failed");
+    assert!(
+        job.error_message
+            .as_deref()
+            .is_some_and(|message| message.contains("max_job_duration_secs"))
+    );
+    assert_eq!(job.error_data.unwrap()["reason"], "timeout");
+}
+
+#[cfg(unix)]
+#[test]
+fn t466_bootstrap_launcher_override_binary_and_env_propagation() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let _env_guard = ENV_LOCK.lock().expect("env lock");
+    let tmp = tempfile::tempdir().unwrap();
+    let workspace = tmp.path().join("workspace-bootstrap");
+    std::fs::create_dir_all(&workspace).unwrap();
+    let project_id = generate_project_id(&workspace.to_string_lossy());
+    let data_dir = tmp.path().join("cc-data").join("data").join(&project_id);
+    let storage_data_dir = tmp.path().join("cc-data");
+    let storage_data_dir_str = storage_data_dir.to_string_lossy().to_string();
+
+    let capture_dir = tmp.path().join("capture-bootstrap");
+    std::fs::create_dir_all(&capture_dir).unwrap();
+    let script_path = tmp.path().join("mock-bootstrap-indexer.sh");
+    let script = format!(
+        "#!/bin/sh\nprintf '%s' \"$CRUXE_PROJECT_ID\" > \"{}\"\nprintf '%s' \"$CRUXE_STORAGE_DATA_DIR\" > \"{}\"\nprintf '%s' \"$CRUXE_JOB_ID\" > \"{}\"\n",
+        capture_dir.join("project_id.txt").display(),
+        capture_dir.join("storage_dir.txt").display(),
+        capture_dir.join("job_id.txt").display(),
+    );
+    std::fs::write(&script_path, script).unwrap();
+    let mut perms = std::fs::metadata(&script_path).unwrap().permissions();
+    perms.set_mode(0o755);
+    std::fs::set_permissions(&script_path, perms).unwrap();
+
+    unsafe {
+        std::env::set_var("CRUXE_INDEX_BIN", &script_path);
+    }
+
+    let result = bootstrap_and_index(
+        &workspace,
+        &project_id,
+        &data_dir,
+        &storage_data_dir_str,
+        constants::REF_LIVE,
+        None,
+    );
+
+    unsafe {
+        std::env::remove_var("CRUXE_INDEX_BIN");
+    }
+
+    assert!(
+        result.is_ok(),
+        "bootstrap launcher should use override binary"
+    );
+
+    for _ in 0..80 {
+        let job_path = capture_dir.join("job_id.txt");
+        if job_path.exists()
+            && std::fs::metadata(&job_path)
+                .map(|m| m.len() > 0)
+                .unwrap_or(false)
+        {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(25));
+    }
+
+    let captured_project_id = std::fs::read_to_string(capture_dir.join("project_id.txt"))
+        .expect("bootstrap launcher should capture CRUXE_PROJECT_ID");
+    let captured_storage_dir = std::fs::read_to_string(capture_dir.join("storage_dir.txt"))
+        .expect("bootstrap launcher should capture CRUXE_STORAGE_DATA_DIR");
+    let captured_job_id = std::fs::read_to_string(capture_dir.join("job_id.txt"))
+        .expect("bootstrap launcher should capture CRUXE_JOB_ID");
+
+    assert_eq!(captured_project_id, project_id);
+    assert_eq!(captured_storage_dir, storage_data_dir_str);
+    assert!(
+        !captured_job_id.trim().is_empty(),
+        "captured bootstrap job id should be non-empty"
+    );
+}
+
+#[test]
+fn t467_connection_manager_reopens_after_failure_and_invalidate() {
+    let tmp = tempfile::tempdir().unwrap();
+    let blocking_parent = tmp.path().join("nested");
+    std::fs::write(&blocking_parent, "blocking").unwrap();
+    let db_path = blocking_parent.join("state.db");
+    let manager = ConnectionManager::new();
+
+    assert!(
+        manager.get_or_open(&db_path).is_err(),
+        "opening when parent path is a file should fail"
+    );
+
+    std::fs::remove_file(&blocking_parent).unwrap();
+    std::fs::create_dir_all(db_path.parent().unwrap()).unwrap();
+    let first = manager
+        .get_or_open(&db_path)
+        .expect("open sqlite after setup");
+    {
+        let conn = first.lock().expect("lock sqlite");
+        cruxe_state::schema::create_tables(&conn).unwrap();
+    }
+
+    manager.invalidate(&db_path);
+    let second = manager
+        .get_or_open(&db_path)
+        .expect("re-open sqlite after invalidate");
+    let conn = second.lock().expect("lock re-opened sqlite");
+    let one: i64 = conn.query_row("SELECT 1", [], |row| row.get(0)).unwrap();
+    assert_eq!(one, 1);
+}
+
+#[test]
+fn t468_connection_manager_evicts_oldest_idle_entry_when_at_capacity() {
+    let tmp = tempfile::tempdir().unwrap();
+    let db1 = tmp.path().join("db1.sqlite");
+    let db2 = tmp.path().join("db2.sqlite");
+    let db3 = tmp.path().join("db3.sqlite");
+    let manager = ConnectionManager::with_capacity(2);
+
+    drop(manager.get_or_open(&db1).expect("open db1"));
+    std::thread::sleep(Duration::from_millis(5));
+    drop(manager.get_or_open(&db2).expect("open db2"));
+    assert!(manager.contains_connection(&db1));
+    assert!(manager.contains_connection(&db2));
+
+    drop(manager.get_or_open(&db3).expect("open db3"));
+
+    assert_eq!(
+        manager.cached_connection_count(),
+        2,
+        "manager should enforce bounded cache size for idle connections"
+    );
+    assert!(
+        !manager.contains_connection(&db1),
+        "oldest idle connection should be evicted first"
+    );
+    assert!(manager.contains_connection(&db2));
+    assert!(manager.contains_connection(&db3));
+}
+
+#[test]
+fn t469_connection_manager_keeps_in_use_entries_even_when_over_capacity() {
+    let tmp = tempfile::tempdir().unwrap();
+    let db1 = tmp.path().join("db1.sqlite");
+    let db2 = tmp.path().join("db2.sqlite");
+    let manager = ConnectionManager::with_capacity(1);
+
+    let held = manager.get_or_open(&db1).expect("open db1");
+    let second = manager.get_or_open(&db2).expect("open db2");
+
+    assert!(
+        manager.contains_connection(&db1),
+        "in-use entry should remain cached"
+    );
+    assert!(manager.contains_connection(&db2));
+    assert!(
+        manager.cached_connection_count() >= 2,
+        "manager may temporarily exceed capacity when all older entries are in use"
+    );
+
+    drop(second);
+    drop(held);
+}
+
+// ------------------------------------------------------------------
+// T465: canonical error envelope + metadata enum snapshots
+// ------------------------------------------------------------------
+
+#[test]
+fn t465_protocol_error_envelope_and_metadata_enum_snapshots() {
+    let tmp = tempfile::tempdir().unwrap();
+    let workspace = tmp.path().join("workspace");
+    std::fs::create_dir_all(&workspace).unwrap();
+    let config = Config::default();
+    let project_id = generate_project_id(&workspace.to_string_lossy());
+
+    let validation_request = make_request(
+        "tools/call",
+        json!({
+            "name": "locate_symbol",
+            "arguments": {}
+        }),
+    );
+    let validation_response = handle_request_with_ctx(
+        &validation_request,
+        &RequestContext {
+            config: &config,
+            index_set: None,
+            schema_status: SchemaStatus::Compatible,
+            compatibility_reason: None,
+            conn: None,
+            workspace: &workspace,
+            project_id: &project_id,
+            prewarm_status: &test_prewarm_status(),
+            server_start: &test_server_start(),
+            notifier: Arc::new(NullProgressNotifier),
+            progress_token: None,
+        },
+    );
+    let validation_payload = extract_payload_from_response(&validation_response);
+    assert_eq!(
+        validation_payload.get("error").and_then(|v| v.get("code")),
+        Some(&json!("invalid_input"))
+    );
+    assert_eq!(
+        validation_payload
+            .get("metadata")
+            .and_then(|v| v.get("indexing_status")),
+        Some(&json!("ready"))
+    );
+    assert_eq!(
+        validation_payload
+            .get("metadata")
+            .and_then(|v| v.get("result_completeness")),
+        Some(&json!("complete"))
+    );
+
+    let compatibility_request = make_request(
+        "tools/call",
+        json!({
+            "name": "search_code",
+            "arguments": { "query": "foo" }
+        }),
+    );
+    let compatibility_response = handle_request_with_ctx(
+        &compatibility_request,
+        &RequestContext {
+            config: &config,
+            index_set: None,
+            schema_status: SchemaStatus::NotIndexed,
+            compatibility_reason: Some("No index found"),
+            conn: None,
+            workspace: &workspace,
+            project_id: &project_id,
+            prewarm_status: &test_prewarm_status(),
+            server_start: &test_server_start(),
+            notifier: Arc::new(NullProgressNotifier),
+            progress_token: None,
+        },
+    );
+    let compatibility_payload = extract_payload_from_response(&compatibility_response);
+    assert_eq!(
+        compatibility_payload
+            .get("error")
+            .and_then(|v| v.get("code")),
+        Some(&json!("project_not_found"))
+    );
+    assert_eq!(
+        compatibility_payload
+            .get("metadata")
+            .and_then(|v| v.get("indexing_status")),
+        Some(&json!("not_indexed"))
+    );
+    assert_eq!(
+        compatibility_payload
+            .get("metadata")
+            .and_then(|v| v.get("result_completeness")),
+        Some(&json!("partial"))
+    );
+}
+
+// ------------------------------------------------------------------
+// T456: restart recovery report + warmset visibility in health_check
+// ------------------------------------------------------------------
+
+#[test]
+fn t456_health_check_surfaces_interrupted_recovery_and_warmset_members() {
+    let tmp = tempfile::tempdir().unwrap();
+    let workspace = tmp.path().join("workspace-a");
+    let workspace_b = tmp.path().join("workspace-b");
+    std::fs::create_dir_all(&workspace).unwrap();
+    std::fs::create_dir_all(&workspace_b).unwrap();
+
+    let (config, conn, project_id) = setup_indexing_runtime(&workspace);
+    let project_b_id = generate_project_id(&workspace_b.to_string_lossy());
+    let now = "2026-02-24T00:00:00Z".to_string();
     let project_b = Project {
         project_id: project_b_id.clone(),
         repo_root: workspace_b.to_string_lossy().to_string(),
@@ -7243,63 +9532,523 @@ fn t456_health_check_surfaces_interrupted_recovery_and_warmset_members() {
         schema_version: cruxe_core::constants::SCHEMA_VERSION,
         parser_version: cruxe_core::constants::PARSER_VERSION,
         created_at: now.clone(),
-        updated_at: now.clone(),
+        updated_at: now.clone(),
+    };
+    cruxe_state::project::create_project(&conn, &project_b).unwrap();
+
+    cruxe_state::workspace::register_workspace(
+        &conn,
+        &workspace.to_string_lossy(),
+        Some(&project_id),
+        false,
+        "2026-02-24T00:00:01Z",
+    )
+    .unwrap();
+    cruxe_state::workspace::register_workspace(
+        &conn,
+        &workspace_b.to_string_lossy(),
+        Some(&project_b_id),
+        true,
+        "2026-02-24T00:00:02Z",
+    )
+    .unwrap();
+
+    let interrupted_job = cruxe_state::jobs::IndexJob {
+        job_id: "job-recovery-1".to_string(),
+        project_id: project_id.clone(),
+        r#ref: cruxe_core::constants::REF_LIVE.to_string(),
+        mode: "incremental".to_string(),
+        head_commit: None,
+        sync_id: None,
+        status: "running".to_string(),
+        changed_files: 0,
+        duration_ms: None,
+        error_message: None,
+        retry_count: 0,
+        progress_token: Some("index-job-job-recovery-1".to_string()),
+        files_scanned: 0,
+        files_indexed: 0,
+        symbols_extracted: 0,
+        files_skipped: 0,
+        files_unchanged: 0,
+        files_excluded: 0,
+        files_per_second: None,
+        index_commits: 0,
+        created_at: now.clone(),
+        updated_at: now,
+        error_data: None,
+    };
+    cruxe_state::jobs::create_job(&conn, &interrupted_job).unwrap();
+    let marked = cruxe_state::jobs::mark_interrupted_jobs(&conn).unwrap();
+    assert_eq!(marked, 1, "should mark running job as interrupted");
+
+    let request = make_request(
+        "tools/call",
+        json!({
+            "name": "health_check",
+            "arguments": {}
+        }),
+    );
+    let response = handle_request_with_ctx(
+        &request,
+        &RequestContext {
+            config: &config,
+            index_set: None,
+            schema_status: SchemaStatus::NotIndexed,
+            compatibility_reason: None,
+            conn: Some(&conn),
+            workspace: &workspace,
+            project_id: &project_id,
+            prewarm_status: &test_prewarm_status(),
+            server_start: &test_server_start(),
+            notifier: Arc::new(NullProgressNotifier),
+            progress_token: None,
+        },
+    );
+    assert!(response.error.is_none(), "health_check should succeed");
+    let payload = extract_payload_from_response(&response);
+
+    let report = payload
+        .get("interrupted_recovery_report")
+        .expect("interrupted recovery report should be present");
+    assert_eq!(report.get("detected"), Some(&json!(true)));
+    assert!(
+        report
+            .get("interrupted_jobs")
+            .and_then(|v| v.as_u64())
+            .is_some_and(|count| count >= 1)
+    );
+
+    let members = payload["workspace_warmset"]["members"]
+        .as_array()
+        .expect("warmset members should be an array");
+    let has_workspace_a = members.iter().any(|m| {
+        m.as_str()
+            .is_some_and(|s| s == workspace.to_string_lossy().as_ref())
+    });
+    let has_workspace_b = members.iter().any(|m| {
+        m.as_str()
+            .is_some_and(|s| s == workspace_b.to_string_lossy().as_ref())
+    });
+    assert!(
+        has_workspace_a || has_workspace_b,
+        "warmset members should include recent registered workspaces"
+    );
+}
+
+// ------------------------------------------------------------------
+// T457: warmset-enabled first-query smoke guard
+// ------------------------------------------------------------------
+
+#[test]
+fn t457_first_query_smoke_guard() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+    let project_id = "test-repo";
+
+    let mut samples = Vec::new();
+    for _ in 0..12 {
+        let request = make_request(
+            "tools/call",
+            json!({
+                "name": "search_code",
+                "arguments": {
+                    "query": "validate_token"
+                }
+            }),
+        );
+        let started = Instant::now();
+        let response = handle_request_with_ctx(
+            &request,
+            &RequestContext {
+                config: &config,
+                index_set: Some(&index_set),
+                schema_status: SchemaStatus::Compatible,
+                compatibility_reason: None,
+                conn: Some(&conn),
+                workspace,
+                project_id,
+                prewarm_status: &test_prewarm_status(),
+                server_start: &test_server_start(),
+                notifier: Arc::new(NullProgressNotifier),
+                progress_token: None,
+            },
+        );
+        assert!(response.error.is_none(), "search_code should succeed");
+        samples.push(started.elapsed());
+    }
+    samples.sort();
+    let p95 = samples[samples.len() * 95 / 100];
+    assert!(
+        p95.as_millis() < 5_000,
+        "warmset-enabled first-query smoke budget should remain < 5000ms, got {}ms",
+        p95.as_millis()
+    );
+}
+
+#[test]
+#[ignore = "benchmark harness"]
+fn benchmark_t457_first_query_p95_under_400ms() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+    let project_id = "test-repo";
+
+    let mut samples = Vec::new();
+    for _ in 0..12 {
+        let request = make_request(
+            "tools/call",
+            json!({
+                "name": "search_code",
+                "arguments": {
+                    "query": "validate_token"
+                }
+            }),
+        );
+        let started = Instant::now();
+        let response = handle_request_with_ctx(
+            &request,
+            &RequestContext {
+                config: &config,
+                index_set: Some(&index_set),
+                schema_status: SchemaStatus::Compatible,
+                compatibility_reason: None,
+                conn: Some(&conn),
+                workspace,
+                project_id,
+                prewarm_status: &test_prewarm_status(),
+                server_start: &test_server_start(),
+                notifier: Arc::new(NullProgressNotifier),
+                progress_token: None,
+            },
+        );
+        assert!(response.error.is_none(), "search_code should succeed");
+        samples.push(started.elapsed());
+    }
+    samples.sort();
+    let p95 = samples[samples.len() * 95 / 100];
+    assert!(
+        p95.as_millis() < 400,
+        "warmset-enabled first-query benchmark p95 should be < 400ms, got {}ms",
+        p95.as_millis()
+    );
+}
+
+#[cfg(unix)]
+fn vcs_fixture_setup_script() -> std::path::PathBuf {
+    std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../testdata/fixtures/vcs-sample/setup.sh")
+}
+
+#[cfg(unix)]
+fn setup_vcs_mcp_fixture() -> (
+    tempfile::TempDir,
+    std::path::PathBuf,
+    Config,
+    rusqlite::Connection,
+    String,
+) {
+    let tmp = tempfile::tempdir().expect("create tempdir");
+    let script = vcs_fixture_setup_script();
+    assert!(
+        script.exists(),
+        "missing fixture script: {}",
+        script.display()
+    );
+    let output = std::process::Command::new("bash")
+        .arg(&script)
+        .arg(tmp.path())
+        .output()
+        .expect("run fixture setup");
+    assert!(
+        output.status.success(),
+        "fixture setup failed:\nstdout:{}\nstderr:{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let workspace = tmp.path().join("vcs-sample");
+    let mut config = Config::default();
+    config.storage.data_dir = tmp.path().join("cc-data").to_string_lossy().to_string();
+
+    let project_id = generate_project_id(&workspace.to_string_lossy());
+    let data_dir = config.project_data_dir(&project_id);
+    std::fs::create_dir_all(&data_dir).expect("create data dir");
+    let db_path = data_dir.join(cruxe_core::constants::STATE_DB_FILE);
+    let conn = cruxe_state::db::open_connection(&db_path).expect("open sqlite");
+    cruxe_state::schema::create_tables(&conn).expect("create schema");
+
+    let now = "2026-02-25T00:00:00Z".to_string();
+    let project = Project {
+        project_id: project_id.clone(),
+        repo_root: workspace.to_string_lossy().to_string(),
+        display_name: Some("vcs-mcp-test".to_string()),
+        default_ref: "main".to_string(),
+        vcs_mode: true,
+        schema_version: cruxe_core::constants::SCHEMA_VERSION,
+        parser_version: cruxe_core::constants::PARSER_VERSION,
+        created_at: now.clone(),
+        updated_at: now,
     };
-    cruxe_state::project::create_project(&conn, &project_b).unwrap();
+    cruxe_state::project::create_project(&conn, &project).expect("create project");
+
+    (tmp, workspace, config, conn, project_id)
+}
+
+#[cfg(unix)]
+fn git_in_repo(repo_root: &std::path::Path, args: &[&str]) -> String {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(repo_root)
+        .output()
+        .expect("run git command");
+    assert!(
+        output.status.success(),
+        "git {:?} failed:\nstdout:{}\nstderr:{}",
+        args,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+#[cfg(unix)]
+fn index_checkout_as_main(
+    repo_root: &std::path::Path,
+    data_dir: &std::path::Path,
+    conn: &rusqlite::Connection,
+    project_id: &str,
+) {
+    use cruxe_indexer::{
+        languages, parser, scanner, snippet_extract, symbol_extract, sync_incremental, writer,
+    };
+    use cruxe_state::manifest;
+    use cruxe_state::tantivy_index::IndexSet;
+
+    let index_set = IndexSet::open(data_dir).expect("open base index");
+    let files = scanner::scan_directory_filtered(
+        repo_root,
+        cruxe_core::constants::MAX_FILE_SIZE,
+        &["rust".to_string()],
+    );
+
+    for file in files {
+        let source = std::fs::read_to_string(&file.path).expect("read source file");
+        let tree = parser::parse_file(&source, &file.language).expect("parse source");
+        let extracted = languages::extract_symbols(&tree, &source, &file.language);
+        let symbols = symbol_extract::build_symbol_records(
+            &extracted,
+            project_id,
+            "main",
+            &file.relative_path,
+            None,
+        );
+        let snippets = snippet_extract::build_snippet_records(
+            &extracted,
+            project_id,
+            "main",
+            &file.relative_path,
+            None,
+        );
+        let record = cruxe_core::types::FileRecord {
+            repo: project_id.to_string(),
+            r#ref: "main".to_string(),
+            commit: None,
+            path: file.relative_path.clone(),
+            filename: file
+                .path
+                .file_name()
+                .map(|value| value.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            language: file.language.clone(),
+            content_hash: blake3::hash(source.as_bytes()).to_hex().to_string(),
+            size_bytes: source.len() as u64,
+            updated_at: cruxe_core::time::now_iso8601(),
+            content_head: Some(source.lines().take(20).collect::<Vec<_>>().join("\n")),
+            encoding_warning: false,
+        };
+        writer::write_file_records(&index_set, conn, &symbols, &snippets, &record)
+            .expect("write base records");
+    }
+
+    let head = cruxe_core::vcs::detect_head_commit(repo_root).expect("detect base head");
+    sync_incremental::persist_branch_sync_state(
+        conn,
+        &sync_incremental::BranchSyncStateUpdate {
+            repo: project_id.to_string(),
+            ref_name: "main".to_string(),
+            merge_base_commit: None,
+            last_indexed_commit: head,
+            overlay_dir: None,
+            file_count: manifest::file_count(conn, project_id, "main").expect("base file count")
+                as i64,
+            symbol_count: 0,
+            is_default_branch: true,
+        },
+    )
+    .expect("persist main branch state");
+}
+
+#[cfg(unix)]
+fn sync_vcs_ref(
+    conn: &mut rusqlite::Connection,
+    repo_root: &std::path::Path,
+    data_dir: &std::path::Path,
+    project_id: &str,
+    ref_name: &str,
+    sync_id: &str,
+) {
+    use cruxe_indexer::sync_incremental::{self, IncrementalSyncRequest};
+    let adapter = cruxe_vcs::Git2VcsAdapter;
+    sync_incremental::run_incremental_sync(
+        &adapter,
+        conn,
+        IncrementalSyncRequest {
+            repo_root,
+            data_dir,
+            project_id,
+            ref_name,
+            base_ref: "main",
+            sync_id,
+            last_indexed_commit: None,
+            is_default_branch: false,
+        },
+    )
+    .expect("run incremental sync");
+}
+
+#[cfg(unix)]
+#[test]
+fn t288_mcp_search_code_vcs_ref_includes_source_layer_handles_and_schema_status() {
+    let (_tmp, workspace, config, mut conn, project_id) = setup_vcs_mcp_fixture();
+    let data_dir = config.project_data_dir(&project_id);
+
+    git_in_repo(&workspace, &["checkout", "main"]);
+    index_checkout_as_main(&workspace, &data_dir, &conn, &project_id);
+    let index_set =
+        cruxe_state::tantivy_index::IndexSet::open_existing(&data_dir).expect("open base index");
+
+    git_in_repo(&workspace, &["checkout", "feat/add-file"]);
+    sync_vcs_ref(
+        &mut conn,
+        &workspace,
+        &data_dir,
+        &project_id,
+        "feat/add-file",
+        "sync-mcp-add-file",
+    );
+
+    let request = make_request(
+        "tools/call",
+        json!({
+            "name": "search_code",
+            "arguments": {
+                "query": "added_branch_file",
+                "ref": "feat/add-file"
+            }
+        }),
+    );
+    let response = handle_request_with_ctx(
+        &request,
+        &RequestContext {
+            config: &config,
+            index_set: Some(&index_set),
+            schema_status: SchemaStatus::Compatible,
+            compatibility_reason: None,
+            conn: Some(&conn),
+            workspace: &workspace,
+            project_id: &project_id,
+            prewarm_status: &test_prewarm_status(),
+            server_start: &test_server_start(),
+            notifier: Arc::new(NullProgressNotifier),
+            progress_token: None,
+        },
+    );
+    assert!(response.error.is_none(), "search_code should succeed");
+    let payload = extract_payload_from_response(&response);
 
-    cruxe_state::workspace::register_workspace(
-        &conn,
-        &workspace.to_string_lossy(),
-        Some(&project_id),
-        false,
-        "2026-02-24T00:00:01Z",
-    )
-    .unwrap();
-    cruxe_state::workspace::register_workspace(
-        &conn,
-        &workspace_b.to_string_lossy(),
-        Some(&project_b_id),
-        true,
-        "2026-02-24T00:00:02Z",
-    )
-    .unwrap();
+    let metadata = payload.get("metadata").expect("metadata should be present");
+    assert_eq!(
+        metadata
+            .get("schema_status")
+            .and_then(|value| value.as_str()),
+        Some("compatible"),
+        "metadata should include schema_status compatibility signal"
+    );
 
-    let interrupted_job = cruxe_state::jobs::IndexJob {
-        job_id: "job-recovery-1".to_string(),
-        project_id: project_id.clone(),
-        r#ref: cruxe_core::constants::REF_LIVE.to_string(),
-        mode: "incremental".to_string(),
-        head_commit: None,
-        sync_id: None,
-        status: "running".to_string(),
-        changed_files: 0,
-        duration_ms: None,
-        error_message: None,
-        retry_count: 0,
-        progress_token: Some("index-job-job-recovery-1".to_string()),
-        files_scanned: 0,
-        files_indexed: 0,
-        symbols_extracted: 0,
-        created_at: now.clone(),
-        updated_at: now,
-    };
-    cruxe_state::jobs::create_job(&conn, &interrupted_job).unwrap();
-    let marked = cruxe_state::jobs::mark_interrupted_jobs(&conn).unwrap();
-    assert_eq!(marked, 1, "should mark running job as interrupted");
+    let results = payload
+        .get("results")
+        .and_then(|value| value.as_array())
+        .expect("results should be present");
+    let added = results
+        .iter()
+        .find(|result| {
+            result.get("path").and_then(|value| value.as_str()) == Some("src/add_file.rs")
+        })
+        .expect("search should include added file result for feature ref");
+    assert_eq!(
+        added.get("source_layer").and_then(|value| value.as_str()),
+        Some("overlay"),
+        "result should expose overlay provenance"
+    );
+    assert!(
+        added
+            .get("symbol_id")
+            .and_then(|value| value.as_str())
+            .is_some_and(|value| !value.is_empty()),
+        "symbol_id should be present for MCP follow-up calls"
+    );
+    assert!(
+        added
+            .get("symbol_stable_id")
+            .and_then(|value| value.as_str())
+            .is_some_and(|value| !value.is_empty()),
+        "symbol_stable_id should be present for MCP follow-up calls"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn t288_mcp_locate_symbol_vcs_ref_reports_overlay_handles_and_schema_status() {
+    let (_tmp, workspace, config, mut conn, project_id) = setup_vcs_mcp_fixture();
+    let data_dir = config.project_data_dir(&project_id);
+
+    git_in_repo(&workspace, &["checkout", "main"]);
+    index_checkout_as_main(&workspace, &data_dir, &conn, &project_id);
+    let index_set =
+        cruxe_state::tantivy_index::IndexSet::open_existing(&data_dir).expect("open base index");
+
+    git_in_repo(&workspace, &["checkout", "feat/modify-sig"]);
+    sync_vcs_ref(
+        &mut conn,
+        &workspace,
+        &data_dir,
+        &project_id,
+        "feat/modify-sig",
+        "sync-mcp-modify-sig",
+    );
 
     let request = make_request(
         "tools/call",
         json!({
-            "name": "health_check",
-            "arguments": {}
+            "name": "locate_symbol",
+            "arguments": {
+                "name": "shared",
+                "ref": "feat/modify-sig"
+            }
         }),
     );
     let response = handle_request_with_ctx(
         &request,
         &RequestContext {
             config: &config,
-            index_set: None,
-            schema_status: SchemaStatus::NotIndexed,
+            index_set: Some(&index_set),
+            schema_status: SchemaStatus::Compatible,
             compatibility_reason: None,
             conn: Some(&conn),
             workspace: &workspace,
@@ -7310,496 +10059,1232 @@ fn t456_health_check_surfaces_interrupted_recovery_and_warmset_members() {
             progress_token: None,
         },
     );
-    assert!(response.error.is_none(), "health_check should succeed");
+    assert!(response.error.is_none(), "locate_symbol should succeed");
+    let payload = extract_payload_from_response(&response);
+
+    let metadata = payload.get("metadata").expect("metadata should be present");
+    assert_eq!(
+        metadata
+            .get("schema_status")
+            .and_then(|value| value.as_str()),
+        Some("compatible"),
+        "metadata should include schema_status compatibility signal"
+    );
+
+    let results = payload
+        .get("results")
+        .and_then(|value| value.as_array())
+        .expect("results should be present");
+    let shared = results
+        .iter()
+        .find(|result| result.get("path").and_then(|value| value.as_str()) == Some("src/lib.rs"))
+        .expect("locate should include shared symbol for feature ref");
+    assert_eq!(
+        shared.get("source_layer").and_then(|value| value.as_str()),
+        Some("overlay"),
+        "locate result should expose overlay provenance"
+    );
+    assert!(
+        shared
+            .get("symbol_id")
+            .and_then(|value| value.as_str())
+            .is_some_and(|value| !value.is_empty()),
+        "locate result should include symbol_id"
+    );
+    assert!(
+        shared
+            .get("symbol_stable_id")
+            .and_then(|value| value.as_str())
+            .is_some_and(|value| !value.is_empty()),
+        "locate result should include symbol_stable_id"
+    );
+}
+
+// ------------------------------------------------------------------
+// T516: warmset LRU eviction is reported in health_check
+// ------------------------------------------------------------------
+
+#[test]
+fn t516_warmset_evicts_least_recently_used_workspace_past_capacity() {
+    let tmp = tempfile::tempdir().unwrap();
+    let workspace = tmp.path().join("workspace-default");
+    std::fs::create_dir_all(&workspace).unwrap();
+    let (config, conn, project_id) = setup_indexing_runtime(&workspace);
+
+    // Capacity is 3 and the serving workspace is always pinned, so only the
+    // two most recently used of these three stay warm.
+    let others: Vec<String> = ["oldest", "middle", "newest"]
+        .iter()
+        .map(|name| tmp.path().join(name).to_string_lossy().to_string())
+        .collect();
+    for (idx, path) in others.iter().enumerate() {
+        cruxe_state::workspace::register_workspace(
+            &conn,
+            path,
+            None,
+            true,
+            &format!("2026-03-0{}T00:00:00Z", idx + 1),
+        )
+        .unwrap();
+    }
+
+    let selection = crate::server::collect_warmset_members(Some(&conn), &workspace, 3);
+    let default_member = workspace.to_string_lossy().to_string();
+    assert_eq!(
+        selection.members,
+        vec![default_member, others[2].clone(), others[1].clone()]
+    );
+    assert_eq!(selection.evicted, vec![others[0].clone()]);
+
+    // Touching the oldest workspace makes it most recent; `middle` is now LRU.
+    cruxe_state::workspace::update_last_used(&conn, &others[0], "2026-03-09T00:00:00Z").unwrap();
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: None,
+        conn: Some(&conn),
+        workspace: &workspace,
+        project_id: &project_id,
+    };
+    let request = make_request(
+        "tools/call",
+        json!({ "name": "health_check", "arguments": {} }),
+    );
+    let response = tool_ctx.handle_with_schema(&request, SchemaStatus::NotIndexed);
     let payload = extract_payload_from_response(&response);
+    assert_eq!(payload["workspace_warmset"]["capacity"], json!(3));
+    assert_eq!(
+        payload["workspace_warmset"]["evicted"],
+        json!([others[1].clone()])
+    );
+    let members = payload["workspace_warmset"]["members"].as_array().unwrap();
+    assert!(members.contains(&json!(others[0].clone())));
+}
+
+#[test]
+fn t517_search_code_language_accepts_an_array_of_languages() {
+    let tmp = tempfile::tempdir().unwrap();
+    let index_set = build_fixture_index(tmp.path());
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: None,
+        workspace,
+        project_id: "test-repo",
+    };
+    let call = |language: Value| {
+        extract_payload_from_response(&call_tool(
+            &tool_ctx,
+            "search_code",
+            json!({ "query": "validate_token", "language": language }),
+        ))
+    };
+    let languages = |payload: &Value| -> Vec<String> {
+        payload["results"]
+            .as_array()
+            .unwrap_or_else(|| panic!("results array: {payload}"))
+            .iter()
+            .filter_map(|r| r["language"].as_str().map(str::to_string))
+            .collect()
+    };
+
+    let single = call(json!("rust"));
+    assert!(!languages(&single).is_empty(), "{single}");
+
+    let listed = call(json!(["go", "rust"]));
+    let listed_languages = languages(&listed);
+    assert!(!listed_languages.is_empty(), "{listed}");
+    assert!(listed_languages.iter().all(|l| l == "rust"), "{listed}");
+
+    let unmatched = call(json!(["go", "typescript"]));
+    assert!(languages(&unmatched).is_empty(), "{unmatched}");
+
+    let invalid = call(json!(["rust", 7]));
+    assert_eq!(invalid["error"]["code"], "invalid_input", "{invalid}");
+    assert!(
+        invalid["error"]["message"]
+            .as_str()
+            .unwrap_or_default()
+            .contains("language"),
+        "{invalid}"
+    );
+}
+
+#[test]
+fn t518_get_file_outline_nested_false_returns_flat_symbols() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: Some(&conn),
+        workspace,
+        project_id: "test-repo",
+    };
+    let call = |nested: bool| {
+        extract_payload_from_response(&call_tool(
+            &tool_ctx,
+            "get_file_outline",
+            json!({ "path": "src/types.rs", "nested": nested }),
+        ))
+    };
+
+    let nested = call(true);
+    let roots = nested["symbols"].as_array().expect("symbols array");
+    assert!(
+        roots
+            .iter()
+            .any(|s| s["children"].as_array().is_some_and(|c| !c.is_empty())),
+        "{nested}"
+    );
+
+    let flat = call(false);
+    let symbols = flat["symbols"].as_array().expect("symbols array");
+    assert_eq!(
+        symbols.len() as u64,
+        flat["metadata"]["symbol_count"].as_u64().unwrap(),
+        "{flat}"
+    );
+    assert!(symbols.len() > roots.len(), "{flat}");
+    assert!(
+        symbols.iter().all(|s| s.get("children").is_none()),
+        "{flat}"
+    );
+    assert!(
+        symbols.iter().any(|s| s["parent_symbol_id"].is_string()),
+        "{flat}"
+    );
+    let line_starts: Vec<u64> = symbols
+        .iter()
+        .map(|s| s["line_start"].as_u64().unwrap())
+        .collect();
+    assert!(line_starts.windows(2).all(|w| w[0] <= w[1]), "{flat}");
+}
+
+#[test]
+fn t519_search_code_semantic_flag_requires_hybrid_mode() {
+    let tmp = tempfile::tempdir().unwrap();
+    let index_set = build_fixture_index(tmp.path());
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: None,
+        workspace,
+        project_id: "test-repo",
+    };
+    let call = |semantic: bool| {
+        extract_payload_from_response(&call_tool(
+            &tool_ctx,
+            "search_code",
+            json!({ "query": "validate_token", "semantic": semantic }),
+        ))
+    };
 
-    let report = payload
-        .get("interrupted_recovery_report")
-        .expect("interrupted recovery report should be present");
-    assert_eq!(report.get("detected"), Some(&json!(true)));
+    let disabled = call(true);
+    assert_eq!(disabled["error"]["code"], "invalid_input", "{disabled}");
     assert!(
-        report
-            .get("interrupted_jobs")
-            .and_then(|v| v.as_u64())
-            .is_some_and(|count| count >= 1)
+        disabled["error"]["message"]
+            .as_str()
+            .unwrap_or_default()
+            .contains("Semantic search is disabled"),
+        "{disabled}"
     );
+    assert_eq!(disabled["error"]["data"]["semantic_mode"], "off");
 
-    let members = payload["workspace_warmset"]["members"]
-        .as_array()
-        .expect("warmset members should be an array");
-    let has_workspace_a = members.iter().any(|m| {
-        m.as_str()
-            .is_some_and(|s| s == workspace.to_string_lossy().as_ref())
-    });
-    let has_workspace_b = members.iter().any(|m| {
-        m.as_str()
-            .is_some_and(|s| s == workspace_b.to_string_lossy().as_ref())
-    });
+    let lexical = call(false);
+    assert!(lexical.get("error").is_none(), "{lexical}");
     assert!(
-        has_workspace_a || has_workspace_b,
-        "warmset members should include recent registered workspaces"
+        !lexical["results"].as_array().unwrap().is_empty(),
+        "{lexical}"
     );
 }
 
-// ------------------------------------------------------------------
-// T457: warmset-enabled first-query smoke guard
-// ------------------------------------------------------------------
+#[test]
+fn t520_tool_calls_carry_a_fresh_request_id_in_metadata() {
+    let tmp = tempfile::tempdir().unwrap();
+    let index_set = build_fixture_index(tmp.path());
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: None,
+        workspace,
+        project_id: "test-repo",
+    };
+    let call = |arguments: Value| {
+        extract_payload_from_response(&call_tool(&tool_ctx, "search_code", arguments))
+    };
+
+    let first = call(json!({ "query": "validate_token" }));
+    let second = call(json!({ "query": "validate_token" }));
+    let failed = call(json!({}));
+
+    let request_id = |payload: &Value| payload["metadata"]["request_id"].as_str().map(String::from);
+    let first_id = request_id(&first).unwrap_or_else(|| panic!("{first}"));
+    assert!(first_id.starts_with("req-"), "{first_id}");
+    assert_ne!(Some(first_id), request_id(&second));
+    assert_eq!(failed["error"]["code"], "invalid_input", "{failed}");
+    assert!(request_id(&failed).is_some(), "{failed}");
+}
 
 #[test]
-fn t457_first_query_smoke_guard() {
+fn t521_get_symbol_fetches_a_symbol_by_stable_id() {
     let tmp = tempfile::tempdir().unwrap();
     let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
     let conn = cruxe_state::db::open_connection(&db_path).unwrap();
     let config = Config::default();
     let workspace = Path::new("/tmp/fake-workspace");
-    let project_id = "test-repo";
 
-    let mut samples = Vec::new();
-    for _ in 0..12 {
-        let request = make_request(
-            "tools/call",
-            json!({
-                "name": "search_code",
-                "arguments": {
-                    "query": "validate_token"
-                }
-            }),
-        );
-        let started = Instant::now();
-        let response = handle_request_with_ctx(
-            &request,
-            &RequestContext {
-                config: &config,
-                index_set: Some(&index_set),
-                schema_status: SchemaStatus::Compatible,
-                compatibility_reason: None,
-                conn: Some(&conn),
-                workspace,
-                project_id,
-                prewarm_status: &test_prewarm_status(),
-                server_start: &test_server_start(),
-                notifier: Arc::new(NullProgressNotifier),
-                progress_token: None,
-            },
-        );
-        assert!(response.error.is_none(), "search_code should succeed");
-        samples.push(started.elapsed());
-    }
-    samples.sort();
-    let p95 = samples[samples.len() * 95 / 100];
+    let (stable_id, parent_id): (String, String) = conn
+        .query_row(
+            "SELECT symbol_stable_id, parent_symbol_id FROM symbol_relations
+             WHERE repo = 'test-repo' AND parent_symbol_id IS NOT NULL
+             ORDER BY path, line_start LIMIT 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: Some(&conn),
+        workspace,
+        project_id: "test-repo",
+    };
+    let call = |arguments: Value| {
+        extract_payload_from_response(&call_tool(&tool_ctx, "get_symbol", arguments))
+    };
+
+    let found = call(json!({ "symbol_stable_id": stable_id }));
+    let symbol = &found["symbol"];
+    assert_eq!(symbol["symbol_stable_id"], stable_id.as_str(), "{found}");
+    assert_eq!(symbol["parent_symbol_id"], parent_id.as_str(), "{found}");
+    assert_eq!(found["parent"]["symbol_id"], parent_id.as_str(), "{found}");
+    assert!(symbol["line_start"].as_u64().unwrap() <= symbol["line_end"].as_u64().unwrap());
+    let name = symbol["name"].as_str().unwrap();
     assert!(
-        p95.as_millis() < 5_000,
-        "warmset-enabled first-query smoke budget should remain < 5000ms, got {}ms",
-        p95.as_millis()
+        symbol["content"]
+            .as_str()
+            .is_some_and(|body| body.contains(name)),
+        "{found}"
+    );
+
+    let without_body = call(json!({ "symbol_stable_id": stable_id, "include_body": false }));
+    assert!(
+        without_body["symbol"]["content"].is_null(),
+        "{without_body}"
+    );
+
+    let missing = call(json!({ "symbol_stable_id": "no-such-stable-id" }));
+    assert_eq!(missing["error"]["code"], "symbol_not_found", "{missing}");
+    assert_eq!(
+        missing["error"]["data"]["symbol_stable_id"],
+        "no-such-stable-id"
     );
+
+    let empty = call(json!({}));
+    assert_eq!(empty["error"]["code"], "invalid_input", "{empty}");
 }
 
 #[test]
-#[ignore = "benchmark harness"]
-fn benchmark_t457_first_query_p95_under_400ms() {
+fn t522_search_code_kind_filter_returns_only_that_kind() {
     let tmp = tempfile::tempdir().unwrap();
-    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
-    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    let index_set = build_fixture_index(tmp.path());
     let config = Config::default();
     let workspace = Path::new("/tmp/fake-workspace");
-    let project_id = "test-repo";
 
-    let mut samples = Vec::new();
-    for _ in 0..12 {
-        let request = make_request(
-            "tools/call",
-            json!({
-                "name": "search_code",
-                "arguments": {
-                    "query": "validate_token"
-                }
-            }),
-        );
-        let started = Instant::now();
-        let response = handle_request_with_ctx(
-            &request,
-            &RequestContext {
-                config: &config,
-                index_set: Some(&index_set),
-                schema_status: SchemaStatus::Compatible,
-                compatibility_reason: None,
-                conn: Some(&conn),
-                workspace,
-                project_id,
-                prewarm_status: &test_prewarm_status(),
-                server_start: &test_server_start(),
-                notifier: Arc::new(NullProgressNotifier),
-                progress_token: None,
-            },
-        );
-        assert!(response.error.is_none(), "search_code should succeed");
-        samples.push(started.elapsed());
-    }
-    samples.sort();
-    let p95 = samples[samples.len() * 95 / 100];
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: None,
+        workspace,
+        project_id: "test-repo",
+    };
+    let call = |kind: &str| {
+        extract_payload_from_response(&call_tool(
+            &tool_ctx,
+            "search_code",
+            json!({ "query": "validate_token", "kind": kind }),
+        ))
+    };
+
+    let functions = call("fn");
+    let results = functions["results"].as_array().expect("results array");
+    assert!(!results.is_empty(), "{functions}");
     assert!(
-        p95.as_millis() < 400,
-        "warmset-enabled first-query benchmark p95 should be < 400ms, got {}ms",
-        p95.as_millis()
+        results
+            .iter()
+            .all(|r| r["result_type"] == "symbol" && r["kind"] == "function"),
+        "{functions}"
     );
+
+    let invalid = call("widget");
+    assert_eq!(invalid["error"]["code"], "invalid_input", "{invalid}");
+    assert_eq!(invalid["error"]["data"]["kind"], "widget");
 }
 
-#[cfg(unix)]
-fn vcs_fixture_setup_script() -> std::path::PathBuf {
-    std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-        .join("../../testdata/fixtures/vcs-sample/setup.sh")
+#[test]
+fn t523_compact_index_prunes_rows_outside_the_manifest() {
+    let tmp = tempfile::tempdir().unwrap();
+    let workspace = tmp.path().join("workspace");
+    std::fs::create_dir_all(&workspace).unwrap();
+    let (config, conn, project_id) = setup_indexing_runtime(&workspace);
+    let data_dir = config.project_data_dir(&project_id);
+    let index_set = IndexSet::open(&data_dir).unwrap();
+
+    // A symbol left behind for a file that is no longer in the manifest.
+    cruxe_state::symbols::insert_symbol(
+        &conn,
+        &cruxe_core::types::SymbolRecord {
+            repo: project_id.clone(),
+            r#ref: cruxe_core::constants::REF_LIVE.to_string(),
+            commit: None,
+            path: "src/deleted.rs".to_string(),
+            symbol_id: "live::stale".to_string(),
+            symbol_stable_id: "stable::stale".to_string(),
+            name: "stale".to_string(),
+            qualified_name: "crate::stale".to_string(),
+            kind: cruxe_core::types::SymbolKind::Function,
+            language: "rust".to_string(),
+            line_start: 1,
+            line_end: 3,
+            signature: None,
+            parent_symbol_id: None,
+            visibility: None,
+            content: Some("fn stale() {}".to_string()),
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
+        },
+    )
+    .unwrap();
+
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: Some(&conn),
+        workspace: &workspace,
+        project_id: &project_id,
+    };
+    let call = || extract_payload_from_response(&call_tool(&tool_ctx, "compact_index", json!({})));
+
+    let report = call();
+    assert_eq!(report["pruned_symbol_rows"], 1, "{report}");
+    assert_eq!(report["index_roots"], 1, "{report}");
+    assert!(report["bytes_reclaimed"].is_u64(), "{report}");
+    assert!(report["metadata"].is_object(), "{report}");
+    let remaining: i64 = conn
+        .query_row("SELECT COUNT(*) FROM symbol_relations", [], |row| {
+            row.get(0)
+        })
+        .unwrap();
+    assert_eq!(remaining, 0);
+
+    let _held =
+        cruxe_state::maintenance_lock::acquire_project_lock(&data_dir, "sync_repo").unwrap();
+    let busy = call();
+    assert_eq!(busy["error"]["code"], "sync_in_progress", "{busy}");
 }
 
-#[cfg(unix)]
-fn setup_vcs_mcp_fixture() -> (
-    tempfile::TempDir,
-    std::path::PathBuf,
-    Config,
-    rusqlite::Connection,
-    String,
-) {
-    let tmp = tempfile::tempdir().expect("create tempdir");
-    let script = vcs_fixture_setup_script();
-    assert!(
-        script.exists(),
-        "missing fixture script: {}",
-        script.display()
-    );
-    let output = std::process::Command::new("bash")
-        .arg(&script)
-        .arg(tmp.path())
-        .output()
-        .expect("run fixture setup");
-    assert!(
-        output.status.success(),
-        "fixture setup failed:\nstdout:{}\nstderr:{}",
-        String::from_utf8_lossy(&output.stdout),
-        String::from_utf8_lossy(&output.stderr)
-    );
+#[test]
+fn t533_drop_ref_guards_the_default_ref_and_reports_removed_counts() {
+    let tmp = tempfile::tempdir().unwrap();
+    let workspace = tmp.path().join("workspace");
+    std::fs::create_dir_all(&workspace).unwrap();
+    let (config, conn, project_id) = setup_indexing_runtime(&workspace);
 
-    let workspace = tmp.path().join("vcs-sample");
-    let mut config = Config::default();
-    config.storage.data_dir = tmp.path().join("cc-data").to_string_lossy().to_string();
+    for r#ref in [cruxe_core::constants::REF_LIVE, "feat/old"] {
+        cruxe_state::symbols::insert_symbol(
+            &conn,
+            &cruxe_core::types::SymbolRecord {
+                repo: project_id.clone(),
+                r#ref: r#ref.to_string(),
+                commit: None,
+                path: "src/lib.rs".to_string(),
+                symbol_id: format!("{ref}::run"),
+                symbol_stable_id: "stable::run".to_string(),
+                name: "run".to_string(),
+                qualified_name: "crate::run".to_string(),
+                kind: cruxe_core::types::SymbolKind::Function,
+                language: "rust".to_string(),
+                line_start: 1,
+                line_end: 3,
+                signature: None,
+                parent_symbol_id: None,
+                visibility: None,
+                content: Some("fn run() {}".to_string()),
+                attributes: Vec::new(),
+                byte_start: None,
+                byte_end: None,
+                doc_comment: None,
+                parsed_signature: None,
+            },
+        )
+        .unwrap();
+    }
 
-    let project_id = generate_project_id(&workspace.to_string_lossy());
-    let data_dir = config.project_data_dir(&project_id);
-    std::fs::create_dir_all(&data_dir).expect("create data dir");
-    let db_path = data_dir.join(cruxe_core::constants::STATE_DB_FILE);
-    let conn = cruxe_state::db::open_connection(&db_path).expect("open sqlite");
-    cruxe_state::schema::create_tables(&conn).expect("create schema");
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: None,
+        conn: Some(&conn),
+        workspace: &workspace,
+        project_id: &project_id,
+    };
+    let call = |arguments: Value| {
+        extract_payload_from_response(&call_tool(&tool_ctx, "drop_ref", arguments))
+    };
+    let remaining = |r#ref: &str| -> i64 {
+        conn.query_row(
+            "SELECT COUNT(*) FROM symbol_relations WHERE \"ref\" = ?1",
+            [r#ref],
+            |row| row.get(0),
+        )
+        .unwrap()
+    };
 
-    let now = "2026-02-25T00:00:00Z".to_string();
-    let project = Project {
-        project_id: project_id.clone(),
-        repo_root: workspace.to_string_lossy().to_string(),
-        display_name: Some("vcs-mcp-test".to_string()),
-        default_ref: "main".to_string(),
-        vcs_mode: true,
-        schema_version: cruxe_core::constants::SCHEMA_VERSION,
-        parser_version: cruxe_core::constants::PARSER_VERSION,
-        created_at: now.clone(),
-        updated_at: now,
+    let missing = call(json!({}));
+    assert_eq!(missing["error"]["code"], "invalid_input", "{missing}");
+
+    let guarded = call(json!({ "ref": "live" }));
+    assert_eq!(guarded["error"]["code"], "invalid_input", "{guarded}");
+    assert_eq!(guarded["error"]["data"]["default_ref"], "live");
+    assert_eq!(remaining("live"), 1);
+
+    let dropped = call(json!({ "ref": "feat/old" }));
+    assert_eq!(dropped["ref"], "feat/old", "{dropped}");
+    assert_eq!(dropped["was_default"], false);
+    assert_eq!(dropped["removed"]["symbol_rows"], 1, "{dropped}");
+    assert!(dropped["metadata"].is_object(), "{dropped}");
+    assert_eq!(remaining("feat/old"), 0);
+    assert_eq!(remaining("live"), 1);
+
+    let forced = call(json!({ "ref": "live", "force": true }));
+    assert_eq!(forced["was_default"], true, "{forced}");
+    assert_eq!(forced["removed"]["symbol_rows"], 1, "{forced}");
+    assert_eq!(remaining("live"), 0);
+}
+
+#[test]
+fn t524_index_status_changed_since_requires_a_git_workspace() {
+    let tmp = tempfile::tempdir().unwrap();
+    let workspace = tmp.path().join("workspace");
+    std::fs::create_dir_all(&workspace).unwrap();
+    let (config, conn, project_id) = setup_indexing_runtime(&workspace);
+
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: None,
+        conn: Some(&conn),
+        workspace: &workspace,
+        project_id: &project_id,
+    };
+    let call = |arguments: Value| {
+        extract_payload_from_response(&call_tool(&tool_ctx, "index_status", arguments))
     };
-    cruxe_state::project::create_project(&conn, &project).expect("create project");
 
-    (tmp, workspace, config, conn, project_id)
-}
+    let status = call(json!({}));
+    assert!(status.get("changed_since").is_none(), "{status}");
 
-#[cfg(unix)]
-fn git_in_repo(repo_root: &std::path::Path, args: &[&str]) -> String {
-    let output = std::process::Command::new("git")
-        .args(args)
-        .current_dir(repo_root)
-        .output()
-        .expect("run git command");
+    let rejected = call(json!({ "changed_since": "abc123" }));
+    assert_eq!(rejected["error"]["code"], "invalid_input", "{rejected}");
     assert!(
-        output.status.success(),
-        "git {:?} failed:\nstdout:{}\nstderr:{}",
-        args,
-        String::from_utf8_lossy(&output.stdout),
-        String::from_utf8_lossy(&output.stderr)
+        rejected["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("requires a git workspace"),
+        "{rejected}"
     );
-    String::from_utf8_lossy(&output.stdout).trim().to_string()
 }
 
-#[cfg(unix)]
-fn index_checkout_as_main(
-    repo_root: &std::path::Path,
-    data_dir: &std::path::Path,
-    conn: &rusqlite::Connection,
-    project_id: &str,
-) {
-    use cruxe_indexer::{
-        languages, parser, scanner, snippet_extract, symbol_extract, sync_incremental, writer,
+#[test]
+fn t527_search_code_highlight_returns_fragment_ranges_at_every_detail_level() {
+    let tmp = tempfile::tempdir().unwrap();
+    let index_set = build_fixture_index(tmp.path());
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: None,
+        workspace,
+        project_id: "test-repo",
+    };
+    let call = |arguments: Value| {
+        extract_payload_from_response(&call_tool(&tool_ctx, "search_code", arguments))
     };
-    use cruxe_state::manifest;
-    use cruxe_state::tantivy_index::IndexSet;
 
-    let index_set = IndexSet::open(data_dir).expect("open base index");
-    let files = scanner::scan_directory_filtered(
-        repo_root,
-        cruxe_core::constants::MAX_FILE_SIZE,
-        &["rust".to_string()],
+    let plain = call(json!({ "query": "validate_token", "detail_level": "context" }));
+    let plain_results = plain["results"].as_array().expect("results array");
+    assert!(
+        plain_results.iter().all(|r| r.get("highlight").is_none()),
+        "{plain}"
     );
 
-    for file in files {
-        let source = std::fs::read_to_string(&file.path).expect("read source file");
-        let tree = parser::parse_file(&source, &file.language).expect("parse source");
-        let extracted = languages::extract_symbols(&tree, &source, &file.language);
-        let symbols = symbol_extract::build_symbol_records(
-            &extracted,
-            project_id,
-            "main",
-            &file.relative_path,
-            None,
-        );
-        let snippets = snippet_extract::build_snippet_records(
-            &extracted,
-            project_id,
-            "main",
-            &file.relative_path,
-            None,
-        );
-        let record = cruxe_core::types::FileRecord {
-            repo: project_id.to_string(),
-            r#ref: "main".to_string(),
-            commit: None,
-            path: file.relative_path.clone(),
-            filename: file
-                .path
-                .file_name()
-                .map(|value| value.to_string_lossy().to_string())
-                .unwrap_or_default(),
-            language: file.language.clone(),
-            content_hash: blake3::hash(source.as_bytes()).to_hex().to_string(),
-            size_bytes: source.len() as u64,
-            updated_at: cruxe_core::time::now_iso8601(),
-            content_head: Some(source.lines().take(20).collect::<Vec<_>>().join("\n")),
-        };
-        writer::write_file_records(&index_set, conn, &symbols, &snippets, &record)
-            .expect("write base records");
+    for detail_level in ["location", "signature", "context"] {
+        let payload = call(json!({
+            "query": "validate_token",
+            "highlight": true,
+            "detail_level": detail_level,
+        }));
+        let results = payload["results"].as_array().expect("results array");
+        let highlighted: Vec<&Value> = results
+            .iter()
+            .filter(|r| r.get("highlight").is_some())
+            .collect();
+        assert!(!highlighted.is_empty(), "{detail_level}: {payload}");
+        for result in highlighted {
+            assert!(result.get("snippet").is_none(), "{result}");
+            let fragment = result["highlight"]["fragment"].as_str().unwrap();
+            let ranges = result["highlight"]["match_ranges"].as_array().unwrap();
+            assert!(!ranges.is_empty(), "{result}");
+            for range in ranges {
+                let start = range["start"].as_u64().unwrap() as usize;
+                let end = range["end"].as_u64().unwrap() as usize;
+                let matched = fragment[start..end].to_ascii_lowercase();
+                assert!("validate_token".contains(&matched), "{matched} in {result}");
+            }
+        }
     }
-
-    let head = cruxe_core::vcs::detect_head_commit(repo_root).expect("detect base head");
-    sync_incremental::persist_branch_sync_state(
-        conn,
-        &sync_incremental::BranchSyncStateUpdate {
-            repo: project_id.to_string(),
-            ref_name: "main".to_string(),
-            merge_base_commit: None,
-            last_indexed_commit: head,
-            overlay_dir: None,
-            file_count: manifest::file_count(conn, project_id, "main").expect("base file count")
-                as i64,
-            symbol_count: 0,
-            is_default_branch: true,
-        },
-    )
-    .expect("persist main branch state");
 }
 
-#[cfg(unix)]
-fn sync_vcs_ref(
-    conn: &mut rusqlite::Connection,
-    repo_root: &std::path::Path,
-    data_dir: &std::path::Path,
-    project_id: &str,
-    ref_name: &str,
-    sync_id: &str,
-) {
-    use cruxe_indexer::sync_incremental::{self, IncrementalSyncRequest};
-    let adapter = cruxe_vcs::Git2VcsAdapter;
-    sync_incremental::run_incremental_sync(
-        &adapter,
-        conn,
-        IncrementalSyncRequest {
-            repo_root,
-            data_dir,
-            project_id,
-            ref_name,
-            base_ref: "main",
-            sync_id,
-            last_indexed_commit: None,
-            is_default_branch: false,
-        },
-    )
-    .expect("run incremental sync");
+#[test]
+fn t528_search_code_min_score_drops_low_scoring_results() {
+    let tmp = tempfile::tempdir().unwrap();
+    let index_set = build_fixture_index(tmp.path());
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
+
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: None,
+        workspace,
+        project_id: "test-repo",
+    };
+    let call = |arguments: Value| {
+        extract_payload_from_response(&call_tool(&tool_ctx, "search_code", arguments))
+    };
+
+    let scores = |payload: &Value| -> Vec<f64> {
+        payload["results"]
+            .as_array()
+            .expect("results array")
+            .iter()
+            .map(|r| r["score"].as_f64().unwrap())
+            .collect()
+    };
+
+    let unfiltered = call(json!({ "query": "validate_token", "limit": 50 }));
+    assert!(
+        unfiltered["metadata"]
+            .get("min_score_dropped_count")
+            .is_none()
+    );
+    let all_scores = scores(&unfiltered);
+    let top = all_scores.iter().copied().fold(f64::MIN, f64::max);
+    let bottom = all_scores.iter().copied().fold(f64::MAX, f64::min);
+    assert!(top > bottom, "need a score spread: {unfiltered}");
+
+    let threshold = (top + bottom) / 2.0;
+    let filtered = call(json!({ "query": "validate_token", "limit": 50, "min_score": threshold }));
+    let kept = scores(&filtered);
+    assert!(!kept.is_empty(), "{filtered}");
+    assert!(kept.iter().all(|score| *score >= threshold), "{filtered}");
+    let expected_dropped = all_scores.iter().filter(|s| **s < threshold).count();
+    assert_eq!(
+        filtered["metadata"]["min_score_dropped_count"],
+        json!(expected_dropped)
+    );
+
+    let none_left = call(json!({ "query": "validate_token", "min_score": top + 1.0 }));
+    assert!(scores(&none_left).is_empty(), "{none_left}");
+    assert!(none_left["metadata"].get("next_cursor").is_none());
+
+    let invalid = call(json!({ "query": "validate_token", "min_score": "high" }));
+    assert_eq!(invalid["error"]["code"], "invalid_input", "{invalid}");
 }
 
-#[cfg(unix)]
 #[test]
-fn t288_mcp_search_code_vcs_ref_includes_source_layer_handles_and_schema_status() {
-    let (_tmp, workspace, config, mut conn, project_id) = setup_vcs_mcp_fixture();
-    let data_dir = config.project_data_dir(&project_id);
+fn t529_get_symbol_hierarchy_module_mode_aggregates_across_files() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    let config = Config::default();
+    let workspace = Path::new("/tmp/fake-workspace");
 
-    git_in_repo(&workspace, &["checkout", "main"]);
-    index_checkout_as_main(&workspace, &data_dir, &conn, &project_id);
-    let index_set =
-        cruxe_state::tantivy_index::IndexSet::open_existing(&data_dir).expect("open base index");
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: Some(&conn),
+        workspace,
+        project_id: "test-repo",
+    };
+    let call = |arguments: Value| {
+        extract_payload_from_response(&call_tool(&tool_ctx, "get_symbol_hierarchy", arguments))
+    };
 
-    git_in_repo(&workspace, &["checkout", "feat/add-file"]);
-    sync_vcs_ref(
-        &mut conn,
-        &workspace,
-        &data_dir,
-        &project_id,
-        "feat/add-file",
-        "sync-mcp-add-file",
+    fn collect_paths<'a>(nodes: &'a [Value], out: &mut Vec<&'a str>) {
+        for node in nodes {
+            out.push(node["path"].as_str().unwrap());
+            if let Some(children) = node.get("children").and_then(|c| c.as_array()) {
+                collect_paths(children, out);
+            }
+        }
+    }
+
+    let auth = call(json!({ "module": "crate::auth" }));
+    assert_eq!(auth["module"], "crate::auth", "{auth}");
+    // `pub mod auth;` in lib.rs is the module's own declaration.
+    assert_eq!(auth["files"], json!(["src/auth.rs", "src/lib.rs"]));
+    let roots = auth["hierarchy"].as_array().expect("hierarchy array");
+    assert!(
+        roots.iter().any(|node| node["name"] == "validate_token"),
+        "{auth}"
+    );
+    let mut paths = Vec::new();
+    collect_paths(roots, &mut paths);
+    assert_eq!(paths.len(), auth["symbol_count"].as_u64().unwrap() as usize);
+    assert_eq!(
+        paths.iter().filter(|path| **path == "src/lib.rs").count(),
+        1,
+        "{auth}"
     );
 
-    let request = make_request(
-        "tools/call",
-        json!({
-            "name": "search_code",
-            "arguments": {
-                "query": "added_branch_file",
-                "ref": "feat/add-file"
+    let whole_crate = call(json!({ "module": "crate", "limit": 2 }));
+    assert_eq!(whole_crate["truncated"], true, "{whole_crate}");
+    assert!(whole_crate["symbol_count"].as_u64().unwrap() > 2);
+    let files = whole_crate["files"].as_array().unwrap();
+    assert!(!files.is_empty() && files.len() <= 2);
+
+    let missing = call(json!({ "module": "crate::billing" }));
+    assert_eq!(missing["error"]["code"], "symbol_not_found", "{missing}");
+
+    let both = call(json!({ "module": "crate::auth", "symbol_name": "Claims" }));
+    assert_eq!(both["error"]["code"], "invalid_input", "{both}");
+}
+
+#[test]
+fn t530_index_spawn_retries_transient_failures_with_a_bounded_count() {
+    use crate::index_launcher::spawn_with_retry;
+    use std::io::{Error, ErrorKind};
+    use std::time::Duration;
+
+    let mut calls = 0;
+    let spawned = spawn_with_retry(
+        || {
+            calls += 1;
+            if calls < 3 {
+                Err(Error::from(ErrorKind::WouldBlock))
+            } else {
+                Ok("child")
             }
-        }),
-    );
-    let response = handle_request_with_ctx(
-        &request,
-        &RequestContext {
-            config: &config,
-            index_set: Some(&index_set),
-            schema_status: SchemaStatus::Compatible,
-            compatibility_reason: None,
-            conn: Some(&conn),
-            workspace: &workspace,
-            project_id: &project_id,
-            prewarm_status: &test_prewarm_status(),
-            server_start: &test_server_start(),
-            notifier: Arc::new(NullProgressNotifier),
-            progress_token: None,
         },
-    );
-    assert!(response.error.is_none(), "search_code should succeed");
-    let payload = extract_payload_from_response(&response);
+        3,
+        Duration::ZERO,
+    )
+    .unwrap();
+    assert_eq!(spawned, "child");
+    assert_eq!(calls, 3);
 
-    let metadata = payload.get("metadata").expect("metadata should be present");
-    assert_eq!(
-        metadata
-            .get("schema_status")
-            .and_then(|value| value.as_str()),
-        Some("compatible"),
-        "metadata should include schema_status compatibility signal"
-    );
+    let exhausted = spawn_with_retry(
+        || Err::<(), _>(Error::from(ErrorKind::WouldBlock)),
+        3,
+        Duration::ZERO,
+    )
+    .unwrap_err();
+    assert_eq!(exhausted.attempts, 3);
+    assert_eq!(exhausted.error.kind(), ErrorKind::WouldBlock);
+
+    let permanent = spawn_with_retry(
+        || Err::<(), _>(Error::from(ErrorKind::NotFound)),
+        3,
+        Duration::ZERO,
+    )
+    .unwrap_err();
+    assert_eq!(permanent.attempts, 1);
+}
 
-    let results = payload
-        .get("results")
-        .and_then(|value| value.as_array())
-        .expect("results should be present");
-    let added = results
-        .iter()
-        .find(|result| {
-            result.get("path").and_then(|value| value.as_str()) == Some("src/add_file.rs")
-        })
-        .expect("search should include added file result for feature ref");
+#[test]
+fn t540_get_code_context_widens_depth_bodies_with_prefix_and_suffix_lines() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    let config = Config::default();
+    let fixture_dir = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../testdata/fixtures/rust-sample");
+
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: Some(&conn),
+        workspace: &fixture_dir,
+        project_id: "test-repo",
+    };
+    let call = |arguments: Value| {
+        extract_payload_from_response(&call_tool(&tool_ctx, "get_code_context", arguments))
+    };
+    let depth = |extra: Value| {
+        let mut arguments = json!({
+            "query": "validate_token",
+            "max_tokens": 4000,
+            "strategy": "depth",
+            "language": "rust"
+        });
+        arguments
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        let payload = call(arguments);
+        payload["context_items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|item| item["name"] == "validate_token")
+            .cloned()
+            .expect("validate_token should be in the context items")
+    };
+
+    let plain = depth(json!({}));
+    let line_start = plain["line_start"].as_u64().unwrap();
+    let line_end = plain["line_end"].as_u64().unwrap();
+    assert_eq!(plain["body_line_start"], line_start);
+    assert_eq!(plain["body_line_end"], line_end);
+
+    let widened = depth(json!({ "prefix_lines": 4, "suffix_lines": 2, "with_line_numbers": true }));
     assert_eq!(
-        added.get("source_layer").and_then(|value| value.as_str()),
-        Some("overlay"),
-        "result should expose overlay provenance"
+        widened["line_start"], line_start,
+        "symbol range is unchanged"
+    );
+    assert_eq!(widened["body_line_start"], line_start - 4);
+    assert_eq!(widened["body_line_end"], line_end + 2);
+    let body = widened["body"].as_str().unwrap();
+    assert_eq!(
+        body.lines().count() as u64,
+        line_end + 2 - (line_start - 4) + 1
     );
     assert!(
-        added
-            .get("symbol_id")
-            .and_then(|value| value.as_str())
-            .is_some_and(|value| !value.is_empty()),
-        "symbol_id should be present for MCP follow-up calls"
+        body.starts_with(&format!("{:>4}| ", line_start - 4)),
+        "{body}"
     );
     assert!(
-        added
-            .get("symbol_stable_id")
-            .and_then(|value| value.as_str())
-            .is_some_and(|value| !value.is_empty()),
-        "symbol_stable_id should be present for MCP follow-up calls"
+        body.contains("/// Validate an authorization header"),
+        "{body}"
     );
+
+    let clamped = depth(json!({ "prefix_lines": 500, "suffix_lines": 500 }));
+    assert_eq!(clamped["body_line_start"], 1);
+
+    let invalid = call(json!({ "query": "validate_token", "prefix_lines": 501 }));
+    assert_eq!(invalid["error"]["code"], "invalid_input");
+    let invalid = call(json!({ "query": "validate_token", "suffix_lines": -1 }));
+    assert_eq!(invalid["error"]["code"], "invalid_input");
 }
 
-#[cfg(unix)]
 #[test]
-fn t288_mcp_locate_symbol_vcs_ref_reports_overlay_handles_and_schema_status() {
-    let (_tmp, workspace, config, mut conn, project_id) = setup_vcs_mcp_fixture();
-    let data_dir = config.project_data_dir(&project_id);
+fn t541_get_code_context_returns_symbol_bodies_for_an_id_batch() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    let fixture_dir = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../testdata/fixtures/rust-sample");
+    let stable_id = |name: &str| -> String {
+        conn.query_row(
+            "SELECT symbol_stable_id FROM symbol_relations WHERE repo = 'test-repo' AND name = ?1 LIMIT 1",
+            [name],
+            |row| row.get(0),
+        )
+        .unwrap()
+    };
+    let validate = stable_id("validate_token");
+    let require = stable_id("require_role");
 
-    git_in_repo(&workspace, &["checkout", "main"]);
-    index_checkout_as_main(&workspace, &data_dir, &conn, &project_id);
-    let index_set =
-        cruxe_state::tantivy_index::IndexSet::open_existing(&data_dir).expect("open base index");
+    let call = |config: &Config, arguments: Value| {
+        extract_payload_from_response(&call_tool(
+            &ToolCallFixture {
+                config,
+                index_set: Some(&index_set),
+                conn: Some(&conn),
+                workspace: &fixture_dir,
+                project_id: "test-repo",
+            },
+            "get_code_context",
+            arguments,
+        ))
+    };
 
-    git_in_repo(&workspace, &["checkout", "feat/modify-sig"]);
-    sync_vcs_ref(
-        &mut conn,
-        &workspace,
-        &data_dir,
-        &project_id,
-        "feat/modify-sig",
-        "sync-mcp-modify-sig",
+    let config = Config::default();
+    let payload = call(
+        &config,
+        json!({ "ids": [require, "missing-id", validate], "max_tokens": 8000 }),
+    );
+    let names: Vec<&str> = payload["context_items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["require_role", "validate_token"]);
+    assert!(
+        payload["context_items"][1]["body"]
+            .as_str()
+            .unwrap()
+            .starts_with("pub fn validate_token(")
+    );
+    assert_eq!(payload["truncated"], false);
+    assert_eq!(payload["metadata"]["missing_ids"], json!(["missing-id"]));
+
+    let mut small = Config::default();
+    small.search.max_response_bytes = 900;
+    let capped = call(
+        &small,
+        json!({ "ids": [require, validate], "max_tokens": 8000 }),
+    );
+    assert_eq!(capped["truncated"], true);
+    assert_eq!(capped["metadata"]["safety_limit_applied"], true);
+    assert_eq!(capped["metadata"]["result_completeness"], "truncated");
+    let returned = capped["context_items"].as_array().unwrap().len();
+    assert!(returned < 2, "{capped}");
+    assert_eq!(
+        capped["metadata"]["remaining_ids"]
+            .as_array()
+            .unwrap()
+            .len(),
+        2 - returned
     );
 
-    let request = make_request(
-        "tools/call",
-        json!({
-            "name": "locate_symbol",
-            "arguments": {
-                "name": "shared",
-                "ref": "feat/modify-sig"
-            }
-        }),
+    for ids in [json!([]), json!("not-an-array"), json!([""])] {
+        let invalid = call(&config, json!({ "ids": ids }));
+        assert_eq!(invalid["error"]["code"], "invalid_input", "{invalid}");
+    }
+}
+
+#[test]
+fn t542_find_unreferenced_symbols_lists_public_symbols_without_references() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    let config = Config::default();
+
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: Some(&conn),
+        workspace: Path::new("/tmp/fake-workspace"),
+        project_id: "test-repo",
+    };
+    let call = |arguments: Value| {
+        extract_payload_from_response(&call_tool(
+            &tool_ctx,
+            "find_unreferenced_symbols",
+            arguments,
+        ))
+    };
+
+    let report = call(json!({ "language": "rust", "limit": 500 }));
+    let symbols = report["symbols"].as_array().unwrap();
+    assert!(!symbols.is_empty(), "{report}");
+    assert!(
+        report["public_symbols_scanned"].as_u64().unwrap() >= symbols.len() as u64,
+        "{report}"
     );
-    let response = handle_request_with_ctx(
-        &request,
-        &RequestContext {
-            config: &config,
-            index_set: Some(&index_set),
-            schema_status: SchemaStatus::Compatible,
-            compatibility_reason: None,
-            conn: Some(&conn),
-            workspace: &workspace,
-            project_id: &project_id,
-            prewarm_status: &test_prewarm_status(),
-            server_start: &test_server_start(),
-            notifier: Arc::new(NullProgressNotifier),
-            progress_token: None,
-        },
+    assert!(!report["limitations"].as_array().unwrap().is_empty());
+    assert!(symbols.iter().all(|symbol| symbol["language"] == "rust"));
+    assert!(
+        symbols
+            .iter()
+            .all(|symbol| symbol["kind"] != "field" && symbol["name"] != "main")
     );
-    assert!(response.error.is_none(), "locate_symbol should succeed");
-    let payload = extract_payload_from_response(&response);
 
-    let metadata = payload.get("metadata").expect("metadata should be present");
-    assert_eq!(
-        metadata
-            .get("schema_status")
-            .and_then(|value| value.as_str()),
-        Some("compatible"),
-        "metadata should include schema_status compatibility signal"
+    let structs = call(json!({ "kind": "struct" }));
+    assert!(
+        structs["symbols"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|symbol| symbol["kind"] == "struct")
     );
 
-    let results = payload
-        .get("results")
-        .and_then(|value| value.as_array())
-        .expect("results should be present");
-    let shared = results
-        .iter()
-        .find(|result| result.get("path").and_then(|value| value.as_str()) == Some("src/lib.rs"))
-        .expect("locate should include shared symbol for feature ref");
-    assert_eq!(
-        shared.get("source_layer").and_then(|value| value.as_str()),
-        Some("overlay"),
-        "locate result should expose overlay provenance"
+    let capped = call(json!({ "limit": 1 }));
+    assert!(capped["symbols"].as_array().unwrap().len() <= 1);
+    if capped["total_unreferenced"].as_u64().unwrap() > 1 {
+        assert_eq!(capped["metadata"]["result_completeness"], "truncated");
+    }
+
+    let invalid = call(json!({ "kind": "gizmo" }));
+    assert_eq!(invalid["error"]["code"], "invalid_input");
+    let invalid = call(json!({ "limit": 0 }));
+    assert_eq!(invalid["error"]["code"], "invalid_input");
+}
+
+#[test]
+fn t543_ref_not_indexed_error_lists_available_refs() {
+    let tmp = tempfile::tempdir().unwrap();
+    let workspace_dir = tmp.path().join("workspace");
+    std::fs::create_dir_all(&workspace_dir).unwrap();
+
+    let conn = cruxe_state::db::open_connection(&tmp.path().join("state.db")).unwrap();
+    cruxe_state::schema::create_tables(&conn).unwrap();
+
+    let project_id = "ref-not-indexed-available";
+    let now = "2026-02-26T00:00:00Z".to_string();
+    cruxe_state::project::create_project(
+        &conn,
+        &Project {
+            project_id: project_id.to_string(),
+            repo_root: workspace_dir.to_string_lossy().to_string(),
+            display_name: None,
+            default_ref: "main".to_string(),
+            vcs_mode: true,
+            schema_version: 1,
+            parser_version: 1,
+            created_at: now.clone(),
+            updated_at: now.clone(),
+        },
+    )
+    .unwrap();
+    for ref_name in ["main", "feat/auth"] {
+        cruxe_state::branch_state::upsert_branch_state(
+            &conn,
+            &cruxe_state::branch_state::BranchState {
+                repo: project_id.to_string(),
+                r#ref: ref_name.to_string(),
+                merge_base_commit: None,
+                last_indexed_commit: format!("commit-{ref_name}"),
+                overlay_dir: None,
+                file_count: 3,
+                symbol_count: 7,
+                is_default_branch: ref_name == "main",
+                status: "active".to_string(),
+                eviction_eligible_at: None,
+                created_at: now.clone(),
+                last_accessed_at: now.clone(),
+            },
+        )
+        .unwrap();
+    }
+
+    let config = Config::default();
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: None,
+        conn: Some(&conn),
+        workspace: workspace_dir.as_path(),
+        project_id,
+    };
+    let call = |name: &str, arguments: Value| {
+        let response = call_tool(&tool_ctx, name, arguments);
+        extract_payload_from_response(&response)
+    };
+
+    let payload = call(
+        "get_call_graph",
+        json!({ "symbol_name": "validate_token", "ref": "feat/missing" }),
     );
-    assert!(
-        shared
-            .get("symbol_id")
-            .and_then(|value| value.as_str())
-            .is_some_and(|value| !value.is_empty()),
-        "locate result should include symbol_id"
+    assert_eq!(payload["error"]["code"], "ref_not_indexed", "{payload}");
+    assert_eq!(
+        payload["error"]["data"]["available_refs"],
+        json!(["feat/auth", "main"])
     );
-    assert!(
-        shared
-            .get("symbol_stable_id")
-            .and_then(|value| value.as_str())
-            .is_some_and(|value| !value.is_empty()),
-        "locate result should include symbol_stable_id"
+    assert!(payload["error"]["data"]["remediation"].is_string());
+
+    // Other errors are left alone.
+    let payload = call("get_call_graph", json!({ "ref": "main" }));
+    assert_eq!(payload["error"]["code"], "invalid_input", "{payload}");
+    assert!(payload["error"]["data"].get("available_refs").is_none());
+
+    let refs = call("list_refs", json!({}));
+    assert_eq!(refs["total_refs"], 2, "{refs}");
+    assert_eq!(refs["refs"][0]["ref"], "feat/auth");
+    assert_eq!(refs["refs"][0]["last_indexed_commit"], "commit-feat/auth");
+    assert_eq!(refs["refs"][0]["file_count"], 3);
+}
+
+#[test]
+fn t545_search_code_cursor_rejects_changed_ranking_options() {
+    let tmp = tempfile::tempdir().unwrap();
+    let (index_set, db_path) = build_fixture_index_with_db(tmp.path());
+    let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+    let config = Config::default();
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: Some(&conn),
+        workspace: Path::new("/tmp/fake-workspace"),
+        project_id: "test-repo",
+    };
+    let search = |arguments: Value| {
+        extract_payload_from_response(&call_tool(&tool_ctx, "search_code", arguments))
+    };
+
+    let first = search(json!({ "query": "user", "limit": 2, "highlight": false }));
+    let cursor = first["metadata"]["next_cursor"]
+        .as_str()
+        .unwrap_or_else(|| panic!("first page should have a cursor: {first}"))
+        .to_string();
+
+    let same = search(json!({
+        "query": "user", "limit": 2, "highlight": false, "cursor": cursor
+    }));
+    assert!(same.get("error").is_none(), "{same}");
+
+    for (key, value) in [
+        ("fold_naming_conventions", json!(false)),
+        ("prefer_language", json!("python")),
+        ("context_path", json!("src/app.py")),
+        ("plan", json!("lexical_fast")),
+        ("semantic_ratio", json!(0.5)),
+        ("highlight", json!(true)),
+        ("semantic", json!(false)),
+    ] {
+        let mut arguments = json!({ "query": "user", "limit": 2, "cursor": cursor });
+        arguments[key] = value;
+        let changed = search(arguments);
+        assert_eq!(
+            changed["error"]["code"], "invalid_input",
+            "cursor reused with a different `{key}`: {changed}"
+        );
+    }
+}
+
+#[test]
+fn t546_search_code_min_score_pages_tile_the_filtered_ranking() {
+    let tmp = tempfile::tempdir().unwrap();
+    let index_set = build_fixture_index(tmp.path());
+    let config = Config::default();
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: Some(&index_set),
+        conn: None,
+        workspace: Path::new("/tmp/fake-workspace"),
+        project_id: "test-repo",
+    };
+    let search = |arguments: Value| {
+        extract_payload_from_response(&call_tool(&tool_ctx, "search_code", arguments))
+    };
+    let scores = |payload: &Value| -> Vec<f64> {
+        payload["results"]
+            .as_array()
+            .unwrap_or_else(|| panic!("results array: {payload}"))
+            .iter()
+            .map(|r| r["score"].as_f64().unwrap())
+            .collect()
+    };
+
+    let unfiltered = scores(&search(json!({ "query": "user", "limit": 50 })));
+    let mut sorted = unfiltered.clone();
+    sorted.sort_by(|a, b| b.total_cmp(a));
+    assert!(sorted.len() >= 3, "need several hits: {unfiltered:?}");
+    // Keep exactly the two best-scoring hits.
+    let threshold = (sorted[1] + sorted[2]) / 2.0;
+    assert!(sorted[1] > sorted[2], "need a score gap: {sorted:?}");
+
+    let mut paged = Vec::new();
+    let mut cursor: Option<String> = None;
+    for _ in 0..unfiltered.len() {
+        let mut arguments = json!({ "query": "user", "limit": 1, "min_score": threshold });
+        if let Some(cursor) = &cursor {
+            arguments["cursor"] = json!(cursor);
+        }
+        let page = search(arguments);
+        let page_scores = scores(&page);
+        assert!(
+            !page_scores.is_empty(),
+            "no page may come back empty: {page}"
+        );
+        paged.extend(page_scores);
+        cursor = page["metadata"]["next_cursor"].as_str().map(str::to_string);
+        if cursor.is_none() {
+            break;
+        }
+    }
+    assert_eq!(paged.len(), 2, "{paged:?}");
+    assert!(paged.iter().all(|score| *score >= threshold), "{paged:?}");
+
+    let first = search(json!({ "query": "user", "limit": 1, "min_score": threshold }));
+    let cursor = first["metadata"]["next_cursor"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let changed = search(json!({
+        "query": "user", "limit": 1, "min_score": threshold - 1.0, "cursor": cursor
+    }));
+    assert_eq!(changed["error"]["code"], "invalid_input", "{changed}");
+}
+
+#[test]
+fn t547_index_status_reports_running_job_progress_counts() {
+    let tmp = tempfile::tempdir().unwrap();
+    let workspace = tmp.path().join("workspace");
+    std::fs::create_dir_all(&workspace).unwrap();
+    let (config, conn, project_id) = setup_indexing_runtime(&workspace);
+
+    let now = "2026-02-24T00:00:00Z".to_string();
+    cruxe_state::jobs::create_job(
+        &conn,
+        &cruxe_state::jobs::IndexJob {
+            job_id: "job-progress-counts".to_string(),
+            project_id: project_id.clone(),
+            r#ref: cruxe_core::constants::REF_LIVE.to_string(),
+            mode: "incremental".to_string(),
+            head_commit: None,
+            sync_id: None,
+            status: "running".to_string(),
+            changed_files: 0,
+            duration_ms: None,
+            error_message: None,
+            retry_count: 0,
+            progress_token: Some("index-job-job-progress-counts".to_string()),
+            files_scanned: 120,
+            files_indexed: 50,
+            symbols_extracted: 300,
+            files_skipped: 0,
+            files_unchanged: 0,
+            files_excluded: 0,
+            files_per_second: None,
+            index_commits: 0,
+            created_at: now.clone(),
+            updated_at: now,
+            error_data: None,
+        },
+    )
+    .unwrap();
+
+    let tool_ctx = ToolCallFixture {
+        config: &config,
+        index_set: None,
+        conn: Some(&conn),
+        workspace: &workspace,
+        project_id: &project_id,
+    };
+    let payload = extract_payload_from_response(&tool_ctx.handle_with_schema(
+        &make_request(
+            "tools/call",
+            json!({ "name": "index_status", "arguments": {} }),
+        ),
+        SchemaStatus::NotIndexed,
+    ));
+    let active_job = &payload["active_job"];
+    assert_eq!(active_job["job_id"], "job-progress-counts", "{payload}");
+    assert_eq!(active_job["files_scanned"], 120);
+    assert_eq!(active_job["files_indexed"], 50);
+    assert_eq!(active_job["symbols_extracted"], 300);
+    assert!(
+        active_job["estimated_completion_pct"].is_number(),
+        "{payload}"
     );
 }
@@ -62,7 +62,201 @@ mod status;
 mod structure;
 use shared::*;
 
+/// Streams `search_code` results through `emit`; see `query::stream_search_code`.
+pub(super) fn stream_search_code_call(
+    params: ToolCallParams<'_>,
+    emit: &mut dyn FnMut(Value) -> bool,
+) -> Option<JsonRpcResponse> {
+    let request_id = cruxe_core::ids::new_request_id();
+    let span = tool_call_span(&request_id, &params);
+    let _entered = span.enter();
+    let started = Instant::now();
+    let mut emit = |mut line: Value| {
+        if line.get("type").and_then(Value::as_str) == Some("error") {
+            warn!(error = %line["error"], "streamed tool call returned an error");
+        }
+        stamp_request_id(&mut line, &request_id);
+        emit(line)
+    };
+    let (conn, project_id) = (params.conn, params.project_id);
+    let response = run_search_code_stream(params, &mut emit);
+    if let Some(response) = &response {
+        log_tool_call_outcome(response, started);
+    }
+    response.map(|response| {
+        let response = attach_available_refs(response, conn, project_id);
+        attach_request_id(response, &request_id)
+    })
+}
+
+fn run_search_code_stream(
+    params: ToolCallParams<'_>,
+    emit: &mut dyn FnMut(Value) -> bool,
+) -> Option<JsonRpcResponse> {
+    let ToolCallParams {
+        id,
+        arguments,
+        config,
+        index_set,
+        schema_status,
+        compatibility_reason,
+        conn,
+        workspace,
+        project_id,
+        ..
+    } = params;
+    query::stream_search_code(
+        QueryToolParams {
+            id,
+            arguments,
+            config,
+            index_set,
+            schema_status,
+            compatibility_reason,
+            conn,
+            workspace,
+            project_id,
+        },
+        emit,
+    )
+}
+
+/// Run one `tools/call` inside a `tool_call` span and stamp the generated
+/// `request_id` into the payload metadata, so a response can be matched to
+/// the log lines (including error logs) it produced on either transport.
 pub(super) fn handle_tool_call(params: ToolCallParams<'_>) -> JsonRpcResponse {
+    let request_id = cruxe_core::ids::new_request_id();
+    let span = tool_call_span(&request_id, &params);
+    let _entered = span.enter();
+    let started = Instant::now();
+    let (conn, project_id) = (params.conn, params.project_id);
+    let response = dispatch_tool_call(params);
+    log_tool_call_outcome(&response, started);
+    let response = attach_available_refs(response, conn, project_id);
+    attach_request_id(response, &request_id)
+}
+
+fn tool_call_span(request_id: &str, params: &ToolCallParams<'_>) -> tracing::Span {
+    let jsonrpc_id = params.id.as_ref().map(Value::to_string).unwrap_or_default();
+    tracing::info_span!(
+        "tool_call",
+        request_id,
+        jsonrpc_id = %jsonrpc_id,
+        tool = params.tool_name,
+        project_id = params.project_id,
+        r#ref = tracing::field::Empty,
+    )
+}
+
+fn log_tool_call_outcome(response: &JsonRpcResponse, started: Instant) {
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+    if let Some(error) = &response.error {
+        warn!(code = error.code, message = %error.message, elapsed_ms, "tool call failed");
+        return;
+    }
+    let error = tool_payload(response).and_then(|payload| payload.get("error").cloned());
+    match error {
+        Some(error) => {
+            let code = error
+                .get("code")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default();
+            let message = error
+                .get("message")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default();
+            warn!(code, message, elapsed_ms, "tool call returned an error");
+        }
+        None => tracing::debug!(elapsed_ms, "tool call completed"),
+    }
+}
+
+/// Decode the JSON payload carried in a tool response's text content.
+fn tool_payload(response: &JsonRpcResponse) -> Option<Value> {
+    let text = response
+        .result
+        .as_ref()?
+        .get("content")?
+        .get(0)?
+        .get("text")?;
+    serde_json::from_str(text.as_str()?).ok()
+}
+
+/// Add `request_id` to the payload's `metadata` object when it has one.
+fn attach_request_id(response: JsonRpcResponse, request_id: &str) -> JsonRpcResponse {
+    rewrite_tool_payload(response, |payload| stamp_request_id(payload, request_id))
+}
+
+/// On a `ref_not_indexed` error, list the refs that do have indexed state as
+/// `error.data.available_refs`, so the caller can retry without guessing.
+fn attach_available_refs(
+    response: JsonRpcResponse,
+    conn: Option<&rusqlite::Connection>,
+    project_id: &str,
+) -> JsonRpcResponse {
+    let Some(conn) = conn else {
+        return response;
+    };
+    rewrite_tool_payload(response, |payload| {
+        let Some(error) = payload.get_mut("error").and_then(Value::as_object_mut) else {
+            return false;
+        };
+        if error.get("code").and_then(Value::as_str)
+            != Some(ProtocolErrorCode::RefNotIndexed.as_str())
+        {
+            return false;
+        }
+        let Ok(states) = cruxe_state::branch_state::list_branch_states(conn, project_id) else {
+            return false;
+        };
+        let mut refs: Vec<String> = states.into_iter().map(|state| state.r#ref).collect();
+        refs.sort();
+        let data = error.entry("data").or_insert_with(|| json!({}));
+        if !data.is_object() {
+            *data = json!({ "details": data.clone() });
+        }
+        data["available_refs"] = json!(refs);
+        true
+    })
+}
+
+/// Re-encode the payload of a tool response after `rewrite` changed it;
+/// `rewrite` returns `false` to leave the response untouched.
+fn rewrite_tool_payload(
+    mut response: JsonRpcResponse,
+    rewrite: impl FnOnce(&mut Value) -> bool,
+) -> JsonRpcResponse {
+    let Some(mut payload) = tool_payload(&response) else {
+        return response;
+    };
+    if !rewrite(&mut payload) {
+        return response;
+    }
+    if let Some(content) = response
+        .result
+        .as_mut()
+        .and_then(|result| result.get_mut("content"))
+        .and_then(|content| content.get_mut(0))
+    {
+        content["text"] = Value::String(serde_json::to_string(&payload).unwrap_or_default());
+    }
+    response
+}
+
+fn stamp_request_id(payload: &mut Value, request_id: &str) -> bool {
+    match payload.get_mut("metadata").and_then(Value::as_object_mut) {
+        Some(metadata) => {
+            metadata.insert(
+                "request_id".to_string(),
+                Value::String(request_id.to_string()),
+            );
+            true
+        }
+        None => false,
+    }
+}
+
+fn dispatch_tool_call(params: ToolCallParams<'_>) -> JsonRpcResponse {
     if params.tool_name == "health_check" {
         return health::handle_health_check(&params);
     }
@@ -117,6 +311,17 @@ pub(super) fn handle_tool_call(params: ToolCallParams<'_>) -> JsonRpcResponse {
             workspace,
             project_id,
         }),
+        "implementations_of" => structure::handle_implementations_of(QueryToolParams {
+            id,
+            arguments,
+            config,
+            index_set,
+            schema_status,
+            compatibility_reason,
+            conn,
+            workspace,
+            project_id,
+        }),
         "find_related_symbols" => structure::handle_find_related_symbols(QueryToolParams {
             id,
             arguments,
@@ -161,6 +366,39 @@ pub(super) fn handle_tool_call(params: ToolCallParams<'_>) -> JsonRpcResponse {
             workspace,
             project_id,
         }),
+        "diff_refs" => query::handle_diff_refs(QueryToolParams {
+            id,
+            arguments,
+            config,
+            index_set,
+            schema_status,
+            compatibility_reason,
+            conn,
+            workspace,
+            project_id,
+        }),
+        "tests_for_symbol" => query::handle_tests_for_symbol(QueryToolParams {
+            id,
+            arguments,
+            config,
+            index_set,
+            schema_status,
+            compatibility_reason,
+            conn,
+            workspace,
+            project_id,
+        }),
+        "resolve_import" => query::handle_resolve_import(QueryToolParams {
+            id,
+            arguments,
+            config,
+            index_set,
+            schema_status,
+            compatibility_reason,
+            conn,
+            workspace,
+            project_id,
+        }),
         "find_references" => query::handle_find_references(QueryToolParams {
             id,
             arguments,
@@ -183,6 +421,17 @@ pub(super) fn handle_tool_call(params: ToolCallParams<'_>) -> JsonRpcResponse {
             workspace,
             project_id,
         }),
+        "get_import_graph" => query::handle_get_import_graph(QueryToolParams {
+            id,
+            arguments,
+            config,
+            index_set,
+            schema_status,
+            compatibility_reason,
+            conn,
+            workspace,
+            project_id,
+        }),
         "compare_symbol_between_commits" => {
             query::handle_compare_symbol_between_commits(QueryToolParams {
                 id,
@@ -196,6 +445,118 @@ pub(super) fn handle_tool_call(params: ToolCallParams<'_>) -> JsonRpcResponse {
                 project_id,
             })
         }
+        "export_symbol_names" => structure::handle_export_symbol_names(QueryToolParams {
+            id,
+            arguments,
+            config,
+            index_set,
+            schema_status,
+            compatibility_reason,
+            conn,
+            workspace,
+            project_id,
+        }),
+        "large_symbols" => structure::handle_large_symbols(QueryToolParams {
+            id,
+            arguments,
+            config,
+            index_set,
+            schema_status,
+            compatibility_reason,
+            conn,
+            workspace,
+            project_id,
+        }),
+        "find_unreferenced_symbols" => {
+            structure::handle_find_unreferenced_symbols(QueryToolParams {
+                id,
+                arguments,
+                config,
+                index_set,
+                schema_status,
+                compatibility_reason,
+                conn,
+                workspace,
+                project_id,
+            })
+        }
+        "list_files" => structure::handle_list_files(QueryToolParams {
+            id,
+            arguments,
+            config,
+            index_set,
+            schema_status,
+            compatibility_reason,
+            conn,
+            workspace,
+            project_id,
+        }),
+        "list_skipped_files" => structure::handle_list_skipped_files(QueryToolParams {
+            id,
+            arguments,
+            config,
+            index_set,
+            schema_status,
+            compatibility_reason,
+            conn,
+            workspace,
+            project_id,
+        }),
+        "symbol_at_offset" => structure::handle_symbol_at_offset(QueryToolParams {
+            id,
+            arguments,
+            config,
+            index_set,
+            schema_status,
+            compatibility_reason,
+            conn,
+            workspace,
+            project_id,
+        }),
+        "symbol_at_location" => structure::handle_symbol_at_location(QueryToolParams {
+            id,
+            arguments,
+            config,
+            index_set,
+            schema_status,
+            compatibility_reason,
+            conn,
+            workspace,
+            project_id,
+        }),
+        "recent_symbols" => structure::handle_recent_symbols(QueryToolParams {
+            id,
+            arguments,
+            config,
+            index_set,
+            schema_status,
+            compatibility_reason,
+            conn,
+            workspace,
+            project_id,
+        }),
+        "compare_symbols" => query::handle_compare_symbols(QueryToolParams {
+            id,
+            arguments,
+            config,
+            index_set,
+            schema_status,
+            compatibility_reason,
+            conn,
+            workspace,
+            project_id,
+        }),
+        "get_symbol" => structure::handle_get_symbol(QueryToolParams {
+            id,
+            arguments,
+            config,
+            index_set,
+            schema_status,
+            compatibility_reason,
+            conn,
+            workspace,
+            project_id,
+        }),
         "suggest_followup_queries" => query::handle_suggest_followup_queries(QueryToolParams {
             id,
             arguments,
@@ -240,6 +601,17 @@ pub(super) fn handle_tool_call(params: ToolCallParams<'_>) -> JsonRpcResponse {
             workspace,
             project_id,
         }),
+        "drop_ref" => refs::handle_drop_ref(QueryToolParams {
+            id,
+            arguments,
+            config,
+            index_set,
+            schema_status,
+            compatibility_reason,
+            conn,
+            workspace,
+            project_id,
+        }),
         "get_file_outline" => structure::handle_get_file_outline(QueryToolParams {
             id,
             arguments,
@@ -251,6 +623,17 @@ pub(super) fn handle_tool_call(params: ToolCallParams<'_>) -> JsonRpcResponse {
             workspace,
             project_id,
         }),
+        "directory_outline" => structure::handle_directory_outline(QueryToolParams {
+            id,
+            arguments,
+            config,
+            index_set,
+            schema_status,
+            compatibility_reason,
+            conn,
+            workspace,
+            project_id,
+        }),
         "index_status" => status::handle_index_status(IndexStatusToolParams {
             id,
             arguments,
@@ -261,6 +644,16 @@ pub(super) fn handle_tool_call(params: ToolCallParams<'_>) -> JsonRpcResponse {
             workspace,
             project_id,
         }),
+        "compact_index" => index::handle_compact_index(IndexStatusToolParams {
+            id,
+            arguments,
+            config,
+            schema_status,
+            compatibility_reason,
+            conn,
+            workspace,
+            project_id,
+        }),
         "index_repo" | "sync_repo" => index::handle_index_operation(IndexOperationParams {
             id,
             tool_name,
@@ -366,6 +759,25 @@ fn tool_error_response(
     tool_text_response(id, Value::Object(payload))
 }
 
+/// Per-node edge cap requested by relation tools via `max_breadth`.
+fn requested_max_breadth(arguments: &Value) -> usize {
+    arguments
+        .get("max_breadth")
+        .and_then(|value| value.as_u64())
+        .map(|value| value.min(usize::MAX as u64) as usize)
+        .unwrap_or(cruxe_core::constants::DEFAULT_RELATION_MAX_BREADTH)
+}
+
+fn max_breadth_warning(requested: usize) -> Option<String> {
+    let applied = cruxe_query::call_graph::clamp_breadth(requested);
+    (applied != requested).then(|| {
+        format!(
+            "Requested max_breadth {requested} is outside 1..={MAX}; using {applied}.",
+            MAX = cruxe_core::constants::MAX_RELATION_BREADTH
+        )
+    })
+}
+
 /// Helper: wrap a JSON value as MCP tool text content response.
 pub(crate) fn tool_text_response(id: Option<Value>, payload: Value) -> JsonRpcResponse {
     JsonRpcResponse::success(
@@ -391,6 +803,8 @@ mod tests {
             path: "src/lib.rs".to_string(),
             line_start: 10,
             line_end: 20,
+            byte_start: None,
+            byte_end: None,
             kind: Some("fn".to_string()),
             name: Some("foo".to_string()),
             qualified_name: Some("foo".to_string()),
@@ -402,6 +816,7 @@ mod tests {
             chunk_type: None,
             source_layer: None,
             provenance: "lexical".to_string(),
+            highlight: None,
         };
         let mut second = base.clone();
         second.result_id = "r2".to_string();
@@ -414,6 +829,8 @@ mod tests {
             path: "src/other.rs".to_string(),
             line_start: 1,
             line_end: 1,
+            byte_start: None,
+            byte_end: None,
             kind: None,
             name: None,
             qualified_name: None,
@@ -425,6 +842,7 @@ mod tests {
             chunk_type: None,
             source_layer: None,
             provenance: "lexical".to_string(),
+            highlight: None,
         };
 
         let (deduped, kept_indices, suppressed) = dedup_search_results(vec![base, second, third]);
@@ -442,13 +860,18 @@ mod tests {
             path: "src/lib.rs".to_string(),
             line_start: 10,
             line_end: 20,
+            byte_start: None,
+            byte_end: None,
             kind: "fn".to_string(),
             name: "foo".to_string(),
             qualified_name: "foo".to_string(),
             signature: None,
             language: "rust".to_string(),
             visibility: None,
+            attributes: Vec::new(),
+            match_type: locate::LocateMatchType::Exact,
             source_layer: None,
+            parent: None,
             score: 1.0,
         };
         let mut b = a.clone();
@@ -491,6 +914,7 @@ mod tests {
             DetailLevel::Context,
             true,
             None,
+            None,
             "proj_1",
             "main",
             4096,
@@ -506,6 +930,38 @@ mod tests {
         assert!(first.get("body_preview").is_none());
     }
 
+    #[test]
+    fn filtered_payload_fields_whitelist_projects_keys() {
+        let results = vec![json!({
+            "result_id": "res_1",
+            "path": "src/lib.rs",
+            "line_start": 10,
+            "name": "validate_token",
+            "signature": "fn validate_token()",
+        })];
+        let fields = [
+            "path".to_string(),
+            "name".to_string(),
+            "unknown".to_string(),
+        ];
+
+        let payload = build_filtered_result_payload(
+            results,
+            DetailLevel::Signature,
+            false,
+            Some(&fields),
+            None,
+            "proj_1",
+            "main",
+            4096,
+        );
+
+        assert_eq!(
+            payload.filtered,
+            vec![json!({"path": "src/lib.rs", "name": "validate_token"})]
+        );
+    }
+
     #[test]
     fn ranking_payload_basic_uses_compact_fields() {
         let reasons = vec![cruxe_core::types::RankingReasons {
@@ -517,6 +973,7 @@ mod tests {
             definition_boost: 1.0,
             kind_match: 2.0,
             test_file_penalty: 0.0,
+            language_preference_boost: 0.0,
             confidence_structural_boost: 0.0,
             structural_weighted_centrality: 0.0,
             structural_raw_centrality: 0.0,
@@ -4,6 +4,9 @@ use cruxe_core::types::PolicyMode;
 const MAX_CONTEXT_PACK_BUDGET_TOKENS: usize = 200_000;
 
 pub(super) fn handle_get_code_context(params: QueryToolParams<'_>) -> JsonRpcResponse {
+    if params.arguments.get("line").is_some() {
+        return handle_code_context_window(params);
+    }
     let QueryToolParams {
         id,
         arguments,
@@ -22,10 +25,22 @@ pub(super) fn handle_get_code_context(params: QueryToolParams<'_>) -> JsonRpcRes
         .unwrap_or("");
     let requested_ref = arguments.get("ref").and_then(|v| v.as_str());
     let language = arguments.get("language").and_then(|v| v.as_str());
-    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id);
+    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
     let mut metadata = validation_metadata(&effective_ref, schema_status);
 
-    if query.trim().is_empty() {
+    let symbol_ids = match parse_context_ids(arguments) {
+        Ok(symbol_ids) => symbol_ids,
+        Err(message) => {
+            return tool_error_response(
+                id,
+                ProtocolErrorCode::InvalidInput,
+                message,
+                None,
+                metadata,
+            );
+        }
+    };
+    if symbol_ids.is_none() && query.trim().is_empty() {
         return tool_error_response(
             id,
             ProtocolErrorCode::InvalidInput,
@@ -108,6 +123,40 @@ pub(super) fn handle_get_code_context(params: QueryToolParams<'_>) -> JsonRpcRes
         },
         None => None,
     };
+    let with_line_numbers = match arguments.get("with_line_numbers") {
+        None => false,
+        Some(value) => match value.as_bool() {
+            Some(flag) => flag,
+            None => {
+                return tool_error_response(
+                    id,
+                    ProtocolErrorCode::InvalidInput,
+                    "Parameter `with_line_numbers` must be a boolean.",
+                    None,
+                    metadata,
+                );
+            }
+        },
+    };
+    let mut body_extension = [0u32; 2];
+    for (slot, name) in body_extension
+        .iter_mut()
+        .zip(["prefix_lines", "suffix_lines"])
+    {
+        match parse_body_extension(arguments, name) {
+            Ok(lines) => *slot = lines,
+            Err(message) => {
+                return tool_error_response(
+                    id,
+                    ProtocolErrorCode::InvalidInput,
+                    message,
+                    None,
+                    metadata,
+                );
+            }
+        }
+    }
+    let [prefix_lines, suffix_lines] = body_extension;
 
     let Some(index_set) = index_set else {
         return tool_compatibility_error(ToolCompatibilityParams {
@@ -150,18 +199,58 @@ pub(super) fn handle_get_code_context(params: QueryToolParams<'_>) -> JsonRpcRes
     }
     metadata = freshness.metadata;
 
-    match cruxe_query::context::get_code_context(cruxe_query::context::GetCodeContextParams {
-        index_set,
-        conn,
-        search_config: &config.search,
-        workspace,
-        query,
-        ref_name: Some(&effective_ref),
-        language,
-        max_tokens,
-        strategy,
-        policy_mode_override,
-    }) {
+    let result = match symbol_ids {
+        Some(symbol_ids) => {
+            let Some(c) = conn else {
+                return tool_compatibility_error(ToolCompatibilityParams {
+                    id,
+                    schema_status,
+                    compatibility_reason,
+                    config,
+                    conn,
+                    workspace,
+                    project_id,
+                    ref_name: &effective_ref,
+                });
+            };
+            cruxe_query::context::get_symbols_context(
+                cruxe_query::context::GetSymbolsContextParams {
+                    conn: c,
+                    search_config: &config.search,
+                    workspace,
+                    repo: project_id,
+                    ref_name: &effective_ref,
+                    ids: &symbol_ids,
+                    max_tokens,
+                    policy_mode_override,
+                    with_line_numbers,
+                    prefix_lines,
+                    suffix_lines,
+                    max_file_size: config.index.max_file_size,
+                    max_response_bytes: config.search.max_response_bytes,
+                },
+            )
+        }
+        None => {
+            cruxe_query::context::get_code_context(cruxe_query::context::GetCodeContextParams {
+                index_set,
+                conn,
+                search_config: &config.search,
+                workspace,
+                query,
+                ref_name: Some(&effective_ref),
+                language,
+                max_tokens,
+                strategy,
+                policy_mode_override,
+                with_line_numbers,
+                prefix_lines,
+                suffix_lines,
+                max_file_size: config.index.max_file_size,
+            })
+        }
+    };
+    match result {
         Ok(response) => {
             if response.truncated {
                 metadata.result_completeness = cruxe_core::types::ResultCompleteness::Truncated;
@@ -215,6 +304,284 @@ pub(super) fn handle_get_code_context(params: QueryToolParams<'_>) -> JsonRpcRes
             None,
             metadata,
         ),
+        // Window-mode errors; query-driven context never produces them.
+        Err(err) => tool_error_response(
+            id,
+            ProtocolErrorCode::InternalError,
+            err.to_string(),
+            None,
+            metadata,
+        ),
+    }
+}
+
+/// `ids`: symbol ids for batch mode, in request order. `None` when absent.
+fn parse_context_ids(arguments: &Value) -> Result<Option<Vec<String>>, String> {
+    let Some(value) = arguments.get("ids") else {
+        return Ok(None);
+    };
+    let invalid = || {
+        format!(
+            "Parameter `ids` must be an array of 1 to {} non-empty symbol id strings.",
+            cruxe_query::context::MAX_CONTEXT_IDS
+        )
+    };
+    let raw = value.as_array().ok_or_else(invalid)?;
+    if raw.is_empty() || raw.len() > cruxe_query::context::MAX_CONTEXT_IDS {
+        return Err(invalid());
+    }
+    raw.iter()
+        .map(|id| {
+            id.as_str()
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .map(str::to_string)
+                .ok_or_else(invalid)
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
+}
+
+/// `prefix_lines` / `suffix_lines`: lines added around each depth-strategy
+/// body, capped like `context_lines`.
+fn parse_body_extension(arguments: &Value, name: &str) -> Result<u32, String> {
+    match arguments.get(name) {
+        None => Ok(0),
+        Some(value) => value
+            .as_u64()
+            .filter(|n| *n <= u64::from(cruxe_query::context::MAX_CONTEXT_LINES))
+            .map(|n| n as u32)
+            .ok_or_else(|| {
+                format!(
+                    "Parameter `{name}` must be an integer between 0 and {}.",
+                    cruxe_query::context::MAX_CONTEXT_LINES
+                )
+            }),
+    }
+}
+
+/// `get_code_context` with `path` + `line`: a numbered window of
+/// `context_lines` around the line instead of query-driven symbol context.
+fn handle_code_context_window(params: QueryToolParams<'_>) -> JsonRpcResponse {
+    let QueryToolParams {
+        id,
+        arguments,
+        config,
+        index_set,
+        schema_status,
+        compatibility_reason,
+        conn,
+        workspace,
+        project_id,
+    } = params;
+
+    let requested_ref = arguments.get("ref").and_then(|v| v.as_str());
+    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
+    let metadata = validation_metadata(&effective_ref, schema_status);
+
+    let path = arguments
+        .get("path")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .trim();
+    if path.is_empty() {
+        return tool_error_response(
+            id,
+            ProtocolErrorCode::InvalidInput,
+            "Parameter `path` is required when `line` is given.",
+            None,
+            metadata,
+        );
+    }
+    let Some(line) = arguments
+        .get("line")
+        .and_then(|v| v.as_u64())
+        .filter(|line| *line >= 1)
+        .and_then(|line| u32::try_from(line).ok())
+    else {
+        return tool_error_response(
+            id,
+            ProtocolErrorCode::InvalidInput,
+            "Parameter `line` must be a positive integer.",
+            None,
+            metadata,
+        );
+    };
+    let context_lines = match arguments.get("context_lines") {
+        None => cruxe_query::context::DEFAULT_CONTEXT_LINES,
+        Some(value) => match value
+            .as_u64()
+            .filter(|n| *n <= u64::from(cruxe_query::context::MAX_CONTEXT_LINES))
+        {
+            Some(n) => n as u32,
+            None => {
+                return tool_error_response(
+                    id,
+                    ProtocolErrorCode::InvalidInput,
+                    format!(
+                        "Parameter `context_lines` must be an integer between 0 and {}.",
+                        cruxe_query::context::MAX_CONTEXT_LINES
+                    ),
+                    None,
+                    metadata,
+                );
+            }
+        },
+    };
+
+    let Some(index_set) = index_set else {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    };
+    if schema_status != SchemaStatus::Compatible {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    }
+
+    let freshness = check_and_enforce_freshness(
+        id.clone(),
+        arguments,
+        config,
+        conn,
+        workspace,
+        project_id,
+        &effective_ref,
+        schema_status,
+    );
+    if let Some(block) = freshness.block_response {
+        return block;
+    }
+    let metadata = freshness.metadata;
+
+    // Overlay refs keep changed files in their own index; unchanged files
+    // are still served from the base ref.
+    let overlay = match query::resolve_vcs_overlay_context(conn, config, project_id, &effective_ref)
+    {
+        Ok(overlay) => overlay,
+        Err(err) => {
+            let (code, message, data) = map_state_error(&err);
+            return tool_error_response(id, code, message, data, metadata);
+        }
+    };
+    let mut indexed_sources = Vec::new();
+    if let Some(overlay) = &overlay {
+        indexed_sources.push((&overlay.overlay_index_set.files, effective_ref.as_str()));
+        if !overlay.tombstones.contains(path) {
+            indexed_sources.push((&index_set.files, overlay.default_ref.as_str()));
+        }
+    } else {
+        indexed_sources.push((&index_set.files, effective_ref.as_str()));
+    }
+
+    match cruxe_query::context::get_line_window(cruxe_query::context::LineWindowParams {
+        workspace,
+        repo: project_id,
+        path,
+        line,
+        context_lines,
+        max_file_size: config.index.max_file_size,
+        indexed_sources: &indexed_sources,
+    }) {
+        Ok(window) => {
+            let mut payload = json!({
+                "path": window.path,
+                "line": window.line,
+                "line_start": window.line_start,
+                "line_end": window.line_end,
+                "content": window.content,
+                "source": window.source,
+                "metadata": metadata,
+            });
+            if window.source == cruxe_query::context::LineWindowSource::ContentHead {
+                payload["note"] = json!(
+                    "File is not on disk; the window was built from the indexed head of the file, which only covers its first lines."
+                );
+            }
+            tool_text_response(id, payload)
+        }
+        Err(cruxe_query::context::ContextError::InvalidPath { path }) => tool_error_response(
+            id,
+            ProtocolErrorCode::InvalidInput,
+            "Parameter `path` must be relative to the workspace.",
+            Some(json!({ "path": path })),
+            metadata,
+        ),
+        Err(cruxe_query::context::ContextError::FileNotFound { path }) => tool_error_response(
+            id,
+            ProtocolErrorCode::FileNotFound,
+            format!(
+                "File '{}' is neither on disk nor indexed on ref '{}'.",
+                path, effective_ref
+            ),
+            Some(json!({
+                "path": path,
+                "ref": effective_ref,
+                "remediation": "Verify the file path and ensure the project is indexed.",
+            })),
+            metadata,
+        ),
+        Err(
+            err @ cruxe_query::context::ContextError::FileTooLarge {
+                size_bytes,
+                max_file_size,
+                ..
+            },
+        ) => tool_error_response(
+            id,
+            ProtocolErrorCode::InvalidInput,
+            err.to_string(),
+            Some(json!({
+                "path": path,
+                "size_bytes": size_bytes,
+                "max_file_size": max_file_size,
+            })),
+            metadata,
+        ),
+        Err(
+            err @ cruxe_query::context::ContextError::LineOutOfRange {
+                line_count,
+                read_from,
+                ..
+            },
+        ) => tool_error_response(
+            id,
+            ProtocolErrorCode::InvalidInput,
+            err.to_string(),
+            Some(json!({
+                "path": path,
+                "line": line,
+                "line_count": line_count,
+                "source": read_from,
+            })),
+            metadata,
+        ),
+        Err(cruxe_query::context::ContextError::State(err)) => {
+            let (code, message, data) = map_state_error(&err);
+            tool_error_response(id, code, message, data, metadata)
+        }
+        Err(err) => tool_error_response(
+            id,
+            ProtocolErrorCode::InternalError,
+            err.to_string(),
+            None,
+            metadata,
+        ),
     }
 }
 
@@ -237,7 +604,7 @@ pub(super) fn handle_build_context_pack(params: QueryToolParams<'_>) -> JsonRpcR
         .unwrap_or("");
     let requested_ref = arguments.get("ref").and_then(|value| value.as_str());
     let language = arguments.get("language").and_then(|value| value.as_str());
-    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id);
+    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
     let mut metadata = validation_metadata(&effective_ref, schema_status);
 
     if query.trim().is_empty() {
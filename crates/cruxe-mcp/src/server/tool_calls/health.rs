@@ -25,6 +25,8 @@ struct WarmsetPayload {
     enabled: bool,
     capacity: usize,
     members: Vec<String>,
+    /// Least recently used workspaces left out because the warmset is full.
+    evicted: Vec<String>,
 }
 
 #[derive(Serialize)]
@@ -64,7 +66,7 @@ pub(super) fn handle_health_check(params: &ToolCallParams<'_>) -> JsonRpcRespons
         .get("workspace")
         .and_then(|v| v.as_str())
         .is_some_and(|s| !s.trim().is_empty());
-    let effective_ref = resolve_tool_ref(None, workspace, *conn, project_id);
+    let effective_ref = resolve_tool_ref(None, workspace, *conn, project_id, config);
     let metadata = build_metadata(
         &effective_ref,
         *schema_status,
@@ -77,8 +79,7 @@ pub(super) fn handle_health_check(params: &ToolCallParams<'_>) -> JsonRpcRespons
     let pw_status = prewarm_status.load(Ordering::Acquire);
     let pw_label = prewarm_status_label(pw_status);
     let warmset_capacity = crate::server::warmset_capacity();
-    let warmset_members =
-        crate::server::collect_warmset_members(*conn, workspace, warmset_capacity);
+    let warmset = crate::server::collect_warmset_members(*conn, workspace, warmset_capacity);
     let warmset_enabled = pw_status != PREWARM_SKIPPED;
 
     let tantivy_checks = if let Some(idx) = index_set {
@@ -144,7 +145,12 @@ pub(super) fn handle_health_check(params: &ToolCallParams<'_>) -> JsonRpcRespons
             enabled: warmset_enabled,
             capacity: warmset_capacity,
             members: if warmset_enabled {
-                warmset_members
+                warmset.members
+            } else {
+                Vec::new()
+            },
+            evicted: if warmset_enabled {
+                warmset.evicted
             } else {
                 Vec::new()
             },
@@ -20,7 +20,7 @@ pub(super) fn handle_index_operation(params: IndexOperationParams<'_>) -> JsonRp
         .unwrap_or(false);
     let mode = if force { "full" } else { "incremental" };
     let requested_ref = arguments.get("ref").and_then(|v| v.as_str());
-    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id);
+    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
     let metadata = build_metadata(
         &effective_ref,
         schema_status,
@@ -55,7 +55,44 @@ pub(super) fn handle_index_operation(params: IndexOperationParams<'_>) -> JsonRp
         );
     }
 
+    // Running out of space mid-write leaves a corrupt manifest; refuse up front.
+    if let Err(err) = cruxe_state::disk_space::ensure_free_disk(
+        &config.project_data_dir(project_id),
+        config.index.min_free_disk_mb,
+    ) {
+        let (code, message, data) = map_state_error(&err);
+        return tool_error_response(id, code, message, data, metadata);
+    }
+
     let job_id = crate::index_launcher::generate_job_id();
+    // The pre-check above is advisory; two near-simultaneous calls can both pass
+    // it. Reserving the job row is what actually admits one of them.
+    if let Some(conn) = conn {
+        match cruxe_state::jobs::reserve_job(conn, &job_id, project_id, &effective_ref, mode) {
+            Ok(()) => {}
+            Err(StateError::SyncInProgress {
+                job_id: active_job_id,
+                ..
+            }) => {
+                return tool_error_response(
+                    id,
+                    ProtocolErrorCode::IndexInProgress,
+                    "An indexing job is already running.",
+                    Some(json!({
+                        "project_id": project_id,
+                        "ref": effective_ref,
+                        "job_id": active_job_id,
+                        "remediation": "Use index_status to poll and retry after completion.",
+                    })),
+                    metadata,
+                );
+            }
+            Err(err) => {
+                let (code, message, data) = map_state_error(&err);
+                return tool_error_response(id, code, message, data, metadata);
+            }
+        }
+    }
     let server_progress_token = format!("index-job-{}", job_id);
     let effective_progress_token = progress_token
         .clone()
@@ -89,6 +126,8 @@ pub(super) fn handle_index_operation(params: IndexOperationParams<'_>) -> JsonRp
                 .project_data_dir(project_id)
                 .join(constants::STATE_DB_FILE);
             let poll_project_id = project_id.to_string();
+            let poll_job_id = job_id.clone();
+            let max_job_duration = config.index.max_job_duration();
             let notification_start = std::time::Instant::now();
             std::thread::spawn(move || {
                 let mut child = child;
@@ -125,6 +164,30 @@ pub(super) fn handle_index_operation(params: IndexOperationParams<'_>) -> JsonRp
                             break;
                         }
                         Ok(None) => {
+                            if crate::index_launcher::kill_if_overdue(
+                                &mut child,
+                                notification_start,
+                                max_job_duration,
+                            ) {
+                                if let Some(max_job_duration) = max_job_duration {
+                                    crate::index_launcher::mark_job_timed_out(
+                                        &poll_db_path,
+                                        &poll_job_id,
+                                        max_job_duration,
+                                    );
+                                    if let Some(ref token) = poll_token {
+                                        notifier_clone.emit_end(
+                                            token,
+                                            "Indexing failed",
+                                            &format!(
+                                                "Error: Indexer exceeded {}s and was killed",
+                                                max_job_duration.as_secs()
+                                            ),
+                                        );
+                                    }
+                                }
+                                break;
+                            }
                             if poll_conn.is_none() {
                                 poll_conn = cruxe_state::db::open_connection(&poll_db_path).ok();
                             }
@@ -209,6 +272,12 @@ pub(super) fn handle_index_operation(params: IndexOperationParams<'_>) -> JsonRp
                         }
                     }
                 }
+                // An indexer that exited before starting its job leaves the slot queued.
+                if let Some(conn) =
+                    poll_conn.or_else(|| cruxe_state::db::open_connection(&poll_db_path).ok())
+                {
+                    let _ = cruxe_state::jobs::release_reserved_job(&conn, &poll_job_id);
+                }
             });
 
             let mut payload = serde_json::Map::new();
@@ -224,15 +293,89 @@ pub(super) fn handle_index_operation(params: IndexOperationParams<'_>) -> JsonRp
             payload.insert("metadata".to_string(), json!(metadata));
             tool_text_response(id, Value::Object(payload))
         }
-        Err(e) => tool_error_response(
+        Err(failure) => {
+            if let Some(conn) = conn {
+                let _ = cruxe_state::jobs::release_reserved_job(conn, &job_id);
+            }
+            tool_error_response(
+                id,
+                ProtocolErrorCode::InternalError,
+                "Failed to spawn indexer process.",
+                Some(json!({
+                    "details": failure.error.to_string(),
+                    "attempts": failure.attempts,
+                    "remediation": "Run `cruxe index` manually to inspect logs.",
+                })),
+                metadata,
+            )
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+struct CompactIndexPayload {
+    #[serde(flatten)]
+    report: cruxe_state::compaction::CompactionReport,
+    metadata: ProtocolMetadata,
+}
+
+pub(super) fn handle_compact_index(params: IndexStatusToolParams<'_>) -> JsonRpcResponse {
+    let IndexStatusToolParams {
+        id,
+        config,
+        schema_status,
+        conn,
+        workspace,
+        project_id,
+        ..
+    } = params;
+
+    // Compaction covers every ref; the default ref only labels the metadata.
+    let effective_ref = resolve_tool_ref(None, workspace, conn, project_id, config);
+    let metadata = build_metadata(
+        &effective_ref,
+        schema_status,
+        config,
+        conn,
+        workspace,
+        project_id,
+    );
+
+    let Some(conn) = conn.filter(|_| is_project_registered(conn, workspace)) else {
+        return tool_error_response(
+            id,
+            ProtocolErrorCode::ProjectNotFound,
+            "Project is not initialized for this workspace. Run `cruxe init` first.",
+            Some(json!({
+                "workspace": workspace.to_string_lossy(),
+                "remediation": "cruxe init --path <workspace>",
+            })),
+            metadata,
+        );
+    };
+    // Merging segments needs the index writer the indexer would be holding.
+    if has_active_job(Some(conn), project_id) {
+        return tool_error_response(
             id,
-            ProtocolErrorCode::InternalError,
-            "Failed to spawn indexer process.",
+            ProtocolErrorCode::IndexInProgress,
+            "An indexing job is already running.",
             Some(json!({
-                "details": e.to_string(),
-                "remediation": "Run `cruxe index` manually to inspect logs.",
+                "project_id": project_id,
+                "remediation": "Use index_status to poll and retry after completion.",
             })),
             metadata,
-        ),
+        );
+    }
+
+    match cruxe_state::compaction::compact_project(conn, &config.project_data_dir(project_id)) {
+        Ok(report) => {
+            let result = serde_json::to_value(CompactIndexPayload { report, metadata })
+                .unwrap_or_else(|_| json!({"error": "failed to serialize compact_index payload"}));
+            tool_text_response(id, result)
+        }
+        Err(err) => {
+            let (code, message, data) = map_state_error(&err);
+            tool_error_response(id, code, message, data, metadata)
+        }
     }
 }
@@ -1,13 +1,14 @@
 use super::*;
+use cruxe_core::languages::detect_language_from_extension;
 use cruxe_core::types::PolicyMode;
 use std::collections::HashSet;
 use std::path::PathBuf;
 use tracing::warn;
 
-struct VcsOverlayContext {
-    default_ref: String,
-    overlay_index_set: IndexSet,
-    tombstones: HashSet<String>,
+pub(super) struct VcsOverlayContext {
+    pub(super) default_ref: String,
+    pub(super) overlay_index_set: IndexSet,
+    pub(super) tombstones: HashSet<String>,
 }
 
 struct QueryExecutionContext<'a> {
@@ -18,7 +19,7 @@ struct QueryExecutionContext<'a> {
     effective_ref: &'a str,
 }
 
-fn resolve_vcs_overlay_context(
+pub(super) fn resolve_vcs_overlay_context(
     conn: Option<&rusqlite::Connection>,
     config: &Config,
     project_id: &str,
@@ -111,6 +112,7 @@ fn execute_locate_with_optional_overlay(
     role: Option<&str>,
     language: Option<&str>,
     limit: usize,
+    options: locate::LocateOptions,
 ) -> Result<(Vec<locate::LocateResult>, usize), StateError> {
     let QueryExecutionContext {
         index_set,
@@ -134,10 +136,11 @@ fn execute_locate_with_optional_overlay(
             role,
             language,
             limit,
+            options,
         );
     }
 
-    let results = locate::locate_symbol(
+    let results = locate::locate_symbol_with_options(
         &index_set.symbols,
         name,
         kind,
@@ -145,6 +148,7 @@ fn execute_locate_with_optional_overlay(
         language,
         Some(effective_ref),
         limit,
+        options,
     )?;
     let total_candidates = results.len();
     Ok((results, total_candidates))
@@ -237,10 +241,41 @@ pub(super) fn handle_locate_symbol(params: QueryToolParams<'_>) -> JsonRpcRespon
     let limit = arguments
         .get("limit")
         .and_then(|v| v.as_u64())
-        .unwrap_or(10) as usize;
-    let detail_level = parse_detail_level(arguments);
+        .map_or(config.search.default_limit, |limit| limit as usize);
+    let fuzzy = arguments
+        .get("fuzzy")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let case_sensitive = arguments
+        .get("case_sensitive")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let detail_level = parse_detail_level(arguments, config);
     let compact = parse_compact(arguments);
-    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id);
+    let mut effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
+    let pinned_commit = match arguments.get("commit").and_then(|v| v.as_str()) {
+        Some(commit) => match resolve_tool_commit(
+            commit,
+            requested_ref.map(|_| effective_ref.as_str()),
+            conn,
+            project_id,
+        ) {
+            Ok(state) => {
+                effective_ref = state.r#ref;
+                Some(state.last_indexed_commit)
+            }
+            Err(message) => {
+                return tool_error_response(
+                    id,
+                    ProtocolErrorCode::InvalidInput,
+                    message,
+                    Some(json!({ "commit": commit })),
+                    validation_metadata(&effective_ref, schema_status),
+                );
+            }
+        },
+        None => None,
+    };
     let base_metadata = validation_metadata(&effective_ref, schema_status);
 
     if name.trim().is_empty() {
@@ -252,6 +287,45 @@ pub(super) fn handle_locate_symbol(params: QueryToolParams<'_>) -> JsonRpcRespon
             base_metadata,
         );
     }
+    let visibility = match parse_visibility_filter(arguments) {
+        Ok(visibility) => visibility,
+        Err(message) => {
+            return tool_error_response(
+                id,
+                ProtocolErrorCode::InvalidInput,
+                message,
+                Some(json!({ "visibility": arguments.get("visibility") })),
+                base_metadata,
+            );
+        }
+    };
+
+    let cursor_fingerprint = page_cursor_fingerprint(
+        "locate_symbol",
+        &effective_ref,
+        &[
+            Some(name),
+            kind,
+            role,
+            language,
+            Some(if fuzzy { "fuzzy" } else { "exact" }),
+            case_sensitive.then_some("case_sensitive"),
+            (!visibility.is_any()).then_some(visibility.as_str()),
+        ],
+    );
+    let raw_cursor = arguments.get("cursor").and_then(|v| v.as_str());
+    let offset = match decode_page_cursor(raw_cursor, &cursor_fingerprint) {
+        Ok(offset) => offset,
+        Err(message) => {
+            return tool_error_response(
+                id,
+                ProtocolErrorCode::InvalidInput,
+                message,
+                Some(json!({ "cursor": raw_cursor })),
+                base_metadata,
+            );
+        }
+    };
 
     let ranking_explain_level = match resolve_ranking_explain_level(arguments, config) {
         Ok(level) => level,
@@ -292,16 +366,19 @@ pub(super) fn handle_locate_symbol(params: QueryToolParams<'_>) -> JsonRpcRespon
         });
     }
 
-    let freshness = check_and_enforce_freshness(
-        id.clone(),
-        arguments,
-        config,
-        conn,
-        workspace,
-        project_id,
-        &effective_ref,
-        schema_status,
-    );
+    let freshness = match pinned_commit.as_deref() {
+        Some(commit) => pinned_commit_freshness(&effective_ref, schema_status, commit),
+        None => check_and_enforce_freshness(
+            id.clone(),
+            arguments,
+            config,
+            conn,
+            workspace,
+            project_id,
+            &effective_ref,
+            schema_status,
+        ),
+    };
     if let Some(block) = freshness.block_response {
         return block;
     }
@@ -319,13 +396,26 @@ pub(super) fn handle_locate_symbol(params: QueryToolParams<'_>) -> JsonRpcRespon
         kind,
         role,
         language,
-        limit,
+        // One extra result tells whether another page exists.
+        limit + 1,
+        locate::LocateOptions {
+            fuzzy,
+            offset,
+            case_sensitive,
+            visibility,
+        },
     ) {
-        Ok((results, total_candidates)) => {
-            let (results, suppressed_duplicate_count) = dedup_locate_results(results);
+        Ok((mut results, total_candidates)) => {
+            let has_more = results.len() > limit;
+            results.truncate(limit);
+            let page_len = results.len();
+            let (mut results, suppressed_duplicate_count) = dedup_locate_results(results);
             if suppressed_duplicate_count > 0 {
                 metadata.suppressed_duplicate_count = Some(suppressed_duplicate_count);
             }
+            if let Some(conn) = conn {
+                locate::attach_parents(conn, &mut results);
+            }
 
             let result_values: Vec<Value> = results
                 .iter()
@@ -338,6 +428,7 @@ pub(super) fn handle_locate_symbol(params: QueryToolParams<'_>) -> JsonRpcRespon
                 result_values,
                 detail_level,
                 compact,
+                None,
                 conn,
                 project_id,
                 &effective_ref,
@@ -347,12 +438,41 @@ pub(super) fn handle_locate_symbol(params: QueryToolParams<'_>) -> JsonRpcRespon
                 metadata.result_completeness = cruxe_core::types::ResultCompleteness::Truncated;
                 metadata.safety_limit_applied = Some(true);
             }
+            metadata.next_cursor = next_page_cursor(
+                offset,
+                page_len,
+                filtered.len(),
+                has_more,
+                safety_limit_applied,
+                &cursor_fingerprint,
+            );
+
+            recent_symbols::record(
+                workspace,
+                project_id,
+                &effective_ref,
+                "locate_symbol",
+                results
+                    .iter()
+                    .take(filtered.len())
+                    .map(|result| recent_symbols::ReturnedSymbol {
+                        symbol_id: &result.symbol_id,
+                        symbol_stable_id: Some(&result.symbol_stable_id),
+                        name: &result.name,
+                        qualified_name: &result.qualified_name,
+                        kind: &result.kind,
+                        path: &result.path,
+                        line_start: result.line_start,
+                        line_end: result.line_end,
+                    }),
+            );
 
             if ranking_explain_level != cruxe_core::types::RankingExplainLevel::Off {
                 let reasons = ranking::locate_ranking_reasons_with_budget(
                     &results,
                     name,
                     &config.search.ranking_signal_budgets,
+                    case_sensitive,
                 );
                 metadata.ranking_reasons = ranking_reasons_payload(
                     reasons.into_iter().take(filtered.len()).collect(),
@@ -382,7 +502,171 @@ pub(super) fn handle_locate_symbol(params: QueryToolParams<'_>) -> JsonRpcRespon
     }
 }
 
-pub(super) fn handle_search_code(params: QueryToolParams<'_>) -> JsonRpcResponse {
+/// Ranked, deduplicated `search_code` output shared by the buffered JSON-RPC
+/// response and the NDJSON streaming transport.
+struct SearchCodeExecution<'a> {
+    id: Option<Value>,
+    query: &'a str,
+    limit: usize,
+    detail_level: DetailLevel,
+    compact: bool,
+    fields: Option<Vec<String>>,
+    ranking_explain_level: cruxe_core::types::RankingExplainLevel,
+    config: &'a Config,
+    conn: Option<&'a rusqlite::Connection>,
+    workspace: &'a Path,
+    project_id: &'a str,
+    effective_ref: String,
+    metadata: ProtocolMetadata,
+    response: search::SearchResponse,
+    results: Vec<search::SearchResult>,
+    /// Ranking reasons aligned to `results` after dedup.
+    ranking_reasons: Option<Vec<cruxe_core::types::RankingReasons>>,
+    page: SearchCodePage,
+}
+
+/// `search_code` arguments that change the ranking or the returned hits, so a
+/// cursor issued under one value must not be replayed under another. Output
+/// shaping (`detail_level`, `fields`, `compact`) leaves the page boundaries
+/// alone and is not listed.
+const SEARCH_CODE_CURSOR_OPTIONS: &[&str] = &[
+    "commit",
+    "fold_naming_conventions",
+    "prefer_language",
+    "context_path",
+    "plan",
+    "semantic_ratio",
+    "confidence_threshold",
+    "policy_mode",
+    "highlight",
+    "min_score",
+    "semantic",
+];
+
+/// Raw value of `key` for a cursor fingerprint: strings as-is, anything else
+/// as its JSON text; absent and `null` are the same.
+fn cursor_option_value(arguments: &Value, key: &str) -> Option<String> {
+    match arguments.get(key)? {
+        Value::Null => None,
+        Value::String(value) => Some(value.clone()),
+        value => Some(value.to_string()),
+    }
+}
+
+/// Position of a `search_code` page within the full ranking.
+struct SearchCodePage {
+    offset: usize,
+    /// Ranked results consumed by this page, before filtering and
+    /// deduplication.
+    page_len: usize,
+    /// Ranked position, relative to `offset`, of each entry in `results`.
+    result_positions: Vec<usize>,
+    has_more: bool,
+    cursor_fingerprint: String,
+}
+
+impl SearchCodeExecution<'_> {
+    fn serialize_results(
+        &self,
+        results: &[search::SearchResult],
+        max_response_bytes: usize,
+    ) -> FilteredResultPayload {
+        let result_values: Vec<Value> = results
+            .iter()
+            .filter_map(|r| serde_json::to_value(r).ok())
+            .collect();
+        build_filtered_result_payload(
+            result_values,
+            self.detail_level,
+            self.compact,
+            self.fields.as_deref(),
+            self.conn,
+            self.project_id,
+            &self.effective_ref,
+            max_response_bytes,
+        )
+    }
+
+    fn apply_ranking_reasons(&mut self, emitted: usize) {
+        if let Some(reasons) = self.ranking_reasons.take() {
+            self.metadata.ranking_reasons = ranking_reasons_payload(
+                reasons.into_iter().take(emitted).collect(),
+                self.ranking_explain_level,
+            );
+        }
+    }
+
+    fn apply_next_cursor(&mut self, emitted: usize, safety_limit_applied: bool) {
+        // A page cut short by the byte cap resumes at the ranked position of
+        // the first result it did not emit.
+        let resume = self
+            .page
+            .result_positions
+            .get(emitted)
+            .copied()
+            .unwrap_or(self.page.page_len);
+        self.metadata.next_cursor = next_page_cursor(
+            self.page.offset,
+            self.page.page_len,
+            resume,
+            self.page.has_more,
+            safety_limit_applied,
+            &self.page.cursor_fingerprint,
+        );
+    }
+
+    /// Add the first `emitted` symbol results to the session working set.
+    fn record_recent_symbols(&self, results: &[search::SearchResult], emitted: usize) {
+        recent_symbols::record(
+            self.workspace,
+            self.project_id,
+            &self.effective_ref,
+            "search_code",
+            results.iter().take(emitted).filter_map(|result| {
+                Some(recent_symbols::ReturnedSymbol {
+                    symbol_id: result.symbol_id.as_deref()?,
+                    symbol_stable_id: result.symbol_stable_id.as_deref(),
+                    name: result.name.as_deref()?,
+                    qualified_name: result.qualified_name.as_deref().unwrap_or_default(),
+                    kind: result.kind.as_deref().unwrap_or_default(),
+                    path: &result.path,
+                    line_start: result.line_start,
+                    line_end: result.line_end,
+                })
+            }),
+        );
+    }
+
+    fn summary_payload(&self, suggested_next_actions: Vec<search::SuggestedAction>) -> Value {
+        json!({
+            "query_intent": &self.response.query_intent,
+            "total_candidates": self.response.total_candidates,
+            "suggested_next_actions": suggested_next_actions,
+            "metadata": &self.metadata,
+        })
+    }
+}
+
+fn parse_search_kind(arguments: &Value) -> Result<Option<&'static str>, &'static str> {
+    match arguments.get("kind").and_then(|v| v.as_str()) {
+        Some(raw) => cruxe_core::types::SymbolKind::parse_kind(&raw.trim().to_ascii_lowercase())
+            .map(|kind| Some(kind.as_str()))
+            .ok_or(
+                "Parameter `kind` must be a symbol kind such as function, method, struct, class, enum, trait, interface, constant, variable, type_alias, module or field.",
+            ),
+        None => Ok(None),
+    }
+}
+
+/// `export: true` asks `search_code` for every match instead of a ranked page.
+fn parse_export(arguments: &Value) -> bool {
+    arguments
+        .get("export")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+fn execute_search_code(params: QueryToolParams<'_>) -> DispatchOutcome<SearchCodeExecution<'_>> {
     let QueryToolParams {
         id,
         arguments,
@@ -400,42 +684,153 @@ pub(super) fn handle_search_code(params: QueryToolParams<'_>) -> JsonRpcResponse
         .and_then(|v| v.as_str())
         .unwrap_or("");
     let requested_ref = arguments.get("ref").and_then(|v| v.as_str());
-    let language = arguments.get("language").and_then(|v| v.as_str());
     let role = arguments.get("role").and_then(|v| v.as_str());
     let limit = arguments
         .get("limit")
         .and_then(|v| v.as_u64())
-        .unwrap_or(10) as usize;
-    let detail_level = parse_detail_level(arguments);
+        .map_or(config.search.default_limit, |limit| limit as usize);
+    let detail_level = parse_detail_level(arguments, config);
     let compact = parse_compact(arguments);
-    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id);
+    let fields = parse_result_fields(arguments);
+    let mut effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
+    let pinned_commit = match arguments.get("commit").and_then(|v| v.as_str()) {
+        Some(commit) => match resolve_tool_commit(
+            commit,
+            requested_ref.map(|_| effective_ref.as_str()),
+            conn,
+            project_id,
+        ) {
+            Ok(state) => {
+                effective_ref = state.r#ref;
+                Some(state.last_indexed_commit)
+            }
+            Err(message) => {
+                return DispatchOutcome::Response(tool_error_response(
+                    id,
+                    ProtocolErrorCode::InvalidInput,
+                    message,
+                    Some(json!({ "commit": commit })),
+                    validation_metadata(&effective_ref, schema_status),
+                ));
+            }
+        },
+        None => None,
+    };
     let base_metadata = validation_metadata(&effective_ref, schema_status);
 
     if query.trim().is_empty() {
-        return tool_error_response(
+        return DispatchOutcome::Response(tool_error_response(
             id,
             ProtocolErrorCode::InvalidInput,
             "Parameter `query` is required.",
             None,
             base_metadata,
-        );
+        ));
     }
+    let languages = match parse_language_filter(arguments) {
+        Ok(languages) => languages,
+        Err(message) => {
+            return DispatchOutcome::Response(tool_error_response(
+                id,
+                ProtocolErrorCode::InvalidInput,
+                message,
+                Some(json!({ "language": arguments.get("language") })),
+                base_metadata,
+            ));
+        }
+    };
+    // The first language travels as the plain filter so a single-language call
+    // (and its cursor) is unchanged; the rest are OR-ed in by the search.
+    let (language, extra_languages) = match languages.split_first() {
+        Some((first, rest)) => (Some(first.as_str()), rest.to_vec()),
+        None => (None, Vec::new()),
+    };
+    let language_key = languages.join(",");
+    let kind = match parse_search_kind(arguments) {
+        Ok(kind) => kind,
+        Err(message) => {
+            return DispatchOutcome::Response(tool_error_response(
+                id,
+                ProtocolErrorCode::InvalidInput,
+                message,
+                Some(json!({ "kind": arguments.get("kind") })),
+                base_metadata,
+            ));
+        }
+    };
+    let visibility = match parse_visibility_filter(arguments) {
+        Ok(visibility) => visibility,
+        Err(message) => {
+            return DispatchOutcome::Response(tool_error_response(
+                id,
+                ProtocolErrorCode::InvalidInput,
+                message,
+                Some(json!({ "visibility": arguments.get("visibility") })),
+                base_metadata,
+            ));
+        }
+    };
+    let intent_override = match parse_intent_override(arguments) {
+        Ok(intent) => intent,
+        Err(message) => {
+            return DispatchOutcome::Response(tool_error_response(
+                id,
+                ProtocolErrorCode::InvalidInput,
+                message,
+                Some(json!({ "intent": arguments.get("intent") })),
+                base_metadata,
+            ));
+        }
+    };
+
+    let ranking_options: Vec<Option<String>> = SEARCH_CODE_CURSOR_OPTIONS
+        .iter()
+        .map(|key| cursor_option_value(arguments, key))
+        .collect();
+    let cursor_fingerprint = page_cursor_fingerprint(
+        "search_code",
+        &effective_ref,
+        &[
+            Some(query),
+            (!languages.is_empty()).then_some(language_key.as_str()),
+            role,
+            kind,
+            (!visibility.is_any()).then_some(visibility.as_str()),
+            intent_override.map(|intent| intent.as_str()),
+        ]
+        .into_iter()
+        .chain(ranking_options.iter().map(Option::as_deref))
+        .collect::<Vec<_>>(),
+    );
+    let raw_cursor = arguments.get("cursor").and_then(|v| v.as_str());
+    let offset = match decode_page_cursor(raw_cursor, &cursor_fingerprint) {
+        Ok(offset) => offset,
+        Err(message) => {
+            return DispatchOutcome::Response(tool_error_response(
+                id,
+                ProtocolErrorCode::InvalidInput,
+                message,
+                Some(json!({ "cursor": raw_cursor })),
+                base_metadata,
+            ));
+        }
+    };
 
     let ranking_explain_level = match resolve_ranking_explain_level(arguments, config) {
         Ok(level) => level,
         Err(message) => {
-            return tool_error_response(
+            return DispatchOutcome::Response(tool_error_response(
                 id,
                 ProtocolErrorCode::InvalidInput,
                 message,
                 None,
                 base_metadata,
-            );
+            ));
         }
     };
 
     let Some(index_set) = index_set else {
-        return tool_compatibility_error(ToolCompatibilityParams {
+        return DispatchOutcome::Response(tool_compatibility_error(ToolCompatibilityParams {
             id,
             schema_status,
             compatibility_reason,
@@ -444,11 +839,11 @@ pub(super) fn handle_search_code(params: QueryToolParams<'_>) -> JsonRpcResponse
             workspace,
             project_id,
             ref_name: &effective_ref,
-        });
+        }));
     };
 
     if schema_status != SchemaStatus::Compatible {
-        return tool_compatibility_error(ToolCompatibilityParams {
+        return DispatchOutcome::Response(tool_compatibility_error(ToolCompatibilityParams {
             id,
             schema_status,
             compatibility_reason,
@@ -457,21 +852,24 @@ pub(super) fn handle_search_code(params: QueryToolParams<'_>) -> JsonRpcResponse
             workspace,
             project_id,
             ref_name: &effective_ref,
-        });
+        }));
     }
 
-    let freshness = check_and_enforce_freshness(
-        id.clone(),
-        arguments,
-        config,
-        conn,
-        workspace,
-        project_id,
-        &effective_ref,
-        schema_status,
-    );
+    let freshness = match pinned_commit.as_deref() {
+        Some(commit) => pinned_commit_freshness(&effective_ref, schema_status, commit),
+        None => check_and_enforce_freshness(
+            id.clone(),
+            arguments,
+            config,
+            conn,
+            workspace,
+            project_id,
+            &effective_ref,
+            schema_status,
+        ),
+    };
     if let Some(block) = freshness.block_response {
-        return block;
+        return DispatchOutcome::Response(block);
     }
     let mut metadata = freshness.metadata;
 
@@ -483,13 +881,13 @@ pub(super) fn handle_search_code(params: QueryToolParams<'_>) -> JsonRpcResponse
     let semantic_ratio_override = match arguments.get("semantic_ratio").and_then(|v| v.as_f64()) {
         Some(value) if value.is_finite() && (0.0..=1.0).contains(&value) => Some(value),
         Some(_) => {
-            return tool_error_response(
+            return DispatchOutcome::Response(tool_error_response(
                 id,
                 ProtocolErrorCode::InvalidInput,
                 "Parameter `semantic_ratio` must be a number between 0.0 and 1.0.",
                 None,
                 metadata,
-            );
+            ));
         }
         None => None,
     };
@@ -499,16 +897,43 @@ pub(super) fn handle_search_code(params: QueryToolParams<'_>) -> JsonRpcResponse
     {
         Some(value) if value.is_finite() && (0.0..=1.0).contains(&value) => Some(value),
         Some(_) => {
-            return tool_error_response(
+            return DispatchOutcome::Response(tool_error_response(
                 id,
                 ProtocolErrorCode::InvalidInput,
                 "Parameter `confidence_threshold` must be a number between 0.0 and 1.0.",
                 None,
                 metadata,
-            );
+            ));
         }
         None => None,
     };
+    let min_score = match arguments.get("min_score") {
+        None | Some(Value::Null) => None,
+        Some(value) => match value.as_f64().filter(|score| score.is_finite()) {
+            Some(score) => Some(score),
+            None => {
+                return DispatchOutcome::Response(tool_error_response(
+                    id,
+                    ProtocolErrorCode::InvalidInput,
+                    "Parameter `min_score` must be a finite number.",
+                    Some(json!({ "min_score": value })),
+                    metadata,
+                ));
+            }
+        },
+    };
+    let semantic = arguments.get("semantic").and_then(|v| v.as_bool());
+    if semantic == Some(true)
+        && config.search.semantic_mode_typed() != cruxe_core::types::SemanticMode::Hybrid
+    {
+        return DispatchOutcome::Response(tool_error_response(
+            id,
+            ProtocolErrorCode::InvalidInput,
+            "Semantic search is disabled: set `search.semantic.mode = \"hybrid\"` and configure an embedding provider to use `semantic: true`.",
+            Some(json!({ "semantic_mode": config.search.semantic.mode })),
+            metadata,
+        ));
+    }
     let plan_override = match arguments.get("plan").and_then(|v| v.as_str()) {
         Some(raw) => {
             let normalized = raw.trim().to_ascii_lowercase();
@@ -517,22 +942,22 @@ pub(super) fn handle_search_code(params: QueryToolParams<'_>) -> JsonRpcResponse
                 "lexical_fast" | "hybrid_standard" | "semantic_deep"
             );
             if !valid {
-                return tool_error_response(
+                return DispatchOutcome::Response(tool_error_response(
                     id,
                     ProtocolErrorCode::InvalidInput,
                     "Parameter `plan` must be one of: lexical_fast, hybrid_standard, semantic_deep.",
                     Some(json!({ "plan": raw })),
                     metadata,
-                );
+                ));
             }
             if !config.search.adaptive_plan.allow_override {
-                return tool_error_response(
+                return DispatchOutcome::Response(tool_error_response(
                     id,
                     ProtocolErrorCode::InvalidInput,
                     "Plan override is disabled by configuration (`search.adaptive_plan.allow_override=false`).",
                     Some(json!({ "plan": raw })),
                     metadata,
-                );
+                ));
             }
             Some(normalized)
         }
@@ -542,27 +967,59 @@ pub(super) fn handle_search_code(params: QueryToolParams<'_>) -> JsonRpcResponse
         Some(raw) => match raw.parse::<PolicyMode>() {
             Ok(mode) => Some(mode),
             Err(_) => {
-                return tool_error_response(
+                return DispatchOutcome::Response(tool_error_response(
                     id,
                     ProtocolErrorCode::InvalidInput,
                     "Parameter `policy_mode` must be one of: strict, balanced, off, audit_only.",
                     None,
                     metadata,
-                );
+                ));
             }
         },
         None => None,
     };
+    let mut search_config = config.search.clone();
+    if let Some(fold) = arguments
+        .get("fold_naming_conventions")
+        .and_then(|v| v.as_bool())
+    {
+        search_config.fold_naming_conventions = fold;
+    }
+    let preferred_language = arguments
+        .get("prefer_language")
+        .and_then(|v| v.as_str())
+        .map(|raw| raw.trim().to_ascii_lowercase())
+        .filter(|language| !language.is_empty())
+        .or_else(|| {
+            arguments
+                .get("context_path")
+                .and_then(|v| v.as_str())
+                .and_then(|path| std::path::Path::new(path).extension()?.to_str())
+                .and_then(|ext| detect_language_from_extension(&ext.to_ascii_lowercase()))
+                .map(str::to_string)
+        });
+    let highlight = arguments
+        .get("highlight")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
     let search_options = search::SearchExecutionOptions {
-        search_config: config.search.clone(),
+        search_config,
         semantic_ratio_override,
         confidence_threshold_override,
         role: role.map(ToString::to_string),
         plan_override,
         policy_mode_override,
         policy_runtime: None,
+        preferred_language,
+        languages: extra_languages,
+        semantic,
+        kind: kind.map(str::to_string),
+        offset,
+        highlight,
+        visibility,
+        intent: intent_override,
     };
-    match execute_search_with_optional_overlay(
+    let mut response = match execute_search_with_optional_overlay(
         QueryExecutionContext {
             index_set,
             conn,
@@ -572,136 +1029,455 @@ pub(super) fn handle_search_code(params: QueryToolParams<'_>) -> JsonRpcResponse
         },
         query,
         language,
-        limit,
+        // One extra result tells whether another page exists.
+        limit + 1,
         debug_ranking,
         search_options,
     ) {
-        Ok(response) => {
-            let mut response = response;
-            let ranking_reasons = response.ranking_reasons.take();
-            let (results, kept_reason_indices, suppressed_duplicate_count) =
-                dedup_search_results(std::mem::take(&mut response.results));
-            if suppressed_duplicate_count > 0 {
-                metadata.suppressed_duplicate_count = Some(suppressed_duplicate_count);
-            }
-
-            let result_values: Vec<Value> = results
-                .iter()
-                .filter_map(|r| serde_json::to_value(r).ok())
-                .collect();
-            let FilteredResultPayload {
-                filtered,
-                safety_limit_applied,
-            } = build_filtered_result_payload(
-                result_values,
-                detail_level,
-                compact,
-                conn,
-                project_id,
-                &effective_ref,
-                config.search.max_response_bytes,
-            );
-            if safety_limit_applied {
-                metadata.result_completeness = cruxe_core::types::ResultCompleteness::Truncated;
-                metadata.safety_limit_applied = Some(true);
-            }
-
-            if let Some(reasons) = ranking_reasons.as_ref() {
-                let aligned_reasons = align_ranking_reasons_to_dedup(reasons, &kept_reason_indices);
-                metadata.ranking_reasons = ranking_reasons_payload(
-                    aligned_reasons.into_iter().take(filtered.len()).collect(),
-                    ranking_explain_level,
-                );
-            }
-
-            metadata.semantic_mode = Some(response.metadata.semantic_mode.clone());
-            metadata.policy_mode = Some(response.metadata.policy_mode.clone());
-            metadata.policy_blocked_count = Some(response.metadata.policy_blocked_count);
-            metadata.policy_redacted_count = Some(response.metadata.policy_redacted_count);
-            if !response.metadata.policy_warnings.is_empty() {
-                metadata.policy_warnings = Some(response.metadata.policy_warnings.clone());
-            }
-            if !response.metadata.policy_audit_counts.is_empty() {
-                metadata.policy_audit_counts =
-                    serde_json::to_value(&response.metadata.policy_audit_counts).ok();
-            }
-            if !response.metadata.policy_redaction_categories.is_empty() {
-                metadata.policy_redaction_categories =
-                    serde_json::to_value(&response.metadata.policy_redaction_categories).ok();
-            }
-            metadata.semantic_enabled = Some(response.metadata.semantic_enabled);
-            metadata.semantic_ratio_used = Some(response.metadata.semantic_ratio_used);
-            metadata.semantic_triggered = Some(response.metadata.semantic_triggered);
-            metadata.semantic_skipped_reason = response.metadata.semantic_skipped_reason.clone();
-            metadata.semantic_fallback = Some(response.metadata.semantic_fallback);
-            metadata.semantic_degraded = Some(response.metadata.semantic_degraded);
-            metadata.semantic_limit_used = Some(response.metadata.semantic_limit_used);
-            metadata.lexical_fanout_used = Some(response.metadata.lexical_fanout_used);
-            metadata.semantic_fanout_used = Some(response.metadata.semantic_fanout_used);
-            metadata.semantic_budget_exhausted = Some(response.metadata.semantic_budget_exhausted);
-            metadata.external_provider_blocked = Some(response.metadata.external_provider_blocked);
-            metadata.embedding_model_version =
-                Some(response.metadata.embedding_model_version.clone());
-            metadata.rerank_provider = Some(response.metadata.rerank_provider.clone());
-            metadata.rerank_fallback = Some(response.metadata.rerank_fallback);
-            metadata.rerank_fallback_reason = response.metadata.rerank_fallback_reason.clone();
-            metadata.low_confidence = Some(response.metadata.low_confidence);
-            metadata.suggested_action = response.metadata.suggested_action.clone();
-            metadata.confidence_threshold = Some(response.metadata.confidence_threshold);
-            metadata.top_score = Some(response.metadata.top_score);
-            metadata.score_margin = Some(response.metadata.score_margin);
-            metadata.channel_agreement = Some(response.metadata.channel_agreement);
-            metadata.query_intent_confidence = Some(response.metadata.query_intent_confidence);
-            metadata.intent_escalation_hint = response.metadata.intent_escalation_hint.clone();
-            metadata.query_plan_selected = Some(response.metadata.query_plan_selected.clone());
-            metadata.query_plan_executed = Some(response.metadata.query_plan_executed.clone());
-            metadata.query_plan_selection_reason =
-                Some(response.metadata.query_plan_selection_reason.clone());
-            metadata.query_plan_downgraded = Some(response.metadata.query_plan_downgraded);
-            metadata.query_plan_downgrade_reason =
-                response.metadata.query_plan_downgrade_reason.clone();
-            metadata.query_plan_budget_used =
-                serde_json::to_value(&response.metadata.query_plan_budget_used).ok();
-            if !response.metadata.warnings.is_empty() {
-                let mut warnings = metadata.warnings.take().unwrap_or_default();
-                warnings.extend(response.metadata.warnings.clone());
-                metadata.warnings = Some(warnings);
-            }
-
-            let suggested_next_actions = if safety_limit_applied {
-                deterministic_suggested_actions(
-                    &response.suggested_next_actions,
-                    query,
-                    &effective_ref,
-                    limit,
-                )
-            } else {
-                response.suggested_next_actions.clone()
-            };
-
-            let mut result = json!({
-                "results": filtered,
-                "query_intent": &response.query_intent,
-                "total_candidates": response.total_candidates,
-                "suggested_next_actions": suggested_next_actions,
-                "metadata": metadata,
-            });
-            if let Some(debug_payload) = &response.debug
-                && let Ok(value) = serde_json::to_value(debug_payload)
-            {
-                result["debug"] = value;
-            }
-            tool_text_response(id, result)
-        }
+        Ok(response) => response,
         Err(e) => {
             let (code, message, data) = map_state_error(&e);
-            tool_error_response(id, code, message, data, metadata)
+            return DispatchOutcome::Response(tool_error_response(
+                id, code, message, data, metadata,
+            ));
         }
-    }
-}
+    };
 
-pub(super) fn handle_diff_context(params: QueryToolParams<'_>) -> JsonRpcResponse {
-    let QueryToolParams {
+    // `min_score` filters the whole look-ahead window before it is cut to a
+    // page, so `has_more` reflects what is actually left to show and does not
+    // rely on scores staying sorted after reranking or diversification.
+    let fetched = std::mem::take(&mut response.results);
+    let fetched_len = fetched.len();
+    let fetched_reasons = response.ranking_reasons.take();
+    let (mut window, mut window_positions, window_reasons) = match min_score {
+        Some(min_score) => {
+            let (kept, kept_indices, _) = filter_search_results_by_min_score(fetched, min_score);
+            let reasons = fetched_reasons
+                .map(|reasons| align_ranking_reasons_to_dedup(&reasons, &kept_indices));
+            (kept, kept_indices, reasons)
+        }
+        None => (
+            fetched,
+            (0..fetched_len).collect::<Vec<_>>(),
+            fetched_reasons,
+        ),
+    };
+    let has_more = window.len() > limit;
+    // Ranked positions consumed by this page; the next page resumes at the
+    // first hit that was not shown.
+    let page_len = if has_more {
+        window_positions[limit]
+    } else {
+        fetched_len
+    };
+    window.truncate(limit);
+    window_positions.truncate(limit);
+    if min_score.is_some() {
+        metadata.min_score_dropped_count = Some(page_len - window.len());
+    }
+    let ranking_reasons = window_reasons.map(|mut reasons| {
+        reasons.truncate(limit);
+        reasons
+    });
+    let (results, kept_reason_indices, suppressed_duplicate_count) = dedup_search_results(window);
+    if suppressed_duplicate_count > 0 {
+        metadata.suppressed_duplicate_count = Some(suppressed_duplicate_count);
+    }
+    let result_positions = kept_reason_indices
+        .iter()
+        .map(|&index| window_positions[index])
+        .collect();
+    let ranking_reasons = ranking_reasons
+        .map(|reasons| align_ranking_reasons_to_dedup(&reasons, &kept_reason_indices));
+    copy_search_metadata(&mut metadata, &response.metadata);
+
+    DispatchOutcome::Continue(SearchCodeExecution {
+        id,
+        query,
+        limit,
+        detail_level,
+        compact,
+        fields,
+        ranking_explain_level,
+        config,
+        conn,
+        workspace,
+        project_id,
+        effective_ref,
+        metadata,
+        response,
+        results,
+        ranking_reasons,
+        page: SearchCodePage {
+            offset,
+            page_len,
+            result_positions,
+            has_more,
+            cursor_fingerprint,
+        },
+    })
+}
+
+fn copy_search_metadata(metadata: &mut ProtocolMetadata, search: &search::SearchMetadata) {
+    metadata.semantic_mode = Some(search.semantic_mode.clone());
+    metadata.policy_mode = Some(search.policy_mode.clone());
+    metadata.policy_blocked_count = Some(search.policy_blocked_count);
+    metadata.policy_redacted_count = Some(search.policy_redacted_count);
+    if !search.policy_warnings.is_empty() {
+        metadata.policy_warnings = Some(search.policy_warnings.clone());
+    }
+    if !search.policy_audit_counts.is_empty() {
+        metadata.policy_audit_counts = serde_json::to_value(&search.policy_audit_counts).ok();
+    }
+    if !search.policy_redaction_categories.is_empty() {
+        metadata.policy_redaction_categories =
+            serde_json::to_value(&search.policy_redaction_categories).ok();
+    }
+    metadata.semantic_enabled = Some(search.semantic_enabled);
+    metadata.semantic_ratio_used = Some(search.semantic_ratio_used);
+    metadata.semantic_triggered = Some(search.semantic_triggered);
+    metadata.semantic_skipped_reason = search.semantic_skipped_reason.clone();
+    metadata.semantic_fallback = Some(search.semantic_fallback);
+    metadata.semantic_degraded = Some(search.semantic_degraded);
+    metadata.semantic_limit_used = Some(search.semantic_limit_used);
+    metadata.lexical_fanout_used = Some(search.lexical_fanout_used);
+    metadata.semantic_fanout_used = Some(search.semantic_fanout_used);
+    metadata.semantic_budget_exhausted = Some(search.semantic_budget_exhausted);
+    metadata.external_provider_blocked = Some(search.external_provider_blocked);
+    metadata.embedding_model_version = Some(search.embedding_model_version.clone());
+    metadata.rerank_provider = Some(search.rerank_provider.clone());
+    metadata.rerank_fallback = Some(search.rerank_fallback);
+    metadata.rerank_fallback_reason = search.rerank_fallback_reason.clone();
+    metadata.low_confidence = Some(search.low_confidence);
+    metadata.suggested_action = search.suggested_action.clone();
+    metadata.confidence_threshold = Some(search.confidence_threshold);
+    metadata.top_score = Some(search.top_score);
+    metadata.score_margin = Some(search.score_margin);
+    metadata.channel_agreement = Some(search.channel_agreement);
+    metadata.query_intent_confidence = Some(search.query_intent_confidence);
+    metadata.intent_escalation_hint = search.intent_escalation_hint.clone();
+    metadata.query_plan_selected = Some(search.query_plan_selected.clone());
+    metadata.query_plan_executed = Some(search.query_plan_executed.clone());
+    metadata.query_plan_selection_reason = Some(search.query_plan_selection_reason.clone());
+    metadata.query_plan_downgraded = Some(search.query_plan_downgraded);
+    metadata.query_plan_downgrade_reason = search.query_plan_downgrade_reason.clone();
+    metadata.query_plan_budget_used = serde_json::to_value(&search.query_plan_budget_used).ok();
+    if !search.warnings.is_empty() {
+        let mut warnings = metadata.warnings.take().unwrap_or_default();
+        warnings.extend(search.warnings.clone());
+        metadata.warnings = Some(warnings);
+    }
+}
+
+pub(super) fn handle_search_code(params: QueryToolParams<'_>) -> JsonRpcResponse {
+    if parse_export(params.arguments) {
+        let requested_ref = params.arguments.get("ref").and_then(|v| v.as_str());
+        let effective_ref = resolve_tool_ref(
+            requested_ref,
+            params.workspace,
+            params.conn,
+            params.project_id,
+            params.config,
+        );
+        return tool_error_response(
+            params.id,
+            ProtocolErrorCode::InvalidInput,
+            "Parameter `export` is only supported when streaming over HTTP with `Accept: application/x-ndjson`.",
+            Some(json!({ "export": true })),
+            validation_metadata(&effective_ref, params.schema_status),
+        );
+    }
+    let mut execution = match execute_search_code(params) {
+        DispatchOutcome::Continue(execution) => execution,
+        DispatchOutcome::Response(response) => return response,
+    };
+
+    let FilteredResultPayload {
+        filtered,
+        safety_limit_applied,
+    } = execution.serialize_results(
+        &execution.results,
+        execution.config.search.max_response_bytes,
+    );
+    if safety_limit_applied {
+        execution.metadata.result_completeness = cruxe_core::types::ResultCompleteness::Truncated;
+        execution.metadata.safety_limit_applied = Some(true);
+    }
+    execution.apply_ranking_reasons(filtered.len());
+    execution.apply_next_cursor(filtered.len(), safety_limit_applied);
+    execution.record_recent_symbols(&execution.results, filtered.len());
+
+    let suggested_next_actions = if safety_limit_applied {
+        deterministic_suggested_actions(
+            &execution.response.suggested_next_actions,
+            execution.query,
+            &execution.effective_ref,
+            execution.limit,
+        )
+    } else {
+        execution.response.suggested_next_actions.clone()
+    };
+
+    let mut result = execution.summary_payload(suggested_next_actions);
+    result["results"] = Value::Array(filtered);
+    if let Some(debug_payload) = &execution.response.debug
+        && let Ok(value) = serde_json::to_value(debug_payload)
+    {
+        result["debug"] = value;
+    }
+    tool_text_response(execution.id, result)
+}
+
+/// NDJSON rendering of a ranked `search_code` page.
+///
+/// This is not incremental: the page is searched, ranked and deduplicated in
+/// full exactly as for the JSON response, so peak memory and time to the first
+/// line match the buffered path. Only serialization, detail-level enrichment
+/// and output are done one result at a time; `export: true` is the mode whose
+/// output starts while the index is still being read. Each hit is emitted as
+/// `{"type":"result","result":{..}}`, followed by a single
+/// `{"type":"summary",..}` line carrying the response's remaining fields.
+/// `max_response_bytes` is not applied to the emitted lines. `emit` returns
+/// `false` when the consumer has gone away, which stops the output early.
+/// Validation and search failures return the regular tool response so the
+/// transport can render it as an error line.
+pub(super) fn stream_search_code(
+    params: QueryToolParams<'_>,
+    emit: &mut dyn FnMut(Value) -> bool,
+) -> Option<JsonRpcResponse> {
+    if parse_export(params.arguments) {
+        return stream_search_code_export(params, emit);
+    }
+    let mut execution = match execute_search_code(params) {
+        DispatchOutcome::Continue(execution) => execution,
+        DispatchOutcome::Response(response) => return Some(response),
+    };
+
+    let results = std::mem::take(&mut execution.results);
+    let mut emitted = 0usize;
+    for result in results.iter() {
+        let FilteredResultPayload { filtered, .. } =
+            execution.serialize_results(std::slice::from_ref(result), usize::MAX);
+        for value in filtered {
+            if !emit(json!({ "type": "result", "result": value })) {
+                return None;
+            }
+            emitted += 1;
+        }
+    }
+    execution.apply_ranking_reasons(emitted);
+    execution.apply_next_cursor(emitted, false);
+    execution.record_recent_symbols(&results, emitted);
+
+    let mut summary = execution.summary_payload(execution.response.suggested_next_actions.clone());
+    summary["type"] = json!("summary");
+    if let Some(debug_payload) = &execution.response.debug
+        && let Ok(value) = serde_json::to_value(debug_payload)
+    {
+        summary["debug"] = value;
+    }
+    emit(summary);
+    None
+}
+
+/// `search_code` with `export: true`: every lexical match, unranked and
+/// uncapped, emitted as `{"type":"result",..}` lines while the index is
+/// walked, then one `{"type":"summary",..}` line with per-type counts.
+/// Ranking, paging and semantic options are ignored; the filters, detail
+/// level, field selection and policy redaction still apply.
+fn stream_search_code_export(
+    params: QueryToolParams<'_>,
+    emit: &mut dyn FnMut(Value) -> bool,
+) -> Option<JsonRpcResponse> {
+    let QueryToolParams {
+        id,
+        arguments,
+        config,
+        index_set,
+        schema_status,
+        compatibility_reason,
+        conn,
+        workspace,
+        project_id,
+    } = params;
+
+    let query = arguments
+        .get("query")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    let requested_ref = arguments.get("ref").and_then(|v| v.as_str());
+    let role = arguments.get("role").and_then(|v| v.as_str());
+    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
+    let base_metadata = validation_metadata(&effective_ref, schema_status);
+    let invalid = |message: &str, data: Option<Value>, metadata: ProtocolMetadata| {
+        Some(tool_error_response(
+            id.clone(),
+            ProtocolErrorCode::InvalidInput,
+            message,
+            data,
+            metadata,
+        ))
+    };
+
+    if query.trim().is_empty() {
+        return invalid("Parameter `query` is required.", None, base_metadata);
+    }
+    let languages = match parse_language_filter(arguments) {
+        Ok(languages) => languages,
+        Err(message) => {
+            return invalid(
+                message,
+                Some(json!({ "language": arguments.get("language") })),
+                base_metadata,
+            );
+        }
+    };
+    let kind = match parse_search_kind(arguments) {
+        Ok(kind) => kind,
+        Err(message) => {
+            return invalid(
+                message,
+                Some(json!({ "kind": arguments.get("kind") })),
+                base_metadata,
+            );
+        }
+    };
+    let visibility = match parse_visibility_filter(arguments) {
+        Ok(visibility) => visibility,
+        Err(message) => {
+            return invalid(
+                message,
+                Some(json!({ "visibility": arguments.get("visibility") })),
+                base_metadata,
+            );
+        }
+    };
+    let policy_mode_override = match arguments.get("policy_mode").and_then(|v| v.as_str()) {
+        Some(raw) => match raw.parse::<PolicyMode>() {
+            Ok(mode) => Some(mode),
+            Err(_) => {
+                return invalid(
+                    "Parameter `policy_mode` must be one of: strict, balanced, off, audit_only.",
+                    None,
+                    base_metadata,
+                );
+            }
+        },
+        None => None,
+    };
+
+    let Some(index_set) = index_set.filter(|_| schema_status == SchemaStatus::Compatible) else {
+        return Some(tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        }));
+    };
+    let freshness = check_and_enforce_freshness(
+        id.clone(),
+        arguments,
+        config,
+        conn,
+        workspace,
+        project_id,
+        &effective_ref,
+        schema_status,
+    );
+    if let Some(block) = freshness.block_response {
+        return Some(block);
+    }
+    let metadata = freshness.metadata;
+
+    let policy = match cruxe_query::policy::PolicyRuntime::from_search_config(
+        &config.search,
+        policy_mode_override,
+    ) {
+        Ok(policy) => policy,
+        Err(err) => {
+            let (code, message, data) = map_state_error(&err);
+            return Some(tool_error_response(id, code, message, data, metadata));
+        }
+    };
+    let language_refs: Vec<&str> = languages.iter().map(String::as_str).collect();
+    let scope = search::SearchExportScope {
+        languages: &language_refs,
+        kind,
+        role,
+        visibility,
+    };
+    let detail_level = parse_detail_level(arguments, config);
+    let compact = parse_compact(arguments);
+    let fields = parse_result_fields(arguments);
+    let mut emit_result = |result: search::SearchResult| {
+        let Ok(value) = serde_json::to_value(&result) else {
+            return true;
+        };
+        let FilteredResultPayload { filtered, .. } = build_filtered_result_payload(
+            vec![value],
+            detail_level,
+            compact,
+            fields.as_deref(),
+            conn,
+            project_id,
+            &effective_ref,
+            usize::MAX,
+        );
+        filtered
+            .into_iter()
+            .all(|value| emit(json!({ "type": "result", "result": value })))
+    };
+
+    let exported = match resolve_vcs_overlay_context(conn, config, project_id, &effective_ref) {
+        Ok(Some(vcs)) => search::export_search_results_vcs_merged(
+            search::VcsSearchContext {
+                base_index_set: index_set,
+                overlay_index_set: &vcs.overlay_index_set,
+                tombstones: &vcs.tombstones,
+                base_ref: &vcs.default_ref,
+                target_ref: &effective_ref,
+            },
+            conn,
+            query,
+            scope,
+            &policy,
+            &mut emit_result,
+        ),
+        Ok(None) => search::export_search_results(
+            index_set,
+            conn,
+            query,
+            &effective_ref,
+            scope,
+            &policy,
+            &mut emit_result,
+        ),
+        Err(err) => Err(err),
+    };
+    let summary = match exported {
+        Ok(summary) if summary.complete => summary,
+        Ok(_) => return None,
+        Err(err) => {
+            let (code, message, data) = map_state_error(&err);
+            return Some(tool_error_response(id, code, message, data, metadata));
+        }
+    };
+
+    emit(json!({
+        "type": "summary",
+        "total_results": summary.total(),
+        "export": summary,
+        "metadata": metadata,
+    }));
+    None
+}
+
+pub(super) fn handle_diff_context(params: QueryToolParams<'_>) -> JsonRpcResponse {
+    let QueryToolParams {
         id,
         arguments,
         config,
@@ -718,7 +1494,7 @@ pub(super) fn handle_diff_context(params: QueryToolParams<'_>) -> JsonRpcRespons
         .and_then(|value| value.as_str())
         .filter(|value| !value.trim().is_empty())
         .map(ToString::to_string)
-        .unwrap_or_else(|| resolve_tool_ref(None, workspace, conn, project_id));
+        .unwrap_or_else(|| resolve_tool_ref(None, workspace, conn, project_id, config));
     let base_ref = arguments
         .get("base_ref")
         .and_then(|value| value.as_str())
@@ -800,7 +1576,7 @@ pub(super) fn handle_diff_context(params: QueryToolParams<'_>) -> JsonRpcRespons
     }
 }
 
-pub(super) fn handle_find_references(params: QueryToolParams<'_>) -> JsonRpcResponse {
+pub(super) fn handle_diff_refs(params: QueryToolParams<'_>) -> JsonRpcResponse {
     let QueryToolParams {
         id,
         arguments,
@@ -813,30 +1589,42 @@ pub(super) fn handle_find_references(params: QueryToolParams<'_>) -> JsonRpcResp
         ..
     } = params;
 
-    let symbol_name = arguments
-        .get("symbol_name")
+    let head_ref = arguments
+        .get("head_ref")
         .and_then(|value| value.as_str())
-        .unwrap_or("");
-    let kind = arguments.get("kind").and_then(|value| value.as_str());
-    let requested_ref = arguments.get("ref").and_then(|value| value.as_str());
+        .filter(|value| !value.trim().is_empty())
+        .map(ToString::to_string)
+        .unwrap_or_else(|| resolve_tool_ref(None, workspace, conn, project_id, config));
+    let base_ref = arguments
+        .get("base_ref")
+        .and_then(|value| value.as_str())
+        .filter(|value| !value.trim().is_empty())
+        .map(ToString::to_string)
+        .unwrap_or_else(|| {
+            project_default_ref(conn, project_id).unwrap_or_else(|| "main".to_string())
+        });
+    let path_filter = arguments
+        .get("path_filter")
+        .and_then(|value| value.as_str())
+        .filter(|value| !value.trim().is_empty());
     let limit = arguments
         .get("limit")
         .and_then(|value| value.as_u64())
-        .unwrap_or(20) as usize;
-    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id);
+        .unwrap_or(200) as usize;
 
-    let base_metadata = validation_metadata(&effective_ref, schema_status);
-    if symbol_name.trim().is_empty() {
-        return tool_error_response(
+    if schema_status != SchemaStatus::Compatible {
+        return tool_compatibility_error(ToolCompatibilityParams {
             id,
-            ProtocolErrorCode::InvalidInput,
-            "Parameter `symbol_name` is required.",
-            None,
-            base_metadata,
-        );
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &head_ref,
+        });
     }
-
-    if schema_status != SchemaStatus::Compatible {
+    let Some(c) = conn else {
         return tool_compatibility_error(ToolCompatibilityParams {
             id,
             schema_status,
@@ -845,21 +1633,515 @@ pub(super) fn handle_find_references(params: QueryToolParams<'_>) -> JsonRpcResp
             conn,
             workspace,
             project_id,
-            ref_name: &effective_ref,
+            ref_name: &head_ref,
         });
+    };
+
+    let freshness = check_and_enforce_freshness(
+        id.clone(),
+        arguments,
+        config,
+        conn,
+        workspace,
+        project_id,
+        &head_ref,
+        schema_status,
+    );
+    if let Some(block) = freshness.block_response {
+        return block;
+    }
+    let metadata = freshness.metadata;
+
+    match ref_diff::diff_refs(c, project_id, &base_ref, &head_ref, path_filter, limit) {
+        Ok(result) => {
+            let mut payload = serde_json::to_value(result)
+                .unwrap_or_else(|_| json!({"error": "failed to serialize diff_refs payload"}));
+            if let Value::Object(object) = &mut payload {
+                object.insert("metadata".to_string(), json!(metadata));
+            }
+            tool_text_response(id, payload)
+        }
+        Err(ref_diff::RefDiffError::RefsNotIndexed { refs }) => tool_error_response(
+            id,
+            ProtocolErrorCode::RefNotIndexed,
+            format!("Both refs must be indexed; missing: {}.", refs.join(", ")),
+            Some(json!({
+                "missing_refs": refs,
+                "remediation": "Run sync_repo for each missing ref before comparing.",
+            })),
+            metadata,
+        ),
+        Err(ref_diff::RefDiffError::State(err)) => {
+            let (code, message, data) = map_state_error(&err);
+            tool_error_response(id, code, message, data, metadata)
+        }
+    }
+}
+
+pub(super) fn handle_find_references(params: QueryToolParams<'_>) -> JsonRpcResponse {
+    let QueryToolParams {
+        id,
+        arguments,
+        config,
+        schema_status,
+        compatibility_reason,
+        conn,
+        workspace,
+        project_id,
+        ..
+    } = params;
+
+    // A stable id resolves through the same lookup as a name.
+    let symbol_name = arguments
+        .get("symbol_name")
+        .and_then(|value| value.as_str())
+        .filter(|value| !value.trim().is_empty())
+        .or_else(|| {
+            arguments
+                .get("symbol_stable_id")
+                .and_then(|value| value.as_str())
+        })
+        .unwrap_or("");
+    let kind = arguments.get("kind").and_then(|value| value.as_str());
+    let include_name_matches = arguments
+        .get("include_name_matches")
+        .and_then(|value| value.as_bool())
+        .unwrap_or(false);
+    let requested_ref = arguments.get("ref").and_then(|value| value.as_str());
+    let limit = arguments
+        .get("limit")
+        .and_then(|value| value.as_u64())
+        .unwrap_or(20) as usize;
+    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
+
+    let base_metadata = validation_metadata(&effective_ref, schema_status);
+    if symbol_name.trim().is_empty() {
+        return tool_error_response(
+            id,
+            ProtocolErrorCode::InvalidInput,
+            "Parameter `symbol_name` or `symbol_stable_id` is required.",
+            None,
+            base_metadata,
+        );
+    }
+
+    if schema_status != SchemaStatus::Compatible {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    }
+    let Some(c) = conn else {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    };
+
+    let freshness = check_and_enforce_freshness(
+        id.clone(),
+        arguments,
+        config,
+        conn,
+        workspace,
+        project_id,
+        &effective_ref,
+        schema_status,
+    );
+    if let Some(block) = freshness.block_response {
+        return block;
+    }
+    let metadata = freshness.metadata;
+
+    match find_references::find_references_with_options(
+        c,
+        workspace,
+        project_id,
+        &effective_ref,
+        symbol_name,
+        find_references::FindReferencesOptions {
+            kind_filter: kind,
+            include_name_matches,
+        },
+        limit,
+    ) {
+        Ok(result) => {
+            let mut payload = serde_json::to_value(result).unwrap_or_else(
+                |_| json!({"error": "failed to serialize find_references payload"}),
+            );
+            if let Value::Object(object) = &mut payload {
+                object.insert("metadata".to_string(), json!(metadata));
+            }
+            tool_text_response(id, payload)
+        }
+        Err(find_references::FindReferencesError::SymbolNotFound) => tool_error_response(
+            id,
+            ProtocolErrorCode::SymbolNotFound,
+            "No symbol matching the requested name was found.",
+            Some(json!({
+                "symbol_name": symbol_name,
+                "ref": effective_ref,
+            })),
+            metadata,
+        ),
+        Err(find_references::FindReferencesError::NoEdgesAvailable) => tool_error_response(
+            id,
+            ProtocolErrorCode::NoEdgesAvailable,
+            "symbol_edges data is not available for this project/ref yet.",
+            Some(json!({
+                "symbol_name": symbol_name,
+                "ref": effective_ref,
+                "remediation": "Run index_repo/sync_repo to populate relation edges.",
+            })),
+            metadata,
+        ),
+        Err(find_references::FindReferencesError::State(err)) => {
+            let (code, message, data) = map_state_error(&err);
+            tool_error_response(id, code, message, data, metadata)
+        }
+    }
+}
+
+pub(super) fn handle_tests_for_symbol(params: QueryToolParams<'_>) -> JsonRpcResponse {
+    let QueryToolParams {
+        id,
+        arguments,
+        config,
+        schema_status,
+        compatibility_reason,
+        conn,
+        workspace,
+        project_id,
+        ..
+    } = params;
+
+    let symbol_name = arguments
+        .get("symbol_name")
+        .and_then(|value| value.as_str())
+        .unwrap_or("");
+    let requested_ref = arguments.get("ref").and_then(|value| value.as_str());
+    let limit = arguments
+        .get("limit")
+        .and_then(|value| value.as_u64())
+        .unwrap_or(50) as usize;
+    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
+
+    let base_metadata = validation_metadata(&effective_ref, schema_status);
+    if symbol_name.trim().is_empty() {
+        return tool_error_response(
+            id,
+            ProtocolErrorCode::InvalidInput,
+            "Parameter `symbol_name` is required.",
+            None,
+            base_metadata,
+        );
+    }
+
+    if schema_status != SchemaStatus::Compatible {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    }
+    let Some(c) = conn else {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    };
+
+    let freshness = check_and_enforce_freshness(
+        id.clone(),
+        arguments,
+        config,
+        conn,
+        workspace,
+        project_id,
+        &effective_ref,
+        schema_status,
+    );
+    if let Some(block) = freshness.block_response {
+        return block;
+    }
+    let mut metadata = freshness.metadata;
+
+    match tests_for_symbol::tests_for_symbol(
+        c,
+        workspace,
+        project_id,
+        &effective_ref,
+        symbol_name,
+        limit,
+    ) {
+        Ok(result) => {
+            if result.tests.len() < result.total_tests {
+                metadata.result_completeness = cruxe_core::types::ResultCompleteness::Truncated;
+            }
+            let mut payload = serde_json::to_value(result).unwrap_or_else(
+                |_| json!({"error": "failed to serialize tests_for_symbol payload"}),
+            );
+            if let Value::Object(object) = &mut payload {
+                object.insert("association".to_string(), json!("reference_based"));
+                object.insert("metadata".to_string(), json!(metadata));
+            }
+            tool_text_response(id, payload)
+        }
+        Err(find_references::FindReferencesError::SymbolNotFound) => tool_error_response(
+            id,
+            ProtocolErrorCode::SymbolNotFound,
+            "No symbol matching the requested name was found.",
+            Some(json!({
+                "symbol_name": symbol_name,
+                "ref": effective_ref,
+            })),
+            metadata,
+        ),
+        Err(find_references::FindReferencesError::NoEdgesAvailable) => tool_error_response(
+            id,
+            ProtocolErrorCode::NoEdgesAvailable,
+            "symbol_edges data is not available for this project/ref yet.",
+            Some(json!({
+                "symbol_name": symbol_name,
+                "ref": effective_ref,
+                "remediation": "Run index_repo/sync_repo to populate relation edges.",
+            })),
+            metadata,
+        ),
+        Err(find_references::FindReferencesError::State(err)) => {
+            let (code, message, data) = map_state_error(&err);
+            tool_error_response(id, code, message, data, metadata)
+        }
+    }
+}
+
+pub(super) fn handle_resolve_import(params: QueryToolParams<'_>) -> JsonRpcResponse {
+    let QueryToolParams {
+        id,
+        arguments,
+        config,
+        schema_status,
+        compatibility_reason,
+        conn,
+        workspace,
+        project_id,
+        ..
+    } = params;
+
+    let path = arguments
+        .get("path")
+        .and_then(|value| value.as_str())
+        .unwrap_or("")
+        .trim();
+    let statement = arguments
+        .get("import")
+        .and_then(|value| value.as_str())
+        .unwrap_or("");
+    let requested_ref = arguments.get("ref").and_then(|value| value.as_str());
+    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
+
+    let base_metadata = validation_metadata(&effective_ref, schema_status);
+    if path.is_empty() || statement.trim().is_empty() {
+        return tool_error_response(
+            id,
+            ProtocolErrorCode::InvalidInput,
+            "Parameters `path` and `import` are required.",
+            None,
+            base_metadata,
+        );
+    }
+
+    if schema_status != SchemaStatus::Compatible {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    }
+    let Some(c) = conn else {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    };
+
+    let freshness = check_and_enforce_freshness(
+        id.clone(),
+        arguments,
+        config,
+        conn,
+        workspace,
+        project_id,
+        &effective_ref,
+        schema_status,
+    );
+    if let Some(block) = freshness.block_response {
+        return block;
+    }
+    let metadata = freshness.metadata;
+
+    match resolve_import::resolve_import(c, project_id, &effective_ref, path, statement) {
+        Ok(result) => {
+            let mut payload = serde_json::to_value(result)
+                .unwrap_or_else(|_| json!({"error": "failed to serialize resolve_import payload"}));
+            if let Value::Object(object) = &mut payload {
+                object.insert("metadata".to_string(), json!(metadata));
+            }
+            tool_text_response(id, payload)
+        }
+        Err(resolve_import::ResolveImportError::State(err)) => {
+            let (code, message, data) = map_state_error(&err);
+            tool_error_response(id, code, message, data, metadata)
+        }
+        Err(err) => tool_error_response(
+            id,
+            ProtocolErrorCode::InvalidInput,
+            err.to_string(),
+            Some(json!({ "path": path, "import": statement })),
+            metadata,
+        ),
+    }
+}
+
+pub(super) fn handle_get_call_graph(params: QueryToolParams<'_>) -> JsonRpcResponse {
+    let QueryToolParams {
+        id,
+        arguments,
+        config,
+        schema_status,
+        compatibility_reason,
+        conn,
+        workspace,
+        project_id,
+        ..
+    } = params;
+
+    let symbol_name = arguments
+        .get("symbol_name")
+        .and_then(|value| value.as_str())
+        .unwrap_or("");
+    let path = arguments.get("path").and_then(|value| value.as_str());
+    let requested_ref = arguments.get("ref").and_then(|value| value.as_str());
+    let direction_raw = arguments
+        .get("direction")
+        .and_then(|value| value.as_str())
+        .unwrap_or("both");
+    let requested_depth = arguments
+        .get("depth")
+        .and_then(|value| value.as_u64())
+        .unwrap_or(1) as u32;
+    let limit = arguments
+        .get("limit")
+        .and_then(|value| value.as_u64())
+        .unwrap_or(20) as usize;
+    let requested_breadth = requested_max_breadth(arguments);
+    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
+    let base_metadata = validation_metadata(&effective_ref, schema_status);
+
+    if symbol_name.trim().is_empty() {
+        return tool_error_response(
+            id,
+            ProtocolErrorCode::InvalidInput,
+            "Parameter `symbol_name` is required.",
+            None,
+            base_metadata,
+        );
+    }
+
+    let Some(direction) = call_graph::CallGraphDirection::parse(direction_raw) else {
+        return tool_error_response(
+            id,
+            ProtocolErrorCode::InvalidInput,
+            "Parameter `direction` must be one of: callers, callees, both.",
+            Some(json!({ "direction": direction_raw })),
+            base_metadata,
+        );
+    };
+
+    if schema_status != SchemaStatus::Compatible {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    }
+    let Some(c) = conn else {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    };
+
+    match cruxe_state::branch_state::get_branch_state(c, project_id, &effective_ref) {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return tool_error_response(
+                id,
+                ProtocolErrorCode::RefNotIndexed,
+                "The requested ref has no indexed state yet.",
+                Some(json!({
+                    "ref": effective_ref,
+                    "remediation": "Run sync_repo for this ref before querying.",
+                })),
+                validation_metadata(&effective_ref, schema_status),
+            );
+        }
+        Err(err) => {
+            let (code, message, data) = map_state_error(&err);
+            return tool_error_response(
+                id,
+                code,
+                message,
+                data,
+                validation_metadata(&effective_ref, schema_status),
+            );
+        }
     }
-    let Some(c) = conn else {
-        return tool_compatibility_error(ToolCompatibilityParams {
-            id,
-            schema_status,
-            compatibility_reason,
-            config,
-            conn,
-            workspace,
-            project_id,
-            ref_name: &effective_ref,
-        });
-    };
 
     let freshness = check_and_enforce_freshness(
         id.clone(),
@@ -874,55 +2156,78 @@ pub(super) fn handle_find_references(params: QueryToolParams<'_>) -> JsonRpcResp
     if let Some(block) = freshness.block_response {
         return block;
     }
-    let metadata = freshness.metadata;
+    let mut metadata = freshness.metadata;
+    let mut warnings = Vec::new();
+    if requested_depth == 0 {
+        warnings.push("Depth 0 is invalid; using depth=1.".to_string());
+    }
+    if requested_depth > call_graph::MAX_CALL_GRAPH_DEPTH {
+        warnings.push(format!(
+            "Requested depth {} exceeds max {}; clamped.",
+            requested_depth,
+            call_graph::MAX_CALL_GRAPH_DEPTH
+        ));
+    }
+    if let Some(warning) = max_breadth_warning(requested_breadth) {
+        warnings.push(warning);
+    }
+    if !warnings.is_empty() {
+        metadata.warnings = Some(warnings);
+    }
 
-    match find_references::find_references(
+    match call_graph::get_call_graph(
         c,
-        workspace,
         project_id,
         &effective_ref,
-        kind,
-        symbol_name,
-        limit,
+        &call_graph::CallGraphRequest {
+            symbol_name,
+            path,
+            direction,
+            depth: requested_depth,
+            limit,
+            max_breadth: requested_breadth,
+        },
     ) {
         Ok(result) => {
-            let mut payload = serde_json::to_value(result).unwrap_or_else(
-                |_| json!({"error": "failed to serialize find_references payload"}),
-            );
+            if result.truncated || result.truncated_edges > 0 {
+                metadata.result_completeness = cruxe_core::types::ResultCompleteness::Truncated;
+            }
+            let mut payload = match serde_json::to_value(result) {
+                Ok(value) => value,
+                Err(err) => {
+                    return tool_error_response(
+                        id,
+                        ProtocolErrorCode::InternalError,
+                        "Failed to serialize get_call_graph payload.",
+                        Some(json!({ "error": err.to_string() })),
+                        metadata.clone(),
+                    );
+                }
+            };
             if let Value::Object(object) = &mut payload {
                 object.insert("metadata".to_string(), json!(metadata));
             }
             tool_text_response(id, payload)
         }
-        Err(find_references::FindReferencesError::SymbolNotFound) => tool_error_response(
+        Err(call_graph::CallGraphError::SymbolNotFound) => tool_error_response(
             id,
             ProtocolErrorCode::SymbolNotFound,
             "No symbol matching the requested name was found.",
             Some(json!({
                 "symbol_name": symbol_name,
+                "path": path,
                 "ref": effective_ref,
             })),
             metadata,
         ),
-        Err(find_references::FindReferencesError::NoEdgesAvailable) => tool_error_response(
-            id,
-            ProtocolErrorCode::NoEdgesAvailable,
-            "symbol_edges data is not available for this project/ref yet.",
-            Some(json!({
-                "symbol_name": symbol_name,
-                "ref": effective_ref,
-                "remediation": "Run index_repo/sync_repo to populate relation edges.",
-            })),
-            metadata,
-        ),
-        Err(find_references::FindReferencesError::State(err)) => {
+        Err(call_graph::CallGraphError::State(err)) => {
             let (code, message, data) = map_state_error(&err);
             tool_error_response(id, code, message, data, metadata)
         }
     }
 }
 
-pub(super) fn handle_get_call_graph(params: QueryToolParams<'_>) -> JsonRpcResponse {
+pub(super) fn handle_get_import_graph(params: QueryToolParams<'_>) -> JsonRpcResponse {
     let QueryToolParams {
         id,
         arguments,
@@ -938,13 +2243,16 @@ pub(super) fn handle_get_call_graph(params: QueryToolParams<'_>) -> JsonRpcRespo
     let symbol_name = arguments
         .get("symbol_name")
         .and_then(|value| value.as_str())
-        .unwrap_or("");
-    let path = arguments.get("path").and_then(|value| value.as_str());
+        .filter(|value| !value.trim().is_empty());
+    let path = arguments
+        .get("path")
+        .and_then(|value| value.as_str())
+        .filter(|value| !value.trim().is_empty());
     let requested_ref = arguments.get("ref").and_then(|value| value.as_str());
     let direction_raw = arguments
         .get("direction")
         .and_then(|value| value.as_str())
-        .unwrap_or("both");
+        .unwrap_or("importers");
     let requested_depth = arguments
         .get("depth")
         .and_then(|value| value.as_u64())
@@ -952,25 +2260,25 @@ pub(super) fn handle_get_call_graph(params: QueryToolParams<'_>) -> JsonRpcRespo
     let limit = arguments
         .get("limit")
         .and_then(|value| value.as_u64())
-        .unwrap_or(20) as usize;
-    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id);
+        .unwrap_or(50) as usize;
+    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
     let base_metadata = validation_metadata(&effective_ref, schema_status);
 
-    if symbol_name.trim().is_empty() {
+    if symbol_name.is_none() && path.is_none() {
         return tool_error_response(
             id,
             ProtocolErrorCode::InvalidInput,
-            "Parameter `symbol_name` is required.",
+            "Either `symbol_name` or `path` is required.",
             None,
             base_metadata,
         );
     }
 
-    let Some(direction) = call_graph::CallGraphDirection::parse(direction_raw) else {
+    let Some(direction) = import_graph::ImportGraphDirection::parse(direction_raw) else {
         return tool_error_response(
             id,
             ProtocolErrorCode::InvalidInput,
-            "Parameter `direction` must be one of: callers, callees, both.",
+            "Parameter `direction` must be one of: importers, imports, both.",
             Some(json!({ "direction": direction_raw })),
             base_metadata,
         );
@@ -1045,22 +2353,22 @@ pub(super) fn handle_get_call_graph(params: QueryToolParams<'_>) -> JsonRpcRespo
     if requested_depth == 0 {
         warnings.push("Depth 0 is invalid; using depth=1.".to_string());
     }
-    if requested_depth > call_graph::MAX_CALL_GRAPH_DEPTH {
+    if requested_depth > import_graph::MAX_IMPORT_GRAPH_DEPTH {
         warnings.push(format!(
             "Requested depth {} exceeds max {}; clamped.",
             requested_depth,
-            call_graph::MAX_CALL_GRAPH_DEPTH
+            import_graph::MAX_IMPORT_GRAPH_DEPTH
         ));
     }
     if !warnings.is_empty() {
         metadata.warnings = Some(warnings);
     }
 
-    match call_graph::get_call_graph(
+    match import_graph::get_import_graph(
         c,
         project_id,
         &effective_ref,
-        &call_graph::CallGraphRequest {
+        &import_graph::ImportGraphRequest {
             symbol_name,
             path,
             direction,
@@ -1078,7 +2386,7 @@ pub(super) fn handle_get_call_graph(params: QueryToolParams<'_>) -> JsonRpcRespo
                     return tool_error_response(
                         id,
                         ProtocolErrorCode::InternalError,
-                        "Failed to serialize get_call_graph payload.",
+                        "Failed to serialize get_import_graph payload.",
                         Some(json!({ "error": err.to_string() })),
                         metadata.clone(),
                     );
@@ -1089,7 +2397,7 @@ pub(super) fn handle_get_call_graph(params: QueryToolParams<'_>) -> JsonRpcRespo
             }
             tool_text_response(id, payload)
         }
-        Err(call_graph::CallGraphError::SymbolNotFound) => tool_error_response(
+        Err(import_graph::ImportGraphError::SymbolNotFound) => tool_error_response(
             id,
             ProtocolErrorCode::SymbolNotFound,
             "No symbol matching the requested name was found.",
@@ -1100,7 +2408,17 @@ pub(super) fn handle_get_call_graph(params: QueryToolParams<'_>) -> JsonRpcRespo
             })),
             metadata,
         ),
-        Err(call_graph::CallGraphError::State(err)) => {
+        Err(import_graph::ImportGraphError::FileNotFound) => tool_error_response(
+            id,
+            ProtocolErrorCode::FileNotFound,
+            "The requested file is not indexed for this ref.",
+            Some(json!({
+                "path": path,
+                "ref": effective_ref,
+            })),
+            metadata,
+        ),
+        Err(import_graph::ImportGraphError::State(err)) => {
             let (code, message, data) = map_state_error(&err);
             tool_error_response(id, code, message, data, metadata)
         }
@@ -1267,7 +2585,10 @@ pub(super) fn handle_compare_symbol_between_commits(
             }
             tool_text_response(id, payload)
         }
-        Err(symbol_compare::SymbolCompareError::SymbolNotFound) => tool_error_response(
+        Err(
+            symbol_compare::SymbolCompareError::SymbolNotFound
+            | symbol_compare::SymbolCompareError::StableIdNotFound { .. },
+        ) => tool_error_response(
             id,
             ProtocolErrorCode::SymbolNotFound,
             "No symbol matching the requested name was found.",
@@ -1286,10 +2607,187 @@ pub(super) fn handle_compare_symbol_between_commits(
     }
 }
 
+pub(super) fn handle_compare_symbols(params: QueryToolParams<'_>) -> JsonRpcResponse {
+    let QueryToolParams {
+        id,
+        arguments,
+        config,
+        schema_status,
+        compatibility_reason,
+        conn,
+        workspace,
+        project_id,
+        ..
+    } = params;
+
+    let left_stable_id = arguments
+        .get("left_symbol_stable_id")
+        .and_then(|value| value.as_str())
+        .unwrap_or("");
+    let right_stable_id = arguments
+        .get("right_symbol_stable_id")
+        .and_then(|value| value.as_str())
+        .unwrap_or("");
+    let requested_ref = arguments.get("ref").and_then(|value| value.as_str());
+    let normalize_raw = arguments
+        .get("normalize")
+        .and_then(|value| value.as_str())
+        .unwrap_or("whitespace");
+    let similarity_only = arguments
+        .get("similarity_only")
+        .and_then(|value| value.as_bool())
+        .unwrap_or(false);
+    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
+    let base_metadata = validation_metadata(&effective_ref, schema_status);
+
+    if left_stable_id.trim().is_empty() || right_stable_id.trim().is_empty() {
+        return tool_error_response(
+            id,
+            ProtocolErrorCode::InvalidInput,
+            "Parameters `left_symbol_stable_id` and `right_symbol_stable_id` are required.",
+            None,
+            base_metadata,
+        );
+    }
+
+    let Some(normalization) = symbol_compare::BodyNormalization::parse(normalize_raw) else {
+        return tool_error_response(
+            id,
+            ProtocolErrorCode::InvalidInput,
+            "Parameter `normalize` must be one of: none, whitespace, identifiers.",
+            Some(json!({ "normalize": normalize_raw })),
+            base_metadata,
+        );
+    };
+
+    if schema_status != SchemaStatus::Compatible {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    }
+    let Some(c) = conn else {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    };
+
+    match cruxe_state::branch_state::get_branch_state(c, project_id, &effective_ref) {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return tool_error_response(
+                id,
+                ProtocolErrorCode::RefNotIndexed,
+                "The requested ref has no indexed state yet.",
+                Some(json!({
+                    "ref": effective_ref,
+                    "remediation": "Run sync_repo for this ref before querying.",
+                })),
+                validation_metadata(&effective_ref, schema_status),
+            );
+        }
+        Err(err) => {
+            let (code, message, data) = map_state_error(&err);
+            return tool_error_response(
+                id,
+                code,
+                message,
+                data,
+                validation_metadata(&effective_ref, schema_status),
+            );
+        }
+    }
+
+    let freshness = check_and_enforce_freshness(
+        id.clone(),
+        arguments,
+        config,
+        conn,
+        workspace,
+        project_id,
+        &effective_ref,
+        schema_status,
+    );
+    if let Some(block) = freshness.block_response {
+        return block;
+    }
+    let metadata = freshness.metadata;
+
+    let options = symbol_compare::BodyCompareOptions {
+        normalization,
+        similarity_only,
+        max_diff_bytes: config.search.max_response_bytes,
+    };
+    match symbol_compare::compare_symbol_bodies(
+        c,
+        project_id,
+        &effective_ref,
+        left_stable_id,
+        right_stable_id,
+        options,
+    ) {
+        Ok(result) => {
+            let mut payload = match serde_json::to_value(result) {
+                Ok(value) => value,
+                Err(err) => {
+                    return tool_error_response(
+                        id,
+                        ProtocolErrorCode::InternalError,
+                        "Failed to serialize compare_symbols payload.",
+                        Some(json!({ "error": err.to_string() })),
+                        metadata.clone(),
+                    );
+                }
+            };
+            if let Value::Object(object) = &mut payload {
+                object.insert("metadata".to_string(), json!(metadata));
+            }
+            tool_text_response(id, payload)
+        }
+        Err(symbol_compare::SymbolCompareError::StableIdNotFound { symbol_stable_id }) => {
+            tool_error_response(
+                id,
+                ProtocolErrorCode::SymbolNotFound,
+                "No symbol matching the requested stable id was found.",
+                Some(json!({
+                    "symbol_stable_id": symbol_stable_id,
+                    "ref": effective_ref,
+                })),
+                metadata.clone(),
+            )
+        }
+        Err(symbol_compare::SymbolCompareError::SymbolNotFound) => tool_error_response(
+            id,
+            ProtocolErrorCode::SymbolNotFound,
+            "No symbol matching the requested stable id was found.",
+            Some(json!({ "ref": effective_ref })),
+            metadata.clone(),
+        ),
+        Err(symbol_compare::SymbolCompareError::State(err)) => {
+            let (code, message, data) = map_state_error(&err);
+            tool_error_response(id, code, message, data, metadata)
+        }
+    }
+}
+
 pub(super) fn handle_suggest_followup_queries(params: QueryToolParams<'_>) -> JsonRpcResponse {
     let QueryToolParams {
         id,
         arguments,
+        config,
         conn,
         workspace,
         project_id,
@@ -1298,7 +2796,7 @@ pub(super) fn handle_suggest_followup_queries(params: QueryToolParams<'_>) -> Js
     } = params;
 
     let requested_ref = arguments.get("ref").and_then(|value| value.as_str());
-    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id);
+    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
     let metadata = validation_metadata(&effective_ref, schema_status);
 
     let Some(previous_query) = arguments
@@ -1408,7 +2906,7 @@ pub(super) fn handle_explain_ranking(params: QueryToolParams<'_>) -> JsonRpcResp
         .get("limit")
         .and_then(|value| value.as_u64())
         .unwrap_or(200) as usize;
-    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id);
+    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
     let base_metadata = validation_metadata(&effective_ref, schema_status);
 
     if query.trim().is_empty() {
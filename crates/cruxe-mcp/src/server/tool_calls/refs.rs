@@ -1,5 +1,4 @@
 use super::*;
-use cruxe_core::constants;
 use cruxe_core::time::now_iso8601;
 use cruxe_vcs::{Git2VcsAdapter, WorktreeManager};
 use serde::Serialize;
@@ -50,7 +49,7 @@ pub(super) fn handle_list_refs(params: QueryToolParams<'_>) -> JsonRpcResponse {
         ..
     } = params;
 
-    let effective_ref = resolve_tool_ref(None, workspace, conn, project_id);
+    let effective_ref = resolve_tool_ref(None, workspace, conn, project_id, config);
     let metadata = build_metadata(
         &effective_ref,
         schema_status,
@@ -116,14 +115,18 @@ pub(super) fn handle_list_refs(params: QueryToolParams<'_>) -> JsonRpcResponse {
             }
         }
     } else {
+        let non_vcs_ref = if project_row.default_ref.trim().is_empty() {
+            config.index.non_vcs_ref()
+        } else {
+            project_row.default_ref.as_str()
+        };
         vec![RefDescriptorPayload {
-            ref_name: constants::REF_LIVE.to_string(),
+            ref_name: non_vcs_ref.to_string(),
             is_default: true,
             last_indexed_commit: None,
             merge_base_commit: None,
-            file_count: cruxe_state::manifest::file_count(c, project_id, constants::REF_LIVE)
-                .unwrap_or(0),
-            symbol_count: cruxe_state::symbols::symbol_count(c, project_id, constants::REF_LIVE)
+            file_count: cruxe_state::manifest::file_count(c, project_id, non_vcs_ref).unwrap_or(0),
+            symbol_count: cruxe_state::symbols::symbol_count(c, project_id, non_vcs_ref)
                 .unwrap_or(0),
             status: "active".to_string(),
             last_accessed_at: None,
@@ -157,7 +160,7 @@ pub(super) fn handle_switch_ref(params: QueryToolParams<'_>) -> JsonRpcResponse
 
     let requested_ref = arguments.get("ref").and_then(|value| value.as_str());
     let target_ref = requested_ref.unwrap_or("").trim();
-    let previous_ref = resolve_tool_ref(None, workspace, conn, project_id);
+    let previous_ref = resolve_tool_ref(None, workspace, conn, project_id, config);
     let base_metadata = validation_metadata(&previous_ref, schema_status);
     if target_ref.is_empty() {
         return tool_error_response(
@@ -295,6 +298,164 @@ pub(super) fn handle_switch_ref(params: QueryToolParams<'_>) -> JsonRpcResponse
     )
 }
 
+#[derive(Debug, Serialize)]
+struct DropRefPayload {
+    #[serde(rename = "ref")]
+    ref_name: String,
+    was_default: bool,
+    removed: cruxe_state::ref_drop::DropRefReport,
+    metadata: ProtocolMetadata,
+}
+
+pub(super) fn handle_drop_ref(params: QueryToolParams<'_>) -> JsonRpcResponse {
+    let QueryToolParams {
+        id,
+        arguments,
+        config,
+        schema_status,
+        compatibility_reason,
+        conn,
+        workspace,
+        project_id,
+        ..
+    } = params;
+
+    let target_ref = arguments
+        .get("ref")
+        .and_then(|value| value.as_str())
+        .unwrap_or("")
+        .trim();
+    let force = arguments
+        .get("force")
+        .and_then(|value| value.as_bool())
+        .unwrap_or(false);
+    let session_ref = resolve_tool_ref(None, workspace, conn, project_id, config);
+    let base_metadata = validation_metadata(&session_ref, schema_status);
+    if target_ref.is_empty() {
+        return tool_error_response(
+            id,
+            ProtocolErrorCode::InvalidInput,
+            "Parameter `ref` is required.",
+            None,
+            base_metadata,
+        );
+    }
+
+    let Some(c) = conn else {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &session_ref,
+        });
+    };
+    let project_row = match cruxe_state::project::get_by_id(c, project_id) {
+        Ok(Some(project)) => project,
+        Ok(None) => {
+            return tool_error_response(
+                id,
+                ProtocolErrorCode::ProjectNotFound,
+                "Project is not initialized for this workspace. Run `cruxe init` first.",
+                Some(json!({
+                    "workspace": workspace.to_string_lossy(),
+                    "remediation": "cruxe init --path <workspace>"
+                })),
+                base_metadata,
+            );
+        }
+        Err(err) => {
+            let (code, message, data) = map_state_error(&err);
+            return tool_error_response(id, code, message, data, base_metadata);
+        }
+    };
+    let default_ref = if project_row.default_ref.trim().is_empty() {
+        config.index.non_vcs_ref()
+    } else {
+        project_row.default_ref.as_str()
+    };
+    let was_default = target_ref == default_ref;
+    if was_default && !force {
+        return tool_error_response(
+            id,
+            ProtocolErrorCode::InvalidInput,
+            "Refusing to drop the project's default ref without `force`.",
+            Some(json!({
+                "ref": target_ref,
+                "default_ref": default_ref,
+                "remediation": "Pass force: true to drop it; re-run index_repo to rebuild it.",
+            })),
+            base_metadata,
+        );
+    }
+    if has_active_job(Some(c), project_id) {
+        return tool_error_response(
+            id,
+            ProtocolErrorCode::IndexInProgress,
+            "An indexing job is already running.",
+            Some(json!({
+                "project_id": project_id,
+                "remediation": "Use index_status to poll and retry after completion.",
+            })),
+            base_metadata,
+        );
+    }
+
+    let data_dir = config.project_data_dir(project_id);
+    let recorded_overlay = cruxe_state::branch_state::get_branch_state(c, project_id, target_ref)
+        .ok()
+        .flatten()
+        .and_then(|state| state.overlay_dir)
+        .map(PathBuf::from);
+    let overlay_dir = match recorded_overlay {
+        Some(path) if path.is_absolute() => path,
+        Some(path) => data_dir.join(path),
+        None => cruxe_indexer::overlay::overlay_dir_for_ref(&data_dir, target_ref),
+    };
+    let removed = match cruxe_state::ref_drop::drop_ref(
+        c,
+        &data_dir,
+        project_id,
+        target_ref,
+        &overlay_dir,
+        config.search.semantic.vector_backend_opt(),
+    ) {
+        Ok(removed) => removed,
+        Err(err) => {
+            let (code, message, data) = map_state_error(&err);
+            return tool_error_response(id, code, message, data, base_metadata);
+        }
+    };
+    if session_ref == target_ref
+        && let Err(err) = clear_session_ref_override(workspace, project_id)
+    {
+        let (code, message, data) = map_state_error(&err);
+        return tool_error_response(id, code, message, data, base_metadata);
+    }
+
+    let metadata = build_metadata(
+        &resolve_tool_ref(None, workspace, conn, project_id, config),
+        schema_status,
+        config,
+        conn,
+        workspace,
+        project_id,
+    );
+    tool_text_response(
+        id,
+        serde_json::to_value(DropRefPayload {
+            ref_name: target_ref.to_string(),
+            was_default,
+            removed,
+            metadata,
+        })
+        .unwrap_or_else(|_| json!({"error":"failed to serialize drop_ref payload"})),
+    )
+}
+
 fn default_worktrees_root(data_dir: &Path, project_id: &str) -> PathBuf {
     let storage_root = data_dir
         .file_name()
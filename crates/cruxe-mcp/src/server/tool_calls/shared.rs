@@ -76,8 +76,24 @@ pub(super) fn check_and_enforce_freshness(
     }
 }
 
-/// Parse `detail_level` from MCP tool arguments, defaulting to `Signature`.
-pub(super) fn parse_detail_level(arguments: &Value) -> DetailLevel {
+/// Freshness for a commit-pinned query. The pinned snapshot is fixed, so the
+/// workspace scan is skipped and no background sync may move the ref past it.
+pub(super) fn pinned_commit_freshness(
+    effective_ref: &str,
+    schema_status: SchemaStatus,
+    commit: &str,
+) -> FreshnessEnforced {
+    let mut metadata = validation_metadata(effective_ref, schema_status);
+    metadata.commit = Some(commit.to_string());
+    FreshnessEnforced {
+        metadata,
+        block_response: None,
+    }
+}
+
+/// Parse `detail_level` from MCP tool arguments, defaulting to
+/// `search.default_detail_level` when absent.
+pub(super) fn parse_detail_level(arguments: &Value, config: &Config) -> DetailLevel {
     arguments
         .get("detail_level")
         .and_then(|v| v.as_str())
@@ -86,7 +102,7 @@ pub(super) fn parse_detail_level(arguments: &Value) -> DetailLevel {
             "context" => DetailLevel::Context,
             _ => DetailLevel::Signature,
         })
-        .unwrap_or(DetailLevel::Signature)
+        .unwrap_or_else(|| config.search.default_detail_level_typed())
 }
 
 pub(super) fn parse_compact(arguments: &Value) -> bool {
@@ -96,6 +112,68 @@ pub(super) fn parse_compact(arguments: &Value) -> bool {
         .unwrap_or(false)
 }
 
+/// Parse the optional `fields` whitelist; non-string entries are ignored.
+pub(super) fn parse_result_fields(arguments: &Value) -> Option<Vec<String>> {
+    let fields: Vec<String> = arguments
+        .get("fields")?
+        .as_array()?
+        .iter()
+        .filter_map(|v| v.as_str())
+        .map(str::to_string)
+        .collect();
+    (!fields.is_empty()).then_some(fields)
+}
+
+/// Parse a `language` filter given as one string or an array of strings.
+///
+/// Blank entries are ignored; an absent parameter yields an empty list.
+pub(super) fn parse_language_filter(arguments: &Value) -> Result<Vec<String>, &'static str> {
+    const MESSAGE: &str = "Parameter `language` must be a string or an array of strings.";
+    let raw: Vec<&str> = match arguments.get("language") {
+        None | Some(Value::Null) => return Ok(Vec::new()),
+        Some(Value::String(language)) => vec![language.as_str()],
+        Some(Value::Array(values)) => values
+            .iter()
+            .map(|v| v.as_str().ok_or(MESSAGE))
+            .collect::<Result<_, _>>()?,
+        Some(_) => return Err(MESSAGE),
+    };
+    let mut languages: Vec<String> = Vec::new();
+    for language in raw.into_iter().map(str::trim).filter(|l| !l.is_empty()) {
+        if !languages.iter().any(|seen| seen == language) {
+            languages.push(language.to_string());
+        }
+    }
+    Ok(languages)
+}
+
+/// Parse the `visibility` filter; an absent parameter means `any`.
+pub(super) fn parse_visibility_filter(
+    arguments: &Value,
+) -> Result<cruxe_core::types::VisibilityFilter, &'static str> {
+    match arguments.get("visibility") {
+        None | Some(Value::Null) => Ok(cruxe_core::types::VisibilityFilter::Any),
+        Some(raw) => raw
+            .as_str()
+            .and_then(|raw| raw.parse().ok())
+            .ok_or("Parameter `visibility` must be `public`, `private`, or `any`."),
+    }
+}
+
+/// Parse the `intent` override; an absent parameter leaves intent to the classifier.
+pub(super) fn parse_intent_override(
+    arguments: &Value,
+) -> Result<Option<cruxe_core::types::QueryIntent>, &'static str> {
+    match arguments.get("intent") {
+        None | Some(Value::Null) => Ok(None),
+        Some(raw) => raw
+            .as_str()
+            .and_then(|raw| raw.parse().ok())
+            .map(Some)
+            .ok_or("Parameter `intent` must be one of: symbol, path, error, phrase, natural."),
+    }
+}
+
 pub(super) fn resolve_ranking_explain_level(
     arguments: &Value,
     config: &Config,
@@ -141,6 +219,71 @@ pub(super) fn ranking_reasons_payload(
     }
 }
 
+/// Fingerprint binding a paging cursor to the tool, ref and query arguments
+/// it was issued for.
+pub(super) fn page_cursor_fingerprint(tool: &str, ref_name: &str, args: &[Option<&str>]) -> String {
+    let mut hasher = blake3::Hasher::new();
+    for part in [Some(tool), Some(ref_name)].iter().chain(args) {
+        // Length-prefix each part so adjacent values cannot run together.
+        let part = part.unwrap_or("\u{0}");
+        hasher.update(&(part.len() as u64).to_le_bytes());
+        hasher.update(part.as_bytes());
+    }
+    hasher.finalize().to_hex()[..16].to_string()
+}
+
+pub(super) fn encode_page_cursor(offset: usize, fingerprint: &str) -> String {
+    format!("{offset}.{fingerprint}")
+}
+
+/// Decode a cursor produced by [`encode_page_cursor`] into its offset.
+///
+/// A missing cursor starts at offset 0. Cursors that do not parse, or that were
+/// issued for a different ref or query, are rejected with a message for the
+/// `invalid_input` error.
+pub(super) fn decode_page_cursor(
+    raw: Option<&str>,
+    fingerprint: &str,
+) -> Result<usize, &'static str> {
+    let Some(raw) = raw else {
+        return Ok(0);
+    };
+    let (offset, cursor_fingerprint) = raw
+        .split_once('.')
+        .and_then(|(offset, fp)| Some((offset.parse::<usize>().ok()?, fp)))
+        .ok_or("Parameter `cursor` must be a `next_cursor` value from a previous page.")?;
+    if cursor_fingerprint != fingerprint {
+        return Err(
+            "Parameter `cursor` was issued for a different ref or query; restart without a cursor.",
+        );
+    }
+    Ok(offset)
+}
+
+/// Cursor for the page after one that consumed `page_len` ranked results and
+/// emitted `emitted` of them.
+///
+/// When the byte budget cut the page short, the next page resumes after the
+/// last emitted result; this can repeat results hidden by deduplication but
+/// never skips one.
+pub(super) fn next_page_cursor(
+    offset: usize,
+    page_len: usize,
+    emitted: usize,
+    has_more: bool,
+    safety_limit_applied: bool,
+    fingerprint: &str,
+) -> Option<String> {
+    let next_offset = if safety_limit_applied {
+        offset + emitted
+    } else {
+        offset + page_len
+    };
+    let cut_short = safety_limit_applied && emitted < page_len;
+    ((has_more || cut_short) && next_offset > offset)
+        .then(|| encode_page_cursor(next_offset, fingerprint))
+}
+
 pub(super) fn dedup_search_results(
     results: Vec<search::SearchResult>,
 ) -> (Vec<search::SearchResult>, Vec<usize>, usize) {
@@ -174,6 +317,26 @@ pub(super) fn dedup_search_results(
     (deduped, kept_indices, suppressed)
 }
 
+/// Drop results whose final score is below `min_score`, keeping rank order.
+/// Returns the kept results, their original indices, and the dropped count.
+pub(super) fn filter_search_results_by_min_score(
+    results: Vec<search::SearchResult>,
+    min_score: f64,
+) -> (Vec<search::SearchResult>, Vec<usize>, usize) {
+    let mut kept = Vec::with_capacity(results.len());
+    let mut kept_indices = Vec::with_capacity(results.len());
+    let mut dropped = 0usize;
+    for (index, result) in results.into_iter().enumerate() {
+        if f64::from(result.score) >= min_score {
+            kept.push(result);
+            kept_indices.push(index);
+        } else {
+            dropped += 1;
+        }
+    }
+    (kept, kept_indices, dropped)
+}
+
 pub(super) fn dedup_locate_results(
     results: Vec<locate::LocateResult>,
 ) -> (Vec<locate::LocateResult>, usize) {
@@ -254,10 +417,12 @@ pub(super) struct FilteredResultPayload {
     pub(super) safety_limit_applied: bool,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(super) fn build_filtered_result_payload(
     mut result_values: Vec<Value>,
     detail_level: DetailLevel,
     compact: bool,
+    fields: Option<&[String]>,
     conn: Option<&rusqlite::Connection>,
     project_id: &str,
     effective_ref: &str,
@@ -270,7 +435,15 @@ pub(super) fn build_filtered_result_payload(
         }
     }
 
-    let filtered = detail::serialize_results_at_level(&result_values, detail_level, compact);
+    let mut filtered = detail::serialize_results_at_level(&result_values, detail_level, compact);
+    if let Some(fields) = fields {
+        // Unknown field names simply match nothing.
+        for result in &mut filtered {
+            if let Some(object) = result.as_object_mut() {
+                object.retain(|key, _| fields.iter().any(|field| field == key));
+            }
+        }
+    }
     let (filtered, safety_limit_applied) =
         enforce_payload_safety_limit(filtered, max_response_bytes);
     FilteredResultPayload {
@@ -390,6 +563,34 @@ pub(super) fn map_state_error(err: &StateError) -> (ProtocolErrorCode, String, O
                 "remediation": "Validate the refs and repository integrity, then retry.",
             })),
         ),
+        StateError::SymbolCapExceeded {
+            ref_name,
+            symbol_count,
+            cap,
+        } => (
+            ProtocolErrorCode::SymbolCapExceeded,
+            "Indexing stopped: the ref exceeded the configured symbol cap.".to_string(),
+            Some(json!({
+                "ref": ref_name,
+                "symbol_count": symbol_count,
+                "max_symbols_per_ref": cap,
+                "remediation": "Narrow index.languages or ignore generated code, or raise index.max_symbols_per_ref.",
+            })),
+        ),
+        StateError::InsufficientDisk {
+            path,
+            available_bytes,
+            required_bytes,
+        } => (
+            ProtocolErrorCode::InsufficientDisk,
+            "Not enough free disk space to index safely.".to_string(),
+            Some(json!({
+                "path": path,
+                "available_bytes": available_bytes,
+                "required_bytes": required_bytes,
+                "remediation": "Free disk space on the data volume, or lower index.min_free_disk_mb.",
+            })),
+        ),
         StateError::ResultNotFound { path, line_start } => (
             ProtocolErrorCode::ResultNotFound,
             "Requested result target was not found.".to_string(),
@@ -1,10 +1,14 @@
 use super::*;
+use cruxe_query::changed_since::{ChangedSinceResult, symbols_changed_since};
 use cruxe_query::semantic_advisor::{
     SemanticAdvisorInput, SemanticAdvisorRecommendation, recommend_semantic_profile,
 };
 use serde::Serialize;
 use std::collections::BTreeMap;
 
+/// Default cap on symbols returned for `changed_since`.
+const CHANGED_SINCE_DEFAULT_LIMIT: u64 = 200;
+
 #[derive(Serialize)]
 struct IndexStatusActiveJobPayload {
     job_id: String,
@@ -24,8 +28,23 @@ struct IndexStatusRecentJobPayload {
     mode: String,
     status: String,
     changed_files: i64,
+    files_indexed: i64,
+    /// Files left untouched because their content hash matched the manifest.
+    files_unchanged: i64,
+    files_skipped: i64,
+    /// Files dropped by `index.exclude` globs.
+    files_excluded: i64,
     duration_ms: Option<i64>,
+    /// Indexing throughput of a finished full index run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    files_per_second: Option<f64>,
+    /// Tantivy commits the run made.
+    index_commits: i64,
     created_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_data: Option<Value>,
 }
 
 #[derive(Serialize)]
@@ -48,12 +67,17 @@ struct IndexStatusPayload {
     last_indexed_at: Option<String>,
     file_count: u64,
     symbol_count: u64,
+    /// Symbols per language, including manifest languages that yielded none.
+    symbols_by_language: BTreeMap<String, u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     semantic_profile_recommendation: Option<SemanticProfileRecommendationPayload>,
     compatibility_reason: Option<String>,
     active_job: Option<IndexStatusActiveJobPayload>,
     recent_jobs: Vec<IndexStatusRecentJobPayload>,
     interrupted_recovery_report: Option<Value>,
+    /// Symbols in files changed between the `changed_since` commit and HEAD.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    changed_since: Option<ChangedSinceResult>,
     metadata: ProtocolMetadata,
 }
 
@@ -70,7 +94,30 @@ pub(super) fn handle_index_status(params: IndexStatusToolParams<'_>) -> JsonRpcR
     } = params;
 
     let requested_ref = arguments.get("ref").and_then(|v| v.as_str());
-    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id);
+    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
+    let changed_since = match arguments
+        .get("changed_since")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+    {
+        Some(since) => match resolve_changed_since(ChangedSinceParams {
+            id: id.clone(),
+            arguments,
+            config,
+            schema_status,
+            compatibility_reason,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+            since,
+        }) {
+            Ok(result) => Some(result),
+            Err(response) => return *response,
+        },
+        None => None,
+    };
     let stored_schema_version = conn.and_then(|c| {
         cruxe_state::project::get_by_id(c, project_id)
             .ok()
@@ -93,6 +140,11 @@ pub(super) fn handle_index_status(params: IndexStatusToolParams<'_>) -> JsonRpcR
             (fc, sc)
         })
         .unwrap_or((0, 0));
+    let symbols_by_language = conn
+        .and_then(|c| {
+            cruxe_state::symbols::symbol_count_by_language(c, project_id, &effective_ref).ok()
+        })
+        .unwrap_or_default();
     let semantic_profile_recommendation =
         build_semantic_profile_recommendation(conn, config, project_id, &effective_ref);
 
@@ -139,8 +191,16 @@ pub(super) fn handle_index_status(params: IndexStatusToolParams<'_>) -> JsonRpcR
             mode: j.mode.clone(),
             status: j.status.clone(),
             changed_files: j.changed_files,
+            files_indexed: j.files_indexed,
+            files_unchanged: j.files_unchanged,
+            files_skipped: j.files_skipped,
+            files_excluded: j.files_excluded,
             duration_ms: j.duration_ms,
+            files_per_second: j.files_per_second,
+            index_commits: j.index_commits,
             created_at: j.created_at.clone(),
+            error_message: j.error_message.clone(),
+            error_data: j.error_data.clone(),
         })
         .collect::<Vec<_>>();
     let result = serde_json::to_value(IndexStatusPayload {
@@ -154,11 +214,13 @@ pub(super) fn handle_index_status(params: IndexStatusToolParams<'_>) -> JsonRpcR
         last_indexed_at,
         file_count,
         symbol_count,
+        symbols_by_language,
         semantic_profile_recommendation,
         compatibility_reason: compatibility_reason.map(str::to_string),
         active_job: active_job_payload,
         recent_jobs: recent_jobs_payload,
         interrupted_recovery_report,
+        changed_since,
         metadata: build_metadata(
             &effective_ref,
             schema_status,
@@ -172,6 +234,91 @@ pub(super) fn handle_index_status(params: IndexStatusToolParams<'_>) -> JsonRpcR
     tool_text_response(id, result)
 }
 
+struct ChangedSinceParams<'a> {
+    id: Option<Value>,
+    arguments: &'a Value,
+    config: &'a Config,
+    schema_status: SchemaStatus,
+    compatibility_reason: Option<&'a str>,
+    conn: Option<&'a rusqlite::Connection>,
+    workspace: &'a Path,
+    project_id: &'a str,
+    ref_name: &'a str,
+    since: &'a str,
+}
+
+/// Answer `changed_since` from git plus the existing index, without re-indexing.
+fn resolve_changed_since(
+    params: ChangedSinceParams<'_>,
+) -> Result<ChangedSinceResult, Box<JsonRpcResponse>> {
+    let ChangedSinceParams {
+        id,
+        arguments,
+        config,
+        schema_status,
+        compatibility_reason,
+        conn,
+        workspace,
+        project_id,
+        ref_name,
+        since,
+    } = params;
+
+    let metadata = || build_metadata(ref_name, schema_status, config, conn, workspace, project_id);
+    let vcs_mode = conn
+        .and_then(|c| {
+            cruxe_state::project::get_by_id(c, project_id)
+                .ok()
+                .flatten()
+        })
+        .is_none_or(|project| project.vcs_mode);
+    if !vcs_mode || !cruxe_core::vcs::is_git_repo(workspace) {
+        return Err(Box::new(tool_error_response(
+            id,
+            ProtocolErrorCode::InvalidInput,
+            "`changed_since` requires a git workspace; this project is indexed in single-version (non-VCS) mode.",
+            Some(json!({
+                "workspace": workspace.to_string_lossy(),
+                "remediation": "Omit `changed_since`, or index the project from a git checkout.",
+            })),
+            metadata(),
+        )));
+    }
+    let Some(c) = conn.filter(|_| schema_status == SchemaStatus::Compatible) else {
+        return Err(Box::new(tool_compatibility_error(
+            ToolCompatibilityParams {
+                id,
+                schema_status,
+                compatibility_reason,
+                config,
+                conn,
+                workspace,
+                project_id,
+                ref_name,
+            },
+        )));
+    };
+
+    let limit = arguments
+        .get("limit")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(CHANGED_SINCE_DEFAULT_LIMIT) as usize;
+    symbols_changed_since(c, workspace, project_id, ref_name, since, limit).map_err(|err| {
+        let (code, message, data) = match &err {
+            StateError::Vcs(details) => (
+                ProtocolErrorCode::InvalidInput,
+                format!("Unable to diff `changed_since` commit `{since}` against HEAD."),
+                Some(json!({
+                    "details": details,
+                    "remediation": "Pass a commit sha or ref that exists in this repository.",
+                })),
+            ),
+            other => map_state_error(other),
+        };
+        Box::new(tool_error_response(id, code, message, data, metadata()))
+    })
+}
+
 fn build_semantic_profile_recommendation(
     conn: Option<&rusqlite::Connection>,
     config: &Config,
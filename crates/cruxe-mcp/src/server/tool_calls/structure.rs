@@ -1,5 +1,26 @@
 use super::*;
 
+const EXPORT_SYMBOL_NAMES_DEFAULT_LIMIT: usize = 1_000;
+const EXPORT_SYMBOL_NAMES_MAX_LIMIT: usize = 10_000;
+const LIST_SKIPPED_FILES_DEFAULT_LIMIT: usize = 200;
+const LIST_SKIPPED_FILES_MAX_LIMIT: usize = 2_000;
+const LIST_FILES_DEFAULT_LIMIT: usize = 200;
+const LIST_FILES_MAX_LIMIT: usize = 2_000;
+const LARGE_SYMBOLS_DEFAULT_MIN_LINES: u32 = 100;
+const LARGE_SYMBOLS_DEFAULT_LIMIT: usize = 20;
+const LARGE_SYMBOLS_MAX_LIMIT: usize = 500;
+const UNREFERENCED_DEFAULT_LIMIT: usize = 50;
+const UNREFERENCED_MAX_LIMIT: usize = 500;
+const RECENT_SYMBOLS_DEFAULT_LIMIT: usize = 20;
+const RECENT_SYMBOLS_MAX_LIMIT: usize = 200;
+const DIRECTORY_OUTLINE_DEFAULT_MAX_FILES: usize = 50;
+const DIRECTORY_OUTLINE_DEFAULT_MAX_SYMBOLS: usize = 50;
+const DIRECTORY_OUTLINE_MAX_LIMIT: usize = 500;
+const IMPLEMENTATIONS_OF_DEFAULT_LIMIT: usize = 50;
+const IMPLEMENTATIONS_OF_MAX_LIMIT: usize = 500;
+const MODULE_HIERARCHY_DEFAULT_LIMIT: usize = 500;
+const MODULE_HIERARCHY_MAX_LIMIT: usize = 5_000;
+
 pub(super) fn handle_get_symbol_hierarchy(params: QueryToolParams<'_>) -> JsonRpcResponse {
     let QueryToolParams {
         id,
@@ -27,7 +48,8 @@ pub(super) fn handle_get_symbol_hierarchy(params: QueryToolParams<'_>) -> JsonRp
         "ancestors" => hierarchy::HierarchyDirection::Ancestors,
         "descendants" => hierarchy::HierarchyDirection::Descendants,
         _ => {
-            let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id);
+            let effective_ref =
+                resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
             let metadata = validation_metadata(&effective_ref, schema_status);
             return tool_error_response(
                 id,
@@ -38,7 +60,7 @@ pub(super) fn handle_get_symbol_hierarchy(params: QueryToolParams<'_>) -> JsonRp
             );
         }
     };
-    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id);
+    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
 
     let freshness = check_and_enforce_freshness(
         id.clone(),
@@ -55,15 +77,52 @@ pub(super) fn handle_get_symbol_hierarchy(params: QueryToolParams<'_>) -> JsonRp
     }
     let metadata = freshness.metadata;
 
-    if symbol_name.trim().is_empty() {
-        return tool_error_response(
-            id,
-            ProtocolErrorCode::InvalidInput,
-            "Parameter `symbol_name` is required.",
-            None,
-            metadata,
-        );
+    let module = arguments
+        .get("module")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|module| !module.is_empty());
+    match (module.is_some(), symbol_name.trim().is_empty()) {
+        (true, false) => {
+            return tool_error_response(
+                id,
+                ProtocolErrorCode::InvalidInput,
+                "Pass either `symbol_name` or `module`, not both.",
+                None,
+                metadata,
+            );
+        }
+        (false, true) => {
+            return tool_error_response(
+                id,
+                ProtocolErrorCode::InvalidInput,
+                "Parameter `symbol_name` (or `module`) is required.",
+                None,
+                metadata,
+            );
+        }
+        _ => {}
     }
+    let limit = match arguments.get("limit") {
+        None => MODULE_HIERARCHY_DEFAULT_LIMIT,
+        Some(value) => match value.as_u64() {
+            Some(limit) if (1..=MODULE_HIERARCHY_MAX_LIMIT as u64).contains(&limit) => {
+                limit as usize
+            }
+            _ => {
+                return tool_error_response(
+                    id,
+                    ProtocolErrorCode::InvalidInput,
+                    format!(
+                        "Parameter `limit` must be an integer between 1 and {}.",
+                        MODULE_HIERARCHY_MAX_LIMIT
+                    ),
+                    Some(json!({ "limit": value })),
+                    metadata,
+                );
+            }
+        },
+    };
 
     let Some(c) = conn else {
         return tool_compatibility_error(ToolCompatibilityParams {
@@ -78,6 +137,36 @@ pub(super) fn handle_get_symbol_hierarchy(params: QueryToolParams<'_>) -> JsonRp
         });
     };
 
+    if let Some(module) = module {
+        return match hierarchy::get_module_hierarchy(c, project_id, &effective_ref, module, limit) {
+            Ok(response) => tool_text_response(
+                id,
+                json!({
+                    "module": response.module,
+                    "hierarchy": response.hierarchy,
+                    "files": response.files,
+                    "symbol_count": response.symbol_count,
+                    "truncated": response.truncated,
+                    "metadata": metadata,
+                }),
+            ),
+            Err(hierarchy::HierarchyError::State(e)) => {
+                let (code, message, data) = map_state_error(&e);
+                tool_error_response(id, code, message, data, metadata)
+            }
+            Err(_) => tool_error_response(
+                id,
+                ProtocolErrorCode::SymbolNotFound,
+                "No symbols were found under the requested module.",
+                Some(json!({
+                    "module": module,
+                    "ref": effective_ref,
+                })),
+                metadata,
+            ),
+        };
+    }
+
     match hierarchy::get_symbol_hierarchy(
         c,
         project_id,
@@ -123,6 +212,253 @@ pub(super) fn handle_get_symbol_hierarchy(params: QueryToolParams<'_>) -> JsonRp
     }
 }
 
+pub(super) fn handle_get_symbol(params: QueryToolParams<'_>) -> JsonRpcResponse {
+    let QueryToolParams {
+        id,
+        arguments,
+        config,
+        schema_status,
+        compatibility_reason,
+        conn,
+        workspace,
+        project_id,
+        ..
+    } = params;
+
+    let stable_id = arguments
+        .get("symbol_stable_id")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .trim();
+    let requested_ref = arguments.get("ref").and_then(|v| v.as_str());
+    let include_body = arguments
+        .get("include_body")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
+
+    if stable_id.is_empty() {
+        return tool_error_response(
+            id,
+            ProtocolErrorCode::InvalidInput,
+            "Parameter `symbol_stable_id` is required.",
+            None,
+            validation_metadata(&effective_ref, schema_status),
+        );
+    }
+
+    let Some(c) = conn else {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    };
+
+    let freshness = check_and_enforce_freshness(
+        id.clone(),
+        arguments,
+        config,
+        conn,
+        workspace,
+        project_id,
+        &effective_ref,
+        schema_status,
+    );
+    if let Some(block) = freshness.block_response {
+        return block;
+    }
+    let mut metadata = freshness.metadata;
+
+    let symbol = match cruxe_state::symbols::get_symbol_with_content_by_stable_id(
+        c,
+        project_id,
+        &effective_ref,
+        stable_id,
+    ) {
+        Ok(Some(symbol)) => symbol,
+        Ok(None) => {
+            return tool_error_response(
+                id,
+                ProtocolErrorCode::SymbolNotFound,
+                format!(
+                    "No symbol with stable id '{}' exists on ref '{}'.",
+                    stable_id, effective_ref
+                ),
+                Some(json!({
+                    "symbol_stable_id": stable_id,
+                    "ref": effective_ref,
+                    "remediation": "The symbol may have been removed or renamed, or the id came from another ref. Locate it again with search_code or locate_symbol.",
+                })),
+                metadata,
+            );
+        }
+        Err(err) => {
+            let (code, message, data) = map_state_error(&err);
+            return tool_error_response(id, code, message, data, metadata);
+        }
+    };
+
+    let parent = symbol.parent_symbol_id.as_deref().and_then(|parent_id| {
+        cruxe_state::symbols::get_symbol_by_id(c, project_id, &effective_ref, parent_id)
+            .ok()
+            .flatten()
+    });
+    let mut content = if include_body { symbol.content } else { None };
+    if let Some(body) = content.as_mut()
+        && body.len() > config.search.max_response_bytes
+    {
+        let mut cut = config.search.max_response_bytes;
+        while !body.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        body.truncate(cut);
+        metadata.result_completeness = cruxe_core::types::ResultCompleteness::Truncated;
+        metadata.safety_limit_applied = Some(true);
+    }
+
+    tool_text_response(
+        id,
+        json!({
+            "symbol": {
+                "symbol_id": symbol.symbol_id,
+                "symbol_stable_id": symbol.symbol_stable_id,
+                "path": symbol.path,
+                "name": symbol.name,
+                "qualified_name": symbol.qualified_name,
+                "kind": symbol.kind.as_str(),
+                "language": symbol.language,
+                "line_start": symbol.line_start,
+                "line_end": symbol.line_end,
+                "byte_start": symbol.byte_start,
+                "byte_end": symbol.byte_end,
+                "signature": symbol.signature,
+                "visibility": symbol.visibility,
+                "parent_symbol_id": symbol.parent_symbol_id,
+                "attributes": symbol.attributes,
+                "doc_comment": symbol.doc_comment,
+                "content": content,
+            },
+            "parent": parent.map(|parent| json!({
+                "symbol_id": parent.symbol_id,
+                "symbol_stable_id": parent.symbol_stable_id,
+                "name": parent.name,
+                "qualified_name": parent.qualified_name,
+                "kind": parent.kind.as_str(),
+                "line_start": parent.line_start,
+                "line_end": parent.line_end,
+            })),
+            "metadata": metadata,
+        }),
+    )
+}
+
+pub(super) fn handle_implementations_of(params: QueryToolParams<'_>) -> JsonRpcResponse {
+    let QueryToolParams {
+        id,
+        arguments,
+        config,
+        schema_status,
+        compatibility_reason,
+        conn,
+        workspace,
+        project_id,
+        ..
+    } = params;
+
+    let symbol_name = arguments
+        .get("symbol_name")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    let path = arguments.get("path").and_then(|v| v.as_str());
+    let requested_ref = arguments.get("ref").and_then(|v| v.as_str());
+    let limit = arguments
+        .get("limit")
+        .and_then(|v| v.as_u64())
+        .map(|v| (v as usize).clamp(1, IMPLEMENTATIONS_OF_MAX_LIMIT))
+        .unwrap_or(IMPLEMENTATIONS_OF_DEFAULT_LIMIT);
+    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
+
+    let freshness = check_and_enforce_freshness(
+        id.clone(),
+        arguments,
+        config,
+        conn,
+        workspace,
+        project_id,
+        &effective_ref,
+        schema_status,
+    );
+    if let Some(block) = freshness.block_response {
+        return block;
+    }
+    let metadata = freshness.metadata;
+
+    if symbol_name.trim().is_empty() {
+        return tool_error_response(
+            id,
+            ProtocolErrorCode::InvalidInput,
+            "Parameter `symbol_name` is required.",
+            None,
+            metadata,
+        );
+    }
+
+    let Some(c) = conn else {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    };
+
+    match implementations::implementations_of(
+        c,
+        workspace,
+        project_id,
+        &effective_ref,
+        symbol_name,
+        path,
+        limit,
+    ) {
+        Ok(result) => tool_text_response(
+            id,
+            json!({
+                "definitions": result.definitions,
+                "implementations": result.implementations,
+                "total_implementations": result.total_implementations,
+                "truncated": result.truncated,
+                "metadata": metadata,
+            }),
+        ),
+        Err(implementations::ImplementationsError::SymbolNotFound) => tool_error_response(
+            id,
+            ProtocolErrorCode::SymbolNotFound,
+            "No method matching the requested name was found.",
+            Some(json!({
+                "symbol_name": symbol_name,
+                "path": path,
+                "ref": effective_ref,
+            })),
+            metadata,
+        ),
+        Err(implementations::ImplementationsError::State(e)) => {
+            let (code, message, data) = map_state_error(&e);
+            tool_error_response(id, code, message, data, metadata)
+        }
+    }
+}
+
 pub(super) fn handle_find_related_symbols(params: QueryToolParams<'_>) -> JsonRpcResponse {
     let QueryToolParams {
         id,
@@ -151,7 +487,8 @@ pub(super) fn handle_find_related_symbols(params: QueryToolParams<'_>) -> JsonRp
         "module" => related::RelatedScope::Module,
         "package" => related::RelatedScope::Package,
         _ => {
-            let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id);
+            let effective_ref =
+                resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
             let metadata = validation_metadata(&effective_ref, schema_status);
             return tool_error_response(
                 id,
@@ -166,7 +503,8 @@ pub(super) fn handle_find_related_symbols(params: QueryToolParams<'_>) -> JsonRp
         .get("limit")
         .and_then(|v| v.as_u64())
         .unwrap_or(20) as usize;
-    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id);
+    let max_breadth = requested_max_breadth(arguments);
+    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
 
     let freshness = check_and_enforce_freshness(
         id.clone(),
@@ -210,21 +548,35 @@ pub(super) fn handle_find_related_symbols(params: QueryToolParams<'_>) -> JsonRp
         c,
         project_id,
         &effective_ref,
-        symbol_name,
-        path,
-        scope,
-        limit,
+        &related::RelatedRequest {
+            symbol_name,
+            path,
+            scope,
+            limit,
+            max_breadth,
+        },
     ) {
-        Ok(response) => tool_text_response(
-            id,
-            json!({
-                "anchor": response.anchor,
-                "related": response.related,
-                "scope_used": response.scope_used,
-                "total_found": response.total_found,
-                "metadata": metadata,
-            }),
-        ),
+        Ok(response) => {
+            let mut metadata = metadata;
+            if let Some(warning) = max_breadth_warning(max_breadth) {
+                metadata.warnings.get_or_insert_with(Vec::new).push(warning);
+            }
+            if response.truncated_edges > 0 {
+                metadata.result_completeness = cruxe_core::types::ResultCompleteness::Truncated;
+            }
+            tool_text_response(
+                id,
+                json!({
+                    "anchor": response.anchor,
+                    "related": response.related,
+                    "scope_used": response.scope_used,
+                    "total_found": response.total_found,
+                    "max_breadth_applied": response.max_breadth_applied,
+                    "truncated_edges": response.truncated_edges,
+                    "metadata": metadata,
+                }),
+            )
+        }
         Err(related::RelatedError::SymbolNotFound) => tool_error_response(
             id,
             ProtocolErrorCode::SymbolNotFound,
@@ -272,7 +624,11 @@ pub(super) fn handle_get_file_outline(params: QueryToolParams<'_>) -> JsonRpcRes
         .get("depth")
         .and_then(|v| v.as_str())
         .unwrap_or("all");
-    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id);
+    let nested = arguments
+        .get("nested")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
     let metadata = build_metadata(
         &effective_ref,
         schema_status,
@@ -363,7 +719,7 @@ pub(super) fn handle_get_file_outline(params: QueryToolParams<'_>) -> JsonRpcRes
                 .map(|s| s.language.clone())
                 .unwrap_or_default();
 
-            let symbols = if top_only {
+            let symbols = if top_only || !nested {
                 flat_symbols
             } else {
                 cruxe_state::symbols::build_symbol_tree(flat_symbols)
@@ -391,3 +747,1292 @@ pub(super) fn handle_get_file_outline(params: QueryToolParams<'_>) -> JsonRpcRes
         }
     }
 }
+
+pub(super) fn handle_export_symbol_names(params: QueryToolParams<'_>) -> JsonRpcResponse {
+    let QueryToolParams {
+        id,
+        arguments,
+        config,
+        schema_status,
+        compatibility_reason,
+        conn,
+        workspace,
+        project_id,
+        ..
+    } = params;
+
+    let requested_ref = arguments.get("ref").and_then(|v| v.as_str());
+    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
+    let base_metadata = validation_metadata(&effective_ref, schema_status);
+
+    let offset = match arguments.get("cursor").and_then(|v| v.as_str()) {
+        None => 0,
+        Some(raw) => match raw.parse::<u64>() {
+            Ok(offset) => offset,
+            Err(_) => {
+                return tool_error_response(
+                    id,
+                    ProtocolErrorCode::InvalidInput,
+                    "Parameter `cursor` must be a `next_cursor` value from a previous page.",
+                    Some(json!({ "cursor": raw })),
+                    base_metadata,
+                );
+            }
+        },
+    };
+    let limit = match arguments.get("limit") {
+        None => EXPORT_SYMBOL_NAMES_DEFAULT_LIMIT,
+        Some(value) => match value.as_u64() {
+            Some(limit) if (1..=EXPORT_SYMBOL_NAMES_MAX_LIMIT as u64).contains(&limit) => {
+                limit as usize
+            }
+            _ => {
+                return tool_error_response(
+                    id,
+                    ProtocolErrorCode::InvalidInput,
+                    format!(
+                        "Parameter `limit` must be an integer between 1 and {}.",
+                        EXPORT_SYMBOL_NAMES_MAX_LIMIT
+                    ),
+                    Some(json!({ "limit": value })),
+                    base_metadata,
+                );
+            }
+        },
+    };
+
+    if schema_status != SchemaStatus::Compatible {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    }
+    let Some(c) = conn else {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    };
+
+    match cruxe_state::branch_state::get_branch_state(c, project_id, &effective_ref) {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return tool_error_response(
+                id,
+                ProtocolErrorCode::RefNotIndexed,
+                "The requested ref has no indexed state yet.",
+                Some(json!({
+                    "ref": effective_ref,
+                    "remediation": "Run sync_repo for this ref before querying.",
+                })),
+                base_metadata,
+            );
+        }
+        Err(err) => {
+            let (code, message, data) = map_state_error(&err);
+            return tool_error_response(id, code, message, data, base_metadata);
+        }
+    }
+
+    let freshness = check_and_enforce_freshness(
+        id.clone(),
+        arguments,
+        config,
+        conn,
+        workspace,
+        project_id,
+        &effective_ref,
+        schema_status,
+    );
+    if let Some(block) = freshness.block_response {
+        return block;
+    }
+    let mut metadata = freshness.metadata;
+
+    let page =
+        cruxe_state::symbols::list_symbol_names_page(c, project_id, &effective_ref, offset, limit)
+            .and_then(|page| {
+                cruxe_state::symbols::symbol_count(c, project_id, &effective_ref)
+                    .map(|total| (page, total))
+            });
+    let (page, total_symbols) = match page {
+        Ok(page) => page,
+        Err(err) => {
+            let (code, message, data) = map_state_error(&err);
+            return tool_error_response(id, code, message, data, metadata);
+        }
+    };
+
+    let fetched = page.len();
+    let rows: Vec<Value> = page
+        .into_iter()
+        .map(|entry| {
+            json!([
+                entry.qualified_name,
+                entry.kind,
+                entry.path,
+                entry.line_start
+            ])
+        })
+        .collect();
+    // Trim to the byte budget; the cursor resumes right after the last row sent.
+    // Always send at least one row so a tiny budget cannot stall paging.
+    let first_row = rows.first().cloned();
+    let (mut rows, safety_limit_applied) =
+        enforce_payload_safety_limit(rows, config.search.max_response_bytes);
+    if rows.is_empty()
+        && let Some(first_row) = first_row
+    {
+        rows.push(first_row);
+    }
+    if safety_limit_applied {
+        metadata.safety_limit_applied = Some(true);
+    }
+    let next_offset = offset + rows.len() as u64;
+    let has_more = (rows.len() < fetched || fetched == limit) && next_offset < total_symbols;
+    if has_more {
+        metadata.result_completeness = cruxe_core::types::ResultCompleteness::Truncated;
+    }
+
+    tool_text_response(
+        id,
+        json!({
+            "ref": effective_ref,
+            "columns": ["qualified_name", "kind", "path", "line_start"],
+            "symbols": rows,
+            "total_symbols": total_symbols,
+            "next_cursor": has_more.then(|| next_offset.to_string()),
+            "metadata": metadata,
+        }),
+    )
+}
+
+pub(super) fn handle_large_symbols(params: QueryToolParams<'_>) -> JsonRpcResponse {
+    let QueryToolParams {
+        id,
+        arguments,
+        config,
+        schema_status,
+        compatibility_reason,
+        conn,
+        workspace,
+        project_id,
+        ..
+    } = params;
+
+    let requested_ref = arguments.get("ref").and_then(|v| v.as_str());
+    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
+    let base_metadata = validation_metadata(&effective_ref, schema_status);
+
+    let path_prefix = arguments
+        .get("path")
+        .and_then(|v| v.as_str())
+        .filter(|path| !path.trim().is_empty());
+    let language = arguments
+        .get("language")
+        .and_then(|v| v.as_str())
+        .filter(|language| !language.trim().is_empty());
+    let sort = match arguments.get("sort").and_then(|v| v.as_str()) {
+        None => cruxe_state::symbols::LargeSymbolSort::Lines,
+        Some(raw) => match cruxe_state::symbols::LargeSymbolSort::parse(raw) {
+            Some(sort) => sort,
+            None => {
+                return tool_error_response(
+                    id,
+                    ProtocolErrorCode::InvalidInput,
+                    "Parameter `sort` must be one of: lines, body_bytes, nesting.",
+                    Some(json!({ "sort": raw })),
+                    base_metadata,
+                );
+            }
+        },
+    };
+    let min_lines = match arguments.get("min_lines") {
+        None => LARGE_SYMBOLS_DEFAULT_MIN_LINES,
+        Some(value) => match value.as_u64() {
+            Some(min_lines) if (1..=u32::MAX as u64).contains(&min_lines) => min_lines as u32,
+            _ => {
+                return tool_error_response(
+                    id,
+                    ProtocolErrorCode::InvalidInput,
+                    "Parameter `min_lines` must be a positive integer.",
+                    Some(json!({ "min_lines": value })),
+                    base_metadata,
+                );
+            }
+        },
+    };
+    let limit = match arguments.get("limit") {
+        None => LARGE_SYMBOLS_DEFAULT_LIMIT,
+        Some(value) => match value.as_u64() {
+            Some(limit) if (1..=LARGE_SYMBOLS_MAX_LIMIT as u64).contains(&limit) => limit as usize,
+            _ => {
+                return tool_error_response(
+                    id,
+                    ProtocolErrorCode::InvalidInput,
+                    format!(
+                        "Parameter `limit` must be an integer between 1 and {}.",
+                        LARGE_SYMBOLS_MAX_LIMIT
+                    ),
+                    Some(json!({ "limit": value })),
+                    base_metadata,
+                );
+            }
+        },
+    };
+
+    if schema_status != SchemaStatus::Compatible {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    }
+    let Some(c) = conn else {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    };
+
+    let freshness = check_and_enforce_freshness(
+        id.clone(),
+        arguments,
+        config,
+        conn,
+        workspace,
+        project_id,
+        &effective_ref,
+        schema_status,
+    );
+    if let Some(block) = freshness.block_response {
+        return block;
+    }
+    let mut metadata = freshness.metadata;
+
+    let entries = match cruxe_state::symbols::list_large_symbols(
+        c,
+        project_id,
+        &effective_ref,
+        cruxe_state::symbols::LargeSymbolQuery {
+            min_lines,
+            path_prefix,
+            language,
+            sort,
+            limit,
+        },
+    ) {
+        Ok(entries) => entries,
+        Err(err) => {
+            let (code, message, data) = map_state_error(&err);
+            return tool_error_response(id, code, message, data, metadata);
+        }
+    };
+
+    let symbols: Vec<Value> = entries
+        .into_iter()
+        .map(|entry| serde_json::to_value(entry).unwrap_or(Value::Null))
+        .collect();
+    let (symbols, safety_limit_applied) =
+        enforce_payload_safety_limit(symbols, config.search.max_response_bytes);
+    if safety_limit_applied {
+        metadata.safety_limit_applied = Some(true);
+        metadata.result_completeness = cruxe_core::types::ResultCompleteness::Truncated;
+    }
+
+    tool_text_response(
+        id,
+        json!({
+            "ref": effective_ref,
+            "min_lines": min_lines,
+            "sort": sort.as_str(),
+            "symbols": symbols,
+            "metadata": metadata,
+        }),
+    )
+}
+
+pub(super) fn handle_find_unreferenced_symbols(params: QueryToolParams<'_>) -> JsonRpcResponse {
+    let QueryToolParams {
+        id,
+        arguments,
+        config,
+        schema_status,
+        compatibility_reason,
+        conn,
+        workspace,
+        project_id,
+        ..
+    } = params;
+
+    let requested_ref = arguments.get("ref").and_then(|v| v.as_str());
+    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
+    let base_metadata = validation_metadata(&effective_ref, schema_status);
+
+    let path_prefix = arguments
+        .get("path")
+        .and_then(|v| v.as_str())
+        .filter(|path| !path.trim().is_empty());
+    let language = arguments
+        .get("language")
+        .and_then(|v| v.as_str())
+        .filter(|language| !language.trim().is_empty());
+    let kind = match arguments
+        .get("kind")
+        .and_then(|v| v.as_str())
+        .filter(|kind| !kind.trim().is_empty())
+    {
+        None => None,
+        Some(raw) => match cruxe_core::types::SymbolKind::parse_kind(raw) {
+            Some(kind) => Some(kind),
+            None => {
+                return tool_error_response(
+                    id,
+                    ProtocolErrorCode::InvalidInput,
+                    "Parameter `kind` is not a known symbol kind.",
+                    Some(json!({ "kind": raw })),
+                    base_metadata,
+                );
+            }
+        },
+    };
+    let limit = match arguments.get("limit") {
+        None => UNREFERENCED_DEFAULT_LIMIT,
+        Some(value) => match value.as_u64() {
+            Some(limit) if (1..=UNREFERENCED_MAX_LIMIT as u64).contains(&limit) => limit as usize,
+            _ => {
+                return tool_error_response(
+                    id,
+                    ProtocolErrorCode::InvalidInput,
+                    format!(
+                        "Parameter `limit` must be an integer between 1 and {}.",
+                        UNREFERENCED_MAX_LIMIT
+                    ),
+                    Some(json!({ "limit": value })),
+                    base_metadata,
+                );
+            }
+        },
+    };
+
+    if schema_status != SchemaStatus::Compatible {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    }
+    let Some(c) = conn else {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    };
+
+    let freshness = check_and_enforce_freshness(
+        id.clone(),
+        arguments,
+        config,
+        conn,
+        workspace,
+        project_id,
+        &effective_ref,
+        schema_status,
+    );
+    if let Some(block) = freshness.block_response {
+        return block;
+    }
+    let mut metadata = freshness.metadata;
+
+    let report = match cruxe_query::unreferenced::find_unreferenced_symbols(
+        c,
+        project_id,
+        &effective_ref,
+        cruxe_query::unreferenced::UnreferencedQuery {
+            language,
+            kind,
+            path_prefix,
+            limit,
+        },
+    ) {
+        Ok(report) => report,
+        Err(err) => {
+            let (code, message, data) = map_state_error(&err);
+            return tool_error_response(id, code, message, data, metadata);
+        }
+    };
+
+    let symbols: Vec<Value> = report
+        .symbols
+        .into_iter()
+        .map(|symbol| serde_json::to_value(symbol).unwrap_or(Value::Null))
+        .collect();
+    let returned = symbols.len();
+    let (symbols, safety_limit_applied) =
+        enforce_payload_safety_limit(symbols, config.search.max_response_bytes);
+    if safety_limit_applied || returned < report.total_unreferenced {
+        metadata.result_completeness = cruxe_core::types::ResultCompleteness::Truncated;
+    }
+    if safety_limit_applied {
+        metadata.safety_limit_applied = Some(true);
+    }
+
+    tool_text_response(
+        id,
+        json!({
+            "ref": effective_ref,
+            "symbols": symbols,
+            "total_unreferenced": report.total_unreferenced,
+            "public_symbols_scanned": report.public_symbols_scanned,
+            "limitations": report.limitations,
+            "metadata": metadata,
+        }),
+    )
+}
+
+pub(super) fn handle_directory_outline(params: QueryToolParams<'_>) -> JsonRpcResponse {
+    let QueryToolParams {
+        id,
+        arguments,
+        config,
+        schema_status,
+        compatibility_reason,
+        conn,
+        workspace,
+        project_id,
+        ..
+    } = params;
+
+    let requested_ref = arguments.get("ref").and_then(|v| v.as_str());
+    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
+    let base_metadata = validation_metadata(&effective_ref, schema_status);
+
+    let Some(path) = arguments
+        .get("path")
+        .and_then(|v| v.as_str())
+        .filter(|path| !path.trim().is_empty())
+    else {
+        return tool_error_response(
+            id,
+            ProtocolErrorCode::InvalidInput,
+            "Parameter `path` is required.",
+            None,
+            base_metadata,
+        );
+    };
+    let mut limits = [
+        ("max_files", DIRECTORY_OUTLINE_DEFAULT_MAX_FILES),
+        (
+            "max_symbols_per_file",
+            DIRECTORY_OUTLINE_DEFAULT_MAX_SYMBOLS,
+        ),
+    ];
+    for (name, limit) in &mut limits {
+        let Some(value) = arguments.get(*name) else {
+            continue;
+        };
+        match value.as_u64() {
+            Some(parsed) if (1..=DIRECTORY_OUTLINE_MAX_LIMIT as u64).contains(&parsed) => {
+                *limit = parsed as usize;
+            }
+            _ => {
+                return tool_error_response(
+                    id,
+                    ProtocolErrorCode::InvalidInput,
+                    format!(
+                        "Parameter `{}` must be an integer between 1 and {}.",
+                        name, DIRECTORY_OUTLINE_MAX_LIMIT
+                    ),
+                    Some(json!({ *name: value })),
+                    base_metadata,
+                );
+            }
+        }
+    }
+    let [(_, max_files), (_, max_symbols_per_file)] = limits;
+
+    if schema_status != SchemaStatus::Compatible {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    }
+    let Some(c) = conn else {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    };
+
+    let freshness = check_and_enforce_freshness(
+        id.clone(),
+        arguments,
+        config,
+        conn,
+        workspace,
+        project_id,
+        &effective_ref,
+        schema_status,
+    );
+    if let Some(block) = freshness.block_response {
+        return block;
+    }
+    let mut metadata = freshness.metadata;
+
+    let outline = match cruxe_state::symbols::list_directory_outline(
+        c,
+        project_id,
+        &effective_ref,
+        cruxe_state::symbols::DirectoryOutlineQuery {
+            path_prefix: path,
+            max_files,
+            max_symbols_per_file,
+        },
+    ) {
+        Ok(outline) => outline,
+        Err(err) => {
+            let (code, message, data) = map_state_error(&err);
+            return tool_error_response(id, code, message, data, metadata);
+        }
+    };
+
+    let files: Vec<Value> = outline
+        .files
+        .into_iter()
+        .map(|file| serde_json::to_value(file).unwrap_or(Value::Null))
+        .collect();
+    let (files, safety_limit_applied) =
+        enforce_payload_safety_limit(files, config.search.max_response_bytes);
+    let symbols_truncated = files
+        .iter()
+        .any(|file| file.get("symbols_truncated").is_some());
+    if safety_limit_applied {
+        metadata.safety_limit_applied = Some(true);
+    }
+    if outline.truncated || safety_limit_applied || symbols_truncated {
+        metadata.result_completeness = cruxe_core::types::ResultCompleteness::Truncated;
+    }
+
+    tool_text_response(
+        id,
+        json!({
+            "path": path,
+            "ref": effective_ref,
+            "file_count": files.len(),
+            "total_files": outline.total_files,
+            "truncated": outline.truncated || safety_limit_applied,
+            "files": files,
+            "metadata": metadata,
+        }),
+    )
+}
+
+pub(super) fn handle_list_skipped_files(params: QueryToolParams<'_>) -> JsonRpcResponse {
+    let QueryToolParams {
+        id,
+        arguments,
+        config,
+        schema_status,
+        compatibility_reason,
+        conn,
+        workspace,
+        project_id,
+        ..
+    } = params;
+
+    let requested_ref = arguments.get("ref").and_then(|v| v.as_str());
+    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
+    let base_metadata = validation_metadata(&effective_ref, schema_status);
+
+    let reason = match arguments.get("reason").and_then(|v| v.as_str()) {
+        None => None,
+        Some(raw) => match cruxe_core::types::SkipReason::parse(raw) {
+            Some(reason) => Some(reason),
+            None => {
+                let allowed: Vec<&str> = cruxe_core::types::SkipReason::ALL
+                    .iter()
+                    .map(|reason| reason.as_str())
+                    .collect();
+                return tool_error_response(
+                    id,
+                    ProtocolErrorCode::InvalidInput,
+                    format!("Parameter `reason` must be one of: {}.", allowed.join(", ")),
+                    Some(json!({ "reason": raw })),
+                    base_metadata,
+                );
+            }
+        },
+    };
+    let offset = match arguments.get("cursor").and_then(|v| v.as_str()) {
+        None => 0,
+        Some(raw) => match raw.parse::<u64>() {
+            Ok(offset) => offset,
+            Err(_) => {
+                return tool_error_response(
+                    id,
+                    ProtocolErrorCode::InvalidInput,
+                    "Parameter `cursor` must be a `next_cursor` value from a previous page.",
+                    Some(json!({ "cursor": raw })),
+                    base_metadata,
+                );
+            }
+        },
+    };
+    let limit = match arguments.get("limit") {
+        None => LIST_SKIPPED_FILES_DEFAULT_LIMIT,
+        Some(value) => match value.as_u64() {
+            Some(limit) if (1..=LIST_SKIPPED_FILES_MAX_LIMIT as u64).contains(&limit) => {
+                limit as usize
+            }
+            _ => {
+                return tool_error_response(
+                    id,
+                    ProtocolErrorCode::InvalidInput,
+                    format!(
+                        "Parameter `limit` must be an integer between 1 and {}.",
+                        LIST_SKIPPED_FILES_MAX_LIMIT
+                    ),
+                    Some(json!({ "limit": value })),
+                    base_metadata,
+                );
+            }
+        },
+    };
+
+    if schema_status != SchemaStatus::Compatible {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    }
+    let Some(c) = conn else {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    };
+
+    match cruxe_state::branch_state::get_branch_state(c, project_id, &effective_ref) {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return tool_error_response(
+                id,
+                ProtocolErrorCode::RefNotIndexed,
+                "The requested ref has no indexed state yet.",
+                Some(json!({
+                    "ref": effective_ref,
+                    "remediation": "Run index_repo or sync_repo for this ref before querying.",
+                })),
+                base_metadata,
+            );
+        }
+        Err(err) => {
+            let (code, message, data) = map_state_error(&err);
+            return tool_error_response(id, code, message, data, base_metadata);
+        }
+    }
+
+    let page = match cruxe_state::skipped_files::list_for_ref(
+        c,
+        project_id,
+        &effective_ref,
+        reason.map(|reason| reason.as_str()),
+        offset,
+        limit,
+    ) {
+        Ok(page) => page,
+        Err(err) => {
+            let (code, message, data) = map_state_error(&err);
+            return tool_error_response(id, code, message, data, base_metadata);
+        }
+    };
+
+    let mut metadata = base_metadata;
+    let next_offset = offset + page.files.len() as u64;
+    let has_more = next_offset < page.total;
+    if has_more {
+        metadata.result_completeness = cruxe_core::types::ResultCompleteness::Truncated;
+    }
+
+    tool_text_response(
+        id,
+        json!({
+            "ref": effective_ref,
+            "job_id": page.job_id,
+            "reason": reason.map(|reason| reason.as_str()),
+            "total_skipped": page.total,
+            "skipped_files": page.files,
+            "next_cursor": has_more.then(|| next_offset.to_string()),
+            "metadata": metadata,
+        }),
+    )
+}
+
+pub(super) fn handle_list_files(params: QueryToolParams<'_>) -> JsonRpcResponse {
+    let QueryToolParams {
+        id,
+        arguments,
+        config,
+        schema_status,
+        compatibility_reason,
+        conn,
+        workspace,
+        project_id,
+        ..
+    } = params;
+
+    let requested_ref = arguments.get("ref").and_then(|v| v.as_str());
+    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
+    let base_metadata = validation_metadata(&effective_ref, schema_status);
+
+    let language = arguments
+        .get("language")
+        .and_then(|v| v.as_str())
+        .map(|raw| raw.trim().to_ascii_lowercase())
+        .filter(|language| !language.is_empty());
+    let path_prefix = arguments
+        .get("path_prefix")
+        .and_then(|v| v.as_str())
+        .map(|raw| raw.trim().trim_start_matches("./"))
+        .filter(|prefix| !prefix.is_empty());
+    let limit = match arguments.get("limit") {
+        None => LIST_FILES_DEFAULT_LIMIT,
+        Some(value) => match value.as_u64() {
+            Some(limit) if (1..=LIST_FILES_MAX_LIMIT as u64).contains(&limit) => limit as usize,
+            _ => {
+                return tool_error_response(
+                    id,
+                    ProtocolErrorCode::InvalidInput,
+                    format!(
+                        "Parameter `limit` must be an integer between 1 and {}.",
+                        LIST_FILES_MAX_LIMIT
+                    ),
+                    Some(json!({ "limit": value })),
+                    base_metadata,
+                );
+            }
+        },
+    };
+
+    if schema_status != SchemaStatus::Compatible {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    }
+    let Some(c) = conn else {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    };
+
+    match cruxe_state::branch_state::get_branch_state(c, project_id, &effective_ref) {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return tool_error_response(
+                id,
+                ProtocolErrorCode::RefNotIndexed,
+                "The requested ref has no indexed state yet.",
+                Some(json!({
+                    "ref": effective_ref,
+                    "remediation": "Run index_repo or sync_repo for this ref before querying.",
+                })),
+                base_metadata,
+            );
+        }
+        Err(err) => {
+            let (code, message, data) = map_state_error(&err);
+            return tool_error_response(id, code, message, data, base_metadata);
+        }
+    }
+
+    let page = match cruxe_state::manifest::list_files(
+        c,
+        project_id,
+        &effective_ref,
+        language.as_deref(),
+        path_prefix,
+        limit,
+    ) {
+        Ok(page) => page,
+        Err(err) => {
+            let (code, message, data) = map_state_error(&err);
+            return tool_error_response(id, code, message, data, base_metadata);
+        }
+    };
+
+    let mut metadata = base_metadata;
+    if (page.files.len() as u64) < page.total {
+        metadata.result_completeness = cruxe_core::types::ResultCompleteness::Truncated;
+    }
+
+    tool_text_response(
+        id,
+        json!({
+            "ref": effective_ref,
+            "language": language,
+            "path_prefix": path_prefix,
+            "total_files": page.total,
+            "files": page.files,
+            "metadata": metadata,
+        }),
+    )
+}
+
+pub(super) fn handle_symbol_at_offset(params: QueryToolParams<'_>) -> JsonRpcResponse {
+    let QueryToolParams {
+        id,
+        arguments,
+        config,
+        schema_status,
+        compatibility_reason,
+        conn,
+        workspace,
+        project_id,
+        ..
+    } = params;
+
+    let path = arguments.get("path").and_then(|v| v.as_str()).unwrap_or("");
+    let requested_ref = arguments.get("ref").and_then(|v| v.as_str());
+    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
+    let base_metadata = validation_metadata(&effective_ref, schema_status);
+
+    if path.trim().is_empty() {
+        return tool_error_response(
+            id,
+            ProtocolErrorCode::InvalidInput,
+            "Parameter `path` is required.",
+            None,
+            base_metadata,
+        );
+    }
+    let byte_offset = match arguments.get("byte_offset").map(|v| v.as_u64()) {
+        Some(Some(offset)) if offset <= u32::MAX as u64 => offset as u32,
+        _ => {
+            return tool_error_response(
+                id,
+                ProtocolErrorCode::InvalidInput,
+                "Parameter `byte_offset` must be a non-negative integer.",
+                arguments
+                    .get("byte_offset")
+                    .map(|value| json!({ "byte_offset": value })),
+                base_metadata,
+            );
+        }
+    };
+
+    if schema_status != SchemaStatus::Compatible {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    }
+    let Some(c) = conn else {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    };
+
+    let freshness = check_and_enforce_freshness(
+        id.clone(),
+        arguments,
+        config,
+        conn,
+        workspace,
+        project_id,
+        &effective_ref,
+        schema_status,
+    );
+    if let Some(block) = freshness.block_response {
+        return block;
+    }
+    let metadata = freshness.metadata;
+
+    match cruxe_state::symbols::find_innermost_symbol_at_offset(
+        c,
+        project_id,
+        &effective_ref,
+        path,
+        byte_offset,
+    ) {
+        Ok(Some(symbol)) => {
+            recent_symbols::record(
+                workspace,
+                project_id,
+                &effective_ref,
+                "symbol_at_offset",
+                [recent_symbols::ReturnedSymbol {
+                    symbol_id: &symbol.symbol_id,
+                    symbol_stable_id: Some(&symbol.symbol_stable_id),
+                    name: &symbol.name,
+                    qualified_name: &symbol.qualified_name,
+                    kind: symbol.kind.as_str(),
+                    path: &symbol.path,
+                    line_start: symbol.line_start,
+                    line_end: symbol.line_end,
+                }],
+            );
+            tool_text_response(
+                id,
+                json!({
+                    "path": path,
+                    "byte_offset": byte_offset,
+                    "symbol": {
+                        "symbol_id": symbol.symbol_id,
+                        "symbol_stable_id": symbol.symbol_stable_id,
+                        "name": symbol.name,
+                        "qualified_name": symbol.qualified_name,
+                        "kind": symbol.kind.as_str(),
+                        "language": symbol.language,
+                        "line_start": symbol.line_start,
+                        "line_end": symbol.line_end,
+                        "byte_start": symbol.byte_start,
+                        "byte_end": symbol.byte_end,
+                        "signature": symbol.signature,
+                        "parent_symbol_id": symbol.parent_symbol_id,
+                        "visibility": symbol.visibility,
+                        "attributes": symbol.attributes,
+                    },
+                    "metadata": metadata,
+                }),
+            )
+        }
+        Ok(None) => {
+            let file_exists =
+                cruxe_state::manifest::get_content_hash(c, project_id, &effective_ref, path)
+                    .ok()
+                    .flatten()
+                    .is_some();
+            if file_exists {
+                tool_error_response(
+                    id,
+                    ProtocolErrorCode::SymbolNotFound,
+                    format!(
+                        "No symbol contains byte offset {} in '{}' on ref '{}'.",
+                        byte_offset, path, effective_ref
+                    ),
+                    Some(json!({
+                        "path": path,
+                        "byte_offset": byte_offset,
+                        "ref": effective_ref,
+                        "remediation": "The offset may fall outside any definition. Files indexed before byte ranges were recorded need a re-index.",
+                    })),
+                    metadata,
+                )
+            } else {
+                tool_error_response(
+                    id,
+                    ProtocolErrorCode::FileNotFound,
+                    format!("File '{}' is not indexed on ref '{}'.", path, effective_ref),
+                    Some(json!({
+                        "path": path,
+                        "ref": effective_ref,
+                        "remediation": "Verify the file path and ensure the project is indexed.",
+                    })),
+                    metadata,
+                )
+            }
+        }
+        Err(err) => {
+            let (code, message, data) = map_state_error(&err);
+            tool_error_response(id, code, message, data, metadata)
+        }
+    }
+}
+
+pub(super) fn handle_symbol_at_location(params: QueryToolParams<'_>) -> JsonRpcResponse {
+    let QueryToolParams {
+        id,
+        arguments,
+        config,
+        schema_status,
+        compatibility_reason,
+        conn,
+        workspace,
+        project_id,
+        ..
+    } = params;
+
+    let path = arguments.get("path").and_then(|v| v.as_str()).unwrap_or("");
+    let requested_ref = arguments.get("ref").and_then(|v| v.as_str());
+    let effective_ref = resolve_tool_ref(requested_ref, workspace, conn, project_id, config);
+    let base_metadata = validation_metadata(&effective_ref, schema_status);
+
+    if path.trim().is_empty() {
+        return tool_error_response(
+            id,
+            ProtocolErrorCode::InvalidInput,
+            "Parameter `path` is required.",
+            None,
+            base_metadata,
+        );
+    }
+    let line = match arguments.get("line").map(|v| v.as_u64()) {
+        Some(Some(line)) if (1..=u32::MAX as u64).contains(&line) => line as u32,
+        _ => {
+            return tool_error_response(
+                id,
+                ProtocolErrorCode::InvalidInput,
+                "Parameter `line` must be a positive integer.",
+                arguments.get("line").map(|value| json!({ "line": value })),
+                base_metadata,
+            );
+        }
+    };
+
+    if schema_status != SchemaStatus::Compatible {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    }
+    let Some(c) = conn else {
+        return tool_compatibility_error(ToolCompatibilityParams {
+            id,
+            schema_status,
+            compatibility_reason,
+            config,
+            conn,
+            workspace,
+            project_id,
+            ref_name: &effective_ref,
+        });
+    };
+
+    let freshness = check_and_enforce_freshness(
+        id.clone(),
+        arguments,
+        config,
+        conn,
+        workspace,
+        project_id,
+        &effective_ref,
+        schema_status,
+    );
+    if let Some(block) = freshness.block_response {
+        return block;
+    }
+    let metadata = freshness.metadata;
+
+    let symbol = match cruxe_state::symbols::find_innermost_symbol_at_line(
+        c,
+        project_id,
+        &effective_ref,
+        path,
+        line,
+    ) {
+        Ok(symbol) => symbol,
+        Err(err) => {
+            let (code, message, data) = map_state_error(&err);
+            return tool_error_response(id, code, message, data, metadata);
+        }
+    };
+    let Some(symbol) = symbol else {
+        // Not an error: a frame outside any definition (or in an unindexed
+        // file) is a normal answer for stack trace lookups.
+        let file_indexed =
+            cruxe_state::manifest::get_content_hash(c, project_id, &effective_ref, path)
+                .ok()
+                .flatten()
+                .is_some();
+        let message = if file_indexed {
+            format!(
+                "No symbol encloses line {} in '{}' on ref '{}'.",
+                line, path, effective_ref
+            )
+        } else {
+            format!("File '{}' is not indexed on ref '{}'.", path, effective_ref)
+        };
+        return tool_text_response(
+            id,
+            json!({
+                "path": path,
+                "line": line,
+                "file_indexed": file_indexed,
+                "symbol": null,
+                "parents": [],
+                "message": message,
+                "metadata": metadata,
+            }),
+        );
+    };
+
+    recent_symbols::record(
+        workspace,
+        project_id,
+        &effective_ref,
+        "symbol_at_location",
+        [recent_symbols::ReturnedSymbol {
+            symbol_id: &symbol.symbol_id,
+            symbol_stable_id: Some(&symbol.symbol_stable_id),
+            name: &symbol.name,
+            qualified_name: &symbol.qualified_name,
+            kind: symbol.kind.as_str(),
+            path: &symbol.path,
+            line_start: symbol.line_start,
+            line_end: symbol.line_end,
+        }],
+    );
+    let symbol_payload = json!({
+        "symbol_id": symbol.symbol_id,
+        "symbol_stable_id": symbol.symbol_stable_id,
+        "name": symbol.name,
+        "qualified_name": symbol.qualified_name,
+        "kind": symbol.kind.as_str(),
+        "language": symbol.language,
+        "line_start": symbol.line_start,
+        "line_end": symbol.line_end,
+        "signature": symbol.signature,
+        "parent_symbol_id": symbol.parent_symbol_id,
+        "visibility": symbol.visibility,
+    });
+    // The chain starts with the symbol itself; parents are listed nearest first.
+    let parents = match hierarchy::ancestor_chain(c, project_id, &effective_ref, symbol) {
+        Ok(chain) => chain.into_iter().skip(1).collect::<Vec<_>>(),
+        Err(err) => {
+            let (code, message, data) = map_state_error(&err);
+            return tool_error_response(id, code, message, data, metadata);
+        }
+    };
+    tool_text_response(
+        id,
+        json!({
+            "path": path,
+            "line": line,
+            "file_indexed": true,
+            "symbol": symbol_payload,
+            "parents": parents,
+            "metadata": metadata,
+        }),
+    )
+}
+
+pub(super) fn handle_recent_symbols(params: QueryToolParams<'_>) -> JsonRpcResponse {
+    let QueryToolParams {
+        id,
+        arguments,
+        config,
+        schema_status,
+        conn,
+        workspace,
+        project_id,
+        ..
+    } = params;
+
+    let ref_filter = arguments.get("ref").and_then(|v| v.as_str());
+    let limit = arguments
+        .get("limit")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(RECENT_SYMBOLS_DEFAULT_LIMIT as u64)
+        .clamp(1, RECENT_SYMBOLS_MAX_LIMIT as u64) as usize;
+    let clear = arguments
+        .get("clear")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let effective_ref = resolve_tool_ref(ref_filter, workspace, conn, project_id, config);
+    let metadata = validation_metadata(&effective_ref, schema_status);
+
+    let symbols = recent_symbols::list(workspace, project_id, ref_filter, limit);
+    if clear {
+        recent_symbols::clear(workspace, project_id);
+    }
+    tool_text_response(
+        id,
+        json!({
+            "symbols": symbols,
+            "total": symbols.len(),
+            "cleared": clear,
+            "metadata": metadata,
+        }),
+    )
+}
@@ -20,7 +20,7 @@ pub fn definition() -> ToolDefinition {
                 },
                 "ref": {
                     "type": "string",
-                    "description": "Branch/ref scope."
+                    "description": "Branch/ref scope. `HEAD`, `head` or `current` means the checked-out branch; `default` means the project default ref."
                 },
                 "language": {
                     "type": "string",
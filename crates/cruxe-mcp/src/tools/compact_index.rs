@@ -0,0 +1,19 @@
+use super::ToolDefinition;
+use serde_json::json;
+
+pub fn definition() -> ToolDefinition {
+    ToolDefinition {
+        name: "compact_index".into(),
+        description: "Reclaim space left by incremental re-indexing: prune rows and documents for files no longer in the manifest, merge index segments and vacuum SQLite. Reports bytes reclaimed."
+            .into(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "workspace": {
+                    "type": "string",
+                    "description": "Absolute path to target workspace. Default: server's default project."
+                }
+            }
+        }),
+    }
+}
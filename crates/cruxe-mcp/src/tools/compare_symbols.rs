@@ -0,0 +1,47 @@
+use super::ToolDefinition;
+use serde_json::json;
+
+pub fn definition() -> ToolDefinition {
+    ToolDefinition {
+        name: "compare_symbols".into(),
+        description:
+            "Diff the stored bodies of two symbols and report a similarity score (optionally normalized)."
+                .into(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "workspace": {
+                    "type": "string",
+                    "description": "Absolute path to target workspace. Default: server's default project."
+                },
+                "left_symbol_stable_id": {
+                    "type": "string",
+                    "description": "Stable id of the first symbol."
+                },
+                "right_symbol_stable_id": {
+                    "type": "string",
+                    "description": "Stable id of the second symbol."
+                },
+                "ref": {
+                    "type": "string",
+                    "description": "Branch/ref scope. `HEAD`, `head` or `current` means the checked-out branch; `default` means the project default ref."
+                },
+                "normalize": {
+                    "type": "string",
+                    "enum": ["none", "whitespace", "identifiers"],
+                    "description": "Body normalization before diffing. `identifiers` also masks non-keyword identifiers. Default: whitespace."
+                },
+                "similarity_only": {
+                    "type": "boolean",
+                    "description": "Return only the similarity summary without diff text. Default: false."
+                },
+                "freshness_policy": {
+                    "type": "string",
+                    "description": "Freshness behavior: \"strict\", \"balanced\" (default), \"best_effort\"",
+                    "enum": ["strict", "balanced", "best_effort"]
+                }
+            },
+            "required": ["left_symbol_stable_id", "right_symbol_stable_id"]
+        }),
+    }
+}
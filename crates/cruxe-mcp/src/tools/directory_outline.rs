@@ -0,0 +1,39 @@
+use super::ToolDefinition;
+use serde_json::json;
+
+pub fn definition() -> ToolDefinition {
+    ToolDefinition {
+        name: "directory_outline".into(),
+        description: "Return the top-level symbols of every indexed file under a directory, grouped by file and sorted by path. A package-wide `get_file_outline` for getting oriented in a subtree.".into(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "workspace": {
+                    "type": "string",
+                    "description": "Absolute path to target workspace. Default: server's default project."
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Directory path relative to repo root (`.` for the whole repo). Matches whole path segments."
+                },
+                "ref": {
+                    "type": "string",
+                    "description": "Branch/ref scope. `HEAD`, `head` or `current` means the checked-out branch; `default` means the project default ref."
+                },
+                "max_files": {
+                    "type": "integer",
+                    "description": "Maximum files to return, in path order (default: 50, max: 500).",
+                    "minimum": 1,
+                    "maximum": 500
+                },
+                "max_symbols_per_file": {
+                    "type": "integer",
+                    "description": "Maximum top-level symbols listed per file (default: 50, max: 500).",
+                    "minimum": 1,
+                    "maximum": 500
+                }
+            },
+            "required": ["path"]
+        }),
+    }
+}
@@ -0,0 +1,28 @@
+use super::ToolDefinition;
+use serde_json::json;
+
+pub fn definition() -> ToolDefinition {
+    ToolDefinition {
+        name: "drop_ref".into(),
+        description: "Delete all index data for a ref (e.g. a branch deleted upstream): symbols, manifest entries, call/import edges, branch state, tombstones, index documents and its overlay. Reports removed record counts. Refuses the project's default ref unless `force` is set."
+            .into(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "workspace": {
+                    "type": "string",
+                    "description": "Absolute path to target workspace. Default: server's default project."
+                },
+                "ref": {
+                    "type": "string",
+                    "description": "Ref whose index data to delete."
+                },
+                "force": {
+                    "type": "boolean",
+                    "description": "Allow dropping the project's default ref. Default: false."
+                }
+            },
+            "required": ["ref"]
+        }),
+    }
+}
@@ -0,0 +1,37 @@
+use super::ToolDefinition;
+use serde_json::json;
+
+pub fn definition() -> ToolDefinition {
+    ToolDefinition {
+        name: "export_symbol_names".into(),
+        description: "Export every (qualified_name, kind, path, line_start) tuple for a ref in compact, paged form for client-side fuzzy finders.".into(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "workspace": {
+                    "type": "string",
+                    "description": "Absolute path to target workspace. Default: server's default project."
+                },
+                "ref": {
+                    "type": "string",
+                    "description": "Branch/ref scope. `HEAD`, `head` or `current` means the checked-out branch; `default` means the project default ref."
+                },
+                "cursor": {
+                    "type": "string",
+                    "description": "Opaque `next_cursor` from a previous page. Omit to start from the beginning."
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum tuples per page (default: 1000, max: 10000). Pages are also trimmed to the response byte budget.",
+                    "minimum": 1,
+                    "maximum": 10000
+                },
+                "freshness_policy": {
+                    "type": "string",
+                    "description": "Freshness behavior: \"strict\", \"balanced\" (default), \"best_effort\"",
+                    "enum": ["strict", "balanced", "best_effort"]
+                }
+            }
+        }),
+    }
+}
@@ -4,7 +4,10 @@ use serde_json::json;
 pub fn definition() -> ToolDefinition {
     ToolDefinition {
         name: "find_references".into(),
-        description: "Find symbol references using relation graph edges.".into(),
+        description: "Find symbol references using relation graph edges, optionally \
+                      including every line that mentions the symbol name. Each result \
+                      carries a `kind` of `definition` or `usage`."
+            .into(),
         input_schema: json!({
             "type": "object",
             "properties": {
@@ -14,11 +17,15 @@ pub fn definition() -> ToolDefinition {
                 },
                 "symbol_name": {
                     "type": "string",
-                    "description": "Name or qualified name of the symbol."
+                    "description": "Name or qualified name of the symbol. Either `symbol_name` or `symbol_stable_id` is required."
+                },
+                "symbol_stable_id": {
+                    "type": "string",
+                    "description": "Stable id of the symbol, as returned by locate_symbol or search_code."
                 },
                 "ref": {
                     "type": "string",
-                    "description": "Branch/ref scope."
+                    "description": "Branch/ref scope. `HEAD`, `head` or `current` means the checked-out branch; `default` means the project default ref."
                 },
                 "kind": {
                     "type": "string",
@@ -28,9 +35,12 @@ pub fn definition() -> ToolDefinition {
                 "limit": {
                     "type": "integer",
                     "description": "Max references to return (default: 20)."
+                },
+                "include_name_matches": {
+                    "type": "boolean",
+                    "description": "Also return each line of a symbol body in the ref that mentions the symbol name as a whole word (edge_type `name_match`). Ignored when `kind` is set. Default: false."
                 }
-            },
-            "required": ["symbol_name"]
+            }
         }),
     }
 }
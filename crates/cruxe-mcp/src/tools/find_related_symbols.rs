@@ -23,7 +23,7 @@ pub fn definition() -> ToolDefinition {
                 },
                 "ref": {
                     "type": "string",
-                    "description": "Branch/ref scope"
+                    "description": "Branch/ref scope. `HEAD`, `head` or `current` means the checked-out branch; `default` means the project default ref."
                 },
                 "scope": {
                     "type": "string",
@@ -35,6 +35,11 @@ pub fn definition() -> ToolDefinition {
                     "type": "integer",
                     "description": "Max related symbols (default: 20)",
                     "default": 20
+                },
+                "max_breadth": {
+                    "type": "integer",
+                    "description": "Max import edges followed from any single node (1-1000, default: 50). Skipped edges are counted in truncated_edges.",
+                    "default": 50
                 }
             },
             "required": ["symbol_name"]
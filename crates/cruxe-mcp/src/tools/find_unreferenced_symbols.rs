@@ -0,0 +1,40 @@
+use super::ToolDefinition;
+use serde_json::json;
+
+pub fn definition() -> ToolDefinition {
+    ToolDefinition {
+        name: "find_unreferenced_symbols".into(),
+        description: "List public symbols with no inbound import or call edges and no name references within the indexed ref, as dead-code candidates. Approximate: dynamic dispatch, reflection and macros are invisible; the response lists the limitations.".into(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "workspace": {
+                    "type": "string",
+                    "description": "Absolute path to target workspace. Default: server's default project."
+                },
+                "ref": {
+                    "type": "string",
+                    "description": "Branch/ref scope. `HEAD`, `head` or `current` means the checked-out branch; `default` means the project default ref."
+                },
+                "language": {
+                    "type": "string",
+                    "description": "Only include symbols in this language (e.g. `rust`, `python`)."
+                },
+                "kind": {
+                    "type": "string",
+                    "description": "Only include this kind (fn, struct, class, method, etc.). Default: every kind except fields and modules."
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Only include symbols whose file path starts with this prefix."
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum symbols to return (default: 50, max: 500).",
+                    "minimum": 1,
+                    "maximum": 500
+                }
+            }
+        }),
+    }
+}
@@ -22,7 +22,7 @@ pub fn definition() -> ToolDefinition {
                 },
                 "ref": {
                     "type": "string",
-                    "description": "Branch/ref scope."
+                    "description": "Branch/ref scope. `HEAD`, `head` or `current` means the checked-out branch; `default` means the project default ref."
                 },
                 "direction": {
                     "type": "string",
@@ -36,6 +36,10 @@ pub fn definition() -> ToolDefinition {
                 "limit": {
                     "type": "integer",
                     "description": "Max edges returned per direction (default: 20)."
+                },
+                "max_breadth": {
+                    "type": "integer",
+                    "description": "Max edges expanded from any single node (1-1000, default: 50). Skipped edges are counted in truncated_edges."
                 }
             },
             "required": ["symbol_name"]
@@ -5,7 +5,7 @@ pub fn definition() -> ToolDefinition {
     ToolDefinition {
         name: "get_code_context".into(),
         description:
-            "Retrieve code context fitted to a token budget using breadth/depth strategies.".into(),
+            "Retrieve code context fitted to a token budget using breadth/depth strategies, the bodies of a batch of symbol `ids`, or a numbered line window around `path`:`line`.".into(),
         input_schema: json!({
             "type": "object",
             "properties": {
@@ -15,7 +15,30 @@ pub fn definition() -> ToolDefinition {
                 },
                 "query": {
                     "type": "string",
-                    "description": "Search query for relevant code context"
+                    "description": "Search query for relevant code context. Required unless `ids` or `line` is given."
+                },
+                "ids": {
+                    "type": "array",
+                    "description": "Batch mode: symbol ids (`symbol_stable_id`, or ref-local `symbol_id`) whose bodies are returned in this order, as with the depth strategy. Replaces `query`; unknown ids are listed in `metadata.missing_ids`.",
+                    "items": { "type": "string" },
+                    "minItems": 1,
+                    "maxItems": 100
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Workspace-relative file path for line-window mode (required with `line`)."
+                },
+                "line": {
+                    "type": "integer",
+                    "description": "1-based line to center a window on. Switches to line-window mode: returns numbered lines from `path` instead of query-driven context.",
+                    "minimum": 1
+                },
+                "context_lines": {
+                    "type": "integer",
+                    "description": "Lines shown before and after `line` in line-window mode (default: 10, max: 500).",
+                    "default": 10,
+                    "minimum": 0,
+                    "maximum": 500
                 },
                 "max_tokens": {
                     "type": "integer",
@@ -30,19 +53,37 @@ pub fn definition() -> ToolDefinition {
                 },
                 "ref": {
                     "type": "string",
-                    "description": "Branch/ref scope"
+                    "description": "Branch/ref scope. `HEAD`, `head` or `current` means the checked-out branch; `default` means the project default ref."
                 },
                 "language": {
                     "type": "string",
                     "description": "Language filter"
                 },
+                "with_line_numbers": {
+                    "type": "boolean",
+                    "description": "Prefix each returned body line with its file line number, e.g. `  42| fn foo() {` (depth strategy only; default: false keeps bodies copyable).",
+                    "default": false
+                },
+                "prefix_lines": {
+                    "type": "integer",
+                    "description": "Extra lines (imports, leading comments) returned before each symbol body, clamped to the file (depth strategy only; default: 0, max: 500).",
+                    "default": 0,
+                    "minimum": 0,
+                    "maximum": 500
+                },
+                "suffix_lines": {
+                    "type": "integer",
+                    "description": "Extra lines returned after each symbol body, clamped to the file (depth strategy only; default: 0, max: 500).",
+                    "default": 0,
+                    "minimum": 0,
+                    "maximum": 500
+                },
                 "policy_mode": {
                     "type": "string",
                     "description": "Optional retrieval policy override when allowed by runtime policy config.",
                     "enum": ["strict", "balanced", "off", "audit_only"]
                 }
-            },
-            "required": ["query"]
+            }
         }),
     }
 }
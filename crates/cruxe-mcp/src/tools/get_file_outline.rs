@@ -18,13 +18,17 @@ pub fn definition() -> ToolDefinition {
                 },
                 "ref": {
                     "type": "string",
-                    "description": "Branch/ref scope"
+                    "description": "Branch/ref scope. `HEAD`, `head` or `current` means the checked-out branch; `default` means the project default ref."
                 },
                 "depth": {
                     "type": "string",
                     "description": "\"top\" (top-level only) or \"all\" (nested). Default: \"all\"",
                     "enum": ["top", "all"]
                 },
+                "nested": {
+                    "type": "boolean",
+                    "description": "Nest symbols under their parent (methods under their class, inner functions under their function); false returns every symbol as a flat list in line order with `parent_symbol_id`. Default: true"
+                },
                 "language": {
                     "type": "string",
                     "description": "Filter hint (informational)"
@@ -0,0 +1,43 @@
+use super::ToolDefinition;
+use serde_json::json;
+
+pub fn definition() -> ToolDefinition {
+    ToolDefinition {
+        name: "get_import_graph".into(),
+        description: "Return files importing (or imported by) a symbol or file, following import edges up to a bounded depth.".into(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "workspace": {
+                    "type": "string",
+                    "description": "Absolute path to target workspace. Default: server's default project."
+                },
+                "symbol_name": {
+                    "type": "string",
+                    "description": "Name (or qualified name) of the symbol to start from. Either `symbol_name` or `path` is required."
+                },
+                "path": {
+                    "type": "string",
+                    "description": "File to start from, or a file path disambiguating `symbol_name`."
+                },
+                "ref": {
+                    "type": "string",
+                    "description": "Branch/ref scope. `HEAD`, `head` or `current` means the checked-out branch; `default` means the project default ref."
+                },
+                "direction": {
+                    "type": "string",
+                    "enum": ["importers", "imports", "both"],
+                    "description": "Traversal direction. Default: importers."
+                },
+                "depth": {
+                    "type": "integer",
+                    "description": "Traversal depth (1-5). Values above 5 are clamped."
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Max edges returned per direction (default: 50)."
+                }
+            }
+        }),
+    }
+}
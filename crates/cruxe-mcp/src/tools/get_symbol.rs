@@ -0,0 +1,36 @@
+use super::ToolDefinition;
+use serde_json::json;
+
+pub fn definition() -> ToolDefinition {
+    ToolDefinition {
+        name: "get_symbol".into(),
+        description: "Fetch one symbol by `symbol_stable_id`: signature, visibility, parent, line range and stored body. Re-reads a symbol from an earlier result without searching again.".into(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "workspace": {
+                    "type": "string",
+                    "description": "Absolute path to target workspace. Default: server's default project."
+                },
+                "symbol_stable_id": {
+                    "type": "string",
+                    "description": "Stable id from a previous result (search_code, locate_symbol, ...)."
+                },
+                "ref": {
+                    "type": "string",
+                    "description": "Branch/ref scope. `HEAD`, `head` or `current` means the checked-out branch; `default` means the project default ref."
+                },
+                "include_body": {
+                    "type": "boolean",
+                    "description": "Include the stored symbol body as `content`. Default: true"
+                },
+                "freshness_policy": {
+                    "type": "string",
+                    "description": "Freshness behavior: \"strict\", \"balanced\" (default), \"best_effort\"",
+                    "enum": ["strict", "balanced", "best_effort"]
+                }
+            },
+            "required": ["symbol_stable_id"]
+        }),
+    }
+}
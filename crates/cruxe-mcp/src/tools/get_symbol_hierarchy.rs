@@ -5,7 +5,7 @@ pub fn definition() -> ToolDefinition {
     ToolDefinition {
         name: "get_symbol_hierarchy".into(),
         description:
-            "Traverse the parent chain (ancestors) or child tree (descendants) for a symbol.".into(),
+            "Traverse the parent chain (ancestors) or child tree (descendants) for a symbol, or nest every symbol of a module path (e.g. `crate::auth`) across its files.".into(),
         input_schema: json!({
             "type": "object",
             "properties": {
@@ -15,7 +15,17 @@ pub fn definition() -> ToolDefinition {
                 },
                 "symbol_name": {
                     "type": "string",
-                    "description": "Symbol name to start from"
+                    "description": "Symbol name to start from. Required unless `module` is given."
+                },
+                "module": {
+                    "type": "string",
+                    "description": "Module path prefix (e.g. `crate::auth`, `auth::session`) to aggregate across all files of the ref instead of walking one symbol. Matches qualified names by prefix; Rust symbols also match by their file's module path. Each node's `path` names its source file."
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Max symbols returned in `module` mode (default: 500, max: 5000)",
+                    "minimum": 1,
+                    "maximum": 5000
                 },
                 "path": {
                     "type": "string",
@@ -23,7 +33,7 @@ pub fn definition() -> ToolDefinition {
                 },
                 "ref": {
                     "type": "string",
-                    "description": "Branch/ref scope"
+                    "description": "Branch/ref scope. `HEAD`, `head` or `current` means the checked-out branch; `default` means the project default ref."
                 },
                 "direction": {
                     "type": "string",
@@ -31,8 +41,7 @@ pub fn definition() -> ToolDefinition {
                     "enum": ["ancestors", "descendants"],
                     "default": "ancestors"
                 }
-            },
-            "required": ["symbol_name"]
+            }
         }),
     }
 }
@@ -0,0 +1,38 @@
+use super::ToolDefinition;
+use serde_json::json;
+
+pub fn definition() -> ToolDefinition {
+    ToolDefinition {
+        name: "implementations_of".into(),
+        description: "List the methods that override or implement a base/interface method across the type hierarchy. Heuristic: Python base classes, TypeScript extends/implements clauses, and Rust `impl Trait for Type` blocks, matched by type name.".into(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "workspace": {
+                    "type": "string",
+                    "description": "Absolute path to target workspace. Default: server's default project."
+                },
+                "symbol_name": {
+                    "type": "string",
+                    "description": "Method name, optionally qualified by its declaring type (`Greeter::greet`, `Base.run`)."
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Only treat declarations in this file as the base definition."
+                },
+                "ref": {
+                    "type": "string",
+                    "description": "Branch/ref scope. `HEAD`, `head` or `current` means the checked-out branch; `default` means the project default ref."
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Max implementations to return (default: 50, max: 500).",
+                    "default": 50,
+                    "minimum": 1,
+                    "maximum": 500
+                }
+            },
+            "required": ["symbol_name"]
+        }),
+    }
+}
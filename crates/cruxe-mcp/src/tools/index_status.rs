@@ -4,7 +4,7 @@ use serde_json::json;
 pub fn definition() -> ToolDefinition {
     ToolDefinition {
         name: "index_status".into(),
-        description: "Get current indexing status and job history for a project.".into(),
+        description: "Get current indexing status and job history for a project. With `changed_since`, also list the indexed symbols in files changed between that commit and HEAD (git workspaces only).".into(),
         input_schema: json!({
             "type": "object",
             "properties": {
@@ -14,7 +14,16 @@ pub fn definition() -> ToolDefinition {
                 },
                 "ref": {
                     "type": "string",
-                    "description": "Branch/ref scope"
+                    "description": "Branch/ref scope. `HEAD`, `head` or `current` means the checked-out branch; `default` means the project default ref."
+                },
+                "changed_since": {
+                    "type": "string",
+                    "description": "Commit sha (or any git revision) to diff against HEAD. Returns the symbols indexed for `ref` in the changed files. Requires a git workspace."
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Max symbols to return for `changed_since` (default: 200, 0 = no limit).",
+                    "minimum": 0
                 }
             }
         }),
@@ -0,0 +1,46 @@
+use super::ToolDefinition;
+use serde_json::json;
+
+pub fn definition() -> ToolDefinition {
+    ToolDefinition {
+        name: "large_symbols".into(),
+        description: "List symbols spanning at least `min_lines` lines, largest first. Useful for finding refactor candidates; can instead sort by body size or a crude nesting-depth estimate.".into(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "workspace": {
+                    "type": "string",
+                    "description": "Absolute path to target workspace. Default: server's default project."
+                },
+                "ref": {
+                    "type": "string",
+                    "description": "Branch/ref scope. `HEAD`, `head` or `current` means the checked-out branch; `default` means the project default ref."
+                },
+                "min_lines": {
+                    "type": "integer",
+                    "description": "Minimum number of lines a symbol must span (default: 100).",
+                    "minimum": 1
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Only include symbols whose file path starts with this prefix."
+                },
+                "language": {
+                    "type": "string",
+                    "description": "Only include symbols in this language (e.g. `rust`, `python`)."
+                },
+                "sort": {
+                    "type": "string",
+                    "description": "Ordering: `lines` (line span), `body_bytes` (stored body length) or `nesting` (estimated nesting depth). Default: `lines`.",
+                    "enum": ["lines", "body_bytes", "nesting"]
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum symbols to return (default: 20, max: 500).",
+                    "minimum": 1,
+                    "maximum": 500
+                }
+            }
+        }),
+    }
+}
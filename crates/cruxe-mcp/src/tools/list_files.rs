@@ -0,0 +1,36 @@
+use super::ToolDefinition;
+use serde_json::json;
+
+pub fn definition() -> ToolDefinition {
+    ToolDefinition {
+        name: "list_files".into(),
+        description: "List indexed files for a ref with language, size and symbol count, optionally filtered by language or path prefix.".into(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "workspace": {
+                    "type": "string",
+                    "description": "Absolute path to target workspace. Default: server's default project."
+                },
+                "ref": {
+                    "type": "string",
+                    "description": "Branch/ref scope. `HEAD`, `head` or `current` means the checked-out branch; `default` means the project default ref."
+                },
+                "language": {
+                    "type": "string",
+                    "description": "Only return files indexed as this language (e.g. rust, typescript)."
+                },
+                "path_prefix": {
+                    "type": "string",
+                    "description": "Only return files whose repository-relative path starts with this prefix (e.g. `src/auth/`)."
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum files returned, ordered by path (default: 200, max: 2000).",
+                    "minimum": 1,
+                    "maximum": 2000
+                }
+            }
+        }),
+    }
+}
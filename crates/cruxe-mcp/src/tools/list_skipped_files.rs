@@ -0,0 +1,37 @@
+use super::ToolDefinition;
+use serde_json::json;
+
+pub fn definition() -> ToolDefinition {
+    ToolDefinition {
+        name: "list_skipped_files".into(),
+        description: "List files the last index run left out for a ref, with the reason (unsupported language, too large, binary, ignored, parse failure, parse timeout, unreadable).".into(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "workspace": {
+                    "type": "string",
+                    "description": "Absolute path to target workspace. Default: server's default project."
+                },
+                "ref": {
+                    "type": "string",
+                    "description": "Branch/ref scope. `HEAD`, `head` or `current` means the checked-out branch; `default` means the project default ref."
+                },
+                "reason": {
+                    "type": "string",
+                    "description": "Only return files skipped for this reason.",
+                    "enum": ["unsupported_language", "too_large", "binary", "ignored", "parse_failure", "parse_timeout", "unreadable"]
+                },
+                "cursor": {
+                    "type": "string",
+                    "description": "Opaque `next_cursor` from a previous page. Omit to start from the beginning."
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum files per page (default: 200, max: 2000).",
+                    "minimum": 1,
+                    "maximum": 2000
+                }
+            }
+        }),
+    }
+}
@@ -29,14 +29,35 @@ pub fn definition() -> ToolDefinition {
                     "type": "string",
                     "description": "Filter by language"
                 },
+                "visibility": {
+                    "type": "string",
+                    "description": "Filter by symbol visibility: \"public\", \"private\" (any non-public visibility, e.g. internal or protected) or \"any\" (default). Symbols without a recorded visibility only match \"any\".",
+                    "enum": ["public", "private", "any"]
+                },
                 "ref": {
                     "type": "string",
-                    "description": "Branch/ref scope"
+                    "description": "Branch/ref scope. `HEAD`, `head` or `current` means the checked-out branch; `default` means the project default ref."
+                },
+                "commit": {
+                    "type": "string",
+                    "description": "Pin the query to an indexed commit (full or >=7-char hash). Must be the last indexed commit of `ref`, or of any ref when `ref` is omitted."
                 },
                 "limit": {
                     "type": "integer",
                     "description": "Max results (default: 10)"
                 },
+                "cursor": {
+                    "type": "string",
+                    "description": "Opaque `metadata.next_cursor` from a previous page. Only valid with the same ref and query arguments."
+                },
+                "fuzzy": {
+                    "type": "boolean",
+                    "description": "Also return typo-tolerant matches (edit distance <= 2), ranked after exact matches and tagged match_type \"fuzzy\". Default: false."
+                },
+                "case_sensitive": {
+                    "type": "boolean",
+                    "description": "Require the name's exact casing: drops fuzzy matches that differ only by case (e.g. `user` for `User`) and compares case-sensitively when explaining ranking. Default: false."
+                },
                 "detail_level": {
                     "type": "string",
                     "description": "Response verbosity: \"location\", \"signature\" (default), \"context\"",
@@ -1,22 +1,40 @@
 pub mod build_context_pack;
+pub mod compact_index;
 pub mod compare_symbol_between_commits;
+pub mod compare_symbols;
 pub mod diff_context;
+pub mod diff_refs;
+pub mod directory_outline;
+pub mod drop_ref;
 pub mod explain_ranking;
+pub mod export_symbol_names;
 pub mod find_references;
 pub mod find_related_symbols;
+pub mod find_unreferenced_symbols;
 pub mod get_call_graph;
 pub mod get_code_context;
 pub mod get_file_outline;
+pub mod get_import_graph;
+pub mod get_symbol;
 pub mod get_symbol_hierarchy;
 pub mod health_check;
+pub mod implementations_of;
 pub mod index_repo;
 pub mod index_status;
+pub mod large_symbols;
+pub mod list_files;
 pub mod list_refs;
+pub mod list_skipped_files;
 pub mod locate_symbol;
+pub mod recent_symbols;
+pub mod resolve_import;
 pub mod search_code;
 pub mod suggest_followup_queries;
 pub mod switch_ref;
+pub mod symbol_at_location;
+pub mod symbol_at_offset;
 pub mod sync_repo;
+pub mod tests_for_symbol;
 
 use serde::{Deserialize, Serialize};
 
@@ -37,19 +55,37 @@ pub fn list_tools() -> Vec<ToolDefinition> {
         search_code::definition(),
         locate_symbol::definition(),
         get_file_outline::definition(),
+        directory_outline::definition(),
+        symbol_at_offset::definition(),
+        symbol_at_location::definition(),
+        recent_symbols::definition(),
+        export_symbol_names::definition(),
+        large_symbols::definition(),
+        find_unreferenced_symbols::definition(),
         get_call_graph::definition(),
+        get_import_graph::definition(),
         compare_symbol_between_commits::definition(),
+        compare_symbols::definition(),
+        get_symbol::definition(),
         get_symbol_hierarchy::definition(),
+        implementations_of::definition(),
         find_related_symbols::definition(),
         get_code_context::definition(),
         build_context_pack::definition(),
         suggest_followup_queries::definition(),
         health_check::definition(),
         index_status::definition(),
+        compact_index::definition(),
+        list_skipped_files::definition(),
+        list_files::definition(),
         diff_context::definition(),
+        diff_refs::definition(),
         find_references::definition(),
+        tests_for_symbol::definition(),
+        resolve_import::definition(),
         explain_ranking::definition(),
         list_refs::definition(),
         switch_ref::definition(),
+        drop_ref::definition(),
     ]
 }
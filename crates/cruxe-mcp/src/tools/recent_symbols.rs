@@ -0,0 +1,32 @@
+use super::ToolDefinition;
+use serde_json::json;
+
+pub fn definition() -> ToolDefinition {
+    ToolDefinition {
+        name: "recent_symbols".into(),
+        description: "List symbols this session has already been given by locate_symbol, search_code, symbol_at_offset, or symbol_at_location, most recent first, so an agent can reuse its working set instead of repeating lookups. Session-local and in-memory: capped at 200 symbols and expiring after 2 hours of inactivity.".into(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "workspace": {
+                    "type": "string",
+                    "description": "Absolute path to target workspace. Default: server's default project."
+                },
+                "ref": {
+                    "type": "string",
+                    "description": "Only return symbols seen on this ref. Default: all refs."
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Max symbols to return (default: 20, max: 200).",
+                    "minimum": 1,
+                    "maximum": 200
+                },
+                "clear": {
+                    "type": "boolean",
+                    "description": "Forget the session's working set after returning it (default: false)."
+                }
+            }
+        }),
+    }
+}
@@ -0,0 +1,31 @@
+use super::ToolDefinition;
+use serde_json::json;
+
+pub fn definition() -> ToolDefinition {
+    ToolDefinition {
+        name: "resolve_import".into(),
+        description: "Resolve an import statement, as written in a file, to the indexed file it targets and, for named imports, the imported symbol's location. Uses the same import resolution as relation edges. Imports that leave the indexed corpus (third-party/stdlib) are reported with `external: true`.".into(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "workspace": {
+                    "type": "string",
+                    "description": "Absolute path to target workspace. Default: server's default project."
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Repository-relative path of the importing file; selects the import syntax and anchors relative imports."
+                },
+                "import": {
+                    "type": "string",
+                    "description": "Import statement, e.g. `use crate::auth::Claims;` or `import { Router } from \"./router\";`."
+                },
+                "ref": {
+                    "type": "string",
+                    "description": "Branch/ref scope. `HEAD`, `head` or `current` means the checked-out branch; `default` means the project default ref."
+                }
+            },
+            "required": ["path", "import"]
+        }),
+    }
+}
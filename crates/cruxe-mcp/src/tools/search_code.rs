@@ -19,30 +19,72 @@ pub fn definition() -> ToolDefinition {
                 },
                 "ref": {
                     "type": "string",
-                    "description": "Branch/ref scope"
+                    "description": "Branch/ref scope. `HEAD`, `head` or `current` means the checked-out branch; `default` means the project default ref."
+                },
+                "commit": {
+                    "type": "string",
+                    "description": "Pin the query to an indexed commit (full or >=7-char hash). Must be the last indexed commit of `ref`, or of any ref when `ref` is omitted."
                 },
                 "language": {
+                    "oneOf": [
+                        { "type": "string" },
+                        { "type": "array", "items": { "type": "string" } }
+                    ],
+                    "description": "Filter by language; an array matches any of the listed languages (e.g. [\"rust\", \"go\"])"
+                },
+                "kind": {
                     "type": "string",
-                    "description": "Filter by language"
+                    "description": "Only return symbols of this kind (function, method, struct, class, enum, trait, interface, ...). Snippet and file results are excluded."
                 },
                 "role": {
                     "type": "string",
                     "description": "Filter by semantic symbol role",
                     "enum": ["type", "callable", "value", "namespace", "alias"]
                 },
+                "visibility": {
+                    "type": "string",
+                    "description": "Filter by symbol visibility: \"public\", \"private\" (any non-public visibility, e.g. internal or protected) or \"any\" (default). Snippet and file results are excluded unless \"any\".",
+                    "enum": ["public", "private", "any"]
+                },
+                "intent": {
+                    "type": "string",
+                    "description": "Force the search strategy instead of classifying the query. `query_intent` in the response reports the intent used.",
+                    "enum": ["symbol", "path", "error", "phrase", "natural"]
+                },
                 "limit": {
                     "type": "integer",
                     "description": "Max results (default: 10)"
                 },
+                "min_score": {
+                    "type": "number",
+                    "description": "Drop results whose final (post-rerank) score is below this value. `metadata.min_score_dropped_count` reports how many were dropped."
+                },
+                "cursor": {
+                    "type": "string",
+                    "description": "Opaque `metadata.next_cursor` from a previous page. Only valid with the same ref and query arguments."
+                },
+                "export": {
+                    "type": "boolean",
+                    "description": "Stream every lexical match, unranked and ignoring `limit`, `cursor` and ranking options. Only supported over HTTP with `Accept: application/x-ndjson`. Default: false."
+                },
                 "detail_level": {
                     "type": "string",
                     "description": "Response verbosity: \"location\", \"signature\" (default), \"context\"",
                     "enum": ["location", "signature", "context"]
                 },
+                "highlight": {
+                    "type": "boolean",
+                    "description": "Return `highlight` ({ fragment, match_ranges }) for lexical hits in place of `snippet`. `match_ranges` are byte offsets into `fragment`. Default: false."
+                },
                 "compact": {
                     "type": "boolean",
                     "description": "Token-thrifty serialization flag. Keeps identity/location/score fields while omitting large context blocks."
                 },
+                "fields": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Optional whitelist of result keys to keep after detail_level serialization (e.g. [\"path\", \"name\", \"line_start\"]). Unknown names are ignored."
+                },
                 "freshness_policy": {
                     "type": "string",
                     "description": "Freshness behavior: \"strict\", \"balanced\" (default), \"best_effort\"",
@@ -53,6 +95,10 @@ pub fn definition() -> ToolDefinition {
                     "description": "Ranking explainability payload level: \"off\" (default), \"basic\", \"full\"",
                     "enum": ["off", "basic", "full"]
                 },
+                "semantic": {
+                    "type": "boolean",
+                    "description": "true ranks snippets by embedding similarity blended with keyword scores for any query; false keeps the search keyword-only. Requires search.semantic.mode = \"hybrid\"; default follows config and query intent."
+                },
                 "semantic_ratio": {
                     "type": "number",
                     "description": "Optional semantic blend ratio cap override (0.0-1.0). Runtime may reduce actual usage.",
@@ -74,6 +120,18 @@ pub fn definition() -> ToolDefinition {
                     "type": "string",
                     "description": "Optional retrieval policy override when allowed by runtime policy config.",
                     "enum": ["strict", "balanced", "off", "audit_only"]
+                },
+                "fold_naming_conventions": {
+                    "type": "boolean",
+                    "description": "Also match identifiers spelled in other naming conventions (getUser / get_user / GetUser). Defaults to search.fold_naming_conventions."
+                },
+                "prefer_language": {
+                    "type": "string",
+                    "description": "Slightly favor results in this language (e.g. rust, python) when scores are otherwise close. Does not filter."
+                },
+                "context_path": {
+                    "type": "string",
+                    "description": "Path of the file the caller is working in; its extension infers prefer_language when that is not set."
                 }
             },
             "required": ["query"]
@@ -0,0 +1,37 @@
+use super::ToolDefinition;
+use serde_json::json;
+
+pub fn definition() -> ToolDefinition {
+    ToolDefinition {
+        name: "symbol_at_location".into(),
+        description: "Resolve the innermost symbol enclosing a `path:line` (for example a stack trace frame), plus its parent chain. Overlapping symbols resolve to the tightest line range.".into(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "workspace": {
+                    "type": "string",
+                    "description": "Absolute path to target workspace. Default: server's default project."
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Source file path relative to repo root"
+                },
+                "line": {
+                    "type": "integer",
+                    "description": "One-based line number in the file as indexed.",
+                    "minimum": 1
+                },
+                "ref": {
+                    "type": "string",
+                    "description": "Branch/ref scope. `HEAD`, `head` or `current` means the checked-out branch; `default` means the project default ref."
+                },
+                "freshness_policy": {
+                    "type": "string",
+                    "description": "Freshness behavior: \"strict\", \"balanced\" (default), \"best_effort\"",
+                    "enum": ["strict", "balanced", "best_effort"]
+                }
+            },
+            "required": ["path", "line"]
+        }),
+    }
+}
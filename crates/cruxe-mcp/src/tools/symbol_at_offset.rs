@@ -0,0 +1,37 @@
+use super::ToolDefinition;
+use serde_json::json;
+
+pub fn definition() -> ToolDefinition {
+    ToolDefinition {
+        name: "symbol_at_offset".into(),
+        description: "Resolve the innermost symbol whose definition contains a byte offset in a file. For editors and LSP clients that address code by byte offset instead of line/column.".into(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "workspace": {
+                    "type": "string",
+                    "description": "Absolute path to target workspace. Default: server's default project."
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Source file path relative to repo root"
+                },
+                "byte_offset": {
+                    "type": "integer",
+                    "description": "Zero-based byte offset into the file as indexed.",
+                    "minimum": 0
+                },
+                "ref": {
+                    "type": "string",
+                    "description": "Branch/ref scope. `HEAD`, `head` or `current` means the checked-out branch; `default` means the project default ref."
+                },
+                "freshness_policy": {
+                    "type": "string",
+                    "description": "Freshness behavior: \"strict\", \"balanced\" (default), \"best_effort\"",
+                    "enum": ["strict", "balanced", "best_effort"]
+                }
+            },
+            "required": ["path", "byte_offset"]
+        }),
+    }
+}
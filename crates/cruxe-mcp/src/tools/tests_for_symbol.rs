@@ -0,0 +1,32 @@
+use super::ToolDefinition;
+use serde_json::json;
+
+pub fn definition() -> ToolDefinition {
+    ToolDefinition {
+        name: "tests_for_symbol".into(),
+        description: "List tests that directly reference a symbol, for change-impact test selection. Approximate: based on relation edges, not execution coverage. Tests are detected by `#[test]`-style attributes or `test_*`/`*_test`/`TestXxx` names; tests reaching the symbol only through helpers are not reported.".into(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "workspace": {
+                    "type": "string",
+                    "description": "Absolute path to target workspace. Default: server's default project."
+                },
+                "symbol_name": {
+                    "type": "string",
+                    "description": "Name or qualified name of the symbol."
+                },
+                "ref": {
+                    "type": "string",
+                    "description": "Branch/ref scope. `HEAD`, `head` or `current` means the checked-out branch; `default` means the project default ref."
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Max tests to return (default: 50).",
+                    "minimum": 1
+                }
+            },
+            "required": ["symbol_name"]
+        }),
+    }
+}
@@ -1,5 +1,5 @@
 use cruxe_core::error::WorkspaceError;
-use cruxe_core::types::{WorkspaceConfig, generate_project_id};
+use cruxe_core::types::{OUTSIDE_ALLOWED_ROOTS_REASON, WorkspaceConfig, generate_project_id};
 use std::path::{Path, PathBuf};
 
 /// Workspace resolution result containing the resolved project context.
@@ -13,6 +13,24 @@ pub struct ResolvedWorkspace {
     pub should_bootstrap: bool,
 }
 
+/// Merge `server.allowed_workspace_roots` from the config file into the
+/// `--allowed-root` set. Roots that cannot be canonicalized are skipped with a
+/// warning, which only ever narrows what is allowed.
+pub fn merge_configured_roots(workspace_config: &mut WorkspaceConfig, roots: &[String]) {
+    let canonical_roots = roots.iter().filter_map(|root| {
+        std::fs::canonicalize(root)
+            .map_err(|err| {
+                tracing::warn!(
+                    root = %root,
+                    "Ignoring server.allowed_workspace_roots entry: {}",
+                    err
+                );
+            })
+            .ok()
+    });
+    workspace_config.allowed_roots.extend(canonical_roots);
+}
+
 /// Router that resolves workspace parameters to project contexts.
 #[derive(Debug)]
 pub struct WorkspaceRouter {
@@ -119,6 +137,14 @@ impl WorkspaceRouter {
             })?
         {
             // Case 2: Known workspace
+            // Auto-discovered entries must still sit under an allowed root, so
+            // narrowing the allowlist takes effect without purging the registry.
+            if ws.auto_discovered && !self.config.allowed_roots.contains(&canonical) {
+                return Err(WorkspaceError::NotAllowed {
+                    path: canonical_str,
+                    reason: OUTSIDE_ALLOWED_ROOTS_REASON.to_string(),
+                });
+            }
             // If a known workspace has no bound project yet, it is in bootstrap discovery
             // and callers should receive on-demand semantics even before the indexing claim
             // flips `index_status` to `indexing`.
@@ -150,7 +176,7 @@ impl WorkspaceRouter {
         if !self.config.allowed_roots.contains(&canonical) {
             return Err(WorkspaceError::NotAllowed {
                 path: canonical_str,
-                reason: "path is outside all --allowed-root prefixes".to_string(),
+                reason: OUTSIDE_ALLOWED_ROOTS_REASON.to_string(),
             });
         }
 
@@ -913,4 +939,65 @@ mod tests {
         let result = WorkspaceRouter::new(config, dir.path().to_path_buf(), db_path);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn configured_roots_satisfy_auto_workspace_and_skip_missing_paths() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("data/state.db");
+        setup_db(&db_path);
+        let ws = dir.path().join("shared/repo_z");
+        std::fs::create_dir_all(&ws).unwrap();
+        let ws_str = std::fs::canonicalize(&ws)
+            .unwrap()
+            .to_string_lossy()
+            .to_string();
+
+        let mut config = WorkspaceConfig {
+            auto_workspace: true,
+            ..WorkspaceConfig::default()
+        };
+        merge_configured_roots(
+            &mut config,
+            &[
+                dir.path().join("missing").to_string_lossy().to_string(),
+                dir.path().join("shared").to_string_lossy().to_string(),
+            ],
+        );
+        let router = WorkspaceRouter::new(config, dir.path().to_path_buf(), db_path).unwrap();
+
+        let resolved = router.resolve_workspace(Some(&ws_str)).unwrap();
+        assert!(resolved.should_bootstrap);
+    }
+
+    #[test]
+    fn known_auto_discovered_workspace_outside_allowlist_is_rejected() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("data/state.db");
+        setup_db(&db_path);
+        let ws = dir.path().join("formerly_allowed/repo");
+        std::fs::create_dir_all(&ws).unwrap();
+        let ws_str = std::fs::canonicalize(&ws)
+            .unwrap()
+            .to_string_lossy()
+            .to_string();
+        {
+            let conn = cruxe_state::db::open_connection(&db_path).unwrap();
+            let now = cruxe_core::time::now_iso8601();
+            cruxe_state::workspace::register_workspace(&conn, &ws_str, None, true, &now).unwrap();
+        }
+
+        let allowed_root = dir.path().join("allowed");
+        std::fs::create_dir_all(&allowed_root).unwrap();
+        let config = WorkspaceConfig {
+            auto_workspace: true,
+            allowed_roots: AllowedRoots::new(vec![std::fs::canonicalize(allowed_root).unwrap()]),
+            max_auto_workspaces: 10,
+        };
+        let router = WorkspaceRouter::new(config, dir.path().to_path_buf(), db_path).unwrap();
+
+        assert!(matches!(
+            router.resolve_workspace(Some(&ws_str)),
+            Err(WorkspaceError::NotAllowed { reason, .. }) if reason == OUTSIDE_ALLOWED_ROOTS_REASON
+        ));
+    }
 }
@@ -76,6 +76,8 @@ fn sample_results() -> Vec<SearchResult> {
             path: "src/auth.rs".to_string(),
             line_start: 10,
             line_end: 22,
+            byte_start: None,
+            byte_end: None,
             kind: Some("function".to_string()),
             name: Some("authenticate".to_string()),
             qualified_name: Some("authenticate".to_string()),
@@ -87,6 +89,7 @@ fn sample_results() -> Vec<SearchResult> {
             chunk_type: None,
             source_layer: None,
             provenance: "lexical".to_string(),
+            highlight: None,
         },
         SearchResult {
             repo: "repo".to_string(),
@@ -97,6 +100,8 @@ fn sample_results() -> Vec<SearchResult> {
             path: "src/secrets/keys.rs".to_string(),
             line_start: 3,
             line_end: 3,
+            byte_start: None,
+            byte_end: None,
             kind: Some("constant".to_string()),
             name: Some("API_KEY".to_string()),
             qualified_name: Some("API_KEY".to_string()),
@@ -111,6 +116,7 @@ fn sample_results() -> Vec<SearchResult> {
             chunk_type: None,
             source_layer: None,
             provenance: "lexical".to_string(),
+            highlight: None,
         },
         SearchResult {
             repo: "repo".to_string(),
@@ -121,6 +127,8 @@ fn sample_results() -> Vec<SearchResult> {
             path: "src/notify.rs".to_string(),
             line_start: 8,
             line_end: 12,
+            byte_start: None,
+            byte_end: None,
             kind: Some("function".to_string()),
             name: Some("notify".to_string()),
             qualified_name: Some("notify".to_string()),
@@ -135,6 +143,7 @@ fn sample_results() -> Vec<SearchResult> {
             chunk_type: None,
             source_layer: None,
             provenance: "lexical".to_string(),
+            highlight: None,
         },
     ]
 }
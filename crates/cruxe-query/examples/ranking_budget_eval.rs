@@ -242,6 +242,8 @@ fn candidate(
         path: path.to_string(),
         line_start: 1,
         line_end: 2,
+        byte_start: None,
+        byte_end: None,
         kind: Some(kind.to_string()),
         name: Some(name.to_string()),
         qualified_name: Some(qualified_name.to_string()),
@@ -253,6 +255,7 @@ fn candidate(
         chunk_type: None,
         source_layer: None,
         provenance: "lexical".to_string(),
+        highlight: None,
     }
 }
 
@@ -134,6 +134,14 @@ fn main() -> Result<(), Box<dyn Error>> {
                 plan_override: None,
                 policy_mode_override: None,
                 policy_runtime: None,
+                preferred_language: None,
+                languages: Vec::new(),
+                semantic: None,
+                kind: None,
+                offset: 0,
+                highlight: false,
+                visibility: cruxe_core::types::VisibilityFilter::Any,
+                intent: None,
             },
         )?;
         let latency_ms = start.elapsed().as_secs_f64() * 1000.0;
@@ -185,6 +185,7 @@ fn select_without_override(
             QueryIntent::Symbol
             | QueryIntent::Path
             | QueryIntent::Error
+            | QueryIntent::Phrase
             | QueryIntent::NaturalLanguage => {
                 (QueryPlan::LexicalFast, SelectionReason::SemanticUnavailable)
             }
@@ -193,7 +194,7 @@ fn select_without_override(
 
     if matches!(
         intent,
-        QueryIntent::Symbol | QueryIntent::Path | QueryIntent::Error
+        QueryIntent::Symbol | QueryIntent::Path | QueryIntent::Error | QueryIntent::Phrase
     ) && lexical_confidence >= config.high_confidence_threshold
     {
         return (
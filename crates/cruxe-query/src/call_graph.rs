@@ -1,3 +1,4 @@
+use cruxe_core::constants::MAX_RELATION_BREADTH;
 use cruxe_core::error::StateError;
 use cruxe_core::types::{CallEdge, SymbolKind, SymbolRecord};
 use cruxe_state::{edges, symbols};
@@ -67,6 +68,9 @@ pub struct CallGraphResult {
     pub total_edges: usize,
     pub truncated: bool,
     pub depth_applied: u32,
+    pub max_breadth_applied: usize,
+    /// Edges skipped because a node already emitted `max_breadth_applied` edges.
+    pub truncated_edges: usize,
 }
 
 #[derive(Debug, Clone)]
@@ -76,12 +80,18 @@ pub struct CallGraphRequest<'a> {
     pub direction: CallGraphDirection,
     pub depth: u32,
     pub limit: usize,
+    /// Maximum edges expanded from any single node.
+    pub max_breadth: usize,
 }
 
 pub fn clamp_depth(depth: u32) -> u32 {
     depth.clamp(1, MAX_CALL_GRAPH_DEPTH)
 }
 
+pub fn clamp_breadth(max_breadth: usize) -> usize {
+    max_breadth.clamp(1, MAX_RELATION_BREADTH)
+}
+
 pub fn get_call_graph(
     conn: &Connection,
     repo: &str,
@@ -93,44 +103,64 @@ pub fn get_call_graph(
     let root_symbol = to_call_graph_symbol(&root);
     let depth_applied = clamp_depth(request.depth);
     let limit = request.limit.max(1);
+    let max_breadth_applied = clamp_breadth(request.max_breadth);
+    let bounds = TraversalBounds {
+        depth_limit: depth_applied,
+        limit,
+        max_breadth: max_breadth_applied,
+    };
 
-    let (callers, callers_truncated) = match request.direction {
+    let callers = match request.direction {
         CallGraphDirection::Callers | CallGraphDirection::Both => traverse_direction(
             conn,
             repo,
             ref_name,
             &root.symbol_stable_id,
-            depth_applied,
-            limit,
+            bounds,
             TraversalMode::Callers,
         )?,
-        CallGraphDirection::Callees => (Vec::new(), false),
+        CallGraphDirection::Callees => TraversalOutcome::default(),
     };
 
-    let (callees, callees_truncated) = match request.direction {
+    let callees = match request.direction {
         CallGraphDirection::Callees | CallGraphDirection::Both => traverse_direction(
             conn,
             repo,
             ref_name,
             &root.symbol_stable_id,
-            depth_applied,
-            limit,
+            bounds,
             TraversalMode::Callees,
         )?,
-        CallGraphDirection::Callers => (Vec::new(), false),
+        CallGraphDirection::Callers => TraversalOutcome::default(),
     };
 
-    let total_edges = callers.len() + callees.len();
+    let total_edges = callers.edges.len() + callees.edges.len();
     Ok(CallGraphResult {
         symbol: root_symbol,
-        callers,
-        callees,
+        truncated: callers.truncated || callees.truncated,
+        truncated_edges: callers.truncated_edges + callees.truncated_edges,
+        callers: callers.edges,
+        callees: callees.edges,
         total_edges,
-        truncated: callers_truncated || callees_truncated,
         depth_applied,
+        max_breadth_applied,
     })
 }
 
+#[derive(Debug, Clone, Copy)]
+struct TraversalBounds {
+    depth_limit: u32,
+    limit: usize,
+    max_breadth: usize,
+}
+
+#[derive(Debug, Default)]
+struct TraversalOutcome {
+    edges: Vec<CallGraphEdgeResult>,
+    truncated: bool,
+    truncated_edges: usize,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum TraversalMode {
     Callers,
@@ -142,18 +172,18 @@ fn traverse_direction(
     repo: &str,
     ref_name: &str,
     root_symbol_stable_id: &str,
-    depth_limit: u32,
-    limit: usize,
+    bounds: TraversalBounds,
     mode: TraversalMode,
-) -> Result<(Vec<CallGraphEdgeResult>, bool), StateError> {
+) -> Result<TraversalOutcome, StateError> {
     let mut queue = VecDeque::from([(root_symbol_stable_id.to_string(), 0u32)]);
     let mut expanded = HashSet::from([root_symbol_stable_id.to_string()]);
     let mut emitted = HashSet::<(String, String, u32, u32)>::new();
     let mut results = Vec::new();
     let mut truncated = false;
+    let mut truncated_edges = 0usize;
 
     while let Some((current_symbol_id, current_depth)) = queue.pop_front() {
-        if current_depth >= depth_limit {
+        if current_depth >= bounds.depth_limit {
             continue;
         }
 
@@ -167,6 +197,7 @@ fn traverse_direction(
         };
         let resolved_targets =
             resolve_target_symbols_batch(conn, repo, ref_name, &edges_for_symbol, mode)?;
+        let mut node_edges = 0usize;
 
         for edge in edges_for_symbol {
             let Some(target_lookup_id) = target_id_for_edge(&edge, mode) else {
@@ -189,10 +220,15 @@ fn traverse_direction(
                 continue;
             }
 
-            if results.len() >= limit {
+            if node_edges >= bounds.max_breadth {
+                truncated_edges += 1;
+                continue;
+            }
+            if results.len() >= bounds.limit {
                 truncated = true;
                 break;
             }
+            node_edges += 1;
 
             results.push(CallGraphEdgeResult {
                 symbol: target_symbol.clone(),
@@ -214,7 +250,11 @@ fn traverse_direction(
         }
     }
 
-    Ok((results, truncated))
+    Ok(TraversalOutcome {
+        edges: results,
+        truncated,
+        truncated_edges,
+    })
 }
 
 fn target_id_for_edge(edge: &CallEdge, mode: TraversalMode) -> Option<&str> {
@@ -320,7 +360,7 @@ fn query_symbols_by_field(
 
     Ok(records)
 }
-fn resolve_root_symbol(
+pub(crate) fn resolve_root_symbol(
     conn: &Connection,
     repo: &str,
     ref_name: &str,
@@ -396,6 +436,11 @@ fn row_to_symbol_record(row: &rusqlite::Row<'_>) -> rusqlite::Result<SymbolRecor
         parent_symbol_id: row.get(13)?,
         visibility: row.get(14)?,
         content: None,
+        attributes: Vec::new(),
+        byte_start: None,
+        byte_end: None,
+        doc_comment: None,
+        parsed_signature: None,
     })
 }
 
@@ -415,6 +460,7 @@ fn to_call_graph_symbol(symbol: &SymbolRecord) -> CallGraphSymbol {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use cruxe_core::constants::DEFAULT_RELATION_MAX_BREADTH;
     use cruxe_core::types::CallEdge;
     use cruxe_state::{db, schema, symbols};
 
@@ -443,6 +489,11 @@ mod tests {
             parent_symbol_id: None,
             visibility: Some("pub".to_string()),
             content: None,
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
         }
     }
 
@@ -491,6 +542,7 @@ mod tests {
                 direction: CallGraphDirection::Callees,
                 depth: 1,
                 limit: 20,
+                max_breadth: DEFAULT_RELATION_MAX_BREADTH,
             },
         )
         .unwrap();
@@ -508,6 +560,7 @@ mod tests {
                 direction: CallGraphDirection::Callees,
                 depth: 2,
                 limit: 20,
+                max_breadth: DEFAULT_RELATION_MAX_BREADTH,
             },
         )
         .unwrap();
@@ -548,6 +601,7 @@ mod tests {
                 direction: CallGraphDirection::Callees,
                 depth: 99,
                 limit: 20,
+                max_breadth: DEFAULT_RELATION_MAX_BREADTH,
             },
         )
         .unwrap();
@@ -564,6 +618,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn max_breadth_caps_edges_per_node_and_reports_truncation() {
+        let conn = setup();
+        let mut records = vec![symbol("stable-hub", "hub", "src/hub.rs", 1)];
+        let mut calls = Vec::new();
+        for idx in 0..5u32 {
+            let stable = format!("stable-leaf-{idx}");
+            records.push(symbol(
+                &stable,
+                &format!("leaf_{idx}"),
+                "src/leaf.rs",
+                10 + idx,
+            ));
+            calls.push(call("stable-hub", Some(&stable), "src/hub.rs", 2 + idx));
+        }
+        for record in &records {
+            symbols::insert_symbol(&conn, record).unwrap();
+        }
+        edges::insert_call_edges(&conn, "repo", "main", &calls).unwrap();
+
+        let graph = get_call_graph(
+            &conn,
+            "repo",
+            "main",
+            &CallGraphRequest {
+                symbol_name: "hub",
+                path: None,
+                direction: CallGraphDirection::Callees,
+                depth: 1,
+                limit: 20,
+                max_breadth: 2,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(graph.callees.len(), 2);
+        assert_eq!(graph.max_breadth_applied, 2);
+        assert_eq!(graph.truncated_edges, 3);
+        assert!(!graph.truncated);
+    }
+
     #[test]
     fn get_call_graph_resolves_callee_via_symbol_id_fallback() {
         let conn = setup();
@@ -601,6 +696,7 @@ mod tests {
                 direction: CallGraphDirection::Callees,
                 depth: 1,
                 limit: 20,
+                max_breadth: DEFAULT_RELATION_MAX_BREADTH,
             },
         )
         .unwrap();
@@ -651,6 +747,7 @@ mod tests {
                     direction: CallGraphDirection::Callees,
                     depth: 1,
                     limit: 256,
+                    max_breadth: DEFAULT_RELATION_MAX_BREADTH,
                 },
             )
             .unwrap();
@@ -668,6 +765,7 @@ mod tests {
                     direction: CallGraphDirection::Callees,
                     depth: 2,
                     limit: 256,
+                    max_breadth: DEFAULT_RELATION_MAX_BREADTH,
                 },
             )
             .unwrap();
@@ -0,0 +1,197 @@
+use crate::diff_context::{DiffFileChange, DiffSymbolSnapshot, summarize_file_changes};
+use cruxe_core::error::StateError;
+use cruxe_vcs::{FileChangeKind, Git2VcsAdapter, VcsAdapter};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Symbols currently indexed in files that changed between a commit and HEAD.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ChangedSinceResult {
+    pub since: String,
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    pub changed_files: Vec<DiffFileChange>,
+    /// Total symbols found in changed files, before `limit` is applied.
+    pub symbol_count: usize,
+    pub symbols: Vec<DiffSymbolSnapshot>,
+    pub truncated: bool,
+}
+
+/// Diff `since` against the workspace HEAD and return the indexed symbols of
+/// `ref_name` that live in the changed files. Deleted files are reported in
+/// `changed_files` but contribute no symbols.
+pub fn symbols_changed_since(
+    conn: &Connection,
+    repo_root: &Path,
+    project_id: &str,
+    ref_name: &str,
+    since: &str,
+    limit: usize,
+) -> Result<ChangedSinceResult, StateError> {
+    let diff_entries = Git2VcsAdapter
+        .diff_name_status(repo_root, since, "HEAD")
+        .map_err(StateError::vcs)?;
+
+    let mut symbols = Vec::new();
+    for entry in &diff_entries {
+        if matches!(entry.kind, FileChangeKind::Deleted) {
+            continue;
+        }
+        let records =
+            cruxe_state::symbols::list_symbols_in_file(conn, project_id, ref_name, &entry.path)?;
+        symbols.extend(records.into_iter().map(|record| DiffSymbolSnapshot {
+            symbol_id: record.symbol_id,
+            symbol_stable_id: record.symbol_stable_id,
+            kind: record.kind.as_str().to_string(),
+            qualified_name: record.qualified_name,
+            signature: record.signature,
+            path: record.path,
+            line_start: record.line_start,
+            line_end: record.line_end,
+        }));
+    }
+    symbols.sort_by(|a, b| {
+        a.path
+            .cmp(&b.path)
+            .then_with(|| a.line_start.cmp(&b.line_start))
+            .then_with(|| a.qualified_name.cmp(&b.qualified_name))
+    });
+
+    let symbol_count = symbols.len();
+    let truncated = limit > 0 && symbol_count > limit;
+    if truncated {
+        symbols.truncate(limit);
+    }
+
+    Ok(ChangedSinceResult {
+        since: since.to_string(),
+        ref_name: ref_name.to_string(),
+        changed_files: summarize_file_changes(&diff_entries),
+        symbol_count,
+        symbols,
+        truncated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cruxe_core::types::{SymbolKind, SymbolRecord};
+    use cruxe_state::{db, schema, symbols};
+    use std::process::Command;
+
+    fn git(repo: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(repo)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    fn insert_symbol(conn: &Connection, path: &str, name: &str, line_start: u32) {
+        symbols::insert_symbol(
+            conn,
+            &SymbolRecord {
+                repo: "proj".to_string(),
+                r#ref: "main".to_string(),
+                commit: None,
+                path: path.to_string(),
+                language: "rust".to_string(),
+                symbol_id: format!("sym-{name}"),
+                symbol_stable_id: format!("stable-{name}"),
+                name: name.to_string(),
+                qualified_name: format!("crate::{name}"),
+                kind: SymbolKind::Function,
+                signature: Some(format!("pub fn {name}()")),
+                line_start,
+                line_end: line_start,
+                parent_symbol_id: None,
+                visibility: Some("pub".to_string()),
+                content: None,
+                attributes: Vec::new(),
+                byte_start: None,
+                byte_end: None,
+                doc_comment: None,
+                parsed_signature: None,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn symbols_changed_since_returns_symbols_in_changed_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo = tmp.path().join("repo");
+        std::fs::create_dir_all(repo.join("src")).unwrap();
+        git(&repo, &["init"]);
+        git(&repo, &["config", "user.email", "test@example.com"]);
+        git(&repo, &["config", "user.name", "Test User"]);
+        std::fs::write(repo.join("src/lib.rs"), "pub fn stable() {}\n").unwrap();
+        std::fs::write(repo.join("src/old.rs"), "pub fn gone() {}\n").unwrap();
+        std::fs::write(repo.join("src/edit.rs"), "pub fn edited() {}\n").unwrap();
+        git(&repo, &["add", "."]);
+        git(&repo, &["commit", "-m", "base"]);
+        let since = git(&repo, &["rev-parse", "HEAD"]);
+
+        std::fs::write(
+            repo.join("src/edit.rs"),
+            "pub fn edited() {}\npub fn helper() {}\n",
+        )
+        .unwrap();
+        std::fs::write(repo.join("src/new.rs"), "pub fn added() {}\n").unwrap();
+        std::fs::remove_file(repo.join("src/old.rs")).unwrap();
+        git(&repo, &["add", "-A"]);
+        git(&repo, &["commit", "-m", "feature"]);
+
+        let conn = db::open_connection(&tmp.path().join("state.db")).unwrap();
+        schema::create_tables(&conn).unwrap();
+        insert_symbol(&conn, "src/lib.rs", "stable", 1);
+        insert_symbol(&conn, "src/edit.rs", "helper", 2);
+        insert_symbol(&conn, "src/edit.rs", "edited", 1);
+        insert_symbol(&conn, "src/new.rs", "added", 1);
+
+        let result = symbols_changed_since(&conn, &repo, "proj", "main", &since, 0).unwrap();
+        let mut files: Vec<(&str, &str)> = result
+            .changed_files
+            .iter()
+            .map(|change| (change.path.as_str(), change.change_type.as_str()))
+            .collect();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![
+                ("src/edit.rs", "modified"),
+                ("src/new.rs", "added"),
+                ("src/old.rs", "deleted"),
+            ]
+        );
+        let names: Vec<&str> = result
+            .symbols
+            .iter()
+            .map(|symbol| symbol.qualified_name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["crate::edited", "crate::helper", "crate::added"]
+        );
+        assert_eq!(result.symbol_count, 3);
+        assert!(!result.truncated);
+
+        let limited = symbols_changed_since(&conn, &repo, "proj", "main", &since, 1).unwrap();
+        assert_eq!(limited.symbols.len(), 1);
+        assert_eq!(limited.symbol_count, 3);
+        assert!(limited.truncated);
+
+        let err =
+            symbols_changed_since(&conn, &repo, "proj", "main", "no-such-commit", 0).unwrap_err();
+        assert!(matches!(err, StateError::Vcs(_)));
+    }
+}
@@ -105,6 +105,9 @@ fn suggested_action(query: &str, intent: QueryIntent, results: &[SearchResult])
         QueryIntent::Error => {
             "Try search_code with exact error substring or stack-frame snippet".to_string()
         }
+        QueryIntent::Phrase => {
+            "Drop the surrounding quotes to match the words individually".to_string()
+        }
     }
 }
 
@@ -143,6 +146,8 @@ mod tests {
             path: "src/lib.rs".to_string(),
             line_start: 1,
             line_end: 1,
+            byte_start: None,
+            byte_end: None,
             kind: Some("function".to_string()),
             name: Some("demo".to_string()),
             qualified_name: Some("demo".to_string()),
@@ -154,6 +159,7 @@ mod tests {
             chunk_type: None,
             source_layer: None,
             provenance: provenance.to_string(),
+            highlight: None,
         }
     }
 
@@ -1,3 +1,4 @@
+use crate::detail;
 use crate::search;
 use crate::{policy::PolicyRuntime, search::SearchExecutionOptions};
 use cruxe_core::config::SearchConfig as CoreSearchConfig;
@@ -9,9 +10,20 @@ use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::BTreeMap;
-use std::path::Path;
+use std::path::{Component, Path};
+use tantivy::collector::TopDocs;
+use tantivy::query::TermQuery;
+use tantivy::schema::{IndexRecordOption, Value};
+use tantivy::{Index, Term};
 use thiserror::Error;
 
+/// Lines shown on each side of the target line in window mode by default.
+pub const DEFAULT_CONTEXT_LINES: u32 = 10;
+/// Largest accepted `context_lines`.
+pub const MAX_CONTEXT_LINES: u32 = 500;
+/// Most symbol ids accepted by one batch ([`get_symbols_context`]) call.
+pub const MAX_CONTEXT_IDS: usize = 100;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ContextStrategy {
@@ -42,6 +54,23 @@ pub enum ContextError {
     InvalidStrategy,
     #[error("invalid max_tokens")]
     InvalidMaxTokens,
+    #[error("path must be relative to the workspace: {path}")]
+    InvalidPath { path: String },
+    #[error("file not found on disk or in the index: {path}")]
+    FileNotFound { path: String },
+    #[error("file {path} is {size_bytes} bytes, over the {max_file_size}-byte limit")]
+    FileTooLarge {
+        path: String,
+        size_bytes: u64,
+        max_file_size: u64,
+    },
+    #[error("line {line} is past the end of {path} ({line_count} lines)")]
+    LineOutOfRange {
+        path: String,
+        line: u32,
+        line_count: usize,
+        read_from: LineWindowSource,
+    },
     #[error("state error: {0}")]
     State(#[from] StateError),
 }
@@ -65,6 +94,14 @@ pub struct GetCodeContextParams<'a> {
     pub max_tokens: usize,
     pub strategy: ContextStrategy,
     pub policy_mode_override: Option<PolicyMode>,
+    /// Prefix each `body` line with its file line number (depth strategy only).
+    pub with_line_numbers: bool,
+    /// Extra lines read before/after each symbol's range for its `body`
+    /// (depth strategy only), clamped to the file.
+    pub prefix_lines: u32,
+    pub suffix_lines: u32,
+    /// Bodies are not extended from files larger than this (`0` = no limit).
+    pub max_file_size: u64,
 }
 
 pub fn get_code_context(
@@ -81,6 +118,10 @@ pub fn get_code_context(
         max_tokens,
         strategy,
         policy_mode_override,
+        with_line_numbers,
+        prefix_lines,
+        suffix_lines,
+        max_file_size,
     } = params;
 
     if max_tokens == 0 {
@@ -104,6 +145,14 @@ pub fn get_code_context(
             plan_override: None,
             policy_mode_override,
             policy_runtime: Some(policy_runtime.clone()),
+            preferred_language: None,
+            languages: Vec::new(),
+            semantic: None,
+            kind: None,
+            offset: 0,
+            highlight: false,
+            visibility: cruxe_core::types::VisibilityFilter::Any,
+            intent: None,
         },
     )?;
     let total_candidates = search_response.results.len();
@@ -114,6 +163,12 @@ pub fn get_code_context(
     let mut body_redaction_categories = BTreeMap::new();
 
     for result in search_response.results {
+        let annotations = conn
+            .zip(result.symbol_id.as_deref())
+            .and_then(|(conn, symbol_id)| {
+                detail::resolve_symbol_annotations(conn, &result.repo, symbol_id)
+            })
+            .unwrap_or_default();
         let item = match strategy {
             ContextStrategy::Breadth => json!({
                 "symbol_id": result.symbol_id,
@@ -125,17 +180,26 @@ pub fn get_code_context(
                 "line_start": result.line_start,
                 "line_end": result.line_end,
                 "signature": result.signature,
+                "doc_comment": annotations.doc_comment,
+                "attributes": annotations.attributes,
                 "language": result.language,
                 "score": result.score,
             }),
             ContextStrategy::Depth => {
-                let original_body = load_symbol_body(
+                let SymbolBody {
+                    text: original_body,
+                    line_start: body_line_start,
+                    line_end: body_line_end,
+                } = load_symbol_body(SymbolBodyParams {
                     workspace,
-                    &result.path,
-                    result.line_start,
-                    result.line_end,
-                    result.snippet.as_deref(),
-                );
+                    relative_path: &result.path,
+                    line_start: result.line_start,
+                    line_end: result.line_end,
+                    prefix_lines,
+                    suffix_lines,
+                    max_file_size,
+                    fallback: result.snippet.as_deref(),
+                });
                 let body_redaction = policy_runtime.redact_text(&original_body);
                 if body_redaction.redacted_count > 0 {
                     body_redacted_count += body_redaction.redacted_count;
@@ -148,6 +212,11 @@ pub fn get_code_context(
                 } else {
                     body_redaction.text
                 };
+                let body = if with_line_numbers {
+                    number_lines(&body, body_line_start)
+                } else {
+                    body
+                };
                 json!({
                     "symbol_id": result.symbol_id,
                     "symbol_stable_id": result.symbol_stable_id,
@@ -158,9 +227,13 @@ pub fn get_code_context(
                     "line_start": result.line_start,
                     "line_end": result.line_end,
                     "signature": result.signature,
+                    "doc_comment": annotations.doc_comment,
+                    "attributes": annotations.attributes,
                     "language": result.language,
                     "score": result.score,
                     "body": body,
+                    "body_line_start": body_line_start,
+                    "body_line_end": body_line_end,
                 })
             }
         };
@@ -222,28 +295,392 @@ pub fn get_code_context(
     })
 }
 
-fn load_symbol_body(
-    workspace: &Path,
-    relative_path: &str,
+pub struct GetSymbolsContextParams<'a> {
+    pub conn: &'a Connection,
+    pub search_config: &'a CoreSearchConfig,
+    pub workspace: &'a Path,
+    pub repo: &'a str,
+    pub ref_name: &'a str,
+    /// `symbol_stable_id`s (or ref-local `symbol_id`s), returned in this order.
+    pub ids: &'a [String],
+    pub max_tokens: usize,
+    pub policy_mode_override: Option<PolicyMode>,
+    pub with_line_numbers: bool,
+    pub prefix_lines: u32,
+    pub suffix_lines: u32,
+    pub max_file_size: u64,
+    /// Byte cap on the serialized items of the whole batch (`0` = no limit).
+    pub max_response_bytes: usize,
+}
+
+/// Depth-strategy context for a known list of symbols, e.g. every symbol a
+/// diff touches, read in one pass over a single connection. Ids that do not
+/// resolve on `ref_name` are listed in `metadata.missing_ids`; items stop at
+/// `max_tokens` or `max_response_bytes` and the rest are listed in
+/// `metadata.remaining_ids`.
+pub fn get_symbols_context(
+    params: GetSymbolsContextParams<'_>,
+) -> Result<CodeContextResponse, ContextError> {
+    let GetSymbolsContextParams {
+        conn,
+        search_config,
+        workspace,
+        repo,
+        ref_name,
+        ids,
+        max_tokens,
+        policy_mode_override,
+        with_line_numbers,
+        prefix_lines,
+        suffix_lines,
+        max_file_size,
+        max_response_bytes,
+    } = params;
+
+    if max_tokens == 0 {
+        return Err(ContextError::InvalidMaxTokens);
+    }
+
+    let policy_runtime = PolicyRuntime::from_search_config(search_config, policy_mode_override)?;
+    let mut items = Vec::new();
+    let mut used_bytes = 2usize; // '[' + ']'
+    let mut safety_limit_applied = false;
+    let mut missing_ids = Vec::new();
+    let mut remaining_ids = Vec::new();
+    let mut estimated = 0usize;
+    let mut redacted_count = 0usize;
+    let mut redaction_categories = BTreeMap::new();
+
+    for (position, id) in ids.iter().enumerate() {
+        let symbol = match cruxe_state::symbols::get_symbol_with_content_by_stable_id(
+            conn, repo, ref_name, id,
+        )? {
+            Some(symbol) => Some(symbol),
+            None => cruxe_state::symbols::get_symbol_by_id(conn, repo, ref_name, id)?,
+        };
+        let Some(symbol) = symbol else {
+            missing_ids.push(id.clone());
+            continue;
+        };
+
+        let SymbolBody {
+            text: original_body,
+            line_start: body_line_start,
+            line_end: body_line_end,
+        } = load_symbol_body(SymbolBodyParams {
+            workspace,
+            relative_path: &symbol.path,
+            line_start: symbol.line_start,
+            line_end: symbol.line_end,
+            prefix_lines,
+            suffix_lines,
+            max_file_size,
+            fallback: symbol.content.as_deref(),
+        });
+        let redaction = policy_runtime.redact_text(&original_body);
+        let body = if policy_runtime.mode() == PolicyMode::AuditOnly {
+            original_body
+        } else {
+            redaction.text
+        };
+        let body = if with_line_numbers {
+            number_lines(&body, body_line_start)
+        } else {
+            body
+        };
+        let item = json!({
+            "symbol_id": symbol.symbol_id,
+            "symbol_stable_id": symbol.symbol_stable_id,
+            "name": symbol.name,
+            "kind": symbol.kind.as_str(),
+            "qualified_name": symbol.qualified_name,
+            "path": symbol.path,
+            "line_start": symbol.line_start,
+            "line_end": symbol.line_end,
+            "signature": symbol.signature,
+            "doc_comment": symbol.doc_comment,
+            "attributes": symbol.attributes,
+            "language": symbol.language,
+            "body": body,
+            "body_line_start": body_line_start,
+            "body_line_end": body_line_end,
+        });
+
+        let item_text = serde_json::to_string(&item).unwrap_or_default();
+        let item_tokens = estimate_tokens(&item_text);
+        let item_bytes = item_text.len() + usize::from(!items.is_empty());
+        let over_bytes = max_response_bytes > 0 && used_bytes + item_bytes > max_response_bytes;
+        if estimated + item_tokens > max_tokens || over_bytes {
+            safety_limit_applied = over_bytes;
+            remaining_ids.extend(ids[position..].iter().cloned());
+            break;
+        }
+        estimated += item_tokens;
+        used_bytes += item_bytes;
+        redacted_count += redaction.redacted_count;
+        for (category, count) in redaction.category_counts {
+            *redaction_categories.entry(category).or_insert(0) += count;
+        }
+        items.push(item);
+    }
+
+    let truncated = !remaining_ids.is_empty();
+    let mut metadata = json!({
+        "requested": ids.len(),
+        "returned": items.len(),
+        "missing_ids": missing_ids,
+        "strategy": ContextStrategy::Depth.as_str(),
+        "policy_mode": policy_runtime.mode().to_string(),
+        "policy_redacted_count": redacted_count,
+        "policy_redaction_categories": redaction_categories,
+    });
+    if safety_limit_applied {
+        metadata["safety_limit_applied"] = json!(true);
+    }
+    if truncated {
+        metadata["remaining_ids"] = json!(remaining_ids);
+        metadata["suggestion"] =
+            json!("Request the remaining_ids in another call, or increase max_tokens");
+    }
+
+    Ok(CodeContextResponse {
+        context_items: items,
+        estimated_tokens: estimated,
+        truncated,
+        metadata,
+    })
+}
+
+/// Where a [`LineWindow`] was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LineWindowSource {
+    /// The file in the workspace.
+    Workspace,
+    /// The indexed `content_head` (the first lines of the file), used when the
+    /// file is not on disk, e.g. for a VCS overlay ref that is not checked out.
+    ContentHead,
+}
+
+/// Numbered source lines around one line of a file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LineWindow {
+    pub path: String,
+    pub line: u32,
+    pub line_start: u32,
+    pub line_end: u32,
+    /// Window text, each line prefixed as by [`number_lines`].
+    pub content: String,
+    pub source: LineWindowSource,
+}
+
+pub struct LineWindowParams<'a> {
+    pub workspace: &'a Path,
+    pub repo: &'a str,
+    pub path: &'a str,
+    pub line: u32,
+    pub context_lines: u32,
+    /// Files larger than this are refused (`0` = no limit).
+    pub max_file_size: u64,
+    /// `(files index, ref)` pairs searched in order for the file's
+    /// `content_head` when it is not on disk.
+    pub indexed_sources: &'a [(&'a Index, &'a str)],
+}
+
+/// Return `context_lines` lines on either side of `line` (1-based) in `path`.
+///
+/// The file is read from the workspace; when it is missing there the indexed
+/// `content_head` is used instead, which only covers the start of the file.
+pub fn get_line_window(params: LineWindowParams<'_>) -> Result<LineWindow, ContextError> {
+    let LineWindowParams {
+        workspace,
+        repo,
+        path,
+        line,
+        context_lines,
+        max_file_size,
+        indexed_sources,
+    } = params;
+
+    let relative = Path::new(path);
+    if path.is_empty()
+        || relative
+            .components()
+            .any(|component| !matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(ContextError::InvalidPath {
+            path: path.to_string(),
+        });
+    }
+
+    let full_path = workspace.join(relative);
+    let (content, source) = match std::fs::metadata(&full_path) {
+        Ok(meta) if meta.is_file() => {
+            if max_file_size > 0 && meta.len() > max_file_size {
+                return Err(ContextError::FileTooLarge {
+                    path: path.to_string(),
+                    size_bytes: meta.len(),
+                    max_file_size,
+                });
+            }
+            let content = std::fs::read_to_string(&full_path).map_err(StateError::Io)?;
+            (content, LineWindowSource::Workspace)
+        }
+        _ => {
+            let mut head = None;
+            for (index, ref_name) in indexed_sources {
+                head = indexed_content_head(index, repo, ref_name, path)?;
+                if head.is_some() {
+                    break;
+                }
+            }
+            let Some(head) = head else {
+                return Err(ContextError::FileNotFound {
+                    path: path.to_string(),
+                });
+            };
+            (head, LineWindowSource::ContentHead)
+        }
+    };
+
+    let lines: Vec<&str> = content.lines().collect();
+    let target = line.max(1);
+    if target as usize > lines.len() {
+        return Err(ContextError::LineOutOfRange {
+            path: path.to_string(),
+            line: target,
+            line_count: lines.len(),
+            read_from: source,
+        });
+    }
+    let line_start = target.saturating_sub(context_lines).max(1);
+    let line_end = target.saturating_add(context_lines).min(lines.len() as u32);
+    let body = lines[(line_start - 1) as usize..line_end as usize].join("\n");
+    Ok(LineWindow {
+        path: path.to_string(),
+        line: target,
+        line_start,
+        line_end,
+        content: number_lines(&body, line_start),
+        source,
+    })
+}
+
+/// Stored `content_head` of the files-index document for `repo|ref|path`.
+fn indexed_content_head(
+    index: &Index,
+    repo: &str,
+    ref_name: &str,
+    path: &str,
+) -> Result<Option<String>, StateError> {
+    let schema = index.schema();
+    let file_key_field = schema.get_field("file_key").map_err(StateError::tantivy)?;
+    let content_head_field = schema
+        .get_field("content_head")
+        .map_err(StateError::tantivy)?;
+    let reader = index.reader().map_err(StateError::tantivy)?;
+    let searcher = reader.searcher();
+    let key = cruxe_state::tantivy_index::file_key(repo, ref_name, path);
+    let query = TermQuery::new(
+        Term::from_field_text(file_key_field, &key),
+        IndexRecordOption::Basic,
+    );
+    let Some((_, address)) = searcher
+        .search(&query, &TopDocs::with_limit(1))
+        .map_err(StateError::tantivy)?
+        .into_iter()
+        .next()
+    else {
+        return Ok(None);
+    };
+    let doc = searcher
+        .doc::<tantivy::TantivyDocument>(address)
+        .map_err(StateError::tantivy)?;
+    Ok(doc
+        .get_first(content_head_field)
+        .and_then(|value| value.as_str())
+        .map(str::to_string))
+}
+
+/// Prefix each line of `body` with its absolute line number, right-aligned to
+/// a common width (`  42| fn foo() {`).
+pub fn number_lines(body: &str, first_line: u32) -> String {
+    let first_line = first_line.max(1) as usize;
+    let last_line = first_line + body.lines().count().saturating_sub(1);
+    let width = last_line.to_string().len().max(4);
+    body.lines()
+        .enumerate()
+        .map(|(offset, line)| format!("{:>width$}| {line}", first_line + offset))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A symbol body and the file lines it covers.
+struct SymbolBody {
+    text: String,
+    line_start: u32,
+    line_end: u32,
+}
+
+struct SymbolBodyParams<'a> {
+    workspace: &'a Path,
+    relative_path: &'a str,
     line_start: u32,
     line_end: u32,
-    fallback: Option<&str>,
-) -> String {
+    prefix_lines: u32,
+    suffix_lines: u32,
+    max_file_size: u64,
+    fallback: Option<&'a str>,
+}
+
+/// Read a symbol's lines from the workspace, widened by `prefix_lines` and
+/// `suffix_lines` and clamped to the file. Files over `max_file_size` are not
+/// widened; when the file cannot be read the indexed snippet is used.
+fn load_symbol_body(params: SymbolBodyParams<'_>) -> SymbolBody {
+    let SymbolBodyParams {
+        workspace,
+        relative_path,
+        line_start,
+        line_end,
+        prefix_lines,
+        suffix_lines,
+        max_file_size,
+        fallback,
+    } = params;
+    let fallback_body = || SymbolBody {
+        text: fallback.unwrap_or("").to_string(),
+        line_start,
+        line_end,
+    };
+
     if line_start == 0 || line_end == 0 || line_end < line_start {
-        return fallback.unwrap_or("").to_string();
+        return fallback_body();
     }
 
     let full_path = workspace.join(relative_path);
     let Ok(content) = std::fs::read_to_string(full_path) else {
-        return fallback.unwrap_or("").to_string();
+        return fallback_body();
     };
     let lines = content.lines().collect::<Vec<_>>();
     let start = (line_start.saturating_sub(1) as usize).min(lines.len());
     let end = (line_end as usize).min(lines.len());
     if start >= end {
-        return fallback.unwrap_or("").to_string();
+        return fallback_body();
+    }
+    let oversized = max_file_size > 0 && content.len() as u64 > max_file_size;
+    let (start, end) = if oversized {
+        (start, end)
+    } else {
+        (
+            start.saturating_sub(prefix_lines as usize),
+            end.saturating_add(suffix_lines as usize).min(lines.len()),
+        )
+    };
+    SymbolBody {
+        text: lines[start..end].join("\n"),
+        line_start: start as u32 + 1,
+        line_end: end as u32,
     }
-    lines[start..end].join("\n")
 }
 
 #[cfg(test)]
@@ -264,8 +701,259 @@ mod tests {
     #[test]
     fn load_symbol_body_uses_fallback_when_file_missing() {
         let workspace = std::path::Path::new("/tmp/non-existent-workspace");
-        let body = load_symbol_body(workspace, "missing.rs", 1, 2, Some("fallback"));
-        assert_eq!(body, "fallback");
+        let body = load_symbol_body(SymbolBodyParams {
+            workspace,
+            relative_path: "missing.rs",
+            line_start: 1,
+            line_end: 2,
+            prefix_lines: 3,
+            suffix_lines: 3,
+            max_file_size: 0,
+            fallback: Some("fallback"),
+        });
+        assert_eq!(body.text, "fallback");
+        assert_eq!((body.line_start, body.line_end), (1, 2));
+    }
+
+    #[test]
+    fn load_symbol_body_extends_window_within_file_bounds() {
+        let dir = tempfile::tempdir().unwrap();
+        let source: String = (1..=10).map(|n| format!("line {n}\n")).collect();
+        std::fs::write(dir.path().join("lib.rs"), &source).unwrap();
+        let params =
+            |line_start, line_end, prefix_lines, suffix_lines, max_file_size| SymbolBodyParams {
+                workspace: dir.path(),
+                relative_path: "lib.rs",
+                line_start,
+                line_end,
+                prefix_lines,
+                suffix_lines,
+                max_file_size,
+                fallback: None,
+            };
+
+        let widened = load_symbol_body(params(4, 5, 2, 1, 0));
+        assert_eq!((widened.line_start, widened.line_end), (2, 6));
+        assert_eq!(widened.text, "line 2\nline 3\nline 4\nline 5\nline 6");
+
+        let clamped = load_symbol_body(params(2, 9, 5, 5, 0));
+        assert_eq!((clamped.line_start, clamped.line_end), (1, 10));
+
+        // Too large to widen: only the symbol's own lines come back.
+        let oversized = load_symbol_body(params(4, 5, 2, 1, 10));
+        assert_eq!((oversized.line_start, oversized.line_end), (4, 5));
+        assert_eq!(oversized.text, "line 4\nline 5");
+    }
+
+    #[test]
+    fn number_lines_prefixes_absolute_line_numbers() {
+        assert_eq!(
+            number_lines("fn foo() {\n    1\n}", 42),
+            "  42| fn foo() {\n  43|     1\n  44| }"
+        );
+        assert_eq!(number_lines("a\nb", 99_999), " 99999| a\n100000| b");
+    }
+
+    fn window(workspace: &Path, path: &str, line: u32, context_lines: u32) -> LineWindow {
+        get_line_window(LineWindowParams {
+            workspace,
+            repo: "proj",
+            path,
+            line,
+            context_lines,
+            max_file_size: 1_048_576,
+            indexed_sources: &[],
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn line_window_reads_numbered_lines_clamped_to_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let source: String = (1..=30).map(|n| format!("line {n}\n")).collect();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/lib.rs"), &source).unwrap();
+
+        let middle = window(dir.path(), "src/lib.rs", 15, 2);
+        assert_eq!((middle.line_start, middle.line_end), (13, 17));
+        assert_eq!(middle.source, LineWindowSource::Workspace);
+        assert!(middle.content.starts_with("  13| line 13"));
+        assert!(middle.content.ends_with("  17| line 17"));
+
+        let head = window(dir.path(), "src/lib.rs", 2, 5);
+        assert_eq!((head.line_start, head.line_end), (1, 7));
+        let tail = window(dir.path(), "src/lib.rs", 30, 5);
+        assert_eq!((tail.line_start, tail.line_end), (25, 30));
+
+        let params = |path, line, max_file_size| LineWindowParams {
+            workspace: dir.path(),
+            repo: "proj",
+            path,
+            line,
+            context_lines: 2,
+            max_file_size,
+            indexed_sources: &[],
+        };
+        assert!(matches!(
+            get_line_window(params("src/lib.rs", 31, 0)),
+            Err(ContextError::LineOutOfRange { line_count: 30, .. })
+        ));
+        assert!(matches!(
+            get_line_window(params("src/lib.rs", 1, 10)),
+            Err(ContextError::FileTooLarge { .. })
+        ));
+        assert!(matches!(
+            get_line_window(params("../etc/passwd", 1, 0)),
+            Err(ContextError::InvalidPath { .. })
+        ));
+    }
+
+    #[test]
+    fn line_window_falls_back_to_indexed_content_head() {
+        let dir = tempfile::tempdir().unwrap();
+        let index_set = IndexSet::open(&dir.path().join("index")).unwrap();
+        let conn = cruxe_state::db::open_connection(&dir.path().join("state.db")).unwrap();
+        cruxe_state::schema::create_tables(&conn).unwrap();
+        let content: String = (1..=40).map(|n| format!("row {n}\n")).collect();
+        let file = cruxe_indexer::prepare::build_file_record(
+            "proj",
+            "feat/x",
+            "src/new.rs",
+            "new.rs",
+            "rust",
+            &content,
+        );
+        cruxe_indexer::writer::write_file_records(&index_set, &conn, &[], &[], &file).unwrap();
+
+        let workspace = dir.path().join("workspace");
+        let sources = [(&index_set.files, "feat/x")];
+        let params = |line| LineWindowParams {
+            workspace: &workspace,
+            repo: "proj",
+            path: "src/new.rs",
+            line,
+            context_lines: 1,
+            max_file_size: 0,
+            indexed_sources: &sources,
+        };
+        let found = get_line_window(params(5)).unwrap();
+        assert_eq!(found.source, LineWindowSource::ContentHead);
+        assert_eq!(found.content, "   4| row 4\n   5| row 5\n   6| row 6");
+
+        // Only the indexed head is available, not the whole file.
+        assert!(matches!(
+            get_line_window(params(30)),
+            Err(ContextError::LineOutOfRange {
+                read_from: LineWindowSource::ContentHead,
+                ..
+            })
+        ));
+        assert!(matches!(
+            get_line_window(LineWindowParams {
+                path: "src/gone.rs",
+                ..params(1)
+            }),
+            Err(ContextError::FileNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn symbols_context_returns_bodies_in_request_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let index_set = IndexSet::open(&dir.path().join("index")).unwrap();
+        let conn = cruxe_state::db::open_connection(&dir.path().join("state.db")).unwrap();
+        cruxe_state::schema::create_tables(&conn).unwrap();
+        let workspace = dir.path().join("workspace");
+        std::fs::create_dir_all(workspace.join("src")).unwrap();
+        let content = "use std::io;\n\nfn read() {}\n\nfn write() {}\n";
+        std::fs::write(workspace.join("src/io.rs"), content).unwrap();
+        let file = cruxe_indexer::prepare::build_file_record(
+            "proj",
+            "main",
+            "src/io.rs",
+            "io.rs",
+            "rust",
+            content,
+        );
+        let symbol = |name: &str, line: u32| cruxe_core::types::SymbolRecord {
+            repo: "proj".to_string(),
+            r#ref: "main".to_string(),
+            commit: None,
+            path: "src/io.rs".to_string(),
+            language: "rust".to_string(),
+            symbol_id: format!("sym-{name}"),
+            symbol_stable_id: format!("stable-{name}"),
+            name: name.to_string(),
+            qualified_name: name.to_string(),
+            kind: cruxe_core::types::SymbolKind::Function,
+            signature: Some(format!("fn {name}()")),
+            line_start: line,
+            line_end: line,
+            parent_symbol_id: None,
+            visibility: None,
+            content: Some(format!("fn {name}() {{}}")),
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
+        };
+        cruxe_indexer::writer::write_file_records(
+            &index_set,
+            &conn,
+            &[symbol("read", 3), symbol("write", 5)],
+            &[],
+            &file,
+        )
+        .unwrap();
+
+        let search_config = CoreSearchConfig::default();
+        let ids = [
+            "stable-write".to_string(),
+            "nope".to_string(),
+            "sym-read".to_string(),
+        ];
+        let run = |max_tokens, max_response_bytes| {
+            get_symbols_context(GetSymbolsContextParams {
+                conn: &conn,
+                search_config: &search_config,
+                workspace: &workspace,
+                repo: "proj",
+                ref_name: "main",
+                ids: &ids,
+                max_tokens,
+                policy_mode_override: None,
+                with_line_numbers: false,
+                prefix_lines: 2,
+                suffix_lines: 0,
+                max_file_size: 0,
+                max_response_bytes,
+            })
+            .unwrap()
+        };
+
+        let full = run(4000, 0);
+        assert!(!full.truncated);
+        let names: Vec<&str> = full
+            .context_items
+            .iter()
+            .map(|item| item["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["write", "read"]);
+        assert_eq!(
+            full.context_items[1]["body"],
+            "use std::io;\n\nfn read() {}"
+        );
+        assert_eq!(full.metadata["missing_ids"], json!(["nope"]));
+
+        let capped = run(4000, 64);
+        assert!(capped.truncated);
+        assert!(capped.context_items.is_empty());
+        assert_eq!(capped.metadata["safety_limit_applied"], true);
+        assert_eq!(
+            capped.metadata["remaining_ids"],
+            json!(["stable-write", "nope", "sym-read"])
+        );
     }
 
     #[test]
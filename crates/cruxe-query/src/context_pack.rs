@@ -435,7 +435,7 @@ fn assemble_pack(
     let mut dropped_candidates = 0usize;
     let mut dropped_by_budget = BTreeMap::<String, usize>::new();
 
-    for (section, candidate) in primary_queue.into_iter().chain(overflow_queue.into_iter()) {
+    for (section, candidate) in primary_queue.into_iter().chain(overflow_queue) {
         if token_budget_used.saturating_add(candidate.estimated_tokens) > budget_tokens {
             dropped_candidates += 1;
             *dropped_by_budget
@@ -920,6 +920,8 @@ mod tests {
             path: path.to_string(),
             line_start,
             line_end,
+            byte_start: None,
+            byte_end: None,
             kind: Some("function".to_string()),
             name: Some(format!("name_{result_id}")),
             qualified_name: Some(format!("qualified::{result_id}")),
@@ -931,6 +933,7 @@ mod tests {
             chunk_type: Some("function_body".to_string()),
             source_layer: None,
             provenance: "lexical".to_string(),
+            highlight: None,
         }
     }
 
@@ -1,4 +1,4 @@
-use cruxe_core::types::DetailLevel;
+use cruxe_core::types::{DetailLevel, ParsedSignature};
 use rusqlite::Connection;
 use serde_json::{Value, json};
 
@@ -15,9 +15,13 @@ const LOCATION_FIELDS: &[&str] = &[
     "path",
     "line_start",
     "line_end",
+    "byte_start",
+    "byte_end",
     "kind",
     "name",
+    "match_type",
     "score",
+    "highlight",
 ];
 const SIGNATURE_FIELDS: &[&str] = &[
     "symbol_id",
@@ -29,13 +33,19 @@ const SIGNATURE_FIELDS: &[&str] = &[
     "path",
     "line_start",
     "line_end",
+    "byte_start",
+    "byte_end",
     "kind",
     "name",
     "qualified_name",
     "signature",
     "language",
     "visibility",
+    "attributes",
+    "parent",
+    "match_type",
     "score",
+    "highlight",
 ];
 const COMPACT_OMIT_FIELDS: &[&str] = &["snippet", "body_preview", "parent", "related_symbols"];
 
@@ -141,6 +151,52 @@ pub fn resolve_parent(
     .ok()
 }
 
+/// Stored annotations for a symbol: its doc comment, attributes/decorators and
+/// structured signature.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SymbolAnnotations {
+    pub doc_comment: Option<String>,
+    pub attributes: Vec<String>,
+    pub parsed_signature: Option<ParsedSignature>,
+}
+
+/// Look up the stored doc comment, attributes and parsed signature for a symbol.
+/// Symbol ids already encode the ref, so only the repo is needed to scope the lookup.
+pub fn resolve_symbol_annotations(
+    conn: &Connection,
+    repo: &str,
+    symbol_id: &str,
+) -> Option<SymbolAnnotations> {
+    if symbol_id.is_empty() {
+        return None;
+    }
+    let mut stmt = conn
+        .prepare(
+            "SELECT doc_comment, attributes, parsed_signature FROM symbol_relations
+         WHERE repo = ?1 AND symbol_id = ?2
+         LIMIT 1",
+        )
+        .ok()?;
+    stmt.query_row(rusqlite::params![repo, symbol_id], |row| {
+        let doc_comment = row
+            .get::<_, Option<String>>(0)?
+            .filter(|doc| !doc.is_empty());
+        let attributes = row
+            .get::<_, Option<String>>(1)?
+            .and_then(|raw| serde_json::from_str::<Vec<String>>(&raw).ok())
+            .unwrap_or_default();
+        let parsed_signature = row
+            .get::<_, Option<String>>(2)?
+            .and_then(|raw| serde_json::from_str::<ParsedSignature>(&raw).ok());
+        Ok(SymbolAnnotations {
+            doc_comment,
+            attributes,
+            parsed_signature,
+        })
+    })
+    .ok()
+}
+
 /// Find related symbols in the same file (siblings or nearby symbols), limited to N.
 pub fn resolve_related_symbols(
     conn: &Connection,
@@ -196,7 +252,8 @@ pub fn enrich_body_previews(results: &mut [Value]) {
     }
 }
 
-/// Enrich result JSON objects with parent and related_symbols from SQLite.
+/// Enrich result JSON objects with parent, doc_comment, parsed_signature and
+/// related_symbols from SQLite.
 /// Requires a DB connection.
 pub fn enrich_results_with_relations(
     results: &mut [Value],
@@ -209,14 +266,31 @@ pub fn enrich_results_with_relations(
             continue;
         };
 
-        // parent resolution
+        // parent resolution; locate results already carry their own `parent`
         let parent_id = get_parent_symbol_id(conn, repo, r#ref, obj);
-        if let Some(parent_id) = &parent_id
+        if !obj.contains_key("parent")
+            && let Some(parent_id) = &parent_id
             && let Some(parent) = resolve_parent(conn, repo, r#ref, parent_id)
         {
             obj.insert("parent".to_string(), parent);
         }
 
+        // doc comment and parsed signature resolution
+        let annotations = obj
+            .get("symbol_id")
+            .and_then(|v| v.as_str())
+            .and_then(|symbol_id| resolve_symbol_annotations(conn, repo, symbol_id))
+            .unwrap_or_default();
+        if let Some(doc) = annotations.doc_comment {
+            obj.insert("doc_comment".to_string(), Value::String(doc));
+        }
+        if let Some(parsed) = annotations
+            .parsed_signature
+            .and_then(|parsed| serde_json::to_value(parsed).ok())
+        {
+            obj.insert("parsed_signature".to_string(), parsed);
+        }
+
         // related_symbols resolution
         let symbol_id = obj.get("symbol_id").and_then(|v| v.as_str()).unwrap_or("");
         let path = obj.get("path").and_then(|v| v.as_str()).unwrap_or("");
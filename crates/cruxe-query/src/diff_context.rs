@@ -151,7 +151,7 @@ fn matches_path_filter(entry: &DiffEntry, prefix: &str) -> bool {
     }
 }
 
-fn summarize_file_changes(entries: &[DiffEntry]) -> Vec<DiffFileChange> {
+pub(crate) fn summarize_file_changes(entries: &[DiffEntry]) -> Vec<DiffFileChange> {
     entries
         .iter()
         .map(|entry| match &entry.kind {
@@ -381,6 +381,11 @@ mod tests {
                 parent_symbol_id: None,
                 visibility: Some("pub".to_string()),
                 content: Some(content.to_string()),
+                attributes: Vec::new(),
+                byte_start: None,
+                byte_end: None,
+                doc_comment: None,
+                parsed_signature: None,
             },
         )
         .unwrap();
@@ -224,6 +224,11 @@ mod tests {
             content: Some(
                 "pub fn validate_token(token: &str) -> bool { !token.is_empty() }".to_string(),
             ),
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
         };
         let snippet = cruxe_core::types::SnippetRecord {
             repo: repo.to_string(),
@@ -248,6 +253,7 @@ mod tests {
             size_bytes: 120,
             updated_at: now_iso8601(),
             content_head: Some("pub fn validate_token(token: &str) -> bool".to_string()),
+            encoding_warning: false,
         };
         writer::write_file_records(index_set, conn, &[symbol], &[snippet], &file)
     }
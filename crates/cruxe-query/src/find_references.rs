@@ -1,6 +1,6 @@
 use cruxe_core::error::StateError;
 use cruxe_core::types::{SourceLayer, SymbolRecord};
-use cruxe_state::{project, symbols, tombstones};
+use cruxe_state::{edges, project, symbols, tombstones};
 use rusqlite::{Connection, params};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
@@ -29,6 +29,18 @@ pub struct ReferenceSymbol {
     pub line_end: Option<u32>,
 }
 
+/// Edge type reported for references found by scanning symbol bodies for the
+/// target's name rather than through a relation edge.
+pub const NAME_MATCH_EDGE_TYPE: &str = "name_match";
+
+/// Whether a reference is the target's own declaration or a use of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferenceKind {
+    Definition,
+    Usage,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReferenceResult {
     pub path: String,
@@ -36,6 +48,7 @@ pub struct ReferenceResult {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub line_end: Option<u32>,
     pub edge_type: String,
+    pub kind: ReferenceKind,
     pub source_layer: SourceLayer,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub context: Option<String>,
@@ -50,6 +63,15 @@ pub struct FindReferencesResult {
     pub unresolved_count: usize,
 }
 
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FindReferencesOptions<'a> {
+    /// Only follow relation edges of this type; disables name matching.
+    pub kind_filter: Option<&'a str>,
+    /// Also report every line of a symbol body in the ref that mentions the
+    /// target name as a whole word, not just relation edges.
+    pub include_name_matches: bool,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct EdgeRow {
     from_symbol_id: String,
@@ -66,6 +88,37 @@ pub fn find_references(
     symbol_name: &str,
     limit: usize,
 ) -> Result<FindReferencesResult, FindReferencesError> {
+    find_references_with_options(
+        conn,
+        workspace,
+        project_id,
+        ref_name,
+        symbol_name,
+        FindReferencesOptions {
+            kind_filter,
+            include_name_matches: false,
+        },
+        limit,
+    )
+}
+
+/// Find references to `symbol_name`, which may also be a qualified name or a
+/// `symbol_stable_id`.
+///
+/// Results are ranked definition first, then by locality: the target's own
+/// file, files in the same directory or importing the target's file, and
+/// finally everything else.
+pub fn find_references_with_options(
+    conn: &Connection,
+    workspace: &Path,
+    project_id: &str,
+    ref_name: &str,
+    symbol_name: &str,
+    options: FindReferencesOptions<'_>,
+    limit: usize,
+) -> Result<FindReferencesResult, FindReferencesError> {
+    let kind_filter = options.kind_filter;
+    let include_name_matches = options.include_name_matches && kind_filter.is_none();
     let Some(project_row) = project::get_by_id(conn, project_id)? else {
         return Err(FindReferencesError::State(StateError::ProjectNotFound {
             project_id: project_id.to_string(),
@@ -97,7 +150,7 @@ pub fn find_references(
     } else {
         edge_count(conn, project_id, ref_name)? == 0
     };
-    if no_edges {
+    if no_edges && !include_name_matches {
         return Err(FindReferencesError::NoEdgesAvailable);
     }
 
@@ -119,6 +172,7 @@ pub fn find_references(
     };
 
     let mut merged: HashMap<(String, String, String), ReferenceResult> = HashMap::new();
+    let layered = project_row.vcs_mode && ref_name != project_row.default_ref;
     let tombstone_paths = if project_row.vcs_mode && ref_name != project_row.default_ref {
         tombstones::list_paths_for_ref(conn, project_id, ref_name)?
             .into_iter()
@@ -155,10 +209,64 @@ pub fn find_references(
         merged.insert(key, reference);
     }
 
+    if include_name_matches {
+        let layers = if layered {
+            vec![
+                (project_row.default_ref.as_str(), SourceLayer::Base),
+                (ref_name, SourceLayer::Overlay),
+            ]
+        } else {
+            vec![(ref_name, SourceLayer::Base)]
+        };
+        for (layer_ref, source_layer) in layers {
+            for reference in
+                query_name_matches(conn, project_id, layer_ref, &target_symbol, source_layer)?
+            {
+                if source_layer == SourceLayer::Base && tombstone_paths.contains(&reference.path) {
+                    continue;
+                }
+                let key = reference_key(&reference);
+                merged.insert(key, reference);
+            }
+        }
+    }
+
+    let mut nearby_paths: HashSet<String> =
+        edges::get_import_edges_into_file(conn, project_id, ref_name, &target_symbol.path, None)?
+            .into_iter()
+            .map(|edge| edge.source_path)
+            .collect();
+    if layered {
+        nearby_paths.extend(
+            edges::get_import_edges_into_file(
+                conn,
+                project_id,
+                &project_row.default_ref,
+                &target_symbol.path,
+                None,
+            )?
+            .into_iter()
+            .map(|edge| edge.source_path),
+        );
+    }
+    let locality = |path: &str| -> u8 {
+        if path == target_symbol.path {
+            0
+        } else if nearby_paths.contains(path)
+            || Path::new(path).parent() == Path::new(&target_symbol.path).parent()
+        {
+            1
+        } else {
+            2
+        }
+    };
+
     let mut references: Vec<ReferenceResult> = merged.into_values().collect();
     references.sort_by(|a, b| {
-        a.path
-            .cmp(&b.path)
+        (a.kind != ReferenceKind::Definition)
+            .cmp(&(b.kind != ReferenceKind::Definition))
+            .then_with(|| locality(&a.path).cmp(&locality(&b.path)))
+            .then_with(|| a.path.cmp(&b.path))
             .then_with(|| a.line_start.cmp(&b.line_start))
             .then_with(|| a.edge_type.cmp(&b.edge_type))
             .then_with(|| a.from_symbol.symbol_id.cmp(&b.from_symbol.symbol_id))
@@ -215,6 +323,10 @@ fn lookup_symbol(
     if let Some(first) = exact.into_iter().next() {
         return Ok(Some(first));
     }
+    if let Some(symbol) = symbols::get_symbol_by_stable_id(conn, project_id, ref_name, symbol_name)?
+    {
+        return Ok(Some(symbol));
+    }
 
     let mut stmt = conn
         .prepare(
@@ -245,6 +357,11 @@ fn lookup_symbol(
             parent_symbol_id: row.get(13)?,
             visibility: row.get(14)?,
             content: None,
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
         })
     }) {
         Ok(symbol) => Ok(Some(symbol)),
@@ -362,12 +479,117 @@ fn to_reference_result(
         line_start,
         line_end: from_symbol.line_end,
         edge_type: row.edge_type,
+        kind: ReferenceKind::Usage,
         source_layer,
         context: read_source_line(workspace, ref_name, &path, line_start),
         from_symbol,
     })
 }
 
+/// Lines of symbol bodies in `ref_name` that mention the target name as a
+/// whole word, attributed to the innermost enclosing symbol.
+///
+/// The first such line inside the target itself is its definition; every
+/// other line, including recursive uses, is a usage.
+fn query_name_matches(
+    conn: &Connection,
+    project_id: &str,
+    ref_name: &str,
+    target: &SymbolRecord,
+    source_layer: SourceLayer,
+) -> Result<Vec<ReferenceResult>, StateError> {
+    let pattern = format!("%{}%", escape_like_pattern(&target.name));
+    let mut stmt = conn
+        .prepare(
+            "SELECT symbol_id, symbol_stable_id, name, qualified_name, kind, path, \
+                    line_start, line_end, content \
+             FROM symbol_relations \
+             WHERE repo = ?1 AND \"ref\" = ?2 AND content LIKE ?3 ESCAPE '\\' \
+             ORDER BY path, line_start",
+        )
+        .map_err(StateError::sqlite)?;
+    let rows = stmt
+        .query_map(params![project_id, ref_name, pattern], |row| {
+            Ok((
+                ReferenceSymbol {
+                    symbol_id: row.get(0)?,
+                    symbol_stable_id: row.get(1)?,
+                    name: row.get(2)?,
+                    qualified_name: row.get(3)?,
+                    kind: row.get(4)?,
+                    path: row.get(5)?,
+                    line_start: row.get(6)?,
+                    line_end: row.get(7)?,
+                },
+                row.get::<_, Option<String>>(8)?.unwrap_or_default(),
+            ))
+        })
+        .map_err(StateError::sqlite)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(StateError::sqlite)?;
+
+    // Nested symbols repeat their parent's lines; keep the innermost owner.
+    let mut by_line: HashMap<(String, u32), ReferenceResult> = HashMap::new();
+    for (symbol, content) in rows {
+        let is_target = symbol.symbol_id == target.symbol_id;
+        let mut definition_seen = false;
+        for (offset, line) in content.lines().enumerate() {
+            if !contains_identifier(line, &target.name) {
+                continue;
+            }
+            let kind = if is_target && !definition_seen {
+                definition_seen = true;
+                ReferenceKind::Definition
+            } else {
+                ReferenceKind::Usage
+            };
+            let line_number = symbol.line_start + offset as u32;
+            let key = (symbol.path.clone(), line_number);
+            if let Some(existing) = by_line.get(&key)
+                && (existing.kind == ReferenceKind::Definition
+                    || (kind == ReferenceKind::Usage
+                        && line_span(&existing.from_symbol) <= line_span(&symbol)))
+            {
+                continue;
+            }
+            by_line.insert(
+                key,
+                ReferenceResult {
+                    path: symbol.path.clone(),
+                    line_start: line_number,
+                    line_end: None,
+                    edge_type: NAME_MATCH_EDGE_TYPE.to_string(),
+                    kind,
+                    source_layer,
+                    context: Some(line.trim().to_string()),
+                    from_symbol: symbol.clone(),
+                },
+            );
+        }
+    }
+    Ok(by_line.into_values().collect())
+}
+
+fn line_span(symbol: &ReferenceSymbol) -> u32 {
+    symbol
+        .line_end
+        .unwrap_or(symbol.line_start)
+        .saturating_sub(symbol.line_start)
+}
+
+/// Whether `line` contains `name` delimited by non-identifier characters.
+fn contains_identifier(line: &str, name: &str) -> bool {
+    if name.is_empty() {
+        return false;
+    }
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    line.match_indices(name).any(|(start, _)| {
+        let before = line[..start].chars().next_back();
+        let after = line[start + name.len()..].chars().next();
+        !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+    })
+}
+
 fn resolve_from_symbol(
     conn: &Connection,
     project_id: &str,
@@ -412,7 +634,7 @@ fn resolve_from_symbol(
     })
 }
 
-fn symbol_to_reference_symbol(symbol: &SymbolRecord) -> ReferenceSymbol {
+pub(crate) fn symbol_to_reference_symbol(symbol: &SymbolRecord) -> ReferenceSymbol {
     ReferenceSymbol {
         symbol_id: symbol.symbol_id.clone(),
         symbol_stable_id: symbol.symbol_stable_id.clone(),
@@ -479,9 +701,15 @@ fn read_source_line_from_git_ref(
 }
 
 fn reference_key(reference: &ReferenceResult) -> (String, String, String) {
+    // Name matches are per line; relation edges are per referencing symbol.
+    let symbol_or_line = if reference.edge_type == NAME_MATCH_EDGE_TYPE {
+        reference.line_start.to_string()
+    } else {
+        reference.from_symbol.symbol_id.clone()
+    };
     (
         reference.path.clone(),
-        reference.from_symbol.symbol_id.clone(),
+        symbol_or_line,
         reference.edge_type.clone(),
     )
 }
@@ -543,6 +771,11 @@ mod tests {
                 parent_symbol_id: None,
                 visibility: Some("pub".to_string()),
                 content: Some(name.to_string()),
+                attributes: Vec::new(),
+                byte_start: None,
+                byte_end: None,
+                doc_comment: None,
+                parsed_signature: None,
             },
         )
         .unwrap();
@@ -981,4 +1214,122 @@ mod tests {
 
         assert_eq!(result.unresolved_count, 3);
     }
+
+    #[test]
+    fn find_references_name_matches_rank_definition_and_nearby_files_first() {
+        let (tmp, conn) = setup();
+        let workspace = tmp.path().join("workspace");
+        let project_id = "proj";
+        let now = "2026-02-25T00:00:00Z".to_string();
+        project::create_project(
+            &conn,
+            &cruxe_core::types::Project {
+                project_id: project_id.to_string(),
+                repo_root: workspace.to_string_lossy().to_string(),
+                display_name: Some("test".to_string()),
+                default_ref: "main".to_string(),
+                vcs_mode: false,
+                schema_version: 1,
+                parser_version: 1,
+                created_at: now.clone(),
+                updated_at: now,
+            },
+        )
+        .unwrap();
+
+        let bodies = [
+            (
+                "sym-target",
+                "stable-target",
+                "validate_token",
+                "src/auth.rs",
+                10,
+                "pub fn validate_token() {\n    validate_token_inner();\n}",
+            ),
+            (
+                "sym-cli",
+                "stable-cli",
+                "run_cli",
+                "tools/cli.rs",
+                4,
+                "fn run_cli() {\n    let check = validate_token;\n}",
+            ),
+            (
+                "sym-caller",
+                "stable-caller",
+                "call_site",
+                "src/lib.rs",
+                2,
+                "fn call_site() {\n    validate_token();\n}",
+            ),
+        ];
+        for (id, stable_id, name, path, line, body) in bodies {
+            insert_symbol(&conn, project_id, "main", id, stable_id, name, path, line);
+            conn.execute(
+                "UPDATE symbol_relations SET content = ?1 WHERE symbol_id = ?2",
+                params![body, id],
+            )
+            .unwrap();
+        }
+
+        assert!(matches!(
+            find_references(
+                &conn,
+                &workspace,
+                project_id,
+                "main",
+                None,
+                "validate_token",
+                20
+            ),
+            Err(FindReferencesError::NoEdgesAvailable)
+        ));
+
+        let result = find_references_with_options(
+            &conn,
+            &workspace,
+            project_id,
+            "main",
+            "stable-target",
+            FindReferencesOptions {
+                kind_filter: None,
+                include_name_matches: true,
+            },
+            20,
+        )
+        .unwrap();
+
+        assert_eq!(result.symbol.name, "validate_token");
+        let hits: Vec<(&str, u32, ReferenceKind)> = result
+            .references
+            .iter()
+            .map(|reference| {
+                (
+                    reference.path.as_str(),
+                    reference.line_start,
+                    reference.kind,
+                )
+            })
+            .collect();
+        assert_eq!(
+            hits,
+            vec![
+                ("src/auth.rs", 10, ReferenceKind::Definition),
+                ("src/lib.rs", 3, ReferenceKind::Usage),
+                ("tools/cli.rs", 5, ReferenceKind::Usage),
+            ],
+            "validate_token_inner must not match and same-module usages rank first"
+        );
+        assert!(
+            result
+                .references
+                .iter()
+                .all(|reference| reference.edge_type == NAME_MATCH_EDGE_TYPE)
+        );
+        assert_eq!(
+            result.references[1].context.as_deref(),
+            Some("validate_token();")
+        );
+        assert_eq!(result.references[2].from_symbol.name, "run_cli");
+    }
 }
@@ -2,7 +2,7 @@ use cruxe_core::error::StateError;
 use cruxe_state::symbols;
 use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use thiserror::Error;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -45,6 +45,19 @@ pub struct HierarchyResponse {
     pub chain_length: usize,
 }
 
+/// Symbols of one module path, nested by parent links across its files.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleHierarchyResponse {
+    pub module: String,
+    /// Top-level symbols of the module; each node's `path` is its source file.
+    pub hierarchy: Vec<HierarchyNode>,
+    /// Files contributing symbols, in path order.
+    pub files: Vec<String>,
+    /// Symbols matched before `limit` was applied.
+    pub symbol_count: usize,
+    pub truncated: bool,
+}
+
 #[derive(Debug, Error)]
 pub enum HierarchyError {
     #[error("symbol not found")]
@@ -80,27 +93,7 @@ pub fn get_symbol_hierarchy(
 
     match direction {
         HierarchyDirection::Ancestors => {
-            let mut nodes = Vec::new();
-            let mut visited = HashSet::new();
-            let mut current = anchor;
-            let mut depth = 0u32;
-
-            loop {
-                if !visited.insert(current.symbol_id.clone()) {
-                    break;
-                }
-                nodes.push(to_hierarchy_node(current.clone(), depth, Vec::new()));
-                let Some(parent_id) = current.parent_symbol_id.as_deref() else {
-                    break;
-                };
-                let Some(parent) = symbols::get_symbol_by_id(conn, repo, ref_name, parent_id)?
-                else {
-                    break;
-                };
-                current = parent;
-                depth += 1;
-            }
-
+            let nodes = ancestor_chain(conn, repo, ref_name, anchor)?;
             let chain_length = nodes.len();
             Ok(HierarchyResponse {
                 hierarchy: nodes,
@@ -121,6 +114,170 @@ pub fn get_symbol_hierarchy(
     }
 }
 
+/// Collect the symbols of a module path such as `crate::auth` or `auth::session`
+/// across every file of the ref. A symbol belongs to the module when its
+/// qualified name starts with the path; Rust symbols are also matched by their
+/// file's module path (`src/auth/handler.rs` is `auth::handler`), since their
+/// qualified names only carry in-file parents. `crate` alone selects every Rust
+/// symbol under a `src/` directory. Nesting follows `parent_symbol_id` within
+/// the matched set; the first `limit` symbols in path order are kept
+/// (`0` = no cap).
+pub fn get_module_hierarchy(
+    conn: &Connection,
+    repo: &str,
+    ref_name: &str,
+    module: &str,
+    limit: usize,
+) -> Result<ModuleHierarchyResponse, HierarchyError> {
+    let module = module.trim();
+    let scope = if module == "crate" {
+        ""
+    } else {
+        module.strip_prefix("crate::").unwrap_or(module)
+    };
+    let first_segment = scope.split("::").next().unwrap_or_default();
+    let path_fragment = format!("src/{first_segment}");
+    let candidates = symbols::list_module_scope_candidates(
+        conn,
+        repo,
+        ref_name,
+        (!scope.is_empty()).then_some(scope),
+        Some(&path_fragment),
+    )?;
+    let mut matched: Vec<cruxe_core::types::SymbolRecord> = candidates
+        .into_iter()
+        .filter(|symbol| symbol_in_module(symbol, scope))
+        .collect();
+    if matched.is_empty() {
+        return Err(HierarchyError::SymbolNotFound);
+    }
+
+    let symbol_count = matched.len();
+    let truncated = limit > 0 && symbol_count > limit;
+    if truncated {
+        matched.truncate(limit);
+    }
+    let mut files: Vec<String> = Vec::new();
+    for symbol in &matched {
+        if files.last() != Some(&symbol.path) {
+            files.push(symbol.path.clone());
+        }
+    }
+
+    let matched_ids: HashSet<String> = matched
+        .iter()
+        .map(|symbol| symbol.symbol_id.clone())
+        .collect();
+    let mut roots = Vec::new();
+    let mut children_of: HashMap<String, Vec<cruxe_core::types::SymbolRecord>> = HashMap::new();
+    for symbol in matched {
+        match symbol.parent_symbol_id.clone() {
+            Some(parent) if matched_ids.contains(&parent) => {
+                children_of.entry(parent).or_default().push(symbol)
+            }
+            _ => roots.push(symbol),
+        }
+    }
+    let hierarchy = roots
+        .into_iter()
+        .map(|root| nest_module_children(root, 0, &mut children_of))
+        .collect();
+
+    Ok(ModuleHierarchyResponse {
+        module: module.to_string(),
+        hierarchy,
+        files,
+        symbol_count,
+        truncated,
+    })
+}
+
+fn nest_module_children(
+    symbol: cruxe_core::types::SymbolRecord,
+    depth: u32,
+    children_of: &mut HashMap<String, Vec<cruxe_core::types::SymbolRecord>>,
+) -> HierarchyNode {
+    // Removing the entry also guards against parent-link cycles.
+    let children = children_of
+        .remove(&symbol.symbol_id)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|child| nest_module_children(child, depth + 1, children_of))
+        .collect();
+    to_hierarchy_node(symbol, depth, children)
+}
+
+fn symbol_in_module(symbol: &cruxe_core::types::SymbolRecord, scope: &str) -> bool {
+    let file_module = (symbol.language == "rust")
+        .then(|| rust_file_module_path(&symbol.path))
+        .flatten();
+    if scope.is_empty() {
+        return file_module.is_some();
+    }
+    if name_in_module(&symbol.qualified_name, scope) {
+        return true;
+    }
+    file_module.is_some_and(|file_module| {
+        if file_module.is_empty() {
+            return false;
+        }
+        name_in_module(&format!("{file_module}::{}", symbol.qualified_name), scope)
+    })
+}
+
+fn name_in_module(name: &str, scope: &str) -> bool {
+    name.strip_prefix(scope)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with("::") || rest.starts_with('.'))
+}
+
+/// Module path of a Rust source file relative to its crate root, e.g.
+/// `crates/core/src/auth/mod.rs` -> `auth`; `src/lib.rs` -> `""`. `None` for
+/// files outside a `src/` directory.
+fn rust_file_module_path(path: &str) -> Option<String> {
+    let stem = path.strip_suffix(".rs")?;
+    let relative = if let Some(rest) = stem.strip_prefix("src/") {
+        rest
+    } else {
+        let index = stem.rfind("/src/")?;
+        &stem[index + "/src/".len()..]
+    };
+    let mut segments: Vec<&str> = relative.split('/').collect();
+    if segments.last() == Some(&"mod") || matches!(segments.as_slice(), ["lib"] | ["main"]) {
+        segments.pop();
+    }
+    Some(segments.join("::"))
+}
+
+/// Walk `parent_symbol_id` links from `symbol` upward. The first node is
+/// `symbol` itself at depth 0; each parent is one level deeper.
+pub fn ancestor_chain(
+    conn: &Connection,
+    repo: &str,
+    ref_name: &str,
+    symbol: cruxe_core::types::SymbolRecord,
+) -> Result<Vec<HierarchyNode>, StateError> {
+    let mut nodes = Vec::new();
+    let mut visited = HashSet::new();
+    let mut current = symbol;
+    let mut depth = 0u32;
+
+    loop {
+        if !visited.insert(current.symbol_id.clone()) {
+            break;
+        }
+        nodes.push(to_hierarchy_node(current.clone(), depth, Vec::new()));
+        let Some(parent_id) = current.parent_symbol_id.as_deref() else {
+            break;
+        };
+        let Some(parent) = symbols::get_symbol_by_id(conn, repo, ref_name, parent_id)? else {
+            break;
+        };
+        current = parent;
+        depth += 1;
+    }
+    Ok(nodes)
+}
+
 fn build_descendants(
     conn: &Connection,
     repo: &str,
@@ -226,6 +383,11 @@ mod tests {
             parent_symbol_id: parent_symbol_id.map(String::from),
             visibility: Some("pub".into()),
             content: None,
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
         };
         cruxe_state::symbols::insert_symbol(conn, &record).unwrap();
     }
@@ -414,4 +576,124 @@ mod tests {
             "expected AmbiguousSymbol, got: {err:?}"
         );
     }
+
+    #[test]
+    fn get_module_hierarchy_spans_files_of_the_module() {
+        let conn = setup_test_db();
+        let symbols = [
+            (
+                "src/auth/handler.rs",
+                "h",
+                "AuthHandler",
+                "AuthHandler",
+                SymbolKind::Struct,
+                None,
+                1,
+            ),
+            (
+                "src/auth/handler.rs",
+                "v",
+                "validate",
+                "AuthHandler::validate",
+                SymbolKind::Method,
+                Some("h"),
+                3,
+            ),
+            (
+                "src/auth/mod.rs",
+                "l",
+                "login",
+                "login",
+                SymbolKind::Function,
+                None,
+                1,
+            ),
+            (
+                "src/authz.rs",
+                "c",
+                "check",
+                "check",
+                SymbolKind::Function,
+                None,
+                1,
+            ),
+            (
+                "src/lib.rs",
+                "r",
+                "run",
+                "run",
+                SymbolKind::Function,
+                None,
+                1,
+            ),
+        ];
+        for (path, symbol_id, name, qualified_name, kind, parent_symbol_id, line_start) in symbols {
+            insert_symbol_with_path(
+                &conn,
+                path,
+                TestSymbolInput {
+                    symbol_id,
+                    name,
+                    qualified_name,
+                    kind,
+                    parent_symbol_id,
+                    line_start,
+                },
+            );
+        }
+
+        let response = get_module_hierarchy(&conn, "repo", "main", "crate::auth", 0).unwrap();
+        assert_eq!(
+            response.files,
+            vec!["src/auth/handler.rs", "src/auth/mod.rs"]
+        );
+        assert_eq!(response.symbol_count, 3);
+        assert!(!response.truncated);
+        let roots: Vec<(&str, &str)> = response
+            .hierarchy
+            .iter()
+            .map(|node| (node.name.as_str(), node.path.as_str()))
+            .collect();
+        assert_eq!(
+            roots,
+            vec![
+                ("AuthHandler", "src/auth/handler.rs"),
+                ("login", "src/auth/mod.rs"),
+            ]
+        );
+        assert_eq!(response.hierarchy[0].children.len(), 1);
+        assert_eq!(response.hierarchy[0].children[0].name, "validate");
+        assert_eq!(response.hierarchy[0].children[0].depth, 1);
+
+        let nested = get_module_hierarchy(&conn, "repo", "main", "auth::handler", 0).unwrap();
+        assert_eq!(nested.files, vec!["src/auth/handler.rs"]);
+        assert_eq!(nested.symbol_count, 2);
+
+        let whole_crate = get_module_hierarchy(&conn, "repo", "main", "crate", 0).unwrap();
+        assert_eq!(whole_crate.symbol_count, 5);
+
+        let limited = get_module_hierarchy(&conn, "repo", "main", "crate::auth", 1).unwrap();
+        assert!(limited.truncated);
+        assert_eq!(limited.symbol_count, 3);
+        assert_eq!(limited.hierarchy.len(), 1);
+
+        let err = get_module_hierarchy(&conn, "repo", "main", "crate::billing", 0).unwrap_err();
+        assert!(matches!(err, HierarchyError::SymbolNotFound), "{err:?}");
+    }
+
+    #[test]
+    fn rust_file_module_path_follows_crate_layout() {
+        assert_eq!(rust_file_module_path("src/lib.rs").as_deref(), Some(""));
+        assert_eq!(rust_file_module_path("src/main.rs").as_deref(), Some(""));
+        assert_eq!(
+            rust_file_module_path("src/auth/mod.rs").as_deref(),
+            Some("auth")
+        );
+        assert_eq!(
+            rust_file_module_path("crates/core/src/auth/handler.rs").as_deref(),
+            Some("auth::handler")
+        );
+        assert_eq!(rust_file_module_path("tests/login.rs"), None);
+        assert_eq!(rust_file_module_path("src/auth.py"), None);
+    }
 }
@@ -1,4 +1,4 @@
-use crate::search::{RRF_K, SearchResult};
+use crate::search::{RRF_K, SearchResult, StableResultIdInput, compute_stable_result_id};
 use cruxe_core::config::SearchConfig;
 use cruxe_core::error::StateError;
 use cruxe_state::embedding;
@@ -64,12 +64,15 @@ pub fn semantic_query(
         results: matches
             .into_iter()
             .map(|matched| {
-                let result_id = semantic_result_id(
-                    &matched.symbol_stable_id,
-                    &matched.path,
-                    matched.line_start,
-                    matched.line_end,
-                );
+                let result_id = compute_stable_result_id(StableResultIdInput {
+                    result_type: "symbol",
+                    repo: project_id,
+                    ref_name,
+                    path: &matched.path,
+                    line_start: matched.line_start,
+                    name: "",
+                    symbol_stable_id: &matched.symbol_stable_id,
+                });
                 SearchResult {
                     repo: project_id.to_string(),
                     result_id,
@@ -79,6 +82,8 @@ pub fn semantic_query(
                     path: matched.path,
                     line_start: matched.line_start,
                     line_end: matched.line_end,
+                    byte_start: None,
+                    byte_end: None,
                     kind: None,
                     name: None,
                     qualified_name: None,
@@ -90,6 +95,7 @@ pub fn semantic_query(
                     chunk_type: matched.chunk_type,
                     source_layer: None,
                     provenance: "semantic".to_string(),
+                    highlight: None,
                 }
             })
             .collect(),
@@ -202,19 +208,6 @@ fn dedup_key(result: &SearchResult) -> String {
     )
 }
 
-fn semantic_result_id(
-    symbol_stable_id: &str,
-    path: &str,
-    line_start: u32,
-    line_end: u32,
-) -> String {
-    let payload = format!(
-        "semantic:v1|{}|{}|{}|{}",
-        symbol_stable_id, path, line_start, line_end
-    );
-    format!("res_{}", blake3::hash(payload.as_bytes()).to_hex())
-}
-
 #[derive(Default)]
 struct HybridAccumulator {
     lexical: Option<SearchResult>,
@@ -243,6 +236,8 @@ mod tests {
             path: path.to_string(),
             line_start: 1,
             line_end: 1,
+            byte_start: None,
+            byte_end: None,
             kind: None,
             name: None,
             qualified_name: None,
@@ -254,6 +249,7 @@ mod tests {
             chunk_type: None,
             source_layer: None,
             provenance: provenance.to_string(),
+            highlight: None,
         }
     }
 
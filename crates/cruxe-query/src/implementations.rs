@@ -0,0 +1,619 @@
+//! Heuristic override/implementation lookup for methods.
+//!
+//! The index records no inheritance edges, so the type hierarchy is rebuilt
+//! at query time from declarations:
+//! - Python: base classes listed in `class Name(Base, ...)`.
+//! - TypeScript: `extends` / `implements` clauses of classes and interfaces.
+//! - Rust: the `impl Trait for Type` block enclosing a method, read from the
+//!   workspace file.
+//!
+//! Types are matched by their last name segment, so same-named types in
+//! different modules are conflated. Rust supertraits, Go's implicit
+//! interfaces, and bases brought in through aliases or dynamic construction
+//! are not resolved.
+
+use cruxe_core::error::StateError;
+use cruxe_core::types::{SymbolKind, SymbolRecord};
+use cruxe_state::symbols;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
+
+/// Deepest subtype chain followed from the base type.
+const MAX_HIERARCHY_DEPTH: u32 = 8;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ImplementationsError {
+    #[error("symbol not found")]
+    SymbolNotFound,
+    #[error(transparent)]
+    State(#[from] StateError),
+}
+
+/// How a subtype relates to the type declaring the base method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InheritanceRelation {
+    Extends,
+    Implements,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MethodSite {
+    pub symbol_id: String,
+    pub symbol_stable_id: String,
+    pub name: String,
+    pub qualified_name: String,
+    pub kind: String,
+    pub language: String,
+    pub path: String,
+    pub line_start: u32,
+    pub line_end: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    pub defining_type: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Implementation {
+    #[serde(flatten)]
+    pub method: MethodSite,
+    /// Type declaring the method this one overrides or implements.
+    pub base_type: String,
+    /// Relation of the first hop from `base_type` towards `defining_type`.
+    pub relation: InheritanceRelation,
+    /// Subtype distance from `base_type` (1 = direct subtype).
+    pub depth: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImplementationsResult {
+    pub definitions: Vec<MethodSite>,
+    pub implementations: Vec<Implementation>,
+    pub total_implementations: usize,
+    pub truncated: bool,
+}
+
+/// Find the methods that override or implement `symbol_name`.
+///
+/// `symbol_name` is a method name, optionally qualified by its type
+/// (`Greeter::greet`, `Base.run`); `path` narrows which declarations count as
+/// the base definition. A qualified name whose type is not indexed still
+/// returns implementations, with no definitions. `limit == 0` returns every
+/// implementation.
+pub fn implementations_of(
+    conn: &Connection,
+    workspace: &Path,
+    project_id: &str,
+    ref_name: &str,
+    symbol_name: &str,
+    path: Option<&str>,
+    limit: usize,
+) -> Result<ImplementationsResult, ImplementationsError> {
+    let (type_filter, method_name) = split_qualified_method(symbol_name.trim());
+    let methods: Vec<SymbolRecord> =
+        symbols::find_symbols_by_name(conn, project_id, ref_name, method_name, None)?
+            .into_iter()
+            .filter(|symbol| symbol.kind == SymbolKind::Method)
+            .collect();
+    let anchors: Vec<&SymbolRecord> = methods
+        .iter()
+        .filter(|symbol| path.is_none_or(|path| symbol.path == path))
+        .filter(|symbol| type_filter.is_none_or(|ty| defining_type(symbol) == ty))
+        .collect();
+
+    // Base types to expand, per language. A qualified name whose declaring type
+    // is not indexed (e.g. `Display::fmt` from std) is still expanded in every
+    // language that has a method of that name.
+    let mut bases: Vec<(String, String)> = anchors
+        .iter()
+        .map(|anchor| (anchor.language.clone(), defining_type(anchor).to_string()))
+        .collect();
+    if anchors.is_empty() {
+        let Some(base_type) = type_filter else {
+            return Err(ImplementationsError::SymbolNotFound);
+        };
+        bases = methods
+            .iter()
+            .map(|method| (method.language.clone(), base_type.to_string()))
+            .collect();
+    }
+
+    let mut resolver = HierarchyResolver::new(conn, workspace, project_id, ref_name);
+    let mut seen_bases = HashSet::new();
+    let mut seen_impls = HashSet::new();
+    let mut implementations = Vec::new();
+    for (language, base_type) in bases {
+        if !seen_bases.insert((language.clone(), base_type.clone())) {
+            continue;
+        }
+        for found in resolver.implementations(&language, &base_type, &methods)? {
+            if seen_impls.insert(found.method.symbol_id.clone()) {
+                implementations.push(found);
+            }
+        }
+    }
+    if anchors.is_empty() && implementations.is_empty() {
+        return Err(ImplementationsError::SymbolNotFound);
+    }
+
+    // Without a type qualifier, overriding methods match the name too; keep
+    // only the declarations nothing else accounts for as definitions.
+    let definitions = anchors
+        .into_iter()
+        .filter(|anchor| !seen_impls.contains(&anchor.symbol_id))
+        .map(method_site)
+        .collect();
+
+    implementations.sort_by(|a, b| {
+        a.depth
+            .cmp(&b.depth)
+            .then_with(|| a.method.path.cmp(&b.method.path))
+            .then_with(|| a.method.line_start.cmp(&b.method.line_start))
+    });
+    let total_implementations = implementations.len();
+    let truncated = limit > 0 && total_implementations > limit;
+    if truncated {
+        implementations.truncate(limit);
+    }
+    Ok(ImplementationsResult {
+        definitions,
+        implementations,
+        total_implementations,
+        truncated,
+    })
+}
+
+struct HierarchyResolver<'a> {
+    conn: &'a Connection,
+    workspace: &'a Path,
+    project_id: &'a str,
+    ref_name: &'a str,
+    /// Per language: base type name -> direct subtypes.
+    subtypes: HashMap<String, HashMap<String, Vec<(String, InheritanceRelation)>>>,
+    sources: HashMap<String, Option<String>>,
+}
+
+impl<'a> HierarchyResolver<'a> {
+    fn new(
+        conn: &'a Connection,
+        workspace: &'a Path,
+        project_id: &'a str,
+        ref_name: &'a str,
+    ) -> Self {
+        Self {
+            conn,
+            workspace,
+            project_id,
+            ref_name,
+            subtypes: HashMap::new(),
+            sources: HashMap::new(),
+        }
+    }
+
+    fn implementations(
+        &mut self,
+        language: &str,
+        base_type: &str,
+        methods: &[SymbolRecord],
+    ) -> Result<Vec<Implementation>, StateError> {
+        let candidates = methods
+            .iter()
+            .filter(|method| method.language == language && defining_type(method) != base_type);
+        match language {
+            "rust" => {
+                let mut found = Vec::new();
+                for method in candidates {
+                    let Some(header) = self.enclosing_impl_header(method) else {
+                        continue;
+                    };
+                    if parse_rust_impl_trait(&header).as_deref() == Some(base_type) {
+                        found.push(Implementation {
+                            method: method_site(method),
+                            base_type: base_type.to_string(),
+                            relation: InheritanceRelation::Implements,
+                            depth: 1,
+                        });
+                    }
+                }
+                Ok(found)
+            }
+            "python" | "typescript" => {
+                let reachable = self.reachable_subtypes(language, base_type)?;
+                Ok(candidates
+                    .filter_map(|method| {
+                        let (relation, depth) = reachable.get(defining_type(method))?;
+                        Some(Implementation {
+                            method: method_site(method),
+                            base_type: base_type.to_string(),
+                            relation: *relation,
+                            depth: *depth,
+                        })
+                    })
+                    .collect())
+            }
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Subtypes reachable from `base_type`, with the first-hop relation and depth.
+    fn reachable_subtypes(
+        &mut self,
+        language: &str,
+        base_type: &str,
+    ) -> Result<HashMap<String, (InheritanceRelation, u32)>, StateError> {
+        if !self.subtypes.contains_key(language) {
+            let declarations = symbols::list_type_declarations(
+                self.conn,
+                self.project_id,
+                self.ref_name,
+                &[language],
+            )?;
+            let mut by_base: HashMap<String, Vec<(String, InheritanceRelation)>> = HashMap::new();
+            for declaration in declarations {
+                let content = declaration.content.as_deref().unwrap_or("");
+                let bases = match language {
+                    "python" => parse_python_bases(content),
+                    _ => parse_typescript_bases(content),
+                };
+                for (base, relation) in bases {
+                    by_base
+                        .entry(base)
+                        .or_default()
+                        .push((declaration.name.clone(), relation));
+                }
+            }
+            self.subtypes.insert(language.to_string(), by_base);
+        }
+        let by_base = &self.subtypes[language];
+
+        let mut reachable = HashMap::new();
+        let mut queue = VecDeque::from([(base_type.to_string(), None, 0u32)]);
+        while let Some((current, first_relation, depth)) = queue.pop_front() {
+            if depth >= MAX_HIERARCHY_DEPTH {
+                continue;
+            }
+            for (subtype, relation) in by_base.get(&current).into_iter().flatten() {
+                if subtype == base_type || reachable.contains_key(subtype) {
+                    continue;
+                }
+                let relation = first_relation.unwrap_or(*relation);
+                reachable.insert(subtype.clone(), (relation, depth + 1));
+                queue.push_back((subtype.clone(), Some(relation), depth + 1));
+            }
+        }
+        Ok(reachable)
+    }
+
+    /// Header (`impl ... {`) of the last `impl` block opening before `method`.
+    fn enclosing_impl_header(&mut self, method: &SymbolRecord) -> Option<String> {
+        let workspace = self.workspace;
+        let source = self
+            .sources
+            .entry(method.path.clone())
+            .or_insert_with(|| std::fs::read_to_string(workspace.join(&method.path)).ok())
+            .as_deref()?;
+        let offset = match method.byte_start {
+            Some(start) => (start as usize).min(source.len()),
+            None => line_offset(source, method.line_start),
+        };
+        let before = source.get(..offset)?;
+        let start = before
+            .rmatch_indices("impl")
+            .map(|(idx, _)| idx)
+            .find(|&idx| is_impl_keyword(before, idx))?;
+        let header = &source[start..];
+        let end = header.find('{').unwrap_or(header.len());
+        Some(header[..end].to_string())
+    }
+}
+
+fn is_impl_keyword(text: &str, idx: usize) -> bool {
+    let preceded_ok = text[..idx]
+        .chars()
+        .next_back()
+        .is_none_or(|ch| ch.is_whitespace());
+    let followed_ok = text[idx + 4..]
+        .chars()
+        .next()
+        .is_none_or(|ch| ch.is_whitespace() || ch == '<');
+    preceded_ok && followed_ok
+}
+
+fn line_offset(source: &str, line: u32) -> usize {
+    source
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1) as usize)
+        .map(str::len)
+        .sum()
+}
+
+/// Split `Type::method` / `Type.method` into the type and method name.
+fn split_qualified_method(symbol_name: &str) -> (Option<&str>, &str) {
+    let split = symbol_name
+        .rsplit_once("::")
+        .or_else(|| symbol_name.rsplit_once('.'));
+    match split {
+        Some((ty, method)) if !ty.is_empty() && !method.is_empty() => {
+            (Some(last_segment(ty)), method)
+        }
+        _ => (None, symbol_name),
+    }
+}
+
+/// Type declaring `method`, taken from its qualified name (`Type::method`).
+fn defining_type(method: &SymbolRecord) -> &str {
+    let qualified = method.qualified_name.as_str();
+    let prefix = qualified
+        .strip_suffix(method.name.as_str())
+        .map(|prefix| {
+            prefix
+                .strip_suffix("::")
+                .or_else(|| prefix.strip_suffix('.'))
+                .unwrap_or(prefix)
+        })
+        .unwrap_or(qualified);
+    last_segment(prefix)
+}
+
+fn last_segment(path: &str) -> &str {
+    let tail = path.rsplit("::").next().unwrap_or(path);
+    tail.rsplit('.').next().unwrap_or(tail).trim()
+}
+
+fn method_site(symbol: &SymbolRecord) -> MethodSite {
+    MethodSite {
+        symbol_id: symbol.symbol_id.clone(),
+        symbol_stable_id: symbol.symbol_stable_id.clone(),
+        name: symbol.name.clone(),
+        qualified_name: symbol.qualified_name.clone(),
+        kind: symbol.kind.as_str().to_string(),
+        language: symbol.language.clone(),
+        path: symbol.path.clone(),
+        line_start: symbol.line_start,
+        line_end: symbol.line_end,
+        signature: symbol.signature.clone(),
+        defining_type: defining_type(symbol).to_string(),
+    }
+}
+
+/// Remove `<...>` and `[...]` generic arguments.
+fn strip_generics(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut depth = 0usize;
+    for ch in text.chars() {
+        match ch {
+            '<' | '[' => depth += 1,
+            '>' | ']' => depth = depth.saturating_sub(1),
+            _ if depth == 0 => out.push(ch),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Base class names from `class Name(Base, pkg.Other, metaclass=Meta):`.
+fn parse_python_bases(content: &str) -> Vec<(String, InheritanceRelation)> {
+    let header = strip_generics(content.split(':').next().unwrap_or(""));
+    let Some((_, rest)) = header.split_once('(') else {
+        return Vec::new();
+    };
+    let args = rest.rsplit_once(')').map_or(rest, |(args, _)| args);
+    args.split(',')
+        .map(str::trim)
+        .filter(|arg| !arg.is_empty() && !arg.contains('=') && !arg.starts_with('*'))
+        .map(|arg| (last_segment(arg).to_string(), InheritanceRelation::Extends))
+        .collect()
+}
+
+/// Base names from `class A extends B implements C, D {` or `interface A extends B, C {`.
+fn parse_typescript_bases(content: &str) -> Vec<(String, InheritanceRelation)> {
+    let header = strip_generics(content.split('{').next().unwrap_or(""));
+    let mut relation = None;
+    let mut bases = Vec::new();
+    for token in header.split(|ch: char| ch.is_whitespace() || ch == ',') {
+        match token {
+            "" => {}
+            "extends" => relation = Some(InheritanceRelation::Extends),
+            "implements" => relation = Some(InheritanceRelation::Implements),
+            name => {
+                if let Some(relation) = relation {
+                    bases.push((last_segment(name).to_string(), relation));
+                }
+            }
+        }
+    }
+    bases
+}
+
+/// Trait name from `impl<T> path::Trait<T> for Type<T>`, or `None` for inherent impls.
+fn parse_rust_impl_trait(header: &str) -> Option<String> {
+    let header = strip_generics(header);
+    let mut tokens = header.split_whitespace();
+    if tokens.next()? != "impl" {
+        return None;
+    }
+    let trait_path = tokens.next()?;
+    (tokens.next()? == "for").then(|| last_segment(trait_path.trim_start_matches('!')).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cruxe_indexer::prepare;
+    use cruxe_state::{db, schema};
+
+    fn index_sources(conn: &Connection, workspace: &Path, files: &[(&str, &str, &str)]) {
+        for (path, language, source) in files {
+            let full_path = workspace.join(path);
+            std::fs::create_dir_all(full_path.parent().unwrap()).unwrap();
+            std::fs::write(&full_path, source).unwrap();
+            let artifacts = prepare::build_source_artifacts(
+                prepare::ArtifactBuildInput {
+                    content: source,
+                    language,
+                    source_path: path,
+                    project_id: "proj",
+                    ref_name: "main",
+                    source_layer: None,
+                    include_imports: false,
+                    max_syntax_depth: cruxe_core::constants::DEFAULT_MAX_SYNTAX_DEPTH,
+                    max_snippet_lines: cruxe_core::constants::DEFAULT_MAX_SNIPPET_LINES,
+                },
+                0,
+            );
+            for symbol in &artifacts.symbols {
+                symbols::insert_symbol(conn, symbol).unwrap();
+            }
+        }
+    }
+
+    fn qualified_names(result: &ImplementationsResult) -> Vec<(&str, InheritanceRelation, u32)> {
+        result
+            .implementations
+            .iter()
+            .map(|found| {
+                (
+                    found.method.qualified_name.as_str(),
+                    found.relation,
+                    found.depth,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn header_parsers_read_declared_bases() {
+        assert_eq!(
+            parse_python_bases("class Child(base.Parent, Mixin[T], metaclass=Meta):\n    pass"),
+            vec![
+                ("Parent".to_string(), InheritanceRelation::Extends),
+                ("Mixin".to_string(), InheritanceRelation::Extends),
+            ]
+        );
+        assert!(parse_python_bases("class Plain:\n    pass").is_empty());
+        assert_eq!(
+            parse_typescript_bases(
+                "export class Svc<T> extends Base<T> implements api.Handler, Closeable {"
+            ),
+            vec![
+                ("Base".to_string(), InheritanceRelation::Extends),
+                ("Handler".to_string(), InheritanceRelation::Implements),
+                ("Closeable".to_string(), InheritanceRelation::Implements),
+            ]
+        );
+        assert_eq!(
+            parse_rust_impl_trait("impl<T: Clone> crate::io::Greeter<T> for Wrapper<T> "),
+            Some("Greeter".to_string())
+        );
+        assert_eq!(parse_rust_impl_trait("impl Wrapper "), None);
+        assert_eq!(
+            split_qualified_method("Greeter::greet"),
+            (Some("Greeter"), "greet")
+        );
+        assert_eq!(split_qualified_method("greet"), (None, "greet"));
+    }
+
+    #[test]
+    fn finds_rust_trait_implementations_and_skips_inherent_methods() {
+        let dir = tempfile::tempdir().unwrap();
+        let conn = db::open_connection(&dir.path().join("state.db")).unwrap();
+        schema::create_tables(&conn).unwrap();
+        let workspace = dir.path().join("ws");
+        index_sources(
+            &conn,
+            &workspace,
+            &[
+                (
+                    "src/greeter.rs",
+                    "rust",
+                    "pub trait Greeter {\n    fn greet(&self) -> String;\n}\n",
+                ),
+                (
+                    "src/english.rs",
+                    "rust",
+                    "pub struct English;\n\nimpl English {\n    fn greet(&self) -> String { String::new() }\n}\n\nimpl crate::greeter::Greeter for English {\n    fn greet(&self) -> String {\n        \"hello\".into()\n    }\n}\n",
+                ),
+                (
+                    "src/french.rs",
+                    "rust",
+                    "pub struct French<T>(T);\n\nimpl<T> Greeter for French<T> {\n    fn greet(&self) -> String {\n        \"bonjour\".into()\n    }\n}\n",
+                ),
+            ],
+        );
+
+        let result =
+            implementations_of(&conn, &workspace, "proj", "main", "Greeter::greet", None, 0)
+                .unwrap();
+        assert_eq!(result.definitions.len(), 1);
+        assert_eq!(result.definitions[0].defining_type, "Greeter");
+        let found: Vec<(&str, u32)> = result
+            .implementations
+            .iter()
+            .map(|found| (found.method.path.as_str(), found.method.line_start))
+            .collect();
+        assert_eq!(found, vec![("src/english.rs", 8), ("src/french.rs", 4)]);
+        assert!(
+            result
+                .implementations
+                .iter()
+                .all(|found| found.relation == InheritanceRelation::Implements)
+        );
+    }
+
+    #[test]
+    fn follows_python_and_typescript_subtype_chains() {
+        let dir = tempfile::tempdir().unwrap();
+        let conn = db::open_connection(&dir.path().join("state.db")).unwrap();
+        schema::create_tables(&conn).unwrap();
+        let workspace = dir.path().join("ws");
+        index_sources(
+            &conn,
+            &workspace,
+            &[
+                (
+                    "shapes.py",
+                    "python",
+                    "class Shape:\n    def area(self):\n        raise NotImplementedError\n\nclass Rect(Shape):\n    def area(self):\n        return 1\n\nclass Square(Rect):\n    def area(self):\n        return 2\n\nclass Circle:\n    def area(self):\n        return 3\n",
+                ),
+                (
+                    "handlers.ts",
+                    "typescript",
+                    "interface Handler {\n  handle(): void;\n}\n\nclass BaseHandler implements Handler {\n  handle(): void {}\n}\n\nclass AuditHandler extends BaseHandler {\n  handle(): void {}\n}\n",
+                ),
+            ],
+        );
+
+        let shapes =
+            implementations_of(&conn, &workspace, "proj", "main", "area", None, 0).unwrap();
+        let definitions: Vec<&str> = shapes
+            .definitions
+            .iter()
+            .map(|site| site.qualified_name.as_str())
+            .collect();
+        assert_eq!(definitions, vec!["Shape.area", "Circle.area"]);
+        assert_eq!(
+            qualified_names(&shapes),
+            vec![
+                ("Rect.area", InheritanceRelation::Extends, 1),
+                ("Square.area", InheritanceRelation::Extends, 2),
+            ]
+        );
+
+        let handlers =
+            implementations_of(&conn, &workspace, "proj", "main", "Handler.handle", None, 1)
+                .unwrap();
+        assert_eq!(handlers.total_implementations, 2);
+        assert!(handlers.truncated);
+        assert_eq!(
+            qualified_names(&handlers),
+            vec![("BaseHandler.handle", InheritanceRelation::Implements, 1)]
+        );
+
+        assert!(matches!(
+            implementations_of(&conn, &workspace, "proj", "main", "missing", None, 0),
+            Err(ImplementationsError::SymbolNotFound)
+        ));
+    }
+}
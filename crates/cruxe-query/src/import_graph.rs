@@ -0,0 +1,461 @@
+use crate::call_graph;
+use cruxe_core::edge_confidence::{RESOLUTION_EXTERNAL_REFERENCE, RESOLUTION_RESOLVED_INTERNAL};
+use cruxe_core::error::StateError;
+use cruxe_state::edges::{self, FileImportEdge};
+use cruxe_state::manifest;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+
+pub const MAX_IMPORT_GRAPH_DEPTH: u32 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportGraphDirection {
+    Importers,
+    Imports,
+    Both,
+}
+
+impl ImportGraphDirection {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "importers" => Some(Self::Importers),
+            "imports" => Some(Self::Imports),
+            "both" => Some(Self::Both),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ImportGraphError {
+    #[error("symbol not found")]
+    SymbolNotFound,
+    #[error("file not indexed")]
+    FileNotFound,
+    #[error(transparent)]
+    State(#[from] StateError),
+}
+
+/// The file (and optionally the symbol within it) the traversal starts from.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ImportGraphRoot {
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub qualified_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ImportGraphEdge {
+    pub source_path: String,
+    /// `None` for imports that leave the indexed corpus or failed to resolve.
+    pub target_path: Option<String>,
+    pub target_name: String,
+    /// `internal`, `external` or `unresolved`.
+    pub import_kind: String,
+    pub confidence: String,
+    pub depth: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportGraphResult {
+    pub root: ImportGraphRoot,
+    /// Files importing the root, then files importing those, and so on.
+    pub importers: Vec<ImportGraphEdge>,
+    /// What the root file imports, then what those files import, and so on.
+    pub imports: Vec<ImportGraphEdge>,
+    pub total_edges: usize,
+    pub truncated: bool,
+    pub depth_applied: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportGraphRequest<'a> {
+    /// Symbol to start from; its defining file is the root for further hops.
+    pub symbol_name: Option<&'a str>,
+    /// File to start from, or the file disambiguating `symbol_name`.
+    pub path: Option<&'a str>,
+    pub direction: ImportGraphDirection,
+    pub depth: u32,
+    pub limit: usize,
+}
+
+pub fn clamp_depth(depth: u32) -> u32 {
+    depth.clamp(1, MAX_IMPORT_GRAPH_DEPTH)
+}
+
+/// Traverse file-level import edges from a symbol or file.
+///
+/// When rooted at a symbol, the first importer hop only follows imports that
+/// resolved to that symbol; every later hop (and the imports direction) works
+/// on whole files, since import edges originate from files.
+pub fn get_import_graph(
+    conn: &Connection,
+    repo: &str,
+    ref_name: &str,
+    request: &ImportGraphRequest<'_>,
+) -> Result<ImportGraphResult, ImportGraphError> {
+    let symbol_name = request.symbol_name.filter(|name| !name.trim().is_empty());
+    let (root, root_stable_id) = match symbol_name {
+        Some(name) => {
+            let symbol = call_graph::resolve_root_symbol(conn, repo, ref_name, name, request.path)?
+                .ok_or(ImportGraphError::SymbolNotFound)?;
+            (
+                ImportGraphRoot {
+                    path: symbol.path,
+                    symbol_name: Some(symbol.name),
+                    qualified_name: Some(symbol.qualified_name),
+                },
+                Some(symbol.symbol_stable_id),
+            )
+        }
+        None => {
+            let path = request.path.ok_or(ImportGraphError::FileNotFound)?;
+            if manifest::get_content_hash(conn, repo, ref_name, path)?.is_none() {
+                return Err(ImportGraphError::FileNotFound);
+            }
+            (
+                ImportGraphRoot {
+                    path: path.to_string(),
+                    symbol_name: None,
+                    qualified_name: None,
+                },
+                None,
+            )
+        }
+    };
+
+    let depth_applied = clamp_depth(request.depth);
+    let limit = request.limit.max(1);
+    let importers = match request.direction {
+        ImportGraphDirection::Importers | ImportGraphDirection::Both => traverse(
+            conn,
+            repo,
+            ref_name,
+            &root.path,
+            root_stable_id.as_deref(),
+            depth_applied,
+            limit,
+            TraversalMode::Importers,
+        )?,
+        ImportGraphDirection::Imports => (Vec::new(), false),
+    };
+    let imports = match request.direction {
+        ImportGraphDirection::Imports | ImportGraphDirection::Both => traverse(
+            conn,
+            repo,
+            ref_name,
+            &root.path,
+            None,
+            depth_applied,
+            limit,
+            TraversalMode::Imports,
+        )?,
+        ImportGraphDirection::Importers => (Vec::new(), false),
+    };
+
+    Ok(ImportGraphResult {
+        root,
+        total_edges: importers.0.len() + imports.0.len(),
+        truncated: importers.1 || imports.1,
+        importers: importers.0,
+        imports: imports.0,
+        depth_applied,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TraversalMode {
+    Importers,
+    Imports,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn traverse(
+    conn: &Connection,
+    repo: &str,
+    ref_name: &str,
+    root_path: &str,
+    root_stable_id: Option<&str>,
+    depth_limit: u32,
+    limit: usize,
+    mode: TraversalMode,
+) -> Result<(Vec<ImportGraphEdge>, bool), StateError> {
+    let mut queue = VecDeque::from([(root_path.to_string(), 0u32)]);
+    let mut expanded = HashSet::from([root_path.to_string()]);
+    let mut emitted = HashSet::<(String, Option<String>, String)>::new();
+    let mut results = Vec::new();
+
+    while let Some((current_path, current_depth)) = queue.pop_front() {
+        if current_depth >= depth_limit {
+            continue;
+        }
+        let file_edges = match mode {
+            TraversalMode::Importers => {
+                let target = (current_depth == 0).then_some(root_stable_id).flatten();
+                edges::get_import_edges_into_file(conn, repo, ref_name, &current_path, target)?
+            }
+            TraversalMode::Imports => {
+                edges::get_import_edges_from_file(conn, repo, ref_name, &current_path)?
+            }
+        };
+
+        for edge in file_edges {
+            let key = (
+                edge.source_path.clone(),
+                edge.target_path.clone(),
+                edge.target_name.clone(),
+            );
+            if !emitted.insert(key) {
+                continue;
+            }
+            if results.len() >= limit {
+                return Ok((results, true));
+            }
+
+            let next_path = match mode {
+                TraversalMode::Importers => Some(edge.source_path.clone()),
+                TraversalMode::Imports => edge.target_path.clone(),
+            };
+            let edge_depth = current_depth + 1;
+            results.push(to_graph_edge(edge, edge_depth));
+            if let Some(next_path) = next_path
+                && expanded.insert(next_path.clone())
+            {
+                queue.push_back((next_path, edge_depth));
+            }
+        }
+    }
+
+    Ok((results, false))
+}
+
+fn to_graph_edge(edge: FileImportEdge, depth: u32) -> ImportGraphEdge {
+    let import_kind =
+        if edge.target_path.is_some() || edge.resolution_outcome == RESOLUTION_RESOLVED_INTERNAL {
+            "internal"
+        } else if edge.resolution_outcome == RESOLUTION_EXTERNAL_REFERENCE {
+            "external"
+        } else {
+            "unresolved"
+        };
+    ImportGraphEdge {
+        source_path: edge.source_path,
+        target_path: edge.target_path,
+        target_name: edge.target_name,
+        import_kind: import_kind.to_string(),
+        confidence: edge.confidence,
+        depth,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cruxe_core::types::{SymbolKind, SymbolRecord};
+    use cruxe_state::manifest::ManifestEntry;
+    use cruxe_state::{db, schema, symbols};
+
+    fn setup() -> Connection {
+        let tmp = tempfile::tempdir().unwrap();
+        let conn = db::open_connection(&tmp.path().join("state.db")).unwrap();
+        schema::create_tables(&conn).unwrap();
+        conn
+    }
+
+    fn symbol(stable: &str, name: &str, path: &str) -> SymbolRecord {
+        SymbolRecord {
+            repo: "repo".to_string(),
+            r#ref: "main".to_string(),
+            commit: None,
+            path: path.to_string(),
+            language: "rust".to_string(),
+            symbol_id: format!("sym::{name}"),
+            symbol_stable_id: stable.to_string(),
+            name: name.to_string(),
+            qualified_name: format!("crate::{name}"),
+            kind: SymbolKind::Function,
+            signature: None,
+            line_start: 1,
+            line_end: 3,
+            parent_symbol_id: None,
+            visibility: None,
+            content: None,
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
+        }
+    }
+
+    fn import(conn: &Connection, from_path: &str, to_stable: Option<&str>, to_name: &str) {
+        let outcome = if to_stable.is_some() {
+            RESOLUTION_RESOLVED_INTERNAL
+        } else {
+            RESOLUTION_EXTERNAL_REFERENCE
+        };
+        conn.execute(
+            "INSERT INTO symbol_edges (repo, \"ref\", from_symbol_id, to_symbol_id, to_name, edge_type, confidence, resolution_outcome)
+             VALUES ('repo', 'main', ?1, ?2, ?3, 'imports', 'high', ?4)",
+            rusqlite::params![
+                format!("file::{from_path}"),
+                to_stable,
+                to_stable.is_none().then_some(to_name),
+                outcome
+            ],
+        )
+        .unwrap();
+    }
+
+    /// `main.rs` imports `handler`, `handler.rs` imports `token` and `serde`.
+    fn seed(conn: &Connection) {
+        for path in ["src/auth.rs", "src/handler.rs", "src/main.rs", "src/cli.rs"] {
+            manifest::upsert_manifest(
+                conn,
+                &ManifestEntry {
+                    repo: "repo".to_string(),
+                    r#ref: "main".to_string(),
+                    path: path.to_string(),
+                    content_hash: format!("hash-{path}"),
+                    size_bytes: 1,
+                    mtime_ns: None,
+                    language: Some("rust".to_string()),
+                    indexed_at: "2026-01-01T00:00:00Z".to_string(),
+                },
+            )
+            .unwrap();
+        }
+        for record in [
+            symbol("stable-token", "token", "src/auth.rs"),
+            symbol("stable-claims", "claims", "src/auth.rs"),
+            symbol("stable-handler", "handler", "src/handler.rs"),
+        ] {
+            symbols::insert_symbol(conn, &record).unwrap();
+        }
+        import(conn, "src/handler.rs", Some("stable-token"), "token");
+        import(conn, "src/handler.rs", None, "serde::Serialize");
+        import(conn, "src/main.rs", Some("stable-handler"), "handler");
+        import(conn, "src/cli.rs", Some("stable-claims"), "claims");
+    }
+
+    fn request(
+        symbol_name: Option<&'static str>,
+        path: Option<&'static str>,
+        direction: ImportGraphDirection,
+        depth: u32,
+    ) -> ImportGraphRequest<'static> {
+        ImportGraphRequest {
+            symbol_name,
+            path,
+            direction,
+            depth,
+            limit: 20,
+        }
+    }
+
+    #[test]
+    fn importers_of_a_symbol_follow_file_hops_transitively() {
+        let conn = setup();
+        seed(&conn);
+
+        let result = get_import_graph(
+            &conn,
+            "repo",
+            "main",
+            &request(Some("token"), None, ImportGraphDirection::Importers, 2),
+        )
+        .unwrap();
+
+        assert_eq!(result.root.path, "src/auth.rs");
+        let hops: Vec<(&str, u32)> = result
+            .importers
+            .iter()
+            .map(|edge| (edge.source_path.as_str(), edge.depth))
+            .collect();
+        // `cli.rs` imports a different symbol from auth.rs, so it is not an importer of `token`.
+        assert_eq!(hops, vec![("src/handler.rs", 1), ("src/main.rs", 2)]);
+        assert!(result.imports.is_empty());
+    }
+
+    #[test]
+    fn imports_of_a_file_classify_internal_and_external_targets() {
+        let conn = setup();
+        seed(&conn);
+
+        let result = get_import_graph(
+            &conn,
+            "repo",
+            "main",
+            &request(None, Some("src/handler.rs"), ImportGraphDirection::Both, 3),
+        )
+        .unwrap();
+        let imports: Vec<(&str, Option<&str>, &str, u32)> = result
+            .imports
+            .iter()
+            .map(|edge| {
+                (
+                    edge.target_name.as_str(),
+                    edge.target_path.as_deref(),
+                    edge.import_kind.as_str(),
+                    edge.depth,
+                )
+            })
+            .collect();
+        assert_eq!(
+            imports,
+            vec![
+                ("crate::token", Some("src/auth.rs"), "internal", 1),
+                ("serde::Serialize", None, "external", 1),
+            ]
+        );
+        assert_eq!(result.importers.len(), 1);
+        assert_eq!(result.total_edges, 3);
+    }
+
+    #[test]
+    fn unknown_roots_are_reported() {
+        let conn = setup();
+        seed(&conn);
+
+        let missing_symbol = get_import_graph(
+            &conn,
+            "repo",
+            "main",
+            &request(Some("missing"), None, ImportGraphDirection::Both, 1),
+        );
+        assert!(matches!(
+            missing_symbol,
+            Err(ImportGraphError::SymbolNotFound)
+        ));
+
+        let missing_file = get_import_graph(
+            &conn,
+            "repo",
+            "main",
+            &request(None, Some("src/missing.rs"), ImportGraphDirection::Both, 1),
+        );
+        assert!(matches!(missing_file, Err(ImportGraphError::FileNotFound)));
+    }
+
+    #[test]
+    fn limit_truncates_edges() {
+        let conn = setup();
+        seed(&conn);
+
+        let result = get_import_graph(
+            &conn,
+            "repo",
+            "main",
+            &ImportGraphRequest {
+                limit: 1,
+                ..request(Some("handler"), None, ImportGraphDirection::Imports, 1)
+            },
+        )
+        .unwrap();
+        assert_eq!(result.imports.len(), 1);
+        assert!(result.truncated);
+    }
+}
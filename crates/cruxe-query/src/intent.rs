@@ -78,6 +78,9 @@ pub fn classify_intent_with_policy(query: &str, policy: &IntentPolicy) -> Intent
                 }
             }
             IntentRule::QuotedError => {
+                if let Some(confidence) = phrase_intent_confidence(trimmed) {
+                    return build_classification(QueryIntent::Phrase, confidence);
+                }
                 if let Some(confidence) = quoted_error_intent_confidence(
                     trimmed,
                     policy.enable_wrapped_quoted_error_literal,
@@ -107,6 +110,16 @@ pub fn classify_intent_with_policy(query: &str, policy: &IntentPolicy) -> Intent
     )
 }
 
+/// Classification for an intent the caller chose explicitly; it is taken at
+/// full confidence and never suggests escalation.
+pub fn forced_intent(intent: QueryIntent) -> IntentClassification {
+    IntentClassification {
+        intent,
+        confidence: 1.0,
+        escalation_hint: None,
+    }
+}
+
 fn build_classification(intent: QueryIntent, confidence: f64) -> IntentClassification {
     let confidence = confidence.clamp(0.0, 1.0);
     let escalation_hint = if confidence >= 0.65 {
@@ -129,6 +142,10 @@ fn build_classification(intent: QueryIntent, confidence: f64) -> IntentClassific
                 "Intent confidence is low; include exact error text or stack-frame snippet."
                     .to_string()
             }
+            QueryIntent::Phrase => {
+                "Intent confidence is low; drop the quotes to match words individually."
+                    .to_string()
+            }
         })
     };
 
@@ -190,6 +207,58 @@ fn looks_like_quoted_error_literal(query: &str) -> bool {
             || (trimmed.starts_with('`') && trimmed.ends_with('`')))
 }
 
+/// Words that mark a quoted literal as an error message rather than a phrase.
+const ERROR_LITERAL_WORDS: &[&str] = &[
+    "refused",
+    "failed",
+    "failure",
+    "error",
+    "denied",
+    "timeout",
+    "timed",
+    "panic",
+    "panicked",
+    "exception",
+    "invalid",
+    "cannot",
+    "unable",
+    "unexpected",
+    "missing",
+    "overflow",
+    "unreachable",
+    "fatal",
+];
+
+fn phrase_intent_confidence(query: &str) -> Option<f64> {
+    phrase_literal(query).map(|_| 0.85)
+}
+
+/// Inner text of a balanced double-quoted multi-word query that does not read
+/// like an error message or stack frame (e.g. `"rate limiter"`).
+/// Phrase text for a forced phrase search: the quoted body when the query is
+/// wrapped in double quotes, otherwise the whole query.
+pub(crate) fn forced_phrase_literal(query: &str) -> &str {
+    let trimmed = query.trim();
+    trimmed
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .unwrap_or(trimmed)
+        .trim()
+}
+
+pub(crate) fn phrase_literal(query: &str) -> Option<&str> {
+    let inner = query.trim().strip_prefix('"')?.strip_suffix('"')?.trim();
+    if inner.contains('"') || inner.split_whitespace().count() < 2 {
+        return None;
+    }
+    let stack_trace_like = inner.contains(':')
+        || inner.contains('\'')
+        || inner
+            .split(|c: char| !c.is_ascii_alphabetic())
+            .any(|word| ERROR_LITERAL_WORDS.contains(&word.to_ascii_lowercase().as_str()));
+    (!stack_trace_like).then_some(inner)
+}
+
 fn symbol_intent_confidence(query: &str, symbol_kind_keywords: &[String]) -> Option<f64> {
     let words: Vec<&str> = query.split_whitespace().collect();
 
@@ -274,6 +343,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_phrase_intent() {
+        assert_eq!(classify_intent("\"rate limiter\""), QueryIntent::Phrase);
+        assert_eq!(
+            classify_intent("\"connection refused\""),
+            QueryIntent::Error
+        );
+        assert_eq!(classify_intent("\"refused\""), QueryIntent::Error);
+        assert_eq!(classify_intent("\"rate_limiter\""), QueryIntent::Error);
+        assert_eq!(
+            classify_intent("error: \"rate limiter\""),
+            QueryIntent::Error
+        );
+        assert_eq!(
+            phrase_literal(" \" rate  limiter \" "),
+            Some("rate  limiter")
+        );
+    }
+
     #[test]
     fn test_apostrophe_does_not_force_error_intent() {
         assert_eq!(
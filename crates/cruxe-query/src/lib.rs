@@ -1,5 +1,6 @@
 pub mod adaptive_plan;
 pub mod call_graph;
+pub mod changed_since;
 pub mod confidence;
 pub mod context;
 pub mod context_pack;
@@ -11,20 +12,28 @@ pub mod followup;
 pub mod freshness;
 pub mod hierarchy;
 pub mod hybrid;
+pub mod implementations;
+pub mod import_graph;
 pub mod intent;
 pub mod locate;
 pub mod overlay_merge;
 pub mod planner;
 pub mod policy;
 pub mod ranking;
+pub mod ranking_log;
+pub mod ref_diff;
 pub mod related;
 pub mod rerank;
+pub mod resolve_import;
 pub mod retrieval_eval;
 mod scoring;
 pub mod search;
 pub mod semantic_advisor;
 pub mod symbol_compare;
+pub mod synonyms;
+pub mod tests_for_symbol;
 pub mod tombstone;
+pub mod unreferenced;
 
 #[cfg(test)]
 mod vcs_e2e;
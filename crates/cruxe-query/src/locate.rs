@@ -1,16 +1,46 @@
 use cruxe_core::error::StateError;
-use cruxe_core::types::SourceLayer;
+use cruxe_core::types::{SourceLayer, VisibilityFilter};
+use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use tantivy::collector::TopDocs;
-use tantivy::query::{BooleanQuery, Occur, TermQuery};
+use tantivy::query::{BooleanQuery, FuzzyTermQuery, Occur, Query, TermQuery};
 use tantivy::schema::IndexRecordOption;
 use tantivy::schema::Value;
-use tantivy::{Index, Term};
+use tantivy::{DocAddress, Index, Searcher, Term};
 use tracing::debug;
 
 use crate::overlay_merge;
 
+/// Maximum Levenshtein distance accepted by fuzzy name matching.
+pub const FUZZY_MAX_EDIT_DISTANCE: u8 = 2;
+
+/// How a located symbol's name matched the requested name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LocateMatchType {
+    #[default]
+    Exact,
+    /// Within [`FUZZY_MAX_EDIT_DISTANCE`] edits of the requested name.
+    Fuzzy,
+}
+
+/// Optional behaviour for [`locate_symbol_with_options`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LocateOptions {
+    /// Fall back to typo-tolerant name matching when exact matches do not
+    /// fill `limit`. Fuzzy matches always rank after exact ones.
+    pub fuzzy: bool,
+    /// Number of leading results to skip before returning `limit` results.
+    pub offset: usize,
+    /// Only return names spelled with the requested casing: fuzzy matches
+    /// that differ from the query by case alone (`user` for `User`) are
+    /// dropped. The exact stage already matches case-sensitively.
+    pub case_sensitive: bool,
+    /// Keep only symbols whose stored visibility passes the filter.
+    pub visibility: VisibilityFilter,
+}
+
 /// A located symbol result.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LocateResult {
@@ -21,6 +51,12 @@ pub struct LocateResult {
     pub path: String,
     pub line_start: u32,
     pub line_end: u32,
+    /// Byte range of the definition (end exclusive); absent for indexes built
+    /// before byte ranges were recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub byte_start: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub byte_end: Option<u32>,
     pub kind: String,
     pub name: String,
     pub qualified_name: String,
@@ -29,11 +65,56 @@ pub struct LocateResult {
     pub language: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub visibility: Option<String>,
+    /// Outer attributes such as `test` or `derive(Debug, Clone)` (Rust), or
+    /// decorators such as `app.get("/users")` (Python).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<String>,
+    #[serde(default)]
+    pub match_type: LocateMatchType,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub source_layer: Option<SourceLayer>,
+    /// Enclosing symbol (e.g. the class or impl of a method), filled in by
+    /// [`attach_parents`]; absent for top-level symbols.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<LocateParent>,
     pub score: f32,
 }
 
+/// The symbol a located symbol is nested in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocateParent {
+    pub name: String,
+    pub kind: String,
+    pub line_start: u32,
+}
+
+/// Resolve each result's `parent_symbol_id` in `symbol_relations` and attach
+/// the parent's name, kind and start line. Symbol ids already encode the ref,
+/// so the lookup is scoped by repo only.
+pub fn attach_parents(conn: &Connection, results: &mut [LocateResult]) {
+    let Ok(mut stmt) = conn.prepare(
+        "SELECT parent.name, parent.kind, parent.line_start
+         FROM symbol_relations child
+         JOIN symbol_relations parent
+           ON parent.repo = child.repo AND parent.symbol_id = child.parent_symbol_id
+         WHERE child.repo = ?1 AND child.symbol_id = ?2
+         LIMIT 1",
+    ) else {
+        return;
+    };
+    for result in results.iter_mut().filter(|r| r.parent.is_none()) {
+        result.parent = stmt
+            .query_row(rusqlite::params![result.repo, result.symbol_id], |row| {
+                Ok(LocateParent {
+                    name: row.get(0)?,
+                    kind: row.get(1)?,
+                    line_start: row.get(2)?,
+                })
+            })
+            .ok();
+    }
+}
+
 /// Locate symbols by name in the Tantivy symbols index.
 pub fn locate_symbol(
     index: &Index,
@@ -44,25 +125,118 @@ pub fn locate_symbol(
     r#ref: Option<&str>,
     limit: usize,
 ) -> Result<Vec<LocateResult>, StateError> {
-    let reader = index.reader().map_err(StateError::tantivy)?;
-    let searcher = reader.searcher();
-    let schema = index.schema();
+    locate_symbol_with_options(
+        index,
+        name,
+        kind,
+        role,
+        language,
+        r#ref,
+        limit,
+        LocateOptions::default(),
+    )
+}
 
+/// Locate symbols by name, optionally topping up with fuzzy name matches.
+#[allow(clippy::too_many_arguments)]
+pub fn locate_symbol_with_options(
+    index: &Index,
+    name: &str,
+    kind: Option<&str>,
+    role: Option<&str>,
+    language: Option<&str>,
+    r#ref: Option<&str>,
+    limit: usize,
+    options: LocateOptions,
+) -> Result<Vec<LocateResult>, StateError> {
+    let schema = index.schema();
     let symbol_exact = schema
         .get_field("symbol_exact")
         .map_err(StateError::tantivy)?;
+    let name_term = Term::from_field_text(symbol_exact, name);
+    let page_limit = limit;
+    let limit = options.offset.saturating_add(page_limit);
+
+    let exact_query = TermQuery::new(name_term.clone(), IndexRecordOption::Basic);
+    let mut results = search_symbols(
+        index,
+        Box::new(exact_query),
+        kind,
+        role,
+        language,
+        r#ref,
+        limit,
+        options.visibility,
+    )?;
+
+    if options.fuzzy && results.len() < limit {
+        let seen: HashSet<String> = results.iter().map(|r| r.symbol_id.clone()).collect();
+        let fuzzy_query = FuzzyTermQuery::new(name_term, FUZZY_MAX_EDIT_DISTANCE, true);
+        // Over-fetch so exact hits (which the fuzzy query also matches) do not
+        // crowd out approximate ones.
+        let fuzzy = search_symbols(
+            index,
+            Box::new(fuzzy_query),
+            kind,
+            role,
+            language,
+            r#ref,
+            limit + seen.len(),
+            options.visibility,
+        )?;
+        let remaining = limit - results.len();
+        results.extend(
+            fuzzy
+                .into_iter()
+                .filter(|result| !seen.contains(&result.symbol_id))
+                .filter(|result| !options.case_sensitive || !is_case_variant(&result.name, name))
+                .take(remaining)
+                .map(|mut result| {
+                    result.match_type = LocateMatchType::Fuzzy;
+                    result
+                }),
+        );
+    }
+
+    results.drain(..options.offset.min(results.len()));
+    results.truncate(page_limit);
+
+    debug!(
+        name,
+        fuzzy = options.fuzzy,
+        case_sensitive = options.case_sensitive,
+        offset = options.offset,
+        results = results.len(),
+        "locate_symbol"
+    );
+    Ok(results)
+}
+
+/// `candidate` spells `name` with different letter casing.
+fn is_case_variant(candidate: &str, name: &str) -> bool {
+    candidate != name && candidate.to_lowercase() == name.to_lowercase()
+}
+
+#[allow(clippy::too_many_arguments)]
+fn search_symbols(
+    index: &Index,
+    name_query: Box<dyn Query>,
+    kind: Option<&str>,
+    role: Option<&str>,
+    language: Option<&str>,
+    r#ref: Option<&str>,
+    limit: usize,
+    visibility: VisibilityFilter,
+) -> Result<Vec<LocateResult>, StateError> {
+    let reader = index.reader().map_err(StateError::tantivy)?;
+    let searcher = reader.searcher();
+    let schema = index.schema();
 
     // Build boolean query
-    let mut clauses: Vec<(Occur, Box<dyn tantivy::query::Query>)> = Vec::new();
+    let mut clauses: Vec<(Occur, Box<dyn Query>)> = Vec::new();
 
     // Must match symbol name
-    clauses.push((
-        Occur::Must,
-        Box::new(TermQuery::new(
-            Term::from_field_text(symbol_exact, name),
-            IndexRecordOption::Basic,
-        )),
-    ));
+    clauses.push((Occur::Must, name_query));
 
     // Optional kind filter
     if let Some(k) = kind {
@@ -114,9 +288,7 @@ pub fn locate_symbol(
 
     let query = BooleanQuery::new(clauses);
 
-    let top_docs = searcher
-        .search(&query, &TopDocs::with_limit(limit))
-        .map_err(StateError::tantivy)?;
+    let top_docs = top_docs_with_visibility(&searcher, &query, limit, visibility)?;
 
     let mut results = Vec::new();
     for (score, doc_address) in top_docs {
@@ -146,6 +318,23 @@ pub fn locate_symbol(
             let s = get_text(field_name);
             if s.is_empty() { None } else { Some(s) }
         };
+        let opt_u32 = |field_name: &str| -> Option<u32> {
+            schema
+                .get_field(field_name)
+                .ok()
+                .and_then(|f| doc.get_first(f))
+                .and_then(|v| v.as_u64())
+                .map(|v| v as u32)
+        };
+        let attributes: Vec<String> = schema
+            .get_field("attributes")
+            .map(|f| {
+                doc.get_all(f)
+                    .filter_map(|v| v.as_str())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
 
         results.push(LocateResult {
             repo: get_text("repo"),
@@ -154,21 +343,70 @@ pub fn locate_symbol(
             path: get_text("path"),
             line_start: get_u64("line_start") as u32,
             line_end: get_u64("line_end") as u32,
+            byte_start: opt_u32("byte_start"),
+            byte_end: opt_u32("byte_end"),
             kind: get_text("kind"),
             name: get_text("symbol_exact"),
             qualified_name: get_text("qualified_name"),
             signature: opt_text("signature"),
             language: get_text("language"),
             visibility: opt_text("visibility"),
+            attributes,
+            match_type: LocateMatchType::Exact,
             source_layer: None,
+            parent: None,
             score,
         });
     }
 
-    debug!(name, results = results.len(), "locate_symbol");
     Ok(results)
 }
 
+/// Top `limit` hits of `query` whose stored `visibility` passes `filter`.
+///
+/// Visibility is a stored-only field, so it cannot join the query itself;
+/// the hit window doubles until enough documents pass or the query runs dry.
+pub(crate) fn top_docs_with_visibility(
+    searcher: &Searcher,
+    query: &dyn Query,
+    limit: usize,
+    filter: VisibilityFilter,
+) -> Result<Vec<(f32, DocAddress)>, StateError> {
+    let visibility_field = match searcher.schema().get_field("visibility") {
+        Ok(field) if !filter.is_any() && limit > 0 => field,
+        _ => {
+            return searcher
+                .search(query, &TopDocs::with_limit(limit))
+                .map_err(StateError::tantivy);
+        }
+    };
+
+    let mut window = limit;
+    loop {
+        let hits = searcher
+            .search(query, &TopDocs::with_limit(window))
+            .map_err(StateError::tantivy)?;
+        let exhausted = hits.len() < window;
+        let mut kept = Vec::with_capacity(limit);
+        for (score, address) in hits {
+            let doc = searcher
+                .doc::<tantivy::TantivyDocument>(address)
+                .map_err(StateError::tantivy)?;
+            let visibility = doc.get_first(visibility_field).and_then(|v| v.as_str());
+            if filter.matches(visibility) {
+                kept.push((score, address));
+                if kept.len() == limit {
+                    return Ok(kept);
+                }
+            }
+        }
+        if exhausted {
+            return Ok(kept);
+        }
+        window = window.saturating_mul(2);
+    }
+}
+
 pub struct VcsLocateContext<'a> {
     pub base_index: &'a Index,
     pub overlay_index: &'a Index,
@@ -185,28 +423,37 @@ pub fn locate_symbol_vcs_merged(
     role: Option<&str>,
     language: Option<&str>,
     limit: usize,
+    options: LocateOptions,
 ) -> Result<(Vec<LocateResult>, usize), StateError> {
+    // Each layer returns the whole window; the page is cut after merging.
+    let window = options.offset.saturating_add(limit);
+    let layer_options = LocateOptions {
+        offset: 0,
+        ..options
+    };
     let (base, overlay) = std::thread::scope(|scope| {
         let base_task = scope.spawn(|| {
-            locate_symbol(
+            locate_symbol_with_options(
                 ctx.base_index,
                 name,
                 kind,
                 role,
                 language,
                 Some(ctx.base_ref),
-                limit,
+                window,
+                layer_options,
             )
         });
         let overlay_task = scope.spawn(|| {
-            locate_symbol(
+            locate_symbol_with_options(
                 ctx.overlay_index,
                 name,
                 kind,
                 role,
                 language,
                 Some(ctx.target_ref),
-                limit,
+                window,
+                layer_options,
             )
         });
 
@@ -220,6 +467,110 @@ pub fn locate_symbol_vcs_merged(
     })?;
 
     let total_candidates = base.len() + overlay.len();
-    let merged = overlay_merge::merged_locate(base, overlay, ctx.tombstones);
+    let mut merged = overlay_merge::merged_locate(base, overlay, ctx.tombstones);
+    merged.drain(..options.offset.min(merged.len()));
+    merged.truncate(limit);
     Ok((merged, total_candidates))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cruxe_core::types::{FileRecord, SymbolKind, SymbolRecord};
+    use cruxe_state::tantivy_index::IndexSet;
+    use cruxe_state::{db, schema};
+    use tempfile::tempdir;
+
+    fn write_go_symbol(index_set: &IndexSet, conn: &rusqlite::Connection, name: &str) {
+        let path = format!("pkg/{}.go", name.to_lowercase());
+        let symbol = SymbolRecord {
+            repo: "proj".to_string(),
+            r#ref: "main".to_string(),
+            commit: None,
+            path: path.clone(),
+            language: "go".to_string(),
+            symbol_id: format!("sym-{name}"),
+            symbol_stable_id: format!("stable-{name}"),
+            name: name.to_string(),
+            qualified_name: name.to_string(),
+            kind: SymbolKind::Struct,
+            signature: Some(format!("type {name} struct")),
+            line_start: 1,
+            line_end: 3,
+            parent_symbol_id: None,
+            visibility: None,
+            content: None,
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
+        };
+        let file = FileRecord {
+            repo: "proj".to_string(),
+            r#ref: "main".to_string(),
+            commit: None,
+            filename: path.rsplit('/').next().unwrap().to_string(),
+            path,
+            language: "go".to_string(),
+            content_hash: blake3::hash(name.as_bytes()).to_hex().to_string(),
+            size_bytes: 0,
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+            content_head: None,
+            encoding_warning: false,
+        };
+        cruxe_indexer::writer::write_file_records(index_set, conn, &[symbol], &[], &file).unwrap();
+    }
+
+    #[test]
+    fn case_sensitive_locate_excludes_case_variants() {
+        let dir = tempdir().unwrap();
+        let index_set = IndexSet::open(dir.path()).unwrap();
+        let conn = db::open_connection(&dir.path().join("state.db")).unwrap();
+        schema::create_tables(&conn).unwrap();
+        write_go_symbol(&index_set, &conn, "User");
+        write_go_symbol(&index_set, &conn, "user");
+
+        let locate = |case_sensitive: bool| {
+            locate_symbol_with_options(
+                &index_set.symbols,
+                "User",
+                None,
+                None,
+                None,
+                Some("main"),
+                10,
+                LocateOptions {
+                    fuzzy: true,
+                    case_sensitive,
+                    ..LocateOptions::default()
+                },
+            )
+            .unwrap()
+        };
+        let names = |results: &[LocateResult]| -> Vec<String> {
+            results.iter().map(|result| result.name.clone()).collect()
+        };
+
+        let folded = locate(false);
+        assert_eq!(names(&folded), vec!["User", "user"]);
+        assert_eq!(names(&locate(true)), vec!["User"]);
+
+        let budgets = cruxe_core::config::RankingSignalBudgetConfig::default();
+        let exact_boosts = |case_sensitive: bool| -> Vec<f64> {
+            crate::ranking::locate_ranking_reasons_with_budget(
+                &folded,
+                "User",
+                &budgets,
+                case_sensitive,
+            )
+            .into_iter()
+            .map(|reason| reason.exact_match_boost)
+            .collect()
+        };
+        assert!(exact_boosts(false).iter().all(|boost| *boost > 0.0));
+        let strict = exact_boosts(true);
+        assert!(strict[0] > 0.0);
+        assert_eq!(strict[1], 0.0, "`user` must not earn the exact-match boost");
+    }
+}
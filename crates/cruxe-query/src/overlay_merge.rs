@@ -1,4 +1,4 @@
-use crate::locate::LocateResult;
+use crate::locate::{LocateMatchType, LocateResult};
 use crate::search::SearchResult;
 use cruxe_core::types::{OverlayMergeKey, SourceLayer};
 use std::collections::{HashMap, HashSet};
@@ -136,7 +136,12 @@ pub fn merged_locate(
     }
 
     let mut out: Vec<LocateResult> = merged.into_values().collect();
-    out.sort_by(|a, b| b.score.total_cmp(&a.score));
+    // Exact name matches stay ahead of fuzzy ones regardless of layer scores.
+    out.sort_by(|a, b| {
+        (a.match_type == LocateMatchType::Fuzzy)
+            .cmp(&(b.match_type == LocateMatchType::Fuzzy))
+            .then_with(|| b.score.total_cmp(&a.score))
+    });
     out
 }
 
@@ -160,6 +165,8 @@ mod tests {
             path: path.to_string(),
             line_start,
             line_end,
+            byte_start: None,
+            byte_end: None,
             kind: None,
             name: None,
             qualified_name: None,
@@ -171,6 +178,7 @@ mod tests {
             chunk_type: (result_type == "snippet").then(|| "symbol_body".to_string()),
             source_layer: None,
             provenance: "lexical".to_string(),
+            highlight: None,
         }
     }
 
@@ -182,13 +190,18 @@ mod tests {
             path: path.to_string(),
             line_start: 10,
             line_end: 20,
+            byte_start: None,
+            byte_end: None,
             kind: kind.to_string(),
             name: "run".to_string(),
             qualified_name: "mod::run".to_string(),
             signature: None,
             language: "rust".to_string(),
             visibility: None,
+            attributes: Vec::new(),
+            match_type: LocateMatchType::Exact,
             source_layer: None,
+            parent: None,
             score,
         }
     }
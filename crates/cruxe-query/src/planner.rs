@@ -71,6 +71,16 @@ pub fn build_plan_with_ref(intent: QueryIntent, ref_scope: RefScope) -> QueryPla
             snippet_weight: 3.0,
             file_weight: 1.0,
         },
+        QueryIntent::Phrase => QueryPlan {
+            intent,
+            ref_scope,
+            search_symbols: false,
+            search_snippets: true,
+            search_files: false,
+            symbol_weight: 0.0,
+            snippet_weight: 3.0,
+            file_weight: 0.0,
+        },
         QueryIntent::NaturalLanguage => QueryPlan {
             intent,
             ref_scope,
@@ -762,6 +762,8 @@ mod tests {
             path: path.to_string(),
             line_start: 1,
             line_end: 1,
+            byte_start: None,
+            byte_end: None,
             kind: Some("function".to_string()),
             name: Some("demo".to_string()),
             qualified_name: Some("demo".to_string()),
@@ -773,6 +775,7 @@ mod tests {
             chunk_type: None,
             source_layer: None,
             provenance: "lexical".to_string(),
+            highlight: None,
         }
     }
 
@@ -16,6 +16,10 @@ const SIGNAL_PATH_AFFINITY: &str = "path_affinity";
 const SIGNAL_DEFINITION_BOOST: &str = "definition_boost";
 const SIGNAL_KIND_MATCH: &str = "kind_match";
 const SIGNAL_TEST_FILE_PENALTY: &str = "test_file_penalty";
+const SIGNAL_LANGUAGE_PREFERENCE: &str = "language_preference";
+const SIGNAL_SHORT_MATCH_PENALTY: &str = "short_match_penalty";
+/// Symbols spanning at most this many lines count as trivial for NL queries.
+const SHORT_MATCH_MAX_LINES: u32 = 2;
 const SCORE_EPSILON: f64 = 1e-9;
 
 #[derive(Debug, Clone, Copy)]
@@ -34,6 +38,8 @@ struct BudgetedScoreBreakdown {
     definition_boost: SignalScore,
     kind_match: SignalScore,
     test_file_penalty: SignalScore,
+    language_preference: SignalScore,
+    short_match_penalty: SignalScore,
     precedence_audit: RankingPrecedenceAudit,
 }
 
@@ -46,6 +52,8 @@ struct RawSignalInputs {
     definition_boost: f64,
     kind_match: f64,
     test_file_penalty: f64,
+    language_preference: f64,
+    short_match_penalty: f64,
 }
 
 impl BudgetedScoreBreakdown {
@@ -57,6 +65,8 @@ impl BudgetedScoreBreakdown {
             + self.definition_boost.effective
             + self.kind_match.effective
             + self.test_file_penalty.effective
+            + self.language_preference.effective
+            + self.short_match_penalty.effective
     }
 
     fn exact_match_present(&self) -> bool {
@@ -64,6 +74,29 @@ impl BudgetedScoreBreakdown {
     }
 
     fn to_reason(&self, result_index: usize, result_id: String) -> RankingReasons {
+        let mut signal_contributions = vec![
+            signal_contribution(SIGNAL_BM25, self.bm25),
+            signal_contribution(SIGNAL_EXACT_MATCH, self.exact_match),
+            signal_contribution(SIGNAL_QUALIFIED_NAME, self.qualified_name),
+            signal_contribution(SIGNAL_PATH_AFFINITY, self.path_affinity),
+            signal_contribution(SIGNAL_DEFINITION_BOOST, self.definition_boost),
+            signal_contribution(SIGNAL_KIND_MATCH, self.kind_match),
+            signal_contribution(SIGNAL_TEST_FILE_PENALTY, self.test_file_penalty),
+        ];
+        // Only surface the language nudge when a preference actually applied so
+        // explain payloads for unhinted queries keep their established shape.
+        if self.language_preference.raw.abs() > SCORE_EPSILON {
+            signal_contributions.push(signal_contribution(
+                SIGNAL_LANGUAGE_PREFERENCE,
+                self.language_preference,
+            ));
+        }
+        if self.short_match_penalty.raw.abs() > SCORE_EPSILON {
+            signal_contributions.push(signal_contribution(
+                SIGNAL_SHORT_MATCH_PENALTY,
+                self.short_match_penalty,
+            ));
+        }
         RankingReasons {
             result_index,
             result_id,
@@ -75,6 +108,7 @@ impl BudgetedScoreBreakdown {
             definition_boost: self.definition_boost.raw,
             kind_match: self.kind_match.raw,
             test_file_penalty: self.test_file_penalty.raw,
+            language_preference_boost: self.language_preference.raw,
             confidence_structural_boost: 0.0,
             structural_weighted_centrality: 0.0,
             structural_raw_centrality: 0.0,
@@ -82,15 +116,7 @@ impl BudgetedScoreBreakdown {
             confidence_coverage: 1.0,
             bm25_score: self.bm25.raw,
             final_score: self.final_score(),
-            signal_contributions: vec![
-                signal_contribution(SIGNAL_BM25, self.bm25),
-                signal_contribution(SIGNAL_EXACT_MATCH, self.exact_match),
-                signal_contribution(SIGNAL_QUALIFIED_NAME, self.qualified_name),
-                signal_contribution(SIGNAL_PATH_AFFINITY, self.path_affinity),
-                signal_contribution(SIGNAL_DEFINITION_BOOST, self.definition_boost),
-                signal_contribution(SIGNAL_KIND_MATCH, self.kind_match),
-                signal_contribution(SIGNAL_TEST_FILE_PENALTY, self.test_file_penalty),
-            ],
+            signal_contributions,
             precedence_audit: Some(self.precedence_audit.clone()),
         }
     }
@@ -104,7 +130,7 @@ pub fn kind_weight(kind: &str) -> f64 {
         "type_alias" | "function" | "method" => 1.5,
         "constant" => 1.0,
         "module" => 0.8,
-        "variable" => 0.5,
+        "variable" | "field" => 0.5,
         _ => {
             log_unknown_kind_once(&normalized_kind);
             0.0
@@ -181,6 +207,15 @@ pub(crate) fn semantic_signal_adjustment(
         + score_with_budget(test_file_penalty_raw, &budgets.test_file_penalty).effective
 }
 
+/// Caller-supplied hints that nudge reranking without changing the query.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RerankPreferences<'a> {
+    /// Language (as stored on indexed results, e.g. `rust`) to favor slightly.
+    pub preferred_language: Option<&'a str>,
+    /// Query was classified as natural language; enables the short-match penalty.
+    pub natural_language: bool,
+}
+
 /// Apply rule-based reranking boosts to search results.
 pub fn rerank(results: &mut [SearchResult], query: &str) {
     rerank_with_budget(results, query, &RankingSignalBudgetConfig::default());
@@ -192,7 +227,17 @@ pub fn rerank_with_budget(
     query: &str,
     budgets: &RankingSignalBudgetConfig,
 ) {
-    let _ = rerank_inner(results, query, budgets, false);
+    rerank_with_preferences(results, query, budgets, RerankPreferences::default());
+}
+
+/// Apply rule-based reranking with signal budgets and caller preferences.
+pub fn rerank_with_preferences(
+    results: &mut [SearchResult],
+    query: &str,
+    budgets: &RankingSignalBudgetConfig,
+    preferences: RerankPreferences<'_>,
+) {
+    let _ = rerank_inner(results, query, budgets, preferences, false);
 }
 
 /// Apply reranking and collect per-result ranking explanations.
@@ -206,16 +251,31 @@ pub fn rerank_with_reasons_with_budget(
     query: &str,
     budgets: &RankingSignalBudgetConfig,
 ) -> Vec<RankingReasons> {
-    rerank_inner(results, query, budgets, true)
+    rerank_with_reasons_with_preferences(results, query, budgets, RerankPreferences::default())
+}
+
+/// Apply reranking with signal budgets and caller preferences, collecting explain payloads.
+pub fn rerank_with_reasons_with_preferences(
+    results: &mut [SearchResult],
+    query: &str,
+    budgets: &RankingSignalBudgetConfig,
+    preferences: RerankPreferences<'_>,
+) -> Vec<RankingReasons> {
+    rerank_inner(results, query, budgets, preferences, true)
 }
 
 fn rerank_inner(
     results: &mut [SearchResult],
     query: &str,
     budgets: &RankingSignalBudgetConfig,
+    preferences: RerankPreferences<'_>,
     collect_reasons: bool,
 ) -> Vec<RankingReasons> {
     let query_lower = query.to_lowercase();
+    let preferred_language = preferences
+        .preferred_language
+        .map(str::trim)
+        .filter(|language| !language.is_empty());
     let mut reasons = Vec::with_capacity(results.len());
     let mut exact_match_flags = Vec::with_capacity(results.len());
 
@@ -231,6 +291,22 @@ fn rerank_inner(
             .map(|kind| kind_weight(kind) + query_intent_boost(query, kind))
             .unwrap_or(0.0);
         let test_file_penalty_raw = test_file_penalty(&result.path);
+        let language_preference_raw = match preferred_language {
+            Some(language) if result.language.eq_ignore_ascii_case(language) => {
+                budgets.language_preference.default
+            }
+            _ => 0.0,
+        };
+        // Natural-language queries often fully match a tiny helper's name; nudge
+        // substantive definitions ahead of one-liners.
+        let short_match_penalty_raw = if preferences.natural_language
+            && result.result_type == "symbol"
+            && result.line_end.saturating_sub(result.line_start) < SHORT_MATCH_MAX_LINES
+        {
+            budgets.short_match_penalty.default
+        } else {
+            0.0
+        };
 
         // Exact symbol name match boost
         if let Some(ref name) = result.name
@@ -265,6 +341,8 @@ fn rerank_inner(
                 definition_boost: definition_boost_raw,
                 kind_match: kind_match_raw,
                 test_file_penalty: test_file_penalty_raw,
+                language_preference: language_preference_raw,
+                short_match_penalty: short_match_penalty_raw,
             },
             budgets,
         );
@@ -309,33 +387,44 @@ fn rerank_inner(
 /// `locate_symbol` uses exact-match queries, so all results have
 /// `exact_match_boost = 1.0` and `definition_boost = 1.0` by definition.
 pub fn locate_ranking_reasons(results: &[LocateResult], query: &str) -> Vec<RankingReasons> {
-    locate_ranking_reasons_with_budget(results, query, &RankingSignalBudgetConfig::default())
+    locate_ranking_reasons_with_budget(results, query, &RankingSignalBudgetConfig::default(), false)
 }
 
 /// Generate ranking reasons for locate_symbol results with explicit signal budgets.
+///
+/// With `case_sensitive`, names and paths must match the query's casing to
+/// earn the exact-match, qualified-name and path boosts.
 pub fn locate_ranking_reasons_with_budget(
     results: &[LocateResult],
     query: &str,
     budgets: &RankingSignalBudgetConfig,
+    case_sensitive: bool,
 ) -> Vec<RankingReasons> {
-    let query_lower = query.to_lowercase();
+    let fold = |text: &str| {
+        if case_sensitive {
+            text.to_string()
+        } else {
+            text.to_lowercase()
+        }
+    };
+    let query_folded = fold(query);
     results
         .iter()
         .enumerate()
         .map(|(idx, r)| {
             let bm25_score = r.score as f64;
-            let exact_match_raw = if r.name.to_lowercase() == query_lower {
+            let exact_match_raw = if fold(&r.name) == query_folded {
                 budgets.exact_match.default
             } else {
                 0.0
             };
-            let qualified_name_raw = if r.qualified_name.to_lowercase().contains(&query_lower) {
+            let qualified_name_raw = if fold(&r.qualified_name).contains(&query_folded) {
                 budgets.qualified_name.default
             } else {
                 0.0
             };
             let definition_boost_raw = budgets.definition_boost.default; // locate always returns definitions
-            let path_affinity_raw = if r.path.to_lowercase().contains(&query_lower) {
+            let path_affinity_raw = if fold(&r.path).contains(&query_folded) {
                 budgets.path_affinity.default
             } else {
                 0.0
@@ -351,6 +440,8 @@ pub fn locate_ranking_reasons_with_budget(
                     definition_boost: definition_boost_raw,
                     kind_match: kind_match_raw,
                     test_file_penalty: test_file_penalty_raw,
+                    language_preference: 0.0,
+                    short_match_penalty: 0.0,
                 },
                 budgets,
             )
@@ -389,6 +480,10 @@ fn budgeted_breakdown(
     let mut definition_boost = score_with_budget(raw.definition_boost, &budgets.definition_boost);
     let mut kind_match = score_with_budget(raw.kind_match, &budgets.kind_match);
     let test_file_penalty = score_with_budget(raw.test_file_penalty, &budgets.test_file_penalty);
+    let language_preference =
+        score_with_budget(raw.language_preference, &budgets.language_preference);
+    let short_match_penalty =
+        score_with_budget(raw.short_match_penalty, &budgets.short_match_penalty);
 
     let exact_match_present = exact_match.effective > SCORE_EPSILON;
     let mut lexical_dominance_applied = false;
@@ -438,6 +533,8 @@ fn budgeted_breakdown(
         definition_boost,
         kind_match,
         test_file_penalty,
+        language_preference,
+        short_match_penalty,
         precedence_audit,
     }
 }
@@ -534,6 +631,8 @@ mod tests {
             path: path.to_string(),
             line_start: 1,
             line_end: 2,
+            byte_start: None,
+            byte_end: None,
             kind: Some(kind.to_string()),
             name: Some(name.to_string()),
             qualified_name: Some(qn.to_string()),
@@ -545,6 +644,7 @@ mod tests {
             chunk_type: None,
             source_layer: None,
             provenance: "lexical".to_string(),
+            highlight: None,
         }
     }
 
@@ -713,6 +813,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn raising_path_affinity_budget_lets_path_matches_outrank_name_matches() {
+        let name_hit = search_result(
+            "a-id",
+            "check",
+            "crate::auth_rules::check",
+            "src/rules.rs",
+            "function",
+            1.0,
+        );
+        let path_hit = search_result(
+            "b-id",
+            "login",
+            "crate::login",
+            "src/auth/login.rs",
+            "function",
+            1.0,
+        );
+
+        let mut results = vec![name_hit.clone(), path_hit.clone()];
+        rerank(&mut results, "auth");
+        assert_eq!(
+            results[0].result_id, "a-id",
+            "defaults favor the name match"
+        );
+
+        let mut budgets = RankingSignalBudgetConfig::default();
+        budgets.path_affinity.max = 6.0;
+        budgets.path_affinity.default = 6.0;
+        let mut results = vec![name_hit, path_hit];
+        let reasons = rerank_with_reasons_with_budget(&mut results, "auth", &budgets);
+        assert_eq!(results[0].result_id, "b-id");
+        assert_eq!(reasons[0].path_affinity, 6.0);
+    }
+
     #[test]
     fn deterministic_order_uses_result_id_on_equal_precedence_and_score() {
         let mut budgets = RankingSignalBudgetConfig::default();
@@ -731,6 +866,159 @@ mod tests {
         assert_eq!(results[1].result_id, "b-id");
     }
 
+    #[test]
+    fn preferred_language_breaks_ties_without_overriding_exact_match() {
+        let budgets = RankingSignalBudgetConfig::default();
+        let mut rust = search_result("a-id", "parse", "cfg::parse", "src/a.rs", "function", 1.0);
+        let mut python = search_result("b-id", "parse", "cfg.parse", "cfg/a.py", "function", 1.0);
+        python.language = "python".to_string();
+        rust.name = Some("parse_config".to_string());
+
+        let mut results = vec![rust.clone(), python.clone()];
+        let reasons = rerank_with_reasons_with_preferences(
+            &mut results,
+            "nomatch",
+            &budgets,
+            RerankPreferences {
+                preferred_language: Some("Python"),
+                ..Default::default()
+            },
+        );
+        assert_eq!(results[0].result_id, "b-id");
+        assert_eq!(
+            reasons[0].language_preference_boost,
+            budgets.language_preference.default
+        );
+        assert!(
+            reasons[0]
+                .signal_contributions
+                .iter()
+                .any(|c| c.signal == SIGNAL_LANGUAGE_PREFERENCE)
+        );
+        assert!(
+            reasons[1]
+                .signal_contributions
+                .iter()
+                .all(|c| c.signal != SIGNAL_LANGUAGE_PREFERENCE)
+        );
+
+        // An exact lexical hit keeps precedence over the language nudge.
+        let mut results = vec![rust, python];
+        rerank_with_preferences(
+            &mut results,
+            "parse_config",
+            &budgets,
+            RerankPreferences {
+                preferred_language: Some("python"),
+                ..Default::default()
+            },
+        );
+        assert_eq!(results[0].result_id, "a-id");
+    }
+
+    #[test]
+    fn short_match_penalty_favors_substantive_symbols_for_nl_queries() {
+        let budgets = RankingSignalBudgetConfig::default();
+        let mut helper = search_result(
+            "a-id",
+            "token",
+            "auth::token",
+            "src/auth.rs",
+            "function",
+            1.0,
+        );
+        helper.line_end = helper.line_start;
+        let mut implementation = search_result(
+            "b-id",
+            "refresh_session",
+            "auth::refresh_session",
+            "src/session.rs",
+            "function",
+            1.0,
+        );
+        implementation.line_end = 40;
+
+        let mut results = vec![helper.clone(), implementation.clone()];
+        let reasons = rerank_with_reasons_with_preferences(
+            &mut results,
+            "how is the session token refreshed",
+            &budgets,
+            RerankPreferences {
+                natural_language: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(results[0].result_id, "b-id");
+        assert!(
+            reasons[0]
+                .signal_contributions
+                .iter()
+                .all(|c| c.signal != SIGNAL_SHORT_MATCH_PENALTY)
+        );
+        let penalty = reasons[1]
+            .signal_contributions
+            .iter()
+            .find(|c| c.signal == SIGNAL_SHORT_MATCH_PENALTY)
+            .unwrap();
+        assert_eq!(penalty.effective_value, budgets.short_match_penalty.default);
+
+        // Without NL intent the tie falls back to result id order.
+        let mut results = vec![helper, implementation];
+        rerank(&mut results, "how is the session token refreshed");
+        assert_eq!(results[0].result_id, "a-id");
+    }
+
+    #[test]
+    fn short_match_penalty_leaves_symbol_intent_exact_matches_alone() {
+        let budgets = RankingSignalBudgetConfig::default();
+        let mut helper = search_result(
+            "a-id",
+            "token",
+            "auth::token",
+            "src/auth.rs",
+            "function",
+            0.1,
+        );
+        helper.line_end = helper.line_start;
+        let mut implementation = search_result(
+            "b-id",
+            "token_store",
+            "auth::token_store",
+            "src/token.rs",
+            "struct",
+            2.0,
+        );
+        implementation.line_end = 80;
+
+        let mut results = vec![implementation.clone(), helper.clone()];
+        let reasons = rerank_with_reasons_with_preferences(
+            &mut results,
+            "token",
+            &budgets,
+            RerankPreferences::default(),
+        );
+        assert_eq!(results[0].result_id, "a-id");
+        assert!(reasons.iter().all(|reason| {
+            reason
+                .signal_contributions
+                .iter()
+                .all(|c| c.signal != SIGNAL_SHORT_MATCH_PENALTY)
+        }));
+
+        // Even when penalized, exact-match precedence keeps the short symbol on top.
+        let mut results = vec![implementation, helper];
+        rerank_with_preferences(
+            &mut results,
+            "token",
+            &budgets,
+            RerankPreferences {
+                natural_language: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(results[0].result_id, "a-id");
+    }
+
     #[test]
     fn nan_budget_values_do_not_poison_ranking_scores() {
         let mut budgets = RankingSignalBudgetConfig::default();
@@ -0,0 +1,116 @@
+//! Opt-in local JSONL log of ranking outcomes (`search.ranking_log`).
+//!
+//! One record per `search_code` call with the intent, executed plan, anonymized
+//! query features, and the top result scores. Raw query text is written only
+//! when `include_query_text` is set. Write failures are logged and never fail
+//! the search.
+
+use crate::search::SearchResponse;
+use cruxe_core::config::RankingLogConfig;
+use serde::Serialize;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use tracing::warn;
+
+/// Serializes appends so concurrent searches never interleave partial lines.
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Serialize)]
+struct RankingLogRecord<'a> {
+    ts: String,
+    intent: &'a str,
+    query_plan: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    query: Option<&'a str>,
+    query_features: QueryFeatures,
+    result_count: usize,
+    total_candidates: usize,
+    top_score: f64,
+    score_margin: f64,
+    low_confidence: bool,
+    top_results: Vec<RankedScore<'a>>,
+}
+
+/// Shape of the query without its content.
+#[derive(Debug, Serialize)]
+struct QueryFeatures {
+    chars: usize,
+    tokens: usize,
+    has_path_separator: bool,
+    has_qualifier: bool,
+    quoted: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct RankedScore<'a> {
+    rank: usize,
+    score: f32,
+    result_type: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kind: Option<&'a str>,
+    language: &'a str,
+}
+
+/// Append a record for `response` when ranking logging is enabled.
+pub fn record(config: &RankingLogConfig, query: &str, response: &SearchResponse) {
+    if !config.enabled || config.path.trim().is_empty() {
+        return;
+    }
+    let intent = serde_json::to_value(response.query_intent)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default();
+    let record = RankingLogRecord {
+        ts: cruxe_core::time::now_iso8601(),
+        intent: &intent,
+        query_plan: &response.metadata.query_plan_executed,
+        query: config.include_query_text.then_some(query),
+        query_features: QueryFeatures {
+            chars: query.chars().count(),
+            tokens: query.split_whitespace().count(),
+            has_path_separator: query.contains('/') || query.contains('\\'),
+            has_qualifier: query.contains("::") || query.contains('.'),
+            quoted: query.contains('"') || query.contains('\''),
+        },
+        result_count: response.results.len(),
+        total_candidates: response.total_candidates,
+        top_score: response.metadata.top_score,
+        score_margin: response.metadata.score_margin,
+        low_confidence: response.metadata.low_confidence,
+        top_results: response
+            .results
+            .iter()
+            .take(config.top_n)
+            .enumerate()
+            .map(|(idx, result)| RankedScore {
+                rank: idx + 1,
+                score: result.score,
+                result_type: &result.result_type,
+                kind: result.kind.as_deref(),
+                language: &result.language,
+            })
+            .collect(),
+    };
+    if let Err(err) = append_line(Path::new(&config.path), &record) {
+        warn!(path = %config.path, "Failed to append ranking log record: {}", err);
+    }
+}
+
+fn append_line(path: &Path, record: &RankingLogRecord<'_>) -> std::io::Result<()> {
+    let mut line = serde_json::to_vec(record).map_err(std::io::Error::other)?;
+    line.push(b'\n');
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        std::fs::create_dir_all(parent)?;
+    }
+    let _guard = WRITE_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    file.write_all(&line)
+}
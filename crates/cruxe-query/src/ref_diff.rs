@@ -0,0 +1,487 @@
+use cruxe_core::error::StateError;
+use cruxe_state::{branch_state, project, tombstones};
+use rusqlite::{Connection, params};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+#[derive(Debug, thiserror::Error)]
+pub enum RefDiffError {
+    #[error("refs not indexed: {}", refs.join(", "))]
+    RefsNotIndexed { refs: Vec<String> },
+    #[error(transparent)]
+    State(#[from] StateError),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RefDiffSymbol {
+    pub symbol_stable_id: String,
+    pub name: String,
+    pub qualified_name: String,
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    pub line_start: u32,
+    pub line_end: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RefSignatureChange {
+    pub symbol_stable_id: String,
+    pub name: String,
+    pub qualified_name: String,
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before_signature: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after_signature: Option<String>,
+    pub line_start: u32,
+    pub line_end: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RefDiffFile {
+    pub path: String,
+    pub added: Vec<RefDiffSymbol>,
+    pub removed: Vec<RefDiffSymbol>,
+    pub signature_changed: Vec<RefSignatureChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RefDiffResult {
+    pub base_ref: String,
+    pub head_ref: String,
+    pub added_count: usize,
+    pub removed_count: usize,
+    pub signature_changed_count: usize,
+    pub files: Vec<RefDiffFile>,
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone)]
+struct IndexedSymbol {
+    path: String,
+    symbol: RefDiffSymbol,
+}
+
+enum RefChange {
+    Added(RefDiffSymbol),
+    Removed(RefDiffSymbol),
+    SignatureChanged(RefSignatureChange),
+}
+
+/// Compare the indexed symbols of two refs without consulting git.
+///
+/// Symbols are joined on `(symbol_stable_id, kind)`; a symbol present on both
+/// sides is only reported when its signature differs. Overlay refs are
+/// compared through their effective view (default-branch base minus
+/// tombstoned paths, plus overlay rows), so an untouched file never shows up
+/// as removed. `limit` caps the number of reported changes; the counts always
+/// cover the full diff.
+pub fn diff_refs(
+    conn: &Connection,
+    project_id: &str,
+    base_ref: &str,
+    head_ref: &str,
+    path_filter: Option<&str>,
+    limit: usize,
+) -> Result<RefDiffResult, RefDiffError> {
+    let mut missing = Vec::new();
+    for ref_name in [base_ref, head_ref] {
+        if branch_state::get_branch_state(conn, project_id, ref_name)?.is_none()
+            && !missing.iter().any(|m| m == ref_name)
+        {
+            missing.push(ref_name.to_string());
+        }
+    }
+    if !missing.is_empty() {
+        return Err(RefDiffError::RefsNotIndexed { refs: missing });
+    }
+
+    let Some(project_row) = project::get_by_id(conn, project_id)? else {
+        return Err(RefDiffError::State(StateError::ProjectNotFound {
+            project_id: project_id.to_string(),
+        }));
+    };
+    let path_filter = path_filter.filter(|value| !value.trim().is_empty());
+    let load = |ref_name: &str| {
+        load_effective_symbols(
+            conn,
+            project_id,
+            ref_name,
+            &project_row.default_ref,
+            project_row.vcs_mode,
+            path_filter,
+        )
+    };
+    let base_symbols = load(base_ref)?;
+    let head_symbols = load(head_ref)?;
+
+    let mut keys = BTreeSet::new();
+    keys.extend(base_symbols.keys().cloned());
+    keys.extend(head_symbols.keys().cloned());
+
+    let mut changes: Vec<(String, RefChange)> = Vec::new();
+    for key in keys {
+        match (base_symbols.get(&key), head_symbols.get(&key)) {
+            (None, Some(after)) => {
+                changes.push((after.path.clone(), RefChange::Added(after.symbol.clone())));
+            }
+            (Some(before), None) => {
+                changes.push((
+                    before.path.clone(),
+                    RefChange::Removed(before.symbol.clone()),
+                ));
+            }
+            (Some(before), Some(after)) if before.symbol.signature != after.symbol.signature => {
+                changes.push((
+                    after.path.clone(),
+                    RefChange::SignatureChanged(RefSignatureChange {
+                        symbol_stable_id: after.symbol.symbol_stable_id.clone(),
+                        name: after.symbol.name.clone(),
+                        qualified_name: after.symbol.qualified_name.clone(),
+                        kind: after.symbol.kind.clone(),
+                        before_signature: before.symbol.signature.clone(),
+                        after_signature: after.symbol.signature.clone(),
+                        line_start: after.symbol.line_start,
+                        line_end: after.symbol.line_end,
+                    }),
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    let added_count = changes
+        .iter()
+        .filter(|(_, change)| matches!(change, RefChange::Added(_)))
+        .count();
+    let removed_count = changes
+        .iter()
+        .filter(|(_, change)| matches!(change, RefChange::Removed(_)))
+        .count();
+    let signature_changed_count = changes.len() - added_count - removed_count;
+
+    changes.sort_by(|(a_path, a), (b_path, b)| {
+        a_path
+            .cmp(b_path)
+            .then_with(|| change_line(a).cmp(&change_line(b)))
+            .then_with(|| change_name(a).cmp(change_name(b)))
+    });
+    let truncated = limit > 0 && changes.len() > limit;
+    if truncated {
+        changes.truncate(limit);
+    }
+
+    let mut files: BTreeMap<String, RefDiffFile> = BTreeMap::new();
+    for (path, change) in changes {
+        let file = files.entry(path.clone()).or_insert_with(|| RefDiffFile {
+            path,
+            ..RefDiffFile::default()
+        });
+        match change {
+            RefChange::Added(symbol) => file.added.push(symbol),
+            RefChange::Removed(symbol) => file.removed.push(symbol),
+            RefChange::SignatureChanged(change) => file.signature_changed.push(change),
+        }
+    }
+
+    Ok(RefDiffResult {
+        base_ref: base_ref.to_string(),
+        head_ref: head_ref.to_string(),
+        added_count,
+        removed_count,
+        signature_changed_count,
+        files: files.into_values().collect(),
+        truncated,
+    })
+}
+
+fn change_line(change: &RefChange) -> u32 {
+    match change {
+        RefChange::Added(symbol) | RefChange::Removed(symbol) => symbol.line_start,
+        RefChange::SignatureChanged(change) => change.line_start,
+    }
+}
+
+fn change_name(change: &RefChange) -> &str {
+    match change {
+        RefChange::Added(symbol) | RefChange::Removed(symbol) => &symbol.qualified_name,
+        RefChange::SignatureChanged(change) => &change.qualified_name,
+    }
+}
+
+fn load_effective_symbols(
+    conn: &Connection,
+    repo: &str,
+    ref_name: &str,
+    default_ref: &str,
+    vcs_mode: bool,
+    path_filter: Option<&str>,
+) -> Result<HashMap<(String, String), IndexedSymbol>, StateError> {
+    if !vcs_mode || ref_name == default_ref {
+        return load_ref_symbols(conn, repo, ref_name, path_filter);
+    }
+
+    let overlay = load_ref_symbols(conn, repo, ref_name, path_filter)?;
+    let mut hidden_paths: HashSet<String> = tombstones::list_paths_for_ref(conn, repo, ref_name)?
+        .into_iter()
+        .collect();
+    hidden_paths.extend(overlay.values().map(|symbol| symbol.path.clone()));
+
+    let mut symbols = load_ref_symbols(conn, repo, default_ref, path_filter)?;
+    symbols.retain(|_, symbol| !hidden_paths.contains(&symbol.path));
+    symbols.extend(overlay);
+    Ok(symbols)
+}
+
+fn load_ref_symbols(
+    conn: &Connection,
+    repo: &str,
+    ref_name: &str,
+    path_filter: Option<&str>,
+) -> Result<HashMap<(String, String), IndexedSymbol>, StateError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT symbol_stable_id, name, qualified_name, kind, signature, path,
+                    line_start, line_end
+             FROM symbol_relations
+             WHERE repo = ?1 AND \"ref\" = ?2",
+        )
+        .map_err(StateError::sqlite)?;
+    let rows = stmt
+        .query_map(params![repo, ref_name], |row| {
+            Ok(IndexedSymbol {
+                symbol: RefDiffSymbol {
+                    symbol_stable_id: row.get(0)?,
+                    name: row.get(1)?,
+                    qualified_name: row.get(2)?,
+                    kind: row.get(3)?,
+                    signature: row.get(4)?,
+                    line_start: row.get(6)?,
+                    line_end: row.get(7)?,
+                },
+                path: row.get(5)?,
+            })
+        })
+        .map_err(StateError::sqlite)?;
+
+    let mut result = HashMap::new();
+    for row in rows {
+        let symbol = row.map_err(StateError::sqlite)?;
+        if path_filter.is_some_and(|prefix| !symbol.path.starts_with(prefix)) {
+            continue;
+        }
+        result.insert(
+            (
+                symbol.symbol.symbol_stable_id.clone(),
+                symbol.symbol.kind.clone(),
+            ),
+            symbol,
+        );
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cruxe_core::types::{Project, SymbolKind, SymbolRecord};
+    use cruxe_state::branch_state::BranchState;
+    use cruxe_state::tombstones::BranchTombstone;
+    use cruxe_state::{db, schema, symbols};
+
+    fn setup(vcs_mode: bool) -> (tempfile::TempDir, Connection) {
+        let tmp = tempfile::tempdir().unwrap();
+        let conn = db::open_connection(&tmp.path().join("state.db")).unwrap();
+        schema::create_tables(&conn).unwrap();
+        project::create_project(
+            &conn,
+            &Project {
+                project_id: "repo".to_string(),
+                repo_root: tmp.path().to_string_lossy().to_string(),
+                display_name: None,
+                default_ref: "main".to_string(),
+                vcs_mode,
+                schema_version: 1,
+                parser_version: 1,
+                created_at: "2026-01-01T00:00:00Z".to_string(),
+                updated_at: "2026-01-01T00:00:00Z".to_string(),
+            },
+        )
+        .unwrap();
+        (tmp, conn)
+    }
+
+    fn mark_indexed(conn: &Connection, ref_name: &str) {
+        branch_state::upsert_branch_state(
+            conn,
+            &BranchState {
+                repo: "repo".to_string(),
+                r#ref: ref_name.to_string(),
+                merge_base_commit: None,
+                last_indexed_commit: "abc".to_string(),
+                overlay_dir: None,
+                file_count: 0,
+                symbol_count: 0,
+                is_default_branch: ref_name == "main",
+                status: "active".to_string(),
+                eviction_eligible_at: None,
+                created_at: "2026-01-01T00:00:00Z".to_string(),
+                last_accessed_at: "2026-01-01T00:00:00Z".to_string(),
+            },
+        )
+        .unwrap();
+    }
+
+    fn insert_symbol(
+        conn: &Connection,
+        ref_name: &str,
+        path: &str,
+        name: &str,
+        signature: &str,
+        line_start: u32,
+    ) {
+        symbols::insert_symbol(
+            conn,
+            &SymbolRecord {
+                repo: "repo".to_string(),
+                r#ref: ref_name.to_string(),
+                commit: None,
+                path: path.to_string(),
+                language: "rust".to_string(),
+                symbol_id: format!("{ref_name}:{path}:{name}"),
+                symbol_stable_id: format!("stable:{name}"),
+                name: name.to_string(),
+                qualified_name: format!("crate::{name}"),
+                kind: SymbolKind::Function,
+                signature: Some(signature.to_string()),
+                line_start,
+                line_end: line_start + 2,
+                parent_symbol_id: None,
+                visibility: Some("pub".to_string()),
+                content: None,
+                attributes: Vec::new(),
+                byte_start: None,
+                byte_end: None,
+                doc_comment: None,
+                parsed_signature: None,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn diff_refs_groups_added_removed_and_signature_changes_by_file() {
+        let (_tmp, conn) = setup(false);
+        mark_indexed(&conn, "main");
+        mark_indexed(&conn, "feat");
+        for ref_name in ["main", "feat"] {
+            insert_symbol(&conn, ref_name, "src/lib.rs", "keep", "fn keep()", 1);
+        }
+        insert_symbol(&conn, "main", "src/lib.rs", "reshape", "fn reshape()", 5);
+        insert_symbol(
+            &conn,
+            "feat",
+            "src/lib.rs",
+            "reshape",
+            "fn reshape(v: u8)",
+            5,
+        );
+        insert_symbol(&conn, "main", "src/old.rs", "gone", "fn gone()", 1);
+        insert_symbol(&conn, "feat", "src/new.rs", "fresh", "fn fresh()", 1);
+
+        let diff = diff_refs(&conn, "repo", "main", "feat", None, 0).unwrap();
+        assert_eq!(
+            (
+                diff.added_count,
+                diff.removed_count,
+                diff.signature_changed_count
+            ),
+            (1, 1, 1)
+        );
+        assert!(!diff.truncated);
+        let paths: Vec<&str> = diff.files.iter().map(|file| file.path.as_str()).collect();
+        assert_eq!(paths, vec!["src/lib.rs", "src/new.rs", "src/old.rs"]);
+        let lib = &diff.files[0];
+        assert!(lib.added.is_empty() && lib.removed.is_empty());
+        assert_eq!(lib.signature_changed[0].name, "reshape");
+        assert_eq!(
+            lib.signature_changed[0].before_signature.as_deref(),
+            Some("fn reshape()")
+        );
+        assert_eq!(
+            lib.signature_changed[0].after_signature.as_deref(),
+            Some("fn reshape(v: u8)")
+        );
+        assert_eq!(diff.files[1].added[0].name, "fresh");
+        assert_eq!(diff.files[2].removed[0].name, "gone");
+
+        let limited = diff_refs(&conn, "repo", "main", "feat", Some("src/"), 1).unwrap();
+        assert!(limited.truncated);
+        assert_eq!(limited.files.len(), 1);
+        assert_eq!(limited.added_count, 1, "counts cover the full diff");
+    }
+
+    #[test]
+    fn diff_refs_reads_overlay_refs_through_their_effective_view() {
+        let (_tmp, conn) = setup(true);
+        mark_indexed(&conn, "main");
+        mark_indexed(&conn, "feat");
+        insert_symbol(
+            &conn,
+            "main",
+            "src/lib.rs",
+            "untouched",
+            "fn untouched()",
+            1,
+        );
+        insert_symbol(&conn, "main", "src/edit.rs", "edited", "fn edited()", 1);
+        insert_symbol(&conn, "main", "src/drop.rs", "dropped", "fn dropped()", 1);
+        // Overlay only carries the changed file; the deleted one is tombstoned.
+        insert_symbol(
+            &conn,
+            "feat",
+            "src/edit.rs",
+            "edited",
+            "fn edited(x: i32)",
+            1,
+        );
+        tombstones::create_tombstone(
+            &conn,
+            &BranchTombstone {
+                repo: "repo".to_string(),
+                r#ref: "feat".to_string(),
+                path: "src/drop.rs".to_string(),
+                tombstone_type: "deleted".to_string(),
+                created_at: "2026-01-01T00:00:00Z".to_string(),
+            },
+        )
+        .unwrap();
+
+        let diff = diff_refs(&conn, "repo", "main", "feat", None, 0).unwrap();
+        assert_eq!(
+            (
+                diff.added_count,
+                diff.removed_count,
+                diff.signature_changed_count
+            ),
+            (0, 1, 1)
+        );
+        assert!(diff.files.iter().all(|file| file.path != "src/lib.rs"));
+        assert_eq!(diff.files[0].path, "src/drop.rs");
+        assert_eq!(diff.files[1].signature_changed[0].name, "edited");
+    }
+
+    #[test]
+    fn diff_refs_lists_every_unindexed_ref() {
+        let (_tmp, conn) = setup(true);
+        mark_indexed(&conn, "main");
+
+        let err = diff_refs(&conn, "repo", "nope", "feat", None, 0).unwrap_err();
+        match err {
+            RefDiffError::RefsNotIndexed { refs } => assert_eq!(refs, vec!["nope", "feat"]),
+            other => panic!("unexpected error: {other:?}"),
+        }
+        assert!(diff_refs(&conn, "repo", "main", "main", None, 0).is_ok());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::call_graph::clamp_breadth;
 use cruxe_core::error::StateError;
 use cruxe_indexer::import_extract::source_symbol_id_for_path;
 use cruxe_state::{edges, symbols};
@@ -57,6 +58,19 @@ pub struct RelatedResponse {
     pub related: Vec<RelatedSymbol>,
     pub scope_used: String,
     pub total_found: usize,
+    pub max_breadth_applied: usize,
+    /// Import edges skipped because a source node hit `max_breadth_applied`.
+    pub truncated_edges: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct RelatedRequest<'a> {
+    pub symbol_name: &'a str,
+    pub path: Option<&'a str>,
+    pub scope: RelatedScope,
+    pub limit: usize,
+    /// Maximum import edges followed from any single node.
+    pub max_breadth: usize,
 }
 
 #[derive(Debug, Error)]
@@ -73,11 +87,17 @@ pub fn find_related_symbols(
     conn: &Connection,
     repo: &str,
     ref_name: &str,
-    symbol_name: &str,
-    path: Option<&str>,
-    scope: RelatedScope,
-    limit: usize,
+    request: &RelatedRequest<'_>,
 ) -> Result<RelatedResponse, RelatedError> {
+    let RelatedRequest {
+        symbol_name,
+        path,
+        scope,
+        limit,
+        ..
+    } = *request;
+    let max_breadth_applied = clamp_breadth(request.max_breadth);
+    let mut truncated_edges = 0usize;
     let matches = symbols::find_symbols_by_name(conn, repo, ref_name, symbol_name, path)?;
     if matches.is_empty() {
         return Err(RelatedError::SymbolNotFound);
@@ -147,10 +167,16 @@ pub fn find_related_symbols(
         edge_source_ids.dedup();
 
         for source_id in edge_source_ids {
+            let mut node_edges = 0usize;
             for edge in edges::get_edges_from(conn, repo, ref_name, &source_id)? {
                 if edge.edge_type != "imports" {
                     continue;
                 }
+                if node_edges >= max_breadth_applied {
+                    truncated_edges += 1;
+                    continue;
+                }
+                node_edges += 1;
                 let Some(target_symbol) =
                     symbols::get_symbol_by_stable_id(conn, repo, ref_name, &edge.to_symbol_id)?
                 else {
@@ -192,6 +218,8 @@ pub fn find_related_symbols(
         related,
         scope_used: scope.as_str().to_string(),
         total_found,
+        max_breadth_applied,
+        truncated_edges,
     })
 }
 
@@ -240,6 +268,7 @@ fn to_related_symbol(symbol: cruxe_core::types::SymbolRecord, relation: &str) ->
 #[cfg(test)]
 mod tests {
     use super::*;
+    use cruxe_core::constants::DEFAULT_RELATION_MAX_BREADTH;
     use cruxe_core::types::{SymbolEdge, SymbolKind, SymbolRecord};
     use cruxe_state::{db, schema};
     use tempfile::tempdir;
@@ -276,6 +305,11 @@ mod tests {
             parent_symbol_id: None,
             visibility: Some("pub".into()),
             content: None,
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
         };
         symbols::insert_symbol(conn, &record).unwrap();
     }
@@ -298,10 +332,13 @@ mod tests {
             &conn,
             "repo",
             "main",
-            "validate",
-            Some("src/auth/handler.rs"),
-            RelatedScope::File,
-            20,
+            &RelatedRequest {
+                symbol_name: "validate",
+                path: Some("src/auth/handler.rs"),
+                scope: RelatedScope::File,
+                limit: 20,
+                max_breadth: DEFAULT_RELATION_MAX_BREADTH,
+            },
         )
         .unwrap();
 
@@ -339,10 +376,13 @@ mod tests {
             &conn,
             "repo",
             "main",
-            "validate",
-            Some("src/auth/handler.rs"),
-            RelatedScope::Module,
-            20,
+            &RelatedRequest {
+                symbol_name: "validate",
+                path: Some("src/auth/handler.rs"),
+                scope: RelatedScope::Module,
+                limit: 20,
+                max_breadth: DEFAULT_RELATION_MAX_BREADTH,
+            },
         )
         .unwrap();
 
@@ -350,6 +390,63 @@ mod tests {
         assert!(response.related.iter().any(|r| r.relation == "imported"));
     }
 
+    #[test]
+    fn find_related_symbols_max_breadth_caps_import_fan_out() {
+        let conn = setup_test_db();
+        insert_symbol(
+            &conn,
+            "a",
+            "stable_a",
+            "validate",
+            "src/auth/handler.rs",
+            10,
+        );
+        let mut import_edges = Vec::new();
+        for idx in 0..4u32 {
+            let stable = format!("stable_dep_{idx}");
+            insert_symbol(
+                &conn,
+                &format!("dep_{idx}"),
+                &stable,
+                &format!("dep_{idx}"),
+                "src/types.rs",
+                10 + idx,
+            );
+            import_edges.push(SymbolEdge {
+                repo: "repo".into(),
+                ref_name: "main".into(),
+                from_symbol_id: source_symbol_id_for_path("src/auth/handler.rs"),
+                to_symbol_id: stable,
+                edge_type: "imports".into(),
+                confidence: "static".into(),
+            });
+        }
+        edges::insert_edges(&conn, "repo", "main", import_edges).unwrap();
+
+        let response = find_related_symbols(
+            &conn,
+            "repo",
+            "main",
+            &RelatedRequest {
+                symbol_name: "validate",
+                path: Some("src/auth/handler.rs"),
+                scope: RelatedScope::Module,
+                limit: 20,
+                max_breadth: 1,
+            },
+        )
+        .unwrap();
+
+        let imported = response
+            .related
+            .iter()
+            .filter(|r| r.relation == "imported")
+            .count();
+        assert_eq!(imported, 1);
+        assert_eq!(response.max_breadth_applied, 1);
+        assert_eq!(response.truncated_edges, 3);
+    }
+
     #[test]
     fn find_related_symbols_scope_package_includes_same_package() {
         let conn = setup_test_db();
@@ -375,10 +472,13 @@ mod tests {
             &conn,
             "repo",
             "main",
-            "validate",
-            Some("src/auth/handler.rs"),
-            RelatedScope::Package,
-            20,
+            &RelatedRequest {
+                symbol_name: "validate",
+                path: Some("src/auth/handler.rs"),
+                scope: RelatedScope::Package,
+                limit: 20,
+                max_breadth: DEFAULT_RELATION_MAX_BREADTH,
+            },
         )
         .unwrap();
 
@@ -410,10 +510,13 @@ mod tests {
             &conn,
             "repo",
             "main",
-            "validate",
-            Some("src/auth/handler.rs"),
-            RelatedScope::Module,
-            1,
+            &RelatedRequest {
+                symbol_name: "validate",
+                path: Some("src/auth/handler.rs"),
+                scope: RelatedScope::Module,
+                limit: 1,
+                max_breadth: DEFAULT_RELATION_MAX_BREADTH,
+            },
         )
         .unwrap();
 
@@ -447,10 +550,13 @@ mod tests {
             &conn,
             "repo",
             "main",
-            "validate",
-            None,
-            RelatedScope::File,
-            10,
+            &RelatedRequest {
+                symbol_name: "validate",
+                path: None,
+                scope: RelatedScope::File,
+                limit: 10,
+                max_breadth: DEFAULT_RELATION_MAX_BREADTH,
+            },
         )
         .unwrap_err();
 
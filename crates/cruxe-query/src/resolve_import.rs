@@ -0,0 +1,225 @@
+//! "Go to imported definition": resolve an import statement written in a file
+//! to the indexed files and symbols it refers to.
+//!
+//! The statement is parsed with the importing file's import extractor and each
+//! imported name goes through the same resolution pass the indexer uses for
+//! `imports` edges, so results match what relation queries see.
+
+use crate::find_references::{ReferenceSymbol, symbol_to_reference_symbol};
+use cruxe_core::edge_confidence::RESOLUTION_EXTERNAL_REFERENCE;
+use cruxe_core::error::StateError;
+use cruxe_indexer::{import_extract, parser};
+use cruxe_state::symbols;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ResolveImportError {
+    #[error("imports are not extracted for file: {0}")]
+    UnsupportedLanguage(String),
+    #[error("no import found in statement")]
+    NoImport,
+    #[error("failed to parse import statement: {0}")]
+    Parse(String),
+    #[error(transparent)]
+    State(#[from] StateError),
+}
+
+/// Resolution of one name brought in by the statement.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolvedImport {
+    /// Local name the import binds (alias, namespace, or imported name).
+    pub imported_name: String,
+    pub qualified_name: String,
+    /// True when the import leaves the indexed corpus (third-party/stdlib).
+    pub external: bool,
+    pub resolution_outcome: String,
+    /// Indexed file holding the target: the symbol's file for named imports,
+    /// otherwise the module file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<ReferenceSymbol>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolveImportResult {
+    pub path: String,
+    pub language: String,
+    pub targets: Vec<ResolvedImport>,
+    /// True when every target is external.
+    pub external: bool,
+}
+
+/// Resolve `statement`, as written in `path`, against the index for `ref_name`.
+pub fn resolve_import(
+    conn: &Connection,
+    project_id: &str,
+    ref_name: &str,
+    path: &str,
+    statement: &str,
+) -> Result<ResolveImportResult, ResolveImportError> {
+    let language = import_extract::import_language_for_path(path);
+    if language.is_empty() {
+        return Err(ResolveImportError::UnsupportedLanguage(path.to_string()));
+    }
+    let tree = parser::parse_file(statement, language)
+        .map_err(|err| ResolveImportError::Parse(err.to_string()))?;
+    let raw_imports = import_extract::extract_imports(&tree, statement, language, path);
+    if raw_imports.is_empty() {
+        return Err(ResolveImportError::NoImport);
+    }
+
+    let mut targets = Vec::with_capacity(raw_imports.len());
+    for mut raw in raw_imports {
+        let imported_name = raw.target_name.clone();
+        let named = !raw.target_qualified_name.ends_with("::*");
+        if !named {
+            // A namespace alias is not a symbol name; resolve only the module.
+            raw.target_name.clear();
+        }
+        let target = import_extract::resolve_import_target(conn, project_id, ref_name, &raw)?;
+        let symbol = match target.to_symbol_id.as_deref() {
+            Some(stable_id) if named => {
+                symbols::get_symbol_by_stable_id(conn, project_id, ref_name, stable_id)?
+            }
+            _ => None,
+        };
+        let target_path = symbol
+            .as_ref()
+            .map(|symbol| symbol.path.clone())
+            .or(target.module_path);
+        targets.push(ResolvedImport {
+            imported_name,
+            qualified_name: raw.target_qualified_name,
+            external: target.outcome == RESOLUTION_EXTERNAL_REFERENCE,
+            resolution_outcome: target.outcome.to_string(),
+            target_path,
+            symbol: symbol.as_ref().map(symbol_to_reference_symbol),
+        });
+    }
+
+    let external = targets.iter().all(|target| target.external);
+    Ok(ResolveImportResult {
+        path: path.to_string(),
+        language: language.to_string(),
+        targets,
+        external,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cruxe_core::types::{SymbolKind, SymbolRecord};
+    use cruxe_state::{db, manifest, schema};
+
+    fn index_file(conn: &Connection, path: &str, symbol_name: Option<&str>) {
+        manifest::upsert_manifest(
+            conn,
+            &manifest::ManifestEntry {
+                repo: "proj".to_string(),
+                r#ref: "main".to_string(),
+                path: path.to_string(),
+                content_hash: format!("hash-{path}"),
+                size_bytes: 1,
+                mtime_ns: None,
+                language: Some("typescript".to_string()),
+                indexed_at: "2026-01-01T00:00:00Z".to_string(),
+            },
+        )
+        .unwrap();
+        let Some(name) = symbol_name else {
+            return;
+        };
+        symbols::insert_symbol(
+            conn,
+            &SymbolRecord {
+                repo: "proj".to_string(),
+                r#ref: "main".to_string(),
+                commit: None,
+                path: path.to_string(),
+                language: "typescript".to_string(),
+                symbol_id: format!("sym-{name}"),
+                symbol_stable_id: format!("stable-{name}"),
+                name: name.to_string(),
+                qualified_name: name.to_string(),
+                kind: SymbolKind::Class,
+                signature: None,
+                line_start: 4,
+                line_end: 20,
+                parent_symbol_id: None,
+                visibility: None,
+                content: None,
+                attributes: Vec::new(),
+                byte_start: None,
+                byte_end: None,
+                doc_comment: None,
+                parsed_signature: None,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn resolve_import_locates_named_namespace_and_external_targets() {
+        let tmp = tempfile::tempdir().unwrap();
+        let conn = db::open_connection(&tmp.path().join("state.db")).unwrap();
+        schema::create_tables(&conn).unwrap();
+        index_file(&conn, "src/router.ts", Some("Router"));
+        index_file(&conn, "src/utils/index.ts", None);
+
+        let named = resolve_import(
+            &conn,
+            "proj",
+            "main",
+            "src/app.ts",
+            r#"import { Router } from "./router";"#,
+        )
+        .unwrap();
+        assert!(!named.external);
+        let target = &named.targets[0];
+        assert_eq!(target.target_path.as_deref(), Some("src/router.ts"));
+        let symbol = target.symbol.as_ref().unwrap();
+        assert_eq!(symbol.symbol_stable_id, "stable-Router");
+        assert_eq!(symbol.line_start, 4);
+
+        let namespace = resolve_import(
+            &conn,
+            "proj",
+            "main",
+            "src/app.ts",
+            r#"import * as Utils from "./utils";"#,
+        )
+        .unwrap();
+        let target = &namespace.targets[0];
+        assert_eq!(target.target_path.as_deref(), Some("src/utils/index.ts"));
+        assert!(target.symbol.is_none());
+
+        let external = resolve_import(
+            &conn,
+            "proj",
+            "main",
+            "src/app.ts",
+            r#"import { useState } from "react";"#,
+        )
+        .unwrap();
+        assert!(external.external);
+        assert!(external.targets[0].target_path.is_none());
+    }
+
+    #[test]
+    fn resolve_import_rejects_statements_without_imports() {
+        let tmp = tempfile::tempdir().unwrap();
+        let conn = db::open_connection(&tmp.path().join("state.db")).unwrap();
+        schema::create_tables(&conn).unwrap();
+        assert!(matches!(
+            resolve_import(&conn, "proj", "main", "src/app.ts", "const x = 1;"),
+            Err(ResolveImportError::NoImport)
+        ));
+        assert!(matches!(
+            resolve_import(&conn, "proj", "main", "README.md", "import x"),
+            Err(ResolveImportError::UnsupportedLanguage(_))
+        ));
+    }
+}
@@ -5,7 +5,7 @@ use cruxe_core::edge_confidence::{
 use cruxe_core::error::StateError;
 use cruxe_core::types::{
     PolicyMode, QueryIntent, RankingReasons, RankingSignalContribution, RefScope, SourceLayer,
-    SymbolKind, SymbolRecord, SymbolRole,
+    SymbolKind, SymbolRecord, SymbolRole, VisibilityFilter,
 };
 use cruxe_state::tantivy_index::IndexSet;
 use rusqlite::Connection;
@@ -13,28 +13,34 @@ use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::Path;
 use std::time::Instant;
-use tantivy::Term;
 use tantivy::collector::TopDocs;
-use tantivy::query::{BooleanQuery, Occur, QueryParser, TermQuery};
+use tantivy::query::{BooleanQuery, EnableScoring, Occur, PhraseQuery, QueryParser, TermQuery};
 use tantivy::schema::{IndexRecordOption, Value};
+use tantivy::snippet::SnippetGenerator;
+use tantivy::{DocAddress, DocSet, TERMINATED, Term};
 use tracing::{debug, warn};
 
 use crate::confidence::evaluate_confidence;
 use crate::hybrid::{blend_hybrid_results, semantic_query};
-use crate::intent::{IntentPolicy, classify_intent_with_policy};
+use crate::intent::{
+    IntentPolicy, classify_intent_with_policy, forced_intent, forced_phrase_literal, phrase_literal,
+};
+use crate::locate::top_docs_with_visibility;
 use crate::overlay_merge;
 use crate::planner::build_plan_with_ref;
 use crate::policy::PolicyRuntime;
 use crate::ranking::{
-    rerank_with_budget, rerank_with_reasons_with_budget, semantic_signal_adjustment,
+    RerankPreferences, rerank_with_preferences, rerank_with_reasons_with_preferences,
+    semantic_signal_adjustment,
 };
+use crate::ranking_log;
 use crate::rerank::{RerankDocument, rerank_documents};
 use crate::scoring::normalize_relevance_score;
 use crate::{
     adaptive_plan::{
         DowngradeReason, PlanBudget, PlanController, PlanSelectionInput, QueryPlan, plan_budget,
     },
-    scoring,
+    scoring, synonyms,
 };
 
 /// Reciprocal Rank Fusion constant (standard value from the RRF paper).
@@ -43,6 +49,8 @@ use crate::{
 pub(crate) const RRF_K: f64 = 60.0;
 const SEARCH_SCORE_TOLERANCE: f64 = 1e-9;
 const SIGNAL_CONFIDENCE_STRUCTURAL: &str = "confidence_structural_boost";
+/// Longest highlighted fragment, matching the plain snippet truncation.
+const HIGHLIGHT_FRAGMENT_MAX_CHARS: usize = 200;
 
 /// A search result from search_code.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -58,6 +66,13 @@ pub struct SearchResult {
     pub path: String,
     pub line_start: u32,
     pub line_end: u32,
+    /// Byte range of the matched symbol (end exclusive). Set for symbol hits
+    /// and for snippets that cover exactly one symbol; absent for file hits
+    /// and indexes built before byte ranges were recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub byte_start: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub byte_end: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub kind: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -78,6 +93,24 @@ pub struct SearchResult {
     pub source_layer: Option<SourceLayer>,
     #[serde(default = "default_result_provenance")]
     pub provenance: String,
+    /// Best-matching fragment of the indexed content with query-term ranges,
+    /// set when the search ran with `highlight`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub highlight: Option<SearchHighlight>,
+}
+
+/// A fragment of a result's content around its query-term matches.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchHighlight {
+    pub fragment: String,
+    /// Byte ranges of matched terms within `fragment` (end exclusive).
+    pub match_ranges: Vec<HighlightRange>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighlightRange {
+    pub start: usize,
+    pub end: usize,
 }
 
 /// A suggested next action for the AI agent.
@@ -192,12 +225,36 @@ pub struct SearchExecutionOptions {
     pub plan_override: Option<String>,
     pub policy_mode_override: Option<PolicyMode>,
     pub policy_runtime: Option<PolicyRuntime>,
+    /// Language to nudge ahead of equally ranked results (e.g. the caller's open file).
+    pub preferred_language: Option<String>,
+    /// Further languages accepted alongside the `language` argument; a result
+    /// matches when its language is any of them.
+    pub languages: Vec<String>,
+    /// Per-request semantic switch: `Some(true)` runs the vector branch whatever
+    /// the query intent, `Some(false)` keeps the search lexical, `None` follows
+    /// the configured mode and intent.
+    pub semantic: Option<bool>,
+    /// Restrict results to symbols of this kind (e.g. `struct`). Snippet and
+    /// file hits carry no kind and are left out.
+    pub kind: Option<String>,
+    /// Number of top-ranked results to skip before returning `limit` results.
+    pub offset: usize,
+    /// Return a highlighted fragment per lexical hit in place of `snippet`.
+    pub highlight: bool,
+    /// Restrict results to symbols whose stored visibility passes the filter.
+    /// Snippet and file hits carry no visibility and are left out unless `Any`.
+    pub visibility: VisibilityFilter,
+    /// Caller-chosen intent that replaces the classifier's verdict.
+    pub intent: Option<QueryIntent>,
 }
 
 /// Optional debug payload for search_code.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct SearchDebugInfo {
     pub join_status: JoinStatus,
+    /// Synonyms appended to a natural-language query before lexical search.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub expanded_terms: Vec<String>,
 }
 
 pub struct VcsSearchContext<'a> {
@@ -265,10 +322,19 @@ pub fn search_code_with_options(
     options: SearchExecutionOptions,
 ) -> Result<SearchResponse, StateError> {
     let query_start = Instant::now();
-    let mut debug = tracing::enabled!(tracing::Level::DEBUG).then_some(SearchDebugInfo::default());
+    // Rank the whole window up to the requested page so page boundaries are
+    // cut from one consistent ordering.
+    let page_offset = options.offset;
+    let page_limit = limit;
+    let limit = page_offset.saturating_add(page_limit);
+    let mut debug = (debug_ranking || tracing::enabled!(tracing::Level::DEBUG))
+        .then_some(SearchDebugInfo::default());
 
     let intent_policy = IntentPolicy::from(&options.search_config.intent);
-    let intent = classify_intent_with_policy(query, &intent_policy);
+    let intent = match options.intent {
+        Some(forced) => forced_intent(forced),
+        None => classify_intent_with_policy(query, &intent_policy),
+    };
     let ref_scope = match r#ref {
         Some(explicit) => RefScope::explicit(explicit),
         None => RefScope::live(),
@@ -276,11 +342,32 @@ pub fn search_code_with_options(
     let plan = build_plan_with_ref(intent.intent, ref_scope);
     let effective_ref = plan.ref_scope.r#ref.clone();
     let search_ref = Some(effective_ref.as_str());
+    let languages: Vec<&str> = language
+        .into_iter()
+        .chain(options.languages.iter().map(String::as_str))
+        .collect();
+    let kind = options.kind.as_deref();
     let mut semantic_state = semantic_execution_state(&intent, &options);
     let mut semantic_budget_exhausted = false;
     let mut response_warnings = Vec::new();
 
     let mut all_results = Vec::new();
+    let mut lexical_query = if options.search_config.fold_naming_conventions {
+        fold_naming_conventions(query)
+    } else {
+        query.to_string()
+    };
+    // Only natural-language queries are expanded; symbol and path lookups stay exact.
+    if options.search_config.expand_synonyms && intent.intent == QueryIntent::NaturalLanguage {
+        let expansion = synonyms::expand_query(&lexical_query, &options.search_config.synonyms);
+        if !expansion.expanded_terms.is_empty() {
+            debug!(expanded_terms = ?expansion.expanded_terms, "expanded query synonyms");
+            lexical_query = expansion.query;
+            if let Some(debug) = debug.as_mut() {
+                debug.expanded_terms = expansion.expanded_terms;
+            }
+        }
+    }
 
     // Search each index and apply RRF (Reciprocal Rank Fusion) scoring.
     // RRF score per source = weight / (k + rank), where k=60 is the standard constant.
@@ -292,12 +379,15 @@ pub fn search_code_with_options(
             &index_set.symbols,
             &mut debug,
             conn,
-            query,
+            &lexical_query,
             "symbol",
             SearchScope {
                 ref_name: search_ref,
-                language,
+                languages: &languages,
+                kind,
                 role: options.role.as_deref(),
+                visibility: options.visibility,
+                highlight: options.highlight,
             },
             limit,
         )?;
@@ -305,18 +395,46 @@ pub fn search_code_with_options(
         all_results.extend(results);
     }
 
-    // Search snippets index
-    if plan.search_snippets {
+    // Search snippets index; phrase intent matches the quoted words in order.
+    let phrase = match options.intent {
+        Some(QueryIntent::Phrase) => Some(forced_phrase_literal(query)),
+        _ if intent.intent == QueryIntent::Phrase => phrase_literal(query),
+        _ => None,
+    };
+    let symbols_only = kind.is_some() || !options.visibility.is_any();
+    let search_snippets = plan.search_snippets && !symbols_only;
+    if let Some(phrase) = phrase.filter(|_| search_snippets) {
+        let mut results = search_snippet_phrase(
+            &index_set.snippets,
+            &mut debug,
+            conn,
+            phrase,
+            SearchScope {
+                ref_name: search_ref,
+                languages: &languages,
+                kind,
+                role: options.role.as_deref(),
+                visibility: options.visibility,
+                highlight: options.highlight,
+            },
+            limit,
+        )?;
+        apply_rrf_scores(&mut results, plan.snippet_weight, RRF_K as f32);
+        all_results.extend(results);
+    } else if search_snippets {
         let mut results = search_index(
             &index_set.snippets,
             &mut debug,
             conn,
-            query,
+            &lexical_query,
             "snippet",
             SearchScope {
                 ref_name: search_ref,
-                language,
+                languages: &languages,
+                kind,
                 role: options.role.as_deref(),
+                visibility: options.visibility,
+                highlight: options.highlight,
             },
             limit,
         )?;
@@ -325,17 +443,20 @@ pub fn search_code_with_options(
     }
 
     // Search files index
-    if plan.search_files {
+    if plan.search_files && !symbols_only {
         let mut results = search_index(
             &index_set.files,
             &mut debug,
             conn,
-            query,
+            &lexical_query,
             "file",
             SearchScope {
                 ref_name: search_ref,
-                language,
+                languages: &languages,
+                kind,
                 role: options.role.as_deref(),
+                visibility: options.visibility,
+                highlight: options.highlight,
             },
             limit,
         )?;
@@ -344,18 +465,24 @@ pub fn search_code_with_options(
     }
 
     // Apply local lexical reranking boosts on top of RRF scores.
+    let rerank_preferences = RerankPreferences {
+        preferred_language: options.preferred_language.as_deref(),
+        natural_language: intent.intent == QueryIntent::NaturalLanguage,
+    };
     let mut ranking_reasons = if debug_ranking {
-        let reasons = rerank_with_reasons_with_budget(
+        let reasons = rerank_with_reasons_with_preferences(
             &mut all_results,
             query,
             &options.search_config.ranking_signal_budgets,
+            rerank_preferences,
         );
         Some(reasons)
     } else {
-        rerank_with_budget(
+        rerank_with_preferences(
             &mut all_results,
             query,
             &options.search_config.ranking_signal_budgets,
+            rerank_preferences,
         );
         None
     };
@@ -369,8 +496,14 @@ pub fn search_code_with_options(
     let semantic_runtime_available = conn.is_some()
         && options.search_config.semantic_enabled()
         && options.search_config.semantic_mode_typed() == cruxe_core::types::SemanticMode::Hybrid;
+    // A requested semantic search is planned like a natural-language query.
+    let plan_intent = if options.semantic == Some(true) {
+        QueryIntent::NaturalLanguage
+    } else {
+        intent.intent
+    };
     let mut plan_controller = PlanController::select(PlanSelectionInput {
-        intent: intent.intent,
+        intent: plan_intent,
         lexical_confidence,
         semantic_runtime_available,
         override_plan: options.plan_override.as_deref(),
@@ -530,6 +663,12 @@ pub fn search_code_with_options(
     if let Some(role) = options.role.as_deref() {
         retain_role_filtered_results(&mut all_results, role);
     }
+    if !options.visibility.is_any() {
+        all_results.retain(|result| {
+            result.result_type == "symbol"
+                && options.visibility.matches(result.visibility.as_deref())
+        });
+    }
 
     let policy_runtime =
         options
@@ -605,6 +744,12 @@ pub fn search_code_with_options(
         semantic_state.rerank_provider = "local".to_string();
     }
 
+    if let Some(kind) = kind {
+        // Semantic hits bypass the index filter; drop any that are not of `kind`.
+        all_results.retain(|result| {
+            result.result_type == "symbol" && result.kind.as_deref() == Some(kind)
+        });
+    }
     let confidence_structural = apply_confidence_weighted_structural_boost(
         conn,
         effective_ref.as_str(),
@@ -639,7 +784,15 @@ pub fn search_code_with_options(
     }
 
     all_results.truncate(limit);
-    let ranking_reasons = ranking_reasons.map(|reasons| reasons.into_iter().take(limit).collect());
+    all_results.drain(..page_offset.min(all_results.len()));
+    let ranking_reasons = ranking_reasons.map(|reasons| {
+        reasons
+            .into_iter()
+            .take(limit)
+            .skip(page_offset)
+            .take(page_limit)
+            .collect()
+    });
 
     let confidence_threshold = options
         .search_config
@@ -720,7 +873,7 @@ pub fn search_code_with_options(
         "search_code"
     );
 
-    Ok(SearchResponse {
+    let response = SearchResponse {
         results: all_results,
         query_intent: intent.intent,
         total_candidates: total,
@@ -728,7 +881,9 @@ pub fn search_code_with_options(
         metadata,
         debug,
         ranking_reasons,
-    })
+    };
+    ranking_log::record(&options.search_config.ranking_log, query, &response);
+    Ok(response)
 }
 
 /// Execute VCS-mode merged search for a non-default ref.
@@ -764,8 +919,16 @@ pub fn search_code_vcs_merged_with_options(
     debug_ranking: bool,
     options: SearchExecutionOptions,
 ) -> Result<SearchResponse, StateError> {
-    let base_options = options.clone();
-    let overlay_options = options.clone();
+    // Per-layer searches are intermediate; only the merged ranking is logged.
+    // Layers return the whole window and the page is cut after merging.
+    let page_offset = options.offset;
+    let page_limit = limit;
+    let limit = page_offset.saturating_add(page_limit);
+    let mut layer_options = options.clone();
+    layer_options.search_config.ranking_log.enabled = false;
+    layer_options.offset = 0;
+    let base_options = layer_options.clone();
+    let overlay_options = layer_options.clone();
     let run_sequential = || -> Result<(SearchResponse, SearchResponse), StateError> {
         let base = search_code_with_options(
             ctx.base_index_set,
@@ -842,7 +1005,7 @@ pub fn search_code_vcs_merged_with_options(
                     language,
                     limit,
                     false,
-                    options.clone(),
+                    layer_options.clone(),
                 )
             });
             let overlay_task = scope.spawn(|| {
@@ -854,7 +1017,7 @@ pub fn search_code_vcs_merged_with_options(
                     language,
                     limit,
                     false,
-                    options.clone(),
+                    layer_options.clone(),
                 )
             });
 
@@ -869,17 +1032,23 @@ pub fn search_code_vcs_merged_with_options(
     };
 
     let mut results = overlay_merge::merged_search(base.results, overlay.results, ctx.tombstones);
+    let rerank_preferences = RerankPreferences {
+        preferred_language: options.preferred_language.as_deref(),
+        natural_language: base.query_intent == QueryIntent::NaturalLanguage,
+    };
     let mut ranking_reasons = if debug_ranking {
-        Some(rerank_with_reasons_with_budget(
+        Some(rerank_with_reasons_with_preferences(
             &mut results,
             query,
             &options.search_config.ranking_signal_budgets,
+            rerank_preferences,
         ))
     } else {
-        rerank_with_budget(
+        rerank_with_preferences(
             &mut results,
             query,
             &options.search_config.ranking_signal_budgets,
+            rerank_preferences,
         );
         None
     };
@@ -893,7 +1062,15 @@ pub fn search_code_vcs_merged_with_options(
         &mut merged_warnings,
     )?;
     results.truncate(limit);
-    let ranking_reasons = ranking_reasons.map(|reasons| reasons.into_iter().take(limit).collect());
+    results.drain(..page_offset.min(results.len()));
+    let ranking_reasons = ranking_reasons.map(|reasons| {
+        reasons
+            .into_iter()
+            .take(limit)
+            .skip(page_offset)
+            .take(page_limit)
+            .collect()
+    });
 
     // Recalculate confidence on the merged result set (not the stale overlay snapshot).
     let mut metadata = overlay.metadata;
@@ -932,7 +1109,7 @@ pub fn search_code_vcs_merged_with_options(
     metadata.confidence_structural = confidence_structural.or(metadata.confidence_structural);
     metadata.warnings = merged_warnings;
 
-    Ok(SearchResponse {
+    let response = SearchResponse {
         results,
         query_intent: base.query_intent,
         total_candidates: base.total_candidates + overlay.total_candidates,
@@ -944,7 +1121,200 @@ pub fn search_code_vcs_merged_with_options(
         metadata,
         debug: None,
         ranking_reasons,
-    })
+    };
+    ranking_log::record(&options.search_config.ranking_log, query, &response);
+    Ok(response)
+}
+
+/// Filters applied by [`export_search_results`]; the same ones `search_code`
+/// honors, without the ranking options.
+#[derive(Debug, Clone, Copy)]
+pub struct SearchExportScope<'a> {
+    /// OR-ed language filter; empty means any language.
+    pub languages: &'a [&'a str],
+    pub kind: Option<&'a str>,
+    pub role: Option<&'a str>,
+    pub visibility: VisibilityFilter,
+}
+
+/// Counts reported once an export finishes or is stopped.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchExportSummary {
+    pub symbols: usize,
+    pub snippets: usize,
+    pub files: usize,
+    pub policy_blocked_count: usize,
+    pub policy_redacted_count: usize,
+    /// `false` when `emit` stopped the export before every match was written.
+    pub complete: bool,
+}
+
+impl SearchExportSummary {
+    pub fn total(&self) -> usize {
+        self.symbols + self.snippets + self.files
+    }
+}
+
+/// Stream every lexical match of `query` on `ref_name` to `emit`, unranked
+/// and without a result cap: symbols, then snippets, then files, in index
+/// order. Each hit is converted and passed through `policy` as it is read
+/// from its segment, so memory stays flat however many documents match.
+/// Scores are `0.0`. `emit` returns `false` to stop early.
+pub fn export_search_results(
+    index_set: &IndexSet,
+    conn: Option<&Connection>,
+    query: &str,
+    ref_name: &str,
+    scope: SearchExportScope<'_>,
+    policy: &PolicyRuntime,
+    emit: &mut dyn FnMut(SearchResult) -> bool,
+) -> Result<SearchExportSummary, StateError> {
+    let mut summary = SearchExportSummary::default();
+    let layer = ExportLayer {
+        index_set,
+        ref_name,
+        source_layer: None,
+        skip_paths: None,
+    };
+    summary.complete = export_layer(layer, conn, query, scope, policy, &mut summary, emit)?;
+    Ok(summary)
+}
+
+/// VCS variant of [`export_search_results`]: overlay matches on
+/// `target_ref` first, then base matches on `base_ref` outside tombstoned
+/// paths, each tagged with its `source_layer`.
+pub fn export_search_results_vcs_merged(
+    ctx: VcsSearchContext<'_>,
+    conn: Option<&Connection>,
+    query: &str,
+    scope: SearchExportScope<'_>,
+    policy: &PolicyRuntime,
+    emit: &mut dyn FnMut(SearchResult) -> bool,
+) -> Result<SearchExportSummary, StateError> {
+    let mut summary = SearchExportSummary::default();
+    let overlay = ExportLayer {
+        index_set: ctx.overlay_index_set,
+        ref_name: ctx.target_ref,
+        source_layer: Some(SourceLayer::Overlay),
+        skip_paths: None,
+    };
+    let base = ExportLayer {
+        index_set: ctx.base_index_set,
+        ref_name: ctx.base_ref,
+        source_layer: Some(SourceLayer::Base),
+        skip_paths: Some(ctx.tombstones),
+    };
+    summary.complete = export_layer(overlay, conn, query, scope, policy, &mut summary, emit)?
+        && export_layer(base, conn, query, scope, policy, &mut summary, emit)?;
+    Ok(summary)
+}
+
+struct ExportLayer<'a> {
+    index_set: &'a IndexSet,
+    ref_name: &'a str,
+    source_layer: Option<SourceLayer>,
+    skip_paths: Option<&'a HashSet<String>>,
+}
+
+/// Walk each segment's matching documents with an unscored scorer instead of
+/// a top-k collector. Returns `false` once `emit` asks to stop.
+fn export_layer(
+    layer: ExportLayer<'_>,
+    conn: Option<&Connection>,
+    query: &str,
+    scope: SearchExportScope<'_>,
+    policy: &PolicyRuntime,
+    summary: &mut SearchExportSummary,
+    emit: &mut dyn FnMut(SearchResult) -> bool,
+) -> Result<bool, StateError> {
+    let indexes = [
+        (&layer.index_set.symbols, "symbol"),
+        (&layer.index_set.snippets, "snippet"),
+        (&layer.index_set.files, "file"),
+    ];
+    for (index, result_type) in indexes {
+        if (scope.role.is_some() || !scope.visibility.is_any()) && result_type != "symbol" {
+            continue;
+        }
+        let Some(parsed_query) = parse_index_query(index, query, result_type)? else {
+            continue;
+        };
+        let schema = index.schema();
+        let search_scope = SearchScope {
+            ref_name: Some(layer.ref_name),
+            languages: scope.languages,
+            kind: scope.kind,
+            role: scope.role,
+            visibility: scope.visibility,
+            highlight: false,
+        };
+        let final_query = scoped_query(&schema, parsed_query, &search_scope);
+        let visibility_field = if scope.visibility.is_any() {
+            None
+        } else {
+            schema.get_field("visibility").ok()
+        };
+
+        let reader = index.reader().map_err(StateError::tantivy)?;
+        let searcher = reader.searcher();
+        let weight = final_query
+            .weight(EnableScoring::disabled_from_searcher(&searcher))
+            .map_err(StateError::tantivy)?;
+        for (segment_ord, segment_reader) in searcher.segment_readers().iter().enumerate() {
+            let alive = segment_reader.alive_bitset();
+            let mut scorer = weight
+                .scorer(segment_reader, 1.0)
+                .map_err(StateError::tantivy)?;
+            let mut doc_id = scorer.doc();
+            while doc_id != TERMINATED {
+                if alive.is_none_or(|bits| bits.is_alive(doc_id)) {
+                    let doc = searcher
+                        .doc::<tantivy::TantivyDocument>(DocAddress::new(
+                            segment_ord as u32,
+                            doc_id,
+                        ))
+                        .map_err(StateError::tantivy)?;
+                    let visible = visibility_field.is_none_or(|field| {
+                        scope
+                            .visibility
+                            .matches(doc.get_first(field).and_then(|v| v.as_str()))
+                    });
+                    if visible {
+                        let mut result = search_result_from_doc(
+                            &schema,
+                            &doc,
+                            0.0,
+                            result_type,
+                            conn,
+                            &mut None,
+                            None,
+                        );
+                        let skipped = layer
+                            .skip_paths
+                            .is_some_and(|paths| paths.contains(result.path.as_str()));
+                        if !skipped {
+                            result.source_layer = layer.source_layer;
+                            let applied = policy.apply(vec![result])?;
+                            summary.policy_blocked_count += applied.blocked_count;
+                            summary.policy_redacted_count += applied.redacted_count;
+                            for result in applied.results {
+                                match result_type {
+                                    "symbol" => summary.symbols += 1,
+                                    "snippet" => summary.snippets += 1,
+                                    _ => summary.files += 1,
+                                }
+                                if !emit(result) {
+                                    return Ok(false);
+                                }
+                            }
+                        }
+                    }
+                }
+                doc_id = scorer.advance();
+            }
+        }
+    }
+    Ok(true)
 }
 
 fn clone_connection_for_parallel(conn: &Connection) -> Option<Connection> {
@@ -1226,6 +1596,7 @@ fn rebuild_ranking_reasons(
                 definition_boost: 0.0,
                 kind_match: 0.0,
                 test_file_penalty: 0.0,
+                language_preference_boost: 0.0,
                 confidence_structural_boost: 0.0,
                 structural_weighted_centrality: 0.0,
                 structural_raw_centrality: 0.0,
@@ -1521,6 +1892,7 @@ struct SemanticExecutionState {
     rerank_fallback: bool,
     rerank_fallback_reason: Option<String>,
     lexical_short_circuit_threshold: f64,
+    semantic_requested: bool,
 }
 
 fn semantic_execution_state(
@@ -1529,12 +1901,20 @@ fn semantic_execution_state(
 ) -> SemanticExecutionState {
     let semantic_mode = options.search_config.semantic_mode_typed();
     let semantic_enabled = options.search_config.semantic_enabled();
+    let semantic_requested = options.semantic == Some(true);
+    let ratio_intent = if semantic_requested {
+        QueryIntent::NaturalLanguage
+    } else {
+        intent.intent
+    };
     let semantic_ratio_cap = options
         .search_config
-        .semantic_ratio_for_intent(intent.intent, options.semantic_ratio_override);
-    let semantic_skipped_reason = if !semantic_enabled {
+        .semantic_ratio_for_intent(ratio_intent, options.semantic_ratio_override);
+    let semantic_skipped_reason = if options.semantic == Some(false) {
+        Some("semantic_not_requested".to_string())
+    } else if !semantic_enabled {
         Some("semantic_disabled".to_string())
-    } else if intent.intent != QueryIntent::NaturalLanguage {
+    } else if ratio_intent != QueryIntent::NaturalLanguage {
         Some("intent_not_nl".to_string())
     } else if semantic_ratio_cap <= 0.0 {
         Some("semantic_ratio_zero".to_string())
@@ -1572,6 +1952,7 @@ fn semantic_execution_state(
             .search_config
             .semantic
             .lexical_short_circuit_threshold,
+        semantic_requested,
     }
 }
 
@@ -1588,7 +1969,7 @@ impl SemanticExecutionState {
     }
 
     fn apply_lexical_short_circuit(&mut self, lexical_results: &[SearchResult]) {
-        if self.semantic_skipped_reason.is_some() {
+        if self.semantic_skipped_reason.is_some() || self.semantic_requested {
             return;
         }
         let top_lexical = lexical_results
@@ -1608,8 +1989,79 @@ fn semantic_degraded(semantic_fallback: bool, semantic_succeeded: bool) -> bool
 #[derive(Clone, Copy)]
 struct SearchScope<'a> {
     ref_name: Option<&'a str>,
-    language: Option<&'a str>,
+    /// OR-ed language filter; empty means any language.
+    languages: &'a [&'a str],
+    kind: Option<&'a str>,
     role: Option<&'a str>,
+    visibility: VisibilityFilter,
+    highlight: bool,
+}
+
+/// Expand identifier-like query terms into their snake_case, camelCase, and
+/// PascalCase spellings so `getUser` also matches `get_user` and `GetUser`.
+///
+/// Terms that are not plain identifiers (paths, qualified names, query syntax)
+/// and single-word identifiers are passed through unchanged. The variants are
+/// OR-ed by the query parser, trading precision for cross-language recall.
+fn fold_naming_conventions(query: &str) -> String {
+    let mut terms: Vec<String> = Vec::new();
+    for term in query.split_whitespace() {
+        let mut push = |candidate: String| {
+            if !terms.contains(&candidate) {
+                terms.push(candidate);
+            }
+        };
+        push(term.to_string());
+        if !term.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            continue;
+        }
+        let words = identifier_words(term);
+        if words.len() < 2 {
+            continue;
+        }
+        push(words.join("_"));
+        let pascal: String = words.iter().map(|word| capitalize(word)).collect();
+        let mut camel = words[0].clone();
+        camel.extend(words[1..].iter().map(|word| capitalize(word)));
+        push(camel);
+        push(pascal);
+    }
+    terms.join(" ")
+}
+
+/// Split an identifier into lowercase words on `_` and case boundaries
+/// (`HTTPServerError` → `http`, `server`, `error`).
+fn identifier_words(identifier: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for part in identifier.split('_').filter(|part| !part.is_empty()) {
+        let chars: Vec<char> = part.chars().collect();
+        let mut current = String::new();
+        for (i, &c) in chars.iter().enumerate() {
+            if c.is_ascii_uppercase() && !current.is_empty() {
+                let prev = chars[i - 1];
+                let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_ascii_lowercase());
+                if prev.is_ascii_lowercase()
+                    || prev.is_ascii_digit()
+                    || (prev.is_ascii_uppercase() && next_is_lower)
+                {
+                    words.push(std::mem::take(&mut current));
+                }
+            }
+            current.push(c.to_ascii_lowercase());
+        }
+        if !current.is_empty() {
+            words.push(current);
+        }
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+        None => String::new(),
+    }
 }
 
 fn search_index(
@@ -1621,12 +2073,23 @@ fn search_index(
     scope: SearchScope<'_>,
     limit: usize,
 ) -> Result<Vec<SearchResult>, StateError> {
-    if scope.role.is_some() && result_type != "symbol" {
+    if (scope.role.is_some() || !scope.visibility.is_any()) && result_type != "symbol" {
         return Ok(Vec::new());
     }
 
-    let reader = index.reader().map_err(StateError::tantivy)?;
-    let searcher = reader.searcher();
+    let Some(parsed_query) = parse_index_query(index, query, result_type)? else {
+        return Ok(Vec::new());
+    };
+    search_index_with_query(index, debug, conn, parsed_query, result_type, scope, limit)
+}
+
+/// Parse `query` against the full-text fields searched for `result_type`,
+/// with the symbol field boosts; `None` when the index has none of them.
+fn parse_index_query(
+    index: &tantivy::Index,
+    query: &str,
+    result_type: &str,
+) -> Result<Option<Box<dyn tantivy::query::Query>>, StateError> {
     let schema = index.schema();
 
     let search_fields: Vec<tantivy::schema::Field> = match result_type {
@@ -1648,11 +2111,11 @@ fn search_index(
             .iter()
             .filter_map(|name| schema.get_field(name).ok())
             .collect(),
-        _ => return Ok(Vec::new()),
+        _ => return Ok(None),
     };
 
     if search_fields.is_empty() {
-        return Ok(Vec::new());
+        return Ok(None);
     }
 
     let mut query_parser = QueryParser::for_index(index, search_fields);
@@ -1673,186 +2136,339 @@ fn search_index(
             query_parser.set_field_boost(field, 0.5);
         }
     }
-    let parsed_query = query_parser
+    query_parser
         .parse_query(query)
+        .map(Some)
+        .map_err(StateError::tantivy)
+}
+
+/// Match `phrase` as consecutive tokens of the snippets `content` field.
+fn search_snippet_phrase(
+    index: &tantivy::Index,
+    debug: &mut Option<SearchDebugInfo>,
+    conn: Option<&Connection>,
+    phrase: &str,
+    scope: SearchScope<'_>,
+    limit: usize,
+) -> Result<Vec<SearchResult>, StateError> {
+    if scope.role.is_some() || !scope.visibility.is_any() {
+        return Ok(Vec::new());
+    }
+    let Ok(content) = index.schema().get_field("content") else {
+        return Ok(Vec::new());
+    };
+
+    let mut analyzer = index
+        .tokenizer_for_field(content)
         .map_err(StateError::tantivy)?;
+    let mut terms = Vec::new();
+    let mut stream = analyzer.token_stream(phrase);
+    while let Some(token) = stream.next() {
+        terms.push((token.position, Term::from_field_text(content, &token.text)));
+    }
 
-    // Build final query with optional ref and language filters
-    let final_query: Box<dyn tantivy::query::Query> =
-        if scope.ref_name.is_some() || scope.language.is_some() || scope.role.is_some() {
-            let mut clauses: Vec<(Occur, Box<dyn tantivy::query::Query>)> = Vec::new();
-            clauses.push((Occur::Must, parsed_query));
+    let phrase_query: Box<dyn tantivy::query::Query> = match terms.len() {
+        0 => return Ok(Vec::new()),
+        1 => Box::new(TermQuery::new(
+            terms.remove(0).1,
+            IndexRecordOption::WithFreqs,
+        )),
+        _ => Box::new(PhraseQuery::new_with_offset(terms)),
+    };
 
-            if let Some(r) = scope.ref_name
-                && let Ok(ref_field) = schema.get_field("ref")
-            {
-                clauses.push((
-                    Occur::Must,
-                    Box::new(TermQuery::new(
-                        Term::from_field_text(ref_field, r),
-                        IndexRecordOption::Basic,
-                    )),
-                ));
-            }
-            if let Some(lang) = scope.language
-                && let Ok(lang_field) = schema.get_field("language")
-            {
-                clauses.push((
-                    Occur::Must,
-                    Box::new(TermQuery::new(
-                        Term::from_field_text(lang_field, lang),
-                        IndexRecordOption::Basic,
-                    )),
-                ));
-            }
-            if let Some(role) = scope.role
-                && let Ok(role_field) = schema.get_field("role")
-            {
-                clauses.push((
-                    Occur::Must,
-                    Box::new(TermQuery::new(
-                        Term::from_field_text(role_field, role),
-                        IndexRecordOption::Basic,
-                    )),
-                ));
-            }
-            Box::new(BooleanQuery::new(clauses))
+    search_index_with_query(index, debug, conn, phrase_query, "snippet", scope, limit)
+}
+
+fn search_index_with_query(
+    index: &tantivy::Index,
+    debug: &mut Option<SearchDebugInfo>,
+    conn: Option<&Connection>,
+    parsed_query: Box<dyn tantivy::query::Query>,
+    result_type: &str,
+    scope: SearchScope<'_>,
+    limit: usize,
+) -> Result<Vec<SearchResult>, StateError> {
+    let reader = index.reader().map_err(StateError::tantivy)?;
+    let searcher = reader.searcher();
+    let schema = index.schema();
+
+    // Highlight against the text query only; the ref/language/kind filters
+    // below are exact-term clauses on other fields.
+    let highlighter = if scope.highlight {
+        let content_field = if result_type == "file" {
+            "content_head"
         } else {
-            parsed_query
+            "content"
         };
+        match schema.get_field(content_field) {
+            Ok(field) => {
+                let mut generator =
+                    SnippetGenerator::create(&searcher, parsed_query.as_ref(), field)
+                        .map_err(StateError::tantivy)?;
+                generator.set_max_num_chars(HIGHLIGHT_FRAGMENT_MAX_CHARS);
+                Some(generator)
+            }
+            Err(_) => None,
+        }
+    } else {
+        None
+    };
 
-    let top_docs = searcher
-        .search(&final_query, &TopDocs::with_limit(limit))
-        .map_err(StateError::tantivy)?;
+    let final_query = scoped_query(&schema, parsed_query, &scope);
+    let top_docs =
+        top_docs_with_visibility(&searcher, final_query.as_ref(), limit, scope.visibility)?;
 
     let mut results = Vec::new();
     for (score, doc_address) in top_docs {
         let doc = searcher
             .doc::<tantivy::TantivyDocument>(doc_address)
             .map_err(StateError::tantivy)?;
-
-        let get_text = |field_name: &str| -> Option<String> {
-            schema
-                .get_field(field_name)
-                .ok()
-                .and_then(|f| doc.get_first(f))
-                .and_then(|v| v.as_str())
-                .map(|s: &str| s.to_string())
-                .filter(|s| !s.is_empty())
-        };
-        let get_u64 = |field_name: &str| -> u64 {
-            schema
-                .get_field(field_name)
-                .ok()
-                .and_then(|f| doc.get_first(f))
-                .and_then(|v| v.as_u64())
-                .unwrap_or(0)
-        };
-
-        let path = get_text("path").unwrap_or_default();
-        let line_start = get_u64("line_start") as u32;
-        let line_end = get_u64("line_end") as u32;
-        let mut kind = get_text("kind");
-        let mut symbol_name = get_text("symbol_exact").or_else(|| get_text("filename"));
-        let mut qualified_name = get_text("qualified_name");
-        let mut symbol_id = get_text("symbol_id");
-        let mut symbol_stable_id = get_text("symbol_stable_id");
-        let language = get_text("language").unwrap_or_default();
-        let doc_repo = get_text("repo").unwrap_or_default();
-        let doc_ref = get_text("ref").unwrap_or_default();
-
-        if result_type == "snippet" {
-            let join_hit = enrich_snippet_with_symbol_metadata(
-                conn,
-                &doc_repo,
-                &doc_ref,
-                &path,
-                line_start,
-                line_end,
-                SnippetSymbolMetadata {
-                    symbol_id: &mut symbol_id,
-                    symbol_stable_id: &mut symbol_stable_id,
-                    kind: &mut kind,
-                    name: &mut symbol_name,
-                    qualified_name: &mut qualified_name,
-                },
-            );
-            if let Some(debug) = debug.as_mut() {
-                if join_hit {
-                    debug.join_status.hits += 1;
-                } else {
-                    debug.join_status.misses += 1;
-                }
-            }
-        }
-
-        let result_id = compute_stable_result_id(StableResultIdInput {
-            result_type,
-            repo: &doc_repo,
-            ref_name: &doc_ref,
-            path: &path,
-            line_start,
-            line_end,
-            kind: kind.as_deref().unwrap_or(""),
-            name: symbol_name.as_deref().unwrap_or(""),
-            qualified_name: qualified_name.as_deref().unwrap_or(""),
-            language: &language,
-            symbol_stable_id: symbol_stable_id.as_deref().unwrap_or(""),
-        });
-
-        results.push(SearchResult {
-            repo: doc_repo,
-            result_id,
-            symbol_id,
-            symbol_stable_id,
-            result_type: result_type.to_string(),
-            path,
-            line_start,
-            line_end,
-            kind,
-            name: symbol_name,
-            qualified_name,
-            language,
-            signature: get_text("signature"),
-            visibility: get_text("visibility"),
+        results.push(search_result_from_doc(
+            &schema,
+            &doc,
             score,
-            snippet: get_text("content").map(|c| {
-                if c.len() > 200 {
-                    // Truncate at a char boundary to avoid panic on multi-byte UTF-8.
-                    let end = c
-                        .char_indices()
-                        .map(|(i, _)| i)
-                        .take_while(|&i| i <= 200)
-                        .last()
-                        .unwrap_or(0);
-                    format!("{}...", &c[..end])
-                } else {
-                    c
-                }
-            }),
-            chunk_type: get_text("chunk_type"),
-            source_layer: None,
-            provenance: default_result_provenance(),
-        });
+            result_type,
+            conn,
+            debug,
+            highlighter.as_ref(),
+        ));
     }
 
     Ok(results)
 }
 
-struct SnippetSymbolMetadata<'a> {
-    symbol_id: &'a mut Option<String>,
-    symbol_stable_id: &'a mut Option<String>,
-    kind: &'a mut Option<String>,
-    name: &'a mut Option<String>,
-    qualified_name: &'a mut Option<String>,
+/// Attach the ref/language/kind/role filters of `scope` to `parsed_query`.
+fn scoped_query(
+    schema: &tantivy::schema::Schema,
+    parsed_query: Box<dyn tantivy::query::Query>,
+    scope: &SearchScope<'_>,
+) -> Box<dyn tantivy::query::Query> {
+    if scope.ref_name.is_some()
+        || !scope.languages.is_empty()
+        || scope.kind.is_some()
+        || scope.role.is_some()
+    {
+        let mut clauses: Vec<(Occur, Box<dyn tantivy::query::Query>)> = Vec::new();
+        clauses.push((Occur::Must, parsed_query));
+
+        if let Some(r) = scope.ref_name
+            && let Ok(ref_field) = schema.get_field("ref")
+        {
+            clauses.push((
+                Occur::Must,
+                Box::new(TermQuery::new(
+                    Term::from_field_text(ref_field, r),
+                    IndexRecordOption::Basic,
+                )),
+            ));
+        }
+        if !scope.languages.is_empty()
+            && let Ok(lang_field) = schema.get_field("language")
+        {
+            let language_clauses: Vec<(Occur, Box<dyn tantivy::query::Query>)> = scope
+                .languages
+                .iter()
+                .map(|lang| {
+                    let term: Box<dyn tantivy::query::Query> = Box::new(TermQuery::new(
+                        Term::from_field_text(lang_field, lang),
+                        IndexRecordOption::Basic,
+                    ));
+                    (Occur::Should, term)
+                })
+                .collect();
+            clauses.push((Occur::Must, Box::new(BooleanQuery::new(language_clauses))));
+        }
+        if let Some(kind) = scope.kind
+            && let Ok(kind_field) = schema.get_field("kind")
+        {
+            clauses.push((
+                Occur::Must,
+                Box::new(TermQuery::new(
+                    Term::from_field_text(kind_field, kind),
+                    IndexRecordOption::Basic,
+                )),
+            ));
+        }
+        if let Some(role) = scope.role
+            && let Ok(role_field) = schema.get_field("role")
+        {
+            clauses.push((
+                Occur::Must,
+                Box::new(TermQuery::new(
+                    Term::from_field_text(role_field, role),
+                    IndexRecordOption::Basic,
+                )),
+            ));
+        }
+        Box::new(BooleanQuery::new(clauses))
+    } else {
+        parsed_query
+    }
 }
 
-fn enrich_snippet_with_symbol_metadata(
+/// Build the result for one stored document; snippets are joined to their
+/// enclosing symbol through `conn` when available.
+fn search_result_from_doc(
+    schema: &tantivy::schema::Schema,
+    doc: &tantivy::TantivyDocument,
+    score: f32,
+    result_type: &str,
     conn: Option<&Connection>,
-    repo: &str,
-    r#ref: &str,
-    path: &str,
-    line_start: u32,
+    debug: &mut Option<SearchDebugInfo>,
+    highlighter: Option<&SnippetGenerator>,
+) -> SearchResult {
+    let get_text = |field_name: &str| -> Option<String> {
+        schema
+            .get_field(field_name)
+            .ok()
+            .and_then(|f| doc.get_first(f))
+            .and_then(|v| v.as_str())
+            .map(|s: &str| s.to_string())
+            .filter(|s| !s.is_empty())
+    };
+    let get_u64 = |field_name: &str| -> u64 {
+        schema
+            .get_field(field_name)
+            .ok()
+            .and_then(|f| doc.get_first(f))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0)
+    };
+
+    let path = get_text("path").unwrap_or_default();
+    let line_start = get_u64("line_start") as u32;
+    let line_end = get_u64("line_end") as u32;
+    let get_opt_u32 = |field_name: &str| -> Option<u32> {
+        schema
+            .get_field(field_name)
+            .ok()
+            .and_then(|f| doc.get_first(f))
+            .and_then(|v| v.as_u64())
+            .map(|v| v as u32)
+    };
+    let mut byte_start = get_opt_u32("byte_start");
+    let mut byte_end = get_opt_u32("byte_end");
+    let mut kind = get_text("kind");
+    let mut symbol_name = get_text("symbol_exact").or_else(|| get_text("filename"));
+    let mut qualified_name = get_text("qualified_name");
+    let mut symbol_id = get_text("symbol_id");
+    let mut symbol_stable_id = get_text("symbol_stable_id");
+    let language = get_text("language").unwrap_or_default();
+    let doc_repo = get_text("repo").unwrap_or_default();
+    let doc_ref = get_text("ref").unwrap_or_default();
+
+    if result_type == "snippet" {
+        let join_hit = enrich_snippet_with_symbol_metadata(
+            conn,
+            &doc_repo,
+            &doc_ref,
+            &path,
+            line_start,
+            line_end,
+            SnippetSymbolMetadata {
+                symbol_id: &mut symbol_id,
+                symbol_stable_id: &mut symbol_stable_id,
+                kind: &mut kind,
+                name: &mut symbol_name,
+                qualified_name: &mut qualified_name,
+                byte_start: &mut byte_start,
+                byte_end: &mut byte_end,
+            },
+        );
+        if let Some(debug) = debug.as_mut() {
+            if join_hit {
+                debug.join_status.hits += 1;
+            } else {
+                debug.join_status.misses += 1;
+            }
+        }
+    }
+
+    // Content without a usable fragment keeps the plain snippet.
+    let highlight = highlighter.and_then(|generator| {
+        let snippet = generator.snippet_from_doc(doc);
+        (!snippet.fragment().is_empty()).then(|| SearchHighlight {
+            fragment: snippet.fragment().to_string(),
+            match_ranges: snippet
+                .highlighted()
+                .iter()
+                .map(|range| HighlightRange {
+                    start: range.start,
+                    end: range.end,
+                })
+                .collect(),
+        })
+    });
+
+    let result_id = compute_stable_result_id(StableResultIdInput {
+        result_type,
+        repo: &doc_repo,
+        ref_name: &doc_ref,
+        path: &path,
+        line_start,
+        name: symbol_name.as_deref().unwrap_or(""),
+        symbol_stable_id: symbol_stable_id.as_deref().unwrap_or(""),
+    });
+
+    SearchResult {
+        repo: doc_repo,
+        result_id,
+        symbol_id,
+        symbol_stable_id,
+        result_type: result_type.to_string(),
+        path,
+        line_start,
+        line_end,
+        byte_start,
+        byte_end,
+        kind,
+        name: symbol_name,
+        qualified_name,
+        language,
+        signature: get_text("signature"),
+        visibility: get_text("visibility"),
+        score,
+        snippet: if highlight.is_some() {
+            None
+        } else {
+            get_text("content").map(|c| {
+                if c.len() > 200 {
+                    // Truncate at a char boundary to avoid panic on multi-byte UTF-8.
+                    let end = c
+                        .char_indices()
+                        .map(|(i, _)| i)
+                        .take_while(|&i| i <= 200)
+                        .last()
+                        .unwrap_or(0);
+                    format!("{}...", &c[..end])
+                } else {
+                    c
+                }
+            })
+        },
+        chunk_type: get_text("chunk_type"),
+        source_layer: None,
+        provenance: default_result_provenance(),
+        highlight,
+    }
+}
+
+struct SnippetSymbolMetadata<'a> {
+    symbol_id: &'a mut Option<String>,
+    symbol_stable_id: &'a mut Option<String>,
+    kind: &'a mut Option<String>,
+    name: &'a mut Option<String>,
+    qualified_name: &'a mut Option<String>,
+    byte_start: &'a mut Option<u32>,
+    byte_end: &'a mut Option<u32>,
+}
+
+fn enrich_snippet_with_symbol_metadata(
+    conn: Option<&Connection>,
+    repo: &str,
+    r#ref: &str,
+    path: &str,
+    line_start: u32,
     line_end: u32,
     metadata: SnippetSymbolMetadata<'_>,
 ) -> bool {
@@ -1862,6 +2478,8 @@ fn enrich_snippet_with_symbol_metadata(
         kind,
         name,
         qualified_name,
+        byte_start,
+        byte_end,
     } = metadata;
 
     let Some(conn) = conn else {
@@ -1880,6 +2498,11 @@ fn enrich_snippet_with_symbol_metadata(
         return false;
     };
 
+    // A byte range is only precise when the snippet spans exactly this symbol.
+    if best_symbol.line_start == line_start && best_symbol.line_end == line_end {
+        *byte_start = best_symbol.byte_start;
+        *byte_end = best_symbol.byte_end;
+    }
     *symbol_id = Some(best_symbol.symbol_id);
     *symbol_stable_id = Some(best_symbol.symbol_stable_id);
     *kind = Some(best_symbol.kind.as_str().to_string());
@@ -1903,48 +2526,36 @@ fn choose_best_symbol_match(
     })
 }
 
-struct StableResultIdInput<'a> {
-    result_type: &'a str,
-    repo: &'a str,
-    ref_name: &'a str,
-    path: &'a str,
-    line_start: u32,
-    line_end: u32,
-    kind: &'a str,
-    name: &'a str,
-    qualified_name: &'a str,
-    language: &'a str,
-    symbol_stable_id: &'a str,
+pub(crate) struct StableResultIdInput<'a> {
+    pub result_type: &'a str,
+    pub repo: &'a str,
+    pub ref_name: &'a str,
+    pub path: &'a str,
+    pub line_start: u32,
+    pub name: &'a str,
+    pub symbol_stable_id: &'a str,
 }
 
-fn compute_stable_result_id(input: StableResultIdInput<'_>) -> String {
+/// Content-derived `result_id`: the same symbol gets the same id across
+/// re-indexes, segment orderings and retrieval channels (lexical or semantic).
+/// Results with a `symbol_stable_id` are keyed by it, so moving a symbol
+/// within its file keeps the id; other results fall back to `line_start:name`.
+pub(crate) fn compute_stable_result_id(input: StableResultIdInput<'_>) -> String {
     let StableResultIdInput {
         result_type,
         repo,
         ref_name,
         path,
         line_start,
-        line_end,
-        kind,
         name,
-        qualified_name,
-        language,
         symbol_stable_id,
     } = input;
-    let payload = format!(
-        "result:v2|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}",
-        result_type,
-        repo,
-        ref_name,
-        path,
-        line_start,
-        line_end,
-        kind,
-        name,
-        qualified_name,
-        language,
-        symbol_stable_id
-    );
+    let identity = if symbol_stable_id.is_empty() {
+        format!("{line_start}:{name}")
+    } else {
+        format!("symbol:{symbol_stable_id}")
+    };
+    let payload = format!("result:v3|{result_type}|{repo}|{ref_name}|{path}|{identity}");
     format!("res_{}", blake3::hash(payload.as_bytes()).to_hex())
 }
 
@@ -2042,6 +2653,7 @@ fn enrich_semantic_hits_with_symbol_index(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ranking::rerank_with_reasons_with_budget;
     use cruxe_core::config::SearchConfig as CoreSearchConfig;
     use cruxe_core::types::{SymbolKind, SymbolRecord};
     use cruxe_state::{db, schema, vector_index, vector_index::VectorRecord};
@@ -2059,6 +2671,8 @@ mod tests {
             path: "src/lib.rs".to_string(),
             line_start: 1,
             line_end: 1,
+            byte_start: None,
+            byte_end: None,
             kind: Some("function".to_string()),
             name: Some("demo".to_string()),
             qualified_name: Some("demo".to_string()),
@@ -2070,6 +2684,7 @@ mod tests {
             chunk_type: None,
             source_layer: None,
             provenance: "lexical".to_string(),
+            highlight: None,
         }
     }
 
@@ -2083,6 +2698,8 @@ mod tests {
             path: format!("src/{symbol_stable_id}.rs"),
             line_start: 1,
             line_end: 1,
+            byte_start: None,
+            byte_end: None,
             kind: Some("function".to_string()),
             name: Some(symbol_stable_id.to_string()),
             qualified_name: Some(symbol_stable_id.to_string()),
@@ -2094,6 +2711,7 @@ mod tests {
             chunk_type: None,
             source_layer: None,
             provenance: "lexical".to_string(),
+            highlight: None,
         }
     }
 
@@ -2134,6 +2752,7 @@ mod tests {
             rerank_fallback: false,
             rerank_fallback_reason: None,
             lexical_short_circuit_threshold: 0.85,
+            semantic_requested: false,
         };
 
         state.apply_lexical_short_circuit(&[make_result(0.05)]);
@@ -2320,6 +2939,7 @@ mod tests {
                 definition_boost: 0.0,
                 kind_match: 0.0,
                 test_file_penalty: 0.0,
+                language_preference_boost: 0.0,
                 confidence_structural_boost: 0.0,
                 structural_weighted_centrality: 0.0,
                 structural_raw_centrality: 0.0,
@@ -2339,6 +2959,7 @@ mod tests {
                 definition_boost: 0.0,
                 kind_match: 0.0,
                 test_file_penalty: 0.0,
+                language_preference_boost: 0.0,
                 confidence_structural_boost: 0.0,
                 structural_weighted_centrality: 0.0,
                 structural_raw_centrality: 0.0,
@@ -2554,6 +3175,11 @@ mod tests {
                     "fn authenticate_user(request: LoginRequest) -> Result<User> { todo!() }"
                         .to_string(),
                 ),
+                attributes: Vec::new(),
+                byte_start: None,
+                byte_end: None,
+                doc_comment: None,
+                parsed_signature: None,
             },
         )
         .unwrap();
@@ -2574,6 +3200,14 @@ mod tests {
                 plan_override: None,
                 policy_mode_override: None,
                 policy_runtime: None,
+                preferred_language: None,
+                languages: Vec::new(),
+                semantic: None,
+                kind: None,
+                offset: 0,
+                highlight: false,
+                visibility: VisibilityFilter::Any,
+                intent: None,
             },
         )
         .unwrap();
@@ -2662,6 +3296,11 @@ mod tests {
                     "fn authenticate_user(request: LoginRequest) -> Result<User> { todo!() }"
                         .to_string(),
                 ),
+                attributes: Vec::new(),
+                byte_start: None,
+                byte_end: None,
+                doc_comment: None,
+                parsed_signature: None,
             },
         )
         .unwrap();
@@ -2682,6 +3321,14 @@ mod tests {
                 plan_override: None,
                 policy_mode_override: None,
                 policy_runtime: None,
+                preferred_language: None,
+                languages: Vec::new(),
+                semantic: None,
+                kind: None,
+                offset: 0,
+                highlight: false,
+                visibility: VisibilityFilter::Any,
+                intent: None,
             },
         )
         .unwrap();
@@ -2766,6 +3413,11 @@ mod tests {
                     "fn refresh_access_token(claims: Claims) -> Result<Token> { todo!() }"
                         .to_string(),
                 ),
+                attributes: Vec::new(),
+                byte_start: None,
+                byte_end: None,
+                doc_comment: None,
+                parsed_signature: None,
             },
         )
         .unwrap();
@@ -2786,6 +3438,14 @@ mod tests {
                 plan_override: None,
                 policy_mode_override: None,
                 policy_runtime: None,
+                preferred_language: None,
+                languages: Vec::new(),
+                semantic: None,
+                kind: None,
+                offset: 0,
+                highlight: false,
+                visibility: VisibilityFilter::Any,
+                intent: None,
             },
         )
         .unwrap();
@@ -2807,6 +3467,8 @@ mod tests {
             path: "src/lib.rs".to_string(),
             line_start: 1,
             line_end: 1,
+            byte_start: None,
+            byte_end: None,
             kind: None,
             name: None,
             qualified_name: None,
@@ -2818,6 +3480,7 @@ mod tests {
             chunk_type: None,
             source_layer: None,
             provenance: "lexical".to_string(),
+            highlight: None,
         }];
 
         let dir = tempdir().unwrap();
@@ -2855,6 +3518,11 @@ mod tests {
                 parent_symbol_id: None,
                 visibility: Some("pub".to_string()),
                 content: Some("fn handler() {}".to_string()),
+                attributes: Vec::new(),
+                byte_start: None,
+                byte_end: None,
+                doc_comment: None,
+                parsed_signature: None,
             },
         )
         .unwrap();
@@ -2902,6 +3570,11 @@ mod tests {
                     parent_symbol_id: None,
                     visibility: Some("pub".to_string()),
                     content: Some("fn handler() {}".to_string()),
+                    attributes: Vec::new(),
+                    byte_start: None,
+                    byte_end: None,
+                    doc_comment: None,
+                    parsed_signature: None,
                 },
             )
             .unwrap();
@@ -2923,6 +3596,14 @@ mod tests {
                 plan_override: None,
                 policy_mode_override: None,
                 policy_runtime: None,
+                preferred_language: None,
+                languages: Vec::new(),
+                semantic: None,
+                kind: None,
+                offset: 0,
+                highlight: false,
+                visibility: VisibilityFilter::Any,
+                intent: None,
             },
         )
         .unwrap();
@@ -2963,6 +3644,14 @@ mod tests {
                 plan_override: None,
                 policy_mode_override: None,
                 policy_runtime: None,
+                preferred_language: None,
+                languages: Vec::new(),
+                semantic: None,
+                kind: None,
+                offset: 0,
+                highlight: false,
+                visibility: VisibilityFilter::Any,
+                intent: None,
             },
         )
         .unwrap();
@@ -3007,6 +3696,11 @@ mod tests {
                 parent_symbol_id: None,
                 visibility: Some("pub".to_string()),
                 content: Some("fn authenticate_user() {}".to_string()),
+                attributes: Vec::new(),
+                byte_start: None,
+                byte_end: None,
+                doc_comment: None,
+                parsed_signature: None,
             },
         )
         .unwrap();
@@ -3027,6 +3721,14 @@ mod tests {
                 plan_override: None,
                 policy_mode_override: None,
                 policy_runtime: None,
+                preferred_language: None,
+                languages: Vec::new(),
+                semantic: None,
+                kind: None,
+                offset: 0,
+                highlight: false,
+                visibility: VisibilityFilter::Any,
+                intent: None,
             },
         )
         .unwrap();
@@ -3080,6 +3782,14 @@ mod tests {
                 plan_override: None,
                 policy_mode_override: None,
                 policy_runtime: None,
+                preferred_language: None,
+                languages: Vec::new(),
+                semantic: None,
+                kind: None,
+                offset: 0,
+                highlight: false,
+                visibility: VisibilityFilter::Any,
+                intent: None,
             },
         )
         .unwrap();
@@ -3134,6 +3844,14 @@ mod tests {
                 plan_override: None,
                 policy_mode_override: None,
                 policy_runtime: None,
+                preferred_language: None,
+                languages: Vec::new(),
+                semantic: None,
+                kind: None,
+                offset: 0,
+                highlight: false,
+                visibility: VisibilityFilter::Any,
+                intent: None,
             },
         )
         .unwrap();
@@ -3167,6 +3885,14 @@ mod tests {
                 plan_override: Some("lexical_fast".to_string()),
                 policy_mode_override: None,
                 policy_runtime: None,
+                preferred_language: None,
+                languages: Vec::new(),
+                semantic: None,
+                kind: None,
+                offset: 0,
+                highlight: false,
+                visibility: VisibilityFilter::Any,
+                intent: None,
             },
         )
         .unwrap();
@@ -3204,6 +3930,14 @@ mod tests {
                 plan_override: Some("semantic_deep".to_string()),
                 policy_mode_override: None,
                 policy_runtime: None,
+                preferred_language: None,
+                languages: Vec::new(),
+                semantic: None,
+                kind: None,
+                offset: 0,
+                highlight: false,
+                visibility: VisibilityFilter::Any,
+                intent: None,
             },
         )
         .unwrap();
@@ -3218,4 +3952,736 @@ mod tests {
             Some("semantic_unavailable")
         );
     }
+
+    #[test]
+    fn fold_naming_conventions_expands_identifier_spellings() {
+        assert_eq!(
+            fold_naming_conventions("getUser"),
+            "getUser get_user GetUser"
+        );
+        assert_eq!(
+            fold_naming_conventions("HTTPServer error"),
+            "HTTPServer http_server httpServer HttpServer error"
+        );
+        assert_eq!(
+            fold_naming_conventions("get_user"),
+            "get_user getUser GetUser"
+        );
+        // Qualified names, paths, and single words are left alone.
+        assert_eq!(
+            fold_naming_conventions("auth::get_user src/user.ts token"),
+            "auth::get_user src/user.ts token"
+        );
+    }
+
+    fn write_named_symbol_fixture(
+        index_set: &IndexSet,
+        conn: &Connection,
+        path: &str,
+        language: &str,
+        name: &str,
+        signature: &str,
+    ) {
+        write_symbol_fixture(
+            index_set,
+            conn,
+            path,
+            language,
+            name,
+            SymbolKind::Function,
+            signature,
+        );
+    }
+
+    fn write_symbol_fixture(
+        index_set: &IndexSet,
+        conn: &Connection,
+        path: &str,
+        language: &str,
+        name: &str,
+        kind: SymbolKind,
+        signature: &str,
+    ) {
+        let symbol = SymbolRecord {
+            repo: "proj-fold".to_string(),
+            r#ref: "main".to_string(),
+            commit: None,
+            path: path.to_string(),
+            language: language.to_string(),
+            symbol_id: format!("sym-{name}"),
+            symbol_stable_id: format!("stable-{name}"),
+            name: name.to_string(),
+            qualified_name: name.to_string(),
+            kind,
+            signature: Some(signature.to_string()),
+            line_start: 1,
+            line_end: 3,
+            parent_symbol_id: None,
+            visibility: None,
+            content: Some(signature.to_string()),
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
+        };
+        let file = cruxe_core::types::FileRecord {
+            repo: "proj-fold".to_string(),
+            r#ref: "main".to_string(),
+            commit: None,
+            path: path.to_string(),
+            filename: path.rsplit('/').next().unwrap_or(path).to_string(),
+            language: language.to_string(),
+            content_hash: blake3::hash(path.as_bytes()).to_hex().to_string(),
+            size_bytes: signature.len() as u64,
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+            content_head: Some(signature.to_string()),
+            encoding_warning: false,
+        };
+        cruxe_indexer::writer::write_file_records(index_set, conn, &[symbol], &[], &file).unwrap();
+    }
+
+    #[test]
+    fn result_id_is_stable_across_independent_index_builds() {
+        fn build(symbols: &[(&str, u32)]) -> Vec<(String, String)> {
+            let dir = tempdir().unwrap();
+            let index_set = IndexSet::open(dir.path()).unwrap();
+            let conn = db::open_connection(&dir.path().join("state.db")).unwrap();
+            schema::create_tables(&conn).unwrap();
+            for (name, line_start) in symbols {
+                let path = format!("src/{name}.rs");
+                let signature = format!("fn {name}_handler()");
+                let symbol = SymbolRecord {
+                    repo: "proj-ids".to_string(),
+                    r#ref: "main".to_string(),
+                    commit: None,
+                    path: path.clone(),
+                    language: "rust".to_string(),
+                    symbol_id: format!("sym-{name}-{line_start}"),
+                    symbol_stable_id: format!("stable-{name}"),
+                    name: format!("{name}_handler"),
+                    qualified_name: format!("{name}_handler"),
+                    kind: SymbolKind::Function,
+                    signature: Some(signature.clone()),
+                    line_start: *line_start,
+                    line_end: line_start + 2,
+                    parent_symbol_id: None,
+                    visibility: None,
+                    content: Some(signature.clone()),
+                    attributes: Vec::new(),
+                    byte_start: None,
+                    byte_end: None,
+                    doc_comment: None,
+                    parsed_signature: None,
+                };
+                let file = cruxe_core::types::FileRecord {
+                    repo: "proj-ids".to_string(),
+                    r#ref: "main".to_string(),
+                    commit: None,
+                    path: path.clone(),
+                    filename: format!("{name}.rs"),
+                    language: "rust".to_string(),
+                    content_hash: blake3::hash(path.as_bytes()).to_hex().to_string(),
+                    size_bytes: signature.len() as u64,
+                    updated_at: "2026-01-01T00:00:00Z".to_string(),
+                    content_head: Some(signature),
+                    encoding_warning: false,
+                };
+                cruxe_indexer::writer::write_file_records(&index_set, &conn, &[symbol], &[], &file)
+                    .unwrap();
+            }
+            let response = search_code(
+                &index_set,
+                Some(&conn),
+                "handler",
+                Some("main"),
+                None,
+                10,
+                false,
+            )
+            .unwrap();
+            let mut ids: Vec<(String, String)> = response
+                .results
+                .into_iter()
+                .filter(|r| r.result_type == "symbol")
+                .map(|r| (r.name.unwrap_or_default(), r.result_id))
+                .collect();
+            ids.sort();
+            ids
+        }
+
+        // Different write order and shifted lines: the ids must not change.
+        let first = build(&[("login", 1), ("logout", 10)]);
+        let second = build(&[("logout", 14), ("login", 5)]);
+        assert_eq!(first.len(), 2, "{first:?}");
+        assert_eq!(first, second);
+        assert_ne!(first[0].1, first[1].1);
+    }
+
+    #[test]
+    fn fold_naming_conventions_matches_equivalent_symbols_across_languages() {
+        let dir = tempdir().unwrap();
+        let index_set = IndexSet::open(dir.path()).unwrap();
+        let conn = db::open_connection(&dir.path().join("state.db")).unwrap();
+        schema::create_tables(&conn).unwrap();
+        write_named_symbol_fixture(
+            &index_set,
+            &conn,
+            "web/user.ts",
+            "typescript",
+            "getUser",
+            "function getUser(id: string): User",
+        );
+        write_named_symbol_fixture(
+            &index_set,
+            &conn,
+            "svc/user.py",
+            "python",
+            "get_user",
+            "def get_user(user_id)",
+        );
+
+        let run = |fold: bool| {
+            let search_config = CoreSearchConfig {
+                fold_naming_conventions: fold,
+                ..CoreSearchConfig::default()
+            };
+            search_code_with_options(
+                &index_set,
+                Some(&conn),
+                "getUser",
+                Some("main"),
+                None,
+                10,
+                false,
+                SearchExecutionOptions {
+                    search_config,
+                    ..SearchExecutionOptions::default()
+                },
+            )
+            .unwrap()
+        };
+        let symbol_paths = |response: &SearchResponse| -> Vec<String> {
+            response
+                .results
+                .iter()
+                .filter(|r| r.result_type == "symbol")
+                .map(|r| r.path.clone())
+                .collect()
+        };
+
+        let unfolded = symbol_paths(&run(false));
+        assert_eq!(unfolded, vec!["web/user.ts".to_string()]);
+
+        let folded = symbol_paths(&run(true));
+        assert!(folded.contains(&"web/user.ts".to_string()), "{folded:?}");
+        assert!(folded.contains(&"svc/user.py".to_string()), "{folded:?}");
+    }
+
+    #[test]
+    fn language_list_filters_to_any_listed_language() {
+        let dir = tempdir().unwrap();
+        let index_set = IndexSet::open(dir.path()).unwrap();
+        let conn = db::open_connection(&dir.path().join("state.db")).unwrap();
+        schema::create_tables(&conn).unwrap();
+        for (path, language, signature) in [
+            ("src/handler.rs", "rust", "fn handle_request(req: Request)"),
+            ("svc/handler.go", "go", "func handle_request(req Request)"),
+            (
+                "web/handler.ts",
+                "typescript",
+                "function handle_request(req: Request)",
+            ),
+        ] {
+            write_named_symbol_fixture(
+                &index_set,
+                &conn,
+                path,
+                language,
+                "handle_request",
+                signature,
+            );
+        }
+
+        let run = |language: Option<&str>, languages: &[&str]| -> Vec<String> {
+            let response = search_code_with_options(
+                &index_set,
+                Some(&conn),
+                "handle_request",
+                Some("main"),
+                language,
+                10,
+                false,
+                SearchExecutionOptions {
+                    languages: languages.iter().map(|l| l.to_string()).collect(),
+                    ..SearchExecutionOptions::default()
+                },
+            )
+            .unwrap();
+            let mut languages: Vec<String> =
+                response.results.into_iter().map(|r| r.language).collect();
+            languages.sort();
+            languages.dedup();
+            languages
+        };
+
+        assert_eq!(run(Some("rust"), &["go"]), vec!["go", "rust"]);
+        assert_eq!(run(Some("rust"), &[]), vec!["rust"]);
+        assert_eq!(run(None, &[]), vec!["go", "rust", "typescript"]);
+    }
+
+    #[test]
+    fn kind_filter_keeps_only_symbols_of_that_kind() {
+        let dir = tempdir().unwrap();
+        let index_set = IndexSet::open(dir.path()).unwrap();
+        let conn = db::open_connection(&dir.path().join("state.db")).unwrap();
+        schema::create_tables(&conn).unwrap();
+        write_symbol_fixture(
+            &index_set,
+            &conn,
+            "src/handler.rs",
+            "rust",
+            "Handler",
+            SymbolKind::Struct,
+            "pub struct Handler",
+        );
+        write_symbol_fixture(
+            &index_set,
+            &conn,
+            "src/routes.rs",
+            "rust",
+            "handler",
+            SymbolKind::Function,
+            "fn handler(req: Request)",
+        );
+
+        let run = |kind: Option<&str>| {
+            search_code_with_options(
+                &index_set,
+                Some(&conn),
+                "handler",
+                Some("main"),
+                None,
+                10,
+                false,
+                SearchExecutionOptions {
+                    kind: kind.map(str::to_string),
+                    ..SearchExecutionOptions::default()
+                },
+            )
+            .unwrap()
+            .results
+        };
+
+        let unfiltered = run(None);
+        assert!(
+            unfiltered
+                .iter()
+                .any(|r| r.kind.as_deref() == Some("function")),
+            "{unfiltered:?}"
+        );
+
+        let structs = run(Some("struct"));
+        assert!(!structs.is_empty());
+        assert!(
+            structs
+                .iter()
+                .all(|r| r.result_type == "symbol" && r.kind.as_deref() == Some("struct")),
+            "{structs:?}"
+        );
+    }
+
+    #[test]
+    fn synonym_expansion_applies_only_to_natural_language_queries() {
+        let dir = tempdir().unwrap();
+        let index_set = IndexSet::open(dir.path()).unwrap();
+        let conn = db::open_connection(&dir.path().join("state.db")).unwrap();
+        schema::create_tables(&conn).unwrap();
+        write_named_symbol_fixture(
+            &index_set,
+            &conn,
+            "src/session.rs",
+            "rust",
+            "verify_authentication",
+            "fn verify_authentication(token: &str) -> bool",
+        );
+
+        let run = |query: &str, expand: bool| {
+            let search_config = CoreSearchConfig {
+                expand_synonyms: expand,
+                ..CoreSearchConfig::default()
+            };
+            search_code_with_options(
+                &index_set,
+                Some(&conn),
+                query,
+                Some("main"),
+                None,
+                10,
+                true,
+                SearchExecutionOptions {
+                    search_config,
+                    ..SearchExecutionOptions::default()
+                },
+            )
+            .unwrap()
+        };
+        let expanded_terms =
+            |response: &SearchResponse| response.debug.as_ref().unwrap().expanded_terms.clone();
+
+        let plain = run("where do we check auth", false);
+        assert_eq!(plain.query_intent, QueryIntent::NaturalLanguage);
+        assert!(plain.results.is_empty(), "{:?}", plain.results);
+        assert!(expanded_terms(&plain).is_empty());
+
+        let expanded = run("where do we check auth", true);
+        assert!(
+            expanded.results.iter().any(|r| r.path == "src/session.rs"),
+            "{:?}",
+            expanded.results
+        );
+        assert_eq!(expanded_terms(&expanded), vec!["authentication", "authn"]);
+
+        let symbol = run("auth", true);
+        assert_ne!(symbol.query_intent, QueryIntent::NaturalLanguage);
+        assert!(expanded_terms(&symbol).is_empty());
+    }
+
+    #[test]
+    fn phrase_intent_matches_quoted_words_in_order() {
+        let dir = tempdir().unwrap();
+        let index_set = IndexSet::open(dir.path()).unwrap();
+        let conn = db::open_connection(&dir.path().join("state.db")).unwrap();
+        schema::create_tables(&conn).unwrap();
+        for (path, content) in [
+            ("src/throttle.rs", "// The rate limiter rejects bursts."),
+            ("src/billing.rs", "// Limiter applied to the billing rate."),
+        ] {
+            let snippet = cruxe_core::types::SnippetRecord {
+                repo: "proj-phrase".to_string(),
+                r#ref: "main".to_string(),
+                commit: None,
+                path: path.to_string(),
+                language: "rust".to_string(),
+                chunk_type: "function_body".to_string(),
+                imports: None,
+                line_start: 1,
+                line_end: 3,
+                content: content.to_string(),
+            };
+            let file = cruxe_core::types::FileRecord {
+                repo: "proj-phrase".to_string(),
+                r#ref: "main".to_string(),
+                commit: None,
+                path: path.to_string(),
+                filename: path.rsplit('/').next().unwrap_or(path).to_string(),
+                language: "rust".to_string(),
+                content_hash: blake3::hash(content.as_bytes()).to_hex().to_string(),
+                size_bytes: content.len() as u64,
+                updated_at: "2026-01-01T00:00:00Z".to_string(),
+                content_head: Some(content.to_string()),
+                encoding_warning: false,
+            };
+            cruxe_indexer::writer::write_file_records(&index_set, &conn, &[], &[snippet], &file)
+                .unwrap();
+        }
+
+        let response = search_code(
+            &index_set,
+            Some(&conn),
+            "\"rate limiter\"",
+            Some("main"),
+            None,
+            10,
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(response.query_intent, QueryIntent::Phrase);
+        let paths: Vec<&str> = response.results.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(paths, vec!["src/throttle.rs"]);
+    }
+
+    #[test]
+    fn identifier_tokenizer_matches_across_naming_conventions() {
+        fn paths_for(identifier_tokenizer: bool, query: &str) -> Vec<String> {
+            let dir = tempdir().unwrap();
+            let index_set = IndexSet::open_with_options(
+                dir.path(),
+                cruxe_state::tantivy_index::IndexSchemaOptions {
+                    identifier_tokenizer,
+                },
+            )
+            .unwrap();
+            let conn = db::open_connection(&dir.path().join("state.db")).unwrap();
+            schema::create_tables(&conn).unwrap();
+            for (path, content) in [
+                ("src/users.rs", "let user = get_user_by_id(db, 42)?;"),
+                ("src/api.ts", "const order = await fetchOrderById(id);"),
+            ] {
+                let snippet = cruxe_core::types::SnippetRecord {
+                    repo: "proj-ident".to_string(),
+                    r#ref: "main".to_string(),
+                    commit: None,
+                    path: path.to_string(),
+                    language: "rust".to_string(),
+                    chunk_type: "function_body".to_string(),
+                    imports: None,
+                    line_start: 1,
+                    line_end: 1,
+                    content: content.to_string(),
+                };
+                let file = cruxe_core::types::FileRecord {
+                    repo: "proj-ident".to_string(),
+                    r#ref: "main".to_string(),
+                    commit: None,
+                    path: path.to_string(),
+                    filename: path.rsplit('/').next().unwrap_or(path).to_string(),
+                    language: "rust".to_string(),
+                    content_hash: blake3::hash(content.as_bytes()).to_hex().to_string(),
+                    size_bytes: content.len() as u64,
+                    updated_at: "2026-01-01T00:00:00Z".to_string(),
+                    content_head: Some(content.to_string()),
+                    encoding_warning: false,
+                };
+                cruxe_indexer::writer::write_file_records(
+                    &index_set,
+                    &conn,
+                    &[],
+                    &[snippet],
+                    &file,
+                )
+                .unwrap();
+            }
+            let response = search_code(
+                &index_set,
+                Some(&conn),
+                query,
+                Some("main"),
+                None,
+                10,
+                false,
+            )
+            .unwrap();
+            let mut paths: Vec<String> = response.results.into_iter().map(|r| r.path).collect();
+            paths.dedup();
+            paths
+        }
+
+        assert_eq!(paths_for(true, "getUserById"), vec!["src/users.rs"]);
+        assert_eq!(paths_for(true, "fetch_order_by_id"), vec!["src/api.ts"]);
+        // Whole identifiers still match exactly, and single parts match too.
+        assert_eq!(paths_for(true, "fetchOrderById"), vec!["src/api.ts"]);
+        assert_eq!(paths_for(true, "order"), vec!["src/api.ts"]);
+
+        // The default analyzer keeps camelCase words whole.
+        assert!(paths_for(false, "getUserById").is_empty());
+        assert!(paths_for(false, "fetch_order_by_id").is_empty());
+    }
+
+    #[test]
+    fn export_streams_every_match_past_the_search_limit() {
+        let dir = tempdir().unwrap();
+        let index_set = IndexSet::open(dir.path()).unwrap();
+        let conn = db::open_connection(&dir.path().join("state.db")).unwrap();
+        schema::create_tables(&conn).unwrap();
+        for i in 0..30 {
+            write_named_symbol_fixture(
+                &index_set,
+                &conn,
+                &format!("src/handler_{i}.rs"),
+                if i % 3 == 0 { "python" } else { "rust" },
+                &format!("handle_request_{i}"),
+                &format!("fn handle_request_{i}(request: Request)"),
+            );
+        }
+        let policy = PolicyRuntime::from_search_config(&CoreSearchConfig::default(), None).unwrap();
+        let export = |languages: &[&str], stop_after: Option<usize>| {
+            let mut results = Vec::new();
+            let summary = export_search_results(
+                &index_set,
+                Some(&conn),
+                "request",
+                "main",
+                SearchExportScope {
+                    languages,
+                    kind: None,
+                    role: None,
+                    visibility: VisibilityFilter::Any,
+                },
+                &policy,
+                &mut |result| {
+                    results.push(result);
+                    stop_after.is_none_or(|n| results.len() < n)
+                },
+            )
+            .unwrap();
+            (summary, results)
+        };
+
+        let (summary, results) = export(&[], None);
+        assert!(summary.complete);
+        assert_eq!(summary.symbols, 30);
+        assert_eq!(summary.files, 30);
+        assert_eq!(summary.total(), results.len());
+        assert!(results.iter().all(|result| result.score == 0.0));
+        let symbol_paths: HashSet<&str> = results
+            .iter()
+            .filter(|result| result.result_type == "symbol")
+            .map(|result| result.path.as_str())
+            .collect();
+        assert_eq!(symbol_paths.len(), 30);
+
+        let (python, _) = export(&["python"], None);
+        assert_eq!(python.symbols, 10);
+
+        let (stopped, results) = export(&[], Some(5));
+        assert!(!stopped.complete);
+        assert_eq!(results.len(), 5);
+    }
+
+    #[test]
+    fn highlight_returns_fragment_with_match_ranges_instead_of_snippet() {
+        let dir = tempdir().unwrap();
+        let index_set = IndexSet::open(dir.path()).unwrap();
+        let conn = db::open_connection(&dir.path().join("state.db")).unwrap();
+        schema::create_tables(&conn).unwrap();
+        let content = format!(
+            "fn refill() {{\n{}    bucket.throttle(now);\n}}",
+            "    let filler = 0;\n".repeat(20)
+        );
+        let path = "src/limiter.rs";
+        let snippet = cruxe_core::types::SnippetRecord {
+            repo: "proj-highlight".to_string(),
+            r#ref: "main".to_string(),
+            commit: None,
+            path: path.to_string(),
+            language: "rust".to_string(),
+            chunk_type: "function_body".to_string(),
+            imports: None,
+            line_start: 1,
+            line_end: 22,
+            content: content.clone(),
+        };
+        let file = cruxe_core::types::FileRecord {
+            repo: "proj-highlight".to_string(),
+            r#ref: "main".to_string(),
+            commit: None,
+            path: path.to_string(),
+            filename: "limiter.rs".to_string(),
+            language: "rust".to_string(),
+            content_hash: blake3::hash(content.as_bytes()).to_hex().to_string(),
+            size_bytes: content.len() as u64,
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+            content_head: Some(content.clone()),
+            encoding_warning: false,
+        };
+        cruxe_indexer::writer::write_file_records(&index_set, &conn, &[], &[snippet], &file)
+            .unwrap();
+
+        let run = |highlight: bool| {
+            search_code_with_options(
+                &index_set,
+                Some(&conn),
+                "throttle",
+                Some("main"),
+                None,
+                10,
+                false,
+                SearchExecutionOptions {
+                    highlight,
+                    ..SearchExecutionOptions::default()
+                },
+            )
+            .unwrap()
+            .results
+        };
+
+        let plain = run(false);
+        let plain_hit = plain.iter().find(|r| r.result_type == "snippet").unwrap();
+        assert!(plain_hit.highlight.is_none());
+        assert!(plain_hit.snippet.is_some());
+
+        let highlighted = run(true);
+        let hit = highlighted
+            .iter()
+            .find(|r| r.result_type == "snippet")
+            .unwrap();
+        assert!(hit.snippet.is_none());
+        let highlight = hit.highlight.as_ref().expect("highlight fragment");
+        assert!(highlight.fragment.len() <= HIGHLIGHT_FRAGMENT_MAX_CHARS);
+        assert!(!highlight.match_ranges.is_empty());
+        for range in &highlight.match_ranges {
+            assert_eq!(&highlight.fragment[range.start..range.end], "throttle");
+        }
+    }
+
+    #[test]
+    fn ranking_log_records_scores_without_query_text_by_default() {
+        let dir = tempdir().unwrap();
+        let index_set = IndexSet::open(dir.path()).unwrap();
+        let conn = db::open_connection(&dir.path().join("state.db")).unwrap();
+        schema::create_tables(&conn).unwrap();
+        write_named_symbol_fixture(
+            &index_set,
+            &conn,
+            "src/session.rs",
+            "rust",
+            "verify_authentication",
+            "fn verify_authentication(token: &str) -> bool",
+        );
+        let log_path = dir.path().join("logs/ranking.jsonl");
+
+        let run = |enabled: bool, include_query_text: bool| {
+            let search_config = CoreSearchConfig {
+                ranking_log: cruxe_core::config::RankingLogConfig {
+                    enabled,
+                    path: log_path.to_string_lossy().to_string(),
+                    include_query_text,
+                    top_n: 3,
+                },
+                ..CoreSearchConfig::default()
+            };
+            search_code_with_options(
+                &index_set,
+                Some(&conn),
+                "verify_authentication",
+                Some("main"),
+                None,
+                10,
+                false,
+                SearchExecutionOptions {
+                    search_config,
+                    ..SearchExecutionOptions::default()
+                },
+            )
+            .unwrap()
+        };
+        let read_lines = || -> Vec<serde_json::Value> {
+            std::fs::read_to_string(&log_path)
+                .unwrap_or_default()
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect()
+        };
+
+        run(false, false);
+        assert!(read_lines().is_empty());
+
+        let response = run(true, false);
+        let lines = read_lines();
+        assert_eq!(lines.len(), 1);
+        let record = &lines[0];
+        assert!(record.get("query").is_none(), "{record}");
+        assert_eq!(record["intent"], "symbol");
+        assert_eq!(record["query_features"]["tokens"], 1);
+        assert_eq!(record["result_count"], response.results.len());
+        assert_eq!(record["top_results"][0]["rank"], 1);
+        assert!(record["top_results"].as_array().unwrap().len() <= 3);
+
+        run(true, true);
+        let lines = read_lines();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1]["query"], "verify_authentication");
+    }
 }
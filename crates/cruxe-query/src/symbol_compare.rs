@@ -6,6 +6,8 @@ use serde::{Deserialize, Serialize};
 pub enum SymbolCompareError {
     #[error("symbol not found")]
     SymbolNotFound,
+    #[error("symbol not found for stable id: {symbol_stable_id}")]
+    StableIdNotFound { symbol_stable_id: String },
     #[error(transparent)]
     State(#[from] StateError),
 }
@@ -48,7 +50,164 @@ pub struct SymbolComparisonResult {
     pub diff_summary: SymbolDiffSummary,
 }
 
+/// Body normalization applied before diffing two symbol bodies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BodyNormalization {
+    /// Compare raw body lines.
+    None,
+    /// Collapse whitespace runs and drop blank lines.
+    Whitespace,
+    /// Whitespace normalization plus replacing non-keyword identifiers with `_`.
+    Identifiers,
+}
+
+impl BodyNormalization {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "none" | "raw" => Some(Self::None),
+            "whitespace" => Some(Self::Whitespace),
+            "identifiers" | "identifier" => Some(Self::Identifiers),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Whitespace => "whitespace",
+            Self::Identifiers => "identifiers",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BodyCompareOptions {
+    pub normalization: BodyNormalization,
+    /// Skip diff text and return only the similarity summary.
+    pub similarity_only: bool,
+    /// Byte cap for the rendered diff text.
+    pub max_diff_bytes: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SymbolBodySide {
+    pub symbol_id: String,
+    pub symbol_stable_id: String,
+    pub name: String,
+    pub path: String,
+    pub kind: String,
+    pub language: String,
+    pub line_start: u32,
+    pub line_end: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SymbolBodyComparison {
+    pub r#ref: String,
+    pub left: SymbolBodySide,
+    pub right: SymbolBodySide,
+    pub normalization: BodyNormalization,
+    /// Similarity of the normalized bodies in `[0.0, 1.0]`.
+    pub similarity: f64,
+    /// True when the normalized bodies are line-for-line equal.
+    pub identical: bool,
+    pub lines_added: u32,
+    pub lines_removed: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff_truncated: Option<bool>,
+}
+
 const MAX_LCS_LINES: usize = 2000;
+/// Line-level diff rendering keeps a full LCS table, so it gets a tighter bound
+/// than the count-only LCS above.
+const MAX_DIFF_TABLE_LINES: usize = 1000;
+
+/// Keywords kept verbatim during identifier normalization (union across the
+/// indexed languages) so control flow still contributes to similarity.
+const NORMALIZATION_KEYWORDS: &[&str] = &[
+    "and",
+    "as",
+    "async",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "chan",
+    "class",
+    "const",
+    "continue",
+    "def",
+    "default",
+    "defer",
+    "elif",
+    "else",
+    "enum",
+    "except",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "fn",
+    "for",
+    "func",
+    "function",
+    "go",
+    "if",
+    "impl",
+    "implements",
+    "import",
+    "in",
+    "interface",
+    "is",
+    "lambda",
+    "let",
+    "loop",
+    "map",
+    "match",
+    "mod",
+    "move",
+    "mut",
+    "new",
+    "nil",
+    "None",
+    "not",
+    "null",
+    "or",
+    "package",
+    "pass",
+    "pub",
+    "raise",
+    "range",
+    "ref",
+    "return",
+    "select",
+    "self",
+    "Self",
+    "static",
+    "struct",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "trait",
+    "true",
+    "True",
+    "False",
+    "try",
+    "type",
+    "undefined",
+    "use",
+    "var",
+    "where",
+    "while",
+    "with",
+    "yield",
+];
 
 #[derive(Debug, Clone)]
 struct SymbolSnapshot {
@@ -111,6 +270,255 @@ pub fn compare_symbol_between_refs(
     })
 }
 
+/// Compare the stored bodies of two symbols (by stable id) within one ref.
+pub fn compare_symbol_bodies(
+    conn: &Connection,
+    repo: &str,
+    ref_name: &str,
+    left_stable_id: &str,
+    right_stable_id: &str,
+    options: BodyCompareOptions,
+) -> Result<SymbolBodyComparison, SymbolCompareError> {
+    let left = resolve_snapshot_by_stable_id(conn, repo, ref_name, left_stable_id)?;
+    let right = resolve_snapshot_by_stable_id(conn, repo, ref_name, right_stable_id)?;
+
+    let left_lines = normalize_body(left.content.as_deref().unwrap_or(""), options.normalization);
+    let right_lines = normalize_body(
+        right.content.as_deref().unwrap_or(""),
+        options.normalization,
+    );
+    let identical = left_lines == right_lines;
+
+    let (similarity, lines_added, lines_removed, ops) = if identical {
+        let ops = left_lines
+            .iter()
+            .map(|line| DiffOp::Equal(line.as_str()))
+            .collect::<Vec<_>>();
+        (1.0, 0, 0, Some(ops))
+    } else if left_lines.len() > MAX_DIFF_TABLE_LINES || right_lines.len() > MAX_DIFF_TABLE_LINES {
+        let (added, removed) = line_diff_counts(&left_lines.join("\n"), &right_lines.join("\n"));
+        (
+            token_dice_similarity(&left_lines, &right_lines),
+            added,
+            removed,
+            None,
+        )
+    } else {
+        let ops = diff_lines(&left_lines, &right_lines);
+        let common = ops
+            .iter()
+            .filter(|op| matches!(op, DiffOp::Equal(_)))
+            .count();
+        let added = ops
+            .iter()
+            .filter(|op| matches!(op, DiffOp::Insert(_)))
+            .count() as u32;
+        let removed = ops
+            .iter()
+            .filter(|op| matches!(op, DiffOp::Delete(_)))
+            .count() as u32;
+        let total = left_lines.len() + right_lines.len();
+        let similarity = if total == 0 {
+            1.0
+        } else {
+            (2 * common) as f64 / total as f64
+        };
+        (similarity, added, removed, Some(ops))
+    };
+
+    let (diff, diff_truncated) = if options.similarity_only {
+        (None, None)
+    } else {
+        match ops {
+            Some(ops) => {
+                let (text, truncated) = render_diff(&ops, options.max_diff_bytes);
+                (Some(text), Some(truncated))
+            }
+            // Bodies too large for a line diff: report counts and similarity only.
+            None => (None, Some(true)),
+        }
+    };
+
+    Ok(SymbolBodyComparison {
+        r#ref: ref_name.to_string(),
+        left: to_body_side(&left),
+        right: to_body_side(&right),
+        normalization: options.normalization,
+        similarity: (similarity * 10_000.0).round() / 10_000.0,
+        identical,
+        lines_added,
+        lines_removed,
+        diff,
+        diff_truncated,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DiffOp<'a> {
+    Equal(&'a str),
+    Delete(&'a str),
+    Insert(&'a str),
+}
+
+fn diff_lines<'a>(left: &'a [String], right: &'a [String]) -> Vec<DiffOp<'a>> {
+    let rows = left.len() + 1;
+    let cols = right.len() + 1;
+    // table[i][j] = LCS length of left[i..] and right[j..].
+    let mut table = vec![0u32; rows * cols];
+    for i in (0..left.len()).rev() {
+        for j in (0..right.len()).rev() {
+            table[i * cols + j] = if left[i] == right[j] {
+                table[(i + 1) * cols + j + 1] + 1
+            } else {
+                table[(i + 1) * cols + j].max(table[i * cols + j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::with_capacity(left.len().max(right.len()));
+    let (mut i, mut j) = (0usize, 0usize);
+    while i < left.len() && j < right.len() {
+        if left[i] == right[j] {
+            ops.push(DiffOp::Equal(&left[i]));
+            i += 1;
+            j += 1;
+        } else if table[(i + 1) * cols + j] >= table[i * cols + j + 1] {
+            ops.push(DiffOp::Delete(&left[i]));
+            i += 1;
+        } else {
+            ops.push(DiffOp::Insert(&right[j]));
+            j += 1;
+        }
+    }
+    ops.extend(left[i..].iter().map(|line| DiffOp::Delete(line)));
+    ops.extend(right[j..].iter().map(|line| DiffOp::Insert(line)));
+    ops
+}
+
+fn render_diff(ops: &[DiffOp<'_>], max_bytes: usize) -> (String, bool) {
+    let mut out = String::new();
+    for op in ops {
+        let (prefix, line) = match op {
+            DiffOp::Equal(line) => (' ', *line),
+            DiffOp::Delete(line) => ('-', *line),
+            DiffOp::Insert(line) => ('+', *line),
+        };
+        let needed = prefix.len_utf8() + line.len() + 1;
+        if out.len() + needed > max_bytes {
+            return (out, true);
+        }
+        out.push(prefix);
+        out.push_str(line);
+        out.push('\n');
+    }
+    (out, false)
+}
+
+fn normalize_body(body: &str, normalization: BodyNormalization) -> Vec<String> {
+    match normalization {
+        BodyNormalization::None => body.lines().map(ToString::to_string).collect(),
+        BodyNormalization::Whitespace => body.lines().filter_map(collapse_whitespace).collect(),
+        BodyNormalization::Identifiers => body
+            .lines()
+            .filter_map(collapse_whitespace)
+            .map(|line| normalize_identifiers(&line))
+            .collect(),
+    }
+}
+
+fn collapse_whitespace(line: &str) -> Option<String> {
+    let collapsed = line.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!collapsed.is_empty()).then_some(collapsed)
+}
+
+fn normalize_identifiers(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.char_indices().peekable();
+    while let Some((start, ch)) = chars.next() {
+        if ch.is_alphanumeric() || ch == '_' {
+            let mut end = start + ch.len_utf8();
+            while let Some(&(idx, next)) = chars.peek() {
+                if next.is_alphanumeric() || next == '_' {
+                    end = idx + next.len_utf8();
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let token = &line[start..end];
+            if ch.is_ascii_digit() || NORMALIZATION_KEYWORDS.contains(&token) {
+                out.push_str(token);
+            } else {
+                out.push('_');
+            }
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+/// Token-multiset Dice coefficient used when bodies are too large for a line LCS.
+fn token_dice_similarity(left: &[String], right: &[String]) -> f64 {
+    use std::collections::HashMap;
+
+    let mut counts: HashMap<&str, (usize, usize)> = HashMap::new();
+    for token in left.iter().flat_map(|line| line.split_whitespace()) {
+        counts.entry(token).or_default().0 += 1;
+    }
+    for token in right.iter().flat_map(|line| line.split_whitespace()) {
+        counts.entry(token).or_default().1 += 1;
+    }
+    let (mut shared, mut total) = (0usize, 0usize);
+    for (left_count, right_count) in counts.values() {
+        shared += left_count.min(right_count);
+        total += left_count + right_count;
+    }
+    if total == 0 {
+        1.0
+    } else {
+        (2 * shared) as f64 / total as f64
+    }
+}
+
+fn to_body_side(snapshot: &SymbolSnapshot) -> SymbolBodySide {
+    SymbolBodySide {
+        symbol_id: snapshot.symbol_id.clone(),
+        symbol_stable_id: snapshot.symbol_stable_id.clone(),
+        name: snapshot.name.clone(),
+        path: snapshot.path.clone(),
+        kind: snapshot.kind.clone(),
+        language: snapshot.language.clone(),
+        line_start: snapshot.line_start,
+        line_end: snapshot.line_end,
+        signature: snapshot.signature.clone(),
+    }
+}
+
+fn resolve_snapshot_by_stable_id(
+    conn: &Connection,
+    repo: &str,
+    ref_name: &str,
+    symbol_stable_id: &str,
+) -> Result<SymbolSnapshot, SymbolCompareError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT symbol_id, symbol_stable_id, name, path, signature, kind, language, line_start, line_end, content_hash, content
+             FROM symbol_relations
+             WHERE repo = ?1 AND \"ref\" = ?2 AND symbol_stable_id = ?3
+             ORDER BY path, line_start
+             LIMIT 1",
+        )
+        .map_err(StateError::sqlite)?;
+    match stmt.query_row(params![repo, ref_name, symbol_stable_id], row_to_snapshot) {
+        Ok(snapshot) => Ok(snapshot),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Err(SymbolCompareError::StableIdNotFound {
+            symbol_stable_id: symbol_stable_id.to_string(),
+        }),
+        Err(err) => Err(StateError::sqlite(err).into()),
+    }
+}
+
 fn build_diff_summary(
     base: Option<&SymbolSnapshot>,
     head: Option<&SymbolSnapshot>,
@@ -370,6 +778,11 @@ mod tests {
             parent_symbol_id: None,
             visibility: Some("pub".to_string()),
             content: Some(body.to_string()),
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
         }
     }
 
@@ -576,4 +989,166 @@ mod tests {
             "large bodies should avoid quadratic LCS cost and return coarse fallback deltas"
         );
     }
+
+    fn body_options(normalization: BodyNormalization) -> BodyCompareOptions {
+        BodyCompareOptions {
+            normalization,
+            similarity_only: false,
+            max_diff_bytes: 64 * 1024,
+        }
+    }
+
+    #[test]
+    fn compare_symbol_bodies_renders_line_diff_and_similarity() {
+        let conn = setup();
+        for (name, body) in [
+            ("left_fn", "let a = 1;\nlet b = 2;\nreturn a + b;"),
+            ("right_fn", "let a = 1;\nlet c = 3;\nreturn a + b;"),
+        ] {
+            symbols::insert_symbol(
+                &conn,
+                &make_symbol("main", name, "src/lib.rs", "fn f()", 1, 3, body),
+            )
+            .unwrap();
+        }
+
+        let result = compare_symbol_bodies(
+            &conn,
+            "repo",
+            "main",
+            "stable::left_fn",
+            "stable::right_fn",
+            body_options(BodyNormalization::None),
+        )
+        .unwrap();
+
+        assert!(!result.identical);
+        assert_eq!((result.lines_added, result.lines_removed), (1, 1));
+        assert!((result.similarity - 0.6667).abs() < 1e-4);
+        assert_eq!(
+            result.diff.as_deref(),
+            Some(" let a = 1;\n-let b = 2;\n+let c = 3;\n return a + b;\n")
+        );
+        assert_eq!(result.diff_truncated, Some(false));
+    }
+
+    #[test]
+    fn compare_symbol_bodies_identifier_normalization_ignores_renames() {
+        let conn = setup();
+        symbols::insert_symbol(
+            &conn,
+            &make_symbol(
+                "main",
+                "sum_items",
+                "src/a.rs",
+                "fn sum_items()",
+                1,
+                3,
+                "let total = items.len();\n    return total;",
+            ),
+        )
+        .unwrap();
+        symbols::insert_symbol(
+            &conn,
+            &make_symbol(
+                "main",
+                "count_rows",
+                "src/b.rs",
+                "fn count_rows()",
+                1,
+                3,
+                "let  count = rows.len();\n\nreturn count;",
+            ),
+        )
+        .unwrap();
+
+        let whitespace = compare_symbol_bodies(
+            &conn,
+            "repo",
+            "main",
+            "stable::sum_items",
+            "stable::count_rows",
+            body_options(BodyNormalization::Whitespace),
+        )
+        .unwrap();
+        assert!(!whitespace.identical);
+
+        let identifiers = compare_symbol_bodies(
+            &conn,
+            "repo",
+            "main",
+            "stable::sum_items",
+            "stable::count_rows",
+            BodyCompareOptions {
+                similarity_only: true,
+                ..body_options(BodyNormalization::Identifiers)
+            },
+        )
+        .unwrap();
+        assert!(identifiers.identical);
+        assert_eq!(identifiers.similarity, 1.0);
+        assert!(identifiers.diff.is_none());
+    }
+
+    #[test]
+    fn compare_symbol_bodies_truncates_diff_to_byte_budget() {
+        let conn = setup();
+        let left = (0..50).map(|i| format!("left_{i}();")).collect::<Vec<_>>();
+        let right = (0..50).map(|i| format!("right_{i}();")).collect::<Vec<_>>();
+        symbols::insert_symbol(
+            &conn,
+            &make_symbol("main", "l", "src/lib.rs", "fn l()", 1, 50, &left.join("\n")),
+        )
+        .unwrap();
+        symbols::insert_symbol(
+            &conn,
+            &make_symbol(
+                "main",
+                "r",
+                "src/lib.rs",
+                "fn r()",
+                60,
+                110,
+                &right.join("\n"),
+            ),
+        )
+        .unwrap();
+
+        let result = compare_symbol_bodies(
+            &conn,
+            "repo",
+            "main",
+            "stable::l",
+            "stable::r",
+            BodyCompareOptions {
+                max_diff_bytes: 128,
+                ..body_options(BodyNormalization::None)
+            },
+        )
+        .unwrap();
+
+        assert_eq!(result.diff_truncated, Some(true));
+        assert!(result.diff.as_deref().unwrap().len() <= 128);
+        assert_eq!((result.lines_added, result.lines_removed), (50, 50));
+        assert_eq!(result.similarity, 0.0);
+    }
+
+    #[test]
+    fn compare_symbol_bodies_reports_missing_stable_id() {
+        let conn = setup();
+        let err = compare_symbol_bodies(
+            &conn,
+            "repo",
+            "main",
+            "stable::missing",
+            "stable::other",
+            body_options(BodyNormalization::Whitespace),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            SymbolCompareError::StableIdNotFound { ref symbol_stable_id }
+                if symbol_stable_id == "stable::missing"
+        ));
+    }
 }
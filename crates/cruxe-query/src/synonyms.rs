@@ -0,0 +1,129 @@
+//! Synonym expansion for natural-language queries.
+//!
+//! Code and questions about code often use different vocabulary for the same
+//! idea (`auth` vs `authentication`). Expansion appends the other members of a
+//! synonym group to the query; the lexical query parser ORs terms, so this
+//! trades a little precision for recall.
+
+use std::collections::BTreeMap;
+
+/// Built-in groups of common programming abbreviations and their long forms.
+const BUILTIN_SYNONYM_GROUPS: &[&[&str]] = &[
+    &["auth", "authentication", "authn"],
+    &["authz", "authorization"],
+    &["config", "configuration", "cfg", "settings"],
+    &["db", "database"],
+    &["repo", "repository"],
+    &["err", "error"],
+    &["msg", "message"],
+    &["req", "request"],
+    &["resp", "response"],
+    &["ctx", "context"],
+    &["env", "environment"],
+    &["init", "initialize", "initialization"],
+    &["param", "parameter"],
+    &["arg", "argument"],
+    &["conn", "connection"],
+    &["dir", "directory"],
+    &["util", "utils", "utility"],
+    &["tmp", "temp", "temporary"],
+];
+
+/// A query after synonym expansion.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SynonymExpansion {
+    pub query: String,
+    /// Terms appended to the original query, in the order they were added.
+    pub expanded_terms: Vec<String>,
+}
+
+/// Expand each plain word in `query` with the other members of its synonym
+/// groups, drawn from the built-in set and `user_synonyms`.
+///
+/// Matching is case-insensitive; terms containing punctuation (paths,
+/// qualified names, query syntax) are left alone.
+pub fn expand_query(
+    query: &str,
+    user_synonyms: &BTreeMap<String, Vec<String>>,
+) -> SynonymExpansion {
+    let groups = synonym_groups(user_synonyms);
+    let original: Vec<&str> = query.split_whitespace().collect();
+    let mut seen: Vec<String> = original.iter().map(|term| term.to_lowercase()).collect();
+    let mut expanded_terms = Vec::new();
+
+    for term in &original {
+        if !term.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            continue;
+        }
+        let term = term.to_lowercase();
+        for group in groups.iter().filter(|group| group.contains(&term)) {
+            for synonym in group {
+                if !seen.contains(synonym) {
+                    seen.push(synonym.clone());
+                    expanded_terms.push(synonym.clone());
+                }
+            }
+        }
+    }
+
+    let mut expanded = original.join(" ");
+    for term in &expanded_terms {
+        expanded.push(' ');
+        expanded.push_str(term);
+    }
+    SynonymExpansion {
+        query: expanded,
+        expanded_terms,
+    }
+}
+
+fn synonym_groups(user_synonyms: &BTreeMap<String, Vec<String>>) -> Vec<Vec<String>> {
+    let builtin = BUILTIN_SYNONYM_GROUPS
+        .iter()
+        .map(|group| group.iter().map(|term| term.to_string()).collect());
+    let user = user_synonyms.iter().map(|(key, values)| {
+        std::iter::once(key)
+            .chain(values)
+            .map(|term| term.trim().to_lowercase())
+            .filter(|term| !term.is_empty())
+            .collect()
+    });
+    builtin.chain(user).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_builtin_abbreviations_in_both_directions() {
+        let expansion = expand_query("where is auth handled", &BTreeMap::new());
+        assert_eq!(expansion.expanded_terms, vec!["authentication", "authn"]);
+        assert_eq!(
+            expansion.query,
+            "where is auth handled authentication authn"
+        );
+
+        let expansion = expand_query("load Configuration", &BTreeMap::new());
+        assert_eq!(expansion.expanded_terms, vec!["config", "cfg", "settings"]);
+    }
+
+    #[test]
+    fn user_synonyms_extend_builtins_and_skip_non_word_terms() {
+        let mut user = BTreeMap::new();
+        user.insert(
+            "tenant".to_string(),
+            vec!["Org".to_string(), "workspace".to_string()],
+        );
+
+        let expansion = expand_query("tenant db src/auth.rs", &user);
+        assert_eq!(
+            expansion.expanded_terms,
+            vec!["org", "workspace", "database"]
+        );
+
+        let expansion = expand_query("rate limiter", &user);
+        assert!(expansion.expanded_terms.is_empty());
+        assert_eq!(expansion.query, "rate limiter");
+    }
+}
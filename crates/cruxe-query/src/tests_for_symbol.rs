@@ -0,0 +1,267 @@
+//! Approximate test association: tests that directly reference a symbol.
+//!
+//! Built on relation edges, not execution traces. A test only shows up when it
+//! references the target itself; tests that reach it through helpers, dynamic
+//! dispatch, or macros are missed, and any function named like a test counts.
+
+use crate::find_references::{self, FindReferencesError, ReferenceResult, ReferenceSymbol};
+use cruxe_core::error::StateError;
+use cruxe_state::{project, symbols};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Attribute paths (last segment) that mark a Rust test function.
+const TEST_ATTRIBUTE_NAMES: &[&str] = &["test", "rstest", "test_case"];
+
+/// Which heuristic classified a referencing symbol as a test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TestMatch {
+    Attribute,
+    Name,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestReference {
+    #[serde(flatten)]
+    pub reference: ReferenceResult,
+    pub matched_by: TestMatch,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestsForSymbolResult {
+    pub symbol: ReferenceSymbol,
+    pub tests: Vec<TestReference>,
+    pub total_tests: usize,
+    /// Incoming references inspected, tests or not.
+    pub references_scanned: usize,
+}
+
+/// Find tests with a direct reference edge to `symbol_name`, one entry per test.
+pub fn tests_for_symbol(
+    conn: &Connection,
+    workspace: &Path,
+    project_id: &str,
+    ref_name: &str,
+    symbol_name: &str,
+    limit: usize,
+) -> Result<TestsForSymbolResult, FindReferencesError> {
+    let references = find_references::find_references(
+        conn,
+        workspace,
+        project_id,
+        ref_name,
+        None,
+        symbol_name,
+        0,
+    )?;
+    let default_ref = project::get_by_id(conn, project_id)?
+        .map(|project| project.default_ref)
+        .unwrap_or_else(|| ref_name.to_string());
+
+    let references_scanned = references.references.len();
+    let mut seen = HashSet::new();
+    let mut tests = Vec::new();
+    for reference in references.references {
+        if !seen.insert(reference.from_symbol.symbol_id.clone()) {
+            continue;
+        }
+        let attributes = symbol_attributes(
+            conn,
+            project_id,
+            ref_name,
+            &default_ref,
+            &reference.from_symbol.symbol_id,
+        )?;
+        if let Some(matched_by) = classify_test(&reference.from_symbol.name, &attributes) {
+            tests.push(TestReference {
+                reference,
+                matched_by,
+            });
+        }
+    }
+
+    let total_tests = tests.len();
+    if limit > 0 {
+        tests.truncate(limit);
+    }
+    Ok(TestsForSymbolResult {
+        symbol: references.symbol,
+        tests,
+        total_tests,
+        references_scanned,
+    })
+}
+
+/// Classify a symbol as a test by its attributes first, then by naming
+/// convention (`test_*`, `*_test`, Go-style `TestXxx`).
+pub fn classify_test(name: &str, attributes: &[String]) -> Option<TestMatch> {
+    if attributes
+        .iter()
+        .any(|attribute| is_test_attribute(attribute))
+    {
+        return Some(TestMatch::Attribute);
+    }
+    let go_style = name
+        .strip_prefix("Test")
+        .and_then(|rest| rest.chars().next())
+        .is_some_and(|ch| ch.is_ascii_uppercase());
+    if name.starts_with("test_") || name.ends_with("_test") || go_style {
+        return Some(TestMatch::Name);
+    }
+    None
+}
+
+fn is_test_attribute(attribute: &str) -> bool {
+    let inner = attribute
+        .trim()
+        .trim_start_matches("#[")
+        .trim_end_matches(']');
+    let path = inner.split('(').next().unwrap_or("").trim();
+    let last_segment = path.rsplit("::").next().unwrap_or(path);
+    TEST_ATTRIBUTE_NAMES.contains(&last_segment)
+}
+
+fn symbol_attributes(
+    conn: &Connection,
+    project_id: &str,
+    ref_name: &str,
+    default_ref: &str,
+    symbol_id: &str,
+) -> Result<Vec<String>, StateError> {
+    if let Some(symbol) = symbols::get_symbol_by_id(conn, project_id, ref_name, symbol_id)? {
+        return Ok(symbol.attributes);
+    }
+    if ref_name != default_ref
+        && let Some(symbol) = symbols::get_symbol_by_id(conn, project_id, default_ref, symbol_id)?
+    {
+        return Ok(symbol.attributes);
+    }
+    Ok(Vec::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cruxe_core::types::{SymbolEdge, SymbolKind, SymbolRecord};
+    use cruxe_state::{db, edges, schema};
+
+    fn symbol(id: &str, name: &str, path: &str, attributes: &[&str]) -> SymbolRecord {
+        SymbolRecord {
+            repo: "proj".to_string(),
+            r#ref: "main".to_string(),
+            commit: None,
+            path: path.to_string(),
+            language: "rust".to_string(),
+            symbol_id: id.to_string(),
+            symbol_stable_id: format!("stable-{id}"),
+            name: name.to_string(),
+            qualified_name: format!("crate::{name}"),
+            kind: SymbolKind::Function,
+            signature: Some(format!("fn {name}()")),
+            line_start: 1,
+            line_end: 3,
+            parent_symbol_id: None,
+            visibility: None,
+            content: None,
+            attributes: attributes.iter().map(|a| a.to_string()).collect(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
+        }
+    }
+
+    #[test]
+    fn classify_test_uses_attributes_then_names() {
+        let attrs = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            classify_test("works", &attrs(&["#[tokio::test]"])),
+            Some(TestMatch::Attribute)
+        );
+        assert_eq!(
+            classify_test("case", &attrs(&["#[test_case(1)]"])),
+            Some(TestMatch::Attribute)
+        );
+        assert_eq!(classify_test("test_login", &[]), Some(TestMatch::Name));
+        assert_eq!(classify_test("login_test", &[]), Some(TestMatch::Name));
+        assert_eq!(classify_test("TestLogin", &[]), Some(TestMatch::Name));
+        assert_eq!(classify_test("Testify", &[]), None);
+        assert_eq!(classify_test("helper", &attrs(&["#[cfg(test)]"])), None);
+    }
+
+    #[test]
+    fn tests_for_symbol_keeps_only_test_callers() {
+        let tmp = tempfile::tempdir().unwrap();
+        let conn = db::open_connection(&tmp.path().join("state.db")).unwrap();
+        schema::create_tables(&conn).unwrap();
+        let now = "2026-02-25T00:00:00Z".to_string();
+        project::create_project(
+            &conn,
+            &cruxe_core::types::Project {
+                project_id: "proj".to_string(),
+                repo_root: tmp.path().to_string_lossy().to_string(),
+                display_name: None,
+                default_ref: "main".to_string(),
+                vcs_mode: false,
+                schema_version: 1,
+                parser_version: 1,
+                created_at: now.clone(),
+                updated_at: now,
+            },
+        )
+        .unwrap();
+        for record in [
+            symbol("target", "validate_token", "src/auth.rs", &[]),
+            symbol("caller", "login", "src/lib.rs", &[]),
+            symbol("attr-test", "rejects_expired", "src/auth.rs", &["#[test]"]),
+            symbol("named-test", "test_validate", "tests/auth.rs", &[]),
+        ] {
+            symbols::insert_symbol(&conn, &record).unwrap();
+        }
+        let edge = |from: &str, edge_type: &str| SymbolEdge {
+            repo: "proj".to_string(),
+            ref_name: "main".to_string(),
+            from_symbol_id: from.to_string(),
+            to_symbol_id: "stable-target".to_string(),
+            edge_type: edge_type.to_string(),
+            confidence: "static".to_string(),
+        };
+        edges::insert_edges(
+            &conn,
+            "proj",
+            "main",
+            vec![
+                edge("caller", "calls"),
+                edge("attr-test", "calls"),
+                edge("named-test", "calls"),
+                edge("named-test", "references"),
+            ],
+        )
+        .unwrap();
+
+        let result =
+            tests_for_symbol(&conn, tmp.path(), "proj", "main", "validate_token", 0).unwrap();
+        assert_eq!(result.references_scanned, 4);
+        assert_eq!(result.total_tests, 2);
+        let names: Vec<(&str, TestMatch)> = result
+            .tests
+            .iter()
+            .map(|test| (test.reference.from_symbol.name.as_str(), test.matched_by))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("rejects_expired", TestMatch::Attribute),
+                ("test_validate", TestMatch::Name)
+            ]
+        );
+
+        let limited =
+            tests_for_symbol(&conn, tmp.path(), "proj", "main", "validate_token", 1).unwrap();
+        assert_eq!(limited.tests.len(), 1);
+        assert_eq!(limited.total_tests, 2);
+    }
+}
@@ -0,0 +1,302 @@
+//! Approximate dead-code report: public symbols that nothing in the ref points at.
+//!
+//! Built on relation edges. A symbol counts as referenced when an import or
+//! call edge from another symbol resolves to it, or when an unresolved edge
+//! names it. Anything reached only through dynamic dispatch, reflection,
+//! macros, or code outside the indexed ref is reported as unreferenced.
+
+use crate::tests_for_symbol::classify_test;
+use cruxe_core::error::StateError;
+use cruxe_core::types::{SymbolKind, SymbolRecord, VisibilityFilter};
+use cruxe_state::symbols;
+use rusqlite::{Connection, params};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Caveats returned with every report so callers do not treat it as proof.
+pub const UNREFERENCED_LIMITATIONS: &[&str] = &[
+    "Edges are static: symbols reached only through dynamic dispatch, trait objects, reflection, macros, or string-based lookup are reported as unreferenced.",
+    "Only the indexed ref is scanned; uses from other repositories, generated code, or non-indexed files are not seen.",
+    "Unresolved edges match by bare name, so a symbol whose name is used anywhere is treated as referenced.",
+    "Entry points (`main`) and tests are skipped; other framework entry points (handlers, exported APIs) may still appear.",
+];
+
+/// Filters for [`find_unreferenced_symbols`].
+#[derive(Debug, Clone, Copy)]
+pub struct UnreferencedQuery<'a> {
+    pub language: Option<&'a str>,
+    /// Only this kind; when `None`, fields and modules are skipped because no
+    /// edges are recorded for field access or module paths.
+    pub kind: Option<SymbolKind>,
+    pub path_prefix: Option<&'a str>,
+    pub limit: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnreferencedSymbol {
+    pub symbol_id: String,
+    pub symbol_stable_id: String,
+    pub name: String,
+    pub qualified_name: String,
+    pub kind: String,
+    pub language: String,
+    pub path: String,
+    pub line_start: u32,
+    pub line_end: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnreferencedSymbolsResult {
+    pub symbols: Vec<UnreferencedSymbol>,
+    /// Unreferenced symbols found before `limit` was applied.
+    pub total_unreferenced: usize,
+    /// Public symbols that passed the filters and were checked.
+    pub public_symbols_scanned: usize,
+    pub limitations: Vec<String>,
+}
+
+/// List public symbols in `ref_name` with no inbound import/call edge and no
+/// unresolved edge naming them, ordered by path and line.
+pub fn find_unreferenced_symbols(
+    conn: &Connection,
+    repo: &str,
+    ref_name: &str,
+    query: UnreferencedQuery<'_>,
+) -> Result<UnreferencedSymbolsResult, StateError> {
+    let (referenced_ids, referenced_names) = load_reference_targets(conn, repo, ref_name)?;
+    let candidates = symbols::list_symbols_by_path_prefix(
+        conn,
+        repo,
+        ref_name,
+        query.path_prefix.unwrap_or(""),
+    )?;
+
+    let mut public_symbols_scanned = 0usize;
+    let mut unreferenced = Vec::new();
+    for symbol in candidates {
+        if !is_candidate(&symbol, &query) {
+            continue;
+        }
+        public_symbols_scanned += 1;
+        if referenced_ids.contains(&symbol.symbol_stable_id)
+            || referenced_names.contains(symbol.name.as_str())
+            || referenced_names.contains(symbol.qualified_name.as_str())
+        {
+            continue;
+        }
+        unreferenced.push(UnreferencedSymbol {
+            symbol_id: symbol.symbol_id,
+            symbol_stable_id: symbol.symbol_stable_id,
+            name: symbol.name,
+            qualified_name: symbol.qualified_name,
+            kind: symbol.kind.as_str().to_string(),
+            language: symbol.language,
+            path: symbol.path,
+            line_start: symbol.line_start,
+            line_end: symbol.line_end,
+        });
+    }
+
+    let total_unreferenced = unreferenced.len();
+    if query.limit > 0 {
+        unreferenced.truncate(query.limit);
+    }
+    Ok(UnreferencedSymbolsResult {
+        symbols: unreferenced,
+        total_unreferenced,
+        public_symbols_scanned,
+        limitations: UNREFERENCED_LIMITATIONS
+            .iter()
+            .map(|note| note.to_string())
+            .collect(),
+    })
+}
+
+fn is_candidate(symbol: &SymbolRecord, query: &UnreferencedQuery<'_>) -> bool {
+    if !VisibilityFilter::Public.matches(symbol.visibility.as_deref()) {
+        return false;
+    }
+    if query
+        .language
+        .is_some_and(|language| symbol.language != language)
+    {
+        return false;
+    }
+    let kind_matches = match query.kind {
+        Some(kind) => symbol.kind == kind,
+        None => !matches!(symbol.kind, SymbolKind::Field | SymbolKind::Module),
+    };
+    kind_matches
+        && symbol.name != "main"
+        && classify_test(&symbol.name, &symbol.attributes).is_none()
+}
+
+/// Stable ids that resolved edges point at, and the bare names carried by
+/// unresolved edges (`a::b::name` and `a.name` reduce to `name`). Self edges
+/// (recursion) are ignored.
+fn load_reference_targets(
+    conn: &Connection,
+    repo: &str,
+    ref_name: &str,
+) -> Result<(HashSet<String>, HashSet<String>), StateError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT to_symbol_id, to_name
+             FROM symbol_edges
+             WHERE repo = ?1 AND \"ref\" = ?2
+             AND (to_symbol_id IS NULL OR to_symbol_id != from_symbol_id)",
+        )
+        .map_err(StateError::sqlite)?;
+    let rows = stmt
+        .query_map(params![repo, ref_name], |row| {
+            Ok((
+                row.get::<_, Option<String>>(0)?,
+                row.get::<_, Option<String>>(1)?,
+            ))
+        })
+        .map_err(StateError::sqlite)?;
+
+    let mut ids = HashSet::new();
+    let mut names = HashSet::new();
+    for row in rows {
+        let (to_symbol_id, to_name) = row.map_err(StateError::sqlite)?;
+        if let Some(id) = to_symbol_id {
+            ids.insert(id);
+        }
+        if let Some(name) = to_name.filter(|name| !name.trim().is_empty()) {
+            let bare = name
+                .rsplit([':', '.', '/', '\\'])
+                .next()
+                .unwrap_or(&name)
+                .to_string();
+            names.insert(bare);
+            names.insert(name);
+        }
+    }
+    Ok((ids, names))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cruxe_core::types::CallEdge;
+    use cruxe_state::{db, edges, schema};
+
+    fn symbol(name: &str, kind: SymbolKind, visibility: Option<&str>) -> SymbolRecord {
+        SymbolRecord {
+            repo: "proj".to_string(),
+            r#ref: "main".to_string(),
+            commit: None,
+            path: format!("src/{name}.rs"),
+            language: "rust".to_string(),
+            symbol_id: format!("sym-{name}"),
+            symbol_stable_id: format!("stable-{name}"),
+            name: name.to_string(),
+            qualified_name: format!("crate::{name}"),
+            kind,
+            signature: None,
+            line_start: 1,
+            line_end: 3,
+            byte_start: None,
+            byte_end: None,
+            parent_symbol_id: None,
+            visibility: visibility.map(str::to_string),
+            content: None,
+            attributes: Vec::new(),
+            doc_comment: None,
+            parsed_signature: None,
+        }
+    }
+
+    fn edge(from: &str, to_symbol_id: Option<&str>, to_name: Option<&str>) -> CallEdge {
+        CallEdge {
+            repo: "proj".to_string(),
+            ref_name: "main".to_string(),
+            from_symbol_id: from.to_string(),
+            to_symbol_id: to_symbol_id.map(str::to_string),
+            to_name: to_name.map(str::to_string),
+            edge_type: "calls".to_string(),
+            confidence: "static".to_string(),
+            source_file: "src/caller.rs".to_string(),
+            source_line: 1,
+        }
+    }
+
+    #[test]
+    fn reports_public_symbols_without_inbound_edges() {
+        let dir = tempfile::tempdir().unwrap();
+        let conn = db::open_connection(&dir.path().join("state.db")).unwrap();
+        schema::create_tables(&conn).unwrap();
+
+        let mut test_fn = symbol("it_works", SymbolKind::Function, Some("private"));
+        test_fn.attributes = vec!["test".to_string()];
+        let mut recursive = symbol("recurse", SymbolKind::Function, Some("pub"));
+        recursive.path = "lib/recurse.rs".to_string();
+        for sym in [
+            symbol("called", SymbolKind::Function, Some("pub")),
+            symbol("named", SymbolKind::Function, Some("pub")),
+            symbol("orphan", SymbolKind::Function, Some("pub")),
+            symbol("Orphaned", SymbolKind::Struct, Some("public")),
+            symbol("hidden", SymbolKind::Function, Some("private")),
+            symbol("field", SymbolKind::Field, Some("pub")),
+            symbol("main", SymbolKind::Function, Some("pub")),
+            test_fn,
+            recursive,
+        ] {
+            symbols::insert_symbol(&conn, &sym).unwrap();
+        }
+        edges::insert_call_edges(
+            &conn,
+            "proj",
+            "main",
+            &[
+                edge("stable-main", Some("stable-called"), None),
+                edge("stable-main", None, Some("other::module::named")),
+                edge("stable-recurse", Some("stable-recurse"), None),
+            ],
+        )
+        .unwrap();
+
+        let run = |query| find_unreferenced_symbols(&conn, "proj", "main", query).unwrap();
+        let names = |result: &UnreferencedSymbolsResult| {
+            result
+                .symbols
+                .iter()
+                .map(|symbol| symbol.name.clone())
+                .collect::<Vec<_>>()
+        };
+        let all = UnreferencedQuery {
+            language: None,
+            kind: None,
+            path_prefix: None,
+            limit: 50,
+        };
+
+        let report = run(all);
+        assert_eq!(names(&report), vec!["recurse", "Orphaned", "orphan"]);
+        assert_eq!(report.total_unreferenced, 3);
+        // called, named, orphan, Orphaned and recurse; not main, tests or fields.
+        assert_eq!(report.public_symbols_scanned, 5);
+        assert!(!report.limitations.is_empty());
+
+        let structs = run(UnreferencedQuery {
+            kind: Some(SymbolKind::Struct),
+            ..all
+        });
+        assert_eq!(names(&structs), vec!["Orphaned"]);
+
+        let scoped = run(UnreferencedQuery {
+            path_prefix: Some("src/"),
+            limit: 1,
+            ..all
+        });
+        assert_eq!(names(&scoped), vec!["Orphaned"]);
+        assert_eq!(scoped.total_unreferenced, 2);
+
+        let python = run(UnreferencedQuery {
+            language: Some("python"),
+            ..all
+        });
+        assert!(python.symbols.is_empty());
+    }
+}
@@ -124,6 +124,7 @@ fn index_current_checkout_as_base(
             size_bytes: content.len() as u64,
             updated_at: now_iso8601(),
             content_head: Some(content.lines().take(20).collect::<Vec<_>>().join("\n")),
+            encoding_warning: false,
         };
         writer::write_file_records(&base_index_set, conn, &symbols, &snippets, &file_record)
             .expect("write base file records");
@@ -220,6 +221,7 @@ fn merged_locate(
         None,
         Some("rust"),
         20,
+        locate::LocateOptions::default(),
     )
     .expect("run merged locate");
     results
@@ -77,6 +77,7 @@ fn parse_intent(raw: &str) -> QueryIntent {
         "symbol" => QueryIntent::Symbol,
         "path" => QueryIntent::Path,
         "error" => QueryIntent::Error,
+        "phrase" => QueryIntent::Phrase,
         "natural_language" => QueryIntent::NaturalLanguage,
         other => panic!("unsupported fixture intent: {other}"),
     }
@@ -118,6 +119,14 @@ fn run_eval(
                 plan_override: plan_override.map(ToString::to_string),
                 policy_mode_override: None,
                 policy_runtime: None,
+                preferred_language: None,
+                languages: Vec::new(),
+                semantic: None,
+                kind: None,
+                offset: 0,
+                highlight: false,
+                visibility: cruxe_core::types::VisibilityFilter::Any,
+                intent: None,
             },
         )
         .expect("eval search invocation should succeed");
@@ -274,6 +283,14 @@ fn adaptive_plan_benchmark_asserts_plan_p95_budgets_and_downgrade_rates() {
                     plan_override: override_plan.map(ToString::to_string),
                     policy_mode_override: None,
                     policy_runtime: None,
+                    preferred_language: None,
+                    languages: Vec::new(),
+                    semantic: None,
+                    kind: None,
+                    offset: 0,
+                    highlight: false,
+                    visibility: cruxe_core::types::VisibilityFilter::Any,
+                    intent: None,
                 },
             )
             .expect("search invocation should succeed");
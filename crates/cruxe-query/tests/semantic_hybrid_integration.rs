@@ -99,6 +99,11 @@ fn hybrid_mode_returns_semantic_match_for_conceptual_query_without_keyword_overl
             parent_symbol_id: None,
             visibility: Some("pub".to_string()),
             content: Some("fn ttl_refresh() { grant_access_window(); }".to_string()),
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
         },
     )
     .unwrap();
@@ -121,6 +126,11 @@ fn hybrid_mode_returns_semantic_match_for_conceptual_query_without_keyword_overl
             parent_symbol_id: None,
             visibility: Some("pub".to_string()),
             content: Some("fn purge_entries() { evict_all(); }".to_string()),
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
         },
     )
     .unwrap();
@@ -141,6 +151,14 @@ fn hybrid_mode_returns_semantic_match_for_conceptual_query_without_keyword_overl
             plan_override: None,
             policy_mode_override: None,
             policy_runtime: None,
+            preferred_language: None,
+            languages: Vec::new(),
+            semantic: None,
+            kind: None,
+            offset: 0,
+            highlight: false,
+            visibility: cruxe_core::types::VisibilityFilter::Any,
+            intent: None,
         },
     )
     .unwrap();
@@ -161,3 +179,113 @@ fn hybrid_mode_returns_semantic_match_for_conceptual_query_without_keyword_overl
         "query should not contain direct snippet keywords"
     );
 }
+
+#[test]
+fn semantic_request_runs_vector_branch_for_symbol_queries() {
+    let workspace = tempdir().unwrap();
+    let conn = cruxe_state::db::open_connection(&workspace.path().join("state.db")).unwrap();
+    cruxe_state::schema::create_tables(&conn).unwrap();
+    let index_set = IndexSet::open_at(&workspace.path().join("index")).unwrap();
+
+    let config = hybrid_search_config();
+    let query = "refresh_session_ttl";
+    let mut provider = embedding::build_embedding_provider(&config.semantic)
+        .unwrap()
+        .provider;
+    let vector = provider
+        .embed_batch(&[query.to_string()])
+        .unwrap()
+        .into_iter()
+        .next()
+        .unwrap();
+    vector_index::upsert_vectors(
+        &conn,
+        &[VectorRecord {
+            project_id: "proj".to_string(),
+            ref_name: "main".to_string(),
+            symbol_stable_id: "stable-login-keepalive".to_string(),
+            snippet_hash: "hash-login-keepalive".to_string(),
+            embedding_model_id: "NomicEmbedTextV15Q".to_string(),
+            embedding_model_version: "fastembed-1".to_string(),
+            embedding_dimensions: vector.len(),
+            path: "src/login.rs".to_string(),
+            line_start: 3,
+            line_end: 9,
+            language: "rust".to_string(),
+            chunk_type: Some("function_body".to_string()),
+            snippet_text: "fn keep_login_alive() { extend_expiry(); }".to_string(),
+            vector,
+        }],
+    )
+    .unwrap();
+    cruxe_state::symbols::insert_symbol(
+        &conn,
+        &SymbolRecord {
+            repo: "proj".to_string(),
+            r#ref: "main".to_string(),
+            commit: None,
+            path: "src/login.rs".to_string(),
+            language: "rust".to_string(),
+            symbol_id: "sym-login-keepalive".to_string(),
+            symbol_stable_id: "stable-login-keepalive".to_string(),
+            name: "keep_login_alive".to_string(),
+            qualified_name: "keep_login_alive".to_string(),
+            kind: SymbolKind::Function,
+            signature: Some("fn keep_login_alive()".to_string()),
+            line_start: 3,
+            line_end: 9,
+            parent_symbol_id: None,
+            visibility: None,
+            content: Some("fn keep_login_alive() { extend_expiry(); }".to_string()),
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
+        },
+    )
+    .unwrap();
+
+    let run = |semantic: Option<bool>| {
+        search_code_with_options(
+            &index_set,
+            Some(&conn),
+            query,
+            Some("main"),
+            None,
+            5,
+            false,
+            SearchExecutionOptions {
+                search_config: config.clone(),
+                semantic,
+                ..SearchExecutionOptions::default()
+            },
+        )
+        .unwrap()
+    };
+
+    let default = run(None);
+    assert_eq!(default.query_intent, QueryIntent::Symbol);
+    assert!(!default.metadata.semantic_triggered);
+    assert_eq!(
+        default.metadata.semantic_skipped_reason.as_deref(),
+        Some("intent_not_nl")
+    );
+
+    let requested = run(Some(true));
+    assert!(
+        requested.metadata.semantic_triggered,
+        "{:?}",
+        requested.metadata.semantic_skipped_reason
+    );
+    assert_eq!(requested.metadata.semantic_skipped_reason, None);
+    assert_eq!(requested.results[0].path, "src/login.rs");
+    assert_eq!(requested.results[0].provenance, "semantic");
+
+    let lexical = run(Some(false));
+    assert!(!lexical.metadata.semantic_triggered);
+    assert_eq!(
+        lexical.metadata.semantic_skipped_reason.as_deref(),
+        Some("semantic_not_requested")
+    );
+}
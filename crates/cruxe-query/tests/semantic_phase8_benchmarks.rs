@@ -281,7 +281,8 @@ fn seed_corpus(
     let mut docs = Vec::with_capacity(file_count);
     let mut expected_paths = HashMap::with_capacity(query_cases.len());
     let mut symbol_cases = Vec::with_capacity(symbol_case_count);
-    let batch = BatchWriter::new(index_set).map_err(|err| format!("create batch writer: {err}"))?;
+    let mut batch =
+        BatchWriter::new(index_set).map_err(|err| format!("create batch writer: {err}"))?;
     let now = now_iso8601();
 
     for idx in 0..file_count {
@@ -318,6 +319,11 @@ fn seed_corpus(
             parent_symbol_id: None,
             visibility: Some("pub".to_string()),
             content: Some(snippet_content.to_string()),
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
         };
         let snippet = SnippetRecord {
             repo: PROJECT_ID.to_string(),
@@ -342,6 +348,7 @@ fn seed_corpus(
             size_bytes: snippet_content.len() as u64,
             updated_at: now.clone(),
             content_head: Some(snippet_content.to_string()),
+            encoding_warning: false,
         };
 
         batch
@@ -377,7 +384,7 @@ fn seed_corpus(
     }
 
     batch
-        .commit()
+        .commit(conn)
         .map_err(|err| format!("commit batch: {err}"))?;
     Ok((docs, expected_paths, symbol_cases))
 }
@@ -470,6 +477,14 @@ fn run_natural_language_eval(
                 plan_override: None,
                 policy_mode_override: None,
                 policy_runtime: None,
+                preferred_language: None,
+                languages: Vec::new(),
+                semantic: None,
+                kind: None,
+                offset: 0,
+                highlight: false,
+                visibility: cruxe_core::types::VisibilityFilter::Any,
+                intent: None,
             },
         )
         .map_err(|err| format!("search failed for {}: {err}", case.id))?;
@@ -542,6 +557,14 @@ fn run_symbol_precision_eval(
                 plan_override: None,
                 policy_mode_override: None,
                 policy_runtime: None,
+                preferred_language: None,
+                languages: Vec::new(),
+                semantic: None,
+                kind: None,
+                offset: 0,
+                highlight: false,
+                visibility: cruxe_core::types::VisibilityFilter::Any,
+                intent: None,
             },
         )
         .map_err(|err| format!("symbol search failed for {}: {err}", case.query))?;
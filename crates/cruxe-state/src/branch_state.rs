@@ -132,6 +132,48 @@ pub fn list_branch_states(conn: &Connection, repo: &str) -> Result<Vec<BranchSta
         .map_err(|e| StateError::Sqlite(e.to_string()))
 }
 
+/// Branch states whose last indexed commit is `commit`, or starts with it when
+/// `commit` is an abbreviated hash of at least 7 characters. The default branch
+/// sorts first, then refs by name.
+pub fn find_by_indexed_commit(
+    conn: &Connection,
+    repo: &str,
+    commit: &str,
+) -> Result<Vec<BranchState>, StateError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT repo, \"ref\", merge_base_commit, last_indexed_commit, overlay_dir, file_count, symbol_count, is_default_branch, status, eviction_eligible_at, created_at, last_accessed_at
+             FROM branch_state
+             WHERE repo = ?1
+               AND (last_indexed_commit = ?2
+                    OR (length(?2) >= 7 AND substr(last_indexed_commit, 1, length(?2)) = ?2))
+             ORDER BY is_default_branch DESC, \"ref\"",
+        )
+        .map_err(StateError::sqlite)?;
+
+    let rows = stmt
+        .query_map(params![repo, commit], |row| {
+            Ok(BranchState {
+                repo: row.get(0)?,
+                r#ref: row.get(1)?,
+                merge_base_commit: row.get(2)?,
+                last_indexed_commit: row.get(3)?,
+                overlay_dir: row.get(4)?,
+                file_count: row.get(5)?,
+                symbol_count: row.get(6)?,
+                is_default_branch: row.get::<_, i64>(7)? != 0,
+                status: row.get(8)?,
+                eviction_eligible_at: row.get(9)?,
+                created_at: row.get(10)?,
+                last_accessed_at: row.get(11)?,
+            })
+        })
+        .map_err(StateError::sqlite)?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(StateError::sqlite)
+}
+
 pub fn set_status(
     conn: &Connection,
     repo: &str,
@@ -387,4 +429,29 @@ mod tests {
             Some("2026-03-01T00:00:00Z".to_string())
         );
     }
+
+    #[test]
+    fn test_find_by_indexed_commit_matches_full_and_abbreviated_hashes() {
+        let conn = setup_test_db();
+        let mut feature = sample_entry();
+        feature.r#ref = "feat/auth".to_string();
+        feature.is_default_branch = false;
+        feature.last_indexed_commit = "0123456789abcdef".to_string();
+        upsert_branch_state(&conn, &feature).unwrap();
+        let mut main = sample_entry();
+        main.last_indexed_commit = "0123456789abcdef".to_string();
+        upsert_branch_state(&conn, &main).unwrap();
+
+        let refs = |commit: &str| -> Vec<String> {
+            find_by_indexed_commit(&conn, "my-repo", commit)
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.r#ref)
+                .collect()
+        };
+        assert_eq!(refs("0123456789abcdef"), vec!["main", "feat/auth"]);
+        assert_eq!(refs("0123456"), vec!["main", "feat/auth"]);
+        assert!(refs("012345").is_empty(), "short prefixes are ambiguous");
+        assert!(refs("fedcba9876543210").is_empty());
+    }
 }
@@ -0,0 +1,406 @@
+use cruxe_core::constants;
+use cruxe_core::error::StateError;
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tantivy::schema::Field;
+use tantivy::{Index, IndexWriter, Term};
+use tracing::info;
+
+use crate::disk_space::directory_size;
+use crate::maintenance_lock;
+use crate::tantivy_index::{self, IndexSet};
+
+/// Outcome of [`compact_project`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CompactionReport {
+    /// `symbol_relations` rows removed because their file left the manifest.
+    pub pruned_symbol_rows: u64,
+    /// Call edges removed because their source file left the manifest.
+    pub pruned_edge_rows: u64,
+    /// Tantivy documents (symbols, snippets, files) removed for the same reason.
+    pub pruned_documents: u64,
+    /// Index roots (`base` plus one per overlay) that were compacted.
+    pub index_roots: usize,
+    pub segments_before: usize,
+    pub segments_after: usize,
+    pub sqlite_bytes_before: u64,
+    pub sqlite_bytes_after: u64,
+    pub index_bytes_before: u64,
+    pub index_bytes_after: u64,
+    /// Total bytes freed across SQLite and Tantivy (never negative).
+    pub bytes_reclaimed: u64,
+}
+
+/// Reclaim space left behind by incremental re-indexing.
+///
+/// Rows and documents whose `(repo, ref, path)` no longer has a
+/// `file_manifest` entry are deleted, every Tantivy index under `data_dir` is
+/// merged down to a single segment, and SQLite is vacuumed. Holds the project
+/// maintenance lock for the duration, so it fails fast with
+/// [`StateError::MaintenanceLockBusy`] while a sync is running.
+pub fn compact_project(conn: &Connection, data_dir: &Path) -> Result<CompactionReport, StateError> {
+    let _lock = maintenance_lock::acquire_project_lock(data_dir, "compact_index")?;
+    let db_path = data_dir.join(constants::STATE_DB_FILE);
+    let roots = index_roots(data_dir)?;
+
+    let mut report = CompactionReport {
+        sqlite_bytes_before: sqlite_size(&db_path),
+        index_bytes_before: roots.iter().map(|root| directory_size(root)).sum(),
+        index_roots: roots.len(),
+        ..CompactionReport::default()
+    };
+
+    let live_keys = live_file_keys(conn)?;
+    for root in &roots {
+        let index_set = IndexSet::open_existing_at(root)?;
+        for index in [&index_set.symbols, &index_set.snippets, &index_set.files] {
+            let outcome = compact_tantivy_index(index, &live_keys)?;
+            report.pruned_documents += outcome.deleted_documents;
+            report.segments_before += outcome.segments_before;
+            report.segments_after += outcome.segments_after;
+        }
+    }
+
+    let pruned = prune_orphaned_rows(conn)?;
+    report.pruned_symbol_rows = pruned.symbol_rows;
+    report.pruned_edge_rows = pruned.edge_rows;
+    vacuum(conn)?;
+
+    report.sqlite_bytes_after = sqlite_size(&db_path);
+    report.index_bytes_after = roots.iter().map(|root| directory_size(root)).sum();
+    report.bytes_reclaimed = (report.sqlite_bytes_before + report.index_bytes_before)
+        .saturating_sub(report.sqlite_bytes_after + report.index_bytes_after);
+    info!(
+        bytes_reclaimed = report.bytes_reclaimed,
+        pruned_documents = report.pruned_documents,
+        pruned_symbol_rows = report.pruned_symbol_rows,
+        "Index compaction complete"
+    );
+    Ok(report)
+}
+
+/// SQLite rows removed by [`prune_orphaned_rows`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PrunedRows {
+    pub symbol_rows: u64,
+    pub edge_rows: u64,
+}
+
+/// Delete symbols and call edges whose file has no manifest entry for their
+/// repo/ref.
+pub fn prune_orphaned_rows(conn: &Connection) -> Result<PrunedRows, StateError> {
+    let symbol_rows = conn
+        .execute(
+            "DELETE FROM symbol_relations
+             WHERE NOT EXISTS (
+                 SELECT 1 FROM file_manifest m
+                 WHERE m.repo = symbol_relations.repo
+                   AND m.\"ref\" = symbol_relations.\"ref\"
+                   AND m.path = symbol_relations.path
+             )",
+            [],
+        )
+        .map_err(StateError::sqlite)?;
+    let edge_rows = conn
+        .execute(
+            "DELETE FROM symbol_edges
+             WHERE source_file IS NOT NULL
+               AND NOT EXISTS (
+                 SELECT 1 FROM file_manifest m
+                 WHERE m.repo = symbol_edges.repo
+                   AND m.\"ref\" = symbol_edges.\"ref\"
+                   AND m.path = symbol_edges.source_file
+             )",
+            [],
+        )
+        .map_err(StateError::sqlite)?;
+    Ok(PrunedRows {
+        symbol_rows: symbol_rows as u64,
+        edge_rows: edge_rows as u64,
+    })
+}
+
+/// Rebuild the database file and truncate the WAL so freed pages are returned
+/// to the filesystem.
+pub fn vacuum(conn: &Connection) -> Result<(), StateError> {
+    conn.execute_batch("VACUUM").map_err(StateError::sqlite)?;
+    conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))
+        .map_err(StateError::sqlite)?;
+    Ok(())
+}
+
+/// Per-index outcome of [`compact_tantivy_index`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TantivyCompaction {
+    pub deleted_documents: u64,
+    pub segments_before: usize,
+    pub segments_after: usize,
+}
+
+/// Delete documents whose `file_key` is not in `live_keys`, then merge all
+/// segments into one so the deletes are physically dropped.
+pub fn compact_tantivy_index(
+    index: &Index,
+    live_keys: &HashSet<String>,
+) -> Result<TantivyCompaction, StateError> {
+    let file_key = index
+        .schema()
+        .get_field("file_key")
+        .map_err(StateError::tantivy)?;
+    let segments_before = index
+        .searchable_segment_ids()
+        .map_err(StateError::tantivy)?
+        .len();
+    let docs_before = live_doc_count(index)?;
+    let orphaned = orphaned_file_keys(index, file_key, live_keys)?;
+
+    let mut writer: IndexWriter = index.writer(50_000_000).map_err(StateError::tantivy)?;
+    for key in &orphaned {
+        writer.delete_term(Term::from_field_text(file_key, key));
+    }
+    writer.commit().map_err(StateError::tantivy)?;
+
+    let metas = index
+        .searchable_segment_metas()
+        .map_err(StateError::tantivy)?;
+    if metas.len() > 1 || metas.iter().any(|meta| meta.has_deletes()) {
+        let ids: Vec<_> = metas.iter().map(|meta| meta.id()).collect();
+        writer.merge(&ids).wait().map_err(StateError::tantivy)?;
+    }
+    writer
+        .garbage_collect_files()
+        .wait()
+        .map_err(StateError::tantivy)?;
+    writer.wait_merging_threads().map_err(StateError::tantivy)?;
+
+    let segments_after = index
+        .searchable_segment_ids()
+        .map_err(StateError::tantivy)?
+        .len();
+    let docs_after = live_doc_count(index)?;
+    Ok(TantivyCompaction {
+        deleted_documents: docs_before.saturating_sub(docs_after),
+        segments_before,
+        segments_after,
+    })
+}
+
+/// `file_key` values of every file currently in the manifest.
+fn live_file_keys(conn: &Connection) -> Result<HashSet<String>, StateError> {
+    let mut stmt = conn
+        .prepare("SELECT repo, \"ref\", path FROM file_manifest")
+        .map_err(StateError::sqlite)?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(tantivy_index::file_key(
+                &row.get::<_, String>(0)?,
+                &row.get::<_, String>(1)?,
+                &row.get::<_, String>(2)?,
+            ))
+        })
+        .map_err(StateError::sqlite)?;
+    rows.collect::<Result<_, _>>().map_err(StateError::sqlite)
+}
+
+fn orphaned_file_keys(
+    index: &Index,
+    file_key: Field,
+    live_keys: &HashSet<String>,
+) -> Result<HashSet<String>, StateError> {
+    let reader = index.reader().map_err(StateError::tantivy)?;
+    let searcher = reader.searcher();
+    let mut orphaned = HashSet::new();
+    for segment in searcher.segment_readers() {
+        let inverted = segment
+            .inverted_index(file_key)
+            .map_err(StateError::tantivy)?;
+        let mut terms = inverted.terms().stream().map_err(StateError::tantivy)?;
+        while terms.advance() {
+            let key = String::from_utf8_lossy(terms.key());
+            if !live_keys.contains(key.as_ref()) {
+                orphaned.insert(key.into_owned());
+            }
+        }
+    }
+    Ok(orphaned)
+}
+
+pub(crate) fn live_doc_count(index: &Index) -> Result<u64, StateError> {
+    let reader = index.reader().map_err(StateError::tantivy)?;
+    Ok(reader.searcher().num_docs())
+}
+
+/// `<data_dir>/base` plus every overlay index directory that exists.
+fn index_roots(data_dir: &Path) -> Result<Vec<PathBuf>, StateError> {
+    let mut roots = Vec::new();
+    let base = data_dir.join("base");
+    if base.exists() {
+        roots.push(base);
+    }
+    let overlay_root = data_dir.join("overlay");
+    if overlay_root.exists() {
+        let mut overlays = Vec::new();
+        for entry in std::fs::read_dir(&overlay_root)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                overlays.push(entry.path());
+            }
+        }
+        overlays.sort();
+        roots.extend(overlays);
+    }
+    Ok(roots)
+}
+
+/// Size of the database file plus its write-ahead log.
+fn sqlite_size(db_path: &Path) -> u64 {
+    let wal_path = db_path.with_extension(
+        db_path
+            .extension()
+            .map(|ext| format!("{}-wal", ext.to_string_lossy()))
+            .unwrap_or_else(|| "wal".to_string()),
+    );
+    [db_path, wal_path.as_path()]
+        .iter()
+        .filter_map(|path| std::fs::metadata(path).ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{db, manifest, schema, symbols};
+    use cruxe_core::types::{CallEdge, SymbolKind, SymbolRecord};
+    use tantivy::doc;
+
+    fn symbol(path: &str, id: &str) -> SymbolRecord {
+        SymbolRecord {
+            repo: "proj".to_string(),
+            r#ref: "main".to_string(),
+            commit: None,
+            path: path.to_string(),
+            symbol_id: id.to_string(),
+            symbol_stable_id: format!("stable_{id}"),
+            name: id.to_string(),
+            qualified_name: id.to_string(),
+            kind: SymbolKind::Function,
+            language: "rust".to_string(),
+            line_start: 1,
+            line_end: 2,
+            signature: None,
+            parent_symbol_id: None,
+            visibility: None,
+            content: Some("fn f() {}".repeat(200)),
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
+        }
+    }
+
+    fn add_documents(index: &Index, paths: &[&str]) {
+        let schema = index.schema();
+        let file_key = schema.get_field("file_key").unwrap();
+        let path_field = schema.get_field("path").unwrap();
+        let mut writer: IndexWriter = index.writer(50_000_000).unwrap();
+        for path in paths {
+            let key = tantivy_index::file_key("proj", "main", path);
+            writer
+                .add_document(doc!(file_key => key.as_str(), path_field => *path))
+                .unwrap();
+        }
+        writer.commit().unwrap();
+    }
+
+    #[test]
+    fn compaction_prunes_files_missing_from_the_manifest() {
+        let tmp = tempfile::tempdir().unwrap();
+        let data_dir = tmp.path().join("project");
+        std::fs::create_dir_all(&data_dir).unwrap();
+        let conn = db::open_connection(&data_dir.join(constants::STATE_DB_FILE)).unwrap();
+        schema::create_tables(&conn).unwrap();
+
+        let index_set = IndexSet::open(&data_dir).unwrap();
+        // Two commits leave two segments behind.
+        add_documents(&index_set.snippets, &["src/kept.rs", "src/gone.rs"]);
+        add_documents(&index_set.snippets, &["src/kept.rs"]);
+        add_documents(&index_set.files, &["src/kept.rs", "src/gone.rs"]);
+
+        symbols::insert_symbol(&conn, &symbol("src/kept.rs", "kept")).unwrap();
+        symbols::insert_symbol(&conn, &symbol("src/gone.rs", "gone")).unwrap();
+        crate::edges::insert_call_edges(
+            &conn,
+            "proj",
+            "main",
+            &[CallEdge {
+                repo: "proj".to_string(),
+                ref_name: "main".to_string(),
+                from_symbol_id: "stable_gone".to_string(),
+                to_symbol_id: None,
+                to_name: Some("helper".to_string()),
+                edge_type: "calls".to_string(),
+                confidence: "low".to_string(),
+                source_file: "src/gone.rs".to_string(),
+                source_line: 1,
+            }],
+        )
+        .unwrap();
+        manifest::upsert_manifest(
+            &conn,
+            &manifest::ManifestEntry {
+                repo: "proj".to_string(),
+                r#ref: "main".to_string(),
+                path: "src/kept.rs".to_string(),
+                content_hash: "hash".to_string(),
+                size_bytes: 10,
+                mtime_ns: None,
+                language: Some("rust".to_string()),
+                indexed_at: cruxe_core::time::now_iso8601(),
+            },
+        )
+        .unwrap();
+
+        let report = compact_project(&conn, &data_dir).unwrap();
+
+        assert_eq!(report.pruned_symbol_rows, 1);
+        assert_eq!(report.pruned_edge_rows, 1);
+        assert_eq!(report.pruned_documents, 2, "one snippet and one file doc");
+        assert_eq!(report.index_roots, 1);
+        assert!(report.segments_before > report.segments_after);
+        assert_eq!(
+            report.bytes_reclaimed,
+            (report.sqlite_bytes_before + report.index_bytes_before)
+                .saturating_sub(report.sqlite_bytes_after + report.index_bytes_after)
+        );
+
+        let remaining =
+            symbols::list_symbols_in_file(&conn, "proj", "main", "src/gone.rs").unwrap();
+        assert!(remaining.is_empty());
+        assert_eq!(
+            symbols::list_symbols_in_file(&conn, "proj", "main", "src/kept.rs")
+                .unwrap()
+                .len(),
+            1
+        );
+        let snippets = IndexSet::open(&data_dir).unwrap().snippets;
+        assert_eq!(snippets.searchable_segment_ids().unwrap().len(), 1);
+        assert_eq!(live_doc_count(&snippets).unwrap(), 2);
+    }
+
+    #[test]
+    fn compaction_is_refused_while_the_maintenance_lock_is_held() {
+        let tmp = tempfile::tempdir().unwrap();
+        let data_dir = tmp.path().join("project");
+        std::fs::create_dir_all(&data_dir).unwrap();
+        let conn = db::open_connection(&data_dir.join(constants::STATE_DB_FILE)).unwrap();
+        schema::create_tables(&conn).unwrap();
+
+        let _held = maintenance_lock::acquire_project_lock(&data_dir, "sync").unwrap();
+        let err = compact_project(&conn, &data_dir).unwrap_err();
+        assert!(matches!(err, StateError::MaintenanceLockBusy { .. }));
+    }
+}
@@ -28,16 +28,21 @@ pub fn open_connection_with_config(
 }
 
 /// Apply required SQLite pragmas per data-model spec.
+///
+/// WAL lets readers (MCP queries) proceed while an indexer subprocess holds
+/// the write lock. `busy_timeout` goes first so that switching the journal
+/// mode, which needs a brief exclusive lock, waits instead of failing with
+/// "database is locked" when another process is mid-write.
 fn apply_pragmas(
     conn: &Connection,
     busy_timeout_ms: u32,
     cache_size: i32,
 ) -> Result<(), StateError> {
     conn.execute_batch(&format!(
-        "PRAGMA journal_mode = WAL;
+        "PRAGMA busy_timeout = {};
+         PRAGMA journal_mode = WAL;
          PRAGMA synchronous = NORMAL;
          PRAGMA foreign_keys = ON;
-         PRAGMA busy_timeout = {};
          PRAGMA cache_size = {};",
         busy_timeout_ms, cache_size
     ))
@@ -99,4 +104,32 @@ mod tests {
             .unwrap();
         assert_eq!(cache, -32000);
     }
+
+    #[test]
+    fn test_reader_is_not_blocked_by_open_write_transaction() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("concurrent.db");
+        let writer = open_connection(&db_path).unwrap();
+        writer
+            .execute_batch(
+                "CREATE TABLE items (id INTEGER PRIMARY KEY);
+                 INSERT INTO items (id) VALUES (1);",
+            )
+            .unwrap();
+        // A zero busy timeout makes any lock wait fail immediately.
+        let reader = open_connection_with_config(&db_path, 0, -2000).unwrap();
+
+        writer
+            .execute_batch("BEGIN IMMEDIATE; INSERT INTO items (id) VALUES (2);")
+            .unwrap();
+        let count_items = || -> i64 {
+            reader
+                .query_row("SELECT COUNT(*) FROM items", [], |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!(count_items(), 1, "reader sees the last committed snapshot");
+
+        writer.execute_batch("COMMIT;").unwrap();
+        assert_eq!(count_items(), 2);
+    }
 }
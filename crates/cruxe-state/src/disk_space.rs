@@ -0,0 +1,116 @@
+use cruxe_core::constants::INDEX_DISK_GROWTH_FACTOR;
+use cruxe_core::error::StateError;
+use std::path::Path;
+use tracing::warn;
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// Total size in bytes of all files under `dir` (`0` when it does not exist yet).
+pub fn directory_size(dir: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => directory_size(&entry.path()),
+            Ok(kind) if kind.is_file() => entry.metadata().map_or(0, |m| m.len()),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Free space an index run against `data_dir` should have before it starts:
+/// the larger of `min_free_disk_mb` and the current index size times
+/// [`INDEX_DISK_GROWTH_FACTOR`].
+pub fn required_free_bytes(data_dir: &Path, min_free_disk_mb: u64) -> u64 {
+    let floor = min_free_disk_mb.saturating_mul(BYTES_PER_MB);
+    let estimate = directory_size(data_dir).saturating_mul(INDEX_DISK_GROWTH_FACTOR);
+    floor.max(estimate)
+}
+
+/// Fail with [`StateError::InsufficientDisk`] when the volume holding
+/// `data_dir` cannot fit an index run, so indexing is refused up front
+/// instead of running out of space mid-write.
+///
+/// `min_free_disk_mb == 0` disables the check. If free space cannot be read
+/// the check is skipped with a warning rather than blocking indexing.
+pub fn ensure_free_disk(data_dir: &Path, min_free_disk_mb: u64) -> Result<(), StateError> {
+    if min_free_disk_mb == 0 {
+        return Ok(());
+    }
+    let Some(probe) = data_dir.ancestors().find(|dir| dir.exists()) else {
+        return Ok(());
+    };
+    let available_bytes = match fs4::available_space(probe) {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            warn!(path = %probe.display(), "Skipping free-disk check: {}", err);
+            return Ok(());
+        }
+    };
+    check_free_bytes(
+        data_dir,
+        available_bytes,
+        required_free_bytes(data_dir, min_free_disk_mb),
+    )
+}
+
+fn check_free_bytes(
+    data_dir: &Path,
+    available_bytes: u64,
+    required_bytes: u64,
+) -> Result<(), StateError> {
+    if available_bytes < required_bytes {
+        return Err(StateError::insufficient_disk(
+            data_dir.to_string_lossy(),
+            available_bytes,
+            required_bytes,
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn required_free_bytes_uses_larger_of_floor_and_growth_estimate() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("base/symbols");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(nested.join("seg"), vec![0u8; 3 * BYTES_PER_MB as usize]).unwrap();
+
+        assert_eq!(directory_size(dir.path()), 3 * BYTES_PER_MB);
+        assert_eq!(required_free_bytes(dir.path(), 1), 6 * BYTES_PER_MB);
+        assert_eq!(required_free_bytes(dir.path(), 10), 10 * BYTES_PER_MB);
+        assert_eq!(
+            required_free_bytes(&dir.path().join("missing"), 1),
+            BYTES_PER_MB
+        );
+    }
+
+    #[test]
+    fn check_free_bytes_reports_insufficient_disk() {
+        let err = check_free_bytes(Path::new("/data"), 10, 20).unwrap_err();
+        assert!(matches!(
+            err,
+            StateError::InsufficientDisk {
+                available_bytes: 10,
+                required_bytes: 20,
+                ..
+            }
+        ));
+        assert!(check_free_bytes(Path::new("/data"), 20, 20).is_ok());
+    }
+
+    #[test]
+    fn ensure_free_disk_is_disabled_by_zero_and_rejects_impossible_floor() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(ensure_free_disk(dir.path(), 0).is_ok());
+        assert!(ensure_free_disk(&dir.path().join("not/yet/created"), 1).is_ok());
+        let err = ensure_free_disk(dir.path(), u64::MAX / BYTES_PER_MB).unwrap_err();
+        assert!(matches!(err, StateError::InsufficientDisk { .. }));
+    }
+}
@@ -548,6 +548,119 @@ pub fn get_callees(
         .map_err(StateError::sqlite)
 }
 
+/// A file-level import edge joined with the symbol it resolved to, if any.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileImportEdge {
+    /// Path of the importing file.
+    pub source_path: String,
+    /// Path of the file defining the imported symbol; `None` when unresolved.
+    pub target_path: Option<String>,
+    /// Qualified name of the resolved target, or the raw import name otherwise.
+    pub target_name: String,
+    pub resolution_outcome: String,
+    pub confidence: String,
+}
+
+/// Import edges whose importing file is `path`.
+pub fn get_import_edges_from_file(
+    conn: &Connection,
+    repo: &str,
+    ref_name: &str,
+    path: &str,
+) -> Result<Vec<FileImportEdge>, StateError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT DISTINCT e.from_symbol_id, r.path, COALESCE(r.qualified_name, e.to_name, e.to_symbol_id, ''),
+                    e.resolution_outcome, COALESCE(e.confidence, 'low')
+             FROM symbol_edges e
+             LEFT JOIN symbol_relations r
+               ON r.repo = e.repo AND r.\"ref\" = e.\"ref\" AND r.symbol_stable_id = e.to_symbol_id
+             WHERE e.repo = ?1 AND e.\"ref\" = ?2 AND e.edge_type = 'imports' AND e.from_symbol_id = ?3
+             ORDER BY 3, 2",
+        )
+        .map_err(StateError::sqlite)?;
+    let rows = stmt
+        .query_map(
+            params![repo, ref_name, format!("file::{path}")],
+            map_file_import_edge_row,
+        )
+        .map_err(StateError::sqlite)?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(StateError::sqlite)
+}
+
+/// Import edges that resolved to a symbol defined in `path`, optionally
+/// restricted to a single target symbol.
+pub fn get_import_edges_into_file(
+    conn: &Connection,
+    repo: &str,
+    ref_name: &str,
+    path: &str,
+    symbol_stable_id: Option<&str>,
+) -> Result<Vec<FileImportEdge>, StateError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT DISTINCT e.from_symbol_id, r.path, r.qualified_name,
+                    e.resolution_outcome, COALESCE(e.confidence, 'low')
+             FROM symbol_edges e
+             JOIN symbol_relations r
+               ON r.repo = e.repo AND r.\"ref\" = e.\"ref\" AND r.symbol_stable_id = e.to_symbol_id
+             WHERE e.repo = ?1 AND e.\"ref\" = ?2 AND e.edge_type = 'imports' AND r.path = ?3
+               AND (?4 IS NULL OR r.symbol_stable_id = ?4)
+             ORDER BY 1, 3",
+        )
+        .map_err(StateError::sqlite)?;
+    let rows = stmt
+        .query_map(
+            params![repo, ref_name, path, symbol_stable_id],
+            map_file_import_edge_row,
+        )
+        .map_err(StateError::sqlite)?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(StateError::sqlite)
+}
+
+/// Import edges whose target is `target`: the qualified name of a resolved
+/// symbol, or the raw import name of an unresolved or external import.
+pub fn get_importers_of(
+    conn: &Connection,
+    repo: &str,
+    ref_name: &str,
+    target: &str,
+) -> Result<Vec<FileImportEdge>, StateError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT DISTINCT e.from_symbol_id, r.path, COALESCE(r.qualified_name, e.to_name, e.to_symbol_id, ''),
+                    e.resolution_outcome, COALESCE(e.confidence, 'low')
+             FROM symbol_edges e
+             LEFT JOIN symbol_relations r
+               ON r.repo = e.repo AND r.\"ref\" = e.\"ref\" AND r.symbol_stable_id = e.to_symbol_id
+             WHERE e.repo = ?1 AND e.\"ref\" = ?2 AND e.edge_type = 'imports'
+               AND (r.qualified_name = ?3 OR e.to_name = ?3)
+             ORDER BY 1, 3",
+        )
+        .map_err(StateError::sqlite)?;
+    let rows = stmt
+        .query_map(params![repo, ref_name, target], map_file_import_edge_row)
+        .map_err(StateError::sqlite)?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(StateError::sqlite)
+}
+
+fn map_file_import_edge_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<FileImportEdge> {
+    let from_symbol_id: String = row.get(0)?;
+    Ok(FileImportEdge {
+        source_path: from_symbol_id
+            .strip_prefix("file::")
+            .unwrap_or(&from_symbol_id)
+            .to_string(),
+        target_path: row.get(1)?,
+        target_name: row.get(2)?,
+        resolution_outcome: row.get(3)?,
+        confidence: row.get(4)?,
+    })
+}
+
 fn map_call_edge_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<CallEdge> {
     let source_line = row.get::<_, Option<i64>>(8)?.unwrap_or_default().max(0) as u32;
     Ok(CallEdge {
@@ -610,6 +723,128 @@ mod tests {
         }
     }
 
+    /// `handler.rs` imports `crate::auth::token` (resolved) and `serde::Serialize`
+    /// (external); `main.rs` imports the same token.
+    fn seed_import_edges(conn: &Connection) {
+        crate::symbols::insert_symbol(
+            conn,
+            &cruxe_core::types::SymbolRecord {
+                repo: "my-repo".to_string(),
+                r#ref: "main".to_string(),
+                commit: None,
+                path: "src/auth.rs".to_string(),
+                language: "rust".to_string(),
+                symbol_id: "sym-token".to_string(),
+                symbol_stable_id: "stable-token".to_string(),
+                name: "token".to_string(),
+                qualified_name: "crate::auth::token".to_string(),
+                kind: cruxe_core::types::SymbolKind::Function,
+                signature: None,
+                line_start: 1,
+                line_end: 3,
+                parent_symbol_id: None,
+                visibility: None,
+                content: None,
+                attributes: Vec::new(),
+                byte_start: None,
+                byte_end: None,
+                doc_comment: None,
+                parsed_signature: None,
+            },
+        )
+        .unwrap();
+        for (from_path, to_symbol_id, to_name, outcome) in [
+            (
+                "src/handler.rs",
+                Some("stable-token"),
+                None,
+                "resolved_internal",
+            ),
+            (
+                "src/handler.rs",
+                None,
+                Some("serde::Serialize"),
+                "external_reference",
+            ),
+            (
+                "src/main.rs",
+                Some("stable-token"),
+                None,
+                "resolved_internal",
+            ),
+        ] {
+            conn.execute(
+                "INSERT INTO symbol_edges (repo, \"ref\", from_symbol_id, to_symbol_id, to_name, edge_type, confidence, resolution_outcome)
+                 VALUES ('my-repo', 'main', ?1, ?2, ?3, 'imports', 'high', ?4)",
+                params![format!("file::{from_path}"), to_symbol_id, to_name, outcome],
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_import_edges_from_and_into_file() {
+        let conn = setup_test_db();
+        seed_import_edges(&conn);
+
+        let imports =
+            get_import_edges_from_file(&conn, "my-repo", "main", "src/handler.rs").unwrap();
+        let targets: Vec<(&str, Option<&str>)> = imports
+            .iter()
+            .map(|edge| (edge.target_name.as_str(), edge.target_path.as_deref()))
+            .collect();
+        assert_eq!(
+            targets,
+            vec![
+                ("crate::auth::token", Some("src/auth.rs")),
+                ("serde::Serialize", None),
+            ]
+        );
+        assert!(
+            imports
+                .iter()
+                .all(|edge| edge.source_path == "src/handler.rs")
+        );
+
+        let importers =
+            get_import_edges_into_file(&conn, "my-repo", "main", "src/auth.rs", None).unwrap();
+        let sources: Vec<&str> = importers
+            .iter()
+            .map(|edge| edge.source_path.as_str())
+            .collect();
+        assert_eq!(sources, vec!["src/handler.rs", "src/main.rs"]);
+    }
+
+    #[test]
+    fn test_get_importers_of_matches_resolved_and_raw_targets() {
+        let conn = setup_test_db();
+        seed_import_edges(&conn);
+
+        let resolved = get_importers_of(&conn, "my-repo", "main", "crate::auth::token").unwrap();
+        let sources: Vec<&str> = resolved
+            .iter()
+            .map(|edge| edge.source_path.as_str())
+            .collect();
+        assert_eq!(sources, vec!["src/handler.rs", "src/main.rs"]);
+        assert!(
+            resolved
+                .iter()
+                .all(|edge| edge.target_path.as_deref() == Some("src/auth.rs"))
+        );
+
+        let external = get_importers_of(&conn, "my-repo", "main", "serde::Serialize").unwrap();
+        assert_eq!(external.len(), 1);
+        assert_eq!(external[0].source_path, "src/handler.rs");
+        assert_eq!(external[0].target_path, None);
+        assert_eq!(external[0].resolution_outcome, "external_reference");
+
+        assert!(
+            get_importers_of(&conn, "my-repo", "feat", "serde::Serialize")
+                .unwrap()
+                .is_empty()
+        );
+    }
+
     #[test]
     fn test_insert_and_get_edges_from() {
         let conn = setup_test_db();
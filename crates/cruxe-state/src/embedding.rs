@@ -325,7 +325,7 @@ impl EmbeddingProvider for FastEmbedProvider {
         }
         if !uncached.is_empty() {
             let vectors = self.embed_uncached(&uncached);
-            for (input, vector) in uncached.into_iter().zip(vectors.into_iter()) {
+            for (input, vector) in uncached.into_iter().zip(vectors) {
                 self.insert_cache_entry(input, vector);
             }
         }
@@ -1,3 +1,4 @@
+use cruxe_core::constants;
 use cruxe_core::error::StateError;
 use cruxe_core::time::now_iso8601;
 use serde::{Deserialize, Serialize};
@@ -11,6 +12,9 @@ pub struct PortableStateMetadata {
     pub project_id: String,
     pub repo_root: String,
     pub exported_at: String,
+    /// SQLite migration level of the bundled `state.db` (0 in bundles that predate it).
+    #[serde(default)]
+    pub db_schema_version: u32,
 }
 
 impl PortableStateMetadata {
@@ -26,6 +30,7 @@ impl PortableStateMetadata {
             project_id: project_id.into(),
             repo_root: repo_root.into(),
             exported_at: now_iso8601(),
+            db_schema_version: 0,
         }
     }
 }
@@ -46,6 +51,17 @@ pub fn export_bundle(
         std::fs::create_dir_all(parent).map_err(StateError::Io)?;
     }
 
+    if !data_dir.join(constants::STATE_DB_FILE).is_file() {
+        return Err(StateError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!(
+                "data directory has no {}: {}",
+                constants::STATE_DB_FILE,
+                data_dir.display()
+            ),
+        )));
+    }
+
     let file = File::create(bundle_path).map_err(StateError::Io)?;
     let encoder = zstd::Encoder::new(file, 3).map_err(StateError::Io)?;
     let mut tar = tar::Builder::new(encoder);
@@ -1,4 +1,5 @@
 use crate::export::PortableStateMetadata;
+use crate::schema;
 use cruxe_core::constants;
 use cruxe_core::error::StateError;
 use std::fs::File;
@@ -38,11 +39,12 @@ pub fn import_bundle(
     let metadata: PortableStateMetadata = serde_json::from_slice(&metadata_bytes)
         .map_err(|err| StateError::CorruptManifest(err.to_string()))?;
 
-    if metadata.schema_version > constants::SCHEMA_VERSION {
-        return Err(StateError::SchemaMigrationRequired {
-            current: constants::SCHEMA_VERSION,
-            required: metadata.schema_version,
-        });
+    check_bundle_compatibility(&metadata)?;
+    if !temp.path().join(constants::STATE_DB_FILE).is_file() {
+        return Err(StateError::CorruptManifest(format!(
+            "bundle is missing {}",
+            constants::STATE_DB_FILE
+        )));
     }
 
     let parent_dir = data_dir.parent().ok_or_else(|| {
@@ -76,6 +78,36 @@ pub fn import_bundle(
     Ok(metadata)
 }
 
+/// Reject bundles whose index layout or extraction output this build cannot
+/// serve as-is, so a mismatched bundle never replaces a working index.
+///
+/// Older SQLite migration levels are accepted because `schema::create_tables`
+/// migrates them forward on open.
+pub fn check_bundle_compatibility(metadata: &PortableStateMetadata) -> Result<(), StateError> {
+    if metadata.schema_version != constants::SCHEMA_VERSION {
+        return Err(StateError::IncompatibleBundle {
+            component: "schema".to_string(),
+            bundle: metadata.schema_version,
+            local: constants::SCHEMA_VERSION,
+        });
+    }
+    if metadata.parser_version != constants::PARSER_VERSION {
+        return Err(StateError::IncompatibleBundle {
+            component: "parser".to_string(),
+            bundle: metadata.parser_version,
+            local: constants::PARSER_VERSION,
+        });
+    }
+    if metadata.db_schema_version > schema::CURRENT_SCHEMA_VERSION {
+        return Err(StateError::IncompatibleBundle {
+            component: "state_db".to_string(),
+            bundle: metadata.db_schema_version,
+            local: schema::CURRENT_SCHEMA_VERSION,
+        });
+    }
+    Ok(())
+}
+
 fn promote_staged_data(staged_data: tempfile::TempDir, data_dir: &Path) -> Result<(), StateError> {
     let parent_dir = data_dir.parent().ok_or_else(|| {
         StateError::Io(std::io::Error::new(
@@ -162,4 +194,53 @@ mod tests {
         assert!(target_dir.join("state.db").exists());
         assert!(target_dir.join("base").join("marker.txt").exists());
     }
+
+    #[test]
+    fn import_bundle_rejects_version_mismatch_without_touching_data_dir() {
+        let tmp = tempfile::tempdir().unwrap();
+        let source_dir = tmp.path().join("source");
+        std::fs::create_dir_all(&source_dir).unwrap();
+        std::fs::write(source_dir.join("state.db"), b"sqlite").unwrap();
+        let target_dir = tmp.path().join("target");
+        std::fs::create_dir_all(&target_dir).unwrap();
+        std::fs::write(target_dir.join("state.db"), b"existing").unwrap();
+
+        let parser_bundle = tmp.path().join("parser.tar.zst");
+        export_bundle(
+            &source_dir,
+            &parser_bundle,
+            &PortableStateMetadata::new(
+                constants::SCHEMA_VERSION,
+                constants::PARSER_VERSION + 1,
+                "proj",
+                "/tmp/repo",
+            ),
+        )
+        .unwrap();
+        let err = import_bundle(&parser_bundle, &target_dir).unwrap_err();
+        assert!(matches!(
+            err,
+            StateError::IncompatibleBundle { ref component, .. } if component == "parser"
+        ));
+
+        let mut metadata = PortableStateMetadata::new(
+            constants::SCHEMA_VERSION,
+            constants::PARSER_VERSION,
+            "proj",
+            "/tmp/repo",
+        );
+        metadata.db_schema_version = schema::CURRENT_SCHEMA_VERSION + 1;
+        let db_bundle = tmp.path().join("db.tar.zst");
+        export_bundle(&source_dir, &db_bundle, &metadata).unwrap();
+        let err = import_bundle(&db_bundle, &target_dir).unwrap_err();
+        assert!(matches!(
+            err,
+            StateError::IncompatibleBundle { ref component, .. } if component == "state_db"
+        ));
+
+        assert_eq!(
+            std::fs::read(target_dir.join("state.db")).unwrap(),
+            b"existing"
+        );
+    }
 }
@@ -20,15 +20,34 @@ pub struct IndexJob {
     pub files_scanned: i64,
     pub files_indexed: i64,
     pub symbols_extracted: i64,
+    /// Files the scan/parse phase left out; details live in `skipped_files`.
+    #[serde(default)]
+    pub files_skipped: i64,
+    /// Files whose content hash matched the manifest, so parsing was skipped.
+    #[serde(default)]
+    pub files_unchanged: i64,
+    /// Files dropped by `index.exclude` globs during the scan.
+    #[serde(default)]
+    pub files_excluded: i64,
+    /// Files indexed per second of wall-clock time, set when a full index run
+    /// finishes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub files_per_second: Option<f64>,
+    /// Tantivy commits the run made (see `index.commit_every_files`).
+    #[serde(default)]
+    pub index_commits: i64,
     pub created_at: String,
     pub updated_at: String,
+    /// Structured failure details (for example symbol cap diagnostics).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_data: Option<serde_json::Value>,
 }
 
 /// Create a new index job.
 pub fn create_job(conn: &Connection, job: &IndexJob) -> Result<(), StateError> {
     match conn.execute(
-        "INSERT INTO index_jobs (job_id, project_id, \"ref\", mode, head_commit, sync_id, status, changed_files, duration_ms, error_message, retry_count, progress_token, files_scanned, files_indexed, symbols_extracted, created_at, updated_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
+        "INSERT INTO index_jobs (job_id, project_id, \"ref\", mode, head_commit, sync_id, status, changed_files, duration_ms, error_message, retry_count, progress_token, files_scanned, files_indexed, symbols_extracted, created_at, updated_at, error_data, files_skipped, files_unchanged, files_excluded, files_per_second, index_commits)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23)",
         params![
             job.job_id,
             job.project_id,
@@ -47,6 +66,12 @@ pub fn create_job(conn: &Connection, job: &IndexJob) -> Result<(), StateError> {
             job.symbols_extracted,
             job.created_at,
             job.updated_at,
+            job.error_data.as_ref().map(|data| data.to_string()),
+            job.files_skipped,
+            job.files_unchanged,
+            job.files_excluded,
+            job.files_per_second,
+            job.index_commits,
         ],
     ) {
         Ok(_) => Ok(()),
@@ -68,6 +93,73 @@ pub fn create_job(conn: &Connection, job: &IndexJob) -> Result<(), StateError> {
     }
 }
 
+/// Atomically claim the active slot for `(project_id, ref)` with a `queued` job.
+///
+/// The partial unique index on active jobs makes this the arbitration point for
+/// concurrent callers: exactly one reservation wins, the rest get
+/// [`StateError::SyncInProgress`] naming the winner.
+pub fn reserve_job(
+    conn: &Connection,
+    job_id: &str,
+    project_id: &str,
+    ref_name: &str,
+    mode: &str,
+) -> Result<(), StateError> {
+    let now = cruxe_core::time::now_iso8601();
+    create_job(
+        conn,
+        &IndexJob {
+            job_id: job_id.to_string(),
+            project_id: project_id.to_string(),
+            r#ref: ref_name.to_string(),
+            mode: mode.to_string(),
+            head_commit: None,
+            sync_id: None,
+            status: JobStatus::Queued.as_str().to_string(),
+            changed_files: 0,
+            duration_ms: None,
+            error_message: None,
+            retry_count: 0,
+            progress_token: Some(format!("index-job-{job_id}")),
+            files_scanned: 0,
+            files_indexed: 0,
+            symbols_extracted: 0,
+            files_skipped: 0,
+            files_unchanged: 0,
+            files_excluded: 0,
+            files_per_second: None,
+            index_commits: 0,
+            created_at: now.clone(),
+            updated_at: now,
+            error_data: None,
+        },
+    )
+}
+
+/// Move a job reserved by [`reserve_job`] to `running`. Returns `false` when no
+/// queued job with that id exists (the caller should create its own).
+pub fn start_reserved_job(conn: &Connection, job_id: &str, mode: &str) -> Result<bool, StateError> {
+    let updated = conn
+        .execute(
+            "UPDATE index_jobs SET status = 'running', mode = ?1, updated_at = ?2 WHERE job_id = ?3 AND status = 'queued'",
+            params![mode, cruxe_core::time::now_iso8601(), job_id],
+        )
+        .map_err(StateError::sqlite)?;
+    Ok(updated > 0)
+}
+
+/// Drop a reservation that never started. Returns `false` if the job already
+/// started or does not exist.
+pub fn release_reserved_job(conn: &Connection, job_id: &str) -> Result<bool, StateError> {
+    let deleted = conn
+        .execute(
+            "DELETE FROM index_jobs WHERE job_id = ?1 AND status = 'queued'",
+            params![job_id],
+        )
+        .map_err(StateError::sqlite)?;
+    Ok(deleted > 0)
+}
+
 /// Update job status.
 pub fn update_job_status(
     conn: &Connection,
@@ -92,10 +184,42 @@ pub fn update_job_status(
     Ok(())
 }
 
+/// Structured failure details for errors that carry more than a message, in
+/// the shape stored by [`set_job_error_data`]; `None` for all other errors.
+pub fn error_data_for(err: &StateError) -> Option<serde_json::Value> {
+    match err {
+        StateError::SymbolCapExceeded {
+            ref_name,
+            symbol_count,
+            cap,
+        } => Some(serde_json::json!({
+            "code": cruxe_core::error::ProtocolErrorCode::SymbolCapExceeded.as_str(),
+            "ref": ref_name,
+            "symbol_count": symbol_count,
+            "max_symbols_per_ref": cap,
+        })),
+        _ => None,
+    }
+}
+
+/// Attach structured failure details to a job.
+pub fn set_job_error_data(
+    conn: &Connection,
+    job_id: &str,
+    error_data: &serde_json::Value,
+) -> Result<(), StateError> {
+    conn.execute(
+        "UPDATE index_jobs SET error_data = ?1 WHERE job_id = ?2",
+        params![error_data.to_string(), job_id],
+    )
+    .map_err(StateError::sqlite)?;
+    Ok(())
+}
+
 /// Get the active (running) job for a project, if any.
 pub fn get_active_job(conn: &Connection, project_id: &str) -> Result<Option<IndexJob>, StateError> {
     let mut stmt = conn.prepare(
-        "SELECT job_id, project_id, \"ref\", mode, head_commit, sync_id, status, changed_files, duration_ms, error_message, retry_count, progress_token, files_scanned, files_indexed, symbols_extracted, created_at, updated_at
+        "SELECT job_id, project_id, \"ref\", mode, head_commit, sync_id, status, changed_files, duration_ms, error_message, retry_count, progress_token, files_scanned, files_indexed, symbols_extracted, created_at, updated_at, error_data, files_skipped, files_unchanged, files_excluded, files_per_second, index_commits
          FROM index_jobs WHERE project_id = ?1 AND status IN ('queued', 'running', 'validating')
          ORDER BY created_at DESC LIMIT 1"
     ).map_err(StateError::sqlite)?;
@@ -117,7 +241,7 @@ pub fn get_active_job_for_ref(
 ) -> Result<Option<IndexJob>, StateError> {
     let mut stmt = conn
         .prepare(
-            "SELECT job_id, project_id, \"ref\", mode, head_commit, sync_id, status, changed_files, duration_ms, error_message, retry_count, progress_token, files_scanned, files_indexed, symbols_extracted, created_at, updated_at
+            "SELECT job_id, project_id, \"ref\", mode, head_commit, sync_id, status, changed_files, duration_ms, error_message, retry_count, progress_token, files_scanned, files_indexed, symbols_extracted, created_at, updated_at, error_data, files_skipped, files_unchanged, files_excluded, files_per_second, index_commits
              FROM index_jobs
              WHERE project_id = ?1 AND \"ref\" = ?2 AND status IN ('queued', 'running', 'validating')
              ORDER BY created_at DESC
@@ -134,6 +258,20 @@ pub fn get_active_job_for_ref(
     }
 }
 
+/// Get a single job by id.
+pub fn get_job(conn: &Connection, job_id: &str) -> Result<Option<IndexJob>, StateError> {
+    let mut stmt = conn.prepare(
+        "SELECT job_id, project_id, \"ref\", mode, head_commit, sync_id, status, changed_files, duration_ms, error_message, retry_count, progress_token, files_scanned, files_indexed, symbols_extracted, created_at, updated_at, error_data, files_skipped, files_unchanged, files_excluded, files_per_second, index_commits
+         FROM index_jobs WHERE job_id = ?1"
+    ).map_err(StateError::sqlite)?;
+
+    match stmt.query_row(params![job_id], row_to_job) {
+        Ok(job) => Ok(Some(job)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(StateError::sqlite(e)),
+    }
+}
+
 /// Get recent jobs for a project.
 pub fn get_recent_jobs(
     conn: &Connection,
@@ -141,7 +279,7 @@ pub fn get_recent_jobs(
     limit: usize,
 ) -> Result<Vec<IndexJob>, StateError> {
     let mut stmt = conn.prepare(
-        "SELECT job_id, project_id, \"ref\", mode, head_commit, sync_id, status, changed_files, duration_ms, error_message, retry_count, progress_token, files_scanned, files_indexed, symbols_extracted, created_at, updated_at
+        "SELECT job_id, project_id, \"ref\", mode, head_commit, sync_id, status, changed_files, duration_ms, error_message, retry_count, progress_token, files_scanned, files_indexed, symbols_extracted, created_at, updated_at, error_data, files_skipped, files_unchanged, files_excluded, files_per_second, index_commits
          FROM index_jobs WHERE project_id = ?1
          ORDER BY created_at DESC LIMIT ?2"
     ).map_err(StateError::sqlite)?;
@@ -170,6 +308,66 @@ pub fn update_progress(
     Ok(())
 }
 
+/// Record how many files the scan/parse phase skipped for a job.
+pub fn set_files_skipped(
+    conn: &Connection,
+    job_id: &str,
+    files_skipped: i64,
+) -> Result<(), StateError> {
+    conn.execute(
+        "UPDATE index_jobs SET files_skipped = ?1 WHERE job_id = ?2",
+        params![files_skipped, job_id],
+    )
+    .map_err(StateError::sqlite)?;
+    Ok(())
+}
+
+/// Record how many files a job left untouched because their content hash
+/// matched the manifest.
+pub fn set_files_unchanged(
+    conn: &Connection,
+    job_id: &str,
+    files_unchanged: i64,
+) -> Result<(), StateError> {
+    conn.execute(
+        "UPDATE index_jobs SET files_unchanged = ?1 WHERE job_id = ?2",
+        params![files_unchanged, job_id],
+    )
+    .map_err(StateError::sqlite)?;
+    Ok(())
+}
+
+/// Record how many files a job's scan dropped because they matched
+/// `index.exclude`.
+pub fn set_files_excluded(
+    conn: &Connection,
+    job_id: &str,
+    files_excluded: i64,
+) -> Result<(), StateError> {
+    conn.execute(
+        "UPDATE index_jobs SET files_excluded = ?1 WHERE job_id = ?2",
+        params![files_excluded, job_id],
+    )
+    .map_err(StateError::sqlite)?;
+    Ok(())
+}
+
+/// Record a finished run's indexing throughput and how many Tantivy commits
+/// it made.
+pub fn set_throughput(
+    conn: &Connection,
+    job_id: &str,
+    files_per_second: f64,
+    index_commits: i64,
+) -> Result<(), StateError> {
+    conn.execute(
+        "UPDATE index_jobs SET files_per_second = ?1, index_commits = ?2 WHERE job_id = ?3",
+        params![files_per_second, index_commits, job_id],
+    )
+    .map_err(StateError::sqlite)?;
+    Ok(())
+}
+
 /// Mark all running/queued jobs as interrupted. Returns the count of affected jobs.
 pub fn mark_interrupted_jobs(conn: &Connection) -> Result<usize, StateError> {
     let count = conn
@@ -184,7 +382,7 @@ pub fn mark_interrupted_jobs(conn: &Connection) -> Result<usize, StateError> {
 /// Get interrupted jobs (for recovery reporting).
 pub fn get_interrupted_jobs(conn: &Connection) -> Result<Vec<IndexJob>, StateError> {
     let mut stmt = conn.prepare(
-        "SELECT job_id, project_id, \"ref\", mode, head_commit, sync_id, status, changed_files, duration_ms, error_message, retry_count, progress_token, files_scanned, files_indexed, symbols_extracted, created_at, updated_at
+        "SELECT job_id, project_id, \"ref\", mode, head_commit, sync_id, status, changed_files, duration_ms, error_message, retry_count, progress_token, files_scanned, files_indexed, symbols_extracted, created_at, updated_at, error_data, files_skipped, files_unchanged, files_excluded, files_per_second, index_commits
          FROM index_jobs WHERE status = 'interrupted'
          ORDER BY created_at DESC"
     ).map_err(StateError::sqlite)?;
@@ -215,6 +413,14 @@ fn row_to_job(row: &rusqlite::Row) -> rusqlite::Result<IndexJob> {
         symbols_extracted: row.get(14)?,
         created_at: row.get(15)?,
         updated_at: row.get(16)?,
+        error_data: row
+            .get::<_, Option<String>>(17)?
+            .and_then(|raw| serde_json::from_str(&raw).ok()),
+        files_skipped: row.get::<_, Option<i64>>(18)?.unwrap_or(0),
+        files_unchanged: row.get::<_, Option<i64>>(19)?.unwrap_or(0),
+        files_excluded: row.get::<_, Option<i64>>(20)?.unwrap_or(0),
+        files_per_second: row.get(21)?,
+        index_commits: row.get::<_, Option<i64>>(22)?.unwrap_or(0),
     })
 }
 
@@ -266,8 +472,14 @@ mod tests {
             files_scanned: 0,
             files_indexed: 0,
             symbols_extracted: 0,
+            files_skipped: 0,
+            files_unchanged: 0,
+            files_excluded: 0,
+            files_per_second: None,
+            index_commits: 0,
             created_at: "2026-01-01T00:00:00Z".to_string(),
             updated_at: "2026-01-01T00:00:00Z".to_string(),
+            error_data: None,
         }
     }
 
@@ -345,6 +557,22 @@ mod tests {
         assert_eq!(active.unwrap().status, "validating");
     }
 
+    #[test]
+    fn test_get_job_finds_jobs_regardless_of_status() {
+        let conn = setup_test_db();
+        insert_test_project(&conn, "proj_1");
+
+        let mut job = sample_job("proj_1");
+        job.status = JobStatus::Published.as_str().to_string();
+        job.files_indexed = 7;
+        create_job(&conn, &job).unwrap();
+
+        let found = get_job(&conn, "job_001").unwrap().unwrap();
+        assert_eq!(found.status, "published");
+        assert_eq!(found.files_indexed, 7);
+        assert!(get_job(&conn, "job_missing").unwrap().is_none());
+    }
+
     #[test]
     fn test_get_active_job_for_ref_filters_by_ref() {
         let conn = setup_test_db();
@@ -401,6 +629,68 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_concurrent_reservations_admit_one_active_job_per_ref() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let conn = db::open_connection(&db_path).unwrap();
+        schema::create_tables(&conn).unwrap();
+        insert_test_project(&conn, "proj_1");
+
+        let barrier = std::sync::Arc::new(std::sync::Barrier::new(2));
+        let handles: Vec<_> = ["job_a", "job_b"]
+            .into_iter()
+            .map(|job_id| {
+                let db_path = db_path.clone();
+                let barrier = std::sync::Arc::clone(&barrier);
+                std::thread::spawn(move || {
+                    let conn = db::open_connection(&db_path).unwrap();
+                    barrier.wait();
+                    reserve_job(&conn, job_id, "proj_1", "main", "incremental")
+                })
+            })
+            .collect();
+        let outcomes: Vec<_> = handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect();
+
+        let winners = outcomes.iter().filter(|outcome| outcome.is_ok()).count();
+        assert_eq!(winners, 1);
+        let active = get_active_job_for_ref(&conn, "proj_1", "main")
+            .unwrap()
+            .unwrap();
+        let loser = outcomes.into_iter().find_map(Result::err).unwrap();
+        match loser {
+            StateError::SyncInProgress { job_id, .. } => assert_eq!(job_id, active.job_id),
+            other => panic!("expected SyncInProgress, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_reserved_job_can_be_started_or_released_once() {
+        let conn = setup_test_db();
+        insert_test_project(&conn, "proj_1");
+
+        reserve_job(&conn, "job_a", "proj_1", "main", "incremental").unwrap();
+        assert!(start_reserved_job(&conn, "job_a", "full").unwrap());
+        assert!(!start_reserved_job(&conn, "job_a", "full").unwrap());
+        assert!(!release_reserved_job(&conn, "job_a").unwrap());
+        let active = get_active_job_for_ref(&conn, "proj_1", "main")
+            .unwrap()
+            .unwrap();
+        assert_eq!(active.status, "running");
+        assert_eq!(active.mode, "full");
+
+        reserve_job(&conn, "job_b", "proj_1", "dev", "incremental").unwrap();
+        assert!(release_reserved_job(&conn, "job_b").unwrap());
+        assert!(
+            get_active_job_for_ref(&conn, "proj_1", "dev")
+                .unwrap()
+                .is_none()
+        );
+    }
+
     #[test]
     fn test_update_job_status() {
         let conn = setup_test_db();
@@ -482,6 +772,30 @@ mod tests {
         assert_eq!(recent[0].duration_ms, Some(1500));
     }
 
+    #[test]
+    fn test_set_job_error_data_round_trips_json() {
+        let conn = setup_test_db();
+        insert_test_project(&conn, "proj_1");
+
+        let job = sample_job("proj_1");
+        create_job(&conn, &job).unwrap();
+        assert!(
+            get_recent_jobs(&conn, "proj_1", 1).unwrap()[0]
+                .error_data
+                .is_none()
+        );
+
+        let data = serde_json::json!({
+            "code": "symbol_cap_exceeded",
+            "symbol_count": 12,
+            "max_symbols_per_ref": 10,
+        });
+        set_job_error_data(&conn, "job_001", &data).unwrap();
+
+        let recent = get_recent_jobs(&conn, "proj_1", 1).unwrap();
+        assert_eq!(recent[0].error_data, Some(data));
+    }
+
     #[test]
     fn test_get_recent_jobs_ordering() {
         let conn = setup_test_db();
@@ -581,8 +895,14 @@ mod tests {
             files_scanned: 0,
             files_indexed: 0,
             symbols_extracted: 0,
+            files_skipped: 0,
+            files_unchanged: 0,
+            files_excluded: 0,
+            files_per_second: None,
+            index_commits: 0,
             created_at: "2026-01-01T00:00:00Z".to_string(),
             updated_at: "2026-01-01T00:00:00Z".to_string(),
+            error_data: None,
         };
 
         create_job(&conn, &job).unwrap();
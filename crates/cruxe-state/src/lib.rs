@@ -1,5 +1,7 @@
 pub mod branch_state;
+pub mod compaction;
 pub mod db;
+pub mod disk_space;
 pub mod edges;
 pub mod embedding;
 pub mod export;
@@ -9,7 +11,9 @@ pub mod maintenance_lock;
 pub mod manifest;
 pub mod overlay_paths;
 pub mod project;
+pub mod ref_drop;
 pub mod schema;
+pub mod skipped_files;
 pub mod symbols;
 pub mod tantivy_index;
 pub mod tokenizers;
@@ -119,6 +119,79 @@ pub fn get_all_entries(
         .map_err(|e| StateError::Sqlite(e.to_string()))
 }
 
+/// An indexed file with the number of symbols extracted from it.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ManifestFileSummary {
+    pub path: String,
+    pub language: Option<String>,
+    pub size_bytes: u64,
+    pub symbol_count: u64,
+}
+
+/// A page of indexed files plus the total matching the filters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestFilesPage {
+    pub total: u64,
+    pub files: Vec<ManifestFileSummary>,
+}
+
+/// List indexed files for a repo/ref ordered by path, optionally filtered by
+/// language and path prefix.
+pub fn list_files(
+    conn: &Connection,
+    repo: &str,
+    r#ref: &str,
+    language: Option<&str>,
+    path_prefix: Option<&str>,
+    limit: usize,
+) -> Result<ManifestFilesPage, StateError> {
+    let like_pattern = path_prefix.map(|prefix| format!("{prefix}%"));
+    let total: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM file_manifest
+             WHERE repo = ?1 AND \"ref\" = ?2
+               AND (?3 IS NULL OR language = ?3)
+               AND (?4 IS NULL OR path LIKE ?4)",
+            params![repo, r#ref, language, like_pattern],
+            |row| row.get(0),
+        )
+        .map_err(StateError::sqlite)?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT m.path, m.language, m.size_bytes,
+                    (SELECT COUNT(*) FROM symbol_relations s
+                     WHERE s.repo = m.repo AND s.\"ref\" = m.\"ref\" AND s.path = m.path)
+             FROM file_manifest m
+             WHERE m.repo = ?1 AND m.\"ref\" = ?2
+               AND (?3 IS NULL OR m.language = ?3)
+               AND (?4 IS NULL OR m.path LIKE ?4)
+             ORDER BY m.path
+             LIMIT ?5",
+        )
+        .map_err(StateError::sqlite)?;
+    let files = stmt
+        .query_map(
+            params![repo, r#ref, language, like_pattern, limit as i64],
+            |row| {
+                Ok(ManifestFileSummary {
+                    path: row.get(0)?,
+                    language: row.get(1)?,
+                    size_bytes: row.get::<_, i64>(2)? as u64,
+                    symbol_count: row.get::<_, i64>(3)? as u64,
+                })
+            },
+        )
+        .map_err(StateError::sqlite)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(StateError::sqlite)?;
+
+    Ok(ManifestFilesPage {
+        total: total as u64,
+        files,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -306,4 +379,72 @@ mod tests {
 
         assert_eq!(file_count(&conn, "my-repo", "main").unwrap(), 0);
     }
+
+    #[test]
+    fn test_list_files_filters_and_counts_symbols() {
+        let conn = setup_test_db();
+        for (path, language) in [
+            ("src/lib.rs", "rust"),
+            ("src/util/mod.rs", "rust"),
+            ("web/app.ts", "typescript"),
+        ] {
+            let mut entry = sample_entry();
+            entry.path = path.to_string();
+            entry.language = Some(language.to_string());
+            upsert_manifest(&conn, &entry).unwrap();
+        }
+        for name in ["parse", "render"] {
+            crate::symbols::insert_symbol(
+                &conn,
+                &cruxe_core::types::SymbolRecord {
+                    repo: "my-repo".to_string(),
+                    r#ref: "main".to_string(),
+                    commit: None,
+                    path: "src/lib.rs".to_string(),
+                    language: "rust".to_string(),
+                    symbol_id: format!("sym::{name}"),
+                    symbol_stable_id: format!("stable::{name}"),
+                    name: name.to_string(),
+                    qualified_name: name.to_string(),
+                    kind: cruxe_core::types::SymbolKind::Function,
+                    signature: None,
+                    line_start: 1,
+                    line_end: 2,
+                    parent_symbol_id: None,
+                    visibility: None,
+                    content: None,
+                    attributes: Vec::new(),
+                    byte_start: None,
+                    byte_end: None,
+                    doc_comment: None,
+                    parsed_signature: None,
+                },
+            )
+            .unwrap();
+        }
+
+        let all = list_files(&conn, "my-repo", "main", None, None, 10).unwrap();
+        assert_eq!(all.total, 3);
+        let paths: Vec<&str> = all.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["src/lib.rs", "src/util/mod.rs", "web/app.ts"]);
+        assert_eq!(all.files[0].symbol_count, 2);
+        assert_eq!(all.files[0].size_bytes, 1024);
+        assert_eq!(all.files[1].symbol_count, 0);
+
+        let rust_under_src = list_files(
+            &conn,
+            "my-repo",
+            "main",
+            Some("rust"),
+            Some("src/util/"),
+            10,
+        )
+        .unwrap();
+        assert_eq!(rust_under_src.total, 1);
+        assert_eq!(rust_under_src.files[0].path, "src/util/mod.rs");
+
+        let limited = list_files(&conn, "my-repo", "main", None, None, 1).unwrap();
+        assert_eq!(limited.total, 3);
+        assert_eq!(limited.files.len(), 1);
+    }
 }
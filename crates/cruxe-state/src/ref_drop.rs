@@ -0,0 +1,228 @@
+use cruxe_core::error::StateError;
+use rusqlite::{Connection, params};
+use serde::Serialize;
+use std::path::Path;
+use tantivy::{Index, IndexWriter, Term};
+use tracing::info;
+
+use crate::compaction::live_doc_count;
+use crate::tantivy_index::IndexSet;
+use crate::{maintenance_lock, overlay_paths, vector_index};
+
+/// Outcome of [`drop_ref`]: how many records were removed per store.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DropRefReport {
+    pub symbol_rows: u64,
+    pub manifest_rows: u64,
+    /// Call and import edges.
+    pub edge_rows: u64,
+    pub branch_state_rows: u64,
+    pub tombstone_rows: u64,
+    pub skipped_file_rows: u64,
+    pub vector_rows: u64,
+    /// Base index documents tagged with the ref.
+    pub symbol_documents: u64,
+    pub snippet_documents: u64,
+    pub file_documents: u64,
+    /// Documents in the ref's overlay index, removed with its directory.
+    pub overlay_documents: u64,
+    pub overlay_removed: bool,
+}
+
+/// Delete everything indexed for `r#ref`: SQLite rows (symbols, manifest,
+/// edges, branch state, tombstones, skipped files, vectors), base Tantivy
+/// documents carrying the ref, and the ref's overlay directory.
+///
+/// `overlay_dir` is only removed when it resolves under the project's overlay
+/// roots. Holds the project maintenance lock, so it fails fast with
+/// [`StateError::MaintenanceLockBusy`] while a sync is running. Dropping a ref
+/// that has nothing indexed returns an all-zero report.
+pub fn drop_ref(
+    conn: &Connection,
+    data_dir: &Path,
+    repo: &str,
+    r#ref: &str,
+    overlay_dir: &Path,
+    vector_backend: Option<&str>,
+) -> Result<DropRefReport, StateError> {
+    let _lock = maintenance_lock::acquire_project_lock(data_dir, "drop_ref")?;
+    let mut report = DropRefReport::default();
+
+    if let Ok(index_set) = IndexSet::open_existing(data_dir) {
+        report.symbol_documents = delete_ref_documents(&index_set.symbols, r#ref)?;
+        report.snippet_documents = delete_ref_documents(&index_set.snippets, r#ref)?;
+        report.file_documents = delete_ref_documents(&index_set.files, r#ref)?;
+    }
+
+    if overlay_dir.exists() {
+        let data_dir_canonical = overlay_paths::canonicalize_data_dir(data_dir);
+        let overlay_canonical = overlay_paths::canonicalize_overlay_dir(overlay_dir)?;
+        if overlay_paths::is_overlay_dir_allowed(&data_dir_canonical, &overlay_canonical)? {
+            if let Ok(overlay) = IndexSet::open_existing_at(&overlay_canonical) {
+                for index in [&overlay.symbols, &overlay.snippets, &overlay.files] {
+                    report.overlay_documents += live_doc_count(index)?;
+                }
+            }
+            std::fs::remove_dir_all(&overlay_canonical)?;
+            report.overlay_removed = true;
+        }
+    }
+
+    let tx = conn.unchecked_transaction().map_err(StateError::sqlite)?;
+    let delete = |table: &str, repo_column: &str| -> Result<u64, StateError> {
+        tx.execute(
+            &format!("DELETE FROM {table} WHERE {repo_column} = ?1 AND \"ref\" = ?2"),
+            params![repo, r#ref],
+        )
+        .map(|rows| rows as u64)
+        .map_err(StateError::sqlite)
+    };
+    report.symbol_rows = delete("symbol_relations", "repo")?;
+    report.manifest_rows = delete("file_manifest", "repo")?;
+    report.edge_rows = delete("symbol_edges", "repo")?;
+    report.branch_state_rows = delete("branch_state", "repo")?;
+    report.tombstone_rows = delete("branch_tombstones", "repo")?;
+    report.skipped_file_rows = delete("skipped_files", "project_id")?;
+    tx.commit().map_err(StateError::sqlite)?;
+    report.vector_rows =
+        vector_index::delete_vectors_for_ref_with_backend(conn, repo, r#ref, vector_backend)?
+            as u64;
+
+    info!(
+        repo,
+        r#ref,
+        symbol_rows = report.symbol_rows,
+        overlay_removed = report.overlay_removed,
+        "Dropped ref"
+    );
+    Ok(report)
+}
+
+/// Delete every document whose `ref` field equals `r#ref`; returns how many went.
+fn delete_ref_documents(index: &Index, r#ref: &str) -> Result<u64, StateError> {
+    let field = index
+        .schema()
+        .get_field("ref")
+        .map_err(StateError::tantivy)?;
+    let before = live_doc_count(index)?;
+    let mut writer: IndexWriter = index.writer(50_000_000).map_err(StateError::tantivy)?;
+    writer.delete_term(Term::from_field_text(field, r#ref));
+    writer.commit().map_err(StateError::tantivy)?;
+    writer.wait_merging_threads().map_err(StateError::tantivy)?;
+    Ok(before.saturating_sub(live_doc_count(index)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{db, manifest, schema, symbols};
+    use cruxe_core::constants;
+    use cruxe_core::types::{SymbolKind, SymbolRecord};
+    use tantivy::doc;
+
+    fn symbol(r#ref: &str) -> SymbolRecord {
+        SymbolRecord {
+            repo: "proj".to_string(),
+            r#ref: r#ref.to_string(),
+            commit: None,
+            path: "src/lib.rs".to_string(),
+            symbol_id: format!("sym_{ref}"),
+            symbol_stable_id: "stable".to_string(),
+            name: "run".to_string(),
+            qualified_name: "run".to_string(),
+            kind: SymbolKind::Function,
+            language: "rust".to_string(),
+            line_start: 1,
+            line_end: 2,
+            signature: None,
+            parent_symbol_id: None,
+            visibility: None,
+            content: None,
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
+        }
+    }
+
+    fn add_documents(index: &Index, refs: &[&str]) {
+        let ref_field = index.schema().get_field("ref").unwrap();
+        let mut writer: IndexWriter = index.writer(50_000_000).unwrap();
+        for r#ref in refs {
+            writer.add_document(doc!(ref_field => *r#ref)).unwrap();
+        }
+        writer.commit().unwrap();
+    }
+
+    #[test]
+    fn drop_ref_removes_only_the_target_ref() {
+        let tmp = tempfile::tempdir().unwrap();
+        let data_dir = tmp.path().join("project");
+        std::fs::create_dir_all(&data_dir).unwrap();
+        let conn = db::open_connection(&data_dir.join(constants::STATE_DB_FILE)).unwrap();
+        schema::create_tables(&conn).unwrap();
+
+        let index_set = IndexSet::open(&data_dir).unwrap();
+        add_documents(&index_set.symbols, &["main", "feat/gone", "feat/gone"]);
+        add_documents(&index_set.files, &["main", "feat/gone"]);
+        let overlay_dir = data_dir.join("overlay").join("feat-gone");
+        let overlay = IndexSet::open_at(&overlay_dir).unwrap();
+        add_documents(&overlay.snippets, &["feat/gone"]);
+
+        for r#ref in ["main", "feat/gone"] {
+            symbols::insert_symbol(&conn, &symbol(r#ref)).unwrap();
+            manifest::upsert_manifest(
+                &conn,
+                &manifest::ManifestEntry {
+                    repo: "proj".to_string(),
+                    r#ref: r#ref.to_string(),
+                    path: "src/lib.rs".to_string(),
+                    content_hash: "hash".to_string(),
+                    size_bytes: 10,
+                    mtime_ns: None,
+                    language: Some("rust".to_string()),
+                    indexed_at: cruxe_core::time::now_iso8601(),
+                },
+            )
+            .unwrap();
+        }
+
+        let report = drop_ref(&conn, &data_dir, "proj", "feat/gone", &overlay_dir, None).unwrap();
+
+        assert_eq!(report.symbol_rows, 1);
+        assert_eq!(report.manifest_rows, 1);
+        assert_eq!(report.symbol_documents, 2);
+        assert_eq!(report.file_documents, 1);
+        assert_eq!(report.overlay_documents, 1);
+        assert!(report.overlay_removed);
+        assert!(!overlay_dir.exists());
+        assert_eq!(live_doc_count(&index_set.symbols).unwrap(), 1);
+        assert!(
+            symbols::get_symbol_by_id(&conn, "proj", "feat/gone", "sym_feat/gone")
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            symbols::get_symbol_by_id(&conn, "proj", "main", "sym_main")
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn drop_ref_leaves_overlay_dirs_outside_the_project_alone() {
+        let tmp = tempfile::tempdir().unwrap();
+        let data_dir = tmp.path().join("project");
+        std::fs::create_dir_all(&data_dir).unwrap();
+        let conn = db::open_connection(&data_dir.join(constants::STATE_DB_FILE)).unwrap();
+        schema::create_tables(&conn).unwrap();
+        let outside = tmp.path().join("elsewhere");
+        std::fs::create_dir_all(&outside).unwrap();
+
+        let report = drop_ref(&conn, &data_dir, "proj", "feat/x", &outside, None).unwrap();
+
+        assert_eq!(report, DropRefReport::default());
+        assert!(outside.exists());
+    }
+}
@@ -4,7 +4,7 @@ use rusqlite::Connection;
 use tracing::info;
 
 /// Current schema version. Bump this when adding a new migration step.
-pub const CURRENT_SCHEMA_VERSION: u32 = 14;
+pub const CURRENT_SCHEMA_VERSION: u32 = 23;
 
 /// Create all required SQLite tables per data-model.md and run any pending migrations.
 pub fn create_tables(conn: &Connection) -> Result<(), StateError> {
@@ -18,6 +18,26 @@ pub fn create_tables(conn: &Connection) -> Result<(), StateError> {
     Ok(())
 }
 
+/// Highest migration recorded in `schema_migrations` (0 when none has run).
+pub fn applied_schema_version(conn: &Connection) -> Result<u32, StateError> {
+    let has_table: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'schema_migrations'",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(StateError::sqlite)?;
+    if !has_table {
+        return Ok(0);
+    }
+    conn.query_row(
+        "SELECT COALESCE(MAX(version), 0) FROM schema_migrations",
+        [],
+        |row| row.get(0),
+    )
+    .map_err(StateError::sqlite)
+}
+
 /// Run incremental schema migrations up to `CURRENT_SCHEMA_VERSION`.
 ///
 /// The `schema_migrations` table tracks which version has been applied.
@@ -459,6 +479,178 @@ pub fn migrate(conn: &Connection) -> Result<(), StateError> {
             .map_err(StateError::sqlite)?;
             Ok(())
         },
+        // V15: structured failure details (JSON) for index jobs.
+        |conn| {
+            let (has_table, has_error_data): (bool, bool) = conn
+                .query_row(
+                    "SELECT
+                        (SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'index_jobs'),
+                        (SELECT COUNT(*) > 0 FROM pragma_table_info('index_jobs') WHERE name = 'error_data')",
+                    [],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .map_err(StateError::sqlite)?;
+            if has_table && !has_error_data {
+                conn.execute_batch("ALTER TABLE index_jobs ADD COLUMN error_data TEXT;")
+                    .map_err(StateError::sqlite)?;
+            }
+            Ok(())
+        },
+        // V16: per-ref skipped-file diagnostics and a skipped-file count on index jobs.
+        |conn| {
+            let (has_table, has_files_skipped): (bool, bool) = conn
+                .query_row(
+                    "SELECT
+                        (SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'index_jobs'),
+                        (SELECT COUNT(*) > 0 FROM pragma_table_info('index_jobs') WHERE name = 'files_skipped')",
+                    [],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .map_err(StateError::sqlite)?;
+            if has_table && !has_files_skipped {
+                conn.execute_batch(
+                    "ALTER TABLE index_jobs ADD COLUMN files_skipped INTEGER DEFAULT 0;",
+                )
+                .map_err(StateError::sqlite)?;
+            }
+            conn.execute_batch(
+                "CREATE TABLE IF NOT EXISTS skipped_files (
+                    job_id TEXT NOT NULL,
+                    project_id TEXT NOT NULL,
+                    \"ref\" TEXT NOT NULL,
+                    path TEXT NOT NULL,
+                    reason TEXT NOT NULL,
+                    detail TEXT,
+                    PRIMARY KEY (project_id, \"ref\", path)
+                );",
+            )
+            .map_err(StateError::sqlite)?;
+            Ok(())
+        }, // V17: outer attributes (JSON array) captured for symbols.
+        |conn| {
+            let (has_table, has_attributes): (bool, bool) = conn
+                .query_row(
+                    "SELECT
+                        (SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'symbol_relations'),
+                        (SELECT COUNT(*) > 0 FROM pragma_table_info('symbol_relations') WHERE name = 'attributes')",
+                    [],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .map_err(StateError::sqlite)?;
+            if has_table && !has_attributes {
+                conn.execute_batch("ALTER TABLE symbol_relations ADD COLUMN attributes TEXT;")
+                    .map_err(StateError::sqlite)?;
+            }
+            Ok(())
+        }, // V18: symbol byte ranges for offset-based lookups.
+        |conn| {
+            let (has_table, has_byte_start): (bool, bool) = conn
+                .query_row(
+                    "SELECT
+                        (SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'symbol_relations'),
+                        (SELECT COUNT(*) > 0 FROM pragma_table_info('symbol_relations') WHERE name = 'byte_start')",
+                    [],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .map_err(StateError::sqlite)?;
+            if has_table && !has_byte_start {
+                conn.execute_batch(
+                    "ALTER TABLE symbol_relations ADD COLUMN byte_start INTEGER;
+                     ALTER TABLE symbol_relations ADD COLUMN byte_end INTEGER;",
+                )
+                .map_err(StateError::sqlite)?;
+            }
+            Ok(())
+        }, // V19: doc comments captured for symbols.
+        |conn| {
+            let (has_table, has_doc_comment): (bool, bool) = conn
+                .query_row(
+                    "SELECT
+                        (SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'symbol_relations'),
+                        (SELECT COUNT(*) > 0 FROM pragma_table_info('symbol_relations') WHERE name = 'doc_comment')",
+                    [],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .map_err(StateError::sqlite)?;
+            if has_table && !has_doc_comment {
+                conn.execute_batch("ALTER TABLE symbol_relations ADD COLUMN doc_comment TEXT;")
+                    .map_err(StateError::sqlite)?;
+            }
+            Ok(())
+        }, // V20: unchanged-file (content hash hit) count on index jobs.
+        |conn| {
+            let (has_table, has_files_unchanged): (bool, bool) = conn
+                .query_row(
+                    "SELECT
+                        (SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'index_jobs'),
+                        (SELECT COUNT(*) > 0 FROM pragma_table_info('index_jobs') WHERE name = 'files_unchanged')",
+                    [],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .map_err(StateError::sqlite)?;
+            if has_table && !has_files_unchanged {
+                conn.execute_batch(
+                    "ALTER TABLE index_jobs ADD COLUMN files_unchanged INTEGER DEFAULT 0;",
+                )
+                .map_err(StateError::sqlite)?;
+            }
+            Ok(())
+        }, // V21: files dropped by index.exclude globs on index jobs.
+        |conn| {
+            let (has_table, has_files_excluded): (bool, bool) = conn
+                .query_row(
+                    "SELECT
+                        (SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'index_jobs'),
+                        (SELECT COUNT(*) > 0 FROM pragma_table_info('index_jobs') WHERE name = 'files_excluded')",
+                    [],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .map_err(StateError::sqlite)?;
+            if has_table && !has_files_excluded {
+                conn.execute_batch(
+                    "ALTER TABLE index_jobs ADD COLUMN files_excluded INTEGER DEFAULT 0;",
+                )
+                .map_err(StateError::sqlite)?;
+            }
+            Ok(())
+        }, // V22: structured signatures for callables.
+        |conn| {
+            let (has_table, has_parsed_signature): (bool, bool) = conn
+                .query_row(
+                    "SELECT
+                        (SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'symbol_relations'),
+                        (SELECT COUNT(*) > 0 FROM pragma_table_info('symbol_relations') WHERE name = 'parsed_signature')",
+                    [],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .map_err(StateError::sqlite)?;
+            if has_table && !has_parsed_signature {
+                conn.execute_batch(
+                    "ALTER TABLE symbol_relations ADD COLUMN parsed_signature TEXT;",
+                )
+                .map_err(StateError::sqlite)?;
+            }
+            Ok(())
+        }, // V23: indexing throughput and Tantivy commit count on index jobs.
+        |conn| {
+            let (has_table, has_files_per_second): (bool, bool) = conn
+                .query_row(
+                    "SELECT
+                        (SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'index_jobs'),
+                        (SELECT COUNT(*) > 0 FROM pragma_table_info('index_jobs') WHERE name = 'files_per_second')",
+                    [],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .map_err(StateError::sqlite)?;
+            if has_table && !has_files_per_second {
+                conn.execute_batch(
+                    "ALTER TABLE index_jobs ADD COLUMN files_per_second REAL;
+                     ALTER TABLE index_jobs ADD COLUMN index_commits INTEGER DEFAULT 0;",
+                )
+                .map_err(StateError::sqlite)?;
+            }
+            Ok(())
+        },
     ];
 
     for version in (current + 1)..=(CURRENT_SCHEMA_VERSION) {
@@ -521,6 +713,11 @@ CREATE TABLE IF NOT EXISTS symbol_relations (
     visibility TEXT,
     content TEXT,
     content_hash TEXT NOT NULL,
+    attributes TEXT,
+    byte_start INTEGER,
+    byte_end INTEGER,
+    doc_comment TEXT,
+    parsed_signature TEXT,
     UNIQUE(repo, "ref", path, qualified_name, kind, line_start),
     UNIQUE(repo, "ref", symbol_stable_id, kind)
 );
@@ -614,7 +811,13 @@ CREATE TABLE IF NOT EXISTS index_jobs (
     files_indexed INTEGER DEFAULT 0,
     symbols_extracted INTEGER DEFAULT 0,
     created_at TEXT NOT NULL,
-    updated_at TEXT NOT NULL
+    updated_at TEXT NOT NULL,
+    error_data TEXT,
+    files_skipped INTEGER DEFAULT 0,
+    files_unchanged INTEGER DEFAULT 0,
+    files_excluded INTEGER DEFAULT 0,
+    files_per_second REAL,
+    index_commits INTEGER DEFAULT 0
 );
 
 CREATE INDEX IF NOT EXISTS idx_jobs_status ON index_jobs(status, created_at);
@@ -643,6 +846,16 @@ CREATE INDEX IF NOT EXISTS idx_symbol_edges_to_type
 CREATE INDEX IF NOT EXISTS idx_symbol_edges_source_file
     ON symbol_edges(repo, "ref", source_file, edge_type);
 
+CREATE TABLE IF NOT EXISTS skipped_files (
+    job_id TEXT NOT NULL,
+    project_id TEXT NOT NULL,
+    "ref" TEXT NOT NULL,
+    path TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    detail TEXT,
+    PRIMARY KEY (project_id, "ref", path)
+);
+
 CREATE TABLE IF NOT EXISTS known_workspaces (
     workspace_path TEXT PRIMARY KEY,
     project_id TEXT REFERENCES projects(project_id),
@@ -0,0 +1,185 @@
+use cruxe_core::error::StateError;
+use rusqlite::{Connection, params};
+use serde::{Deserialize, Serialize};
+
+/// A file the last index run for a ref left out, with the reason.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkippedFileRecord {
+    pub path: String,
+    pub reason: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// Skipped files recorded for a project/ref, tagged with the job that produced them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedFilesPage {
+    pub job_id: Option<String>,
+    pub total: u64,
+    pub files: Vec<SkippedFileRecord>,
+}
+
+/// Replace the skipped-file list for `project_id`/`ref` with the entries from `job_id`.
+///
+/// Only the latest index run is kept per ref so the table stays bounded.
+pub fn replace_for_job(
+    conn: &mut Connection,
+    project_id: &str,
+    r#ref: &str,
+    job_id: &str,
+    files: &[SkippedFileRecord],
+) -> Result<(), StateError> {
+    let tx = conn.transaction().map_err(StateError::sqlite)?;
+    tx.execute(
+        "DELETE FROM skipped_files WHERE project_id = ?1 AND \"ref\" = ?2",
+        params![project_id, r#ref],
+    )
+    .map_err(StateError::sqlite)?;
+    {
+        let mut stmt = tx
+            .prepare(
+                "INSERT OR REPLACE INTO skipped_files (job_id, project_id, \"ref\", path, reason, detail)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )
+            .map_err(StateError::sqlite)?;
+        for file in files {
+            stmt.execute(params![
+                job_id,
+                project_id,
+                r#ref,
+                file.path,
+                file.reason,
+                file.detail
+            ])
+            .map_err(StateError::sqlite)?;
+        }
+    }
+    tx.commit().map_err(StateError::sqlite)?;
+    Ok(())
+}
+
+/// List skipped files for a project/ref ordered by path, optionally filtered by reason.
+pub fn list_for_ref(
+    conn: &Connection,
+    project_id: &str,
+    r#ref: &str,
+    reason: Option<&str>,
+    offset: u64,
+    limit: usize,
+) -> Result<SkippedFilesPage, StateError> {
+    let job_id: Option<String> = conn
+        .query_row(
+            "SELECT job_id FROM skipped_files WHERE project_id = ?1 AND \"ref\" = ?2 LIMIT 1",
+            params![project_id, r#ref],
+            |row| row.get(0),
+        )
+        .map(Some)
+        .or_else(|err| match err {
+            rusqlite::Error::QueryReturnedNoRows => Ok(None),
+            other => Err(StateError::sqlite(other)),
+        })?;
+
+    let total: u64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM skipped_files
+             WHERE project_id = ?1 AND \"ref\" = ?2 AND (?3 IS NULL OR reason = ?3)",
+            params![project_id, r#ref, reason],
+            |row| row.get::<_, i64>(0),
+        )
+        .map_err(StateError::sqlite)? as u64;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT path, reason, detail FROM skipped_files
+             WHERE project_id = ?1 AND \"ref\" = ?2 AND (?3 IS NULL OR reason = ?3)
+             ORDER BY path ASC
+             LIMIT ?4 OFFSET ?5",
+        )
+        .map_err(StateError::sqlite)?;
+    let rows = stmt
+        .query_map(
+            params![
+                project_id,
+                r#ref,
+                reason,
+                limit.min(i64::MAX as usize) as i64,
+                offset.min(i64::MAX as u64) as i64
+            ],
+            |row| {
+                Ok(SkippedFileRecord {
+                    path: row.get(0)?,
+                    reason: row.get(1)?,
+                    detail: row.get(2)?,
+                })
+            },
+        )
+        .map_err(StateError::sqlite)?;
+    let files = rows
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(StateError::sqlite)?;
+
+    Ok(SkippedFilesPage {
+        job_id,
+        total,
+        files,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{db, schema};
+    use tempfile::tempdir;
+
+    fn record(path: &str, reason: &str) -> SkippedFileRecord {
+        SkippedFileRecord {
+            path: path.to_string(),
+            reason: reason.to_string(),
+            detail: None,
+        }
+    }
+
+    #[test]
+    fn replace_keeps_only_latest_job_and_filters_by_reason() {
+        let dir = tempdir().unwrap();
+        let mut conn = db::open_connection(&dir.path().join("state.db")).unwrap();
+        schema::create_tables(&conn).unwrap();
+
+        replace_for_job(
+            &mut conn,
+            "proj",
+            "main",
+            "job-1",
+            &[record("old.bin", "binary")],
+        )
+        .unwrap();
+        replace_for_job(
+            &mut conn,
+            "proj",
+            "main",
+            "job-2",
+            &[
+                record("docs/readme.md", "unsupported_language"),
+                record("assets/big.rs", "too_large"),
+                record("a.md", "unsupported_language"),
+            ],
+        )
+        .unwrap();
+
+        let all = list_for_ref(&conn, "proj", "main", None, 0, 10).unwrap();
+        assert_eq!(all.job_id.as_deref(), Some("job-2"));
+        assert_eq!(all.total, 3);
+        let paths: Vec<&str> = all.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["a.md", "assets/big.rs", "docs/readme.md"]);
+
+        let filtered =
+            list_for_ref(&conn, "proj", "main", Some("unsupported_language"), 1, 10).unwrap();
+        assert_eq!(filtered.total, 2);
+        assert_eq!(filtered.files.len(), 1);
+        assert_eq!(filtered.files[0].path, "docs/readme.md");
+
+        let other_ref = list_for_ref(&conn, "proj", "feat", None, 0, 10).unwrap();
+        assert_eq!(other_ref.job_id, None);
+        assert_eq!(other_ref.total, 0);
+    }
+}
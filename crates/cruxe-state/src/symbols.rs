@@ -1,13 +1,44 @@
 use cruxe_core::error::StateError;
-use cruxe_core::types::SymbolRecord;
+use cruxe_core::types::{ParsedSignature, SymbolRecord};
 use rusqlite::{Connection, params};
+use std::collections::{BTreeMap, HashSet};
 
-/// Insert a symbol relation record.
+/// Insert a symbol relation record, or update in place the row that already
+/// holds its `symbol_stable_id` and kind for the repo/ref.
+///
+/// The stable id is derived from language, kind, qualified name and signature,
+/// never from line numbers, so a symbol that only moved keeps its row (and
+/// rowid) while its location columns are refreshed. A different symbol still
+/// sitting at the same path/qualified name/kind/line is replaced.
 pub fn insert_symbol(conn: &Connection, sym: &SymbolRecord) -> Result<(), StateError> {
     conn.execute(
-        "INSERT OR REPLACE INTO symbol_relations
-         (repo, \"ref\", \"commit\", path, symbol_id, symbol_stable_id, name, qualified_name, kind, language, line_start, line_end, signature, parent_symbol_id, visibility, content, content_hash)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
+        "DELETE FROM symbol_relations
+         WHERE repo = ?1 AND \"ref\" = ?2 AND path = ?3 AND qualified_name = ?4
+           AND kind = ?5 AND line_start = ?6 AND symbol_stable_id <> ?7",
+        params![
+            sym.repo,
+            sym.r#ref,
+            sym.path,
+            sym.qualified_name,
+            sym.kind.as_str(),
+            sym.line_start,
+            sym.symbol_stable_id,
+        ],
+    )
+    .map_err(StateError::sqlite)?;
+    conn.execute(
+        "INSERT INTO symbol_relations
+         (repo, \"ref\", \"commit\", path, symbol_id, symbol_stable_id, name, qualified_name, kind, language, line_start, line_end, signature, parent_symbol_id, visibility, content, content_hash, attributes, byte_start, byte_end, doc_comment, parsed_signature)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22)
+         ON CONFLICT(repo, \"ref\", symbol_stable_id, kind) DO UPDATE SET
+            \"commit\" = excluded.\"commit\", path = excluded.path, symbol_id = excluded.symbol_id,
+            name = excluded.name, qualified_name = excluded.qualified_name, language = excluded.language,
+            line_start = excluded.line_start, line_end = excluded.line_end, signature = excluded.signature,
+            parent_symbol_id = excluded.parent_symbol_id, visibility = excluded.visibility,
+            content = excluded.content, content_hash = excluded.content_hash,
+            attributes = excluded.attributes, byte_start = excluded.byte_start,
+            byte_end = excluded.byte_end, doc_comment = excluded.doc_comment,
+            parsed_signature = excluded.parsed_signature",
         params![
             sym.repo,
             sym.r#ref,
@@ -28,11 +59,67 @@ pub fn insert_symbol(conn: &Connection, sym: &SymbolRecord) -> Result<(), StateE
             sym.content.as_deref().map(|c| {
                 blake3::hash(c.as_bytes()).to_hex().to_string()
             }).unwrap_or_default(),
+            encode_attributes(&sym.attributes),
+            sym.byte_start,
+            sym.byte_end,
+            sym.doc_comment,
+            encode_parsed_signature(sym.parsed_signature.as_ref()),
         ],
     ).map_err(StateError::sqlite)?;
     Ok(())
 }
 
+/// Make `symbols` the full symbol set for repo/ref/path.
+///
+/// Rows whose stable id and kind are not in `symbols` are deleted; the rest
+/// are upserted via [`insert_symbol`], so unchanged symbols keep their rows
+/// across re-indexing even when their lines shift.
+pub fn replace_symbols_for_file(
+    conn: &Connection,
+    repo: &str,
+    r#ref: &str,
+    path: &str,
+    symbols: &[SymbolRecord],
+) -> Result<(), StateError> {
+    let keep: HashSet<(&str, &str)> = symbols
+        .iter()
+        .map(|sym| (sym.symbol_stable_id.as_str(), sym.kind.as_str()))
+        .collect();
+    let stale: Vec<i64> = {
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, symbol_stable_id, kind FROM symbol_relations
+                 WHERE repo = ?1 AND \"ref\" = ?2 AND path = ?3",
+            )
+            .map_err(StateError::sqlite)?;
+        let rows = stmt
+            .query_map(params![repo, r#ref, path], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })
+            .map_err(StateError::sqlite)?;
+        let mut stale = Vec::new();
+        for row in rows {
+            let (id, stable_id, kind) = row.map_err(StateError::sqlite)?;
+            if !keep.contains(&(stable_id.as_str(), kind.as_str())) {
+                stale.push(id);
+            }
+        }
+        stale
+    };
+    for id in stale {
+        conn.execute("DELETE FROM symbol_relations WHERE id = ?1", params![id])
+            .map_err(StateError::sqlite)?;
+    }
+    for sym in symbols {
+        insert_symbol(conn, sym)?;
+    }
+    Ok(())
+}
+
 /// Look up symbols by name for a repo/ref, used for dual-index join.
 pub fn find_symbols_by_location(
     conn: &Connection,
@@ -43,7 +130,7 @@ pub fn find_symbols_by_location(
     line_end: u32,
 ) -> Result<Vec<SymbolRecord>, StateError> {
     let mut stmt = conn.prepare(
-        "SELECT repo, \"ref\", \"commit\", path, symbol_id, symbol_stable_id, name, qualified_name, kind, language, line_start, line_end, signature, parent_symbol_id, visibility
+        "SELECT repo, \"ref\", \"commit\", path, symbol_id, symbol_stable_id, name, qualified_name, kind, language, line_start, line_end, signature, parent_symbol_id, visibility, attributes, byte_start, byte_end, doc_comment, parsed_signature
          FROM symbol_relations
          WHERE repo = ?1 AND \"ref\" = ?2 AND path = ?3
          AND line_start <= ?5 AND line_end >= ?4"
@@ -60,6 +147,60 @@ pub fn find_symbols_by_location(
         .map_err(StateError::sqlite)
 }
 
+/// Find the innermost symbol whose byte range contains `byte_offset` in a file.
+///
+/// Symbols indexed before byte ranges were recorded never match.
+pub fn find_innermost_symbol_at_offset(
+    conn: &Connection,
+    repo: &str,
+    r#ref: &str,
+    path: &str,
+    byte_offset: u32,
+) -> Result<Option<SymbolRecord>, StateError> {
+    let mut stmt = conn.prepare(
+        "SELECT repo, \"ref\", \"commit\", path, symbol_id, symbol_stable_id, name, qualified_name, kind, language, line_start, line_end, signature, parent_symbol_id, visibility, attributes, byte_start, byte_end, doc_comment, parsed_signature
+         FROM symbol_relations
+         WHERE repo = ?1 AND \"ref\" = ?2 AND path = ?3
+         AND byte_start <= ?4 AND byte_end > ?4
+         ORDER BY (byte_end - byte_start) ASC, byte_start DESC
+         LIMIT 1"
+    ).map_err(StateError::sqlite)?;
+
+    let mut rows = stmt
+        .query_map(
+            params![repo, r#ref, path, byte_offset],
+            row_to_symbol_record,
+        )
+        .map_err(StateError::sqlite)?;
+    rows.next().transpose().map_err(StateError::sqlite)
+}
+
+/// Find the innermost symbol whose line range contains `line` in a file.
+///
+/// Overlapping symbols resolve to the tightest range; ties go to the one
+/// starting last, which is the more deeply nested.
+pub fn find_innermost_symbol_at_line(
+    conn: &Connection,
+    repo: &str,
+    r#ref: &str,
+    path: &str,
+    line: u32,
+) -> Result<Option<SymbolRecord>, StateError> {
+    let mut stmt = conn.prepare(
+        "SELECT repo, \"ref\", \"commit\", path, symbol_id, symbol_stable_id, name, qualified_name, kind, language, line_start, line_end, signature, parent_symbol_id, visibility, attributes, byte_start, byte_end, doc_comment, parsed_signature
+         FROM symbol_relations
+         WHERE repo = ?1 AND \"ref\" = ?2 AND path = ?3
+         AND line_start <= ?4 AND line_end >= ?4
+         ORDER BY (line_end - line_start) ASC, line_start DESC
+         LIMIT 1"
+    ).map_err(StateError::sqlite)?;
+
+    let mut rows = stmt
+        .query_map(params![repo, r#ref, path, line], row_to_symbol_record)
+        .map_err(StateError::sqlite)?;
+    rows.next().transpose().map_err(StateError::sqlite)
+}
+
 /// Delete all symbols for a given repo/ref/path.
 pub fn delete_symbols_for_file(
     conn: &Connection,
@@ -87,6 +228,39 @@ pub fn symbol_count(conn: &Connection, repo: &str, r#ref: &str) -> Result<u64, S
     Ok(count as u64)
 }
 
+/// Count symbols per language for a repo/ref.
+///
+/// Languages that appear in the file manifest but yielded no symbols are
+/// reported with a count of 0, so extraction gaps stay visible.
+pub fn symbol_count_by_language(
+    conn: &Connection,
+    repo: &str,
+    r#ref: &str,
+) -> Result<BTreeMap<String, u64>, StateError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT language, COUNT(*) FROM symbol_relations
+             WHERE repo = ?1 AND \"ref\" = ?2
+             GROUP BY language
+             UNION ALL
+             SELECT DISTINCT language, 0 FROM file_manifest
+             WHERE repo = ?1 AND \"ref\" = ?2 AND language IS NOT NULL",
+        )
+        .map_err(StateError::sqlite)?;
+    let rows = stmt
+        .query_map(params![repo, r#ref], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as u64))
+        })
+        .map_err(StateError::sqlite)?;
+
+    let mut counts = BTreeMap::new();
+    for row in rows {
+        let (language, count) = row.map_err(StateError::sqlite)?;
+        *counts.entry(language).or_insert(0) += count;
+    }
+    Ok(counts)
+}
+
 /// Find symbols by exact name in a repo/ref scope.
 /// If `path` is provided, results are constrained to that file.
 pub fn find_symbols_by_name(
@@ -99,7 +273,7 @@ pub fn find_symbols_by_name(
     if let Some(path) = path {
         let mut stmt = conn
             .prepare(
-                "SELECT repo, \"ref\", \"commit\", path, symbol_id, symbol_stable_id, name, qualified_name, kind, language, line_start, line_end, signature, parent_symbol_id, visibility
+                "SELECT repo, \"ref\", \"commit\", path, symbol_id, symbol_stable_id, name, qualified_name, kind, language, line_start, line_end, signature, parent_symbol_id, visibility, attributes, byte_start, byte_end, doc_comment, parsed_signature
                  FROM symbol_relations
                  WHERE repo = ?1 AND \"ref\" = ?2 AND name = ?3 AND path = ?4
                  ORDER BY line_start",
@@ -113,7 +287,7 @@ pub fn find_symbols_by_name(
     } else {
         let mut stmt = conn
             .prepare(
-                "SELECT repo, \"ref\", \"commit\", path, symbol_id, symbol_stable_id, name, qualified_name, kind, language, line_start, line_end, signature, parent_symbol_id, visibility
+                "SELECT repo, \"ref\", \"commit\", path, symbol_id, symbol_stable_id, name, qualified_name, kind, language, line_start, line_end, signature, parent_symbol_id, visibility, attributes, byte_start, byte_end, doc_comment, parsed_signature
                  FROM symbol_relations
                  WHERE repo = ?1 AND \"ref\" = ?2 AND name = ?3
                  ORDER BY path, line_start",
@@ -136,7 +310,7 @@ pub fn get_symbol_by_id(
 ) -> Result<Option<SymbolRecord>, StateError> {
     let mut stmt = conn
         .prepare(
-            "SELECT repo, \"ref\", \"commit\", path, symbol_id, symbol_stable_id, name, qualified_name, kind, language, line_start, line_end, signature, parent_symbol_id, visibility
+            "SELECT repo, \"ref\", \"commit\", path, symbol_id, symbol_stable_id, name, qualified_name, kind, language, line_start, line_end, signature, parent_symbol_id, visibility, attributes, byte_start, byte_end, doc_comment, parsed_signature
              FROM symbol_relations
              WHERE repo = ?1 AND \"ref\" = ?2 AND symbol_id = ?3
              LIMIT 1",
@@ -158,7 +332,7 @@ pub fn get_symbol_by_stable_id(
 ) -> Result<Option<SymbolRecord>, StateError> {
     let mut stmt = conn
         .prepare(
-            "SELECT repo, \"ref\", \"commit\", path, symbol_id, symbol_stable_id, name, qualified_name, kind, language, line_start, line_end, signature, parent_symbol_id, visibility
+            "SELECT repo, \"ref\", \"commit\", path, symbol_id, symbol_stable_id, name, qualified_name, kind, language, line_start, line_end, signature, parent_symbol_id, visibility, attributes, byte_start, byte_end, doc_comment, parsed_signature
              FROM symbol_relations
              WHERE repo = ?1 AND \"ref\" = ?2 AND symbol_stable_id = ?3
              LIMIT 1",
@@ -171,6 +345,34 @@ pub fn get_symbol_by_stable_id(
     }
 }
 
+/// Fetch a symbol by symbol_stable_id together with its stored body (`content`).
+pub fn get_symbol_with_content_by_stable_id(
+    conn: &Connection,
+    repo: &str,
+    r#ref: &str,
+    symbol_stable_id: &str,
+) -> Result<Option<SymbolRecord>, StateError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT repo, \"ref\", \"commit\", path, symbol_id, symbol_stable_id, name, qualified_name, kind, language, line_start, line_end, signature, parent_symbol_id, visibility, attributes, byte_start, byte_end, doc_comment, parsed_signature, content
+             FROM symbol_relations
+             WHERE repo = ?1 AND \"ref\" = ?2 AND symbol_stable_id = ?3
+             ORDER BY path, line_start
+             LIMIT 1",
+        )
+        .map_err(StateError::sqlite)?;
+    let result = stmt.query_row(params![repo, r#ref, symbol_stable_id], |row| {
+        let mut symbol = row_to_symbol_record(row)?;
+        symbol.content = row.get(20)?;
+        Ok(symbol)
+    });
+    match result {
+        Ok(symbol) => Ok(Some(symbol)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(StateError::sqlite(e)),
+    }
+}
+
 /// List immediate children for a parent symbol.
 pub fn get_children_symbols(
     conn: &Connection,
@@ -180,7 +382,7 @@ pub fn get_children_symbols(
 ) -> Result<Vec<SymbolRecord>, StateError> {
     let mut stmt = conn
         .prepare(
-            "SELECT repo, \"ref\", \"commit\", path, symbol_id, symbol_stable_id, name, qualified_name, kind, language, line_start, line_end, signature, parent_symbol_id, visibility
+            "SELECT repo, \"ref\", \"commit\", path, symbol_id, symbol_stable_id, name, qualified_name, kind, language, line_start, line_end, signature, parent_symbol_id, visibility, attributes, byte_start, byte_end, doc_comment, parsed_signature
              FROM symbol_relations
              WHERE repo = ?1 AND \"ref\" = ?2 AND parent_symbol_id = ?3
              ORDER BY line_start",
@@ -202,7 +404,7 @@ pub fn list_symbols_in_file(
 ) -> Result<Vec<SymbolRecord>, StateError> {
     let mut stmt = conn
         .prepare(
-            "SELECT repo, \"ref\", \"commit\", path, symbol_id, symbol_stable_id, name, qualified_name, kind, language, line_start, line_end, signature, parent_symbol_id, visibility
+            "SELECT repo, \"ref\", \"commit\", path, symbol_id, symbol_stable_id, name, qualified_name, kind, language, line_start, line_end, signature, parent_symbol_id, visibility, attributes, byte_start, byte_end, doc_comment, parsed_signature
              FROM symbol_relations
              WHERE repo = ?1 AND \"ref\" = ?2 AND path = ?3
              ORDER BY line_start",
@@ -215,6 +417,44 @@ pub fn list_symbols_in_file(
         .map_err(StateError::sqlite)
 }
 
+/// List class and interface declarations in `languages`, with their stored
+/// source in `content` so callers can read declared base types.
+pub fn list_type_declarations(
+    conn: &Connection,
+    repo: &str,
+    r#ref: &str,
+    languages: &[&str],
+) -> Result<Vec<SymbolRecord>, StateError> {
+    if languages.is_empty() {
+        return Ok(Vec::new());
+    }
+    let placeholders = std::iter::repeat_n("?", languages.len())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let sql = format!(
+        "SELECT repo, \"ref\", \"commit\", path, symbol_id, symbol_stable_id, name, qualified_name, kind, language, line_start, line_end, signature, parent_symbol_id, visibility, attributes, byte_start, byte_end, doc_comment, parsed_signature, content
+         FROM symbol_relations
+         WHERE repo = ? AND \"ref\" = ? AND kind IN ('class', 'interface') AND language IN ({placeholders})
+         ORDER BY path, line_start"
+    );
+    let mut stmt = conn.prepare(&sql).map_err(StateError::sqlite)?;
+    let mut bind_params: Vec<&dyn rusqlite::ToSql> = Vec::with_capacity(2 + languages.len());
+    bind_params.push(&repo);
+    bind_params.push(&r#ref);
+    for language in languages {
+        bind_params.push(language);
+    }
+    let rows = stmt
+        .query_map(rusqlite::params_from_iter(bind_params), |row| {
+            let mut record = row_to_symbol_record(row)?;
+            record.content = row.get(20)?;
+            Ok(record)
+        })
+        .map_err(StateError::sqlite)?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(StateError::sqlite)
+}
+
 /// List symbols under a path prefix (used for module/package scopes).
 pub fn list_symbols_by_path_prefix(
     conn: &Connection,
@@ -225,7 +465,7 @@ pub fn list_symbols_by_path_prefix(
     let like_pattern = format!("{path_prefix}%");
     let mut stmt = conn
         .prepare(
-            "SELECT repo, \"ref\", \"commit\", path, symbol_id, symbol_stable_id, name, qualified_name, kind, language, line_start, line_end, signature, parent_symbol_id, visibility
+            "SELECT repo, \"ref\", \"commit\", path, symbol_id, symbol_stable_id, name, qualified_name, kind, language, line_start, line_end, signature, parent_symbol_id, visibility, attributes, byte_start, byte_end, doc_comment, parsed_signature
              FROM symbol_relations
              WHERE repo = ?1 AND \"ref\" = ?2 AND path LIKE ?3
              ORDER BY path, line_start",
@@ -238,6 +478,251 @@ pub fn list_symbols_by_path_prefix(
         .map_err(StateError::sqlite)
 }
 
+/// Candidate symbols for a module-scoped view: those whose qualified name
+/// starts with `qualified_prefix`, plus every symbol in a file whose path
+/// contains `path_fragment`. Callers apply the exact module match.
+pub fn list_module_scope_candidates(
+    conn: &Connection,
+    repo: &str,
+    r#ref: &str,
+    qualified_prefix: Option<&str>,
+    path_fragment: Option<&str>,
+) -> Result<Vec<SymbolRecord>, StateError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT repo, \"ref\", \"commit\", path, symbol_id, symbol_stable_id, name, qualified_name, kind, language, line_start, line_end, signature, parent_symbol_id, visibility, attributes, byte_start, byte_end, doc_comment, parsed_signature
+             FROM symbol_relations
+             WHERE repo = ?1 AND \"ref\" = ?2
+               AND ((?3 IS NOT NULL AND substr(qualified_name, 1, length(?3)) = ?3)
+                    OR (?4 IS NOT NULL AND instr(path, ?4) > 0))
+             ORDER BY path, line_start",
+        )
+        .map_err(StateError::sqlite)?;
+    let rows = stmt
+        .query_map(
+            params![repo, r#ref, qualified_prefix, path_fragment],
+            row_to_symbol_record,
+        )
+        .map_err(StateError::sqlite)?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(StateError::sqlite)
+}
+
+/// Compact `(qualified_name, kind, path, line_start)` entry for symbol-name exports.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SymbolNameEntry {
+    pub qualified_name: String,
+    pub kind: String,
+    pub path: String,
+    pub line_start: u32,
+}
+
+/// Page through every symbol name for a repo/ref, ordered by qualified name so
+/// consecutive pages can be merged into a prefix tree without re-sorting.
+pub fn list_symbol_names_page(
+    conn: &Connection,
+    repo: &str,
+    r#ref: &str,
+    offset: u64,
+    limit: usize,
+) -> Result<Vec<SymbolNameEntry>, StateError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT qualified_name, kind, path, line_start
+             FROM symbol_relations
+             WHERE repo = ?1 AND \"ref\" = ?2
+             ORDER BY qualified_name, path, line_start, symbol_id
+             LIMIT ?3 OFFSET ?4",
+        )
+        .map_err(StateError::sqlite)?;
+    let rows = stmt
+        .query_map(params![repo, r#ref, limit as i64, offset as i64], |row| {
+            Ok(SymbolNameEntry {
+                qualified_name: row.get(0)?,
+                kind: row.get(1)?,
+                path: row.get(2)?,
+                line_start: row.get(3)?,
+            })
+        })
+        .map_err(StateError::sqlite)?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(StateError::sqlite)
+}
+
+/// Ordering for [`list_large_symbols`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LargeSymbolSort {
+    /// Line span, largest first.
+    Lines,
+    /// Stored body length in bytes, largest first.
+    BodyBytes,
+    /// Crude nesting-depth estimate of the stored body, deepest first.
+    Nesting,
+}
+
+impl LargeSymbolSort {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Lines => "lines",
+            Self::BodyBytes => "body_bytes",
+            Self::Nesting => "nesting",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw {
+            "lines" => Some(Self::Lines),
+            "body_bytes" => Some(Self::BodyBytes),
+            "nesting" => Some(Self::Nesting),
+            _ => None,
+        }
+    }
+}
+
+/// Filters for [`list_large_symbols`].
+#[derive(Debug, Clone, Copy)]
+pub struct LargeSymbolQuery<'a> {
+    /// Minimum number of lines (inclusive) a symbol must span.
+    pub min_lines: u32,
+    pub path_prefix: Option<&'a str>,
+    pub language: Option<&'a str>,
+    pub sort: LargeSymbolSort,
+    pub limit: usize,
+}
+
+/// A symbol reported by [`list_large_symbols`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct LargeSymbolEntry {
+    pub symbol_id: String,
+    pub qualified_name: String,
+    pub kind: String,
+    pub language: String,
+    pub path: String,
+    pub line_start: u32,
+    pub line_end: u32,
+    pub line_count: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_nesting: Option<u32>,
+}
+
+/// List symbols spanning at least `query.min_lines` lines, largest first.
+///
+/// `body_bytes` and `max_nesting` are derived from the stored body and are
+/// `None` for symbols indexed without one.
+pub fn list_large_symbols(
+    conn: &Connection,
+    repo: &str,
+    r#ref: &str,
+    query: LargeSymbolQuery<'_>,
+) -> Result<Vec<LargeSymbolEntry>, StateError> {
+    let order_by = match query.sort {
+        LargeSymbolSort::Lines | LargeSymbolSort::Nesting => {
+            "(line_end - line_start) DESC, path, line_start"
+        }
+        LargeSymbolSort::BodyBytes => "LENGTH(content) DESC, path, line_start",
+    };
+    // Nesting is computed in Rust, so every candidate has to be ranked before
+    // the limit applies.
+    let sql_limit = match query.sort {
+        LargeSymbolSort::Nesting => -1,
+        _ => query.limit.min(i64::MAX as usize) as i64,
+    };
+    let like_pattern = query.path_prefix.map(|prefix| format!("{prefix}%"));
+    let sql = format!(
+        "SELECT symbol_id, qualified_name, kind, language, path, line_start, line_end, content
+         FROM symbol_relations
+         WHERE repo = ?1 AND \"ref\" = ?2
+         AND line_end - line_start + 1 >= ?3
+         AND (?4 IS NULL OR path LIKE ?4)
+         AND (?5 IS NULL OR language = ?5)
+         ORDER BY {order_by}
+         LIMIT ?6"
+    );
+    let mut stmt = conn.prepare(&sql).map_err(StateError::sqlite)?;
+    let rows = stmt
+        .query_map(
+            params![
+                repo,
+                r#ref,
+                query.min_lines,
+                like_pattern,
+                query.language,
+                sql_limit
+            ],
+            |row| {
+                let line_start: u32 = row.get(5)?;
+                let line_end: u32 = row.get(6)?;
+                let content: Option<String> = row.get(7)?;
+                Ok(LargeSymbolEntry {
+                    symbol_id: row.get(0)?,
+                    qualified_name: row.get(1)?,
+                    kind: row.get(2)?,
+                    language: row.get(3)?,
+                    path: row.get(4)?,
+                    line_start,
+                    line_end,
+                    line_count: line_end.saturating_sub(line_start) + 1,
+                    body_bytes: content.as_ref().map(|body| body.len() as u64),
+                    max_nesting: content.as_deref().map(estimate_nesting_depth),
+                })
+            },
+        )
+        .map_err(StateError::sqlite)?;
+    let mut entries = rows
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(StateError::sqlite)?;
+
+    if query.sort == LargeSymbolSort::Nesting {
+        // Stable sort keeps the line-span order among equal depths.
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.max_nesting));
+        entries.truncate(query.limit);
+    }
+    Ok(entries)
+}
+
+/// Crude nesting-depth estimate for a symbol body.
+///
+/// Counts `{`/`}` depth when the body uses braces; otherwise falls back to
+/// indentation levels relative to the first line (tab or four spaces per
+/// level). String literals and comments are not excluded.
+fn estimate_nesting_depth(body: &str) -> u32 {
+    let mut depth: u32 = 0;
+    let mut max_depth: u32 = 0;
+    let mut saw_brace = false;
+    for ch in body.chars() {
+        match ch {
+            '{' => {
+                saw_brace = true;
+                depth += 1;
+                max_depth = max_depth.max(depth);
+            }
+            '}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    if saw_brace {
+        return max_depth;
+    }
+
+    let indent_width = |line: &str| -> u32 {
+        line.chars()
+            .take_while(|ch| ch.is_whitespace())
+            .map(|ch| if ch == '\t' { 4 } else { 1 })
+            .sum()
+    };
+    let mut lines = body.lines().filter(|line| !line.trim().is_empty());
+    let Some(first) = lines.next() else {
+        return 0;
+    };
+    let base = indent_width(first);
+    lines
+        .map(|line| indent_width(line).saturating_sub(base).div_ceil(4))
+        .max()
+        .unwrap_or(0)
+}
+
 fn row_to_symbol_record(row: &rusqlite::Row<'_>) -> rusqlite::Result<SymbolRecord> {
     Ok(SymbolRecord {
         repo: row.get(0)?,
@@ -253,13 +738,37 @@ fn row_to_symbol_record(row: &rusqlite::Row<'_>) -> rusqlite::Result<SymbolRecor
         language: row.get(9)?,
         line_start: row.get(10)?,
         line_end: row.get(11)?,
+        byte_start: row.get(16)?,
+        byte_end: row.get(17)?,
         signature: row.get(12)?,
         parent_symbol_id: row.get(13)?,
         visibility: row.get(14)?,
         content: None,
+        attributes: decode_attributes(row.get(15)?),
+        doc_comment: row.get(18)?,
+        parsed_signature: row
+            .get::<_, Option<String>>(19)?
+            .and_then(|raw| serde_json::from_str(&raw).ok()),
     })
 }
 
+/// Attributes are stored as a JSON array; symbols without any store NULL.
+fn encode_attributes(attributes: &[String]) -> Option<String> {
+    if attributes.is_empty() {
+        return None;
+    }
+    serde_json::to_string(attributes).ok()
+}
+
+fn encode_parsed_signature(signature: Option<&ParsedSignature>) -> Option<String> {
+    signature.and_then(|signature| serde_json::to_string(signature).ok())
+}
+
+fn decode_attributes(raw: Option<String>) -> Vec<String> {
+    raw.and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
 /// A lightweight symbol record for file outlines (avoids full SymbolRecord overhead).
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct OutlineSymbol {
@@ -277,6 +786,8 @@ pub struct OutlineSymbol {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub parent_symbol_id: Option<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub children: Vec<OutlineSymbol>,
 }
 
@@ -290,12 +801,12 @@ pub fn get_file_outline_query(
     top_only: bool,
 ) -> Result<Vec<OutlineSymbol>, StateError> {
     let sql = if top_only {
-        "SELECT symbol_id, name, qualified_name, kind, language, line_start, line_end, signature, parent_symbol_id, visibility
+        "SELECT symbol_id, name, qualified_name, kind, language, line_start, line_end, signature, parent_symbol_id, visibility, attributes
          FROM symbol_relations
          WHERE repo = ?1 AND \"ref\" = ?2 AND path = ?3 AND parent_symbol_id IS NULL
          ORDER BY line_start"
     } else {
-        "SELECT symbol_id, name, qualified_name, kind, language, line_start, line_end, signature, parent_symbol_id, visibility
+        "SELECT symbol_id, name, qualified_name, kind, language, line_start, line_end, signature, parent_symbol_id, visibility, attributes
          FROM symbol_relations
          WHERE repo = ?1 AND \"ref\" = ?2 AND path = ?3
          ORDER BY line_start"
@@ -315,6 +826,7 @@ pub fn get_file_outline_query(
                 signature: row.get(7)?,
                 parent_symbol_id: row.get(8)?,
                 visibility: row.get(9)?,
+                attributes: decode_attributes(row.get(10)?),
                 children: Vec::new(),
             })
         })
@@ -325,17 +837,124 @@ pub fn get_file_outline_query(
         .map_err(StateError::sqlite)
 }
 
+/// Bounds for [`list_directory_outline`].
+#[derive(Debug, Clone, Copy)]
+pub struct DirectoryOutlineQuery<'a> {
+    /// Directory (or file-path prefix) to outline; empty or `.` means the whole ref.
+    pub path_prefix: &'a str,
+    pub max_files: usize,
+    pub max_symbols_per_file: usize,
+}
+
+/// Top-level symbols of one file in a [`DirectoryOutline`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DirectoryOutlineFile {
+    pub path: String,
+    pub language: String,
+    /// Top-level symbols in the file, before the per-file cap.
+    pub symbol_count: u32,
+    pub symbols: Vec<OutlineSymbol>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub symbols_truncated: bool,
+}
+
+/// Files under a directory with their top-level symbols, sorted by path.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DirectoryOutline {
+    pub files: Vec<DirectoryOutlineFile>,
+    /// Files with symbols under the prefix, before `max_files` applies.
+    pub total_files: u32,
+    /// True when `max_files` cut off part of the subtree.
+    pub truncated: bool,
+}
+
+/// Outline every file under `query.path_prefix`: its top-level symbols in line
+/// order, files in path order. A directory prefix matches on whole path
+/// segments, so `src/auth` covers `src/auth/mod.rs` but not `src/authz.rs`.
+pub fn list_directory_outline(
+    conn: &Connection,
+    repo: &str,
+    r#ref: &str,
+    query: DirectoryOutlineQuery<'_>,
+) -> Result<DirectoryOutline, StateError> {
+    let prefix = query.path_prefix.trim().trim_matches('/');
+    let prefix = if prefix == "." { "" } else { prefix };
+    let dir_prefix = format!("{prefix}/");
+    let scope = "repo = ?1 AND \"ref\" = ?2 AND parent_symbol_id IS NULL
+         AND (?3 = '' OR path = ?3 OR substr(path, 1, length(?4)) = ?4)";
+
+    let total_files: u32 = conn
+        .query_row(
+            &format!("SELECT COUNT(DISTINCT path) FROM symbol_relations WHERE {scope}"),
+            params![repo, r#ref, prefix, dir_prefix],
+            |row| row.get(0),
+        )
+        .map_err(StateError::sqlite)?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT path, MIN(language), COUNT(*) FROM symbol_relations WHERE {scope}
+             GROUP BY path ORDER BY path LIMIT ?5"
+        ))
+        .map_err(StateError::sqlite)?;
+    let file_rows = stmt
+        .query_map(
+            params![
+                repo,
+                r#ref,
+                prefix,
+                dir_prefix,
+                query.max_files.min(i64::MAX as usize) as i64
+            ],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, u32>(2)?,
+                ))
+            },
+        )
+        .map_err(StateError::sqlite)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(StateError::sqlite)?;
+
+    let mut files = Vec::with_capacity(file_rows.len());
+    for (path, language, symbol_count) in file_rows {
+        let mut symbols = get_file_outline_query(conn, repo, r#ref, &path, true)?;
+        let symbols_truncated = symbols.len() > query.max_symbols_per_file;
+        symbols.truncate(query.max_symbols_per_file);
+        files.push(DirectoryOutlineFile {
+            path,
+            language,
+            symbol_count,
+            symbols,
+            symbols_truncated,
+        });
+    }
+
+    Ok(DirectoryOutline {
+        truncated: (files.len() as u32) < total_files,
+        files,
+        total_files,
+    })
+}
+
 /// Build a nested symbol tree from a flat list using parent_symbol_id chains.
+///
+/// Symbols whose parent is not in `flat` are kept as roots rather than dropped.
 pub fn build_symbol_tree(flat: Vec<OutlineSymbol>) -> Vec<OutlineSymbol> {
-    use std::collections::HashMap;
+    use std::collections::{HashMap, HashSet};
+
+    let known_ids: HashSet<String> = flat.iter().map(|sym| sym.symbol_id.clone()).collect();
 
     // Group symbols by their parent_symbol_id
     let mut children_map: HashMap<Option<String>, Vec<OutlineSymbol>> = HashMap::new();
     for sym in flat {
-        children_map
-            .entry(sym.parent_symbol_id.clone())
-            .or_default()
-            .push(sym);
+        let parent = sym
+            .parent_symbol_id
+            .clone()
+            .filter(|parent| known_ids.contains(parent));
+        children_map.entry(parent).or_default().push(sym);
     }
 
     // Recursively assemble the tree starting from root symbols (parent_symbol_id = None)
@@ -389,6 +1008,11 @@ mod tests {
             parent_symbol_id: None,
             visibility: Some("pub".to_string()),
             content: Some("fn my_function(x: i32) -> bool { x > 0 }".to_string()),
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
         }
     }
 
@@ -424,6 +1048,141 @@ mod tests {
         assert_eq!(found[0].visibility, Some("pub".to_string()));
     }
 
+    #[test]
+    fn test_attributes_round_trip_through_storage() {
+        let conn = setup_test_db();
+        let mut sym = sample_symbol();
+        sym.attributes = vec!["test".to_string(), "cfg(feature = \"x\")".to_string()];
+        insert_symbol(&conn, &sym).unwrap();
+
+        let found = get_symbol_by_id(&conn, &sym.repo, &sym.r#ref, &sym.symbol_id)
+            .unwrap()
+            .expect("symbol present");
+        assert_eq!(found.attributes, sym.attributes);
+
+        let plain = SymbolRecord {
+            symbol_id: "sym_002".to_string(),
+            symbol_stable_id: "stable_002".to_string(),
+            name: "plain".to_string(),
+            qualified_name: "crate::plain".to_string(),
+            line_start: 30,
+            line_end: 31,
+            ..sample_symbol()
+        };
+        insert_symbol(&conn, &plain).unwrap();
+        let found = get_symbol_by_id(&conn, &plain.repo, &plain.r#ref, &plain.symbol_id)
+            .unwrap()
+            .expect("symbol present");
+        assert!(found.attributes.is_empty());
+    }
+
+    #[test]
+    fn test_doc_comment_round_trips_through_storage() {
+        let conn = setup_test_db();
+        let mut sym = sample_symbol();
+        sym.doc_comment = Some("Checks the thing.\n\nPanics never.".to_string());
+        insert_symbol(&conn, &sym).unwrap();
+
+        let found = get_symbol_by_id(&conn, &sym.repo, &sym.r#ref, &sym.symbol_id)
+            .unwrap()
+            .expect("symbol present");
+        assert_eq!(found.doc_comment, sym.doc_comment);
+    }
+
+    #[test]
+    fn test_parsed_signature_round_trips_through_storage() {
+        let conn = setup_test_db();
+        let mut sym = sample_symbol();
+        sym.parsed_signature = Some(cruxe_core::types::ParsedSignature {
+            generics: vec!["T".to_string()],
+            receiver: None,
+            params: vec![cruxe_core::types::SignatureParam {
+                name: Some("value".to_string()),
+                r#type: Some("T".to_string()),
+            }],
+            return_type: Some("bool".to_string()),
+        });
+        insert_symbol(&conn, &sym).unwrap();
+
+        let found = get_symbol_by_id(&conn, &sym.repo, &sym.r#ref, &sym.symbol_id)
+            .unwrap()
+            .expect("symbol present");
+        assert_eq!(found.parsed_signature, sym.parsed_signature);
+    }
+
+    #[test]
+    fn test_find_innermost_symbol_at_offset_prefers_narrowest_range() {
+        let conn = setup_test_db();
+        let outer = SymbolRecord {
+            byte_start: Some(100),
+            byte_end: Some(500),
+            ..sample_symbol()
+        };
+        let inner = SymbolRecord {
+            symbol_id: "sym_inner".to_string(),
+            symbol_stable_id: "stable_inner".to_string(),
+            name: "inner".to_string(),
+            qualified_name: "crate::my_function::inner".to_string(),
+            line_start: 12,
+            line_end: 14,
+            byte_start: Some(200),
+            byte_end: Some(260),
+            ..sample_symbol()
+        };
+        let legacy = SymbolRecord {
+            symbol_id: "sym_legacy".to_string(),
+            symbol_stable_id: "stable_legacy".to_string(),
+            name: "legacy".to_string(),
+            qualified_name: "crate::legacy".to_string(),
+            line_start: 40,
+            line_end: 50,
+            ..sample_symbol()
+        };
+        for sym in [&outer, &inner, &legacy] {
+            insert_symbol(&conn, sym).unwrap();
+        }
+
+        let at = |offset| {
+            find_innermost_symbol_at_offset(&conn, "my-repo", "main", "src/lib.rs", offset)
+                .unwrap()
+                .map(|sym| sym.name)
+        };
+        assert_eq!(at(210).as_deref(), Some("inner"));
+        assert_eq!(at(260).as_deref(), Some("my_function"));
+        assert_eq!(at(100).as_deref(), Some("my_function"));
+        assert_eq!(at(500), None);
+        assert_eq!(at(10), None);
+    }
+
+    #[test]
+    fn test_find_innermost_symbol_at_line_prefers_tightest_range() {
+        let conn = setup_test_db();
+        let outer = sample_symbol(); // lines 10-25
+        let inner = SymbolRecord {
+            symbol_id: "sym_inner".to_string(),
+            symbol_stable_id: "stable_inner".to_string(),
+            name: "inner".to_string(),
+            qualified_name: "crate::my_function::inner".to_string(),
+            line_start: 12,
+            line_end: 14,
+            ..sample_symbol()
+        };
+        for sym in [&outer, &inner] {
+            insert_symbol(&conn, sym).unwrap();
+        }
+
+        let at = |line| {
+            find_innermost_symbol_at_line(&conn, "my-repo", "main", "src/lib.rs", line)
+                .unwrap()
+                .map(|sym| sym.name)
+        };
+        assert_eq!(at(13).as_deref(), Some("inner"));
+        assert_eq!(at(14).as_deref(), Some("inner"));
+        assert_eq!(at(15).as_deref(), Some("my_function"));
+        assert_eq!(at(10).as_deref(), Some("my_function"));
+        assert_eq!(at(26), None);
+    }
+
     #[test]
     fn test_find_symbols_by_location_overlapping_range() {
         let conn = setup_test_db();
@@ -493,6 +1252,33 @@ mod tests {
         assert_eq!(found[0].name, "renamed_function");
     }
 
+    #[test]
+    fn test_replace_symbols_for_file_swaps_symbols_at_the_same_location() {
+        let conn = setup_test_db();
+        let sym = sample_symbol();
+        insert_symbol(&conn, &sym).unwrap();
+
+        // Same name and line, new signature: a different stable id.
+        let mut changed = sym.clone();
+        changed.symbol_stable_id = "stable_changed".to_string();
+        changed.signature = Some("pub fn my_function(x: u32)".to_string());
+        replace_symbols_for_file(
+            &conn,
+            &sym.repo,
+            &sym.r#ref,
+            &sym.path,
+            std::slice::from_ref(&changed),
+        )
+        .unwrap();
+
+        let rows = list_symbols_in_file(&conn, &sym.repo, &sym.r#ref, &sym.path).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].symbol_stable_id, "stable_changed");
+
+        replace_symbols_for_file(&conn, &sym.repo, &sym.r#ref, &sym.path, &[]).unwrap();
+        assert_eq!(symbol_count(&conn, &sym.repo, &sym.r#ref).unwrap(), 0);
+    }
+
     #[test]
     fn test_delete_symbols_for_file() {
         let conn = setup_test_db();
@@ -567,6 +1353,55 @@ mod tests {
         assert_eq!(symbol_count(&conn, "my-repo", "develop").unwrap(), 0);
     }
 
+    #[test]
+    fn test_symbol_count_by_language_includes_manifest_languages() {
+        let conn = setup_test_db();
+        insert_symbol(&conn, &sample_symbol()).unwrap();
+        let mut second = sample_symbol();
+        second.symbol_id = "sym_002".to_string();
+        second.symbol_stable_id = "stable_002".to_string();
+        second.name = "other".to_string();
+        second.qualified_name = "crate::other".to_string();
+        insert_symbol(&conn, &second).unwrap();
+        let mut python = sample_symbol();
+        python.path = "tools/gen.py".to_string();
+        python.language = "python".to_string();
+        python.symbol_id = "sym_003".to_string();
+        python.symbol_stable_id = "stable_003".to_string();
+        insert_symbol(&conn, &python).unwrap();
+        for (path, language) in [("src/lib.rs", "rust"), ("web/app.ts", "typescript")] {
+            crate::manifest::upsert_manifest(
+                &conn,
+                &crate::manifest::ManifestEntry {
+                    repo: "my-repo".to_string(),
+                    r#ref: "main".to_string(),
+                    path: path.to_string(),
+                    content_hash: "hash".to_string(),
+                    size_bytes: 10,
+                    mtime_ns: None,
+                    language: Some(language.to_string()),
+                    indexed_at: "2026-01-01T00:00:00Z".to_string(),
+                },
+            )
+            .unwrap();
+        }
+
+        let counts = symbol_count_by_language(&conn, "my-repo", "main").unwrap();
+        assert_eq!(
+            counts.into_iter().collect::<Vec<_>>(),
+            vec![
+                ("python".to_string(), 1),
+                ("rust".to_string(), 2),
+                ("typescript".to_string(), 0),
+            ]
+        );
+        assert!(
+            symbol_count_by_language(&conn, "my-repo", "develop")
+                .unwrap()
+                .is_empty()
+        );
+    }
+
     #[test]
     fn test_symbol_with_no_optional_fields() {
         let conn = setup_test_db();
@@ -587,6 +1422,11 @@ mod tests {
             parent_symbol_id: None,
             visibility: None,
             content: None,
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
         };
 
         insert_symbol(&conn, &sym).unwrap();
@@ -620,6 +1460,11 @@ mod tests {
             parent_symbol_id: None,
             visibility: None,
             content: None,
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
         };
 
         let sym2 = SymbolRecord {
@@ -639,6 +1484,11 @@ mod tests {
             parent_symbol_id: None,
             visibility: None,
             content: None,
+            attributes: Vec::new(),
+            byte_start: None,
+            byte_end: None,
+            doc_comment: None,
+            parsed_signature: None,
         };
 
         insert_symbol(&conn, &sym1).unwrap();
@@ -653,4 +1503,260 @@ mod tests {
         assert_eq!(found.len(), 1);
         assert_eq!(found[0].name, "func_a");
     }
+
+    #[test]
+    fn list_symbol_names_page_orders_by_qualified_name_and_pages() {
+        let conn = setup_test_db();
+        for (idx, qualified_name) in ["b::beta", "a::alpha", "c::gamma"].iter().enumerate() {
+            let mut sym = sample_symbol();
+            sym.symbol_id = format!("sym_{idx}");
+            sym.symbol_stable_id = format!("stable_{idx}");
+            sym.qualified_name = qualified_name.to_string();
+            sym.line_start = idx as u32 + 1;
+            insert_symbol(&conn, &sym).unwrap();
+        }
+
+        let first = list_symbol_names_page(&conn, "my-repo", "main", 0, 2).unwrap();
+        assert_eq!(
+            first
+                .iter()
+                .map(|e| e.qualified_name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a::alpha", "b::beta"]
+        );
+        assert_eq!(first[0].kind, "function");
+        assert_eq!(first[0].path, "src/lib.rs");
+        assert_eq!(first[0].line_start, 2);
+
+        let second = list_symbol_names_page(&conn, "my-repo", "main", 2, 2).unwrap();
+        assert_eq!(second.len(), 1);
+        assert_eq!(second[0].qualified_name, "c::gamma");
+
+        assert!(
+            list_symbol_names_page(&conn, "my-repo", "other", 0, 10)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn list_directory_outline_groups_top_level_symbols_by_file() {
+        let conn = setup_test_db();
+        let specs = [
+            ("login", "src/auth/login.rs", None),
+            ("logout", "src/auth/login.rs", None),
+            ("helper", "src/auth/login.rs", Some("sym_0")),
+            ("Session", "src/auth/session.rs", None),
+            ("authz", "src/authz.rs", None),
+            ("main", "src/main.rs", None),
+        ];
+        for (idx, (name, path, parent)) in specs.iter().enumerate() {
+            let mut sym = sample_symbol();
+            sym.symbol_id = format!("sym_{idx}");
+            sym.symbol_stable_id = format!("stable_{idx}");
+            sym.name = name.to_string();
+            sym.qualified_name = name.to_string();
+            sym.path = path.to_string();
+            sym.line_start = (idx as u32) * 10 + 1;
+            sym.line_end = (idx as u32) * 10 + 5;
+            sym.parent_symbol_id = parent.map(str::to_string);
+            insert_symbol(&conn, &sym).unwrap();
+        }
+        let query = DirectoryOutlineQuery {
+            path_prefix: "src/auth/",
+            max_files: 10,
+            max_symbols_per_file: 10,
+        };
+
+        let outline = list_directory_outline(&conn, "my-repo", "main", query).unwrap();
+        assert!(!outline.truncated);
+        assert_eq!(outline.total_files, 2);
+        let paths: Vec<_> = outline.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["src/auth/login.rs", "src/auth/session.rs"]);
+        let login: Vec<_> = outline.files[0]
+            .symbols
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(login, vec!["login", "logout"]);
+
+        let capped = list_directory_outline(
+            &conn,
+            "my-repo",
+            "main",
+            DirectoryOutlineQuery {
+                path_prefix: "src",
+                max_files: 2,
+                max_symbols_per_file: 1,
+            },
+        )
+        .unwrap();
+        assert!(capped.truncated);
+        assert_eq!(capped.total_files, 4);
+        assert_eq!(capped.files.len(), 2);
+        assert_eq!(capped.files[0].symbol_count, 2);
+        assert_eq!(capped.files[0].symbols.len(), 1);
+        assert!(capped.files[0].symbols_truncated);
+        assert!(!capped.files[1].symbols_truncated);
+    }
+
+    #[test]
+    fn list_large_symbols_filters_by_span_and_sorts() {
+        let conn = setup_test_db();
+        let specs = [
+            ("short", "src/lib.rs", 1, 5, "fn short() {}"),
+            (
+                "long_flat",
+                "src/lib.rs",
+                10,
+                90,
+                "fn long_flat() {\n    a();\n}",
+            ),
+            (
+                "deep",
+                "src/deep.rs",
+                100,
+                140,
+                "fn deep() {\n    if a {\n        for x in y {\n            b();\n        }\n    }\n}",
+            ),
+            ("other_dir", "tests/it.rs", 1, 200, "fn other_dir() {}"),
+        ];
+        for (idx, (name, path, start, end, body)) in specs.iter().enumerate() {
+            let mut sym = sample_symbol();
+            sym.symbol_id = format!("sym_{idx}");
+            sym.symbol_stable_id = format!("stable_{idx}");
+            sym.name = name.to_string();
+            sym.qualified_name = name.to_string();
+            sym.path = path.to_string();
+            sym.line_start = *start;
+            sym.line_end = *end;
+            sym.content = Some(body.to_string());
+            insert_symbol(&conn, &sym).unwrap();
+        }
+        let names = |entries: Vec<LargeSymbolEntry>| {
+            entries
+                .into_iter()
+                .map(|e| e.qualified_name)
+                .collect::<Vec<_>>()
+        };
+        let query = LargeSymbolQuery {
+            min_lines: 10,
+            path_prefix: None,
+            language: None,
+            sort: LargeSymbolSort::Lines,
+            limit: 10,
+        };
+
+        let by_lines = list_large_symbols(&conn, "my-repo", "main", query).unwrap();
+        assert_eq!(by_lines[0].line_count, 200);
+        assert_eq!(names(by_lines), vec!["other_dir", "long_flat", "deep"]);
+
+        let scoped = list_large_symbols(
+            &conn,
+            "my-repo",
+            "main",
+            LargeSymbolQuery {
+                path_prefix: Some("src/"),
+                limit: 1,
+                ..query
+            },
+        )
+        .unwrap();
+        assert_eq!(names(scoped), vec!["long_flat"]);
+
+        let by_nesting = list_large_symbols(
+            &conn,
+            "my-repo",
+            "main",
+            LargeSymbolQuery {
+                sort: LargeSymbolSort::Nesting,
+                ..query
+            },
+        )
+        .unwrap();
+        assert_eq!(by_nesting[0].qualified_name, "deep");
+        assert_eq!(by_nesting[0].max_nesting, Some(3));
+
+        assert!(
+            list_large_symbols(
+                &conn,
+                "my-repo",
+                "main",
+                LargeSymbolQuery {
+                    language: Some("python"),
+                    ..query
+                },
+            )
+            .unwrap()
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn estimate_nesting_depth_falls_back_to_indentation() {
+        assert_eq!(estimate_nesting_depth("fn a() { if b { c } }"), 2);
+        assert_eq!(
+            estimate_nesting_depth("def a():\n    if b:\n        for c in d:\n            pass"),
+            3
+        );
+        assert_eq!(estimate_nesting_depth(""), 0);
+    }
+
+    #[test]
+    fn build_symbol_tree_nests_children_and_keeps_orphans_as_roots() {
+        let symbol = |id: &str, parent: Option<&str>, line_start: u32| OutlineSymbol {
+            symbol_id: id.to_string(),
+            name: id.to_string(),
+            qualified_name: id.to_string(),
+            kind: "function".to_string(),
+            language: "rust".to_string(),
+            line_start,
+            line_end: line_start + 1,
+            signature: None,
+            visibility: None,
+            parent_symbol_id: parent.map(str::to_string),
+            attributes: Vec::new(),
+            children: Vec::new(),
+        };
+        let tree = build_symbol_tree(vec![
+            symbol("class", None, 1),
+            symbol("method", Some("class"), 2),
+            symbol("inner", Some("method"), 3),
+            symbol("orphan", Some("missing"), 10),
+        ]);
+
+        let roots: Vec<&str> = tree.iter().map(|s| s.symbol_id.as_str()).collect();
+        assert_eq!(roots, vec!["class", "orphan"]);
+        assert_eq!(tree[0].children.len(), 1);
+        assert_eq!(tree[0].children[0].symbol_id, "method");
+        assert_eq!(tree[0].children[0].children[0].symbol_id, "inner");
+        assert_eq!(tree[0].children[0].children[0].line_start, 3);
+    }
+
+    #[test]
+    fn get_symbol_with_content_by_stable_id_loads_body() {
+        let conn = setup_test_db();
+        insert_symbol(&conn, &sample_symbol()).unwrap();
+
+        let symbol = get_symbol_with_content_by_stable_id(&conn, "my-repo", "main", "stable_001")
+            .unwrap()
+            .expect("symbol should exist");
+        assert_eq!(symbol.symbol_id, "sym_001");
+        assert_eq!(
+            symbol.content.as_deref(),
+            Some("fn my_function(x: i32) -> bool { x > 0 }")
+        );
+        assert!(
+            get_symbol_by_stable_id(&conn, "my-repo", "main", "stable_001")
+                .unwrap()
+                .unwrap()
+                .content
+                .is_none()
+        );
+        assert!(
+            get_symbol_with_content_by_stable_id(&conn, "my-repo", "other", "stable_001")
+                .unwrap()
+                .is_none()
+        );
+    }
 }
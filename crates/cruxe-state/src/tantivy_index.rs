@@ -1,3 +1,4 @@
+use cruxe_core::config::IndexConfig;
 use cruxe_core::constants;
 use cruxe_core::error::StateError;
 use std::io::ErrorKind;
@@ -54,59 +55,160 @@ const REQUIRED_FILE_FIELDS: &[&str] = &[
     "updated_at",
 ];
 
+/// Schema choices fixed when an index is created. Changing them only takes
+/// effect after a rebuild (`cruxe index --force`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IndexSchemaOptions {
+    /// Tokenize `content`/`content_head` with
+    /// [`tokenizers::CodeIdentifierTokenizer`] instead of Tantivy's default
+    /// analyzer, so `getUserById` and `get_user_by_id` match each other.
+    pub identifier_tokenizer: bool,
+}
+
+impl IndexSchemaOptions {
+    pub fn from_config(config: &IndexConfig) -> Self {
+        Self {
+            identifier_tokenizer: config.identifier_tokenizer,
+        }
+    }
+
+    /// Options the existing indices under `index_root` were built with, or
+    /// `None` when no symbols index exists there yet.
+    pub fn detect(index_root: &Path) -> Option<Self> {
+        let dir = index_root.join(SYMBOLS_INDEX);
+        if !dir.is_dir() || dir_is_empty(&dir).ok()? {
+            return None;
+        }
+        let index = Index::open_in_dir(&dir).ok()?;
+        let tokenizer = content_tokenizer(&index.schema())?;
+        Some(Self {
+            identifier_tokenizer: tokenizer == tokenizers::CODE_IDENTIFIER_TOKENIZER,
+        })
+    }
+
+    fn content_field_options(self) -> TextOptions {
+        if !self.identifier_tokenizer {
+            return TEXT | STORED;
+        }
+        TextOptions::default()
+            .set_indexing_options(
+                TextFieldIndexing::default()
+                    .set_tokenizer(tokenizers::CODE_IDENTIFIER_TOKENIZER)
+                    .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+            )
+            .set_stored()
+    }
+}
+
 /// Create or open the symbols Tantivy index.
 pub fn open_symbols_index(base_dir: &Path) -> Result<Index, StateError> {
-    let dir = base_dir.join(SYMBOLS_INDEX);
-    std::fs::create_dir_all(&dir).map_err(StateError::Io)?;
-
-    let schema = build_symbols_schema();
-    let index = open_or_create_index(&dir, schema, REQUIRED_SYMBOL_FIELDS)?;
-    tokenizers::register_tokenizers(index.tokenizers());
-    info!(?dir, "Symbols index opened");
-    Ok(index)
+    open_index(
+        base_dir,
+        SYMBOLS_INDEX,
+        build_symbols_schema(IndexSchemaOptions::default()),
+        REQUIRED_SYMBOL_FIELDS,
+        false,
+    )
 }
 
 /// Create or open the snippets Tantivy index.
 pub fn open_snippets_index(base_dir: &Path) -> Result<Index, StateError> {
-    let dir = base_dir.join(SNIPPETS_INDEX);
-    std::fs::create_dir_all(&dir).map_err(StateError::Io)?;
-
-    let schema = build_snippets_schema();
-    let index = open_or_create_index(&dir, schema, REQUIRED_SNIPPET_FIELDS)?;
-    tokenizers::register_tokenizers(index.tokenizers());
-    info!(?dir, "Snippets index opened");
-    Ok(index)
+    open_index(
+        base_dir,
+        SNIPPETS_INDEX,
+        build_snippets_schema(IndexSchemaOptions::default()),
+        REQUIRED_SNIPPET_FIELDS,
+        false,
+    )
 }
 
 /// Create or open the files Tantivy index.
 pub fn open_files_index(base_dir: &Path) -> Result<Index, StateError> {
-    let dir = base_dir.join(FILES_INDEX);
+    open_index(
+        base_dir,
+        FILES_INDEX,
+        build_files_schema(IndexSchemaOptions::default()),
+        REQUIRED_FILE_FIELDS,
+        false,
+    )
+}
+
+fn open_index(
+    base_dir: &Path,
+    index_name: &str,
+    schema: Schema,
+    required_fields: &[&str],
+    enforce_options: bool,
+) -> Result<Index, StateError> {
+    let dir = base_dir.join(index_name);
     std::fs::create_dir_all(&dir).map_err(StateError::Io)?;
 
-    let schema = build_files_schema();
-    let index = open_or_create_index(&dir, schema, REQUIRED_FILE_FIELDS)?;
+    let index = open_or_create_index(&dir, schema, required_fields, enforce_options, index_name)?;
     tokenizers::register_tokenizers(index.tokenizers());
-    info!(?dir, "Files index opened");
+    info!(?dir, "{} index opened", index_name);
     Ok(index)
 }
 
+/// Open the index in `dir`, creating it from `schema` when the directory is
+/// empty. With `enforce_options`, an existing index whose content tokenizer
+/// differs from `schema` is recreated if it holds no documents and rejected
+/// with [`StateError::TokenizerChanged`] otherwise.
 fn open_or_create_index(
     dir: &Path,
     schema: Schema,
     required_fields: &[&str],
+    enforce_options: bool,
+    index_name: &str,
 ) -> Result<Index, StateError> {
     let index = if dir_is_empty(dir)? {
         Index::create_in_dir(dir, schema).map_err(StateError::tantivy)?
     } else {
-        Index::open_in_dir(dir).map_err(|e| {
+        let index = Index::open_in_dir(dir).map_err(|e| {
             StateError::CorruptManifest(format!("failed to open index at {}: {}", dir.display(), e))
-        })?
+        })?;
+        let indexed = content_tokenizer(&index.schema());
+        let configured = content_tokenizer(&schema);
+        if !enforce_options || indexed == configured {
+            index
+        } else if index_is_empty(&index)? {
+            drop(index);
+            std::fs::remove_dir_all(dir).map_err(StateError::Io)?;
+            std::fs::create_dir_all(dir).map_err(StateError::Io)?;
+            Index::create_in_dir(dir, schema).map_err(StateError::tantivy)?
+        } else {
+            return Err(StateError::TokenizerChanged {
+                index: index_name.to_string(),
+                indexed: indexed.unwrap_or_default(),
+                configured: configured.unwrap_or_default(),
+            });
+        }
     };
 
     validate_required_fields(&index, required_fields)?;
     Ok(index)
 }
 
+/// Tokenizer of the full-text field (`content`, or `content_head` for files).
+fn content_tokenizer(schema: &Schema) -> Option<String> {
+    let field = schema
+        .get_field("content")
+        .or_else(|_| schema.get_field("content_head"))
+        .ok()?;
+    match schema.get_field_entry(field).field_type() {
+        FieldType::Str(options) => options
+            .get_indexing_options()
+            .map(|indexing| indexing.tokenizer().to_string()),
+        _ => None,
+    }
+}
+
+fn index_is_empty(index: &Index) -> Result<bool, StateError> {
+    let segments = index
+        .searchable_segment_metas()
+        .map_err(StateError::tantivy)?;
+    Ok(segments.iter().all(|segment| segment.num_docs() == 0))
+}
+
 fn open_existing_index(
     dir: &Path,
     required_fields: &[&str],
@@ -164,7 +266,7 @@ pub fn file_key(repo: &str, r#ref: &str, path: &str) -> String {
 }
 
 /// Build the symbols index schema per data-model.md.
-fn build_symbols_schema() -> Schema {
+fn build_symbols_schema(options: IndexSchemaOptions) -> Schema {
     let mut builder = Schema::builder();
 
     // Composite key for delete_term (repo|ref|path)
@@ -181,6 +283,9 @@ fn build_symbols_schema() -> Schema {
     builder.add_text_field("visibility", STORED);
     builder.add_text_field("symbol_id", STRING | STORED);
     builder.add_text_field("symbol_stable_id", STRING | STORED);
+    // Multi-valued outer attributes (e.g. `test`, `derive(Debug)`); optional so
+    // indexes built before the field existed still open.
+    builder.add_text_field("attributes", STRING | STORED);
 
     // TEXT fields with custom tokenizers
     let code_path_options = TextOptions::default()
@@ -211,17 +316,21 @@ fn build_symbols_schema() -> Schema {
     builder.add_text_field("signature", sig_options);
 
     // Full-text content
-    builder.add_text_field("content", TEXT | STORED);
+    builder.add_text_field("content", options.content_field_options());
 
     // Numeric stored fields
     builder.add_u64_field("line_start", STORED);
     builder.add_u64_field("line_end", STORED);
+    // Byte range of the definition (end exclusive); optional like `attributes`
+    // so older indexes open and simply report no range.
+    builder.add_u64_field("byte_start", STORED);
+    builder.add_u64_field("byte_end", STORED);
 
     builder.build()
 }
 
 /// Build the snippets index schema per data-model.md.
-fn build_snippets_schema() -> Schema {
+fn build_snippets_schema(options: IndexSchemaOptions) -> Schema {
     let mut builder = Schema::builder();
 
     // Composite key for delete_term (repo|ref|path)
@@ -251,8 +360,8 @@ fn build_snippets_schema() -> Schema {
         .set_stored();
     builder.add_text_field("imports", code_dotted_options);
 
-    // Content with default tokenizer for full-text search
-    builder.add_text_field("content", TEXT | STORED);
+    // Content for full-text search (default or identifier tokenizer)
+    builder.add_text_field("content", options.content_field_options());
 
     builder.add_u64_field("line_start", STORED);
     builder.add_u64_field("line_end", STORED);
@@ -261,7 +370,7 @@ fn build_snippets_schema() -> Schema {
 }
 
 /// Build the files index schema per data-model.md.
-fn build_files_schema() -> Schema {
+fn build_files_schema(options: IndexSchemaOptions) -> Schema {
     let mut builder = Schema::builder();
 
     // Composite key for delete_term (repo|ref|path)
@@ -283,7 +392,7 @@ fn build_files_schema() -> Schema {
         .set_stored();
     builder.add_text_field("path", code_path_options);
 
-    builder.add_text_field("content_head", TEXT | STORED);
+    builder.add_text_field("content_head", options.content_field_options());
 
     builder.build()
 }
@@ -316,6 +425,57 @@ impl IndexSet {
         })
     }
 
+    /// Like `open`, but new indices are created with `options` and existing
+    /// ones must have been built with them (see [`StateError::TokenizerChanged`]).
+    pub fn open_with_options(
+        base_dir: &Path,
+        options: IndexSchemaOptions,
+    ) -> Result<Self, StateError> {
+        let base = base_dir.join("base");
+        Self::open_at_with_options(&base, options)
+    }
+
+    /// Like `open_at`, but new indices are created with `options` and existing
+    /// ones must have been built with them.
+    pub fn open_at_with_options(
+        index_root: &Path,
+        options: IndexSchemaOptions,
+    ) -> Result<Self, StateError> {
+        Ok(Self {
+            symbols: open_index(
+                index_root,
+                SYMBOLS_INDEX,
+                build_symbols_schema(options),
+                REQUIRED_SYMBOL_FIELDS,
+                true,
+            )?,
+            snippets: open_index(
+                index_root,
+                SNIPPETS_INDEX,
+                build_snippets_schema(options),
+                REQUIRED_SNIPPET_FIELDS,
+                true,
+            )?,
+            files: open_index(
+                index_root,
+                FILES_INDEX,
+                build_files_schema(options),
+                REQUIRED_FILE_FIELDS,
+                true,
+            )?,
+        })
+    }
+
+    /// Open `index_root`, creating missing indices with the same schema
+    /// options as the indices under `reference_root` (overlays and staging
+    /// follow their base index).
+    pub fn open_at_matching(index_root: &Path, reference_root: &Path) -> Result<Self, StateError> {
+        match IndexSchemaOptions::detect(reference_root) {
+            Some(options) => Self::open_at_with_options(index_root, options),
+            None => Self::open_at(index_root),
+        }
+    }
+
     /// Open existing indices without creating new ones.
     ///
     /// Used by query paths to enforce explicit index compatibility handling.
@@ -473,4 +633,48 @@ mod tests {
         assert!(overlay.join("snippets").exists());
         assert!(overlay.join("files").exists());
     }
+
+    #[test]
+    fn changed_tokenizer_rebuilds_empty_indices_and_rejects_populated_ones() {
+        let dir = tempdir().unwrap();
+        let identifier = IndexSchemaOptions {
+            identifier_tokenizer: true,
+        };
+        let set = IndexSet::open(dir.path()).unwrap();
+        assert_eq!(
+            IndexSchemaOptions::detect(&dir.path().join("base")),
+            Some(IndexSchemaOptions::default())
+        );
+        drop(set);
+
+        // Still empty: switching the option just recreates the indices.
+        let set = IndexSet::open_with_options(dir.path(), identifier).unwrap();
+        assert_eq!(
+            IndexSchemaOptions::detect(&dir.path().join("base")),
+            Some(identifier)
+        );
+        let overlay = dir.path().join("overlay").join("feat");
+        IndexSet::open_at_matching(&overlay, &dir.path().join("base")).unwrap();
+        assert_eq!(IndexSchemaOptions::detect(&overlay), Some(identifier));
+
+        let content = set.symbols.schema().get_field("content").unwrap();
+        let mut writer: tantivy::IndexWriter = set.symbols.writer(15_000_000).unwrap();
+        writer
+            .add_document(tantivy::doc!(content => "fn getUserById() {}"))
+            .unwrap();
+        writer.commit().unwrap();
+        drop(writer);
+        drop(set);
+
+        let err = IndexSet::open_with_options(dir.path(), IndexSchemaOptions::default())
+            .err()
+            .unwrap();
+        assert!(matches!(
+            err,
+            StateError::TokenizerChanged { ref index, ref indexed, ref configured }
+                if index == SYMBOLS_INDEX && indexed == "code_identifier" && configured == "default"
+        ));
+        // Opening without options keeps whatever the index was built with.
+        assert!(IndexSet::open(dir.path()).is_ok());
+    }
 }
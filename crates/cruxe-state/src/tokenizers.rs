@@ -77,6 +77,133 @@ impl Tokenizer for CodePathTokenizer {
     }
 }
 
+/// Name under which [`CodeIdentifierTokenizer`] is registered.
+pub const CODE_IDENTIFIER_TOKENIZER: &str = "code_identifier";
+
+/// Tokens longer than this are dropped, matching Tantivy's default analyzer.
+const MAX_IDENTIFIER_TOKEN_LEN: usize = 40;
+
+/// Identifier-aware full-text tokenizer for code bodies.
+///
+/// Each word (a run of alphanumerics and `_`) is emitted whole, with its
+/// separators removed, and then as its case/underscore parts at consecutive
+/// positions starting at the whole token's position:
+/// `getUserById` → `getuserbyid@0, get@0, user@1, by@2, id@3`. Because
+/// `get_user_by_id` produces the same parts, a query for one spelling matches
+/// the other, while the whole token keeps single-word queries exact.
+#[derive(Clone)]
+pub struct CodeIdentifierTokenizer;
+
+impl Tokenizer for CodeIdentifierTokenizer {
+    type TokenStream<'a> = BoxTokenStream<'a>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        BoxTokenStream::new(VecTokenStream {
+            tokens: identifier_tokens(text),
+            index: 0,
+        })
+    }
+}
+
+fn identifier_tokens(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut position = 0usize;
+    let push = |tokens: &mut Vec<Token>, text: String, from: usize, to: usize, pos: usize| {
+        if !text.is_empty() && text.len() <= MAX_IDENTIFIER_TOKEN_LEN {
+            tokens.push(Token {
+                offset_from: from,
+                offset_to: to,
+                position: pos,
+                text,
+                position_length: 1,
+            });
+        }
+    };
+
+    for (word_start, word) in identifier_words(text) {
+        let parts = split_identifier(word);
+        if parts.is_empty() {
+            continue;
+        }
+        let whole: String = parts
+            .iter()
+            .map(|&(start, end)| word[start..end].to_lowercase())
+            .collect();
+        push(
+            &mut tokens,
+            whole,
+            word_start,
+            word_start + word.len(),
+            position,
+        );
+        if parts.len() > 1 {
+            for (i, &(start, end)) in parts.iter().enumerate() {
+                push(
+                    &mut tokens,
+                    word[start..end].to_lowercase(),
+                    word_start + start,
+                    word_start + end,
+                    position + i,
+                );
+            }
+        }
+        position += parts.len();
+    }
+    tokens
+}
+
+/// Runs of alphanumerics and `_` with their byte offsets.
+fn identifier_words(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let mut rest = text.char_indices().peekable();
+    std::iter::from_fn(move || {
+        let (start, _) = rest.by_ref().find(|&(_, c)| is_word(c))?;
+        let mut end = text.len();
+        while let Some(&(i, c)) = rest.peek() {
+            if !is_word(c) {
+                end = i;
+                break;
+            }
+            rest.next();
+        }
+        Some((start, &text[start..end]))
+    })
+}
+
+/// Byte ranges of the parts of one identifier: split at `_`, at
+/// lower/digit→upper transitions, and before the last capital of an acronym
+/// (`HTTPServer` → `HTTP`, `Server`).
+fn split_identifier(word: &str) -> Vec<(usize, usize)> {
+    let chars: Vec<(usize, char)> = word.char_indices().collect();
+    let mut parts = Vec::new();
+    let mut start: Option<usize> = None;
+    for (i, &(offset, ch)) in chars.iter().enumerate() {
+        if ch == '_' {
+            if let Some(s) = start.take() {
+                parts.push((s, offset));
+            }
+            continue;
+        }
+        if let Some(s) = start
+            && ch.is_uppercase()
+        {
+            let prev = chars[i - 1].1;
+            let next_is_lower = chars.get(i + 1).is_some_and(|&(_, c)| c.is_lowercase());
+            if prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_is_lower) {
+                parts.push((s, offset));
+                start = Some(offset);
+            }
+        }
+        if start.is_none() {
+            start = Some(offset);
+        }
+    }
+    if let Some(s) = start {
+        parts.push((s, word.len()));
+    }
+    parts
+}
+
 fn split_camel_case(text: &str) -> Vec<String> {
     let mut tokens = Vec::new();
     let mut current = String::new();
@@ -174,6 +301,7 @@ pub fn register_tokenizers(manager: &tantivy::tokenizer::TokenizerManager) {
     manager.register("code_dotted", CodeDottedTokenizer);
     manager.register("code_path", CodePathTokenizer);
     manager.register("code_signature", CodeSignatureTokenizer);
+    manager.register(CODE_IDENTIFIER_TOKENIZER, CodeIdentifierTokenizer);
 }
 
 #[cfg(test)]
@@ -253,4 +381,54 @@ mod tests {
         assert!(tokens.contains(&"id".to_string()));
         assert!(tokens.contains(&"string".to_string()));
     }
+
+    #[test]
+    fn test_identifier_tokenizer_emits_whole_token_and_parts() {
+        let mut t = CodeIdentifierTokenizer;
+        let positioned = |t: &mut CodeIdentifierTokenizer, text: &str| {
+            let mut stream = t.token_stream(text);
+            let mut result = Vec::new();
+            while stream.advance() {
+                let token = stream.token();
+                result.push((token.text.clone(), token.position));
+            }
+            result
+        };
+        let camel = positioned(&mut t, "getUserById");
+        let snake = positioned(&mut t, "get_user_by_id");
+        assert_eq!(camel, snake);
+        assert_eq!(
+            camel,
+            vec![
+                ("getuserbyid".to_string(), 0),
+                ("get".to_string(), 0),
+                ("user".to_string(), 1),
+                ("by".to_string(), 2),
+                ("id".to_string(), 3),
+            ]
+        );
+
+        assert_eq!(
+            tokenize(&mut t, "HTTPServer.start(utf8Decode)"),
+            vec![
+                "httpserver",
+                "http",
+                "server",
+                "start",
+                "utf8decode",
+                "utf8",
+                "decode"
+            ]
+        );
+        assert_eq!(tokenize(&mut t, "fn main() -> __"), vec!["fn", "main"]);
+
+        let mut stream = t.token_stream("let userId");
+        assert!(stream.advance());
+        assert!(stream.advance());
+        assert_eq!(stream.token().text, "userid");
+        assert_eq!(
+            (stream.token().offset_from, stream.token().offset_to),
+            (4, 10)
+        );
+    }
 }
@@ -212,8 +212,8 @@ pub fn delete_workspace(conn: &Connection, workspace_path: &str) -> Result<(), S
     Ok(())
 }
 
-/// List workspaces ordered by last_used_at descending, limited to `limit`.
-/// Used for warmset prewarm selection.
+/// List workspaces ordered by last_used_at descending (ties broken by path so
+/// the order is stable), limited to `limit`. Used for warmset LRU selection.
 pub fn list_recent_workspaces(
     conn: &Connection,
     limit: usize,
@@ -221,7 +221,7 @@ pub fn list_recent_workspaces(
     let mut stmt = conn
         .prepare(
             "SELECT workspace_path, project_id, auto_discovered, last_used_at, index_status
-             FROM known_workspaces ORDER BY last_used_at DESC LIMIT ?1",
+             FROM known_workspaces ORDER BY last_used_at DESC, workspace_path ASC LIMIT ?1",
         )
         .map_err(StateError::sqlite)?;
 
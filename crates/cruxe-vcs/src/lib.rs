@@ -1,11 +1,13 @@
 pub mod adapter;
 pub mod diff;
 pub mod git2_adapter;
+pub mod tree;
 pub mod worktree;
 
 pub use adapter::VcsAdapter;
 pub use diff::{DiffEntry, FileChangeKind};
 pub use git2_adapter::Git2VcsAdapter;
+pub use tree::{CommitTree, TreeFile};
 pub use worktree::{WorktreeManager, normalize_ref_name};
 
 #[cfg(test)]
@@ -0,0 +1,169 @@
+use cruxe_core::error::VcsError;
+use git2::{ObjectType, Oid, Repository, TreeWalkMode, TreeWalkResult};
+use std::path::Path;
+
+/// A regular file recorded in a commit tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeFile {
+    /// Repository-relative path with `/` separators.
+    pub path: String,
+    pub size: u64,
+    oid: Oid,
+}
+
+/// The files of one commit, read straight from the object database so that
+/// bare repositories and commits other than `HEAD` can be indexed without a
+/// checkout.
+pub struct CommitTree {
+    repo: Repository,
+    commit: String,
+    files: Vec<TreeFile>,
+}
+
+impl CommitTree {
+    /// Resolve `treeish` (a sha, branch, tag or any rev-parse expression) to a
+    /// commit and list its regular files. Symlinks and submodules are skipped.
+    pub fn open(repo_root: &Path, treeish: &str) -> Result<Self, VcsError> {
+        let repo = Repository::open(repo_root).map_err(|_| VcsError::NotGitRepo {
+            path: repo_root.display().to_string(),
+        })?;
+        let (commit, files) = Self::list_files(&repo, treeish)?;
+        Ok(Self {
+            repo,
+            commit,
+            files,
+        })
+    }
+
+    fn list_files(repo: &Repository, treeish: &str) -> Result<(String, Vec<TreeFile>), VcsError> {
+        let commit = repo
+            .revparse_single(treeish)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|e| {
+                VcsError::GitError(format!("failed to resolve commit `{treeish}`: {e}"))
+            })?;
+        let tree = commit
+            .tree()
+            .map_err(|e| VcsError::GitError(format!("failed to read commit tree: {e}")))?;
+
+        let odb = repo
+            .odb()
+            .map_err(|e| VcsError::GitError(format!("failed to open object database: {e}")))?;
+        let mut files = Vec::new();
+        let mut header_error = None;
+        tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+            // 0o100644 and 0o100755 are regular files; 0o120000 is a symlink.
+            let regular_file = matches!(entry.filemode(), 0o100644 | 0o100755);
+            if entry.kind() != Some(ObjectType::Blob) || !regular_file {
+                return TreeWalkResult::Ok;
+            }
+            let Some(name) = entry.name() else {
+                return TreeWalkResult::Ok;
+            };
+            match odb.read_header(entry.id()) {
+                Ok((size, _)) => files.push(TreeFile {
+                    path: format!("{dir}{name}"),
+                    size: size as u64,
+                    oid: entry.id(),
+                }),
+                Err(e) => {
+                    header_error = Some(VcsError::GitError(format!(
+                        "failed to read blob header for `{dir}{name}`: {e}"
+                    )));
+                    return TreeWalkResult::Abort;
+                }
+            }
+            TreeWalkResult::Ok
+        })
+        .map_err(|e| {
+            header_error
+                .take()
+                .unwrap_or(VcsError::GitError(e.to_string()))
+        })?;
+
+        Ok((commit.id().to_string(), files))
+    }
+
+    /// Full sha of the resolved commit.
+    pub fn commit(&self) -> &str {
+        &self.commit
+    }
+
+    pub fn files(&self) -> &[TreeFile] {
+        &self.files
+    }
+
+    /// Read the raw bytes of `file`.
+    pub fn read(&self, file: &TreeFile) -> Result<Vec<u8>, VcsError> {
+        self.repo
+            .find_blob(file.oid)
+            .map(|blob| blob.content().to_vec())
+            .map_err(|e| VcsError::GitError(format!("failed to read blob `{}`: {e}", file.path)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::{Signature, build::TreeUpdateBuilder};
+
+    fn commit_files(repo: &Repository, files: &[(&str, &str)], message: &str) -> Oid {
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let base = match &parent {
+            Some(commit) => commit.tree().unwrap(),
+            None => repo
+                .find_tree(repo.treebuilder(None).unwrap().write().unwrap())
+                .unwrap(),
+        };
+        let mut update = TreeUpdateBuilder::new();
+        for (path, content) in files {
+            let blob = repo.blob(content.as_bytes()).unwrap();
+            update.upsert(*path, blob, git2::FileMode::Blob);
+        }
+        let tree = repo
+            .find_tree(update.create_updated(repo, &base).unwrap())
+            .unwrap();
+        let signature = Signature::now("test", "test@example.com").unwrap();
+        let parents: Vec<&git2::Commit<'_>> = parent.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn commit_tree_reads_files_from_a_bare_repository() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init_bare(dir.path()).unwrap();
+        let first = commit_files(&repo, &[("src/lib.rs", "fn first() {}\n")], "first");
+        commit_files(
+            &repo,
+            &[
+                ("src/lib.rs", "fn second() {}\n"),
+                ("README.md", "# readme\n"),
+            ],
+            "second",
+        );
+
+        let head = CommitTree::open(dir.path(), "HEAD").unwrap();
+        let mut paths: Vec<&str> = head.files().iter().map(|f| f.path.as_str()).collect();
+        paths.sort();
+        assert_eq!(paths, vec!["README.md", "src/lib.rs"]);
+
+        let old = CommitTree::open(dir.path(), &first.to_string()[..10]).unwrap();
+        assert_eq!(old.commit(), first.to_string());
+        assert_eq!(old.files().len(), 1);
+        assert_eq!(old.files()[0].size, 14);
+        assert_eq!(old.read(&old.files()[0]).unwrap(), b"fn first() {}\n");
+
+        assert!(matches!(
+            CommitTree::open(dir.path(), "no-such-rev"),
+            Err(VcsError::GitError(_))
+        ));
+    }
+}